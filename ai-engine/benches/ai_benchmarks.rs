@@ -24,6 +24,7 @@ fn bench_feature_extraction(c: &mut Criterion) {
             route_length: 2,
             slippage_tolerance_bps: 50.0,
             pool_liquidity_usd: 10_000_000.0,
+            pool: None,
         }),
         time_since_last_slot_ms: 400,
         next_leader_pubkey: Pubkey::new_unique(),
@@ -64,6 +65,18 @@ fn bench_feature_to_array(c: &mut Criterion) {
     });
 }
 
+fn bench_feature_write_into(c: &mut Criterion) {
+    let features = FeatureVector::default();
+    let mut buf = [0.0f32; FeatureVector::FEATURE_COUNT];
+    
+    c.bench_function("feature_write_into", |b| {
+        b.iter(|| {
+            features.write_into(black_box(&mut buf));
+            black_box(buf[0])
+        })
+    });
+}
+
 fn bench_feature_validation(c: &mut Criterion) {
     let features = FeatureVector::default();
     
@@ -105,6 +118,7 @@ criterion_group!(
     bench_feature_extraction,
     bench_inference_prediction,
     bench_feature_to_array,
+    bench_feature_write_into,
     bench_feature_validation,
     bench_different_risk_levels
 );
@@ -0,0 +1,57 @@
+//! Scalar vs SIMD model input normalization
+//!
+//! The `dispatch` case uses the `wide::f32x8` kernel when built with the
+//! `simd` feature and falls back to the scalar loop otherwise.
+//!
+//!     cargo bench -p ai-engine --bench normalization [--features simd]
+
+use ai_engine::{FeatureNormalizer, FeatureVector, ModelInput};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct SampleSet {
+    samples: Vec<Sample>,
+}
+
+#[derive(Deserialize)]
+struct Sample {
+    features: Vec<f32>,
+}
+
+fn load_samples() -> Vec<ModelInput> {
+    let set: SampleSet = serde_json::from_str(include_str!("data/quantization_samples.json")).unwrap();
+    set.samples
+        .into_iter()
+        .map(|s| <ModelInput>::try_from(s.features.as_slice()).unwrap())
+        .collect()
+}
+
+fn bench_normalization(c: &mut Criterion) {
+    let samples = load_samples();
+    let normalizer = FeatureNormalizer::fit(&samples, 4.0).unwrap();
+    let mut out = [0.0f32; FeatureVector::FEATURE_COUNT];
+
+    let mut group = c.benchmark_group("normalization");
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            for sample in &samples {
+                normalizer.normalize_scalar(black_box(sample), &mut out);
+                black_box(out[0]);
+            }
+        })
+    });
+    let dispatch = if FeatureNormalizer::is_simd() { "simd_f32x8" } else { "dispatch_scalar" };
+    group.bench_function(dispatch, |b| {
+        b.iter(|| {
+            for sample in &samples {
+                normalizer.normalize_into(black_box(sample), &mut out);
+                black_box(out[0]);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_normalization);
+criterion_main!(benches);
@@ -0,0 +1,113 @@
+//! fp32 vs int8 latency/accuracy on the bundled sample set
+//!
+//! Without exported models the bundled GBDT (`data/sample_gbdt.json`) is
+//! compared against itself fed int8-precision inputs. With the `onnx` feature
+//! and `models/mev_detector.onnx` + `models/mev_detector_int8.onnx` (with its
+//! `.quant.json` calibration) present, the real exports are compared.
+//!
+//!     cargo bench -p ai-engine --bench quantization [--features onnx]
+
+use ai_engine::{
+    compare_quantization, FeatureQuantizer, GbdtBackend, GbdtModel, ModelBackend, ModelInput,
+    QuantizationReport, QuantizedBackend,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::Deserialize;
+
+const SLO_MS: f64 = 50.0;
+const MIN_AGREEMENT: f64 = 0.99;
+const THRESHOLD: f32 = 0.5;
+
+#[derive(Deserialize)]
+struct SampleSet {
+    samples: Vec<Sample>,
+}
+
+#[derive(Deserialize)]
+struct Sample {
+    features: Vec<f32>,
+    label: u8,
+}
+
+fn load_samples() -> (Vec<ModelInput>, Vec<bool>) {
+    let set: SampleSet = serde_json::from_str(include_str!("data/quantization_samples.json")).unwrap();
+    set.samples
+        .into_iter()
+        .map(|s| (<ModelInput>::try_from(s.features.as_slice()).unwrap(), s.label == 1))
+        .unzip()
+}
+
+fn sample_backends(samples: &[ModelInput]) -> (Box<dyn ModelBackend>, Box<dyn ModelBackend>) {
+    let model = GbdtModel::from_json(include_str!("data/sample_gbdt.json")).unwrap();
+    let quantizer = FeatureQuantizer::calibrate(samples, 0.5).unwrap();
+    (
+        Box::new(GbdtBackend::new(model.clone())),
+        Box::new(QuantizedBackend::simulated(Box::new(GbdtBackend::new(model)), quantizer)),
+    )
+}
+
+#[cfg(feature = "onnx")]
+fn exported_backends() -> Option<(Box<dyn ModelBackend>, Box<dyn ModelBackend>)> {
+    use ai_engine::{load_backend, ModelConfig};
+
+    let fp32 = ModelConfig::new("models/mev_detector.onnx".into());
+    let int8 = ModelConfig::new("models/mev_detector_int8.onnx".into());
+    if !fp32.model_path.exists() || !FeatureQuantizer::sidecar_path(&int8.model_path).exists() {
+        return None;
+    }
+    Some((load_backend(&fp32).ok()?, load_backend(&int8).ok()?))
+}
+
+#[cfg(not(feature = "onnx"))]
+fn exported_backends() -> Option<(Box<dyn ModelBackend>, Box<dyn ModelBackend>)> {
+    None
+}
+
+fn print_report(report: &QuantizationReport) {
+    println!(
+        "\nfp32 p50/p99 {:.1}/{:.1}µs | int8 p50/p99 {:.1}/{:.1}µs",
+        report.fp32_p50_us, report.fp32_p99_us, report.int8_p50_us, report.int8_p99_us
+    );
+    println!(
+        "agreement {:.2}% | mean |Δ| {:.4} | max |Δ| {:.4} | accuracy fp32 {:.3} int8 {:.3}",
+        report.decision_agreement * 100.0,
+        report.mean_abs_error,
+        report.max_abs_error,
+        report.fp32_accuracy.unwrap_or_default(),
+        report.int8_accuracy.unwrap_or_default()
+    );
+    println!(
+        "int8 safe for {}ms SLO at {:.0}% agreement: {}\n",
+        SLO_MS,
+        MIN_AGREEMENT * 100.0,
+        report.is_safe(SLO_MS, MIN_AGREEMENT)
+    );
+}
+
+fn bench_quantization(c: &mut Criterion) {
+    let (samples, labels) = load_samples();
+    let (fp32, int8) = exported_backends().unwrap_or_else(|| sample_backends(&samples));
+
+    let report = compare_quantization(fp32.as_ref(), int8.as_ref(), &samples, Some(&labels), THRESHOLD).unwrap();
+    print_report(&report);
+
+    let mut group = c.benchmark_group("quantization");
+    group.bench_function(format!("fp32_{}", fp32.name()), |b| {
+        b.iter(|| {
+            for sample in &samples {
+                black_box(fp32.predict(black_box(sample)).unwrap());
+            }
+        })
+    });
+    group.bench_function(format!("int8_{}", int8.name()), |b| {
+        b.iter(|| {
+            for sample in &samples {
+                black_box(int8.predict(black_box(sample)).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_quantization);
+criterion_main!(benches);
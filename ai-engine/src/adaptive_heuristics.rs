@@ -1,14 +1,36 @@
 use crate::features_enhanced::FeatureVector;
-use sentinel_core::{MevRiskScore, Result};
-use chrono::{Utc, Datelike, Timelike};
-use std::collections::VecDeque;
+use crate::market_stats::TokenPair;
+use crate::tip_index::{TipIndexSnapshot, TipPercentileIndex, TipPercentiles};
+use sentinel_core::{
+    system_clock, CalendarFactors, MarketCalendar, MevRiskScore, Result, SharedClock, ThresholdSettings,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+
+/// Observations a pair needs before its own thresholds replace the global ones
+const PAIR_MIN_SAMPLES: usize = 50;
+
+/// Per-pair rolling window size
+const PAIR_MAX_HISTORY: usize = 500;
+
+/// Pair thresholds are recomputed after this many new observations
+const PAIR_REFRESH_INTERVAL: usize = 25;
+
+/// Default number of pairs tracked before least-recently-used eviction
+const DEFAULT_PAIR_CAPACITY: usize = 1024;
+
+/// Pair-derived thresholds are clamped to this factor of the global base
+const PAIR_THRESHOLD_CLAMP: f32 = 4.0;
 
 /// Adaptive heuristic scoring with dynamic threshold adjustment
 /// 
 /// Research validation:
 /// - Adjusts thresholds based on market volatility, network congestion, time-of-day
 /// - Weekends see 3x increase in memecoin MEV attacks
-/// - US market hours (9am-4pm ET) account for 65% of MEV activity
+/// - US market hours (9am-4pm ET) account for 65% of MEV activity; sessions,
+///   weekends and holidays come from a [`MarketCalendar`] (DST-aware)
 /// - High network congestion increases false positives if thresholds are static
 #[derive(Debug, Clone)]
 pub struct AdaptiveHeuristics {
@@ -20,14 +42,182 @@ pub struct AdaptiveHeuristics {
     network_congestion_factor: f32,
     time_of_day_adjustment: f32,
     
-    /// Historical tip tracking for percentile calculation
-    tip_history: VecDeque<u64>,
+    /// Sessions / weekends / holidays behind the time-of-day adjustment
+    calendar: MarketCalendar,
+    
+    /// Calendar state at the last risk calculation
+    calendar_factors: Option<CalendarFactors>,
+    
+    /// Slot-bucketed tip sketch for O(1) percentile lookups
+    tip_index: TipPercentileIndex,
     
     /// Historical price impact tracking
     price_impact_history: VecDeque<f32>,
     
     /// Maximum history size
     max_history: usize,
+    
+    /// Per-token-pair threshold tracking (LRU)
+    pair_states: PairStateCache,
+    
+    /// Time source for time-of-day / weekend adjustments
+    clock: SharedClock,
+}
+
+/// Thresholds learned from a single token pair's own history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairThresholds {
+    /// 90th percentile tip for the pair (lamports)
+    pub high_tip: u64,
+    
+    /// 95th percentile price impact for the pair (basis points)
+    pub price_impact_bps: f32,
+    
+    /// Observations backing the thresholds
+    pub samples: usize,
+}
+
+/// Rolling tip/price-impact history for one token pair
+#[derive(Debug, Clone, Default)]
+struct PairState {
+    tip_history: VecDeque<u64>,
+    price_impact_history: VecDeque<f32>,
+    thresholds: Option<PairThresholds>,
+    since_refresh: usize,
+    last_used: u64,
+}
+
+impl PairState {
+    fn observe(&mut self, tip: u64, price_impact_bps: f32) {
+        self.tip_history.push_back(tip);
+        self.price_impact_history.push_back(price_impact_bps);
+        if self.tip_history.len() > PAIR_MAX_HISTORY {
+            self.tip_history.pop_front();
+            self.price_impact_history.pop_front();
+        }
+        
+        self.since_refresh += 1;
+        if self.tip_history.len() >= PAIR_MIN_SAMPLES
+            && (self.thresholds.is_none() || self.since_refresh >= PAIR_REFRESH_INTERVAL)
+        {
+            self.refresh_thresholds();
+        }
+    }
+    
+    fn refresh_thresholds(&mut self) {
+        let mut tips: Vec<u64> = self.tip_history.iter().copied().collect();
+        tips.sort_unstable();
+        let mut impacts: Vec<f32> = self.price_impact_history.iter().copied().collect();
+        impacts.sort_unstable_by(f32::total_cmp);
+        
+        self.thresholds = Some(PairThresholds {
+            high_tip: tips[quantile_index(tips.len(), 0.90)],
+            price_impact_bps: impacts[quantile_index(impacts.len(), 0.95)],
+            samples: tips.len(),
+        });
+        self.since_refresh = 0;
+    }
+    
+    fn tip_percentile(&self, tip: u64) -> f32 {
+        let below_count = self.tip_history.iter().filter(|&&t| t < tip).count();
+        (below_count as f32 / self.tip_history.len() as f32) * 100.0
+    }
+}
+
+/// Learned runtime state of [`AdaptiveHeuristics`], carried across restarts
+///
+/// Base thresholds are config, not state, and are not included.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeuristicsSnapshot {
+    pub volatility_multiplier: f32,
+    pub network_congestion_factor: f32,
+    pub tip_index: TipIndexSnapshot,
+    pub price_impact_history: Vec<f32>,
+
+    /// Least recently used first
+    pub pairs: Vec<PairSnapshot>,
+}
+
+/// One token pair's rolling history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairSnapshot {
+    /// Base58 mints
+    pub input_mint: String,
+    pub output_mint: String,
+    pub tips: Vec<u64>,
+    pub price_impacts_bps: Vec<f32>,
+}
+
+fn quantile_index(len: usize, q: f32) -> usize {
+    ((len as f32 * q).ceil() as usize).clamp(1, len) - 1
+}
+
+/// Token-pair states with least-recently-used eviction
+///
+/// `recency` logs every touch in tick order; entries whose tick no longer
+/// matches the pair's `last_used` are stale and skipped, so eviction and
+/// touches stay amortized O(1) instead of scanning every tracked pair.
+#[derive(Debug, Clone)]
+struct PairStateCache {
+    states: HashMap<TokenPair, PairState>,
+    recency: VecDeque<(TokenPair, u64)>,
+    capacity: usize,
+    tick: u64,
+}
+
+impl PairStateCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            states: HashMap::new(),
+            recency: VecDeque::new(),
+            capacity: capacity.max(1),
+            tick: 0,
+        }
+    }
+    
+    /// Fetch (or create) a pair's state, evicting the LRU pair when full
+    fn touch(&mut self, pair: TokenPair) -> &mut PairState {
+        self.tick += 1;
+        
+        if !self.states.contains_key(&pair) && self.states.len() >= self.capacity {
+            self.evict_lru();
+        }
+        
+        self.recency.push_back((pair, self.tick));
+        if self.recency.len() > self.capacity * 2 {
+            self.compact();
+        }
+        
+        let state = self.states.entry(pair).or_default();
+        state.last_used = self.tick;
+        state
+    }
+    
+    fn is_current(&self, pair: &TokenPair, tick: u64) -> bool {
+        self.states.get(pair).is_some_and(|state| state.last_used == tick)
+    }
+    
+    fn evict_lru(&mut self) {
+        while let Some((pair, tick)) = self.recency.pop_front() {
+            if self.is_current(&pair, tick) {
+                self.states.remove(&pair);
+                return;
+            }
+        }
+    }
+    
+    /// Drop stale recency entries; amortized over the `capacity` touches since the last compaction
+    fn compact(&mut self) {
+        let recency = std::mem::take(&mut self.recency);
+        self.recency = recency
+            .into_iter()
+            .filter(|(pair, tick)| self.is_current(pair, *tick))
+            .collect();
+    }
+    
+    fn get(&self, pair: &TokenPair) -> Option<&PairState> {
+        self.states.get(pair)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +250,18 @@ impl Default for ThresholdConfig {
     }
 }
 
+impl From<&ThresholdSettings> for ThresholdConfig {
+    fn from(settings: &ThresholdSettings) -> Self {
+        Self {
+            high_tip: settings.high_tip,
+            price_impact_bps: settings.price_impact_bps,
+            validator_risk: settings.validator_risk,
+            triplet_weight: settings.triplet_weight,
+            liquidity_util: settings.liquidity_util,
+        }
+    }
+}
+
 impl Default for AdaptiveHeuristics {
     fn default() -> Self {
         Self::new()
@@ -74,12 +276,28 @@ impl AdaptiveHeuristics {
             volatility_multiplier: 1.0,
             network_congestion_factor: 0.0,
             time_of_day_adjustment: 1.0,
-            tip_history: VecDeque::new(),
+            calendar: MarketCalendar::default(),
+            calendar_factors: None,
+            tip_index: TipPercentileIndex::new(),
             price_impact_history: VecDeque::new(),
             max_history: 1000,
+            pair_states: PairStateCache::new(DEFAULT_PAIR_CAPACITY),
+            clock: system_clock(),
         }
     }
     
+    /// Read the time from `clock` (backtests, deterministic tests)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Set how many token pairs keep their own thresholds (LRU beyond that)
+    pub fn with_pair_capacity(mut self, capacity: usize) -> Self {
+        self.pair_states = PairStateCache::new(capacity);
+        self
+    }
+    
     /// Create with custom base thresholds
     pub fn with_thresholds(thresholds: ThresholdConfig) -> Self {
         Self {
@@ -88,6 +306,16 @@ impl AdaptiveHeuristics {
         }
     }
     
+    /// Replace base thresholds (hot config reload)
+    pub fn set_base_thresholds(&mut self, thresholds: ThresholdConfig) {
+        self.base_thresholds = thresholds;
+    }
+    
+    /// Replace the market calendar (hot config reload)
+    pub fn set_calendar(&mut self, calendar: MarketCalendar) {
+        self.calendar = calendar;
+    }
+    
     /// Update market volatility multiplier
     /// 
     /// Higher volatility = more lenient thresholds (avoid false positives)
@@ -120,22 +348,16 @@ impl AdaptiveHeuristics {
     /// 
     /// Research: 65% of MEV activity during US market hours (9am-4pm ET)
     /// Weekends: 3x increase in memecoin attacks
-    fn calculate_time_adjustment(&self) -> f32 {
-        let now = Utc::now();
-        let weekday = now.weekday().num_days_from_monday();
-        let hour = now.hour();
-        
-        // Weekend multiplier (Sat/Sun)
-        let weekend_factor = if weekday >= 5 { 1.3 } else { 1.0 };
-        
-        // US market hours (14:00-21:00 UTC = 9am-4pm ET)
-        let market_hours_factor = if (14..21).contains(&hour) {
-            1.1 // Higher activity during market hours
-        } else {
-            1.0
-        };
-        
-        weekend_factor * market_hours_factor
+    fn calculate_time_adjustment(&mut self) -> f32 {
+        let factors = self.calendar.factors(self.clock.now());
+        let adjustment = factors.adjustment();
+        self.calendar_factors = Some(factors);
+        adjustment
+    }
+    
+    /// Weekend day or holiday at the last risk calculation
+    fn market_closed(&self) -> bool {
+        self.calendar_factors.as_ref().is_some_and(|f| f.market_closed)
     }
     
     /// Calculate adaptive risk score
@@ -144,30 +366,57 @@ impl AdaptiveHeuristics {
     /// Risk score: 0-1 (normalized)
     /// Confidence: 0-1 (based on context and signal strength)
     pub fn calculate_risk(&mut self, features: &FeatureVector) -> (f32, f32) {
+        self.calculate_risk_for_pair(features, None)
+    }
+    
+    /// Calculate adaptive risk score against a token pair's own distribution
+    /// 
+    /// Memecoin pairs routinely see price impact that would be anomalous on
+    /// SOL/USDC, so once a pair has enough history its p90 tip and p95 price
+    /// impact replace the global thresholds (clamped to 1/4x-4x of base).
+    pub fn calculate_risk_for_pair(
+        &mut self,
+        features: &FeatureVector,
+        pair: Option<TokenPair>,
+    ) -> (f32, f32) {
         // Update time adjustment
         self.time_of_day_adjustment = self.calculate_time_adjustment();
         
+        // Pick up market volatility from MarketStats-populated features
+        if features.volatility_24h_pct > 0.0 {
+            self.update_volatility(features.volatility_24h_pct);
+        }
+        
         // Track historical data
-        self.tip_history.push_back(features.jito_tip_lamports);
+        self.tip_index.record(features.slot, features.jito_tip_lamports);
         self.price_impact_history.push_back(features.price_impact_bps as f32);
         
         // Maintain rolling window
-        if self.tip_history.len() > self.max_history {
-            self.tip_history.pop_front();
-        }
         if self.price_impact_history.len() > self.max_history {
             self.price_impact_history.pop_front();
         }
         
+        // Pair thresholds apply once warmed up; the transaction is scored
+        // against the pair's prior history and only recorded afterwards
+        let pair_state = pair
+            .and_then(|pair| self.pair_states.get(&pair))
+            .filter(|state| state.thresholds.is_some());
+        let (base_tip, base_price_impact) = match pair_state.and_then(|s| s.thresholds) {
+            Some(t) => self.clamp_pair_thresholds(&t),
+            None => (self.base_thresholds.high_tip as f32, self.base_thresholds.price_impact_bps),
+        };
+        
         let mut risk_factors = Vec::new();
         let mut confidence_factors = Vec::new();
         
         // 1. JITO TIP ANALYSIS (dynamic percentile-based)
-        let adjusted_tip_threshold = self.base_thresholds.high_tip as f32 
-            * (1.0 + self.network_congestion_factor);
+        let adjusted_tip_threshold = base_tip * (1.0 + self.network_congestion_factor);
         
         if features.jito_tip_lamports > adjusted_tip_threshold as u64 {
-            let tip_percentile = self.calculate_tip_percentile(features.jito_tip_lamports);
+            let tip_percentile = match pair_state {
+                Some(state) => state.tip_percentile(features.jito_tip_lamports),
+                None => self.calculate_tip_percentile(features.jito_tip_lamports),
+            };
             
             if tip_percentile > 95.0 {
                 // Research: >95th percentile = MEV bot behavior
@@ -183,8 +432,7 @@ impl AdaptiveHeuristics {
         }
         
         // 2. PRICE IMPACT (adjusted for volatility)
-        let adjusted_price_impact_threshold = self.base_thresholds.price_impact_bps 
-            * self.volatility_multiplier;
+        let adjusted_price_impact_threshold = base_price_impact * self.volatility_multiplier;
         
         if features.price_impact_bps > adjusted_price_impact_threshold as f64 {
             risk_factors.push(0.35);
@@ -211,13 +459,10 @@ impl AdaptiveHeuristics {
         }
         
         // 6. TIME-BASED RISK ADJUSTMENT
-        if features.is_dex_swap {
-            let weekday = Utc::now().weekday().num_days_from_monday();
-            if weekday >= 5 {
-                // Weekend memecoin risk
-                risk_factors.push(0.15);
-                confidence_factors.push(0.6);
-            }
+        if features.is_dex_swap && self.market_closed() {
+            // Weekend (and holiday) memecoin risk
+            risk_factors.push(0.15);
+            confidence_factors.push(0.6);
         }
         
         // 7. COMPUTE PRICE URGENCY
@@ -254,20 +499,100 @@ impl AdaptiveHeuristics {
             (0.15, 0.5) // Default low risk
         };
         
+        if let Some(pair) = pair {
+            self.pair_states
+                .touch(pair)
+                .observe(features.jito_tip_lamports, features.price_impact_bps as f32);
+        }
+        
         (risk_score, confidence)
     }
     
     /// Calculate tip percentile vs recent history
     fn calculate_tip_percentile(&self, tip: u64) -> f32 {
-        if self.tip_history.is_empty() {
-            return 50.0;
+        self.tip_index.percentile_of(tip)
+    }
+    
+    /// Streaming tip percentiles over the recent slot window
+    pub fn tip_percentiles(&self) -> TipPercentiles {
+        self.tip_index.percentiles()
+    }
+    
+    /// Keep pair-derived thresholds within a sane band around the global base
+    fn clamp_pair_thresholds(&self, thresholds: &PairThresholds) -> (f32, f32) {
+        let base_tip = self.base_thresholds.high_tip as f32;
+        let base_impact = self.base_thresholds.price_impact_bps;
+        (
+            (thresholds.high_tip as f32)
+                .clamp(base_tip / PAIR_THRESHOLD_CLAMP, base_tip * PAIR_THRESHOLD_CLAMP),
+            thresholds
+                .price_impact_bps
+                .clamp(base_impact / PAIR_THRESHOLD_CLAMP, base_impact * PAIR_THRESHOLD_CLAMP),
+        )
+    }
+    
+    /// Learned thresholds for a pair (None until it has enough history)
+    pub fn pair_thresholds(&self, pair: &TokenPair) -> Option<PairThresholds> {
+        self.pair_states.get(pair).and_then(|s| s.thresholds)
+    }
+    
+    /// Number of token pairs currently tracked
+    pub fn tracked_pairs(&self) -> usize {
+        self.pair_states.states.len()
+    }
+    
+    /// Capture learned state (histories, tip windows, market multipliers)
+    pub fn snapshot(&self) -> HeuristicsSnapshot {
+        let mut pairs: Vec<_> = self.pair_states.states.iter().collect();
+        pairs.sort_by_key(|(_, state)| state.last_used);
+        
+        HeuristicsSnapshot {
+            volatility_multiplier: self.volatility_multiplier,
+            network_congestion_factor: self.network_congestion_factor,
+            tip_index: self.tip_index.snapshot(),
+            price_impact_history: self.price_impact_history.iter().copied().collect(),
+            pairs: pairs
+                .into_iter()
+                .map(|((input, output), state)| PairSnapshot {
+                    input_mint: input.to_string(),
+                    output_mint: output.to_string(),
+                    tips: state.tip_history.iter().copied().collect(),
+                    price_impacts_bps: state.price_impact_history.iter().copied().collect(),
+                })
+                .collect(),
         }
+    }
+    
+    /// Replace learned state with a snapshot
+    ///
+    /// Pairs with unparseable mints or mismatched histories are skipped; pair
+    /// thresholds are recomputed from the restored histories.
+    pub fn restore(&mut self, snapshot: &HeuristicsSnapshot) {
+        self.volatility_multiplier = snapshot.volatility_multiplier.max(1.0);
+        self.network_congestion_factor = snapshot.network_congestion_factor.max(0.0);
+        self.tip_index.restore(&snapshot.tip_index);
         
-        let below_count = self.tip_history.iter()
-            .filter(|&&t| t < tip)
-            .count();
+        let skip = snapshot.price_impact_history.len().saturating_sub(self.max_history);
+        self.price_impact_history = snapshot.price_impact_history.iter().skip(skip).copied().collect();
         
-        (below_count as f32 / self.tip_history.len() as f32) * 100.0
+        self.pair_states = PairStateCache::new(self.pair_states.capacity);
+        for pair in &snapshot.pairs {
+            let (Ok(input), Ok(output)) = (Pubkey::from_str(&pair.input_mint), Pubkey::from_str(&pair.output_mint)) else {
+                continue;
+            };
+            if pair.tips.len() != pair.price_impacts_bps.len() {
+                continue;
+            }
+            
+            let skip = pair.tips.len().saturating_sub(PAIR_MAX_HISTORY);
+            let state = self.pair_states.touch((input, output));
+            state.tip_history = pair.tips.iter().skip(skip).copied().collect();
+            state.price_impact_history = pair.price_impacts_bps.iter().skip(skip).copied().collect();
+            state.thresholds = None;
+            if state.tip_history.len() >= PAIR_MIN_SAMPLES {
+                state.refresh_thresholds();
+            }
+        }
     }
     
     /// Get current threshold configuration (adjusted)
@@ -279,6 +604,7 @@ impl AdaptiveHeuristics {
                 * self.volatility_multiplier,
             validator_risk: self.base_thresholds.validator_risk,
             time_adjustment: self.time_of_day_adjustment,
+            calendar: self.calendar_factors.clone(),
         }
     }
 }
@@ -289,12 +615,16 @@ pub struct AdjustedThresholds {
     pub price_impact_bps: f32,
     pub validator_risk: f32,
     pub time_adjustment: f32,
+    
+    /// Calendar factors behind `time_adjustment` (None before the first calculation)
+    pub calendar: Option<CalendarFactors>,
 }
 
 /// Multi-stage MEV detection pipeline for false positive reduction
 /// 
 /// Research: Multi-stage validation reduces false positives by 45% (Chainalysis)
 /// Helius: Uses ensemble of 3+ detection methods
+#[derive(Clone)]
 pub struct MEVDetectionPipeline {
     stage1_heuristics: AdaptiveHeuristics,
     enable_pattern_validation: bool,
@@ -316,6 +646,35 @@ impl MEVDetectionPipeline {
         }
     }
     
+    /// Create pipeline with custom base thresholds
+    pub fn with_thresholds(thresholds: ThresholdConfig) -> Self {
+        Self {
+            stage1_heuristics: AdaptiveHeuristics::with_thresholds(thresholds),
+            ..Self::new()
+        }
+    }
+    
+    /// Read stage-1 time adjustments from `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.stage1_heuristics = self.stage1_heuristics.with_clock(clock);
+        self
+    }
+    
+    /// Replace stage-1 base thresholds (hot config reload)
+    pub fn set_thresholds(&mut self, thresholds: ThresholdConfig) {
+        self.stage1_heuristics.set_base_thresholds(thresholds);
+    }
+    
+    /// Replace the stage-1 market calendar (hot config reload)
+    pub fn set_calendar(&mut self, calendar: MarketCalendar) {
+        self.stage1_heuristics.set_calendar(calendar);
+    }
+    
+    /// Stage-1 thresholds and time-of-day factors currently in effect
+    pub fn adjusted_thresholds(&self) -> AdjustedThresholds {
+        self.stage1_heuristics.get_adjusted_thresholds()
+    }
+    
     /// Predict with multi-stage filtering
     /// 
     /// Stage 1: Fast heuristic filter (current system)
@@ -324,9 +683,19 @@ impl MEVDetectionPipeline {
     pub fn predict_with_confidence(
         &mut self,
         features: &FeatureVector,
+    ) -> Result<(MevRiskScore, f32)> {
+        self.predict_with_confidence_for_pair(features, None)
+    }
+    
+    /// Predict with multi-stage filtering using per-pair stage-1 thresholds
+    pub fn predict_with_confidence_for_pair(
+        &mut self,
+        features: &FeatureVector,
+        pair: Option<TokenPair>,
     ) -> Result<(MevRiskScore, f32)> {
         // Stage 1: Fast heuristic scoring
-        let (stage1_score, stage1_confidence) = self.stage1_heuristics.calculate_risk(features);
+        let (stage1_score, stage1_confidence) =
+            self.stage1_heuristics.calculate_risk_for_pair(features, pair);
         
         // Low risk: Return immediately with high confidence
         if stage1_score < 0.5 {
@@ -413,11 +782,36 @@ impl MEVDetectionPipeline {
             && features.next_leader_mev_rate > 0.3
     }
     
+    /// Streaming tip percentiles observed by stage 1
+    pub fn tip_percentiles(&self) -> TipPercentiles {
+        self.stage1_heuristics.tip_percentiles()
+    }
+    
+    /// Learned stage-1 thresholds for a pair
+    pub fn pair_thresholds(&self, pair: &TokenPair) -> Option<PairThresholds> {
+        self.stage1_heuristics.pair_thresholds(pair)
+    }
+    
     /// Update market conditions
     pub fn update_market_conditions(&mut self, volatility_24h_pct: f32, tps_utilization: f32) {
         self.stage1_heuristics.update_volatility(volatility_24h_pct);
         self.stage1_heuristics.update_congestion(tps_utilization);
     }
+    
+    /// Update network congestion only (see `CongestionMonitor`)
+    pub fn update_congestion(&mut self, tps_utilization: f32) {
+        self.stage1_heuristics.update_congestion(tps_utilization);
+    }
+    
+    /// Capture stage-1 learned state
+    pub fn snapshot(&self) -> HeuristicsSnapshot {
+        self.stage1_heuristics.snapshot()
+    }
+    
+    /// Restore stage-1 learned state
+    pub fn restore(&mut self, snapshot: &HeuristicsSnapshot) {
+        self.stage1_heuristics.restore(snapshot);
+    }
 }
 
 #[cfg(test)]
@@ -442,6 +836,36 @@ mod tests {
         assert_eq!(heuristics.volatility_multiplier, 1.5);
     }
     
+    #[test]
+    fn test_volatility_from_features() {
+        let mut heuristics = AdaptiveHeuristics::new();
+        let features = FeatureVector {
+            volatility_24h_pct: 35.0,
+            ..Default::default()
+        };
+        
+        heuristics.calculate_risk(&features);
+        assert_eq!(heuristics.volatility_multiplier, 1.2);
+    }
+    
+    #[test]
+    fn test_tip_percentiles_streamed() {
+        let mut heuristics = AdaptiveHeuristics::new();
+        for i in 1..=100u64 {
+            let features = FeatureVector {
+                slot: 1_000 + i,
+                jito_tip_lamports: i * 10_000,
+                ..Default::default()
+            };
+            heuristics.calculate_risk(&features);
+        }
+        
+        let percentiles = heuristics.tip_percentiles();
+        assert_eq!(percentiles.samples, 100);
+        assert!(percentiles.p95 > percentiles.p50);
+        assert!(heuristics.calculate_tip_percentile(2_000_000) > 99.0);
+    }
+    
     #[test]
     fn test_multi_stage_pipeline() {
         let mut pipeline = MEVDetectionPipeline::new();
@@ -451,6 +875,180 @@ mod tests {
         assert!(result.is_ok());
     }
     
+    #[test]
+    fn test_thresholds_from_settings() {
+        let settings = ThresholdSettings {
+            high_tip: 300_000,
+            ..Default::default()
+        };
+        let config = ThresholdConfig::from(&settings);
+        assert_eq!(config.high_tip, 300_000);
+        assert_eq!(config.validator_risk, 0.6);
+    }
+    
+    #[test]
+    fn test_pair_thresholds_learned() {
+        let mut heuristics = AdaptiveHeuristics::new();
+        let memecoin = (Pubkey::new_unique(), Pubkey::new_unique());
+        
+        // Memecoin pair routinely trades at ~400 bps impact
+        let normal = FeatureVector {
+            price_impact_bps: 400.0,
+            ..Default::default()
+        };
+        for _ in 0..PAIR_MIN_SAMPLES {
+            heuristics.calculate_risk_for_pair(&normal, Some(memecoin));
+        }
+        
+        let thresholds = heuristics.pair_thresholds(&memecoin).unwrap();
+        assert_eq!(thresholds.price_impact_bps, 400.0);
+        assert_eq!(thresholds.samples, PAIR_MIN_SAMPLES);
+        
+        // Same impact is flagged globally but normal for the pair
+        let (global_risk, _) = AdaptiveHeuristics::new().calculate_risk(&normal);
+        let (pair_risk, _) = heuristics.calculate_risk_for_pair(&normal, Some(memecoin));
+        assert!(pair_risk < global_risk);
+    }
+    
+    #[test]
+    fn test_pair_lru_eviction() {
+        let mut heuristics = AdaptiveHeuristics::new().with_pair_capacity(2);
+        let features = FeatureVector::default();
+        let pairs: Vec<TokenPair> = (0..3)
+            .map(|_| (Pubkey::new_unique(), Pubkey::new_unique()))
+            .collect();
+        
+        heuristics.calculate_risk_for_pair(&features, Some(pairs[0]));
+        heuristics.calculate_risk_for_pair(&features, Some(pairs[1]));
+        heuristics.calculate_risk_for_pair(&features, Some(pairs[0]));
+        heuristics.calculate_risk_for_pair(&features, Some(pairs[2]));
+        
+        assert_eq!(heuristics.tracked_pairs(), 2);
+        assert!(heuristics.pair_states.get(&pairs[0]).is_some());
+        assert!(heuristics.pair_states.get(&pairs[1]).is_none());
+    }
+    
+    #[test]
+    fn test_pair_lru_survives_repeated_touches() {
+        let mut cache = PairStateCache::new(2);
+        let pairs: Vec<TokenPair> = (0..3)
+            .map(|_| (Pubkey::new_unique(), Pubkey::new_unique()))
+            .collect();
+        
+        cache.touch(pairs[0]);
+        cache.touch(pairs[1]);
+        for _ in 0..10 {
+            cache.touch(pairs[0]);
+        }
+        assert!(cache.recency.len() <= 4);
+        
+        cache.touch(pairs[2]);
+        assert!(cache.get(&pairs[0]).is_some());
+        assert!(cache.get(&pairs[1]).is_none());
+        assert!(cache.get(&pairs[2]).is_some());
+    }
+    
+    #[test]
+    fn test_pair_scored_before_recording() {
+        let mut heuristics = AdaptiveHeuristics::new();
+        let pair = (Pubkey::new_unique(), Pubkey::new_unique());
+        let normal = FeatureVector { price_impact_bps: 400.0, ..Default::default() };
+        for _ in 0..PAIR_MIN_SAMPLES - 1 {
+            heuristics.calculate_risk_for_pair(&normal, Some(pair));
+        }
+        
+        // The warming-up sample is scored globally, then completes the baseline
+        let (global_risk, _) = AdaptiveHeuristics::new().calculate_risk(&normal);
+        let (risk, _) = heuristics.calculate_risk_for_pair(&normal, Some(pair));
+        assert_eq!(risk, global_risk);
+        assert!(heuristics.pair_thresholds(&pair).is_some());
+        
+        let (pair_risk, _) = heuristics.calculate_risk_for_pair(&normal, Some(pair));
+        assert!(pair_risk < global_risk);
+    }
+    
+    #[test]
+    fn test_snapshot_restores_pair_thresholds() {
+        let mut heuristics = AdaptiveHeuristics::new();
+        heuristics.update_congestion(0.9);
+        let pair = (Pubkey::new_unique(), Pubkey::new_unique());
+        for i in 0..PAIR_MIN_SAMPLES as u64 {
+            let features = FeatureVector {
+                slot: 1_000 + i,
+                jito_tip_lamports: 50_000 + i * 1_000,
+                price_impact_bps: 400.0,
+                ..Default::default()
+            };
+            heuristics.calculate_risk_for_pair(&features, Some(pair));
+        }
+        
+        let json = serde_json::to_string(&heuristics.snapshot()).unwrap();
+        let mut restored = AdaptiveHeuristics::new();
+        restored.restore(&serde_json::from_str(&json).unwrap());
+        
+        assert_eq!(restored.pair_thresholds(&pair), heuristics.pair_thresholds(&pair));
+        assert_eq!(restored.tip_percentiles(), heuristics.tip_percentiles());
+        assert_eq!(restored.get_adjusted_thresholds().high_tip, heuristics.get_adjusted_thresholds().high_tip);
+        assert_eq!(restored.snapshot(), heuristics.snapshot());
+    }
+    
+    #[test]
+    fn test_time_adjustment_follows_clock() {
+        use chrono::{TimeZone, Utc};
+        use sentinel_core::ManualClock;
+        use std::sync::Arc;
+        
+        // Saturday 15:00 UTC: weekend (1.3); no session runs while the market is closed
+        let clock = Arc::new(ManualClock::new(Utc.with_ymd_and_hms(2025, 1, 4, 15, 0, 0).unwrap()));
+        let mut heuristics = AdaptiveHeuristics::new().with_clock(clock.clone());
+        assert!(heuristics.get_adjusted_thresholds().calendar.is_none());
+        heuristics.calculate_risk(&FeatureVector::default());
+        assert!((heuristics.get_adjusted_thresholds().time_adjustment - 1.3).abs() < 1e-6);
+        
+        // Monday 15:00 UTC: US market hours (1.1)
+        clock.set(Utc.with_ymd_and_hms(2025, 1, 6, 15, 0, 0).unwrap());
+        heuristics.calculate_risk(&FeatureVector::default());
+        let adjusted = heuristics.get_adjusted_thresholds();
+        assert!((adjusted.time_adjustment - 1.1).abs() < 1e-6);
+        assert_eq!(adjusted.calendar.unwrap().session.as_deref(), Some("us_equities"));
+        
+        // Monday 03:00 UTC is still Sunday evening in New York
+        clock.set(Utc.with_ymd_and_hms(2025, 1, 6, 3, 0, 0).unwrap());
+        heuristics.calculate_risk(&FeatureVector::default());
+        assert!(heuristics.get_adjusted_thresholds().calendar.unwrap().market_closed);
+        
+        // Monday 23:00 UTC: after the close, no adjustment
+        clock.set(Utc.with_ymd_and_hms(2025, 1, 6, 23, 0, 0).unwrap());
+        heuristics.calculate_risk(&FeatureVector::default());
+        assert_eq!(heuristics.get_adjusted_thresholds().time_adjustment, 1.0);
+    }
+    
+    #[test]
+    fn test_holiday_counts_as_weekend() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+        use sentinel_core::{CalendarSettings, ManualClock};
+        use std::sync::Arc;
+        
+        // Thursday, but a market holiday
+        let clock = Arc::new(ManualClock::new(Utc.with_ymd_and_hms(2025, 12, 25, 15, 0, 0).unwrap()));
+        let calendar = MarketCalendar::new(&CalendarSettings {
+            holidays: vec![NaiveDate::from_ymd_opt(2025, 12, 25).unwrap()],
+            ..Default::default()
+        })
+        .unwrap();
+        let features = FeatureVector { is_dex_swap: true, ..Default::default() };
+        
+        let mut plain = AdaptiveHeuristics::new().with_clock(clock.clone());
+        let (_, weekday_confidence) = plain.calculate_risk(&features);
+        
+        // The weekend memecoin signal fires on the holiday
+        let mut heuristics = AdaptiveHeuristics::new().with_clock(clock);
+        heuristics.set_calendar(calendar);
+        let (_, holiday_confidence) = heuristics.calculate_risk(&features);
+        assert!(holiday_confidence > weekday_confidence);
+        assert!(heuristics.get_adjusted_thresholds().calendar.unwrap().holiday);
+    }
+    
     #[test]
     fn test_lowered_validator_threshold() {
         let config = ThresholdConfig::default();
@@ -0,0 +1,40 @@
+//! Decision replay CLI
+//!
+//! ```text
+//! sentinel-replay <decisions.jsonl> [--request <id>]
+//! ```
+//!
+//! Re-scores and re-routes logged decisions with this build and prints the
+//! JSON [`ReplayReport`](ai_engine::ReplayReport). Exits non-zero when any
+//! decision did not reproduce.
+//!
+//! Environment:
+//! - `SENTINEL_CONFIG`    optional TOML config to replay under (default config otherwise)
+
+use ai_engine::{DecisionReplayer, ReplayVerdict};
+use sentinel_core::SentinelConfig;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: sentinel-replay <decisions.jsonl> [--request <id>]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let log = args.first().filter(|arg| !arg.starts_with("--")).ok_or(USAGE)?;
+    let request = match args.iter().position(|arg| arg == "--request") {
+        Some(i) => Some(args.get(i + 1).ok_or(USAGE)?.as_str()),
+        None => None,
+    };
+
+    let config_path = std::env::var_os("SENTINEL_CONFIG").map(PathBuf::from);
+    let config = SentinelConfig::load(config_path.as_deref())?;
+    let replayer = DecisionReplayer::new(&config)?;
+
+    let report = replayer.replay_log(log, request)?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    let diverged = report.verdicts.iter().any(|(verdict, n)| *verdict != ReplayVerdict::Reproduced && *n > 0);
+    if diverged {
+        std::process::exit(1);
+    }
+    Ok(())
+}
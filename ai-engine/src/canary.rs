@@ -0,0 +1,450 @@
+//! Canary Routing
+//!
+//! Shadow mode scores a candidate model without acting on it. `CanaryRouter`
+//! is the next step: a small, configurable share of live intents is scored by
+//! the candidate engine and acted on, while the rest stay on production.
+//!
+//! Assignment is a stable hash of the request id, so retries of the same intent
+//! land on the same arm. Outcomes (landing, realized slippage, false blocks) are
+//! tracked per arm; once both arms have enough samples and the canary is worse
+//! than production beyond the configured tolerances, the canary is rolled back
+//! and all traffic returns to production.
+
+use sentinel_core::{MevRiskScore, Result};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::{info, warn};
+
+use crate::features_enhanced::FeatureVector;
+use crate::inference_enhanced::InferenceEngine;
+
+/// Assignment resolution (1 bucket = 0.01% of traffic)
+const BASIS_POINTS: u64 = 10_000;
+
+/// Canary arm of a routed request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanaryArm {
+    Production,
+    Canary,
+}
+
+/// Observed result of an intent routed through an arm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CanaryOutcome {
+    /// Transaction landed with the given realized slippage
+    Landed { realized_slippage_bps: f64 },
+
+    /// Submitted but did not land (expired, dropped, failed)
+    Failed,
+
+    /// Blocked by the model; `false_positive` once review shows it was benign
+    Blocked { false_positive: bool },
+}
+
+/// Traffic share and rollback policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanaryConfig {
+    /// Share of traffic routed to the candidate (0-100)
+    pub traffic_percent: f64,
+
+    /// Samples required on each arm before rollback is evaluated
+    pub min_samples: u64,
+
+    /// Allowed landing-rate shortfall vs production (absolute, 0-1)
+    pub max_landing_rate_drop: f64,
+
+    /// Allowed increase in mean realized slippage (bps)
+    pub max_slippage_increase_bps: f64,
+
+    /// Allowed false-block rate increase vs production (absolute, 0-1)
+    pub max_false_block_rate_increase: f64,
+}
+
+impl Default for CanaryConfig {
+    fn default() -> Self {
+        Self {
+            traffic_percent: 5.0,
+            min_samples: 200,
+            max_landing_rate_drop: 0.02,
+            max_slippage_increase_bps: 5.0,
+            max_false_block_rate_increase: 0.01,
+        }
+    }
+}
+
+/// Outcome counters for one arm
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ArmMetrics {
+    pub predictions: u64,
+    pub landed: u64,
+    pub failed: u64,
+    pub blocked: u64,
+    pub false_blocks: u64,
+    pub slippage_bps_sum: f64,
+}
+
+impl ArmMetrics {
+    /// Intents with a recorded outcome
+    pub fn outcomes(&self) -> u64 {
+        self.landed + self.failed + self.blocked
+    }
+
+    /// Landed / submitted (blocked intents are not submitted)
+    pub fn landing_rate(&self) -> f64 {
+        ratio(self.landed, self.landed + self.failed)
+    }
+
+    /// Mean realized slippage of landed intents (bps)
+    pub fn mean_slippage_bps(&self) -> f64 {
+        if self.landed == 0 {
+            return 0.0;
+        }
+        self.slippage_bps_sum / self.landed as f64
+    }
+
+    /// False blocks / all intents with an outcome
+    pub fn false_block_rate(&self) -> f64 {
+        ratio(self.false_blocks, self.outcomes())
+    }
+
+    fn record(&mut self, outcome: CanaryOutcome) {
+        match outcome {
+            CanaryOutcome::Landed { realized_slippage_bps } => {
+                self.landed += 1;
+                self.slippage_bps_sum += realized_slippage_bps;
+            }
+            CanaryOutcome::Failed => self.failed += 1,
+            CanaryOutcome::Blocked { false_positive } => {
+                self.blocked += 1;
+                if false_positive {
+                    self.false_blocks += 1;
+                }
+            }
+        }
+    }
+}
+
+fn ratio(num: u64, den: u64) -> f64 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f64 / den as f64
+    }
+}
+
+/// Canary state for monitoring
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStats {
+    pub active: bool,
+    pub traffic_percent: f64,
+    pub production: ArmMetrics,
+    pub canary: ArmMetrics,
+    pub rollback_reason: Option<String>,
+}
+
+#[derive(Default)]
+struct CanaryState {
+    production: ArmMetrics,
+    canary: ArmMetrics,
+    rollback_reason: Option<String>,
+}
+
+impl CanaryState {
+    fn arm(&mut self, arm: CanaryArm) -> &mut ArmMetrics {
+        match arm {
+            CanaryArm::Production => &mut self.production,
+            CanaryArm::Canary => &mut self.canary,
+        }
+    }
+}
+
+/// Splits live traffic between a production and a candidate engine
+pub struct CanaryRouter {
+    production: Arc<InferenceEngine>,
+    candidate: Arc<InferenceEngine>,
+    config: CanaryConfig,
+    active: AtomicBool,
+    traffic_bps: AtomicU32,
+    state: Mutex<CanaryState>,
+}
+
+impl CanaryRouter {
+    /// Route `config.traffic_percent` of traffic to `candidate`
+    pub fn new(
+        production: Arc<InferenceEngine>,
+        candidate: Arc<InferenceEngine>,
+        config: CanaryConfig,
+    ) -> Self {
+        let router = Self {
+            production,
+            candidate,
+            active: AtomicBool::new(true),
+            traffic_bps: AtomicU32::new(0),
+            config,
+            state: Mutex::new(CanaryState::default()),
+        };
+        router.set_traffic_percent(router.config.traffic_percent);
+
+        info!(
+            "🐤 Canary routing {:.2}% of traffic to candidate model",
+            router.traffic_percent()
+        );
+        router
+    }
+
+    /// Whether the canary still receives traffic
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Current canary share (0-100)
+    pub fn traffic_percent(&self) -> f64 {
+        self.traffic_bps.load(Ordering::Relaxed) as f64 / 100.0
+    }
+
+    /// Adjust the canary share (clamped to 0-100)
+    pub fn set_traffic_percent(&self, percent: f64) {
+        let bps = (percent.clamp(0.0, 100.0) * 100.0).round() as u32;
+        self.traffic_bps.store(bps, Ordering::Relaxed);
+    }
+
+    /// Arm a request is routed to (stable per request id)
+    pub fn assign(&self, request_id: &str) -> CanaryArm {
+        if !self.is_active() {
+            return CanaryArm::Production;
+        }
+
+        let bucket = fnv1a(request_id.as_bytes()) % BASIS_POINTS;
+        if bucket < u64::from(self.traffic_bps.load(Ordering::Relaxed)) {
+            CanaryArm::Canary
+        } else {
+            CanaryArm::Production
+        }
+    }
+
+    /// Engine serving an arm
+    pub fn engine(&self, arm: CanaryArm) -> &Arc<InferenceEngine> {
+        match arm {
+            CanaryArm::Production => &self.production,
+            CanaryArm::Canary => &self.candidate,
+        }
+    }
+
+    /// Score a request on its assigned arm
+    ///
+    /// A candidate error falls back to production so the canary can never fail
+    /// a live intent; the fallback is attributed to the production arm.
+    pub fn predict(&self, features: &FeatureVector, request_id: &str) -> Result<(CanaryArm, MevRiskScore)> {
+        let arm = self.assign(request_id);
+
+        let (arm, score) = match arm {
+            CanaryArm::Canary => match self.candidate.predict(features) {
+                Ok(score) => (arm, score),
+                Err(e) => {
+                    warn!("Canary prediction failed for {}, using production: {}", request_id, e);
+                    (CanaryArm::Production, self.production.predict(features)?)
+                }
+            },
+            CanaryArm::Production => (arm, self.production.predict(features)?),
+        };
+
+        self.lock().arm(arm).predictions += 1;
+        Ok((arm, score))
+    }
+
+    /// Record an outcome and roll back if the canary underperforms
+    pub fn record_outcome(&self, arm: CanaryArm, outcome: CanaryOutcome) {
+        let mut state = self.lock();
+        state.arm(arm).record(outcome);
+
+        if arm != CanaryArm::Canary || !self.is_active() {
+            return;
+        }
+
+        if let Some(reason) = self.regression(&state.production, &state.canary) {
+            self.active.store(false, Ordering::Relaxed);
+            warn!("🔙 Canary rolled back: {}", reason);
+            state.rollback_reason = Some(reason);
+        }
+    }
+
+    /// Stop routing to the canary
+    pub fn rollback(&self, reason: impl Into<String>) {
+        self.active.store(false, Ordering::Relaxed);
+        let reason = reason.into();
+        warn!("🔙 Canary rolled back: {}", reason);
+        self.lock().rollback_reason = Some(reason);
+    }
+
+    /// Resume the canary with fresh metrics
+    pub fn restart(&self) {
+        *self.lock() = CanaryState::default();
+        self.active.store(true, Ordering::Relaxed);
+        info!("🐤 Canary restarted at {:.2}%", self.traffic_percent());
+    }
+
+    pub fn stats(&self) -> CanaryStats {
+        let state = self.lock();
+        CanaryStats {
+            active: self.is_active(),
+            traffic_percent: self.traffic_percent(),
+            production: state.production.clone(),
+            canary: state.canary.clone(),
+            rollback_reason: state.rollback_reason.clone(),
+        }
+    }
+
+    fn regression(&self, production: &ArmMetrics, canary: &ArmMetrics) -> Option<String> {
+        let min = self.config.min_samples;
+        if production.outcomes() < min || canary.outcomes() < min {
+            return None;
+        }
+
+        let landing_drop = production.landing_rate() - canary.landing_rate();
+        if landing_drop > self.config.max_landing_rate_drop {
+            return Some(format!(
+                "landing rate {:.3} vs production {:.3}",
+                canary.landing_rate(),
+                production.landing_rate()
+            ));
+        }
+
+        let slippage_increase = canary.mean_slippage_bps() - production.mean_slippage_bps();
+        if slippage_increase > self.config.max_slippage_increase_bps {
+            return Some(format!(
+                "mean slippage {:.1}bps vs production {:.1}bps",
+                canary.mean_slippage_bps(),
+                production.mean_slippage_bps()
+            ));
+        }
+
+        let false_block_increase = canary.false_block_rate() - production.false_block_rate();
+        if false_block_increase > self.config.max_false_block_rate_increase {
+            return Some(format!(
+                "false-block rate {:.3} vs production {:.3}",
+                canary.false_block_rate(),
+                production.false_block_rate()
+            ));
+        }
+
+        None
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CanaryState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// FNV-1a: stable across processes and releases, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+
+    fn engine() -> Arc<InferenceEngine> {
+        let mut engine = InferenceEngine::new(ModelConfig::default()).unwrap();
+        engine.warmup().unwrap();
+        Arc::new(engine)
+    }
+
+    fn router(config: CanaryConfig) -> CanaryRouter {
+        CanaryRouter::new(engine(), engine(), config)
+    }
+
+    fn fill(router: &CanaryRouter, arm: CanaryArm, landed: u64, failed: u64, slippage: f64) {
+        for _ in 0..landed {
+            router.record_outcome(arm, CanaryOutcome::Landed { realized_slippage_bps: slippage });
+        }
+        for _ in 0..failed {
+            router.record_outcome(arm, CanaryOutcome::Failed);
+        }
+    }
+
+    #[test]
+    fn test_assignment_share_and_stability() {
+        let router = router(CanaryConfig {
+            traffic_percent: 10.0,
+            ..Default::default()
+        });
+
+        let canary = (0..10_000)
+            .filter(|i| router.assign(&format!("req-{}", i)) == CanaryArm::Canary)
+            .count();
+        assert!((800..1_200).contains(&canary), "canary share {}", canary);
+        assert_eq!(router.assign("req-42"), router.assign("req-42"));
+
+        router.set_traffic_percent(0.0);
+        assert_eq!(router.assign("req-42"), CanaryArm::Production);
+    }
+
+    #[test]
+    fn test_predict_tracks_arm() {
+        let router = router(CanaryConfig {
+            traffic_percent: 100.0,
+            ..Default::default()
+        });
+
+        let (arm, score) = router.predict(&FeatureVector::default(), "req-1").unwrap();
+        assert_eq!(arm, CanaryArm::Canary);
+        assert!((0.0..=1.0).contains(&score.0));
+        assert_eq!(router.stats().canary.predictions, 1);
+    }
+
+    #[test]
+    fn test_rollback_on_landing_regression() {
+        let router = router(CanaryConfig {
+            traffic_percent: 50.0,
+            min_samples: 20,
+            ..Default::default()
+        });
+
+        fill(&router, CanaryArm::Production, 19, 1, 10.0);
+        fill(&router, CanaryArm::Canary, 17, 3, 10.0);
+
+        let stats = router.stats();
+        assert!(!stats.active);
+        assert!(stats.rollback_reason.unwrap().contains("landing rate"));
+        assert_eq!(router.assign("any"), CanaryArm::Production);
+
+        router.restart();
+        assert!(router.is_active());
+        assert_eq!(router.stats().canary, ArmMetrics::default());
+    }
+
+    #[test]
+    fn test_no_rollback_within_tolerance() {
+        let router = router(CanaryConfig {
+            min_samples: 20,
+            ..Default::default()
+        });
+
+        fill(&router, CanaryArm::Production, 20, 0, 10.0);
+        fill(&router, CanaryArm::Canary, 20, 0, 12.0);
+        router.record_outcome(CanaryArm::Canary, CanaryOutcome::Blocked { false_positive: false });
+
+        assert!(router.is_active());
+    }
+
+    #[test]
+    fn test_rollback_on_false_blocks() {
+        let router = router(CanaryConfig {
+            min_samples: 10,
+            ..Default::default()
+        });
+
+        fill(&router, CanaryArm::Production, 20, 0, 10.0);
+        fill(&router, CanaryArm::Canary, 18, 0, 10.0);
+        router.record_outcome(CanaryArm::Canary, CanaryOutcome::Blocked { false_positive: true });
+        router.record_outcome(CanaryArm::Canary, CanaryOutcome::Blocked { false_positive: true });
+
+        assert!(router.stats().rollback_reason.unwrap().contains("false-block"));
+    }
+}
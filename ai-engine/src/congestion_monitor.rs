@@ -0,0 +1,256 @@
+//! RPC Congestion Monitor
+//!
+//! Polls `getRecentPerformanceSamples` (and, with `congestion.block_fullness`,
+//! the compute units consumed by the latest block) and turns the samples into
+//! a [`CongestionReading`]. Each reading is pushed to:
+//! - every attached engine / pool (`update_congestion`), which scales the
+//!   adaptive tip thresholds
+//! - a shared [`CongestionCache`] that fee and tip logic read
+//!
+//! Callers no longer compute TPS utilization themselves; `update_market_conditions`
+//! remains for volatility.
+
+use reqwest::Client;
+use sentinel_core::congestion::{block_fullness, parse_performance_samples};
+use sentinel_core::{
+    system_clock, CircuitBreaker, CongestionCache, CongestionReading, CongestionSettings, Result, SentinelConfig,
+    SentinelError, SharedClock,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::inference_enhanced::InferenceEngine;
+use crate::inference_pool::InferencePool;
+
+/// Poller publishing network congestion to the heuristics and fee logic
+pub struct CongestionMonitor {
+    http_client: Client,
+    rpc_url: String,
+    settings: CongestionSettings,
+    cache: CongestionCache,
+    pools: Vec<Arc<InferencePool>>,
+    engines: Vec<Arc<InferenceEngine>>,
+    clock: SharedClock,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl CongestionMonitor {
+    pub fn new(rpc_url: impl Into<String>, settings: CongestionSettings) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+            settings,
+            cache: CongestionCache::default(),
+            pools: Vec::new(),
+            engines: Vec::new(),
+            clock: system_clock(),
+            breaker: None,
+        })
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Result<Self> {
+        Self::new(config.endpoints.rpc_url.clone(), config.congestion.clone())
+    }
+
+    /// Publish into an existing cache (shared with fee / tip logic)
+    pub fn with_cache(mut self, cache: CongestionCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Push readings into every engine of `pool`
+    pub fn with_pool(mut self, pool: Arc<InferencePool>) -> Self {
+        self.pools.push(pool);
+        self
+    }
+
+    /// Push readings into a standalone engine
+    pub fn with_engine(mut self, engine: Arc<InferenceEngine>) -> Self {
+        self.engines.push(engine);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Skip polls while the RPC endpoint keeps failing (the last reading stays published)
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    pub fn settings(&self) -> &CongestionSettings {
+        &self.settings
+    }
+
+    pub fn cache(&self) -> &CongestionCache {
+        &self.cache
+    }
+
+    async fn rpc_call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let call = async {
+            self.http_client
+                .post(&self.rpc_url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| SentinelError::RpcError(format!("{} failed: {}", method, e)))?
+                .json::<serde_json::Value>()
+                .await
+                .map_err(|e| SentinelError::RpcError(format!("Invalid {} response: {}", method, e)))
+        };
+        // JSON-RPC errors below are answers, not outages
+        let response = match self.breaker {
+            Some(ref breaker) => breaker.call(call).await?,
+            None => call.await?,
+        };
+
+        if let Some(error) = response.get("error") {
+            return Err(SentinelError::RpcError(format!("{}: {}", method, error)));
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| SentinelError::RpcError(format!("{}: missing result", method)))
+    }
+
+    /// Compute-unit fullness of the block at `slot`; None when the block is unavailable
+    async fn fetch_block_fullness(&self, slot: u64) -> Option<f32> {
+        let params = serde_json::json!([slot, {
+            "encoding": "base64",
+            "transactionDetails": "full",
+            "rewards": false,
+            "maxSupportedTransactionVersion": 0,
+        }]);
+        match self.rpc_call("getBlock", params).await {
+            Ok(block) => block_fullness(&block, self.settings.max_block_compute_units),
+            Err(e) => {
+                debug!("Congestion: block fullness unavailable: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Sample the network without publishing
+    pub async fn fetch(&self) -> Result<CongestionReading> {
+        let result = self
+            .rpc_call("getRecentPerformanceSamples", serde_json::json!([self.settings.sample_count]))
+            .await?;
+        let samples = parse_performance_samples(&result)?;
+
+        let fullness = match samples.first() {
+            Some(newest) if self.settings.block_fullness => self.fetch_block_fullness(newest.slot).await,
+            _ => None,
+        };
+        CongestionReading::from_samples(&samples, fullness, &self.settings, self.clock.unix_millis())
+    }
+
+    /// Sample, then push into the cache and every attached engine
+    pub async fn refresh(&self) -> Result<CongestionReading> {
+        let reading = self.fetch().await?;
+        self.cache.update(reading);
+        for pool in &self.pools {
+            pool.update_congestion(reading.utilization);
+        }
+        for engine in &self.engines {
+            engine.update_congestion(reading.utilization);
+        }
+        debug!(
+            "🚦 Congestion: {:.0} TPS, utilization={:.1}%, trend={:+.1}%",
+            reading.tps,
+            reading.utilization * 100.0,
+            reading.trend * 100.0
+        );
+        Ok(reading)
+    }
+
+    /// Refresh every `poll_interval_ms` until the task is aborted
+    ///
+    /// Failures are logged and the engines keep their last congestion factor.
+    pub fn spawn_polling(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(self.settings.poll_interval_ms.max(1_000)));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    warn!("Congestion refresh failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serves the same JSON-RPC result on every connection
+    async fn serve_result(result: serde_json::Value) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_refresh_pushes_into_engines_and_cache() {
+        // 4,500 TPS against the default 5,000 capacity: 90% utilization
+        let samples = serde_json::json!([
+            { "slot": 300, "numTransactions": 270_000, "numSlots": 150, "samplePeriodSecs": 60 },
+            { "slot": 150, "numTransactions": 270_000, "numSlots": 150, "samplePeriodSecs": 60 }
+        ]);
+        let url = serve_result(samples).await;
+        let pool = Arc::new(InferencePool::new(ModelConfig::default(), 2).unwrap());
+        let before = pool.engine().adjusted_thresholds().high_tip;
+
+        let monitor = CongestionMonitor::new(url, CongestionSettings::default())
+            .unwrap()
+            .with_pool(pool.clone());
+        let reading = monitor.refresh().await.unwrap();
+
+        assert!((reading.utilization - 0.9).abs() < 1e-6);
+        assert_eq!(reading.trend, 0.0);
+        assert_eq!(monitor.cache().latest(), Some(reading));
+        for _ in 0..2 {
+            assert!(pool.engine().adjusted_thresholds().high_tip > before);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rejects_malformed_samples() {
+        let url = serve_result(serde_json::json!({ "unexpected": true })).await;
+        let monitor = CongestionMonitor::new(url, CongestionSettings::default()).unwrap();
+        assert!(monitor.refresh().await.is_err());
+        assert_eq!(monitor.cache().latest(), None);
+    }
+}
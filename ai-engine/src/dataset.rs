@@ -0,0 +1,576 @@
+//! Labeled Training Dataset Export
+//!
+//! Joins shadow-mode prediction logs (feature vectors) with the routing decision
+//! log (confirmed MEV incidents) on `request_id` and exports labeled rows in the
+//! exact model input order, so the ML team can retrain the ONNX model without
+//! reverse-engineering the Rust feature layout.
+//!
+//! Outputs:
+//! - CSV with a header row plus a `<path>.schema.json` sidecar
+//! - Parquet (feature `parquet`) with the schema stored as key-value metadata
+//!
+//! Every export records `DATASET_SCHEMA_VERSION`; bump it whenever a column is
+//! added, removed or reordered.
+
+use chrono::Utc;
+use sentinel_core::{DecisionRecord, Result, SentinelError};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::enhanced_features::EnhancedFeatureVector;
+use crate::features_enhanced::FeatureVector;
+use crate::shadow_mode::ShadowPrediction;
+
+/// Dataset schema version (bump on any column change)
+pub const DATASET_SCHEMA_VERSION: &str = "1.0.0";
+
+/// Feature layout of exported rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureLayout {
+    /// Production 55-feature vector
+    Base55,
+    /// 55 base + 12 Jito/validator/CPI features
+    Enhanced67,
+}
+
+impl FeatureLayout {
+    pub fn width(&self) -> usize {
+        match self {
+            FeatureLayout::Base55 => FeatureVector::FEATURE_COUNT,
+            FeatureLayout::Enhanced67 => EnhancedFeatureVector::ENHANCED_FEATURE_COUNT,
+        }
+    }
+
+    /// Feature column names in model input order
+    pub fn feature_names(&self) -> Vec<&'static str> {
+        let mut names = FeatureVector::FEATURE_NAMES.to_vec();
+        if *self == FeatureLayout::Enhanced67 {
+            names.extend_from_slice(&EnhancedFeatureVector::ENHANCED_FEATURE_NAMES);
+        }
+        names
+    }
+}
+
+/// Where a row's label came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelSource {
+    /// Post-trade confirmation recorded in the decision log
+    Confirmed,
+    /// Production model classification (weak label, opt-in)
+    Production,
+    /// Cross-slot sandwich triplet settled from the block stream
+    Triplet,
+}
+
+impl LabelSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LabelSource::Confirmed => "confirmed",
+            LabelSource::Production => "production",
+            LabelSource::Triplet => "triplet",
+        }
+    }
+}
+
+/// Single labeled training row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabeledExample {
+    pub request_id: String,
+    pub timestamp_ms: u64,
+    pub features: Vec<f32>,
+    pub label: bool,
+    pub label_source: LabelSource,
+}
+
+/// Schema metadata written alongside every export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetSchema {
+    pub schema_version: String,
+    pub feature_layout: FeatureLayout,
+    pub feature_count: usize,
+    /// All columns in file order (metadata, features, label)
+    pub columns: Vec<String>,
+    pub row_count: usize,
+    pub positive_count: usize,
+    pub created_at: String,
+}
+
+/// Rows dropped while building a dataset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetStats {
+    pub shadow_records: usize,
+    pub decision_records: usize,
+    pub unlabeled: usize,
+    pub wrong_width: usize,
+    pub malformed_lines: usize,
+}
+
+/// Leading/trailing non-feature columns
+const META_COLUMNS: [&str; 2] = ["request_id", "timestamp_ms"];
+const LABEL_COLUMNS: [&str; 2] = ["label", "label_source"];
+
+/// Joins shadow logs and decision logs into a labeled dataset
+#[derive(Debug)]
+pub struct DatasetBuilder {
+    layout: FeatureLayout,
+    include_weak_labels: bool,
+    shadow: Vec<ShadowPrediction>,
+    incidents: HashMap<String, bool>,
+    labeled: Vec<LabeledExample>,
+    stats: DatasetStats,
+}
+
+impl DatasetBuilder {
+    pub fn new(layout: FeatureLayout) -> Self {
+        Self {
+            layout,
+            include_weak_labels: false,
+            shadow: Vec::new(),
+            incidents: HashMap::new(),
+            labeled: Vec::new(),
+            stats: DatasetStats::default(),
+        }
+    }
+
+    /// Fall back to the production classification when no confirmation exists
+    pub fn include_weak_labels(mut self, include: bool) -> Self {
+        self.include_weak_labels = include;
+        self
+    }
+
+    /// Load a shadow-mode JSONL log
+    pub fn load_shadow_log(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let records: Vec<ShadowPrediction> = read_jsonl(path.as_ref(), &mut self.stats)?;
+        self.stats.shadow_records += records.len();
+        self.shadow.extend(records);
+        Ok(self)
+    }
+
+    /// Load a decision JSONL log (records with `mev_incident` set become labels)
+    pub fn load_decision_log(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let records: Vec<DecisionRecord> = read_jsonl(path.as_ref(), &mut self.stats)?;
+        self.stats.decision_records += records.len();
+        self.add_decisions(records);
+        Ok(self)
+    }
+
+    pub fn add_shadow_predictions(mut self, predictions: impl IntoIterator<Item = ShadowPrediction>) -> Self {
+        let before = self.shadow.len();
+        self.shadow.extend(predictions);
+        self.stats.shadow_records += self.shadow.len() - before;
+        self
+    }
+
+    /// Already-labeled rows (e.g. settled [`TripletConfirmer`](crate::triplet_confirmation::TripletConfirmer)
+    /// outcomes); a shadow row with the same `request_id` takes precedence
+    pub fn add_examples(mut self, examples: impl IntoIterator<Item = LabeledExample>) -> Self {
+        self.labeled.extend(examples);
+        self
+    }
+
+    pub fn add_decisions(&mut self, records: impl IntoIterator<Item = DecisionRecord>) {
+        for record in records {
+            if let Some(confirmed) = record.mev_incident {
+                self.incidents.insert(record.request_id, confirmed);
+            }
+        }
+    }
+
+    /// Join and label
+    pub fn build(self) -> Dataset {
+        let width = self.layout.width();
+        let mut stats = self.stats;
+        let mut examples = Vec::with_capacity(self.shadow.len());
+
+        for prediction in self.shadow {
+            let (label, label_source) = match self.incidents.get(&prediction.request_id) {
+                Some(&confirmed) => (confirmed, LabelSource::Confirmed),
+                None => match prediction.production_is_mev {
+                    Some(is_mev) if self.include_weak_labels => (is_mev, LabelSource::Production),
+                    _ => {
+                        stats.unlabeled += 1;
+                        continue;
+                    }
+                },
+            };
+
+            let features: Option<Vec<f32>> = serde_json::from_value(prediction.features).ok();
+            let Some(features) = features.filter(|f| f.len() == width) else {
+                stats.wrong_width += 1;
+                continue;
+            };
+
+            examples.push(LabeledExample {
+                request_id: prediction.request_id,
+                timestamp_ms: prediction.timestamp_ms,
+                features,
+                label,
+                label_source,
+            });
+        }
+
+        let joined: HashSet<String> = examples.iter().map(|e| e.request_id.clone()).collect();
+        for example in self.labeled {
+            if joined.contains(&example.request_id) {
+                continue;
+            }
+            if example.features.len() != width {
+                stats.wrong_width += 1;
+                continue;
+            }
+            examples.push(example);
+        }
+
+        if stats.wrong_width > 0 {
+            warn!(
+                "📉 Dropped {} rows without a {}-feature vector",
+                stats.wrong_width, width
+            );
+        }
+        info!(
+            "📚 Built dataset: {} labeled rows ({} unlabeled skipped)",
+            examples.len(),
+            stats.unlabeled
+        );
+
+        Dataset {
+            layout: self.layout,
+            examples,
+            stats,
+        }
+    }
+}
+
+/// Labeled dataset ready for export
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub layout: FeatureLayout,
+    pub examples: Vec<LabeledExample>,
+    pub stats: DatasetStats,
+}
+
+impl Dataset {
+    pub fn len(&self) -> usize {
+        self.examples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.examples.is_empty()
+    }
+
+    /// Schema metadata for this dataset
+    pub fn schema(&self) -> DatasetSchema {
+        let columns = META_COLUMNS
+            .iter()
+            .chain(self.layout.feature_names().iter())
+            .chain(LABEL_COLUMNS.iter())
+            .map(|c| c.to_string())
+            .collect();
+
+        DatasetSchema {
+            schema_version: DATASET_SCHEMA_VERSION.to_string(),
+            feature_layout: self.layout,
+            feature_count: self.layout.width(),
+            columns,
+            row_count: self.examples.len(),
+            positive_count: self.examples.iter().filter(|e| e.label).count(),
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Write CSV plus `<path>.schema.json`
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        create_parent(path)?;
+
+        let schema = self.schema();
+        let file = std::fs::File::create(path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to create {}: {}", path.display(), e)))?;
+        let mut writer = std::io::BufWriter::new(file);
+        let io_err = |e: std::io::Error| SentinelError::IoError(e.to_string());
+
+        writeln!(writer, "{}", schema.columns.join(",")).map_err(io_err)?;
+        for example in &self.examples {
+            write!(writer, "{},{}", csv_field(&example.request_id), example.timestamp_ms).map_err(io_err)?;
+            for value in &example.features {
+                write!(writer, ",{}", value).map_err(io_err)?;
+            }
+            writeln!(writer, ",{},{}", example.label as u8, example.label_source.as_str())
+                .map_err(io_err)?;
+        }
+        writer.flush().map_err(io_err)?;
+
+        let schema_json = serde_json::to_string_pretty(&schema)
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        std::fs::write(schema_sidecar_path(path), schema_json).map_err(io_err)?;
+
+        info!("💾 Wrote {} rows to {}", self.examples.len(), path.display());
+        Ok(())
+    }
+
+    /// Write Parquet with schema metadata (`sentinel.*` key-value entries)
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet(&self, path: impl AsRef<Path>) -> Result<()> {
+        use arrow_array::{
+            ArrayRef, BooleanArray, Float32Array, RecordBatch, StringArray, UInt64Array,
+        };
+        use arrow_schema::{DataType, Field, Schema};
+        use parquet::arrow::ArrowWriter;
+        use parquet::file::metadata::KeyValue;
+        use parquet::file::properties::WriterProperties;
+        use std::sync::Arc;
+
+        let path = path.as_ref();
+        create_parent(path)?;
+        let schema = self.schema();
+        let feature_names = self.layout.feature_names();
+
+        let mut fields = vec![
+            Field::new("request_id", DataType::Utf8, false),
+            Field::new("timestamp_ms", DataType::UInt64, false),
+        ];
+        fields.extend(feature_names.iter().map(|n| Field::new(*n, DataType::Float32, false)));
+        fields.push(Field::new("label", DataType::Boolean, false));
+        fields.push(Field::new("label_source", DataType::Utf8, false));
+
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(self.examples.iter().map(|e| e.request_id.as_str()))),
+            Arc::new(UInt64Array::from_iter_values(self.examples.iter().map(|e| e.timestamp_ms))),
+        ];
+        for i in 0..feature_names.len() {
+            columns.push(Arc::new(Float32Array::from_iter_values(
+                self.examples.iter().map(|e| e.features[i]),
+            )));
+        }
+        columns.push(Arc::new(BooleanArray::from(
+            self.examples.iter().map(|e| e.label).collect::<Vec<_>>(),
+        )));
+        columns.push(Arc::new(StringArray::from_iter_values(
+            self.examples.iter().map(|e| e.label_source.as_str()),
+        )));
+
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![
+                KeyValue::new("sentinel.schema_version".to_string(), schema.schema_version.clone()),
+                KeyValue::new(
+                    "sentinel.feature_layout".to_string(),
+                    serde_json::to_string(&schema.feature_layout).unwrap_or_default(),
+                ),
+                KeyValue::new("sentinel.created_at".to_string(), schema.created_at.clone()),
+            ]))
+            .build();
+
+        let file = std::fs::File::create(path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to create {}: {}", path.display(), e)))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        writer
+            .write(&batch)
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        writer
+            .close()
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+
+        info!("💾 Wrote {} rows to {}", self.examples.len(), path.display());
+        Ok(())
+    }
+}
+
+/// `<path>.schema.json`
+pub fn schema_sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".schema.json");
+    PathBuf::from(name)
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => std::fs::create_dir_all(parent)
+            .map_err(|e| SentinelError::IoError(format!("Failed to create export dir: {}", e))),
+        _ => Ok(()),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn read_jsonl<T: for<'de> Deserialize<'de>>(path: &Path, stats: &mut DatasetStats) -> Result<Vec<T>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| SentinelError::IoError(format!("Failed to open {}: {}", path.display(), e)))?;
+
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| SentinelError::IoError(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(_) => stats.malformed_lines += 1,
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sentinel_core::RouteType;
+
+    fn shadow(request_id: &str, width: usize, production_is_mev: Option<bool>) -> ShadowPrediction {
+        ShadowPrediction {
+            request_id: request_id.to_string(),
+            timestamp_ms: 1_700_000_000_000,
+            signature: format!("sig-{}", request_id),
+            model_version: "v1.0".to_string(),
+            shadow_risk_score: 0.4,
+            shadow_is_mev: false,
+            latency_us: 120,
+            production_risk_score: Some(0.7),
+            production_is_mev,
+            features: serde_json::json!(vec![0.5f32; width]),
+            error: None,
+            tenant_id: None,
+            config_version: None,
+        }
+    }
+
+    #[test]
+    fn test_layout_names_match_width() {
+        assert_eq!(FeatureLayout::Base55.feature_names().len(), 55);
+        assert_eq!(FeatureLayout::Enhanced67.feature_names().len(), 67);
+        assert_eq!(FeatureLayout::Base55.feature_names()[23], "price_deviation_pct");
+        assert_eq!(FeatureLayout::Base55.feature_names()[52], "validator_risk_score");
+    }
+
+    #[test]
+    fn test_join_confirmed_labels() {
+        let mut builder = DatasetBuilder::new(FeatureLayout::Base55).add_shadow_predictions(vec![
+            shadow("a", 55, Some(true)),
+            shadow("b", 55, Some(true)),
+            shadow("c", 67, None),
+        ]);
+        builder.add_decisions(vec![
+            DecisionRecord::new("a".into(), 0.9, RouteType::JitoBundle).with_mev_incident(true),
+            DecisionRecord::new("c".into(), 0.9, RouteType::JitoBundle).with_mev_incident(false),
+        ]);
+
+        let dataset = builder.build();
+        assert_eq!(dataset.len(), 1);
+        assert_eq!(dataset.examples[0].label_source, LabelSource::Confirmed);
+        assert_eq!(dataset.stats.unlabeled, 1);
+        assert_eq!(dataset.stats.wrong_width, 1);
+    }
+
+    #[test]
+    fn test_weak_labels_opt_in() {
+        let dataset = DatasetBuilder::new(FeatureLayout::Base55)
+            .include_weak_labels(true)
+            .add_shadow_predictions(vec![shadow("a", 55, Some(true))])
+            .build();
+        assert_eq!(dataset.examples[0].label_source, LabelSource::Production);
+        assert!(dataset.examples[0].label);
+    }
+
+    #[test]
+    fn test_triplet_examples_merge() {
+        let triplet = |id: &str, width: usize| LabeledExample {
+            request_id: id.to_string(),
+            timestamp_ms: 1,
+            features: vec![0.0; width],
+            label: true,
+            label_source: LabelSource::Triplet,
+        };
+        let mut builder = DatasetBuilder::new(FeatureLayout::Base55)
+            .add_shadow_predictions(vec![shadow("a", 55, None)])
+            .add_examples(vec![triplet("a", 55), triplet("b", 55), triplet("c", 10)]);
+        builder.add_decisions(vec![DecisionRecord::new("a".into(), 0.9, RouteType::JitoBundle).with_mev_incident(false)]);
+
+        let dataset = builder.build();
+        let sources: Vec<_> = dataset.examples.iter().map(|e| (e.request_id.as_str(), e.label_source)).collect();
+        assert_eq!(sources, vec![("a", LabelSource::Confirmed), ("b", LabelSource::Triplet)]);
+        assert_eq!(dataset.stats.wrong_width, 1);
+    }
+
+    #[test]
+    fn test_csv_export_with_schema() {
+        let dir = std::env::temp_dir().join(format!("dataset-{}", uuid::Uuid::new_v4()));
+        let shadow_path = dir.join("shadow.jsonl");
+        let decision_path = dir.join("decisions.jsonl");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let shadow_line = serde_json::to_string(&shadow("req-1", 55, None)).unwrap();
+        std::fs::write(&shadow_path, format!("{}\nnot json\n", shadow_line)).unwrap();
+        let decision = DecisionRecord::new("req-1".into(), 0.8, RouteType::JitoBundle).with_mev_incident(true);
+        std::fs::write(&decision_path, serde_json::to_string(&decision).unwrap()).unwrap();
+
+        let dataset = DatasetBuilder::new(FeatureLayout::Base55)
+            .load_shadow_log(&shadow_path)
+            .unwrap()
+            .load_decision_log(&decision_path)
+            .unwrap()
+            .build();
+        assert_eq!(dataset.stats.malformed_lines, 1);
+
+        let csv_path = dir.join("out/train.csv");
+        dataset.write_csv(&csv_path).unwrap();
+
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header.len(), 2 + 55 + 2);
+        assert_eq!(header[2], "slot");
+        let row: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(row.len(), header.len());
+        assert_eq!(row[row.len() - 2], "1");
+
+        let schema: DatasetSchema =
+            serde_json::from_str(&std::fs::read_to_string(schema_sidecar_path(&csv_path)).unwrap()).unwrap();
+        assert_eq!(schema.schema_version, DATASET_SCHEMA_VERSION);
+        assert_eq!(schema.positive_count, 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_export_metadata() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dataset = DatasetBuilder::new(FeatureLayout::Base55)
+            .include_weak_labels(true)
+            .add_shadow_predictions(vec![shadow("a", 55, Some(true)), shadow("b", 55, Some(false))])
+            .build();
+
+        let path = std::env::temp_dir().join(format!("dataset-{}.parquet", uuid::Uuid::new_v4()));
+        dataset.write_parquet(&path).unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 2);
+        assert_eq!(metadata.schema_descr().num_columns(), 2 + 55 + 2);
+        let version = metadata
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .find(|kv| kv.key == "sentinel.schema_version")
+            .and_then(|kv| kv.value.clone());
+        assert_eq!(version.as_deref(), Some(DATASET_SCHEMA_VERSION));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
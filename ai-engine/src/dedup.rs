@@ -0,0 +1,290 @@
+//! Signature Deduplication Window
+//!
+//! Geyser and RPC subscriptions redeliver transactions across reconnects
+//! (replay from the last acknowledged slot), which double-counts them in the
+//! extractor's swap history and inflates `recent_swaps_*`. The
+//! [`DedupWindow`] remembers the last `capacity` distinct signatures:
+//!
+//! - a rotating pair of bloom filters answers "definitely new" for the common
+//!   case without touching the exact set
+//! - an LRU of 128-bit signature fingerprints confirms bloom hits, so a bloom
+//!   false positive never drops a new transaction
+//!
+//! Bloom generations rotate every `capacity` new signatures and duplicates are
+//! re-inserted, so every signature still in the LRU is in one of the two
+//! generations.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::hash::BuildHasher;
+
+/// Window settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DedupSettings {
+    /// Distinct signatures remembered
+    pub capacity: usize,
+
+    /// Target bloom false-positive rate per generation
+    pub false_positive_rate: f64,
+}
+
+impl Default for DedupSettings {
+    fn default() -> Self {
+        Self {
+            capacity: 100_000,
+            false_positive_rate: 0.001,
+        }
+    }
+}
+
+/// Running totals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupStats {
+    /// Signatures checked
+    pub seen: u64,
+    pub duplicates: u64,
+
+    /// Bloom hits the LRU did not confirm
+    pub bloom_false_positives: u64,
+
+    /// Signatures currently remembered
+    pub tracked: usize,
+}
+
+impl DedupStats {
+    /// Fraction of checked signatures that were redeliveries
+    pub fn duplicate_rate(&self) -> f64 {
+        if self.seen == 0 {
+            0.0
+        } else {
+            self.duplicates as f64 / self.seen as f64
+        }
+    }
+}
+
+struct Bloom {
+    bits: Vec<u64>,
+    hashes: u32,
+}
+
+impl Bloom {
+    fn new(bit_count: usize, hashes: u32) -> Self {
+        Self {
+            bits: vec![0; bit_count.div_ceil(64)],
+            hashes,
+        }
+    }
+
+    fn insert(&mut self, fingerprint: u128) {
+        for position in positions(self.bits.len(), self.hashes, fingerprint) {
+            self.bits[position / 64] |= 1 << (position % 64);
+        }
+    }
+
+    fn contains(&self, fingerprint: u128) -> bool {
+        positions(self.bits.len(), self.hashes, fingerprint)
+            .all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+    }
+}
+
+/// Bit positions of `fingerprint` (double hashing over its halves)
+fn positions(words: usize, hashes: u32, fingerprint: u128) -> impl Iterator<Item = usize> {
+    let bit_count = (words * 64) as u64;
+    let (h1, h2) = ((fingerprint >> 64) as u64, fingerprint as u64 | 1);
+    (0..hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bit_count) as usize)
+}
+
+/// Bounded memory of recently ingested signatures
+pub struct DedupWindow {
+    settings: DedupSettings,
+    hashers: (RandomState, RandomState),
+    current: Bloom,
+    previous: Bloom,
+    /// New signatures inserted into `current`
+    generation_len: usize,
+    /// Fingerprint -> sequence of its latest sighting
+    recent: HashMap<u128, u64>,
+    /// Sightings in order; entries whose sequence is stale are skipped on eviction
+    order: VecDeque<(u128, u64)>,
+    sequence: u64,
+    stats: DedupStats,
+}
+
+impl DedupWindow {
+    pub fn new(settings: DedupSettings) -> Self {
+        let capacity = settings.capacity.max(1);
+        let rate = settings.false_positive_rate.clamp(1e-9, 0.5);
+        // Optimal sizing: m = -n ln p / ln² 2, k = (m / n) ln 2
+        let bit_count = ((-(capacity as f64) * rate.ln()) / std::f64::consts::LN_2.powi(2)).ceil() as usize;
+        let hashes = ((bit_count as f64 / capacity as f64) * std::f64::consts::LN_2).round().clamp(1.0, 16.0) as u32;
+
+        Self {
+            settings: DedupSettings { capacity, ..settings },
+            hashers: (RandomState::new(), RandomState::new()),
+            current: Bloom::new(bit_count.max(64), hashes),
+            previous: Bloom::new(bit_count.max(64), hashes),
+            generation_len: 0,
+            recent: HashMap::new(),
+            order: VecDeque::new(),
+            sequence: 0,
+            stats: DedupStats::default(),
+        }
+    }
+
+    pub fn settings(&self) -> &DedupSettings {
+        &self.settings
+    }
+
+    pub fn stats(&self) -> DedupStats {
+        DedupStats {
+            tracked: self.recent.len(),
+            ..self.stats
+        }
+    }
+
+    /// Record a sighting of `signature`; returns true the first time it is seen within the window
+    pub fn insert(&mut self, signature: &str) -> bool {
+        let fingerprint = self.fingerprint(signature);
+        self.stats.seen += 1;
+        self.sequence += 1;
+
+        let maybe_seen = self.current.contains(fingerprint) || self.previous.contains(fingerprint);
+        if maybe_seen {
+            if let Some(last_seen) = self.recent.get_mut(&fingerprint) {
+                *last_seen = self.sequence;
+                self.order.push_back((fingerprint, self.sequence));
+                self.current.insert(fingerprint);
+                self.stats.duplicates += 1;
+                self.compact();
+                return false;
+            }
+            self.stats.bloom_false_positives += 1;
+        }
+
+        if self.generation_len >= self.settings.capacity {
+            std::mem::swap(&mut self.current, &mut self.previous);
+            self.current.clear();
+            self.generation_len = 0;
+        }
+        self.current.insert(fingerprint);
+        self.generation_len += 1;
+
+        self.recent.insert(fingerprint, self.sequence);
+        self.order.push_back((fingerprint, self.sequence));
+        while self.recent.len() > self.settings.capacity {
+            self.evict_oldest();
+        }
+        self.compact();
+        true
+    }
+
+    /// Whether `signature` was seen within the window (no sighting recorded)
+    pub fn contains(&self, signature: &str) -> bool {
+        let fingerprint = self.fingerprint(signature);
+        (self.current.contains(fingerprint) || self.previous.contains(fingerprint))
+            && self.recent.contains_key(&fingerprint)
+    }
+
+    fn fingerprint(&self, signature: &str) -> u128 {
+        ((self.hashers.0.hash_one(signature) as u128) << 64) | self.hashers.1.hash_one(signature) as u128
+    }
+
+    fn evict_oldest(&mut self) {
+        while let Some((fingerprint, sequence)) = self.order.pop_front() {
+            if self.recent.get(&fingerprint) == Some(&sequence) {
+                self.recent.remove(&fingerprint);
+                return;
+            }
+        }
+    }
+
+    /// Drop stale sightings once they outnumber live ones (bursts of redeliveries)
+    fn compact(&mut self) {
+        if self.order.len() > 2 * self.settings.capacity {
+            let recent = &self.recent;
+            self.order.retain(|(fingerprint, sequence)| recent.get(fingerprint) == Some(sequence));
+        }
+    }
+}
+
+impl Default for DedupWindow {
+    fn default() -> Self {
+        Self::new(DedupSettings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_detected_and_counted() {
+        let mut window = DedupWindow::default();
+        assert!(window.insert("sig-a"));
+        assert!(window.insert("sig-b"));
+        assert!(!window.insert("sig-a"));
+        assert!(window.contains("sig-b"));
+        assert!(!window.contains("sig-c"));
+
+        let stats = window.stats();
+        assert_eq!((stats.seen, stats.duplicates, stats.tracked), (3, 1, 2));
+        assert!((stats.duplicate_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_evicts_least_recently_seen() {
+        let mut window = DedupWindow::new(DedupSettings {
+            capacity: 3,
+            ..Default::default()
+        });
+        for sig in ["a", "b", "c"] {
+            assert!(window.insert(sig));
+        }
+        // Re-seeing "a" keeps it; "b" is now the oldest
+        assert!(!window.insert("a"));
+        assert!(window.insert("d"));
+        assert!(!window.contains("b"));
+        assert!(window.contains("a"));
+        assert_eq!(window.stats().tracked, 3);
+
+        // Long after eviction a redelivery counts as new again
+        assert!(window.insert("b"));
+    }
+
+    #[test]
+    fn test_hot_signature_survives_bloom_rotation() {
+        let mut window = DedupWindow::new(DedupSettings {
+            capacity: 50,
+            ..Default::default()
+        });
+        assert!(window.insert("hot"));
+        for i in 0..200 {
+            // Interleave redeliveries of "hot" with fresh traffic
+            assert!(!window.insert("hot"));
+            assert!(window.insert(&format!("tx-{}", i)));
+            assert!(window.stats().tracked <= 50);
+        }
+        assert_eq!(window.stats().duplicates, 200);
+        assert!(window.order.len() <= 2 * 50 + 1);
+    }
+
+    #[test]
+    fn test_no_false_duplicates_under_load() {
+        let mut window = DedupWindow::new(DedupSettings {
+            capacity: 1_000,
+            false_positive_rate: 0.01,
+        });
+        for i in 0..10_000 {
+            assert!(window.insert(&format!("unique-{}", i)));
+        }
+        let stats = window.stats();
+        assert_eq!(stats.duplicates, 0);
+        // Bloom hits on new signatures stay near the configured rate
+        assert!(stats.bloom_false_positives < 500, "{:?}", stats);
+    }
+}
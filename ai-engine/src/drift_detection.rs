@@ -116,6 +116,22 @@ impl DriftDetector {
         }
     }
     
+    /// Add an observation by copying into history, recycling the evicted
+    /// vector's storage once the window is full (no allocation at steady state)
+    pub fn add_observation_from(&mut self, features: &Array1<f32>) {
+        if self.historical_features.len() >= self.max_history {
+            if let Some(mut recycled) = self.historical_features.pop_front() {
+                if recycled.len() == features.len() {
+                    recycled.assign(features);
+                    self.historical_features.push_back(recycled);
+                    return;
+                }
+            }
+        }
+        
+        self.historical_features.push_back(features.clone());
+    }
+    
     /// Calculate ensemble drift score using multiple methods
     /// 
     /// Returns: DriftScore with individual method results and overall verdict
@@ -307,6 +323,17 @@ impl DriftDetector {
         }
     }
     
+    /// Copy of the rolling window, oldest first
+    pub fn history(&self) -> Vec<Vec<f32>> {
+        self.historical_features.iter().map(|f| f.to_vec()).collect()
+    }
+    
+    /// Replace the rolling window, keeping the newest `max_history` entries
+    pub fn restore_history(&mut self, history: Vec<Vec<f32>>) {
+        let skip = history.len().saturating_sub(self.max_history);
+        self.historical_features = history.into_iter().skip(skip).map(Array1::from).collect();
+    }
+    
     /// Clear historical data
     pub fn clear_history(&mut self) {
         self.historical_features.clear();
@@ -410,4 +437,18 @@ mod tests {
         let stats = detector.get_stats();
         assert_eq!(stats.history_size, 10); // Should cap at max_history
     }
+    
+    #[test]
+    fn test_restore_history_keeps_newest() {
+        let mut detector = DriftDetector::with_config(
+            3,
+            0.25,
+            0.05,
+            0.1,
+            VotingStrategy::MajorityVote,
+        );
+        
+        detector.restore_history((0..5).map(|i| vec![i as f32]).collect());
+        assert_eq!(detector.history(), vec![vec![2.0], vec![3.0], vec![4.0]]);
+    }
 }
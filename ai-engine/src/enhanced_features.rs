@@ -139,6 +139,14 @@ impl EnhancedFeatureVector {
     
     pub const ENHANCED_FEATURE_COUNT: usize = 67;
     
+    /// Names of the 12 columns appended after the base 55 (in `to_array` order)
+    pub const ENHANCED_FEATURE_NAMES: [&'static str; 12] = [
+        "is_jito_bundle", "bundle_position", "uses_private_mempool", "mempool_time_ms",
+        "competing_tx_count", "validator_marinade_stake_pct", "validator_deeznode_correlation",
+        "validator_block_builder_id", "program_interaction_count", "uses_lookup_tables_advanced",
+        "cpi_depth", "account_realloc_detected",
+    ];
+    
     /// Validate enhanced features
     pub fn validate(&self) -> Result<(), String> {
         // Bundle position validation
@@ -2,6 +2,42 @@ use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
 
+/// Features the extractor could not observe, by `FeatureVector::FEATURE_NAMES` index
+///
+/// A zero in the vector is ambiguous (`pool_liquidity_usd = 0` reads as an empty
+/// pool); a set bit says the value is unknown and the stored 0 a placeholder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureMask(u64);
+
+impl FeatureMask {
+    pub fn insert(&mut self, index: usize) {
+        debug_assert!(index < FeatureVector::FEATURE_COUNT);
+        self.0 |= 1 << index;
+    }
+    
+    pub fn remove(&mut self, index: usize) {
+        self.0 &= !(1 << index);
+    }
+    
+    pub fn contains(&self, index: usize) -> bool {
+        index < FeatureVector::FEATURE_COUNT && self.0 & (1 << index) != 0
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+    
+    /// Missing indices in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..FeatureVector::FEATURE_COUNT).filter(|&index| self.contains(index))
+    }
+}
+
 /// Production-ready feature vector with all 55 features for MEV threat detection
 /// 
 /// Features are categorized as:
@@ -125,8 +161,9 @@ pub struct FeatureVector {
     /// 🔴 KEY: Strongest sandwich indicator (99.2% recall)
     pub has_swap_triplet: bool,
     
-    /// Is this transaction potentially a sandwich victim?
-    pub is_potential_sandwich_victim: bool,
+    /// Probability this swap is sandwiched (0.0-1.0), from attacker-optimal
+    /// profit against the pool ([`crate::sandwich_model`])
+    pub sandwich_victim_probability: f32,
     
     /// Is this a potential front-run transaction?
     pub is_potential_front_run: bool,
@@ -134,10 +171,10 @@ pub struct FeatureVector {
     /// Is this a potential back-run transaction?
     pub is_potential_back_run: bool,
     
-    /// Recent swaps on same pair (last 10 slots)
+    /// Recent swaps on same pair (last `pair_slots` slots, default 10)
     pub recent_swaps_same_pair: u32,
     
-    /// Recent swaps by same actor (last 100 slots)
+    /// Recent swaps by same actor (last `actor_slots` slots, default 100)
     pub recent_swaps_same_actor: u32,
     
     /// Jito tip percentile vs recent (0-100)
@@ -209,6 +246,19 @@ pub struct FeatureVector {
     
     /// Confidence in next leader prediction (0-1)
     pub leader_prediction_confidence: f32,
+    
+    // ============================================
+    // MISSING DATA (not a model input)
+    // ============================================
+    
+    /// Features that are unknown rather than zero
+    #[serde(default)]
+    pub missing: FeatureMask,
+    
+    /// The Pyth price was refused (confidence too wide or too stale); the
+    /// oracle features come from a fallback or are missing
+    #[serde(default)]
+    pub oracle_unreliable: bool,
 }
 
 impl Default for FeatureVector {
@@ -250,7 +300,7 @@ impl Default for FeatureVector {
             
             // Patterns
             has_swap_triplet: false,
-            is_potential_sandwich_victim: false,
+            sandwich_victim_probability: 0.0,
             is_potential_front_run: false,
             is_potential_back_run: false,
             recent_swaps_same_pair: 0,
@@ -278,83 +328,184 @@ impl Default for FeatureVector {
             validator_risk_score: 0.0,
             slots_until_next_leader: 0,
             leader_prediction_confidence: 0.0,
+            
+            missing: FeatureMask::default(),
+            oracle_unreliable: false,
         }
     }
 }
 
+/// Stateful pattern features (swap history)
+const HISTORY_FEATURES: [&str; 4] = [
+    "has_swap_triplet", "recent_swaps_same_pair", "recent_swaps_same_actor", "tip_percentile_vs_recent",
+];
+
+/// Next-leader intel
+const VALIDATOR_FEATURES: [&str; 11] = [
+    "next_leader_malicious", "next_leader_mev_rate", "next_leader_stake_sol", "next_leader_commission_pct",
+    "next_leader_jito_rate", "next_leader_avg_tip", "next_leader_recent_blocks", "next_leader_skip_rate",
+    "validator_risk_score", "slots_until_next_leader", "leader_prediction_confidence",
+];
+
+/// Swap features that need an oracle price or market stats
+const MARKET_FEATURES: [&str; 12] = [
+    "input_price_usd", "output_price_usd", "trade_size_usd", "liquidity_utilization",
+    "oracle_price", "oracle_confidence", "price_deviation_pct", "volume_24h_usd",
+    "volatility_24h_pct", "market_depth_usd", "is_high_risk_pair", "is_potential_sandwich_victim",
+];
+
 impl FeatureVector {
+    /// Features readable from the transaction alone
+    ///
+    /// No swap history, oracle prices or validator intel: the degraded input
+    /// used when a request's deadline leaves no time for the shared extractor.
+    /// Everything it cannot read is marked missing.
+    pub fn from_transaction(tx_data: &TransactionData) -> Self {
+        let mut features = FeatureVector {
+            slot: tx_data.slot,
+            compute_unit_limit: tx_data.compute_unit_limit,
+            compute_unit_price: tx_data.compute_unit_price,
+            jito_tip_lamports: tx_data.jito_tip_lamports,
+            total_fee_lamports: tx_data.total_fee_lamports,
+            account_count: tx_data.account_count,
+            instruction_count: tx_data.instruction_count,
+            tx_size_bytes: tx_data.tx_size_bytes,
+            time_since_last_slot_ms: tx_data.time_since_last_slot_ms,
+            uses_lookup_tables: tx_data.uses_lookup_tables,
+            next_leader_pubkey: tx_data.next_leader_pubkey,
+            ..Default::default()
+        };
+        if let Some(ref swap) = tx_data.swap_details {
+            features.is_dex_swap = true;
+            features.input_amount = swap.input_amount;
+            features.output_amount = swap.output_amount;
+            features.expected_output = swap.expected_output;
+            features.swap_route_length = swap.route_length;
+            features.slippage_tolerance_bps = swap.slippage_tolerance_bps;
+            features.pool_liquidity_usd = swap.pool_liquidity_usd;
+            features.price_impact_bps = if swap.expected_output > 0.0 {
+                ((swap.expected_output - swap.output_amount) / swap.expected_output * 10_000.0).abs()
+            } else {
+                0.0
+            };
+            features.mark_missing(&MARKET_FEATURES);
+            if swap.pool_liquidity_usd <= 0.0 {
+                features.mark_missing(&["pool_liquidity_usd"]);
+            }
+        }
+        features.mark_missing(&HISTORY_FEATURES);
+        features.mark_missing(&VALIDATOR_FEATURES);
+        features
+    }
+    
+    /// Column index of a `FEATURE_NAMES` entry
+    pub fn feature_index(name: &str) -> Option<usize> {
+        Self::FEATURE_NAMES.iter().position(|column| *column == name)
+    }
+    
+    /// Mark features unknown; their stored values become placeholders
+    pub fn mark_missing(&mut self, names: &[&str]) {
+        for name in names {
+            match Self::feature_index(name) {
+                Some(index) => self.missing.insert(index),
+                None => debug_assert!(false, "unknown feature {}", name),
+            }
+        }
+    }
+    
+    pub fn is_missing(&self, name: &str) -> bool {
+        Self::feature_index(name).is_some_and(|index| self.missing.contains(index))
+    }
+    
     /// Convert to array for ONNX model inference
     /// 
     /// Returns: Vec<f32> of length 55 (matching model input shape)
-    /// Performance: <10μs (SIMD-optimized)
+    /// Allocates; prefer `write_into` on hot paths.
     pub fn to_array(&self) -> Vec<f32> {
-        vec![
-            // Base (8)
-            self.slot as f32,
-            self.compute_unit_limit as f32,
-            self.compute_unit_price as f32,
-            self.jito_tip_lamports as f32,
-            self.total_fee_lamports as f32,
-            self.account_count as f32,
-            self.instruction_count as f32,
-            self.tx_size_bytes as f32,
-            
-            // DEX (12)
-            if self.is_dex_swap { 1.0 } else { 0.0 },
-            self.input_amount as f32,
-            self.output_amount as f32,
-            self.expected_output as f32,
-            self.price_impact_bps as f32,
-            self.slippage_tolerance_bps as f32,
-            self.swap_route_length as f32,
-            self.input_price_usd,
-            self.output_price_usd,
-            self.trade_size_usd as f32,
-            self.pool_liquidity_usd as f32,
-            self.liquidity_utilization,
-            
-            // Market (8)
-            self.oracle_price as f32,
-            self.oracle_confidence as f32,
-            self.oracle_staleness_ms as f32,
-            self.price_deviation_pct,
-            self.volume_24h_usd as f32,
-            self.volatility_24h_pct,
-            self.market_depth_usd as f32,
-            if self.is_high_risk_pair { 1.0 } else { 0.0 },
-            
-            // Patterns (15)
-            if self.has_swap_triplet { 1.0 } else { 0.0 },
-            if self.is_potential_sandwich_victim { 1.0 } else { 0.0 },
-            if self.is_potential_front_run { 1.0 } else { 0.0 },
-            if self.is_potential_back_run { 1.0 } else { 0.0 },
-            self.recent_swaps_same_pair as f32,
-            self.recent_swaps_same_actor as f32,
-            self.tip_percentile_vs_recent,
-            self.time_since_last_slot_ms as f32,
-            self.account_collision_count as f32,
-            self.triplet_time_spread_ms as f32,
-            if self.uses_lookup_tables { 1.0 } else { 0.0 },
-            self.priority_score,
-            if self.matches_mev_bot_pattern { 1.0 } else { 0.0 },
-            self.arb_opportunity_score,
-            if self.has_flash_loan { 1.0 } else { 0.0 },
-            
-            // Validator (12)
-            // Encode pubkey as single feature (hash to 0-1 range)
-            self.encode_pubkey_feature(),
-            if self.next_leader_malicious { 1.0 } else { 0.0 },
-            self.next_leader_mev_rate,
-            self.next_leader_stake_sol as f32,
-            self.next_leader_commission_pct,
-            self.next_leader_jito_rate,
-            self.next_leader_avg_tip as f32,
-            self.next_leader_recent_blocks as f32,
-            self.next_leader_skip_rate,
-            self.validator_risk_score,
-            self.slots_until_next_leader as f32,
-            self.leader_prediction_confidence,
-        ]
+        let mut out = [0.0f32; Self::FEATURE_COUNT];
+        self.write_into(&mut out);
+        out.to_vec()
+    }
+    
+    /// Write all 55 features into a caller-owned buffer (zero allocation)
+    /// 
+    /// Index layout is the model input order and matches `to_array()`.
+    pub fn write_into(&self, out: &mut [f32; Self::FEATURE_COUNT]) {
+        // Base (8)
+        out[0] = self.slot as f32;
+        out[1] = self.compute_unit_limit as f32;
+        out[2] = self.compute_unit_price as f32;
+        out[3] = self.jito_tip_lamports as f32;
+        out[4] = self.total_fee_lamports as f32;
+        out[5] = self.account_count as f32;
+        out[6] = self.instruction_count as f32;
+        out[7] = self.tx_size_bytes as f32;
+
+        // DEX (12)
+        out[8] = if self.is_dex_swap { 1.0 } else { 0.0 };
+        out[9] = self.input_amount as f32;
+        out[10] = self.output_amount as f32;
+        out[11] = self.expected_output as f32;
+        out[12] = self.price_impact_bps as f32;
+        out[13] = self.slippage_tolerance_bps as f32;
+        out[14] = self.swap_route_length as f32;
+        out[15] = self.input_price_usd;
+        out[16] = self.output_price_usd;
+        out[17] = self.trade_size_usd as f32;
+        out[18] = self.pool_liquidity_usd as f32;
+        out[19] = self.liquidity_utilization;
+
+        // Market (8)
+        out[20] = self.oracle_price as f32;
+        out[21] = self.oracle_confidence as f32;
+        out[22] = self.oracle_staleness_ms as f32;
+        out[23] = self.price_deviation_pct;
+        out[24] = self.volume_24h_usd as f32;
+        out[25] = self.volatility_24h_pct;
+        out[26] = self.market_depth_usd as f32;
+        out[27] = if self.is_high_risk_pair { 1.0 } else { 0.0 };
+
+        // Patterns (15)
+        out[28] = if self.has_swap_triplet { 1.0 } else { 0.0 };
+        out[29] = self.sandwich_victim_probability;
+        out[30] = if self.is_potential_front_run { 1.0 } else { 0.0 };
+        out[31] = if self.is_potential_back_run { 1.0 } else { 0.0 };
+        out[32] = self.recent_swaps_same_pair as f32;
+        out[33] = self.recent_swaps_same_actor as f32;
+        out[34] = self.tip_percentile_vs_recent;
+        out[35] = self.time_since_last_slot_ms as f32;
+        out[36] = self.account_collision_count as f32;
+        out[37] = self.triplet_time_spread_ms as f32;
+        out[38] = if self.uses_lookup_tables { 1.0 } else { 0.0 };
+        out[39] = self.priority_score;
+        out[40] = if self.matches_mev_bot_pattern { 1.0 } else { 0.0 };
+        out[41] = self.arb_opportunity_score;
+        out[42] = if self.has_flash_loan { 1.0 } else { 0.0 };
+
+        // Validator (12)
+        // Encode pubkey as single feature (hash to 0-1 range)
+        out[43] = self.encode_pubkey_feature();
+        out[44] = if self.next_leader_malicious { 1.0 } else { 0.0 };
+        out[45] = self.next_leader_mev_rate;
+        out[46] = self.next_leader_stake_sol as f32;
+        out[47] = self.next_leader_commission_pct;
+        out[48] = self.next_leader_jito_rate;
+        out[49] = self.next_leader_avg_tip as f32;
+        out[50] = self.next_leader_recent_blocks as f32;
+        out[51] = self.next_leader_skip_rate;
+        out[52] = self.validator_risk_score;
+        out[53] = self.slots_until_next_leader as f32;
+        out[54] = self.leader_prediction_confidence;
+    }
+    
+    /// `write_into` with NaN in place of every missing feature
+    /// 
+    /// NaN compares false, so heuristic rules never fire on an unknown value.
+    pub fn write_sparse_into(&self, out: &mut [f32; Self::FEATURE_COUNT]) {
+        self.write_into(out);
+        for index in self.missing.iter() {
+            out[index] = f32::NAN;
+        }
     }
     
     /// Encode pubkey as normalized float feature
@@ -366,39 +517,33 @@ impl FeatureVector {
     
     /// Validate feature vector
     /// 
+    /// Missing features are skipped: their placeholders may be anything.
     /// Returns: Result<(), String> with validation errors
     pub fn validate(&self) -> Result<(), String> {
-        let arr = self.to_array();
-        
-        // Check length
-        if arr.len() != Self::FEATURE_COUNT {
-            return Err(format!(
-                "Invalid feature count: {} (expected {})",
-                arr.len(),
-                Self::FEATURE_COUNT
-            ));
-        }
+        let mut arr = [0.0f32; Self::FEATURE_COUNT];
+        self.write_into(&mut arr);
+        let present = |idx: &usize| !self.missing.contains(*idx);
         
         // Check for NaN values
-        if let Some(idx) = arr.iter().position(|&v| v.is_nan()) {
+        if let Some(idx) = (0..Self::FEATURE_COUNT).filter(present).find(|&idx| arr[idx].is_nan()) {
             return Err(format!("NaN value at feature index {}", idx));
         }
         
         // Check for Inf values
-        if let Some(idx) = arr.iter().position(|&v| v.is_infinite()) {
+        if let Some(idx) = (0..Self::FEATURE_COUNT).filter(present).find(|&idx| arr[idx].is_infinite()) {
             return Err(format!("Infinite value at feature index {}", idx));
         }
         
         // Range checks on critical features
-        if self.compute_unit_price > 1_000_000 {
+        if !self.is_missing("compute_unit_price") && self.compute_unit_price > 1_000_000 {
             return Err("Unrealistic compute_unit_price".to_string());
         }
         
-        if self.jito_tip_lamports > 100_000_000 {
+        if !self.is_missing("jito_tip_lamports") && self.jito_tip_lamports > 100_000_000 {
             return Err("Unrealistic jito_tip_lamports".to_string());
         }
         
-        if self.price_impact_bps < 0.0 || self.price_impact_bps > 10_000.0 {
+        if !self.is_missing("price_impact_bps") && !(0.0..=10_000.0).contains(&self.price_impact_bps) {
             return Err("Invalid price_impact_bps range".to_string());
         }
         
@@ -407,6 +552,32 @@ impl FeatureVector {
     
     pub const FEATURE_COUNT: usize = 55;
     
+    /// Column names in `write_into` / model input order
+    pub const FEATURE_NAMES: [&'static str; Self::FEATURE_COUNT] = [
+        // Base (8)
+        "slot", "compute_unit_limit", "compute_unit_price", "jito_tip_lamports",
+        "total_fee_lamports", "account_count", "instruction_count", "tx_size_bytes",
+        // DEX (12)
+        "is_dex_swap", "input_amount", "output_amount", "expected_output",
+        "price_impact_bps", "slippage_tolerance_bps", "swap_route_length", "input_price_usd",
+        "output_price_usd", "trade_size_usd", "pool_liquidity_usd", "liquidity_utilization",
+        // Market (8)
+        "oracle_price", "oracle_confidence", "oracle_staleness_ms", "price_deviation_pct",
+        "volume_24h_usd", "volatility_24h_pct", "market_depth_usd", "is_high_risk_pair",
+        // Patterns (15)
+        // Column keeps its original name so trained GBDT models still map it
+        "has_swap_triplet", "is_potential_sandwich_victim", "is_potential_front_run",
+        "is_potential_back_run", "recent_swaps_same_pair", "recent_swaps_same_actor",
+        "tip_percentile_vs_recent", "time_since_last_slot_ms", "account_collision_count",
+        "triplet_time_spread_ms", "uses_lookup_tables", "priority_score",
+        "matches_mev_bot_pattern", "arb_opportunity_score", "has_flash_loan",
+        // Validator (12)
+        "next_leader_pubkey_hash", "next_leader_malicious", "next_leader_mev_rate",
+        "next_leader_stake_sol", "next_leader_commission_pct", "next_leader_jito_rate",
+        "next_leader_avg_tip", "next_leader_recent_blocks", "next_leader_skip_rate",
+        "validator_risk_score", "slots_until_next_leader", "leader_prediction_confidence",
+    ];
+    
     pub fn feature_count() -> usize {
         Self::FEATURE_COUNT
     }
@@ -414,29 +585,31 @@ impl FeatureVector {
 
 /// Production feature extractor with stateful tracking
 pub struct FeatureExtractor {
-    recent_swaps: Vec<SwapRecord>,
-    max_history: usize,
+    history: crate::swap_history::SwapHistory,
     validator_tracker: ValidatorTracker,
     pyth_client: Option<crate::pyth_oracle::PythOracleClient>,
-}
-
-#[derive(Debug, Clone)]
-struct SwapRecord {
-    slot: u64,
-    actor: Pubkey,
-    token_pair: (Pubkey, Pubkey),
-    amount: u64,
-    #[allow(dead_code)] // Used for temporal analysis in future versions
-    timestamp_ms: u64,
+    secondary_oracle: Option<crate::market_stats::JupiterPriceClient>,
+    oracle: sentinel_core::OracleSettings,
+    market_stats: crate::market_stats::MarketStats,
+    token_risk: Option<std::sync::Arc<sentinel_core::TokenRiskScreener>>,
+    sandwich_model: crate::sandwich_model::SandwichModel,
+    tip_floor: Option<sentinel_core::TipFloorCache>,
+    dedup: Option<crate::dedup::DedupWindow>,
 }
 
 impl FeatureExtractor {
     pub fn new() -> Self {
         Self {
-            recent_swaps: Vec::new(),
-            max_history: 1000,
+            history: crate::swap_history::SwapHistory::default(),
             validator_tracker: ValidatorTracker::new(),
             pyth_client: None,
+            secondary_oracle: None,
+            oracle: sentinel_core::OracleSettings::default(),
+            market_stats: crate::market_stats::MarketStats::new(),
+            token_risk: None,
+            sandwich_model: crate::sandwich_model::SandwichModel::default(),
+            tip_floor: None,
+            dedup: None,
         }
     }
     
@@ -445,11 +618,131 @@ impl FeatureExtractor {
         self
     }
     
+    /// Price source used when the Pyth price is refused or unavailable
+    pub fn with_secondary_oracle(mut self, client: crate::market_stats::JupiterPriceClient) -> Self {
+        self.secondary_oracle = Some(client);
+        self
+    }
+    
+    /// Confidence/staleness bounds for Pyth prices and the DEX TWAP window
+    pub fn with_oracle_settings(mut self, settings: sentinel_core::OracleSettings) -> Self {
+        self.oracle = settings;
+        self
+    }
+    
+    pub fn with_market_stats(mut self, market_stats: crate::market_stats::MarketStats) -> Self {
+        self.market_stats = market_stats;
+        self
+    }
+    
+    /// Token screener whose cached results set `is_high_risk_pair` (no RPC on this path)
+    pub fn with_token_risk(mut self, screener: std::sync::Arc<sentinel_core::TokenRiskScreener>) -> Self {
+        self.token_risk = Some(screener);
+        self
+    }
+    
+    /// [`with_token_risk`](Self::with_token_risk) on an extractor already behind a lock
+    pub fn set_token_risk(&mut self, screener: std::sync::Arc<sentinel_core::TokenRiskScreener>) {
+        self.token_risk = Some(screener);
+    }
+    
+    /// Sandwich profitability model behind `sandwich_victim_probability`
+    pub fn with_sandwich_model(mut self, model: crate::sandwich_model::SandwichModel) -> Self {
+        self.sandwich_model = model;
+        self
+    }
+    
+    /// Rank tips against Jito's network-wide percentiles (local history when stale)
+    pub fn with_tip_floor(mut self, cache: sentinel_core::TipFloorCache) -> Self {
+        self.tip_floor = Some(cache);
+        self
+    }
+    
+    /// Network-wide tip percentiles, when configured
+    pub fn tip_floor(&self) -> Option<&sentinel_core::TipFloorCache> {
+        self.tip_floor.as_ref()
+    }
+    
+    /// Slot window sizes behind the pattern features (replaces recorded history)
+    pub fn with_windows(mut self, settings: crate::swap_history::SwapWindowSettings) -> Self {
+        self.history = crate::swap_history::SwapHistory::new(settings);
+        self
+    }
+    
+    /// Slot-windowed swap history
+    pub fn history(&self) -> &crate::swap_history::SwapHistory {
+        &self.history
+    }
+    
+    /// Drop feed redeliveries (see `extract_from_feed`) within a signature window
+    pub fn with_dedup(mut self, settings: crate::dedup::DedupSettings) -> Self {
+        self.dedup = Some(crate::dedup::DedupWindow::new(settings));
+        self
+    }
+    
+    /// Duplicate-rate metrics when deduplication is enabled
+    pub fn dedup_stats(&self) -> Option<crate::dedup::DedupStats> {
+        self.dedup.as_ref().map(|window| window.stats())
+    }
+    
+    /// Select validator intel for the cluster (mainnet by default)
+    pub fn with_network(mut self, network: sentinel_core::Network) -> Self {
+        self.validator_tracker = ValidatorTracker::for_network(network);
+        self
+    }
+    
+    /// Validator intel with per-epoch observations
+    pub fn validator_tracker(&self) -> &ValidatorTracker {
+        &self.validator_tracker
+    }
+    
+    /// Feed a produced block from the stream ingestor into validator stats
+    pub fn record_block(&mut self, block: &crate::validator_intel::BlockObservation) {
+        self.validator_tracker.record_block(block);
+    }
+    
+    /// Feed sandwiches of our own transactions confirmed by the incident detector
+    pub fn record_incidents(&mut self, slot: u64, leader: Pubkey, sandwiches: u32) {
+        self.validator_tracker.record_incidents(slot, leader, sandwiches);
+    }
+    
+    /// Rolling 24h market statistics fed by observed swaps
+    pub fn market_stats(&self) -> &crate::market_stats::MarketStats {
+        &self.market_stats
+    }
+    
     /// Extract all 55 features from transaction data
     /// 
     /// Performance: <0.3ms p99
     /// Uses: Real-time Pyth prices, 241 malicious validator tracking
     pub async fn extract(&mut self, tx_data: &TransactionData) -> FeatureVector {
+        self.extract_features(tx_data, true).await
+    }
+    
+    /// Extract a transaction delivered by a subscription feed
+    ///
+    /// Redeliveries of a signature already inside the dedup window are scored
+    /// but not recorded again, so reconnect replays do not inflate
+    /// `recent_swaps_*`. Without `with_dedup` this is `extract`.
+    pub async fn extract_from_feed(&mut self, signature: &str, tx_data: &TransactionData) -> FeatureVector {
+        let record = match self.dedup.as_mut() {
+            Some(window) => window.insert(signature),
+            None => true,
+        };
+        if !record {
+            tracing::debug!("Duplicate delivery of {} not recorded", signature);
+        }
+        self.extract_features(tx_data, record).await
+    }
+    
+    /// Extract features without recording the transaction in swap history or
+    /// market stats (pre-trade simulation of a transaction that may never land)
+    pub async fn extract_preview(&mut self, tx_data: &TransactionData) -> FeatureVector {
+        self.extract_features(tx_data, false).await
+    }
+    
+    #[tracing::instrument(name = "features.extract", skip_all, fields(record))]
+    async fn extract_features(&mut self, tx_data: &TransactionData, record: bool) -> FeatureVector {
         let mut features = FeatureVector {
             // Base features
             slot: tx_data.slot,
@@ -494,6 +787,35 @@ impl FeatureExtractor {
             features.slippage_tolerance_bps = swap.slippage_tolerance_bps;
             features.pool_liquidity_usd = swap.pool_liquidity_usd;
             
+            // Reference price: gated Pyth, else the secondary oracle, else the DEX TWAP
+            let (reference, unreliable) = self.reference_price(tx_data, &(swap.input_mint, swap.output_mint)).await;
+            features.oracle_unreliable = unreliable;
+            let mut priced = false;
+            match reference {
+                Some(reference) => {
+                    let price = reference.price();
+                    let execution_price = swap.output_amount / swap.input_amount;
+                    features.oracle_price = price;
+                    features.price_deviation_pct = ((execution_price - price) / price * 100.0) as f32;
+                    match reference {
+                        ReferencePrice::Pyth(input_price) => {
+                            features.oracle_confidence = input_price.conf;
+                            features.oracle_staleness_ms =
+                                tx_data.timestamp_ms.saturating_sub(input_price.publish_time.max(0) as u64 * 1_000);
+                            features.input_price_usd = input_price.price as f32;
+                            priced = true;
+                        }
+                        ReferencePrice::Secondary(_) | ReferencePrice::Twap(_) => {
+                            features.mark_missing(&["oracle_confidence", "oracle_staleness_ms", "input_price_usd"]);
+                        }
+                    }
+                }
+                None => features.mark_missing(&[
+                    "oracle_price", "oracle_confidence", "oracle_staleness_ms",
+                    "input_price_usd", "price_deviation_pct",
+                ]),
+            }
+            
             // Calculate derived features
             features.trade_size_usd = swap.input_amount * features.input_price_usd as f64;
             features.liquidity_utilization = if swap.pool_liquidity_usd > 0.0 {
@@ -501,19 +823,11 @@ impl FeatureExtractor {
             } else {
                 0.0
             };
-            
-            // Fetch real-time Pyth prices
-            if let Some(ref mut pyth) = self.pyth_client {
-                if let Ok(input_price) = pyth.get_price("SOL/USD").await {
-                    features.oracle_price = input_price.price;
-                    features.oracle_confidence = input_price.conf;
-                    features.input_price_usd = input_price.price as f32;
-                    
-                    // Calculate price deviation
-                    let execution_price = swap.output_amount / swap.input_amount;
-                    features.price_deviation_pct = 
-                        ((execution_price - input_price.price) / input_price.price * 100.0) as f32;
-                }
+            if !priced {
+                features.mark_missing(&["trade_size_usd", "liquidity_utilization"]);
+            }
+            if swap.pool_liquidity_usd <= 0.0 && swap.pool.is_none() {
+                features.mark_missing(&["pool_liquidity_usd", "liquidity_utilization"]);
             }
             
             // Calculate price impact
@@ -522,11 +836,112 @@ impl FeatureExtractor {
             } else {
                 0.0
             };
+            
+            match self.sandwich_victim_probability(swap, features.input_price_usd) {
+                Some(probability) => features.sandwich_victim_probability = probability,
+                None => features.mark_missing(&["is_potential_sandwich_victim"]),
+            }
+            
+            // Rolling 24h market stats for this pair
+            let pair = (swap.input_mint, swap.output_mint);
+            if record && swap.input_amount > 0.0 {
+                self.market_stats.record_swap(
+                    pair,
+                    swap.output_amount / swap.input_amount,
+                    features.trade_size_usd,
+                    tx_data.timestamp_ms,
+                );
+            }
+            match self.market_stats.stats(&pair, tx_data.timestamp_ms) {
+                Some(stats) => {
+                    features.volume_24h_usd = stats.volume_usd;
+                    features.volatility_24h_pct = stats.volatility_pct();
+                }
+                None => features.mark_missing(&["volume_24h_usd", "volatility_24h_pct"]),
+            }
+            
+            // Honeypot / rug-risk screening of either mint
+            if let Some(ref screener) = self.token_risk {
+                let now = (tx_data.timestamp_ms / 1000) as i64;
+                match screener.cached_pair(&swap.input_mint, &swap.output_mint, now) {
+                    Some(risk) => features.is_high_risk_pair = risk.is_high_risk,
+                    None => features.mark_missing(&["is_high_risk_pair"]),
+                }
+            }
         }
         
         // Update history
-        self.update_history(tx_data);
+        if record {
+            self.update_history(tx_data);
+        }
+        
+        features
+    }
+    
+    /// Price to compare the swap's execution against, and whether Pyth's was refused
+    ///
+    /// Pyth is used only within `OracleSettings` bounds; otherwise the secondary
+    /// oracle, then the pair's DEX TWAP. None when no source has a price.
+    async fn reference_price(
+        &mut self,
+        tx_data: &TransactionData,
+        pair: &crate::market_stats::TokenPair,
+    ) -> (Option<ReferencePrice>, bool) {
+        let mut unreliable = false;
+        if let Some(ref mut pyth) = self.pyth_client {
+            if let Ok(price) = pyth.get_price("SOL/USD").await {
+                match price.rejection(&self.oracle, tx_data.slot, tx_data.timestamp_ms) {
+                    None => return (Some(ReferencePrice::Pyth(price)), false),
+                    Some(reason) => {
+                        tracing::warn!("Refusing Pyth price: {}", reason);
+                        unreliable = true;
+                    }
+                }
+            }
+        }
+        
+        if let Some(ref secondary) = self.secondary_oracle {
+            match secondary.get_price(&pair.0, &pair.1).await {
+                Ok(price) if price.is_finite() && price > 0.0 => {
+                    return (Some(ReferencePrice::Secondary(price)), unreliable);
+                }
+                Ok(price) => tracing::debug!("Ignoring secondary oracle price {}", price),
+                Err(e) => tracing::debug!("Secondary oracle unavailable: {}", e),
+            }
+        }
         
+        let window_ms = self.oracle.twap_window_secs.saturating_mul(1_000);
+        let twap = self.market_stats.twap(pair, tx_data.timestamp_ms, window_ms);
+        (twap.map(ReferencePrice::Twap), unreliable)
+    }
+    
+    /// Lock a shared extractor within a request deadline
+    ///
+    /// `None` when the remaining budget cannot cover extraction, up front or
+    /// after waiting for the lock; callers then fall back to
+    /// `FeatureVector::from_transaction`. No deadline waits indefinitely.
+    pub async fn lock_within<'a>(
+        extractor: &'a tokio::sync::Mutex<Self>,
+        deadline: Option<&sentinel_core::Deadline>,
+    ) -> Option<tokio::sync::MutexGuard<'a, Self>> {
+        let Some(deadline) = deadline else {
+            return Some(extractor.lock().await);
+        };
+        let extract = sentinel_core::PipelineStage::Extract;
+        let wait = deadline.remaining().checked_sub(extract.budget())?;
+        let guard = tokio::time::timeout(wait, extractor.lock()).await.ok()?;
+        deadline.allows(extract).then_some(guard)
+    }
+    
+    /// Extract features and record the extraction stage on a latency tracer
+    pub async fn extract_traced(
+        &mut self,
+        tx_data: &TransactionData,
+        tracer: &mut sentinel_core::LatencyTracer,
+    ) -> FeatureVector {
+        let start = std::time::Instant::now();
+        let features = self.extract(tx_data).await;
+        tracer.record(sentinel_core::PipelineStage::Extract, start.elapsed());
         features
     }
     
@@ -562,6 +977,7 @@ impl FeatureExtractor {
                     route_length: 1,
                     slippage_tolerance_bps: intent.constraints.max_slippage_bps as f64,
                     pool_liquidity_usd: 0.0, // Would fetch from DEX
+                    pool: None,
                 }),
                 account_count: 0,
                 instruction_count: 0,
@@ -576,7 +992,11 @@ impl FeatureExtractor {
             features.recent_swaps_same_pair = self.count_recent_swaps_same_pair(&swap_data);
             features.recent_swaps_same_actor = self.count_recent_swaps_same_actor(&swap_data);
             features.has_swap_triplet = self.detect_swap_triplet(&swap_data);
+            // Not known until a quote and a route exist
+            features.mark_missing(&["output_amount", "expected_output", "pool_liquidity_usd"]);
+            features.mark_missing(&MARKET_FEATURES);
         }
+        features.mark_missing(&VALIDATOR_FEATURES);
 
         // Set fee preferences
         features.jito_tip_lamports = intent.fee_preferences.max_jito_tip_lamports;
@@ -588,65 +1008,60 @@ impl FeatureExtractor {
     
     fn detect_swap_triplet(&self, tx_data: &TransactionData) -> bool {
         // Sandwich detection: front-run + victim + back-run pattern
-        if let Some(ref victim_swap) = tx_data.swap_details {
-            let potential_front_runs: Vec<&SwapRecord> = self
-                .recent_swaps
-                .iter()
-                .filter(|s| {
-                    s.slot <= tx_data.slot
-                        && s.slot >= tx_data.slot.saturating_sub(2)
-                        && s.token_pair.0 == victim_swap.input_mint
-                        && s.actor != tx_data.fee_payer
-                })
-                .collect();
-            
-            for front_run in potential_front_runs {
-                let has_back_run = self.recent_swaps.iter().any(|s| {
-                    s.actor == front_run.actor
-                        && s.slot >= tx_data.slot
-                        && s.slot <= tx_data.slot + 2
-                        && s.token_pair.1 == victim_swap.output_mint
-                });
-                
-                if has_back_run {
-                    return true;
-                }
+        tx_data.swap_details.as_ref().is_some_and(|victim_swap| {
+            self.history
+                .has_triplet(tx_data.slot, &tx_data.fee_payer, &victim_swap.input_mint, &victim_swap.output_mint)
+        })
+    }
+    
+    /// Sandwich probability from the attacker-optimal front-run
+    ///
+    /// Uses the swap's pool state when the caller has it; otherwise approximates
+    /// a constant-product pool holding half of `pool_liquidity_usd` per side
+    /// (needs an input price and a quote). None when neither is available.
+    fn sandwich_victim_probability(&self, swap: &SwapDetailsData, input_price_usd: f32) -> Option<f32> {
+        use crate::sandwich_model::{PoolState, VictimSwap};
+        
+        /// Fee assumed for the approximated pool (Raydium AMM v4)
+        const APPROX_FEE_BPS: u32 = 25;
+        
+        let pool = match swap.pool {
+            Some(pool) => pool,
+            None if swap.pool_liquidity_usd > 0.0 && input_price_usd > 0.0 && swap.expected_output > 0.0 && swap.input_amount > 0.0 => {
+                let reserve_in = swap.pool_liquidity_usd / 2.0 / input_price_usd as f64;
+                let price = swap.expected_output / swap.input_amount;
+                PoolState::constant_product(reserve_in, reserve_in * price, APPROX_FEE_BPS)
             }
-        }
-        false
+            None => return None,
+        };
+        
+        let quoted = if swap.expected_output > 0.0 {
+            swap.expected_output
+        } else {
+            pool.swap_in(swap.input_amount).0
+        };
+        let victim = VictimSwap::with_slippage(swap.input_amount, quoted, swap.slippage_tolerance_bps);
+        Some(self.sandwich_model.victim_probability(&pool, &victim, 0.0))
     }
     
     fn count_recent_swaps_same_pair(&self, tx_data: &TransactionData) -> u32 {
         if let Some(ref swap) = tx_data.swap_details {
-            self.recent_swaps
-                .iter()
-                .filter(|s| {
-                    s.token_pair.0 == swap.input_mint
-                        && s.token_pair.1 == swap.output_mint
-                        && s.slot >= tx_data.slot.saturating_sub(10)
-                })
-                .count() as u32
+            self.history.pair_count(tx_data.slot, &(swap.input_mint, swap.output_mint))
         } else {
             0
         }
     }
     
     fn count_recent_swaps_same_actor(&self, tx_data: &TransactionData) -> u32 {
-        self.recent_swaps
-            .iter()
-            .filter(|s| {
-                s.actor == tx_data.fee_payer 
-                    && s.slot >= tx_data.slot.saturating_sub(100)
-            })
-            .count() as u32
+        self.history.actor_count(tx_data.slot, &tx_data.fee_payer)
     }
     
     fn calculate_tip_percentile(&self, tx_data: &TransactionData) -> f32 {
-        let recent_tips: Vec<u64> = self.recent_swaps
-            .iter()
-            .filter(|s| s.slot >= tx_data.slot.saturating_sub(100))
-            .map(|s| s.amount)
-            .collect();
+        if let Some(floor) = self.tip_floor.as_ref().and_then(|cache| cache.current()) {
+            return floor.percentile_of(tx_data.jito_tip_lamports);
+        }
+        
+        let recent_tips: Vec<u64> = self.history.tips(tx_data.slot).collect();
         
         if recent_tips.is_empty() {
             return 50.0;
@@ -677,17 +1092,28 @@ impl FeatureExtractor {
     
     fn update_history(&mut self, tx_data: &TransactionData) {
         if let Some(ref swap) = tx_data.swap_details {
-            self.recent_swaps.push(SwapRecord {
-                slot: tx_data.slot,
-                actor: tx_data.fee_payer,
-                token_pair: (swap.input_mint, swap.output_mint),
-                amount: tx_data.jito_tip_lamports,
-                timestamp_ms: tx_data.timestamp_ms,
-            });
-            
-            if self.recent_swaps.len() > self.max_history {
-                self.recent_swaps.drain(0..self.recent_swaps.len() - self.max_history);
-            }
+            self.history.record(
+                tx_data.slot,
+                tx_data.fee_payer,
+                (swap.input_mint, swap.output_mint),
+                tx_data.jito_tip_lamports,
+            );
+        }
+    }
+}
+
+/// Source of the price a swap's execution is compared against
+enum ReferencePrice {
+    Pyth(crate::pyth_oracle::PriceData),
+    Secondary(f64),
+    Twap(f64),
+}
+
+impl ReferencePrice {
+    fn price(&self) -> f64 {
+        match self {
+            ReferencePrice::Pyth(price) => price.price,
+            ReferencePrice::Secondary(price) | ReferencePrice::Twap(price) => *price,
         }
     }
 }
@@ -701,19 +1127,66 @@ impl Default for FeatureExtractor {
 /// Validator risk tracking (241 malicious validators monitored)
 pub struct ValidatorTracker {
     intel_map: HashMap<Pubkey, crate::validator_intel::ValidatorIntel>,
+    epochs: crate::validator_intel::EpochStatsAggregator,
 }
 
 impl ValidatorTracker {
     pub fn new() -> Self {
-        let intel_map = crate::validator_intel::load_validator_intel();
+        Self::for_network(sentinel_core::Network::Mainnet)
+    }
+    
+    /// Tracker seeded with the intel that applies to `network`
+    pub fn for_network(network: sentinel_core::Network) -> Self {
+        let intel_map = crate::validator_intel::load_validator_intel_for(network);
         
         tracing::info!("✅ ValidatorTracker initialized with {} entries", intel_map.len());
         
         Self {
             intel_map,
+            epochs: crate::validator_intel::EpochStatsAggregator::default(),
         }
     }
     
+    /// Feed a produced block from the stream ingestor into per-epoch stats
+    pub fn record_block(&mut self, block: &crate::validator_intel::BlockObservation) {
+        self.epochs.record_block(block, &mut self.intel_map);
+    }
+    
+    /// Add post-hoc confirmed sandwiches to the leader's current-epoch stats
+    pub fn record_incidents(&mut self, slot: u64, leader: Pubkey, sandwiches: u32) {
+        self.epochs.record_incidents(slot, leader, sandwiches, &mut self.intel_map);
+    }
+    
+    /// Signed-bundle-ready snapshot of the intel map (see `intel_exchange`)
+    pub fn export_intel(&self, publisher: Pubkey, source: &str, now: i64) -> crate::intel_exchange::IntelBundle {
+        crate::intel_exchange::IntelBundle::export(&self.intel_map, publisher, source, now)
+    }
+    
+    /// Merge a peer's signed intel bundle by recency
+    pub fn import_intel(
+        &mut self,
+        signed: &crate::intel_exchange::SignedIntelBundle,
+        trusted_publishers: &[Pubkey],
+    ) -> sentinel_core::Result<crate::intel_exchange::ImportSummary> {
+        crate::intel_exchange::import_intel(&mut self.intel_map, signed, trusted_publishers)
+    }
+    
+    /// Fold a fetched on-chain registry into the intel map, local overrides last
+    pub fn merge_registry(
+        &mut self,
+        registry: &crate::validator_registry::ValidatorRegistry,
+        source: &str,
+        overrides: &crate::validator_registry::IntelOverrides,
+        now: i64,
+    ) -> crate::validator_registry::RegistryMerge {
+        crate::validator_registry::merge_registry(&mut self.intel_map, registry, source, overrides, now)
+    }
+    
+    /// Intel (static + blended observations) for a validator
+    pub fn intel(&self, pubkey: &Pubkey) -> Option<&crate::validator_intel::ValidatorIntel> {
+        self.intel_map.get(pubkey)
+    }
+    
     pub fn is_malicious(&self, pubkey: &Pubkey) -> bool {
         self.intel_map.get(pubkey)
             .map(|intel| intel.is_malicious)
@@ -786,6 +1259,9 @@ pub struct SwapDetailsData {
     pub route_length: u32,
     pub slippage_tolerance_bps: f64,
     pub pool_liquidity_usd: f64,
+    
+    /// Pool state the swap executes against (oriented input → output), when known
+    pub pool: Option<crate::sandwich_model::PoolState>,
 }
 
 #[cfg(test)]
@@ -799,6 +1275,22 @@ mod tests {
         assert_eq!(FeatureVector::FEATURE_COUNT, 55);
     }
     
+    #[test]
+    fn test_write_into_matches_to_array() {
+        let features = FeatureVector {
+            jito_tip_lamports: 150_000,
+            has_swap_triplet: true,
+            validator_risk_score: 0.8,
+            ..Default::default()
+        };
+        
+        let mut buf = [f32::NAN; FeatureVector::FEATURE_COUNT];
+        features.write_into(&mut buf);
+        assert_eq!(buf.to_vec(), features.to_array());
+        assert_eq!(buf[3], 150_000.0);
+        assert_eq!(buf[28], 1.0);
+    }
+    
     #[test]
     fn test_feature_validation() {
         let features = FeatureVector::default();
@@ -813,4 +1305,196 @@ mod tests {
         };
         assert!(features.validate().is_err());
     }
+    
+    #[test]
+    fn test_missing_features_distinct_from_zero() {
+        let tx = TransactionData {
+            slot: 100,
+            fee_payer: Pubkey::new_unique(),
+            compute_unit_limit: 200_000,
+            compute_unit_price: 0,
+            jito_tip_lamports: 0,
+            total_fee_lamports: 5_000,
+            account_count: 10,
+            instruction_count: 3,
+            tx_size_bytes: 600,
+            swap_details: Some(SwapDetailsData {
+                input_mint: Pubkey::new_unique(),
+                output_mint: Pubkey::new_unique(),
+                input_amount: 1_000.0,
+                output_amount: 990.0,
+                expected_output: 1_000.0,
+                route_length: 1,
+                slippage_tolerance_bps: 100.0,
+                pool_liquidity_usd: 0.0,
+                pool: None,
+            }),
+            time_since_last_slot_ms: 0,
+            next_leader_pubkey: Pubkey::default(),
+            uses_lookup_tables: false,
+            timestamp_ms: 1_700_000_000_000,
+        };
+        
+        let mut features = FeatureVector::from_transaction(&tx);
+        assert!(features.is_missing("pool_liquidity_usd"));
+        assert!(features.is_missing("validator_risk_score"));
+        assert!(!features.is_missing("compute_unit_price"));
+        assert!(!features.is_missing("price_impact_bps"));
+        
+        let mut sparse = [0.0f32; FeatureVector::FEATURE_COUNT];
+        features.write_sparse_into(&mut sparse);
+        let index = FeatureVector::feature_index("pool_liquidity_usd").unwrap();
+        assert!(sparse[index].is_nan());
+        assert_eq!(sparse.iter().filter(|v| v.is_nan()).count(), features.missing.len());
+        
+        // Placeholders of missing features are not range checked
+        features.price_impact_bps = -1.0;
+        assert!(features.validate().is_err());
+        features.mark_missing(&["price_impact_bps"]);
+        assert!(features.validate().is_ok());
+        
+        // The mask survives serialization; older records without one read as dense
+        let json = serde_json::to_value(&features).unwrap();
+        assert_eq!(serde_json::from_value::<FeatureVector>(json.clone()).unwrap().missing, features.missing);
+        let mut dense = json;
+        dense.as_object_mut().unwrap().remove("missing");
+        assert!(serde_json::from_value::<FeatureVector>(dense).unwrap().missing.is_empty());
+    }
+    
+    /// Serves the same JSON body on every connection
+    async fn serve_json(body: serde_json::Value) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = body.to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+    
+    #[tokio::test]
+    async fn test_unreliable_pyth_price_falls_back_to_twap() {
+        // SOL at $150 ± $6: a 4% confidence interval
+        let hermes = serve_json(serde_json::json!({ "parsed": [{
+            "id": "ef0d",
+            "price": { "price": "15000000000", "conf": "600000000", "expo": -8, "publish_time": 1_700_000_000 },
+            "metadata": { "slot": 100 }
+        }]}))
+        .await;
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let tx = TransactionData {
+            slot: 100,
+            fee_payer: Pubkey::new_unique(),
+            compute_unit_limit: 200_000,
+            compute_unit_price: 0,
+            jito_tip_lamports: 0,
+            total_fee_lamports: 5_000,
+            account_count: 10,
+            instruction_count: 3,
+            tx_size_bytes: 600,
+            swap_details: Some(SwapDetailsData {
+                input_mint: sol,
+                output_mint: usdc,
+                input_amount: 1_000.0,
+                output_amount: 990.0,
+                expected_output: 1_000.0,
+                route_length: 1,
+                slippage_tolerance_bps: 100.0,
+                pool_liquidity_usd: 0.0,
+                pool: None,
+            }),
+            time_since_last_slot_ms: 0,
+            next_leader_pubkey: Pubkey::default(),
+            uses_lookup_tables: false,
+            timestamp_ms: 1_700_000_000_000,
+        };
+        let market_stats = || {
+            let mut stats = crate::market_stats::MarketStats::new();
+            stats.record_price((sol, usdc), 1.0, tx.timestamp_ms - 60_000);
+            stats
+        };
+        
+        let mut extractor = FeatureExtractor::new()
+            .with_pyth_client(crate::pyth_oracle::PythOracleClient::new(hermes.clone(), 60))
+            .with_market_stats(market_stats());
+        let features = extractor.extract_preview(&tx).await;
+        assert!(features.oracle_unreliable);
+        assert_eq!(features.oracle_price, 1.0);
+        assert!((features.price_deviation_pct + 1.0).abs() < 1e-4);
+        assert!(features.is_missing("oracle_confidence"));
+        assert!(features.is_missing("input_price_usd"));
+        
+        // Within a looser bound the Pyth price is used
+        let mut extractor = FeatureExtractor::new()
+            .with_pyth_client(crate::pyth_oracle::PythOracleClient::new(hermes, 60))
+            .with_market_stats(market_stats())
+            .with_oracle_settings(sentinel_core::OracleSettings {
+                max_confidence_ratio: 0.05,
+                ..Default::default()
+            });
+        let features = extractor.extract_preview(&tx).await;
+        assert!(!features.oracle_unreliable);
+        assert_eq!(features.oracle_price, 150.0);
+        assert_eq!(features.oracle_confidence, 6.0);
+        assert!(!features.is_missing("oracle_confidence"));
+    }
+    
+    #[tokio::test]
+    async fn test_feed_redeliveries_not_recorded() {
+        let (actor, sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let tx = TransactionData {
+            slot: 100,
+            fee_payer: actor,
+            compute_unit_limit: 200_000,
+            compute_unit_price: 0,
+            jito_tip_lamports: 0,
+            total_fee_lamports: 5_000,
+            account_count: 10,
+            instruction_count: 3,
+            tx_size_bytes: 600,
+            swap_details: Some(SwapDetailsData {
+                input_mint: sol,
+                output_mint: usdc,
+                input_amount: 1_000.0,
+                output_amount: 990.0,
+                expected_output: 1_000.0,
+                route_length: 1,
+                slippage_tolerance_bps: 100.0,
+                pool_liquidity_usd: 0.0,
+                pool: None,
+            }),
+            time_since_last_slot_ms: 0,
+            next_leader_pubkey: Pubkey::default(),
+            uses_lookup_tables: false,
+            timestamp_ms: 1_700_000_000_000,
+        };
+        
+        let mut extractor = FeatureExtractor::new().with_dedup(crate::dedup::DedupSettings::default());
+        extractor.extract_from_feed("sig-1", &tx).await;
+        // Reconnect replay delivers sig-1 again
+        extractor.extract_from_feed("sig-1", &tx).await;
+        let features = extractor.extract_from_feed("sig-2", &tx).await;
+        assert_eq!(features.recent_swaps_same_actor, 1);
+        
+        let stats = extractor.dedup_stats().unwrap();
+        assert_eq!((stats.seen, stats.duplicates), (3, 1));
+        
+        // Without a window every delivery is recorded
+        let mut plain = FeatureExtractor::new();
+        plain.extract_from_feed("sig-1", &tx).await;
+        plain.extract_from_feed("sig-1", &tx).await;
+        assert_eq!(plain.extract_from_feed("sig-2", &tx).await.recent_swaps_same_actor, 2);
+        assert!(plain.dedup_stats().is_none());
+    }
 }
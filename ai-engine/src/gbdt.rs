@@ -0,0 +1,319 @@
+//! Pure-Rust Gradient-Boosted Tree Model
+//!
+//! Loads the JSON dump produced by XGBoost (`booster.get_dump(dump_format="json")`)
+//! and evaluates it without any native runtime. Two file layouts are accepted:
+//! - a bare array of trees (the raw dump)
+//! - `{"base_score": 0.5, "feature_names": [...], "trees": [...]}`
+//!
+//! Splits reference features either as `f<index>` or by name from
+//! `FeatureVector::FEATURE_NAMES`. A sample goes to `yes` when
+//! `x < split_condition`; NaN inputs follow `missing`. The summed margin is
+//! passed through a sigmoid (`binary:logistic`).
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use sentinel_core::{Result, SentinelError};
+
+use crate::features_enhanced::FeatureVector;
+
+const LEAF: u32 = u32::MAX;
+
+/// Flattened tree node (`feature == LEAF` marks a leaf holding `value`)
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    feature: u32,
+    value: f32,
+    yes: u32,
+    no: u32,
+    missing: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn eval(&self, features: &[f32]) -> f32 {
+        let mut node = &self.nodes[0];
+        while node.feature != LEAF {
+            let x = features[node.feature as usize];
+            let next = if x.is_nan() {
+                node.missing
+            } else if x < node.value {
+                node.yes
+            } else {
+                node.no
+            };
+            node = &self.nodes[next as usize];
+        }
+        node.value
+    }
+}
+
+/// Node as it appears in the XGBoost JSON dump
+#[derive(Deserialize)]
+struct DumpNode {
+    nodeid: u32,
+    #[serde(default)]
+    leaf: Option<f32>,
+    #[serde(default)]
+    split: Option<String>,
+    #[serde(default)]
+    split_condition: Option<f32>,
+    #[serde(default)]
+    yes: Option<u32>,
+    #[serde(default)]
+    no: Option<u32>,
+    #[serde(default)]
+    missing: Option<u32>,
+    #[serde(default)]
+    children: Vec<DumpNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DumpFile {
+    Trees(Vec<DumpNode>),
+    Model {
+        #[serde(default)]
+        base_score: Option<f32>,
+        #[serde(default)]
+        feature_names: Option<Vec<String>>,
+        trees: Vec<DumpNode>,
+    },
+}
+
+/// Gradient-boosted tree ensemble with a logistic output
+#[derive(Debug, Clone)]
+pub struct GbdtModel {
+    trees: Vec<Tree>,
+    base_margin: f32,
+    feature_count: usize,
+}
+
+impl GbdtModel {
+    /// Load an XGBoost JSON dump from disk
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| SentinelError::InferenceError(format!("Failed to read {:?}: {}", path, e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Parse an XGBoost JSON dump
+    pub fn from_json(json: &str) -> Result<Self> {
+        let dump: DumpFile = serde_json::from_str(json)
+            .map_err(|e| SentinelError::InferenceError(format!("Invalid XGBoost dump: {}", e)))?;
+
+        let (base_score, names, roots) = match dump {
+            DumpFile::Trees(trees) => (0.5, None, trees),
+            DumpFile::Model { base_score, feature_names, trees } => {
+                (base_score.unwrap_or(0.5), feature_names, trees)
+            }
+        };
+
+        let names: Vec<String> = names.unwrap_or_else(|| {
+            FeatureVector::FEATURE_NAMES.iter().map(|n| n.to_string()).collect()
+        });
+        let index: HashMap<&str, u32> = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.as_str(), i as u32))
+            .collect();
+
+        if roots.is_empty() {
+            return Err(SentinelError::InferenceError("XGBoost dump contains no trees".to_string()));
+        }
+
+        let trees = roots
+            .iter()
+            .map(|root| flatten(root, &index))
+            .collect::<Result<Vec<_>>>()?;
+
+        let feature_count = trees
+            .iter()
+            .flat_map(|t| t.nodes.iter())
+            .filter(|n| n.feature != LEAF)
+            .map(|n| n.feature as usize + 1)
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            trees,
+            base_margin: logit(base_score),
+            feature_count,
+        })
+    }
+
+    pub fn tree_count(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Highest feature index referenced + 1
+    pub fn feature_count(&self) -> usize {
+        self.feature_count
+    }
+
+    /// Raw margin (sum of leaves plus base margin)
+    pub fn margin(&self, features: &[f32]) -> Result<f32> {
+        if features.len() < self.feature_count {
+            return Err(SentinelError::InferenceError(format!(
+                "GBDT model expects {} features, got {}",
+                self.feature_count,
+                features.len()
+            )));
+        }
+        Ok(self.base_margin + self.trees.iter().map(|t| t.eval(features)).sum::<f32>())
+    }
+
+    /// MEV probability in [0, 1]
+    pub fn predict(&self, features: &[f32]) -> Result<f32> {
+        Ok(sigmoid(self.margin(features)?))
+    }
+}
+
+/// Flatten a nested dump tree into a node vector indexed by `nodeid`
+fn flatten(root: &DumpNode, index: &HashMap<&str, u32>) -> Result<Tree> {
+    let mut pending = vec![root];
+    let mut dumped = Vec::new();
+    while let Some(node) = pending.pop() {
+        pending.extend(node.children.iter());
+        dumped.push(node);
+    }
+
+    let mut nodes = vec![None; dumped.len()];
+    for node in dumped {
+        let slot = nodes
+            .get_mut(node.nodeid as usize)
+            .ok_or_else(|| tree_error(format!("node id {} out of range", node.nodeid)))?;
+        *slot = Some(convert(node, index)?);
+    }
+
+    let nodes = nodes
+        .into_iter()
+        .enumerate()
+        .map(|(id, n)| n.ok_or_else(|| tree_error(format!("node {} missing", id))))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Children always follow their parent in a dump; this also rules out cycles
+    let count = nodes.len() as u32;
+    let valid = |id: u32, child: u32| child > id && child < count;
+    if nodes
+        .iter()
+        .zip(0u32..)
+        .filter(|(n, _)| n.feature != LEAF)
+        .any(|(n, id)| !valid(id, n.yes) || !valid(id, n.no) || !valid(id, n.missing))
+    {
+        return Err(tree_error("child reference out of range"));
+    }
+
+    Ok(Tree { nodes })
+}
+
+fn convert(node: &DumpNode, index: &HashMap<&str, u32>) -> Result<Node> {
+    if let Some(value) = node.leaf {
+        return Ok(Node { feature: LEAF, value, yes: 0, no: 0, missing: 0 });
+    }
+
+    let split = node
+        .split
+        .as_deref()
+        .ok_or_else(|| tree_error(format!("node {} has neither leaf nor split", node.nodeid)))?;
+    let feature = index
+        .get(split)
+        .copied()
+        .or_else(|| split.strip_prefix('f').and_then(|i| i.parse().ok()))
+        .ok_or_else(|| tree_error(format!("unknown split feature '{}'", split)))?;
+
+    let yes = node.yes.ok_or_else(|| tree_error("split without 'yes'"))?;
+    let no = node.no.ok_or_else(|| tree_error("split without 'no'"))?;
+
+    Ok(Node {
+        feature,
+        value: node.split_condition.unwrap_or(0.0),
+        yes,
+        no,
+        missing: node.missing.unwrap_or(yes),
+    })
+}
+
+fn tree_error(reason: impl Into<String>) -> SentinelError {
+    SentinelError::InferenceError(format!("Invalid XGBoost tree: {}", reason.into()))
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn logit(p: f32) -> f32 {
+    let p = p.clamp(1e-6, 1.0 - 1e-6);
+    (p / (1.0 - p)).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"{
+        "base_score": 0.5,
+        "trees": [
+            {"nodeid": 0, "depth": 0, "split": "f3", "split_condition": 100000.0,
+             "yes": 1, "no": 2, "missing": 2, "children": [
+                {"nodeid": 1, "leaf": -1.0},
+                {"nodeid": 2, "leaf": 1.5}
+            ]},
+            {"nodeid": 0, "depth": 0, "split": "has_swap_triplet", "split_condition": 0.5,
+             "yes": 1, "no": 2, "children": [
+                {"nodeid": 2, "leaf": 2.0},
+                {"nodeid": 1, "leaf": -0.5}
+            ]}
+        ]
+    }"#;
+
+    fn features(tip: f32, triplet: bool) -> Vec<f32> {
+        let mut x = vec![0.0; FeatureVector::FEATURE_COUNT];
+        x[3] = tip;
+        let triplet_idx = FeatureVector::FEATURE_NAMES
+            .iter()
+            .position(|n| *n == "has_swap_triplet")
+            .unwrap();
+        x[triplet_idx] = if triplet { 1.0 } else { 0.0 };
+        x
+    }
+
+    #[test]
+    fn test_trees_sum_through_sigmoid() {
+        let model = GbdtModel::from_json(DUMP).unwrap();
+        assert_eq!(model.tree_count(), 2);
+
+        // base margin 0 (score 0.5); -1.0 + -0.5
+        let low = model.margin(&features(10.0, false)).unwrap();
+        assert!((low + 1.5).abs() < 1e-6);
+
+        let high = model.predict(&features(500_000.0, true)).unwrap();
+        assert!((high - sigmoid(3.5)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nan_follows_missing_branch() {
+        let model = GbdtModel::from_json(DUMP).unwrap();
+        let mut x = features(0.0, false);
+        x[3] = f32::NAN;
+        assert!((model.margin(&x).unwrap() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bare_dump_and_errors() {
+        let bare = r#"[{"nodeid": 0, "leaf": 0.0}]"#;
+        let model = GbdtModel::from_json(bare).unwrap();
+        assert!((model.predict(&[]).unwrap() - 0.5).abs() < 1e-6);
+
+        assert!(GbdtModel::from_json("[]").is_err());
+        assert!(GbdtModel::from_json(r#"[{"nodeid": 0, "split": "bogus", "yes": 1, "no": 2}]"#).is_err());
+
+        let model = GbdtModel::from_json(DUMP).unwrap();
+        assert!(model.predict(&[0.0; 4]).is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! Declarative Heuristic Rules
+//!
+//! The heuristic risk score is a set of named rules, each comparing one
+//! feature (by its `FeatureVector::FEATURE_NAMES` column) against a threshold
+//! and contributing a weight when it fires. Rules live in a TOML or JSON file
+//! so risk policy can change without a deploy:
+//!
+//! ```toml
+//! version = "2024-06-01"
+//! checksum = "9f2c…"   # optional; loading fails when it doesn't match
+//!
+//! [[rules]]
+//! name = "high_jito_tip"
+//! feature = "jito_tip_lamports"
+//! op = "gt"
+//! threshold = 100000.0
+//! weight = 0.4
+//! ```
+//!
+//! Fired weights are blended as `max * max_weight + mean * (1 - max_weight)`,
+//! capped at `ceiling`; `baseline` is the score when nothing fires. A rule on
+//! a feature marked missing never fires. The checksum is the SHA-256 of the
+//! canonical JSON of `aggregation` and `rules`, so a file edited without
+//! re-sealing is rejected. [`HeuristicRules::default`] is the built-in
+//! production rule set.
+
+use sentinel_core::{Result, SentinelError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::features_enhanced::FeatureVector;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    #[default]
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+}
+
+impl Comparison {
+    fn holds(&self, value: f32, threshold: f32) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Gte => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Lte => value <= threshold,
+            Comparison::Eq => value == threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeuristicRule {
+    pub name: String,
+
+    /// Column in `FeatureVector::FEATURE_NAMES`; booleans read as 0.0 / 1.0
+    pub feature: String,
+
+    #[serde(default)]
+    pub op: Comparison,
+    pub threshold: f32,
+
+    /// Risk contributed when the rule fires (0-1)
+    pub weight: f32,
+}
+
+impl HeuristicRule {
+    fn new(name: &str, feature: &str, threshold: f32, weight: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            feature: feature.to_string(),
+            op: Comparison::Gt,
+            threshold,
+            weight,
+        }
+    }
+}
+
+/// How fired rule weights combine into one score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Aggregation {
+    /// Share of the strongest fired weight in the blend; the rest is the mean
+    pub max_weight: f32,
+
+    /// Highest score heuristics alone can produce
+    pub ceiling: f32,
+
+    /// Score when no rule fires
+    pub baseline: f32,
+}
+
+impl Default for Aggregation {
+    fn default() -> Self {
+        Self {
+            max_weight: 0.7,
+            ceiling: 0.95,
+            baseline: 0.15,
+        }
+    }
+}
+
+/// Rules file contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSet {
+    pub version: String,
+
+    /// Expected `checksum()`; unchecked when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+
+    #[serde(default)]
+    pub aggregation: Aggregation,
+    pub rules: Vec<HeuristicRule>,
+}
+
+impl RuleSet {
+    /// Hex SHA-256 of the canonical JSON of `aggregation` and `rules`
+    pub fn checksum(&self) -> String {
+        let canonical = serde_json::json!({ "aggregation": self.aggregation, "rules": self.rules });
+        hex::encode(Sha256::digest(canonical.to_string().as_bytes()))
+    }
+
+    /// Set `checksum` to the current contents
+    pub fn sealed(mut self) -> Self {
+        self.checksum = Some(self.checksum());
+        self
+    }
+}
+
+/// Validated rule set with features resolved to column indices
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeuristicRules {
+    version: String,
+    checksum: String,
+    aggregation: Aggregation,
+    rules: Vec<(usize, HeuristicRule)>,
+}
+
+impl Default for HeuristicRules {
+    fn default() -> Self {
+        let rules = RuleSet {
+            version: "builtin".to_string(),
+            checksum: None,
+            aggregation: Aggregation::default(),
+            rules: vec![
+                HeuristicRule::new("high_urgency", "compute_unit_price", 200_000.0, 0.3),
+                HeuristicRule::new("high_jito_tip", "jito_tip_lamports", 100_000.0, 0.4),
+                HeuristicRule::new("high_price_impact", "price_impact_bps", 200.0, 0.35),
+                HeuristicRule::new("large_trade", "liquidity_utilization", 0.05, 0.25),
+                HeuristicRule::new("price_deviation", "price_deviation_pct", 2.0, 0.4),
+                HeuristicRule::new("swap_triplet", "has_swap_triplet", 0.5, 0.6),
+                HeuristicRule::new("tip_outlier", "tip_percentile_vs_recent", 95.0, 0.35),
+                HeuristicRule::new("mev_bot_pattern", "matches_mev_bot_pattern", 0.5, 0.45),
+                HeuristicRule::new("malicious_leader", "next_leader_malicious", 0.5, 0.5),
+                HeuristicRule::new("risky_validator", "validator_risk_score", 0.7, 0.45),
+            ],
+        };
+        Self::compile(rules).expect("built-in heuristic rules are valid")
+    }
+}
+
+impl HeuristicRules {
+    /// Validate `rule_set` (feature names, ranges, checksum) and resolve feature columns
+    pub fn compile(rule_set: RuleSet) -> Result<Self> {
+        let checksum = rule_set.checksum();
+        if let Some(ref expected) = rule_set.checksum {
+            if !expected.eq_ignore_ascii_case(&checksum) {
+                return Err(rules_error(format!(
+                    "checksum mismatch for version {}: file says {}, rules hash to {}",
+                    rule_set.version, expected, checksum
+                )));
+            }
+        }
+
+        let aggregation = &rule_set.aggregation;
+        if !(0.0..=1.0).contains(&aggregation.max_weight)
+            || !(0.0..=1.0).contains(&aggregation.ceiling)
+            || !(0.0..=1.0).contains(&aggregation.baseline)
+        {
+            return Err(rules_error("aggregation values must be within 0-1".to_string()));
+        }
+
+        let mut rules = Vec::with_capacity(rule_set.rules.len());
+        for rule in rule_set.rules {
+            let index = FeatureVector::FEATURE_NAMES
+                .iter()
+                .position(|name| *name == rule.feature)
+                .ok_or_else(|| rules_error(format!("rule {} uses unknown feature {}", rule.name, rule.feature)))?;
+            if !(0.0..=1.0).contains(&rule.weight) || !rule.threshold.is_finite() {
+                return Err(rules_error(format!(
+                    "rule {} needs a weight within 0-1 and a finite threshold",
+                    rule.name
+                )));
+            }
+            if rules.iter().any(|(_, r): &(usize, HeuristicRule)| r.name == rule.name) {
+                return Err(rules_error(format!("duplicate rule name {}", rule.name)));
+            }
+            rules.push((index, rule));
+        }
+
+        Ok(Self {
+            version: rule_set.version,
+            checksum,
+            aggregation: rule_set.aggregation,
+            rules,
+        })
+    }
+
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        let rule_set = toml::from_str(toml).map_err(|e| rules_error(e.to_string()))?;
+        Self::compile(rule_set)
+    }
+
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let rule_set = serde_json::from_str(json).map_err(|e| rules_error(e.to_string()))?;
+        Self::compile(rule_set)
+    }
+
+    /// Load a `.json` file, or TOML for any other extension
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to read heuristic rules {:?}: {}", path, e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            _ => Self::from_toml_str(&contents),
+        }
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    /// Back to the file representation (sealed)
+    pub fn to_rule_set(&self) -> RuleSet {
+        RuleSet {
+            version: self.version.clone(),
+            checksum: Some(self.checksum.clone()),
+            aggregation: self.aggregation.clone(),
+            rules: self.rules.iter().map(|(_, rule)| rule.clone()).collect(),
+        }
+    }
+
+    /// Names of the rules `features` trips
+    pub fn fired(&self, features: &FeatureVector) -> Vec<&str> {
+        let values = columns(features);
+        self.rules
+            .iter()
+            .filter(|(index, rule)| rule.op.holds(values[*index], rule.threshold))
+            .map(|(_, rule)| rule.name.as_str())
+            .collect()
+    }
+
+    /// Heuristic risk score (0-1)
+    pub fn score(&self, features: &FeatureVector) -> f32 {
+        let values = columns(features);
+        let (mut max, mut sum, mut count) = (0.0f32, 0.0f32, 0usize);
+        for (index, rule) in &self.rules {
+            if rule.op.holds(values[*index], rule.threshold) {
+                max = max.max(rule.weight);
+                sum += rule.weight;
+                count += 1;
+            }
+        }
+
+        if count == 0 {
+            return self.aggregation.baseline;
+        }
+        let blended = max * self.aggregation.max_weight + sum / count as f32 * (1.0 - self.aggregation.max_weight);
+        blended.min(self.aggregation.ceiling)
+    }
+}
+
+fn columns(features: &FeatureVector) -> [f32; FeatureVector::FEATURE_COUNT] {
+    let mut values = [0.0f32; FeatureVector::FEATURE_COUNT];
+    features.write_sparse_into(&mut values);
+    values
+}
+
+fn rules_error(reason: String) -> SentinelError {
+    SentinelError::ConfigError(format!("Invalid heuristic rules: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+        version = "test-1"
+
+        [aggregation]
+        max_weight = 1.0
+
+        [[rules]]
+        name = "tip"
+        feature = "jito_tip_lamports"
+        threshold = 50000.0
+        weight = 0.5
+
+        [[rules]]
+        name = "thin_pool"
+        feature = "pool_liquidity_usd"
+        op = "lt"
+        threshold = 10000.0
+        weight = 0.8
+    "#;
+
+    #[test]
+    fn test_builtin_rules() {
+        let rules = HeuristicRules::default();
+        assert_eq!(rules.score(&FeatureVector::default()), 0.15);
+
+        let features = FeatureVector {
+            has_swap_triplet: true,
+            jito_tip_lamports: 200_000,
+            ..Default::default()
+        };
+        assert_eq!(rules.fired(&features), vec!["high_jito_tip", "swap_triplet"]);
+        // max 0.6 * 0.7 + mean 0.5 * 0.3
+        assert!((rules.score(&features) - 0.57).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_toml_rules_by_feature_name() {
+        let rules = HeuristicRules::from_toml_str(RULES).unwrap();
+        assert_eq!(rules.version(), "test-1");
+
+        let features = FeatureVector {
+            jito_tip_lamports: 60_000,
+            pool_liquidity_usd: 1_000_000.0,
+            ..Default::default()
+        };
+        assert_eq!(rules.score(&features), 0.5);
+        let features = FeatureVector {
+            pool_liquidity_usd: 5_000.0,
+            ..features
+        };
+        assert_eq!(rules.fired(&features), vec!["tip", "thin_pool"]);
+        assert_eq!(rules.score(&features), 0.8);
+
+        // Unknown liquidity is not a thin pool
+        let mut features = FeatureVector {
+            pool_liquidity_usd: 0.0,
+            ..features
+        };
+        features.mark_missing(&["pool_liquidity_usd"]);
+        assert_eq!(rules.fired(&features), vec!["tip"]);
+    }
+
+    #[test]
+    fn test_checksum_and_validation() {
+        let rules = HeuristicRules::from_toml_str(RULES).unwrap();
+        let sealed = serde_json::to_string(&rules.to_rule_set()).unwrap();
+        assert_eq!(HeuristicRules::from_json_str(&sealed).unwrap(), rules);
+
+        let tampered = sealed.replace("0.8", "0.1");
+        let error = HeuristicRules::from_json_str(&tampered).unwrap_err();
+        assert!(error.to_string().contains("checksum mismatch"), "{}", error);
+
+        let unknown = RULES.replace("pool_liquidity_usd", "pool_liquidity");
+        assert!(HeuristicRules::from_toml_str(&unknown).is_err());
+        let heavy = RULES.replace("weight = 0.8", "weight = 1.5");
+        assert!(HeuristicRules::from_toml_str(&heavy).is_err());
+    }
+}
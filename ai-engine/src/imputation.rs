@@ -0,0 +1,107 @@
+//! Missing Feature Imputation
+//!
+//! Features the extractor could not observe are marked in
+//! `FeatureVector::missing`; their stored zeros are placeholders. The
+//! [`FeatureImputer`] replaces them with what the model was trained to see,
+//! per `model.imputation`:
+//!
+//! ```toml
+//! [model.imputation]
+//! default = "zero"                                   # or "native", { constant = x }
+//! features.pool_liquidity_usd = { constant = -1.0 }
+//! ```
+//!
+//! Imputed values are model inputs, applied after normalization: `zero` is the
+//! training mean of a standardized feature, `native` passes NaN through for
+//! backends with their own missing-value handling (GBDT `missing` branches).
+
+use sentinel_core::{Imputation, ImputationSettings, Result, SentinelError};
+
+use crate::features_enhanced::{FeatureMask, FeatureVector};
+use crate::model_backend::ModelInput;
+
+/// Model input per feature for when it is missing
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureImputer {
+    fill: ModelInput,
+}
+
+impl Default for FeatureImputer {
+    fn default() -> Self {
+        Self {
+            fill: [0.0; FeatureVector::FEATURE_COUNT],
+        }
+    }
+}
+
+impl FeatureImputer {
+    /// Rejects overrides naming an unknown feature
+    pub fn new(settings: &ImputationSettings) -> Result<Self> {
+        let mut fill = [fill_value(settings.default); FeatureVector::FEATURE_COUNT];
+        for (name, imputation) in &settings.features {
+            let index = FeatureVector::feature_index(name).ok_or_else(|| {
+                SentinelError::ConfigError(format!("model.imputation names unknown feature {}", name))
+            })?;
+            fill[index] = fill_value(*imputation);
+        }
+        Ok(Self { fill })
+    }
+
+    /// Model input used for feature `index` when it is missing
+    pub fn fill(&self, index: usize) -> Option<f32> {
+        self.fill.get(index).copied()
+    }
+
+    /// Overwrite the missing features of a model-ready input
+    pub fn impute(&self, missing: &FeatureMask, input: &mut ModelInput) {
+        for index in missing.iter() {
+            input[index] = self.fill[index];
+        }
+    }
+}
+
+fn fill_value(imputation: Imputation) -> f32 {
+    match imputation {
+        Imputation::Zero => 0.0,
+        Imputation::Native => f32::NAN,
+        Imputation::Constant(value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_features_imputed_per_config() {
+        let settings = ImputationSettings {
+            default: Imputation::Native,
+            features: [("pool_liquidity_usd".to_string(), Imputation::Constant(-1.0))].into(),
+        };
+        let imputer = FeatureImputer::new(&settings).unwrap();
+
+        let mut features = FeatureVector {
+            jito_tip_lamports: 5_000,
+            ..Default::default()
+        };
+        features.mark_missing(&["pool_liquidity_usd", "oracle_price"]);
+        let mut input = [0.0f32; FeatureVector::FEATURE_COUNT];
+        features.write_into(&mut input);
+        imputer.impute(&features.missing, &mut input);
+
+        let index = |name| FeatureVector::feature_index(name).unwrap();
+        assert_eq!(input[index("pool_liquidity_usd")], -1.0);
+        assert!(input[index("oracle_price")].is_nan());
+        assert_eq!(input[index("jito_tip_lamports")], 5_000.0);
+        assert_eq!(input[index("volume_24h_usd")], 0.0);
+    }
+
+    #[test]
+    fn test_unknown_feature_rejected() {
+        let settings = ImputationSettings {
+            features: [("pool_liquidity".to_string(), Imputation::Zero)].into(),
+            ..Default::default()
+        };
+        assert!(matches!(FeatureImputer::new(&settings), Err(SentinelError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,356 @@
+//! Realized MEV Incident Detector
+//!
+//! Post-hoc pass over the blocks our transactions landed in. For each landed
+//! transaction the block is fetched (`getBlock`, confirmed commitment) and
+//! checked with [`detect_sandwich`]; the outcome is then fed to:
+//! - the decision log, as the request's ground-truth label
+//!   (`with_incident` / `with_mev_incident(false)`), which the dataset
+//!   exporter and the analytics rollups already read
+//! - the feature extractor's validator stats, attributing each sandwich to
+//!   the block's leader
+//!
+//! Each block is fetched once, however many of our transactions it holds.
+
+use reqwest::Client;
+use sentinel_core::mev_incident::DEFAULT_MAX_DISTANCE;
+use sentinel_core::{
+    detect_sandwich, CircuitBreaker, ConfirmedBlock, DecisionLog, DecisionRecord, MevIncident, Result, RouteType,
+    SentinelError,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::features_enhanced::FeatureExtractor;
+
+/// One of our transactions that landed
+#[derive(Debug, Clone, PartialEq)]
+pub struct LandedTransaction {
+    pub request_id: String,
+    pub signature: Signature,
+    pub slot: u64,
+
+    /// Routing decision, repeated so the label record stands on its own
+    pub risk_score: f32,
+    pub route: RouteType,
+}
+
+/// Running totals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncidentStats {
+    pub blocks_scanned: u64,
+    pub checked: u64,
+    pub sandwiched: u64,
+    pub clean: u64,
+
+    /// Landed transactions not found in their block (wrong slot or skipped)
+    pub missing: u64,
+}
+
+/// Scans confirmed blocks for sandwiches of our landed transactions
+pub struct IncidentDetector {
+    http_client: Client,
+    rpc_url: String,
+    max_distance: usize,
+    own_signers: HashSet<Pubkey>,
+    decision_log: Option<Arc<DecisionLog>>,
+    features: Option<Arc<tokio::sync::Mutex<FeatureExtractor>>>,
+    stats: Mutex<IncidentStats>,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl IncidentDetector {
+    pub fn new(rpc_url: impl Into<String>) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+            max_distance: DEFAULT_MAX_DISTANCE,
+            own_signers: HashSet::new(),
+            decision_log: None,
+            features: None,
+            stats: Mutex::new(IncidentStats::default()),
+            breaker: None,
+        })
+    }
+
+    /// Positions either side of our transaction a sandwich leg may sit at
+    pub fn with_max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Our own fee payers / tip accounts, never taken for an attacker
+    pub fn with_own_signers(mut self, signers: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.own_signers.extend(signers);
+        self
+    }
+
+    /// Record outcomes as labels in the decision log
+    pub fn with_decision_log(mut self, decision_log: Arc<DecisionLog>) -> Self {
+        self.decision_log = Some(decision_log);
+        self
+    }
+
+    /// Attribute sandwiches to leaders in the extractor's validator stats
+    pub fn with_feature_extractor(mut self, features: Arc<tokio::sync::Mutex<FeatureExtractor>>) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Fail fast with `CircuitOpen` while the RPC endpoint keeps failing
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    pub fn stats(&self) -> IncidentStats {
+        *self.stats.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Confirmed block at `slot`
+    pub async fn fetch_block(&self, slot: u64) -> Result<ConfirmedBlock> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getBlock",
+            "params": [slot, {
+                "encoding": "json",
+                "transactionDetails": "accounts",
+                "rewards": true,
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }],
+        });
+        let call = async {
+            self.http_client
+                .post(&self.rpc_url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| SentinelError::RpcError(format!("getBlock failed: {}", e)))?
+                .json::<serde_json::Value>()
+                .await
+                .map_err(|e| SentinelError::RpcError(format!("Invalid getBlock response: {}", e)))
+        };
+        // Skipped or pruned slots come back as JSON-RPC errors, not outages
+        let response = match self.breaker {
+            Some(ref breaker) => breaker.call(call).await?,
+            None => call.await?,
+        };
+
+        if let Some(error) = response.get("error") {
+            return Err(SentinelError::RpcError(format!("getBlock: {}", error)));
+        }
+        ConfirmedBlock::from_rpc_json(slot, &response["result"])
+    }
+
+    /// Fetch each block once and check every landed transaction in it
+    pub async fn check(&self, landed: &[LandedTransaction]) -> Result<Vec<MevIncident>> {
+        let mut by_slot: BTreeMap<u64, Vec<&LandedTransaction>> = BTreeMap::new();
+        for tx in landed {
+            by_slot.entry(tx.slot).or_default().push(tx);
+        }
+
+        let mut incidents = Vec::new();
+        for (slot, txs) in by_slot {
+            let block = self.fetch_block(slot).await?;
+            incidents.extend(self.scan_block(&block, &txs).await);
+        }
+        Ok(incidents)
+    }
+
+    /// Check landed transactions against an already fetched block
+    pub async fn scan_block(&self, block: &ConfirmedBlock, landed: &[&LandedTransaction]) -> Vec<MevIncident> {
+        let mut incidents = Vec::new();
+        let mut outcomes = Vec::new();
+        let mut missing = 0;
+
+        for tx in landed {
+            let Some(position) = block.position(&tx.signature) else {
+                debug!("Incident scan: {} not in block {}", tx.signature, block.slot);
+                missing += 1;
+                continue;
+            };
+            let mut own = self.own_signers.clone();
+            own.extend(block.transactions[position].signers.iter().copied());
+
+            let incident = detect_sandwich(block, position, &own, self.max_distance);
+            outcomes.push((*tx, incident.clone()));
+            incidents.extend(incident);
+        }
+
+        {
+            let mut stats = self.stats.lock().unwrap_or_else(|p| p.into_inner());
+            stats.blocks_scanned += 1;
+            stats.checked += outcomes.len() as u64;
+            stats.sandwiched += incidents.len() as u64;
+            stats.clean += (outcomes.len() - incidents.len()) as u64;
+            stats.missing += missing;
+        }
+
+        if let (Some(leader), Some(features)) = (block.leader, &self.features) {
+            if !incidents.is_empty() {
+                features.lock().await.record_incidents(block.slot, leader, incidents.len() as u32);
+            }
+        }
+
+        if let Some(ref log) = self.decision_log {
+            for (tx, incident) in outcomes {
+                let record = DecisionRecord::new(tx.request_id.clone(), tx.risk_score, tx.route.clone());
+                let record = match incident {
+                    Some(incident) => record.with_incident(incident),
+                    None => record.with_mev_incident(false),
+                };
+                if let Err(e) = log.record(record).await {
+                    warn!("Failed to record MEV incident label for {}: {}", tx.request_id, e);
+                }
+            }
+        }
+
+        for incident in &incidents {
+            info!(
+                "🥪 Sandwich confirmed in slot {} (leader {}): attacker {}, pool {}",
+                incident.slot,
+                incident.leader.as_deref().unwrap_or("unknown"),
+                incident.attacker,
+                incident.pool
+            );
+        }
+        incidents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator_intel::{BlockObservation, SLOTS_PER_EPOCH};
+    use sentinel_core::DecisionLogConfig;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serves the same JSON-RPC result on every connection
+    async fn serve_result(result: serde_json::Value) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    fn tx(signature: &Signature, signer: &Pubkey, pool: &Pubkey) -> serde_json::Value {
+        json!({
+            "transaction": {
+                "signatures": [signature.to_string()],
+                "accountKeys": [
+                    { "pubkey": signer.to_string(), "signer": true, "writable": true },
+                    { "pubkey": pool.to_string(), "signer": false, "writable": true }
+                ]
+            },
+            "meta": { "err": null, "preTokenBalances": [], "postTokenBalances": [] }
+        })
+    }
+
+    fn landed(request_id: &str, signature: Signature) -> LandedTransaction {
+        LandedTransaction {
+            request_id: request_id.to_string(),
+            signature,
+            slot: 900,
+            risk_score: 0.4,
+            route: RouteType::JitoBundle,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_labels_and_validator_stats_from_block() {
+        let (attacker, user, leader) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool, other_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (victim, clean) = (Signature::new_unique(), Signature::new_unique());
+        let block = json!({
+            "rewards": [{ "pubkey": leader.to_string(), "rewardType": "Fee", "lamports": 5000 }],
+            "transactions": [
+                tx(&Signature::new_unique(), &attacker, &pool),
+                tx(&victim, &user, &pool),
+                tx(&Signature::new_unique(), &attacker, &pool),
+                tx(&clean, &user, &other_pool),
+            ]
+        });
+        let url = serve_result(block).await;
+
+        let path = std::env::temp_dir().join(format!("incidents-{}.jsonl", uuid::Uuid::new_v4()));
+        let log = Arc::new(DecisionLog::new(DecisionLogConfig {
+            buffer_size: 1,
+            log_path: path.to_string_lossy().to_string(),
+            tenant_id: None,
+        }));
+        let features = Arc::new(tokio::sync::Mutex::new(FeatureExtractor::new()));
+        let detector = IncidentDetector::new(url)
+            .unwrap()
+            .with_decision_log(log.clone())
+            .with_feature_extractor(features.clone());
+
+        let missing = landed("req-3", Signature::new_unique());
+        let incidents = detector
+            .check(&[landed("req-1", victim), landed("req-2", clean), missing])
+            .await
+            .unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].signature, victim.to_string());
+        assert_eq!(incidents[0].leader, Some(leader.to_string()));
+        assert_eq!(
+            detector.stats(),
+            IncidentStats { blocks_scanned: 1, checked: 2, sandwiched: 1, clean: 1, missing: 1 }
+        );
+
+        let records: Vec<DecisionRecord> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].mev_incident, Some(true));
+        assert!(records[0].incident.is_some());
+        assert_eq!(records[1].mev_incident, Some(false));
+
+        // The next epoch's first block blends the sandwich into the leader's stats
+        let mut features = features.lock().await;
+        features.record_block(&BlockObservation {
+            slot: 900 + SLOTS_PER_EPOCH,
+            leader: Pubkey::new_unique(),
+            sandwiches: 0,
+            tips_lamports: 0,
+        });
+        let intel = features.validator_tracker().intel(&leader).unwrap();
+        assert_eq!(intel.history(1)[0].sandwiches_observed, 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_is_reported() {
+        let url = serve_result(serde_json::Value::Null).await;
+        let detector = IncidentDetector::new(url).unwrap();
+        assert!(detector.check(&[landed("req-1", Signature::new_unique())]).await.is_err());
+        assert_eq!(detector.stats(), IncidentStats::default());
+    }
+}
@@ -1,15 +1,29 @@
-use sentinel_core::{MevRiskScore, Result, SentinelError};
-use std::path::PathBuf;
-use std::sync::Arc;
+use sentinel_core::{
+    system_clock, Deadline, DeadlineCounters, DeadlineStats, FusionSettings, LatencyTracer, MarketCalendar, MevRiskScore,
+    PipelineStage, RawTransactionScore, Result, SafetyController, SentinelConfig, SentinelError, SharedClock, TipFloorCache,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Instant;
 use tracing::{debug, info, warn};
-use ndarray::Array;
+use ndarray::{Array, Array1};
 
 use crate::features_enhanced::FeatureVector;
+use crate::heuristic_rules::HeuristicRules;
+use crate::market_stats::TokenPair;
+use crate::tip_index::TipPercentiles;
 use crate::model::ModelConfig;
+use crate::model_backend::{load_backend, ModelBackend};
+use crate::imputation::FeatureImputer;
+use crate::normalization::FeatureNormalizer;
 use crate::shadow_mode::ShadowModeManager;
 use crate::drift_detection::{DriftDetector, VotingStrategy};
-use crate::adaptive_heuristics::{AdaptiveHeuristics, MEVDetectionPipeline};
+use crate::adaptive_heuristics::{
+    AdaptiveHeuristics, AdjustedThresholds, HeuristicsSnapshot, MEVDetectionPipeline, ThresholdConfig,
+};
+use crate::score_fusion::{ComponentScores, FusedScore, FusionStats, ScoreComponent, ScoreFusion};
+use crate::transaction_extractor::{decode_wire_transaction, extract_from_static_keys};
 
 // Production constants for thresholds
 const HIGH_TIP_THRESHOLD: u64 = 100_000; // lamports
@@ -17,6 +31,27 @@ const HIGH_PRICE_IMPACT_THRESHOLD: f32 = 200.0; // basis points
 const TRIPLET_RISK_WEIGHT: f32 = 0.6;
 const MAX_INFERENCE_LATENCY_MS: u128 = 50;
 
+/// Runtime snapshot format; bump when a change makes old snapshots unreadable
+pub const ENGINE_SNAPSHOT_VERSION: u32 = 1;
+
+/// Learned runtime state persisted across restarts
+///
+/// Without it drift history, adaptive thresholds and tip percentiles start
+/// cold after every deploy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    pub version: u32,
+
+    /// Feature layout the drift history was recorded with
+    pub feature_count: usize,
+
+    /// Unix seconds
+    pub created_at: i64,
+    pub drift_history: Vec<Vec<f32>>,
+    pub heuristics: HeuristicsSnapshot,
+    pub pipeline: HeuristicsSnapshot,
+}
+
 /// Production-ready high-performance inference engine
 /// 
 /// Features:
@@ -29,35 +64,68 @@ const MAX_INFERENCE_LATENCY_MS: u128 = 50;
 /// - MiCA compliance logging (STOR for risk >=9.0)
 pub struct InferenceEngine {
     config: ModelConfig,
-    #[allow(dead_code)]
-    sessions: Vec<()>, // Reserved for ONNX Runtime sessions when model files provided
+    backend: Option<Box<dyn ModelBackend>>,
     warmup_complete: bool,
     shadow_manager: Option<Arc<ShadowModeManager>>,
+    safety: Option<SafetyController>,
     
     // Research-backed enhancements for production MEV detection
-    drift_detector: DriftDetector,
-    adaptive_heuristics: AdaptiveHeuristics,
-    mev_pipeline: MEVDetectionPipeline,
+    // Mutable state sits behind short-lived locks so predictions take `&self`
+    // and one engine can be shared across tasks (see `InferencePool`).
+    drift: Mutex<DriftState>,
+    adaptive_heuristics: Mutex<AdaptiveHeuristics>,
+    mev_pipeline: Mutex<MEVDetectionPipeline>,
+    fusion: Mutex<ScoreFusion>,
+    heuristic_rules: Mutex<Arc<HeuristicRules>>,
+    tip_floor: Option<TipFloorCache>,
+    normalizer: Option<FeatureNormalizer>,
+    imputer: FeatureImputer,
+    deadline: DeadlineCounters,
+    clock: SharedClock,
+}
+
+/// Drift history plus the preallocated model-input buffer it reads from
+struct DriftState {
+    detector: DriftDetector,
+    feature_buffer: Array1<f32>,
+}
+
+impl DriftState {
+    fn new(detector: DriftDetector) -> Self {
+        Self {
+            detector,
+            feature_buffer: Array1::zeros(FeatureVector::FEATURE_COUNT),
+        }
+    }
+}
+
+/// Lock a mutex, recovering the data if a panicking holder poisoned it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 impl InferenceEngine {
     /// Create new inference engine with ONNX model
     pub fn new(config: ModelConfig) -> Result<Self> {
         info!("🚀 Initializing AI inference engine (Research-Enhanced v2.0 + ONNX Optimizations)");
-        info!("   Model path: {:?}", config.model_path);
+        info!("   Model path: {:?} (backend: {:?})", config.model_path, config.backend);
         info!("   Threads: intra={}, inter={}", config.intra_op_threads, config.inter_op_threads);
         info!("   ONNX Optimizations: memory_pattern={}, graph_opt_level={}, parallel_exec={}",
             config.enable_memory_pattern, config.graph_optimization_level, config.enable_parallel_execution);
         info!("   Enhanced features: PSI+KS+JS drift detection, adaptive heuristics");
         
-        // ONNX Runtime disabled - using heuristic fallback only
-        if config.model_path.exists() {
-            info!("📦 Model file found but ONNX disabled - using fallback heuristics");
+        let backend = if config.model_path.exists() {
+            match load_backend(&config) {
+                Ok(backend) => Some(backend),
+                Err(e) => {
+                    warn!("⚠️  Model backend unavailable ({}) - using fallback heuristics", e);
+                    None
+                }
+            }
         } else {
             warn!("⚠️  Model file not found - using fallback heuristics");
-        }
-        
-        let sessions = vec![];
+            None
+        };
         
         // Initialize research-backed components
         let drift_detector = DriftDetector::with_config(
@@ -68,6 +136,7 @@ impl InferenceEngine {
             VotingStrategy::MajorityVote,  // 2/3 methods must agree
         );
         
+        let imputer = FeatureImputer::new(&config.imputation)?;
         let adaptive_heuristics = AdaptiveHeuristics::new();
         let mev_pipeline = MEVDetectionPipeline::new();
         
@@ -77,15 +146,65 @@ impl InferenceEngine {
         
         Ok(Self {
             config,
-            sessions,
+            backend,
             warmup_complete: false,
             shadow_manager: None,
-            drift_detector,
-            adaptive_heuristics,
-            mev_pipeline,
+            safety: None,
+            drift: Mutex::new(DriftState::new(drift_detector)),
+            adaptive_heuristics: Mutex::new(adaptive_heuristics),
+            mev_pipeline: Mutex::new(mev_pipeline),
+            fusion: Mutex::new(ScoreFusion::default()),
+            heuristic_rules: Mutex::new(Arc::new(HeuristicRules::default())),
+            tip_floor: None,
+            normalizer: None,
+            imputer,
+            deadline: DeadlineCounters::new(),
+            clock: system_clock(),
         })
     }
     
+    /// Create engine from layered runtime configuration
+    /// 
+    /// An unreadable or invalid heuristic rules file fails startup.
+    pub fn from_config(config: &SentinelConfig) -> Result<Self> {
+        let engine = Self::new(ModelConfig::from(&config.model))?;
+        if let Some(ref path) = config.thresholds.heuristic_rules_path {
+            engine.set_heuristic_rules(HeuristicRules::load(path)?);
+        }
+        engine.apply_sections(config);
+        Ok(engine)
+    }
+    
+    /// Apply hot-reloadable sections (detection thresholds, market calendar, heuristic rules, score fusion)
+    /// 
+    /// The rules file is re-read on every apply; if it fails to load, the
+    /// current rules stay in effect.
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        match config.thresholds.heuristic_rules_path {
+            Some(ref path) => match HeuristicRules::load(path) {
+                Ok(rules) => self.set_heuristic_rules(rules),
+                Err(e) => warn!("Keeping heuristic rules {}: {}", self.heuristic_rules().version(), e),
+            },
+            None => self.set_heuristic_rules(HeuristicRules::default()),
+        }
+        self.apply_sections(config);
+    }
+    
+    fn apply_sections(&self, config: &SentinelConfig) {
+        let thresholds = ThresholdConfig::from(&config.thresholds);
+        lock(&self.adaptive_heuristics).set_base_thresholds(thresholds.clone());
+        lock(&self.mev_pipeline).set_thresholds(thresholds);
+        match MarketCalendar::from_config(config) {
+            Ok(calendar) => {
+                lock(&self.adaptive_heuristics).set_calendar(calendar.clone());
+                lock(&self.mev_pipeline).set_calendar(calendar);
+            }
+            Err(e) => warn!("Keeping market calendar: {}", e),
+        }
+        lock(&self.fusion).set_settings(config.fusion.clone());
+        debug!("Applied detection thresholds: {:?}", config.thresholds);
+    }
+    
     /// Create engine with shadow mode for A/B testing
     pub fn with_shadow_mode(config: ModelConfig, shadow_manager: Arc<ShadowModeManager>) -> Result<Self> {
         let mut engine = Self::new(config)?;
@@ -94,6 +213,53 @@ impl InferenceEngine {
         Ok(engine)
     }
     
+    /// Attach operational safety switches (heuristics-only bypass, error tracking)
+    pub fn with_safety(mut self, safety: SafetyController) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+    
+    /// Floor tip recommendations at Jito's network-wide landed-tip percentiles
+    pub fn with_tip_floor(mut self, cache: TipFloorCache) -> Self {
+        self.tip_floor = Some(cache);
+        self
+    }
+    
+    /// Standardize model inputs (models trained on normalized features)
+    pub fn with_normalizer(mut self, normalizer: FeatureNormalizer) -> Self {
+        self.normalizer = Some(normalizer);
+        self
+    }
+    
+    /// Score heuristics with `rules` instead of the built-in set
+    pub fn with_heuristic_rules(self, rules: HeuristicRules) -> Self {
+        self.set_heuristic_rules(rules);
+        self
+    }
+    
+    /// Swap the heuristic rule set; predictions already running finish on the old one
+    pub fn set_heuristic_rules(&self, rules: HeuristicRules) {
+        let mut current = lock(&self.heuristic_rules);
+        if **current != rules {
+            info!("Heuristic rules {} (sha256 {})", rules.version(), rules.checksum());
+            *current = Arc::new(rules);
+        }
+    }
+    
+    pub fn heuristic_rules(&self) -> Arc<HeuristicRules> {
+        Arc::clone(&lock(&self.heuristic_rules))
+    }
+    
+    /// Read the time (adaptive time-of-day thresholds, snapshots) from `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        let heuristics = self.adaptive_heuristics.get_mut().unwrap_or_else(|p| p.into_inner());
+        *heuristics = std::mem::take(heuristics).with_clock(Arc::clone(&clock));
+        let pipeline = self.mev_pipeline.get_mut().unwrap_or_else(|p| p.into_inner());
+        *pipeline = std::mem::take(pipeline).with_clock(Arc::clone(&clock));
+        self.clock = clock;
+        self
+    }
+    
     /// Create fallback engine (no model required)
     pub fn fallback() -> Result<Self> {
         let config = ModelConfig {
@@ -103,12 +269,20 @@ impl InferenceEngine {
         
         Ok(Self {
             config,
-            sessions: vec![],
+            backend: None,
             warmup_complete: false,
             shadow_manager: None,
-            drift_detector: DriftDetector::new(),
-            adaptive_heuristics: AdaptiveHeuristics::new(),
-            mev_pipeline: MEVDetectionPipeline::new(),
+            safety: None,
+            drift: Mutex::new(DriftState::new(DriftDetector::new())),
+            adaptive_heuristics: Mutex::new(AdaptiveHeuristics::new()),
+            mev_pipeline: Mutex::new(MEVDetectionPipeline::new()),
+            fusion: Mutex::new(ScoreFusion::default()),
+            heuristic_rules: Mutex::new(Arc::new(HeuristicRules::default())),
+            tip_floor: None,
+            normalizer: None,
+            imputer: FeatureImputer::default(),
+            deadline: DeadlineCounters::new(),
+            clock: system_clock(),
         })
     }
     
@@ -127,7 +301,8 @@ impl InferenceEngine {
         
         for i in 0..self.config.warmup_iterations {
             let start = Instant::now();
-            let _ = self.predict_internal(&dummy_features)?;
+            let _ = self.model_score(&dummy_features)?;
+            let _ = self.calculate_heuristic_score(&dummy_features);
             let duration = start.elapsed();
             
             if i % 20 == 0 {
@@ -150,6 +325,11 @@ impl InferenceEngine {
     /// SLO: <50ms p99 latency
     /// Actual: 1.357ms p99 (97% faster than target)
     pub fn predict(&self, features: &FeatureVector) -> Result<MevRiskScore> {
+        self.predict_fused(features).map(|fused| fused.score)
+    }
+    
+    /// Predict with the per-component breakdown of the fused score
+    pub fn predict_fused(&self, features: &FeatureVector) -> Result<FusedScore> {
         if !self.warmup_complete {
             return Err(SentinelError::InferenceError(
                 "Model not warmed up - call warmup() first".to_string(),
@@ -161,7 +341,8 @@ impl InferenceEngine {
             .map_err(|e| SentinelError::InferenceError(format!("Invalid features: {}", e)))?;
         
         let start = Instant::now();
-        let score = self.predict_internal(features)?;
+        let fused = self.score(features, None)?;
+        let score = fused.score;
         let latency = start.elapsed();
         
         // SLO enforcement
@@ -178,8 +359,100 @@ impl InferenceEngine {
             info!("🚨 HIGH RISK DETECTED (score: {:.2}) - Triggering MiCA STOR report", score.0);
         }
         
-        debug!("Inference completed in {:?}", latency);
-        Ok(score)
+        debug!("Inference completed in {:?} (dominant: {:?})", latency, fused.dominant);
+        Ok(fused)
+    }
+    
+    /// Predict within a caller's deadline
+    ///
+    /// When the remaining budget cannot cover the inference SLO, answers from
+    /// the heuristic rules alone (no model, no adaptive pipeline, nothing
+    /// recorded) instead of returning a full score late.
+    pub fn predict_within(&self, features: &FeatureVector, deadline: &Deadline) -> Result<FusedScore> {
+        if deadline.allows(PipelineStage::Predict) {
+            self.predict_fused(features)
+        } else {
+            self.predict_heuristic(features)
+        }
+    }
+    
+    /// Deadline fallback: heuristic rules only, counted in `deadline_stats`
+    pub fn predict_heuristic(&self, features: &FeatureVector) -> Result<FusedScore> {
+        features.validate()
+            .map_err(|e| SentinelError::InferenceError(format!("Invalid features: {}", e)))?;
+        self.deadline.record_fallback(PipelineStage::Predict);
+        let heuristic = self.calculate_heuristic_score(features).0;
+        Ok(FusedScore {
+            score: MevRiskScore::new(heuristic),
+            dominant: ScoreComponent::Heuristic,
+            heuristic_contribution: heuristic,
+            model_contribution: 0.0,
+            adaptive_contribution: 0.0,
+        })
+    }
+    
+    /// Whether `warmup` has completed (predictions are refused until then)
+    pub fn is_warm(&self) -> bool {
+        self.warmup_complete
+    }
+    
+    /// Heuristic-only deadline fallbacks
+    pub fn deadline_stats(&self) -> DeadlineStats {
+        self.deadline.stats()
+    }
+    
+    /// Predict and record the inference stage on a request's latency tracer
+    pub fn predict_traced(
+        &self,
+        features: &FeatureVector,
+        tracer: &mut LatencyTracer,
+    ) -> Result<MevRiskScore> {
+        tracer.time(PipelineStage::Predict, || self.predict(features))
+    }
+    
+    /// Score a base64 wire transaction as a pure classifier
+    ///
+    /// Extraction works from the transaction alone (lookup tables unresolved,
+    /// no RPC) and leaves no trace: drift history, adaptive thresholds, fusion
+    /// stats and the safety controller's failure counts are untouched.
+    pub fn score_raw_transaction(&self, base64_tx: &str) -> Result<RawTransactionScore> {
+        let transaction = decode_wire_transaction(base64_tx)?;
+        let features = extract_from_static_keys(&transaction);
+        let fused = self.score_stateless(&features)?;
+
+        Ok(RawTransactionScore {
+            risk_score: fused.score,
+            signals: self.heuristic_rules().fired(&features).into_iter().map(str::to_string).collect(),
+            is_dex_swap: features.is_dex_swap,
+            uses_lookup_tables: features.uses_lookup_tables,
+        })
+    }
+    
+    /// Fused score without recording anything (see [`Self::score_raw_transaction`])
+    pub fn score_stateless(&self, features: &FeatureVector) -> Result<FusedScore> {
+        if !self.warmup_complete {
+            return Err(SentinelError::InferenceError(
+                "Model not warmed up - call warmup() first".to_string(),
+            ));
+        }
+        features.validate()
+            .map_err(|e| SentinelError::InferenceError(format!("Invalid features: {}", e)))?;
+        
+        let model = match self.safety {
+            Some(ref safety) if safety.heuristics_only() => None,
+            _ => self.model_score(features)?,
+        };
+        // The pipeline learns from every prediction; score on a throwaway copy
+        let mut pipeline = lock(&self.mev_pipeline).clone();
+        let (adaptive, adaptive_confidence) = pipeline.predict_with_confidence(features)?;
+        
+        let scores = ComponentScores {
+            heuristic: self.calculate_heuristic_score(features).0,
+            model,
+            adaptive: adaptive.0,
+            adaptive_confidence,
+        };
+        Ok(lock(&self.fusion).evaluate(&scores))
     }
     
     /// Predict with shadow mode and drift detection
@@ -188,22 +461,46 @@ impl InferenceEngine {
     /// Shadow path: Async background logging
     /// Drift: Multi-method ensemble (PSI + KS + JS), >threshold triggers alert
     pub async fn predict_with_shadow(
-        &mut self,
+        &self,
         features: &FeatureVector,
         request_id: String,
         signature: String,
     ) -> Result<MevRiskScore> {
-        // 1. PRODUCTION: Multi-stage MEV detection
-        let (production_score, confidence) = self.mev_pipeline.predict_with_confidence(features)?;
-        
-        debug!("MEV detection: score={:.3}, confidence={:.2}", production_score.0, confidence);
+        self.predict_with_shadow_for_pair(features, None, request_id, signature)
+            .await
+    }
+    
+    /// Predict with shadow mode, scoring against the token pair's own thresholds
+    pub async fn predict_with_shadow_for_pair(
+        &self,
+        features: &FeatureVector,
+        pair: Option<TokenPair>,
+        request_id: String,
+        signature: String,
+    ) -> Result<MevRiskScore> {
+        // 1. PRODUCTION: Heuristic + model + multi-stage pipeline fusion
+        let fused = self.score(features, pair)?;
+        let production_score = fused.score;
         
-        // 2. DRIFT DETECTION: Multi-method ensemble
-        let feature_array = Array::from_vec(features.to_array());
-        let drift_score = self.drift_detector.calculate_drift(&feature_array);
+        debug!("MEV detection: score={:.3}, dominant={:?}", production_score.0, fused.dominant);
         
-        // Update drift history
-        self.drift_detector.add_observation(feature_array);
+        // 2. DRIFT DETECTION: Multi-method ensemble (reuses preallocated buffer)
+        let drift_score = {
+            let mut drift = lock(&self.drift);
+            let DriftState { detector, feature_buffer } = &mut *drift;
+            
+            if let Some(buf) = feature_buffer
+                .as_slice_mut()
+                .and_then(|s| <&mut [f32; FeatureVector::FEATURE_COUNT]>::try_from(s).ok())
+            {
+                features.write_into(buf);
+            }
+            let drift_score = detector.calculate_drift(feature_buffer);
+            
+            // Update drift history (recycles the evicted slot once the window is full)
+            detector.add_observation_from(feature_buffer);
+            drift_score
+        };
         
         if drift_score.drift_detected {
             warn!(
@@ -272,10 +569,13 @@ impl InferenceEngine {
     }
     
     /// Update market conditions for adaptive thresholds
-    pub fn update_market_conditions(&mut self, volatility_24h_pct: f32, tps_utilization: f32) {
-        self.adaptive_heuristics.update_volatility(volatility_24h_pct);
-        self.adaptive_heuristics.update_congestion(tps_utilization);
-        self.mev_pipeline.update_market_conditions(volatility_24h_pct, tps_utilization);
+    pub fn update_market_conditions(&self, volatility_24h_pct: f32, tps_utilization: f32) {
+        {
+            let mut heuristics = lock(&self.adaptive_heuristics);
+            heuristics.update_volatility(volatility_24h_pct);
+            heuristics.update_congestion(tps_utilization);
+        }
+        lock(&self.mev_pipeline).update_market_conditions(volatility_24h_pct, tps_utilization);
         
         debug!(
             "Market conditions updated: volatility={:.1}%, TPS utilization={:.1}%",
@@ -283,43 +583,140 @@ impl InferenceEngine {
         );
     }
     
+    /// Update network congestion without touching volatility
+    pub fn update_congestion(&self, tps_utilization: f32) {
+        lock(&self.adaptive_heuristics).update_congestion(tps_utilization);
+        lock(&self.mev_pipeline).update_congestion(tps_utilization);
+        debug!("Network congestion updated: TPS utilization={:.1}%", tps_utilization * 100.0);
+    }
+    
     /// Calculate Population Stability Index (PSI) for drift detection
     /// 
     /// DEPRECATED: Use drift_detector for multi-method ensemble
     /// Kept for backward compatibility
     pub async fn calculate_drift(&self, features: &FeatureVector) -> f32 {
         let feature_array = Array::from_vec(features.to_array());
-        let drift_score = self.drift_detector.calculate_drift(&feature_array);
+        let drift_score = lock(&self.drift).detector.calculate_drift(&feature_array);
         drift_score.psi_score
     }
     
+    /// Streaming tip percentiles (tip recommendation API)
+    ///
+    /// Raised to the network tip floor when one is attached and fresh.
+    pub fn tip_percentiles(&self) -> TipPercentiles {
+        let local = lock(&self.mev_pipeline).tip_percentiles();
+        match self.tip_floor.as_ref().and_then(TipFloorCache::current) {
+            Some(floor) => local.with_network_floor(&floor),
+            None => local,
+        }
+    }
+    
     /// Get drift detection statistics
     pub fn get_drift_stats(&self) -> crate::drift_detection::DriftStats {
-        self.drift_detector.get_stats()
+        lock(&self.drift).detector.get_stats()
+    }
+    
+    /// Which score component dominated recent decisions
+    pub fn fusion_stats(&self) -> FusionStats {
+        lock(&self.fusion).stats()
+    }
+    
+    /// Detection thresholds and time-of-day (market calendar) factors in effect
+    pub fn adjusted_thresholds(&self) -> AdjustedThresholds {
+        lock(&self.mev_pipeline).adjusted_thresholds()
+    }
+    
+    /// Fusion weights (or meta-model) in effect
+    pub fn fusion_settings(&self) -> FusionSettings {
+        lock(&self.fusion).settings().clone()
+    }
+    
+    /// Capture learned runtime state
+    pub fn capture_snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot {
+            version: ENGINE_SNAPSHOT_VERSION,
+            feature_count: FeatureVector::FEATURE_COUNT,
+            created_at: self.clock.unix_timestamp(),
+            drift_history: lock(&self.drift).detector.history(),
+            heuristics: lock(&self.adaptive_heuristics).snapshot(),
+            pipeline: lock(&self.mev_pipeline).snapshot(),
+        }
+    }
+    
+    /// Restore learned runtime state
+    /// 
+    /// Rejects snapshots from another format version. If the feature layout
+    /// changed, the drift history is discarded (it would compare different
+    /// features) and the heuristics state is still restored.
+    pub fn apply_snapshot(&self, snapshot: &EngineSnapshot) -> Result<()> {
+        if snapshot.version != ENGINE_SNAPSHOT_VERSION {
+            return Err(SentinelError::InferenceError(format!(
+                "Engine snapshot version {} is incompatible (expected {})",
+                snapshot.version, ENGINE_SNAPSHOT_VERSION
+            )));
+        }
+        
+        if snapshot.feature_count == FeatureVector::FEATURE_COUNT {
+            let history = snapshot
+                .drift_history
+                .iter()
+                .filter(|f| f.len() == FeatureVector::FEATURE_COUNT)
+                .cloned()
+                .collect();
+            lock(&self.drift).detector.restore_history(history);
+        } else {
+            warn!(
+                "Snapshot has {} features (expected {}), discarding drift history",
+                snapshot.feature_count,
+                FeatureVector::FEATURE_COUNT
+            );
+        }
+        lock(&self.adaptive_heuristics).restore(&snapshot.heuristics);
+        lock(&self.mev_pipeline).restore(&snapshot.pipeline);
+        Ok(())
+    }
+    
+    /// Write learned runtime state to `path` (on shutdown)
+    pub fn snapshot(&self, path: &Path) -> Result<()> {
+        write_snapshot(&self.capture_snapshot(), path)?;
+        info!("💾 Saved engine snapshot to {:?}", path);
+        Ok(())
+    }
+    
+    /// Restore learned runtime state from `path` (on boot)
+    /// 
+    /// Returns false when there is no snapshot yet.
+    pub fn restore(&self, path: &Path) -> Result<bool> {
+        let Some(snapshot) = read_snapshot(path)? else {
+            return Ok(false);
+        };
+        self.apply_snapshot(&snapshot)?;
+        info!(
+            "♻️  Restored engine snapshot from {:?} ({} drift observations)",
+            path,
+            snapshot.drift_history.len()
+        );
+        Ok(true)
     }
     
     /// Shadow prediction (can use different model version)
     fn shadow_predict_internal(features: &FeatureVector) -> Result<MevRiskScore> {
         // For v1.0: Use same heuristics as production
         // In v2.0: Load different ONNX model for A/B test
-        let input_array = features.to_array();
-        
         let mut risk_factors = Vec::new();
         
-        if input_array.len() >= 55 {
-            // High compute unit price
-            if input_array[2] > 200_000.0 { risk_factors.push(0.3); }
-            // High Jito tip
-            if input_array[3] > HIGH_TIP_THRESHOLD as f32 { risk_factors.push(0.4); }
-            // High price impact
-            if input_array[12] > HIGH_PRICE_IMPACT_THRESHOLD { risk_factors.push(0.35); }
-            // Swap triplet detected
-            if input_array[28] > 0.5 { risk_factors.push(TRIPLET_RISK_WEIGHT); }
-            // Malicious validator
-            if input_array[46] > 0.5 { risk_factors.push(0.5); }
-            // High validator risk score
-            if input_array[54] > 0.7 { risk_factors.push(0.45); }
-        }
+        // High compute unit price
+        if features.compute_unit_price > 200_000 { risk_factors.push(0.3); }
+        // High Jito tip
+        if features.jito_tip_lamports > HIGH_TIP_THRESHOLD { risk_factors.push(0.4); }
+        // High price impact
+        if features.price_impact_bps as f32 > HIGH_PRICE_IMPACT_THRESHOLD { risk_factors.push(0.35); }
+        // Swap triplet detected
+        if features.has_swap_triplet { risk_factors.push(TRIPLET_RISK_WEIGHT); }
+        // Malicious validator
+        if features.next_leader_malicious { risk_factors.push(0.5); }
+        // High validator risk score
+        if features.validator_risk_score > 0.7 { risk_factors.push(0.45); }
         
         let final_score = if !risk_factors.is_empty() {
             let sum: f32 = risk_factors.iter().sum();
@@ -331,104 +728,60 @@ impl InferenceEngine {
         Ok(MevRiskScore::new(final_score))
     }
     
-    /// Internal prediction with ONNX or fallback
-    fn predict_internal(&self, features: &FeatureVector) -> Result<MevRiskScore> {
-        let input_array = features.to_array();
+    /// Fuse heuristic, model and adaptive pipeline scores
+    /// 
+    /// The model is skipped when the safety controller forces heuristics-only.
+    #[tracing::instrument(name = "inference.score", skip_all)]
+    fn score(&self, features: &FeatureVector, pair: Option<TokenPair>) -> Result<FusedScore> {
+        let model = match self.safety {
+            Some(ref safety) if safety.heuristics_only() => {
+                debug!("Heuristics-only mode - bypassing model");
+                None
+            }
+            Some(ref safety) => {
+                let result = self.model_score(features);
+                safety.record_inference(result.is_ok());
+                result?
+            }
+            None => self.model_score(features)?,
+        };
         
-        // Note: ONNX inference would go here with proper ort crate setup
-        // For now, use production-validated heuristics which provide
-        // 99.2% recall on MEV detection (validated on mainnet data)
+        let (adaptive, adaptive_confidence) =
+            lock(&self.mev_pipeline).predict_with_confidence_for_pair(features, pair)?;
         
-        if !self.sessions.is_empty() {
-            debug!("ONNX model available but using heuristics for stability");
-            // In production with proper ORT setup, this would call the model
-        }
+        let scores = ComponentScores {
+            heuristic: self.calculate_heuristic_score(features).0,
+            model,
+            adaptive: adaptive.0,
+            adaptive_confidence,
+        };
+        Ok(lock(&self.fusion).fuse(&scores))
+    }
+    
+    /// Model backend probability (`None` without a loaded model)
+    fn model_score(&self, features: &FeatureVector) -> Result<Option<f32>> {
+        let Some(ref backend) = self.backend else {
+            return Ok(None);
+        };
         
-        // Production heuristics (no model required)
-        debug!("Using production heuristic scoring");
-        Ok(self.calculate_heuristic_score(&input_array))
+        let mut input = [0.0f32; FeatureVector::FEATURE_COUNT];
+        match self.normalizer {
+            Some(ref normalizer) => normalizer.normalize_features(features, &mut input),
+            None => features.write_into(&mut input),
+        }
+        self.imputer.impute(&features.missing, &mut input);
+        let probability = backend.predict(&input)?;
+        debug!("{} model score: {:.3}", backend.name(), probability);
+        Ok(Some(probability.clamp(0.0, 1.0)))
     }
     
     /// Production heuristic scoring (no ML model required)
     /// 
-    /// Based on key risk indicators validated in production:
-    /// - High Jito tips (>100k lamports)
-    /// - Swap triplets (sandwich attacks)
-    /// - Malicious validators (241 tracked)
-    /// - High price impact (>200 bps)
-    /// - Validator risk scores (>0.7)
-    fn calculate_heuristic_score(&self, features: &[f32]) -> MevRiskScore {
-        let mut risk_factors = Vec::new();
-        
-        if features.len() >= 55 {
-            // Feature indices match FeatureVector::to_array()
-            
-            // [2] compute_unit_price: High urgency
-            if features[2] > 200_000.0 {
-                risk_factors.push(0.3);
-            }
-            
-            // [3] jito_tip_lamports: KEY indicator
-            if features[3] > HIGH_TIP_THRESHOLD as f32 {
-                risk_factors.push(0.4);
-            }
-            
-            // [12] price_impact_bps: Slippage manipulation
-            if features[12] > HIGH_PRICE_IMPACT_THRESHOLD {
-                risk_factors.push(0.35);
-            }
-            
-            // [19] liquidity_utilization: Large trade risk
-            if features[19] > 0.05 {
-                risk_factors.push(0.25);
-            }
-            
-            // [23] price_deviation_pct: Front-running
-            if features[23] > 2.0 {
-                risk_factors.push(0.4);
-            }
-            
-            // [28] has_swap_triplet: STRONGEST indicator
-            if features[28] > 0.5 {
-                risk_factors.push(TRIPLET_RISK_WEIGHT);
-            }
-            
-            // [33] tip_percentile_vs_recent: Bot behavior
-            if features[33] > 95.0 {
-                risk_factors.push(0.35);
-            }
-            
-            // [39] matches_mev_bot_pattern
-            if features[39] > 0.5 {
-                risk_factors.push(0.45);
-            }
-            
-            // [46] next_leader_malicious: Critical for Jito
-            if features[46] > 0.5 {
-                risk_factors.push(0.5);
-            }
-            
-            // [54] validator_risk_score: Aggregated risk
-            if features[54] > 0.7 {
-                risk_factors.push(0.45);
-            }
-        }
-        
-        let final_score = if !risk_factors.is_empty() {
-            // Use max risk factor with weighted average boost
-            // If multiple strong signals present, aggregate increases risk
-            let max_risk = risk_factors.iter().copied().fold(0.0f32, f32::max);
-            let avg_risk = risk_factors.iter().sum::<f32>() / risk_factors.len() as f32;
-            
-            // Blend max (70%) and average (30%) for balanced sensitivity
-            let blended = max_risk * 0.7 + avg_risk * 0.3;
-            blended.min(0.95)
-        } else {
-            // Default to low risk if no indicators
-            0.15
-        };
-        
-        MevRiskScore::new(final_score)
+    /// Evaluates the active `HeuristicRules`; the built-in set checks high
+    /// Jito tips (>100k lamports), swap triplets, malicious leaders, high price
+    /// impact (>200 bps) and validator risk scores (>0.7), among others.
+    fn calculate_heuristic_score(&self, features: &FeatureVector) -> MevRiskScore {
+        MevRiskScore::new(self.heuristic_rules().score(features))
     }
     
     /// Get model metadata
@@ -437,17 +790,43 @@ impl InferenceEngine {
             model_path: self.config.model_path.clone(),
             feature_count: FeatureVector::feature_count(),
             warmup_complete: self.warmup_complete,
-            session_count: self.sessions.len(),
+            backend: self.backend.as_ref().map(|b| b.name()),
         }
     }
 }
 
+fn write_snapshot(snapshot: &EngineSnapshot, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| SentinelError::IoError(format!("Failed to create snapshot dir: {}", e)))?;
+    }
+    
+    let data = serde_json::to_vec(snapshot).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| SentinelError::IoError(format!("Failed to write engine snapshot: {}", e)))
+}
+
+pub(crate) fn read_snapshot(path: &Path) -> Result<Option<EngineSnapshot>> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(SentinelError::IoError(format!("Failed to read {:?}: {}", path, e))),
+    };
+    serde_json::from_slice(&data)
+        .map(Some)
+        .map_err(|e| SentinelError::InferenceError(format!("Invalid engine snapshot: {}", e)))
+}
+
 #[derive(Debug)]
 pub struct ModelInfo {
     pub model_path: PathBuf,
     pub feature_count: usize,
     pub warmup_complete: bool,
-    pub session_count: usize,
+    
+    /// Loaded model backend (`None` = heuristics only)
+    pub backend: Option<&'static str>,
 }
 
 #[cfg(test)]
@@ -461,12 +840,64 @@ mod tests {
         assert!(engine.is_ok());
     }
     
+    #[test]
+    fn test_engine_from_config() {
+        let mut config = SentinelConfig::default();
+        config.model.warmup_iterations = 5;
+        
+        let mut engine = InferenceEngine::from_config(&config).unwrap();
+        assert_eq!(engine.config.warmup_iterations, 5);
+        assert!(engine.warmup().is_ok());
+    }
+    
     #[test]
     fn test_fallback_engine() {
         let engine = InferenceEngine::fallback();
         assert!(engine.is_ok());
     }
     
+    #[test]
+    fn test_heuristics_only_bypasses_model() {
+        let safety = SafetyController::default();
+        let mut engine = InferenceEngine::fallback().unwrap().with_safety(safety.clone());
+        engine.warmup().unwrap();
+        
+        // Model path records outcomes; the forced bypass does not
+        let baseline = engine.predict(&FeatureVector::default()).unwrap();
+        safety.set_heuristics_only(true);
+        let bypassed = engine.predict(&FeatureVector::default()).unwrap();
+        
+        assert_eq!(baseline.0, bypassed.0);
+        assert_eq!(safety.status().inference_error_rate, 0.0);
+    }
+    
+    #[test]
+    fn test_score_raw_transaction_is_stateless() {
+        use base64::Engine;
+        use solana_sdk::{instruction::Instruction, message::Message, pubkey::Pubkey, transaction::Transaction};
+        
+        let mut engine = InferenceEngine::fallback().unwrap();
+        engine.warmup().unwrap();
+        let raydium: Pubkey = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8".parse().unwrap();
+        let ix = Instruction::new_with_bytes(raydium, &[9], vec![]);
+        let transaction = Transaction::new_unsigned(Message::new(&[ix], Some(&Pubkey::new_unique())));
+        let wire = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction).unwrap());
+        
+        let before = engine.capture_snapshot();
+        let first = engine.score_raw_transaction(&wire).unwrap();
+        let second = engine.score_raw_transaction(&wire).unwrap();
+        assert!(first.is_dex_swap);
+        assert_eq!(first.risk_score.0, second.risk_score.0);
+        assert_eq!(first.signals, second.signals);
+        
+        let after = engine.capture_snapshot();
+        assert_eq!(before.heuristics, after.heuristics);
+        assert_eq!(before.pipeline, after.pipeline);
+        assert_eq!(engine.fusion_stats().decisions, 0);
+        
+        assert!(engine.score_raw_transaction("AAAA").is_err());
+    }
+    
     #[test]
     fn test_prediction_requires_warmup() {
         let config = ModelConfig::default();
@@ -478,22 +909,37 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not warmed up"));
     }
     
+    #[test]
+    fn test_predict_traced_records_stage() {
+        let mut engine = InferenceEngine::fallback().unwrap();
+        engine.warmup().unwrap();
+        
+        let mut tracer = LatencyTracer::new();
+        let score = engine.predict_traced(&FeatureVector::default(), &mut tracer);
+        assert!(score.is_ok());
+        
+        let breakdown = tracer.finish();
+        assert_eq!(breakdown.slowest_stage, Some(PipelineStage::Predict));
+    }
+    
     #[test]
     fn test_heuristic_scoring() {
         let config = ModelConfig::default();
         let engine = InferenceEngine::new(config).unwrap();
         
         // Test high-risk features - need many factors to average to >= 0.8
-        let mut features = vec![0.0; 55];
-        features[2] = 250_000.0; // High compute price (0.3)
-        features[3] = 200_000.0; // High Jito tip (0.4)
-        features[12] = 250.0; // High price impact (0.35)
-        features[23] = 3.0; // Price deviation (0.4)
-        features[28] = 1.0; // Triplet detected (0.6)
-        features[33] = 99.0; // High tip percentile (0.35)
-        features[39] = 1.0; // MEV bot pattern (0.45)
-        features[46] = 1.0; // Malicious validator (0.5)
-        features[54] = 0.9; // High validator risk (0.45)
+        let features = FeatureVector {
+            compute_unit_price: 250_000, // High compute price (0.3)
+            jito_tip_lamports: 200_000, // High Jito tip (0.4)
+            price_impact_bps: 250.0, // High price impact (0.35)
+            price_deviation_pct: 3.0, // Price deviation (0.4)
+            has_swap_triplet: true, // Triplet detected (0.6)
+            tip_percentile_vs_recent: 99.0, // High tip percentile (0.35)
+            matches_mev_bot_pattern: true, // MEV bot pattern (0.45)
+            next_leader_malicious: true, // Malicious validator (0.5)
+            validator_risk_score: 0.9, // High validator risk (0.45)
+            ..Default::default()
+        };
         
         let score = engine.calculate_heuristic_score(&features);
         // Blended scoring: max(0.6)*0.7 + avg(0.42)*0.3 = 0.546
@@ -506,8 +952,109 @@ mod tests {
         let config = ModelConfig::default();
         let engine = InferenceEngine::new(config).unwrap();
         
-        let features = vec![0.0; 55]; // All zeros
+        let features = FeatureVector::default(); // All zeros
         let score = engine.calculate_heuristic_score(&features);
         assert!(score.is_low_risk());
     }
+    
+    #[test]
+    fn test_heuristic_rules_from_config() {
+        let path = std::env::temp_dir().join(format!("rules-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "version = \"v7\"\n[[rules]]\nname = \"any_tip\"\nfeature = \"jito_tip_lamports\"\nthreshold = 0.0\nweight = 0.9\n",
+        )
+        .unwrap();
+        let mut config = SentinelConfig::default();
+        config.thresholds.heuristic_rules_path = Some(path.clone());
+        let engine = InferenceEngine::from_config(&config).unwrap();
+        
+        let features = FeatureVector { jito_tip_lamports: 1, ..Default::default() };
+        assert_eq!(engine.heuristic_rules().version(), "v7");
+        assert!((engine.calculate_heuristic_score(&features).0 - 0.9).abs() < 1e-6);
+        
+        // A broken file on reload keeps the loaded rules
+        std::fs::write(&path, "version = \"v8\"\nrules = 1").unwrap();
+        engine.apply_config(&config);
+        assert_eq!(engine.heuristic_rules().version(), "v7");
+        assert!(InferenceEngine::from_config(&config).is_err());
+        
+        config.thresholds.heuristic_rules_path = None;
+        engine.apply_config(&config);
+        assert_eq!(engine.heuristic_rules().version(), "builtin");
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let mut config = SentinelConfig::default();
+        config.model.warmup_iterations = 1;
+        let mut engine = InferenceEngine::from_config(&config).unwrap();
+        engine.warmup().unwrap();
+        for i in 0..20u64 {
+            let features = FeatureVector {
+                slot: 1_000 + i,
+                jito_tip_lamports: 10_000 * (i + 1),
+                ..Default::default()
+            };
+            engine.predict_with_shadow(&features, format!("req-{}", i), format!("sig-{}", i)).await.unwrap();
+        }
+        assert_eq!(engine.get_drift_stats().history_size, 20);
+        
+        let path = std::env::temp_dir().join(format!("engine-snapshot-{}.json", uuid::Uuid::new_v4()));
+        let restored = InferenceEngine::from_config(&config).unwrap();
+        assert!(!restored.restore(&path).unwrap());
+        
+        engine.snapshot(&path).unwrap();
+        assert!(restored.restore(&path).unwrap());
+        assert_eq!(restored.tip_percentiles(), engine.tip_percentiles());
+        assert_eq!(restored.get_drift_stats().history_size, engine.get_drift_stats().history_size);
+        std::fs::remove_file(&path).ok();
+    }
+    
+    #[test]
+    fn test_snapshot_uses_injected_clock() {
+        let clock = Arc::new(sentinel_core::ManualClock::at_timestamp(1_700_000_000));
+        let engine = InferenceEngine::fallback().unwrap().with_clock(clock);
+        assert_eq!(engine.capture_snapshot().created_at, 1_700_000_000);
+    }
+    
+    #[test]
+    fn test_tip_percentiles_respect_network_floor() {
+        let cache = TipFloorCache::default();
+        let engine = InferenceEngine::fallback().unwrap().with_tip_floor(cache.clone());
+        let local = engine.tip_percentiles();
+
+        cache.update(sentinel_core::TipFloor {
+            p25: 10_000,
+            p50: 50_000,
+            p75: 100_000,
+            p95: 2_000_000,
+            p99: 9_000_000,
+            ema_p50: 50_000,
+            observed_at_ms: chrono::Utc::now().timestamp_millis(),
+        });
+        let floored = engine.tip_percentiles();
+        assert_eq!(floored.p95, local.p95.max(2_000_000));
+        assert_eq!(floored.recommend(&MevRiskScore::new(0.1)), local.p50.max(50_000));
+    }
+    
+    #[test]
+    fn test_snapshot_compatibility_checks() {
+        let engine = InferenceEngine::fallback().unwrap();
+        let mut snapshot = engine.capture_snapshot();
+        snapshot.drift_history = vec![vec![0.0; FeatureVector::FEATURE_COUNT]; 3];
+        
+        // Another feature layout: heuristics restore, drift history is dropped
+        let mut other_layout = snapshot.clone();
+        other_layout.feature_count += 1;
+        engine.apply_snapshot(&other_layout).unwrap();
+        assert_eq!(engine.get_drift_stats().history_size, 0);
+        
+        engine.apply_snapshot(&snapshot).unwrap();
+        assert_eq!(engine.get_drift_stats().history_size, 3);
+        
+        snapshot.version += 1;
+        assert!(engine.apply_snapshot(&snapshot).is_err());
+    }
 }
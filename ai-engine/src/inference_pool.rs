@@ -0,0 +1,349 @@
+//! Concurrent Inference Pool
+//!
+//! `InferenceEngine` keeps its drift history and adaptive heuristics behind
+//! short-lived locks, so a single engine can already be shared across tasks.
+//! Under load those locks become the contention point; the pool shards state
+//! across N independently warmed engines and round-robins requests over them.
+//!
+//! Each engine keeps its own drift window (≈1/N of the traffic), which is still
+//! a representative sample for PSI/KS/JS. Market conditions and config updates
+//! are broadcast to every engine. For the same reason one engine's learned
+//! state stands in for the pool across restarts: it is saved on shutdown and
+//! seeds every engine on boot.
+
+#[cfg(feature = "chaos")]
+use sentinel_core::{ChaosInjector, Fault};
+use sentinel_core::{
+    Deadline, DeadlineStats, HealthCheck, LatencyTracer, MevRiskScore, Result, SentinelConfig, SentinelError,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::drift_detection::DriftStats;
+use crate::features_enhanced::FeatureVector;
+use crate::inference_enhanced::{read_snapshot, InferenceEngine};
+use crate::score_fusion::FusedScore;
+use crate::model::ModelConfig;
+use crate::shadow_mode::ShadowModeManager;
+
+/// Round-robin pool of warmed inference engines
+pub struct InferencePool {
+    engines: Vec<Arc<InferenceEngine>>,
+    next: AtomicUsize,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<ChaosInjector>>,
+}
+
+impl InferencePool {
+    /// Create `size` engines from the same model config and warm them up
+    pub fn new(config: ModelConfig, size: usize) -> Result<Self> {
+        Self::build(size, || InferenceEngine::new(config.clone()))
+    }
+
+    /// Create a pool from layered runtime configuration
+    pub fn from_config(config: &SentinelConfig, size: usize) -> Result<Self> {
+        Self::build(size, || InferenceEngine::from_config(config))
+    }
+
+    /// Create a pool whose engines share one shadow-mode manager
+    pub fn with_shadow_mode(
+        config: ModelConfig,
+        shadow_manager: Arc<ShadowModeManager>,
+        size: usize,
+    ) -> Result<Self> {
+        Self::build(size, || {
+            InferenceEngine::with_shadow_mode(config.clone(), Arc::clone(&shadow_manager))
+        })
+    }
+
+    /// Wrap already warmed engines
+    pub fn from_engines(engines: Vec<InferenceEngine>) -> Result<Self> {
+        if engines.is_empty() {
+            return Err(SentinelError::InferenceError(
+                "Inference pool requires at least one engine".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            engines: engines.into_iter().map(Arc::new).collect(),
+            next: AtomicUsize::new(0),
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        })
+    }
+
+    fn build(size: usize, mut make: impl FnMut() -> Result<InferenceEngine>) -> Result<Self> {
+        if size == 0 {
+            return Err(SentinelError::InferenceError(
+                "Inference pool size must be at least 1".to_string(),
+            ));
+        }
+
+        let engines = (0..size)
+            .map(|_| {
+                let mut engine = make()?;
+                engine.warmup()?;
+                Ok(engine)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        info!("🧵 Inference pool ready with {} engines", size);
+        Self::from_engines(engines)
+    }
+
+    /// Inject slow inference before model predictions (not heuristic fallbacks)
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<ChaosInjector>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    #[cfg(feature = "chaos")]
+    fn slow_down(&self) -> Result<()> {
+        self.chaos
+            .as_ref()
+            .map_or(Ok(()), |chaos| chaos.inject_blocking(Fault::SlowInference))
+    }
+
+    #[cfg(not(feature = "chaos"))]
+    fn slow_down(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Next engine in round-robin order
+    pub fn engine(&self) -> Arc<InferenceEngine> {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.engines.len();
+        Arc::clone(&self.engines[idx])
+    }
+
+    /// Number of engines in the pool
+    pub fn len(&self) -> usize {
+        self.engines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.engines.is_empty()
+    }
+
+    /// Predict on the next engine
+    pub fn predict(&self, features: &FeatureVector) -> Result<MevRiskScore> {
+        self.slow_down()?;
+        self.engine().predict(features)
+    }
+
+    /// Predict on the next engine and record the inference stage
+    pub fn predict_traced(
+        &self,
+        features: &FeatureVector,
+        tracer: &mut LatencyTracer,
+    ) -> Result<MevRiskScore> {
+        self.slow_down()?;
+        self.engine().predict_traced(features, tracer)
+    }
+
+    /// Predict on the next engine, heuristics-only when `deadline` is too close
+    pub fn predict_within(&self, features: &FeatureVector, deadline: &Deadline) -> Result<FusedScore> {
+        self.slow_down()?;
+        self.engine().predict_within(features, deadline)
+    }
+
+    /// Heuristic-only deadline fallback on the next engine
+    pub fn predict_heuristic(&self, features: &FeatureVector) -> Result<FusedScore> {
+        self.engine().predict_heuristic(features)
+    }
+
+    /// Predict with shadow mode and drift detection on the next engine
+    pub async fn predict_with_shadow(
+        &self,
+        features: &FeatureVector,
+        request_id: String,
+        signature: String,
+    ) -> Result<MevRiskScore> {
+        #[cfg(feature = "chaos")]
+        if let Some(ref chaos) = self.chaos {
+            chaos.inject(Fault::SlowInference).await?;
+        }
+        self.engine()
+            .predict_with_shadow(features, request_id, signature)
+            .await
+    }
+
+    /// Drift history summed across engines (thresholds are shared)
+    pub fn drift_stats(&self) -> DriftStats {
+        let mut stats = self.engines[0].get_drift_stats();
+        for engine in &self.engines[1..] {
+            let other = engine.get_drift_stats();
+            stats.history_size += other.history_size;
+            stats.max_history += other.max_history;
+        }
+        stats
+    }
+
+    /// Deadline fallbacks summed across engines
+    pub fn deadline_stats(&self) -> DeadlineStats {
+        let mut stats = DeadlineStats::default();
+        for engine in &self.engines {
+            stats.merge(&engine.deadline_stats());
+        }
+        stats
+    }
+
+    /// Broadcast market conditions to every engine
+    pub fn update_market_conditions(&self, volatility_24h_pct: f32, tps_utilization: f32) {
+        for engine in &self.engines {
+            engine.update_market_conditions(volatility_24h_pct, tps_utilization);
+        }
+    }
+
+    /// Broadcast network congestion to every engine
+    pub fn update_congestion(&self, tps_utilization: f32) {
+        for engine in &self.engines {
+            engine.update_congestion(tps_utilization);
+        }
+    }
+
+    /// Broadcast hot-reloadable config to every engine
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        for engine in &self.engines {
+            engine.apply_config(config);
+        }
+    }
+
+    /// Save the first engine's learned state to `path`
+    pub fn snapshot(&self, path: &Path) -> Result<()> {
+        self.engines[0].snapshot(path)
+    }
+
+    /// Seed every engine from the snapshot at `path` (false if none exists)
+    pub fn restore(&self, path: &Path) -> Result<bool> {
+        let Some(snapshot) = read_snapshot(path)? else {
+            return Ok(false);
+        };
+        for engine in &self.engines {
+            engine.apply_snapshot(&snapshot)?;
+        }
+        info!("♻️  Restored engine snapshot from {:?} into {} engines", path, self.engines.len());
+        Ok(true)
+    }
+}
+
+/// Readiness: every engine warmed up
+impl HealthCheck for InferencePool {
+    fn name(&self) -> &str {
+        "model"
+    }
+
+    fn check(&self) -> Result<String> {
+        let warm = self.engines.iter().filter(|engine| engine.is_warm()).count();
+        if warm < self.engines.len() {
+            return Err(SentinelError::InferenceError(format!(
+                "{}/{} engines warmed up",
+                warm,
+                self.engines.len()
+            )));
+        }
+        Ok(format!("{} engines warmed up", warm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_round_robin() {
+        let pool = InferencePool::new(ModelConfig::default(), 3).unwrap();
+        assert_eq!(pool.len(), 3);
+
+        let first = pool.engine();
+        let _ = pool.engine();
+        let _ = pool.engine();
+        let fourth = pool.engine();
+        assert!(Arc::ptr_eq(&first, &fourth));
+    }
+
+    #[test]
+    fn test_pool_ready_once_engines_warmed() {
+        let pool = InferencePool::new(ModelConfig::default(), 2).unwrap();
+        assert_eq!(pool.check().unwrap(), "2 engines warmed up");
+
+        let cold = InferencePool::from_engines(vec![InferenceEngine::new(ModelConfig::default()).unwrap()]).unwrap();
+        assert!(cold.check().is_err());
+    }
+
+    #[test]
+    fn test_empty_pool_rejected() {
+        assert!(InferencePool::new(ModelConfig::default(), 0).is_err());
+        assert!(InferencePool::from_engines(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_expired_deadline_answers_from_heuristics() {
+        let pool = InferencePool::new(ModelConfig::default(), 2).unwrap();
+        let features = FeatureVector {
+            has_swap_triplet: true,
+            next_leader_malicious: true,
+            ..Default::default()
+        };
+
+        let full = pool.predict_within(&features, &Deadline::after(std::time::Duration::from_secs(1))).unwrap();
+        let late = pool.predict_within(&features, &Deadline::at(std::time::Instant::now())).unwrap();
+        assert_eq!(late.dominant, crate::score_fusion::ScoreComponent::Heuristic);
+        assert!(late.score.score() > 0.3 && late.score.confidence().is_none());
+        assert!(full.score.confidence().is_some());
+
+        // Heuristic answers leave no trace in the fusion window
+        assert_eq!(pool.engines.iter().map(|e| e.fusion_stats().decisions).sum::<usize>(), 1);
+        assert_eq!(pool.deadline_stats().predict_fallbacks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pool_restore_seeds_every_engine() {
+        let pool = InferencePool::new(ModelConfig::default(), 2).unwrap();
+        for i in 0..4 {
+            pool.predict_with_shadow(&FeatureVector::default(), format!("req-{}", i), format!("sig-{}", i))
+                .await
+                .unwrap();
+        }
+        let path = std::env::temp_dir().join(format!("pool-snapshot-{}.json", uuid::Uuid::new_v4()));
+        pool.snapshot(&path).unwrap();
+
+        let restored = InferencePool::new(ModelConfig::default(), 3).unwrap();
+        assert!(restored.restore(&path).unwrap());
+        assert_eq!(restored.drift_stats().history_size, 6);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_predictions() {
+        let pool = Arc::new(InferencePool::new(ModelConfig::default(), 2).unwrap());
+        pool.update_market_conditions(8.0, 0.5);
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let pool = Arc::clone(&pool);
+                tokio::spawn(async move {
+                    pool.predict_with_shadow(
+                        &FeatureVector::default(),
+                        format!("req-{}", i),
+                        format!("sig-{}", i),
+                    )
+                    .await
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let score = handle.await.unwrap().unwrap();
+            assert!((0.0..=1.0).contains(&score.0));
+        }
+
+        let observations: usize = (0..pool.len())
+            .map(|_| pool.engine().get_drift_stats().history_size)
+            .sum();
+        assert_eq!(observations, 16);
+        assert_eq!(pool.drift_stats().history_size, 16);
+    }
+}
@@ -0,0 +1,182 @@
+//! Feed Ingestion Pipeline
+//!
+//! Connects a transaction feed (Geyser, LaserStream, RPC subscriptions) to the
+//! extractor and on to inference consumers over bounded queues, so a slot
+//! burst sheds stale observations instead of growing memory without limit:
+//!
+//! ```text
+//! feed --inbound (drop-oldest)--> extraction worker --extracted (drop-oldest)--> inference
+//! ```
+//!
+//! Both hops carry observations only; losing the oldest under load costs some
+//! swap-history accuracy, never a user's intent (those go through the
+//! never-drop `ExecutionScheduler::admit`).
+
+use sentinel_core::{BoundedQueue, OverflowPolicy, QueueStats};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::features_enhanced::{FeatureExtractor, FeatureVector, TransactionData};
+
+/// Transaction as delivered by the feed
+#[derive(Debug, Clone)]
+pub struct FeedTransaction {
+    pub signature: String,
+    pub data: TransactionData,
+}
+
+/// Extracted features awaiting inference
+#[derive(Debug, Clone)]
+pub struct ExtractedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub features: FeatureVector,
+}
+
+/// Queue capacities
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngestSettings {
+    /// Feed transactions awaiting extraction
+    pub inbound_capacity: usize,
+
+    /// Feature vectors awaiting inference
+    pub extracted_capacity: usize,
+}
+
+impl Default for IngestSettings {
+    fn default() -> Self {
+        Self {
+            inbound_capacity: 10_000,
+            extracted_capacity: 10_000,
+        }
+    }
+}
+
+/// Depth and shed counters of both hops
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestStats {
+    pub inbound: QueueStats,
+    pub extracted: QueueStats,
+}
+
+/// Bounded feed → extraction → inference pipeline
+pub struct FeedIngestor {
+    extractor: Arc<Mutex<FeatureExtractor>>,
+    inbound: BoundedQueue<FeedTransaction>,
+    extracted: BoundedQueue<ExtractedTransaction>,
+}
+
+impl FeedIngestor {
+    pub fn new(extractor: Arc<Mutex<FeatureExtractor>>, settings: IngestSettings) -> Self {
+        Self {
+            extractor,
+            inbound: BoundedQueue::new("ingest.inbound", settings.inbound_capacity, OverflowPolicy::DropOldest),
+            extracted: BoundedQueue::new("ingest.extracted", settings.extracted_capacity, OverflowPolicy::DropOldest),
+        }
+    }
+
+    /// Where the feed pushes transactions (close it to stop the pipeline)
+    pub fn inbound(&self) -> BoundedQueue<FeedTransaction> {
+        self.inbound.clone()
+    }
+
+    /// Where inference consumers pop feature vectors (closed once inbound is drained)
+    pub fn extracted(&self) -> BoundedQueue<ExtractedTransaction> {
+        self.extracted.clone()
+    }
+
+    pub fn stats(&self) -> IngestStats {
+        IngestStats {
+            inbound: self.inbound.stats(),
+            extracted: self.extracted.stats(),
+        }
+    }
+
+    /// Run the extraction worker until `inbound` is closed and drained
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let (extractor, inbound, extracted) = (self.extractor.clone(), self.inbound.clone(), self.extracted.clone());
+        tokio::spawn(async move {
+            while let Some(transaction) = inbound.pop().await {
+                let features = extractor
+                    .lock()
+                    .await
+                    .extract_from_feed(&transaction.signature, &transaction.data)
+                    .await;
+                // Drop-oldest never refuses unless closed by a consumer
+                let _ = extracted.try_push(ExtractedTransaction {
+                    signature: transaction.signature,
+                    slot: transaction.data.slot,
+                    features,
+                });
+            }
+            extracted.close();
+
+            let (inbound, extracted) = (inbound.stats(), extracted.stats());
+            info!(
+                "Feed ingestion stopped: {} extracted, {} shed before extraction, {} shed before inference",
+                inbound.dequeued, inbound.shed, extracted.shed
+            );
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn transaction(slot: u64) -> FeedTransaction {
+        FeedTransaction {
+            signature: format!("sig-{}", slot),
+            data: TransactionData {
+                slot,
+                fee_payer: Pubkey::new_unique(),
+                compute_unit_limit: 200_000,
+                compute_unit_price: 0,
+                jito_tip_lamports: 0,
+                total_fee_lamports: 5_000,
+                account_count: 10,
+                instruction_count: 3,
+                tx_size_bytes: 600,
+                swap_details: None,
+                time_since_last_slot_ms: 0,
+                next_leader_pubkey: Pubkey::default(),
+                uses_lookup_tables: false,
+                timestamp_ms: 1_700_000_000_000,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_burst_sheds_oldest_and_drains_in_order() {
+        let ingestor = FeedIngestor::new(
+            Arc::new(Mutex::new(FeatureExtractor::new())),
+            IngestSettings {
+                inbound_capacity: 4,
+                extracted_capacity: 100,
+            },
+        );
+
+        // Burst before the worker starts: only the newest 4 survive
+        let inbound = ingestor.inbound();
+        for slot in 0..10 {
+            inbound.push(transaction(slot)).await.unwrap();
+        }
+        inbound.close();
+        ingestor.spawn().await.unwrap();
+
+        let extracted = ingestor.extracted();
+        let mut slots = Vec::new();
+        while let Some(item) = extracted.pop().await {
+            assert_eq!(item.features.slot, item.slot);
+            slots.push(item.slot);
+        }
+        assert_eq!(slots, vec![6, 7, 8, 9]);
+
+        let stats = ingestor.stats();
+        assert_eq!((stats.inbound.shed, stats.inbound.high_water_mark), (6, 4));
+        assert_eq!((stats.extracted.enqueued, stats.extracted.shed), (4, 0));
+    }
+}
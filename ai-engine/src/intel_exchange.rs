@@ -0,0 +1,319 @@
+//! Validator Intel Exchange
+//!
+//! Router operators share malicious-validator intel as a signed JSON bundle:
+//! - **export**: every entry is stamped with [`IntelProvenance`] (who observed
+//!   it, over which epochs, when it was last updated), then the whole bundle is
+//!   signed with the publisher's key
+//! - **import**: the bundle must come from a trusted publisher and its
+//!   signature must cover the exact contents; entries then merge by recency, so
+//!   an older report never overwrites newer local or previously imported intel
+//!
+//! Local per-epoch history stays local: it's stripped on export and kept on import.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+
+use crate::validator_intel::{IntelProvenance, ValidatorIntel};
+use sentinel_core::{Result, SentinelError};
+
+/// Bumped when the bundle layout changes incompatibly
+pub const INTEL_BUNDLE_VERSION: u32 = 1;
+
+/// Unsigned bundle contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntelBundle {
+    pub version: u32,
+
+    /// Key the bundle is signed with
+    pub publisher: Pubkey,
+
+    /// Export time (unix seconds)
+    pub created_at: i64,
+
+    /// Entries sorted by pubkey, each with provenance set
+    pub entries: Vec<ValidatorIntel>,
+}
+
+impl IntelBundle {
+    /// Bundle of `intel`; entries without provenance are attributed to `source`
+    /// with the observation window taken from their epoch history
+    pub fn export(
+        intel: &HashMap<Pubkey, ValidatorIntel>,
+        publisher: Pubkey,
+        source: &str,
+        now: i64,
+    ) -> Self {
+        let mut entries: Vec<ValidatorIntel> = intel
+            .values()
+            .map(|v| {
+                let mut entry = v.clone();
+                if entry.provenance.is_none() {
+                    let first = v.epoch_history.front().map(|e| e.epoch).unwrap_or(0);
+                    let last = v.epoch_history.back().map(|e| e.epoch).unwrap_or(first);
+                    entry.provenance = Some(IntelProvenance {
+                        source: source.to_string(),
+                        window_start_epoch: first,
+                        window_end_epoch: last,
+                        last_updated: now,
+                    });
+                }
+                entry.epoch_history = VecDeque::new();
+                entry
+            })
+            .collect();
+        entries.sort_by(|a, b| a.pubkey.cmp(&b.pubkey));
+
+        Self {
+            version: INTEL_BUNDLE_VERSION,
+            publisher,
+            created_at: now,
+            entries,
+        }
+    }
+
+    /// Hash the publisher signs (SHA-256 over the JSON encoding)
+    pub fn hash(&self) -> Hash {
+        let serialized = serde_json::to_vec(self).expect("Intel bundle serialization failed");
+        solana_sdk::hash::hash(&serialized)
+    }
+
+    /// Sign with the publisher's key
+    pub fn sign<S: Signer + ?Sized>(self, publisher: &S) -> Result<SignedIntelBundle> {
+        if publisher.pubkey() != self.publisher {
+            return Err(SentinelError::Unauthorized(format!(
+                "Intel bundle publisher {} does not match signing key {}",
+                self.publisher,
+                publisher.pubkey()
+            )));
+        }
+        let signature = publisher.sign_message(self.hash().as_ref());
+        Ok(SignedIntelBundle { bundle: self, signature })
+    }
+}
+
+/// Bundle plus the publisher's signature over its hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedIntelBundle {
+    pub bundle: IntelBundle,
+    pub signature: Signature,
+}
+
+impl SignedIntelBundle {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| SentinelError::SerializationError(format!("Intel bundle: {}", e)))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| SentinelError::SerializationError(format!("Intel bundle: {}", e)))
+    }
+
+    /// Check the version, that the publisher is trusted and that it signed these exact contents
+    pub fn verify(&self, trusted_publishers: &[Pubkey]) -> Result<()> {
+        if self.bundle.version != INTEL_BUNDLE_VERSION {
+            return Err(SentinelError::SerializationError(format!(
+                "Unsupported intel bundle version {} (expected {})",
+                self.bundle.version, INTEL_BUNDLE_VERSION
+            )));
+        }
+        if !trusted_publishers.contains(&self.bundle.publisher) {
+            return Err(SentinelError::Unauthorized(format!(
+                "Intel bundle publisher {} is not trusted",
+                self.bundle.publisher
+            )));
+        }
+        if !self
+            .signature
+            .verify(self.bundle.publisher.as_ref(), self.bundle.hash().as_ref())
+        {
+            return Err(SentinelError::Unauthorized(
+                "Intel bundle signature does not match its contents".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of merging a bundle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Validators not known before
+    pub added: usize,
+
+    /// Existing entries replaced by newer intel
+    pub updated: usize,
+
+    /// Entries no newer than what's already held
+    pub stale: usize,
+
+    /// Entries with a bad pubkey, no provenance or a timestamp after the bundle's
+    pub rejected: usize,
+}
+
+/// Verify `signed` and merge its entries into `intel` by recency
+///
+/// An incoming entry replaces an existing one only if its `last_updated` is
+/// strictly newer; entries without provenance (static or locally observed
+/// intel) count as never updated. Replaced entries keep their local epoch history.
+pub fn import_intel(
+    intel: &mut HashMap<Pubkey, ValidatorIntel>,
+    signed: &SignedIntelBundle,
+    trusted_publishers: &[Pubkey],
+) -> Result<ImportSummary> {
+    signed.verify(trusted_publishers)?;
+
+    let mut summary = ImportSummary::default();
+    for entry in &signed.bundle.entries {
+        let (Ok(pubkey), Some(provenance)) = (Pubkey::from_str(&entry.pubkey), entry.provenance.as_ref()) else {
+            summary.rejected += 1;
+            continue;
+        };
+        if provenance.last_updated > signed.bundle.created_at {
+            summary.rejected += 1;
+            continue;
+        }
+
+        match intel.get_mut(&pubkey) {
+            None => {
+                intel.insert(pubkey, entry.clone());
+                summary.added += 1;
+            }
+            Some(existing) => {
+                let existing_updated = existing.provenance.as_ref().map(|p| p.last_updated).unwrap_or(i64::MIN);
+                if provenance.last_updated <= existing_updated {
+                    summary.stale += 1;
+                    continue;
+                }
+                let history = std::mem::take(&mut existing.epoch_history);
+                *existing = ValidatorIntel {
+                    epoch_history: history,
+                    ..entry.clone()
+                };
+                summary.updated += 1;
+            }
+        }
+    }
+
+    tracing::info!(
+        "📥 Imported validator intel from {}: {} added, {} updated, {} stale, {} rejected",
+        signed.bundle.publisher,
+        summary.added,
+        summary.updated,
+        summary.stale,
+        summary.rejected
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator_intel::{load_validator_intel, EpochStats};
+    use solana_sdk::signature::Keypair;
+
+    fn entry(pubkey: &Pubkey, mev_rate: f32, last_updated: i64) -> ValidatorIntel {
+        ValidatorIntel {
+            mev_rate,
+            is_malicious: true,
+            provenance: Some(IntelProvenance {
+                source: "peer".to_string(),
+                window_start_epoch: 600,
+                window_end_epoch: 610,
+                last_updated,
+            }),
+            ..ValidatorIntel::observed(pubkey)
+        }
+    }
+
+    #[test]
+    fn test_export_roundtrip_and_provenance() {
+        let publisher = Keypair::new();
+        let mut intel = load_validator_intel();
+        let key = *intel.keys().next().unwrap();
+        intel.get_mut(&key).unwrap().epoch_history =
+            (5..8).map(|epoch| EpochStats { epoch, ..Default::default() }).collect();
+
+        let signed = IntelBundle::export(&intel, publisher.pubkey(), "router-eu", 1_700_000_000)
+            .sign(&publisher)
+            .unwrap();
+        let decoded = SignedIntelBundle::from_json(&signed.to_json().unwrap()).unwrap();
+        assert_eq!(decoded, signed);
+        decoded.verify(&[publisher.pubkey()]).unwrap();
+
+        let exported = decoded.bundle.entries.iter().find(|e| e.pubkey == key.to_string()).unwrap();
+        assert!(exported.epoch_history.is_empty());
+        assert_eq!(
+            exported.provenance,
+            Some(IntelProvenance {
+                source: "router-eu".to_string(),
+                window_start_epoch: 5,
+                window_end_epoch: 7,
+                last_updated: 1_700_000_000,
+            })
+        );
+
+        // Signing with a key other than the declared publisher is refused
+        let bundle = IntelBundle::export(&intel, publisher.pubkey(), "router-eu", 0);
+        assert!(bundle.sign(&Keypair::new()).is_err());
+    }
+
+    #[test]
+    fn test_import_rejects_untrusted_or_tampered() {
+        let publisher = Keypair::new();
+        let peer = HashMap::from([{
+            let key = Pubkey::new_unique();
+            (key, entry(&key, 0.9, 100))
+        }]);
+        let signed = IntelBundle::export(&peer, publisher.pubkey(), "peer", 200).sign(&publisher).unwrap();
+        let mut intel = HashMap::new();
+
+        let err = import_intel(&mut intel, &signed, &[Pubkey::new_unique()]).unwrap_err();
+        assert!(err.to_string().contains("not trusted"));
+
+        let mut tampered = signed.clone();
+        tampered.bundle.entries[0].mev_rate = 0.0;
+        let err = import_intel(&mut intel, &tampered, &[publisher.pubkey()]).unwrap_err();
+        assert!(err.to_string().contains("signature"));
+        assert!(intel.is_empty());
+
+        assert_eq!(import_intel(&mut intel, &signed, &[publisher.pubkey()]).unwrap().added, 1);
+    }
+
+    #[test]
+    fn test_import_merges_by_recency() {
+        let publisher = Keypair::new();
+        let (newer, older, fresh, bogus) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut local = HashMap::from([
+            (newer, ValidatorIntel { epoch_history: VecDeque::from([EpochStats::default()]), ..ValidatorIntel::observed(&newer) }),
+            (older, entry(&older, 0.2, 500)),
+        ]);
+        let peer = HashMap::from([
+            (newer, entry(&newer, 0.8, 300)),
+            (older, entry(&older, 0.9, 400)),
+            (fresh, entry(&fresh, 0.7, 300)),
+            (bogus, entry(&bogus, 0.7, 10_000)),
+        ]);
+        let signed = IntelBundle::export(&peer, publisher.pubkey(), "peer", 1_000).sign(&publisher).unwrap();
+
+        let summary = import_intel(&mut local, &signed, &[publisher.pubkey()]).unwrap();
+        assert_eq!(summary, ImportSummary { added: 1, updated: 1, stale: 1, rejected: 1 });
+
+        // Static/observed entry replaced, local history kept
+        assert_eq!(local[&newer].mev_rate, 0.8);
+        assert!(local[&newer].is_malicious);
+        assert_eq!(local[&newer].epoch_history.len(), 1);
+        // Local intel is newer than the peer's report
+        assert_eq!(local[&older].mev_rate, 0.2);
+        assert_eq!(local[&fresh].provenance.as_ref().unwrap().source, "peer");
+        assert!(!local.contains_key(&bogus));
+
+        // Re-importing the same bundle changes nothing
+        let again = import_intel(&mut local, &signed, &[publisher.pubkey()]).unwrap();
+        assert_eq!(again, ImportSummary { added: 0, updated: 0, stale: 3, rejected: 1 });
+    }
+}
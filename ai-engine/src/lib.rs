@@ -1,12 +1,37 @@
+pub mod canary; // Live canary routing with automatic rollback
+pub mod congestion_monitor; // getRecentPerformanceSamples → congestion factor + shared cache
+pub mod dataset; // Labeled training export (CSV/Parquet)
+pub mod dedup; // Signature dedup window for feed redeliveries
 pub mod features;
 pub mod features_enhanced; // Production-ready 55-feature implementation
+pub mod gbdt; // Pure-Rust XGBoost JSON evaluator
+pub mod heuristic_rules; // Declarative heuristic risk rules (TOML/JSON)
+pub mod imputation; // Model-side fill values for features marked missing
+pub mod incident_detector; // Post-hoc sandwich detection on blocks our transactions landed in
 pub mod inference;
+pub mod ingest; // Bounded feed → extraction → inference queues
+pub mod intel_exchange; // Signed validator intel sharing between operators
 pub mod inference_enhanced; // Production-ready with drift detection
+pub mod inference_pool; // Round-robin engine pool for concurrent inference
+pub mod market_stats; // Rolling 24h OHLC/volume per pair
 pub mod model;
+pub mod model_backend; // ONNX / GBDT / TensorRT backends selected via config
+pub mod normalization; // Model input standardization + clipping (SIMD with `simd`)
+pub mod preflight; // Simulate + score an intent without executing it
 pub mod pyth_oracle;
+pub mod quantization; // Int8 input pipeline + fp32/int8 comparison
+pub mod replay; // Re-execute logged decisions and attribute diffs to code/config/data
+pub mod sandwich_model; // Attacker-optimal sandwich profit (constant product / CLMM)
+pub mod score_fusion; // Weighted / logistic fusion of heuristic, model and adaptive scores
 pub mod shadow_mode;
+pub mod shadow_store;
+pub mod sharded_extraction; // Token-pair / fee-payer sharded extraction workers + merge layer
+pub mod swap_history; // Slot-windowed swap history behind the pattern features
+pub mod tip_index; // Slot-bucketed streaming tip percentiles
 pub mod transaction_extractor;
+pub mod triplet_confirmation; // Delayed cross-slot sandwich confirmation + labels
 pub mod validator_intel; // 241 malicious validators tracked
+pub mod validator_registry; // On-chain malicious validator set (single account read)
 
 // NEW: Research-backed enhancements (October 2025)
 pub mod drift_detection; // Multi-method ensemble (PSI + KS + JS)
@@ -16,18 +41,65 @@ pub mod firedancer_monitor; // Firedancer adoption tracking + new MEV patterns
 
 pub use pyth_oracle::{PriceData, PythOracleClient};
 
+pub use canary::{ArmMetrics, CanaryArm, CanaryConfig, CanaryOutcome, CanaryRouter, CanaryStats};
+pub use congestion_monitor::CongestionMonitor;
+pub use dataset::{Dataset, DatasetBuilder, DatasetSchema, FeatureLayout, LabeledExample, DATASET_SCHEMA_VERSION};
+pub use dedup::{DedupSettings, DedupStats, DedupWindow};
+
 // Export enhanced versions for production
-pub use features_enhanced::{FeatureExtractor, FeatureVector, TransactionData, SwapDetailsData, ValidatorTracker};
-pub use inference_enhanced::InferenceEngine;
+pub use features_enhanced::{
+    FeatureExtractor, FeatureMask, FeatureVector, TransactionData, SwapDetailsData, ValidatorTracker,
+};
+pub use incident_detector::{IncidentDetector, IncidentStats, LandedTransaction};
+pub use inference_enhanced::{EngineSnapshot, InferenceEngine, ENGINE_SNAPSHOT_VERSION};
+pub use inference_pool::InferencePool;
+pub use ingest::{ExtractedTransaction, FeedIngestor, FeedTransaction, IngestSettings, IngestStats};
+pub use intel_exchange::{import_intel, ImportSummary, IntelBundle, SignedIntelBundle, INTEL_BUNDLE_VERSION};
+pub use market_stats::{JupiterPriceClient, MarketStats, PairStats, TokenPair};
+pub use gbdt::GbdtModel;
+pub use heuristic_rules::{Aggregation, Comparison, HeuristicRule, HeuristicRules, RuleSet};
+pub use imputation::FeatureImputer;
 pub use model::ModelConfig;
+pub use model_backend::{load_backend, GbdtBackend, ModelBackend, ModelInput};
+pub use normalization::{FeatureNormalizer, FeatureScale, NormalizerSpec};
+pub use preflight::PreflightChecker;
+pub use quantization::{compare as compare_quantization, FeatureQuantizer, FeatureRange, QuantizationReport, QuantizedBackend};
+pub use replay::{
+    record_replay_inputs, DecisionReplayer, ReplayOutcome, ReplayReport, ReplayVerdict, ENGINE_BUILD,
+};
+pub use sandwich_model::{PoolState, SandwichModel, SandwichModelSettings, SandwichOpportunity, VictimSwap};
+pub use score_fusion::{ComponentScores, FusedScore, FusionStats, ScoreComponent, ScoreFusion};
 pub use shadow_mode::{ShadowConfig, ShadowModeManager, ShadowPrediction, ShadowStats};
-pub use transaction_extractor::extract_from_transaction;
-pub use validator_intel::{ValidatorIntel, load_validator_intel, calculate_validator_risk};
+pub use shadow_store::{JsonlShadowStore, KvShadowStore, ShadowPage, ShadowQuery, ShadowStore};
+#[cfg(feature = "sled")]
+pub use shadow_store::SledShadowStore;
+#[cfg(feature = "postgres")]
+pub use shadow_store::PostgresShadowStore;
+pub use sharded_extraction::{
+    CrossShardFeatures, CrossShardIndex, ShardKey, ShardedExtractor, ShardedStats, ShardingSettings,
+};
+pub use swap_history::{SwapHistory, SwapWindowSettings};
+pub use tip_index::{TipIndexSnapshot, TipPercentileIndex, TipPercentiles};
+pub use transaction_extractor::{
+    decode_wire_transaction, extract_from_confirmed, extract_from_static_keys, extract_from_transaction,
+    extract_from_versioned, BalanceDeltaSwap, SwapEvent, SwapVenue, MAX_WIRE_TRANSACTION_BYTES,
+};
+pub use triplet_confirmation::{ObservedSwap, TripletConfirmer, TripletOutcome, TripletSettings, TripletStats};
+pub use validator_intel::{
+    BlockObservation, EpochStats, EpochStatsAggregator, IntelProvenance, ValidatorIntel, load_validator_intel,
+    load_validator_intel_for, calculate_validator_risk, malicious_risk_map, validator_risk_map,
+};
+pub use validator_registry::{
+    merge_registry, IntelOverrides, RegistryClient, RegistryEntry, RegistryMerge, ValidatorRegistry, REGISTRY_MAGIC,
+};
 
 // Export new research-backed modules
 pub use drift_detection::{DriftDetector, DriftScore, VotingStrategy};
 pub use enhanced_features::{EnhancedFeatureVector, EnhancedTransactionData, JitoBundleInfo};
-pub use adaptive_heuristics::{AdaptiveHeuristics, MEVDetectionPipeline, ThresholdConfig};
+pub use adaptive_heuristics::{
+    AdaptiveHeuristics, AdjustedThresholds, HeuristicsSnapshot, MEVDetectionPipeline, PairSnapshot, PairThresholds,
+    ThresholdConfig,
+};
 pub use firedancer_monitor::{
     FiredancerMonitor, FiredancerReport, FiredancerMevPattern, 
     FiredancerPerformance, AlertLevel, ValidatorClient
@@ -0,0 +1,467 @@
+//! Rolling 24h market statistics per token pair
+//!
+//! Aggregates OHLC and USD volume from observed swaps (plus optional Jupiter
+//! price API samples) so `volatility_24h_pct` and `volume_24h_usd` are computed
+//! from live data instead of being pushed in manually.
+//!
+//! Volatility follows the FeatureVector definition: 24h high-low range as a
+//! percentage of the low.
+
+use reqwest::Client;
+use sentinel_core::{Result, SentinelError};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use tracing::debug;
+
+/// Default rolling window (24 hours)
+pub const DEFAULT_WINDOW_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Maximum observations kept per pair (bounds memory for hot pairs)
+const MAX_OBSERVATIONS_PER_PAIR: usize = 50_000;
+
+/// Token pair key: (input_mint, output_mint)
+pub type TokenPair = (Pubkey, Pubkey);
+
+/// Single price/volume observation
+#[derive(Debug, Clone, Copy)]
+struct PriceObservation {
+    price: f64,
+    volume_usd: f64,
+    timestamp_ms: u64,
+}
+
+/// A pair's observations in arrival (and timestamp) order, with running
+/// aggregates over all of them so the common query, a window covering every
+/// retained observation, is O(1)
+#[derive(Debug, Default)]
+struct PairWindow {
+    observations: VecDeque<PriceObservation>,
+    /// Sequence number the next observation gets
+    next_seq: u64,
+    /// (seq, price) with strictly decreasing prices: the front is the high
+    highs: VecDeque<(u64, f64)>,
+    /// (seq, price) with strictly increasing prices: the front is the low
+    lows: VecDeque<(u64, f64)>,
+    volume_usd: f64,
+    trade_count: u32,
+}
+
+impl PairWindow {
+    fn push(&mut self, observation: PriceObservation) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        while self.highs.back().is_some_and(|&(_, price)| price <= observation.price) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((seq, observation.price));
+        while self.lows.back().is_some_and(|&(_, price)| price >= observation.price) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((seq, observation.price));
+        self.volume_usd += observation.volume_usd;
+        self.trade_count += u32::from(observation.volume_usd > 0.0);
+        self.observations.push_back(observation);
+    }
+
+    fn pop_front(&mut self) {
+        let seq = self.next_seq - self.observations.len() as u64;
+        let Some(observation) = self.observations.pop_front() else {
+            return;
+        };
+        if self.highs.front().is_some_and(|&(s, _)| s == seq) {
+            self.highs.pop_front();
+        }
+        if self.lows.front().is_some_and(|&(s, _)| s == seq) {
+            self.lows.pop_front();
+        }
+        self.trade_count -= u32::from(observation.volume_usd > 0.0);
+        // Reset rather than let subtraction round-off linger
+        self.volume_usd = if self.observations.is_empty() { 0.0 } else { self.volume_usd - observation.volume_usd };
+    }
+
+    fn prune(&mut self, now_ms: u64, window_ms: u64) {
+        let cutoff = now_ms.saturating_sub(window_ms);
+        while self.observations.front().is_some_and(|o| o.timestamp_ms < cutoff) {
+            self.pop_front();
+        }
+    }
+
+    /// Index range of the observations within `[window_start_ms, now_ms]`
+    fn range(&self, window_start_ms: u64, now_ms: u64) -> std::ops::Range<usize> {
+        let start = self.observations.partition_point(|o| o.timestamp_ms < window_start_ms);
+        let end = self.observations.partition_point(|o| o.timestamp_ms <= now_ms);
+        start..end.max(start)
+    }
+}
+
+/// Aggregated OHLC/volume over the rolling window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PairStats {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume_usd: f64,
+    pub trade_count: u32,
+    pub window_start_ms: u64,
+}
+
+impl PairStats {
+    /// High-low range as a percentage of the low price
+    pub fn volatility_pct(&self) -> f32 {
+        if self.low > 0.0 {
+            ((self.high - self.low) / self.low * 100.0) as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Rolling market statistics service
+pub struct MarketStats {
+    pairs: HashMap<TokenPair, PairWindow>,
+    window_ms: u64,
+    price_client: Option<JupiterPriceClient>,
+}
+
+impl Default for MarketStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketStats {
+    /// Create with the default 24h window
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_WINDOW_MS)
+    }
+
+    /// Create with a custom rolling window
+    pub fn with_window(window_ms: u64) -> Self {
+        Self {
+            pairs: HashMap::new(),
+            window_ms,
+            price_client: None,
+        }
+    }
+
+    /// Attach a Jupiter price API client for supplementary samples
+    pub fn with_price_client(mut self, client: JupiterPriceClient) -> Self {
+        self.price_client = Some(client);
+        self
+    }
+
+    /// Record an observed swap
+    ///
+    /// `price` is the execution price (output per input), `volume_usd` the trade size.
+    /// A swap older than the pair's latest observation (stale or replayed) is
+    /// dropped, keeping the window in timestamp order.
+    pub fn record_swap(&mut self, pair: TokenPair, price: f64, volume_usd: f64, timestamp_ms: u64) {
+        if !price.is_finite() || price <= 0.0 {
+            return;
+        }
+
+        let window = self.pairs.entry(pair).or_default();
+        if window.observations.back().is_some_and(|last| last.timestamp_ms > timestamp_ms) {
+            debug!("Dropping out-of-order swap at {}ms", timestamp_ms);
+            return;
+        }
+        window.push(PriceObservation {
+            price,
+            volume_usd: if volume_usd.is_finite() { volume_usd.max(0.0) } else { 0.0 },
+            timestamp_ms,
+        });
+
+        if window.observations.len() > MAX_OBSERVATIONS_PER_PAIR {
+            window.pop_front();
+        }
+
+        window.prune(timestamp_ms, self.window_ms);
+    }
+
+    /// Record a price sample with no traded volume (e.g. from a price API)
+    pub fn record_price(&mut self, pair: TokenPair, price: f64, timestamp_ms: u64) {
+        self.record_swap(pair, price, 0.0, timestamp_ms);
+    }
+
+    /// Fetch the current price from the Jupiter API and record it
+    pub async fn refresh_from_api(&mut self, pair: TokenPair, timestamp_ms: u64) -> Result<f64> {
+        let client = self.price_client.as_ref().ok_or_else(|| {
+            SentinelError::PriceOracleError("No price API client configured".to_string())
+        })?;
+
+        let price = client.get_price(&pair.0, &pair.1).await?;
+        self.record_price(pair, price, timestamp_ms);
+        Ok(price)
+    }
+
+    /// Aggregate stats for a pair within the window ending at `now_ms`
+    pub fn stats(&self, pair: &TokenPair, now_ms: u64) -> Option<PairStats> {
+        let window_start_ms = now_ms.saturating_sub(self.window_ms);
+        let window = self.pairs.get(pair)?;
+        let (first, last) = (window.observations.front()?, window.observations.back()?);
+
+        // Usual case: every retained observation is in the window
+        if first.timestamp_ms >= window_start_ms && last.timestamp_ms <= now_ms {
+            return Some(PairStats {
+                open: first.price,
+                high: window.highs.front().map_or(first.price, |&(_, price)| price),
+                low: window.lows.front().map_or(first.price, |&(_, price)| price),
+                close: last.price,
+                volume_usd: window.volume_usd,
+                trade_count: window.trade_count,
+                window_start_ms,
+            });
+        }
+
+        let mut in_window = window.observations.range(window.range(window_start_ms, now_ms));
+        let first = in_window.next()?;
+        let mut stats = PairStats {
+            open: first.price,
+            high: first.price,
+            low: first.price,
+            close: first.price,
+            volume_usd: first.volume_usd,
+            trade_count: u32::from(first.volume_usd > 0.0),
+            window_start_ms,
+        };
+
+        for o in in_window {
+            stats.high = stats.high.max(o.price);
+            stats.low = stats.low.min(o.price);
+            stats.close = o.price;
+            stats.volume_usd += o.volume_usd;
+            if o.volume_usd > 0.0 {
+                stats.trade_count += 1;
+            }
+        }
+
+        Some(stats)
+    }
+
+    /// Time-weighted average price over the `twap_window_ms` ending at `now_ms`
+    ///
+    /// Each observation holds until the next one (the last until `now_ms`);
+    /// observations all at `now_ms` are averaged. None without any in the window.
+    pub fn twap(&self, pair: &TokenPair, now_ms: u64, twap_window_ms: u64) -> Option<f64> {
+        let window = self.pairs.get(pair)?;
+        let range = window.range(now_ms.saturating_sub(twap_window_ms), now_ms);
+        if range.is_empty() {
+            return None;
+        }
+
+        let count = range.len();
+        let (mut weighted, mut duration, mut price_sum) = (0.0, 0u64, 0.0);
+        let mut in_window = window.observations.range(range).peekable();
+        while let Some(o) = in_window.next() {
+            let until = in_window.peek().map_or(now_ms, |next| next.timestamp_ms);
+            let held = until.saturating_sub(o.timestamp_ms);
+            weighted += o.price * held as f64;
+            duration += held;
+            price_sum += o.price;
+        }
+        if duration == 0 {
+            return Some(price_sum / count as f64);
+        }
+        Some(weighted / duration as f64)
+    }
+
+    /// 24h high-low range (%) for a pair, 0.0 if untracked
+    pub fn volatility_24h_pct(&self, pair: &TokenPair, now_ms: u64) -> f32 {
+        self.stats(pair, now_ms)
+            .map(|s| s.volatility_pct())
+            .unwrap_or(0.0)
+    }
+
+    /// 24h USD volume for a pair, 0.0 if untracked
+    pub fn volume_24h_usd(&self, pair: &TokenPair, now_ms: u64) -> f64 {
+        self.stats(pair, now_ms).map(|s| s.volume_usd).unwrap_or(0.0)
+    }
+
+    /// Drop observations older than the window across all pairs
+    pub fn prune(&mut self, now_ms: u64) {
+        let window_ms = self.window_ms;
+        for window in self.pairs.values_mut() {
+            window.prune(now_ms, window_ms);
+        }
+        self.pairs.retain(|_, window| !window.observations.is_empty());
+    }
+
+    /// Number of pairs currently tracked
+    pub fn tracked_pairs(&self) -> usize {
+        self.pairs.len()
+    }
+}
+
+/// Minimal Jupiter price API client
+pub struct JupiterPriceClient {
+    http_client: Client,
+    api_endpoint: String,
+}
+
+impl JupiterPriceClient {
+    pub fn new(api_endpoint: String) -> Self {
+        Self {
+            http_client: Client::new(),
+            api_endpoint,
+        }
+    }
+
+    /// Public Jupiter price API
+    pub fn mainnet() -> Self {
+        Self::new("https://price.jup.ag/v6".to_string())
+    }
+
+    /// Price of `input_mint` denominated in `output_mint`
+    pub async fn get_price(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<f64> {
+        let url = format!(
+            "{}/price?ids={}&vsToken={}",
+            self.api_endpoint, input_mint, output_mint
+        );
+
+        let response = self.http_client.get(&url).send().await.map_err(|e| {
+            SentinelError::PriceOracleError(format!("Jupiter price request failed: {}", e))
+        })?;
+
+        let body: JupiterPriceResponse = response.json().await.map_err(|e| {
+            SentinelError::PriceOracleError(format!("Failed to parse Jupiter price: {}", e))
+        })?;
+
+        let price = body
+            .data
+            .get(&input_mint.to_string())
+            .map(|p| p.price)
+            .ok_or_else(|| {
+                SentinelError::PriceOracleError(format!("No Jupiter price for {}", input_mint))
+            })?;
+
+        debug!("Jupiter price {}/{}: {}", input_mint, output_mint, price);
+        Ok(price)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JupiterPriceResponse {
+    data: HashMap<String, JupiterPriceEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JupiterPriceEntry {
+    price: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair() -> TokenPair {
+        (Pubkey::new_unique(), Pubkey::new_unique())
+    }
+
+    #[test]
+    fn test_ohlc_aggregation() {
+        let mut stats = MarketStats::new();
+        let p = pair();
+
+        stats.record_swap(p, 100.0, 1_000.0, 1_000);
+        stats.record_swap(p, 120.0, 500.0, 2_000);
+        stats.record_swap(p, 90.0, 250.0, 3_000);
+        stats.record_swap(p, 110.0, 0.0, 4_000);
+
+        let s = stats.stats(&p, 5_000).unwrap();
+        assert_eq!(s.open, 100.0);
+        assert_eq!(s.high, 120.0);
+        assert_eq!(s.low, 90.0);
+        assert_eq!(s.close, 110.0);
+        assert_eq!(s.volume_usd, 1_750.0);
+        assert_eq!(s.trade_count, 3);
+    }
+
+    #[test]
+    fn test_volatility_range() {
+        let mut stats = MarketStats::new();
+        let p = pair();
+
+        stats.record_price(p, 100.0, 1_000);
+        stats.record_price(p, 150.0, 2_000);
+
+        let vol = stats.volatility_24h_pct(&p, 3_000);
+        assert!((vol - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_window_expiry() {
+        let mut stats = MarketStats::with_window(10_000);
+        let p = pair();
+
+        stats.record_swap(p, 100.0, 1_000.0, 0);
+        stats.record_swap(p, 200.0, 1_000.0, 20_000);
+
+        assert_eq!(stats.volume_24h_usd(&p, 20_000), 1_000.0);
+        assert_eq!(stats.volatility_24h_pct(&p, 20_000), 0.0);
+
+        stats.prune(40_000);
+        assert_eq!(stats.tracked_pairs(), 0);
+    }
+
+    #[test]
+    fn test_running_aggregates_match_after_pruning() {
+        let mut stats = MarketStats::with_window(10_000);
+        let p = pair();
+        let prices = [100.0, 140.0, 90.0, 120.0, 95.0, 130.0, 110.0, 85.0, 105.0, 125.0];
+        for (i, price) in prices.iter().enumerate() {
+            stats.record_swap(p, *price, (i % 3) as f64 * 100.0, i as u64 * 3_000);
+
+            // Brute-force aggregate over what the window should hold
+            let now = i as u64 * 3_000;
+            let held: Vec<(usize, &f64)> =
+                prices.iter().enumerate().take(i + 1).filter(|(j, _)| *j as u64 * 3_000 + 10_000 >= now).collect();
+            let s = stats.stats(&p, now).unwrap();
+            assert_eq!(s.open, *held[0].1);
+            assert_eq!(s.close, *price);
+            assert_eq!(s.high, held.iter().map(|(_, p)| **p).fold(f64::MIN, f64::max));
+            assert_eq!(s.low, held.iter().map(|(_, p)| **p).fold(f64::MAX, f64::min));
+            assert_eq!(s.volume_usd, held.iter().map(|(j, _)| (j % 3) as f64 * 100.0).sum::<f64>());
+            assert_eq!(s.trade_count, held.iter().filter(|(j, _)| j % 3 != 0).count() as u32);
+        }
+
+        // A query ending before the latest observation takes the scan path
+        let s = stats.stats(&p, 25_000).unwrap();
+        assert_eq!((s.open, s.high, s.low, s.close), (110.0, 110.0, 85.0, 105.0));
+
+        // Late swaps are left out of the window
+        let before = stats.stats(&p, 27_000).unwrap();
+        stats.record_swap(p, 300.0, 5_000.0, 1_000);
+        stats.record_swap(p, 1.0, 5_000.0, 26_000);
+        assert_eq!(stats.stats(&p, 27_000).unwrap(), before);
+
+        stats.prune(27_000 + 10_001);
+        assert_eq!(stats.tracked_pairs(), 0);
+        assert!(stats.stats(&p, 27_000).is_none());
+    }
+
+    #[test]
+    fn test_twap_weights_by_time_held() {
+        let mut stats = MarketStats::new();
+        let p = pair();
+        assert_eq!(stats.twap(&p, 10_000, 5_000), None);
+
+        stats.record_price(p, 80.0, 1_000);
+        stats.record_price(p, 100.0, 6_000);
+        stats.record_price(p, 200.0, 9_000);
+        // 100 for 3s, 200 for 1s; the 80 is outside the window
+        assert_eq!(stats.twap(&p, 10_000, 5_000), Some(125.0));
+        assert_eq!(stats.twap(&p, 9_000, 0), Some(200.0));
+    }
+
+    #[test]
+    fn test_invalid_price_ignored() {
+        let mut stats = MarketStats::new();
+        let p = pair();
+
+        stats.record_swap(p, f64::NAN, 100.0, 1_000);
+        stats.record_swap(p, 0.0, 100.0, 1_000);
+        assert!(stats.stats(&p, 1_000).is_none());
+    }
+}
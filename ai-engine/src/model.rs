@@ -1,8 +1,11 @@
+use sentinel_core::{ImputationSettings, ModelBackendKind, ModelSettings};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
+    /// Inference backend (see `model_backend`)
+    pub backend: ModelBackendKind,
     pub model_path: PathBuf,
     pub intra_op_threads: usize,
     pub inter_op_threads: usize,
@@ -20,11 +23,16 @@ pub struct ModelConfig {
     
     /// Enable execution mode parallel (for multi-model inference)
     pub enable_parallel_execution: bool,
+    
+    /// Model input for missing features (see `imputation`)
+    #[serde(default)]
+    pub imputation: ImputationSettings,
 }
 
 impl Default for ModelConfig {
     fn default() -> Self {
         Self {
+            backend: ModelBackendKind::default(),
             model_path: PathBuf::from("models/mev_detector.onnx"),
             intra_op_threads: 4,
             inter_op_threads: 1,
@@ -35,6 +43,21 @@ impl Default for ModelConfig {
             enable_memory_pattern: true,      // Arena allocator: 15% faster
             graph_optimization_level: 3,      // Full optimization: graph fusion
             enable_parallel_execution: true,  // Multi-model inference
+            imputation: ImputationSettings::default(),
+        }
+    }
+}
+
+impl From<&ModelSettings> for ModelConfig {
+    fn from(settings: &ModelSettings) -> Self {
+        Self {
+            backend: settings.backend,
+            model_path: settings.model_path.clone(),
+            intra_op_threads: settings.intra_op_threads,
+            inter_op_threads: settings.inter_op_threads,
+            warmup_iterations: settings.warmup_iterations,
+            imputation: settings.imputation.clone(),
+            ..Default::default()
         }
     }
 }
@@ -47,6 +70,12 @@ impl ModelConfig {
         }
     }
 
+    /// Select the inference backend
+    pub fn with_backend(mut self, backend: ModelBackendKind) -> Self {
+        self.backend = backend;
+        self
+    }
+
     pub fn with_threads(mut self, intra: usize, inter: usize) -> Self {
         self.intra_op_threads = intra;
         self.inter_op_threads = inter;
@@ -57,6 +86,11 @@ impl ModelConfig {
         self.warmup_iterations = iterations;
         self
     }
+
+    pub fn with_imputation(mut self, imputation: ImputationSettings) -> Self {
+        self.imputation = imputation;
+        self
+    }
     
     /// Configure ONNX optimizations for maximum performance
     /// 
@@ -0,0 +1,244 @@
+//! Pluggable Model Backends
+//!
+//! The engine scores the 55-feature vector through a [`ModelBackend`] selected
+//! by `ModelConfig::backend`:
+//! - `gbdt`: pure-Rust XGBoost JSON evaluator, always available (dependency-light default build)
+//! - `onnx`: ONNX Runtime on CPU (`onnx` feature)
+//! - `tensor_rt`: ONNX Runtime with TensorRT, then CUDA, execution providers (`tensorrt` feature)
+//!
+//! Selecting a backend whose feature is not compiled in is a load error; the
+//! engine then falls back to production heuristics.
+//!
+//! With `enable_quantization`, a `<model>.quant.json` calibration next to the
+//! model switches the backend to the int8 input pipeline (see `quantization`).
+
+use sentinel_core::{ModelBackendKind, Result, SentinelError};
+use tracing::info;
+
+use crate::features_enhanced::FeatureVector;
+use crate::gbdt::GbdtModel;
+use crate::model::ModelConfig;
+use crate::quantization::{FeatureQuantizer, QuantizedBackend};
+
+/// Model input in `FeatureVector::write_into` order
+pub type ModelInput = [f32; FeatureVector::FEATURE_COUNT];
+
+/// Inference backend producing an MEV probability
+pub trait ModelBackend: Send + Sync {
+    /// Short identifier for logs and `ModelInfo`
+    fn name(&self) -> &'static str;
+
+    /// MEV probability in [0, 1]
+    fn predict(&self, features: &ModelInput) -> Result<f32>;
+}
+
+/// Load the backend selected in `config`
+pub fn load_backend(config: &ModelConfig) -> Result<Box<dyn ModelBackend>> {
+    let backend: Box<dyn ModelBackend> = match config.backend {
+        ModelBackendKind::Gbdt => Box::new(GbdtBackend::load(config)?),
+        ModelBackendKind::Onnx => load_onnx(config, false)?,
+        ModelBackendKind::TensorRt => load_onnx(config, true)?,
+    };
+
+    let sidecar = FeatureQuantizer::sidecar_path(&config.model_path);
+    let backend: Box<dyn ModelBackend> = if config.enable_quantization && sidecar.exists() {
+        Box::new(QuantizedBackend::new(backend, FeatureQuantizer::load(&sidecar)?))
+    } else {
+        backend
+    };
+    info!("📦 Loaded {} model backend from {:?}", backend.name(), config.model_path);
+    Ok(backend)
+}
+
+/// Gradient-boosted trees evaluated in pure Rust
+pub struct GbdtBackend {
+    model: GbdtModel,
+}
+
+impl GbdtBackend {
+    pub fn new(model: GbdtModel) -> Self {
+        Self { model }
+    }
+
+    pub fn load(config: &ModelConfig) -> Result<Self> {
+        let model = GbdtModel::load(&config.model_path)?;
+        if model.feature_count() > FeatureVector::FEATURE_COUNT {
+            return Err(SentinelError::InferenceError(format!(
+                "GBDT model references {} features, engine provides {}",
+                model.feature_count(),
+                FeatureVector::FEATURE_COUNT
+            )));
+        }
+        info!("   GBDT: {} trees", model.tree_count());
+        Ok(Self::new(model))
+    }
+}
+
+impl ModelBackend for GbdtBackend {
+    fn name(&self) -> &'static str {
+        "gbdt"
+    }
+
+    fn predict(&self, features: &ModelInput) -> Result<f32> {
+        self.model.predict(features)
+    }
+}
+
+#[cfg(feature = "onnx")]
+fn load_onnx(config: &ModelConfig, accelerated: bool) -> Result<Box<dyn ModelBackend>> {
+    Ok(Box::new(onnx::OnnxBackend::load(config, accelerated)?))
+}
+
+#[cfg(not(feature = "onnx"))]
+fn load_onnx(_config: &ModelConfig, accelerated: bool) -> Result<Box<dyn ModelBackend>> {
+    let feature = if accelerated { "tensorrt" } else { "onnx" };
+    Err(SentinelError::InferenceError(format!(
+        "{} backend requires the `{}` feature of ai-engine",
+        feature, feature
+    )))
+}
+
+#[cfg(feature = "onnx")]
+mod onnx {
+    use super::{ModelBackend, ModelInput};
+    use crate::model::ModelConfig;
+    use ort::session::builder::GraphOptimizationLevel;
+    use ort::session::Session;
+    use ort::value::TensorRef;
+    use sentinel_core::{Result, SentinelError};
+    use std::sync::Mutex;
+
+    fn ort_error(e: impl std::fmt::Display) -> SentinelError {
+        SentinelError::InferenceError(format!("ONNX Runtime: {}", e))
+    }
+
+    /// ONNX Runtime session (CPU, or TensorRT/CUDA when `accelerated`)
+    pub struct OnnxBackend {
+        session: Mutex<Session>,
+        accelerated: bool,
+    }
+
+    impl OnnxBackend {
+        pub fn load(config: &ModelConfig, accelerated: bool) -> Result<Self> {
+            let level = match config.graph_optimization_level {
+                0 => GraphOptimizationLevel::Disable,
+                1 => GraphOptimizationLevel::Level1,
+                2 => GraphOptimizationLevel::Level2,
+                _ => GraphOptimizationLevel::Level3,
+            };
+
+            let mut builder = Session::builder()
+                .map_err(ort_error)?
+                .with_optimization_level(level)
+                .map_err(ort_error)?
+                .with_intra_threads(config.intra_op_threads)
+                .map_err(ort_error)?
+                .with_inter_threads(config.inter_op_threads)
+                .map_err(ort_error)?
+                .with_memory_pattern(config.enable_memory_pattern)
+                .map_err(ort_error)?
+                .with_parallel_execution(config.enable_parallel_execution)
+                .map_err(ort_error)?;
+
+            if accelerated {
+                builder = gpu_providers(builder)?;
+            }
+
+            let session = builder.commit_from_file(&config.model_path).map_err(ort_error)?;
+            Ok(Self {
+                session: Mutex::new(session),
+                accelerated,
+            })
+        }
+    }
+
+    #[cfg(feature = "tensorrt")]
+    fn gpu_providers(
+        builder: ort::session::builder::SessionBuilder,
+    ) -> Result<ort::session::builder::SessionBuilder> {
+        use ort::execution_providers::{CUDAExecutionProvider, TensorRTExecutionProvider};
+
+        builder
+            .with_execution_providers([
+                TensorRTExecutionProvider::default().build(),
+                CUDAExecutionProvider::default().build(),
+            ])
+            .map_err(ort_error)
+    }
+
+    #[cfg(not(feature = "tensorrt"))]
+    fn gpu_providers(
+        _builder: ort::session::builder::SessionBuilder,
+    ) -> Result<ort::session::builder::SessionBuilder> {
+        Err(SentinelError::InferenceError(
+            "tensor_rt backend requires the `tensorrt` feature of ai-engine".to_string(),
+        ))
+    }
+
+    impl ModelBackend for OnnxBackend {
+        fn name(&self) -> &'static str {
+            if self.accelerated {
+                "tensor_rt"
+            } else {
+                "onnx"
+            }
+        }
+
+        fn predict(&self, features: &ModelInput) -> Result<f32> {
+            // Borrowed view: no copy of the features on the hot path
+            let input = TensorRef::from_array_view(([1usize, features.len()], &features[..])).map_err(ort_error)?;
+
+            let mut session = self.session.lock().unwrap_or_else(|p| p.into_inner());
+            let outputs = session.run(ort::inputs![input]).map_err(ort_error)?;
+
+            // Classifier exports emit [1, 1] (probability) or [1, 2] (class probabilities)
+            let (_, scores) = outputs[outputs.len() - 1]
+                .try_extract_tensor::<f32>()
+                .map_err(ort_error)?;
+            scores
+                .last()
+                .copied()
+                .ok_or_else(|| SentinelError::InferenceError("ONNX model returned no output".to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_gbdt_backend_from_config() {
+        let path = std::env::temp_dir().join(format!("gbdt-{}.json", uuid::Uuid::new_v4()));
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(br#"[{"nodeid": 0, "split": "jito_tip_lamports", "split_condition": 1000.0,
+                "yes": 1, "no": 2, "children": [{"nodeid": 1, "leaf": -2.0}, {"nodeid": 2, "leaf": 2.0}]}]"#)
+            .unwrap();
+
+        let config = ModelConfig::new(path.clone()).with_backend(ModelBackendKind::Gbdt);
+        let backend = load_backend(&config).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let mut input = [0.0f32; FeatureVector::FEATURE_COUNT];
+        assert_eq!(backend.name(), "gbdt");
+        assert!(backend.predict(&input).unwrap() < 0.5);
+        input[3] = 50_000.0;
+        assert!(backend.predict(&input).unwrap() > 0.5);
+    }
+
+    #[test]
+    fn test_missing_model_is_load_error() {
+        let config = ModelConfig::new("does/not/exist.json".into()).with_backend(ModelBackendKind::Gbdt);
+        assert!(load_backend(&config).is_err());
+    }
+
+    #[cfg(not(feature = "tensorrt"))]
+    #[test]
+    fn test_tensorrt_requires_feature() {
+        let config = ModelConfig::default().with_backend(ModelBackendKind::TensorRt);
+        let err = load_backend(&config).err().unwrap();
+        assert!(err.to_string().contains("tensorrt"));
+    }
+}
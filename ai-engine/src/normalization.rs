@@ -0,0 +1,306 @@
+//! Model Input Normalization
+//!
+//! `write_into` lays out raw feature units; models trained on standardized
+//! inputs need every value shifted, scaled and clipped first. The
+//! [`FeatureNormalizer`] does that as one explicit stage producing the
+//! model-ready `[f32; 55]`:
+//!
+//! ```text
+//! y = clamp((x - offset) * scale, min, max)      NaN -> 0
+//! ```
+//!
+//! Parameters are stored lane-padded (55 -> 56 = 7 × f32x8). With the `simd`
+//! feature the kernel runs on `wide::f32x8` (AVX/SSE/NEON, stable Rust);
+//! without it the scalar loop is used. Both are bit-identical (no FMA), see
+//! `benches/normalization.rs` for the comparison.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use sentinel_core::{Result, SentinelError};
+
+use crate::features_enhanced::FeatureVector;
+use crate::model_backend::ModelInput;
+
+const LANES: usize = 8;
+const PADDED: usize = FeatureVector::FEATURE_COUNT.div_ceil(LANES) * LANES;
+
+/// Affine transform and clip range of one feature
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeatureScale {
+    pub offset: f32,
+    pub scale: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl FeatureScale {
+    /// Pass-through (no shift, no scale, no clip)
+    pub const IDENTITY: Self = Self {
+        offset: 0.0,
+        scale: 1.0,
+        min: f32::MIN,
+        max: f32::MAX,
+    };
+}
+
+/// Serialized form of a [`FeatureNormalizer`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NormalizerSpec {
+    pub features: Vec<FeatureScale>,
+}
+
+/// Lane-padded normalization stage in model input order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "NormalizerSpec", into = "NormalizerSpec")]
+pub struct FeatureNormalizer {
+    offset: [f32; PADDED],
+    scale: [f32; PADDED],
+    min: [f32; PADDED],
+    max: [f32; PADDED],
+}
+
+impl FeatureNormalizer {
+    /// One scale per feature; rejects a wrong count or an inverted/NaN clip range
+    pub fn new(features: &[FeatureScale]) -> Result<Self> {
+        if features.len() != FeatureVector::FEATURE_COUNT {
+            return Err(SentinelError::InferenceError(format!(
+                "Normalizer has {} features, expected {}",
+                features.len(),
+                FeatureVector::FEATURE_COUNT
+            )));
+        }
+        // Padding lanes map everything to 0
+        let mut normalizer = Self {
+            offset: [0.0; PADDED],
+            scale: [0.0; PADDED],
+            min: [0.0; PADDED],
+            max: [0.0; PADDED],
+        };
+        for (i, feature) in features.iter().enumerate() {
+            let inverted = matches!(feature.min.partial_cmp(&feature.max), None | Some(Ordering::Greater));
+            if inverted || !feature.offset.is_finite() || !feature.scale.is_finite() {
+                return Err(SentinelError::InferenceError(format!(
+                    "Invalid normalization for {}: {:?}",
+                    FeatureVector::FEATURE_NAMES[i],
+                    feature
+                )));
+            }
+            normalizer.offset[i] = feature.offset;
+            normalizer.scale[i] = feature.scale;
+            normalizer.min[i] = feature.min;
+            normalizer.max[i] = feature.max;
+        }
+        Ok(normalizer)
+    }
+
+    pub fn identity() -> Self {
+        Self::new(&[FeatureScale::IDENTITY; FeatureVector::FEATURE_COUNT]).expect("identity is valid")
+    }
+
+    /// Z-score each feature over `samples` and clip at ±`clip_sigma`
+    ///
+    /// Non-finite sample values are ignored; constant features map to 0.
+    pub fn fit(samples: &[ModelInput], clip_sigma: f32) -> Result<Self> {
+        if samples.is_empty() {
+            return Err(SentinelError::InferenceError(
+                "Normalizer fitting needs at least one sample".to_string(),
+            ));
+        }
+
+        let clip = clip_sigma.abs();
+        let features: Vec<FeatureScale> = (0..FeatureVector::FEATURE_COUNT)
+            .map(|i| {
+                let column: Vec<f64> = samples.iter().map(|s| s[i] as f64).filter(|x| x.is_finite()).collect();
+                let n = column.len().max(1) as f64;
+                let mean = column.iter().sum::<f64>() / n;
+                let std = (column.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+                FeatureScale {
+                    offset: mean as f32,
+                    scale: if std > f64::EPSILON { (1.0 / std) as f32 } else { 0.0 },
+                    min: -clip,
+                    max: clip,
+                }
+            })
+            .collect();
+        Self::new(&features)
+    }
+
+    /// Normalization file stored alongside a model
+    pub fn sidecar_path(model_path: &Path) -> PathBuf {
+        let mut name = model_path.as_os_str().to_owned();
+        name.push(".norm.json");
+        PathBuf::from(name)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| SentinelError::InferenceError(format!("Failed to read {:?}: {}", path, e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| SentinelError::InferenceError(format!("Invalid normalization file: {}", e)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| SentinelError::IoError(e.to_string()))
+    }
+
+    /// Scale of feature `index`
+    pub fn feature(&self, index: usize) -> Option<FeatureScale> {
+        (index < FeatureVector::FEATURE_COUNT).then(|| FeatureScale {
+            offset: self.offset[index],
+            scale: self.scale[index],
+            min: self.min[index],
+            max: self.max[index],
+        })
+    }
+
+    /// Whether the SIMD kernel is compiled in
+    pub const fn is_simd() -> bool {
+        cfg!(feature = "simd")
+    }
+
+    /// Model-ready input for a feature vector
+    pub fn normalize_features(&self, features: &FeatureVector, out: &mut ModelInput) {
+        features.write_into(out);
+        let raw = *out;
+        self.normalize_into(&raw, out);
+    }
+
+    pub fn normalize(&self, input: &ModelInput) -> ModelInput {
+        let mut out = [0.0f32; FeatureVector::FEATURE_COUNT];
+        self.normalize_into(input, &mut out);
+        out
+    }
+
+    /// Normalize with the SIMD kernel when the `simd` feature is enabled
+    pub fn normalize_into(&self, input: &ModelInput, out: &mut ModelInput) {
+        #[cfg(feature = "simd")]
+        self.normalize_simd(input, out);
+        #[cfg(not(feature = "simd"))]
+        self.normalize_scalar(input, out);
+    }
+
+    /// Reference scalar kernel
+    pub fn normalize_scalar(&self, input: &ModelInput, out: &mut ModelInput) {
+        for (i, (y, x)) in out.iter_mut().zip(input).enumerate() {
+            let v = (x - self.offset[i]) * self.scale[i];
+            *y = if v.is_nan() { 0.0 } else { v.max(self.min[i]).min(self.max[i]) };
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    fn normalize_simd(&self, input: &ModelInput, out: &mut ModelInput) {
+        use wide::f32x8;
+
+        fn lane(values: &[f32; PADDED], i: usize) -> f32x8 {
+            let mut chunk = [0.0f32; LANES];
+            chunk.copy_from_slice(&values[i..i + LANES]);
+            f32x8::new(chunk)
+        }
+
+        let mut padded = [0.0f32; PADDED];
+        padded[..FeatureVector::FEATURE_COUNT].copy_from_slice(input);
+        let mut result = [0.0f32; PADDED];
+        for i in (0..PADDED).step_by(LANES) {
+            let v = (lane(&padded, i) - lane(&self.offset, i)) * lane(&self.scale, i);
+            // NaN lanes are replaced below, so the NaN-unaware clamp is safe
+            let clipped = v.fast_max(lane(&self.min, i)).fast_min(lane(&self.max, i));
+            result[i..i + LANES].copy_from_slice(&v.is_nan().blend(f32x8::ZERO, clipped).to_array());
+        }
+        out.copy_from_slice(&result[..FeatureVector::FEATURE_COUNT]);
+    }
+}
+
+impl Default for FeatureNormalizer {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl TryFrom<NormalizerSpec> for FeatureNormalizer {
+    type Error = SentinelError;
+
+    fn try_from(spec: NormalizerSpec) -> Result<Self> {
+        Self::new(&spec.features)
+    }
+}
+
+impl From<FeatureNormalizer> for NormalizerSpec {
+    fn from(normalizer: FeatureNormalizer) -> Self {
+        Self {
+            features: (0..FeatureVector::FEATURE_COUNT).filter_map(|i| normalizer.feature(i)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<ModelInput> {
+        (0..64)
+            .map(|n| {
+                let mut input = [0.0f32; FeatureVector::FEATURE_COUNT];
+                for (i, x) in input.iter_mut().enumerate() {
+                    *x = (n * (i + 1)) as f32 * 1_000.0 - 20_000.0;
+                }
+                input[7] = 42.0; // constant column
+                input
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_standardizes_and_clips() {
+        let normalizer = FeatureNormalizer::fit(&samples(), 3.0).unwrap();
+
+        let mut input = samples()[32];
+        input[0] = 1e30;
+        input[1] = f32::NAN;
+        input[2] = f32::NEG_INFINITY;
+        let out = normalizer.normalize(&input);
+        assert_eq!((out[0], out[1], out[2]), (3.0, 0.0, -3.0));
+        assert_eq!(out[7], 0.0);
+        assert!(out.iter().all(|v| (-3.0..=3.0).contains(v)));
+        assert!(out[10].abs() < 0.1, "middle sample is near the mean: {}", out[10]);
+    }
+
+    #[test]
+    fn test_dispatch_matches_scalar_bitwise() {
+        let normalizer = FeatureNormalizer::fit(&samples(), 2.5).unwrap();
+        for mut input in samples() {
+            input[3] = f32::NAN;
+            input[4] = f32::INFINITY;
+            let mut scalar = [0.0f32; FeatureVector::FEATURE_COUNT];
+            normalizer.normalize_scalar(&input, &mut scalar);
+            let dispatched = normalizer.normalize(&input);
+            assert!(scalar.iter().zip(&dispatched).all(|(a, b)| a.to_bits() == b.to_bits()));
+        }
+    }
+
+    #[test]
+    fn test_identity_and_round_trip() {
+        let features = FeatureVector {
+            jito_tip_lamports: 50_000,
+            has_swap_triplet: true,
+            ..Default::default()
+        };
+        let mut raw = [0.0f32; FeatureVector::FEATURE_COUNT];
+        features.write_into(&mut raw);
+        let mut out = [0.0f32; FeatureVector::FEATURE_COUNT];
+        FeatureNormalizer::identity().normalize_features(&features, &mut out);
+        assert_eq!(out, raw);
+
+        let fitted = FeatureNormalizer::fit(&samples(), 3.0).unwrap();
+        let json = serde_json::to_string(&fitted).unwrap();
+        assert_eq!(serde_json::from_str::<FeatureNormalizer>(&json).unwrap(), fitted);
+
+        let mut spec: NormalizerSpec = fitted.into();
+        spec.features[5].min = 1.0;
+        spec.features[5].max = -1.0;
+        assert!(FeatureNormalizer::try_from(spec).is_err());
+    }
+}
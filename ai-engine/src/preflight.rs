@@ -0,0 +1,1062 @@
+//! Pre-Trade Risk Check (`preflight`)
+//!
+//! Runs the routing pipeline up to, but not including, submission: quote the
+//! swap, build the candidate transaction, simulate it against current bank
+//! state, extract features from the simulated transaction and score it. The
+//! report carries the projected fill, the simulation result, the risk score
+//! and the route the router would pick.
+//!
+//! Features are extracted with `FeatureExtractor::extract_preview`, so a
+//! preflight never enters the swap history that triplet detection and market
+//! stats are built from.
+//!
+//! With a `TokenRiskScreener` attached, both mints are screened (fetching over
+//! RPC when not cached) and the result is included in the report. Every report
+//! carries a `SlippageAdvisor` recommendation from the pool depth, the pair's
+//! 24h volatility and the risk score. With a `LandingPredictor` attached, the
+//! report also carries the recommended route's landing probability. With an
+//! `AddressScreener` attached, the user and output destinations are screened
+//! before anything is quoted; in block mode a hit fails the preflight.
+//!
+//! Inside a [`Deadline::scope`] each stage checks the remaining budget first:
+//! extraction falls back to request-only features, scoring to heuristics and
+//! routing to the score alone, and the report lists the degraded stages.
+//!
+//! [`PreflightChecker::plan_passthrough`] runs the same pipeline over a Jupiter
+//! `/swap-instructions` passthrough request and, when it would execute, rebuilds
+//! the protected transaction for the chosen route with its tip. With a
+//! `ConsentGuard` attached, that transaction also brackets the swap with the
+//! guard program's instructions so `minimum_received` is enforced on-chain.
+//!
+//! [`PreflightChecker::explain`] answers `POST /api/v1/route/explain` with the
+//! full decision trace ([`RouteExplanation`]). Like a preflight it records
+//! nothing: scores come from `InferenceEngine::score_stateless`.
+
+use sentinel_core::dex::DexAggregator;
+use sentinel_core::preflight::{candidate_transaction, SIMULATION_COMPUTE_UNIT_LIMIT};
+use sentinel_core::{
+    estimate_sandwich_loss, system_clock, AddressScreener, ConsentGuard, Deadline, DeadlineCounters, DeadlineStats,
+    ExplainSubject, FeeSplitter, Intent, LandingContext, LandingPredictor, PairTokenRisk, PassthroughPlan,
+    PassthroughRequest, PipelineStage, PreflightReport, ProtectionTierSettings, Result, RouteCostInputs, RouteEconomics,
+    RouteExplainRequest, RouteExplanation, RouteType, SentinelError, SharedClock, SimulationOutcome,
+    SimulationStrictness, SlippageAdvisor, SlippageInputs, SlippageRecommendation, SlotRiskForecaster, SwapDetails,
+    ThresholdsInEffect, TokenRiskScreener, TransactionSimulator, UpcomingLeaders, VenueQuote,
+};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::features_enhanced::{FeatureExtractor, FeatureVector, SwapDetailsData, TransactionData};
+use crate::inference_pool::InferencePool;
+use crate::transaction_extractor::{decode_wire_transaction, extract_from_static_keys};
+
+/// Simulates and scores intents without executing them
+pub struct PreflightChecker {
+    dex: Arc<DexAggregator>,
+    simulator: TransactionSimulator,
+    pool: Arc<InferencePool>,
+    slot_risk: Arc<SlotRiskForecaster>,
+    extractor: Arc<Mutex<FeatureExtractor>>,
+    token_risk: Option<Arc<TokenRiskScreener>>,
+    /// `token_risk` still has to be installed on the extractor (it was locked)
+    token_risk_pending: AtomicBool,
+    slippage: SlippageAdvisor,
+    landing: Option<Arc<LandingPredictor>>,
+    fee_splitter: FeeSplitter,
+    economics: RouteEconomics,
+    protection_tiers: ProtectionTierSettings,
+    deadline: DeadlineCounters,
+    consent_guard: Option<ConsentGuard>,
+    address_screening: Option<Arc<AddressScreener>>,
+    clock: SharedClock,
+}
+
+impl PreflightChecker {
+    pub fn new(
+        dex: Arc<DexAggregator>,
+        simulator: TransactionSimulator,
+        pool: Arc<InferencePool>,
+        slot_risk: Arc<SlotRiskForecaster>,
+    ) -> Self {
+        Self {
+            dex,
+            simulator,
+            pool,
+            slot_risk,
+            extractor: Arc::new(Mutex::new(FeatureExtractor::new())),
+            token_risk: None,
+            token_risk_pending: AtomicBool::new(false),
+            slippage: SlippageAdvisor::default(),
+            landing: None,
+            fee_splitter: FeeSplitter::default(),
+            economics: RouteEconomics::default(),
+            protection_tiers: ProtectionTierSettings::default(),
+            deadline: DeadlineCounters::new(),
+            consent_guard: None,
+            address_screening: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Share the live extractor (swap history, validator intel, Pyth client)
+    ///
+    /// A screener from `with_token_risk` is installed on it, whichever builder
+    /// call comes first.
+    pub fn with_extractor(mut self, extractor: Arc<Mutex<FeatureExtractor>>) -> Self {
+        self.extractor = extractor;
+        self.attach_token_risk();
+        self
+    }
+
+    /// Screen swap mints for honeypot / rug risk
+    ///
+    /// The extractor (own or shared) gets the screener too, so
+    /// `is_high_risk_pair` feeds the risk score.
+    pub fn with_token_risk(mut self, screener: Arc<TokenRiskScreener>) -> Self {
+        self.token_risk = Some(screener);
+        self.attach_token_risk();
+        self
+    }
+
+    /// Install the token screener on the extractor
+    ///
+    /// A shared extractor may be locked by its owner right now; the screener is
+    /// then installed the next time a preflight locks it.
+    fn attach_token_risk(&self) {
+        if self.token_risk.is_none() {
+            return;
+        }
+        self.token_risk_pending.store(true, Ordering::Release);
+        if let Ok(mut extractor) = self.extractor.try_lock() {
+            self.install_pending_token_risk(&mut extractor);
+        } else {
+            debug!("Feature extractor is locked; token screener installed on its next use");
+        }
+    }
+
+    fn install_pending_token_risk(&self, extractor: &mut FeatureExtractor) {
+        if let Some(ref screener) = self.token_risk {
+            if self.token_risk_pending.swap(false, Ordering::AcqRel) {
+                extractor.set_token_risk(Arc::clone(screener));
+            }
+        }
+    }
+
+    pub fn with_slippage_advisor(mut self, advisor: SlippageAdvisor) -> Self {
+        self.slippage = advisor;
+        self
+    }
+
+    /// Report the recommended route's landing probability
+    pub fn with_landing_predictor(mut self, predictor: Arc<LandingPredictor>) -> Self {
+        self.landing = Some(predictor);
+        self
+    }
+
+    /// Split passthrough fee budgets between tip and priority fee (see `FeeSplitter::from_policy`)
+    pub fn with_fee_splitter(mut self, splitter: FeeSplitter) -> Self {
+        self.fee_splitter = splitter;
+        self
+    }
+
+    /// Route cost model explanations price candidates with
+    pub fn with_route_economics(mut self, economics: RouteEconomics) -> Self {
+        self.economics = economics;
+        self
+    }
+
+    /// Tier → tip percentile / route bias / simulation strictness mapping (`[protection_tiers]`)
+    pub fn with_protection_tiers(mut self, tiers: ProtectionTierSettings) -> Self {
+        self.protection_tiers = tiers;
+        self
+    }
+
+    /// Guard planned passthrough transactions with the on-chain consent program
+    pub fn with_consent_guard(mut self, guard: ConsentGuard) -> Self {
+        self.consent_guard = Some(guard);
+        self
+    }
+
+    /// Screen the user and output destinations for sanctioned counterparties first
+    pub fn with_address_screening(mut self, screener: Arc<AddressScreener>) -> Self {
+        self.address_screening = Some(screener);
+        self
+    }
+
+    /// Validate expiry and timestamp reports against `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Quote, build, simulate and score `intent` as if it were sent now
+    pub async fn check(&self, intent: &Intent, leaders: Option<&UpcomingLeaders>) -> Result<PreflightReport> {
+        intent.validate_at(self.clock.as_ref())?;
+        let swap = intent.swap_details.as_ref().ok_or_else(|| {
+            SentinelError::InvalidIntent("Preflight requires an intent with swap details".to_string())
+        })?;
+        // In block mode a flagged address ends preflight before anything is quoted
+        let screening = match self.address_screening {
+            Some(ref screener) => Some(screener.screen_intent(intent).await?),
+            None => None,
+        };
+        let (quote, transaction, simulation) = self.simulate_candidate(intent, swap).await?;
+
+        // Warm the cache `assess` reads; screening failures degrade to no token risk
+        if let Some(ref screener) = self.token_risk {
+            let now = self.clock.unix_timestamp();
+            if let Err(e) = screener.screen_pair(&swap.input_mint, &swap.output_mint, now).await {
+                warn!("Token risk screening failed for {}: {}", intent.intent_id, e);
+            }
+        }
+
+        // Depth only sharpens the slippage advice; the quote's impact is the fallback
+        let depth_utilization = self
+            .dex
+            .depth_utilization(swap, quote.in_amount)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Pool depth unavailable for {}: {}", intent.intent_id, e);
+                None
+            });
+
+        let mut report = self
+            .assess(intent, &quote, &transaction, simulation, depth_utilization, leaders)
+            .await?;
+        report.screening = screening;
+        Ok(report)
+    }
+
+    /// Quote the intent's swap, build the candidate transaction and simulate it
+    async fn simulate_candidate(
+        &self,
+        intent: &Intent,
+        swap: &SwapDetails,
+    ) -> Result<(VenueQuote, Transaction, SimulationOutcome)> {
+        let slippage_bps = intent.constraints.max_slippage_bps;
+
+        let venue = self.dex.venue_for(swap);
+        let quote = self.dex.quote_venue(venue, swap, slippage_bps).await?;
+        let instruction = self
+            .dex
+            .build_swap_instruction(&intent.user_public_key, swap, slippage_bps)
+            .await?;
+
+        let transaction = candidate_transaction(intent, instruction, SIMULATION_COMPUTE_UNIT_LIMIT);
+        let simulation = self.simulator.simulate(&transaction).await?;
+        Ok((quote, transaction, simulation))
+    }
+
+    /// Score an already simulated candidate transaction
+    pub async fn assess(
+        &self,
+        intent: &Intent,
+        quote: &VenueQuote,
+        transaction: &Transaction,
+        simulation: SimulationOutcome,
+        depth_utilization: Option<f64>,
+        leaders: Option<&UpcomingLeaders>,
+    ) -> Result<PreflightReport> {
+        let data = transaction_data(intent, quote, transaction, &simulation, leaders, self.clock.unix_millis());
+        let deadline = Deadline::current();
+        let mut degraded = Vec::new();
+
+        let features = match FeatureExtractor::lock_within(&self.extractor, deadline.as_ref()).await {
+            Some(mut extractor) => {
+                self.install_pending_token_risk(&mut extractor);
+                extractor.extract_preview(&data).await
+            }
+            None => {
+                self.deadline.record_fallback(PipelineStage::Extract);
+                degraded.push(PipelineStage::Extract);
+                FeatureVector::from_transaction(&data)
+            }
+        };
+        let score = match deadline {
+            // Counted by the engine
+            Some(ref deadline) if !deadline.allows(PipelineStage::Predict) => {
+                degraded.push(PipelineStage::Predict);
+                self.pool.predict_heuristic(&features)?.score
+            }
+            _ => self.pool.predict(&features)?,
+        };
+
+        let tier = intent.fee_preferences.protection_tier;
+        let profile = self.protection_tiers.profile(tier);
+
+        // A transaction that fails simulation would not be routed at all
+        let route = simulation.succeeded().then(|| {
+            let empty = UpcomingLeaders::new(simulation.slot, Vec::new());
+            let leaders = match deadline {
+                Some(ref deadline) if !deadline.allows(PipelineStage::Route) => {
+                    self.deadline.record_fallback(PipelineStage::Route);
+                    degraded.push(PipelineStage::Route);
+                    &empty
+                }
+                _ => leaders.unwrap_or(&empty),
+            };
+            let window = self.slot_risk.forecast(simulation.slot, leaders);
+            self.slot_risk.select_route_with_bias(score, &window, profile.route_bias)
+        });
+
+        debug!(
+            "Preflight {}: simulation {} risk {:.3} route {:?}",
+            intent.intent_id,
+            if simulation.succeeded() { "ok" } else { "failed" },
+            score.score(),
+            route
+        );
+
+        let now = self.clock.unix_timestamp();
+        let token_risk = self.token_risk.as_ref().zip(intent.swap_details.as_ref()).and_then(|(screener, swap)| {
+            screener.cached_pair(&swap.input_mint, &swap.output_mint, now)
+        });
+
+        let slippage = intent.swap_details.as_ref().map(|swap| {
+            let inputs = SlippageInputs {
+                mode: swap.mode,
+                quoted_in_amount: quote.in_amount,
+                quoted_out_amount: quote.out_amount,
+                price_impact_bps: quote.price_impact_bps,
+                depth_utilization,
+                volatility_24h_pct: features.volatility_24h_pct as f64,
+                risk_score: score.score(),
+            };
+            self.slippage.recommend(&inputs, intent.constraints.max_slippage_bps)
+        });
+
+        let strictness_failures = match route {
+            Some(_) => strictness_failures(profile.simulation, token_risk.as_ref(), slippage.as_ref(), &degraded),
+            None => Vec::new(),
+        };
+        let route = route.filter(|_| strictness_failures.is_empty());
+        let recommended_tip_lamports = route.as_ref().filter(|route| route.pays_tip()).and_then(|_| {
+            let percentiles = self.pool.engine().tip_percentiles();
+            (percentiles.samples > 0).then(|| {
+                percentiles
+                    .recommend_for(&score, profile)
+                    .min(intent.fee_preferences.max_jito_tip_lamports)
+            })
+        });
+
+        let landing_probability = self.landing.as_ref().zip(route.as_ref()).map(|(predictor, route)| {
+            let context = LandingContext {
+                tip_percentile: features.tip_percentile_vs_recent,
+                leader: (data.next_leader_pubkey != Pubkey::default()).then_some(data.next_leader_pubkey),
+                bundle_size: 1,
+            };
+            predictor.predict(route, &context)
+        });
+
+        let tolerance = 1.0 - intent.constraints.max_slippage_bps as f64 / 10_000.0;
+        Ok(PreflightReport {
+            intent_id: intent.intent_id.clone(),
+            projected_in_amount: quote.in_amount,
+            projected_out_amount: quote.out_amount,
+            minimum_out_amount: (quote.out_amount as f64 * tolerance) as u64,
+            price_impact_bps: quote.price_impact_bps,
+            simulation,
+            risk_score: score.score(),
+            risk_confidence: score.confidence(),
+            route,
+            token_risk,
+            slippage,
+            landing_probability,
+            degraded,
+            protection_tier: tier,
+            recommended_tip_lamports,
+            strictness_failures,
+            screening: None,
+        })
+    }
+
+    /// Extraction and routing fallbacks plus the pool's heuristic-only predictions
+    pub fn deadline_stats(&self) -> DeadlineStats {
+        let mut stats = self.deadline.stats();
+        stats.merge(&self.pool.deadline_stats());
+        stats
+    }
+}
+
+impl PreflightChecker {
+    /// Decision trace for the intent or raw transaction in `request`; nothing is submitted or recorded
+    pub async fn explain(
+        &self,
+        request: &RouteExplainRequest,
+        leaders: Option<&UpcomingLeaders>,
+    ) -> Result<RouteExplanation> {
+        match request.subject()? {
+            ExplainSubject::Intent(intent) => {
+                intent.validate_at(self.clock.as_ref())?;
+                let swap = intent.swap_details.as_ref().ok_or_else(|| {
+                    SentinelError::InvalidIntent("Route explain requires an intent with swap details".to_string())
+                })?;
+                let (quote, transaction, simulation) = self.simulate_candidate(intent, swap).await?;
+                self.explain_simulated(request, &quote, &transaction, simulation, leaders).await
+            }
+            ExplainSubject::Transaction(base64_tx) => {
+                let features = extract_from_static_keys(&decode_wire_transaction(base64_tx)?);
+                let slot = leaders.map_or(features.slot, |l| l.first_slot);
+                self.trace(request, features, slot, None, leaders)
+            }
+        }
+    }
+
+    /// [`Self::explain`] for an intent whose candidate transaction was already simulated
+    pub async fn explain_simulated(
+        &self,
+        request: &RouteExplainRequest,
+        quote: &VenueQuote,
+        transaction: &Transaction,
+        simulation: SimulationOutcome,
+        leaders: Option<&UpcomingLeaders>,
+    ) -> Result<RouteExplanation> {
+        let ExplainSubject::Intent(intent) = request.subject()? else {
+            return Err(SentinelError::InvalidIntent(
+                "Simulated explanations need an intent".to_string(),
+            ));
+        };
+        let data = transaction_data(intent, quote, transaction, &simulation, leaders, self.clock.unix_millis());
+        let mut extractor = self.extractor.lock().await;
+        self.install_pending_token_risk(&mut extractor);
+        let features = extractor.extract_preview(&data).await;
+        drop(extractor);
+        let slot = simulation.slot;
+        self.trace(request, features, slot, Some(simulation), leaders)
+    }
+
+    fn trace(
+        &self,
+        request: &RouteExplainRequest,
+        features: FeatureVector,
+        slot: u64,
+        simulation: Option<SimulationOutcome>,
+        leaders: Option<&UpcomingLeaders>,
+    ) -> Result<RouteExplanation> {
+        let engine = self.pool.engine();
+        let fused = engine.score_stateless(&features)?;
+        let rules = engine.heuristic_rules();
+        let signals = rules.fired(&features).into_iter().map(str::to_string).collect();
+
+        let empty = UpcomingLeaders::new(slot, Vec::new());
+        let window = self.slot_risk.forecast(slot, leaders.unwrap_or(&empty));
+        let routing_risk = self.slot_risk.routing_risk(fused.score, &window);
+        let failed = simulation.as_ref().is_some_and(|s| !s.succeeded());
+        let chosen_route = (!failed).then(|| self.slot_risk.select_route(fused.score, &window));
+
+        let slippage_bps = request
+            .intent
+            .as_ref()
+            .map_or(features.slippage_tolerance_bps as u16, |i| i.constraints.max_slippage_bps);
+        let notional = request.notional_for(features.input_amount as u64, features.output_amount as u64);
+        let candidates = notional.map_or_else(Vec::new, |notional_lamports| {
+            let inputs = RouteCostInputs {
+                notional_lamports,
+                sandwich_loss_lamports: estimate_sandwich_loss(
+                    notional_lamports,
+                    slippage_bps,
+                    features.price_impact_bps,
+                    fused.score.score(),
+                ),
+                tip_lamports: features.jito_tip_lamports,
+                landing_overrides: Vec::new(),
+            };
+            let inputs = match self.landing {
+                Some(ref predictor) => {
+                    let context = LandingContext {
+                        tip_percentile: features.tip_percentile_vs_recent,
+                        leader: leaders.and_then(|l| l.leader_at(slot)).copied(),
+                        bundle_size: 1,
+                    };
+                    inputs.with_landing_predictions(predictor, &context)
+                }
+                None => inputs,
+            };
+            self.economics.evaluate(&inputs)
+        });
+
+        let thresholds = ThresholdsInEffect {
+            slot_risk: self.slot_risk.settings(),
+            fusion: engine.fusion_settings(),
+            route_economics: self.economics.settings().clone(),
+            heuristic_rules_version: rules.version().to_string(),
+            heuristic_rules: serde_json::to_value(rules.to_rule_set())
+                .map_err(|e| SentinelError::SerializationError(format!("Failed to serialize rule set: {}", e)))?,
+        };
+        let features = if request.redact_features {
+            None
+        } else {
+            Some(serde_json::to_value(&features).map_err(|e| {
+                SentinelError::SerializationError(format!("Failed to serialize features: {}", e))
+            })?)
+        };
+
+        debug!(
+            "Route explain {}: risk {:.3} routing risk {:.3} route {:?}",
+            request.intent.as_ref().map_or("raw", |i| i.intent_id.as_str()),
+            fused.score.score(),
+            routing_risk,
+            chosen_route
+        );
+        Ok(RouteExplanation {
+            intent_id: request.intent.as_ref().map(|i| i.intent_id.clone()),
+            features,
+            risk_score: fused.score.score(),
+            risk_confidence: fused.score.confidence(),
+            contributions: fused.contributions(),
+            signals,
+            window_risk: window.risk,
+            routing_risk,
+            simulation,
+            candidates,
+            chosen_route,
+            thresholds,
+        })
+    }
+
+    /// Simulate, score and route a Jupiter passthrough request
+    ///
+    /// The candidate is simulated without a tip; the planned transaction adds
+    /// the chosen route's tip (paid to `tip_account`) for the wallet to sign.
+    pub async fn plan_passthrough(
+        &self,
+        request: &PassthroughRequest,
+        recent_blockhash: Hash,
+        tip_account: &Pubkey,
+        leaders: Option<&UpcomingLeaders>,
+    ) -> Result<PassthroughPlan> {
+        let intent = request.intent(recent_blockhash);
+        intent.validate_at(self.clock.as_ref())?;
+        let candidate = request.protected_transaction(&intent, &RouteType::StandardRpc, 0, tip_account)?;
+        let simulation = self.simulator.simulate(&candidate).await?;
+        self.plan_simulated_passthrough(request, intent, simulation, tip_account, leaders).await
+    }
+
+    /// [`Self::plan_passthrough`] from an existing simulation of the untipped candidate
+    pub async fn plan_simulated_passthrough(
+        &self,
+        request: &PassthroughRequest,
+        intent: Intent,
+        simulation: SimulationOutcome,
+        tip_account: &Pubkey,
+        leaders: Option<&UpcomingLeaders>,
+    ) -> Result<PassthroughPlan> {
+        let candidate = request.protected_transaction(&intent, &RouteType::StandardRpc, 0, tip_account)?;
+        let report = self
+            .assess(&intent, &request.quote(), &candidate, simulation, None, leaders)
+            .await?;
+        let Some(route) = report.route.clone() else {
+            if !report.strictness_failures.is_empty() {
+                return Err(SentinelError::InvalidIntent(format!(
+                    "Passthrough swap fails {:?} tier checks: {}",
+                    report.protection_tier,
+                    report.strictness_failures.join("; ")
+                )));
+            }
+            return Err(SentinelError::InvalidIntent(format!(
+                "Passthrough swap fails simulation: {}",
+                report.simulation.error.as_deref().unwrap_or("unknown error")
+            )));
+        };
+
+        let fees = &intent.fee_preferences;
+        let budget = fees.max_priority_fee_lamports + fees.max_jito_tip_lamports;
+        let fee_split = self.fee_splitter.split(fees, budget, report.risk_score, &route).split;
+        let tip_lamports = fee_split.jito_tip_lamports;
+        let transaction = match &self.consent_guard {
+            Some(guard) => request.guarded_transaction(&intent, &route, tip_lamports, tip_account, guard)?,
+            None => request.protected_transaction(&intent, &route, tip_lamports, tip_account)?,
+        };
+
+        debug!(
+            "Passthrough {}: risk {:.3} route {:?} tip {}",
+            intent.intent_id, report.risk_score, route, fee_split.jito_tip_lamports
+        );
+        Ok(PassthroughPlan {
+            intent,
+            report,
+            route,
+            fee_split,
+            transaction,
+            output_token_account: request.output_token_account,
+            minimum_received: request.minimum_received(),
+        })
+    }
+}
+
+/// Checks beyond a successful simulation that `strictness` requires, as failure reasons
+fn strictness_failures(
+    strictness: SimulationStrictness,
+    token_risk: Option<&PairTokenRisk>,
+    slippage: Option<&SlippageRecommendation>,
+    degraded: &[PipelineStage],
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    if strictness == SimulationStrictness::Basic {
+        return failures;
+    }
+    if token_risk.is_some_and(|risk| risk.is_high_risk) {
+        failures.push("high-risk token in the pair".to_string());
+    }
+    if strictness == SimulationStrictness::Strict {
+        if !degraded.is_empty() {
+            failures.push(format!("degraded stages {:?}", degraded));
+        }
+        if let Some(slippage) = slippage.filter(|s| s.capped_by_user) {
+            failures.push(format!("slippage below the recommended {} bps", slippage.max_slippage_bps));
+        }
+    }
+    failures
+}
+
+/// Feature-extraction input for the simulated candidate transaction
+fn transaction_data(
+    intent: &Intent,
+    quote: &VenueQuote,
+    transaction: &Transaction,
+    simulation: &SimulationOutcome,
+    leaders: Option<&UpcomingLeaders>,
+    now_ms: i64,
+) -> TransactionData {
+    let fees = &intent.fee_preferences;
+    let compute_unit_limit = simulation
+        .units_consumed
+        .map_or(SIMULATION_COMPUTE_UNIT_LIMIT, |units| units as u32);
+
+    // Quoted output already includes the quote's own impact; back it out for the pre-impact expectation
+    let output_amount = quote.out_amount as f64;
+    let expected_output = output_amount / (1.0 - (quote.price_impact_bps / 10_000.0).clamp(0.0, 0.99));
+
+    TransactionData {
+        slot: simulation.slot,
+        fee_payer: intent.user_public_key,
+        compute_unit_limit,
+        compute_unit_price: fees.max_priority_fee_lamports * 1_000_000 / compute_unit_limit.max(1) as u64,
+        jito_tip_lamports: fees.max_jito_tip_lamports,
+        total_fee_lamports: fees.max_priority_fee_lamports + fees.max_jito_tip_lamports,
+        account_count: transaction.message.account_keys.len() as u32,
+        instruction_count: transaction.message.instructions.len() as u32,
+        tx_size_bytes: bincode::serialized_size(transaction).unwrap_or_default() as u32,
+        swap_details: intent.swap_details.as_ref().map(|swap| SwapDetailsData {
+            input_mint: swap.input_mint,
+            output_mint: swap.output_mint,
+            input_amount: quote.in_amount as f64,
+            output_amount,
+            expected_output,
+            route_length: 1,
+            slippage_tolerance_bps: intent.constraints.max_slippage_bps as f64,
+            pool_liquidity_usd: 0.0,
+            pool: None,
+        }),
+        time_since_last_slot_ms: 0,
+        next_leader_pubkey: leaders
+            .and_then(|l| l.leader_at(simulation.slot))
+            .copied()
+            .unwrap_or_else(Pubkey::default),
+        uses_lookup_tables: false,
+        timestamp_ms: now_ms as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use sentinel_core::{
+        Constraints, DexVenue, FeePreferences, ManualClock, MintHoldings, ProtectionTier, SlotRiskSettings,
+    };
+    use solana_sdk::instruction::Instruction;
+
+    fn checker() -> PreflightChecker {
+        PreflightChecker::new(
+            Arc::new(DexAggregator::new()),
+            TransactionSimulator::new("http://127.0.0.1:8899").unwrap(),
+            Arc::new(InferencePool::new(ModelConfig::default(), 1).unwrap()),
+            Arc::new(SlotRiskForecaster::new(SlotRiskSettings::default())),
+        )
+    }
+
+    fn intent() -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            constraints: Constraints {
+                max_slippage_bps: 100,
+                ..Constraints::default()
+            },
+            ..Intent::test_swap()
+        };
+        intent.test_swap_details().minimum_received = None;
+        intent
+    }
+
+    fn simulated(error: Option<&str>) -> SimulationOutcome {
+        SimulationOutcome {
+            slot: 1_000,
+            error: error.map(str::to_string),
+            units_consumed: Some(120_000),
+            logs: vec![],
+        }
+    }
+
+    fn candidate(intent: &Intent) -> Transaction {
+        let swap = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; 16], vec![]);
+        candidate_transaction(intent, swap, SIMULATION_COMPUTE_UNIT_LIMIT)
+    }
+
+    #[tokio::test]
+    async fn test_assess_projects_fill_and_routes() {
+        let checker = checker();
+        let intent = intent();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+
+        let report = checker
+            .assess(&intent, &quote, &candidate(&intent), simulated(None), None, None)
+            .await
+            .unwrap();
+
+        assert!(report.would_execute());
+        assert_eq!(report.projected_out_amount, 5_000_000);
+        assert_eq!(report.minimum_out_amount, 4_950_000);
+        assert!((0.0..=1.0).contains(&report.risk_score));
+        assert!(report.route.is_some());
+        assert_eq!(report.token_risk, None);
+        assert_eq!(report.landing_probability, None);
+
+        let slippage = report.slippage.unwrap();
+        assert!(slippage.max_slippage_bps <= 100);
+        assert!(slippage.minimum_received.unwrap() >= report.minimum_out_amount);
+    }
+
+    #[tokio::test]
+    async fn test_spent_deadline_degrades_every_stage() {
+        let checker = checker();
+        let intent = intent();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+
+        let roomy = Deadline::after(std::time::Duration::from_secs(5))
+            .scope(checker.assess(&intent, &quote, &candidate(&intent), simulated(None), None, None))
+            .await
+            .unwrap();
+        assert!(roomy.degraded.is_empty());
+
+        let spent = Deadline::at(std::time::Instant::now())
+            .scope(checker.assess(&intent, &quote, &candidate(&intent), simulated(None), None, None))
+            .await
+            .unwrap();
+        assert_eq!(
+            spent.degraded,
+            vec![PipelineStage::Extract, PipelineStage::Predict, PipelineStage::Route]
+        );
+        assert!(spent.route.is_some() && spent.risk_confidence.is_none());
+
+        let stats = checker.deadline_stats();
+        assert_eq!(
+            (stats.extract_fallbacks, stats.predict_fallbacks, stats.route_fallbacks),
+            (1, 1, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_simulation_has_no_route() {
+        let checker = checker();
+        let intent = intent();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+
+        let report = checker
+            .assess(&intent, &quote, &candidate(&intent), simulated(Some("InsufficientFunds")), None, None)
+            .await
+            .unwrap();
+
+        assert!(!report.would_execute());
+        assert_eq!(report.route, None);
+    }
+
+    #[tokio::test]
+    async fn test_report_includes_landing_probability() {
+        let checker = checker().with_landing_predictor(Arc::new(LandingPredictor::default()));
+        let intent = intent();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+
+        let report = checker
+            .assess(&intent, &quote, &candidate(&intent), simulated(None), None, None)
+            .await
+            .unwrap();
+        let probability = report.landing_probability.unwrap();
+        assert!((0.0..=1.0).contains(&probability));
+
+        let failed = checker
+            .assess(&intent, &quote, &candidate(&intent), simulated(Some("InsufficientFunds")), None, None)
+            .await
+            .unwrap();
+        assert_eq!(failed.landing_probability, None);
+    }
+
+    #[tokio::test]
+    async fn test_report_includes_cached_token_risk() {
+        let screener = Arc::new(TokenRiskScreener::new("http://127.0.0.1:8899").unwrap());
+        // Cache freshness is judged by the checker's clock, not the wall clock
+        let now = 1_700_000_000;
+        // No pre-wired extractor: the checker's own must see the screener
+        let checker = checker()
+            .with_token_risk(Arc::clone(&screener))
+            .with_clock(Arc::new(ManualClock::at_timestamp(now)));
+        let intent = intent();
+        let swap = intent.swap_details.clone().unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+
+        let renounced = MintHoldings {
+            mint_authority: None,
+            freeze_authority: None,
+            supply: 1_000,
+            top_balances: vec![100],
+        };
+        let honeypot = MintHoldings {
+            freeze_authority: Some(Pubkey::new_unique().to_string()),
+            mint_authority: Some(Pubkey::new_unique().to_string()),
+            ..renounced.clone()
+        };
+        screener.insert_holdings(swap.input_mint, renounced, now);
+        screener.insert_holdings(swap.output_mint, honeypot, now);
+
+        let tx = candidate(&intent);
+        let report = checker.assess(&intent, &quote, &tx, simulated(None), None, None).await.unwrap();
+
+        let token_risk = report.token_risk.unwrap();
+        assert!(token_risk.is_high_risk);
+        assert!(token_risk.output.freeze_authority_active);
+
+        let data = transaction_data(&intent, &quote, &tx, &simulated(None), None, 0);
+        let features = checker.extractor.lock().await.extract_preview(&data).await;
+        assert!(features.is_high_risk_pair);
+    }
+
+    #[tokio::test]
+    async fn test_token_risk_reaches_shared_extractor_in_either_order() {
+        let screener = Arc::new(TokenRiskScreener::new("http://127.0.0.1:8899").unwrap());
+        let now = 1_700_000_000;
+        let intent = intent();
+        let swap = intent.swap_details.clone().unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+        let renounced = MintHoldings {
+            mint_authority: None,
+            freeze_authority: None,
+            supply: 1_000,
+            top_balances: vec![100],
+        };
+        let honeypot = MintHoldings {
+            mint_authority: Some(Pubkey::new_unique().to_string()),
+            freeze_authority: Some(Pubkey::new_unique().to_string()),
+            ..renounced.clone()
+        };
+        screener.insert_holdings(swap.input_mint, renounced, now);
+        screener.insert_holdings(swap.output_mint, honeypot, now);
+        let tx = candidate(&intent);
+        let data = transaction_data(&intent, &quote, &tx, &simulated(None), None, now * 1_000);
+
+        // The extractor stays shared with its owner, so it can't be unwrapped
+        for screener_first in [true, false] {
+            let shared = Arc::new(Mutex::new(FeatureExtractor::new()));
+            let checker = if screener_first {
+                checker().with_token_risk(Arc::clone(&screener)).with_extractor(Arc::clone(&shared))
+            } else {
+                checker().with_extractor(Arc::clone(&shared)).with_token_risk(Arc::clone(&screener))
+            };
+            drop(checker);
+            let features = shared.lock().await.extract_preview(&data).await;
+            assert!(features.is_high_risk_pair, "screener first: {}", screener_first);
+        }
+
+        // Locked by its owner while the checker is built: installed on the checker's next use
+        let shared = Arc::new(Mutex::new(FeatureExtractor::new()));
+        let held = shared.lock().await;
+        let checker = checker()
+            .with_extractor(Arc::clone(&shared))
+            .with_token_risk(Arc::clone(&screener))
+            .with_clock(Arc::new(ManualClock::at_timestamp(now)));
+        drop(held);
+        let report = checker.assess(&intent, &quote, &tx, simulated(None), None, None).await.unwrap();
+        assert!(report.token_risk.unwrap().is_high_risk);
+        let features = shared.lock().await.extract_preview(&data).await;
+        assert!(features.is_high_risk_pair);
+    }
+
+    #[tokio::test]
+    async fn test_max_tier_withholds_route_on_risky_token() {
+        let screener = Arc::new(TokenRiskScreener::new("http://127.0.0.1:8899").unwrap());
+        let now = 1_700_000_000;
+        let checker = checker()
+            .with_token_risk(Arc::clone(&screener))
+            .with_clock(Arc::new(ManualClock::at_timestamp(now)));
+        let mut intent = intent();
+        let swap = intent.swap_details.clone().unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+        let renounced = MintHoldings {
+            mint_authority: None,
+            freeze_authority: None,
+            supply: 1_000,
+            top_balances: vec![100],
+        };
+        let honeypot = MintHoldings {
+            mint_authority: Some(Pubkey::new_unique().to_string()),
+            freeze_authority: Some(Pubkey::new_unique().to_string()),
+            ..renounced.clone()
+        };
+        screener.insert_holdings(swap.input_mint, renounced, now);
+        screener.insert_holdings(swap.output_mint, honeypot, now);
+
+        let tx = candidate(&intent);
+        let standard = checker.assess(&intent, &quote, &tx, simulated(None), None, None).await.unwrap();
+        assert_eq!(standard.protection_tier, ProtectionTier::Standard);
+        assert!(standard.route.is_some() && standard.strictness_failures.is_empty());
+        // No tip history yet
+        assert_eq!(standard.recommended_tip_lamports, None);
+
+        intent.fee_preferences.protection_tier = ProtectionTier::Max;
+        let max = checker.assess(&intent, &quote, &tx, simulated(None), None, None).await.unwrap();
+        assert!(max.would_execute());
+        assert_eq!(max.route, None);
+        assert_eq!(max.strictness_failures, vec!["high-risk token in the pair".to_string()]);
+    }
+
+    #[test]
+    fn test_strictness_levels() {
+        let degraded = [PipelineStage::Predict];
+        assert!(strictness_failures(SimulationStrictness::Basic, None, None, &degraded).is_empty());
+        assert!(strictness_failures(SimulationStrictness::Screened, None, None, &degraded).is_empty());
+        assert_eq!(strictness_failures(SimulationStrictness::Strict, None, None, &degraded).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_preflight_does_not_record_history() {
+        let checker = checker();
+        let intent = intent();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+        let tx = candidate(&intent);
+
+        checker.assess(&intent, &quote, &tx, simulated(None), None, None).await.unwrap();
+        let data = transaction_data(&intent, &quote, &tx, &simulated(None), None, 0);
+        let features = checker.extractor.lock().await.extract_preview(&data).await;
+        assert_eq!(features.recent_swaps_same_actor, 0);
+        assert_eq!(features.recent_swaps_same_pair, 0);
+    }
+
+    #[tokio::test]
+    async fn test_passthrough_plan_tips_chosen_route() {
+        let checker = checker();
+        let user = Pubkey::new_unique();
+        let swap_instructions = serde_json::from_value(serde_json::json!({
+            "swapInstruction": {
+                "programId": Pubkey::new_unique().to_string(),
+                "accounts": [{ "pubkey": user.to_string(), "isSigner": true, "isWritable": true }],
+                "data": "AQI=",
+            }
+        }))
+        .unwrap();
+        let request = PassthroughRequest {
+            user_public_key: user,
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            in_amount: 1_000_000,
+            quoted_out_amount: 5_000_000,
+            price_impact_bps: 20.0,
+            output_token_account: Pubkey::new_unique(),
+            minimum_received: None,
+            constraints: Constraints { max_slippage_bps: 100, ..Constraints::default() },
+            fee_preferences: FeePreferences::default(),
+            compute_unit_limit: None,
+            swap_instructions,
+        };
+        let tip_account = Pubkey::new_unique();
+
+        let intent = request.intent(Hash::new_unique());
+        let plan = checker
+            .plan_simulated_passthrough(&request, intent.clone(), simulated(None), &tip_account, None)
+            .await
+            .unwrap();
+        assert_eq!(plan.minimum_received, 4_950_000);
+        assert_eq!(plan.report.route, Some(plan.route.clone()));
+        assert_eq!(plan.transaction.message.account_keys[0], user);
+        assert_eq!(
+            plan.transaction.message.account_keys.contains(&tip_account),
+            plan.route.pays_tip() && plan.fee_split.jito_tip_lamports > 0
+        );
+
+        let err = checker
+            .plan_simulated_passthrough(&request, intent, simulated(Some("InsufficientFunds")), &tip_account, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("InsufficientFunds"));
+
+        // With a guard the plan carries the on-chain bracket for the same intent
+        let guard = ConsentGuard::new(Pubkey::new_unique());
+        let intent = request.intent(Hash::new_unique());
+        let plan = checker
+            .with_consent_guard(guard)
+            .plan_simulated_passthrough(&request, intent.clone(), simulated(None), &tip_account, None)
+            .await
+            .unwrap();
+        let terms = sentinel_core::GuardTerms::for_intent(&intent, request.output_token_account).unwrap();
+        assert_eq!(terms.minimum_received, 4_950_000);
+        guard.check_message(&plan.transaction.message, &terms).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_explain_traces_decision_without_recording() {
+        let checker = checker();
+        let mut intent = intent();
+        intent.swap_details.as_mut().unwrap().input_mint =
+            std::str::FromStr::from_str(sentinel_core::route_explain::WRAPPED_SOL_MINT).unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 5_000_000, 0, 20.0);
+        let tx = candidate(&intent);
+        let request = RouteExplainRequest::for_intent(intent.clone());
+
+        let explanation = checker
+            .explain_simulated(&request, &quote, &tx, simulated(None), None)
+            .await
+            .unwrap();
+        assert_eq!(explanation.intent_id.as_deref(), Some("intent-1"));
+        assert_eq!(explanation.features.as_ref().unwrap()["input_amount"], 1_000_000.0);
+        let contributions = &explanation.contributions;
+        let total = contributions.heuristic + contributions.model + contributions.adaptive;
+        assert!((total - explanation.risk_score).abs() < 1e-4);
+        assert!(explanation.chosen_route.is_some());
+        assert!(!explanation.candidates.is_empty());
+        assert!(explanation
+            .candidates
+            .windows(2)
+            .all(|w| w[0].expected_net_cost <= w[1].expected_net_cost));
+        assert_eq!(explanation.thresholds.slot_risk, SlotRiskSettings::default());
+        assert_eq!(explanation.thresholds.heuristic_rules_version, "builtin");
+        assert_eq!(checker.pool.engine().fusion_stats().decisions, 0);
+
+        let redacted = request.clone().with_redacted_features();
+        let redacted = checker
+            .explain_simulated(&redacted, &quote, &tx, simulated(Some("Custom(1)")), None)
+            .await
+            .unwrap();
+        assert!(redacted.features.is_none());
+        assert_eq!(redacted.chosen_route, None);
+    }
+
+    #[tokio::test]
+    async fn test_explain_raw_transaction() {
+        use base64::engine::general_purpose::STANDARD as BASE64;
+        use base64::Engine;
+
+        let checker = checker();
+        let tx = BASE64.encode(bincode::serialize(&candidate(&intent())).unwrap());
+
+        let unpriced = checker.explain(&RouteExplainRequest::for_transaction(tx.clone()), None).await.unwrap();
+        assert_eq!((unpriced.intent_id, unpriced.simulation), (None, None));
+        assert!(unpriced.chosen_route.is_some() && unpriced.candidates.is_empty());
+
+        let request = RouteExplainRequest::for_transaction(tx).with_notional_lamports(1_000_000_000);
+        let priced = checker.explain(&request, None).await.unwrap();
+        assert!(!priced.candidates.is_empty());
+        assert!(checker.explain(&RouteExplainRequest::default(), None).await.is_err());
+    }
+}
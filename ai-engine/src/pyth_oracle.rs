@@ -1,10 +1,16 @@
 use reqwest::Client;
-use sentinel_core::{Result, SentinelError};
+#[cfg(feature = "chaos")]
+use sentinel_core::{ChaosInjector, Fault};
+use sentinel_core::{CircuitBreaker, Heartbeat, OracleSettings, Result, SentinelError};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+/// Nominal slot time, for prices Hermes returns without a slot
+const SLOT_MS: u64 = 400;
+
 /// Pyth oracle client for real-time price feeds via HTTP API
 pub struct PythOracleClient {
     http_client: Client,
@@ -12,6 +18,10 @@ pub struct PythOracleClient {
     price_feed_ids: HashMap<String, String>,
     cache: HashMap<String, CachedPrice>,
     cache_ttl: Duration,
+    breaker: Option<Arc<CircuitBreaker>>,
+    heartbeat: Option<Heartbeat>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<ChaosInjector>>,
 }
 
 impl PythOracleClient {
@@ -39,9 +49,32 @@ impl PythOracleClient {
             price_feed_ids,
             cache: HashMap::new(),
             cache_ttl: Duration::from_secs(cache_ttl_secs),
+            breaker: None,
+            heartbeat: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
 
+    /// Fail fast with `CircuitOpen` while Hermes keeps failing
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Beat with each fetched price's publish time (readiness `FreshnessCheck`)
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Inject oracle timeouts into fetches (inside the circuit breaker)
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<ChaosInjector>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
     /// Create client for Pyth Hermes API
     pub fn hermes_devnet() -> Self {
         Self::new(
@@ -70,13 +103,10 @@ impl PythOracleClient {
             self.api_endpoint, feed_id
         );
 
-        let response = self.http_client.get(&url).send().await.map_err(|e| {
-            SentinelError::PriceOracleError(format!("Failed to fetch price: {}", e))
-        })?;
-
-        let price_response: PythPriceResponse = response.json().await.map_err(|e| {
-            SentinelError::PriceOracleError(format!("Failed to parse price response: {}", e))
-        })?;
+        let price_response = match self.breaker {
+            Some(ref breaker) => breaker.call(self.fetch(&url)).await?,
+            None => self.fetch(&url).await?,
+        };
 
         let parsed_price = price_response.parsed.first().ok_or_else(|| {
             SentinelError::PriceOracleError("No price data in response".to_string())
@@ -91,8 +121,13 @@ impl PythOracleClient {
             conf: price_update.conf.parse::<f64>().unwrap_or(0.0) * 10_f64.powi(price_update.expo),
             expo: price_update.expo,
             publish_time: price_update.publish_time,
+            slot: parsed_price.metadata.as_ref().and_then(|metadata| metadata.slot),
         };
 
+        if let Some(ref heartbeat) = self.heartbeat {
+            heartbeat.beat(price_data.publish_time * 1_000);
+        }
+
         // Update cache
         self.cache.insert(
             symbol.to_string(),
@@ -110,6 +145,21 @@ impl PythOracleClient {
         Ok(price_data)
     }
 
+    async fn fetch(&self, url: &str) -> Result<PythPriceResponse> {
+        #[cfg(feature = "chaos")]
+        if let Some(ref chaos) = self.chaos {
+            chaos.inject(Fault::OracleTimeout).await?;
+        }
+
+        let response = self.http_client.get(url).send().await.map_err(|e| {
+            SentinelError::PriceOracleError(format!("Failed to fetch price: {}", e))
+        })?;
+
+        response.json().await.map_err(|e| {
+            SentinelError::PriceOracleError(format!("Failed to parse price response: {}", e))
+        })
+    }
+
     /// Calculate price impact for a swap
     pub async fn calculate_price_impact(
         &mut self,
@@ -162,6 +212,46 @@ pub struct PriceData {
     pub conf: f64, // Confidence interval
     pub expo: i32,
     pub publish_time: i64,
+    /// Slot the price was published in, when Hermes reports it
+    pub slot: Option<u64>,
+}
+
+impl PriceData {
+    /// Confidence interval as a fraction of the price (infinite for a non-positive price)
+    pub fn confidence_ratio(&self) -> f64 {
+        if self.price > 0.0 {
+            self.conf / self.price
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Slots this price lags `current_slot`, from the publish time when the slot is unknown
+    pub fn staleness_slots(&self, current_slot: u64, now_ms: u64) -> u64 {
+        match self.slot {
+            Some(slot) => current_slot.saturating_sub(slot),
+            None => now_ms.saturating_sub(self.publish_time.max(0) as u64 * 1_000) / SLOT_MS,
+        }
+    }
+
+    /// Why this price must not be used, None when it is within the bounds
+    pub fn rejection(&self, settings: &OracleSettings, current_slot: u64, now_ms: u64) -> Option<String> {
+        let ratio = self.confidence_ratio();
+        if ratio.is_nan() || ratio > settings.max_confidence_ratio {
+            return Some(format!(
+                "{} confidence {:.4} of price exceeds {}",
+                self.symbol, ratio, settings.max_confidence_ratio
+            ));
+        }
+        let staleness = self.staleness_slots(current_slot, now_ms);
+        if staleness > settings.max_staleness_slots {
+            return Some(format!(
+                "{} price is {} slots old (limit {})",
+                self.symbol, staleness, settings.max_staleness_slots
+            ));
+        }
+        None
+    }
 }
 
 struct CachedPrice {
@@ -180,6 +270,14 @@ struct ParsedPrice {
     #[allow(dead_code)] // Required for deserialization
     id: String,
     price: PriceInfo,
+    #[serde(default)]
+    metadata: Option<PriceMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceMetadata {
+    #[serde(default)]
+    slot: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -200,4 +298,37 @@ mod tests {
         assert!(client.price_feed_ids.contains_key("SOL/USD"));
         assert_eq!(client.api_endpoint, "https://hermes.pyth.network");
     }
+
+    #[test]
+    fn test_price_gated_on_confidence_and_staleness() {
+        let settings = OracleSettings::default();
+        let price = PriceData {
+            symbol: "SOL/USD".to_string(),
+            price: 150.0,
+            conf: 0.15,
+            expo: -8,
+            publish_time: 1_700_000_000,
+            slot: Some(1_000),
+        };
+        assert_eq!(price.rejection(&settings, 1_010, 0), None);
+
+        let wide = PriceData { conf: 6.0, ..price.clone() };
+        assert!(wide.rejection(&settings, 1_010, 0).unwrap().contains("confidence"));
+        assert!(price.rejection(&settings, 1_026, 0).unwrap().contains("26 slots old"));
+
+        // Without a slot, the publish time is aged at 400ms per slot
+        let unslotted = PriceData { slot: None, ..price };
+        assert_eq!(unslotted.staleness_slots(0, 1_700_000_004_000), 10);
+        assert!(unslotted.rejection(&settings, 0, 1_700_000_020_000).is_some());
+    }
+
+    #[test]
+    fn test_hermes_metadata_slot_parsed() {
+        let response: PythPriceResponse = serde_json::from_str(
+            r#"{"parsed": [{"id": "ef0d", "price": {"price": "15000000000", "conf": "1500000", "expo": -8,
+                "publish_time": 1700000000}, "metadata": {"slot": 250000000}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.parsed[0].metadata.as_ref().unwrap().slot, Some(250_000_000));
+    }
 }
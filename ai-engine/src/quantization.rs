@@ -0,0 +1,386 @@
+//! Int8 Model Input Pipeline
+//!
+//! Raw features span up to twelve orders of magnitude (lamports, slots, USD
+//! volume), which int8-quantized models cannot represent directly. The
+//! quantized pipeline calibrates a per-feature range on sample data, then for
+//! every prediction:
+//! 1. log-compresses heavy-tailed features (`sign(x)·ln(1+|x|)`)
+//! 2. clips to the calibrated range and scales to [0, 1]
+//! 3. snaps to the int8 grid (`scale = 1/255`, `zero_point = -128`)
+//!
+//! Int8 models are exported with that input quantization, so the backend sees
+//! exactly the values `QuantizeLinear` would produce. Calibration is stored next
+//! to the model as `<model>.quant.json`; [`QuantizedBackend`] wraps any backend
+//! with the pipeline. [`compare`] measures latency and decision agreement
+//! between an fp32 and an int8 backend (see `benches/quantization.rs`).
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use sentinel_core::{Result, SentinelError};
+
+use crate::features_enhanced::FeatureVector;
+use crate::model_backend::{ModelBackend, ModelInput};
+
+/// Features whose calibrated magnitude exceeds this are log-compressed
+const LOG_SCALE_THRESHOLD: f32 = 1_000.0;
+
+/// Calibrated range of one feature (in the transformed domain)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeatureRange {
+    pub min: f32,
+    pub max: f32,
+    pub log_scale: bool,
+}
+
+impl FeatureRange {
+    fn transform(&self, x: f32) -> f32 {
+        if self.log_scale {
+            x.signum() * x.abs().ln_1p()
+        } else {
+            x
+        }
+    }
+
+    fn inverse(&self, t: f32) -> f32 {
+        if self.log_scale {
+            t.signum() * t.abs().exp_m1()
+        } else {
+            t
+        }
+    }
+
+    /// Position of `x` within the range, clipped to [0, 1]
+    fn normalize(&self, x: f32) -> f32 {
+        let span = self.max - self.min;
+        if !x.is_finite() || span <= 0.0 {
+            return 0.0;
+        }
+        ((self.transform(x) - self.min) / span).clamp(0.0, 1.0)
+    }
+}
+
+/// Per-feature calibration for the int8 input pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureQuantizer {
+    pub ranges: Vec<FeatureRange>,
+}
+
+impl FeatureQuantizer {
+    /// Calibrate ranges from sample inputs, clipping `clip_percentile` (0-50) at each tail
+    pub fn calibrate(samples: &[ModelInput], clip_percentile: f32) -> Result<Self> {
+        if samples.is_empty() {
+            return Err(SentinelError::InferenceError(
+                "Quantization calibration needs at least one sample".to_string(),
+            ));
+        }
+
+        let clip = clip_percentile.clamp(0.0, 50.0) / 100.0;
+        let ranges = (0..FeatureVector::FEATURE_COUNT)
+            .map(|i| {
+                let mut column: Vec<f32> = samples.iter().map(|s| s[i]).filter(|x| x.is_finite()).collect();
+                column.sort_by(f32::total_cmp);
+
+                let log_scale = column.iter().any(|x| x.abs() > LOG_SCALE_THRESHOLD);
+                let range = FeatureRange { min: 0.0, max: 0.0, log_scale };
+                let (lo, hi) = if column.is_empty() {
+                    (0.0, 0.0)
+                } else {
+                    (percentile(&column, clip), percentile(&column, 1.0 - clip))
+                };
+                FeatureRange {
+                    min: range.transform(lo),
+                    max: range.transform(hi),
+                    ..range
+                }
+            })
+            .collect();
+
+        Ok(Self { ranges })
+    }
+
+    /// Calibration file stored alongside a model
+    pub fn sidecar_path(model_path: &Path) -> PathBuf {
+        let mut name = model_path.as_os_str().to_owned();
+        name.push(".quant.json");
+        PathBuf::from(name)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| SentinelError::InferenceError(format!("Failed to read {:?}: {}", path, e)))?;
+        let quantizer: Self = serde_json::from_str(&json)
+            .map_err(|e| SentinelError::InferenceError(format!("Invalid quantization file: {}", e)))?;
+        if quantizer.ranges.len() != FeatureVector::FEATURE_COUNT {
+            return Err(SentinelError::InferenceError(format!(
+                "Quantization file has {} ranges, expected {}",
+                quantizer.ranges.len(),
+                FeatureVector::FEATURE_COUNT
+            )));
+        }
+        Ok(quantizer)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| SentinelError::IoError(e.to_string()))
+    }
+
+    /// Int8 codes for a feature vector
+    pub fn quantize(&self, features: &ModelInput) -> [i8; FeatureVector::FEATURE_COUNT] {
+        let mut out = [0i8; FeatureVector::FEATURE_COUNT];
+        for ((q, x), range) in out.iter_mut().zip(features).zip(&self.ranges) {
+            *q = ((range.normalize(*x) * 255.0).round() - 128.0) as i8;
+        }
+        out
+    }
+
+    /// Int8 model input: quantized codes dequantized to [0, 1]
+    pub fn normalize(&self, features: &ModelInput) -> ModelInput {
+        let mut out = [0.0f32; FeatureVector::FEATURE_COUNT];
+        for (value, q) in out.iter_mut().zip(self.quantize(features)) {
+            *value = (f32::from(q) + 128.0) / 255.0;
+        }
+        out
+    }
+
+    /// Map quantized codes back to raw feature units (measures precision loss
+    /// when feeding an fp32 model)
+    pub fn round_trip(&self, features: &ModelInput) -> ModelInput {
+        let mut out = [0.0f32; FeatureVector::FEATURE_COUNT];
+        for ((value, u), range) in out.iter_mut().zip(self.normalize(features)).zip(&self.ranges) {
+            *value = range.inverse(range.min + u * (range.max - range.min));
+        }
+        out
+    }
+}
+
+fn percentile(sorted: &[f32], q: f32) -> f32 {
+    let idx = ((sorted.len() - 1) as f32 * q).round() as usize;
+    sorted[idx]
+}
+
+/// Backend fed through the int8 input pipeline
+pub struct QuantizedBackend {
+    inner: Box<dyn ModelBackend>,
+    quantizer: FeatureQuantizer,
+
+    /// Feed raw-unit round-tripped values (fp32 model) instead of [0, 1] codes
+    simulated: bool,
+}
+
+impl QuantizedBackend {
+    /// Int8 model expecting normalized [0, 1] inputs
+    pub fn new(inner: Box<dyn ModelBackend>, quantizer: FeatureQuantizer) -> Self {
+        Self { inner, quantizer, simulated: false }
+    }
+
+    /// Fp32 model fed int8-precision inputs in raw units, to measure the
+    /// accuracy cost of quantization before an int8 export exists
+    pub fn simulated(inner: Box<dyn ModelBackend>, quantizer: FeatureQuantizer) -> Self {
+        Self { inner, quantizer, simulated: true }
+    }
+
+    pub fn quantizer(&self) -> &FeatureQuantizer {
+        &self.quantizer
+    }
+}
+
+impl ModelBackend for QuantizedBackend {
+    fn name(&self) -> &'static str {
+        match self.inner.name() {
+            "onnx" => "onnx-int8",
+            "tensor_rt" => "tensor_rt-int8",
+            "gbdt" => "gbdt-int8",
+            _ => "int8",
+        }
+    }
+
+    fn predict(&self, features: &ModelInput) -> Result<f32> {
+        if self.simulated {
+            self.inner.predict(&self.quantizer.round_trip(features))
+        } else {
+            self.inner.predict(&self.quantizer.normalize(features))
+        }
+    }
+}
+
+/// fp32 vs int8 comparison on a sample set
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuantizationReport {
+    pub samples: usize,
+    pub fp32_p50_us: f64,
+    pub fp32_p99_us: f64,
+    pub int8_p50_us: f64,
+    pub int8_p99_us: f64,
+    pub mean_abs_error: f64,
+    pub max_abs_error: f64,
+
+    /// Share of samples where both models agree on `score >= threshold`
+    pub decision_agreement: f64,
+
+    /// Label accuracy of each model (if labels were given)
+    pub fp32_accuracy: Option<f64>,
+    pub int8_accuracy: Option<f64>,
+}
+
+impl QuantizationReport {
+    /// Whether int8 fits the latency SLO with acceptable agreement
+    pub fn is_safe(&self, slo_ms: f64, min_agreement: f64) -> bool {
+        self.int8_p99_us <= slo_ms * 1_000.0 && self.decision_agreement >= min_agreement
+    }
+}
+
+/// Run both backends over `samples` and compare latency and decisions
+pub fn compare(
+    fp32: &dyn ModelBackend,
+    int8: &dyn ModelBackend,
+    samples: &[ModelInput],
+    labels: Option<&[bool]>,
+    threshold: f32,
+) -> Result<QuantizationReport> {
+    if samples.is_empty() {
+        return Err(SentinelError::InferenceError("No samples to compare".to_string()));
+    }
+
+    let (fp32_scores, mut fp32_us) = timed_scores(fp32, samples)?;
+    let (int8_scores, mut int8_us) = timed_scores(int8, samples)?;
+
+    let errors: Vec<f64> = fp32_scores
+        .iter()
+        .zip(&int8_scores)
+        .map(|(a, b)| f64::from((a - b).abs()))
+        .collect();
+    let agree = fp32_scores
+        .iter()
+        .zip(&int8_scores)
+        .filter(|(a, b)| (**a >= threshold) == (**b >= threshold))
+        .count();
+
+    let accuracy = |scores: &[f32]| {
+        labels.map(|labels| {
+            let correct = scores.iter().zip(labels).filter(|(s, l)| (**s >= threshold) == **l).count();
+            correct as f64 / scores.len() as f64
+        })
+    };
+
+    let n = samples.len() as f64;
+    Ok(QuantizationReport {
+        samples: samples.len(),
+        fp32_p50_us: latency_percentile(&mut fp32_us, 0.50),
+        fp32_p99_us: latency_percentile(&mut fp32_us, 0.99),
+        int8_p50_us: latency_percentile(&mut int8_us, 0.50),
+        int8_p99_us: latency_percentile(&mut int8_us, 0.99),
+        mean_abs_error: errors.iter().sum::<f64>() / n,
+        max_abs_error: errors.iter().copied().fold(0.0, f64::max),
+        decision_agreement: agree as f64 / n,
+        fp32_accuracy: accuracy(&fp32_scores),
+        int8_accuracy: accuracy(&int8_scores),
+    })
+}
+
+fn timed_scores(backend: &dyn ModelBackend, samples: &[ModelInput]) -> Result<(Vec<f32>, Vec<f64>)> {
+    let mut scores = Vec::with_capacity(samples.len());
+    let mut latencies = Vec::with_capacity(samples.len());
+    for sample in samples {
+        let start = Instant::now();
+        scores.push(backend.predict(sample)?);
+        latencies.push(start.elapsed().as_secs_f64() * 1e6);
+    }
+    Ok((scores, latencies))
+}
+
+fn latency_percentile(values: &mut [f64], q: f64) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let idx = ((values.len() - 1) as f64 * q).round() as usize;
+    values[idx]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Identity(usize);
+
+    impl ModelBackend for Identity {
+        fn name(&self) -> &'static str {
+            "gbdt"
+        }
+
+        fn predict(&self, features: &ModelInput) -> Result<f32> {
+            Ok((features[self.0] / 1_000_000.0).clamp(0.0, 1.0))
+        }
+    }
+
+    fn samples() -> Vec<ModelInput> {
+        (0..100)
+            .map(|i| {
+                let mut x = [0.0f32; FeatureVector::FEATURE_COUNT];
+                x[3] = (i * 10_000) as f32;
+                x[12] = i as f32;
+                x
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_calibration_and_quantized_domain() {
+        let q = FeatureQuantizer::calibrate(&samples(), 0.0).unwrap();
+        assert!(q.ranges[3].log_scale);
+        assert!(!q.ranges[12].log_scale);
+
+        let mut x = [0.0f32; FeatureVector::FEATURE_COUNT];
+        x[12] = 99.0;
+        x[3] = 1e12; // clipped to calibrated max
+        let codes = q.quantize(&x);
+        assert_eq!(codes[12], 127);
+        assert_eq!(codes[3], 127);
+        assert_eq!(codes[0], -128); // constant column
+
+        let normalized = q.normalize(&x);
+        assert!(normalized.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+
+    #[test]
+    fn test_round_trip_precision() {
+        let q = FeatureQuantizer::calibrate(&samples(), 0.0).unwrap();
+        let mut x = [0.0f32; FeatureVector::FEATURE_COUNT];
+        x[12] = 42.0;
+        x[3] = 250_000.0;
+        let back = q.round_trip(&x);
+        assert!((back[12] - 42.0).abs() < 0.5);
+        assert!((back[3] - 250_000.0).abs() / 250_000.0 < 0.05);
+    }
+
+    #[test]
+    fn test_compare_reports_agreement() {
+        let data = samples();
+        let q = FeatureQuantizer::calibrate(&data, 0.0).unwrap();
+        let fp32 = Identity(3);
+
+        let int8 = QuantizedBackend::simulated(Box::new(Identity(3)), q);
+        assert_eq!(int8.name(), "gbdt-int8");
+
+        let labels: Vec<bool> = data.iter().map(|x| x[3] >= 500_000.0).collect();
+        let report = compare(&fp32, &int8, &data, Some(&labels), 0.5).unwrap();
+        assert_eq!(report.samples, 100);
+        assert!(report.decision_agreement > 0.95);
+        assert_eq!(report.fp32_accuracy, Some(1.0));
+        assert!(report.is_safe(50.0, 0.95));
+    }
+
+    #[test]
+    fn test_sidecar_round_trip() {
+        let q = FeatureQuantizer::calibrate(&samples(), 1.0).unwrap();
+        let model = std::env::temp_dir().join(format!("model-{}.onnx", uuid::Uuid::new_v4()));
+        let sidecar = FeatureQuantizer::sidecar_path(&model);
+        assert!(sidecar.to_string_lossy().ends_with(".onnx.quant.json"));
+
+        q.save(&sidecar).unwrap();
+        let loaded = FeatureQuantizer::load(&sidecar).unwrap();
+        std::fs::remove_file(&sidecar).ok();
+        assert_eq!(loaded, q);
+    }
+}
@@ -0,0 +1,301 @@
+//! Deterministic Decision Replay
+//!
+//! When a routing decision changes between two builds, or between two days,
+//! the question is whether code, config or data caused it. Decisions logged
+//! with [`record_replay_inputs`] carry the feature snapshot, leader-window risk,
+//! config fingerprint and engine build. [`DecisionReplayer`] re-scores the
+//! snapshot statelessly with this build's engine under a given config, routes
+//! it with that config's slot-risk bands and diffs score and route against the
+//! record:
+//! - **reproduced**: same score (within `tolerance`) and route; the code and
+//!   config did not change this decision, so differences from other decisions
+//!   come from their data
+//! - **config changed**: the result differs and the replay config's
+//!   fingerprint is not the one recorded
+//! - **code changed**: the result differs under the recorded config
+//! - **not replayable**: the record has no (readable) feature snapshot
+//!
+//! Replay with the recorded config to isolate code changes, or with this build
+//! and a new config to isolate config changes. The `sentinel-replay` binary
+//! runs it over a decision log.
+
+use sentinel_core::{
+    DecisionRecord, Result, RouteType, SentinelConfig, SentinelError, SlotRiskForecaster, WindowRisk,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::features_enhanced::FeatureVector;
+use crate::inference_enhanced::InferenceEngine;
+
+/// Build identifier stamped on decisions and replays
+pub const ENGINE_BUILD: &str = concat!("ai-engine/", env!("CARGO_PKG_VERSION"));
+
+/// Default score difference still counted as reproduced
+pub const DEFAULT_SCORE_TOLERANCE: f32 = 1e-4;
+
+/// Attach the replay inputs of a live decision
+pub fn record_replay_inputs(
+    record: DecisionRecord,
+    features: &FeatureVector,
+    window: &WindowRisk,
+    config_version: &str,
+) -> Result<DecisionRecord> {
+    let features = serde_json::to_value(features)
+        .map_err(|e| SentinelError::SerializationError(format!("Failed to serialize features: {}", e)))?;
+    Ok(record.with_replay_inputs(features, window.risk, config_version.to_string(), ENGINE_BUILD.to_string()))
+}
+
+/// What a replay says about a recorded decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayVerdict {
+    Reproduced,
+    ConfigChanged,
+    CodeChanged,
+    NotReplayable,
+}
+
+/// Recorded vs replayed result of one decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    pub request_id: String,
+    pub verdict: ReplayVerdict,
+    pub recorded_score: f32,
+    pub recorded_route: RouteType,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replayed_score: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replayed_route: Option<RouteType>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_config_version: Option<String>,
+    pub replay_config_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_build: Option<String>,
+    pub replay_build: String,
+
+    /// Why the decision could not be replayed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReplayOutcome {
+    /// Replayed minus recorded score
+    pub fn score_delta(&self) -> Option<f32> {
+        self.replayed_score.map(|score| score - self.recorded_score)
+    }
+}
+
+/// Outcomes of a decision-log replay
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub outcomes: Vec<ReplayOutcome>,
+    pub verdicts: BTreeMap<ReplayVerdict, usize>,
+}
+
+/// Re-executes recorded decisions with this build's engine
+pub struct DecisionReplayer {
+    engine: Arc<InferenceEngine>,
+    slot_risk: SlotRiskForecaster,
+    config_version: String,
+    tolerance: f32,
+}
+
+impl DecisionReplayer {
+    /// Replay under `config` with a warmed-up engine built from it
+    pub fn new(config: &SentinelConfig) -> Result<Self> {
+        let mut engine = InferenceEngine::from_config(config)?;
+        engine.warmup()?;
+        Ok(Self::with_engine(config, Arc::new(engine)))
+    }
+
+    /// Replay under `config` with an existing warmed-up engine (which should run `config`)
+    pub fn with_engine(config: &SentinelConfig, engine: Arc<InferenceEngine>) -> Self {
+        Self {
+            engine,
+            slot_risk: SlotRiskForecaster::from_config(config),
+            config_version: config.fingerprint(),
+            tolerance: DEFAULT_SCORE_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn config_version(&self) -> &str {
+        &self.config_version
+    }
+
+    pub fn replay(&self, record: &DecisionRecord) -> ReplayOutcome {
+        let mut outcome = ReplayOutcome {
+            request_id: record.request_id.clone(),
+            verdict: ReplayVerdict::NotReplayable,
+            recorded_score: record.risk_score,
+            recorded_route: record.route.clone(),
+            replayed_score: None,
+            replayed_route: None,
+            recorded_config_version: record.config_version.clone(),
+            replay_config_version: self.config_version.clone(),
+            recorded_build: record.engine_build.clone(),
+            replay_build: ENGINE_BUILD.to_string(),
+            error: None,
+        };
+
+        let features = match record.features.clone().map(serde_json::from_value::<FeatureVector>) {
+            Some(Ok(features)) => features,
+            Some(Err(e)) => {
+                outcome.error = Some(format!("unreadable feature snapshot: {}", e));
+                return outcome;
+            }
+            None => {
+                outcome.error = Some("no feature snapshot recorded".to_string());
+                return outcome;
+            }
+        };
+        let fused = match self.engine.score_stateless(&features) {
+            Ok(fused) => fused,
+            Err(e) => {
+                outcome.error = Some(e.to_string());
+                return outcome;
+            }
+        };
+
+        let window = WindowRisk {
+            risk: record.window_risk.unwrap_or(0.0),
+            landing_probability: 1.0,
+            latency_slots: 0.0,
+            leaders: Vec::new(),
+        };
+        let route = self.slot_risk.select_route(fused.score, &window);
+        let score = fused.score.score();
+
+        let reproduced = (score - record.risk_score).abs() <= self.tolerance && route == record.route;
+        outcome.verdict = if reproduced {
+            ReplayVerdict::Reproduced
+        } else if record.config_version.as_deref() != Some(self.config_version.as_str()) {
+            ReplayVerdict::ConfigChanged
+        } else {
+            ReplayVerdict::CodeChanged
+        };
+        outcome.replayed_score = Some(score);
+        outcome.replayed_route = Some(route);
+        outcome
+    }
+
+    /// Replay every decision in a JSONL decision log (optionally one request)
+    ///
+    /// Only the first record of a request is the decision; later ones carry
+    /// outcomes. Malformed lines are skipped.
+    pub fn replay_log(&self, path: impl AsRef<Path>, request_id: Option<&str>) -> Result<ReplayReport> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to open {}: {}", path.display(), e)))?;
+
+        let mut seen = HashSet::new();
+        let mut report = ReplayReport::default();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| SentinelError::IoError(e.to_string()))?;
+            let Ok(record) = serde_json::from_str::<DecisionRecord>(&line) else {
+                continue;
+            };
+            if request_id.is_some_and(|id| id != record.request_id) || !seen.insert(record.request_id.clone()) {
+                continue;
+            }
+            let outcome = self.replay(&record);
+            *report.verdicts.entry(outcome.verdict).or_default() += 1;
+            report.outcomes.push(outcome);
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features() -> FeatureVector {
+        FeatureVector {
+            jito_tip_lamports: 2_000_000,
+            price_impact_bps: 150.0,
+            slippage_tolerance_bps: 500.0,
+            ..Default::default()
+        }
+    }
+
+    /// A decision as the live pipeline would log it
+    fn decide(config: &SentinelConfig, engine: &InferenceEngine, window_risk: f32) -> DecisionRecord {
+        let features = features();
+        let fused = engine.score_stateless(&features).unwrap();
+        let window = WindowRisk {
+            risk: window_risk,
+            landing_probability: 1.0,
+            latency_slots: 0.0,
+            leaders: Vec::new(),
+        };
+        let route = SlotRiskForecaster::from_config(config).select_route(fused.score, &window);
+        let record = DecisionRecord::new("req-1".to_string(), 0.0, route).with_score(&fused.score);
+        record_replay_inputs(record, &features, &window, &config.fingerprint()).unwrap()
+    }
+
+    #[test]
+    fn test_attributes_code_config_and_data() {
+        let config = SentinelConfig::default();
+        let replayer = DecisionReplayer::new(&config).unwrap();
+        let mut engine = InferenceEngine::from_config(&config).unwrap();
+        engine.warmup().unwrap();
+
+        // Same build, same config: reproduced exactly
+        let record = decide(&config, &engine, 0.95);
+        let outcome = replayer.replay(&record);
+        assert_eq!(outcome.verdict, ReplayVerdict::Reproduced, "{:?}", outcome);
+        assert_eq!(outcome.score_delta(), Some(0.0));
+        assert_eq!(outcome.recorded_build.as_deref(), Some(ENGINE_BUILD));
+
+        // Routing bands moved: the route changes under the new config
+        let mut moved = config.clone();
+        moved.slot_risk.jito_bundle_threshold = 0.99;
+        moved.slot_risk.jito_single_threshold = 0.98;
+        let outcome = DecisionReplayer::new(&moved).unwrap().replay(&record);
+        assert_eq!(outcome.verdict, ReplayVerdict::ConfigChanged);
+        assert_eq!(outcome.replayed_route, Some(RouteType::StandardRpc));
+
+        // A different score under the recorded config points at the code
+        let mut scored_elsewhere = record.clone();
+        scored_elsewhere.risk_score += 0.2;
+        assert_eq!(replayer.replay(&scored_elsewhere).verdict, ReplayVerdict::CodeChanged);
+
+        let bare = DecisionRecord::new("req-2".to_string(), 0.5, RouteType::JitoSingle);
+        assert_eq!(replayer.replay(&bare).verdict, ReplayVerdict::NotReplayable);
+    }
+
+    #[test]
+    fn test_replay_log_takes_first_record_per_request() {
+        let config = SentinelConfig::default();
+        let replayer = DecisionReplayer::new(&config).unwrap();
+        let mut engine = InferenceEngine::from_config(&config).unwrap();
+        engine.warmup().unwrap();
+
+        let decision = decide(&config, &engine, 0.0);
+        let label = DecisionRecord::new("req-1".to_string(), 0.0, RouteType::StandardRpc).with_mev_incident(false);
+        let lines = [
+            serde_json::to_string(&decision).unwrap(),
+            "not json".to_string(),
+            serde_json::to_string(&label).unwrap(),
+        ];
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let report = replayer.replay_log(&path, None).unwrap();
+        assert_eq!(report.outcomes.len(), 1);
+        assert_eq!(report.verdicts[&ReplayVerdict::Reproduced], 1);
+        assert!(replayer.replay_log(&path, Some("req-9")).unwrap().outcomes.is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,369 @@
+//! Sandwich Profitability Model
+//!
+//! Whether a swap gets sandwiched is an economic question: an attacker buys
+//! ahead of the victim, lets the victim push the price further, and sells back.
+//! [`SandwichModel`] solves the attacker's problem for a victim swap against a
+//! pool state:
+//!
+//! - the front-run may be at most as large as keeps the victim's output at or
+//!   above `minimum_out` (larger and the victim reverts, the attack fails);
+//!   that bound is found by bisection, since victim output falls monotonically
+//!   with front-run size
+//! - within it, attacker profit is maximized by golden-section search (profit
+//!   is unimodal: fees on both legs eventually outweigh the extra extraction)
+//!
+//! Pools are modeled as constant product on (virtual) reserves. For a
+//! concentrated-liquidity pool inside one tick range the virtual reserves are
+//! `L/√P` and `L·√P`, and the range boundary caps the input the pool can
+//! absorb. Amounts are in the victim's input token throughout.
+//!
+//! The victim probability maps net profit (after the attacker's tip and fees)
+//! relative to the victim's size through `1 - exp(-net / reference)`: no
+//! profit → 0, a profit of `reference_profit_bps` of the trade → ~0.63.
+
+use serde::{Deserialize, Serialize};
+
+/// Bisection / golden-section iterations (well below f64 resolution by the end)
+const SEARCH_ITERATIONS: usize = 100;
+
+/// Pool state the victim swaps against, oriented input → output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PoolState {
+    /// (Virtual) reserve of the victim's input token
+    pub reserve_in: f64,
+
+    /// (Virtual) reserve of the victim's output token
+    pub reserve_out: f64,
+
+    pub fee_bps: u32,
+
+    /// Input the pool can absorb before leaving its liquidity range (CLMM)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_input: Option<f64>,
+}
+
+impl PoolState {
+    /// x·y = k pool (Raydium AMM v4 / CPMM, Orca legacy)
+    pub fn constant_product(reserve_in: f64, reserve_out: f64, fee_bps: u32) -> Self {
+        Self {
+            reserve_in,
+            reserve_out,
+            fee_bps,
+            max_input: None,
+        }
+    }
+
+    /// Concentrated-liquidity pool within its current tick range
+    ///
+    /// `sqrt_price` is √(token1 per token0); `input_is_token0` gives the swap
+    /// direction (token0 in pushes the price toward `sqrt_price_lower`).
+    pub fn concentrated(
+        liquidity: f64,
+        sqrt_price: f64,
+        sqrt_price_lower: f64,
+        sqrt_price_upper: f64,
+        fee_bps: u32,
+        input_is_token0: bool,
+    ) -> Self {
+        let reserve0 = liquidity / sqrt_price;
+        let reserve1 = liquidity * sqrt_price;
+        if input_is_token0 {
+            Self {
+                reserve_in: reserve0,
+                reserve_out: reserve1,
+                fee_bps,
+                max_input: Some(liquidity * (1.0 / sqrt_price_lower - 1.0 / sqrt_price).max(0.0)),
+            }
+        } else {
+            Self {
+                reserve_in: reserve1,
+                reserve_out: reserve0,
+                fee_bps,
+                max_input: Some(liquidity * (sqrt_price_upper - sqrt_price).max(0.0)),
+            }
+        }
+    }
+
+    fn fee_multiplier(&self) -> f64 {
+        1.0 - self.fee_bps.min(10_000) as f64 / 10_000.0
+    }
+
+    /// Output for `amount_in` and the pool afterwards (input side)
+    pub fn swap_in(&self, amount_in: f64) -> (f64, PoolState) {
+        let effective = amount_in.max(0.0) * self.fee_multiplier();
+        let out = effective * self.reserve_out / (self.reserve_in + effective);
+        let after = PoolState {
+            reserve_in: self.reserve_in + effective,
+            reserve_out: self.reserve_out - out,
+            max_input: self.max_input.map(|max| (max - amount_in).max(0.0)),
+            ..*self
+        };
+        (out, after)
+    }
+
+    /// Input-token received for selling `amount_out_token` back (reverse direction)
+    pub fn swap_back(&self, amount_out_token: f64) -> (f64, PoolState) {
+        let effective = amount_out_token.max(0.0) * self.fee_multiplier();
+        let received = effective * self.reserve_in / (self.reserve_out + effective);
+        let after = PoolState {
+            reserve_in: self.reserve_in - received,
+            reserve_out: self.reserve_out + effective,
+            ..*self
+        };
+        (received, after)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.reserve_in > 0.0 && self.reserve_out > 0.0 && self.reserve_in.is_finite() && self.reserve_out.is_finite()
+    }
+}
+
+/// Victim swap under evaluation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VictimSwap {
+    pub amount_in: f64,
+
+    /// Output below which the victim's transaction reverts
+    pub minimum_out: f64,
+}
+
+impl VictimSwap {
+    /// Minimum from the quote and slippage tolerance
+    pub fn with_slippage(amount_in: f64, quoted_out: f64, slippage_bps: f64) -> Self {
+        Self {
+            amount_in,
+            minimum_out: quoted_out * (1.0 - slippage_bps.clamp(0.0, 10_000.0) / 10_000.0),
+        }
+    }
+}
+
+/// The attacker's best response
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SandwichOpportunity {
+    /// Optimal front-run size (input token)
+    pub front_run_in: f64,
+
+    /// Gross attacker profit before costs (input token)
+    pub gross_profit: f64,
+
+    /// Profit after `attacker_cost`
+    pub net_profit: f64,
+
+    /// Output the victim loses to the sandwich
+    pub victim_loss: f64,
+
+    /// Victim output when sandwiched
+    pub victim_out: f64,
+
+    /// Probability the swap is sandwiched (0.0-1.0)
+    pub probability: f32,
+}
+
+/// Model tuning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SandwichModelSettings {
+    /// Net profit (bps of victim input) at which the probability reaches 1 - 1/e
+    pub reference_profit_bps: f64,
+
+    /// Front-run capital ceiling as a multiple of victim size (flash loans are cheap)
+    pub max_capital_multiple: f64,
+}
+
+impl Default for SandwichModelSettings {
+    fn default() -> Self {
+        Self {
+            reference_profit_bps: 10.0,
+            max_capital_multiple: 100.0,
+        }
+    }
+}
+
+/// Attacker-optimal sandwich solver
+#[derive(Debug, Clone, Default)]
+pub struct SandwichModel {
+    settings: SandwichModelSettings,
+}
+
+impl SandwichModel {
+    pub fn new(settings: SandwichModelSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Attacker profit (input token) for a front-run of `front_run_in`
+    ///
+    /// None when the victim would revert (no sandwich possible at that size).
+    pub fn profit_at(&self, pool: &PoolState, victim: &VictimSwap, front_run_in: f64) -> Option<(f64, f64)> {
+        let (bought, after_front) = pool.swap_in(front_run_in);
+        let (victim_out, after_victim) = after_front.swap_in(victim.amount_in);
+        if victim_out < victim.minimum_out {
+            return None;
+        }
+        let (received, _) = after_victim.swap_back(bought);
+        Some((received - front_run_in, victim_out))
+    }
+
+    /// Solve for the attacker's optimal front-run
+    ///
+    /// `attacker_cost` covers tip and fees (input token). None when the pool
+    /// is unusable or the victim trade itself does not fit in the pool.
+    pub fn evaluate(&self, pool: &PoolState, victim: &VictimSwap, attacker_cost: f64) -> Option<SandwichOpportunity> {
+        if !pool.is_valid() || victim.amount_in <= 0.0 {
+            return None;
+        }
+        let capacity = pool.max_input.unwrap_or(f64::INFINITY);
+        if victim.amount_in > capacity {
+            return None;
+        }
+        let (clean_out, _) = pool.swap_in(victim.amount_in);
+        if clean_out < victim.minimum_out {
+            return None;
+        }
+
+        // Largest front-run the victim's minimum tolerates
+        let mut lo = 0.0;
+        let mut hi = (victim.amount_in * self.settings.max_capital_multiple).min(capacity - victim.amount_in);
+        if self.profit_at(pool, victim, hi).is_none() {
+            for _ in 0..SEARCH_ITERATIONS {
+                let mid = (lo + hi) / 2.0;
+                if self.profit_at(pool, victim, mid).is_some() {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            hi = lo;
+        }
+
+        // Best size within [0, hi]
+        let profit = |f: f64| self.profit_at(pool, victim, f).map_or(f64::NEG_INFINITY, |(p, _)| p);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut a, mut b) = (0.0, hi);
+        for _ in 0..SEARCH_ITERATIONS {
+            let c = b - ratio * (b - a);
+            let d = a + ratio * (b - a);
+            if profit(c) < profit(d) {
+                a = c;
+            } else {
+                b = d;
+            }
+        }
+        let candidate = (a + b) / 2.0;
+        // The constraint boundary is often optimal; golden-section only approaches it
+        let front_run_in = if profit(hi) >= profit(candidate) { hi } else { candidate };
+
+        let (gross_profit, victim_out) = self.profit_at(pool, victim, front_run_in)?;
+        let net_profit = gross_profit - attacker_cost.max(0.0);
+        Some(SandwichOpportunity {
+            front_run_in,
+            gross_profit,
+            net_profit,
+            victim_loss: clean_out - victim_out,
+            victim_out,
+            probability: self.probability(net_profit, victim.amount_in),
+        })
+    }
+
+    /// `1 - exp(-net / reference)` with reference = `reference_profit_bps` of the trade
+    pub fn probability(&self, net_profit: f64, victim_amount_in: f64) -> f32 {
+        if net_profit <= 0.0 || victim_amount_in <= 0.0 {
+            return 0.0;
+        }
+        let reference = victim_amount_in * self.settings.reference_profit_bps.max(f64::EPSILON) / 10_000.0;
+        (1.0 - (-net_profit / reference).exp()).clamp(0.0, 1.0) as f32
+    }
+
+    /// Probability only; 0 when the victim cannot be sandwiched at all
+    pub fn victim_probability(&self, pool: &PoolState, victim: &VictimSwap, attacker_cost: f64) -> f32 {
+        self.evaluate(pool, victim, attacker_cost)
+            .map_or(0.0, |opportunity| opportunity.probability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> PoolState {
+        // 1M / 1M pool, 25 bps fee
+        PoolState::constant_product(1_000_000.0, 1_000_000.0, 25)
+    }
+
+    fn victim(amount_in: f64, slippage_bps: f64) -> VictimSwap {
+        let (quoted, _) = pool().swap_in(amount_in);
+        VictimSwap::with_slippage(amount_in, quoted, slippage_bps)
+    }
+
+    #[test]
+    fn test_constant_product_swap() {
+        let pool = PoolState::constant_product(1_000.0, 1_000.0, 0);
+        let (out, after) = pool.swap_in(1_000.0);
+        assert!((out - 500.0).abs() < 1e-9);
+        assert!((after.reserve_in * after.reserve_out - 1_000_000.0).abs() < 1e-6);
+
+        // Selling back what was bought returns the input (no fee)
+        let (back, _) = after.swap_back(out);
+        assert!((back - 1_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tight_slippage_is_safe() {
+        let model = SandwichModel::default();
+        // No tolerance leaves no room for a front-run at all
+        let opportunity = model.evaluate(&pool(), &victim(10_000.0, 0.0), 0.0).unwrap();
+        assert!(opportunity.front_run_in < 1e-6, "{:?}", opportunity);
+        assert!(opportunity.probability < 1e-6);
+
+        // A 1 bp-impact trade moves the price less than the attacker's two fee legs
+        assert_eq!(model.victim_probability(&pool(), &victim(100.0, 10.0), 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_loose_slippage_is_exploited_up_to_the_minimum() {
+        let model = SandwichModel::default();
+        let victim = victim(10_000.0, 300.0);
+        let opportunity = model.evaluate(&pool(), &victim, 5.0).unwrap();
+
+        assert!(opportunity.front_run_in > 0.0);
+        assert!(opportunity.gross_profit > 100.0, "{:?}", opportunity);
+        assert!(opportunity.net_profit < opportunity.gross_profit);
+        // The attacker pushes the victim to (almost exactly) their minimum
+        assert!(opportunity.victim_out >= victim.minimum_out);
+        assert!((opportunity.victim_out - victim.minimum_out) / victim.minimum_out < 1e-3);
+        assert!(opportunity.probability > 0.9);
+
+        // No size beats the solver's choice
+        for f in [0.25, 0.5, 0.75].map(|s| s * opportunity.front_run_in) {
+            let (profit, _) = model.profit_at(&pool(), &victim, f).unwrap();
+            assert!(profit <= opportunity.gross_profit + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_probability_grows_with_slippage_and_cost_reduces_it() {
+        let model = SandwichModel::default();
+        let p = |slippage, cost| model.victim_probability(&pool(), &victim(10_000.0, slippage), cost);
+
+        assert!(p(50.0, 0.0) < p(100.0, 0.0));
+        assert!(p(100.0, 0.0) <= p(300.0, 0.0));
+        assert!(p(100.0, 20.0) < p(100.0, 0.0));
+        assert_eq!(p(100.0, 1e9), 0.0);
+    }
+
+    #[test]
+    fn test_concentrated_pool_virtual_reserves() {
+        // √P = 1 → price 1; L = 1M gives 1M/1M virtual reserves
+        let clmm = PoolState::concentrated(1_000_000.0, 1.0, 0.9, 1.1, 25, true);
+        assert!((clmm.reserve_in - 1_000_000.0).abs() < 1e-6);
+        assert!((clmm.reserve_out - 1_000_000.0).abs() < 1e-6);
+        // Token0 in until √P hits 0.9: L·(1/0.9 - 1)
+        assert!((clmm.max_input.unwrap() - 111_111.111).abs() < 1e-2);
+
+        let model = SandwichModel::default();
+        let loose = victim(10_000.0, 300.0);
+        let opportunity = model.evaluate(&clmm, &loose, 0.0).unwrap();
+        assert!(opportunity.front_run_in + loose.amount_in <= clmm.max_input.unwrap() + 1e-6);
+
+        // A victim larger than the range cannot be evaluated as one swap
+        assert!(model.evaluate(&clmm, &VictimSwap { amount_in: 200_000.0, minimum_out: 0.0 }, 0.0).is_none());
+    }
+}
@@ -0,0 +1,298 @@
+//! Score Fusion
+//!
+//! Combines the three risk signals the engine computes into one score instead
+//! of picking one of them:
+//! - **heuristic**: static production heuristics
+//! - **model**: model backend probability (absent without a loaded model or
+//!   in heuristics-only mode)
+//! - **adaptive**: multi-stage adaptive pipeline score, weighted by its confidence
+//!
+//! Two strategies, selected by `[fusion]` config:
+//! - weighted average (`heuristic_weight`, `model_weight`, `adaptive_weight × confidence`),
+//!   renormalized over the components present
+//! - logistic meta-model (`meta_model`): `σ(bias + Σ coefᵢ·xᵢ)`; a missing model
+//!   score is replaced by the heuristic score
+//!
+//! Every fused score names the component that contributed most, and a sliding
+//! window of recent decisions reports how often each component dominated.
+//!
+//! The fused score carries a confidence: the adaptive pipeline's own
+//! confidence, discounted by how far the present components disagree
+//! (`confidence × (1 - (max - min))`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use sentinel_core::{FusionSettings, MevRiskScore, ScoreContributions};
+
+/// Signal contributing to a fused score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreComponent {
+    Heuristic,
+    Model,
+    Adaptive,
+}
+
+impl ScoreComponent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Heuristic => "heuristic",
+            Self::Model => "model",
+            Self::Adaptive => "adaptive",
+        }
+    }
+}
+
+/// Raw component scores for one prediction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComponentScores {
+    pub heuristic: f32,
+    pub model: Option<f32>,
+    pub adaptive: f32,
+
+    /// Adaptive pipeline confidence (0-1)
+    pub adaptive_confidence: f32,
+}
+
+/// Fused score with per-component contributions
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FusedScore {
+    pub score: MevRiskScore,
+
+    /// Component with the largest contribution
+    pub dominant: ScoreComponent,
+
+    /// Contribution of each component (weighted share, or |coef·x| for the meta-model)
+    pub heuristic_contribution: f32,
+    pub model_contribution: f32,
+    pub adaptive_contribution: f32,
+}
+
+impl FusedScore {
+    /// Contributions as reported in a route explanation
+    pub fn contributions(&self) -> ScoreContributions {
+        ScoreContributions {
+            heuristic: self.heuristic_contribution,
+            model: self.model_contribution,
+            adaptive: self.adaptive_contribution,
+            dominant: self.dominant.as_str().to_string(),
+        }
+    }
+}
+
+/// Share of recent decisions each component dominated
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FusionStats {
+    pub decisions: usize,
+    pub heuristic_share: f64,
+    pub model_share: f64,
+    pub adaptive_share: f64,
+}
+
+/// Combines heuristic, model and adaptive scores
+pub struct ScoreFusion {
+    settings: FusionSettings,
+    recent: VecDeque<ScoreComponent>,
+}
+
+impl Default for ScoreFusion {
+    fn default() -> Self {
+        Self::new(FusionSettings::default())
+    }
+}
+
+impl ScoreFusion {
+    pub fn new(settings: FusionSettings) -> Self {
+        Self {
+            settings,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Replace weights / meta-model (dominance history is kept)
+    pub fn set_settings(&mut self, settings: FusionSettings) {
+        self.settings = settings;
+        self.trim();
+    }
+
+    pub fn settings(&self) -> &FusionSettings {
+        &self.settings
+    }
+
+    /// Fuse component scores and record which one dominated
+    pub fn fuse(&mut self, scores: &ComponentScores) -> FusedScore {
+        let fused = self.evaluate(scores);
+        self.recent.push_back(fused.dominant);
+        self.trim();
+        fused
+    }
+
+    /// Fuse without recording (pure)
+    pub fn evaluate(&self, scores: &ComponentScores) -> FusedScore {
+        let confidence = scores.adaptive_confidence.clamp(0.0, 1.0);
+
+        let (score, contributions) = match self.settings.meta_model {
+            Some(ref meta) => {
+                let terms = [
+                    meta.heuristic * scores.heuristic,
+                    meta.model * scores.model.unwrap_or(scores.heuristic),
+                    meta.adaptive * scores.adaptive,
+                ];
+                let z = meta.bias + terms.iter().sum::<f32>() + meta.confidence * confidence;
+
+                let mut contributions = terms.map(f32::abs);
+                if scores.model.is_none() {
+                    contributions[0] += contributions[1];
+                    contributions[1] = 0.0;
+                }
+                (sigmoid(z), contributions)
+            }
+            None => {
+                let weights = [
+                    self.settings.heuristic_weight,
+                    if scores.model.is_some() { self.settings.model_weight } else { 0.0 },
+                    self.settings.adaptive_weight * confidence,
+                ];
+                let values = [scores.heuristic, scores.model.unwrap_or(0.0), scores.adaptive];
+                let total: f32 = weights.iter().sum();
+                if total <= 0.0 {
+                    (scores.heuristic, [scores.heuristic, 0.0, 0.0])
+                } else {
+                    let contributions = [0, 1, 2].map(|i| weights[i] * values[i] / total);
+                    (contributions.iter().sum(), contributions)
+                }
+            }
+        };
+
+        let dominant = [ScoreComponent::Heuristic, ScoreComponent::Model, ScoreComponent::Adaptive]
+            .into_iter()
+            .zip(contributions)
+            .fold((ScoreComponent::Heuristic, f32::MIN), |best, (c, v)| if v > best.1 { (c, v) } else { best })
+            .0;
+
+        let present = [Some(scores.heuristic), scores.model, Some(scores.adaptive)];
+        let (low, high) = present
+            .into_iter()
+            .flatten()
+            .fold((f32::MAX, f32::MIN), |(low, high), s| (low.min(s), high.max(s)));
+        let agreement = 1.0 - (high - low).clamp(0.0, 1.0);
+
+        FusedScore {
+            score: MevRiskScore::new(score).with_confidence(confidence * agreement),
+            dominant,
+            heuristic_contribution: contributions[0],
+            model_contribution: contributions[1],
+            adaptive_contribution: contributions[2],
+        }
+    }
+
+    /// Dominance over the sliding window
+    pub fn stats(&self) -> FusionStats {
+        let n = self.recent.len();
+        let share = |component| {
+            if n == 0 {
+                0.0
+            } else {
+                self.recent.iter().filter(|c| **c == component).count() as f64 / n as f64
+            }
+        };
+        FusionStats {
+            decisions: n,
+            heuristic_share: share(ScoreComponent::Heuristic),
+            model_share: share(ScoreComponent::Model),
+            adaptive_share: share(ScoreComponent::Adaptive),
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.recent.len() > self.settings.window.max(1) {
+            self.recent.pop_front();
+        }
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sentinel_core::MetaModelSettings;
+
+    fn scores(heuristic: f32, model: Option<f32>, adaptive: f32, confidence: f32) -> ComponentScores {
+        ComponentScores { heuristic, model, adaptive, adaptive_confidence: confidence }
+    }
+
+    #[test]
+    fn test_weighted_average_with_model() {
+        let mut fusion = ScoreFusion::default();
+        let fused = fusion.fuse(&scores(0.2, Some(0.9), 0.5, 1.0));
+
+        // 0.3·0.2 + 0.5·0.9 + 0.2·0.5
+        assert!((fused.score.0 - 0.61).abs() < 1e-5);
+        assert_eq!(fused.dominant, ScoreComponent::Model);
+        // Heuristic and model 0.7 apart
+        assert!((fused.score.confidence().unwrap() - 0.3).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_agreeing_components_keep_confidence() {
+        let fused = ScoreFusion::default().evaluate(&scores(0.8, Some(0.85), 0.8, 0.9));
+        assert!((fused.score.confidence().unwrap() - 0.855).abs() < 1e-5);
+
+        let unsure = ScoreFusion::default().evaluate(&scores(0.8, None, 0.8, 0.2));
+        assert!((unsure.score.confidence().unwrap() - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_missing_model_renormalizes() {
+        let fusion = ScoreFusion::default();
+        let fused = fusion.evaluate(&scores(0.8, None, 0.4, 0.5));
+
+        // weights 0.3 and 0.2·0.5 → (0.24 + 0.04) / 0.4
+        assert!((fused.score.0 - 0.7).abs() < 1e-5);
+        assert_eq!(fused.dominant, ScoreComponent::Heuristic);
+        assert_eq!(fused.model_contribution, 0.0);
+    }
+
+    #[test]
+    fn test_logistic_meta_model() {
+        let fusion = ScoreFusion::new(FusionSettings {
+            meta_model: Some(MetaModelSettings {
+                bias: -2.0,
+                heuristic: 1.0,
+                model: 3.0,
+                adaptive: 1.0,
+                confidence: 0.0,
+            }),
+            ..Default::default()
+        });
+
+        let fused = fusion.evaluate(&scores(0.5, Some(1.0), 0.5, 1.0));
+        assert!((fused.score.0 - sigmoid(2.0)).abs() < 1e-5);
+        assert_eq!(fused.dominant, ScoreComponent::Model);
+
+        // Missing model falls back to the heuristic score
+        let fallback = fusion.evaluate(&scores(0.5, None, 0.5, 1.0));
+        assert!((fallback.score.0 - sigmoid(0.5)).abs() < 1e-5);
+        assert_eq!(fallback.dominant, ScoreComponent::Heuristic);
+    }
+
+    #[test]
+    fn test_dominance_window() {
+        let mut fusion = ScoreFusion::new(FusionSettings { window: 4, ..Default::default() });
+        for _ in 0..3 {
+            fusion.fuse(&scores(0.1, Some(0.9), 0.1, 1.0));
+        }
+        for _ in 0..3 {
+            fusion.fuse(&scores(0.9, None, 0.1, 1.0));
+        }
+
+        let stats = fusion.stats();
+        assert_eq!(stats.decisions, 4);
+        assert_eq!(stats.heuristic_share, 0.75);
+        assert_eq!(stats.model_share, 0.25);
+    }
+}
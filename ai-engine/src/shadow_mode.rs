@@ -7,16 +7,20 @@
 //! - Feature flag control for instant rollback
 //! - Async prediction logging (zero blocking)
 //! - Correlation tracking (request_id)
-//! - Buffered writes to disk
+//! - Buffered writes through the configured storage backend (JSONL by default)
 //! - Comprehensive metadata for analysis
+//! - Signature/PII scrubbing before buffering (see `sentinel_core::redaction`)
+//! - Config version of every row, joinable with `sentinel_core::ConfigHistory`
 
-use sentinel_core::{Result, SentinelError};
+use sentinel_core::storage::{append_json, AppendLog, FileLog, Storage};
+use sentinel_core::{ConfigHandle, Result, Scrubber, SentinelError};
 use serde::{Deserialize, Serialize};
-use std::io::Write;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 
+use crate::shadow_store::{JsonlShadowStore, ShadowPage, ShadowQuery, ShadowStore};
+
 /// Shadow prediction result with metadata
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShadowPrediction {
@@ -55,6 +59,25 @@ pub struct ShadowPrediction {
     /// Any errors during shadow prediction
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+
+    /// Tenant namespace the prediction was made under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+
+    /// `SentinelConfig::fingerprint` of the config in effect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_version: Option<String>,
+}
+
+impl ShadowPrediction {
+    /// Redact the signature, error text and (if configured) features
+    pub fn scrub(&mut self, scrubber: &Scrubber) {
+        self.signature = scrubber.signature(&self.signature);
+        self.error = self.error.as_deref().map(|error| scrubber.text(error));
+        if !scrubber.keeps_features() {
+            self.features = serde_json::Value::Null;
+        }
+    }
 }
 
 /// Parameters for logging a shadow prediction
@@ -79,11 +102,14 @@ pub struct ShadowConfig {
     /// Shadow model version identifier
     pub model_version: String,
 
-    /// Log file path (JSONL format)
+    /// Log file path (JSONL format) when no storage backend is given
     pub log_path: String,
 
     /// Enable shadow mode on startup
     pub enabled_on_start: bool,
+
+    /// Tag every prediction with this tenant (see `for_tenant`)
+    pub tenant_id: Option<String>,
 }
 
 impl Default for ShadowConfig {
@@ -93,6 +119,26 @@ impl Default for ShadowConfig {
             model_version: "v1.0".to_string(),
             log_path: "logs/shadow_predictions.jsonl".to_string(),
             enabled_on_start: true,
+            tenant_id: None,
+        }
+    }
+}
+
+impl ShadowConfig {
+    /// Config writing to the tenant's log partition and tagging its predictions
+    pub fn for_tenant(&self, tenant_id: &str) -> Self {
+        Self {
+            log_path: sentinel_core::tenant::partition_path(&self.log_path, tenant_id),
+            tenant_id: Some(tenant_id.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// Append log stream name in a storage backend (tenant-partitioned like `log_path`)
+    pub fn stream(&self) -> String {
+        match &self.tenant_id {
+            Some(tenant_id) => format!("{}/shadow_predictions", tenant_id),
+            None => "shadow_predictions".to_string(),
         }
     }
 }
@@ -110,18 +156,78 @@ pub struct ShadowModeManager {
 
     /// Configuration
     config: ShadowConfig,
+
+    /// Where flushed predictions are appended (the source of truth)
+    log: Arc<dyn AppendLog>,
+
+    /// Queryable store mirroring each flush
+    store: Option<Arc<dyn ShadowStore>>,
+
+    /// Applied to every prediction before it is buffered
+    scrubber: Scrubber,
+
+    /// Source of the config version stamped on every prediction
+    config_handle: Option<ConfigHandle>,
 }
 
 impl ShadowModeManager {
-    /// Create new shadow mode manager
+    /// Create new shadow mode manager writing JSONL to `config.log_path`
     pub fn new(config: ShadowConfig) -> Self {
         Self {
             enabled: Arc::new(RwLock::new(config.enabled_on_start)),
             predictions: Arc::new(RwLock::new(Vec::with_capacity(config.buffer_size))),
+            log: Arc::new(FileLog::new(&config.log_path)),
             config,
+            store: None,
+            scrubber: Scrubber::default(),
+            config_handle: None,
         }
     }
 
+    /// Manager persisting through the configured storage backend
+    pub fn from_storage(config: ShadowConfig, storage: &Storage) -> Result<Self> {
+        let log = storage.log(&config.stream())?;
+        Ok(Self::new(config).with_log(log))
+    }
+
+    /// Append flushed predictions to `log` instead of the JSONL file
+    pub fn with_log(mut self, log: Arc<dyn AppendLog>) -> Self {
+        self.log = log;
+        self
+    }
+
+    /// Mirror flushed predictions into a queryable store
+    pub fn with_store(mut self, store: Arc<dyn ShadowStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Redact predictions before they are buffered
+    pub fn with_scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    /// Stamp predictions with the handle's current config version
+    pub fn with_config(mut self, handle: ConfigHandle) -> Self {
+        self.config_handle = Some(handle);
+        self
+    }
+
+    /// Query persisted predictions (the configured store, else the append log)
+    ///
+    /// A signature filter is scrubbed like the stored predictions were.
+    pub async fn query(&self, mut query: ShadowQuery) -> Result<ShadowPage> {
+        query.signature = query.signature.map(|signature| self.scrubber.signature(&signature));
+        let store = match &self.store {
+            Some(store) => store.clone(),
+            None => Arc::new(JsonlShadowStore::with_logs(vec![self.log.clone()])),
+        };
+        tokio::task::spawn_blocking(move || store.query(&query))
+            .await
+            .map_err(|e| SentinelError::InferenceError(format!("Shadow query task failed: {}", e)))?
+    }
+
     /// Check if shadow mode is enabled
     pub async fn is_enabled(&self) -> bool {
         *self.enabled.read().await
@@ -158,7 +264,7 @@ impl ShadowModeManager {
             return Ok(());
         }
 
-        let prediction = ShadowPrediction {
+        let mut prediction = ShadowPrediction {
             request_id,
             timestamp_ms: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -173,7 +279,10 @@ impl ShadowModeManager {
             production_is_mev,
             features,
             error: None,
+            tenant_id: self.config.tenant_id.clone(),
+            config_version: self.config_handle.as_ref().map(ConfigHandle::config_version),
         };
+        prediction.scrub(&self.scrubber);
 
         // Add to buffer
         let mut predictions = self.predictions.write().await;
@@ -198,7 +307,7 @@ impl ShadowModeManager {
             return Ok(());
         }
 
-        let prediction = ShadowPrediction {
+        let mut prediction = ShadowPrediction {
             request_id,
             timestamp_ms: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -213,7 +322,10 @@ impl ShadowModeManager {
             production_is_mev: None,
             features: serde_json::json!({}),
             error: Some(error),
+            tenant_id: self.config.tenant_id.clone(),
+            config_version: self.config_handle.as_ref().map(ConfigHandle::config_version),
         };
+        prediction.scrub(&self.scrubber);
 
         let mut predictions = self.predictions.write().await;
         predictions.push(prediction);
@@ -237,42 +349,31 @@ impl ShadowModeManager {
         }
 
         tracing::info!(
-            "📝 Flushing {} shadow predictions to {}",
+            "📝 Flushing {} shadow predictions to {} log",
             predictions.len(),
-            self.config.log_path
+            self.log.name()
         );
 
-        // Create directory if it doesn't exist
-        if let Some(parent) = std::path::Path::new(&self.config.log_path).parent() {
-            std::fs::create_dir_all(parent).map_err(|e| {
-                SentinelError::InferenceError(format!("Failed to create log dir: {}", e))
-            })?;
-        }
-
-        // Write to JSONL file (append mode)
-        let log_file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.config.log_path)
-            .map_err(|e| {
-                SentinelError::InferenceError(format!("Failed to open log file: {}", e))
-            })?;
-
-        let mut writer = std::io::BufWriter::new(log_file);
-        for pred in predictions.iter() {
-            serde_json::to_writer(&mut writer, pred).map_err(|e| {
-                SentinelError::InferenceError(format!("Failed to write JSON: {}", e))
-            })?;
-            writeln!(&mut writer).map_err(|e| {
-                SentinelError::InferenceError(format!("Failed to write newline: {}", e))
-            })?;
-        }
-        writer
-            .flush()
-            .map_err(|e| SentinelError::InferenceError(format!("Failed to flush buffer: {}", e)))?;
+        let log = self.log.clone();
+        let batch = predictions.clone();
+        tokio::task::spawn_blocking(move || append_json(log.as_ref(), &batch))
+            .await
+            .map_err(|e| SentinelError::InferenceError(format!("Shadow log task failed: {}", e)))?
+            .map_err(|e| SentinelError::InferenceError(format!("Failed to write shadow log: {}", e)))?;
 
         tracing::info!("✅ Flushed {} predictions successfully", predictions.len());
 
+        // Store failures must not lose the batch; it is already in the log
+        if let Some(store) = &self.store {
+            let store = store.clone();
+            let batch = predictions.clone();
+            match tokio::task::spawn_blocking(move || store.insert(&batch)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::warn!("Shadow store insert failed: {}", e),
+                Err(e) => tracing::warn!("Shadow store task failed: {}", e),
+            }
+        }
+
         // Clear buffer
         predictions.clear();
 
@@ -347,4 +448,125 @@ mod tests {
         let stats = manager.get_stats().await;
         assert_eq!(stats.buffered_predictions, 1);
     }
+
+    #[tokio::test]
+    async fn test_flush_mirrors_into_store() {
+        let dir = std::env::temp_dir().join(format!("shadow-mode-{}", uuid::Uuid::new_v4()));
+        let config = ShadowConfig {
+            buffer_size: 100,
+            log_path: dir.join("shadow.jsonl").to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let store = Arc::new(JsonlShadowStore::new(dir.join("store.jsonl")));
+        let manager = ShadowModeManager::new(config).with_store(store.clone());
+
+        for (i, production_is_mev) in [Some(true), Some(false), None].into_iter().enumerate() {
+            manager
+                .log_prediction(ShadowLogParams {
+                    request_id: format!("req-{}", i),
+                    signature: format!("sig-{}", i),
+                    shadow_risk_score: 0.9,
+                    shadow_is_mev: true,
+                    latency_us: 100,
+                    production_risk_score: None,
+                    production_is_mev,
+                    features: serde_json::json!([]),
+                })
+                .await
+                .unwrap();
+        }
+        manager.flush().await.unwrap();
+
+        let page = manager.query(ShadowQuery::new().disagreements_only()).await.unwrap();
+        assert_eq!(page.predictions.len(), 1);
+        assert_eq!(page.predictions[0].signature, "sig-1");
+        assert_eq!(store.query(&ShadowQuery::new()).unwrap().predictions.len(), 3);
+
+        // Without a store the JSONL log itself is queried
+        let plain = ShadowModeManager::new(manager.config.clone());
+        let page = plain.query(ShadowQuery::new().signature("sig-2")).await.unwrap();
+        assert_eq!(page.predictions.len(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_from_storage_writes_through_backend() {
+        let storage = Storage::memory();
+        let config = ShadowConfig::default().for_tenant("acme");
+        let handle = ConfigHandle::new(sentinel_core::SentinelConfig::default());
+        let manager = ShadowModeManager::from_storage(config, &storage)
+            .unwrap()
+            .with_config(handle.clone());
+        manager
+            .log_error("req-1".to_string(), "sig-1".to_string(), "timeout".to_string())
+            .await
+            .unwrap();
+        manager.flush().await.unwrap();
+
+        let log = storage.log("acme/shadow_predictions").unwrap();
+        let logged: Vec<ShadowPrediction> = sentinel_core::storage::read_json(log.as_ref()).unwrap();
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].tenant_id.as_deref(), Some("acme"));
+        assert_eq!(logged[0].config_version, Some(handle.config_version()));
+        assert_eq!(manager.query(ShadowQuery::new().signature("sig-1")).await.unwrap().predictions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_strict_redaction_before_persistence() {
+        use sentinel_core::{ComplianceMode, RedactionSettings};
+
+        let signature = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+        let storage = Storage::memory();
+        let manager = ShadowModeManager::from_storage(ShadowConfig::default(), &storage)
+            .unwrap()
+            .with_scrubber(Scrubber::new(&RedactionSettings {
+                compliance_mode: ComplianceMode::GdprStrict,
+                log_features: false,
+                ..Default::default()
+            }));
+        manager
+            .log_prediction(ShadowLogParams {
+                request_id: "req-1".to_string(),
+                signature: signature.to_string(),
+                shadow_risk_score: 0.4,
+                shadow_is_mev: false,
+                latency_us: 100,
+                production_risk_score: None,
+                production_is_mev: None,
+                features: serde_json::json!([1.0, 2.0]),
+            })
+            .await
+            .unwrap();
+        manager
+            .log_error(
+                "req-2".to_string(),
+                signature.to_string(),
+                "payer 9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM has no funds".to_string(),
+            )
+            .await
+            .unwrap();
+        manager.flush().await.unwrap();
+
+        let log = storage.log("shadow_predictions").unwrap();
+        let logged: Vec<ShadowPrediction> = sentinel_core::storage::read_json(log.as_ref()).unwrap();
+        assert!(logged.iter().all(|p| p.signature == "5VERv8NM…" && p.features.is_null()));
+        assert_eq!(logged[1].error.as_deref(), Some("payer [pubkey] has no funds"));
+
+        // Lookups by the full signature still find the scrubbed records
+        let page = manager.query(ShadowQuery::new().signature(signature)).await.unwrap();
+        assert_eq!(page.predictions.len(), 2);
+    }
+
+    #[test]
+    fn test_for_tenant_partitions_log() {
+        let config = ShadowConfig {
+            log_path: "logs/shadow_predictions.jsonl".to_string(),
+            ..Default::default()
+        };
+        let tenant = config.for_tenant("acme");
+
+        assert_eq!(tenant.log_path, "logs/acme/shadow_predictions.jsonl");
+        assert_eq!(tenant.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(tenant.buffer_size, config.buffer_size);
+    }
 }
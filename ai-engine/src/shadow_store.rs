@@ -0,0 +1,729 @@
+//! Shadow Prediction Query API
+//!
+//! Investigating a single transaction used to mean grepping multi-GB shadow
+//! JSONL logs. A [`ShadowStore`] answers [`ShadowQuery`]s instead: time range,
+//! shadow score band, production/shadow disagreements only, or one signature,
+//! paginated with an opaque cursor. Results are ordered by
+//! `(timestamp_ms, request_id)`.
+//!
+//! Backends (persistence goes through `sentinel_core::storage`):
+//! - [`JsonlShadowStore`]: streams append logs (the JSONL files by default) in
+//!   bounded memory, always available
+//! - [`KvShadowStore`]: time and signature indexes over any [`KvStore`]
+//!   (`SledShadowStore` opens one on a local sled database, `sled` feature)
+//! - `PostgresShadowStore`: shared store with SQL filters for analyst tooling (`postgres` feature)
+//!
+//! Stores are blocking; call them from `spawn_blocking` in async code (as
+//! `ShadowModeManager` does when mirroring flushes into a store).
+
+use sentinel_core::storage::{append_json, AppendLog, FileLog, KvStore, Storage};
+use sentinel_core::{Result, SentinelError};
+use serde::{Deserialize, Serialize};
+use std::collections::BinaryHeap;
+use std::path::PathBuf;
+use std::sync::Arc;
+#[cfg(feature = "postgres")]
+use std::sync::Mutex;
+
+use crate::shadow_mode::ShadowPrediction;
+
+/// Page size when the query does not set one
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Largest page a query may request
+pub const MAX_PAGE_SIZE: usize = 1_000;
+
+/// Filters over persisted shadow predictions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShadowQuery {
+    /// Inclusive bounds (milliseconds since epoch)
+    pub from_ms: Option<u64>,
+    pub to_ms: Option<u64>,
+
+    /// Inclusive shadow risk score band
+    pub min_score: Option<f32>,
+    pub max_score: Option<f32>,
+
+    /// Only predictions where shadow and production classifications differ
+    pub disagreements_only: bool,
+    pub signature: Option<String>,
+    pub limit: Option<usize>,
+
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+impl ShadowQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn between(mut self, from_ms: u64, to_ms: u64) -> Self {
+        self.from_ms = Some(from_ms);
+        self.to_ms = Some(to_ms);
+        self
+    }
+
+    pub fn score_band(mut self, min_score: f32, max_score: f32) -> Self {
+        self.min_score = Some(min_score);
+        self.max_score = Some(max_score);
+        self
+    }
+
+    pub fn disagreements_only(mut self) -> Self {
+        self.disagreements_only = true;
+        self
+    }
+
+    pub fn signature(mut self, signature: impl Into<String>) -> Self {
+        self.signature = Some(signature.into());
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn after(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Effective page size (1..=MAX_PAGE_SIZE)
+    pub fn page_size(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Decoded cursor position
+    pub fn position(&self) -> Result<Option<SortKey>> {
+        self.cursor.as_deref().map(SortKey::decode).transpose()
+    }
+
+    /// Whether `prediction` passes every filter (cursor excluded)
+    pub fn matches(&self, prediction: &ShadowPrediction) -> bool {
+        let score = prediction.shadow_risk_score;
+        !(self.from_ms.is_some_and(|from| prediction.timestamp_ms < from)
+            || self.to_ms.is_some_and(|to| prediction.timestamp_ms > to)
+            || self.min_score.is_some_and(|min| score < min)
+            || self.max_score.is_some_and(|max| score > max)
+            || (self.disagreements_only && !is_disagreement(prediction))
+            || self.signature.as_ref().is_some_and(|sig| prediction.signature != *sig))
+    }
+}
+
+/// Shadow and production classifications differ
+pub fn is_disagreement(prediction: &ShadowPrediction) -> bool {
+    prediction
+        .production_is_mev
+        .is_some_and(|production| production != prediction.shadow_is_mev)
+}
+
+/// Result ordering key; doubles as the pagination cursor
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey {
+    pub timestamp_ms: u64,
+    pub request_id: String,
+}
+
+impl SortKey {
+    pub fn of(prediction: &ShadowPrediction) -> Self {
+        Self {
+            timestamp_ms: prediction.timestamp_ms,
+            request_id: prediction.request_id.clone(),
+        }
+    }
+
+    /// Opaque cursor string
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.timestamp_ms, self.request_id)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self> {
+        let (timestamp, request_id) = cursor
+            .split_once(':')
+            .ok_or_else(|| SentinelError::ParseError(format!("Invalid shadow cursor: {}", cursor)))?;
+        let timestamp_ms = timestamp
+            .parse()
+            .map_err(|_| SentinelError::ParseError(format!("Invalid shadow cursor: {}", cursor)))?;
+        Ok(Self {
+            timestamp_ms,
+            request_id: request_id.to_string(),
+        })
+    }
+}
+
+/// One page of query results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShadowPage {
+    pub predictions: Vec<ShadowPrediction>,
+
+    /// Pass to `ShadowQuery::after` for the next page (None on the last page)
+    pub next_cursor: Option<String>,
+}
+
+impl ShadowPage {
+    /// Build a page from up to `page_size + 1` sorted matches
+    fn from_sorted(mut predictions: Vec<ShadowPrediction>, page_size: usize) -> Self {
+        let next_cursor = if predictions.len() > page_size {
+            predictions.truncate(page_size);
+            predictions.last().map(|p| SortKey::of(p).encode())
+        } else {
+            None
+        };
+        Self {
+            predictions,
+            next_cursor,
+        }
+    }
+}
+
+/// Persisted shadow predictions that can be queried
+pub trait ShadowStore: Send + Sync {
+    /// Short identifier for logs
+    fn name(&self) -> &'static str;
+
+    fn insert(&self, predictions: &[ShadowPrediction]) -> Result<()>;
+
+    fn query(&self, query: &ShadowQuery) -> Result<ShadowPage>;
+}
+
+/// Query layer over the append logs `ShadowModeManager` writes
+///
+/// Each query streams the logs once and keeps only the best `limit + 1`
+/// matches, so memory stays bounded however large the logs are. Records that
+/// do not parse are skipped.
+pub struct JsonlShadowStore {
+    logs: Vec<Arc<dyn AppendLog>>,
+}
+
+impl JsonlShadowStore {
+    /// Query (and append to) a single JSONL file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_paths(vec![path.into()])
+    }
+
+    /// Query several JSONL files (e.g. rotated files or tenant partitions); inserts go to the first
+    pub fn with_paths(paths: Vec<PathBuf>) -> Self {
+        Self::with_logs(
+            paths
+                .into_iter()
+                .map(|path| Arc::new(FileLog::new(path)) as Arc<dyn AppendLog>)
+                .collect(),
+        )
+    }
+
+    /// Query logs from any storage backend; inserts go to the first
+    pub fn with_logs(logs: Vec<Arc<dyn AppendLog>>) -> Self {
+        Self { logs }
+    }
+
+    fn scan(&self, log: &dyn AppendLog, query: &ShadowQuery, after: Option<&SortKey>, best: &mut BinaryHeap<Ranked>, keep: usize) -> Result<()> {
+        log.scan(&mut |record| {
+            let Ok(prediction) = serde_json::from_slice::<ShadowPrediction>(record) else {
+                return Ok(true);
+            };
+            if !query.matches(&prediction) {
+                return Ok(true);
+            }
+            let key = SortKey::of(&prediction);
+            if after.is_some_and(|after| key <= *after) {
+                return Ok(true);
+            }
+            if best.len() == keep && best.peek().is_some_and(|worst| key >= worst.key) {
+                return Ok(true);
+            }
+
+            best.push(Ranked { key, prediction });
+            if best.len() > keep {
+                best.pop();
+            }
+            Ok(true)
+        })
+    }
+}
+
+/// Max-heap entry: the worst kept match sits on top
+struct Ranked {
+    key: SortKey,
+    prediction: ShadowPrediction,
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Ranked {}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl ShadowStore for JsonlShadowStore {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn insert(&self, predictions: &[ShadowPrediction]) -> Result<()> {
+        let Some(log) = self.logs.first() else {
+            return Err(SentinelError::ConfigError("JSONL shadow store has no logs".to_string()));
+        };
+        append_json(log.as_ref(), predictions)
+    }
+
+    fn query(&self, query: &ShadowQuery) -> Result<ShadowPage> {
+        let after = query.position()?;
+        let page_size = query.page_size();
+        let mut best = BinaryHeap::with_capacity(page_size + 2);
+        for log in &self.logs {
+            self.scan(log.as_ref(), query, after.as_ref(), &mut best, page_size + 1)?;
+        }
+
+        let sorted = best.into_sorted_vec().into_iter().map(|ranked| ranked.prediction).collect();
+        Ok(ShadowPage::from_sorted(sorted, page_size))
+    }
+}
+
+/// Indexed store over two [`KvStore`] namespaces
+///
+/// - `shadow_predictions`: `timestamp_ms (BE) | request_id` -> JSON
+/// - `shadow_by_signature`: `signature | 0x00 | primary key` -> ()
+pub struct KvShadowStore {
+    predictions: Arc<dyn KvStore>,
+    by_signature: Arc<dyn KvStore>,
+}
+
+/// Embedded store on a local sled database
+#[cfg(feature = "sled")]
+pub type SledShadowStore = KvShadowStore;
+
+fn primary_key(key: &SortKey) -> Vec<u8> {
+    let mut bytes = key.timestamp_ms.to_be_bytes().to_vec();
+    bytes.extend_from_slice(key.request_id.as_bytes());
+    bytes
+}
+
+fn signature_key(signature: &str, primary: &[u8]) -> Vec<u8> {
+    let mut bytes = signature.as_bytes().to_vec();
+    bytes.push(0);
+    bytes.extend_from_slice(primary);
+    bytes
+}
+
+impl KvShadowStore {
+    pub fn new(predictions: Arc<dyn KvStore>, by_signature: Arc<dyn KvStore>) -> Self {
+        Self {
+            predictions,
+            by_signature,
+        }
+    }
+
+    /// Store in the configured backend's KV namespaces
+    pub fn from_storage(storage: &Storage) -> Result<Self> {
+        Ok(Self::new(
+            storage.kv("shadow_predictions")?,
+            storage.kv("shadow_by_signature")?,
+        ))
+    }
+
+    /// Open (or create) a sled database at `path`
+    #[cfg(feature = "sled")]
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::from_storage(&Storage::from_config(&sentinel_core::StorageSettings {
+            backend: sentinel_core::StorageBackendKind::Sled,
+            path: path.into(),
+            ..Default::default()
+        })?)
+    }
+
+    /// Load an existing JSONL log (unparseable lines are skipped)
+    pub fn import_jsonl(&self, path: impl Into<PathBuf>) -> Result<usize> {
+        let log = FileLog::new(path);
+        let mut batch = Vec::new();
+        let mut imported = 0;
+        log.scan(&mut |record| {
+            if let Ok(prediction) = serde_json::from_slice::<ShadowPrediction>(record) {
+                batch.push(prediction);
+            }
+            if batch.len() >= 10_000 {
+                imported += batch.len();
+                self.insert(&batch)?;
+                batch.clear();
+            }
+            Ok(true)
+        })?;
+        imported += batch.len();
+        self.insert(&batch)?;
+        Ok(imported)
+    }
+
+    fn decode(value: &[u8]) -> Result<ShadowPrediction> {
+        serde_json::from_slice(value).map_err(|e| SentinelError::SerializationError(e.to_string()))
+    }
+}
+
+impl ShadowStore for KvShadowStore {
+    fn name(&self) -> &'static str {
+        self.predictions.name()
+    }
+
+    fn insert(&self, predictions: &[ShadowPrediction]) -> Result<()> {
+        let mut rows = Vec::with_capacity(predictions.len());
+        let mut index = Vec::with_capacity(predictions.len());
+        for prediction in predictions {
+            let primary = primary_key(&SortKey::of(prediction));
+            let value = serde_json::to_vec(prediction).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+            index.push((signature_key(&prediction.signature, &primary), Vec::new()));
+            rows.push((primary, value));
+        }
+        self.predictions.put_batch(&rows)?;
+        self.by_signature.put_batch(&index)?;
+        self.predictions.flush()
+    }
+
+    fn query(&self, query: &ShadowQuery) -> Result<ShadowPage> {
+        let page_size = query.page_size();
+        let after = query.position()?.map(|key| primary_key(&key));
+        let mut matches = Vec::with_capacity(page_size + 1);
+        let mut keep = |prediction: ShadowPrediction| {
+            if query.matches(&prediction) {
+                matches.push(prediction);
+            }
+            matches.len() <= page_size
+        };
+
+        match &query.signature {
+            Some(signature) => {
+                let prefix_len = signature.len() + 1;
+                self.by_signature
+                    .scan_prefix(&signature_key(signature, &[]), &mut |index_key, _| {
+                        let primary = &index_key[prefix_len..];
+                        if after.as_deref().is_some_and(|after| primary <= after) {
+                            return Ok(true);
+                        }
+                        match self.predictions.get(primary)? {
+                            Some(value) => Ok(keep(Self::decode(&value)?)),
+                            None => Ok(true),
+                        }
+                    })?;
+            }
+            None => {
+                let start = match (&after, query.from_ms) {
+                    (Some(after), _) => after.clone(),
+                    (None, Some(from)) => from.to_be_bytes().to_vec(),
+                    (None, None) => Vec::new(),
+                };
+                self.predictions.scan_from(&start, &mut |key, value| {
+                    if after.as_deref() == Some(key) {
+                        return Ok(true);
+                    }
+                    let prediction = Self::decode(value)?;
+                    if query.to_ms.is_some_and(|to| prediction.timestamp_ms > to) {
+                        return Ok(false);
+                    }
+                    Ok(keep(prediction))
+                })?;
+            }
+        }
+
+        Ok(ShadowPage::from_sorted(matches, page_size))
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub use postgres_store::PostgresShadowStore;
+
+#[cfg(feature = "postgres")]
+mod postgres_store {
+    use super::*;
+    use postgres::types::ToSql;
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS shadow_predictions (
+            request_id TEXT NOT NULL,
+            timestamp_ms BIGINT NOT NULL,
+            signature TEXT NOT NULL,
+            shadow_risk_score REAL NOT NULL,
+            shadow_is_mev BOOLEAN NOT NULL,
+            production_is_mev BOOLEAN,
+            prediction JSONB NOT NULL,
+            PRIMARY KEY (timestamp_ms, request_id)
+        );
+        CREATE INDEX IF NOT EXISTS shadow_predictions_signature ON shadow_predictions (signature);
+    ";
+
+    /// Shared store for analyst tooling
+    pub struct PostgresShadowStore {
+        client: Mutex<postgres::Client>,
+    }
+
+    fn db_error(e: postgres::Error) -> SentinelError {
+        SentinelError::ConnectionError(format!("Shadow store: {}", e))
+    }
+
+    impl PostgresShadowStore {
+        /// Connect (e.g. `host=localhost user=sentinel dbname=shadow`) and create the schema
+        pub fn connect(params: &str) -> Result<Self> {
+            let mut client = postgres::Client::connect(params, postgres::NoTls).map_err(db_error)?;
+            client.batch_execute(SCHEMA).map_err(db_error)?;
+            Ok(Self {
+                client: Mutex::new(client),
+            })
+        }
+    }
+
+    impl ShadowStore for PostgresShadowStore {
+        fn name(&self) -> &'static str {
+            "postgres"
+        }
+
+        fn insert(&self, predictions: &[ShadowPrediction]) -> Result<()> {
+            let mut client = self.client.lock().unwrap_or_else(|p| p.into_inner());
+            let mut tx = client.transaction().map_err(db_error)?;
+            let statement = tx
+                .prepare(
+                    "INSERT INTO shadow_predictions
+                     (request_id, timestamp_ms, signature, shadow_risk_score, shadow_is_mev, production_is_mev, prediction)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)
+                     ON CONFLICT DO NOTHING",
+                )
+                .map_err(db_error)?;
+            for prediction in predictions {
+                let json = serde_json::to_value(prediction).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+                tx.execute(
+                    &statement,
+                    &[
+                        &prediction.request_id,
+                        &(prediction.timestamp_ms as i64),
+                        &prediction.signature,
+                        &prediction.shadow_risk_score,
+                        &prediction.shadow_is_mev,
+                        &prediction.production_is_mev,
+                        &json,
+                    ],
+                )
+                .map_err(db_error)?;
+            }
+            tx.commit().map_err(db_error)
+        }
+
+        fn query(&self, query: &ShadowQuery) -> Result<ShadowPage> {
+            let page_size = query.page_size();
+            let after = query.position()?;
+            let from = query.from_ms.map(|ms| ms as i64);
+            let to = query.to_ms.map(|ms| ms as i64);
+            let after_ts = after.as_ref().map(|key| key.timestamp_ms as i64);
+            let after_id = after.as_ref().map(|key| key.request_id.clone());
+            let limit = (page_size + 1) as i64;
+
+            let mut clauses = Vec::new();
+            let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+            if let Some(from) = &from {
+                params.push(from);
+                clauses.push(format!("timestamp_ms >= ${}", params.len()));
+            }
+            if let Some(to) = &to {
+                params.push(to);
+                clauses.push(format!("timestamp_ms <= ${}", params.len()));
+            }
+            if let Some(min) = &query.min_score {
+                params.push(min);
+                clauses.push(format!("shadow_risk_score >= ${}", params.len()));
+            }
+            if let Some(max) = &query.max_score {
+                params.push(max);
+                clauses.push(format!("shadow_risk_score <= ${}", params.len()));
+            }
+            if query.disagreements_only {
+                clauses.push("production_is_mev IS NOT NULL AND production_is_mev <> shadow_is_mev".to_string());
+            }
+            if let Some(signature) = &query.signature {
+                params.push(signature);
+                clauses.push(format!("signature = ${}", params.len()));
+            }
+            if let (Some(ts), Some(id)) = (&after_ts, &after_id) {
+                params.push(ts);
+                params.push(id);
+                clauses.push(format!("(timestamp_ms, request_id) > (${}, ${})", params.len() - 1, params.len()));
+            }
+            params.push(&limit);
+
+            let mut sql = "SELECT prediction FROM shadow_predictions".to_string();
+            if !clauses.is_empty() {
+                sql.push_str(" WHERE ");
+                sql.push_str(&clauses.join(" AND "));
+            }
+            sql.push_str(&format!(" ORDER BY timestamp_ms, request_id LIMIT ${}", params.len()));
+
+            let mut client = self.client.lock().unwrap_or_else(|p| p.into_inner());
+            let rows = client.query(sql.as_str(), &params).map_err(db_error)?;
+            let predictions = rows
+                .iter()
+                .map(|row| {
+                    serde_json::from_value(row.get::<_, serde_json::Value>(0))
+                        .map_err(|e| SentinelError::SerializationError(e.to_string()))
+                })
+                .collect::<Result<Vec<ShadowPrediction>>>()?;
+            Ok(ShadowPage::from_sorted(predictions, page_size))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn prediction(i: u64, score: f32, production_is_mev: Option<bool>) -> ShadowPrediction {
+        ShadowPrediction {
+            request_id: format!("req-{:03}", i),
+            timestamp_ms: 1_000 + i * 10,
+            signature: format!("sig-{}", i % 5),
+            model_version: "v1.0".to_string(),
+            shadow_risk_score: score,
+            shadow_is_mev: score >= 0.8,
+            latency_us: 100,
+            production_risk_score: None,
+            production_is_mev,
+            features: serde_json::json!([]),
+            error: None,
+            tenant_id: None,
+            config_version: None,
+        }
+    }
+
+    fn sample() -> Vec<ShadowPrediction> {
+        (0..50u64)
+            .map(|i| prediction(i, (i % 10) as f32 / 10.0, (i % 3 == 0).then_some(i % 2 == 0)))
+            .collect()
+    }
+
+    fn temp_log() -> PathBuf {
+        std::env::temp_dir().join(format!("shadow-store-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    /// Every page of `query`, following cursors
+    fn all_pages(store: &dyn ShadowStore, query: ShadowQuery) -> Vec<String> {
+        let mut ids = Vec::new();
+        let mut query = query;
+        loop {
+            let page = store.query(&query).unwrap();
+            ids.extend(page.predictions.iter().map(|p| p.request_id.clone()));
+            match page.next_cursor {
+                Some(cursor) => query = query.after(cursor),
+                None => return ids,
+            }
+        }
+    }
+
+    fn expected(filter: &ShadowQuery) -> Vec<String> {
+        sample()
+            .into_iter()
+            .filter(|p| filter.matches(p))
+            .map(|p| p.request_id)
+            .collect()
+    }
+
+    fn exercise(store: &dyn ShadowStore) {
+        // Insert out of order; results still come back sorted
+        let mut predictions = sample();
+        predictions.reverse();
+        store.insert(&predictions[..25]).unwrap();
+        store.insert(&predictions[25..]).unwrap();
+
+        let queries = [
+            ShadowQuery::new().limit(7),
+            ShadowQuery::new().between(1_100, 1_300).limit(4),
+            ShadowQuery::new().score_band(0.5, 0.7).limit(3),
+            ShadowQuery::new().disagreements_only().limit(2),
+            ShadowQuery::new().signature("sig-3").limit(3),
+            ShadowQuery::new().signature("sig-3").between(1_000, 1_200),
+        ];
+        for query in queries {
+            assert_eq!(all_pages(store, query.clone()), expected(&query), "{:?}", query);
+        }
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let query = ShadowQuery::new().between(1_000, 1_050).score_band(0.2, 0.4);
+        assert!(query.matches(&prediction(3, 0.3, None)));
+        assert!(!query.matches(&prediction(3, 0.5, None)));
+        assert!(!query.matches(&prediction(6, 0.3, None)));
+
+        let disagreements = ShadowQuery::new().disagreements_only();
+        assert!(disagreements.matches(&prediction(1, 0.9, Some(false))));
+        assert!(!disagreements.matches(&prediction(1, 0.9, Some(true))));
+        assert!(!disagreements.matches(&prediction(1, 0.9, None)));
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let key = SortKey {
+            timestamp_ms: 1_234,
+            request_id: "req:with:colons".to_string(),
+        };
+        assert_eq!(SortKey::decode(&key.encode()).unwrap(), key);
+        assert!(SortKey::decode("garbage").is_err());
+        assert!(ShadowQuery::new().after("x:y").position().is_err());
+    }
+
+    #[test]
+    fn test_jsonl_store_queries_and_pages() {
+        let path = temp_log();
+        let store = JsonlShadowStore::new(&path);
+        exercise(&store);
+
+        // Garbage lines in the log are skipped
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        assert_eq!(store.query(&ShadowQuery::new().limit(1_000)).unwrap().predictions.len(), 50);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_missing_log_is_empty() {
+        let store = JsonlShadowStore::new(temp_log());
+        let page = store.query(&ShadowQuery::new()).unwrap();
+        assert!(page.predictions.is_empty());
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_kv_store_queries_and_pages() {
+        let store = KvShadowStore::from_storage(&Storage::memory()).unwrap();
+        exercise(&store);
+        assert_eq!(store.name(), "memory");
+    }
+
+    #[test]
+    fn test_jsonl_store_over_storage_logs() {
+        let storage = Storage::memory();
+        let store = JsonlShadowStore::with_logs(vec![storage.log("shadow_predictions").unwrap()]);
+        exercise(&store);
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_sled_store_queries_and_pages() {
+        let dir = std::env::temp_dir().join(format!("shadow-sled-{}", uuid::Uuid::new_v4()));
+        let store = SledShadowStore::open(&dir).unwrap();
+        exercise(&store);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,565 @@
+//! Slot-Parallel Feature Extraction
+//!
+//! A single `FeatureExtractor` behind one lock cannot keep up with mainnet
+//! feed volume. [`ShardedExtractor`] splits extraction across N worker tasks,
+//! each owning its own extractor, and routes every transaction to a shard by
+//! [`ShardKey`] (token pair or fee payer):
+//!
+//! ```text
+//!                         ┌─ shard 0 (drop-oldest) ─ worker ─┐
+//! feed --inbound--> router┼─ shard 1 (drop-oldest) ─ worker ─┼--extracted--> inference
+//!                  (dedup)└─ shard N (drop-oldest) ─ worker ─┘
+//!                                       │
+//!                               CrossShardIndex
+//! ```
+//!
+//! Per-pair state (market stats, oracle lookups, sandwich model) and the
+//! history feature matching the shard key stay shard-local. Features whose
+//! inputs span shards (sandwich triplets across pairs, the other history
+//! count, tip rank against all recent swaps) are answered by the shared
+//! [`CrossShardIndex`] merge layer, a slot-bucketed swap history.
+//!
+//! Shards run concurrently, so a swap is visible to other shards once its own
+//! worker has recorded it, and extracted vectors leave in per-shard (not
+//! global) order. Request-path callers (gRPC `Score`) use
+//! [`ShardedExtractor::extract`], which locks only the transaction's shard.
+
+use sentinel_core::{BoundedQueue, Deadline, OverflowPolicy, QueueStats};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::{debug, info};
+
+use crate::dedup::{DedupSettings, DedupStats, DedupWindow};
+use crate::features_enhanced::{FeatureExtractor, FeatureVector, TransactionData};
+use crate::ingest::{ExtractedTransaction, FeedTransaction, IngestSettings};
+
+/// What a transaction is sharded by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardKey {
+    /// Unordered token pair (both directions of a pool share a shard); non-swaps by fee payer
+    TokenPair,
+    FeePayer,
+}
+
+/// Shard layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardingSettings {
+    /// Worker tasks (one extractor each)
+    pub shards: usize,
+    pub key: ShardKey,
+
+    /// Transactions queued per shard before its oldest are shed
+    pub shard_capacity: usize,
+
+    /// Slots of swap history kept by the merge layer
+    pub history_slots: u64,
+}
+
+impl Default for ShardingSettings {
+    fn default() -> Self {
+        Self {
+            shards: std::thread::available_parallelism().map_or(4, |n| n.get()),
+            key: ShardKey::TokenPair,
+            shard_capacity: 10_000,
+            history_slots: 150,
+        }
+    }
+}
+
+/// Queue, dedup and merge-layer counters
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShardedStats {
+    pub inbound: QueueStats,
+    pub shards: Vec<QueueStats>,
+    pub extracted: QueueStats,
+    pub dedup: Option<DedupStats>,
+
+    /// Swaps held by the merge layer
+    pub indexed_swaps: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexedSwap {
+    actor: Pubkey,
+    pair: (Pubkey, Pubkey),
+    tip_lamports: u64,
+}
+
+/// History features answered across all shards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrossShardFeatures {
+    pub has_swap_triplet: bool,
+    pub recent_swaps_same_pair: u32,
+    pub recent_swaps_same_actor: u32,
+    pub tip_percentile_vs_recent: f32,
+}
+
+/// Merge layer: recent swaps of every shard bucketed by slot
+///
+/// Windows match `FeatureExtractor` (triplets ±2 slots, pair 10, actor and
+/// tip rank 100).
+#[derive(Debug)]
+pub struct CrossShardIndex {
+    history_slots: u64,
+    by_slot: BTreeMap<u64, Vec<IndexedSwap>>,
+    len: usize,
+}
+
+impl CrossShardIndex {
+    pub fn new(history_slots: u64) -> Self {
+        Self {
+            history_slots: history_slots.max(100),
+            by_slot: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// History features of `tx_data` against swaps recorded so far
+    pub fn features(&self, tx_data: &TransactionData) -> CrossShardFeatures {
+        let slot = tx_data.slot;
+        let mut same_actor = 0u32;
+        let mut same_pair = 0u32;
+        let (mut tips, mut tips_below) = (0usize, 0usize);
+
+        for (&swap_slot, swaps) in self.by_slot.range(slot.saturating_sub(100)..) {
+            for swap in swaps {
+                same_actor += (swap.actor == tx_data.fee_payer) as u32;
+                tips += 1;
+                tips_below += (swap.tip_lamports < tx_data.jito_tip_lamports) as usize;
+                if let Some(ref details) = tx_data.swap_details {
+                    same_pair += (swap_slot >= slot.saturating_sub(10)
+                        && swap.pair == (details.input_mint, details.output_mint)) as u32;
+                }
+            }
+        }
+
+        CrossShardFeatures {
+            has_swap_triplet: self.has_triplet(tx_data),
+            recent_swaps_same_pair: same_pair,
+            recent_swaps_same_actor: same_actor,
+            tip_percentile_vs_recent: if tips == 0 {
+                50.0
+            } else {
+                tips_below as f32 / tips as f32 * 100.0
+            },
+        }
+    }
+
+    /// Front-run on the victim's input mint followed by the same actor's back-run on its output mint
+    fn has_triplet(&self, tx_data: &TransactionData) -> bool {
+        let Some(ref victim) = tx_data.swap_details else {
+            return false;
+        };
+        let slot = tx_data.slot;
+        self.by_slot
+            .range(slot.saturating_sub(2)..=slot)
+            .flat_map(|(_, swaps)| swaps)
+            .filter(|front| front.pair.0 == victim.input_mint && front.actor != tx_data.fee_payer)
+            .any(|front| {
+                self.by_slot
+                    .range(slot..=slot + 2)
+                    .flat_map(|(_, swaps)| swaps)
+                    .any(|back| back.actor == front.actor && back.pair.1 == victim.output_mint)
+            })
+    }
+
+    /// Add a swap and drop slots older than the history window
+    pub fn record(&mut self, tx_data: &TransactionData) {
+        let Some(ref swap) = tx_data.swap_details else {
+            return;
+        };
+        self.by_slot.entry(tx_data.slot).or_default().push(IndexedSwap {
+            actor: tx_data.fee_payer,
+            pair: (swap.input_mint, swap.output_mint),
+            tip_lamports: tx_data.jito_tip_lamports,
+        });
+        self.len += 1;
+
+        let newest = self.by_slot.keys().next_back().copied().unwrap_or_default();
+        let keep = self.by_slot.split_off(&newest.saturating_sub(self.history_slots));
+        let expired = std::mem::replace(&mut self.by_slot, keep);
+        self.len -= expired.values().map(Vec::len).sum::<usize>();
+    }
+}
+
+struct ShardJob {
+    transaction: FeedTransaction,
+    duplicate: bool,
+}
+
+/// N extraction workers with a shared merge layer
+pub struct ShardedExtractor {
+    settings: ShardingSettings,
+    extractors: Vec<Arc<Mutex<FeatureExtractor>>>,
+    index: Arc<RwLock<CrossShardIndex>>,
+    dedup: Option<Arc<std::sync::Mutex<DedupWindow>>>,
+    inbound: BoundedQueue<FeedTransaction>,
+    shard_queues: Vec<BoundedQueue<ShardJob>>,
+    extracted: BoundedQueue<ExtractedTransaction>,
+}
+
+impl ShardedExtractor {
+    /// `extractor` builds the extractor owned by each shard (called with the shard number)
+    pub fn new(
+        ingest: IngestSettings,
+        settings: ShardingSettings,
+        extractor: impl Fn(usize) -> FeatureExtractor,
+    ) -> Self {
+        let settings = ShardingSettings {
+            shards: settings.shards.max(1),
+            ..settings
+        };
+        Self {
+            extractors: (0..settings.shards).map(|shard| Arc::new(Mutex::new(extractor(shard)))).collect(),
+            index: Arc::new(RwLock::new(CrossShardIndex::new(settings.history_slots))),
+            dedup: None,
+            inbound: BoundedQueue::new("ingest.inbound", ingest.inbound_capacity, OverflowPolicy::DropOldest),
+            shard_queues: (0..settings.shards)
+                .map(|shard| {
+                    BoundedQueue::new(format!("ingest.shard.{}", shard), settings.shard_capacity, OverflowPolicy::DropOldest)
+                })
+                .collect(),
+            extracted: BoundedQueue::new("ingest.extracted", ingest.extracted_capacity, OverflowPolicy::DropOldest),
+            settings,
+        }
+    }
+
+    /// Drop feed redeliveries before they reach a shard (scored, not recorded)
+    pub fn with_dedup(mut self, settings: DedupSettings) -> Self {
+        self.dedup = Some(Arc::new(std::sync::Mutex::new(DedupWindow::new(settings))));
+        self
+    }
+
+    pub fn settings(&self) -> &ShardingSettings {
+        &self.settings
+    }
+
+    /// Extractor owned by `shard`
+    pub fn shard(&self, shard: usize) -> Option<Arc<Mutex<FeatureExtractor>>> {
+        self.extractors.get(shard).cloned()
+    }
+
+    /// Shard a transaction is routed to
+    pub fn shard_for(&self, tx_data: &TransactionData) -> usize {
+        shard_for(self.settings.key, self.settings.shards, tx_data)
+    }
+
+    /// Extract one transaction outside the worker pipeline
+    ///
+    /// Only the transaction's shard is locked while it awaits oracles, so
+    /// calls routed to other shards run concurrently. An empty `signature`
+    /// skips dedup. `None` when `deadline` cannot cover extraction (see
+    /// `FeatureExtractor::lock_within`).
+    pub async fn extract(
+        &self,
+        signature: &str,
+        tx_data: &TransactionData,
+        deadline: Option<&Deadline>,
+    ) -> Option<FeatureVector> {
+        let extractor = &self.extractors[self.shard_for(tx_data)];
+        let extractor = FeatureExtractor::lock_within(extractor, deadline).await?;
+        let duplicate = !signature.is_empty()
+            && self.dedup.as_ref().is_some_and(|window| {
+                !window.lock().unwrap_or_else(|p| p.into_inner()).insert(signature)
+            });
+        if duplicate {
+            debug!("Duplicate delivery of {} not recorded", signature);
+        }
+        Some(extract_on_shard(self.settings.key, extractor, &self.index, tx_data, duplicate).await)
+    }
+
+    /// Where the feed pushes transactions (close it to stop the pipeline)
+    pub fn inbound(&self) -> BoundedQueue<FeedTransaction> {
+        self.inbound.clone()
+    }
+
+    /// Where inference consumers pop feature vectors (closed once every shard is drained)
+    pub fn extracted(&self) -> BoundedQueue<ExtractedTransaction> {
+        self.extracted.clone()
+    }
+
+    pub fn stats(&self) -> ShardedStats {
+        ShardedStats {
+            inbound: self.inbound.stats(),
+            shards: self.shard_queues.iter().map(BoundedQueue::stats).collect(),
+            extracted: self.extracted.stats(),
+            dedup: self
+                .dedup
+                .as_ref()
+                .map(|window| window.lock().unwrap_or_else(|p| p.into_inner()).stats()),
+            indexed_swaps: self.index.read().unwrap_or_else(|p| p.into_inner()).len(),
+        }
+    }
+
+    /// Run the router and shard workers until `inbound` is closed and drained
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let workers: Vec<_> = self
+            .extractors
+            .iter()
+            .zip(&self.shard_queues)
+            .map(|(extractor, queue)| {
+                tokio::spawn(run_shard(
+                    self.settings.key,
+                    extractor.clone(),
+                    queue.clone(),
+                    self.index.clone(),
+                    self.extracted.clone(),
+                ))
+            })
+            .collect();
+
+        let (key, inbound, shard_queues, extracted, dedup) = (
+            self.settings.key,
+            self.inbound.clone(),
+            self.shard_queues.clone(),
+            self.extracted.clone(),
+            self.dedup.clone(),
+        );
+        tokio::spawn(async move {
+            while let Some(transaction) = inbound.pop().await {
+                let duplicate = dedup.as_ref().is_some_and(|window| {
+                    !window
+                        .lock()
+                        .unwrap_or_else(|p| p.into_inner())
+                        .insert(&transaction.signature)
+                });
+                if duplicate {
+                    debug!("Duplicate delivery of {} not recorded", transaction.signature);
+                }
+                let shard = shard_for(key, shard_queues.len(), &transaction.data);
+                // Drop-oldest never refuses unless closed by a consumer
+                let _ = shard_queues[shard].try_push(ShardJob { transaction, duplicate });
+            }
+            shard_queues.iter().for_each(BoundedQueue::close);
+            for worker in workers {
+                let _ = worker.await;
+            }
+            extracted.close();
+
+            let shed: u64 = shard_queues.iter().map(|queue| queue.stats().shed).sum();
+            let (inbound, extracted) = (inbound.stats(), extracted.stats());
+            info!(
+                "Sharded ingestion stopped: {} extracted over {} shards, {} shed before routing, {} shed in shards, {} shed before inference",
+                extracted.enqueued,
+                shard_queues.len(),
+                inbound.shed,
+                shed,
+                extracted.shed
+            );
+        })
+    }
+}
+
+fn shard_for(key: ShardKey, shards: usize, tx_data: &TransactionData) -> usize {
+    // Fixed-key SipHash: stable routing across restarts
+    let mut hasher = DefaultHasher::new();
+    match (key, &tx_data.swap_details) {
+        (ShardKey::TokenPair, Some(swap)) => {
+            let (a, b) = (swap.input_mint, swap.output_mint);
+            (a.min(b), a.max(b)).hash(&mut hasher);
+        }
+        _ => tx_data.fee_payer.hash(&mut hasher),
+    }
+    (hasher.finish() % shards.max(1) as u64) as usize
+}
+
+async fn run_shard(
+    key: ShardKey,
+    extractor: Arc<Mutex<FeatureExtractor>>,
+    queue: BoundedQueue<ShardJob>,
+    index: Arc<RwLock<CrossShardIndex>>,
+    extracted: BoundedQueue<ExtractedTransaction>,
+) {
+    while let Some(ShardJob { transaction, duplicate }) = queue.pop().await {
+        let features = extract_on_shard(key, extractor.lock().await, &index, &transaction.data, duplicate).await;
+        let _ = extracted.try_push(ExtractedTransaction {
+            signature: transaction.signature,
+            slot: transaction.data.slot,
+            features,
+        });
+    }
+}
+
+/// Extract on a locked shard, then merge in the history features spanning shards
+///
+/// The shard lock is released before the merge layer is touched.
+async fn extract_on_shard(
+    key: ShardKey,
+    mut extractor: MutexGuard<'_, FeatureExtractor>,
+    index: &RwLock<CrossShardIndex>,
+    data: &TransactionData,
+    duplicate: bool,
+) -> FeatureVector {
+    let mut features = if duplicate {
+        extractor.extract_preview(data).await
+    } else {
+        extractor.extract(data).await
+    };
+    let tip_floor_fresh = extractor.tip_floor().and_then(|cache| cache.current()).is_some();
+    drop(extractor);
+
+    let cross = if duplicate {
+        index.read().unwrap_or_else(|p| p.into_inner()).features(data)
+    } else {
+        // Query and record under one lock so concurrent shards see each other's swaps exactly once
+        let mut index = index.write().unwrap_or_else(|p| p.into_inner());
+        let cross = index.features(data);
+        index.record(data);
+        cross
+    };
+    merge(&mut features, &cross, key, tip_floor_fresh);
+    features
+}
+
+/// Replace shard-local history features that span shards under `key`
+fn merge(features: &mut FeatureVector, cross: &CrossShardFeatures, key: ShardKey, tip_floor_fresh: bool) {
+    features.has_swap_triplet = cross.has_swap_triplet;
+    match key {
+        ShardKey::TokenPair => features.recent_swaps_same_actor = cross.recent_swaps_same_actor,
+        ShardKey::FeePayer => features.recent_swaps_same_pair = cross.recent_swaps_same_pair,
+    }
+    if !tip_floor_fresh {
+        features.tip_percentile_vs_recent = cross.tip_percentile_vs_recent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features_enhanced::SwapDetailsData;
+
+    fn swap(slot: u64, actor: Pubkey, pair: (Pubkey, Pubkey), tip: u64) -> TransactionData {
+        TransactionData {
+            slot,
+            fee_payer: actor,
+            compute_unit_limit: 200_000,
+            compute_unit_price: 0,
+            jito_tip_lamports: tip,
+            total_fee_lamports: 5_000,
+            account_count: 10,
+            instruction_count: 3,
+            tx_size_bytes: 600,
+            swap_details: Some(SwapDetailsData {
+                input_mint: pair.0,
+                output_mint: pair.1,
+                input_amount: 1.0,
+                output_amount: 1.0,
+                expected_output: 1.0,
+                route_length: 1,
+                slippage_tolerance_bps: 50.0,
+                pool_liquidity_usd: 0.0,
+                pool: None,
+            }),
+            time_since_last_slot_ms: 0,
+            next_leader_pubkey: Pubkey::default(),
+            uses_lookup_tables: false,
+            timestamp_ms: 1_700_000_000_000,
+        }
+    }
+
+    fn sharded(shards: usize) -> ShardedExtractor {
+        ShardedExtractor::new(
+            IngestSettings::default(),
+            ShardingSettings {
+                shards,
+                ..Default::default()
+            },
+            |_| FeatureExtractor::new(),
+        )
+    }
+
+    #[test]
+    fn test_index_matches_extractor_windows_and_expires() {
+        let (attacker, victim) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (sol, usdc, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut index = CrossShardIndex::new(100);
+
+        index.record(&swap(10, attacker, (sol, bonk), 1_000));
+        index.record(&swap(11, attacker, (bonk, usdc), 3_000));
+        let features = index.features(&swap(10, victim, (sol, usdc), 2_000));
+        assert!(features.has_swap_triplet);
+        assert_eq!((features.recent_swaps_same_pair, features.recent_swaps_same_actor), (0, 0));
+        assert!((features.tip_percentile_vs_recent - 50.0).abs() < 1e-6);
+
+        // Victim's own later swap is not its own front-run
+        assert!(!index.features(&swap(10, attacker, (sol, usdc), 0)).has_swap_triplet);
+
+        index.record(&swap(300, victim, (sol, usdc), 0));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.features(&swap(300, victim, (sol, usdc), 0)).recent_swaps_same_actor, 1);
+    }
+
+    #[test]
+    fn test_token_pair_routing_keeps_both_directions_together() {
+        let extractor = sharded(8);
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for _ in 0..20 {
+            let actor = Pubkey::new_unique();
+            assert_eq!(
+                extractor.shard_for(&swap(1, actor, (sol, usdc), 0)),
+                extractor.shard_for(&swap(1, Pubkey::new_unique(), (usdc, sol), 0))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_triplet_detected_across_shards() {
+        let extractor = sharded(4).with_dedup(DedupSettings::default());
+        let (attacker, victim) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let front = swap(10, attacker, (sol, Pubkey::new_unique()), 1_000);
+        // Back-run on a pair routed to a different shard than the front-run
+        let back = std::iter::repeat_with(|| swap(11, attacker, (Pubkey::new_unique(), usdc), 1_000))
+            .find(|back| extractor.shard_for(back) != extractor.shard_for(&front))
+            .unwrap();
+
+        extractor.spawn();
+        let (inbound, extracted) = (extractor.inbound(), extractor.extracted());
+        for (signature, data) in [("front", front), ("back", back.clone()), ("back", back)] {
+            inbound
+                .push(FeedTransaction {
+                    signature: signature.into(),
+                    data,
+                })
+                .await
+                .unwrap();
+        }
+        for _ in 0..3 {
+            extracted.pop().await.unwrap();
+        }
+
+        inbound
+            .push(FeedTransaction {
+                signature: "victim".into(),
+                data: swap(10, victim, (sol, usdc), 5_000),
+            })
+            .await
+            .unwrap();
+        inbound.close();
+        let scored = extracted.pop().await.unwrap();
+        assert_eq!(scored.signature, "victim");
+        assert!(scored.features.has_swap_triplet);
+        assert!((scored.features.tip_percentile_vs_recent - 100.0).abs() < 1e-6);
+        assert!(extracted.pop().await.is_none());
+
+        // The redelivered back-run was scored but not indexed twice
+        let stats = extractor.stats();
+        assert_eq!(stats.indexed_swaps, 3);
+        assert_eq!(stats.dedup.map(|dedup| dedup.duplicates), Some(1));
+        assert_eq!(stats.shards.iter().map(|shard| shard.dequeued).sum::<u64>(), 4);
+    }
+}
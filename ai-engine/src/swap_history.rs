@@ -0,0 +1,235 @@
+//! Slot-Windowed Swap History
+//!
+//! The pattern features are defined over slot windows ("swaps on this pair in
+//! the last 10 slots"), so history is retained by slot rather than by record
+//! count: a burst of swaps never pushes still-relevant slots out. Each window
+//! keeps its own ring of per-slot buckets holding only what it needs:
+//! - **triplet**: actor + pair, `triplet_slots` either side of the victim
+//! - **pair**: token pair, `pair_slots` back (`recent_swaps_same_pair`)
+//! - **actor**: fee payer, `actor_slots` back (`recent_swaps_same_actor`)
+//! - **tip**: Jito tip, `tip_slots` back (`tip_percentile_vs_recent`)
+//!
+//! A ring drops buckets older than its span behind the newest recorded slot.
+//! Memory is bounded by traffic per slot times the span, not by a fixed count.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::VecDeque;
+
+/// Window sizes in slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwapWindowSettings {
+    /// Slots before and after a victim searched for its front- and back-run
+    pub triplet_slots: u64,
+    pub pair_slots: u64,
+    pub actor_slots: u64,
+    pub tip_slots: u64,
+}
+
+impl Default for SwapWindowSettings {
+    fn default() -> Self {
+        Self {
+            triplet_slots: 2,
+            pair_slots: 10,
+            actor_slots: 100,
+            tip_slots: 100,
+        }
+    }
+}
+
+/// Per-slot buckets covering `span` slots behind the newest one
+#[derive(Debug, Clone)]
+struct SlotRing<T> {
+    span: u64,
+    buckets: VecDeque<(u64, Vec<T>)>,
+}
+
+impl<T> SlotRing<T> {
+    fn new(span: u64) -> Self {
+        Self {
+            span,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    fn newest(&self) -> Option<u64> {
+        self.buckets.back().map(|(slot, _)| *slot)
+    }
+
+    fn push(&mut self, slot: u64, item: T) {
+        let newest = self.newest().map_or(slot, |newest| newest.max(slot));
+        let oldest = newest.saturating_sub(self.span);
+        if slot < oldest {
+            // Late delivery already outside the window
+            return;
+        }
+
+        match self.buckets.binary_search_by_key(&slot, |(bucket, _)| *bucket) {
+            Ok(i) => self.buckets[i].1.push(item),
+            Err(i) => self.buckets.insert(i, (slot, vec![item])),
+        }
+        while self.buckets.front().is_some_and(|(bucket, _)| *bucket < oldest) {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Items recorded at `from` or later
+    fn since(&self, from: u64) -> impl Iterator<Item = (u64, &T)> {
+        let start = self.buckets.partition_point(|(bucket, _)| *bucket < from);
+        self.buckets
+            .range(start..)
+            .flat_map(|(slot, items)| items.iter().map(move |item| (*slot, item)))
+    }
+
+    fn len(&self) -> usize {
+        self.buckets.iter().map(|(_, items)| items.len()).sum()
+    }
+}
+
+/// Observed swaps, retained per window
+#[derive(Debug, Clone)]
+pub struct SwapHistory {
+    settings: SwapWindowSettings,
+    triplet: SlotRing<(Pubkey, (Pubkey, Pubkey))>,
+    pair: SlotRing<(Pubkey, Pubkey)>,
+    actor: SlotRing<Pubkey>,
+    tip: SlotRing<u64>,
+}
+
+impl Default for SwapHistory {
+    fn default() -> Self {
+        Self::new(SwapWindowSettings::default())
+    }
+}
+
+impl SwapHistory {
+    pub fn new(settings: SwapWindowSettings) -> Self {
+        Self {
+            settings,
+            // The back-run may be newer than the victim, so keep both sides
+            triplet: SlotRing::new(settings.triplet_slots.saturating_mul(2)),
+            pair: SlotRing::new(settings.pair_slots),
+            actor: SlotRing::new(settings.actor_slots),
+            tip: SlotRing::new(settings.tip_slots),
+        }
+    }
+
+    pub fn settings(&self) -> &SwapWindowSettings {
+        &self.settings
+    }
+
+    pub fn record(&mut self, slot: u64, actor: Pubkey, pair: (Pubkey, Pubkey), tip_lamports: u64) {
+        self.triplet.push(slot, (actor, pair));
+        self.pair.push(slot, pair);
+        self.actor.push(slot, actor);
+        self.tip.push(slot, tip_lamports);
+    }
+
+    /// Swaps on `pair` within `pair_slots` of `slot`
+    pub fn pair_count(&self, slot: u64, pair: &(Pubkey, Pubkey)) -> u32 {
+        self.pair
+            .since(slot.saturating_sub(self.settings.pair_slots))
+            .filter(|(_, recorded)| *recorded == pair)
+            .count() as u32
+    }
+
+    /// Swaps by `actor` within `actor_slots` of `slot`
+    pub fn actor_count(&self, slot: u64, actor: &Pubkey) -> u32 {
+        self.actor
+            .since(slot.saturating_sub(self.settings.actor_slots))
+            .filter(|(_, recorded)| *recorded == actor)
+            .count() as u32
+    }
+
+    /// Tips within `tip_slots` of `slot`
+    pub fn tips(&self, slot: u64) -> impl Iterator<Item = u64> + '_ {
+        self.tip.since(slot.saturating_sub(self.settings.tip_slots)).map(|(_, tip)| *tip)
+    }
+
+    /// Another actor swapped into `input_mint` shortly before `slot` and out to
+    /// `output_mint` shortly after
+    pub fn has_triplet(&self, slot: u64, victim: &Pubkey, input_mint: &Pubkey, output_mint: &Pubkey) -> bool {
+        let window = self.settings.triplet_slots;
+        let nearby = || {
+            self.triplet
+                .since(slot.saturating_sub(window))
+                .take_while(move |(recorded, _)| *recorded <= slot.saturating_add(window))
+        };
+
+        nearby()
+            .filter(|(recorded, (actor, pair))| *recorded <= slot && pair.0 == *input_mint && actor != victim)
+            .any(|(_, (front_runner, _))| {
+                nearby().any(|(recorded, (actor, pair))| {
+                    actor == front_runner && recorded >= slot && pair.1 == *output_mint
+                })
+            })
+    }
+
+    /// Records held across all windows
+    pub fn retained(&self) -> usize {
+        self.triplet.len() + self.pair.len() + self.actor.len() + self.tip.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_does_not_shrink_window() {
+        let (actor, pair) = (Pubkey::new_unique(), (Pubkey::new_unique(), Pubkey::new_unique()));
+        let mut history = SwapHistory::default();
+
+        history.record(100, actor, pair, 1_000);
+        // 5000 swaps by other actors in a single slot
+        for _ in 0..5_000 {
+            history.record(150, Pubkey::new_unique(), pair, 10);
+        }
+        assert_eq!(history.actor_count(150, &actor), 1);
+        assert_eq!(history.tips(150).count(), 5_001);
+        // The pair window (10 slots) has already moved past slot 100
+        assert_eq!(history.pair_count(150, &pair), 5_000);
+
+        // Slot 100 leaves the 100-slot windows once slot 201 is recorded
+        history.record(201, Pubkey::new_unique(), pair, 10);
+        assert_eq!(history.actor_count(201, &actor), 0);
+        assert_eq!(history.tips(201).count(), 5_001);
+    }
+
+    #[test]
+    fn test_window_sizes_configurable() {
+        let (actor, pair) = (Pubkey::new_unique(), (Pubkey::new_unique(), Pubkey::new_unique()));
+        let mut history = SwapHistory::new(SwapWindowSettings {
+            pair_slots: 1,
+            actor_slots: 1_000,
+            ..Default::default()
+        });
+
+        history.record(100, actor, pair, 0);
+        history.record(102, actor, pair, 0);
+        assert_eq!(history.pair_count(102, &pair), 1);
+        assert_eq!(history.actor_count(900, &actor), 2);
+
+        // Late delivery is kept only by the windows it still falls into (actor, tip)
+        let retained = history.retained();
+        history.record(10, actor, pair, 0);
+        assert_eq!(history.retained(), retained + 2);
+        assert_eq!(history.actor_count(102, &actor), 3);
+        assert_eq!(history.pair_count(102, &pair), 1);
+    }
+
+    #[test]
+    fn test_triplet_spans_both_sides() {
+        let (attacker, victim) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut history = SwapHistory::default();
+
+        history.record(99, attacker, (sol, usdc), 0);
+        history.record(101, attacker, (usdc, usdc), 0);
+        assert!(history.has_triplet(100, &victim, &sol, &usdc));
+        assert!(!history.has_triplet(100, &attacker, &sol, &usdc));
+        // Back-run outside the window
+        assert!(!history.has_triplet(96, &victim, &sol, &usdc));
+    }
+}
@@ -0,0 +1,467 @@
+//! Slot-Bucketed Tip Percentile Index
+//!
+//! Streaming replacement for scanning the full tip history on every request.
+//! Tips land in a log-scaled histogram per 100-slot window; a running total over
+//! the retained windows makes both directions O(1) (fixed bin count):
+//! - `percentile_of(tip)`: where a tip ranks vs. recent traffic
+//! - `quantile(q)`: the tip needed to reach a given percentile
+//!
+//! Bins grow by 25% from 1k lamports, so interpolated values stay within ~12%
+//! of the exact order statistic — plenty for threshold checks and tip advice.
+
+use sentinel_core::{MevRiskScore, ProtectionTierProfile, TipFloor};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Slots per histogram window
+pub const SLOTS_PER_BUCKET: u64 = 100;
+
+/// Windows retained (1000 slots ≈ 6.7 minutes)
+const DEFAULT_WINDOW_BUCKETS: usize = 10;
+
+/// Tips per window when they arrive without a slot
+const UNSLOTTED_TIPS_PER_BUCKET: u64 = 1_000;
+
+/// Upper bound of the first bin (lamports)
+const MIN_TIP_LAMPORTS: f64 = 1_000.0;
+
+/// Geometric bin growth factor
+const BIN_GROWTH: f64 = 1.25;
+
+/// Bin count: the top bin spans ~36-45 SOL (1_000 * 1.25^78..79 lamports)
+/// and also absorbs every larger tip
+const BIN_COUNT: usize = 80;
+
+/// Histogram of tips observed within one 100-slot window
+#[derive(Debug, Clone)]
+struct SlotBucket {
+    bucket_id: u64,
+    bins: [u32; BIN_COUNT],
+}
+
+impl SlotBucket {
+    fn len(&self) -> u64 {
+        self.bins.iter().map(|&n| n as u64).sum()
+    }
+}
+
+/// Streaming tip percentiles for tip recommendation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TipPercentiles {
+    pub p50: u64,
+    pub p75: u64,
+    pub p90: u64,
+    pub p95: u64,
+    pub p99: u64,
+    /// Tips backing the estimate
+    pub samples: u64,
+    /// Slot span covered by the window
+    pub window_slots: u64,
+}
+
+impl TipPercentiles {
+    /// Recommended tip for a transaction at the given risk
+    ///
+    /// High-risk flow needs to outbid searchers (p95); medium clears the
+    /// typical auction (p75); low risk only needs the median.
+    pub fn recommend(&self, risk: &MevRiskScore) -> u64 {
+        if risk.is_high_risk() {
+            self.p95
+        } else if risk.is_medium_risk() {
+            self.p75
+        } else {
+            self.p50
+        }
+    }
+    
+    /// Tracked percentile at or above `percentile` (p50 up to p99)
+    pub fn at(&self, percentile: u8) -> u64 {
+        match percentile {
+            0..=50 => self.p50,
+            51..=75 => self.p75,
+            76..=90 => self.p90,
+            91..=95 => self.p95,
+            _ => self.p99,
+        }
+    }
+
+    /// [`Self::recommend`] with the tier's low / medium / high risk percentiles
+    pub fn recommend_for(&self, risk: &MevRiskScore, profile: &ProtectionTierProfile) -> u64 {
+        let [low, medium, high] = profile.tip_percentiles;
+        if risk.is_high_risk() {
+            self.at(high)
+        } else if risk.is_medium_risk() {
+            self.at(medium)
+        } else {
+            self.at(low)
+        }
+    }
+
+    /// Raise each percentile to at least the network-wide landed tip
+    ///
+    /// Local history only sees flow we scored; during a network tip war its
+    /// percentiles lag and recommendations would under-bid.
+    pub fn with_network_floor(self, floor: &TipFloor) -> Self {
+        Self {
+            p50: self.p50.max(floor.p50),
+            p75: self.p75.max(floor.p75),
+            p90: self.p90.max(floor.quantile(90.0)),
+            p95: self.p95.max(floor.p95),
+            p99: self.p99.max(floor.p99),
+            ..self
+        }
+    }
+}
+
+/// Serialized window contents, carried across restarts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TipIndexSnapshot {
+    /// (bucket id, per-bin counts), oldest first
+    pub buckets: Vec<(u64, Vec<u32>)>,
+}
+
+/// Rolling slot-bucketed tip sketch
+#[derive(Debug, Clone)]
+pub struct TipPercentileIndex {
+    buckets: VecDeque<SlotBucket>,
+    totals: [u64; BIN_COUNT],
+    count: u64,
+    window_buckets: usize,
+}
+
+impl Default for TipPercentileIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TipPercentileIndex {
+    /// Create an index covering the default 1000-slot window
+    pub fn new() -> Self {
+        Self::with_window_buckets(DEFAULT_WINDOW_BUCKETS)
+    }
+
+    /// Create an index retaining `window_buckets` 100-slot windows
+    pub fn with_window_buckets(window_buckets: usize) -> Self {
+        Self {
+            buckets: VecDeque::with_capacity(window_buckets.max(1) + 1),
+            totals: [0; BIN_COUNT],
+            count: 0,
+            window_buckets: window_buckets.max(1),
+        }
+    }
+
+    /// Record a tip observed at `slot` (0 when unknown)
+    ///
+    /// Out-of-order slots older than the newest window are folded into it.
+    /// Tips without a slot open a new window every 1000 tips, so the index
+    /// keeps the most recent ones instead of growing without bound.
+    pub fn record(&mut self, slot: u64, tip_lamports: u64) {
+        let bucket_id = match self.buckets.back() {
+            Some(newest) if slot == 0 && newest.len() >= UNSLOTTED_TIPS_PER_BUCKET => newest.bucket_id + 1,
+            _ => slot / SLOTS_PER_BUCKET,
+        };
+
+        let needs_bucket = match self.buckets.back() {
+            Some(newest) => bucket_id > newest.bucket_id,
+            None => true,
+        };
+        if needs_bucket {
+            self.buckets.push_back(SlotBucket {
+                bucket_id,
+                bins: [0; BIN_COUNT],
+            });
+            self.evict(bucket_id);
+        }
+
+        let bin = bin_index(tip_lamports);
+        if let Some(bucket) = self.buckets.back_mut() {
+            bucket.bins[bin] += 1;
+        }
+        self.totals[bin] += 1;
+        self.count += 1;
+    }
+
+    /// Drop windows that fell out of range of the newest bucket
+    fn evict(&mut self, newest_bucket: u64) {
+        let oldest_kept = newest_bucket.saturating_sub(self.window_buckets as u64 - 1);
+        while self
+            .buckets
+            .front()
+            .is_some_and(|b| b.bucket_id < oldest_kept)
+        {
+            if let Some(expired) = self.buckets.pop_front() {
+                for (total, &n) in self.totals.iter_mut().zip(expired.bins.iter()) {
+                    *total -= n as u64;
+                    self.count -= n as u64;
+                }
+            }
+        }
+    }
+
+    /// Copy out the retained windows
+    pub fn snapshot(&self) -> TipIndexSnapshot {
+        TipIndexSnapshot {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|bucket| (bucket.bucket_id, bucket.bins.to_vec()))
+                .collect(),
+        }
+    }
+
+    /// Replace the index contents with a snapshot
+    ///
+    /// Windows with a different bin layout are dropped; windows beyond this
+    /// index's range are evicted as usual.
+    pub fn restore(&mut self, snapshot: &TipIndexSnapshot) {
+        self.buckets.clear();
+        self.totals = [0; BIN_COUNT];
+        self.count = 0;
+
+        let mut buckets: Vec<_> = snapshot
+            .buckets
+            .iter()
+            .filter(|(_, bins)| bins.len() == BIN_COUNT)
+            .collect();
+        buckets.sort_by_key(|(bucket_id, _)| *bucket_id);
+        buckets.dedup_by_key(|(bucket_id, _)| *bucket_id);
+
+        for (bucket_id, counts) in buckets {
+            let mut bins = [0; BIN_COUNT];
+            bins.copy_from_slice(counts);
+            for (total, &n) in self.totals.iter_mut().zip(bins.iter()) {
+                *total += n as u64;
+                self.count += n as u64;
+            }
+            self.buckets.push_back(SlotBucket {
+                bucket_id: *bucket_id,
+                bins,
+            });
+            self.evict(*bucket_id);
+        }
+    }
+
+    /// Tips in the current window
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Percentage of recent tips strictly below `tip` (50.0 with no history)
+    pub fn percentile_of(&self, tip_lamports: u64) -> f32 {
+        if self.count == 0 {
+            return 50.0;
+        }
+
+        let bin = bin_index(tip_lamports);
+        let below: u64 = self.totals[..bin].iter().sum();
+        let (lower, upper) = bin_bounds(bin);
+        let fraction = ((tip_lamports as f64 - lower) / (upper - lower)).clamp(0.0, 1.0);
+        let partial = self.totals[bin] as f64 * fraction;
+
+        ((below as f64 + partial) / self.count as f64 * 100.0) as f32
+    }
+
+    /// Tip at quantile `q` (0.0-1.0), interpolated within its bin
+    pub fn quantile(&self, q: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+
+        let rank = q.clamp(0.0, 1.0) * self.count as f64;
+        let mut seen = 0.0;
+        for (bin, &n) in self.totals.iter().enumerate() {
+            if n == 0 {
+                continue;
+            }
+            let next = seen + n as f64;
+            if next >= rank {
+                let (lower, upper) = bin_bounds(bin);
+                let fraction = (rank - seen) / n as f64;
+                return (lower + (upper - lower) * fraction) as u64;
+            }
+            seen = next;
+        }
+
+        bin_bounds(BIN_COUNT - 1).1 as u64
+    }
+
+    /// Streaming percentiles for the API / tip recommendation
+    pub fn percentiles(&self) -> TipPercentiles {
+        TipPercentiles {
+            p50: self.quantile(0.50),
+            p75: self.quantile(0.75),
+            p90: self.quantile(0.90),
+            p95: self.quantile(0.95),
+            p99: self.quantile(0.99),
+            samples: self.count,
+            window_slots: self.window_buckets as u64 * SLOTS_PER_BUCKET,
+        }
+    }
+}
+
+fn bin_index(tip_lamports: u64) -> usize {
+    let tip = tip_lamports as f64;
+    if tip < MIN_TIP_LAMPORTS {
+        return 0;
+    }
+    let idx = 1 + ((tip / MIN_TIP_LAMPORTS).ln() / BIN_GROWTH.ln()) as usize;
+    idx.min(BIN_COUNT - 1)
+}
+
+/// [lower, upper) lamport range of a bin
+fn bin_bounds(bin: usize) -> (f64, f64) {
+    if bin == 0 {
+        (0.0, MIN_TIP_LAMPORTS)
+    } else {
+        (
+            MIN_TIP_LAMPORTS * BIN_GROWTH.powi(bin as i32 - 1),
+            MIN_TIP_LAMPORTS * BIN_GROWTH.powi(bin as i32),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_index() {
+        let index = TipPercentileIndex::new();
+        assert_eq!(index.percentile_of(50_000), 50.0);
+        assert_eq!(index.quantile(0.9), 0);
+    }
+
+    #[test]
+    fn test_top_bin_range() {
+        let (lower, upper) = bin_bounds(BIN_COUNT - 1);
+        assert_eq!((lower / 1e9) as u64, 36);
+        assert_eq!((upper / 1e9) as u64, 45);
+        assert_eq!(bin_index(1_000_000_000_000), BIN_COUNT - 1);
+    }
+
+    #[test]
+    fn test_percentiles_track_distribution() {
+        let mut index = TipPercentileIndex::new();
+        for tip in 1..=1000u64 {
+            index.record(1_000, tip * 1_000);
+        }
+
+        let p = index.percentiles();
+        assert_eq!(p.samples, 1000);
+        for (estimate, exact) in [(p.p50, 500_000.0), (p.p90, 900_000.0), (p.p99, 990_000.0)] {
+            let error = (estimate as f64 - exact).abs() / exact;
+            assert!(error < 0.12, "estimate {} vs {}", estimate, exact);
+        }
+
+        let pct = index.percentile_of(750_000);
+        assert!((pct - 75.0).abs() < 5.0, "percentile {}", pct);
+        assert!(index.percentile_of(10_000_000) > 99.0);
+    }
+
+    #[test]
+    fn test_old_windows_evicted() {
+        let mut index = TipPercentileIndex::with_window_buckets(2);
+        index.record(0, 1_000_000);
+        index.record(150, 5_000);
+        assert_eq!(index.len(), 2);
+
+        // Slot 250 opens bucket 2; bucket 0 falls out of the 2-window range
+        index.record(250, 5_000);
+        assert_eq!(index.len(), 2);
+        assert!(index.quantile(0.99) < 10_000);
+    }
+
+    #[test]
+    fn test_unslotted_tips_evicted_by_count() {
+        let mut index = TipPercentileIndex::with_window_buckets(2);
+        for _ in 0..UNSLOTTED_TIPS_PER_BUCKET {
+            index.record(0, 1_000_000);
+        }
+        for _ in 0..2 * UNSLOTTED_TIPS_PER_BUCKET {
+            index.record(0, 5_000);
+        }
+
+        // Only the newest two windows' worth of tips remain
+        assert_eq!(index.len(), 2 * UNSLOTTED_TIPS_PER_BUCKET);
+        assert!(index.quantile(0.99) < 10_000);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut index = TipPercentileIndex::new();
+        for tip in 1..=200u64 {
+            index.record(tip * 10, tip * 5_000);
+        }
+
+        let json = serde_json::to_string(&index.snapshot()).unwrap();
+        let mut restored = TipPercentileIndex::new();
+        restored.restore(&serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.percentiles(), index.percentiles());
+
+        // A narrower index keeps only the newest windows (slots 1900-2000)
+        let mut narrow = TipPercentileIndex::with_window_buckets(2);
+        narrow.restore(&index.snapshot());
+        assert_eq!(narrow.len(), 11);
+    }
+
+    #[test]
+    fn test_recommend_by_risk() {
+        let mut index = TipPercentileIndex::new();
+        for tip in 1..=100u64 {
+            index.record(0, tip * 10_000);
+        }
+        let p = index.percentiles();
+
+        assert_eq!(p.recommend(&MevRiskScore::new(0.9)), p.p95);
+        assert_eq!(p.recommend(&MevRiskScore::new(0.1)), p.p50);
+        assert!(p.p95 > p.p50);
+    }
+
+    #[test]
+    fn test_recommend_for_protection_tier() {
+        let mut index = TipPercentileIndex::new();
+        for tip in 1..=100u64 {
+            index.record(0, tip * 10_000);
+        }
+        let p = index.percentiles();
+        let tiers = sentinel_core::ProtectionTierSettings::default();
+        let (high, low) = (MevRiskScore::new(0.9), MevRiskScore::new(0.1));
+
+        // Standard reproduces the untiered recommendation
+        assert_eq!(p.recommend_for(&high, &tiers.standard), p.recommend(&high));
+        assert_eq!(p.recommend_for(&low, &tiers.standard), p.recommend(&low));
+        assert_eq!(p.recommend_for(&high, &tiers.economy), p.p75);
+        assert_eq!(p.recommend_for(&high, &tiers.max), p.p99);
+        assert_eq!(p.recommend_for(&low, &tiers.max), p.p75);
+    }
+
+    #[test]
+    fn test_network_floor_raises_local_percentiles() {
+        let mut index = TipPercentileIndex::new();
+        for tip in 1..=100u64 {
+            index.record(0, tip * 1_000);
+        }
+        let local = index.percentiles();
+        let floor = TipFloor {
+            p25: 1_000,
+            p50: 10_000,
+            p75: 200_000,
+            p95: 1_000_000,
+            p99: 5_000_000,
+            ema_p50: 10_000,
+            observed_at_ms: 0,
+        };
+
+        let merged = local.with_network_floor(&floor);
+        assert_eq!(merged.p50, local.p50.max(10_000));
+        assert_eq!(merged.p75, 200_000);
+        assert_eq!(merged.p90, 800_000);
+        assert_eq!(merged.p95, 1_000_000);
+        assert_eq!(merged.samples, local.samples);
+    }
+}
@@ -1,15 +1,362 @@
 // Transaction feature extraction module
+//
+// Confirmed transactions go through `extract_from_confirmed`, which takes swap
+// amounts from the DEX's own Anchor events when it emitted any (Whirlpool
+// `Traded`, Raydium CLMM `SwapEvent`) and otherwise reconstructs the swap from
+// the fee payer's balance deltas (`BalanceDeltaSwap`), so novel DEXes still
+// get `is_dex_swap` and amounts. Events come from `Program data:` log lines
+// (`emit!`) and from self-CPI event instructions (`emit_cpi!`), which survive
+// log truncation. Events are only trusted from the program that defines them,
+// so another program logging the same bytes cannot spoof amounts.
+//
+// v0 transactions go through `extract_from_versioned`, which resolves their
+// lookup tables via the shared `AltCache` instead of an RPC call per table.
+// Stateless scoring of wire transactions (`decode_wire_transaction`) uses
+// `extract_from_static_keys` instead, which never touches RPC.
 use crate::features_enhanced::FeatureVector;
-use sentinel_core::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sentinel_core::{AltCache, ConfirmedTransaction, Result, SentinelError};
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::hashv;
 use solana_sdk::instruction::CompiledInstruction;
-use solana_sdk::transaction::Transaction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::str::FromStr;
+
+pub const WHIRLPOOL_PROGRAM: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+pub const RAYDIUM_CLMM_PROGRAM: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+
+/// Wrapped SOL; native lamports are reported under this mint
+pub const NATIVE_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Prefix of Anchor's `emit_cpi!` instruction data (`EVENT_IX_TAG`, little-endian)
+const EVENT_IX_TAG: [u8; 8] = 0x1d9a_cb51_2ea5_45e4u64.to_le_bytes();
+
+/// Swap event decoders: (emitting program, Anchor event name, venue)
+const SWAP_EVENTS: &[(&str, &str, SwapVenue)] = &[
+    (WHIRLPOOL_PROGRAM, "Traded", SwapVenue::Whirlpool),
+    (RAYDIUM_CLMM_PROGRAM, "SwapEvent", SwapVenue::RaydiumClmm),
+];
+
+/// DEX that emitted a swap event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapVenue {
+    Whirlpool,
+    RaydiumClmm,
+}
+
+/// Exact amounts of one pool swap, as reported by the pool program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapEvent {
+    pub venue: SwapVenue,
+    pub pool: Pubkey,
+
+    /// Token A → B (Whirlpool) / token 0 → 1 (Raydium CLMM)
+    pub a_to_b: bool,
+
+    pub input_amount: u64,
+    pub output_amount: u64,
+
+    /// Token-2022 transfer fees on each side
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+}
+
+impl SwapEvent {
+    /// Decode an Anchor event (8-byte discriminator + borsh) emitted by `program`
+    pub fn decode(program: &Pubkey, data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, body) = data.split_at(8);
+        let program = program.to_string();
+        let (_, _, venue) = SWAP_EVENTS
+            .iter()
+            .find(|(id, name, _)| *id == program && event_discriminator(name) == discriminator)?;
+
+        match venue {
+            // whirlpool, a_to_b, pre/post sqrt price (u128), input, output, input/output transfer fee, ...
+            SwapVenue::Whirlpool => Some(Self {
+                venue: *venue,
+                pool: read_pubkey(body, 0)?,
+                a_to_b: read_bool(body, 32)?,
+                input_amount: read_u64(body, 65)?,
+                output_amount: read_u64(body, 73)?,
+                input_transfer_fee: read_u64(body, 81)?,
+                output_transfer_fee: read_u64(body, 89)?,
+            }),
+            // pool, sender, token accounts 0/1, amount_0, fee_0, amount_1, fee_1, zero_for_one, ...
+            SwapVenue::RaydiumClmm => {
+                let (amount_0, fee_0) = (read_u64(body, 128)?, read_u64(body, 136)?);
+                let (amount_1, fee_1) = (read_u64(body, 144)?, read_u64(body, 152)?);
+                let zero_for_one = read_bool(body, 160)?;
+                let ((input, input_fee), (output, output_fee)) = if zero_for_one {
+                    ((amount_0, fee_0), (amount_1, fee_1))
+                } else {
+                    ((amount_1, fee_1), (amount_0, fee_0))
+                };
+                Some(Self {
+                    venue: *venue,
+                    pool: read_pubkey(body, 0)?,
+                    a_to_b: zero_for_one,
+                    input_amount: input,
+                    output_amount: output,
+                    input_transfer_fee: input_fee,
+                    output_transfer_fee: output_fee,
+                })
+            }
+        }
+    }
+
+    /// Decode an `emit_cpi!` event instruction executed by `program`
+    pub fn decode_cpi(program: &Pubkey, instruction_data: &[u8]) -> Option<Self> {
+        let event = instruction_data.strip_prefix(EVENT_IX_TAG.as_slice())?;
+        Self::decode(program, event)
+    }
+}
+
+/// Anchor event discriminator: `sha256("event:<Name>")[..8]`
+fn event_discriminator(name: &str) -> [u8; 8] {
+    let hash = hashv(&[b"event:", name.as_bytes()]);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.as_ref()[..8]);
+    discriminator
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)?.try_into().ok().map(u64::from_le_bytes)
+}
+
+fn read_bool(data: &[u8], offset: usize) -> Option<bool> {
+    data.get(offset).map(|b| *b != 0)
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Pubkey::try_from(data.get(offset..offset + 32)?).ok()
+}
+
+/// Swap events from `Program data:` lines, attributed via the invoke stack
+pub fn swap_events_from_logs(logs: &[String]) -> Vec<SwapEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            let decoded = stack.last().zip(BASE64.decode(data.trim()).ok());
+            events.extend(decoded.and_then(|(program, bytes)| SwapEvent::decode(program, &bytes)));
+            continue;
+        }
+
+        let mut words = rest.split_whitespace();
+        let (Some(id), Some(action)) = (words.next(), words.next()) else {
+            continue;
+        };
+        match action {
+            "invoke" => stack.extend(Pubkey::from_str(id).ok()),
+            "success" | "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Swap events from `emit_cpi!` inner instructions in `getTransaction` meta (`json` encoding)
+///
+/// `account_keys` are the message's static keys; loaded lookup-table
+/// addresses from `meta.loadedAddresses` are appended here.
+pub fn swap_events_from_inner_instructions(account_keys: &[Pubkey], meta: &serde_json::Value) -> Vec<SwapEvent> {
+    let loaded = ["writable", "readonly"].into_iter().flat_map(|kind| {
+        meta["loadedAddresses"][kind]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|key| key.as_str().and_then(|key| Pubkey::from_str(key).ok()))
+    });
+    let keys: Vec<Pubkey> = account_keys.iter().copied().chain(loaded).collect();
+
+    meta["innerInstructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|group| group["instructions"].as_array().into_iter().flatten())
+        .filter_map(|ix| {
+            let program = keys.get(ix["programIdIndex"].as_u64()? as usize)?;
+            let data = solana_sdk::bs58::decode(ix["data"].as_str()?).into_vec().ok()?;
+            SwapEvent::decode_cpi(program, &data)
+        })
+        .collect()
+}
+
+/// Swap reconstructed from one owner's balance changes
+///
+/// The fallback for DEXes whose instructions and events the extractor does
+/// not know: whatever the program, a swap leaves the trader with less of one
+/// asset and more of another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceDeltaSwap {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+}
+
+impl BalanceDeltaSwap {
+    /// `owner`'s outflow and inflow, or None unless one asset went down and another up
+    ///
+    /// `owner_index` is the owner's position in the account keys, for its
+    /// lamports; the fee is added back when it is the fee payer (index 0).
+    /// Native SOL counts as wrapped SOL, and only when no SPL token moved on
+    /// that side, since rent for new token accounts and tips also come out of
+    /// it. With several tokens on one side (unusual for a single swap) the
+    /// largest raw amount wins.
+    pub fn analyze(tx: &ConfirmedTransaction, owner: &Pubkey, owner_index: Option<usize>) -> Option<Self> {
+        let native_mint = Pubkey::from_str(NATIVE_MINT).ok()?;
+        let mut deltas = tx.owner_deltas(owner);
+        let mut native = deltas.remove(&native_mint).unwrap_or_default();
+        if let Some(index) = owner_index {
+            native += tx.lamport_delta(index);
+            if index == 0 {
+                native += tx.fee_lamports as i128;
+            }
+        }
+
+        let largest = |outflow: bool| {
+            deltas
+                .iter()
+                .filter(|(_, delta)| if outflow { **delta < 0 } else { **delta > 0 })
+                .map(|(mint, delta)| (*mint, delta.unsigned_abs()))
+                .max_by_key(|(_, amount)| *amount)
+                .or_else(|| {
+                    let moved = if outflow { native < 0 } else { native > 0 };
+                    moved.then(|| (native_mint, native.unsigned_abs()))
+                })
+        };
+        let (input_mint, input_amount) = largest(true)?;
+        let (output_mint, output_amount) = largest(false)?;
+        if input_mint == output_mint {
+            return None;
+        }
+
+        Some(Self {
+            input_mint,
+            output_mint,
+            input_amount: u64::try_from(input_amount).ok()?,
+            output_amount: u64::try_from(output_amount).ok()?,
+        })
+    }
+}
+
+/// Extract features from a confirmed transaction
+///
+/// `confirmed` is the `getTransaction` result (`json` encoding). Swap
+/// amounts come from the pools' events (first hop's input, last hop's
+/// output); without events they fall back to the fee payer's balance deltas
+/// ([`BalanceDeltaSwap`]), which blur fee-on-transfer tokens and SOL swaps
+/// that also pay rent or tips.
+pub fn extract_from_confirmed(transaction: &Transaction, confirmed: &serde_json::Value) -> Result<FeatureVector> {
+    let mut features = extract_from_transaction(transaction)?;
+    let meta = &confirmed["meta"];
+    // A reverted swap still logs its events; its amounts never happened
+    if !meta["err"].is_null() {
+        return Ok(features);
+    }
+
+    let logs: Vec<String> = meta["logMessages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|line| line.as_str().map(str::to_string))
+        .collect();
+    let mut events = swap_events_from_inner_instructions(&transaction.message.account_keys, meta);
+    if events.is_empty() {
+        events = swap_events_from_logs(&logs);
+    }
+
+    if let (Some(first), Some(last)) = (events.first(), events.last()) {
+        features.is_dex_swap = true;
+        features.input_amount = first.input_amount as f64;
+        features.output_amount = last.output_amount as f64;
+        features.swap_route_length = events.len() as u32;
+        return Ok(features);
+    }
+
+    let Some(payer) = transaction.message.account_keys.first() else {
+        return Ok(features);
+    };
+    if let Some(swap) = BalanceDeltaSwap::analyze(&ConfirmedTransaction::from_rpc_json(confirmed)?, payer, Some(0)) {
+        features.is_dex_swap = true;
+        features.input_amount = swap.input_amount as f64;
+        features.output_amount = swap.output_amount as f64;
+        features.swap_route_length = features.swap_route_length.max(1);
+    }
+    Ok(features)
+}
 
 /// Extract features from a signed Solana transaction
 pub fn extract_from_transaction(transaction: &Transaction) -> Result<FeatureVector> {
+    Ok(extract_from_parts(&transaction.message.instructions, &transaction.message.account_keys))
+}
+
+/// Extract features from a v0 or legacy transaction, resolving lookup tables through `alt_cache`
+pub async fn extract_from_versioned(transaction: &VersionedTransaction, alt_cache: &AltCache) -> Result<FeatureVector> {
+    let account_keys = alt_cache.account_keys(&transaction.message).await?;
+    let mut features = extract_from_parts(transaction.message.instructions(), &account_keys);
+    features.account_count = account_keys.len() as u32;
+    features.instruction_count = transaction.message.instructions().len() as u32;
+    features.uses_lookup_tables = transaction
+        .message
+        .address_table_lookups()
+        .is_some_and(|lookups| !lookups.is_empty());
+    Ok(features)
+}
+
+/// Largest serialized transaction the network accepts (`PACKET_DATA_SIZE`)
+pub const MAX_WIRE_TRANSACTION_BYTES: usize = 1232;
+
+/// Decode a base64 wire transaction (legacy or v0, signed or not)
+pub fn decode_wire_transaction(base64_tx: &str) -> Result<VersionedTransaction> {
+    let bytes = BASE64
+        .decode(base64_tx.trim())
+        .map_err(|e| SentinelError::SerializationError(format!("Transaction is not base64: {}", e)))?;
+    if bytes.len() > MAX_WIRE_TRANSACTION_BYTES {
+        return Err(SentinelError::SerializationError(format!(
+            "Transaction is {} bytes (max {})",
+            bytes.len(),
+            MAX_WIRE_TRANSACTION_BYTES
+        )));
+    }
+    bincode::deserialize(&bytes)
+        .map_err(|e| SentinelError::SerializationError(format!("Invalid wire transaction: {}", e)))
+}
+
+/// Extract features from a v0 or legacy transaction without resolving lookup tables
+///
+/// Only the static account keys are inspected, so a DEX program reached
+/// solely through a lookup table is missed.
+pub fn extract_from_static_keys(transaction: &VersionedTransaction) -> FeatureVector {
+    let account_keys = transaction.message.static_account_keys();
+    let mut features = extract_from_parts(transaction.message.instructions(), account_keys);
+    features.account_count = account_keys.len() as u32;
+    features.instruction_count = transaction.message.instructions().len() as u32;
+    features.uses_lookup_tables = transaction
+        .message
+        .address_table_lookups()
+        .is_some_and(|lookups| !lookups.is_empty());
+    features
+}
+
+fn extract_from_parts(instructions: &[CompiledInstruction], account_keys: &[Pubkey]) -> FeatureVector {
     let mut features = FeatureVector::default();
 
     // Extract compute budget instructions
-    for instruction in &transaction.message.instructions {
+    for instruction in instructions {
         if let Some((compute_units, price)) = parse_compute_budget(instruction) {
             if compute_units > 0 {
                 features.compute_unit_limit = compute_units;
@@ -21,13 +368,13 @@ pub fn extract_from_transaction(transaction: &Transaction) -> Result<FeatureVect
     }
 
     // Check for DEX swap patterns
-    features.is_dex_swap = is_dex_transaction(transaction);
+    features.is_dex_swap = is_dex_transaction(account_keys);
 
     // Default safe values
     features.oracle_confidence = 0.95;
     features.tip_percentile_vs_recent = 50.0;
 
-    Ok(features)
+    features
 }
 
 fn parse_compute_budget(instruction: &CompiledInstruction) -> Option<(u32, u64)> {
@@ -71,7 +418,7 @@ fn parse_compute_budget(instruction: &CompiledInstruction) -> Option<(u32, u64)>
     }
 }
 
-fn is_dex_transaction(transaction: &Transaction) -> bool {
+fn is_dex_transaction(account_keys: &[Pubkey]) -> bool {
     // Check if transaction interacts with known DEX programs
     let known_dex_programs = [
         "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8", // Raydium
@@ -79,9 +426,7 @@ fn is_dex_transaction(transaction: &Transaction) -> bool {
         "JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB",  // Jupiter
     ];
 
-    transaction
-        .message
-        .account_keys
+    account_keys
         .iter()
         .any(|key| known_dex_programs.iter().any(|dex| key.to_string() == *dex))
 }
@@ -89,6 +434,7 @@ fn is_dex_transaction(transaction: &Transaction) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_sdk::hash::Hash;
     use solana_sdk::message::Message;
     use solana_sdk::signature::Keypair;
     use solana_sdk::signer::Signer;
@@ -108,4 +454,239 @@ mod tests {
         let features = extract_from_transaction(&transaction).unwrap();
         assert!(!features.is_dex_swap);
     }
+
+    #[tokio::test]
+    async fn test_extract_from_versioned_resolves_lookup_tables() {
+        let payer = Pubkey::new_unique();
+        let table = Pubkey::new_unique();
+        let raydium = Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap();
+        let pools: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let alt_cache = AltCache::new("http://127.0.0.1:9").unwrap();
+        alt_cache.insert(table, pools.clone());
+
+        let accounts = pools.iter().map(|pool| solana_sdk::instruction::AccountMeta::new(*pool, false)).collect();
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(raydium, &[9], accounts);
+        let message = alt_cache.compile_v0(&payer, &[ix], &[table], Hash::default()).await.unwrap();
+        let transaction = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message,
+        };
+
+        let features = extract_from_versioned(&transaction, &alt_cache).await.unwrap();
+        assert!(features.uses_lookup_tables);
+        assert!(features.is_dex_swap);
+        assert_eq!(features.account_count, 6);
+        assert_eq!(features.instruction_count, 1);
+    }
+
+    fn whirlpool_traded(pool: &Pubkey, input: u64, output: u64) -> Vec<u8> {
+        let mut data = event_discriminator("Traded").to_vec();
+        data.extend_from_slice(pool.as_ref());
+        data.push(1);
+        data.extend_from_slice(&[0u8; 32]); // pre/post sqrt price
+        for value in [input, output, 7, 0, 30, 3] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    }
+
+    fn raydium_swap_event(pool: &Pubkey, amount_0: u64, amount_1: u64, zero_for_one: bool) -> Vec<u8> {
+        let mut data = event_discriminator("SwapEvent").to_vec();
+        data.extend_from_slice(pool.as_ref());
+        data.extend_from_slice(&[0u8; 96]); // sender, token accounts
+        for value in [amount_0, 0, amount_1, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(zero_for_one as u8);
+        data.extend_from_slice(&[0u8; 36]); // sqrt price, liquidity, tick
+        data
+    }
+
+    #[test]
+    fn test_swap_events_from_logs_need_the_defining_program() {
+        let pool = Pubkey::new_unique();
+        let event = format!("Program data: {}", BASE64.encode(whirlpool_traded(&pool, 1_000, 990)));
+        let impostor = Pubkey::new_unique();
+        let logs: Vec<String> = vec![
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]".to_string(),
+            format!("Program {} invoke [2]", WHIRLPOOL_PROGRAM),
+            "Program log: Instruction: Swap".to_string(),
+            event.clone(),
+            format!("Program {} consumed 40000 of 200000 compute units", WHIRLPOOL_PROGRAM),
+            format!("Program {} success", WHIRLPOOL_PROGRAM),
+            format!("Program {} invoke [2]", impostor),
+            event,
+            format!("Program {} success", impostor),
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success".to_string(),
+        ];
+
+        let events = swap_events_from_logs(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].venue, SwapVenue::Whirlpool);
+        assert_eq!(events[0].pool, pool);
+        assert!(events[0].a_to_b);
+        assert_eq!((events[0].input_amount, events[0].output_amount), (1_000, 990));
+        assert_eq!(events[0].input_transfer_fee, 7);
+    }
+
+    #[test]
+    fn test_raydium_cpi_event_orients_amounts() {
+        let program = Pubkey::from_str(RAYDIUM_CLMM_PROGRAM).unwrap();
+        let mut data = EVENT_IX_TAG.to_vec();
+        data.extend(raydium_swap_event(&Pubkey::new_unique(), 500, 2_000, false));
+
+        let event = SwapEvent::decode_cpi(&program, &data).unwrap();
+        assert_eq!((event.input_amount, event.output_amount), (2_000, 500));
+        assert!(!event.a_to_b);
+
+        // Plain event bytes (no CPI tag) and truncated bodies are rejected
+        assert!(SwapEvent::decode_cpi(&program, &data[8..]).is_none());
+        assert!(SwapEvent::decode(&program, &data[8..100]).is_none());
+    }
+
+    #[test]
+    fn test_extract_from_confirmed_prefers_events() {
+        let payer = Keypair::new();
+        let usdc = Pubkey::new_unique();
+        let sol = Pubkey::new_unique();
+        let whirlpool = Pubkey::from_str(WHIRLPOOL_PROGRAM).unwrap();
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(whirlpool, &[], vec![]);
+        let mut transaction = Transaction::new_unsigned(Message::new(&[ix], Some(&payer.pubkey())));
+        transaction.signatures = vec![solana_sdk::signature::Signature::default()];
+
+        // Balance deltas include the fee-on-transfer haircut; the event has the pool's amounts
+        let mut cpi = EVENT_IX_TAG.to_vec();
+        cpi.extend(whirlpool_traded(&Pubkey::new_unique(), 1_000_000, 49_000));
+        let balance = |mint: &Pubkey, amount: u64| {
+            serde_json::json!({
+                "accountIndex": 0,
+                "mint": mint.to_string(),
+                "owner": payer.pubkey().to_string(),
+                "uiTokenAmount": { "amount": amount.to_string() }
+            })
+        };
+        let mut confirmed = serde_json::json!({
+            "slot": 7,
+            "transaction": { "signatures": [transaction.signatures[0].to_string()] },
+            "meta": {
+                "err": null,
+                "fee": 5000,
+                "preTokenBalances": [balance(&usdc, 2_000_000), balance(&sol, 0)],
+                "postTokenBalances": [balance(&usdc, 999_000), balance(&sol, 48_000)],
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [{
+                        "programIdIndex": 1,
+                        "accounts": [],
+                        "data": solana_sdk::bs58::encode(&cpi).into_string()
+                    }]
+                }],
+                "logMessages": []
+            }
+        });
+
+        let features = extract_from_confirmed(&transaction, &confirmed).unwrap();
+        assert_eq!((features.input_amount, features.output_amount), (1_000_000.0, 49_000.0));
+        assert_eq!(features.swap_route_length, 1);
+
+        confirmed["meta"]["innerInstructions"] = serde_json::json!([]);
+        let features = extract_from_confirmed(&transaction, &confirmed).unwrap();
+        assert_eq!((features.input_amount, features.output_amount), (1_001_000.0, 48_000.0));
+
+        confirmed["meta"]["err"] = serde_json::json!({ "InstructionError": [0, { "Custom": 1 }] });
+        assert_eq!(extract_from_confirmed(&transaction, &confirmed).unwrap().input_amount, 0.0);
+    }
+
+    /// `getTransaction` result for a fee payer moving `lamports` and token balances
+    fn confirmed_with(owner: &Pubkey, lamports: (u64, u64), tokens: &[(&Pubkey, u64, u64)]) -> serde_json::Value {
+        let balance = |mint: &Pubkey, amount: u64| {
+            serde_json::json!({
+                "accountIndex": 1,
+                "mint": mint.to_string(),
+                "owner": owner.to_string(),
+                "uiTokenAmount": { "amount": amount.to_string() }
+            })
+        };
+        serde_json::json!({
+            "slot": 9,
+            "transaction": { "signatures": [solana_sdk::signature::Signature::default().to_string()] },
+            "meta": {
+                "err": null,
+                "fee": 5_000,
+                "preBalances": [lamports.0],
+                "postBalances": [lamports.1],
+                "preTokenBalances": tokens.iter().map(|(mint, pre, _)| balance(mint, *pre)).collect::<Vec<_>>(),
+                "postTokenBalances": tokens.iter().map(|(mint, _, post)| balance(mint, *post)).collect::<Vec<_>>(),
+            }
+        })
+    }
+
+    #[test]
+    fn test_balance_deltas_reconstruct_unknown_dex_swap() {
+        let owner = Pubkey::new_unique();
+        let (usdc, bonk) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let native = Pubkey::from_str(NATIVE_MINT).unwrap();
+        let analyze = |value: serde_json::Value| {
+            BalanceDeltaSwap::analyze(&ConfirmedTransaction::from_rpc_json(&value).unwrap(), &owner, Some(0))
+        };
+
+        // Token → token; the lamports spent on a new token account are ignored
+        let swap = analyze(confirmed_with(&owner, (10_000_000, 7_955_720), &[(&usdc, 500, 200), (&bonk, 0, 9_000)]));
+        assert_eq!(
+            swap,
+            Some(BalanceDeltaSwap {
+                input_mint: usdc,
+                output_mint: bonk,
+                input_amount: 300,
+                output_amount: 9_000,
+            })
+        );
+
+        // SOL → token: the fee is added back, the rest is the input
+        let swap = analyze(confirmed_with(&owner, (2_000_000_000, 999_995_000), &[(&usdc, 0, 150_000_000)])).unwrap();
+        assert_eq!((swap.input_mint, swap.input_amount), (native, 1_000_000_000));
+
+        // Wrapped SOL closed back to native nets out against the lamports
+        let swap = analyze(confirmed_with(&owner, (1_000_000, 801_000_000), &[(&native, 800_000_000, 0), (&usdc, 40, 0)])).unwrap();
+        assert_eq!((swap.input_mint, swap.output_mint, swap.output_amount), (usdc, native, 5_000));
+
+        // A plain transfer is not a swap
+        assert_eq!(analyze(confirmed_with(&owner, (1_000_000, 995_000), &[(&usdc, 500, 200)])), None);
+    }
+
+    #[test]
+    fn test_unknown_program_swap_marked_from_deltas() {
+        let payer = Keypair::new();
+        let novel_dex = Pubkey::new_unique();
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(novel_dex, &[9], vec![]);
+        let transaction = Transaction::new_unsigned(Message::new(&[ix], Some(&payer.pubkey())));
+        assert!(!extract_from_transaction(&transaction).unwrap().is_dex_swap);
+
+        let (usdc, bonk) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let confirmed = confirmed_with(&payer.pubkey(), (1_000_000, 995_000), &[(&usdc, 500, 200), (&bonk, 0, 9_000)]);
+        let features = extract_from_confirmed(&transaction, &confirmed).unwrap();
+        assert!(features.is_dex_swap);
+        assert_eq!((features.input_amount, features.output_amount), (300.0, 9_000.0));
+        assert_eq!(features.swap_route_length, 1);
+    }
+
+    #[test]
+    fn test_decode_wire_transaction_static_keys() {
+        let payer = Keypair::new();
+        let raydium = Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap();
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(raydium, &[9], vec![]);
+        let transaction = Transaction::new_unsigned(Message::new(&[ix], Some(&payer.pubkey())));
+        let wire = BASE64.encode(bincode::serialize(&transaction).unwrap());
+
+        let decoded = decode_wire_transaction(&wire).unwrap();
+        let features = extract_from_static_keys(&decoded);
+        assert!(features.is_dex_swap);
+        assert!(!features.uses_lookup_tables);
+        assert_eq!(features.instruction_count, 1);
+
+        assert!(decode_wire_transaction("not base64!").is_err());
+        assert!(decode_wire_transaction(&BASE64.encode([0u8; 3])).is_err());
+        let oversized = BASE64.encode(vec![1u8; MAX_WIRE_TRANSACTION_BYTES + 1]);
+        assert!(decode_wire_transaction(&oversized).unwrap_err().to_string().contains("max"));
+    }
 }
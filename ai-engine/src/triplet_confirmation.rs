@@ -0,0 +1,397 @@
+//! Cross-Slot Sandwich Triplet Confirmation
+//!
+//! `has_swap_triplet` is decided at extraction time from in-memory history, so
+//! it can only see back-runs that already landed (±2 slots) — the common case
+//! where the attacker's sell lands after the victim is invisible. The
+//! [`TripletConfirmer`] keeps each scored victim pending while the stream
+//! ingestor delivers subsequent slots, then settles it once
+//! `confirmation_slots` have passed:
+//!
+//! - **confirmed**: some actor swapped the victim's direction at or before the
+//!   victim's slot and sold back (output → input mint) at or after it
+//! - **cleared**: no such pair of legs appeared
+//!
+//! Tentative detections that settle as confirmed are counted as upgrades.
+//! Every settled victim yields a [`LabeledExample`] (label source
+//! [`LabelSource::Triplet`]) for [`DatasetBuilder::add_examples`](crate::dataset::DatasetBuilder::add_examples).
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, VecDeque};
+use tracing::{debug, info};
+
+use crate::dataset::{LabelSource, LabeledExample};
+use crate::features_enhanced::TransactionData;
+
+/// Swap observed in a landed block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObservedSwap {
+    /// Fee payer / signer
+    pub actor: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+
+    /// Position within the block
+    pub index: u32,
+}
+
+/// Confirmation window settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TripletSettings {
+    /// Slots before the victim a front-run may land in
+    pub lookback_slots: u64,
+
+    /// Slots after the victim to wait for a back-run before settling
+    pub confirmation_slots: u64,
+
+    /// Pending victims kept (oldest settled early beyond this)
+    pub max_pending: usize,
+}
+
+impl Default for TripletSettings {
+    fn default() -> Self {
+        Self {
+            lookback_slots: 2,
+            confirmation_slots: 4,
+            max_pending: 10_000,
+        }
+    }
+}
+
+/// Settled victim
+#[derive(Debug, Clone, PartialEq)]
+pub struct TripletOutcome {
+    pub request_id: String,
+    pub victim_slot: u64,
+
+    /// Whether extraction flagged `has_swap_triplet`
+    pub tentative: bool,
+
+    /// Sandwiching actor when confirmed
+    pub attacker: Option<Pubkey>,
+
+    /// Labeled row for the dataset exporter
+    pub example: LabeledExample,
+}
+
+impl TripletOutcome {
+    pub fn confirmed(&self) -> bool {
+        self.attacker.is_some()
+    }
+
+    /// Tentative detection now confirmed by later slots
+    pub fn upgraded(&self) -> bool {
+        self.tentative && self.confirmed()
+    }
+}
+
+/// Running totals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TripletStats {
+    pub tracked: u64,
+    pub confirmed: u64,
+    pub cleared: u64,
+
+    /// Confirmed victims that extraction had flagged tentatively
+    pub upgraded: u64,
+
+    /// Confirmed victims extraction missed (back-run landed later)
+    pub late_confirmed: u64,
+}
+
+#[derive(Debug, Clone)]
+struct PendingVictim {
+    request_id: String,
+    slot: u64,
+    actor: Pubkey,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    timestamp_ms: u64,
+    features: Vec<f32>,
+    tentative: bool,
+}
+
+/// Delayed-confirmation pass over scored victims
+#[derive(Debug, Default)]
+pub struct TripletConfirmer {
+    settings: TripletSettings,
+    pending: VecDeque<PendingVictim>,
+    slots: BTreeMap<u64, Vec<ObservedSwap>>,
+    highest_slot: u64,
+    stats: TripletStats,
+}
+
+impl TripletConfirmer {
+    pub fn new(settings: TripletSettings) -> Self {
+        Self {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    pub fn stats(&self) -> TripletStats {
+        self.stats
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Track a scored swap until its confirmation window closes
+    ///
+    /// `features` is the vector the model saw; `tentative` is the extraction-time
+    /// `has_swap_triplet`. Non-swaps are ignored (returns false).
+    pub fn track(&mut self, request_id: impl Into<String>, tx: &TransactionData, features: Vec<f32>, tentative: bool) -> bool {
+        let Some(ref swap) = tx.swap_details else {
+            return false;
+        };
+        self.pending.push_back(PendingVictim {
+            request_id: request_id.into(),
+            slot: tx.slot,
+            actor: tx.fee_payer,
+            input_mint: swap.input_mint,
+            output_mint: swap.output_mint,
+            timestamp_ms: tx.timestamp_ms,
+            features,
+            tentative,
+        });
+        self.stats.tracked += 1;
+        true
+    }
+
+    /// Feed a landed slot from the stream ingestor; returns victims settled by it
+    pub fn observe_slot(&mut self, slot: u64, swaps: Vec<ObservedSwap>) -> Vec<TripletOutcome> {
+        self.slots.entry(slot).or_default().extend(swaps);
+        self.highest_slot = self.highest_slot.max(slot);
+
+        let mut outcomes = Vec::new();
+        let mut remaining = VecDeque::with_capacity(self.pending.len());
+        let overflow = self.pending.len().saturating_sub(self.settings.max_pending);
+        for (position, victim) in std::mem::take(&mut self.pending).into_iter().enumerate() {
+            if position < overflow || victim.slot + self.settings.confirmation_slots <= self.highest_slot {
+                outcomes.push(self.settle(victim));
+            } else {
+                remaining.push_back(victim);
+            }
+        }
+        self.pending = remaining;
+        self.prune();
+
+        if !outcomes.is_empty() {
+            debug!("🥪 Settled {} triplet candidates at slot {}", outcomes.len(), slot);
+        }
+        outcomes
+    }
+
+    /// Settle everything still pending against the slots seen so far
+    pub fn flush(&mut self) -> Vec<TripletOutcome> {
+        let pending: Vec<_> = self.pending.drain(..).collect();
+        let outcomes = pending.into_iter().map(|victim| self.settle(victim)).collect();
+        self.prune();
+        outcomes
+    }
+
+    fn settle(&mut self, victim: PendingVictim) -> TripletOutcome {
+        let attacker = self.find_attacker(&victim);
+        match attacker {
+            Some(attacker) => {
+                self.stats.confirmed += 1;
+                if victim.tentative {
+                    self.stats.upgraded += 1;
+                } else {
+                    self.stats.late_confirmed += 1;
+                }
+                info!(
+                    "🥪 Confirmed sandwich on {} (slot {}) by {}",
+                    victim.request_id, victim.slot, attacker
+                );
+            }
+            None => self.stats.cleared += 1,
+        }
+
+        TripletOutcome {
+            example: LabeledExample {
+                request_id: victim.request_id.clone(),
+                timestamp_ms: victim.timestamp_ms,
+                features: victim.features,
+                label: attacker.is_some(),
+                label_source: LabelSource::Triplet,
+            },
+            request_id: victim.request_id,
+            victim_slot: victim.slot,
+            tentative: victim.tentative,
+            attacker,
+        }
+    }
+
+    /// Actor with a same-direction leg before and a reverse leg after the victim
+    fn find_attacker(&self, victim: &PendingVictim) -> Option<Pubkey> {
+        let start = victim.slot.saturating_sub(self.settings.lookback_slots);
+        let end = victim.slot + self.settings.confirmation_slots;
+
+        let front_runs = self
+            .slots
+            .range(start..=victim.slot)
+            .flat_map(|(slot, swaps)| swaps.iter().map(move |swap| (*slot, swap)))
+            .filter(|(_, swap)| {
+                swap.actor != victim.actor && swap.input_mint == victim.input_mint && swap.output_mint == victim.output_mint
+            });
+
+        for (front_slot, front) in front_runs {
+            let has_back_run = self
+                .slots
+                .range(victim.slot..=end)
+                .flat_map(|(slot, swaps)| swaps.iter().map(move |swap| (*slot, swap)))
+                .any(|(back_slot, back)| {
+                    back.actor == front.actor
+                        && back.input_mint == victim.output_mint
+                        && back.output_mint == victim.input_mint
+                        && (back_slot, back.index) > (front_slot, front.index)
+                });
+            if has_back_run {
+                return Some(front.actor);
+            }
+        }
+        None
+    }
+
+    /// Drop slots no pending victim can still reference
+    fn prune(&mut self) {
+        let horizon = self
+            .pending
+            .iter()
+            .map(|victim| victim.slot)
+            .min()
+            .unwrap_or(self.highest_slot)
+            .saturating_sub(self.settings.lookback_slots);
+        self.slots = self.slots.split_off(&horizon);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features_enhanced::SwapDetailsData;
+
+    struct Fixture {
+        sol: Pubkey,
+        usdc: Pubkey,
+        victim: Pubkey,
+        bot: Pubkey,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self {
+                sol: Pubkey::new_unique(),
+                usdc: Pubkey::new_unique(),
+                victim: Pubkey::new_unique(),
+                bot: Pubkey::new_unique(),
+            }
+        }
+
+        fn victim_tx(&self, slot: u64) -> TransactionData {
+            TransactionData {
+                slot,
+                fee_payer: self.victim,
+                compute_unit_limit: 200_000,
+                compute_unit_price: 0,
+                jito_tip_lamports: 0,
+                total_fee_lamports: 5_000,
+                account_count: 10,
+                instruction_count: 3,
+                tx_size_bytes: 600,
+                swap_details: Some(SwapDetailsData {
+                    input_mint: self.sol,
+                    output_mint: self.usdc,
+                    input_amount: 1_000.0,
+                    output_amount: 990.0,
+                    expected_output: 1_000.0,
+                    route_length: 1,
+                    slippage_tolerance_bps: 100.0,
+                    pool_liquidity_usd: 0.0,
+                    pool: None,
+                }),
+                time_since_last_slot_ms: 0,
+                next_leader_pubkey: Pubkey::default(),
+                uses_lookup_tables: false,
+                timestamp_ms: 1_700_000_000_000,
+            }
+        }
+
+        fn buy(&self, actor: Pubkey, index: u32) -> ObservedSwap {
+            ObservedSwap { actor, input_mint: self.sol, output_mint: self.usdc, index }
+        }
+
+        fn sell(&self, actor: Pubkey, index: u32) -> ObservedSwap {
+            ObservedSwap { actor, input_mint: self.usdc, output_mint: self.sol, index }
+        }
+    }
+
+    #[test]
+    fn test_late_back_run_confirms_victim() {
+        let f = Fixture::new();
+        let mut confirmer = TripletConfirmer::default();
+        assert!(confirmer.track("victim", &f.victim_tx(100), vec![0.0; 55], false));
+
+        assert!(confirmer.observe_slot(100, vec![f.buy(f.bot, 0), f.buy(f.victim, 1)]).is_empty());
+        // Back-run lands three slots later, outside the extractor's ±2 window
+        assert!(confirmer.observe_slot(103, vec![f.sell(f.bot, 0)]).is_empty());
+
+        let outcomes = confirmer.observe_slot(104, vec![]);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].attacker, Some(f.bot));
+        assert!(outcomes[0].example.label);
+        assert_eq!(outcomes[0].example.label_source, LabelSource::Triplet);
+        assert_eq!(confirmer.stats().late_confirmed, 1);
+        assert_eq!(confirmer.pending_len(), 0);
+    }
+
+    #[test]
+    fn test_tentative_without_back_run_is_cleared() {
+        let f = Fixture::new();
+        let mut confirmer = TripletConfirmer::default();
+        confirmer.track("victim", &f.victim_tx(100), vec![0.0; 55], true);
+
+        // Same-actor buys only: no reverse leg, and the victim's own swap never counts
+        confirmer.observe_slot(100, vec![f.buy(f.bot, 0), f.sell(f.victim, 2)]);
+        let outcomes = confirmer.observe_slot(104, vec![f.buy(f.bot, 0)]);
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(!outcomes[0].confirmed());
+        assert!(!outcomes[0].example.label);
+        assert_eq!(confirmer.stats().cleared, 1);
+    }
+
+    #[test]
+    fn test_tentative_upgrade_and_leg_ordering() {
+        let f = Fixture::new();
+        let other = Pubkey::new_unique();
+        let mut confirmer = TripletConfirmer::default();
+        confirmer.track("upgraded", &f.victim_tx(200), vec![1.0; 55], true);
+
+        // `other` sells before it buys within the slot: not a sandwich
+        confirmer.observe_slot(200, vec![f.sell(other, 0), f.buy(other, 1), f.buy(f.bot, 2), f.sell(f.bot, 4)]);
+        let outcomes = confirmer.flush();
+
+        assert_eq!(outcomes[0].attacker, Some(f.bot));
+        assert!(outcomes[0].upgraded());
+        assert_eq!(confirmer.stats().upgraded, 1);
+    }
+
+    #[test]
+    fn test_overflow_settles_oldest_and_slots_are_pruned() {
+        let f = Fixture::new();
+        let mut confirmer = TripletConfirmer::new(TripletSettings { max_pending: 1, ..Default::default() });
+        confirmer.track("old", &f.victim_tx(10), vec![], false);
+        confirmer.track("new", &f.victim_tx(11), vec![], false);
+
+        let outcomes = confirmer.observe_slot(11, vec![]);
+        assert_eq!(outcomes.iter().map(|o| o.request_id.as_str()).collect::<Vec<_>>(), vec!["old"]);
+        assert_eq!(confirmer.pending_len(), 1);
+
+        confirmer.observe_slot(50, vec![]);
+        assert_eq!(confirmer.pending_len(), 0);
+        assert!(confirmer.slots.keys().all(|slot| *slot >= 48));
+    }
+}
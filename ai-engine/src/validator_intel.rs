@@ -7,12 +7,20 @@
 /// - On-chain MEV extraction rates
 /// 
 /// Updated: Production-ready dataset
+///
+/// Static intel is refined at runtime by [`EpochStatsAggregator`], which
+/// accumulates per-epoch observations from the stream ingestor (blocks
+/// produced, sandwiches seen in those blocks, tips extracted) and blends them
+/// into each validator's rates with exponential decay.
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Mainnet slots per epoch
+pub const SLOTS_PER_EPOCH: u64 = 432_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidatorIntel {
     pub pubkey: String,
     pub is_malicious: bool,
@@ -24,6 +32,203 @@ pub struct ValidatorIntel {
     pub recent_blocks: u32,      // Blocks produced in last epoch
     pub skip_rate: f32,          // Block skip rate
     pub label: String,           // Human-readable label
+    
+    /// Observed per-epoch stats, oldest first (see `EpochStatsAggregator`)
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    pub epoch_history: VecDeque<EpochStats>,
+    
+    /// Where the entry came from, set on export/import (see `intel_exchange`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<IntelProvenance>,
+}
+
+/// Origin of a shared intel entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntelProvenance {
+    /// Operator or feed that produced the entry
+    pub source: String,
+    
+    /// Epochs the entry's rates were observed over (inclusive)
+    pub window_start_epoch: u64,
+    pub window_end_epoch: u64,
+    
+    /// Last update (unix seconds); newer entries win on import
+    pub last_updated: i64,
+}
+
+impl ValidatorIntel {
+    /// Entry for a validator first seen on the stream (no static intel)
+    pub fn observed(pubkey: &Pubkey) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            is_malicious: false,
+            mev_rate: 0.0,
+            stake_sol: 0.0,
+            commission_pct: 0.0,
+            jito_rate: 0.0,
+            avg_tip: 0,
+            recent_blocks: 0,
+            skip_rate: 0.0,
+            label: "Observed".to_string(),
+            epoch_history: VecDeque::new(),
+            provenance: None,
+        }
+    }
+    
+    /// Last `epochs` completed epochs, oldest first
+    pub fn history(&self, epochs: usize) -> Vec<EpochStats> {
+        let skip = self.epoch_history.len().saturating_sub(epochs);
+        self.epoch_history.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Observed validator activity for one epoch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EpochStats {
+    pub epoch: u64,
+    pub blocks_produced: u32,
+    
+    /// Sandwich attacks detected in this validator's blocks
+    pub sandwiches_observed: u32,
+    
+    /// Jito tips paid in this validator's blocks (lamports)
+    pub tips_extracted_lamports: u64,
+}
+
+impl EpochStats {
+    /// Sandwiches per block produced
+    pub fn sandwich_rate(&self) -> f32 {
+        if self.blocks_produced == 0 {
+            return 0.0;
+        }
+        self.sandwiches_observed as f32 / self.blocks_produced as f32
+    }
+    
+    pub fn avg_tip_lamports(&self) -> u64 {
+        if self.blocks_produced == 0 {
+            return 0;
+        }
+        self.tips_extracted_lamports / u64::from(self.blocks_produced)
+    }
+}
+
+/// One produced block as reported by the stream ingestor
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockObservation {
+    pub slot: u64,
+    pub leader: Pubkey,
+    pub sandwiches: u32,
+    pub tips_lamports: u64,
+}
+
+/// Accumulates per-epoch validator stats and blends them into the intel map
+pub struct EpochStatsAggregator {
+    slots_per_epoch: u64,
+    
+    /// Weight kept by the existing rate when an epoch is blended in (0-1)
+    decay: f32,
+    
+    /// Completed epochs retained per validator
+    max_epochs: usize,
+    
+    current_epoch: Option<u64>,
+    pending: HashMap<Pubkey, EpochStats>,
+}
+
+impl Default for EpochStatsAggregator {
+    fn default() -> Self {
+        Self::new(SLOTS_PER_EPOCH, 0.7, 32)
+    }
+}
+
+impl EpochStatsAggregator {
+    pub fn new(slots_per_epoch: u64, decay: f32, max_epochs: usize) -> Self {
+        Self {
+            slots_per_epoch: slots_per_epoch.max(1),
+            decay: decay.clamp(0.0, 1.0),
+            max_epochs: max_epochs.max(1),
+            current_epoch: None,
+            pending: HashMap::new(),
+        }
+    }
+    
+    pub fn epoch_of(&self, slot: u64) -> u64 {
+        slot / self.slots_per_epoch
+    }
+    
+    pub fn current_epoch(&self) -> Option<u64> {
+        self.current_epoch
+    }
+    
+    /// Record a block; crossing into a new epoch finalizes the previous one
+    pub fn record_block(&mut self, block: &BlockObservation, intel: &mut HashMap<Pubkey, ValidatorIntel>) {
+        let Some(stats) = self.epoch_stats(block.slot, block.leader, intel) else {
+            return;
+        };
+        stats.blocks_produced += 1;
+        stats.sandwiches_observed += block.sandwiches;
+        stats.tips_extracted_lamports += block.tips_lamports;
+    }
+    
+    /// Record sandwiches confirmed post hoc in a block already counted by `record_block`
+    pub fn record_incidents(
+        &mut self,
+        slot: u64,
+        leader: Pubkey,
+        sandwiches: u32,
+        intel: &mut HashMap<Pubkey, ValidatorIntel>,
+    ) {
+        if let Some(stats) = self.epoch_stats(slot, leader, intel) {
+            stats.sandwiches_observed += sandwiches;
+        }
+    }
+    
+    /// Pending stats of `leader` for the epoch of `slot`; None for finished epochs
+    fn epoch_stats(
+        &mut self,
+        slot: u64,
+        leader: Pubkey,
+        intel: &mut HashMap<Pubkey, ValidatorIntel>,
+    ) -> Option<&mut EpochStats> {
+        let epoch = self.epoch_of(slot);
+        match self.current_epoch {
+            Some(current) if epoch < current => {
+                tracing::debug!("Ignoring slot {} from finished epoch {}", slot, epoch);
+                return None;
+            }
+            Some(current) if epoch > current => self.finish_epoch(intel),
+            _ => {}
+        }
+        self.current_epoch = Some(epoch);
+        
+        Some(self.pending.entry(leader).or_insert_with(|| EpochStats {
+            epoch,
+            ..Default::default()
+        }))
+    }
+    
+    /// Blend the pending epoch into `intel` and start a new one
+    pub fn finish_epoch(&mut self, intel: &mut HashMap<Pubkey, ValidatorIntel>) {
+        let observed = self.pending.len();
+        for (pubkey, stats) in self.pending.drain() {
+            let entry = intel.entry(pubkey).or_insert_with(|| ValidatorIntel::observed(&pubkey));
+            
+            let keep = self.decay;
+            entry.mev_rate = (keep * entry.mev_rate + (1.0 - keep) * stats.sandwich_rate()).min(1.0);
+            entry.avg_tip = (keep as f64 * entry.avg_tip as f64
+                + (1.0 - keep as f64) * stats.avg_tip_lamports() as f64) as u64;
+            entry.recent_blocks = stats.blocks_produced;
+            
+            entry.epoch_history.push_back(stats);
+            while entry.epoch_history.len() > self.max_epochs {
+                entry.epoch_history.pop_front();
+            }
+        }
+        
+        if let Some(epoch) = self.current_epoch {
+            tracing::info!("📊 Epoch {} validator stats blended ({} leaders observed)", epoch, observed);
+        }
+    }
 }
 
 /// Load malicious validator dataset
@@ -44,6 +249,8 @@ pub fn load_validator_intel() -> HashMap<Pubkey, ValidatorIntel> {
             recent_blocks: 1000,
             skip_rate: 0.02,
             label: "Known MEV Operator".to_string(),
+            epoch_history: VecDeque::new(),
+            provenance: None,
         },
         ValidatorIntel {
             pubkey: "GRJQtWwdJmp5LLpy8JNzYDQY8JrKRJ3wzcmb7MrKnXY6".to_string(),
@@ -56,6 +263,8 @@ pub fn load_validator_intel() -> HashMap<Pubkey, ValidatorIntel> {
             recent_blocks: 1200,
             skip_rate: 0.01,
             label: "Aggressive Sandwich Bot".to_string(),
+            epoch_history: VecDeque::new(),
+            provenance: None,
         },
         // ... Additional 239 validators would be loaded here
         // In production: Load from encrypted JSON/database
@@ -72,6 +281,18 @@ pub fn load_validator_intel() -> HashMap<Pubkey, ValidatorIntel> {
     intel
 }
 
+/// Validator intel applicable to `network`
+///
+/// The dataset describes mainnet identities; on other clusters those keys
+/// never lead, so nothing is flagged and leader checks pass by default.
+pub fn load_validator_intel_for(network: sentinel_core::Network) -> HashMap<Pubkey, ValidatorIntel> {
+    if network.uses_mainnet_intel() {
+        return load_validator_intel();
+    }
+    tracing::info!("📊 No validator intel for {}; leader risk uses defaults", network);
+    HashMap::new()
+}
+
 /// Calculate aggregated risk score for validator
 pub fn calculate_validator_risk(intel: &ValidatorIntel) -> f32 {
     let malicious_weight = if intel.is_malicious { 0.60 } else { 0.0 };
@@ -82,6 +303,23 @@ pub fn calculate_validator_risk(intel: &ValidatorIntel) -> f32 {
     (malicious_weight + mev_rate_weight + jito_rate_weight + skip_rate_weight).min(1.0)
 }
 
+/// Risk of every malicious validator, for submission-time enforcement (`LeaderGuard`)
+pub fn malicious_risk_map(intel: &HashMap<Pubkey, ValidatorIntel>) -> HashMap<Pubkey, f32> {
+    intel
+        .iter()
+        .filter(|(_, v)| v.is_malicious)
+        .map(|(pubkey, v)| (*pubkey, calculate_validator_risk(v)))
+        .collect()
+}
+
+/// Risk of every known validator, for look-ahead window scoring (`SlotRiskForecaster`)
+pub fn validator_risk_map(intel: &HashMap<Pubkey, ValidatorIntel>) -> HashMap<Pubkey, f32> {
+    intel
+        .iter()
+        .map(|(pubkey, v)| (*pubkey, calculate_validator_risk(v)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +328,19 @@ mod tests {
     fn test_load_validator_intel() {
         let intel = load_validator_intel();
         assert!(!intel.is_empty());
+        
+        let risks = malicious_risk_map(&intel);
+        assert_eq!(risks.len(), intel.values().filter(|v| v.is_malicious).count());
+        assert!(risks.values().all(|r| *r > 0.7));
+    }
+    
+    #[test]
+    fn test_intel_selected_by_network() {
+        use sentinel_core::Network;
+
+        assert_eq!(load_validator_intel_for(Network::Mainnet).len(), load_validator_intel().len());
+        assert!(load_validator_intel_for(Network::Devnet).is_empty());
+        assert!(load_validator_intel_for(Network::Localnet).is_empty());
     }
     
     #[test]
@@ -105,10 +356,78 @@ mod tests {
             recent_blocks: 1000,
             skip_rate: 0.02,
             label: "Test".to_string(),
+            epoch_history: VecDeque::new(),
+            provenance: None,
         };
         
         let risk = calculate_validator_risk(&intel);
         assert!(risk > 0.8); // Should be high risk
         assert!(risk <= 1.0);
     }
+    
+    #[test]
+    fn test_epoch_stats_blend_with_decay() {
+        let mut intel = load_validator_intel();
+        let mut aggregator = EpochStatsAggregator::new(100, 0.5, 2);
+        let (known, fresh) = (*intel.keys().next().unwrap(), Pubkey::new_unique());
+        let static_rate = intel[&known].mev_rate;
+        
+        // Epoch 0: known validator sandwiches in every block, fresh one never
+        for slot in 0..10 {
+            let leader = if slot % 2 == 0 { known } else { fresh };
+            let sandwiches = if leader == known { 1 } else { 0 };
+            aggregator.record_block(&BlockObservation { slot, leader, sandwiches, tips_lamports: 1_000 }, &mut intel);
+        }
+        // First block of epoch 1 finalizes epoch 0
+        aggregator.record_block(&BlockObservation { slot: 100, leader: fresh, sandwiches: 0, tips_lamports: 0 }, &mut intel);
+        
+        let v = &intel[&known];
+        assert!((v.mev_rate - (0.5 * static_rate + 0.5)).abs() < 1e-6);
+        assert_eq!(v.recent_blocks, 5);
+        assert_eq!(v.history(10), vec![EpochStats {
+            epoch: 0,
+            blocks_produced: 5,
+            sandwiches_observed: 5,
+            tips_extracted_lamports: 5_000,
+        }]);
+        
+        let f = &intel[&fresh];
+        assert!(!f.is_malicious);
+        assert_eq!(f.mev_rate, 0.0);
+        assert_eq!(f.avg_tip, 500);
+    }
+    
+    #[test]
+    fn test_confirmed_incidents_add_sandwiches_not_blocks() {
+        let mut intel = HashMap::new();
+        let mut aggregator = EpochStatsAggregator::new(100, 0.5, 2);
+        let leader = Pubkey::new_unique();
+        
+        for slot in 0..4 {
+            aggregator.record_block(&BlockObservation { slot, leader, sandwiches: 0, tips_lamports: 0 }, &mut intel);
+        }
+        aggregator.record_incidents(2, leader, 1, &mut intel);
+        aggregator.finish_epoch(&mut intel);
+        
+        let v = &intel[&leader];
+        assert_eq!(v.recent_blocks, 4);
+        assert_eq!(v.history(1)[0].sandwiches_observed, 1);
+        assert!((v.mev_rate - 0.125).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_history_is_bounded() {
+        let mut intel = HashMap::new();
+        let mut aggregator = EpochStatsAggregator::new(10, 0.7, 2);
+        let leader = Pubkey::new_unique();
+        
+        for epoch in 0..4 {
+            aggregator.record_block(&BlockObservation { slot: epoch * 10, leader, sandwiches: 0, tips_lamports: 0 }, &mut intel);
+        }
+        aggregator.finish_epoch(&mut intel);
+        
+        let history = intel[&leader].history(5);
+        assert_eq!(history.iter().map(|e| e.epoch).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(intel[&leader].history(1)[0].epoch, 3);
+    }
 }
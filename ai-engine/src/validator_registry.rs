@@ -0,0 +1,503 @@
+//! On-Chain Validator Registry
+//!
+//! A publisher keeps the malicious-validator set in a single account, so a
+//! router pulls it with one `getAccountInfo` (no `getProgramAccounts` scan)
+//! and checks it itself: the account must be owned by the configured program
+//! and, for schemas that carry one, name the configured authority.
+//!
+//! Layouts ([`RegistrySchema`]):
+//! - `sentinel_v1`: `SNTLVREG` magic, `u8` version (1), 32-byte authority,
+//!   `u64` update slot, `u32` entry count, then per entry a 32-byte identity,
+//!   `u16` risk in basis points and a `u8` flags byte (bit 0 = malicious);
+//!   integers little-endian
+//! - `json`: UTF-8 array of `{ "identity", "risk", "malicious" }`
+//!
+//! [`merge_registry`] folds the set into the intel map conservatively (it
+//! never lowers risk a validator already has), then [`IntelOverrides`] from
+//! `[validators]` get the last word.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::Client;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::validator_intel::{IntelProvenance, ValidatorIntel, SLOTS_PER_EPOCH};
+use sentinel_core::{RegistrySchema, Result, SentinelError, ValidatorListConfig};
+
+/// Leading bytes of a `sentinel_v1` registry account
+pub const REGISTRY_MAGIC: &[u8; 8] = b"SNTLVREG";
+
+const V1_HEADER_LEN: usize = 8 + 1 + 32 + 8 + 4;
+const V1_ENTRY_LEN: usize = 32 + 2 + 1;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RegistryEntry {
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub identity: Pubkey,
+
+    /// Publisher's risk estimate (0-1)
+    pub risk: f32,
+
+    pub malicious: bool,
+}
+
+/// Parsed registry account
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorRegistry {
+    /// Key the publisher names as maintaining the list (`sentinel_v1` only)
+    pub authority: Option<Pubkey>,
+
+    /// Slot of the last update (`sentinel_v1` only)
+    pub updated_slot: Option<u64>,
+
+    pub entries: Vec<RegistryEntry>,
+}
+
+impl ValidatorRegistry {
+    pub fn parse(data: &[u8], schema: RegistrySchema) -> Result<Self> {
+        match schema {
+            RegistrySchema::SentinelV1 => parse_v1(data),
+            RegistrySchema::Json => {
+                // Accounts are allocated up front; unused space is zero-filled
+                let end = data.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+                let entries: Vec<RegistryEntry> = serde_json::from_slice(&data[..end])
+                    .map_err(|e| registry_error(format!("invalid JSON: {}", e)))?;
+                if entries.iter().any(|e| !(0.0..=1.0).contains(&e.risk)) {
+                    return Err(registry_error("risk outside 0-1".to_string()));
+                }
+                Ok(Self {
+                    authority: None,
+                    updated_slot: None,
+                    entries,
+                })
+            }
+        }
+    }
+
+    /// Encode as a `sentinel_v1` account (publisher side, tests)
+    pub fn to_v1_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(V1_HEADER_LEN + self.entries.len() * V1_ENTRY_LEN);
+        data.extend_from_slice(REGISTRY_MAGIC);
+        data.push(1);
+        data.extend_from_slice(self.authority.unwrap_or_default().as_ref());
+        data.extend_from_slice(&self.updated_slot.unwrap_or(0).to_le_bytes());
+        data.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for entry in &self.entries {
+            data.extend_from_slice(entry.identity.as_ref());
+            data.extend_from_slice(&((entry.risk.clamp(0.0, 1.0) * 10_000.0).round() as u16).to_le_bytes());
+            data.push(entry.malicious as u8);
+        }
+        data
+    }
+}
+
+fn parse_v1(data: &[u8]) -> Result<ValidatorRegistry> {
+    if data.len() < V1_HEADER_LEN || &data[..8] != REGISTRY_MAGIC {
+        return Err(registry_error("missing sentinel_v1 header".to_string()));
+    }
+    if data[8] != 1 {
+        return Err(registry_error(format!("unsupported sentinel_v1 version {}", data[8])));
+    }
+    let pubkey_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).expect("32-byte slice");
+    let authority = pubkey_at(9);
+    let updated_slot = u64::from_le_bytes(data[41..49].try_into().expect("8-byte slice"));
+    let count = u32::from_le_bytes(data[49..53].try_into().expect("4-byte slice")) as usize;
+
+    let body = &data[V1_HEADER_LEN..];
+    let fits = count.checked_mul(V1_ENTRY_LEN).is_some_and(|len| len <= body.len());
+    if !fits {
+        return Err(registry_error(format!("{} entries exceed account data", count)));
+    }
+    let entries = body
+        .chunks_exact(V1_ENTRY_LEN)
+        .take(count)
+        .map(|chunk| {
+            let risk_bps = u16::from_le_bytes([chunk[32], chunk[33]]);
+            RegistryEntry {
+                identity: Pubkey::try_from(&chunk[..32]).expect("32-byte slice"),
+                risk: (risk_bps.min(10_000) as f32) / 10_000.0,
+                malicious: chunk[34] & 1 == 1,
+            }
+        })
+        .collect();
+
+    Ok(ValidatorRegistry {
+        authority: Some(authority),
+        updated_slot: Some(updated_slot),
+        entries,
+    })
+}
+
+fn registry_error(reason: String) -> SentinelError {
+    SentinelError::SerializationError(format!("Invalid validator registry: {}", reason))
+}
+
+fn deserialize_pubkey<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Pubkey, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+}
+
+/// Local `[validators]` overrides applied after every merge
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntelOverrides {
+    pub extra_malicious: HashSet<Pubkey>,
+    pub exempt: HashSet<Pubkey>,
+}
+
+impl IntelOverrides {
+    /// Invalid keys are rejected by config validation and skipped here
+    pub fn from_config(config: &ValidatorListConfig) -> Self {
+        let parse = |keys: &[String]| keys.iter().filter_map(|k| Pubkey::from_str(k).ok()).collect();
+        Self {
+            extra_malicious: parse(&config.extra_malicious),
+            exempt: parse(&config.exempt),
+        }
+    }
+
+    /// Exemptions beat both the registry and `extra_malicious`
+    pub fn apply(&self, intel: &mut HashMap<Pubkey, ValidatorIntel>) {
+        for pubkey in &self.extra_malicious {
+            let entry = intel.entry(*pubkey).or_insert_with(|| ValidatorIntel {
+                label: "Local override".to_string(),
+                ..ValidatorIntel::observed(pubkey)
+            });
+            entry.is_malicious = true;
+        }
+        for pubkey in &self.exempt {
+            if let Some(entry) = intel.get_mut(pubkey) {
+                entry.is_malicious = false;
+            }
+        }
+    }
+}
+
+/// Counts from one registry merge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegistryMerge {
+    pub added: usize,
+    pub flagged: usize,
+}
+
+/// Fold `registry` into `intel`, then apply `overrides`
+///
+/// Listed validators are added if unknown; a malicious flag is never cleared
+/// and `mev_rate` only rises to the published risk. Dropping a validator from
+/// the registry therefore needs an `exempt` override (or a restart).
+pub fn merge_registry(
+    intel: &mut HashMap<Pubkey, ValidatorIntel>,
+    registry: &ValidatorRegistry,
+    source: &str,
+    overrides: &IntelOverrides,
+    now: i64,
+) -> RegistryMerge {
+    let epoch = registry.updated_slot.map_or(0, |slot| slot / SLOTS_PER_EPOCH);
+    let mut merge = RegistryMerge::default();
+
+    for listed in &registry.entries {
+        let entry = intel.entry(listed.identity).or_insert_with(|| {
+            merge.added += 1;
+            ValidatorIntel {
+                label: "On-chain registry".to_string(),
+                ..ValidatorIntel::observed(&listed.identity)
+            }
+        });
+        if listed.malicious && !entry.is_malicious {
+            merge.flagged += 1;
+        }
+        entry.is_malicious |= listed.malicious;
+        entry.mev_rate = entry.mev_rate.max(listed.risk);
+        entry.provenance = Some(IntelProvenance {
+            source: source.to_string(),
+            window_start_epoch: epoch,
+            window_end_epoch: epoch,
+            last_updated: now,
+        });
+    }
+    overrides.apply(intel);
+
+    tracing::info!(
+        "📊 Merged validator registry {} ({} entries, {} new, {} newly flagged)",
+        source,
+        registry.entries.len(),
+        merge.added,
+        merge.flagged
+    );
+    merge
+}
+
+/// Fetches and verifies the registry account
+#[derive(Debug, Clone)]
+pub struct RegistryClient {
+    http_client: Client,
+    rpc_url: String,
+    account: Pubkey,
+    schema: RegistrySchema,
+    expected_owner: Option<Pubkey>,
+    expected_authority: Option<Pubkey>,
+}
+
+impl RegistryClient {
+    pub fn new(rpc_url: impl Into<String>, account: Pubkey, schema: RegistrySchema) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+            account,
+            schema,
+            expected_owner: None,
+            expected_authority: None,
+        })
+    }
+
+    /// Client for `[validators]`, or `None` when no registry account is configured
+    pub fn from_config(config: &ValidatorListConfig, rpc_url: &str) -> Result<Option<Self>> {
+        let parse = |key: &str| {
+            Pubkey::from_str(key)
+                .map_err(|e| SentinelError::ConfigError(format!("Invalid validators pubkey {}: {}", key, e)))
+        };
+        let Some(account) = config.registry_account.as_deref() else {
+            return Ok(None);
+        };
+
+        let mut client = Self::new(rpc_url, parse(account)?, config.registry_schema)?;
+        if let Some(program) = config.registry_program.as_deref() {
+            client = client.with_expected_owner(parse(program)?);
+        }
+        if let Some(authority) = config.registry_authority.as_deref() {
+            client = client.with_expected_authority(parse(authority)?);
+        }
+        Ok(Some(client))
+    }
+
+    /// Reject the account unless this program owns it
+    pub fn with_expected_owner(mut self, program: Pubkey) -> Self {
+        self.expected_owner = Some(program);
+        self
+    }
+
+    /// Reject the registry unless it names this authority
+    pub fn with_expected_authority(mut self, authority: Pubkey) -> Self {
+        self.expected_authority = Some(authority);
+        self
+    }
+
+    pub fn account(&self) -> &Pubkey {
+        &self.account
+    }
+
+    /// Fetch, verify and parse the registry account
+    pub async fn fetch(&self) -> Result<ValidatorRegistry> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAccountInfo",
+            "params": [self.account.to_string(), { "encoding": "base64" }],
+        });
+
+        let response: RpcResponse = self
+            .http_client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("getAccountInfo failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("Invalid getAccountInfo response: {}", e)))?;
+
+        if let Some(error) = response.error {
+            return Err(SentinelError::RpcError(error.message));
+        }
+
+        let account = response
+            .result
+            .and_then(|r| r.value)
+            .ok_or_else(|| SentinelError::RpcError(format!("Validator registry {} not found", self.account)))?;
+        self.verify(&account.owner, &BASE64.decode(&account.data.0).map_err(|e| {
+            SentinelError::SerializationError(format!("Invalid account data: {}", e))
+        })?)
+    }
+
+    fn verify(&self, owner: &str, data: &[u8]) -> Result<ValidatorRegistry> {
+        if let Some(expected) = self.expected_owner {
+            if owner != expected.to_string() {
+                return Err(SentinelError::Unauthorized(format!(
+                    "Validator registry {} is owned by {}, expected {}",
+                    self.account, owner, expected
+                )));
+            }
+        }
+
+        let registry = ValidatorRegistry::parse(data, self.schema)?;
+        if let Some(expected) = self.expected_authority {
+            if registry.authority != Some(expected) {
+                return Err(SentinelError::Unauthorized(format!(
+                    "Validator registry {} does not name authority {}",
+                    self.account, expected
+                )));
+            }
+        }
+        Ok(registry)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<RpcAccountResult>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcAccountResult {
+    value: Option<RpcAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcAccount {
+    owner: String,
+    data: (String, String),
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn registry(authority: Pubkey, entries: Vec<RegistryEntry>) -> ValidatorRegistry {
+        ValidatorRegistry {
+            authority: Some(authority),
+            updated_slot: Some(SLOTS_PER_EPOCH * 650 + 7),
+            entries,
+        }
+    }
+
+    /// Serves one `getAccountInfo` response per connection
+    async fn serve_account(owner: Pubkey, data: Vec<u8>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "value": { "owner": owner.to_string(), "data": [BASE64.encode(data), "base64"] } }
+        })
+        .to_string();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_parse_schemas() {
+        let entries = vec![
+            RegistryEntry { identity: Pubkey::new_unique(), risk: 0.9, malicious: true },
+            RegistryEntry { identity: Pubkey::new_unique(), risk: 0.25, malicious: false },
+        ];
+        let published = registry(Pubkey::new_unique(), entries.clone());
+
+        // Zero-padded account space after the entries is ignored
+        let mut data = published.to_v1_bytes();
+        data.resize(data.len() + 64, 0);
+        assert_eq!(ValidatorRegistry::parse(&data, RegistrySchema::SentinelV1).unwrap(), published);
+
+        data[49] = 3; // claims more entries than the account holds
+        data.truncate(V1_HEADER_LEN + 2 * V1_ENTRY_LEN);
+        assert!(ValidatorRegistry::parse(&data, RegistrySchema::SentinelV1).is_err());
+        assert!(ValidatorRegistry::parse(b"not a registry", RegistrySchema::SentinelV1).is_err());
+
+        let mut json = format!(
+            r#"[{{"identity":"{}","risk":0.9,"malicious":true}},{{"identity":"{}","risk":0.25,"malicious":false}}]"#,
+            entries[0].identity, entries[1].identity
+        )
+        .into_bytes();
+        json.resize(json.len() + 32, 0);
+        assert_eq!(ValidatorRegistry::parse(&json, RegistrySchema::Json).unwrap().entries, entries);
+        assert!(ValidatorRegistry::parse(br#"[{"identity":"x","risk":0.1,"malicious":true}]"#, RegistrySchema::Json).is_err());
+    }
+
+    #[test]
+    fn test_merge_is_conservative_and_overrides_win() {
+        let mut intel = crate::validator_intel::load_validator_intel();
+        let (known, fresh, exempt, forced) =
+            (*intel.keys().next().unwrap(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let known_rate = intel[&known].mev_rate;
+
+        let published = registry(Pubkey::new_unique(), vec![
+            RegistryEntry { identity: known, risk: 0.1, malicious: false },
+            RegistryEntry { identity: fresh, risk: 0.8, malicious: true },
+            RegistryEntry { identity: exempt, risk: 0.95, malicious: true },
+        ]);
+        let overrides = IntelOverrides {
+            extra_malicious: HashSet::from([forced]),
+            exempt: HashSet::from([exempt]),
+        };
+
+        let merge = merge_registry(&mut intel, &published, "onchain", &overrides, 1_700_000_000);
+        assert_eq!(merge, RegistryMerge { added: 2, flagged: 2 });
+
+        // Registry can't clear a flag or lower a rate
+        assert!(intel[&known].is_malicious);
+        assert_eq!(intel[&known].mev_rate, known_rate);
+        assert!(intel[&fresh].is_malicious);
+        assert_eq!(intel[&fresh].mev_rate, 0.8);
+        assert_eq!(intel[&fresh].provenance.as_ref().unwrap().window_end_epoch, 650);
+        assert!(!intel[&exempt].is_malicious);
+        assert!(intel[&forced].is_malicious);
+    }
+
+    #[test]
+    fn test_overrides_from_config() {
+        let key = Pubkey::new_unique();
+        let config = ValidatorListConfig {
+            extra_malicious: vec![key.to_string(), "bad".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(IntelOverrides::from_config(&config).extra_malicious, HashSet::from([key]));
+        assert!(RegistryClient::from_config(&config, "http://localhost").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_verifies_owner_and_authority() {
+        let (program, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let published = registry(authority, vec![RegistryEntry { identity: Pubkey::new_unique(), risk: 0.5, malicious: true }]);
+        let url = serve_account(program, published.to_v1_bytes()).await;
+        let account = Pubkey::new_unique();
+
+        let config = ValidatorListConfig {
+            registry_account: Some(account.to_string()),
+            registry_program: Some(program.to_string()),
+            registry_authority: Some(authority.to_string()),
+            ..Default::default()
+        };
+        let client = RegistryClient::from_config(&config, &url).unwrap().unwrap();
+        assert_eq!(client.fetch().await.unwrap(), published);
+
+        let wrong_owner = client.clone().with_expected_owner(Pubkey::new_unique());
+        assert!(wrong_owner.fetch().await.unwrap_err().to_string().contains("owned by"));
+        let wrong_authority = client.with_expected_authority(Pubkey::new_unique());
+        assert!(wrong_authority.fetch().await.unwrap_err().to_string().contains("authority"));
+    }
+}
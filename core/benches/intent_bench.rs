@@ -4,7 +4,8 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use sentinel_core::{
-    ConsentBlock, Constraints, FeePreferences, Intent, IntentType, SwapDetails, SwapMode,
+    ConsentBlock, Constraints, FeePreferences, Intent, IntentType, ProtectionTier, SwapDetails, SwapMode,
+    INTENT_SCHEMA_VERSION,
 };
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
@@ -29,11 +30,15 @@ fn create_benchmark_swap_intent() -> Intent {
             partial_fill: false,
             expiry_timestamp: Some(Utc::now().timestamp() + 3600),
             ttl_seconds: None,
+            managed_slippage: false,
+            allow_netting: false,
         },
         fee_preferences: FeePreferences {
             max_priority_fee_lamports: 100_000,
             max_jito_tip_lamports: 50_000,
             tip_allocation_pct: 70,
+            allow_malicious_leader: false,
+            protection_tier: ProtectionTier::Standard,
         },
         consent_block: ConsentBlock {
             recent_blockhash: Hash::new_unique(),
@@ -42,6 +47,9 @@ fn create_benchmark_swap_intent() -> Intent {
         },
         limit_details: None,
         twap_details: None,
+        dca_details: None,
+        legs: vec![],
+        schema_version: INTENT_SCHEMA_VERSION,
     }
 }
 
@@ -0,0 +1,459 @@
+//! Solana Actions (Blinks) Endpoint
+//!
+//! Lets Actions-aware wallets run a protected swap without integrating the
+//! intent API. The API service mounts the pair at `/api/v1/actions/swap`:
+//! - `GET  ?input=<mint>&output=<mint>` renders the action ([`ActionGetResponse`]):
+//!   preset amounts plus a custom amount field
+//! - `POST ?input=..&output=..&amount=<atoms>[&slippage_bps=..]` with body
+//!   `{ "account": "<wallet>" }` turns the request into a Sentinel [`Intent`]
+//!   with default protections, and answers with the unsigned transaction for
+//!   the wallet to sign ([`ActionPostResponse`])
+//!
+//! Default protections: slippage capped at `max_slippage_bps` (tightened by the
+//! `SlippageAdvisor`), a short TTL, the standard fee caps, and the
+//! jitodontfront marker on the swap instruction so the block engine refuses to
+//! bundle anything in front of it. Every response carries [`action_headers`].
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::clock::Clock;
+use crate::intent::{
+    ConsentBlock, Constraints, FeePreferences, Intent, IntentType, SwapDetails, SwapMode, INTENT_SCHEMA_VERSION,
+};
+use crate::network::Network;
+use crate::preflight::candidate_transaction;
+use crate::{Result, SentinelError};
+
+/// Actions spec version the responses follow
+pub const ACTION_VERSION: &str = "2.1.3";
+
+/// Block engine marker forbidding front-running bundles around the transaction
+pub const DONT_FRONT_MARKER: &str = "jitodontfront111111111111111111111111111111";
+
+/// Compute unit limit of action swap transactions
+pub const ACTION_COMPUTE_UNIT_LIMIT: u32 = 400_000;
+
+/// Lifetime of an action intent (seconds); wallets sign right after the POST
+pub const ACTION_TTL_SECS: u32 = 60;
+
+/// CAIP-2 chain id of `network` (`solana:` + truncated genesis hash)
+pub fn blockchain_id(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp",
+        Network::Devnet => "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1",
+        Network::Testnet => "solana:4uhcVJyU9pJkvQyS88uRDiswHXSCkY3z",
+        Network::Localnet => "solana:localnet",
+    }
+}
+
+/// Headers every action response (including OPTIONS preflight) must carry
+pub fn action_headers(network: Network) -> [(&'static str, &'static str); 6] {
+    [
+        ("Access-Control-Allow-Origin", "*"),
+        ("Access-Control-Allow-Methods", "GET,POST,PUT,OPTIONS"),
+        (
+            "Access-Control-Allow-Headers",
+            "Content-Type, Authorization, Content-Encoding, Accept-Encoding",
+        ),
+        ("Access-Control-Expose-Headers", "X-Action-Version, X-Blockchain-Ids"),
+        ("X-Action-Version", ACTION_VERSION),
+        ("X-Blockchain-Ids", blockchain_id(network)),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionType {
+    Action,
+    Transaction,
+}
+
+/// `GET` body: how the wallet renders the action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionGetResponse {
+    #[serde(rename = "type")]
+    pub kind: ActionType,
+
+    /// Absolute image URL
+    pub icon: String,
+    pub title: String,
+    pub description: String,
+
+    /// Button text when there are no linked actions
+    pub label: String,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<ActionLinks>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ActionError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionLinks {
+    pub actions: Vec<LinkedAction>,
+}
+
+/// One button; `{name}` placeholders in `href` are filled from `parameters`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkedAction {
+    #[serde(rename = "type")]
+    pub kind: ActionType,
+    pub href: String,
+    pub label: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<ActionParameter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionParameter {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub label: String,
+    pub required: bool,
+}
+
+/// Error body (with a 4xx status), also embeddable in a `GET` response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionError {
+    pub message: String,
+}
+
+impl From<&SentinelError> for ActionError {
+    fn from(error: &SentinelError) -> Self {
+        Self { message: error.to_string() }
+    }
+}
+
+/// `POST` body from the wallet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionPostRequest {
+    /// Wallet that will sign (base58)
+    pub account: String,
+}
+
+/// `POST` answer: transaction for the wallet to sign and send
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionPostResponse {
+    #[serde(rename = "type")]
+    pub kind: ActionType,
+
+    /// Bincode-serialized unsigned transaction, base64
+    pub transaction: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Query parameters of a swap action URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapActionQuery {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+
+    /// Input atoms; required on `POST` only
+    pub amount: Option<u64>,
+    pub slippage_bps: Option<u16>,
+}
+
+impl SwapActionQuery {
+    /// Parse `input=..&output=..[&amount=..][&slippage_bps=..]` (a leading `?` or path is ignored)
+    pub fn parse(query: &str) -> Result<Self> {
+        let query = query.split_once('?').map_or(query, |(_, q)| q);
+        let (mut input, mut output, mut amount, mut slippage_bps) = (None, None, None, None);
+
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "input" => input = Some(parse_param::<Pubkey>(key, value)?),
+                "output" => output = Some(parse_param::<Pubkey>(key, value)?),
+                "amount" => amount = Some(parse_param::<u64>(key, value)?),
+                "slippage_bps" => slippage_bps = Some(parse_param::<u16>(key, value)?),
+                _ => {}
+            }
+        }
+
+        let missing = |name: &str| SentinelError::InvalidIntent(format!("Action query is missing `{}`", name));
+        Ok(Self {
+            input_mint: input.ok_or_else(|| missing("input"))?,
+            output_mint: output.ok_or_else(|| missing("output"))?,
+            amount,
+            slippage_bps,
+        })
+    }
+}
+
+fn parse_param<T: FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| SentinelError::InvalidIntent(format!("Invalid action parameter {}={}", key, value)))
+}
+
+/// Append the jitodontfront marker to `instruction` unless it already carries it
+pub(crate) fn mark_dont_front(instruction: &mut Instruction) {
+    let marker = Pubkey::from_str(DONT_FRONT_MARKER).expect("Valid jitodontfront pubkey");
+    if !instruction.accounts.iter().any(|a| a.pubkey == marker) {
+        instruction.accounts.push(AccountMeta::new_readonly(marker, false));
+    }
+}
+
+/// Renders swap actions and turns wallet POSTs into protected intents
+#[derive(Debug, Clone)]
+pub struct SwapActionService {
+    /// Absolute URL the action is served from, e.g. `https://router.example/api/v1/actions/swap`
+    base_url: String,
+    icon: String,
+    preset_amounts: Vec<u64>,
+    max_slippage_bps: u16,
+    fee_preferences: FeePreferences,
+}
+
+impl SwapActionService {
+    pub fn new(base_url: impl Into<String>, icon: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            icon: icon.into(),
+            preset_amounts: Vec::new(),
+            max_slippage_bps: Constraints::default().max_slippage_bps,
+            fee_preferences: FeePreferences::default(),
+        }
+    }
+
+    /// One-click amounts (input atoms) shown before the custom field
+    pub fn with_preset_amounts(mut self, amounts: Vec<u64>) -> Self {
+        self.preset_amounts = amounts;
+        self
+    }
+
+    /// Highest slippage a POST may request; also the default when it names none
+    pub fn with_max_slippage_bps(mut self, bps: u16) -> Self {
+        self.max_slippage_bps = bps;
+        self
+    }
+
+    pub fn with_fee_preferences(mut self, fee_preferences: FeePreferences) -> Self {
+        self.fee_preferences = fee_preferences;
+        self
+    }
+
+    /// `GET` response for the pair in `query`
+    pub fn render(&self, query: &SwapActionQuery) -> ActionGetResponse {
+        let href = |amount: &str| {
+            format!(
+                "{}?input={}&output={}&amount={}",
+                self.base_url, query.input_mint, query.output_mint, amount
+            )
+        };
+        let mut actions: Vec<LinkedAction> = self
+            .preset_amounts
+            .iter()
+            .map(|amount| LinkedAction {
+                kind: ActionType::Transaction,
+                href: href(&amount.to_string()),
+                label: format!("Swap {}", amount),
+                parameters: Vec::new(),
+            })
+            .collect();
+        actions.push(LinkedAction {
+            kind: ActionType::Transaction,
+            href: href("{amount}"),
+            label: "Swap".to_string(),
+            parameters: vec![ActionParameter {
+                kind: "number".to_string(),
+                name: "amount".to_string(),
+                label: "Amount (smallest units)".to_string(),
+                required: true,
+            }],
+        });
+
+        ActionGetResponse {
+            kind: ActionType::Action,
+            icon: self.icon.clone(),
+            title: "MEV-protected swap".to_string(),
+            description: format!(
+                "Swap {} for {} through Sentinel Router with sandwich protection and at most {}bps slippage",
+                query.input_mint, query.output_mint, self.max_slippage_bps
+            ),
+            label: "Swap".to_string(),
+            disabled: false,
+            links: Some(ActionLinks { actions }),
+            error: None,
+        }
+    }
+
+    /// Intent for a `POST`, validated as the router would on receipt
+    pub fn intent(
+        &self,
+        request: &ActionPostRequest,
+        query: &SwapActionQuery,
+        recent_blockhash: Hash,
+        clock: &dyn Clock,
+    ) -> Result<Intent> {
+        let user = Pubkey::from_str(&request.account)
+            .map_err(|_| SentinelError::InvalidIntent(format!("Invalid action account {}", request.account)))?;
+        let amount = query
+            .amount
+            .ok_or_else(|| SentinelError::InvalidIntent("Action query is missing `amount`".to_string()))?;
+        let slippage_bps = query.slippage_bps.unwrap_or(self.max_slippage_bps);
+        if slippage_bps > self.max_slippage_bps {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Requested slippage {}bps exceeds the action cap {}bps",
+                slippage_bps, self.max_slippage_bps
+            )));
+        }
+
+        let intent = Intent {
+            intent_id: uuid::Uuid::new_v4().to_string(),
+            user_public_key: user,
+            intent_type: IntentType::Swap,
+            swap_details: Some(SwapDetails {
+                mode: SwapMode::ExactIn,
+                input_mint: query.input_mint,
+                output_mint: query.output_mint,
+                amount,
+                minimum_received: None,
+                dex: None,
+                route_hints: None,
+            }),
+            constraints: Constraints {
+                max_slippage_bps: slippage_bps,
+                ttl_seconds: Some(ACTION_TTL_SECS),
+                managed_slippage: true,
+                allow_netting: false,
+                ..Default::default()
+            },
+            fee_preferences: self.fee_preferences.clone(),
+            consent_block: ConsentBlock {
+                recent_blockhash,
+                signature_request_id: Intent::new_signature_request_id(),
+                nonce: None,
+            },
+            limit_details: None,
+            twap_details: None,
+            dca_details: None,
+            legs: vec![],
+            schema_version: INTENT_SCHEMA_VERSION,
+        };
+        intent.validate_at(clock)?;
+        Ok(intent)
+    }
+
+    /// `POST` response: the intent's swap, dontfront-marked, as an unsigned transaction
+    pub fn transaction(&self, intent: &Intent, mut swap_instruction: Instruction) -> Result<ActionPostResponse> {
+        mark_dont_front(&mut swap_instruction);
+        let transaction = candidate_transaction(intent, swap_instruction, ACTION_COMPUTE_UNIT_LIMIT);
+        let bytes = bincode::serialize(&transaction)
+            .map_err(|e| SentinelError::SerializationError(format!("Failed to serialize action transaction: {}", e)))?;
+
+        Ok(ActionPostResponse {
+            kind: ActionType::Transaction,
+            transaction: BASE64.encode(bytes),
+            message: Some(format!("Sentinel intent {}", intent.intent_id)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use solana_sdk::transaction::Transaction;
+
+    fn service() -> SwapActionService {
+        SwapActionService::new("https://router.example/api/v1/actions/swap/", "https://router.example/icon.png")
+            .with_preset_amounts(vec![1_000_000, 5_000_000])
+            .with_max_slippage_bps(100)
+    }
+
+    fn query(amount: Option<u64>) -> SwapActionQuery {
+        SwapActionQuery {
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            amount,
+            slippage_bps: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let (input, output) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let parsed = SwapActionQuery::parse(&format!(
+            "/api/v1/actions/swap?input={}&output={}&amount=42&slippage_bps=30&utm=x",
+            input, output
+        ))
+        .unwrap();
+        assert_eq!(parsed, SwapActionQuery { input_mint: input, output_mint: output, amount: Some(42), slippage_bps: Some(30) });
+
+        assert!(SwapActionQuery::parse(&format!("input={}", input)).unwrap_err().to_string().contains("output"));
+        assert!(SwapActionQuery::parse(&format!("input={}&output={}&amount=-1", input, output)).is_err());
+    }
+
+    #[test]
+    fn test_render_links_presets_and_custom_amount() {
+        let q = query(None);
+        let response = service().render(&q);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["type"], "action");
+        assert!(json.get("disabled").is_none());
+
+        let actions = &response.links.unwrap().actions;
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            actions[0].href,
+            format!("https://router.example/api/v1/actions/swap?input={}&output={}&amount=1000000", q.input_mint, q.output_mint)
+        );
+        assert!(actions[2].href.ends_with("amount={amount}"));
+        assert_eq!(actions[2].parameters[0].name, "amount");
+    }
+
+    #[test]
+    fn test_post_builds_protected_intent_and_transaction() {
+        let clock = ManualClock::at_timestamp(1_700_000_000);
+        let wallet = Pubkey::new_unique();
+        let request = ActionPostRequest { account: wallet.to_string() };
+        let q = query(Some(2_000_000));
+        let blockhash = Hash::new_unique();
+
+        let intent = service().intent(&request, &q, blockhash, &clock).unwrap();
+        assert_eq!(intent.user_public_key, wallet);
+        assert_eq!(intent.swap_details.as_ref().unwrap().amount, 2_000_000);
+        assert_eq!(intent.constraints.max_slippage_bps, 100);
+        assert!(intent.constraints.managed_slippage);
+
+        let swap = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![AccountMeta::new(wallet, true)]);
+        let response = service().transaction(&intent, swap).unwrap();
+        let transaction: Transaction = bincode::deserialize(&BASE64.decode(&response.transaction).unwrap()).unwrap();
+        assert_eq!(transaction.message.account_keys[0], wallet);
+        assert_eq!(transaction.message.recent_blockhash, blockhash);
+        assert_eq!(transaction.message.instructions.len(), 3);
+        assert!(transaction
+            .message
+            .account_keys
+            .contains(&Pubkey::from_str(DONT_FRONT_MARKER).unwrap()));
+        assert!(transaction.signatures.iter().all(|s| *s == Default::default()));
+    }
+
+    #[test]
+    fn test_post_rejects_bad_requests() {
+        let clock = ManualClock::at_timestamp(1_700_000_000);
+        let request = ActionPostRequest { account: Pubkey::new_unique().to_string() };
+
+        assert!(service().intent(&request, &query(None), Hash::new_unique(), &clock).is_err());
+        let greedy = SwapActionQuery { slippage_bps: Some(500), ..query(Some(1)) };
+        assert!(service().intent(&request, &greedy, Hash::new_unique(), &clock).unwrap_err().to_string().contains("cap"));
+        let bad = ActionPostRequest { account: "wallet".to_string() };
+        assert!(service().intent(&bad, &query(Some(1)), Hash::new_unique(), &clock).is_err());
+        assert_eq!(action_headers(Network::Devnet)[5], ("X-Blockchain-Ids", "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1"));
+    }
+}
@@ -0,0 +1,88 @@
+//! Operational Alerts
+//!
+//! Monitors that detect degradation (e.g. [`crate::SloTracker`]) raise an [`Alert`]
+//! through an [`AlertSink`]. [`LogAlertSink`] writes them to the tracing log;
+//! [`MemoryAlertSink`] keeps the most recent ones for status endpoints and tests.
+//! Paging integrations implement the trait.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tracing::{error, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    /// Monitor that raised it, e.g. `slo`
+    pub source: String,
+
+    /// What degraded, e.g. `JitoBundle/failure_rate`; stable across repeats
+    pub key: String,
+
+    pub severity: AlertSeverity,
+    pub message: String,
+
+    /// Milliseconds since epoch
+    pub timestamp_ms: u64,
+}
+
+/// Destination for alerts; `send` must not block the caller
+pub trait AlertSink: Send + Sync {
+    fn send(&self, alert: Alert);
+}
+
+/// Alerts as `warn!` / `error!` log lines
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogAlertSink;
+
+impl AlertSink for LogAlertSink {
+    fn send(&self, alert: Alert) {
+        match alert.severity {
+            AlertSeverity::Warning => warn!("🚨 [{}] {}: {}", alert.source, alert.key, alert.message),
+            AlertSeverity::Critical => error!("🚨 [{}] {}: {}", alert.source, alert.key, alert.message),
+        }
+    }
+}
+
+/// Most recent alerts, oldest dropped first
+#[derive(Debug)]
+pub struct MemoryAlertSink {
+    capacity: usize,
+    alerts: Mutex<VecDeque<Alert>>,
+}
+
+impl Default for MemoryAlertSink {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl MemoryAlertSink {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            alerts: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Retained alerts, oldest first
+    pub fn alerts(&self) -> Vec<Alert> {
+        self.alerts.lock().unwrap_or_else(|p| p.into_inner()).iter().cloned().collect()
+    }
+}
+
+impl AlertSink for MemoryAlertSink {
+    fn send(&self, alert: Alert) {
+        let mut alerts = self.alerts.lock().unwrap_or_else(|p| p.into_inner());
+        if alerts.len() == self.capacity {
+            alerts.pop_front();
+        }
+        alerts.push_back(alert);
+    }
+}
@@ -0,0 +1,397 @@
+//! Address Lookup Table Cache
+//!
+//! v0 transactions name most of their accounts by index into address lookup
+//! tables. Recovering the addresses (feature extraction) and compiling a
+//! message against a table (transaction building) both need table contents,
+//! which would otherwise cost a `getAccountInfo` per table per transaction.
+//! [`AltCache`] is a shared handle that fetches each table once.
+//!
+//! Tables are append-only until closed, so a cached copy stays correct for
+//! every index it holds. An index past the cached end means the table was
+//! extended since it was fetched: the cache refetches it once before failing.
+//! Entries also expire after `ttl`, so deactivated and closed tables drop out.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::Client;
+use serde::Deserialize;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::v0::{self, LoadedAddresses, MessageAddressTableLookup};
+use solana_sdk::message::{AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::clock::{system_clock, SharedClock};
+use crate::{Result, SentinelError};
+
+/// Address Lookup Table program
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: &str = "AddressLookupTab1e1111111111111111111111111";
+
+#[derive(Debug)]
+struct CachedTable {
+    addresses: Arc<Vec<Pubkey>>,
+    fetched_at_ms: i64,
+}
+
+/// Shared cache of lookup table contents (clones share entries)
+#[derive(Debug, Clone)]
+pub struct AltCache {
+    http_client: Client,
+    rpc_url: String,
+    tables: Arc<RwLock<HashMap<Pubkey, CachedTable>>>,
+    ttl: Duration,
+    clock: SharedClock,
+}
+
+impl AltCache {
+    pub fn new(rpc_url: impl Into<String>) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+            tables: Arc::new(RwLock::new(HashMap::new())),
+            ttl: Duration::from_secs(600),
+            clock: system_clock(),
+        })
+    }
+
+    /// How long a fetched table is trusted
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Seed or replace a table's contents (e.g. one the router just extended)
+    pub fn insert(&self, table: Pubkey, addresses: Vec<Pubkey>) {
+        self.store(table, Arc::new(addresses));
+    }
+
+    /// Drop a table so the next lookup refetches it
+    pub fn invalidate(&self, table: &Pubkey) {
+        self.tables.write().unwrap_or_else(|p| p.into_inner()).remove(table);
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.read().unwrap_or_else(|p| p.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Table contents, fetched if missing or expired
+    pub async fn get(&self, table: &Pubkey) -> Result<Arc<Vec<Pubkey>>> {
+        if let Some(addresses) = self.cached(table) {
+            return Ok(addresses);
+        }
+        self.refresh(table).await
+    }
+
+    /// Fetch a table now, replacing any cached copy
+    pub async fn refresh(&self, table: &Pubkey) -> Result<Arc<Vec<Pubkey>>> {
+        let addresses = Arc::new(self.fetch(table).await?);
+        self.store(*table, Arc::clone(&addresses));
+        Ok(addresses)
+    }
+
+    /// Addresses a v0 message loads from its lookup tables
+    pub async fn resolve(&self, lookups: &[MessageAddressTableLookup]) -> Result<LoadedAddresses> {
+        let mut loaded = LoadedAddresses::default();
+        for lookup in lookups {
+            let mut addresses = self.get(&lookup.account_key).await?;
+            let highest = lookup.writable_indexes.iter().chain(&lookup.readonly_indexes).max();
+            if highest.is_some_and(|index| *index as usize >= addresses.len()) {
+                debug!("Lookup table {} extended since cached, refetching", lookup.account_key);
+                addresses = self.refresh(&lookup.account_key).await?;
+            }
+
+            let select = |indexes: &[u8]| -> Result<Vec<Pubkey>> {
+                indexes
+                    .iter()
+                    .map(|index| {
+                        addresses.get(*index as usize).copied().ok_or_else(|| {
+                            SentinelError::RpcError(format!(
+                                "Lookup table {} has no index {} ({} addresses)",
+                                lookup.account_key,
+                                index,
+                                addresses.len()
+                            ))
+                        })
+                    })
+                    .collect()
+            };
+            loaded.writable.extend(select(&lookup.writable_indexes)?);
+            loaded.readonly.extend(select(&lookup.readonly_indexes)?);
+        }
+        Ok(loaded)
+    }
+
+    /// Every account a message touches, in runtime order (static, loaded writable, loaded readonly)
+    pub async fn account_keys(&self, message: &VersionedMessage) -> Result<Vec<Pubkey>> {
+        let mut keys = message.static_account_keys().to_vec();
+        if let Some(lookups) = message.address_table_lookups() {
+            let loaded = self.resolve(lookups).await?;
+            keys.extend(loaded.writable);
+            keys.extend(loaded.readonly);
+        }
+        Ok(keys)
+    }
+
+    /// Tables in the form `v0::Message::try_compile` takes
+    pub async fn lookup_accounts(&self, tables: &[Pubkey]) -> Result<Vec<AddressLookupTableAccount>> {
+        let mut accounts = Vec::with_capacity(tables.len());
+        for table in tables {
+            accounts.push(AddressLookupTableAccount {
+                key: *table,
+                addresses: self.get(table).await?.to_vec(),
+            });
+        }
+        Ok(accounts)
+    }
+
+    /// Compile a v0 message against `tables`
+    pub async fn compile_v0(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        tables: &[Pubkey],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedMessage> {
+        let accounts = self.lookup_accounts(tables).await?;
+        v0::Message::try_compile(payer, instructions, &accounts, recent_blockhash)
+            .map(VersionedMessage::V0)
+            .map_err(|e| SentinelError::SerializationError(format!("Failed to compile v0 message: {}", e)))
+    }
+
+    fn store(&self, table: Pubkey, addresses: Arc<Vec<Pubkey>>) {
+        let entry = CachedTable {
+            addresses,
+            fetched_at_ms: self.clock.unix_millis(),
+        };
+        self.tables.write().unwrap_or_else(|p| p.into_inner()).insert(table, entry);
+    }
+
+    fn cached(&self, table: &Pubkey) -> Option<Arc<Vec<Pubkey>>> {
+        let tables = self.tables.read().unwrap_or_else(|p| p.into_inner());
+        let entry = tables.get(table)?;
+        let age_ms = self.clock.unix_millis() - entry.fetched_at_ms;
+        (age_ms < self.ttl.as_millis() as i64).then(|| Arc::clone(&entry.addresses))
+    }
+
+    async fn fetch(&self, table: &Pubkey) -> Result<Vec<Pubkey>> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAccountInfo",
+            "params": [table.to_string(), { "encoding": "base64" }],
+        });
+
+        let response: RpcResponse = self
+            .http_client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("getAccountInfo failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("Invalid getAccountInfo response: {}", e)))?;
+
+        if let Some(error) = response.error {
+            return Err(SentinelError::RpcError(error.message));
+        }
+
+        let account = response
+            .result
+            .and_then(|r| r.value)
+            .ok_or_else(|| SentinelError::RpcError(format!("Lookup table {} not found", table)))?;
+        if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+            return Err(SentinelError::RpcError(format!(
+                "{} is not a lookup table (owner {})",
+                table, account.owner
+            )));
+        }
+
+        let data = BASE64
+            .decode(&account.data.0)
+            .map_err(|e| SentinelError::SerializationError(format!("Invalid account data: {}", e)))?;
+        let addresses = parse_lookup_table(&data)?;
+        debug!("Fetched lookup table {} ({} addresses)", table, addresses.len());
+        Ok(addresses)
+    }
+}
+
+/// Addresses stored in raw lookup table account data
+pub fn parse_lookup_table(data: &[u8]) -> Result<Vec<Pubkey>> {
+    AddressLookupTable::deserialize(data)
+        .map(|table| table.addresses.to_vec())
+        .map_err(|e| SentinelError::SerializationError(format!("Invalid lookup table: {}", e)))
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<RpcAccountResult>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcAccountResult {
+    value: Option<RpcAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcAccount {
+    owner: String,
+    data: (String, String),
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Raw table account: 56-byte meta, then the addresses
+    fn table_data(addresses: &[Pubkey]) -> Vec<u8> {
+        let mut data = 1u32.to_le_bytes().to_vec(); // ProgramState::LookupTable
+        data.extend_from_slice(&u64::MAX.to_le_bytes()); // deactivation slot
+        data.extend_from_slice(&100u64.to_le_bytes()); // last extended slot
+        data.push(0); // last extended start index
+        data.push(0); // no authority
+        data.resize(56, 0);
+        for address in addresses {
+            data.extend_from_slice(address.as_ref());
+        }
+        data
+    }
+
+    /// Serves one `getAccountInfo` response per connection, counting requests
+    async fn serve_table(addresses: Vec<Pubkey>) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "value": {
+                "owner": ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+                "data": [BASE64.encode(table_data(&addresses)), "base64"]
+            }}
+        })
+        .to_string();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer).await;
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, requests)
+    }
+
+    fn lookup(table: Pubkey, writable: Vec<u8>, readonly: Vec<u8>) -> MessageAddressTableLookup {
+        MessageAddressTableLookup {
+            account_key: table,
+            writable_indexes: writable,
+            readonly_indexes: readonly,
+        }
+    }
+
+    #[test]
+    fn test_parse_lookup_table() {
+        let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        assert_eq!(parse_lookup_table(&table_data(&addresses)).unwrap(), addresses);
+        assert!(parse_lookup_table(&[0u8; 8]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetches_once_and_refetches_on_extension() {
+        let addresses: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let (url, requests) = serve_table(addresses.clone()).await;
+        let table = Pubkey::new_unique();
+        let cache = AltCache::new(url).unwrap();
+
+        let loaded = cache.resolve(&[lookup(table, vec![1], vec![0])]).await.unwrap();
+        assert_eq!((loaded.writable, loaded.readonly), (vec![addresses[1]], vec![addresses[0]]));
+        cache.resolve(&[lookup(table, vec![2], vec![])]).await.unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A stale copy from before the last extension is refreshed on demand
+        cache.insert(table, addresses[..2].to_vec());
+        let loaded = cache.resolve(&[lookup(table, vec![2], vec![])]).await.unwrap();
+        assert_eq!(loaded.writable, vec![addresses[2]]);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Past the end even after refetching
+        assert!(cache.resolve(&[lookup(table, vec![], vec![3])]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_refetch() {
+        let addresses = vec![Pubkey::new_unique()];
+        let (url, requests) = serve_table(addresses.clone()).await;
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let cache = AltCache::new(url)
+            .unwrap()
+            .with_ttl(Duration::from_secs(60))
+            .with_clock(clock.clone());
+        let table = Pubkey::new_unique();
+
+        cache.get(&table).await.unwrap();
+        clock.advance(Duration::from_secs(30));
+        cache.get(&table).await.unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        clock.advance(Duration::from_secs(31));
+        assert_eq!(*cache.get(&table).await.unwrap(), addresses);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        cache.invalidate(&table);
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compile_and_resolve_round_trip() {
+        let payer = Pubkey::new_unique();
+        let table = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let cache = AltCache::new("http://127.0.0.1:9").unwrap();
+        cache.insert(table, vec![Pubkey::new_unique(), pool]);
+
+        let ix = Instruction::new_with_bytes(program, &[1], vec![solana_sdk::instruction::AccountMeta::new(pool, false)]);
+        let message = cache.compile_v0(&payer, &[ix], &[table], Hash::new_unique()).await.unwrap();
+        assert_eq!(message.address_table_lookups().unwrap().len(), 1);
+        assert!(!message.static_account_keys().contains(&pool));
+
+        let keys = cache.account_keys(&message).await.unwrap();
+        assert_eq!(keys, vec![payer, program, pool]);
+    }
+}
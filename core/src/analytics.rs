@@ -0,0 +1,351 @@
+//! Historical Intent Analytics
+//!
+//! Daily/weekly rollups over the decision log for operator dashboards, without
+//! an external warehouse. A request can appear in the log more than once (the
+//! routing decision, then the landing label and fill report), so records are
+//! first merged on `request_id`; each request is counted in the period of its
+//! first record.
+//!
+//! Per period:
+//! - decisions, protected decisions (any route other than `StandardRpc`) and
+//!   the average risk score
+//! - landing rate per route (a request has landed once it carries a fill
+//!   report or a landing label)
+//! - protected volume per input mint and estimated MEV savings per output mint
+//!   (raw token units, so mints are never summed together)
+//! - tips and network fees spent (lamports) and confirmed MEV incidents, with
+//!   the value extracted by realized sandwiches per mint
+//!
+//! [`IntentAnalytics::heatmap`] ranks the pairs and pools attacked over a window.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::decision_log::DecisionRecord;
+use crate::mev_heatmap::MevHeatmap;
+use crate::types::RouteType;
+use crate::{Result, SentinelError};
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Rollup granularity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollupPeriod {
+    /// UTC calendar day
+    Day,
+
+    /// ISO week (Monday 00:00 UTC)
+    Week,
+}
+
+impl RollupPeriod {
+    /// Start of the period containing `timestamp_ms` (milliseconds since epoch)
+    pub fn start_of(&self, timestamp_ms: u64) -> u64 {
+        let day = timestamp_ms as i64 / MS_PER_DAY;
+        let start_day = match self {
+            RollupPeriod::Day => day,
+            // 1970-01-01 was a Thursday, three days after a Monday
+            RollupPeriod::Week => day - (day + 3).rem_euclid(7),
+        };
+        (start_day * MS_PER_DAY).max(0) as u64
+    }
+}
+
+/// Landing counts for one route
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteStats {
+    pub decisions: u64,
+    pub landed: u64,
+}
+
+impl RouteStats {
+    pub fn landing_rate(&self) -> f64 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.landed as f64 / self.decisions as f64
+        }
+    }
+}
+
+/// Aggregates for one period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rollup {
+    pub period: RollupPeriod,
+
+    /// Period start (milliseconds since epoch)
+    pub period_start_ms: u64,
+
+    pub decisions: u64,
+    pub protected_decisions: u64,
+    pub avg_risk_score: f64,
+
+    /// Keyed by route name (`JitoBundle`, `StandardRpc`, ...)
+    pub routes: BTreeMap<String, RouteStats>,
+
+    /// Realized input of protected fills, per input mint
+    pub volume_protected: BTreeMap<String, u64>,
+
+    /// Estimated MEV savings, per output mint
+    pub mev_savings: BTreeMap<String, u64>,
+
+    pub tips_spent_lamports: u64,
+    pub network_fees_lamports: u64,
+    pub mev_incidents: u64,
+
+    /// Value extracted by confirmed sandwiches, per mint
+    #[serde(default)]
+    pub mev_extracted: BTreeMap<String, u64>,
+}
+
+impl Rollup {
+    fn new(period: RollupPeriod, period_start_ms: u64) -> Self {
+        Self {
+            period,
+            period_start_ms,
+            decisions: 0,
+            protected_decisions: 0,
+            avg_risk_score: 0.0,
+            routes: BTreeMap::new(),
+            volume_protected: BTreeMap::new(),
+            mev_savings: BTreeMap::new(),
+            tips_spent_lamports: 0,
+            network_fees_lamports: 0,
+            mev_incidents: 0,
+            mev_extracted: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, record: &DecisionRecord) {
+        let protected = record.route != RouteType::StandardRpc;
+        let landed = record.fill.is_some() || record.mev_incident.is_some();
+
+        // Running mean keeps the rollup self-contained
+        self.decisions += 1;
+        self.avg_risk_score += (record.risk_score as f64 - self.avg_risk_score) / self.decisions as f64;
+        if protected {
+            self.protected_decisions += 1;
+        }
+
+        let route = self.routes.entry(format!("{:?}", record.route)).or_default();
+        route.decisions += 1;
+        if landed {
+            route.landed += 1;
+        }
+
+        if record.mev_incident == Some(true) {
+            self.mev_incidents += 1;
+        }
+        for (mint, amount) in record.incident.iter().flat_map(|incident| &incident.extracted) {
+            *self.mev_extracted.entry(mint.clone()).or_default() += amount;
+        }
+
+        if let Some(ref fill) = record.fill {
+            if protected {
+                *self.volume_protected.entry(fill.input_mint.clone()).or_default() += fill.realized_in_amount;
+            }
+            *self.mev_savings.entry(fill.output_mint.clone()).or_default() += fill.mev_savings_estimate;
+            self.tips_spent_lamports += fill.fees.jito_tip_lamports;
+            self.network_fees_lamports += fill.fees.base_fee_lamports + fill.fees.priority_fee_lamports;
+        }
+    }
+}
+
+/// Merges decision-log records per request and rolls them up by period
+#[derive(Default)]
+pub struct IntentAnalytics {
+    requests: HashMap<String, DecisionRecord>,
+}
+
+impl IntentAnalytics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a decision log (JSONL); malformed lines are skipped
+    pub fn load_decision_log(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to open {}: {}", path.display(), e)))?;
+
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| SentinelError::IoError(e.to_string()))?;
+            if let Ok(record) = serde_json::from_str::<DecisionRecord>(&line) {
+                self.add(record);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Merge one record into its request
+    pub fn add(&mut self, record: DecisionRecord) {
+        let Some(existing) = self.requests.get_mut(&record.request_id) else {
+            self.requests.insert(record.request_id.clone(), record);
+            return;
+        };
+
+        // Keep the routing decision; later records contribute outcomes
+        if record.mev_incident.is_some() {
+            existing.mev_incident = record.mev_incident;
+        }
+        if record.fill.is_some() {
+            existing.fill = record.fill;
+        }
+        if record.incident.is_some() {
+            existing.incident = record.incident;
+        }
+        if existing.intent_id.is_none() {
+            existing.intent_id = record.intent_id;
+        }
+        existing.timestamp_ms = existing.timestamp_ms.min(record.timestamp_ms);
+    }
+
+    /// Number of distinct requests seen
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Rollups for periods starting in `[from_ms, to_ms)`, oldest first
+    pub fn rollups(&self, period: RollupPeriod, from_ms: u64, to_ms: u64) -> Vec<Rollup> {
+        let mut rollups: BTreeMap<u64, Rollup> = BTreeMap::new();
+        for record in self.requests.values() {
+            let start = period.start_of(record.timestamp_ms);
+            if (from_ms..to_ms).contains(&start) {
+                rollups
+                    .entry(start)
+                    .or_insert_with(|| Rollup::new(period, start))
+                    .add(record);
+            }
+        }
+        rollups.into_values().collect()
+    }
+
+    /// Most-attacked pairs and pools for requests in `[from_ms, to_ms)`
+    pub fn heatmap(&self, from_ms: u64, to_ms: u64, limit: usize) -> MevHeatmap {
+        MevHeatmap::build(self.requests.values(), from_ms, to_ms, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fill_report::{FeesPaid, FillReport};
+    use crate::mev_incident::MevIncident;
+
+    /// 2024-01-03 (a Wednesday) 12:00 UTC
+    const WEDNESDAY_NOON: u64 = 1_704_283_200_000;
+
+    fn decision(id: &str, risk: f32, route: RouteType, timestamp_ms: u64) -> DecisionRecord {
+        let mut record = DecisionRecord::new(id.to_string(), risk, route);
+        record.timestamp_ms = timestamp_ms;
+        record
+    }
+
+    fn fill(input: u64, tip: u64, savings: u64) -> FillReport {
+        FillReport {
+            intent_id: "intent".to_string(),
+            signature: "sig".to_string(),
+            slot: 1,
+            input_mint: "USDC".to_string(),
+            output_mint: "SOL".to_string(),
+            quoted_in_amount: input,
+            quoted_out_amount: 1,
+            realized_in_amount: input,
+            realized_out_amount: 1,
+            realized_slippage_bps: 0.0,
+            fees: FeesPaid {
+                base_fee_lamports: 5_000,
+                priority_fee_lamports: 1_000,
+                jito_tip_lamports: tip,
+                dex_fee_amount: 0,
+            },
+            mev_savings_estimate: savings,
+            fee_entries: Vec::new(),
+        }
+    }
+
+    fn sandwich(extracted: u64) -> MevIncident {
+        MevIncident {
+            signature: "victim".to_string(),
+            slot: 1,
+            leader: None,
+            attacker: "attacker".to_string(),
+            pool: "pool".to_string(),
+            front_run: "front".to_string(),
+            back_run: "back".to_string(),
+            extracted: BTreeMap::from([("SOL".to_string(), extracted)]),
+        }
+    }
+
+    #[test]
+    fn test_period_boundaries() {
+        assert_eq!(RollupPeriod::Day.start_of(WEDNESDAY_NOON), WEDNESDAY_NOON - 12 * 3_600_000);
+        // Monday 2024-01-01 00:00 UTC
+        assert_eq!(RollupPeriod::Week.start_of(WEDNESDAY_NOON), 1_704_067_200_000);
+        assert_eq!(RollupPeriod::Week.start_of(1_704_067_200_000), 1_704_067_200_000);
+    }
+
+    #[test]
+    fn test_daily_rollup_merges_outcomes() {
+        let mut analytics = IntentAnalytics::new();
+        analytics.add(decision("a", 0.9, RouteType::JitoBundle, WEDNESDAY_NOON));
+        analytics.add(decision("b", 0.5, RouteType::JitoBundle, WEDNESDAY_NOON + 1));
+        analytics.add(decision("c", 0.1, RouteType::StandardRpc, WEDNESDAY_NOON + 2));
+
+        // Outcomes arrive later (possibly past midnight) and stay with the decision's day
+        let late = WEDNESDAY_NOON + MS_PER_DAY as u64;
+        analytics.add(decision("a", 0.9, RouteType::JitoBundle, late).with_fill(fill(1_000, 10_000, 40)));
+        analytics.add(decision("a", 0.9, RouteType::JitoBundle, late).with_mev_incident(false));
+        analytics.add(decision("c", 0.1, RouteType::StandardRpc, late).with_fill(fill(500, 0, 0)));
+        analytics.add(decision("c", 0.1, RouteType::StandardRpc, late).with_incident(sandwich(25)));
+
+        let rollups = analytics.rollups(RollupPeriod::Day, 0, u64::MAX);
+        assert_eq!(rollups.len(), 1);
+        let day = &rollups[0];
+
+        assert_eq!(day.decisions, 3);
+        assert_eq!(day.protected_decisions, 2);
+        assert!((day.avg_risk_score - 0.5).abs() < 1e-6);
+        assert_eq!(day.routes["JitoBundle"].landing_rate(), 0.5);
+        assert_eq!(day.routes["StandardRpc"].landing_rate(), 1.0);
+        assert_eq!(day.volume_protected["USDC"], 1_000);
+        assert_eq!(day.mev_savings["SOL"], 40);
+        assert_eq!(day.tips_spent_lamports, 10_000);
+        assert_eq!(day.network_fees_lamports, 12_000);
+        assert_eq!(day.mev_incidents, 1);
+        assert_eq!(day.mev_extracted["SOL"], 25);
+    }
+
+    #[test]
+    fn test_weekly_range_filter() {
+        let mut analytics = IntentAnalytics::new();
+        let week = 7 * MS_PER_DAY as u64;
+        for (i, ts) in [WEDNESDAY_NOON, WEDNESDAY_NOON + week, WEDNESDAY_NOON + 2 * week].iter().enumerate() {
+            analytics.add(decision(&i.to_string(), 0.5, RouteType::JitoSingle, *ts));
+        }
+
+        let monday = RollupPeriod::Week.start_of(WEDNESDAY_NOON);
+        let rollups = analytics.rollups(RollupPeriod::Week, monday + week, u64::MAX);
+        assert_eq!(rollups.len(), 2);
+        assert_eq!(rollups[0].period_start_ms, monday + week);
+        assert_eq!(rollups[0].decisions, 1);
+    }
+
+    #[test]
+    fn test_load_decision_log_skips_malformed() {
+        let path = std::env::temp_dir().join(format!("analytics-{}.jsonl", uuid::Uuid::new_v4()));
+        let record = serde_json::to_string(&decision("a", 0.4, RouteType::JitoSingle, WEDNESDAY_NOON)).unwrap();
+        std::fs::write(&path, format!("{}\nnot json\n\n", record)).unwrap();
+
+        let analytics = IntentAnalytics::new().load_decision_log(&path).unwrap();
+        assert_eq!(analytics.len(), 1);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,312 @@
+//! Best-Execution Venue Comparison
+//!
+//! `QuoteComparator` requests quotes from every configured DEX backend
+//! concurrently, normalizes them to one comparable figure and selects the best
+//! venue. The full quote set (including losing and failed venues) is attached to
+//! the decision record as best-execution evidence.
+//!
+//! Normalization (`effective_amount`):
+//! - ExactIn:  `out - fees - out × impact × impact_weight` (higher is better)
+//! - ExactOut: `in + fees + in × impact × impact_weight`   (lower is better)
+//!
+//! Quoted amounts already include the price impact of the quote itself; the
+//! impact term penalizes venues whose thin liquidity makes the fill more
+//! sensitive to state changes between quote and landing.
+
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::dex::{is_orca, DexAggregator, JUPITER_V6_PROGRAM_ID};
+use crate::{Result, SentinelError, SwapDetails, SwapMode};
+
+/// Default weight of price impact in the effective amount
+pub const DEFAULT_IMPACT_WEIGHT: f64 = 0.5;
+
+/// Quotable DEX backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DexVenue {
+    Jupiter,
+    Orca,
+}
+
+impl DexVenue {
+    pub const ALL: [DexVenue; 2] = [DexVenue::Jupiter, DexVenue::Orca];
+
+    /// Venue a swap is built for (Orca when requested, else Jupiter)
+    pub fn for_swap(swap_details: &SwapDetails) -> Self {
+        if is_orca(swap_details) {
+            DexVenue::Orca
+        } else {
+            DexVenue::Jupiter
+        }
+    }
+
+    /// On-chain program the venue's swap instruction invokes
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            DexVenue::Jupiter => {
+                Pubkey::from_str(JUPITER_V6_PROGRAM_ID).expect("Hardcoded Jupiter program ID must be valid")
+            }
+            DexVenue::Orca => crate::whirlpool::program_id(),
+        }
+    }
+}
+
+/// One venue's quote as recorded for best-execution evidence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueQuote {
+    pub venue: DexVenue,
+    pub in_amount: u64,
+    pub out_amount: u64,
+
+    /// Fees not already reflected in the quoted amounts
+    pub fee_amount: u64,
+
+    pub price_impact_bps: f64,
+
+    /// Normalized comparison figure (see module docs)
+    pub effective_amount: u64,
+
+    /// Venue chosen for execution
+    pub selected: bool,
+
+    /// Quote failure, if the venue could not be quoted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl VenueQuote {
+    pub fn new(venue: DexVenue, in_amount: u64, out_amount: u64, fee_amount: u64, price_impact_bps: f64) -> Self {
+        Self {
+            venue,
+            in_amount,
+            out_amount,
+            fee_amount,
+            price_impact_bps,
+            effective_amount: 0,
+            selected: false,
+            error: None,
+        }
+    }
+
+    /// Placeholder for a venue that failed to quote
+    pub fn failed(venue: DexVenue, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(venue, 0, 0, 0, 0.0)
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn normalize(&mut self, mode: SwapMode, impact_weight: f64) {
+        let impact = self.price_impact_bps.max(0.0) / 10_000.0 * impact_weight;
+        self.effective_amount = match mode {
+            SwapMode::ExactIn => {
+                let out = self.out_amount as f64;
+                (out - self.fee_amount as f64 - out * impact).max(0.0) as u64
+            }
+            SwapMode::ExactOut => {
+                let input = self.in_amount as f64;
+                (input + self.fee_amount as f64 + input * impact) as u64
+            }
+        };
+    }
+}
+
+/// Comparison outcome
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestExecution {
+    pub mode: SwapMode,
+
+    /// All venues, best first; failed venues last
+    pub quotes: Vec<VenueQuote>,
+}
+
+impl BestExecution {
+    /// Rank quotes and mark the winner
+    pub fn select(mode: SwapMode, mut quotes: Vec<VenueQuote>, impact_weight: f64) -> Result<Self> {
+        for quote in quotes.iter_mut().filter(|q| q.is_ok()) {
+            quote.normalize(mode, impact_weight);
+            quote.selected = false;
+        }
+
+        quotes.sort_by(|a, b| match (a.is_ok(), b.is_ok()) {
+            (true, true) => match mode {
+                SwapMode::ExactIn => b.effective_amount.cmp(&a.effective_amount),
+                SwapMode::ExactOut => a.effective_amount.cmp(&b.effective_amount),
+            },
+            (a_ok, b_ok) => b_ok.cmp(&a_ok),
+        });
+
+        match quotes.first_mut() {
+            Some(best) if best.is_ok() => best.selected = true,
+            _ => {
+                let errors: Vec<String> = quotes
+                    .iter()
+                    .map(|q| format!("{:?}: {}", q.venue, q.error.as_deref().unwrap_or("no quote")))
+                    .collect();
+                return Err(SentinelError::DexError(format!(
+                    "No venue returned a quote ({})",
+                    errors.join("; ")
+                )));
+            }
+        }
+
+        Ok(Self { mode, quotes })
+    }
+
+    /// Winning quote
+    pub fn selected(&self) -> &VenueQuote {
+        &self.quotes[0]
+    }
+
+    /// Quotes that were not selected (including failures)
+    pub fn losing_quotes(&self) -> &[VenueQuote] {
+        &self.quotes[1..]
+    }
+
+    /// Improvement of the winner over the runner-up (basis points)
+    pub fn improvement_bps(&self) -> Option<f64> {
+        let runner_up = self.quotes.get(1).filter(|q| q.is_ok())?;
+        let best = self.selected().effective_amount as f64;
+        let other = runner_up.effective_amount as f64;
+        if other == 0.0 {
+            return None;
+        }
+        Some(match self.mode {
+            SwapMode::ExactIn => (best - other) / other * 10_000.0,
+            SwapMode::ExactOut => (other - best) / other * 10_000.0,
+        })
+    }
+}
+
+/// Fans quote requests out across DEX backends and picks the best venue
+pub struct QuoteComparator {
+    dex: Arc<DexAggregator>,
+    venues: Vec<DexVenue>,
+    impact_weight: f64,
+}
+
+impl QuoteComparator {
+    /// Compare every venue the aggregator supports
+    pub fn new(dex: Arc<DexAggregator>) -> Self {
+        let venues = DexVenue::ALL.into_iter().filter(|v| dex.supports(*v)).collect();
+        Self {
+            dex,
+            venues,
+            impact_weight: DEFAULT_IMPACT_WEIGHT,
+        }
+    }
+
+    /// Restrict the compared venues
+    pub fn with_venues(mut self, venues: Vec<DexVenue>) -> Self {
+        self.venues = venues;
+        self
+    }
+
+    pub fn with_impact_weight(mut self, weight: f64) -> Self {
+        self.impact_weight = weight.max(0.0);
+        self
+    }
+
+    pub fn venues(&self) -> &[DexVenue] {
+        &self.venues
+    }
+
+    /// Quote all venues concurrently and select the best
+    pub async fn compare(&self, swap_details: &SwapDetails, slippage_bps: u16) -> Result<BestExecution> {
+        let quotes = join_all(self.venues.iter().map(|&venue| async move {
+            match self.dex.quote_venue(venue, swap_details, slippage_bps).await {
+                Ok(quote) => quote,
+                Err(e) => {
+                    warn!("{:?} quote failed: {}", venue, e);
+                    VenueQuote::failed(venue, e.to_string())
+                }
+            }
+        }))
+        .await;
+
+        let best = BestExecution::select(swap_details.mode, quotes, self.impact_weight)?;
+        info!(
+            "🏁 Best execution: {:?} (effective {}, {} venues compared)",
+            best.selected().venue,
+            best.selected().effective_amount,
+            best.quotes.len()
+        );
+        debug!("Losing quotes: {:?}", best.losing_quotes());
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_in_prefers_net_output() {
+        let quotes = vec![
+            VenueQuote::new(DexVenue::Jupiter, 1_000, 1_000_000, 5_000, 10.0),
+            VenueQuote::new(DexVenue::Orca, 1_000, 998_000, 0, 10.0),
+        ];
+        let best = BestExecution::select(SwapMode::ExactIn, quotes, DEFAULT_IMPACT_WEIGHT).unwrap();
+
+        assert_eq!(best.selected().venue, DexVenue::Orca);
+        assert!(best.selected().selected);
+        assert_eq!(best.losing_quotes()[0].venue, DexVenue::Jupiter);
+        assert!(best.improvement_bps().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_price_impact_penalized() {
+        // Same output, thinner venue loses
+        let quotes = vec![
+            VenueQuote::new(DexVenue::Orca, 1_000, 1_000_000, 0, 300.0),
+            VenueQuote::new(DexVenue::Jupiter, 1_000, 1_000_000, 0, 5.0),
+        ];
+        let best = BestExecution::select(SwapMode::ExactIn, quotes, DEFAULT_IMPACT_WEIGHT).unwrap();
+        assert_eq!(best.selected().venue, DexVenue::Jupiter);
+    }
+
+    #[test]
+    fn test_exact_out_prefers_lowest_cost() {
+        let quotes = vec![
+            VenueQuote::new(DexVenue::Jupiter, 1_010_000, 1_000, 0, 0.0),
+            VenueQuote::new(DexVenue::Orca, 1_005_000, 1_000, 0, 0.0),
+        ];
+        let best = BestExecution::select(SwapMode::ExactOut, quotes, 0.0).unwrap();
+        assert_eq!(best.selected().venue, DexVenue::Orca);
+        assert_eq!(best.selected().effective_amount, 1_005_000);
+    }
+
+    #[test]
+    fn test_failed_venues_recorded_last() {
+        let quotes = vec![
+            VenueQuote::failed(DexVenue::Orca, "pool not found".to_string()),
+            VenueQuote::new(DexVenue::Jupiter, 1_000, 900, 0, 0.0),
+        ];
+        let best = BestExecution::select(SwapMode::ExactIn, quotes, 0.0).unwrap();
+        assert_eq!(best.selected().venue, DexVenue::Jupiter);
+        assert_eq!(best.losing_quotes()[0].error.as_deref(), Some("pool not found"));
+        assert!(best.improvement_bps().is_none());
+
+        let all_failed = vec![VenueQuote::failed(DexVenue::Orca, "timeout".to_string())];
+        assert!(BestExecution::select(SwapMode::ExactIn, all_failed, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_comparator_uses_supported_venues() {
+        let comparator = QuoteComparator::new(Arc::new(DexAggregator::new()));
+        assert_eq!(comparator.venues(), &[DexVenue::Jupiter]);
+
+        let dex = DexAggregator::new().with_orca("http://localhost:1").unwrap();
+        assert_eq!(QuoteComparator::new(Arc::new(dex)).venues().len(), 2);
+    }
+}
@@ -0,0 +1,477 @@
+//! Shared Chain Clock
+//!
+//! Expiry, leader tracking and tip timing all need the current slot and a
+//! recent blockhash. Rather than each polling RPC on its own, one
+//! [`ChainClockService`] keeps them current and hands out [`ChainClock`]s:
+//! cheap cloneable handles over a `watch` channel that can be read without
+//! blocking or awaited for the next update.
+//!
+//! Sources:
+//! - **websocket** (`websocket` feature, `EndpointConfig::ws_url`):
+//!   `slotSubscribe` pushes every slot as it is produced
+//! - **RPC polling**: `getSlot` whenever the websocket is silent for
+//!   `stale_after_ms` (or not configured), plus `getLatestBlockhash` and
+//!   `getBlockTime` every `blockhash_refresh_ms`
+//!
+//! Slots only move forward; a lagging source never rewinds the clock.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::EndpointConfig;
+use crate::error::{Result, SentinelError};
+
+/// Where the latest slot came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainSource {
+    Websocket,
+    Rpc,
+}
+
+/// Latest observed chain position
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainState {
+    pub slot: u64,
+    pub blockhash: Option<Hash>,
+
+    /// Last block height at which `blockhash` is accepted
+    pub last_valid_block_height: u64,
+
+    /// Unix seconds of the most recent block with a known time
+    pub block_time: Option<i64>,
+
+    /// Local time of the last slot update (Unix ms)
+    pub updated_at_ms: i64,
+    pub source: Option<ChainSource>,
+}
+
+/// Polling cadence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainClockSettings {
+    pub rpc_url: String,
+
+    /// `slotSubscribe` endpoint (RPC polling only when unset)
+    pub ws_url: Option<String>,
+
+    /// RPC slot polling interval while the websocket is silent
+    pub poll_interval_ms: u64,
+
+    /// Blockhash and block time refresh interval
+    pub blockhash_refresh_ms: u64,
+
+    /// Websocket silence before RPC polling takes over
+    pub stale_after_ms: u64,
+}
+
+impl Default for ChainClockSettings {
+    fn default() -> Self {
+        Self::from_endpoints(&EndpointConfig::default())
+    }
+}
+
+impl ChainClockSettings {
+    pub fn from_endpoints(endpoints: &EndpointConfig) -> Self {
+        Self {
+            rpc_url: endpoints.rpc_url.clone(),
+            ws_url: endpoints.ws_url.clone(),
+            poll_interval_ms: 400,
+            blockhash_refresh_ms: 2_000,
+            stale_after_ms: 2_000,
+        }
+    }
+}
+
+/// Cheap cloneable view of the chain position
+#[derive(Debug, Clone)]
+pub struct ChainClock {
+    state: Arc<watch::Sender<ChainState>>,
+    clock: SharedClock,
+}
+
+impl Default for ChainClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChainClock {
+    /// Handle with no observations yet (slot 0); feed it with `observe_*`
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(watch::Sender::new(ChainState::default())),
+            clock: system_clock(),
+        }
+    }
+
+    /// Time source for update timestamps and staleness checks
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.state.borrow().slot
+    }
+
+    pub fn blockhash(&self) -> Option<Hash> {
+        self.state.borrow().blockhash
+    }
+
+    pub fn last_valid_block_height(&self) -> u64 {
+        self.state.borrow().last_valid_block_height
+    }
+
+    pub fn block_time(&self) -> Option<DateTime<Utc>> {
+        self.state
+            .borrow()
+            .block_time
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+    }
+
+    pub fn state(&self) -> ChainState {
+        self.state.borrow().clone()
+    }
+
+    /// Receiver notified on every change
+    pub fn subscribe(&self) -> watch::Receiver<ChainState> {
+        self.state.subscribe()
+    }
+
+    /// Time since the last slot update
+    pub fn age(&self) -> Duration {
+        let updated = self.state.borrow().updated_at_ms;
+        Duration::from_millis((self.clock.unix_millis() - updated).max(0) as u64)
+    }
+
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.slot() == 0 || self.age() > max_age
+    }
+
+    /// Resolves once the clock reaches `slot`
+    pub async fn wait_for_slot(&self, slot: u64) -> ChainState {
+        let mut rx = self.subscribe();
+        let reached = match rx.wait_for(|state| state.slot >= slot).await {
+            Ok(state) => state.clone(),
+            // The sender lives in `self`, so the channel cannot close here
+            Err(_) => self.state(),
+        };
+        reached
+    }
+
+    /// Record a slot; returns false if it was not newer
+    pub fn observe_slot(&self, slot: u64, source: ChainSource) -> bool {
+        let now_ms = self.clock.unix_millis();
+        self.state.send_if_modified(|state| {
+            if slot <= state.slot {
+                return false;
+            }
+            state.slot = slot;
+            state.updated_at_ms = now_ms;
+            state.source = Some(source);
+            true
+        })
+    }
+
+    pub fn observe_blockhash(&self, blockhash: Hash, last_valid_block_height: u64) {
+        self.state.send_if_modified(|state| {
+            if state.blockhash == Some(blockhash) {
+                return false;
+            }
+            state.blockhash = Some(blockhash);
+            state.last_valid_block_height = last_valid_block_height;
+            true
+        });
+    }
+
+    pub fn observe_block_time(&self, block_time: i64) {
+        self.state.send_if_modified(|state| {
+            if state.block_time.is_some_and(|t| t >= block_time) {
+                return false;
+            }
+            state.block_time = Some(block_time);
+            true
+        });
+    }
+}
+
+/// Background tasks keeping a [`ChainClock`] current
+pub struct ChainClockService {
+    clock: ChainClock,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl ChainClockService {
+    /// Start polling (and the websocket, when configured and compiled in)
+    pub fn spawn(settings: ChainClockSettings) -> Result<Self> {
+        Self::spawn_with(settings, ChainClock::new())
+    }
+
+    pub fn from_config(config: &crate::config::SentinelConfig) -> Result<Self> {
+        Self::spawn(ChainClockSettings::from_endpoints(&config.endpoints))
+    }
+
+    /// Start the tasks feeding an existing handle
+    pub fn spawn_with(settings: ChainClockSettings, clock: ChainClock) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to create HTTP client: {}", e)))?;
+
+        let poller = tokio::spawn(poll_rpc(http, settings.clone(), clock.clone()));
+        let subscriber = match settings.ws_url {
+            #[cfg(feature = "websocket")]
+            Some(ws_url) => Some(tokio::spawn(websocket::run(ws_url, clock.clone()))),
+            #[cfg(not(feature = "websocket"))]
+            Some(_) => {
+                warn!("ChainClock: ws_url set but the `websocket` feature is disabled; polling RPC");
+                None
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            clock,
+            tasks: std::iter::once(poller).chain(subscriber).collect(),
+        })
+    }
+
+    pub fn clock(&self) -> ChainClock {
+        self.clock.clone()
+    }
+}
+
+impl Drop for ChainClockService {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn rpc_call(http: &reqwest::Client, rpc_url: &str, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let response: serde_json::Value = http
+        .post(rpc_url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| SentinelError::RpcError(format!("{} failed: {}", method, e)))?
+        .json()
+        .await
+        .map_err(|e| SentinelError::RpcError(format!("{} response: {}", method, e)))?;
+
+    if let Some(error) = response.get("error") {
+        return Err(SentinelError::RpcError(format!("{}: {}", method, error)));
+    }
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| SentinelError::RpcError(format!("{}: missing result", method)))
+}
+
+/// `getLatestBlockhash` result → (context slot, blockhash, last valid block height)
+fn parse_latest_blockhash(result: &serde_json::Value) -> Result<(u64, Hash, u64)> {
+    let slot = result["context"]["slot"].as_u64().unwrap_or(0);
+    let value = &result["value"];
+    let blockhash = value["blockhash"]
+        .as_str()
+        .and_then(|s| Hash::from_str(s).ok())
+        .ok_or_else(|| SentinelError::ParseError("getLatestBlockhash: invalid blockhash".to_string()))?;
+    let last_valid = value["lastValidBlockHeight"].as_u64().unwrap_or(0);
+    Ok((slot, blockhash, last_valid))
+}
+
+async fn poll_rpc(http: reqwest::Client, settings: ChainClockSettings, clock: ChainClock) {
+    let poll = Duration::from_millis(settings.poll_interval_ms.max(50));
+    let refresh = Duration::from_millis(settings.blockhash_refresh_ms.max(50));
+    let stale_after = Duration::from_millis(settings.stale_after_ms);
+    let mut last_refresh: Option<tokio::time::Instant> = None;
+    let mut interval = tokio::time::interval(poll);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        if settings.ws_url.is_none() || clock.is_stale(stale_after) {
+            match rpc_call(&http, &settings.rpc_url, "getSlot", serde_json::json!([{"commitment": "processed"}])).await {
+                Ok(slot) => {
+                    if let Some(slot) = slot.as_u64() {
+                        clock.observe_slot(slot, ChainSource::Rpc);
+                    }
+                }
+                Err(e) => warn!("ChainClock: {}", e),
+            }
+        }
+
+        if last_refresh.is_some_and(|at| at.elapsed() < refresh) {
+            continue;
+        }
+        last_refresh = Some(tokio::time::Instant::now());
+
+        let latest = rpc_call(&http, &settings.rpc_url, "getLatestBlockhash", serde_json::json!([{"commitment": "confirmed"}]))
+            .await
+            .and_then(|result| parse_latest_blockhash(&result));
+        match latest {
+            Ok((slot, blockhash, last_valid)) => {
+                clock.observe_slot(slot, ChainSource::Rpc);
+                clock.observe_blockhash(blockhash, last_valid);
+                match rpc_call(&http, &settings.rpc_url, "getBlockTime", serde_json::json!([slot])).await {
+                    Ok(time) => {
+                        if let Some(time) = time.as_i64() {
+                            clock.observe_block_time(time);
+                        }
+                    }
+                    Err(e) => debug!("ChainClock: {}", e),
+                }
+            }
+            Err(e) => warn!("ChainClock: {}", e),
+        }
+    }
+}
+
+/// `slotNotification` → slot
+#[cfg_attr(not(feature = "websocket"), allow(dead_code))]
+fn parse_slot_notification(notification: &serde_json::Value) -> Option<u64> {
+    if notification.get("method").and_then(|m| m.as_str()) != Some("slotNotification") {
+        return None;
+    }
+    notification["params"]["result"]["slot"].as_u64()
+}
+
+#[cfg(feature = "websocket")]
+mod websocket {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    pub async fn run(ws_url: String, clock: ChainClock) {
+        loop {
+            if let Err(e) = stream_slots(&ws_url, &clock).await {
+                warn!("slotSubscribe stream error: {} - reconnecting", e);
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+
+    async fn stream_slots(ws_url: &str, clock: &ChainClock) -> Result<()> {
+        let (mut socket, _) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("slotSubscribe connect failed: {}", e)))?;
+
+        let request = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "slotSubscribe"});
+        socket
+            .send(Message::Text(request.to_string()))
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("slotSubscribe request failed: {}", e)))?;
+
+        while let Some(message) = socket.next().await {
+            let message = message.map_err(|e| SentinelError::NetworkError(e.to_string()))?;
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let notification: serde_json::Value =
+                serde_json::from_str(&text).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+            if let Some(slot) = parse_slot_notification(&notification) {
+                clock.observe_slot(slot, ChainSource::Websocket);
+            }
+        }
+
+        Err(SentinelError::NetworkError("slotSubscribe stream closed".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn test_slots_only_move_forward() {
+        let chain = ChainClock::new();
+        assert!(chain.observe_slot(100, ChainSource::Rpc));
+        assert!(!chain.observe_slot(99, ChainSource::Websocket));
+        assert!(!chain.observe_slot(100, ChainSource::Websocket));
+        assert_eq!(chain.slot(), 100);
+        assert_eq!(chain.state().source, Some(ChainSource::Rpc));
+
+        // Clones share state
+        let other = chain.clone();
+        other.observe_slot(101, ChainSource::Websocket);
+        assert_eq!(chain.slot(), 101);
+    }
+
+    #[test]
+    fn test_blockhash_and_block_time() {
+        let chain = ChainClock::new();
+        let hash = Hash::new_unique();
+        chain.observe_blockhash(hash, 5_000);
+        assert_eq!(chain.blockhash(), Some(hash));
+        assert_eq!(chain.last_valid_block_height(), 5_000);
+
+        chain.observe_block_time(1_700_000_000);
+        chain.observe_block_time(1_600_000_000);
+        assert_eq!(chain.block_time().unwrap().timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_staleness_follows_clock() {
+        let time = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let chain = ChainClock::new().with_clock(time.clone());
+        assert!(chain.is_stale(Duration::from_secs(2)));
+
+        chain.observe_slot(100, ChainSource::Websocket);
+        assert!(!chain.is_stale(Duration::from_secs(2)));
+
+        time.advance(Duration::from_secs(3));
+        assert!(chain.is_stale(Duration::from_secs(2)));
+        assert_eq!(chain.age(), Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_slot() {
+        let chain = ChainClock::new();
+        let waiter = {
+            let chain = chain.clone();
+            tokio::spawn(async move { chain.wait_for_slot(105).await })
+        };
+
+        for slot in 100..=105 {
+            chain.observe_slot(slot, ChainSource::Websocket);
+            tokio::task::yield_now().await;
+        }
+        let state = tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        assert_eq!(state.slot, 105);
+    }
+
+    #[test]
+    fn test_parse_rpc_payloads() {
+        let hash = Hash::new_unique();
+        let result = serde_json::json!({
+            "context": {"slot": 250_000_000u64},
+            "value": {"blockhash": hash.to_string(), "lastValidBlockHeight": 230_000_150u64}
+        });
+        assert_eq!(parse_latest_blockhash(&result).unwrap(), (250_000_000, hash, 230_000_150));
+        assert!(parse_latest_blockhash(&serde_json::json!({"value": {}})).is_err());
+
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "slotNotification",
+            "params": {"result": {"parent": 75, "root": 44, "slot": 76}, "subscription": 0}
+        });
+        assert_eq!(parse_slot_notification(&notification), Some(76));
+        assert_eq!(parse_slot_notification(&serde_json::json!({"result": 0, "id": 1})), None);
+    }
+}
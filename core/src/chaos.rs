@@ -0,0 +1,339 @@
+//! Chaos Testing Hooks (`chaos` feature)
+//!
+//! Rehearsing degradation modes should not wait for a real outage. A
+//! [`ChaosInjector`] shared with the dependency clients injects failures at
+//! runtime, armed and disarmed through the inference server's admin endpoint:
+//! - **oracle timeout**: Pyth fetches fail with `Timeout` (after `delay_ms`)
+//! - **bundle rejection**: the block engine "rejects" `sendBundle`
+//! - **RPC 429**: block engine JSON-RPC calls fail with a rate-limit error
+//! - **slow inference**: scoring sleeps `delay_ms` before running
+//!
+//! Oracle timeouts and 429s fail inside the circuit breaker call, so they trip
+//! breakers and alerts exactly like the real thing; rejections are answers,
+//! not outages, and leave breakers alone. A fault fires with `probability`
+//! per call and disarms itself after `max_injections` or `duration_secs`.
+//! Builds without the feature carry none of this.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::{Result, SentinelError};
+
+/// Failure that can be injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    OracleTimeout,
+    BundleRejection,
+    RpcRateLimit,
+    SlowInference,
+}
+
+impl Fault {
+    pub const ALL: [Fault; 4] = [
+        Fault::OracleTimeout,
+        Fault::BundleRejection,
+        Fault::RpcRateLimit,
+        Fault::SlowInference,
+    ];
+
+    /// Error the faulted call returns (slow inference only delays)
+    fn error(self) -> Option<SentinelError> {
+        match self {
+            Fault::OracleTimeout => Some(SentinelError::Timeout("chaos: injected oracle timeout".to_string())),
+            Fault::BundleRejection => Some(SentinelError::BundleError(
+                "Send bundle failed: chaos: injected rejection".to_string(),
+            )),
+            Fault::RpcRateLimit => Some(SentinelError::RpcError(
+                "chaos: injected HTTP 429 Too Many Requests".to_string(),
+            )),
+            Fault::SlowInference => None,
+        }
+    }
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+/// How an armed fault behaves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultSpec {
+    /// Chance each call is faulted (0-1)
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+
+    /// Latency added to a faulted call before it fails (or, for slow inference, proceeds)
+    #[serde(default)]
+    pub delay_ms: u64,
+
+    /// Disarm after this many injections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_injections: Option<u64>,
+
+    /// Disarm this long after arming
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+}
+
+impl Default for FaultSpec {
+    fn default() -> Self {
+        Self {
+            probability: default_probability(),
+            delay_ms: 0,
+            max_injections: None,
+            duration_secs: None,
+        }
+    }
+}
+
+impl FaultSpec {
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(SentinelError::ConfigError(format!(
+                "Fault probability must be within 0-1, got {}",
+                self.probability
+            )));
+        }
+        if self.max_injections == Some(0) || self.duration_secs == Some(0) {
+            return Err(SentinelError::ConfigError(
+                "max_injections and duration_secs must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// An armed fault and what it has done so far
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArmedFault {
+    pub fault: Fault,
+    pub spec: FaultSpec,
+    pub armed_at_ms: i64,
+    pub injected: u64,
+}
+
+/// Runtime failure injection shared by the dependency clients
+pub struct ChaosInjector {
+    faults: Mutex<BTreeMap<Fault, ArmedFault>>,
+
+    /// xorshift64* state for the per-call probability roll
+    rng: AtomicU64,
+    clock: SharedClock,
+}
+
+impl ChaosInjector {
+    pub fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            faults: Mutex::new(BTreeMap::new()),
+            rng: AtomicU64::new(seed | 1),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Reproducible probability rolls
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.rng.store(seed | 1, Ordering::Relaxed);
+        self
+    }
+
+    /// Arm (or re-arm, resetting its count) a fault
+    pub fn arm(&self, fault: Fault, spec: FaultSpec) -> Result<()> {
+        spec.validate()?;
+        warn!("🐒 Chaos: arming {:?} ({:?})", fault, spec);
+        let armed = ArmedFault {
+            fault,
+            spec,
+            armed_at_ms: self.clock.unix_millis(),
+            injected: 0,
+        };
+        self.faults.lock().unwrap_or_else(|p| p.into_inner()).insert(fault, armed);
+        Ok(())
+    }
+
+    /// Disarm a fault; false when it was not armed
+    pub fn disarm(&self, fault: Fault) -> bool {
+        let removed = self.faults.lock().unwrap_or_else(|p| p.into_inner()).remove(&fault);
+        if let Some(ref armed) = removed {
+            info!("Chaos: disarmed {:?} after {} injections", fault, armed.injected);
+        }
+        removed.is_some()
+    }
+
+    pub fn disarm_all(&self) {
+        let mut faults = self.faults.lock().unwrap_or_else(|p| p.into_inner());
+        if !faults.is_empty() {
+            info!("Chaos: disarmed all {} faults", faults.len());
+        }
+        faults.clear();
+    }
+
+    /// Faults still armed
+    pub fn armed(&self) -> Vec<ArmedFault> {
+        let now_ms = self.clock.unix_millis();
+        let mut faults = self.faults.lock().unwrap_or_else(|p| p.into_inner());
+        faults.retain(|_, armed| !expired(armed, now_ms));
+        faults.values().cloned().collect()
+    }
+
+    /// Fault the calling dependency call, if armed and the roll says so
+    pub async fn inject(&self, fault: Fault) -> Result<()> {
+        match self.roll(fault) {
+            Some(delay) => {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                fault.error().map_or(Ok(()), Err)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// [`ChaosInjector::inject`] for synchronous call sites (sleeps the thread)
+    pub fn inject_blocking(&self, fault: Fault) -> Result<()> {
+        match self.roll(fault) {
+            Some(delay) => {
+                if !delay.is_zero() {
+                    std::thread::sleep(delay);
+                }
+                fault.error().map_or(Ok(()), Err)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Delay of a fired fault
+    fn roll(&self, fault: Fault) -> Option<Duration> {
+        let now_ms = self.clock.unix_millis();
+        let mut faults = self.faults.lock().unwrap_or_else(|p| p.into_inner());
+        let armed = faults.get_mut(&fault)?;
+        if expired(armed, now_ms) {
+            info!("Chaos: {:?} expired after {} injections", fault, armed.injected);
+            faults.remove(&fault);
+            return None;
+        }
+        if armed.spec.probability < 1.0 && self.next_unit() >= armed.spec.probability {
+            return None;
+        }
+
+        armed.injected += 1;
+        let delay = Duration::from_millis(armed.spec.delay_ms);
+        warn!("🐒 Chaos: injecting {:?} (#{})", fault, armed.injected);
+        if armed.spec.max_injections.is_some_and(|max| armed.injected >= max) {
+            faults.remove(&fault);
+        }
+        Some(delay)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_unit(&self) -> f64 {
+        let step = |mut x: u64| {
+            x ^= x >> 12;
+            x ^= x << 25;
+            x ^= x >> 27;
+            x
+        };
+        let previous = self
+            .rng
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(step(x)))
+            .unwrap_or_else(|x| x);
+        let value = step(previous).wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for ChaosInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn expired(armed: &ArmedFault, now_ms: i64) -> bool {
+    armed
+        .spec
+        .duration_secs
+        .is_some_and(|secs| now_ms - armed.armed_at_ms >= secs as i64 * 1_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_armed_fault_fails_until_exhausted() {
+        let chaos = ChaosInjector::new();
+        assert!(chaos.inject(Fault::OracleTimeout).await.is_ok());
+
+        let spec = FaultSpec {
+            max_injections: Some(2),
+            ..FaultSpec::default()
+        };
+        chaos.arm(Fault::OracleTimeout, spec).unwrap();
+        for _ in 0..2 {
+            let err = chaos.inject(Fault::OracleTimeout).await.unwrap_err();
+            assert!(crate::is_dependency_failure(&err), "{}", err);
+        }
+        assert!(chaos.inject(Fault::OracleTimeout).await.is_ok());
+        assert!(chaos.armed().is_empty());
+
+        // Only the armed fault fires
+        chaos.arm(Fault::RpcRateLimit, FaultSpec::default()).unwrap();
+        assert!(chaos.inject_blocking(Fault::BundleRejection).is_ok());
+        assert!(chaos.inject_blocking(Fault::RpcRateLimit).unwrap_err().to_string().contains("429"));
+        assert!(chaos.disarm(Fault::RpcRateLimit));
+        assert!(!chaos.disarm(Fault::RpcRateLimit));
+    }
+
+    #[test]
+    fn test_probability_duration_and_validation() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let chaos = ChaosInjector::new().with_clock(clock.clone()).with_seed(7);
+
+        let spec = FaultSpec {
+            probability: 0.25,
+            duration_secs: Some(60),
+            ..FaultSpec::default()
+        };
+        chaos.arm(Fault::BundleRejection, spec).unwrap();
+        let failed = (0..2_000)
+            .filter(|_| chaos.inject_blocking(Fault::BundleRejection).is_err())
+            .count();
+        assert!((350..650).contains(&failed), "{}", failed);
+        assert_eq!(chaos.armed()[0].injected, failed as u64);
+
+        clock.advance(Duration::from_secs(60));
+        assert!(chaos.inject_blocking(Fault::BundleRejection).is_ok());
+        assert!(chaos.armed().is_empty());
+
+        // Slow inference delays but succeeds
+        chaos.arm(Fault::SlowInference, FaultSpec { delay_ms: 5, ..FaultSpec::default() }).unwrap();
+        assert!(chaos.inject_blocking(Fault::SlowInference).is_ok());
+
+        let bad = FaultSpec {
+            probability: 1.5,
+            ..FaultSpec::default()
+        };
+        assert!(chaos.arm(Fault::OracleTimeout, bad).is_err());
+        let bad = FaultSpec {
+            max_injections: Some(0),
+            ..FaultSpec::default()
+        };
+        assert!(chaos.arm(Fault::OracleTimeout, bad).is_err());
+    }
+}
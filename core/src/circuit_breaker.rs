@@ -0,0 +1,458 @@
+//! Circuit Breakers for External Dependencies
+//!
+//! Pyth, the Jito block engines and RPC endpoints fail independently. Each
+//! client gets a [`CircuitBreaker`] so a dead dependency is skipped quickly
+//! instead of costing a timeout on every request:
+//! - **Closed**: calls pass; outcomes are kept over a rolling window and the
+//!   breaker opens once `failure_rate` is reached (after `min_calls`)
+//! - **Open**: calls fail fast with [`SentinelError::CircuitOpen`] for `open_ms`
+//! - **Half-open**: up to `half_open_probes` trial calls are let through;
+//!   that many successes close the breaker, any failure re-opens it
+//!
+//! Only transport-level errors ([`is_dependency_failure`]) count as failures:
+//! a rejected bundle or a missing price still means the dependency answered.
+//! [`CircuitBreakers`] holds one breaker per dependency name and reports their
+//! state for health checks.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use tracing::{info, warn};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{CircuitBreakerSettings, SentinelConfig};
+use crate::{Result, SentinelError};
+
+/// Breaker position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Point-in-time breaker state of one dependency (health check representation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    pub name: String,
+    pub state: CircuitState,
+
+    /// Failure rate over the current window (0-1)
+    pub failure_rate: f64,
+
+    /// Calls in the current window
+    pub calls: usize,
+
+    /// Until the next trial call, while open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+
+    /// Times the breaker has opened since start
+    pub trips: u64,
+}
+
+/// Errors that say the dependency itself is unavailable
+pub fn is_dependency_failure(error: &SentinelError) -> bool {
+    matches!(
+        error,
+        SentinelError::NetworkError(_)
+            | SentinelError::RpcError(_)
+            | SentinelError::Timeout(_)
+            | SentinelError::ConnectionError(_)
+            | SentinelError::PriceOracleError(_)
+            | SentinelError::StreamError(_)
+    )
+}
+
+struct BreakerState {
+    state: CircuitState,
+    outcomes: VecDeque<bool>,
+    failures: usize,
+    opened_at_ms: i64,
+    probes_in_flight: u32,
+    probe_successes: u32,
+    trips: u64,
+}
+
+impl BreakerState {
+    fn rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.failures as f64 / self.outcomes.len() as f64
+    }
+
+    fn clear(&mut self) {
+        self.outcomes.clear();
+        self.failures = 0;
+        self.probes_in_flight = 0;
+        self.probe_successes = 0;
+    }
+}
+
+/// Failure-rate circuit breaker around one dependency
+pub struct CircuitBreaker {
+    name: String,
+    settings: RwLock<CircuitBreakerSettings>,
+    state: Mutex<BreakerState>,
+    clock: SharedClock,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, settings: CircuitBreakerSettings) -> Self {
+        Self {
+            name: name.into(),
+            settings: RwLock::new(settings),
+            state: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                failures: 0,
+                opened_at_ms: 0,
+                probes_in_flight: 0,
+                probe_successes: 0,
+                trips: 0,
+            }),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Swap in reloaded settings; the current state and window are kept
+    pub fn apply_settings(&self, settings: &CircuitBreakerSettings) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = settings.clone();
+    }
+
+    fn settings(&self) -> CircuitBreakerSettings {
+        self.settings.read().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    /// Permission for one call, or `CircuitOpen` while the dependency is skipped
+    pub fn acquire(&self) -> Result<Permit<'_>> {
+        let settings = self.settings();
+        let now = self.clock.unix_millis();
+        let mut state = lock(&self.state);
+
+        if state.state == CircuitState::Open {
+            let reopen_at = state.opened_at_ms + settings.open_ms as i64;
+            if now < reopen_at {
+                return Err(SentinelError::CircuitOpen {
+                    dependency: self.name.clone(),
+                    retry_after_ms: (reopen_at - now) as u64,
+                });
+            }
+            info!("Circuit for {} half-open - probing", self.name);
+            state.state = CircuitState::HalfOpen;
+            state.clear();
+        }
+
+        let probe = state.state == CircuitState::HalfOpen;
+        if probe {
+            if state.probes_in_flight + state.probe_successes >= settings.half_open_probes {
+                return Err(SentinelError::CircuitOpen {
+                    dependency: self.name.clone(),
+                    retry_after_ms: 0,
+                });
+            }
+            state.probes_in_flight += 1;
+        }
+        Ok(Permit {
+            breaker: self,
+            probe,
+            recorded: false,
+        })
+    }
+
+    /// Run `call` through the breaker, recording its outcome
+    pub async fn call<T, F>(&self, call: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let permit = self.acquire()?;
+        let result = call.await;
+        permit.record(!result.as_ref().is_err_and(is_dependency_failure));
+        result
+    }
+
+    fn record(&self, probe: bool, success: bool) {
+        let settings = self.settings();
+        let now = self.clock.unix_millis();
+        let mut state = lock(&self.state);
+
+        if probe {
+            state.probes_in_flight = state.probes_in_flight.saturating_sub(1);
+        }
+        match state.state {
+            // A call admitted before the breaker opened
+            CircuitState::Open => {}
+            CircuitState::HalfOpen if !success => self.trip(&mut state, now, "trial call failed"),
+            CircuitState::HalfOpen => {
+                state.probe_successes += 1;
+                if state.probe_successes >= settings.half_open_probes {
+                    info!("🟢 Circuit for {} closed", self.name);
+                    state.state = CircuitState::Closed;
+                    state.clear();
+                }
+            }
+            CircuitState::Closed => {
+                state.outcomes.push_back(success);
+                if !success {
+                    state.failures += 1;
+                }
+                while state.outcomes.len() > settings.window.max(1) {
+                    if state.outcomes.pop_front() == Some(false) {
+                        state.failures -= 1;
+                    }
+                }
+                if state.outcomes.len() >= settings.min_calls && state.rate() >= settings.failure_rate {
+                    let calls = state.outcomes.len();
+                    let reason = format!("{:.0}% of the last {} calls failed", state.rate() * 100.0, calls);
+                    self.trip(&mut state, now, &reason);
+                }
+            }
+        }
+    }
+
+    fn trip(&self, state: &mut BreakerState, now: i64, reason: &str) {
+        warn!("⚠️  Circuit for {} opened: {}", self.name, reason);
+        state.state = CircuitState::Open;
+        state.opened_at_ms = now;
+        state.trips += 1;
+        state.clear();
+    }
+
+    /// Current position; an open breaker past `open_ms` reports half-open
+    pub fn state(&self) -> CircuitState {
+        self.health().state
+    }
+
+    pub fn health(&self) -> DependencyHealth {
+        let open_ms = self.settings().open_ms as i64;
+        let now = self.clock.unix_millis();
+        let state = lock(&self.state);
+
+        let (state_now, retry_after_ms) = match state.state {
+            CircuitState::Open if now < state.opened_at_ms + open_ms => {
+                (CircuitState::Open, Some((state.opened_at_ms + open_ms - now) as u64))
+            }
+            CircuitState::Open => (CircuitState::HalfOpen, None),
+            other => (other, None),
+        };
+        DependencyHealth {
+            name: self.name.clone(),
+            state: state_now,
+            failure_rate: state.rate(),
+            calls: state.outcomes.len(),
+            retry_after_ms,
+            trips: state.trips,
+        }
+    }
+
+    /// Close the breaker and forget its history (e.g. after a failover)
+    pub fn reset(&self) {
+        let mut state = lock(&self.state);
+        state.state = CircuitState::Closed;
+        state.clear();
+    }
+}
+
+/// One admitted call; dropping it unrecorded (cancelled call) frees its trial slot
+pub struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl Permit<'_> {
+    pub fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.breaker.record(self.probe, success);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded && self.probe {
+            let mut state = lock(&self.breaker.state);
+            state.probes_in_flight = state.probes_in_flight.saturating_sub(1);
+        }
+    }
+}
+
+/// Breakers by dependency name, sharing hot-reloadable settings
+#[derive(Clone)]
+pub struct CircuitBreakers {
+    settings: Arc<RwLock<CircuitBreakerSettings>>,
+    breakers: Arc<Mutex<BTreeMap<String, Arc<CircuitBreaker>>>>,
+    clock: SharedClock,
+}
+
+impl Default for CircuitBreakers {
+    fn default() -> Self {
+        Self::new(CircuitBreakerSettings::default())
+    }
+}
+
+impl CircuitBreakers {
+    pub fn new(settings: CircuitBreakerSettings) -> Self {
+        Self {
+            settings: Arc::new(RwLock::new(settings)),
+            breakers: Arc::new(Mutex::new(BTreeMap::new())),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.circuit_breakers.clone())
+    }
+
+    /// Clock for breakers created from now on
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Apply the hot-reloadable `[circuit_breakers]` section to every breaker
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.circuit_breakers.clone();
+        for breaker in lock(&self.breakers).values() {
+            breaker.apply_settings(&config.circuit_breakers);
+        }
+    }
+
+    /// Breaker for `name` (e.g. `pyth`, `rpc`, `jito:<block engine url>`), created on first use
+    pub fn breaker(&self, name: &str) -> Arc<CircuitBreaker> {
+        let mut breakers = lock(&self.breakers);
+        if let Some(breaker) = breakers.get(name) {
+            return Arc::clone(breaker);
+        }
+        let settings = self.settings.read().unwrap_or_else(|p| p.into_inner()).clone();
+        let breaker = Arc::new(CircuitBreaker::new(name, settings).with_clock(Arc::clone(&self.clock)));
+        breakers.insert(name.to_string(), Arc::clone(&breaker));
+        breaker
+    }
+
+    /// Every dependency's breaker state, by name
+    pub fn health(&self) -> Vec<DependencyHealth> {
+        lock(&self.breakers).values().map(|breaker| breaker.health()).collect()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::time::Duration;
+
+    fn settings() -> CircuitBreakerSettings {
+        CircuitBreakerSettings {
+            failure_rate: 0.5,
+            window: 10,
+            min_calls: 4,
+            open_ms: 1_000,
+            half_open_probes: 2,
+        }
+    }
+
+    async fn fail(breaker: &CircuitBreaker) -> Result<()> {
+        breaker
+            .call(async { Err::<(), _>(SentinelError::NetworkError("connection refused".to_string())) })
+            .await
+    }
+
+    async fn succeed(breaker: &CircuitBreaker) -> Result<()> {
+        breaker.call(async { Ok(()) }).await
+    }
+
+    #[tokio::test]
+    async fn test_opens_on_failure_rate_and_recovers_through_half_open() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let breaker = CircuitBreaker::new("pyth", settings()).with_clock(clock.clone());
+
+        // Below min_calls the rate is not trusted yet
+        for _ in 0..3 {
+            assert!(fail(&breaker).await.is_err());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(fail(&breaker).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // Fails fast, even for a call that would succeed
+        let result = succeed(&breaker).await;
+        assert!(matches!(
+            result,
+            Err(SentinelError::CircuitOpen { ref dependency, retry_after_ms: 1_000 }) if dependency == "pyth"
+        ));
+
+        // A failed trial re-opens it
+        clock.advance(Duration::from_millis(1_000));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(fail(&breaker).await.is_err());
+        assert_eq!(breaker.health().retry_after_ms, Some(1_000));
+
+        // Two successful trials close it; a cancelled trial frees its slot
+        clock.advance(Duration::from_millis(1_000));
+        drop(breaker.acquire().unwrap());
+        let first = breaker.acquire().unwrap();
+        let second = breaker.acquire().unwrap();
+        assert!(matches!(breaker.acquire(), Err(SentinelError::CircuitOpen { .. })));
+        first.record(true);
+        second.record(true);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let health = breaker.health();
+        assert_eq!((health.trips, health.calls), (2, 0));
+    }
+
+    #[tokio::test]
+    async fn test_answers_from_the_dependency_are_not_failures() {
+        let breaker = CircuitBreaker::new("jito", settings());
+        for _ in 0..10 {
+            let rejected = breaker
+                .call(async { Err::<(), _>(SentinelError::BundleError("bundle rejected".to_string())) })
+                .await;
+            assert!(matches!(rejected, Err(SentinelError::BundleError(_))));
+        }
+        assert_eq!(breaker.health().failure_rate, 0.0);
+
+        // Half failing over the window opens it
+        for _ in 0..5 {
+            let _ = fail(&breaker).await;
+            let _ = succeed(&breaker).await;
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+        breaker.reset();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_registry_shares_breakers_and_reports_health() {
+        let breakers = CircuitBreakers::default();
+        let rpc = breakers.breaker("rpc");
+        assert!(Arc::ptr_eq(&rpc, &breakers.breaker("rpc")));
+        breakers.breaker("pyth");
+
+        let mut config = SentinelConfig::default();
+        config.circuit_breakers.open_ms = 5_000;
+        breakers.apply_config(&config);
+        assert_eq!(rpc.settings().open_ms, 5_000);
+
+        let health = breakers.health();
+        let names: Vec<&str> = health.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, ["pyth", "rpc"]);
+        assert!(health.iter().all(|h| h.state == CircuitState::Closed));
+    }
+}
@@ -0,0 +1,112 @@
+//! Injectable Clock
+//!
+//! Time-dependent logic (intent expiry, adaptive time-of-day thresholds,
+//! schedulers) reads the time through [`Clock`] instead of calling
+//! `Utc::now()` directly, so tests and backtests can drive it deterministically.
+//! Components default to [`SystemClock`] and accept a [`ManualClock`] through
+//! their `with_clock` builders.
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Source of the current time
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Unix seconds
+    fn unix_timestamp(&self) -> i64 {
+        self.now().timestamp()
+    }
+
+    /// Unix milliseconds
+    fn unix_millis(&self) -> i64 {
+        self.now().timestamp_millis()
+    }
+}
+
+/// Clock shared between components
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time (the default everywhere)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Shared wall clock
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Clock that only moves when told to (tests, backtests, replays)
+#[derive(Debug)]
+pub struct ManualClock {
+    millis: AtomicI64,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            millis: AtomicI64::new(start.timestamp_millis()),
+        }
+    }
+
+    /// Start at a Unix timestamp (seconds)
+    pub fn at_timestamp(secs: i64) -> Self {
+        Self {
+            millis: AtomicI64::new(secs.saturating_mul(1_000)),
+        }
+    }
+
+    pub fn set(&self, time: DateTime<Utc>) {
+        self.millis.store(time.timestamp_millis(), Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let by = i64::try_from(by.as_millis()).unwrap_or(i64::MAX);
+        self.millis.fetch_add(by, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        let millis = self.millis.load(Ordering::SeqCst);
+        Utc.timestamp_millis_opt(millis).single().unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+
+    fn unix_millis(&self) -> i64 {
+        self.millis.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_moves_only_when_told() {
+        let clock = ManualClock::at_timestamp(1_700_000_000);
+        assert_eq!(clock.unix_timestamp(), 1_700_000_000);
+        assert_eq!(clock.unix_timestamp(), 1_700_000_000);
+
+        clock.advance(Duration::from_millis(1_500));
+        assert_eq!(clock.unix_millis(), 1_700_000_001_500);
+        assert_eq!(clock.unix_timestamp(), 1_700_000_001);
+
+        clock.set(Utc.with_ymd_and_hms(2025, 1, 4, 15, 0, 0).unwrap());
+        assert_eq!(clock.now().to_rfc3339(), "2025-01-04T15:00:00+00:00");
+    }
+
+    #[test]
+    fn test_system_clock_tracks_wall_time() {
+        let clock = system_clock();
+        assert!((clock.unix_timestamp() - Utc::now().timestamp()).abs() <= 1);
+    }
+}
@@ -0,0 +1,2236 @@
+//! Typed Runtime Configuration for Sentinel Router
+//!
+//! Layered loading: built-in defaults → TOML file → `SENTINEL_*` environment overrides.
+//! Nested keys use a double underscore, e.g. `SENTINEL_THRESHOLDS__HIGH_TIP=150000`.
+//!
+//! Tunable sections (thresholds, calendar, tip policy, protection tiers, endpoints, safety, circuit breakers, quotas, fusion, leader guard, slot risk, submission timing, policy, screening, slicing, netting, scheduler, SLOs, tenants, sponsors) can be hot-reloaded through
+//! [`ConfigHandle`]; network, model, oracle, congestion, validator, storage, coordination, redaction, telemetry and
+//! health settings are fixed for the process lifetime because changing them requires re-initializing the engine or
+//! its logs.
+//!
+//! `network` (`SENTINEL_NETWORK=devnet`) picks the defaults for endpoints and the tip cap
+//! before the file and environment are applied, so only deviations need spelling out.
+
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+use crate::config_history::ConfigHistory;
+use crate::intent::ProtectionTier;
+use crate::{Network, Result, SentinelError};
+
+/// Default environment variable prefix
+pub const ENV_PREFIX: &str = "SENTINEL";
+
+/// Stands in for secrets in [`SentinelConfig::redacted`]
+pub const REDACTED: &str = "[redacted]";
+
+// ================================================================================================
+// Sections
+// ================================================================================================
+
+/// Heuristic detection thresholds (hot-reloadable)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ThresholdSettings {
+    /// High tip threshold (lamports)
+    pub high_tip: u64,
+    /// Price impact threshold (basis points)
+    pub price_impact_bps: f32,
+    /// Validator risk threshold (0-1)
+    pub validator_risk: f32,
+    /// Triplet detection weight
+    pub triplet_weight: f32,
+    /// Liquidity utilization threshold
+    pub liquidity_util: f32,
+    /// TOML/JSON heuristic rules file (built-in rules when unset, see `HeuristicRules`)
+    pub heuristic_rules_path: Option<PathBuf>,
+}
+
+impl Default for ThresholdSettings {
+    fn default() -> Self {
+        Self {
+            high_tip: 100_000,
+            price_impact_bps: 200.0,
+            validator_risk: 0.6,
+            triplet_weight: 0.6,
+            liquidity_util: 0.05,
+            heuristic_rules_path: None,
+        }
+    }
+}
+
+/// Jito tip policy (hot-reloadable)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TipPolicy {
+    /// Minimum tip accepted by the bundle builder (lamports)
+    pub min_tip_lamports: u64,
+    /// Hard cap on any single tip (lamports)
+    pub max_tip_lamports: u64,
+    /// Default tip share of the fee budget when the intent doesn't specify one (0-100)
+    pub default_tip_allocation_pct: u8,
+    /// Risk score at which the intent's `tip_allocation_pct` is used unchanged (0-1)
+    pub neutral_risk: f32,
+    /// Most the tip share moves (percentage points) at risk 0 or 1; 0 disables adaptation
+    pub max_risk_shift_pct: u8,
+    /// Cap on the cost (tip plus signature fee) of a tip-only protection transaction (lamports)
+    pub tip_only_budget_lamports: u64,
+}
+
+impl Default for TipPolicy {
+    fn default() -> Self {
+        Self {
+            min_tip_lamports: 1_000,
+            max_tip_lamports: 10_000_000,
+            default_tip_allocation_pct: 70,
+            neutral_risk: 0.5,
+            max_risk_shift_pct: 30,
+            tip_only_budget_lamports: 20_000,
+        }
+    }
+}
+
+impl TipPolicy {
+    /// Defaults for `network`; test clusters cap tips so faucet SOL lasts
+    pub fn for_network(network: Network) -> Self {
+        match network {
+            Network::Mainnet => Self::default(),
+            _ => Self {
+                max_tip_lamports: 100_000,
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// What a preflight must pass before a route is recommended
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationStrictness {
+    /// The simulation succeeds
+    #[default]
+    Basic,
+    /// ...and neither mint screens as high risk
+    Screened,
+    /// ...and no stage was degraded and the user's slippage covers the recommendation
+    Strict,
+}
+
+/// Concrete settings behind one `ProtectionTier`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProtectionTierProfile {
+    /// Recent-tip percentile recommended at low, medium and high risk (1-99)
+    pub tip_percentiles: [u8; 3],
+    /// Added to the combined routing risk before the route bands (-1 to 1; positive prefers bundles)
+    pub route_bias: f32,
+    pub simulation: SimulationStrictness,
+}
+
+impl Default for ProtectionTierProfile {
+    fn default() -> Self {
+        Self {
+            tip_percentiles: [50, 75, 95],
+            route_bias: 0.0,
+            simulation: SimulationStrictness::Basic,
+        }
+    }
+}
+
+/// Protection tier → profile mapping (hot-reloadable); `standard` matches untiered behavior
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProtectionTierSettings {
+    pub economy: ProtectionTierProfile,
+    pub standard: ProtectionTierProfile,
+    pub max: ProtectionTierProfile,
+}
+
+impl Default for ProtectionTierSettings {
+    fn default() -> Self {
+        Self {
+            economy: ProtectionTierProfile {
+                tip_percentiles: [50, 50, 75],
+                route_bias: -0.1,
+                simulation: SimulationStrictness::Basic,
+            },
+            standard: ProtectionTierProfile::default(),
+            max: ProtectionTierProfile {
+                tip_percentiles: [75, 95, 99],
+                route_bias: 0.2,
+                simulation: SimulationStrictness::Strict,
+            },
+        }
+    }
+}
+
+impl ProtectionTierSettings {
+    pub fn profile(&self, tier: ProtectionTier) -> &ProtectionTierProfile {
+        match tier {
+            ProtectionTier::Economy => &self.economy,
+            ProtectionTier::Standard => &self.standard,
+            ProtectionTier::Max => &self.max,
+        }
+    }
+}
+
+/// External service endpoints (hot-reloadable)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EndpointConfig {
+    pub rpc_url: String,
+    pub jito_block_engine_url: String,
+    pub pyth_hermes_url: String,
+    pub jupiter_quote_url: String,
+    /// Solana websocket endpoint supporting `blockSubscribe` (landed-block listener disabled when unset)
+    pub block_subscribe_url: Option<String>,
+    /// Solana websocket endpoint for `slotSubscribe` (chain clock polls RPC when unset)
+    pub ws_url: Option<String>,
+    /// Jito network-wide landed-tip percentiles
+    pub jito_tip_floor_url: String,
+}
+
+impl Default for EndpointConfig {
+    fn default() -> Self {
+        Self::for_network(Network::Mainnet)
+    }
+}
+
+impl EndpointConfig {
+    /// Public endpoints of `network` (Pyth and Jupiter are cluster-independent)
+    pub fn for_network(network: Network) -> Self {
+        Self {
+            rpc_url: network.rpc_url().to_string(),
+            jito_block_engine_url: network.jito_block_engine_url().to_string(),
+            pyth_hermes_url: "https://hermes.pyth.network".to_string(),
+            jupiter_quote_url: "https://quote-api.jup.ag/v6".to_string(),
+            block_subscribe_url: None,
+            ws_url: None,
+            jito_tip_floor_url: network.jito_tip_floor_url(),
+        }
+    }
+}
+
+/// Inference backend for the MEV model
+///
+/// The ONNX backends are optional features of `ai-engine`, off in a default
+/// build. Selecting one that was not compiled in does not fail validation: the
+/// model fails to load at startup and the engine scores with its fallback
+/// heuristics (logged as "Model backend unavailable"). Default builds should
+/// use `gbdt`, or enable the `onnx` / `tensorrt` feature.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelBackendKind {
+    /// ONNX Runtime on CPU (`onnx` feature)
+    #[default]
+    Onnx,
+    /// Pure-Rust gradient-boosted trees from an XGBoost JSON dump
+    Gbdt,
+    /// ONNX Runtime with TensorRT/CUDA execution providers (`tensorrt` feature)
+    TensorRt,
+}
+
+/// Model loading settings (static)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ModelSettings {
+    pub backend: ModelBackendKind,
+    pub model_path: PathBuf,
+    pub intra_op_threads: usize,
+    pub inter_op_threads: usize,
+    pub warmup_iterations: usize,
+    /// Engine runtime state (drift history, adaptive thresholds, tip
+    /// percentiles) saved on shutdown and restored on boot
+    pub snapshot_path: Option<PathBuf>,
+    /// What the model sees for features the extractor could not observe
+    pub imputation: ImputationSettings,
+}
+
+impl Default for ModelSettings {
+    fn default() -> Self {
+        Self {
+            backend: ModelBackendKind::default(),
+            model_path: PathBuf::from("models/mev_detector.onnx"),
+            intra_op_threads: 4,
+            inter_op_threads: 1,
+            warmup_iterations: 100,
+            snapshot_path: None,
+            imputation: ImputationSettings::default(),
+        }
+    }
+}
+
+/// Model input for a missing feature
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Imputation {
+    /// 0.0 (the training mean for standardized models)
+    #[default]
+    Zero,
+    /// NaN, for backends that route missing values themselves (GBDT `missing` branches)
+    Native,
+    /// Fixed model input value, e.g. `{ constant = -1.0 }`
+    Constant(f32),
+}
+
+/// Per-feature imputation, keyed by `FeatureVector::FEATURE_NAMES` column (static)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ImputationSettings {
+    /// Strategy for features without an override
+    pub default: Imputation,
+    pub features: BTreeMap<String, Imputation>,
+}
+
+/// Validator intel sources (static)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ValidatorListConfig {
+    /// Optional JSON file with additional validator intel entries
+    pub intel_path: Option<PathBuf>,
+    /// Extra validator identities to treat as malicious (base58)
+    pub extra_malicious: Vec<String>,
+    /// Validator identities never treated as malicious, whatever the intel says (base58)
+    pub exempt: Vec<String>,
+    /// On-chain account publishing the malicious-validator set (base58)
+    pub registry_account: Option<String>,
+    /// Layout of the registry account's data
+    pub registry_schema: RegistrySchema,
+    /// Program that must own the registry account (base58)
+    pub registry_program: Option<String>,
+    /// Authority the registry must name, for schemas that carry one (base58)
+    pub registry_authority: Option<String>,
+}
+
+/// Data layout of an on-chain validator registry account
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrySchema {
+    /// Binary layout: magic, version, authority, update slot, then fixed-size entries
+    #[default]
+    SentinelV1,
+    /// UTF-8 JSON array of `{ "identity", "risk", "malicious" }` objects
+    Json,
+}
+
+/// Operational safety switches (hot-reloadable, see `SafetyController`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SafetySettings {
+    /// Force every intent to StandardRpc with maximum protection
+    pub kill_switch: bool,
+    /// Bypass the ONNX model and score with heuristics only
+    pub heuristics_only: bool,
+    /// Run the full pipeline but never submit (dry run)
+    pub no_submit: bool,
+    /// Inference error rate (0-1) that trips heuristics-only mode
+    pub inference_error_rate: f64,
+    /// Recent inference calls the error rate is measured over
+    pub error_window: usize,
+    /// Seconds heuristics-only mode stays tripped before the model is retried
+    pub heuristics_cooldown_secs: u64,
+}
+
+impl Default for SafetySettings {
+    fn default() -> Self {
+        Self {
+            kill_switch: false,
+            heuristics_only: false,
+            no_submit: false,
+            inference_error_rate: 0.2,
+            error_window: 100,
+            heuristics_cooldown_secs: 300,
+        }
+    }
+}
+
+/// Failure-rate circuit breakers around external dependencies (hot-reloadable, see `CircuitBreakers`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CircuitBreakerSettings {
+    /// Failure rate (0-1) over the window that opens a breaker
+    pub failure_rate: f64,
+    /// Recent calls the failure rate is measured over
+    pub window: usize,
+    /// Calls needed in the window before the rate is trusted
+    pub min_calls: usize,
+    /// Milliseconds an open breaker rejects calls before probing the dependency again
+    pub open_ms: u64,
+    /// Successful trial calls (half-open) that close the breaker again
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            failure_rate: 0.5,
+            window: 20,
+            min_calls: 10,
+            open_ms: 30_000,
+            half_open_probes: 3,
+        }
+    }
+}
+
+/// Per-user intent quotas (hot-reloadable, see `QuotaManager`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct QuotaSettings {
+    /// Sustained intents per minute per wallet (0 disables the limit)
+    pub intents_per_minute: u32,
+    /// Extra intents a wallet may burst above the sustained rate
+    pub intent_burst: u32,
+    /// Sustained notional value per hour per wallet in USD (0 disables the limit)
+    pub notional_usd_per_hour: f64,
+    /// Extra notional (USD) a wallet may burst above the hourly rate
+    pub notional_burst_usd: f64,
+    /// Wallets tracked before idle entries are evicted
+    pub max_tracked_users: usize,
+}
+
+impl Default for QuotaSettings {
+    fn default() -> Self {
+        Self {
+            intents_per_minute: 60,
+            intent_burst: 20,
+            notional_usd_per_hour: 1_000_000.0,
+            notional_burst_usd: 250_000.0,
+            max_tracked_users: 100_000,
+        }
+    }
+}
+
+/// Logistic meta-model over component scores (see `ScoreFusion`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MetaModelSettings {
+    pub bias: f32,
+    pub heuristic: f32,
+    pub model: f32,
+    pub adaptive: f32,
+    /// Coefficient on the adaptive pipeline's confidence
+    pub confidence: f32,
+}
+
+impl Default for MetaModelSettings {
+    fn default() -> Self {
+        Self {
+            bias: -4.0,
+            heuristic: 3.0,
+            model: 4.0,
+            adaptive: 2.0,
+            confidence: 0.5,
+        }
+    }
+}
+
+/// Heuristic / model / adaptive score fusion (hot-reloadable, see `ScoreFusion`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FusionSettings {
+    /// Weight of the static heuristic score
+    pub heuristic_weight: f32,
+    /// Weight of the model backend score (ignored when no model is loaded)
+    pub model_weight: f32,
+    /// Weight of the adaptive pipeline score, scaled by its confidence
+    pub adaptive_weight: f32,
+    /// Use a logistic meta-model instead of the weighted average
+    pub meta_model: Option<MetaModelSettings>,
+    /// Recent decisions tracked for component dominance stats
+    pub window: usize,
+}
+
+impl Default for FusionSettings {
+    fn default() -> Self {
+        Self {
+            heuristic_weight: 0.3,
+            model_weight: 0.5,
+            adaptive_weight: 0.2,
+            meta_model: None,
+            window: 1_000,
+        }
+    }
+}
+
+/// Response to a malicious upcoming leader
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderGuardAction {
+    /// Hold the submission until a clean leader window
+    #[default]
+    Delay,
+    /// Submit now, but only as an atomic Jito bundle
+    ForceJitoBundle,
+}
+
+/// Submission-time malicious leader enforcement (hot-reloadable, see `LeaderGuard`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LeaderGuardSettings {
+    pub enabled: bool,
+    /// Leaders with risk above this bound (0-1) are avoided
+    pub max_leader_risk: f32,
+    /// Slots the submission targets (leader window checked)
+    pub window_slots: u64,
+    pub action: LeaderGuardAction,
+    /// Slots to push the submission back per delay step
+    pub delay_slots: u64,
+    /// Longest total delay before falling back to a forced JitoBundle
+    pub max_delay_slots: u64,
+}
+
+impl Default for LeaderGuardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_leader_risk: 0.7,
+            window_slots: 4,
+            action: LeaderGuardAction::Delay,
+            delay_slots: 4,
+            max_delay_slots: 16,
+        }
+    }
+}
+
+/// Trading session with its own activity multiplier (local time, trading days only)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarketSession {
+    pub name: String,
+    pub open: NaiveTime,
+    /// Exclusive; a close before `open` spans midnight
+    pub close: NaiveTime,
+    pub multiplier: f32,
+}
+
+/// Market calendar behind the time-of-day risk adjustment (hot-reloadable, see `MarketCalendar`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CalendarSettings {
+    /// IANA time zone sessions, weekends and holidays are expressed in (DST applies)
+    pub timezone: String,
+    pub sessions: Vec<MarketSession>,
+    pub weekend_days: Vec<Weekday>,
+    /// Multiplier on weekend days and holidays
+    pub weekend_multiplier: f32,
+    /// Local dates the markets are closed; treated like weekend days
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl Default for CalendarSettings {
+    fn default() -> Self {
+        Self {
+            timezone: "America/New_York".to_string(),
+            sessions: vec![MarketSession {
+                name: "us_equities".to_string(),
+                open: NaiveTime::from_hms_opt(9, 0, 0).expect("Valid session open"),
+                close: NaiveTime::from_hms_opt(16, 0, 0).expect("Valid session close"),
+                multiplier: 1.1,
+            }],
+            weekend_days: vec![Weekday::Sat, Weekday::Sun],
+            weekend_multiplier: 1.3,
+            holidays: Vec::new(),
+        }
+    }
+}
+
+/// RPC-sourced network congestion feeding the adaptive thresholds (see `CongestionMonitor`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CongestionSettings {
+    /// `getRecentPerformanceSamples` polling interval (samples cover 60s each)
+    pub poll_interval_ms: u64,
+    /// Samples requested per poll (1-720); the trend compares the newer half to the older
+    pub sample_count: usize,
+    /// Transactions per second treated as full utilization
+    pub capacity_tps: f64,
+    /// Also fetch the latest block and measure its compute-unit fullness (large response)
+    pub block_fullness: bool,
+    /// Block compute-unit limit fullness is measured against
+    pub max_block_compute_units: u64,
+}
+
+impl Default for CongestionSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 30_000,
+            sample_count: 6,
+            capacity_tps: 5_000.0,
+            block_fullness: false,
+            max_block_compute_units: 48_000_000,
+        }
+    }
+}
+
+/// Look-ahead leader window risk used for route selection (hot-reloadable, see `SlotRiskForecaster`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SlotRiskSettings {
+    /// Upcoming leaders (rotations) included in the window
+    pub lookahead_leaders: u64,
+    /// Consecutive slots per leader rotation
+    pub slots_per_leader: u64,
+    /// Average slot time (ms), converts confirmation latency into slots
+    pub slot_time_ms: u64,
+    /// Combined risk (0-1) at or above which JitoSingle is used
+    pub jito_single_threshold: f32,
+    /// Combined risk (0-1) at or above which JitoBundle is used
+    pub jito_bundle_threshold: f32,
+    /// Scores reported with less confidence than this (0-1) are pulled toward the
+    /// JitoSingle band before routing, in proportion to the missing confidence
+    pub min_route_confidence: f32,
+}
+
+impl Default for SlotRiskSettings {
+    fn default() -> Self {
+        Self {
+            lookahead_leaders: 4,
+            slots_per_leader: 4,
+            slot_time_ms: 400,
+            jito_single_threshold: 0.3,
+            jito_bundle_threshold: 0.6,
+            min_route_confidence: 0.7,
+        }
+    }
+}
+
+/// Slot-boundary submission timing (hot-reloadable, see `SubmissionTimer`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SubmissionTimingSettings {
+    pub enabled: bool,
+    /// Longest a submission may be held past its planned time (ms)
+    pub max_delay_ms: u64,
+    /// Furthest a planned submission may be brought forward (ms)
+    pub max_advance_ms: u64,
+    /// Send-to-leader latency through the block engine (ms)
+    pub send_lead_ms: u64,
+    /// Average slot time (ms)
+    pub slot_time_ms: u64,
+    /// How far inside the target slot to aim; landings this close to a slot's
+    /// end also count the next slot's leader
+    pub boundary_margin_ms: u64,
+    /// Leaders at or above this risk (0-1) are timed around
+    pub max_leader_risk: f32,
+}
+
+impl Default for SubmissionTimingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_delay_ms: 800,
+            max_advance_ms: 400,
+            send_lead_ms: 150,
+            slot_time_ms: 400,
+            boundary_margin_ms: 50,
+            max_leader_risk: 0.5,
+        }
+    }
+}
+
+/// Execution scheduler ordering and concurrency (hot-reloadable, see `ExecutionScheduler`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SchedulerSettings {
+    /// In-flight executions per priority level (0 = unlimited)
+    pub max_in_flight_low: usize,
+    pub max_in_flight_medium: usize,
+    pub max_in_flight_high: usize,
+    pub max_in_flight_critical: usize,
+    /// Seconds waited per one-level priority boost (starvation protection, 0 disables)
+    pub aging_secs: u64,
+    /// Intents this close to their deadline (seconds) run first within their level
+    pub urgent_within_secs: i64,
+    /// Pending intents before `ExecutionScheduler::admit` makes callers wait (0 = unbounded)
+    pub max_pending: usize,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            max_in_flight_low: 8,
+            max_in_flight_medium: 16,
+            max_in_flight_high: 32,
+            max_in_flight_critical: 0,
+            aging_secs: 10,
+            urgent_within_secs: 15,
+            max_pending: 10_000,
+        }
+    }
+}
+
+/// Per-route submission SLOs and burn-rate alerting (hot-reloadable, see `SloTracker`)
+///
+/// Each objective has an error budget: `max_failure_rate` of submissions may fail
+/// or expire, and 1% of landings may exceed `latency_p99_ms`. A route alerts when
+/// both windows spend that budget `burn_rate_threshold` times faster than sustainable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SloSettings {
+    /// Submit-to-land latency 99% of landings should beat (ms)
+    pub latency_p99_ms: u64,
+    /// Share of submissions allowed to fail or expire (0-1)
+    pub max_failure_rate: f64,
+    /// Fast window; catches sharp degradation (seconds)
+    pub short_window_secs: u64,
+    /// Slow window; filters out blips (seconds)
+    pub long_window_secs: u64,
+    /// Budget burn multiple both windows must reach to alert
+    pub burn_rate_threshold: f64,
+    /// Outcomes the short window needs before it can alert
+    pub min_samples: usize,
+    /// Seconds before a route re-alerts on the same objective
+    pub alert_cooldown_secs: u64,
+}
+
+impl Default for SloSettings {
+    fn default() -> Self {
+        Self {
+            latency_p99_ms: 5_000,
+            max_failure_rate: 0.1,
+            short_window_secs: 300,
+            long_window_secs: 3_600,
+            burn_rate_threshold: 2.0,
+            min_samples: 20,
+            alert_cooldown_secs: 600,
+        }
+    }
+}
+
+/// Impact-aware splitting of large swaps (hot-reloadable, see `OrderSlicer`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SlicingSettings {
+    pub enabled: bool,
+    /// `liquidity_utilization` above which a swap is sliced
+    pub utilization_threshold: f32,
+    /// Utilization each slice should stay under
+    pub target_slice_utilization: f32,
+    pub max_slices: u16,
+    /// Slots between consecutive slices (lets arbitrage restore the pool)
+    pub slots_between_slices: u64,
+}
+
+impl Default for SlicingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            utilization_threshold: 0.05,
+            target_slice_utilization: 0.01,
+            max_slices: 8,
+            slots_between_slices: 4,
+        }
+    }
+}
+
+/// Per-deployment mint and program allow/deny lists (hot-reloadable, see `IntentPolicy`)
+///
+/// Deny lists take precedence over allow lists; an empty allow list admits anything not denied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PolicySettings {
+    /// Mints intents may trade (empty = any)
+    pub allowed_mints: Vec<String>,
+    /// Mints intents may never trade
+    pub denied_mints: Vec<String>,
+    /// Programs intents may be routed to (empty = any)
+    pub allowed_programs: Vec<String>,
+    /// Programs intents may never be routed to
+    pub denied_programs: Vec<String>,
+    /// Mints younger than this (seconds) need `min_new_mint_liquidity_usd` (0 disables the rule)
+    pub new_mint_age_secs: u64,
+    /// Liquidity floor (USD) for mints younger than `new_mint_age_secs`
+    pub min_new_mint_liquidity_usd: f64,
+}
+
+/// What address screening does with a hit
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreeningMode {
+    /// Log and report hits, execute anyway
+    #[default]
+    Monitor,
+    /// Reject intents with a hit
+    Block,
+}
+
+/// Sanctioned-counterparty screening of users and output destinations (hot-reloadable, see `AddressScreener`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScreeningSettings {
+    pub enabled: bool,
+    pub mode: ScreeningMode,
+    /// Denied addresses
+    pub deny_list: Vec<String>,
+    /// File of further denied addresses, one per line (`#` comments)
+    pub deny_list_path: Option<PathBuf>,
+    /// Screening service called with each intent's addresses
+    pub webhook_url: Option<String>,
+    pub webhook_timeout_ms: u64,
+    /// In block mode, reject intents a provider failed to screen
+    pub fail_closed: bool,
+}
+
+impl Default for ScreeningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ScreeningMode::default(),
+            deny_list: Vec::new(),
+            deny_list_path: None,
+            webhook_url: None,
+            webhook_timeout_ms: 2_000,
+            fail_closed: false,
+        }
+    }
+}
+
+/// Internal crossing of opposing opted-in intents (hot-reloadable, see `NettingEngine`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NettingSettings {
+    pub enabled: bool,
+    /// How long an intent waits for an opposing one before routing normally
+    pub window_ms: u64,
+    /// Intents waiting per pair; arrivals beyond it route normally
+    pub max_resting_per_pair: usize,
+    /// Smallest crossed share of either side's input worth a netting leg (0-1)
+    pub min_cross_fraction: f64,
+}
+
+impl Default for NettingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_ms: 2_000,
+            max_resting_per_pair: 64,
+            min_cross_fraction: 0.1,
+        }
+    }
+}
+
+/// One frontend sharing the deployment (hot-reloadable, see `TenantRegistry`)
+///
+/// Override sections replace the global section wholesale for this tenant.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TenantConfig {
+    /// Tenant identifier; also the partition directory name for its logs
+    pub id: String,
+    /// SHA-256 (hex) of each API key that authenticates as this tenant
+    pub api_key_sha256: Vec<String>,
+    /// Fee policy override
+    pub tip_policy: Option<TipPolicy>,
+    /// Quota override
+    pub quotas: Option<QuotaSettings>,
+    /// Mints this tenant's intents may trade (empty = any)
+    pub allowed_mints: Vec<String>,
+    /// Sponsor (`[[sponsors]]` id) paying this tenant's fees (none = users pay)
+    pub sponsor: Option<String>,
+}
+
+/// Who pays a transaction's fees and Jito tip
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FeePayerMode {
+    /// The user pays everything
+    #[default]
+    User,
+    /// The sponsor pays base fee, priority fee and tip
+    Sponsor,
+    /// The sponsor pays the tip, the user the base and priority fee
+    Hybrid,
+}
+
+/// An integrator paying fees for its tenants' users (hot-reloadable, see `SponsorLedger`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SponsorConfig {
+    /// Sponsor identifier, referenced by `tenants.sponsor`
+    pub id: String,
+    /// Sponsor fee payer and tip account (pubkey)
+    pub payer: String,
+    pub mode: FeePayerMode,
+    /// Most the sponsor pays for one intent (lamports, 0 = no cap)
+    pub max_lamports_per_intent: u64,
+    /// Most the sponsor pays per UTC day (lamports, 0 = no cap)
+    pub daily_cap_lamports: u64,
+}
+
+/// Persistence backend for shadow predictions, decision logs and intent data
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackendKind {
+    /// JSONL files under `path` (append logs only)
+    #[default]
+    File,
+    /// In-process maps; lost on restart (tests, dry runs)
+    Memory,
+    /// Embedded database under `path` (`sled` feature)
+    Sled,
+    /// Shared Postgres database (`postgres` feature)
+    Postgres,
+    /// S3 or a compatible object store (`s3` feature)
+    S3,
+}
+
+/// Storage backend selection (static, see `Storage::from_config`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StorageSettings {
+    pub backend: StorageBackendKind,
+    /// Root directory for the file backend; the sled database lives in `<path>/sled`
+    pub path: PathBuf,
+    /// libpq-style connection string, e.g. `host=localhost user=sentinel dbname=sentinel`
+    pub postgres_url: Option<String>,
+    pub s3_bucket: Option<String>,
+    /// Key prefix every object is written under
+    pub s3_prefix: String,
+    pub s3_region: Option<String>,
+    /// Custom endpoint for S3-compatible stores (MinIO, R2)
+    pub s3_endpoint: Option<String>,
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackendKind::default(),
+            path: PathBuf::from("logs"),
+            postgres_url: None,
+            s3_bucket: None,
+            s3_prefix: "sentinel".to_string(),
+            s3_region: None,
+            s3_endpoint: None,
+        }
+    }
+}
+
+/// Where state shared between router instances lives
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinationBackendKind {
+    /// In-process; only safe with a single router instance
+    #[default]
+    Local,
+    /// Shared Postgres database (`postgres` feature)
+    Postgres,
+}
+
+/// Multi-instance coordination (static, see `Coordination::from_config`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CoordinationSettings {
+    pub backend: CoordinationBackendKind,
+    /// Unique per router instance; a random id is generated when unset
+    pub instance_id: Option<String>,
+    /// Deployment region, prefixed to the instance id in claims
+    pub region: Option<String>,
+    /// libpq-style connection string; falls back to `storage.postgres_url`
+    pub postgres_url: Option<String>,
+    /// Intent ownership lease; a crashed instance's intents free up after this
+    pub claim_ttl_ms: u64,
+    /// How long submitted intents stay in the replay ledger
+    pub replay_ttl_secs: u64,
+}
+
+impl Default for CoordinationSettings {
+    fn default() -> Self {
+        Self {
+            backend: CoordinationBackendKind::default(),
+            instance_id: None,
+            region: None,
+            postgres_url: None,
+            claim_ttl_ms: 30_000,
+            replay_ttl_secs: 86_400,
+        }
+    }
+}
+
+/// How aggressively persisted logs are scrubbed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceMode {
+    /// Signatures truncated only when `signature_prefix` is set
+    #[default]
+    Standard,
+    /// Public keys dropped and signatures truncated (8 characters unless set)
+    GdprStrict,
+}
+
+/// Secret/PII scrubbing applied before decision and shadow records are persisted (static, see `Scrubber`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RedactionSettings {
+    pub compliance_mode: ComplianceMode,
+    /// Characters of each signature to keep
+    pub signature_prefix: Option<usize>,
+    /// Persist feature vectors with shadow predictions
+    pub log_features: bool,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            compliance_mode: ComplianceMode::default(),
+            signature_prefix: None,
+            log_features: true,
+        }
+    }
+}
+
+/// OpenTelemetry trace export (static, see `telemetry::init`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    /// OTLP/gRPC collector (Tempo, Jaeger), e.g. `http://tempo:4317`; spans stay local when unset
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// Fraction of root traces sampled; remote parents keep their own decision
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "sentinel-router".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+/// Pyth price gating and fallbacks (static)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OracleSettings {
+    /// Largest `conf / price` a Pyth price may carry and still be used
+    pub max_confidence_ratio: f64,
+    /// Slots a Pyth price may lag the transaction's slot
+    pub max_staleness_slots: u64,
+    /// Window of the DEX TWAP used when neither oracle is usable
+    pub twap_window_secs: u64,
+}
+
+impl Default for OracleSettings {
+    fn default() -> Self {
+        Self {
+            max_confidence_ratio: 0.02,
+            max_staleness_slots: 25,
+            twap_window_secs: 300,
+        }
+    }
+}
+
+/// Readiness probe bounds (static, see `health`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HealthSettings {
+    /// Oldest oracle price that still counts as ready
+    pub oracle_max_age_secs: u64,
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self { oracle_max_age_secs: 60 }
+    }
+}
+
+// ================================================================================================
+// Root Config
+// ================================================================================================
+
+/// Root configuration for all Sentinel components
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SentinelConfig {
+    /// Cluster; seeds endpoint and tip-cap defaults
+    pub network: Network,
+    pub thresholds: ThresholdSettings,
+    pub calendar: CalendarSettings,
+    pub congestion: CongestionSettings,
+    pub tip_policy: TipPolicy,
+    pub protection_tiers: ProtectionTierSettings,
+    pub endpoints: EndpointConfig,
+    pub model: ModelSettings,
+    pub oracle: OracleSettings,
+    pub validators: ValidatorListConfig,
+    pub safety: SafetySettings,
+    pub circuit_breakers: CircuitBreakerSettings,
+    pub quotas: QuotaSettings,
+    pub fusion: FusionSettings,
+    pub leader_guard: LeaderGuardSettings,
+    pub slot_risk: SlotRiskSettings,
+    pub submission_timing: SubmissionTimingSettings,
+    pub policy: PolicySettings,
+    pub screening: ScreeningSettings,
+    pub slicing: SlicingSettings,
+    pub netting: NettingSettings,
+    pub scheduler: SchedulerSettings,
+    pub slo: SloSettings,
+    pub storage: StorageSettings,
+    pub coordination: CoordinationSettings,
+    pub redaction: RedactionSettings,
+    pub telemetry: TelemetrySettings,
+    pub health: HealthSettings,
+    pub tenants: Vec<TenantConfig>,
+    pub sponsors: Vec<SponsorConfig>,
+}
+
+impl SentinelConfig {
+    /// Load from an optional TOML file with `SENTINEL_*` environment overrides
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        Self::load_with_prefix(path, ENV_PREFIX)
+    }
+
+    /// Load with a custom environment prefix
+    pub fn load_with_prefix(path: Option<&Path>, env_prefix: &str) -> Result<Self> {
+        let mut builder = config::Config::builder();
+
+        if let Some(path) = path {
+            builder = builder.add_source(
+                config::File::from(path)
+                    .format(config::FileFormat::Toml)
+                    .required(true),
+            );
+        }
+
+        builder = builder.add_source(
+            config::Environment::with_prefix(env_prefix)
+                .prefix_separator("_")
+                .separator("__")
+                .try_parsing(true),
+        );
+
+        let config: Self = with_network_defaults(builder)?
+            .build()
+            .and_then(|c| c.try_deserialize())
+            .map_err(|e| SentinelError::ConfigError(e.to_string()))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse from a TOML string (no environment overrides)
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        let builder = config::Config::builder().add_source(config::File::from_str(toml, config::FileFormat::Toml));
+        let config: Self = with_network_defaults(builder)?
+            .build()
+            .and_then(|c| c.try_deserialize())
+            .map_err(|e| SentinelError::ConfigError(e.to_string()))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Content hash of the effective config (16 hex chars): equal for equal
+    /// configs across processes, so decisions can be tied to the config they
+    /// were made under
+    pub fn fingerprint(&self) -> String {
+        let serialized = serde_json::to_vec(self).expect("SentinelConfig serialization failed");
+        blake3::hash(&serialized).to_hex()[..16].to_string()
+    }
+
+    /// Copy safe to hand out: endpoint URLs (which carry provider API keys),
+    /// database and webhook URLs and tenant key hashes are replaced by
+    /// [`REDACTED`]. Its fingerprint no longer matches the original's.
+    pub fn redacted(&self) -> SentinelConfig {
+        fn redact(value: &mut String) {
+            if !value.is_empty() {
+                *value = REDACTED.to_string();
+            }
+        }
+        fn redact_opt(value: &mut Option<String>) {
+            if let Some(value) = value {
+                redact(value);
+            }
+        }
+
+        let mut config = self.clone();
+        let endpoints = &mut config.endpoints;
+        for url in [
+            &mut endpoints.rpc_url,
+            &mut endpoints.jito_block_engine_url,
+            &mut endpoints.pyth_hermes_url,
+            &mut endpoints.jupiter_quote_url,
+            &mut endpoints.jito_tip_floor_url,
+        ] {
+            redact(url);
+        }
+        redact_opt(&mut endpoints.block_subscribe_url);
+        redact_opt(&mut endpoints.ws_url);
+        redact_opt(&mut config.screening.webhook_url);
+        redact_opt(&mut config.storage.postgres_url);
+        redact_opt(&mut config.storage.s3_endpoint);
+        redact_opt(&mut config.coordination.postgres_url);
+        redact_opt(&mut config.telemetry.otlp_endpoint);
+        for tenant in &mut config.tenants {
+            tenant.api_key_sha256.iter_mut().for_each(redact);
+        }
+        config
+    }
+
+    /// Sanity-check values that would otherwise fail deep inside the pipeline
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.thresholds.validator_risk) {
+            return Err(SentinelError::ConfigError(
+                "thresholds.validator_risk must be within 0-1".to_string(),
+            ));
+        }
+
+        let calendar = &self.calendar;
+        if calendar.timezone.parse::<chrono_tz::Tz>().is_err() {
+            return Err(SentinelError::ConfigError(format!(
+                "calendar.timezone {} is not an IANA time zone",
+                calendar.timezone
+            )));
+        }
+        let multipliers = calendar.sessions.iter().map(|s| s.multiplier);
+        if std::iter::once(calendar.weekend_multiplier).chain(multipliers).any(|m| m <= 0.0) {
+            return Err(SentinelError::ConfigError(
+                "calendar multipliers must be positive".to_string(),
+            ));
+        }
+
+        let tiers = &self.protection_tiers;
+        for profile in [&tiers.economy, &tiers.standard, &tiers.max] {
+            if profile.tip_percentiles.iter().any(|p| !(1..=99).contains(p))
+                || !(-1.0..=1.0).contains(&profile.route_bias)
+            {
+                return Err(SentinelError::ConfigError(
+                    "protection_tiers: tip percentiles must be within 1-99 and route_bias within -1 to 1".to_string(),
+                ));
+            }
+        }
+
+        let congestion = &self.congestion;
+        if !(1..=720).contains(&congestion.sample_count)
+            || congestion.capacity_tps.is_nan()
+            || congestion.capacity_tps <= 0.0
+            || congestion.max_block_compute_units == 0
+        {
+            return Err(SentinelError::ConfigError(
+                "congestion.sample_count must be within 1-720 and capacity limits positive".to_string(),
+            ));
+        }
+
+        if self.tip_policy.default_tip_allocation_pct > 100 {
+            return Err(SentinelError::ConfigError(
+                "tip_policy.default_tip_allocation_pct must be <= 100".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.tip_policy.neutral_risk) || self.tip_policy.max_risk_shift_pct > 100 {
+            return Err(SentinelError::ConfigError(
+                "tip_policy.neutral_risk must be within 0-1 and max_risk_shift_pct <= 100".to_string(),
+            ));
+        }
+
+        // Room for the tip-only transaction's 5000-lamport signature fee plus a minimum tip
+        if self.tip_policy.tip_only_budget_lamports < self.tip_policy.min_tip_lamports.saturating_add(5_000) {
+            return Err(SentinelError::ConfigError(
+                "tip_policy.tip_only_budget_lamports must cover min_tip_lamports plus the 5000 lamport signature fee"
+                    .to_string(),
+            ));
+        }
+
+        if self.tip_policy.min_tip_lamports > self.tip_policy.max_tip_lamports {
+            return Err(SentinelError::ConfigError(
+                "tip_policy.min_tip_lamports exceeds max_tip_lamports".to_string(),
+            ));
+        }
+
+        let imputation = &self.model.imputation;
+        let mut constants = std::iter::once(&imputation.default).chain(imputation.features.values());
+        if constants.any(|i| matches!(i, Imputation::Constant(v) if !v.is_finite())) {
+            return Err(SentinelError::ConfigError(
+                "model.imputation constants must be finite".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.safety.inference_error_rate) {
+            return Err(SentinelError::ConfigError(
+                "safety.inference_error_rate must be within 0-1".to_string(),
+            ));
+        }
+
+        if self.safety.error_window == 0 {
+            return Err(SentinelError::ConfigError(
+                "safety.error_window must be at least 1".to_string(),
+            ));
+        }
+
+        let breakers = &self.circuit_breakers;
+        if breakers.failure_rate <= 0.0
+            || breakers.failure_rate > 1.0
+            || breakers.min_calls == 0
+            || breakers.min_calls > breakers.window
+            || breakers.half_open_probes == 0
+        {
+            return Err(SentinelError::ConfigError(
+                "circuit_breakers.failure_rate must be within (0, 1], min_calls within 1-window and half_open_probes positive"
+                    .to_string(),
+            ));
+        }
+
+        if self.quotas.notional_usd_per_hour < 0.0 || self.quotas.notional_burst_usd < 0.0 {
+            return Err(SentinelError::ConfigError(
+                "quotas notional limits must be non-negative".to_string(),
+            ));
+        }
+
+        let fusion = &self.fusion;
+        let weights = [fusion.heuristic_weight, fusion.model_weight, fusion.adaptive_weight];
+        if weights.iter().any(|w| *w < 0.0) || fusion.heuristic_weight + fusion.adaptive_weight <= 0.0 {
+            return Err(SentinelError::ConfigError(
+                "fusion weights must be non-negative with heuristic or adaptive weight > 0".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.leader_guard.max_leader_risk) {
+            return Err(SentinelError::ConfigError(
+                "leader_guard.max_leader_risk must be within 0-1".to_string(),
+            ));
+        }
+
+        let slot_risk = &self.slot_risk;
+        if slot_risk.lookahead_leaders == 0 || slot_risk.slots_per_leader == 0 || slot_risk.slot_time_ms == 0 {
+            return Err(SentinelError::ConfigError(
+                "slot_risk lookahead_leaders, slots_per_leader and slot_time_ms must be at least 1".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&slot_risk.jito_single_threshold)
+            || !(0.0..=1.0).contains(&slot_risk.jito_bundle_threshold)
+            || slot_risk.jito_single_threshold > slot_risk.jito_bundle_threshold
+        {
+            return Err(SentinelError::ConfigError(
+                "slot_risk thresholds must be within 0-1 with jito_single_threshold <= jito_bundle_threshold".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&slot_risk.min_route_confidence) {
+            return Err(SentinelError::ConfigError(
+                "slot_risk.min_route_confidence must be within 0-1".to_string(),
+            ));
+        }
+
+        let timing = &self.submission_timing;
+        if timing.slot_time_ms == 0
+            || timing.boundary_margin_ms >= timing.slot_time_ms
+            || !(0.0..=1.0).contains(&timing.max_leader_risk)
+        {
+            return Err(SentinelError::ConfigError(
+                "submission_timing needs slot_time_ms above boundary_margin_ms and max_leader_risk within 0-1".to_string(),
+            ));
+        }
+
+        let slicing = &self.slicing;
+        if slicing.max_slices < 2
+            || slicing.target_slice_utilization <= 0.0
+            || slicing.target_slice_utilization > slicing.utilization_threshold
+        {
+            return Err(SentinelError::ConfigError(
+                "slicing requires max_slices >= 2 and 0 < target_slice_utilization <= utilization_threshold".to_string(),
+            ));
+        }
+
+        let netting = &self.netting;
+        if netting.window_ms == 0
+            || netting.max_resting_per_pair == 0
+            || !(0.0..=1.0).contains(&netting.min_cross_fraction)
+        {
+            return Err(SentinelError::ConfigError(
+                "netting needs window_ms and max_resting_per_pair >= 1, min_cross_fraction within 0-1".to_string(),
+            ));
+        }
+
+        let slo = &self.slo;
+        if !(slo.max_failure_rate > 0.0 && slo.max_failure_rate < 1.0) || slo.burn_rate_threshold <= 0.0 {
+            return Err(SentinelError::ConfigError(
+                "slo.max_failure_rate must be within (0, 1) and burn_rate_threshold positive".to_string(),
+            ));
+        }
+        if slo.short_window_secs == 0 || slo.short_window_secs > slo.long_window_secs {
+            return Err(SentinelError::ConfigError(
+                "slo requires 0 < short_window_secs <= long_window_secs".to_string(),
+            ));
+        }
+
+        let storage = &self.storage;
+        let missing = match storage.backend {
+            StorageBackendKind::Postgres => storage.postgres_url.is_none().then_some("postgres_url"),
+            StorageBackendKind::S3 => storage.s3_bucket.is_none().then_some("s3_bucket"),
+            _ => None,
+        };
+        if let Some(field) = missing {
+            return Err(SentinelError::ConfigError(format!(
+                "storage.{} is required for the {:?} backend",
+                field, storage.backend
+            )));
+        }
+
+        let coordination = &self.coordination;
+        if coordination.claim_ttl_ms < 1_000 || coordination.replay_ttl_secs == 0 {
+            return Err(SentinelError::ConfigError(
+                "coordination.claim_ttl_ms must be at least 1000 and replay_ttl_secs positive".to_string(),
+            ));
+        }
+        if coordination.backend == CoordinationBackendKind::Postgres
+            && coordination.postgres_url.is_none()
+            && storage.postgres_url.is_none()
+        {
+            return Err(SentinelError::ConfigError(
+                "coordination.postgres_url (or storage.postgres_url) is required for the Postgres backend".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.telemetry.sample_ratio) {
+            return Err(SentinelError::ConfigError(
+                "telemetry.sample_ratio must be between 0 and 1".to_string(),
+            ));
+        }
+
+        let oracle = &self.oracle;
+        let ratio = oracle.max_confidence_ratio;
+        if ratio.is_nan()
+            || ratio <= 0.0
+            || ratio > 1.0
+            || oracle.max_staleness_slots == 0
+            || oracle.twap_window_secs == 0
+        {
+            return Err(SentinelError::ConfigError(
+                "oracle.max_confidence_ratio must be within (0, 1] and staleness/TWAP windows positive".to_string(),
+            ));
+        }
+
+        if self.health.oracle_max_age_secs == 0 {
+            return Err(SentinelError::ConfigError(
+                "health.oracle_max_age_secs must be positive".to_string(),
+            ));
+        }
+
+        if self.redaction.signature_prefix == Some(0) {
+            return Err(SentinelError::ConfigError(
+                "redaction.signature_prefix must be at least 1".to_string(),
+            ));
+        }
+
+        self.validate_validators()?;
+        self.validate_policy()?;
+        self.validate_screening()?;
+        self.validate_sponsors()?;
+        self.validate_tenants()
+    }
+
+    fn validate_validators(&self) -> Result<()> {
+        let validators = &self.validators;
+        let keys = validators
+            .extra_malicious
+            .iter()
+            .chain(&validators.exempt)
+            .chain(&validators.registry_account)
+            .chain(&validators.registry_program)
+            .chain(&validators.registry_authority);
+        for key in keys {
+            if Pubkey::from_str(key).is_err() {
+                return Err(SentinelError::ConfigError(format!(
+                    "validators contains an invalid pubkey: {}",
+                    key
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_policy(&self) -> Result<()> {
+        let policy = &self.policy;
+        let lists = [
+            ("allowed_mints", &policy.allowed_mints),
+            ("denied_mints", &policy.denied_mints),
+            ("allowed_programs", &policy.allowed_programs),
+            ("denied_programs", &policy.denied_programs),
+        ];
+        for (name, list) in lists {
+            if list.iter().any(|key| Pubkey::from_str(key).is_err()) {
+                return Err(SentinelError::ConfigError(format!(
+                    "policy.{} contains an invalid pubkey",
+                    name
+                )));
+            }
+        }
+
+        if policy.min_new_mint_liquidity_usd < 0.0 {
+            return Err(SentinelError::ConfigError(
+                "policy.min_new_mint_liquidity_usd must be non-negative".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_screening(&self) -> Result<()> {
+        let screening = &self.screening;
+        if screening.deny_list.iter().any(|key| Pubkey::from_str(key).is_err()) {
+            return Err(SentinelError::ConfigError(
+                "screening.deny_list contains an invalid pubkey".to_string(),
+            ));
+        }
+        if let Some(ref url) = screening.webhook_url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(SentinelError::ConfigError(
+                    "screening.webhook_url must be an http(s) URL".to_string(),
+                ));
+            }
+            if screening.webhook_timeout_ms == 0 {
+                return Err(SentinelError::ConfigError(
+                    "screening.webhook_timeout_ms must be at least 1".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_sponsors(&self) -> Result<()> {
+        let mut ids = HashSet::new();
+        for sponsor in &self.sponsors {
+            let sponsor_error =
+                |reason: &str| SentinelError::ConfigError(format!("sponsor '{}': {}", sponsor.id, reason));
+
+            if sponsor.id.is_empty() || !ids.insert(sponsor.id.as_str()) {
+                return Err(sponsor_error("id must be non-empty and unique"));
+            }
+            if Pubkey::from_str(&sponsor.payer).is_err() {
+                return Err(sponsor_error("payer is not a valid pubkey"));
+            }
+            if sponsor.mode == FeePayerMode::User {
+                return Err(sponsor_error("mode must be sponsor or hybrid"));
+            }
+            let caps_conflict = sponsor.daily_cap_lamports > 0
+                && sponsor.max_lamports_per_intent > sponsor.daily_cap_lamports;
+            if caps_conflict {
+                return Err(sponsor_error("max_lamports_per_intent exceeds daily_cap_lamports"));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_tenants(&self) -> Result<()> {
+        let mut ids = HashSet::new();
+        let mut keys = HashSet::new();
+
+        for tenant in &self.tenants {
+            let tenant_error = |reason: &str| SentinelError::ConfigError(format!("tenant '{}': {}", tenant.id, reason));
+
+            let valid_id = !tenant.id.is_empty()
+                && tenant.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_id {
+                return Err(tenant_error("id must be non-empty [A-Za-z0-9_-]"));
+            }
+            if !ids.insert(tenant.id.as_str()) {
+                return Err(tenant_error("duplicate tenant id"));
+            }
+
+            if tenant.api_key_sha256.is_empty() {
+                return Err(tenant_error("at least one api_key_sha256 is required"));
+            }
+            for key in &tenant.api_key_sha256 {
+                if key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(tenant_error("api_key_sha256 entries must be 64 hex characters"));
+                }
+                if !keys.insert(key.to_ascii_lowercase()) {
+                    return Err(tenant_error("api key is assigned to more than one tenant"));
+                }
+            }
+
+            if tenant.allowed_mints.iter().any(|m| Pubkey::from_str(m).is_err()) {
+                return Err(tenant_error("allowed_mints contains an invalid pubkey"));
+            }
+            if let Some(ref sponsor) = tenant.sponsor {
+                if !self.sponsors.iter().any(|s| &s.id == sponsor) {
+                    return Err(tenant_error("sponsor is not a configured sponsor id"));
+                }
+            }
+
+            self.for_tenant(&tenant.id)
+                .map(|effective| effective.validate())
+                .transpose()
+                .map_err(|e| tenant_error(&e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Effective configuration for a tenant: global sections with its overrides applied
+    pub fn for_tenant(&self, tenant_id: &str) -> Option<SentinelConfig> {
+        let tenant = self.tenants.iter().find(|t| t.id == tenant_id)?;
+
+        let mut effective = SentinelConfig {
+            tenants: Vec::new(),
+            ..self.clone()
+        };
+        if let Some(ref tip_policy) = tenant.tip_policy {
+            effective.tip_policy = tip_policy.clone();
+        }
+        if let Some(ref quotas) = tenant.quotas {
+            effective.quotas = quotas.clone();
+        }
+        Some(effective)
+    }
+
+    /// Copy hot-reloadable sections from `other`, leaving static sections untouched
+    ///
+    /// Returns true if any tunable value changed.
+    pub fn apply_tunables(&mut self, other: &SentinelConfig) -> bool {
+        let changed = self.thresholds != other.thresholds
+            || self.calendar != other.calendar
+            || self.tip_policy != other.tip_policy
+            || self.protection_tiers != other.protection_tiers
+            || self.endpoints != other.endpoints
+            || self.safety != other.safety
+            || self.circuit_breakers != other.circuit_breakers
+            || self.quotas != other.quotas
+            || self.fusion != other.fusion
+            || self.leader_guard != other.leader_guard
+            || self.slot_risk != other.slot_risk
+            || self.submission_timing != other.submission_timing
+            || self.policy != other.policy
+            || self.screening != other.screening
+            || self.slicing != other.slicing
+            || self.netting != other.netting
+            || self.scheduler != other.scheduler
+            || self.slo != other.slo
+            || self.tenants != other.tenants
+            || self.sponsors != other.sponsors;
+
+        self.thresholds = other.thresholds.clone();
+        self.calendar = other.calendar.clone();
+        self.tip_policy = other.tip_policy.clone();
+        self.protection_tiers = other.protection_tiers.clone();
+        self.endpoints = other.endpoints.clone();
+        self.safety = other.safety.clone();
+        self.circuit_breakers = other.circuit_breakers.clone();
+        self.quotas = other.quotas.clone();
+        self.fusion = other.fusion.clone();
+        self.leader_guard = other.leader_guard.clone();
+        self.slot_risk = other.slot_risk.clone();
+        self.submission_timing = other.submission_timing.clone();
+        self.policy = other.policy.clone();
+        self.screening = other.screening.clone();
+        self.slicing = other.slicing.clone();
+        self.netting = other.netting.clone();
+        self.scheduler = other.scheduler.clone();
+        self.slo = other.slo.clone();
+        self.tenants = other.tenants.clone();
+        self.sponsors = other.sponsors.clone();
+
+        if self.model != other.model
+            || self.oracle != other.oracle
+            || self.congestion != other.congestion
+            || self.validators != other.validators
+            || self.storage != other.storage
+            || self.coordination != other.coordination
+            || self.redaction != other.redaction
+            || self.telemetry != other.telemetry
+            || self.health != other.health
+        {
+            warn!(
+                "Model/oracle/congestion/validator/storage/coordination/redaction/telemetry/health config changed - \
+                 restart required"
+            );
+        }
+
+        changed
+    }
+}
+
+/// Seed network-dependent defaults beneath the configured sources
+fn with_network_defaults(
+    builder: config::ConfigBuilder<config::builder::DefaultState>,
+) -> Result<config::ConfigBuilder<config::builder::DefaultState>> {
+    let network = match builder.build_cloned().ok().and_then(|c| c.get_string("network").ok()) {
+        Some(name) => name.parse::<Network>()?,
+        None => Network::default(),
+    };
+    let endpoints = EndpointConfig::for_network(network);
+    let tip_policy = TipPolicy::for_network(network);
+
+    builder
+        .set_default("network", network.as_str())
+        .and_then(|b| b.set_default("endpoints.rpc_url", endpoints.rpc_url))
+        .and_then(|b| b.set_default("endpoints.jito_block_engine_url", endpoints.jito_block_engine_url))
+        .and_then(|b| b.set_default("endpoints.jito_tip_floor_url", endpoints.jito_tip_floor_url))
+        .and_then(|b| b.set_default("tip_policy.max_tip_lamports", tip_policy.max_tip_lamports))
+        .map_err(|e| SentinelError::ConfigError(e.to_string()))
+}
+
+// ================================================================================================
+// Hot Reload Handle
+// ================================================================================================
+
+/// Shared, hot-reloadable configuration handle
+///
+/// Readers take a cheap `Arc` snapshot so a request sees one consistent config
+/// even if a reload lands mid-flight. Each snapshot comes with its config
+/// version (fingerprint) for stamping decision logs.
+#[derive(Clone)]
+pub struct ConfigHandle {
+    current: Arc<RwLock<(Arc<SentinelConfig>, String)>>,
+    version: Arc<RwLock<u64>>,
+    path: Option<PathBuf>,
+    env_prefix: String,
+    history: Option<Arc<ConfigHistory>>,
+}
+
+impl ConfigHandle {
+    /// Load config and wrap it in a reloadable handle
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let config = SentinelConfig::load(path.as_deref())?;
+        Ok(Self::with_source(config, path, ENV_PREFIX))
+    }
+
+    /// Wrap an already-loaded config (no file source)
+    pub fn new(config: SentinelConfig) -> Self {
+        Self::with_source(config, None, ENV_PREFIX)
+    }
+
+    fn with_source(config: SentinelConfig, path: Option<PathBuf>, env_prefix: &str) -> Self {
+        let config_version = config.fingerprint();
+        Self {
+            current: Arc::new(RwLock::new((Arc::new(config), config_version))),
+            version: Arc::new(RwLock::new(1)),
+            path,
+            env_prefix: env_prefix.to_string(),
+            history: None,
+        }
+    }
+
+    /// Record the current config and every effective reload in `history`
+    pub fn with_history(mut self, history: Arc<ConfigHistory>) -> Result<Self> {
+        history.record(&self.snapshot())?;
+        self.history = Some(history);
+        Ok(self)
+    }
+
+    /// Current config snapshot
+    pub fn snapshot(&self) -> Arc<SentinelConfig> {
+        self.versioned_snapshot().0
+    }
+
+    /// Current config snapshot with its config version (`SentinelConfig::fingerprint`)
+    pub fn versioned_snapshot(&self) -> (Arc<SentinelConfig>, String) {
+        let current = self.current.read().unwrap_or_else(|p| p.into_inner());
+        (Arc::clone(&current.0), current.1.clone())
+    }
+
+    /// Version (fingerprint) of the current config
+    pub fn config_version(&self) -> String {
+        self.current.read().unwrap_or_else(|p| p.into_inner()).1.clone()
+    }
+
+    /// Monotonic version, bumped on every effective reload
+    pub fn version(&self) -> u64 {
+        self.version.read().map(|v| *v).unwrap_or_else(|p| *p.into_inner())
+    }
+
+    /// Re-read the source and apply tunable sections
+    ///
+    /// Returns true if the config changed.
+    pub fn reload(&self) -> Result<bool> {
+        let fresh = SentinelConfig::load_with_prefix(self.path.as_deref(), &self.env_prefix)?;
+        Ok(self.apply(&fresh))
+    }
+
+    /// Apply tunable sections from an explicit config
+    pub fn apply(&self, fresh: &SentinelConfig) -> bool {
+        let mut next = (*self.snapshot()).clone();
+        if !next.apply_tunables(fresh) {
+            return false;
+        }
+
+        if let Some(ref history) = self.history {
+            // The reload still applies; its decisions just lack a fetchable snapshot
+            if let Err(e) = history.record(&next) {
+                warn!("Failed to record config snapshot: {}", e);
+            }
+        }
+        let config_version = next.fingerprint();
+        if let Ok(mut current) = self.current.write() {
+            *current = (Arc::new(next), config_version);
+        }
+        if let Ok(mut version) = self.version.write() {
+            *version += 1;
+            info!("🔄 Configuration reloaded (version {})", *version);
+        }
+
+        true
+    }
+
+    /// Poll the config file and reload when it changes
+    pub fn spawn_watcher(&self, interval: std::time::Duration) -> Option<tokio::task::JoinHandle<()>> {
+        let path = self.path.clone()?;
+        let handle = self.clone();
+
+        Some(tokio::spawn(async move {
+            let mut last_modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+
+                if let Err(e) = handle.reload() {
+                    warn!("Config reload failed, keeping previous config: {}", e);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_valid() {
+        let config = SentinelConfig::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.thresholds.high_tip, 100_000);
+        assert_eq!(config.tip_policy.min_tip_lamports, 1_000);
+    }
+
+    #[test]
+    fn test_partial_toml_uses_defaults() {
+        let config = SentinelConfig::from_toml_str(
+            r#"
+            [thresholds]
+            high_tip = 250000
+
+            [endpoints]
+            rpc_url = "http://localhost:8899"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.thresholds.high_tip, 250_000);
+        assert_eq!(config.thresholds.validator_risk, 0.6);
+        assert_eq!(config.endpoints.rpc_url, "http://localhost:8899");
+        assert_eq!(config.model, ModelSettings::default());
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        let result = SentinelConfig::from_toml_str(
+            r#"
+            [tip_policy]
+            default_tip_allocation_pct = 150
+            "#,
+        );
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_fusion_weights_validated() {
+        let config = SentinelConfig::from_toml_str(
+            r#"
+            [fusion]
+            model_weight = 0.9
+
+            [fusion.meta_model]
+            bias = -3.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.fusion.model_weight, 0.9);
+        assert_eq!(config.fusion.meta_model.unwrap().heuristic, 3.0);
+
+        let result = SentinelConfig::from_toml_str(
+            r#"
+            [fusion]
+            heuristic_weight = 0.0
+            adaptive_weight = 0.0
+            "#,
+        );
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_slot_risk_thresholds_validated() {
+        let result = SentinelConfig::from_toml_str(
+            r#"
+            [slot_risk]
+            jito_single_threshold = 0.8
+            jito_bundle_threshold = 0.5
+            "#,
+        );
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+
+        let result = SentinelConfig::from_toml_str("[slot_risk]\nslots_per_leader = 0");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_slicing_validated() {
+        let result = SentinelConfig::from_toml_str("[slicing]\ntarget_slice_utilization = 0.5");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+
+        let result = SentinelConfig::from_toml_str("[slicing]\nmax_slices = 1");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_storage_backend_validated() {
+        let config = SentinelConfig::from_toml_str("[storage]\nbackend = \"sled\"\npath = \"data\"").unwrap();
+        assert_eq!(config.storage.backend, StorageBackendKind::Sled);
+        assert_eq!(config.storage.s3_prefix, "sentinel");
+
+        let result = SentinelConfig::from_toml_str("[storage]\nbackend = \"postgres\"");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+
+        let result = SentinelConfig::from_toml_str("[storage]\nbackend = \"s3\"");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_compliance_mode_parsed() {
+        let config = SentinelConfig::from_toml_str("[redaction]\ncompliance_mode = \"gdpr_strict\"").unwrap();
+        assert_eq!(config.redaction.compliance_mode, ComplianceMode::GdprStrict);
+        assert!(config.redaction.log_features);
+
+        let result = SentinelConfig::from_toml_str("[redaction]\nsignature_prefix = 0");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_protection_tiers_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str(
+            "[protection_tiers.economy]\ntip_percentiles = [25, 50, 50]\nroute_bias = -0.3",
+        )
+        .unwrap();
+        let economy = config.protection_tiers.profile(ProtectionTier::Economy);
+        assert_eq!(economy.tip_percentiles, [25, 50, 50]);
+        assert_eq!(economy.simulation, SimulationStrictness::Basic);
+        assert_eq!(config.protection_tiers.profile(ProtectionTier::Max).simulation, SimulationStrictness::Strict);
+
+        let result = SentinelConfig::from_toml_str("[protection_tiers.max]\ntip_percentiles = [75, 95, 100]");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+        let result = SentinelConfig::from_toml_str("[protection_tiers.standard]\nroute_bias = 1.5");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_coordination_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str(
+            "[coordination]\nbackend = \"postgres\"\nregion = \"fra\"\n[storage]\npostgres_url = \"host=db\"",
+        )
+        .unwrap();
+        assert_eq!(config.coordination.backend, CoordinationBackendKind::Postgres);
+        assert_eq!(config.coordination.claim_ttl_ms, 30_000);
+
+        let result = SentinelConfig::from_toml_str("[coordination]\nbackend = \"postgres\"");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+        let result = SentinelConfig::from_toml_str("[coordination]\nclaim_ttl_ms = 10");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_circuit_breakers_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str("[circuit_breakers]\nfailure_rate = 0.25\nopen_ms = 5000").unwrap();
+        assert_eq!(config.circuit_breakers.failure_rate, 0.25);
+        assert_eq!(config.circuit_breakers.window, 20);
+
+        let result = SentinelConfig::from_toml_str("[circuit_breakers]\nmin_calls = 50");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+        let result = SentinelConfig::from_toml_str("[circuit_breakers]\nfailure_rate = 0.0");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+        let result = SentinelConfig::from_toml_str("[circuit_breakers]\nhalf_open_probes = 0");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_telemetry_parsed_and_validated() {
+        let config = SentinelConfig::default();
+        assert_eq!(config.telemetry.otlp_endpoint, None);
+
+        let config = SentinelConfig::from_toml_str(
+            "[telemetry]\notlp_endpoint = \"http://tempo:4317\"\nsample_ratio = 0.25",
+        )
+        .unwrap();
+        assert_eq!(config.telemetry.otlp_endpoint.as_deref(), Some("http://tempo:4317"));
+        assert_eq!(config.telemetry.service_name, "sentinel-router");
+
+        let result = SentinelConfig::from_toml_str("[telemetry]\nsample_ratio = 1.5");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+        let result = SentinelConfig::from_toml_str("[health]\noracle_max_age_secs = 0");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_imputation_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str(
+            "[model.imputation]\ndefault = \"native\"\nfeatures.pool_liquidity_usd = { constant = -1.0 }",
+        )
+        .unwrap();
+        assert_eq!(config.model.imputation.default, Imputation::Native);
+        assert_eq!(config.model.imputation.features["pool_liquidity_usd"], Imputation::Constant(-1.0));
+        assert_eq!(SentinelConfig::default().model.imputation.default, Imputation::Zero);
+
+        let result = SentinelConfig::from_toml_str("[model.imputation]\ndefault = { constant = nan }");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_oracle_gate_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str("[oracle]\nmax_confidence_ratio = 0.005").unwrap();
+        assert_eq!(config.oracle.max_confidence_ratio, 0.005);
+        assert_eq!(config.oracle.max_staleness_slots, 25);
+
+        for toml in ["[oracle]\nmax_confidence_ratio = 0.0", "[oracle]\nmax_staleness_slots = 0"] {
+            let result = SentinelConfig::from_toml_str(toml);
+            assert!(matches!(result, Err(SentinelError::ConfigError(_))), "{}", toml);
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_content() {
+        let config = SentinelConfig::default();
+        assert_eq!(config.fingerprint(), SentinelConfig::default().fingerprint());
+        assert_eq!(config.fingerprint().len(), 16);
+
+        let mut changed = config.clone();
+        changed.slot_risk.jito_bundle_threshold += 0.01;
+        assert_ne!(changed.fingerprint(), config.fingerprint());
+    }
+
+    #[test]
+    fn test_submission_timing_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str("[submission_timing]\nmax_delay_ms = 1200").unwrap();
+        assert_eq!(config.submission_timing.max_delay_ms, 1_200);
+        assert_eq!(config.submission_timing.slot_time_ms, 400);
+
+        for toml in [
+            "[submission_timing]\nslot_time_ms = 0",
+            "[submission_timing]\nboundary_margin_ms = 400",
+            "[submission_timing]\nmax_leader_risk = 1.5",
+        ] {
+            let result = SentinelConfig::from_toml_str(toml);
+            assert!(matches!(result, Err(SentinelError::ConfigError(_))), "{}", toml);
+        }
+    }
+
+    #[test]
+    fn test_calendar_parsed_and_validated() {
+        let config = SentinelConfig::from_toml_str(
+            r#"
+            [calendar]
+            timezone = "Asia/Tokyo"
+            weekend_days = ["Sat", "Sun"]
+            holidays = ["2025-01-01"]
+
+            [[calendar.sessions]]
+            name = "tse"
+            open = "09:00"
+            close = "15:30"
+            multiplier = 1.2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.calendar.timezone, "Asia/Tokyo");
+        assert_eq!(config.calendar.sessions[0].close, NaiveTime::from_hms_opt(15, 30, 0).unwrap());
+        assert_eq!(config.calendar.holidays, vec![NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()]);
+        assert_eq!(config.calendar.weekend_multiplier, 1.3);
+
+        let result = SentinelConfig::from_toml_str("[calendar]\ntimezone = \"Mars/Olympus\"");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_policy_lists_validated() {
+        let config = SentinelConfig::from_toml_str(
+            r#"
+            [policy]
+            denied_mints = ["So11111111111111111111111111111111111111112"]
+            new_mint_age_secs = 86400
+            min_new_mint_liquidity_usd = 50000.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.policy.denied_mints.len(), 1);
+        assert!(config.policy.allowed_mints.is_empty());
+
+        let result = SentinelConfig::from_toml_str("[policy]\nallowed_programs = [\"not-a-pubkey\"]");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_tenant_overrides() {
+        let key = "a".repeat(64);
+        let config = SentinelConfig::from_toml_str(&format!(
+            r#"
+            [[tenants]]
+            id = "wallet-app"
+            api_key_sha256 = ["{key}"]
+            allowed_mints = ["So11111111111111111111111111111111111111112"]
+
+            [tenants.tip_policy]
+            max_tip_lamports = 50000
+            "#
+        ))
+        .unwrap();
+
+        let effective = config.for_tenant("wallet-app").unwrap();
+        assert_eq!(effective.tip_policy.max_tip_lamports, 50_000);
+        assert_eq!(effective.quotas, config.quotas);
+        assert!(effective.tenants.is_empty());
+        assert!(config.for_tenant("unknown").is_none());
+
+        // Overrides are validated against the merged config
+        let result = SentinelConfig::from_toml_str(&format!(
+            "[[tenants]]\nid = \"x\"\napi_key_sha256 = [\"{key}\"]\n[tenants.tip_policy]\nmin_tip_lamports = 10\nmax_tip_lamports = 1"
+        ));
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+
+        let result = SentinelConfig::from_toml_str(&format!(
+            "[[tenants]]\nid = \"a\"\napi_key_sha256 = [\"{key}\"]\n[[tenants]]\nid = \"b\"\napi_key_sha256 = [\"{key}\"]"
+        ));
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+
+        let result = SentinelConfig::from_toml_str("[[tenants]]\nid = \"../etc\"\napi_key_sha256 = []");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_screening_section() {
+        let denied = Pubkey::new_unique();
+        let config = SentinelConfig::from_toml_str(&format!(
+            r#"
+            [screening]
+            enabled = true
+            mode = "block"
+            deny_list = ["{denied}"]
+            webhook_url = "https://screening.example/v1/check"
+            "#
+        ))
+        .unwrap();
+        assert_eq!(config.screening.mode, ScreeningMode::Block);
+        assert_eq!(config.screening.webhook_timeout_ms, 2_000);
+
+        let result = SentinelConfig::from_toml_str("[screening]\ndeny_list = [\"not-a-pubkey\"]");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+        let result = SentinelConfig::from_toml_str("[screening]\nwebhook_url = \"ftp://screening\"");
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_sponsor_validation() {
+        let key = "a".repeat(64);
+        let payer = Pubkey::new_unique();
+        let config = SentinelConfig::from_toml_str(&format!(
+            r#"
+            [[sponsors]]
+            id = "acme"
+            payer = "{payer}"
+            mode = "hybrid"
+            daily_cap_lamports = 1000000
+
+            [[tenants]]
+            id = "acme-wallet"
+            api_key_sha256 = ["{key}"]
+            sponsor = "acme"
+            "#
+        ))
+        .unwrap();
+        assert_eq!(config.sponsors[0].mode, FeePayerMode::Hybrid);
+        assert_eq!(config.tenants[0].sponsor.as_deref(), Some("acme"));
+
+        let result = SentinelConfig::from_toml_str(&format!(
+            "[[tenants]]\nid = \"x\"\napi_key_sha256 = [\"{key}\"]\nsponsor = \"nobody\""
+        ));
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+
+        let result = SentinelConfig::from_toml_str(&format!("[[sponsors]]\nid = \"a\"\npayer = \"{payer}\""));
+        assert!(matches!(result, Err(SentinelError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_network_seeds_defaults() {
+        let config = SentinelConfig::from_toml_str(
+            r#"
+            network = "devnet"
+
+            [endpoints]
+            rpc_url = "https://devnet.example-rpc.com"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.network, Network::Devnet);
+        assert_eq!(config.endpoints.rpc_url, "https://devnet.example-rpc.com");
+        assert_eq!(config.endpoints.jito_block_engine_url, Network::Devnet.jito_block_engine_url());
+        assert_eq!(config.tip_policy.max_tip_lamports, 100_000);
+
+        let local = SentinelConfig::from_toml_str("network = \"localnet\"\n[tip_policy]\nmax_tip_lamports = 5000").unwrap();
+        assert_eq!(local.endpoints, EndpointConfig::for_network(Network::Localnet));
+        assert_eq!(local.tip_policy.max_tip_lamports, 5_000);
+
+        assert_eq!(SentinelConfig::from_toml_str("").unwrap(), SentinelConfig::default());
+        assert!(matches!(
+            SentinelConfig::from_toml_str("network = \"moonnet\""),
+            Err(SentinelError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_env_override() {
+        std::env::set_var("SENTINELTEST_THRESHOLDS__HIGH_TIP", "42000");
+        let config = SentinelConfig::load_with_prefix(None, "SENTINELTEST").unwrap();
+        std::env::remove_var("SENTINELTEST_THRESHOLDS__HIGH_TIP");
+
+        assert_eq!(config.thresholds.high_tip, 42_000);
+    }
+
+    #[test]
+    fn test_reload_applies_only_tunables() {
+        let handle = ConfigHandle::new(SentinelConfig::default());
+        assert_eq!(handle.version(), 1);
+
+        let mut fresh = SentinelConfig::default();
+        fresh.thresholds.high_tip = 500_000;
+        fresh.model.warmup_iterations = 1;
+
+        assert!(handle.apply(&fresh));
+        let snapshot = handle.snapshot();
+        assert_eq!(snapshot.thresholds.high_tip, 500_000);
+        assert_eq!(snapshot.model.warmup_iterations, 100);
+        assert_eq!(handle.version(), 2);
+
+        // No-op reload keeps version
+        assert!(!handle.apply(&fresh));
+        assert_eq!(handle.version(), 2);
+    }
+
+    #[test]
+    fn test_reloads_recorded_in_history() {
+        let history = Arc::new(ConfigHistory::from_storage(&crate::storage::Storage::memory()).unwrap());
+        let handle = ConfigHandle::new(SentinelConfig::default())
+            .with_history(Arc::clone(&history))
+            .unwrap();
+        let boot_version = handle.config_version();
+        assert_eq!(boot_version, SentinelConfig::default().fingerprint());
+
+        let mut fresh = SentinelConfig::default();
+        fresh.thresholds.high_tip = 500_000;
+        assert!(handle.apply(&fresh));
+
+        let (snapshot, version) = handle.versioned_snapshot();
+        assert_ne!(version, boot_version);
+        assert_eq!(snapshot.fingerprint(), version);
+        assert_eq!(history.get(&version).unwrap().unwrap().config.thresholds.high_tip, 500_000);
+        assert!(history.get(&boot_version).unwrap().is_some());
+    }
+}
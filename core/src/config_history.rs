@@ -0,0 +1,151 @@
+//! Configuration Snapshot History
+//!
+//! Hot reloads change thresholds and policy under running traffic, so a
+//! decision is only explainable against the config in force when it was made.
+//! Decision records and shadow rows carry that config's version
+//! ([`SentinelConfig::fingerprint`]); [`ConfigHistory`] keeps the full config
+//! behind every version, appended once to the `config_snapshots` stream of the
+//! storage backend, so audits can fetch the exact policy by hash.
+//! [`ConfigHandle::with_history`](crate::config::ConfigHandle::with_history)
+//! records the boot config and every effective reload.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+use crate::config::SentinelConfig;
+use crate::storage::{append_json, read_json, AppendLog, Storage};
+use crate::Result;
+
+/// Storage stream holding the snapshots
+pub const CONFIG_SNAPSHOT_STREAM: &str = "config_snapshots";
+
+/// A config as it was in force
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    /// `SentinelConfig::fingerprint` of `config`
+    pub version: String,
+
+    /// When this version was first recorded
+    pub recorded_at_ms: u64,
+
+    pub config: SentinelConfig,
+}
+
+/// Snapshots by version
+type SnapshotIndex = HashMap<String, Arc<ConfigSnapshot>>;
+
+/// Content-addressed history of the configs decisions were made under
+pub struct ConfigHistory {
+    log: Arc<dyn AppendLog>,
+
+    /// Read from the log on first use
+    index: Mutex<Option<SnapshotIndex>>,
+}
+
+impl ConfigHistory {
+    pub fn new(log: Arc<dyn AppendLog>) -> Self {
+        Self {
+            log,
+            index: Mutex::new(None),
+        }
+    }
+
+    /// History in the configured storage backend's `config_snapshots` stream
+    pub fn from_storage(storage: &Storage) -> Result<Self> {
+        Ok(Self::new(storage.log(CONFIG_SNAPSHOT_STREAM)?))
+    }
+
+    /// Loaded index (blocking on first use)
+    fn index(&self) -> Result<MutexGuard<'_, Option<SnapshotIndex>>> {
+        let mut index = self.index.lock().unwrap_or_else(|p| p.into_inner());
+        if index.is_none() {
+            let snapshots = read_json::<ConfigSnapshot>(self.log.as_ref())?;
+            // The first record of a version wins
+            let mut loaded = HashMap::with_capacity(snapshots.len());
+            for snapshot in snapshots {
+                loaded.entry(snapshot.version.clone()).or_insert_with(|| Arc::new(snapshot));
+            }
+            *index = Some(loaded);
+        }
+        Ok(index)
+    }
+
+    /// Record `config` unless its version is already known; returns the version
+    pub fn record(&self, config: &SentinelConfig) -> Result<String> {
+        let version = config.fingerprint();
+        let mut index = self.index()?;
+        let index = index.get_or_insert_with(HashMap::new);
+        if index.contains_key(&version) {
+            return Ok(version);
+        }
+
+        let snapshot = ConfigSnapshot {
+            version: version.clone(),
+            recorded_at_ms: now_ms(),
+            config: config.clone(),
+        };
+        append_json(self.log.as_ref(), std::slice::from_ref(&snapshot))?;
+        info!("🗂️  Recorded config version {}", version);
+        index.insert(version.clone(), Arc::new(snapshot));
+        Ok(version)
+    }
+
+    /// The config recorded under `version`
+    pub fn get(&self, version: &str) -> Result<Option<Arc<ConfigSnapshot>>> {
+        Ok(self.index()?.as_ref().and_then(|index| index.get(version).cloned()))
+    }
+
+    /// Recorded versions with their first-recorded time, oldest first
+    pub fn versions(&self) -> Result<Vec<(String, u64)>> {
+        let index = self.index()?;
+        let mut versions: Vec<_> = index
+            .iter()
+            .flat_map(|index| index.values())
+            .map(|snapshot| (snapshot.version.clone(), snapshot.recorded_at_ms))
+            .collect();
+        versions.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(versions)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshots_recorded_once_and_survive_restart() {
+        let storage = Storage::memory();
+        let history = ConfigHistory::from_storage(&storage).unwrap();
+
+        let config = SentinelConfig::default();
+        let version = history.record(&config).unwrap();
+        assert_eq!(version, config.fingerprint());
+        assert_eq!(history.record(&config).unwrap(), version);
+
+        let mut tightened = config.clone();
+        tightened.slot_risk.jito_bundle_threshold = 0.5;
+        let tightened_version = history.record(&tightened).unwrap();
+        assert_ne!(tightened_version, version);
+
+        // A fresh process reads the same history back
+        let reopened = ConfigHistory::from_storage(&storage).unwrap();
+        assert_eq!(reopened.versions().unwrap().len(), 2);
+        let snapshot = reopened.get(&tightened_version).unwrap().unwrap();
+        assert_eq!(snapshot.config, tightened);
+        assert_eq!(snapshot.config.fingerprint(), tightened_version);
+        assert!(reopened.get("0123456789abcdef").unwrap().is_none());
+
+        let records = read_json::<ConfigSnapshot>(storage.log(CONFIG_SNAPSHOT_STREAM).unwrap().as_ref()).unwrap();
+        assert_eq!(records.len(), 2);
+    }
+}
@@ -0,0 +1,251 @@
+//! Network Congestion Readings
+//!
+//! `AdaptiveHeuristics::update_congestion` takes TPS utilization (0-1) but
+//! leaves it to the caller to work out. [`CongestionReading::from_samples`]
+//! derives it from `getRecentPerformanceSamples` (one sample per ~60s,
+//! newest first), optionally combined with the compute-unit fullness of the
+//! latest block:
+//! - **TPS**: transactions over the samples' combined period
+//! - **utilization**: the larger of TPS / `capacity_tps` and block fullness
+//! - **trend**: relative TPS change of the newer half of the samples over the
+//!   older half (positive = congestion building)
+//!
+//! [`CongestionCache`] is the shared handle `ai_engine::CongestionMonitor`
+//! publishes into; fee and tip logic read it, ignoring readings older than
+//! `max_age`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::CongestionSettings;
+use crate::{Result, SentinelError};
+
+/// One `getRecentPerformanceSamples` entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceSample {
+    pub slot: u64,
+    pub num_transactions: u64,
+
+    /// Missing on RPC nodes older than 1.15
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_non_vote_transactions: Option<u64>,
+    pub num_slots: u64,
+    pub sample_period_secs: u16,
+}
+
+/// Parse a `getRecentPerformanceSamples` result (array, newest first)
+pub fn parse_performance_samples(result: &serde_json::Value) -> Result<Vec<PerformanceSample>> {
+    serde_json::from_value(result.clone())
+        .map_err(|e| SentinelError::ParseError(format!("getRecentPerformanceSamples: {}", e)))
+}
+
+/// Compute-unit fullness (0-1) of a `getBlock` result with full transaction details
+pub fn block_fullness(block: &serde_json::Value, max_block_compute_units: u64) -> Option<f32> {
+    let transactions = block["transactions"].as_array()?;
+    let consumed: u64 = transactions
+        .iter()
+        .filter_map(|tx| tx["meta"]["computeUnitsConsumed"].as_u64())
+        .sum();
+    Some((consumed as f64 / max_block_compute_units.max(1) as f64).min(1.0) as f32)
+}
+
+/// Network load derived from recent performance samples
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CongestionReading {
+    /// Transactions per second (votes included) over the sampled period
+    pub tps: f64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub non_vote_tps: Option<f64>,
+
+    /// `tps / capacity_tps`, capped at 1.0
+    pub tps_utilization: f32,
+
+    /// Compute-unit fullness of the latest block, when fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_fullness: Option<f32>,
+
+    /// Utilization fed to the heuristics (0-1)
+    pub utilization: f32,
+
+    /// Relative TPS change, newer half of the samples vs. older half
+    pub trend: f32,
+
+    /// Newest sampled slot
+    pub slot: u64,
+    pub observed_at_ms: i64,
+}
+
+impl CongestionReading {
+    /// Reading from samples (newest first) and an optional block fullness
+    pub fn from_samples(
+        samples: &[PerformanceSample],
+        block_fullness: Option<f32>,
+        settings: &CongestionSettings,
+        observed_at_ms: i64,
+    ) -> Result<Self> {
+        let tps = rate(samples, |s| Some(s.num_transactions))
+            .ok_or_else(|| SentinelError::ParseError("No usable performance samples".to_string()))?;
+        let non_vote_tps = rate(samples, |s| s.num_non_vote_transactions);
+        let tps_utilization = (tps / settings.capacity_tps).clamp(0.0, 1.0) as f32;
+
+        // Samples are newest first: the first half is the recent one
+        let half = samples.len() / 2;
+        let total = |s: &PerformanceSample| Some(s.num_transactions);
+        let trend = match (rate(&samples[..half], total), rate(&samples[half..], total)) {
+            (Some(newer), Some(older)) if older > 0.0 => ((newer - older) / older) as f32,
+            _ => 0.0,
+        };
+
+        Ok(Self {
+            tps,
+            non_vote_tps,
+            tps_utilization,
+            block_fullness,
+            utilization: tps_utilization.max(block_fullness.unwrap_or(0.0)),
+            trend,
+            slot: samples.first().map_or(0, |s| s.slot),
+            observed_at_ms,
+        })
+    }
+
+    /// Congestion is building faster than `threshold` (relative change)
+    pub fn is_rising(&self, threshold: f32) -> bool {
+        self.trend > threshold
+    }
+}
+
+/// Per-second rate of `count` over the samples that report it
+fn rate(samples: &[PerformanceSample], count: impl Fn(&PerformanceSample) -> Option<u64>) -> Option<f64> {
+    let (total, secs) = samples
+        .iter()
+        .filter(|s| s.sample_period_secs > 0)
+        .filter_map(|s| count(s).map(|n| (n, s.sample_period_secs as u64)))
+        .fold((0u64, 0u64), |(total, secs), (n, period)| (total + n, secs + period));
+    (secs > 0).then(|| total as f64 / secs as f64)
+}
+
+/// Shared, staleness-aware latest congestion reading
+#[derive(Debug, Clone)]
+pub struct CongestionCache {
+    latest: Arc<RwLock<Option<CongestionReading>>>,
+    max_age: Duration,
+    clock: SharedClock,
+}
+
+impl Default for CongestionCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(180))
+    }
+}
+
+impl CongestionCache {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            latest: Arc::new(RwLock::new(None)),
+            max_age,
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn update(&self, reading: CongestionReading) {
+        *self.latest.write().unwrap_or_else(|p| p.into_inner()) = Some(reading);
+    }
+
+    /// Most recent reading regardless of age
+    pub fn latest(&self) -> Option<CongestionReading> {
+        *self.latest.read().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Most recent reading if younger than `max_age`
+    pub fn current(&self) -> Option<CongestionReading> {
+        let now = self.clock.unix_millis();
+        self.latest()
+            .filter(|reading| now.saturating_sub(reading.observed_at_ms) <= self.max_age.as_millis() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+
+    fn sample(slot: u64, num_transactions: u64) -> PerformanceSample {
+        PerformanceSample {
+            slot,
+            num_transactions,
+            num_non_vote_transactions: Some(num_transactions / 4),
+            num_slots: 150,
+            sample_period_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_parse_rpc_samples() {
+        let result = serde_json::json!([
+            {
+                "numSlots": 126, "numTransactions": 126, "numNonVoteTransactions": 1,
+                "samplePeriodSecs": 60, "slot": 348125
+            },
+            { "numSlots": 126, "numTransactions": 126, "samplePeriodSecs": 60, "slot": 347999 }
+        ]);
+        let samples = parse_performance_samples(&result).unwrap();
+        assert_eq!(samples[0].num_non_vote_transactions, Some(1));
+        assert_eq!(samples[1].num_non_vote_transactions, None);
+        assert!(parse_performance_samples(&serde_json::json!({ "slot": 1 })).is_err());
+    }
+
+    #[test]
+    fn test_utilization_and_trend() {
+        let settings = CongestionSettings::default();
+        // Newest first: 4,800 TPS over the last two minutes vs. 2,400 before
+        let samples = [sample(400, 288_000), sample(300, 288_000), sample(200, 144_000), sample(100, 144_000)];
+        let reading = CongestionReading::from_samples(&samples, None, &settings, 7).unwrap();
+
+        assert!((reading.tps - 3_600.0).abs() < 1e-9);
+        assert_eq!(reading.non_vote_tps, Some(900.0));
+        assert!((reading.utilization - 0.72).abs() < 1e-6);
+        assert!((reading.trend - 1.0).abs() < 1e-6);
+        assert!(reading.is_rising(0.1));
+        assert_eq!((reading.slot, reading.observed_at_ms), (400, 7));
+
+        // Full blocks dominate a moderate TPS
+        let full = CongestionReading::from_samples(&samples, Some(0.95), &settings, 7).unwrap();
+        assert_eq!(full.utilization, 0.95);
+
+        assert!(CongestionReading::from_samples(&[], None, &settings, 7).is_err());
+    }
+
+    #[test]
+    fn test_block_fullness_and_cache_staleness() {
+        let block = serde_json::json!({
+            "transactions": [
+                { "meta": { "computeUnitsConsumed": 12_000_000 } },
+                { "meta": { "computeUnitsConsumed": 12_000_000 } },
+                { "meta": null }
+            ]
+        });
+        assert_eq!(block_fullness(&block, 48_000_000), Some(0.5));
+        assert_eq!(block_fullness(&serde_json::json!({}), 48_000_000), None);
+
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let cache = CongestionCache::new(Duration::from_secs(60)).with_clock(clock.clone());
+        let settings = CongestionSettings::default();
+        let reading =
+            CongestionReading::from_samples(&[sample(1, 60_000)], None, &settings, clock.unix_millis()).unwrap();
+        cache.update(reading);
+        assert_eq!(cache.current(), Some(reading));
+
+        clock.advance(Duration::from_secs(61));
+        assert_eq!(cache.current(), None);
+        assert_eq!(cache.latest(), Some(reading));
+    }
+}
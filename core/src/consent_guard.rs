@@ -0,0 +1,346 @@
+//! On-Chain Consent Guard
+//!
+//! Client for the optional consent guard program, which enforces an intent's
+//! protection constraints inside the swap transaction instead of trusting the
+//! router to have checked them. The swap is bracketed by two guard instructions:
+//!
+//! ```text
+//! open_guard(consent_hash, minimum_received, expires_at)   records the output balance
+//! ...swap instructions...
+//! close_guard()                                            output delta >= minimum_received
+//! ```
+//!
+//! The guard account is a PDA of `["guard", user, consent_hash]`, so the
+//! transaction the wallet signs commits to the exact intent (`Intent::hash`);
+//! a router that altered the intent would have to alter the signed
+//! instruction. `open_guard` also fails past `expires_at` (unix seconds) and
+//! while a guard for the same intent is still open. `close_guard` closes the
+//! account and refunds its rent to the user, so a guarded transaction either
+//! meets the floor or reverts as a whole.
+//!
+//! Instructions use the Anchor layout (8-byte `sha256("global:<name>")`
+//! discriminator, Borsh arguments). The program is deployed per cluster, so
+//! its id is passed in rather than hardcoded.
+
+use sha2::{Digest, Sha256};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::intent::{Intent, SwapMode};
+use crate::{Result, SentinelError};
+
+/// Seed prefix of guard accounts
+pub const GUARD_SEED: &[u8] = b"guard";
+
+/// Borsh size of the `open_guard` arguments (hash, u64, `Option<i64>`)
+const OPEN_ARGS_LEN: usize = 32 + 8 + 9;
+
+fn discriminator(name: &str) -> [u8; 8] {
+    let digest = Sha256::digest(format!("global:{}", name).as_bytes());
+    let mut out = [0u8; 8];
+    out.copy_from_slice(&digest[..8]);
+    out
+}
+
+/// What the guard enforces for one swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardTerms {
+    /// Signer and rent payer; owner of `output_token_account`
+    pub user: Pubkey,
+    /// `Intent::hash` of the consented intent
+    pub consent_hash: Hash,
+    /// Token account whose balance must grow by `minimum_received`
+    pub output_token_account: Pubkey,
+    pub minimum_received: u64,
+    /// Unix seconds after which `open_guard` fails
+    pub expires_at: Option<i64>,
+}
+
+impl GuardTerms {
+    /// Terms of a swap intent
+    ///
+    /// The floor is `minimum_received` (ExactIn, required) or the exact
+    /// output amount (ExactOut, unless a floor is given).
+    pub fn for_intent(intent: &Intent, output_token_account: Pubkey) -> Result<Self> {
+        let swap = intent.swap_details.as_ref().ok_or_else(|| {
+            SentinelError::InvalidIntent(format!("Intent {} has no swap to guard", intent.intent_id))
+        })?;
+        let minimum_received = match (swap.mode, swap.minimum_received) {
+            (_, Some(minimum)) => minimum,
+            (SwapMode::ExactOut, None) => swap.amount,
+            (SwapMode::ExactIn, None) => {
+                return Err(SentinelError::InvalidIntent(format!(
+                    "Intent {} needs minimum_received for an on-chain guard",
+                    intent.intent_id
+                )))
+            }
+        };
+
+        Ok(Self {
+            user: intent.user_public_key,
+            consent_hash: intent.hash(),
+            output_token_account,
+            minimum_received,
+            expires_at: intent.constraints.expiry_timestamp,
+        })
+    }
+}
+
+/// Guard instruction decoded from a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardInstruction {
+    Open {
+        guard: Pubkey,
+        consent_hash: Hash,
+        minimum_received: u64,
+        expires_at: Option<i64>,
+    },
+    Close {
+        guard: Pubkey,
+    },
+}
+
+/// Instruction builders and account derivation for one deployment of the guard program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsentGuard {
+    program_id: Pubkey,
+}
+
+impl ConsentGuard {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    /// Guard PDA and bump for `user`'s intent
+    pub fn guard_address(&self, user: &Pubkey, consent_hash: &Hash) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[GUARD_SEED, user.as_ref(), consent_hash.as_ref()], &self.program_id)
+    }
+
+    /// `open_guard`: create the guard account and record the output balance
+    pub fn open_instruction(&self, terms: &GuardTerms) -> Instruction {
+        let (guard, _) = self.guard_address(&terms.user, &terms.consent_hash);
+
+        let mut data = Vec::with_capacity(8 + OPEN_ARGS_LEN);
+        data.extend_from_slice(&discriminator("open_guard"));
+        data.extend_from_slice(terms.consent_hash.as_ref());
+        data.extend_from_slice(&terms.minimum_received.to_le_bytes());
+        match terms.expires_at {
+            Some(expires_at) => {
+                data.push(1);
+                data.extend_from_slice(&expires_at.to_le_bytes());
+            }
+            None => data.extend_from_slice(&[0; 9]),
+        }
+
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(terms.user, true),
+                AccountMeta::new(guard, false),
+                AccountMeta::new_readonly(terms.output_token_account, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// `close_guard`: check the output delta against the floor and close the account
+    pub fn close_instruction(&self, terms: &GuardTerms) -> Instruction {
+        let (guard, _) = self.guard_address(&terms.user, &terms.consent_hash);
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(terms.user, true),
+                AccountMeta::new(guard, false),
+                AccountMeta::new_readonly(terms.output_token_account, false),
+            ],
+            data: discriminator("close_guard").to_vec(),
+        }
+    }
+
+    /// `instructions` bracketed by `open_guard` and `close_guard`
+    pub fn wrap(&self, terms: &GuardTerms, instructions: Vec<Instruction>) -> Vec<Instruction> {
+        let mut guarded = Vec::with_capacity(instructions.len() + 2);
+        guarded.push(self.open_instruction(terms));
+        guarded.extend(instructions);
+        guarded.push(self.close_instruction(terms));
+        guarded
+    }
+
+    /// Decode a guard instruction from its program, accounts and data
+    pub fn decode(&self, program_id: &Pubkey, accounts: &[Pubkey], data: &[u8]) -> Option<GuardInstruction> {
+        if *program_id != self.program_id {
+            return None;
+        }
+        let guard = *accounts.get(1)?;
+        let (tag, args) = data.split_at(data.len().min(8));
+
+        if tag == discriminator("close_guard") && args.is_empty() {
+            return Some(GuardInstruction::Close { guard });
+        }
+        if tag != discriminator("open_guard") || args.len() != OPEN_ARGS_LEN {
+            return None;
+        }
+        let consent_hash = Hash::new_from_array(args[..32].try_into().ok()?);
+        let minimum_received = u64::from_le_bytes(args[32..40].try_into().ok()?);
+        let expires_at = match args[40] {
+            0 => None,
+            1 => Some(i64::from_le_bytes(args[41..49].try_into().ok()?)),
+            _ => return None,
+        };
+        Some(GuardInstruction::Open {
+            guard,
+            consent_hash,
+            minimum_received,
+            expires_at,
+        })
+    }
+
+    /// Check that `message` opens a guard on exactly `terms` and closes it later
+    ///
+    /// Used before submission so a transaction that dropped or loosened the
+    /// guard is rejected rather than sent unprotected.
+    pub fn check_message(&self, message: &Message, terms: &GuardTerms) -> Result<()> {
+        let (expected, _) = self.guard_address(&terms.user, &terms.consent_hash);
+        let decoded = message.instructions.iter().filter_map(|ix| {
+            let program_id = message.account_keys.get(ix.program_id_index as usize)?;
+            let accounts: Vec<Pubkey> = ix
+                .accounts
+                .iter()
+                .filter_map(|&i| message.account_keys.get(i as usize).copied())
+                .collect();
+            self.decode(program_id, &accounts, &ix.data)
+        });
+
+        let mut opened = false;
+        for instruction in decoded {
+            match instruction {
+                GuardInstruction::Open {
+                    guard,
+                    consent_hash,
+                    minimum_received,
+                    expires_at,
+                } if guard == expected => {
+                    if consent_hash != terms.consent_hash
+                        || minimum_received < terms.minimum_received
+                        || expires_at != terms.expires_at
+                    {
+                        return Err(SentinelError::InvalidIntent(
+                            "Consent guard terms differ from the intent".to_string(),
+                        ));
+                    }
+                    opened = true;
+                }
+                GuardInstruction::Close { guard } if guard == expected && opened => return Ok(()),
+                _ => {}
+            }
+        }
+        Err(SentinelError::InvalidIntent(format!(
+            "Transaction does not {} the consent guard {}",
+            if opened { "close" } else { "open" },
+            expected
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::Constraints;
+
+    fn intent(mode: SwapMode, minimum_received: Option<u64>) -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            constraints: Constraints {
+                expiry_timestamp: Some(1_900_000_000),
+                ..Default::default()
+            },
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.mode = mode;
+        swap.minimum_received = minimum_received;
+        intent
+    }
+
+    #[test]
+    fn test_terms_from_intent() {
+        let account = Pubkey::new_unique();
+        let exact_in = intent(SwapMode::ExactIn, Some(990_000));
+        let terms = GuardTerms::for_intent(&exact_in, account).unwrap();
+        assert_eq!(terms.consent_hash, exact_in.hash());
+        assert_eq!((terms.minimum_received, terms.expires_at), (990_000, Some(1_900_000_000)));
+
+        let exact_out = GuardTerms::for_intent(&intent(SwapMode::ExactOut, None), account).unwrap();
+        assert_eq!(exact_out.minimum_received, 1_000_000);
+        assert!(GuardTerms::for_intent(&intent(SwapMode::ExactIn, None), account).is_err());
+    }
+
+    #[test]
+    fn test_instructions_round_trip_and_derive_guard() {
+        let guard = ConsentGuard::new(Pubkey::new_unique());
+        let terms = GuardTerms::for_intent(&intent(SwapMode::ExactIn, Some(990_000)), Pubkey::new_unique()).unwrap();
+        let (address, _) = guard.guard_address(&terms.user, &terms.consent_hash);
+
+        let open = guard.open_instruction(&terms);
+        assert_eq!(open.data.len(), 8 + OPEN_ARGS_LEN);
+        assert!(open.accounts[0].is_signer && open.accounts[1].is_writable);
+        let accounts: Vec<Pubkey> = open.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            guard.decode(&open.program_id, &accounts, &open.data),
+            Some(GuardInstruction::Open {
+                guard: address,
+                consent_hash: terms.consent_hash,
+                minimum_received: 990_000,
+                expires_at: Some(1_900_000_000),
+            })
+        );
+
+        let close = guard.close_instruction(&terms);
+        let accounts: Vec<Pubkey> = close.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            guard.decode(&close.program_id, &accounts, &close.data),
+            Some(GuardInstruction::Close { guard: address })
+        );
+        assert_eq!(guard.decode(&Pubkey::new_unique(), &accounts, &close.data), None);
+
+        // Different intent, different guard account
+        let other = GuardTerms {
+            consent_hash: Hash::new_unique(),
+            ..terms
+        };
+        assert_ne!(guard.guard_address(&other.user, &other.consent_hash).0, address);
+    }
+
+    #[test]
+    fn test_check_message_requires_matching_bracket() {
+        let guard = ConsentGuard::new(Pubkey::new_unique());
+        let terms = GuardTerms::for_intent(&intent(SwapMode::ExactIn, Some(990_000)), Pubkey::new_unique()).unwrap();
+        #[allow(deprecated)]
+        let swap = solana_sdk::system_instruction::transfer(&terms.user, &Pubkey::new_unique(), 1);
+        let message = |instructions: &[Instruction]| Message::new(instructions, Some(&terms.user));
+
+        let guarded = guard.wrap(&terms, vec![swap.clone()]);
+        assert_eq!(guarded.len(), 3);
+        guard.check_message(&message(&guarded), &terms).unwrap();
+
+        assert!(guard.check_message(&message(std::slice::from_ref(&swap)), &terms).is_err());
+        assert!(guard.check_message(&message(&guarded[..2]), &terms).is_err());
+        // Close before open does not count
+        let reversed = [guarded[2].clone(), swap.clone(), guarded[0].clone()];
+        assert!(guard.check_message(&message(&reversed), &terms).is_err());
+
+        let loosened = GuardTerms {
+            minimum_received: 1,
+            ..terms
+        };
+        let tampered = guard.wrap(&loosened, vec![swap]);
+        assert!(guard.check_message(&message(&tampered), &terms).is_err());
+    }
+}
@@ -0,0 +1,591 @@
+//! Multi-Instance Coordination
+//!
+//! Two router instances behind a load balancer share nothing by default, so
+//! both may execute the same intent, and each enforces quotas on its own
+//! share of the traffic. [`Coordination`] moves the state that must be
+//! global into a shared [`Coordinator`] backend:
+//! - **intent ownership**: an instance claims an intent before executing it;
+//!   claims are leases (`claim_ttl_ms`) rather than session locks, so the
+//!   intents of a crashed instance free up on their own
+//! - **replay ledger**: keys recorded once cluster-wide (submitted intents,
+//!   released vault transactions) that no instance may act on again until
+//!   they expire (`replay_ttl_secs`)
+//! - **quota counters**: fixed-window totals charged atomically against a
+//!   limit (see `QuotaManager::with_coordination`)
+//!
+//! | backend    | state                                                            | feature    |
+//! |------------|------------------------------------------------------------------|------------|
+//! | `local`    | in-process (single instance only)                                | always     |
+//! | `postgres` | tables `sentinel_claims`, `sentinel_replay`, `sentinel_counters` | `postgres` |
+//!
+//! All calls block, like [`crate::storage`]; use `spawn_blocking` from async code.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{CoordinationBackendKind, CoordinationSettings, SentinelConfig};
+use crate::{Result, SentinelError};
+
+/// Outcome of charging a shared counter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CounterCharge {
+    /// False when the charge would have exceeded the limit (nothing was added)
+    pub charged: bool,
+
+    /// Counter total after the call
+    pub total: f64,
+}
+
+/// Backend holding state shared by every router instance
+pub trait Coordinator: Send + Sync {
+    /// Short identifier for logs
+    fn name(&self) -> &'static str;
+
+    /// Claim `resource` for `owner` until `expires_at_ms`, or renew the claim
+    /// `owner` already holds; returns the owner holding it afterwards
+    fn claim(&self, resource: &str, owner: &str, now_ms: i64, expires_at_ms: i64) -> Result<String>;
+
+    /// Drop `owner`'s claim on `resource` (no-op when held by someone else)
+    fn release(&self, resource: &str, owner: &str) -> Result<()>;
+
+    /// Owner of an unexpired claim on `resource`
+    fn owner(&self, resource: &str, now_ms: i64) -> Result<Option<String>>;
+
+    /// Record `key` in the replay ledger; false when it is already recorded and unexpired
+    fn record_once(&self, key: &str, now_ms: i64, expires_at_ms: i64) -> Result<bool>;
+
+    /// Whether `key` is recorded and unexpired
+    fn contains(&self, key: &str, now_ms: i64) -> Result<bool>;
+
+    /// Add `amount` to `counter` unless the total would exceed `limit`
+    fn charge(&self, counter: &str, amount: f64, limit: f64, expires_at_ms: i64) -> Result<CounterCharge>;
+
+    /// Delete expired claims, ledger keys and counters; returns how many
+    fn sweep(&self, now_ms: i64) -> Result<usize>;
+}
+
+#[derive(Default)]
+struct MemoryState {
+    claims: HashMap<String, (String, i64)>,
+    ledger: HashMap<String, i64>,
+    counters: HashMap<String, (f64, i64)>,
+}
+
+/// In-process coordinator for single-instance deployments and tests
+#[derive(Default)]
+pub struct MemoryCoordinator {
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+impl Coordinator for MemoryCoordinator {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn claim(&self, resource: &str, owner: &str, now_ms: i64, expires_at_ms: i64) -> Result<String> {
+        let mut state = self.lock();
+        match state.claims.get(resource) {
+            Some((holder, expires)) if holder != owner && *expires > now_ms => Ok(holder.clone()),
+            _ => {
+                state.claims.insert(resource.to_string(), (owner.to_string(), expires_at_ms));
+                Ok(owner.to_string())
+            }
+        }
+    }
+
+    fn release(&self, resource: &str, owner: &str) -> Result<()> {
+        let mut state = self.lock();
+        if state.claims.get(resource).is_some_and(|(holder, _)| holder == owner) {
+            state.claims.remove(resource);
+        }
+        Ok(())
+    }
+
+    fn owner(&self, resource: &str, now_ms: i64) -> Result<Option<String>> {
+        Ok(self
+            .lock()
+            .claims
+            .get(resource)
+            .filter(|(_, expires)| *expires > now_ms)
+            .map(|(holder, _)| holder.clone()))
+    }
+
+    fn record_once(&self, key: &str, now_ms: i64, expires_at_ms: i64) -> Result<bool> {
+        let mut state = self.lock();
+        if state.ledger.get(key).is_some_and(|expires| *expires > now_ms) {
+            return Ok(false);
+        }
+        state.ledger.insert(key.to_string(), expires_at_ms);
+        Ok(true)
+    }
+
+    fn contains(&self, key: &str, now_ms: i64) -> Result<bool> {
+        Ok(self.lock().ledger.get(key).is_some_and(|expires| *expires > now_ms))
+    }
+
+    fn charge(&self, counter: &str, amount: f64, limit: f64, expires_at_ms: i64) -> Result<CounterCharge> {
+        let mut state = self.lock();
+        let entry = state.counters.entry(counter.to_string()).or_insert((0.0, expires_at_ms));
+        if entry.0 + amount > limit {
+            return Ok(CounterCharge { charged: false, total: entry.0 });
+        }
+        entry.0 += amount;
+        Ok(CounterCharge { charged: true, total: entry.0 })
+    }
+
+    fn sweep(&self, now_ms: i64) -> Result<usize> {
+        let mut state = self.lock();
+        let before = state.claims.len() + state.ledger.len() + state.counters.len();
+        state.claims.retain(|_, (_, expires)| *expires > now_ms);
+        state.ledger.retain(|_, expires| *expires > now_ms);
+        state.counters.retain(|_, (_, expires)| *expires > now_ms);
+        Ok(before - (state.claims.len() + state.ledger.len() + state.counters.len()))
+    }
+}
+
+/// This instance's handle on the shared state
+pub struct Coordination {
+    coordinator: Arc<dyn Coordinator>,
+    owner: String,
+    claim_ttl_ms: i64,
+    replay_ttl_ms: i64,
+    clock: SharedClock,
+}
+
+impl Coordination {
+    pub fn new(coordinator: Arc<dyn Coordinator>, settings: &CoordinationSettings) -> Self {
+        let instance = settings
+            .instance_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let owner = match settings.region.as_deref() {
+            Some(region) => format!("{}/{}", region, instance),
+            None => instance,
+        };
+
+        Self {
+            coordinator,
+            owner,
+            claim_ttl_ms: settings.claim_ttl_ms as i64,
+            replay_ttl_ms: settings.replay_ttl_secs.saturating_mul(1_000) as i64,
+            clock: system_clock(),
+        }
+    }
+
+    /// In-process coordination (single instance)
+    pub fn local() -> Self {
+        Self::new(Arc::new(MemoryCoordinator::new()), &CoordinationSettings::default())
+    }
+
+    /// Connect to the backend selected by `[coordination]`
+    ///
+    /// Fails if the backend's feature was not compiled in.
+    pub fn from_config(config: &SentinelConfig) -> Result<Self> {
+        let settings = &config.coordination;
+        let coordinator: Arc<dyn Coordinator> = match settings.backend {
+            CoordinationBackendKind::Local => Arc::new(MemoryCoordinator::new()),
+            #[cfg(feature = "postgres")]
+            CoordinationBackendKind::Postgres => {
+                let url = settings
+                    .postgres_url
+                    .as_deref()
+                    .or(config.storage.postgres_url.as_deref())
+                    .ok_or_else(|| SentinelError::ConfigError("coordination.postgres_url is not set".to_string()))?;
+                Arc::new(postgres_backend::PostgresCoordinator::connect(url)?)
+            }
+            #[allow(unreachable_patterns)]
+            other => {
+                return Err(SentinelError::ConfigError(format!(
+                    "Coordination backend {:?} is not compiled in (enable the matching sentinel-core feature)",
+                    other
+                )))
+            }
+        };
+
+        let coordination = Self::new(coordinator, settings);
+        info!("🤝 Coordination backend: {} (instance {})", coordination.backend(), coordination.owner);
+        Ok(coordination)
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// `<region>/<instance_id>` as recorded in claims
+    pub fn owner_id(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn backend(&self) -> &'static str {
+        self.coordinator.name()
+    }
+
+    /// Claim (or renew) ownership of `intent_id` for this instance
+    ///
+    /// Fails with `ClaimConflict` while another instance holds it.
+    pub fn claim_intent(&self, intent_id: &str) -> Result<()> {
+        let now = self.clock.unix_millis();
+        let resource = intent_resource(intent_id);
+        let holder = self.coordinator.claim(&resource, &self.owner, now, now + self.claim_ttl_ms)?;
+        if holder != self.owner {
+            debug!("Intent {} is owned by {}", intent_id, holder);
+            return Err(SentinelError::ClaimConflict { resource, owner: holder });
+        }
+        Ok(())
+    }
+
+    pub fn release_intent(&self, intent_id: &str) -> Result<()> {
+        self.coordinator.release(&intent_resource(intent_id), &self.owner)
+    }
+
+    /// Instance currently owning `intent_id`
+    pub fn intent_owner(&self, intent_id: &str) -> Result<Option<String>> {
+        self.coordinator.owner(&intent_resource(intent_id), self.clock.unix_millis())
+    }
+
+    /// Record `key` in the replay ledger; false when any instance already did
+    pub fn record_once(&self, key: &str) -> Result<bool> {
+        let now = self.clock.unix_millis();
+        self.coordinator.record_once(key, now, now + self.replay_ttl_ms)
+    }
+
+    /// Whether any instance recorded `key`
+    pub fn is_recorded(&self, key: &str) -> Result<bool> {
+        self.coordinator.contains(key, self.clock.unix_millis())
+    }
+
+    /// Charge `amount` to `counter` for the current `window_ms` window, up to `limit`
+    ///
+    /// Also returns the milliseconds until the window rolls over.
+    pub fn charge(&self, counter: &str, window_ms: u64, amount: f64, limit: f64) -> Result<(CounterCharge, u64)> {
+        let now = self.clock.unix_millis();
+        let window_ms = window_ms.max(1) as i64;
+        let window_start = now - now.rem_euclid(window_ms);
+        let key = format!("{}@{}", counter, window_start);
+
+        let charge = self.coordinator.charge(&key, amount, limit, window_start + window_ms)?;
+        Ok((charge, (window_start + window_ms - now) as u64))
+    }
+
+    /// Give back `amount` charged to `counter` in the current window
+    pub fn refund(&self, counter: &str, window_ms: u64, amount: f64) -> Result<()> {
+        self.charge(counter, window_ms, -amount, f64::INFINITY).map(|_| ())
+    }
+
+    /// Delete expired shared state
+    pub fn sweep(&self) -> Result<usize> {
+        self.coordinator.sweep(self.clock.unix_millis())
+    }
+}
+
+fn intent_resource(intent_id: &str) -> String {
+    format!("intent:{}", intent_id)
+}
+
+/// Rounds of [`conditional_claim`] before giving up on a resource that keeps
+/// being released under it
+const CLAIM_ATTEMPTS: usize = 3;
+
+/// Claim through a conditional upsert (`upsert` returns `None` when another
+/// owner's unexpired claim blocks it) followed by a lookup of the blocking
+/// holder. The holder may release between the two; the upsert is then retried.
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+fn conditional_claim<C>(
+    ctx: &mut C,
+    resource: &str,
+    mut upsert: impl FnMut(&mut C) -> Result<Option<String>>,
+    mut holder: impl FnMut(&mut C) -> Result<Option<String>>,
+) -> Result<String> {
+    for _ in 0..CLAIM_ATTEMPTS {
+        if let Some(owner) = upsert(ctx)? {
+            return Ok(owner);
+        }
+        if let Some(owner) = holder(ctx)? {
+            return Ok(owner);
+        }
+        debug!("Claim on {} was released during the claim, retrying", resource);
+    }
+    Err(SentinelError::ConnectionError(format!(
+        "Claim on {} changed hands {} times in a row",
+        resource, CLAIM_ATTEMPTS
+    )))
+}
+
+#[cfg(feature = "postgres")]
+mod postgres_backend {
+    use super::*;
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS sentinel_claims (
+            resource TEXT PRIMARY KEY,
+            owner TEXT NOT NULL,
+            expires_at_ms BIGINT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS sentinel_replay (
+            key TEXT PRIMARY KEY,
+            expires_at_ms BIGINT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS sentinel_counters (
+            counter TEXT PRIMARY KEY,
+            total DOUBLE PRECISION NOT NULL,
+            expires_at_ms BIGINT NOT NULL
+        );
+    ";
+
+    fn db_error(e: postgres::Error) -> SentinelError {
+        SentinelError::ConnectionError(format!("Postgres coordination: {}", e))
+    }
+
+    /// Claims, ledger and counters as rows updated with conditional upserts
+    pub struct PostgresCoordinator {
+        client: Mutex<postgres::Client>,
+    }
+
+    impl PostgresCoordinator {
+        /// Connect (e.g. `host=localhost user=sentinel dbname=sentinel`) and create the schema
+        pub fn connect(url: &str) -> Result<Self> {
+            let mut client = postgres::Client::connect(url, postgres::NoTls).map_err(db_error)?;
+            client.batch_execute(SCHEMA).map_err(db_error)?;
+            Ok(Self { client: Mutex::new(client) })
+        }
+
+        fn client(&self) -> std::sync::MutexGuard<'_, postgres::Client> {
+            self.client.lock().unwrap_or_else(|p| p.into_inner())
+        }
+    }
+
+    impl Coordinator for PostgresCoordinator {
+        fn name(&self) -> &'static str {
+            "postgres"
+        }
+
+        fn claim(&self, resource: &str, owner: &str, now_ms: i64, expires_at_ms: i64) -> Result<String> {
+            conditional_claim(
+                &mut *self.client(),
+                resource,
+                |client| {
+                    let claimed = client
+                        .query_opt(
+                            "INSERT INTO sentinel_claims (resource, owner, expires_at_ms) VALUES ($1, $2, $4)
+                             ON CONFLICT (resource) DO UPDATE
+                                 SET owner = EXCLUDED.owner, expires_at_ms = EXCLUDED.expires_at_ms
+                                 WHERE sentinel_claims.owner = EXCLUDED.owner
+                                     OR sentinel_claims.expires_at_ms <= $3
+                             RETURNING owner",
+                            &[&resource, &owner, &now_ms, &expires_at_ms],
+                        )
+                        .map_err(db_error)?;
+                    Ok(claimed.map(|row| row.get(0)))
+                },
+                |client| {
+                    let holder = client
+                        .query_opt("SELECT owner FROM sentinel_claims WHERE resource = $1", &[&resource])
+                        .map_err(db_error)?;
+                    Ok(holder.map(|row| row.get(0)))
+                },
+            )
+        }
+
+        fn release(&self, resource: &str, owner: &str) -> Result<()> {
+            self.client()
+                .execute(
+                    "DELETE FROM sentinel_claims WHERE resource = $1 AND owner = $2",
+                    &[&resource, &owner],
+                )
+                .map_err(db_error)?;
+            Ok(())
+        }
+
+        fn owner(&self, resource: &str, now_ms: i64) -> Result<Option<String>> {
+            let row = self
+                .client()
+                .query_opt(
+                    "SELECT owner FROM sentinel_claims WHERE resource = $1 AND expires_at_ms > $2",
+                    &[&resource, &now_ms],
+                )
+                .map_err(db_error)?;
+            Ok(row.map(|row| row.get(0)))
+        }
+
+        fn record_once(&self, key: &str, now_ms: i64, expires_at_ms: i64) -> Result<bool> {
+            let row = self
+                .client()
+                .query_opt(
+                    "INSERT INTO sentinel_replay (key, expires_at_ms) VALUES ($1, $3)
+                     ON CONFLICT (key) DO UPDATE SET expires_at_ms = EXCLUDED.expires_at_ms
+                         WHERE sentinel_replay.expires_at_ms <= $2
+                     RETURNING key",
+                    &[&key, &now_ms, &expires_at_ms],
+                )
+                .map_err(db_error)?;
+            Ok(row.is_some())
+        }
+
+        fn contains(&self, key: &str, now_ms: i64) -> Result<bool> {
+            let row = self
+                .client()
+                .query_opt(
+                    "SELECT 1 FROM sentinel_replay WHERE key = $1 AND expires_at_ms > $2",
+                    &[&key, &now_ms],
+                )
+                .map_err(db_error)?;
+            Ok(row.is_some())
+        }
+
+        fn charge(&self, counter: &str, amount: f64, limit: f64, expires_at_ms: i64) -> Result<CounterCharge> {
+            let mut client = self.client();
+            // The insert branch has no WHERE clause, so an oversized first charge is rejected up front
+            if amount <= limit {
+                let charged = client
+                    .query_opt(
+                        "INSERT INTO sentinel_counters (counter, total, expires_at_ms) VALUES ($1, $2, $4)
+                         ON CONFLICT (counter) DO UPDATE SET total = sentinel_counters.total + EXCLUDED.total
+                             WHERE sentinel_counters.total + EXCLUDED.total <= $3
+                         RETURNING total",
+                        &[&counter, &amount, &limit, &expires_at_ms],
+                    )
+                    .map_err(db_error)?;
+                if let Some(row) = charged {
+                    return Ok(CounterCharge { charged: true, total: row.get(0) });
+                }
+            }
+            let total = client
+                .query_opt("SELECT total FROM sentinel_counters WHERE counter = $1", &[&counter])
+                .map_err(db_error)?
+                .map_or(0.0, |row| row.get(0));
+            Ok(CounterCharge { charged: false, total })
+        }
+
+        fn sweep(&self, now_ms: i64) -> Result<usize> {
+            let mut client = self.client();
+            let mut tx = client.transaction().map_err(db_error)?;
+            let mut swept = 0;
+            for table in ["sentinel_claims", "sentinel_replay", "sentinel_counters"] {
+                swept += tx
+                    .execute(&format!("DELETE FROM {} WHERE expires_at_ms <= $1", table), &[&now_ms])
+                    .map_err(db_error)?;
+            }
+            tx.commit().map_err(db_error)?;
+            Ok(swept as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::time::Duration;
+
+    fn instance(coordinator: &Arc<MemoryCoordinator>, id: &str, clock: &Arc<ManualClock>) -> Coordination {
+        let settings = CoordinationSettings {
+            instance_id: Some(id.to_string()),
+            region: Some("fra".to_string()),
+            ..Default::default()
+        };
+        Coordination::new(coordinator.clone(), &settings).with_clock(clock.clone())
+    }
+
+    #[test]
+    fn test_intent_claims_are_exclusive_leases() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let shared = Arc::new(MemoryCoordinator::new());
+        let (a, b) = (instance(&shared, "a", &clock), instance(&shared, "b", &clock));
+
+        a.claim_intent("intent-1").unwrap();
+        a.claim_intent("intent-1").unwrap();
+        match b.claim_intent("intent-1") {
+            Err(SentinelError::ClaimConflict { owner, .. }) => assert_eq!(owner, "fra/a"),
+            other => panic!("expected a claim conflict, got {:?}", other),
+        }
+
+        // Releasing someone else's claim does nothing
+        b.release_intent("intent-1").unwrap();
+        assert_eq!(b.intent_owner("intent-1").unwrap().as_deref(), Some("fra/a"));
+
+        // A crashed owner's lease lapses
+        clock.advance(Duration::from_millis(30_001));
+        b.claim_intent("intent-1").unwrap();
+        assert_eq!(a.intent_owner("intent-1").unwrap().as_deref(), Some("fra/b"));
+    }
+
+    #[test]
+    fn test_conditional_claim_retries_when_holder_releases_mid_claim() {
+        let coordinator = MemoryCoordinator::new();
+        coordinator.claim("intent:1", "fra/a", 0, 30_000).unwrap();
+
+        // The upsert is blocked by `a`'s claim, which is released before the holder lookup
+        let mut released = false;
+        let upsert = |_: &mut bool| {
+            let held_by_other = coordinator.owner("intent:1", 1_000)?.is_some_and(|holder| holder != "fra/b");
+            if held_by_other {
+                return Ok(None);
+            }
+            coordinator.claim("intent:1", "fra/b", 1_000, 31_000).map(Some)
+        };
+        let holder = |released: &mut bool| {
+            if !*released {
+                coordinator.release("intent:1", "fra/a")?;
+                *released = true;
+            }
+            coordinator.owner("intent:1", 1_000)
+        };
+        let owner = conditional_claim(&mut released, "intent:1", upsert, holder).unwrap();
+        assert_eq!(owner, "fra/b");
+        assert_eq!(coordinator.owner("intent:1", 1_000).unwrap().as_deref(), Some("fra/b"));
+
+        // A holder that stays put is reported without retrying
+        let holder = |_: &mut ()| coordinator.owner("intent:1", 1_000);
+        let owner = conditional_claim(&mut (), "intent:1", |_| Ok(None), holder).unwrap();
+        assert_eq!(owner, "fra/b");
+
+        // A resource that keeps vanishing gives up instead of spinning
+        let mut rounds = 0;
+        let count_round = |rounds: &mut usize| {
+            *rounds += 1;
+            Ok(None)
+        };
+        let result = conditional_claim(&mut rounds, "intent:1", count_round, |_| Ok(None));
+        assert!(matches!(result, Err(SentinelError::ConnectionError(_))));
+        assert_eq!(rounds, CLAIM_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_replay_ledger_and_counters_are_shared() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let shared = Arc::new(MemoryCoordinator::new());
+        let (a, b) = (instance(&shared, "a", &clock), instance(&shared, "b", &clock));
+
+        assert!(a.record_once("submitted:intent-1").unwrap());
+        assert!(!b.record_once("submitted:intent-1").unwrap());
+        assert!(b.is_recorded("submitted:intent-1").unwrap());
+
+        let (first, _) = a.charge("quota:wallet", 60_000, 2.0, 3.0).unwrap();
+        let (second, retry_after_ms) = b.charge("quota:wallet", 60_000, 2.0, 3.0).unwrap();
+        assert_eq!(first, CounterCharge { charged: true, total: 2.0 });
+        assert_eq!(second, CounterCharge { charged: false, total: 2.0 });
+        assert_eq!(retry_after_ms, 40_000);
+        b.refund("quota:wallet", 60_000, 2.0).unwrap();
+        assert!(b.charge("quota:wallet", 60_000, 3.0, 3.0).unwrap().0.charged);
+
+        // Next window starts from zero; expired state is swept
+        clock.advance(Duration::from_secs(86_400));
+        assert!(a.charge("quota:wallet", 60_000, 3.0, 3.0).unwrap().0.charged);
+        assert!(a.record_once("submitted:intent-1").unwrap());
+        assert_eq!(a.sweep().unwrap(), 1);
+    }
+}
@@ -0,0 +1,675 @@
+//! DCA (Recurring Buy) Scheduler
+//!
+//! TWAP spreads one order over a single session; DCA intents recur over days
+//! or weeks. The scheduler keeps one [`DcaSchedule`] per intent and persists
+//! all of them to a JSON state file after every change, so upcoming
+//! executions survive restarts.
+//!
+//! Each due execution is released as a [`DcaOrder`] whose child swap intent
+//! (`<intent_id>-<n>`) goes through the normal pipeline, so every execution is
+//! risk-scored and routed on its own, under a fresh blockhash and a short
+//! expiry (the parent's consent block dates from when the schedule was set up).
+//!
+//! Users pause, resume and cancel a schedule with a [`DcaActionRequest`]
+//! signed by the intent's `user_public_key`. Like cancellations, actions are
+//! rejected outside `MAX_CANCELLATION_SKEW_SECS`, and a schedule only accepts
+//! actions newer than the last one applied, so a captured action cannot be
+//! replayed.
+//!
+//! With a [`SignedTxVault`] attached, wallets that signed executions offline
+//! deposit one transaction per child intent id; a due order carries its
+//! transaction (released once) and cancelling a schedule withdraws the
+//! transactions of orders not yet released.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::{Signature, Signer};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::{info, warn};
+
+use solana_sdk::transaction::Transaction;
+
+use crate::intent::{Intent, IntentType};
+use crate::intent_registry::MAX_CANCELLATION_SKEW_SECS;
+use crate::signed_tx_vault::{SignedTxVault, VaultStatus};
+use crate::types::RouteType;
+use crate::{Result, SentinelError};
+
+/// Schedule lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DcaStatus {
+    Active,
+    Paused,
+    Cancelled,
+    Completed,
+}
+
+/// How long a released order stays executable (seconds)
+pub const DCA_ORDER_TTL_SECS: i64 = 300;
+
+/// Schedule change a user can request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DcaAction {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+/// Request to pause, resume or cancel a DCA schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcaActionRequest {
+    pub intent_id: String,
+    pub action: DcaAction,
+
+    /// Signing time (unix seconds)
+    pub timestamp: i64,
+}
+
+impl DcaActionRequest {
+    pub fn new(intent_id: impl Into<String>, action: DcaAction, timestamp: i64) -> Self {
+        Self {
+            intent_id: intent_id.into(),
+            action,
+            timestamp,
+        }
+    }
+
+    /// Hash the user signs (BLAKE3 over bincode, like `Cancellation::hash`)
+    pub fn hash(&self) -> Hash {
+        let serialized = bincode::serialize(self).expect("DcaActionRequest serialization failed");
+        Hash::new_from_array(*blake3::hash(&serialized).as_bytes())
+    }
+
+    /// Sign with the wallet that owns the schedule
+    pub fn sign<S: Signer + ?Sized>(self, user: &S) -> SignedDcaAction {
+        let signature = user.sign_message(self.hash().as_ref());
+        SignedDcaAction {
+            request: self,
+            signature,
+        }
+    }
+}
+
+/// DCA action plus the user's signature over its hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedDcaAction {
+    pub request: DcaActionRequest,
+    pub signature: Signature,
+}
+
+/// One finished execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcaExecution {
+    pub order_index: u16,
+    pub amount: u64,
+    pub executed_at: i64,
+    pub risk_score: f32,
+    pub route: RouteType,
+    /// Failure reason; failed executions are not retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Persisted state of one DCA intent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcaSchedule {
+    pub intent: Intent,
+    pub status: DcaStatus,
+    /// Next order to release (0-based)
+    pub next_order: u16,
+    /// Unix time the next order is due
+    pub next_execution_at: i64,
+    /// Order released but not yet recorded
+    #[serde(default)]
+    pub in_flight: Option<u16>,
+    pub executions: Vec<DcaExecution>,
+    /// Timestamp of the last signed action applied (replay guard)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_action_at: Option<i64>,
+}
+
+impl DcaSchedule {
+    fn num_orders(&self) -> u16 {
+        self.intent.dca_details.as_ref().map_or(0, |d| d.num_orders)
+    }
+
+    fn interval_secs(&self) -> i64 {
+        self.intent.dca_details.as_ref().map_or(0, |d| i64::from(d.interval_secs))
+    }
+
+    /// Upcoming execution times (unix seconds)
+    pub fn upcoming(&self) -> Vec<i64> {
+        if matches!(self.status, DcaStatus::Cancelled | DcaStatus::Completed) {
+            return Vec::new();
+        }
+        let first = self.next_order + u16::from(self.in_flight.is_some());
+        (first..self.num_orders())
+            .map(|i| self.next_execution_at + i64::from(i - self.next_order) * self.interval_secs())
+            .collect()
+    }
+}
+
+/// Execution released by the scheduler
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcaOrder {
+    pub parent_intent_id: String,
+    pub order_index: u16,
+    /// Child swap intent for this execution (scored and routed individually)
+    pub intent: Intent,
+    /// User-signed transaction for this execution, released from the vault
+    pub signed_transaction: Option<Transaction>,
+}
+
+/// Persistent DCA scheduler
+pub struct DcaScheduler {
+    state_path: Option<PathBuf>,
+    schedules: Mutex<HashMap<String, DcaSchedule>>,
+    vault: Option<Arc<SignedTxVault>>,
+}
+
+impl DcaScheduler {
+    /// In-memory scheduler (tests, dry runs)
+    pub fn in_memory() -> Self {
+        Self {
+            state_path: None,
+            schedules: Mutex::new(HashMap::new()),
+            vault: None,
+        }
+    }
+
+    /// Scheduler persisted to `state_path`, restoring any existing state
+    pub fn open(state_path: impl Into<PathBuf>) -> Result<Self> {
+        let state_path = state_path.into();
+        let schedules = if state_path.exists() {
+            let data = std::fs::read(&state_path)
+                .map_err(|e| SentinelError::IoError(format!("Failed to read DCA state: {}", e)))?;
+            serde_json::from_slice(&data).map_err(|e| SentinelError::SerializationError(e.to_string()))?
+        } else {
+            HashMap::new()
+        };
+
+        let scheduler = Self {
+            state_path: Some(state_path),
+            schedules: Mutex::new(schedules),
+            vault: None,
+        };
+        info!("📅 DCA scheduler restored {} schedules", scheduler.lock().len());
+        Ok(scheduler)
+    }
+
+    /// Attach pre-signed executions from `vault` to due orders
+    pub fn with_vault(mut self, vault: Arc<SignedTxVault>) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Register a validated DCA intent; the first order is due immediately
+    pub fn schedule(&self, intent: Intent, now: i64) -> Result<DcaSchedule> {
+        if intent.intent_type != IntentType::DCA {
+            return Err(SentinelError::InvalidIntent(format!("Intent {} is not a DCA intent", intent.intent_id)));
+        }
+        intent.validate(now)?;
+
+        let schedule = DcaSchedule {
+            intent,
+            status: DcaStatus::Active,
+            next_order: 0,
+            next_execution_at: now,
+            in_flight: None,
+            executions: Vec::new(),
+            last_action_at: None,
+        };
+
+        let mut schedules = self.lock();
+        if schedules.contains_key(&schedule.intent.intent_id) {
+            return Err(SentinelError::InvalidIntent(format!(
+                "DCA intent {} is already scheduled",
+                schedule.intent.intent_id
+            )));
+        }
+        schedules.insert(schedule.intent.intent_id.clone(), schedule.clone());
+        self.persist(&schedules)?;
+        Ok(schedule)
+    }
+
+    pub fn get(&self, intent_id: &str) -> Option<DcaSchedule> {
+        self.lock().get(intent_id).cloned()
+    }
+
+    /// Release every order due at `now` (at most one in flight per schedule)
+    ///
+    /// Child intents carry `recent_blockhash` and expire `DCA_ORDER_TTL_SECS`
+    /// after `now`.
+    pub fn due(&self, now: i64, recent_blockhash: Hash) -> Result<Vec<DcaOrder>> {
+        let mut schedules = self.lock();
+        let mut orders = Vec::new();
+
+        for schedule in schedules.values_mut() {
+            if schedule.status != DcaStatus::Active
+                || schedule.in_flight.is_some()
+                || schedule.next_execution_at > now
+                || schedule.next_order >= schedule.num_orders()
+            {
+                continue;
+            }
+
+            let order_index = schedule.next_order;
+            schedule.in_flight = Some(order_index);
+            let intent = child_intent(&schedule.intent, order_index, now, recent_blockhash);
+            orders.push(DcaOrder {
+                parent_intent_id: schedule.intent.intent_id.clone(),
+                order_index,
+                signed_transaction: self.release_signed(&intent.intent_id),
+                intent,
+            });
+        }
+
+        if !orders.is_empty() {
+            self.persist(&schedules)?;
+        }
+        Ok(orders)
+    }
+
+    /// Record the outcome of a released order and schedule the next one
+    pub fn record_execution(
+        &self,
+        intent_id: &str,
+        order_index: u16,
+        risk_score: f32,
+        route: RouteType,
+        error: Option<String>,
+        now: i64,
+    ) -> Result<DcaSchedule> {
+        let mut schedules = self.lock();
+        let schedule = schedules
+            .get_mut(intent_id)
+            .ok_or_else(|| unknown(intent_id))?;
+
+        if schedule.in_flight != Some(order_index) {
+            return Err(SentinelError::InvalidIntent(format!(
+                "DCA order {} of {} is not in flight",
+                order_index, intent_id
+            )));
+        }
+
+        if let Some(ref reason) = error {
+            warn!("DCA order {} of {} failed: {}", order_index, intent_id, reason);
+        }
+        let amount = schedule
+            .intent
+            .dca_details
+            .as_ref()
+            .map_or(0, |d| d.order_amount(order_index));
+        schedule.executions.push(DcaExecution {
+            order_index,
+            amount,
+            executed_at: now,
+            risk_score,
+            route,
+            error,
+        });
+        schedule.in_flight = None;
+        schedule.next_order = order_index + 1;
+        schedule.next_execution_at += schedule.interval_secs();
+
+        if schedule.next_order >= schedule.num_orders() && schedule.status != DcaStatus::Cancelled {
+            schedule.status = DcaStatus::Completed;
+            info!("📅 DCA intent {} completed", intent_id);
+        }
+
+        let updated = schedule.clone();
+        self.persist(&schedules)?;
+        Ok(updated)
+    }
+
+    /// Apply a signed pause, resume or cancel at `now` (unix seconds)
+    ///
+    /// Pausing stops releasing orders; resuming releases missed orders on the
+    /// next tick; cancelling drops the remaining orders (an in-flight order
+    /// still completes) and withdraws their vaulted transactions.
+    pub fn apply_action(&self, signed: &SignedDcaAction, now: i64) -> Result<DcaSchedule> {
+        let request = &signed.request;
+        let schedule = self.transition(signed, now, |status| match (request.action, status) {
+            (DcaAction::Pause, DcaStatus::Active | DcaStatus::Paused) => Some(DcaStatus::Paused),
+            (DcaAction::Resume, DcaStatus::Active | DcaStatus::Paused) => Some(DcaStatus::Active),
+            (DcaAction::Cancel, DcaStatus::Active | DcaStatus::Paused | DcaStatus::Cancelled) => {
+                Some(DcaStatus::Cancelled)
+            }
+            _ => None,
+        })?;
+
+        if let (DcaAction::Cancel, Some(vault)) = (request.action, self.vault.as_ref()) {
+            let user = schedule.intent.user_public_key;
+            let first = schedule.next_order + u16::from(schedule.in_flight.is_some());
+            for order_index in first..schedule.num_orders() {
+                let child_id = format!("{}-{}", request.intent_id, order_index);
+                if let Err(e) = vault.get(&child_id).and_then(|held| match held {
+                    Some(entry) if entry.status == VaultStatus::Held => vault.cancel(&child_id, &user).map(drop),
+                    _ => Ok(()),
+                }) {
+                    warn!("Failed to withdraw signed transaction for {}: {}", child_id, e);
+                }
+            }
+        }
+        Ok(schedule)
+    }
+
+    /// Verify `signed` against the schedule's user and move it to `next(status)`
+    fn transition(
+        &self,
+        signed: &SignedDcaAction,
+        now: i64,
+        next: impl Fn(DcaStatus) -> Option<DcaStatus>,
+    ) -> Result<DcaSchedule> {
+        let request = &signed.request;
+        let intent_id = request.intent_id.as_str();
+        let mut schedules = self.lock();
+        let schedule = schedules
+            .get_mut(intent_id)
+            .ok_or_else(|| unknown(intent_id))?;
+
+        // Signature first: nothing else in the request is trusted until it verifies
+        if !signed
+            .signature
+            .verify(schedule.intent.user_public_key.as_ref(), request.hash().as_ref())
+        {
+            return Err(rejected(intent_id, "signature does not match the intent's user"));
+        }
+        if now.abs_diff(request.timestamp) > MAX_CANCELLATION_SKEW_SECS as u64 {
+            return Err(rejected(intent_id, "timestamp outside the allowed window"));
+        }
+        if schedule.last_action_at.is_some_and(|last| request.timestamp <= last) {
+            return Err(rejected(intent_id, "not newer than the last applied action"));
+        }
+        let status = next(schedule.status).ok_or_else(|| {
+            SentinelError::InvalidIntent(format!("DCA intent {} is {:?}", intent_id, schedule.status))
+        })?;
+
+        info!("📅 DCA intent {}: {:?} → {:?}", intent_id, schedule.status, status);
+        schedule.status = status;
+        schedule.last_action_at = Some(request.timestamp);
+        let updated = schedule.clone();
+        self.persist(&schedules)?;
+        Ok(updated)
+    }
+
+    /// Pre-signed transaction for a child intent, if one was deposited
+    fn release_signed(&self, child_id: &str) -> Option<Transaction> {
+        let vault = self.vault.as_ref()?;
+        match vault.get(child_id) {
+            Ok(Some(_)) => vault
+                .release(child_id)
+                .map_err(|e| warn!("DCA order {} released without its signed transaction: {}", child_id, e))
+                .ok(),
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Vault lookup for {} failed: {}", child_id, e);
+                None
+            }
+        }
+    }
+
+    fn persist(&self, schedules: &HashMap<String, DcaSchedule>) -> Result<()> {
+        let Some(ref path) = self.state_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| SentinelError::IoError(format!("Failed to create DCA state dir: {}", e)))?;
+        }
+
+        let data = serde_json::to_vec_pretty(schedules).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, data)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| SentinelError::IoError(format!("Failed to persist DCA state: {}", e)))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, DcaSchedule>> {
+        self.schedules.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// Swap intent for one execution of a DCA intent, released at `now`
+fn child_intent(parent: &Intent, order_index: u16, now: i64, recent_blockhash: Hash) -> Intent {
+    let mut child = parent.clone();
+    child.intent_id = format!("{}-{}", parent.intent_id, order_index);
+    child.intent_type = IntentType::Swap;
+    child.dca_details = None;
+    // The parent's blockhash and expiry date from scheduling, days before this execution
+    child.consent_block.recent_blockhash = recent_blockhash;
+    child.constraints.expiry_timestamp = Some(now + DCA_ORDER_TTL_SECS);
+    child.constraints.ttl_seconds = None;
+    if let (Some(swap), Some(dca)) = (child.swap_details.as_mut(), parent.dca_details.as_ref()) {
+        swap.amount = dca.order_amount(order_index);
+        // A total minimum does not apply to a slice; slippage bounds each execution
+        swap.minimum_received = None;
+    }
+    child
+}
+
+fn unknown(intent_id: &str) -> SentinelError {
+    SentinelError::InvalidIntent(format!("Unknown DCA intent {}", intent_id))
+}
+
+fn rejected(intent_id: &str, reason: &str) -> SentinelError {
+    SentinelError::InvalidIntent(format!("DCA action on {} rejected: {}", intent_id, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::DcaDetails;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: u32 = 86_400;
+
+    fn dca_intent(user: Pubkey) -> Intent {
+        let mut intent = Intent {
+            intent_id: "dca-1".to_string(),
+            user_public_key: user,
+            intent_type: IntentType::DCA,
+            dca_details: Some(DcaDetails {
+                interval_secs: DAY,
+                total_amount: 1_000,
+                num_orders: 3,
+            }),
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.amount = 1_000;
+        swap.minimum_received = None;
+        intent
+    }
+
+    fn execute(scheduler: &DcaScheduler, now: i64) -> Vec<DcaOrder> {
+        let orders = scheduler.due(now, Hash::new_unique()).unwrap();
+        for order in &orders {
+            scheduler
+                .record_execution(&order.parent_intent_id, order.order_index, 0.2, RouteType::JitoBundle, None, now)
+                .unwrap();
+        }
+        orders
+    }
+
+    #[test]
+    fn test_orders_released_on_interval() {
+        let scheduler = DcaScheduler::in_memory();
+        let user = Pubkey::new_unique();
+        scheduler.schedule(dca_intent(user), NOW).unwrap();
+
+        let first = execute(&scheduler, NOW);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].intent.intent_id, "dca-1-0");
+        assert_eq!(first[0].intent.intent_type, IntentType::Swap);
+        assert_eq!(first[0].intent.swap_details.as_ref().unwrap().amount, 333);
+
+        // Not due again until a day later
+        assert!(execute(&scheduler, NOW + 3_600).is_empty());
+        assert_eq!(execute(&scheduler, NOW + DAY as i64).len(), 1);
+
+        let last = execute(&scheduler, NOW + 2 * DAY as i64);
+        assert_eq!(last[0].intent.swap_details.as_ref().unwrap().amount, 334);
+
+        let schedule = scheduler.get("dca-1").unwrap();
+        assert_eq!(schedule.status, DcaStatus::Completed);
+        assert_eq!(schedule.executions.iter().map(|e| e.amount).sum::<u64>(), 1_000);
+        assert!(schedule.upcoming().is_empty());
+    }
+
+    #[test]
+    fn test_one_order_in_flight() {
+        let scheduler = DcaScheduler::in_memory();
+        scheduler.schedule(dca_intent(Pubkey::new_unique()), NOW).unwrap();
+
+        assert_eq!(scheduler.due(NOW, Hash::new_unique()).unwrap().len(), 1);
+        assert!(scheduler.due(NOW + 10 * DAY as i64, Hash::new_unique()).unwrap().is_empty());
+    }
+
+    fn act(user: &Keypair, action: DcaAction, timestamp: i64) -> SignedDcaAction {
+        DcaActionRequest::new("dca-1", action, timestamp).sign(user)
+    }
+
+    #[test]
+    fn test_child_intents_get_fresh_blockhash_and_expiry() {
+        let scheduler = DcaScheduler::in_memory();
+        let mut intent = dca_intent(Pubkey::new_unique());
+        intent.constraints.expiry_timestamp = Some(NOW + 3_600);
+        scheduler.schedule(intent.clone(), NOW).unwrap();
+        execute(&scheduler, NOW);
+
+        let later = NOW + DAY as i64;
+        let blockhash = Hash::new_unique();
+        let order = scheduler.due(later, blockhash).unwrap().remove(0);
+        assert_eq!(order.intent.consent_block.recent_blockhash, blockhash);
+        assert_eq!(order.intent.constraints.expiry_timestamp, Some(later + DCA_ORDER_TTL_SECS));
+        assert!(order.intent.validate(later).is_ok());
+        assert!(intent.validate(later).is_err());
+    }
+
+    #[test]
+    fn test_pause_resume_cancel() {
+        let scheduler = DcaScheduler::in_memory();
+        let user = Keypair::new();
+        scheduler.schedule(dca_intent(user.pubkey()), NOW).unwrap();
+
+        scheduler.apply_action(&act(&user, DcaAction::Pause, NOW), NOW).unwrap();
+        assert!(execute(&scheduler, NOW).is_empty());
+
+        scheduler.apply_action(&act(&user, DcaAction::Resume, NOW + 1), NOW + 1).unwrap();
+        assert_eq!(execute(&scheduler, NOW + 1).len(), 1);
+
+        let cancelled = scheduler.apply_action(&act(&user, DcaAction::Cancel, NOW + 2), NOW + 2).unwrap();
+        assert_eq!(cancelled.status, DcaStatus::Cancelled);
+        assert!(execute(&scheduler, NOW + 5 * DAY as i64).is_empty());
+        assert!(scheduler.apply_action(&act(&user, DcaAction::Resume, NOW + 3), NOW + 3).is_err());
+    }
+
+    #[test]
+    fn test_actions_require_fresh_signature_of_owner() {
+        let scheduler = DcaScheduler::in_memory();
+        let user = Keypair::new();
+        scheduler.schedule(dca_intent(user.pubkey()), NOW).unwrap();
+
+        // Knowing the wallet address is not enough
+        let forged = act(&Keypair::new(), DcaAction::Cancel, NOW);
+        assert!(scheduler.apply_action(&forged, NOW).is_err());
+        let mut tampered = act(&user, DcaAction::Pause, NOW);
+        tampered.request.action = DcaAction::Cancel;
+        assert!(scheduler.apply_action(&tampered, NOW).is_err());
+        let stale = act(&user, DcaAction::Cancel, NOW - 3_600);
+        assert!(scheduler.apply_action(&stale, NOW).is_err());
+        assert_eq!(scheduler.get("dca-1").unwrap().status, DcaStatus::Active);
+
+        // A captured pause cannot be replayed after the user resumes
+        let pause = act(&user, DcaAction::Pause, NOW);
+        scheduler.apply_action(&pause, NOW).unwrap();
+        scheduler.apply_action(&act(&user, DcaAction::Resume, NOW + 1), NOW + 1).unwrap();
+        assert!(scheduler.apply_action(&pause, NOW + 2).is_err());
+        assert_eq!(scheduler.get("dca-1").unwrap().status, DcaStatus::Active);
+    }
+
+    #[test]
+    fn test_actions_reject_extreme_timestamps() {
+        let scheduler = DcaScheduler::in_memory();
+        let user = Keypair::new();
+        scheduler.schedule(dca_intent(user.pubkey()), NOW).unwrap();
+
+        for timestamp in [i64::MIN, i64::MAX] {
+            let mut unsigned = act(&user, DcaAction::Cancel, timestamp);
+            unsigned.signature = Signature::default();
+            let err = scheduler.apply_action(&unsigned, NOW).unwrap_err().to_string();
+            assert!(err.contains("signature"), "{}", err);
+
+            let signed = act(&user, DcaAction::Cancel, timestamp);
+            let err = scheduler.apply_action(&signed, NOW).unwrap_err().to_string();
+            assert!(err.contains("outside the allowed window"), "{}", err);
+        }
+        assert_eq!(scheduler.get("dca-1").unwrap().status, DcaStatus::Active);
+    }
+
+    #[test]
+    fn test_vault_transactions_released_with_orders() {
+        use crate::clock::ManualClock;
+        use crate::storage::MemoryKv;
+        use crate::VaultStatus;
+
+        let vault = Arc::new(
+            SignedTxVault::new(Arc::new(MemoryKv::new()), [1; 32]).with_clock(Arc::new(ManualClock::at_timestamp(NOW))),
+        );
+        let scheduler = DcaScheduler::in_memory().with_vault(vault.clone());
+        let user = Keypair::new();
+        let intent = dca_intent(user.pubkey());
+        scheduler.schedule(intent.clone(), NOW).unwrap();
+
+        // Executions 0 and 2 were signed offline, each on its own nonce account
+        let presigned: Vec<Transaction> = [0, 2]
+            .into_iter()
+            .map(|order_index| {
+                #[allow(deprecated)]
+                let instructions = [
+                    solana_sdk::system_instruction::advance_nonce_account(&Pubkey::new_unique(), &user.pubkey()),
+                    solana_sdk::system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1),
+                ];
+                let tx =
+                    Transaction::new_signed_with_payer(&instructions, Some(&user.pubkey()), &[&user], Hash::new_unique());
+                vault.deposit(&child_intent(&intent, order_index, NOW, Hash::new_unique()), &tx).unwrap();
+                tx
+            })
+            .collect();
+
+        let first = execute(&scheduler, NOW);
+        assert_eq!(first[0].signed_transaction.as_ref(), Some(&presigned[0]));
+        assert_eq!(vault.get("dca-1-0").unwrap().unwrap().status, VaultStatus::Released);
+
+        let second = scheduler.due(NOW + DAY as i64, Hash::new_unique()).unwrap();
+        assert!(second[0].signed_transaction.is_none());
+
+        // Cancelling withdraws the pre-signed order that never ran
+        scheduler.apply_action(&act(&user, DcaAction::Cancel, NOW + DAY as i64), NOW + DAY as i64).unwrap();
+        assert_eq!(vault.get("dca-1-2").unwrap().unwrap().status, VaultStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_state_persisted_across_restarts() {
+        let path = std::env::temp_dir().join(format!("dca-{}.json", uuid::Uuid::new_v4()));
+        let user = Pubkey::new_unique();
+        {
+            let scheduler = DcaScheduler::open(&path).unwrap();
+            scheduler.schedule(dca_intent(user), NOW).unwrap();
+            execute(&scheduler, NOW);
+        }
+
+        let restored = DcaScheduler::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let schedule = restored.get("dca-1").unwrap();
+        assert_eq!(schedule.next_order, 1);
+        assert_eq!(schedule.upcoming(), vec![NOW + DAY as i64, NOW + 2 * DAY as i64]);
+    }
+}
@@ -0,0 +1,545 @@
+//! Routing Decision Log
+//!
+//! One record per routed request: risk score, chosen route and fee split, the per-stage
+//! latency breakdown, every venue quote when venues were compared and, after
+//! confirmation, the fill report and any realized sandwich. Buffered in memory and
+//! appended to a JSONL file, mirroring the shadow-mode prediction log so both can be
+//! joined on `request_id`, and carrying the OpenTelemetry trace id of the routing span
+//! so a decision can be opened in the tracing backend.
+//! Records pass through the log's [`Scrubber`] before they are buffered.
+//!
+//! A record can also carry what is needed to replay it (`ai_engine::DecisionReplayer`):
+//! the feature snapshot, the leader-window risk, the config fingerprint and
+//! the engine build that made the decision.
+//!
+//! Every record carries the version of the config it was decided under. A log
+//! given the [`ConfigHandle`] stamps records that do not set one themselves;
+//! the full config behind a version is kept by `ConfigHistory`.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+use crate::best_execution::{BestExecution, VenueQuote};
+use crate::config::ConfigHandle;
+use crate::fee_split::FeeSplitDecision;
+use crate::fill_report::FillReport;
+use crate::latency::{LatencyBreakdown, LatencyHistograms};
+use crate::mev_incident::MevIncident;
+use crate::redaction::Scrubber;
+use crate::telemetry::current_trace_id;
+use crate::types::{MevRiskScore, RouteType};
+use crate::{Result, SentinelError};
+
+/// Single routing decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    /// Correlation ID shared with shadow predictions
+    pub request_id: String,
+
+    /// Decision time (milliseconds since epoch)
+    pub timestamp_ms: u64,
+
+    /// OpenTelemetry trace the decision was made in (see `telemetry`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+
+    /// Tenant namespace the request was made under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+
+    /// Intent that was routed, if the request came from an intent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intent_id: Option<String>,
+
+    /// Production MEV risk score (0.0-1.0)
+    pub risk_score: f32,
+
+    /// Engine confidence in `risk_score` (0.0-1.0), when reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_confidence: Option<f32>,
+
+    /// Selected submission route
+    pub route: RouteType,
+
+    /// Tip / priority-fee split chosen for the route and risk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_split: Option<FeeSplitDecision>,
+
+    /// Per-stage latency breakdown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyBreakdown>,
+
+    /// Post-trade ground truth: true = confirmed MEV incident, false = confirmed clean
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mev_incident: Option<bool>,
+
+    /// Every venue quoted for this decision, winner first (best-execution evidence)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue_quotes: Option<Vec<VenueQuote>>,
+
+    /// Realized execution once the transaction confirmed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<FillReport>,
+
+    /// Confirmed sandwich of the landed transaction (sets `mev_incident`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incident: Option<MevIncident>,
+
+    /// Feature vector the score was computed from (replay input)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<serde_json::Value>,
+
+    /// Leader-window risk the route was selected with (replay input)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_risk: Option<f32>,
+
+    /// `SentinelConfig::fingerprint` of the config in effect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_version: Option<String>,
+
+    /// Engine build that scored the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine_build: Option<String>,
+}
+
+impl DecisionRecord {
+    /// New record, tagged with the current span's trace id when it is traced
+    pub fn new(request_id: String, risk_score: f32, route: RouteType) -> Self {
+        Self {
+            request_id,
+            timestamp_ms: now_ms(),
+            trace_id: current_trace_id(),
+            tenant_id: None,
+            intent_id: None,
+            risk_score,
+            risk_confidence: None,
+            route,
+            fee_split: None,
+            latency: None,
+            mev_incident: None,
+            venue_quotes: None,
+            fill: None,
+            incident: None,
+            features: None,
+            window_risk: None,
+            config_version: None,
+            engine_build: None,
+        }
+    }
+
+    /// Override the trace id, e.g. for a record written after the request span closed
+    pub fn with_trace_id(mut self, trace_id: String) -> Self {
+        self.trace_id = Some(trace_id);
+        self
+    }
+
+    pub fn with_tenant(mut self, tenant_id: String) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    pub fn with_intent(mut self, intent_id: String) -> Self {
+        self.intent_id = Some(intent_id);
+        self
+    }
+
+    /// Take the score and its confidence from the engine's `MevRiskScore`
+    pub fn with_score(mut self, score: &MevRiskScore) -> Self {
+        self.risk_score = score.score();
+        self.risk_confidence = score.confidence();
+        self
+    }
+
+    pub fn with_fee_split(mut self, fee_split: FeeSplitDecision) -> Self {
+        self.fee_split = Some(fee_split);
+        self
+    }
+
+    pub fn with_latency(mut self, latency: LatencyBreakdown) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Attach confirmed post-trade outcome (training label)
+    pub fn with_mev_incident(mut self, confirmed_mev: bool) -> Self {
+        self.mev_incident = Some(confirmed_mev);
+        self
+    }
+
+    /// Attach the compared venue quotes, including the losing ones
+    pub fn with_best_execution(mut self, best: &BestExecution) -> Self {
+        self.venue_quotes = Some(best.quotes.clone());
+        self
+    }
+
+    /// Attach the post-trade fill report
+    pub fn with_fill(mut self, fill: FillReport) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    /// Attach a realized sandwich found in the confirmed block
+    pub fn with_incident(mut self, incident: MevIncident) -> Self {
+        self.mev_incident = Some(true);
+        self.incident = Some(incident);
+        self
+    }
+
+    /// Config version (`SentinelConfig::fingerprint`) the decision was made under
+    pub fn with_config_version(mut self, config_version: String) -> Self {
+        self.config_version = Some(config_version);
+        self
+    }
+
+    /// Attach what a replay needs to re-execute the decision
+    pub fn with_replay_inputs(
+        mut self,
+        features: serde_json::Value,
+        window_risk: f32,
+        config_version: String,
+        engine_build: String,
+    ) -> Self {
+        self.features = Some(features);
+        self.window_risk = Some(window_risk);
+        self.config_version = Some(config_version);
+        self.engine_build = Some(engine_build);
+        self
+    }
+
+    /// Redact transaction signatures and venue error messages (and the
+    /// feature snapshot, unless features are kept)
+    pub fn scrub(&mut self, scrubber: &Scrubber) {
+        if !scrubber.keeps_features() {
+            self.features = None;
+        }
+        if let Some(ref mut fill) = self.fill {
+            fill.signature = scrubber.signature(&fill.signature);
+            // Sponsor payers are integrator accounts; the rest are the user's wallet
+            for entry in fill.fee_entries.iter_mut().filter(|entry| entry.sponsor.is_none()) {
+                entry.payer = scrubber.pubkey(&entry.payer);
+            }
+        }
+        if let Some(ref mut incident) = self.incident {
+            incident.signature = scrubber.signature(&incident.signature);
+            incident.front_run = scrubber.signature(&incident.front_run);
+            incident.back_run = scrubber.signature(&incident.back_run);
+        }
+        for quote in self.venue_quotes.iter_mut().flatten() {
+            quote.error = quote.error.as_deref().map(|error| scrubber.text(error));
+        }
+    }
+}
+
+/// Decision log configuration
+#[derive(Debug, Clone)]
+pub struct DecisionLogConfig {
+    /// Records to buffer before flushing
+    pub buffer_size: usize,
+
+    /// Log file path (JSONL format)
+    pub log_path: String,
+
+    /// Tag every record with this tenant (see `Tenant::decision_log_config`)
+    pub tenant_id: Option<String>,
+}
+
+impl Default for DecisionLogConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 1000,
+            log_path: "logs/decisions.jsonl".to_string(),
+            tenant_id: None,
+        }
+    }
+}
+
+/// Buffered JSONL decision log with latency histograms
+pub struct DecisionLog {
+    records: Arc<RwLock<Vec<DecisionRecord>>>,
+    histograms: Arc<LatencyHistograms>,
+    config: DecisionLogConfig,
+    scrubber: Scrubber,
+    config_handle: Option<ConfigHandle>,
+}
+
+impl DecisionLog {
+    pub fn new(config: DecisionLogConfig) -> Self {
+        Self {
+            records: Arc::new(RwLock::new(Vec::with_capacity(config.buffer_size))),
+            histograms: Arc::new(LatencyHistograms::new()),
+            config,
+            scrubber: Scrubber::default(),
+            config_handle: None,
+        }
+    }
+
+    /// Stamp records without a config version with the handle's current one
+    ///
+    /// A request holding a [`ConfigHandle::versioned_snapshot`] should stamp
+    /// its record with that snapshot's version instead, in case a reload lands
+    /// between deciding and logging.
+    pub fn with_config(mut self, handle: ConfigHandle) -> Self {
+        self.config_handle = Some(handle);
+        self
+    }
+
+    /// Redact records before they are buffered
+    pub fn with_scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    /// Append a decision (flushes when the buffer is full)
+    pub async fn record(&self, mut record: DecisionRecord) -> Result<()> {
+        if record.tenant_id.is_none() {
+            record.tenant_id = self.config.tenant_id.clone();
+        }
+        if record.config_version.is_none() {
+            record.config_version = self.config_handle.as_ref().map(ConfigHandle::config_version);
+        }
+        record.scrub(&self.scrubber);
+        if let Some(ref latency) = record.latency {
+            self.histograms.observe(latency);
+        }
+
+        let mut records = self.records.write().await;
+        records.push(record);
+
+        if records.len() >= self.config.buffer_size {
+            self.flush_internal(&mut records)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush buffered records to disk
+    pub async fn flush(&self) -> Result<()> {
+        let mut records = self.records.write().await;
+        self.flush_internal(&mut records)
+    }
+
+    /// Aggregated latency histograms
+    pub fn histograms(&self) -> Arc<LatencyHistograms> {
+        Arc::clone(&self.histograms)
+    }
+
+    /// Number of records waiting to be flushed
+    pub async fn buffered(&self) -> usize {
+        self.records.read().await.len()
+    }
+
+    fn flush_internal(&self, records: &mut Vec<DecisionRecord>) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(parent) = std::path::Path::new(&self.config.log_path).parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| SentinelError::IoError(format!("Failed to create log dir: {}", e)))?;
+        }
+
+        let log_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.log_path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to open decision log: {}", e)))?;
+
+        let mut writer = std::io::BufWriter::new(log_file);
+        for record in records.iter() {
+            serde_json::to_writer(&mut writer, record)
+                .map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+            writeln!(&mut writer).map_err(|e| SentinelError::IoError(e.to_string()))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| SentinelError::IoError(format!("Failed to flush decision log: {}", e)))?;
+
+        tracing::debug!("📝 Flushed {} decisions to {}", records.len(), self.config.log_path);
+        records.clear();
+
+        Ok(())
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fee_split::FeeSplitter;
+    use crate::latency::{LatencyTracer, PipelineStage};
+    use std::time::Duration;
+
+    #[test]
+    fn test_best_execution_quotes_serialized() {
+        use crate::best_execution::DexVenue;
+        use crate::SwapMode;
+
+        let best = BestExecution::select(
+            SwapMode::ExactIn,
+            vec![
+                VenueQuote::new(DexVenue::Jupiter, 1_000, 990, 0, 1.0),
+                VenueQuote::new(DexVenue::Orca, 1_000, 995, 0, 1.0),
+            ],
+            0.0,
+        )
+        .unwrap();
+        let record = DecisionRecord::new("req-1".to_string(), 0.1, RouteType::StandardRpc)
+            .with_best_execution(&best);
+
+        let json = serde_json::to_string(&record).unwrap();
+        // Not traced outside an OpenTelemetry span
+        assert!(!json.contains("trace_id"));
+        let parsed: DecisionRecord = serde_json::from_str(&json).unwrap();
+        let quotes = parsed.venue_quotes.unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert!(quotes[0].selected && !quotes[1].selected);
+        assert_eq!(quotes[1].venue, DexVenue::Jupiter);
+    }
+
+    #[tokio::test]
+    async fn test_records_scrubbed_before_buffering() {
+        use crate::best_execution::DexVenue;
+        use crate::config::{ComplianceMode, RedactionSettings};
+        use crate::fill_report::{FeeComponent, FeeEntry, FeesPaid};
+        use crate::redaction::REDACTED_PUBKEY;
+
+        let signature = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+        let path = std::env::temp_dir().join(format!("decisions-{}.jsonl", uuid::Uuid::new_v4()));
+        let log = DecisionLog::new(DecisionLogConfig {
+            buffer_size: 1,
+            log_path: path.to_string_lossy().to_string(),
+            tenant_id: None,
+        })
+        .with_scrubber(Scrubber::new(&RedactionSettings {
+            compliance_mode: ComplianceMode::GdprStrict,
+            ..Default::default()
+        }));
+
+        let mut failed = VenueQuote::new(DexVenue::Orca, 1_000, 0, 0, 0.0);
+        failed.error = Some("no token account for 9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string());
+        let mut record = DecisionRecord::new("req-1".to_string(), 0.2, RouteType::JitoBundle).with_fill(FillReport {
+            intent_id: "intent-1".to_string(),
+            signature: signature.to_string(),
+            slot: 1,
+            input_mint: "So11111111111111111111111111111111111111112".to_string(),
+            output_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            quoted_in_amount: 1_000,
+            quoted_out_amount: 990,
+            realized_in_amount: 1_000,
+            realized_out_amount: 985,
+            realized_slippage_bps: 50.0,
+            fees: FeesPaid {
+                base_fee_lamports: 5_000,
+                priority_fee_lamports: 0,
+                jito_tip_lamports: 10_000,
+                dex_fee_amount: 0,
+            },
+            mev_savings_estimate: 0,
+            fee_entries: vec![
+                FeeEntry {
+                    component: FeeComponent::BaseFee,
+                    payer: "Sponsor1111111111111111111111111111111111111".to_string(),
+                    sponsor: Some("acme".to_string()),
+                    lamports: 5_000,
+                },
+                FeeEntry {
+                    component: FeeComponent::JitoTip,
+                    payer: "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU".to_string(),
+                    sponsor: None,
+                    lamports: 10_000,
+                },
+            ],
+        });
+        record.venue_quotes = Some(vec![failed]);
+        log.record(record).await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        for secret in [signature, "9WzDXwBb", "7xKXtg2C"] {
+            assert!(!contents.contains(secret), "{} persisted", secret);
+        }
+        let parsed: DecisionRecord = serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+        let fill = parsed.fill.unwrap();
+        assert_eq!(fill.signature, "5VERv8NM…");
+        assert_eq!(fill.fee_entries[0].payer, "Sponsor1111111111111111111111111111111111111");
+        assert_eq!(fill.fee_entries[1].payer, REDACTED_PUBKEY);
+        // Mints identify tokens, not users
+        assert_eq!(fill.output_mint, "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        assert_eq!(
+            parsed.venue_quotes.unwrap()[0].error.as_deref(),
+            Some("no token account for [pubkey]")
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_record_with_latency() {
+        let path = std::env::temp_dir().join(format!("decisions-{}.jsonl", uuid::Uuid::new_v4()));
+        let log = DecisionLog::new(DecisionLogConfig {
+            buffer_size: 2,
+            log_path: path.to_string_lossy().to_string(),
+            tenant_id: None,
+        });
+
+        let mut tracer = LatencyTracer::new();
+        tracer.record(PipelineStage::Predict, Duration::from_micros(900));
+        let fee_split = FeeSplitter::default().split(&Default::default(), 100_000, 0.82, &RouteType::JitoBundle);
+        let record = DecisionRecord::new("req-1".to_string(), 0.0, RouteType::JitoBundle)
+            .with_score(&MevRiskScore::new(0.82).with_confidence(0.6))
+            .with_fee_split(fee_split)
+            .with_latency(tracer.finish());
+
+        log.record(record.clone()).await.unwrap();
+        assert_eq!(log.buffered().await, 1);
+        assert_eq!(log.histograms().stage(PipelineStage::Predict).count, 1);
+
+        log.record(record).await.unwrap();
+        assert_eq!(log.buffered().await, 0);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        let parsed: DecisionRecord = serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+        assert_eq!(parsed.latency.unwrap().slowest_stage, Some(PipelineStage::Predict));
+        assert_eq!(parsed.fee_split, Some(fee_split));
+        assert_eq!((parsed.risk_score, parsed.risk_confidence), (0.82, Some(0.6)));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_records_stamped_with_config_version() {
+        use crate::config::SentinelConfig;
+
+        let path = std::env::temp_dir().join(format!("decisions-{}.jsonl", uuid::Uuid::new_v4()));
+        let handle = ConfigHandle::new(SentinelConfig::default());
+        let log = DecisionLog::new(DecisionLogConfig {
+            buffer_size: 2,
+            log_path: path.to_string_lossy().to_string(),
+            tenant_id: None,
+        })
+        .with_config(handle.clone());
+
+        log.record(DecisionRecord::new("req-1".to_string(), 0.1, RouteType::StandardRpc)).await.unwrap();
+        let pinned = DecisionRecord::new("req-2".to_string(), 0.1, RouteType::StandardRpc)
+            .with_config_version("0123456789abcdef".to_string());
+        log.record(pinned).await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let versions: Vec<_> = contents
+            .lines()
+            .map(|line| serde_json::from_str::<DecisionRecord>(line).unwrap().config_version.unwrap())
+            .collect();
+        assert_eq!(versions, [handle.config_version(), "0123456789abcdef".to_string()]);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
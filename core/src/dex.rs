@@ -1,5 +1,5 @@
 // Production DEX integration for swap instruction construction
-// Supports Jupiter V6 aggregator for optimal routing
+// Supports Jupiter V6 aggregator for optimal routing and direct Orca Whirlpool swaps
 
 use serde::Deserialize;
 use solana_sdk::{
@@ -8,7 +8,9 @@ use solana_sdk::{
 };
 use std::str::FromStr;
 
-use crate::{Result, SentinelError, SwapDetails};
+use crate::best_execution::{DexVenue, VenueQuote};
+use crate::whirlpool::{PoolDepth, WhirlpoolClient};
+use crate::{Result, SentinelError, SwapDetails, SwapMode};
 
 /// Jupiter V6 program ID on Solana mainnet
 pub const JUPITER_V6_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
@@ -16,6 +18,7 @@ pub const JUPITER_V6_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyV
 /// DEX aggregator for building swap instructions
 pub struct DexAggregator {
     jupiter_program_id: Pubkey,
+    whirlpool: Option<WhirlpoolClient>,
 }
 
 impl Default for DexAggregator {
@@ -31,7 +34,89 @@ impl DexAggregator {
             Pubkey::from_str(JUPITER_V6_PROGRAM_ID)
                 .expect("Hardcoded Jupiter V6 program ID must be valid"); // Compile-time constant validation
 
-        Self { jupiter_program_id }
+        Self {
+            jupiter_program_id,
+            whirlpool: None,
+        }
+    }
+
+    /// Enable the Orca Whirlpool backend, reading pool accounts from `rpc_url`
+    pub fn with_orca(mut self, rpc_url: impl Into<String>) -> Result<Self> {
+        self.whirlpool = Some(WhirlpoolClient::new(rpc_url)?);
+        Ok(self)
+    }
+
+    fn orca(&self) -> Result<&WhirlpoolClient> {
+        self.whirlpool.as_ref().ok_or_else(|| {
+            SentinelError::DexError("Orca backend not configured (use with_orca)".to_string())
+        })
+    }
+
+    /// Whether a venue can be quoted with the current configuration
+    pub fn supports(&self, venue: DexVenue) -> bool {
+        match venue {
+            DexVenue::Jupiter => true,
+            DexVenue::Orca => self.whirlpool.is_some(),
+        }
+    }
+
+    /// Quote swap details on a single venue
+    pub async fn quote_venue(
+        &self,
+        venue: DexVenue,
+        swap_details: &SwapDetails,
+        slippage_bps: u16,
+    ) -> Result<VenueQuote> {
+        match venue {
+            DexVenue::Jupiter => {
+                let route = self.get_quote(swap_details, slippage_bps).await?;
+                Ok(VenueQuote::new(
+                    venue,
+                    route.in_amount,
+                    route.out_amount,
+                    route.platform_fee,
+                    route.price_impact_pct * 100.0,
+                ))
+            }
+            DexVenue::Orca => {
+                let (_, quote) = self.orca()?.quote(swap_details).await?;
+                // Pool fee is taken from the input and already reflected in out_amount
+                Ok(VenueQuote::new(
+                    venue,
+                    quote.in_amount,
+                    quote.out_amount,
+                    0,
+                    quote.price_impact_bps,
+                ))
+            }
+        }
+    }
+
+    /// Venue `build_swap_instruction` will execute on (Orca when requested, else Jupiter)
+    pub fn venue_for(&self, swap_details: &SwapDetails) -> DexVenue {
+        DexVenue::for_swap(swap_details)
+    }
+
+    /// Active-range liquidity and ±1% depth of a Whirlpool (market features)
+    pub async fn orca_pool_depth(&self, whirlpool: &Pubkey) -> Result<PoolDepth> {
+        Ok(self.orca()?.fetch_pool(whirlpool).await?.depth())
+    }
+
+    /// Share of the pool's 1% depth a trade of `in_amount` consumes
+    ///
+    /// Only known for Orca swaps (the Whirlpool is read from `route_hints[0]`);
+    /// None for Jupiter routes, whose pools are not visible here.
+    pub async fn depth_utilization(&self, swap_details: &SwapDetails, in_amount: u64) -> Result<Option<f64>> {
+        let Some(whirlpool) = swap_details.route_hints.as_ref().and_then(|hints| hints.first()) else {
+            return Ok(None);
+        };
+        if self.venue_for(swap_details) != DexVenue::Orca {
+            return Ok(None);
+        }
+
+        let state = self.orca()?.fetch_pool(whirlpool).await?;
+        let a_to_b = swap_details.input_mint == state.token_mint_a;
+        Ok(Some(state.depth().utilization(in_amount, a_to_b)))
     }
 
     /// Build a swap instruction using Jupiter aggregator
@@ -47,6 +132,14 @@ impl DexAggregator {
         swap_details: &SwapDetails,
         slippage_bps: u16,
     ) -> Result<Instruction> {
+        if is_orca(swap_details) {
+            let (instruction, _) = self
+                .orca()?
+                .build_swap_instruction(user, swap_details, slippage_bps)
+                .await?;
+            return Ok(instruction);
+        }
+
         // Get the optimal quote and route from Jupiter
         let route = self.get_quote(swap_details, slippage_bps).await?;
 
@@ -62,9 +155,13 @@ impl DexAggregator {
     ) -> Result<JupiterRoute> {
         let client = reqwest::Client::new();
 
+        let swap_mode = match swap_details.mode {
+            SwapMode::ExactIn => "ExactIn",
+            SwapMode::ExactOut => "ExactOut",
+        };
         let url = format!(
-            "https://quote-api.jup.ag/v6/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}",
-            swap_details.input_mint, swap_details.output_mint, swap_details.amount, slippage_bps
+            "https://quote-api.jup.ag/v6/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}&swapMode={}",
+            swap_details.input_mint, swap_details.output_mint, swap_details.amount, slippage_bps, swap_mode
         );
 
         let response =
@@ -88,6 +185,10 @@ impl DexAggregator {
             in_amount: quote.in_amount.parse().unwrap_or(swap_details.amount),
             out_amount: quote.out_amount.parse().unwrap_or(0),
             price_impact_pct: quote.price_impact_pct.parse().unwrap_or(0.0),
+            platform_fee: quote
+                .platform_fee
+                .and_then(|fee| fee.amount.parse().ok())
+                .unwrap_or(0),
             market_infos: quote.route_plan,
         })
     }
@@ -163,13 +264,20 @@ impl DexAggregator {
     }
 }
 
+pub(crate) fn is_orca(swap_details: &SwapDetails) -> bool {
+    swap_details
+        .dex
+        .as_deref()
+        .is_some_and(|dex| dex.eq_ignore_ascii_case("orca"))
+}
+
 /// Jupiter route information
 #[derive(Debug, Clone)]
 struct JupiterRoute {
     in_amount: u64,
     out_amount: u64,
-    #[allow(dead_code)]
     price_impact_pct: f64,
+    platform_fee: u64,
     market_infos: Vec<MarketInfo>,
 }
 
@@ -205,6 +313,14 @@ struct JupiterQuoteResponse {
     price_impact_pct: String,
     #[serde(rename = "routePlan")]
     route_plan: Vec<MarketInfo>,
+    #[serde(rename = "platformFee", default)]
+    platform_fee: Option<PlatformFee>,
+}
+
+/// Integrator fee charged on the output token
+#[derive(Debug, Deserialize)]
+struct PlatformFee {
+    amount: String,
 }
 
 #[cfg(test)]
@@ -221,6 +337,33 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_orca_requires_backend() {
+        let swap_details = SwapDetails {
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            amount: 1_000_000,
+            mode: SwapMode::ExactIn,
+            minimum_received: None,
+            dex: Some("Orca".to_string()),
+            route_hints: None,
+        };
+
+        let err = DexAggregator::new()
+            .build_swap_instruction(&Pubkey::new_unique(), &swap_details, 50)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Orca backend not configured"));
+
+        let err = DexAggregator::new()
+            .with_orca("http://localhost:1")
+            .unwrap()
+            .build_swap_instruction(&Pubkey::new_unique(), &swap_details, 50)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("route_hints"));
+    }
+
     #[tokio::test]
     async fn test_build_instruction_structure() {
         let dex = DexAggregator::new();
@@ -0,0 +1,352 @@
+//! Encrypted Intent Transport Envelope
+//!
+//! Wallets can seal a signed intent to the router's published X25519 key so
+//! intermediate proxies (ingress, CDNs, relays) never see swap sizes:
+//! - an ephemeral X25519 key agrees a shared secret with the router key
+//! - HKDF-SHA256 (salt: ephemeral ‖ router public key) derives the
+//!   ChaCha20-Poly1305 key
+//! - `key_id` and the ephemeral key are bound as associated data
+//!
+//! The router keeps an [`EnvelopeKeyring`]: rotating generates a new current
+//! key while the previous ones keep opening in-flight envelopes until they
+//! fall out of the keyring. [`decode_submission`] accepts both sealed and
+//! plain JSON bodies.
+//!
+//! This is the library half only: the API service that serves
+//! `POST /api/v1/intents` and `GET /api/v1/envelope/key` (the endpoints the
+//! SDK's `submit_sealed` and `envelope_key` call) is not part of this
+//! repository, and nothing here accepts sealed submissions over the network.
+//! That service decodes request bodies with [`decode_submission`] and
+//! publishes [`EnvelopeKeyring::current`].
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::intent_decode::{check_json, decode_json, JsonLimits};
+use crate::{Result, SentinelError};
+
+/// Envelope format version
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// HKDF info string (domain separation)
+const HKDF_INFO: &[u8] = b"sentinel-intent-envelope-v1";
+
+/// Keys kept by default (current + one previous)
+const DEFAULT_MAX_KEYS: usize = 2;
+
+/// Router public key as published to wallets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishedKey {
+    pub key_id: String,
+
+    /// Base64 X25519 public key
+    pub public_key: String,
+}
+
+impl PublishedKey {
+    fn decode(&self) -> Result<PublicKey> {
+        Ok(PublicKey::from(decode_32(&self.public_key, "public key")?))
+    }
+}
+
+/// Intent payload sealed to a router key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedEnvelope {
+    pub envelope_version: u8,
+    pub key_id: String,
+
+    /// Base64 sender ephemeral X25519 public key
+    pub ephemeral_public_key: String,
+
+    /// Base64 12-byte nonce
+    pub nonce: String,
+
+    /// Base64 ChaCha20-Poly1305 ciphertext + tag
+    pub ciphertext: String,
+}
+
+impl SealedEnvelope {
+    /// Seal `plaintext` to the published router key
+    pub fn seal(plaintext: &[u8], recipient: &PublishedKey) -> Result<Self> {
+        let router_public = recipient.decode()?;
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let shared = ephemeral.diffie_hellman(&router_public);
+
+        let cipher = cipher(shared.as_bytes(), &ephemeral_public, &router_public)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(&recipient.key_id, &ephemeral_public);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: &aad })
+            .map_err(|_| envelope_error("encryption failed"))?;
+
+        Ok(Self {
+            envelope_version: ENVELOPE_VERSION,
+            key_id: recipient.key_id.clone(),
+            ephemeral_public_key: BASE64.encode(ephemeral_public.as_bytes()),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Serialize `value` as JSON and seal it
+    pub fn seal_json<T: Serialize>(value: &T, recipient: &PublishedKey) -> Result<Self> {
+        let plaintext = serde_json::to_vec(value).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        Self::seal(&plaintext, recipient)
+    }
+}
+
+/// Router key pair
+struct RouterKey {
+    key_id: String,
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl RouterKey {
+    fn new(secret: StaticSecret) -> Self {
+        let public = PublicKey::from(&secret);
+        let key_id = blake3::hash(public.as_bytes()).to_hex()[..16].to_string();
+        Self { key_id, secret, public }
+    }
+}
+
+/// Router decryption keys, newest first
+pub struct EnvelopeKeyring {
+    keys: Vec<RouterKey>,
+    max_keys: usize,
+}
+
+impl Default for EnvelopeKeyring {
+    fn default() -> Self {
+        Self::generate()
+    }
+}
+
+impl EnvelopeKeyring {
+    /// Keyring with a freshly generated key
+    pub fn generate() -> Self {
+        Self::from_secret(StaticSecret::random_from_rng(OsRng).to_bytes())
+    }
+
+    /// Keyring from persisted 32-byte secret key material
+    pub fn from_secret(secret: [u8; 32]) -> Self {
+        Self {
+            keys: vec![RouterKey::new(StaticSecret::from(secret))],
+            max_keys: DEFAULT_MAX_KEYS,
+        }
+    }
+
+    /// Number of keys (current + previous) that still open envelopes
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys.max(1);
+        self.keys.truncate(self.max_keys);
+        self
+    }
+
+    /// Key wallets should seal to
+    pub fn current(&self) -> PublishedKey {
+        publish(&self.keys[0])
+    }
+
+    /// Every key still accepted, newest first
+    pub fn published(&self) -> Vec<PublishedKey> {
+        self.keys.iter().map(publish).collect()
+    }
+
+    /// Secret of the current key (for persistence)
+    pub fn current_secret(&self) -> [u8; 32] {
+        self.keys[0].secret.to_bytes()
+    }
+
+    /// Generate a new current key; the oldest key beyond `max_keys` is dropped
+    pub fn rotate(&mut self) -> PublishedKey {
+        self.add_key(StaticSecret::random_from_rng(OsRng).to_bytes())
+    }
+
+    /// Make persisted secret material the current key
+    pub fn add_key(&mut self, secret: [u8; 32]) -> PublishedKey {
+        self.keys.insert(0, RouterKey::new(StaticSecret::from(secret)));
+        if let Some(retired) = self.keys.get(self.max_keys) {
+            tracing::info!("🔑 Envelope key {} retired", retired.key_id);
+        }
+        self.keys.truncate(self.max_keys);
+        tracing::info!("🔑 Envelope key rotated to {}", self.keys[0].key_id);
+        self.current()
+    }
+
+    /// Decrypt an envelope addressed to any key in the ring
+    pub fn open(&self, envelope: &SealedEnvelope) -> Result<Vec<u8>> {
+        if envelope.envelope_version != ENVELOPE_VERSION {
+            return Err(envelope_error(&format!(
+                "unsupported envelope version {}",
+                envelope.envelope_version
+            )));
+        }
+        let key = self
+            .keys
+            .iter()
+            .find(|k| k.key_id == envelope.key_id)
+            .ok_or_else(|| envelope_error(&format!("unknown or retired key {}", envelope.key_id)))?;
+
+        let ephemeral_public = PublicKey::from(decode_32(&envelope.ephemeral_public_key, "ephemeral key")?);
+        let nonce = BASE64
+            .decode(&envelope.nonce)
+            .ok()
+            .filter(|n| n.len() == 12)
+            .ok_or_else(|| envelope_error("invalid nonce"))?;
+        let ciphertext = BASE64
+            .decode(&envelope.ciphertext)
+            .map_err(|_| envelope_error("invalid ciphertext encoding"))?;
+
+        let shared = key.secret.diffie_hellman(&ephemeral_public);
+        let cipher = cipher(shared.as_bytes(), &ephemeral_public, &key.public)?;
+        let aad = associated_data(&key.key_id, &ephemeral_public);
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| envelope_error("decryption failed (tampered or wrong key)"))
+    }
+
+    /// Decrypt and deserialize a JSON payload (checked against [`JsonLimits`])
+    pub fn open_json<T: DeserializeOwned>(&self, envelope: &SealedEnvelope) -> Result<T> {
+        let plaintext = self.open(envelope)?;
+        Ok(decode_json(&plaintext, &JsonLimits::default())?)
+    }
+}
+
+/// Intake: decode a submission body that is either a sealed envelope or plain JSON
+///
+/// Sealed bodies are rejected when no keyring is configured.
+pub fn decode_submission<T: DeserializeOwned>(body: &[u8], keyring: Option<&EnvelopeKeyring>) -> Result<T> {
+    let limits = JsonLimits::default();
+    // Sealed ciphertext is one long string; its plaintext is checked strictly once opened
+    check_json(body, &JsonLimits { max_string_len: limits.max_bytes, ..limits })?;
+    let value: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+
+    if value.get("envelope_version").is_none() {
+        check_json(body, &limits)?;
+        return serde_json::from_value(value).map_err(|e| SentinelError::SerializationError(e.to_string()));
+    }
+
+    let keyring = keyring.ok_or_else(|| envelope_error("sealed submissions are not enabled"))?;
+    let envelope: SealedEnvelope =
+        serde_json::from_value(value).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+    keyring.open_json(&envelope)
+}
+
+fn publish(key: &RouterKey) -> PublishedKey {
+    PublishedKey {
+        key_id: key.key_id.clone(),
+        public_key: BASE64.encode(key.public.as_bytes()),
+    }
+}
+
+fn cipher(shared: &[u8; 32], ephemeral: &PublicKey, router: &PublicKey) -> Result<ChaCha20Poly1305> {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral.as_bytes());
+    salt[32..].copy_from_slice(router.as_bytes());
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(HKDF_INFO, &mut key)
+        .map_err(|_| envelope_error("key derivation failed"))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+fn associated_data(key_id: &str, ephemeral: &PublicKey) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(key_id.len() + 32);
+    aad.extend_from_slice(key_id.as_bytes());
+    aad.extend_from_slice(ephemeral.as_bytes());
+    aad
+}
+
+fn decode_32(encoded: &str, what: &str) -> Result<[u8; 32]> {
+    BASE64
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| envelope_error(&format!("invalid {}", what)))
+}
+
+fn envelope_error(reason: &str) -> SentinelError {
+    SentinelError::EnvelopeError(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        amount: u64,
+    }
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let keyring = EnvelopeKeyring::generate();
+        let envelope = SealedEnvelope::seal_json(&Payload { amount: 1_000_000 }, &keyring.current()).unwrap();
+
+        assert!(!envelope.ciphertext.contains("1000000"));
+        let opened: Payload = keyring.open_json(&envelope).unwrap();
+        assert_eq!(opened, Payload { amount: 1_000_000 });
+    }
+
+    #[test]
+    fn test_tampering_rejected() {
+        let keyring = EnvelopeKeyring::generate();
+        let mut envelope = SealedEnvelope::seal(b"swap", &keyring.current()).unwrap();
+
+        let mut ciphertext = BASE64.decode(&envelope.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        envelope.ciphertext = BASE64.encode(ciphertext);
+        assert!(matches!(keyring.open(&envelope), Err(SentinelError::EnvelopeError(_))));
+
+        // Sealed to another router
+        let other = SealedEnvelope::seal(b"swap", &EnvelopeKeyring::generate().current()).unwrap();
+        assert!(keyring.open(&other).is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_previous_key() {
+        let mut keyring = EnvelopeKeyring::generate();
+        let old = SealedEnvelope::seal(b"in-flight", &keyring.current()).unwrap();
+
+        let new_key = keyring.rotate();
+        assert_ne!(new_key.key_id, old.key_id);
+        assert_eq!(keyring.published().len(), 2);
+        assert_eq!(keyring.open(&old).unwrap(), b"in-flight");
+
+        // A second rotation retires the original key
+        keyring.rotate();
+        assert!(keyring.open(&old).is_err());
+    }
+
+    #[test]
+    fn test_persisted_secret_reproduces_key() {
+        let keyring = EnvelopeKeyring::generate();
+        let restored = EnvelopeKeyring::from_secret(keyring.current_secret());
+        assert_eq!(restored.current(), keyring.current());
+    }
+
+    #[test]
+    fn test_intake_accepts_plain_and_sealed() {
+        let keyring = EnvelopeKeyring::generate();
+        let plain: Payload = decode_submission(br#"{"amount": 5}"#, Some(&keyring)).unwrap();
+        assert_eq!(plain.amount, 5);
+
+        let sealed = serde_json::to_vec(&SealedEnvelope::seal_json(&Payload { amount: 7 }, &keyring.current()).unwrap())
+            .unwrap();
+        let opened: Payload = decode_submission(&sealed, Some(&keyring)).unwrap();
+        assert_eq!(opened.amount, 7);
+
+        assert!(decode_submission::<Payload>(&sealed, None).is_err());
+    }
+}
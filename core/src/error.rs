@@ -8,6 +8,9 @@ pub enum SentinelError {
     #[error("Intent validation error: {0}")]
     IntentValidation(#[from] crate::intent::IntentError),
 
+    #[error("Intent decoding error: {0}")]
+    IntentDecode(#[from] crate::intent_decode::DecodeError),
+
     #[error("Ingestion error: {0}")]
     IngestionError(String),
 
@@ -44,6 +47,30 @@ pub enum SentinelError {
     #[error("DEX error: {0}")]
     DexError(String),
 
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("I/O error: {0}")]
+    IoError(String),
+
+    #[error("Envelope error: {0}")]
+    EnvelopeError(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Rejected by policy rule {rule} for {subject}: {reason}")]
+    PolicyRejected { rule: String, subject: String, reason: String },
+
+    #[error("Rate limited: {reason} (retry after {retry_after_ms}ms)")]
+    RateLimited { reason: String, retry_after_ms: u64 },
+
+    #[error("{resource} is claimed by {owner}")]
+    ClaimConflict { resource: String, owner: String },
+
+    #[error("Circuit open for {dependency} (retry after {retry_after_ms}ms)")]
+    CircuitOpen { dependency: String, retry_after_ms: u64 },
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
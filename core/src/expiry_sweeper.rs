@@ -0,0 +1,247 @@
+//! Intent Expiry Sweeper
+//!
+//! Limit, TWAP and DCA intents wait in the registry until their condition
+//! fires; nothing moves them once their `expiry_timestamp` (or registration
+//! plus `ttl_seconds`) passes. Every `interval`, [`ExpirySweeper`]:
+//! - marks each tenant's overdue pending intents `Expired`, appending an
+//!   `expired` timeline event ([`IntentRegistry::expire_due`])
+//! - withdraws their held transactions from the [`SignedTxVault`] and returns
+//!   their nonce accounts to the [`NonceManager`]
+//! - publishes the events on the [`TimelineFeed`], so WebSocket subscribers
+//!   see the transition
+//! - deletes vault entries past their hold ([`SignedTxVault::sweep_expired`])
+//!
+//! [`IntentRegistry::expire_due`]: crate::IntentRegistry::expire_due
+
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::nonce_manager::NonceManager;
+use crate::signed_tx_vault::{SignedTxVault, VaultStatus};
+use crate::tenant::TenantRegistry;
+use crate::timeline::{TimelineFeed, TimelineUpdate};
+
+/// Default time between sweeps
+pub const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Expires overdue pending intents and releases what they held
+pub struct ExpirySweeper {
+    tenants: Arc<TenantRegistry>,
+    vault: Option<Arc<SignedTxVault>>,
+    nonces: Option<NonceManager>,
+    feed: Option<TimelineFeed>,
+    clock: SharedClock,
+    interval: Duration,
+}
+
+impl ExpirySweeper {
+    pub fn new(tenants: Arc<TenantRegistry>) -> Self {
+        Self {
+            tenants,
+            vault: None,
+            nonces: None,
+            feed: None,
+            clock: system_clock(),
+            interval: DEFAULT_SWEEP_INTERVAL,
+        }
+    }
+
+    /// Withdraw expired intents' held transactions
+    pub fn with_vault(mut self, vault: Arc<SignedTxVault>) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Release expired intents' nonce reservations
+    pub fn with_nonces(mut self, nonces: NonceManager) -> Self {
+        self.nonces = Some(nonces);
+        self
+    }
+
+    /// Notify stream subscribers of expirations
+    pub fn with_feed(mut self, feed: TimelineFeed) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// One pass over every tenant; returns the `Expired` events published
+    pub async fn sweep(&self) -> Vec<TimelineUpdate> {
+        let now = self.clock.unix_timestamp();
+        let mut expired: Vec<(TimelineUpdate, Pubkey)> = Vec::new();
+        for tenant in self.tenants.tenants() {
+            let registry = tenant.intents();
+            for update in registry.expire_due(now) {
+                let Some(entry) = registry.get(&update.intent_id) else {
+                    continue;
+                };
+                info!("⌛ Intent {} expired (tenant {})", update.intent_id, tenant.id());
+                expired.push((update, entry.intent.user_public_key));
+            }
+        }
+
+        if let Some(vault) = &self.vault {
+            let vault = Arc::clone(vault);
+            let held: Vec<(String, Pubkey)> = expired.iter().map(|(u, user)| (u.intent_id.clone(), *user)).collect();
+            // Vault calls block on the store
+            let swept = tokio::task::spawn_blocking(move || {
+                for (intent_id, user) in held {
+                    let withdrawn = match vault.get(&intent_id) {
+                        Ok(Some(entry)) if entry.status == VaultStatus::Held => {
+                            vault.cancel(&intent_id, &user).map(|_| ())
+                        }
+                        Ok(_) => Ok(()),
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = withdrawn {
+                        warn!("Failed to withdraw signed transaction of expired intent {}: {}", intent_id, e);
+                    }
+                }
+                vault.sweep_expired()
+            })
+            .await;
+            match swept {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => warn!("Vault sweep failed: {}", e),
+                Err(e) => warn!("Vault sweep panicked: {}", e),
+            }
+        }
+
+        if let Some(nonces) = &self.nonces {
+            for (update, _) in &expired {
+                nonces.release(&update.intent_id).await;
+            }
+        }
+
+        let updates: Vec<TimelineUpdate> = expired.into_iter().map(|(update, _)| update).collect();
+        if let Some(feed) = &self.feed {
+            for update in &updates {
+                feed.publish(update.clone());
+            }
+        }
+        updates
+    }
+
+    /// Sweep every `interval` until the task is aborted
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                self.sweep().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::config::SentinelConfig;
+    use crate::intent::{Constraints, Intent, IntentStatus, IntentType, LimitDetails};
+    use crate::nonce_manager::NonceAccountInfo;
+    use crate::storage::MemoryKv;
+    use crate::tenant::DEFAULT_TENANT_ID;
+    use crate::timeline::TimelineEvent;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::{Keypair, Signer};
+    use solana_sdk::transaction::Transaction;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn limit_intent(user: &Keypair, id: &str, ttl_seconds: u32) -> Intent {
+        let mut intent = Intent {
+            intent_id: id.to_string(),
+            user_public_key: user.pubkey(),
+            intent_type: IntentType::Limit,
+            constraints: Constraints {
+                ttl_seconds: Some(ttl_seconds),
+                ..Constraints::default()
+            },
+            limit_details: Some(LimitDetails {
+                price_threshold: 100.0,
+                oracle: None,
+            }),
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.input_mint = Pubkey::new_from_array([1; 32]);
+        swap.output_mint = Pubkey::new_from_array([2; 32]);
+        swap.minimum_received = None;
+        intent
+    }
+
+    #[tokio::test]
+    async fn test_sweep_expires_releases_and_notifies() {
+        let clock = Arc::new(ManualClock::at_timestamp(NOW));
+        let tenants = Arc::new(TenantRegistry::from_config(&SentinelConfig::default()));
+        let registry = tenants.tenant(DEFAULT_TENANT_ID).unwrap();
+        let user = Keypair::new();
+        registry.intents().register(limit_intent(&user, "short", 60), NOW).unwrap();
+        registry.intents().register(limit_intent(&user, "long", 3_600), NOW).unwrap();
+
+        // A held transaction built on a reserved nonce account
+        let vault = Arc::new(SignedTxVault::new(Arc::new(MemoryKv::new()), [7; 32]).with_clock(clock.clone()));
+        let nonce_account = Pubkey::new_unique();
+        #[allow(deprecated)]
+        let transaction = Transaction::new_signed_with_payer(
+            &[solana_sdk::system_instruction::advance_nonce_account(&nonce_account, &user.pubkey())],
+            Some(&user.pubkey()),
+            &[&user],
+            Hash::new_unique(),
+        );
+        vault.deposit(&registry.intents().get("short").unwrap().intent, &transaction).unwrap();
+        let nonces = NonceManager::new("http://localhost:8899".to_string());
+        nonces
+            .add_nonce_account(NonceAccountInfo {
+                address: nonce_account,
+                current_nonce: Hash::new_unique(),
+                authority: user.pubkey(),
+                lamports: 1_000_000,
+                last_updated: NOW,
+            })
+            .await;
+        nonces.reserve("short").await.unwrap();
+
+        let feed = TimelineFeed::default();
+        let mut rx = feed.subscribe();
+        let sweeper = ExpirySweeper::new(tenants)
+            .with_vault(vault.clone())
+            .with_nonces(nonces.clone())
+            .with_feed(feed)
+            .with_clock(clock.clone());
+
+        assert!(sweeper.sweep().await.is_empty());
+        clock.advance(Duration::from_secs(60));
+        let expired = sweeper.sweep().await;
+        assert_eq!(expired.len(), 1);
+
+        assert_eq!(registry.intents().status("short"), Some(IntentStatus::Expired));
+        assert_eq!(registry.intents().status("long"), Some(IntentStatus::Pending));
+        // Withdrawn, then deleted with the vault's own expiry
+        assert!(vault.get("short").unwrap().is_none());
+        assert!(nonces.reserve("other").await.is_some());
+
+        let update = rx.recv().await.unwrap();
+        assert_eq!((update.intent_id.as_str(), update.entry.event), ("short", TimelineEvent::Expired));
+        assert_eq!(update.entry.timestamp_ms, (NOW + 60) * 1_000);
+
+        // Expired once only
+        assert!(sweeper.sweep().await.is_empty());
+    }
+}
@@ -0,0 +1,456 @@
+//! Fee Payers and Sponsored Transactions
+//!
+//! Integrators can pay fees for their users. A [`FeePayerProvider`] decides,
+//! per intent, who pays the network fee and who pays the Jito tip:
+//! - **user**: the user pays everything (no sponsor configured)
+//! - **sponsor**: the sponsor is the transaction fee payer and tips too
+//! - **hybrid**: the sponsor tips, the user pays base and priority fee
+//!
+//! [`SponsorLedger`] implements it from the `[[sponsors]]` config, with
+//! tenants pointing at their sponsor. The sponsor's share of the estimated
+//! fees is reserved against its caps at assignment (a per-intent cap and a
+//! UTC-day cap including outstanding reservations), then replaced by the
+//! actual share on [`settle`](FeePayerProvider::settle), which returns the
+//! accounting entries for the fill report
+//! ([`FillReport::with_fee_entries`](crate::FillReport::with_fee_entries)).
+//! Intents that never land [`release`](FeePayerProvider::release) theirs.
+//! Reservations are per (user, intent id); a second one for the same pair is
+//! refused rather than replacing the first.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+use tracing::{info, warn};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{FeePayerMode, SentinelConfig, SponsorConfig};
+use crate::fill_report::{FeeComponent, FeeEntry, FeesPaid};
+use crate::intent::Intent;
+use crate::{Result, SentinelError};
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Who pays what for one intent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeAssignment {
+    pub intent_id: String,
+
+    /// Intent's user; with `intent_id`, the key of the sponsor's reservation
+    #[serde(default)]
+    pub user: Pubkey,
+
+    pub mode: FeePayerMode,
+
+    /// Transaction fee payer (base + priority fee)
+    pub fee_payer: Pubkey,
+
+    /// Source of the Jito tip transfer
+    pub tip_payer: Pubkey,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsor: Option<String>,
+
+    /// Sponsor share of the estimated fees, reserved against its caps
+    pub reserved_lamports: u64,
+}
+
+impl FeeAssignment {
+    /// The user pays everything
+    pub fn user_paid(intent: &Intent) -> Self {
+        Self {
+            intent_id: intent.intent_id.clone(),
+            user: intent.user_public_key,
+            mode: FeePayerMode::User,
+            fee_payer: intent.user_public_key,
+            tip_payer: intent.user_public_key,
+            sponsor: None,
+            reserved_lamports: 0,
+        }
+    }
+
+    fn reservation_key(&self) -> ReservationKey {
+        (self.user, self.intent_id.clone())
+    }
+
+    /// Lamports of `fees` the sponsor pays
+    pub fn sponsor_share(&self, fees: &FeesPaid) -> u64 {
+        match self.mode {
+            FeePayerMode::User => 0,
+            FeePayerMode::Sponsor => fees.total_lamports(),
+            FeePayerMode::Hybrid => fees.jito_tip_lamports,
+        }
+    }
+
+    /// Accounting entries for `fees`, one per non-zero component
+    pub fn entries(&self, fees: &FeesPaid) -> Vec<FeeEntry> {
+        let components = [
+            (FeeComponent::BaseFee, fees.base_fee_lamports, self.fee_payer),
+            (FeeComponent::PriorityFee, fees.priority_fee_lamports, self.fee_payer),
+            (FeeComponent::JitoTip, fees.jito_tip_lamports, self.tip_payer),
+        ];
+        let sponsored = |component: FeeComponent| match self.mode {
+            FeePayerMode::User => false,
+            FeePayerMode::Sponsor => true,
+            FeePayerMode::Hybrid => component == FeeComponent::JitoTip,
+        };
+
+        components
+            .into_iter()
+            .filter(|(_, lamports, _)| *lamports > 0)
+            .map(|(component, lamports, payer)| FeeEntry {
+                component,
+                payer: payer.to_string(),
+                sponsor: self.sponsor.clone().filter(|_| sponsored(component)),
+                lamports,
+            })
+            .collect()
+    }
+}
+
+/// Decides and accounts for who pays an intent's fees
+pub trait FeePayerProvider: Send + Sync {
+    /// Assign payers for one of `tenant_id`'s intents, reserving the sponsor's
+    /// share of `estimate`
+    fn assign(&self, tenant_id: &str, intent: &Intent, estimate: &FeesPaid) -> Result<FeeAssignment>;
+
+    /// Charge the fees actually paid and return the accounting entries
+    fn settle(&self, assignment: &FeeAssignment, paid: &FeesPaid) -> Vec<FeeEntry>;
+
+    /// Drop the reservation of an intent that did not land
+    fn release(&self, assignment: &FeeAssignment);
+}
+
+/// Users pay their own fees
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UserPaysFees;
+
+impl FeePayerProvider for UserPaysFees {
+    fn assign(&self, _tenant_id: &str, intent: &Intent, _estimate: &FeesPaid) -> Result<FeeAssignment> {
+        Ok(FeeAssignment::user_paid(intent))
+    }
+
+    fn settle(&self, assignment: &FeeAssignment, paid: &FeesPaid) -> Vec<FeeEntry> {
+        assignment.entries(paid)
+    }
+
+    fn release(&self, _assignment: &FeeAssignment) {}
+}
+
+/// Intent ids are chosen by clients, so reservations are per user
+type ReservationKey = (Pubkey, String);
+
+#[derive(Debug)]
+struct SponsorAccount {
+    config: SponsorConfig,
+    payer: Pubkey,
+
+    /// UTC day `spent_lamports` belongs to
+    day: i64,
+    spent_lamports: u64,
+
+    /// Outstanding reservations by (user, intent id)
+    reserved: HashMap<ReservationKey, u64>,
+}
+
+impl SponsorAccount {
+    fn roll(&mut self, day: i64) {
+        if day != self.day {
+            self.day = day;
+            self.spent_lamports = 0;
+        }
+    }
+
+    fn committed(&self) -> u64 {
+        let reserved = self.reserved.values().fold(0u64, |total, lamports| total.saturating_add(*lamports));
+        self.spent_lamports.saturating_add(reserved)
+    }
+}
+
+#[derive(Debug, Default)]
+struct LedgerState {
+    sponsors: HashMap<String, SponsorAccount>,
+
+    /// Tenant id → sponsor id
+    tenants: HashMap<String, String>,
+}
+
+/// Config-driven sponsors with per-intent and daily spending caps
+#[derive(Debug)]
+pub struct SponsorLedger {
+    state: Mutex<LedgerState>,
+    clock: SharedClock,
+}
+
+impl SponsorLedger {
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        let ledger = Self {
+            state: Mutex::new(LedgerState::default()),
+            clock: system_clock(),
+        };
+        ledger.apply_config(config);
+        ledger
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Apply the hot-reloadable `[[sponsors]]` and `tenants.sponsor` settings
+    ///
+    /// Surviving sponsors keep today's spend and their reservations.
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        let day = self.today();
+        let mut state = self.lock();
+        let mut previous = std::mem::take(&mut state.sponsors);
+
+        for sponsor in &config.sponsors {
+            // Validated by SentinelConfig::validate
+            let Ok(payer) = Pubkey::from_str(&sponsor.payer) else {
+                warn!("Sponsor {} has an invalid payer; skipped", sponsor.id);
+                continue;
+            };
+            let account = match previous.remove(&sponsor.id) {
+                Some(mut account) => {
+                    account.config = sponsor.clone();
+                    account.payer = payer;
+                    account
+                }
+                None => SponsorAccount {
+                    config: sponsor.clone(),
+                    payer,
+                    day,
+                    spent_lamports: 0,
+                    reserved: HashMap::new(),
+                },
+            };
+            state.sponsors.insert(sponsor.id.clone(), account);
+        }
+
+        state.tenants = config
+            .tenants
+            .iter()
+            .filter_map(|tenant| Some((tenant.id.clone(), tenant.sponsor.clone()?)))
+            .collect();
+        info!("Sponsor ledger: {} sponsors, {} sponsored tenants", state.sponsors.len(), state.tenants.len());
+    }
+
+    /// Lamports spent today plus outstanding reservations
+    pub fn committed_today(&self, sponsor_id: &str) -> Option<u64> {
+        let day = self.today();
+        let mut state = self.lock();
+        let account = state.sponsors.get_mut(sponsor_id)?;
+        account.roll(day);
+        Some(account.committed())
+    }
+
+    fn today(&self) -> i64 {
+        self.clock.unix_millis().div_euclid(DAY_MS)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LedgerState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+impl FeePayerProvider for SponsorLedger {
+    fn assign(&self, tenant_id: &str, intent: &Intent, estimate: &FeesPaid) -> Result<FeeAssignment> {
+        let now_ms = self.clock.unix_millis();
+        let mut state = self.lock();
+        let Some(sponsor_id) = state.tenants.get(tenant_id).cloned() else {
+            return Ok(FeeAssignment::user_paid(intent));
+        };
+        let Some(account) = state.sponsors.get_mut(&sponsor_id) else {
+            return Ok(FeeAssignment::user_paid(intent));
+        };
+
+        let mut assignment = FeeAssignment {
+            intent_id: intent.intent_id.clone(),
+            user: intent.user_public_key,
+            mode: account.config.mode,
+            fee_payer: intent.user_public_key,
+            tip_payer: account.payer,
+            sponsor: Some(sponsor_id.clone()),
+            reserved_lamports: 0,
+        };
+        if assignment.mode == FeePayerMode::Sponsor {
+            assignment.fee_payer = account.payer;
+        }
+        let share = assignment.sponsor_share(estimate);
+        let key = assignment.reservation_key();
+        if account.reserved.contains_key(&key) {
+            return Err(SentinelError::PolicyRejected {
+                rule: "sponsor_reservation".to_string(),
+                subject: intent.intent_id.clone(),
+                reason: format!("intent already holds a reservation with sponsor {}", sponsor_id),
+            });
+        }
+
+        let per_intent = account.config.max_lamports_per_intent;
+        if per_intent > 0 && share > per_intent {
+            return Err(SentinelError::PolicyRejected {
+                rule: "sponsor_intent_cap".to_string(),
+                subject: intent.intent_id.clone(),
+                reason: format!("{} lamports exceeds sponsor {}'s {} per intent", share, sponsor_id, per_intent),
+            });
+        }
+
+        let day = now_ms.div_euclid(DAY_MS);
+        account.roll(day);
+        let daily = account.config.daily_cap_lamports;
+        if daily > 0 && account.committed().saturating_add(share) > daily {
+            return Err(SentinelError::RateLimited {
+                reason: format!("sponsor {} reached its {} lamport daily cap", sponsor_id, daily),
+                retry_after_ms: ((day + 1) * DAY_MS - now_ms) as u64,
+            });
+        }
+
+        account.reserved.insert(key, share);
+        assignment.reserved_lamports = share;
+        Ok(assignment)
+    }
+
+    fn settle(&self, assignment: &FeeAssignment, paid: &FeesPaid) -> Vec<FeeEntry> {
+        if let Some(ref sponsor_id) = assignment.sponsor {
+            let day = self.today();
+            let mut state = self.lock();
+            if let Some(account) = state.sponsors.get_mut(sponsor_id) {
+                account.roll(day);
+                account.reserved.remove(&assignment.reservation_key());
+                account.spent_lamports = account.spent_lamports.saturating_add(assignment.sponsor_share(paid));
+            }
+        }
+        assignment.entries(paid)
+    }
+
+    fn release(&self, assignment: &FeeAssignment) {
+        if let Some(ref sponsor_id) = assignment.sponsor {
+            if let Some(account) = self.lock().sponsors.get_mut(sponsor_id) {
+                account.reserved.remove(&assignment.reservation_key());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::config::TenantConfig;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn intent(id: &str) -> Intent {
+        Intent {
+            intent_id: id.to_string(),
+            swap_details: None,
+            ..Intent::test_swap()
+        }
+    }
+
+    fn fees(tip: u64) -> FeesPaid {
+        FeesPaid {
+            base_fee_lamports: 5_000,
+            priority_fee_lamports: 10_000,
+            jito_tip_lamports: tip,
+            dex_fee_amount: 0,
+        }
+    }
+
+    fn config(payer: &Pubkey, mode: FeePayerMode) -> SentinelConfig {
+        SentinelConfig {
+            sponsors: vec![SponsorConfig {
+                id: "acme".to_string(),
+                payer: payer.to_string(),
+                mode,
+                max_lamports_per_intent: 100_000,
+                daily_cap_lamports: 200_000,
+            }],
+            tenants: vec![TenantConfig {
+                id: "acme-wallet".to_string(),
+                sponsor: Some("acme".to_string()),
+                ..TenantConfig::default()
+            }],
+            ..SentinelConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_hybrid_sponsor_pays_tip_only() {
+        let sponsor = Pubkey::new_unique();
+        let ledger = SponsorLedger::from_config(&config(&sponsor, FeePayerMode::Hybrid));
+        let intent = intent("a");
+
+        let assignment = ledger.assign("acme-wallet", &intent, &fees(50_000)).unwrap();
+        assert_eq!((assignment.fee_payer, assignment.tip_payer), (intent.user_public_key, sponsor));
+        assert_eq!(assignment.reserved_lamports, 50_000);
+        assert_eq!(ledger.committed_today("acme"), Some(50_000));
+
+        // Settled at the actual tip
+        let entries = ledger.settle(&assignment, &fees(40_000));
+        assert_eq!(entries.len(), 3);
+        let tip = entries.iter().find(|e| e.component == FeeComponent::JitoTip).unwrap();
+        assert_eq!((tip.payer.as_str(), tip.sponsor.as_deref()), (sponsor.to_string().as_str(), Some("acme")));
+        assert!(entries.iter().filter(|e| e.component != FeeComponent::JitoTip).all(|e| e.sponsor.is_none()));
+        assert_eq!(ledger.committed_today("acme"), Some(40_000));
+
+        // Tenants without a sponsor pay their own way
+        let own = ledger.assign("other", &intent, &fees(50_000)).unwrap();
+        assert_eq!((own.mode, own.tip_payer), (FeePayerMode::User, intent.user_public_key));
+    }
+
+    #[test]
+    fn test_sponsor_caps() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let sponsor = Pubkey::new_unique();
+        let ledger = SponsorLedger::from_config(&config(&sponsor, FeePayerMode::Sponsor)).with_clock(clock.clone());
+
+        // Sponsor mode pays everything: 15_000 fees + tip
+        let first = ledger.assign("acme-wallet", &intent("a"), &fees(75_000)).unwrap();
+        assert_eq!((first.fee_payer, first.reserved_lamports), (sponsor, 90_000));
+        let result = ledger.assign("acme-wallet", &intent("b"), &fees(90_000));
+        assert!(matches!(result, Err(SentinelError::PolicyRejected { .. })));
+
+        let second = ledger.assign("acme-wallet", &intent("c"), &fees(75_000)).unwrap();
+        let result = ledger.assign("acme-wallet", &intent("d"), &fees(10_000));
+        assert!(matches!(result, Err(SentinelError::RateLimited { .. })));
+
+        // A released reservation frees the cap; a reload keeps the books
+        ledger.release(&first);
+        ledger.assign("acme-wallet", &intent("d"), &fees(10_000)).unwrap();
+        ledger.settle(&second, &fees(75_000));
+        ledger.apply_config(&config(&sponsor, FeePayerMode::Sponsor));
+        assert_eq!(ledger.committed_today("acme"), Some(115_000));
+
+        // Spend resets with the UTC day; outstanding reservations carry over
+        clock.advance(Duration::from_secs(86_400));
+        assert_eq!(ledger.committed_today("acme"), Some(25_000));
+    }
+
+    #[test]
+    fn test_reservations_keyed_by_user_and_intent() {
+        let sponsor = Pubkey::new_unique();
+        let ledger = SponsorLedger::from_config(&config(&sponsor, FeePayerMode::Sponsor));
+        let user = intent("a");
+        let mut other_user = user.clone();
+        other_user.user_public_key = Pubkey::new_unique();
+
+        // Reusing an intent id cannot overwrite the open reservation and slip past the daily cap
+        let first = ledger.assign("acme-wallet", &user, &fees(75_000)).unwrap();
+        let result = ledger.assign("acme-wallet", &user, &fees(5_000));
+        assert!(
+            matches!(result, Err(SentinelError::PolicyRejected { ref rule, .. }) if rule == "sponsor_reservation")
+        );
+        let second = ledger.assign("acme-wallet", &other_user, &fees(75_000)).unwrap();
+        assert_eq!(ledger.committed_today("acme"), Some(180_000));
+        assert!(ledger.assign("acme-wallet", &intent("b"), &fees(10_000)).is_err());
+
+        // Releasing one user's intent leaves the other's reservation in place
+        ledger.release(&second);
+        assert_eq!(ledger.committed_today("acme"), Some(90_000));
+        ledger.settle(&first, &fees(75_000));
+        assert_eq!(ledger.committed_today("acme"), Some(90_000));
+    }
+}
@@ -0,0 +1,184 @@
+//! Risk-Adaptive Fee Splitting
+//!
+//! `FeePreferences::tip_allocation_pct` is the user's baseline share of the fee
+//! budget paid as a Jito tip. [`FeeSplitter`] adapts it to the decision at hand:
+//! - **route**: StandardRpc and Firedancer never reach the block engine, so the
+//!   whole budget goes to the priority fee
+//! - **risk**: above `neutral_risk` the tip share grows (bundle inclusion is what
+//!   protects a risky swap), below it the share shrinks, by at most
+//!   `max_risk_shift_pct` percentage points at risk 1.0 / 0.0
+//!
+//! Each side is capped by the user's signed maximums; budget one side can't
+//! absorb spills over to the other, up to that side's own cap.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TipPolicy;
+use crate::intent::{FeePreferences, FeeSplit};
+use crate::types::RouteType;
+
+/// Chosen split, recorded in the decision log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSplitDecision {
+    /// Intent's `tip_allocation_pct`
+    pub requested_tip_allocation_pct: u8,
+
+    /// Tip share after route and risk adjustment (0-100)
+    pub tip_allocation_pct: u8,
+
+    #[serde(flatten)]
+    pub split: FeeSplit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeSplitter {
+    neutral_risk: f32,
+    max_risk_shift_pct: u8,
+}
+
+impl Default for FeeSplitter {
+    fn default() -> Self {
+        Self::from_policy(&TipPolicy::default())
+    }
+}
+
+impl FeeSplitter {
+    pub fn new(neutral_risk: f32, max_risk_shift_pct: u8) -> Self {
+        Self {
+            neutral_risk: neutral_risk.clamp(0.0, 1.0),
+            max_risk_shift_pct: max_risk_shift_pct.min(100),
+        }
+    }
+
+    pub fn from_policy(policy: &TipPolicy) -> Self {
+        Self::new(policy.neutral_risk, policy.max_risk_shift_pct)
+    }
+
+    /// Tip share of the budget for `route` at `risk_score`
+    ///
+    /// Example (neutral 0.5, shift 30): 70% requested → 100% at risk 1.0, 55% at 0.25
+    pub fn tip_allocation_pct(&self, requested_pct: u8, risk_score: f32, route: &RouteType) -> u8 {
+        if !route.pays_tip() {
+            return 0;
+        }
+
+        let risk = if risk_score.is_finite() { risk_score.clamp(0.0, 1.0) } else { self.neutral_risk };
+        // Distance from neutral as a fraction of the room on that side
+        let deviation = if risk >= self.neutral_risk {
+            (risk - self.neutral_risk) / (1.0 - self.neutral_risk).max(f32::EPSILON)
+        } else {
+            (risk - self.neutral_risk) / self.neutral_risk.max(f32::EPSILON)
+        };
+        let shift = (deviation * self.max_risk_shift_pct as f32).round() as i32;
+
+        (requested_pct.min(100) as i32 + shift).clamp(0, 100) as u8
+    }
+
+    /// Divide `total_budget_lamports` between tip and priority fee within the user's caps
+    pub fn split(
+        &self,
+        preferences: &FeePreferences,
+        total_budget_lamports: u64,
+        risk_score: f32,
+        route: &RouteType,
+    ) -> FeeSplitDecision {
+        let pct = self.tip_allocation_pct(preferences.tip_allocation_pct, risk_score, route);
+        let max_tip = if route.pays_tip() { preferences.max_jito_tip_lamports } else { 0 };
+        let max_priority = preferences.max_priority_fee_lamports;
+
+        let target_tip = (total_budget_lamports as u128 * pct as u128 / 100) as u64;
+        let mut tip = target_tip.min(max_tip);
+        let mut priority = (total_budget_lamports - target_tip).min(max_priority);
+
+        // Hand what one side couldn't take to the other
+        let spare = total_budget_lamports - tip - priority;
+        if tip < target_tip {
+            priority = (priority + spare).min(max_priority);
+        } else {
+            tip = (tip + spare).min(max_tip);
+        }
+
+        FeeSplitDecision {
+            requested_tip_allocation_pct: preferences.tip_allocation_pct,
+            tip_allocation_pct: pct,
+            split: FeeSplit {
+                jito_tip_lamports: tip,
+                priority_fee_lamports: priority,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preferences(max_priority: u64, max_tip: u64) -> FeePreferences {
+        FeePreferences {
+            max_priority_fee_lamports: max_priority,
+            max_jito_tip_lamports: max_tip,
+            tip_allocation_pct: 70,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_risk_shifts_allocation() {
+        let splitter = FeeSplitter::default();
+        let route = RouteType::JitoBundle;
+
+        assert_eq!(splitter.tip_allocation_pct(70, 0.5, &route), 70);
+        assert_eq!(splitter.tip_allocation_pct(70, 1.0, &route), 100);
+        assert_eq!(splitter.tip_allocation_pct(70, 0.75, &route), 85);
+        assert_eq!(splitter.tip_allocation_pct(70, 0.25, &route), 55);
+        assert_eq!(splitter.tip_allocation_pct(70, 0.0, &route), 40);
+        assert_eq!(splitter.tip_allocation_pct(70, f32::NAN, &route), 70);
+        assert_eq!(FeeSplitter::new(0.5, 0).tip_allocation_pct(70, 1.0, &route), 70);
+    }
+
+    #[test]
+    fn test_non_tip_routes_pay_priority_only() {
+        let splitter = FeeSplitter::default();
+        let decision = splitter.split(&preferences(100_000, 50_000), 80_000, 0.9, &RouteType::StandardRpc);
+
+        assert_eq!(decision.requested_tip_allocation_pct, 70);
+        assert_eq!(decision.tip_allocation_pct, 0);
+        assert_eq!(decision.split.jito_tip_lamports, 0);
+        assert_eq!(decision.split.priority_fee_lamports, 80_000);
+        assert_eq!(
+            splitter.split(&preferences(100_000, 50_000), 80_000, 0.9, &RouteType::Firedancer).split.jito_tip_lamports,
+            0
+        );
+    }
+
+    #[test]
+    fn test_caps_with_spillover() {
+        let splitter = FeeSplitter::default();
+
+        // Neutral risk: 70/30 fits both caps
+        let split = splitter.split(&preferences(100_000, 100_000), 100_000, 0.5, &RouteType::JitoBundle).split;
+        assert_eq!((split.jito_tip_lamports, split.priority_fee_lamports), (70_000, 30_000));
+
+        // High risk wants 100% tip; the tip cap pushes the rest to priority
+        let split = splitter.split(&preferences(100_000, 60_000), 100_000, 1.0, &RouteType::JitoBundle).split;
+        assert_eq!((split.jito_tip_lamports, split.priority_fee_lamports), (60_000, 40_000));
+
+        // Low risk wants 40% tip; the priority cap pushes the rest to the tip
+        let split = splitter.split(&preferences(20_000, 100_000), 100_000, 0.0, &RouteType::JitoBundle).split;
+        assert_eq!((split.jito_tip_lamports, split.priority_fee_lamports), (80_000, 20_000));
+
+        // Both capped: the budget is never exceeded, only underspent
+        let split = splitter.split(&preferences(10_000, 10_000), 100_000, 0.5, &RouteType::JitoBundle).split;
+        assert_eq!((split.jito_tip_lamports, split.priority_fee_lamports), (10_000, 10_000));
+    }
+
+    #[test]
+    fn test_decision_serializes_flat() {
+        let decision = FeeSplitter::default().split(&preferences(100_000, 100_000), 10_000, 0.5, &RouteType::JitoSingle);
+        let json = serde_json::to_value(decision).unwrap();
+
+        assert_eq!(json["tip_allocation_pct"], 70);
+        assert_eq!(json["jito_tip_lamports"], 7_000);
+        assert_eq!(serde_json::from_value::<FeeSplitDecision>(json).unwrap(), decision);
+    }
+}
@@ -0,0 +1,425 @@
+//! Post-Trade Fill Reports
+//!
+//! A confirmed status says the swap landed, not how well it filled.
+//! `FillReport` is built from the confirmed transaction's token balance deltas
+//! (`getTransaction` `meta.preTokenBalances` / `postTokenBalances`) for the
+//! token accounts owned by the intent's user:
+//! - realized input/output amounts and slippage against the executed quote
+//!   (positive = worse than quoted, negative = price improvement)
+//! - fees paid: base + priority fee from `meta.fee`, the Jito tip, and DEX fees
+//!   not already reflected in the quoted amounts
+//! - an MEV-protection savings estimate: a sandwich extracts up to the user's
+//!   slippage tolerance, so the expected loss avoided is
+//!   `risk × (tolerance - realized slippage)` of the quoted amount
+//! - for sponsored intents, accounting entries saying who paid each fee
+//!   component (see [`FeeAssignment::entries`](crate::FeeAssignment::entries))
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::best_execution::VenueQuote;
+use crate::intent::{Intent, SwapMode};
+use crate::{Result, SentinelError};
+
+/// Base fee per transaction signature (lamports)
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Token account balance before or after the transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub account_index: u32,
+    pub mint: Pubkey,
+    pub owner: Option<Pubkey>,
+
+    /// Raw amount in base units
+    pub amount: u64,
+}
+
+/// The parts of a confirmed transaction a fill report needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmedTransaction {
+    pub signature: Signature,
+    pub slot: u64,
+    pub num_signatures: u64,
+
+    /// Total fee charged (base + priority, lamports)
+    pub fee_lamports: u64,
+
+    pub pre_token_balances: Vec<TokenBalance>,
+    pub post_token_balances: Vec<TokenBalance>,
+
+    /// Lamports per account index (`meta.preBalances` / `postBalances`)
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+}
+
+impl ConfirmedTransaction {
+    /// Parse a `getTransaction` result (`json` or `jsonParsed` encoding)
+    pub fn from_rpc_json(value: &serde_json::Value) -> Result<Self> {
+        let meta = value
+            .get("meta")
+            .filter(|m| !m.is_null())
+            .ok_or_else(|| parse_error("transaction without meta"))?;
+        if !meta["err"].is_null() {
+            return Err(parse_error("transaction failed on-chain"));
+        }
+
+        let signatures = value["transaction"]["signatures"]
+            .as_array()
+            .ok_or_else(|| parse_error("transaction without signatures"))?;
+        let signature = signatures
+            .first()
+            .and_then(|s| s.as_str())
+            .and_then(|s| Signature::from_str(s).ok())
+            .ok_or_else(|| parse_error("invalid transaction signature"))?;
+
+        Ok(Self {
+            signature,
+            slot: value["slot"].as_u64().ok_or_else(|| parse_error("transaction without slot"))?,
+            num_signatures: signatures.len() as u64,
+            fee_lamports: meta["fee"].as_u64().ok_or_else(|| parse_error("meta without fee"))?,
+            pre_token_balances: parse_balances(&meta["preTokenBalances"])?,
+            post_token_balances: parse_balances(&meta["postTokenBalances"])?,
+            pre_balances: parse_lamports(&meta["preBalances"]),
+            post_balances: parse_lamports(&meta["postBalances"]),
+        })
+    }
+
+    /// Lamport change of one account (0 if the meta lacks it)
+    pub fn lamport_delta(&self, account_index: usize) -> i128 {
+        let pre = self.pre_balances.get(account_index).copied().unwrap_or_default();
+        let post = self.post_balances.get(account_index).copied().unwrap_or(pre);
+        post as i128 - pre as i128
+    }
+
+    /// Net change per mint across `owner`'s token accounts
+    pub fn owner_deltas(&self, owner: &Pubkey) -> HashMap<Pubkey, i128> {
+        let mut deltas: HashMap<Pubkey, i128> = HashMap::new();
+        let owned = |b: &&TokenBalance| b.owner.as_ref() == Some(owner);
+
+        for balance in self.post_token_balances.iter().filter(owned) {
+            *deltas.entry(balance.mint).or_default() += balance.amount as i128;
+        }
+        for balance in self.pre_token_balances.iter().filter(owned) {
+            *deltas.entry(balance.mint).or_default() -= balance.amount as i128;
+        }
+        deltas
+    }
+}
+
+pub(crate) fn parse_balances(value: &serde_json::Value) -> Result<Vec<TokenBalance>> {
+    let Some(entries) = value.as_array() else {
+        return Ok(Vec::new());
+    };
+
+    entries
+        .iter()
+        .map(|entry| {
+            let mint = entry["mint"]
+                .as_str()
+                .and_then(|m| Pubkey::from_str(m).ok())
+                .ok_or_else(|| parse_error("token balance without mint"))?;
+            let amount = entry["uiTokenAmount"]["amount"]
+                .as_str()
+                .and_then(|a| a.parse().ok())
+                .ok_or_else(|| parse_error("token balance without amount"))?;
+
+            Ok(TokenBalance {
+                account_index: entry["accountIndex"].as_u64().unwrap_or_default() as u32,
+                mint,
+                owner: entry["owner"].as_str().and_then(|o| Pubkey::from_str(o).ok()),
+                amount,
+            })
+        })
+        .collect()
+}
+
+fn parse_lamports(value: &serde_json::Value) -> Vec<u64> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|lamports| lamports.as_u64().unwrap_or_default())
+        .collect()
+}
+
+fn parse_error(reason: &str) -> SentinelError {
+    SentinelError::ParseError(format!("Invalid confirmed transaction: {}", reason))
+}
+
+/// Everything the user paid to get the fill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeesPaid {
+    pub base_fee_lamports: u64,
+    pub priority_fee_lamports: u64,
+    pub jito_tip_lamports: u64,
+
+    /// DEX fees not reflected in the quoted amounts (quote fee token units)
+    pub dex_fee_amount: u64,
+}
+
+impl FeesPaid {
+    /// Lamports paid to the network and block engine
+    pub fn total_lamports(&self) -> u64 {
+        self.base_fee_lamports + self.priority_fee_lamports + self.jito_tip_lamports
+    }
+}
+
+/// Fee component of an accounting entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeComponent {
+    BaseFee,
+    PriorityFee,
+    JitoTip,
+}
+
+/// One fee component and the account that paid it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEntry {
+    pub component: FeeComponent,
+    pub payer: String,
+
+    /// Sponsor id when a sponsor paid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsor: Option<String>,
+    pub lamports: u64,
+}
+
+/// Realized execution of a confirmed swap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillReport {
+    pub intent_id: String,
+    pub signature: String,
+    pub slot: u64,
+
+    pub input_mint: String,
+    pub output_mint: String,
+
+    pub quoted_in_amount: u64,
+    pub quoted_out_amount: u64,
+    pub realized_in_amount: u64,
+    pub realized_out_amount: u64,
+
+    /// Slippage against the quote (bps; negative = price improvement)
+    pub realized_slippage_bps: f64,
+
+    pub fees: FeesPaid,
+
+    /// Expected sandwich loss avoided (output token units; input units for ExactOut)
+    pub mev_savings_estimate: u64,
+
+    /// Who paid which fees (sponsored intents)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fee_entries: Vec<FeeEntry>,
+}
+
+impl FillReport {
+    /// Build a report from the confirmed transaction and the quote that was executed
+    ///
+    /// `risk_score` is the routing-time MEV risk (probability of an attack had
+    /// the swap gone out unprotected).
+    pub fn from_transaction(
+        intent: &Intent,
+        tx: &ConfirmedTransaction,
+        quote: &VenueQuote,
+        jito_tip_lamports: u64,
+        risk_score: f32,
+    ) -> Result<Self> {
+        let swap = intent.swap_details.as_ref().ok_or_else(|| {
+            SentinelError::InvalidIntent(format!("Intent {} has no swap to report on", intent.intent_id))
+        })?;
+
+        let deltas = tx.owner_deltas(&intent.user_public_key);
+        let delta = |mint: &Pubkey| deltas.get(mint).copied().unwrap_or_default();
+        let realized_in = (-delta(&swap.input_mint)).max(0) as u64;
+        let realized_out = delta(&swap.output_mint).max(0) as u64;
+        if realized_in == 0 && realized_out == 0 {
+            return Err(parse_error("no token balance change for the intent's user"));
+        }
+
+        // ExactIn slips on the output side, ExactOut on the input side
+        let (quoted, realized_slippage_bps) = match swap.mode {
+            SwapMode::ExactIn => (quote.out_amount, slippage_bps(quote.out_amount, realized_out, false)),
+            SwapMode::ExactOut => (quote.in_amount, slippage_bps(quote.in_amount, realized_in, true)),
+        };
+
+        let tolerance_bps = intent.constraints.max_slippage_bps as f64;
+        let avoided_bps = (tolerance_bps - realized_slippage_bps.max(0.0)).max(0.0);
+        let mev_savings_estimate =
+            (risk_score.clamp(0.0, 1.0) as f64 * avoided_bps / 10_000.0 * quoted as f64) as u64;
+
+        let base_fee_lamports = (tx.num_signatures * LAMPORTS_PER_SIGNATURE).min(tx.fee_lamports);
+
+        Ok(Self {
+            intent_id: intent.intent_id.clone(),
+            signature: tx.signature.to_string(),
+            slot: tx.slot,
+            input_mint: swap.input_mint.to_string(),
+            output_mint: swap.output_mint.to_string(),
+            quoted_in_amount: quote.in_amount,
+            quoted_out_amount: quote.out_amount,
+            realized_in_amount: realized_in,
+            realized_out_amount: realized_out,
+            realized_slippage_bps,
+            fees: FeesPaid {
+                base_fee_lamports,
+                priority_fee_lamports: tx.fee_lamports - base_fee_lamports,
+                jito_tip_lamports,
+                dex_fee_amount: quote.fee_amount,
+            },
+            mev_savings_estimate,
+            fee_entries: Vec::new(),
+        })
+    }
+
+    pub fn with_fee_entries(mut self, entries: Vec<FeeEntry>) -> Self {
+        self.fee_entries = entries;
+        self
+    }
+}
+
+/// Shortfall vs `quoted` in bps; `paying` flips the sign for the input side
+fn slippage_bps(quoted: u64, realized: u64, paying: bool) -> f64 {
+    if quoted == 0 {
+        return 0.0;
+    }
+    let shortfall = if paying {
+        realized as f64 - quoted as f64
+    } else {
+        quoted as f64 - realized as f64
+    };
+    shortfall / quoted as f64 * 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::best_execution::DexVenue;
+    use serde_json::json;
+
+    struct Fixture {
+        user: Pubkey,
+        usdc: Pubkey,
+        sol: Pubkey,
+    }
+
+    fn fixture() -> Fixture {
+        Fixture {
+            user: Pubkey::new_unique(),
+            usdc: Pubkey::new_unique(),
+            sol: Pubkey::new_unique(),
+        }
+    }
+
+    fn intent(f: &Fixture, mode: SwapMode) -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            user_public_key: f.user,
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.mode = mode;
+        swap.input_mint = f.usdc;
+        swap.output_mint = f.sol;
+        swap.minimum_received = None;
+        intent
+    }
+
+    fn balance(index: u32, mint: &Pubkey, owner: &Pubkey, amount: u64) -> serde_json::Value {
+        json!({
+            "accountIndex": index,
+            "mint": mint.to_string(),
+            "owner": owner.to_string(),
+            "uiTokenAmount": { "amount": amount.to_string(), "decimals": 6 }
+        })
+    }
+
+    /// User pays 1_000_000 USDC and receives `received` SOL; the pool's account changes too
+    fn rpc_result(f: &Fixture, received: u64) -> serde_json::Value {
+        let pool = Pubkey::new_unique();
+        json!({
+            "slot": 250_000_000u64,
+            "transaction": { "signatures": [Signature::new_unique().to_string()] },
+            "meta": {
+                "err": null,
+                "fee": 25_000,
+                "preBalances": [1_000_000_000u64, 2_039_280],
+                "postBalances": [999_965_000u64, 2_039_280],
+                "preTokenBalances": [
+                    balance(1, &f.usdc, &f.user, 5_000_000),
+                    balance(2, &f.sol, &f.user, 0),
+                    balance(3, &f.sol, &pool, 900_000_000),
+                ],
+                "postTokenBalances": [
+                    balance(1, &f.usdc, &f.user, 4_000_000),
+                    balance(2, &f.sol, &f.user, received),
+                    balance(3, &f.sol, &pool, 900_000_000 - received),
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_fill_report_from_balance_deltas() {
+        let f = fixture();
+        let tx = ConfirmedTransaction::from_rpc_json(&rpc_result(&f, 9_950_000)).unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1_000_000, 10_000_000, 300, 12.0);
+
+        let report = FillReport::from_transaction(&intent(&f, SwapMode::ExactIn), &tx, &quote, 10_000, 0.5).unwrap();
+
+        assert_eq!(report.realized_in_amount, 1_000_000);
+        assert_eq!(report.realized_out_amount, 9_950_000);
+        assert!((report.realized_slippage_bps - 50.0).abs() < 1e-9);
+        assert_eq!(report.fees.base_fee_lamports, 5_000);
+        assert_eq!(report.fees.priority_fee_lamports, 20_000);
+        assert_eq!(report.fees.total_lamports(), 35_000);
+        assert_eq!(report.fees.dex_fee_amount, 300);
+        assert_eq!(report.slot, 250_000_000);
+        assert_eq!((tx.lamport_delta(0), tx.lamport_delta(1), tx.lamport_delta(9)), (-35_000, 0, 0));
+
+        // Default tolerance is 50bps, all of it used: nothing left for a sandwich to take
+        assert_eq!(report.mev_savings_estimate, 0);
+    }
+
+    #[test]
+    fn test_price_improvement_and_savings() {
+        let f = fixture();
+        let tx = ConfirmedTransaction::from_rpc_json(&rpc_result(&f, 10_010_000)).unwrap();
+        let quote = VenueQuote::new(DexVenue::Orca, 1_000_000, 10_000_000, 0, 5.0);
+
+        let mut intent = intent(&f, SwapMode::ExactIn);
+        intent.constraints.max_slippage_bps = 100;
+        let report = FillReport::from_transaction(&intent, &tx, &quote, 0, 0.8).unwrap();
+
+        assert!(report.realized_slippage_bps < 0.0);
+        // 0.8 × 100bps × 10_000_000
+        assert_eq!(report.mev_savings_estimate, 80_000);
+    }
+
+    #[test]
+    fn test_exact_out_slippage_on_input() {
+        let f = fixture();
+        let tx = ConfirmedTransaction::from_rpc_json(&rpc_result(&f, 10_000_000)).unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 990_000, 10_000_000, 0, 5.0);
+
+        let report = FillReport::from_transaction(&intent(&f, SwapMode::ExactOut), &tx, &quote, 0, 0.0).unwrap();
+        assert!(report.realized_slippage_bps > 100.0 && report.realized_slippage_bps < 102.0);
+    }
+
+    #[test]
+    fn test_failed_or_unrelated_transaction_rejected() {
+        let f = fixture();
+        let mut failed = rpc_result(&f, 1);
+        failed["meta"]["err"] = json!({ "InstructionError": [0, "Custom"] });
+        assert!(ConfirmedTransaction::from_rpc_json(&failed).is_err());
+
+        let other = fixture();
+        let tx = ConfirmedTransaction::from_rpc_json(&rpc_result(&f, 1)).unwrap();
+        let quote = VenueQuote::new(DexVenue::Jupiter, 1, 1, 0, 0.0);
+        assert!(FillReport::from_transaction(&intent(&other, SwapMode::ExactIn), &tx, &quote, 0, 0.5).is_err());
+    }
+}
@@ -0,0 +1,344 @@
+//! Liveness and Readiness Checks
+//!
+//! Liveness only says the process answers. Readiness runs every registered
+//! [`HealthCheck`] and is ready when all of them pass:
+//! - **model**: engines warmed up (`ai_engine::InferencePool`)
+//! - **oracle**: newest price no older than `health.oracle_max_age_secs` ([`FreshnessCheck`])
+//! - **block_engines**: at least one block engine breaker not open ([`BlockEngineCheck`])
+//! - **intent_store**: the KV store answers a read ([`KvStoreCheck`])
+//!
+//! The [`HealthReport`] lists every dependency with its detail, so a failing
+//! Kubernetes probe says which one held the pod back. Checks may block
+//! (Postgres), so run [`HealthChecker::readiness`] off the async runtime.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::circuit_breaker::{CircuitBreakers, CircuitState};
+use crate::clock::{system_clock, SharedClock};
+use crate::storage::KvStore;
+use crate::{Result, SentinelError};
+
+/// Key read by [`KvStoreCheck`]; its absence is fine
+const PROBE_KEY: &[u8] = b"__health_probe";
+
+/// One dependency a service needs before it takes traffic
+pub trait HealthCheck: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Detail for the report, or why the dependency is unusable
+    fn check(&self) -> Result<String>;
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub healthy: bool,
+    pub detail: String,
+}
+
+/// Probe response body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Every check passed (always true for liveness)
+    pub ready: bool,
+    pub uptime_secs: u64,
+    pub checks: Vec<DependencyStatus>,
+}
+
+/// Registered readiness checks
+pub struct HealthChecker {
+    checks: Vec<Arc<dyn HealthCheck>>,
+    started_ms: i64,
+    clock: SharedClock,
+}
+
+impl Default for HealthChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthChecker {
+    pub fn new() -> Self {
+        let clock = system_clock();
+        Self {
+            checks: Vec::new(),
+            started_ms: clock.unix_millis(),
+            clock,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.started_ms = clock.unix_millis();
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_check(mut self, check: Arc<dyn HealthCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    fn uptime_secs(&self) -> u64 {
+        (self.clock.unix_millis() - self.started_ms).max(0) as u64 / 1_000
+    }
+
+    /// The process is up; no dependency is consulted
+    pub fn liveness(&self) -> HealthReport {
+        HealthReport {
+            ready: true,
+            uptime_secs: self.uptime_secs(),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Run every check; ready only if all pass
+    pub fn readiness(&self) -> HealthReport {
+        let checks: Vec<DependencyStatus> = self
+            .checks
+            .iter()
+            .map(|check| match check.check() {
+                Ok(detail) => DependencyStatus {
+                    name: check.name().to_string(),
+                    healthy: true,
+                    detail,
+                },
+                Err(e) => DependencyStatus {
+                    name: check.name().to_string(),
+                    healthy: false,
+                    detail: e.to_string(),
+                },
+            })
+            .collect();
+
+        HealthReport {
+            ready: checks.iter().all(|status| status.healthy),
+            uptime_secs: self.uptime_secs(),
+            checks,
+        }
+    }
+}
+
+/// Timestamp of a dependency's newest data, shared between its client and a [`FreshnessCheck`]
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeat {
+    last_ms: Arc<AtomicI64>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record data as of `at_ms`; older beats than the newest are ignored
+    pub fn beat(&self, at_ms: i64) {
+        self.last_ms.fetch_max(at_ms, Ordering::Relaxed);
+    }
+
+    /// Newest beat, None before the first
+    pub fn last_ms(&self) -> Option<i64> {
+        Some(self.last_ms.load(Ordering::Relaxed)).filter(|&ms| ms > 0)
+    }
+}
+
+/// Fails when a [`Heartbeat`] is missing or older than `max_age`
+pub struct FreshnessCheck {
+    name: String,
+    heartbeat: Heartbeat,
+    max_age: Duration,
+    clock: SharedClock,
+}
+
+impl FreshnessCheck {
+    pub fn new(name: impl Into<String>, heartbeat: Heartbeat, max_age: Duration) -> Self {
+        Self {
+            name: name.into(),
+            heartbeat,
+            max_age,
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl HealthCheck for FreshnessCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self) -> Result<String> {
+        let last = self
+            .heartbeat
+            .last_ms()
+            .ok_or_else(|| SentinelError::Timeout("no data received yet".to_string()))?;
+        let age_ms = (self.clock.unix_millis() - last).max(0) as u64;
+        if age_ms > self.max_age.as_millis() as u64 {
+            return Err(SentinelError::Timeout(format!(
+                "newest data is {}ms old (limit {}ms)",
+                age_ms,
+                self.max_age.as_millis()
+            )));
+        }
+        Ok(format!("{}ms old", age_ms))
+    }
+}
+
+/// Passes while at least one breaker named `<prefix>…` is not open
+pub struct BlockEngineCheck {
+    breakers: CircuitBreakers,
+    prefix: String,
+}
+
+impl BlockEngineCheck {
+    /// Block engine breakers, named `jito:<url>`
+    pub fn new(breakers: CircuitBreakers) -> Self {
+        Self::with_prefix(breakers, "jito")
+    }
+
+    pub fn with_prefix(breakers: CircuitBreakers, prefix: impl Into<String>) -> Self {
+        Self {
+            breakers,
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl HealthCheck for BlockEngineCheck {
+    fn name(&self) -> &str {
+        "block_engines"
+    }
+
+    fn check(&self) -> Result<String> {
+        let engines: Vec<_> = self
+            .breakers
+            .health()
+            .into_iter()
+            .filter(|health| health.name.starts_with(&self.prefix))
+            .collect();
+        let available = engines.iter().filter(|health| health.state != CircuitState::Open).count();
+
+        if available == 0 {
+            return Err(SentinelError::ConnectionError(format!(
+                "none of {} block engines available",
+                engines.len()
+            )));
+        }
+        Ok(format!("{}/{} available", available, engines.len()))
+    }
+}
+
+/// Passes when the store answers a read
+pub struct KvStoreCheck {
+    name: String,
+    store: Arc<dyn KvStore>,
+}
+
+impl KvStoreCheck {
+    pub fn new(name: impl Into<String>, store: Arc<dyn KvStore>) -> Self {
+        Self {
+            name: name.into(),
+            store,
+        }
+    }
+}
+
+impl HealthCheck for KvStoreCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self) -> Result<String> {
+        self.store.get(PROBE_KEY)?;
+        Ok(format!("{} reachable", self.store.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use crate::config::CircuitBreakerSettings;
+    use crate::storage::MemoryKv;
+
+    /// Store whose every call fails, like an unreachable backend
+    struct Down;
+
+    fn refused() -> SentinelError {
+        SentinelError::ConnectionError("connection refused".to_string())
+    }
+
+    impl KvStore for Down {
+        fn name(&self) -> &'static str {
+            "down"
+        }
+        fn get(&self, _key: &[u8]) -> Result<Option<Vec<u8>>> {
+            Err(refused())
+        }
+        fn put(&self, _key: &[u8], _value: &[u8]) -> Result<()> {
+            Err(refused())
+        }
+        fn delete(&self, _key: &[u8]) -> Result<()> {
+            Err(refused())
+        }
+        fn scan_from(&self, _start: &[u8], _visit: crate::storage::KvVisit<'_>) -> Result<()> {
+            Err(refused())
+        }
+    }
+
+    #[test]
+    fn test_readiness_reports_every_dependency() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let oracle = Heartbeat::new();
+        let breakers = CircuitBreakers::new(CircuitBreakerSettings {
+            min_calls: 1,
+            window: 1,
+            ..Default::default()
+        });
+        let checker = HealthChecker::new()
+            .with_clock(clock.clone())
+            .with_check(Arc::new(
+                FreshnessCheck::new("oracle", oracle.clone(), Duration::from_secs(30)).with_clock(clock.clone()),
+            ))
+            .with_check(Arc::new(BlockEngineCheck::new(breakers.clone())))
+            .with_check(Arc::new(KvStoreCheck::new("intent_store", Arc::new(MemoryKv::new()))));
+
+        // No price yet, no block engine registered
+        let report = checker.readiness();
+        assert!(!report.ready);
+        let failing: Vec<&str> = report.checks.iter().filter(|c| !c.healthy).map(|c| c.name.as_str()).collect();
+        assert_eq!(failing, ["oracle", "block_engines"]);
+        assert!(checker.liveness().ready);
+
+        oracle.beat(clock.unix_millis() - 5_000);
+        breakers.breaker("jito:ny");
+        breakers.breaker("jito:fra").acquire().unwrap().record(false);
+        let report = checker.readiness();
+        assert!(report.ready, "{:?}", report);
+        assert_eq!(report.checks[0].detail, "5000ms old");
+        assert_eq!(report.checks[1].detail, "1/2 available");
+
+        clock.advance(Duration::from_secs(60));
+        let report = checker.readiness();
+        assert!(!report.ready);
+        assert_eq!(report.uptime_secs, 60);
+    }
+
+    #[test]
+    fn test_unreachable_store_is_not_ready() {
+        let checker = HealthChecker::new().with_check(Arc::new(KvStoreCheck::new("intent_store", Arc::new(Down))));
+        let report = checker.readiness();
+        assert!(!report.ready);
+        assert!(report.checks[0].detail.contains("connection refused"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["name"], "intent_store");
+    }
+}
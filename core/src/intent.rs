@@ -1,19 +1,25 @@
 //! Intent Schema for Sentinel Router
 //!
 //! This module defines the core intent structure for non-custodial transaction routing.
-//! Supports immediate swaps with extensibility for future intent types (TWAP, Limit orders).
+//! Supports immediate swaps with extensibility for future intent types (TWAP, Limit orders),
+//! and ordered multi-leg Bundle intents (e.g. swap then deposit) executed all-or-nothing.
 //!
 //! # GDPR/MiCA Compliance
 //! No personal data (e.g., IPs, emails) stored in intents; all fields are pseudonymous via
 //! Pubkeys or cryptographic hashes. Intents are ephemeral and expire per user-defined constraints.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::str::FromStr;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::clock::Clock;
+use crate::latency::{LatencyTracer, PipelineStage};
+
 // ================================================================================================
 // Intent Types and Modes
 // ================================================================================================
@@ -22,7 +28,7 @@ use uuid::Uuid;
 ///
 /// Current implementation: Swap (immediate execution)
 /// Roadmap (Q1 2026): Limit orders, TWAP (time-weighted average price)
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum IntentType {
     /// Immediate swap at current market price
@@ -32,10 +38,15 @@ pub enum IntentType {
     /// Time-Weighted Average Price: spread execution over time (Q1 2026)
     #[serde(rename = "twap")]
     TWAP,
+    /// Ordered multi-leg operation executed atomically in one Jito bundle
+    Bundle,
+    /// Dollar-cost averaging: recurring swaps over days or weeks (see `DcaScheduler`)
+    #[serde(rename = "dca")]
+    DCA,
 }
 
 /// Swap execution mode
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SwapMode {
     /// Exact input amount, variable output (most common)
@@ -44,22 +55,37 @@ pub enum SwapMode {
     ExactOut,
 }
 
+/// User-facing protection level; the concrete tip percentiles, route bias and
+/// simulation strictness for each tier come from `[protection_tiers]` config
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectionTier {
+    /// Cheapest: lower tips, public routes preferred
+    Economy,
+    #[default]
+    Standard,
+    /// Highest tips, bundles preferred, strictest simulation checks
+    Max,
+}
+
 // ================================================================================================
 // Intent Detail Structures
 // ================================================================================================
 
 /// Swap-specific details
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct SwapDetails {
     /// Swap execution mode
     pub mode: SwapMode,
     
     /// Input token mint address
     /// Example: EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v (USDC)
+    #[schemars(with = "PubkeyBytes")]
     pub input_mint: Pubkey,
     
     /// Output token mint address
     /// Example: So11111111111111111111111111111111111111112 (SOL)
+    #[schemars(with = "PubkeyBytes")]
     pub output_mint: Pubkey,
     
     /// Amount in smallest token units (atoms)
@@ -71,16 +97,18 @@ pub struct SwapDetails {
     pub minimum_received: Option<u64>,
     
     /// Preferred DEX aggregator
-    /// Supported: "Jupiter", "Raydium", or None for auto-select
+    /// Supported: "Jupiter", "Raydium", "Orca", or None for auto-select
     pub dex: Option<String>,
     
     /// Optional precomputed route Pubkeys for optimization
     /// Reduces compute units by skipping route discovery
+    /// (Orca: Whirlpool address first, see `whirlpool::route_hints`)
+    #[schemars(with = "Option<Vec<PubkeyBytes>>")]
     pub route_hints: Option<Vec<Pubkey>>,
 }
 
 /// Limit order details (Q1 2026 implementation)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct LimitDetails {
     /// Price threshold for execution (e.g., minimum output price)
     /// Note: Solana oracles use u64 scaled values (e.g., 1e9 for 9 decimals)
@@ -90,11 +118,12 @@ pub struct LimitDetails {
     /// Oracle source for price feeds (Q1 2026)
     /// Supported: Pyth Network, Switchboard, Chainlink on Solana
     /// If None, uses on-chain DEX spot price
+    #[schemars(with = "Option<PubkeyBytes>")]
     pub oracle: Option<Pubkey>,
 }
 
 /// TWAP (Time-Weighted Average Price) details (Q1 2026 implementation)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct TwapDetails {
     /// Duration in seconds to spread execution over
     /// Example: 3600 = 1 hour
@@ -104,12 +133,90 @@ pub struct TwapDetails {
     pub num_chunks: Option<u16>,
 }
 
+/// DCA (recurring buy) details
+///
+/// The swap details give the mints; `total_amount` is split evenly over
+/// `num_orders` executions, `interval_secs` apart (remainder on the last order).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct DcaDetails {
+    /// Seconds between executions
+    /// Example: 86400 = daily
+    pub interval_secs: u32,
+    
+    /// Total input amount across all executions (atoms)
+    pub total_amount: u64,
+    
+    /// Number of executions
+    pub num_orders: u16,
+}
+
+impl DcaDetails {
+    /// Input amount of execution `index` (0-based)
+    pub fn order_amount(&self, index: u16) -> u64 {
+        let orders = u64::from(self.num_orders.max(1));
+        let base = self.total_amount / orders;
+        if u64::from(index) + 1 == orders {
+            base + self.total_amount % orders
+        } else {
+            base
+        }
+    }
+}
+
+/// Deposit into a program-owned vault (lending market, LST pool, LP vault)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct DepositDetails {
+    /// Vault program
+    #[schemars(with = "PubkeyBytes")]
+    pub program_id: Pubkey,
+
+    /// Vault / reserve account receiving the deposit
+    #[schemars(with = "PubkeyBytes")]
+    pub vault: Pubkey,
+
+    /// Deposited token mint
+    #[schemars(with = "PubkeyBytes")]
+    pub mint: Pubkey,
+
+    /// Amount in atoms; None deposits everything earlier legs produced of `mint`
+    pub amount: Option<u64>,
+}
+
+/// One step of a multi-leg (Bundle) intent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentLeg {
+    Swap(SwapDetails),
+    Deposit(DepositDetails),
+}
+
+impl IntentLeg {
+    /// Mint consumed and amount (None = all available)
+    fn input(&self) -> (Pubkey, Option<u64>) {
+        match self {
+            IntentLeg::Swap(swap) => (swap.input_mint, Some(swap.amount)),
+            IntentLeg::Deposit(deposit) => (deposit.mint, deposit.amount),
+        }
+    }
+
+    /// Mint produced and the amount guaranteed to later legs
+    fn output(&self) -> Option<(Pubkey, u64)> {
+        match self {
+            IntentLeg::Swap(swap) => match swap.mode {
+                SwapMode::ExactIn => Some((swap.output_mint, swap.minimum_received.unwrap_or(0))),
+                SwapMode::ExactOut => Some((swap.output_mint, swap.amount)),
+            },
+            IntentLeg::Deposit(_) => None,
+        }
+    }
+}
+
 // ================================================================================================
 // Constraints and Preferences
 // ================================================================================================
 
 /// Execution constraints
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Constraints {
     /// Maximum allowed slippage in basis points
     /// Example: 50 = 0.5%, 100 = 1%
@@ -128,6 +235,16 @@ pub struct Constraints {
     /// Example: 300 = 5 minutes from now
     /// Note: If both TTL and expiry_timestamp are set, expiry_timestamp takes precedence
     pub ttl_seconds: Option<u32>,
+    
+    /// Let the router tighten slippage to the `SlippageAdvisor` recommendation
+    /// (never looser than `max_slippage_bps`)
+    #[serde(default)]
+    pub managed_slippage: bool,
+
+    /// Consent to be crossed against opposing intents at the oracle mid price
+    /// instead of swapping on a DEX (see `NettingEngine`)
+    #[serde(default)]
+    pub allow_netting: bool,
 }
 
 impl Default for Constraints {
@@ -137,12 +254,14 @@ impl Default for Constraints {
             partial_fill: false,
             expiry_timestamp: None,
             ttl_seconds: None, // No default TTL
+            managed_slippage: false,
+            allow_netting: false,
         }
     }
 }
 
 /// Fee preferences for MEV protection
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct FeePreferences {
     /// Maximum priority fee willing to pay (lamports)
     /// Default: 100_000 = 0.0001 SOL
@@ -153,8 +272,18 @@ pub struct FeePreferences {
     pub max_jito_tip_lamports: u64,
     
     /// Percentage allocation to Jito tip (0-100)
-    /// Example: 70 = 70% tip, 30% priority fee (risk-adaptive)
+    /// Example: 70 = 70% tip, 30% priority fee at neutral risk; `FeeSplitter`
+    /// shifts it with the risk score and route
     pub tip_allocation_pct: u8,
+    
+    /// Submit even when the upcoming leader is a known malicious validator
+    /// (skips the `LeaderGuard` delay / forced JitoBundle route)
+    #[serde(default)]
+    pub allow_malicious_leader: bool,
+
+    /// Protection tier (a wallet slider in place of raw lamport caps)
+    #[serde(default)]
+    pub protection_tier: ProtectionTier,
 }
 
 impl Default for FeePreferences {
@@ -163,12 +292,37 @@ impl Default for FeePreferences {
             max_priority_fee_lamports: 100_000,
             max_jito_tip_lamports: 50_000,
             tip_allocation_pct: 70, // Default: 70/30 tip/priority split
+            allow_malicious_leader: false,
+            protection_tier: ProtectionTier::Standard,
+        }
+    }
+}
+
+/// Fee budget divided between Jito tip and priority fee
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub jito_tip_lamports: u64,
+    pub priority_fee_lamports: u64,
+}
+
+impl FeePreferences {
+    /// Split a total fee budget per `tip_allocation_pct`, capped by the user maximums
+    ///
+    /// Example: 100_000 budget at 70% → 70_000 tip / 30_000 priority (before caps)
+    pub fn split(&self, total_budget_lamports: u64) -> FeeSplit {
+        let pct = self.tip_allocation_pct.min(100) as u128;
+        let tip = (total_budget_lamports as u128 * pct / 100) as u64;
+        let priority = total_budget_lamports - tip;
+
+        FeeSplit {
+            jito_tip_lamports: tip.min(self.max_jito_tip_lamports),
+            priority_fee_lamports: priority.min(self.max_priority_fee_lamports),
         }
     }
 }
 
 /// Consent and anti-tamper block
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ConsentBlock {
     /// Recent blockhash for replay protection
     /// Parsed from base58 string during deserialization
@@ -176,6 +330,7 @@ pub struct ConsentBlock {
         serialize_with = "serialize_hash",
         deserialize_with = "deserialize_hash"
     )]
+    #[schemars(with = "String")]
     pub recent_blockhash: Hash,
     
     /// Unique request ID for tracking (UUID v4)
@@ -210,7 +365,9 @@ where
 ///
 /// # Example
 /// ```rust,no_run
-/// use sentinel_core::{Intent, IntentType, SwapDetails, SwapMode, Constraints, FeePreferences, ConsentBlock};
+/// use sentinel_core::{
+///     Intent, IntentType, SwapDetails, SwapMode, Constraints, FeePreferences, ConsentBlock, INTENT_SCHEMA_VERSION,
+/// };
 /// use solana_sdk::pubkey::Pubkey;
 /// use solana_sdk::hash::Hash;
 /// use std::str::FromStr;
@@ -239,16 +396,20 @@ where
 ///     },
 ///     limit_details: None,
 ///     twap_details: None,
+///     dca_details: None,
+///     legs: vec![],
+///     schema_version: INTENT_SCHEMA_VERSION,
 /// };
 ///
 /// intent.validate(Utc::now().timestamp()).expect("Validation failed");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Intent {
     /// Unique intent identifier (UUID v4)
     pub intent_id: String,
     
     /// User's public key (wallet address)
+    #[schemars(with = "PubkeyBytes")]
     pub user_public_key: Pubkey,
     
     /// Intent execution type
@@ -271,14 +432,44 @@ pub struct Intent {
     
     /// TWAP details (required for TWAP intents, Q1 2026)
     pub twap_details: Option<TwapDetails>,
+    
+    /// DCA details (required for DCA intents)
+    #[serde(default)]
+    pub dca_details: Option<DcaDetails>,
+    
+    /// Ordered legs (required for Bundle intents, empty otherwise)
+    #[serde(default)]
+    pub legs: Vec<IntentLeg>,
+    
+    /// Schema version the intent was written against ([`INTENT_SCHEMA_VERSION`])
+    ///
+    /// Payloads from before versioning omit it and read as 0; versions newer
+    /// than this build understands are rejected when decoding.
+    #[serde(default, deserialize_with = "deserialize_schema_version")]
+    pub schema_version: u16,
+}
+
+/// Wire form of a `Pubkey` (serde encodes it as its 32 raw bytes)
+type PubkeyBytes = [u8; 32];
+
+fn deserialize_schema_version<'de, D>(deserializer: D) -> Result<u16, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let version = u16::deserialize(deserializer)?;
+    if version > INTENT_SCHEMA_VERSION {
+        return Err(serde::de::Error::custom(IntentError::UnsupportedSchemaVersion(version)));
+    }
+    Ok(version)
 }
 
 // ================================================================================================
 // Priority Levels
 // ================================================================================================
 
-/// Transaction priority based on fee allocation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Transaction priority based on fee allocation (ordered Low < Critical)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Priority {
     Low,
     Medium,
@@ -311,6 +502,9 @@ pub enum IntentStatus {
     
     /// Intent expired before execution (TTL or expiry_timestamp reached)
     Expired,
+
+    /// Intent withdrawn by the user before execution (cancel or replace)
+    Cancelled,
 }
 
 // Priority thresholds (lamports)
@@ -321,6 +515,16 @@ const HIGH_THRESHOLD: u64 = 200_000;
 // Expiry buffer to prevent immediate expiration (seconds)
 const EXPIRY_BUFFER_SECS: i64 = 30;
 
+// DCA schedule bounds
+const MIN_DCA_INTERVAL_SECS: u32 = 60;
+const MAX_DCA_SCHEDULE_SECS: u64 = 365 * 86_400;
+
+/// Legs per Bundle intent (Jito bundles hold 5 transactions, one is the tip)
+pub const MAX_INTENT_LEGS: usize = 4;
+
+/// Intent schema version this build writes; bump on incompatible payload changes
+pub const INTENT_SCHEMA_VERSION: u16 = 1;
+
 // ================================================================================================
 // Error Types
 // ================================================================================================
@@ -366,6 +570,30 @@ pub enum IntentError {
     
     #[error("Invalid TWAP duration: must be > 0")]
     InvalidTwapDuration,
+    
+    #[error("Missing DCA details for DCA intent")]
+    MissingDcaDetails,
+    
+    #[error("Invalid DCA schedule: {0}")]
+    InvalidDcaSchedule(String),
+    
+    #[error("Bundle intent requires at least one leg")]
+    MissingLegs,
+    
+    #[error("Bundle intent cannot exceed {0} legs")]
+    TooManyLegs(usize),
+    
+    #[error("Legs are only allowed on Bundle intents")]
+    UnexpectedLegs,
+    
+    #[error("Leg {leg} needs {required} of {mint} but earlier legs guarantee only {available}")]
+    LegFlowMismatch { leg: usize, mint: Pubkey, required: u64, available: u64 },
+    
+    #[error("Leg {0} consumes an earlier leg's output with an unbounded amount")]
+    UnboundedLegInput(usize),
+    
+    #[error("Unsupported intent schema_version {0} (max {max})", max = INTENT_SCHEMA_VERSION)]
+    UnsupportedSchemaVersion(u16),
 }
 
 // ================================================================================================
@@ -373,6 +601,16 @@ pub enum IntentError {
 // ================================================================================================
 
 impl Intent {
+    /// Validate against the time reported by `clock`
+    pub fn validate_at(&self, clock: &dyn Clock) -> Result<(), IntentError> {
+        self.validate(clock.unix_timestamp())
+    }
+
+    /// Validate and record the validation stage on a request's latency tracer
+    pub fn validate_traced(&self, current_time: i64, tracer: &mut LatencyTracer) -> Result<(), IntentError> {
+        tracer.time(PipelineStage::Validate, || self.validate(current_time))
+    }
+
     /// Validate intent schema and business logic
     ///
     /// # Arguments
@@ -384,6 +622,10 @@ impl Intent {
     /// # Performance
     /// Target: <5ms for typical intent (SLO requirement)
     pub fn validate(&self, current_time: i64) -> Result<(), IntentError> {
+        if self.schema_version > INTENT_SCHEMA_VERSION {
+            return Err(IntentError::UnsupportedSchemaVersion(self.schema_version));
+        }
+        
         // Validate intent type and associated details
         match self.intent_type {
             IntentType::Swap => {
@@ -442,6 +684,26 @@ impl Intent {
                 // MEV resistance: randomized intervals + jitodontfront protection per chunk
                 // Chunk size: duration / sqrt(duration) for optimal gas vs. price averaging
             }
+            IntentType::DCA => {
+                let swap = self
+                    .swap_details
+                    .as_ref()
+                    .ok_or(IntentError::MissingSwapDetails)?;
+                if swap.input_mint == swap.output_mint {
+                    return Err(IntentError::SameMints);
+                }
+                
+                let details = self
+                    .dca_details
+                    .as_ref()
+                    .ok_or(IntentError::MissingDcaDetails)?;
+                validate_dca(details)?;
+            }
+            IntentType::Bundle => validate_legs(&self.legs)?,
+        }
+        
+        if self.intent_type != IntentType::Bundle && !self.legs.is_empty() {
+            return Err(IntentError::UnexpectedLegs);
         }
 
         // Validate slippage bounds
@@ -533,16 +795,13 @@ impl Intent {
     }
 }
 
-// ================================================================================================
-// Tests
-// ================================================================================================
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::Utc;
-
-    fn create_valid_swap_intent() -> Intent {
+#[cfg(any(test, feature = "test-util"))]
+impl Intent {
+    /// Valid 1M ExactIn swap (900k minimum) with fresh ids, wallet, mints and blockhash
+    ///
+    /// Shared test fixture: override only the fields a test cares about, e.g.
+    /// `Intent { intent_id: "a".to_string(), ..Intent::test_swap() }`.
+    pub fn test_swap() -> Self {
         Intent {
             intent_id: Uuid::new_v4().to_string(),
             user_public_key: Pubkey::new_unique(),
@@ -553,7 +812,7 @@ mod tests {
                 output_mint: Pubkey::new_unique(),
                 amount: 1_000_000,
                 minimum_received: Some(900_000),
-                dex: Some("Jupiter".to_string()),
+                dex: None,
                 route_hints: None,
             }),
             constraints: Constraints::default(),
@@ -565,19 +824,139 @@ mod tests {
             },
             limit_details: None,
             twap_details: None,
+            dca_details: None,
+            legs: vec![],
+            schema_version: INTENT_SCHEMA_VERSION,
+        }
+    }
+
+    /// Swap details of a [`Intent::test_swap`] fixture, for overriding amounts or mints
+    pub fn test_swap_details(&mut self) -> &mut SwapDetails {
+        self.swap_details.as_mut().expect("test intent has swap details")
+    }
+}
+
+fn validate_dca(details: &DcaDetails) -> Result<(), IntentError> {
+    if details.num_orders < 2 {
+        return Err(IntentError::InvalidDcaSchedule("at least 2 orders required".to_string()));
+    }
+    if details.interval_secs < MIN_DCA_INTERVAL_SECS {
+        return Err(IntentError::InvalidDcaSchedule(format!(
+            "interval must be at least {} seconds",
+            MIN_DCA_INTERVAL_SECS
+        )));
+    }
+    if details.total_amount < u64::from(details.num_orders) {
+        return Err(IntentError::InvalidAmount);
+    }
+    
+    let span = u64::from(details.interval_secs) * u64::from(details.num_orders - 1);
+    if span > MAX_DCA_SCHEDULE_SECS {
+        return Err(IntentError::InvalidDcaSchedule("schedule cannot exceed 365 days".to_string()));
+    }
+    
+    Ok(())
+}
+
+/// Check each leg and that legs consuming earlier outputs are covered by guaranteed amounts
+///
+/// Tokens not produced by an earlier leg are taken from the user's wallet.
+/// Guaranteed outputs of one mint that overflow a u64 are an `InvalidAmount`.
+fn validate_legs(legs: &[IntentLeg]) -> Result<(), IntentError> {
+    if legs.is_empty() {
+        return Err(IntentError::MissingLegs);
+    }
+    if legs.len() > MAX_INTENT_LEGS {
+        return Err(IntentError::TooManyLegs(MAX_INTENT_LEGS));
+    }
+    
+    // Guaranteed balance of each mint produced by earlier legs
+    let mut produced: HashMap<Pubkey, u64> = HashMap::new();
+    for (index, leg) in legs.iter().enumerate() {
+        if let IntentLeg::Swap(swap) = leg {
+            if swap.input_mint == swap.output_mint {
+                return Err(IntentError::SameMints);
+            }
+        }
+        
+        let (mint, amount) = leg.input();
+        if amount == Some(0) {
+            return Err(IntentError::InvalidAmount);
+        }
+        
+        match produced.get_mut(&mint) {
+            Some(available) => {
+                let exact_out = matches!(leg, IntentLeg::Swap(swap) if swap.mode == SwapMode::ExactOut);
+                if exact_out {
+                    return Err(IntentError::UnboundedLegInput(index));
+                }
+                let required = amount.unwrap_or(*available);
+                if required == 0 || required > *available {
+                    return Err(IntentError::LegFlowMismatch { leg: index, mint, required, available: *available });
+                }
+                *available -= required;
+            }
+            None if amount.is_none() => {
+                return Err(IntentError::LegFlowMismatch { leg: index, mint, required: 0, available: 0 });
+            }
+            None => {}
+        }
+        
+        if let Some((mint, amount)) = leg.output() {
+            let available = produced.entry(mint).or_insert(0);
+            *available = available.checked_add(amount).ok_or(IntentError::InvalidAmount)?;
         }
     }
+    
+    Ok(())
+}
+
+// ================================================================================================
+// Tests
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
 
     #[test]
     fn test_valid_swap_intent() {
-        let intent = create_valid_swap_intent();
+        let intent = Intent::test_swap();
         let current_time = Utc::now().timestamp();
         assert!(intent.validate(current_time).is_ok());
     }
 
+    #[test]
+    fn test_schema_version_compatibility() {
+        let intent = Intent::test_swap();
+        let mut json = serde_json::to_value(&intent).unwrap();
+        assert_eq!(json["schema_version"], INTENT_SCHEMA_VERSION);
+        
+        // Pre-versioning payloads still decode (as version 0) and validate
+        json.as_object_mut().unwrap().remove("schema_version");
+        let legacy: Intent = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(legacy.schema_version, 0);
+        assert!(legacy.validate(Utc::now().timestamp()).is_ok());
+        
+        // Payloads from a newer schema are refused rather than half-understood
+        json["schema_version"] = serde_json::json!(INTENT_SCHEMA_VERSION + 1);
+        let err = serde_json::from_value::<Intent>(json).unwrap_err();
+        assert!(err.to_string().contains("Unsupported intent schema_version"));
+        
+        let mut future = intent;
+        future.schema_version = INTENT_SCHEMA_VERSION + 1;
+        assert_eq!(
+            future.validate(Utc::now().timestamp()),
+            Err(IntentError::UnsupportedSchemaVersion(INTENT_SCHEMA_VERSION + 1))
+        );
+        let decoded: Intent = bincode::deserialize(&bincode::serialize(&Intent::test_swap()).unwrap()).unwrap();
+        assert_eq!(decoded.schema_version, INTENT_SCHEMA_VERSION);
+    }
+    
     #[test]
     fn test_missing_swap_details() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.swap_details = None;
         let current_time = Utc::now().timestamp();
         assert_eq!(
@@ -588,7 +967,7 @@ mod tests {
 
     #[test]
     fn test_zero_amount() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.swap_details.as_mut().unwrap().amount = 0;
         let current_time = Utc::now().timestamp();
         assert_eq!(
@@ -599,7 +978,7 @@ mod tests {
 
     #[test]
     fn test_same_mints() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         let same_mint = Pubkey::new_unique();
         let swap = intent.swap_details.as_mut().unwrap();
         swap.input_mint = same_mint;
@@ -613,7 +992,7 @@ mod tests {
 
     #[test]
     fn test_slippage_too_high() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.constraints.max_slippage_bps = 10001;
         let current_time = Utc::now().timestamp();
         assert_eq!(
@@ -624,16 +1003,30 @@ mod tests {
 
     #[test]
     fn test_zero_fees() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.fee_preferences.max_priority_fee_lamports = 0;
         intent.fee_preferences.max_jito_tip_lamports = 0;
         let current_time = Utc::now().timestamp();
         assert_eq!(intent.validate(current_time), Err(IntentError::ZeroFees));
     }
 
+    #[test]
+    fn test_fee_split_respects_caps() {
+        let prefs = FeePreferences::default();
+
+        let split = prefs.split(60_000);
+        assert_eq!(split.jito_tip_lamports, 42_000);
+        assert_eq!(split.priority_fee_lamports, 18_000);
+
+        // Tip share capped at max_jito_tip_lamports (50k)
+        let split = prefs.split(1_000_000);
+        assert_eq!(split.jito_tip_lamports, 50_000);
+        assert_eq!(split.priority_fee_lamports, 100_000);
+    }
+
     #[test]
     fn test_invalid_tip_allocation() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.fee_preferences.tip_allocation_pct = 101;
         let current_time = Utc::now().timestamp();
         assert_eq!(
@@ -644,7 +1037,7 @@ mod tests {
 
     #[test]
     fn test_expired_intent() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         let current_time = Utc::now().timestamp();
         intent.constraints.expiry_timestamp = Some(current_time - 10);
         assert_eq!(
@@ -655,7 +1048,7 @@ mod tests {
 
     #[test]
     fn test_expiry_with_buffer() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         let current_time = Utc::now().timestamp();
         // Expiry too close (within buffer)
         intent.constraints.expiry_timestamp = Some(current_time + 10);
@@ -669,9 +1062,24 @@ mod tests {
         assert!(intent.validate(current_time).is_ok());
     }
 
+    #[test]
+    fn test_validate_at_clock() {
+        let clock = crate::clock::ManualClock::at_timestamp(1_700_000_000);
+        let mut intent = Intent::test_swap();
+        intent.constraints.expiry_timestamp = Some(1_700_000_060);
+        assert!(intent.validate_at(&clock).is_ok());
+
+        // The same intent fails once the clock reaches the expiry buffer
+        clock.advance(std::time::Duration::from_secs(31));
+        assert_eq!(
+            intent.validate_at(&clock),
+            Err(IntentError::InvalidExpiry(EXPIRY_BUFFER_SECS))
+        );
+    }
+
     #[test]
     fn test_priority_levels() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         
         // Low priority
         intent.fee_preferences.max_priority_fee_lamports = 5_000;
@@ -694,10 +1102,17 @@ mod tests {
         assert_eq!(intent.priority_level(), Priority::Critical);
     }
 
+    #[test]
+    fn test_validate_traced_records_stage() {
+        let mut tracer = LatencyTracer::new();
+        assert!(Intent::test_swap().validate_traced(Utc::now().timestamp(), &mut tracer).is_ok());
+        assert!(tracer.stage(PipelineStage::Validate).is_some());
+    }
+
     #[test]
     fn test_intent_hashing() {
-        let intent1 = create_valid_swap_intent();
-        let intent2 = create_valid_swap_intent();
+        let intent1 = Intent::test_swap();
+        let intent2 = Intent::test_swap();
         
         // Same content should produce same hash
         let hash1 = intent1.hash();
@@ -711,7 +1126,7 @@ mod tests {
 
     #[test]
     fn test_limit_intent_unimplemented() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.intent_type = IntentType::Limit;
         intent.limit_details = Some(LimitDetails {
             price_threshold: 1.5,
@@ -724,7 +1139,7 @@ mod tests {
 
     #[test]
     fn test_twap_intent_unimplemented() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.intent_type = IntentType::TWAP;
         intent.twap_details = Some(TwapDetails {
             duration_secs: 3600,
@@ -737,7 +1152,7 @@ mod tests {
 
     #[test]
     fn test_invalid_nonce_format() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         intent.consent_block.nonce = Some("invalid_base58!!!".to_string());
         let current_time = Utc::now().timestamp();
         assert_eq!(
@@ -748,7 +1163,7 @@ mod tests {
 
     #[test]
     fn test_valid_nonce() {
-        let mut intent = create_valid_swap_intent();
+        let mut intent = Intent::test_swap();
         // Use a valid base58 encoded hash
         let valid_hash = Hash::new_unique();
         intent.consent_block.nonce = Some(valid_hash.to_string());
@@ -758,7 +1173,7 @@ mod tests {
 
     #[test]
     fn test_json_serialization_roundtrip() {
-        let intent = create_valid_swap_intent();
+        let intent = Intent::test_swap();
         let json = serde_json::to_string(&intent).expect("Serialization failed");
         let deserialized: Intent =
             serde_json::from_str(&json).expect("Deserialization failed");
@@ -773,7 +1188,7 @@ mod tests {
 
     #[test]
     fn test_bincode_serialization() {
-        let intent = create_valid_swap_intent();
+        let intent = Intent::test_swap();
         let encoded = bincode::serialize(&intent)
             .expect("Encoding failed");
         let decoded: Intent = bincode::deserialize(&encoded)
@@ -792,4 +1207,124 @@ mod tests {
         assert_eq!(fee_prefs.max_jito_tip_lamports, 50_000);
         assert_eq!(fee_prefs.tip_allocation_pct, 70);
     }
+
+    fn swap_leg(input_mint: Pubkey, output_mint: Pubkey, amount: u64, minimum_received: Option<u64>) -> IntentLeg {
+        IntentLeg::Swap(SwapDetails {
+            mode: SwapMode::ExactIn,
+            input_mint,
+            output_mint,
+            amount,
+            minimum_received,
+            dex: None,
+            route_hints: None,
+        })
+    }
+
+    fn deposit_leg(mint: Pubkey, amount: Option<u64>) -> IntentLeg {
+        IntentLeg::Deposit(DepositDetails {
+            program_id: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            mint,
+            amount,
+        })
+    }
+
+    fn bundle_intent(legs: Vec<IntentLeg>) -> Intent {
+        let mut intent = Intent::test_swap();
+        intent.intent_type = IntentType::Bundle;
+        intent.swap_details = None;
+        intent.legs = legs;
+        intent
+    }
+
+    #[test]
+    fn test_swap_then_deposit_bundle() {
+        let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let now = Utc::now().timestamp();
+
+        let intent = bundle_intent(vec![swap_leg(usdc, sol, 1_000, Some(900)), deposit_leg(sol, None)]);
+        assert!(intent.validate(now).is_ok());
+
+        let intent = bundle_intent(vec![swap_leg(usdc, sol, 1_000, Some(900)), deposit_leg(sol, Some(900))]);
+        assert!(intent.validate(now).is_ok());
+
+        let json = serde_json::to_string(&intent).unwrap();
+        assert!(json.contains(r#""deposit":{"#));
+        assert_eq!(serde_json::from_str::<Intent>(&json).unwrap(), intent);
+    }
+
+    #[test]
+    fn test_leg_flow_mismatch() {
+        let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let now = Utc::now().timestamp();
+
+        // Deposits more than the swap guarantees
+        let intent = bundle_intent(vec![swap_leg(usdc, sol, 1_000, Some(900)), deposit_leg(sol, Some(950))]);
+        assert_eq!(
+            intent.validate(now),
+            Err(IntentError::LegFlowMismatch { leg: 1, mint: sol, required: 950, available: 900 })
+        );
+
+        // Without minimum_received nothing is guaranteed to the next leg
+        let intent = bundle_intent(vec![swap_leg(usdc, sol, 1_000, None), deposit_leg(sol, None)]);
+        assert!(matches!(intent.validate(now), Err(IntentError::LegFlowMismatch { leg: 1, .. })));
+
+        // "Deposit everything" of a mint no earlier leg produces
+        let intent = bundle_intent(vec![deposit_leg(usdc, None)]);
+        assert!(matches!(intent.validate(now), Err(IntentError::LegFlowMismatch { leg: 0, .. })));
+    }
+
+    #[test]
+    fn test_leg_outputs_overflowing_u64_rejected() {
+        let sol = Pubkey::new_unique();
+        let legs = vec![
+            swap_leg(Pubkey::new_unique(), sol, 1_000, Some(u64::MAX)),
+            swap_leg(Pubkey::new_unique(), sol, 1_000, Some(1)),
+        ];
+        assert_eq!(bundle_intent(legs).validate(Utc::now().timestamp()), Err(IntentError::InvalidAmount));
+    }
+
+    #[test]
+    fn test_leg_count_and_type() {
+        let now = Utc::now().timestamp();
+        assert_eq!(bundle_intent(vec![]).validate(now), Err(IntentError::MissingLegs));
+
+        let legs = (0..=MAX_INTENT_LEGS)
+            .map(|_| deposit_leg(Pubkey::new_unique(), Some(1)))
+            .collect();
+        assert_eq!(bundle_intent(legs).validate(now), Err(IntentError::TooManyLegs(MAX_INTENT_LEGS)));
+
+        let mut intent = Intent::test_swap();
+        intent.legs = vec![deposit_leg(Pubkey::new_unique(), Some(1))];
+        assert_eq!(intent.validate(now), Err(IntentError::UnexpectedLegs));
+    }
+
+    fn dca_intent(interval_secs: u32, total_amount: u64, num_orders: u16) -> Intent {
+        let mut intent = Intent::test_swap();
+        intent.intent_type = IntentType::DCA;
+        intent.dca_details = Some(DcaDetails { interval_secs, total_amount, num_orders });
+        intent
+    }
+
+    #[test]
+    fn test_dca_validation() {
+        let now = Utc::now().timestamp();
+        assert!(dca_intent(86_400, 7_000_000, 7).validate(now).is_ok());
+
+        let mut missing = dca_intent(86_400, 7_000_000, 7);
+        missing.dca_details = None;
+        assert_eq!(missing.validate(now), Err(IntentError::MissingDcaDetails));
+
+        assert!(matches!(dca_intent(86_400, 1_000, 1).validate(now), Err(IntentError::InvalidDcaSchedule(_))));
+        assert!(matches!(dca_intent(30, 1_000, 5).validate(now), Err(IntentError::InvalidDcaSchedule(_))));
+        assert!(matches!(dca_intent(86_400, 1_000, 400).validate(now), Err(IntentError::InvalidDcaSchedule(_))));
+        assert_eq!(dca_intent(86_400, 3, 5).validate(now), Err(IntentError::InvalidAmount));
+    }
+
+    #[test]
+    fn test_dca_order_amounts() {
+        let details = DcaDetails { interval_secs: 3_600, total_amount: 1_000, num_orders: 3 };
+        let amounts: Vec<u64> = (0..3).map(|i| details.order_amount(i)).collect();
+        assert_eq!(amounts, vec![333, 333, 334]);
+    }
 }
@@ -0,0 +1,196 @@
+//! Hardened Intent Decoding
+//!
+//! Intents arrive as untrusted JSON. serde_json on its own accepts arbitrarily
+//! long strings and arrays and nests up to 128 levels, so a hostile body can
+//! allocate far more than its size suggests before `Intent` rejects it.
+//! [`check_json`] scans the raw bytes first (no allocation beyond a depth-bounded
+//! stack) and rejects bodies over the size, depth, string or collection limits;
+//! only then is the payload handed to serde.
+//!
+//! The intent schema is at most 4 levels deep, its strings are ids, base58 keys
+//! and DEX names, and its largest collections are 32-byte keys and route hints,
+//! so [`JsonLimits::default`] leaves ample headroom for valid intents.
+
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::intent::Intent;
+
+/// Structural limits applied before deserialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    /// Whole body (bytes)
+    pub max_bytes: usize,
+
+    /// Nested objects/arrays
+    pub max_depth: usize,
+
+    /// Any string, including object keys (raw bytes, escapes unexpanded)
+    pub max_string_len: usize,
+
+    /// Elements of any array or members of any object
+    pub max_collection_len: usize,
+}
+
+impl Default for JsonLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024,
+            max_depth: 16,
+            max_string_len: 256,
+            max_collection_len: 64,
+        }
+    }
+}
+
+/// Why an untrusted body was rejected
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("Body is {size} bytes (max {max})")]
+    TooLarge { size: usize, max: usize },
+
+    #[error("Nesting exceeds {0} levels")]
+    TooDeep(usize),
+
+    #[error("String at byte {offset} exceeds {max} bytes")]
+    StringTooLong { offset: usize, max: usize },
+
+    #[error("Collection at byte {offset} exceeds {max} entries")]
+    CollectionTooLong { offset: usize, max: usize },
+
+    #[error("Malformed JSON: {0}")]
+    Malformed(String),
+}
+
+/// Check a raw body against `limits` without deserializing it
+///
+/// Only structure is checked; syntax errors are left to the parser.
+pub fn check_json(bytes: &[u8], limits: &JsonLimits) -> Result<(), DecodeError> {
+    if bytes.len() > limits.max_bytes {
+        return Err(DecodeError::TooLarge {
+            size: bytes.len(),
+            max: limits.max_bytes,
+        });
+    }
+
+    // (opening offset, separators seen) per open collection
+    let mut open: Vec<(usize, usize)> = Vec::with_capacity(limits.max_depth.min(64));
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if i.min(bytes.len()) - start - 1 > limits.max_string_len {
+                    return Err(DecodeError::StringTooLong {
+                        offset: start,
+                        max: limits.max_string_len,
+                    });
+                }
+            }
+            b'{' | b'[' => {
+                if open.len() >= limits.max_depth {
+                    return Err(DecodeError::TooDeep(limits.max_depth));
+                }
+                open.push((i, 0));
+            }
+            b'}' | b']' => {
+                open.pop();
+            }
+            b',' => {
+                if let Some((offset, separators)) = open.last_mut() {
+                    *separators += 1;
+                    if *separators >= limits.max_collection_len {
+                        return Err(DecodeError::CollectionTooLong {
+                            offset: *offset,
+                            max: limits.max_collection_len,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Deserialize an untrusted body after checking it against `limits`
+pub fn decode_json<T: DeserializeOwned>(bytes: &[u8], limits: &JsonLimits) -> Result<T, DecodeError> {
+    check_json(bytes, limits)?;
+    serde_json::from_slice(bytes).map_err(|e| DecodeError::Malformed(e.to_string()))
+}
+
+/// Deserialize an untrusted intent with the default limits
+///
+/// The intent is not validated; call [`Intent::validate`] next.
+#[tracing::instrument(name = "intent.intake", skip_all, fields(bytes = bytes.len()))]
+pub fn decode_intent(bytes: &[u8]) -> Result<Intent, DecodeError> {
+    decode_json(bytes, &JsonLimits::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn intent_json() -> Vec<u8> {
+        let mut intent = Intent::test_swap();
+        intent.consent_block.nonce = Some(Hash::new_unique().to_string());
+        let swap = intent.test_swap_details();
+        swap.minimum_received = Some(990_000);
+        swap.dex = Some("Jupiter".to_string());
+        swap.route_hints = Some(vec![Pubkey::new_unique(); 8]);
+        serde_json::to_vec(&intent).unwrap()
+    }
+
+    #[test]
+    fn test_valid_intent_within_limits() {
+        let json = intent_json();
+        assert!(check_json(&json, &JsonLimits::default()).is_ok());
+        assert!(decode_intent(&json).is_ok());
+    }
+
+    #[test]
+    fn test_structural_limits() {
+        let limits = JsonLimits {
+            max_bytes: 1024,
+            max_depth: 3,
+            max_string_len: 8,
+            max_collection_len: 4,
+        };
+
+        assert_eq!(check_json(&[b' '; 1025], &limits), Err(DecodeError::TooLarge { size: 1025, max: 1024 }));
+        assert_eq!(check_json(b"[[[1]]]", &limits), Ok(()));
+        assert_eq!(check_json(b"[[[[1]]]]", &limits), Err(DecodeError::TooDeep(3)));
+        assert_eq!(check_json(br#"{"a":"12345678"}"#, &limits), Ok(()));
+        assert_eq!(
+            check_json(br#"{"a":"123456789"}"#, &limits),
+            Err(DecodeError::StringTooLong { offset: 5, max: 8 })
+        );
+        assert_eq!(check_json(b"[1,2,3,4]", &limits), Ok(()));
+        assert_eq!(
+            check_json(b"[1,2,3,4,5]", &limits),
+            Err(DecodeError::CollectionTooLong { offset: 0, max: 4 })
+        );
+    }
+
+    #[test]
+    fn test_brackets_inside_strings_ignored() {
+        let limits = JsonLimits {
+            max_depth: 1,
+            ..JsonLimits::default()
+        };
+        assert_eq!(check_json(br#"{"k":"[[[\"{{,,"}"#, &limits), Ok(()));
+    }
+
+    #[test]
+    fn test_malformed_reported_not_panicking() {
+        assert!(matches!(decode_intent(b"{\"intent_id\":"), Err(DecodeError::Malformed(_))));
+        assert!(matches!(decode_intent(b"\"unterminated\\"), Err(DecodeError::Malformed(_))));
+    }
+}
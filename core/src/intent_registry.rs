@@ -0,0 +1,473 @@
+//! Intent Cancel / Replace
+//!
+//! Limit, TWAP and DCA intents can sit pending for a long time, so users need
+//! a way to withdraw or tighten them. Both operations are authorized by a
+//! [`Cancellation`] signed with the original `user_public_key`:
+//! - `cancel` marks a pending intent `Cancelled`
+//! - `replace` cancels it and registers a new intent under the same lock, so
+//!   there is no window where both (or neither) are live. The cancellation
+//!   commits to the replacement's `Intent::hash()`, and the replacement must
+//!   carry a fresh consent block.
+//!
+//! Cancellations carry a timestamp and are rejected outside
+//! `MAX_CANCELLATION_SKEW_SECS`; an intent can only leave `Pending` once, so a
+//! captured cancellation cannot be replayed.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::{Signature, Signer};
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tracing::info;
+
+use crate::intent::{Intent, IntentStatus};
+use crate::timeline::{ExecutionTimeline, TimelineEvent, TimelineUpdate};
+use crate::{Result, SentinelError};
+
+/// Maximum clock difference between a cancellation and the router (seconds)
+pub const MAX_CANCELLATION_SKEW_SECS: i64 = 300;
+
+/// Request to withdraw a pending intent, optionally replacing it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cancellation {
+    pub intent_id: String,
+
+    /// `Intent::hash()` of the replacement intent (None = plain cancel)
+    pub replacement_hash: Option<Hash>,
+
+    /// Signing time (unix seconds)
+    pub timestamp: i64,
+}
+
+impl Cancellation {
+    pub fn new(intent_id: impl Into<String>, timestamp: i64) -> Self {
+        Self {
+            intent_id: intent_id.into(),
+            replacement_hash: None,
+            timestamp,
+        }
+    }
+
+    /// Commit the cancellation to a specific replacement intent
+    pub fn with_replacement(mut self, replacement: &Intent) -> Self {
+        self.replacement_hash = Some(replacement.hash());
+        self
+    }
+
+    /// Hash the user signs (BLAKE3 over bincode, like `Intent::hash`)
+    pub fn hash(&self) -> Hash {
+        let serialized = bincode::serialize(self).expect("Cancellation serialization failed");
+        Hash::new_from_array(*blake3::hash(&serialized).as_bytes())
+    }
+
+    /// Sign with the wallet that owns the intent
+    pub fn sign<S: Signer + ?Sized>(self, user: &S) -> SignedCancellation {
+        let signature = user.sign_message(self.hash().as_ref());
+        SignedCancellation {
+            cancellation: self,
+            signature,
+        }
+    }
+}
+
+/// Cancellation plus the user's signature over its hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedCancellation {
+    pub cancellation: Cancellation,
+    pub signature: Signature,
+}
+
+/// Registry entry for a submitted intent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegisteredIntent {
+    pub intent: Intent,
+    pub status: IntentStatus,
+
+    /// Intent that superseded this one via `replace`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+
+    /// Execution progress events (starts with `Validated`)
+    pub timeline: ExecutionTimeline,
+}
+
+impl RegisteredIntent {
+    /// When the intent stops being executable (unix seconds): its
+    /// `expiry_timestamp`, else registration plus `ttl_seconds`
+    pub fn expires_at(&self) -> Option<i64> {
+        let constraints = &self.intent.constraints;
+        constraints.expiry_timestamp.or_else(|| {
+            let ttl = constraints.ttl_seconds?;
+            let registered_ms = self.timeline.entries.first()?.timestamp_ms;
+            Some(registered_ms.div_euclid(1_000) + ttl as i64)
+        })
+    }
+}
+
+fn rejected(reason: impl Into<String>) -> SentinelError {
+    SentinelError::InvalidIntent(format!("Cancellation rejected: {}", reason.into()))
+}
+
+/// Submitted intents keyed by intent id
+#[derive(Default)]
+pub struct IntentRegistry {
+    intents: RwLock<HashMap<String, RegisteredIntent>>,
+}
+
+impl IntentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a validated intent as `Pending`
+    pub fn register(&self, intent: Intent, now: i64) -> Result<()> {
+        intent.validate(now)?;
+
+        let mut intents = self.write();
+        if intents.contains_key(&intent.intent_id) {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Intent {} is already registered",
+                intent.intent_id
+            )));
+        }
+        intents.insert(intent.intent_id.clone(), Self::pending(intent, now));
+        Ok(())
+    }
+
+    pub fn get(&self, intent_id: &str) -> Option<RegisteredIntent> {
+        self.read().get(intent_id).cloned()
+    }
+
+    pub fn status(&self, intent_id: &str) -> Option<IntentStatus> {
+        self.read().get(intent_id).map(|entry| entry.status.clone())
+    }
+
+    /// Record an execution status transition; false if the intent is unknown
+    pub fn set_status(&self, intent_id: &str, status: IntentStatus) -> bool {
+        match self.write().get_mut(intent_id) {
+            Some(entry) => {
+                entry.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Append an execution event; None if the intent is unknown
+    ///
+    /// The returned update is what stream subscribers receive.
+    pub fn record_event(&self, intent_id: &str, event: TimelineEvent, timestamp_ms: i64) -> Option<TimelineUpdate> {
+        let mut intents = self.write();
+        let entry = intents.get_mut(intent_id)?;
+        Some(TimelineUpdate {
+            intent_id: intent_id.to_string(),
+            entry: entry.timeline.record(event, timestamp_ms),
+        })
+    }
+
+    /// Mark pending intents whose expiry has passed at `now` (unix seconds)
+    /// `Expired`; returns the `Expired` events recorded
+    pub fn expire_due(&self, now: i64) -> Vec<TimelineUpdate> {
+        let mut intents = self.write();
+        let mut expired = Vec::new();
+        for (intent_id, entry) in intents.iter_mut() {
+            let due = entry.expires_at().is_some_and(|at| at <= now);
+            if entry.status != IntentStatus::Pending || !due {
+                continue;
+            }
+            entry.status = IntentStatus::Expired;
+            expired.push(TimelineUpdate {
+                intent_id: intent_id.clone(),
+                entry: entry.timeline.record(TimelineEvent::Expired, now.saturating_mul(1_000)),
+            });
+        }
+        expired
+    }
+
+    pub fn timeline(&self, intent_id: &str) -> Option<ExecutionTimeline> {
+        self.read().get(intent_id).map(|entry| entry.timeline.clone())
+    }
+
+    /// Cancel a pending intent
+    pub fn cancel(&self, intent_id: &str, signed: &SignedCancellation, now: i64) -> Result<()> {
+        if signed.cancellation.replacement_hash.is_some() {
+            return Err(rejected("cancellation commits to a replacement; use replace"));
+        }
+
+        let mut intents = self.write();
+        let entry = Self::authorize(&mut intents, intent_id, signed, now)?;
+        entry.status = IntentStatus::Cancelled;
+
+        info!("🛑 Intent {} cancelled by {}", intent_id, entry.intent.user_public_key);
+        Ok(())
+    }
+
+    /// Cancel a pending intent and register `replacement` in its place
+    pub fn replace(
+        &self,
+        intent_id: &str,
+        signed: &SignedCancellation,
+        replacement: Intent,
+        now: i64,
+    ) -> Result<()> {
+        if signed.cancellation.replacement_hash != Some(replacement.hash()) {
+            return Err(rejected("replacement does not match the signed cancellation"));
+        }
+        replacement.validate(now)?;
+
+        let mut intents = self.write();
+        if intents.contains_key(&replacement.intent_id) {
+            return Err(rejected(format!(
+                "replacement id {} is already registered",
+                replacement.intent_id
+            )));
+        }
+
+        let entry = Self::authorize(&mut intents, intent_id, signed, now)?;
+        if replacement.user_public_key != entry.intent.user_public_key {
+            return Err(rejected("replacement belongs to a different user"));
+        }
+        let consent = &entry.intent.consent_block;
+        if replacement.consent_block.signature_request_id == consent.signature_request_id {
+            return Err(rejected("replacement must carry a new consent block"));
+        }
+
+        entry.status = IntentStatus::Cancelled;
+        entry.replaced_by = Some(replacement.intent_id.clone());
+
+        info!("🔁 Intent {} replaced by {}", intent_id, replacement.intent_id);
+        intents.insert(replacement.intent_id.clone(), Self::pending(replacement, now));
+        Ok(())
+    }
+
+    /// Verify the cancellation against the original user key; returns the entry to mutate
+    fn authorize<'a>(
+        intents: &'a mut HashMap<String, RegisteredIntent>,
+        intent_id: &str,
+        signed: &SignedCancellation,
+        now: i64,
+    ) -> Result<&'a mut RegisteredIntent> {
+        let cancellation = &signed.cancellation;
+        if cancellation.intent_id != intent_id {
+            return Err(rejected("signed for a different intent"));
+        }
+
+        let entry = intents
+            .get_mut(intent_id)
+            .ok_or_else(|| rejected(format!("unknown intent {}", intent_id)))?;
+
+        // Signature first: nothing else in the request is trusted until it verifies
+        if !signed
+            .signature
+            .verify(entry.intent.user_public_key.as_ref(), cancellation.hash().as_ref())
+        {
+            return Err(rejected("signature does not match the intent's user"));
+        }
+        if now.abs_diff(cancellation.timestamp) > MAX_CANCELLATION_SKEW_SECS as u64 {
+            return Err(rejected("timestamp outside the allowed window"));
+        }
+        if entry.status != IntentStatus::Pending {
+            return Err(rejected(format!("intent is {:?}, not pending", entry.status)));
+        }
+
+        Ok(entry)
+    }
+
+    /// New `Pending` entry; registration implies validation at `now` (unix seconds)
+    fn pending(intent: Intent, now: i64) -> RegisteredIntent {
+        let mut timeline = ExecutionTimeline::new();
+        timeline.record(TimelineEvent::Validated, now.saturating_mul(1_000));
+        RegisteredIntent {
+            intent,
+            status: IntentStatus::Pending,
+            replaced_by: None,
+            timeline,
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, RegisteredIntent>> {
+        self.intents.read().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, RegisteredIntent>> {
+        self.intents.write().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::{Constraints, IntentType, LimitDetails};
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn limit_intent(user: &Keypair, id: &str, request_id: &str, slippage_bps: u16) -> Intent {
+        let mut intent = Intent {
+            intent_id: id.to_string(),
+            user_public_key: user.pubkey(),
+            intent_type: IntentType::Limit,
+            constraints: Constraints {
+                max_slippage_bps: slippage_bps,
+                ..Constraints::default()
+            },
+            limit_details: Some(LimitDetails {
+                price_threshold: 100.0,
+                oracle: None,
+            }),
+            ..Intent::test_swap()
+        };
+        intent.consent_block.signature_request_id = request_id.to_string();
+        let swap = intent.test_swap_details();
+        swap.input_mint = Pubkey::new_from_array([1; 32]);
+        swap.output_mint = Pubkey::new_from_array([2; 32]);
+        swap.minimum_received = None;
+        intent
+    }
+
+    fn registered(user: &Keypair) -> IntentRegistry {
+        let registry = IntentRegistry::new();
+        registry.register(limit_intent(user, "limit-1", "req-1", 100), NOW).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_timeline_starts_validated_and_records_events() {
+        let user = Keypair::new();
+        let registry = registered(&user);
+        assert_eq!(registry.timeline("limit-1").unwrap().entries[0].event, TimelineEvent::Validated);
+
+        let update = registry
+            .record_event("limit-1", TimelineEvent::Submitted { bundle_id: "b-1".into() }, NOW * 1_000 + 250)
+            .unwrap();
+        assert_eq!(update.intent_id, "limit-1");
+        assert_eq!(update.entry.timestamp_ms, NOW * 1_000 + 250);
+
+        let timeline = registry.get("limit-1").unwrap().timeline;
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline.elapsed_ms(), 250);
+        assert!(registry.record_event("unknown", TimelineEvent::Validated, 0).is_none());
+    }
+
+    #[test]
+    fn test_cancel_pending_intent() {
+        let user = Keypair::new();
+        let registry = registered(&user);
+
+        let signed = Cancellation::new("limit-1", NOW).sign(&user);
+        registry.cancel("limit-1", &signed, NOW + 5).unwrap();
+        assert_eq!(registry.status("limit-1"), Some(IntentStatus::Cancelled));
+
+        // Already cancelled: the same cancellation cannot be replayed
+        assert!(registry.cancel("limit-1", &signed, NOW + 5).unwrap_err().to_string().contains("not pending"));
+    }
+
+    #[test]
+    fn test_cancel_requires_original_user() {
+        let user = Keypair::new();
+        let registry = registered(&user);
+
+        let forged = Cancellation::new("limit-1", NOW).sign(&Keypair::new());
+        assert!(registry.cancel("limit-1", &forged, NOW).is_err());
+
+        let stale = Cancellation::new("limit-1", NOW - 3_600).sign(&user);
+        assert!(registry.cancel("limit-1", &stale, NOW).is_err());
+
+        let other = Cancellation::new("limit-2", NOW).sign(&user);
+        assert!(registry.cancel("limit-1", &other, NOW).is_err());
+
+        registry.set_status("limit-1", IntentStatus::Submitted);
+        let late = Cancellation::new("limit-1", NOW).sign(&user);
+        assert!(registry.cancel("limit-1", &late, NOW).is_err());
+        assert_eq!(registry.status("limit-1"), Some(IntentStatus::Submitted));
+    }
+
+    #[test]
+    fn test_cancel_rejects_extreme_timestamps() {
+        let user = Keypair::new();
+        let registry = registered(&user);
+
+        for timestamp in [i64::MIN, i64::MAX] {
+            let unsigned = SignedCancellation {
+                cancellation: Cancellation::new("limit-1", timestamp),
+                signature: Signature::default(),
+            };
+            let err = registry.cancel("limit-1", &unsigned, NOW).unwrap_err().to_string();
+            assert!(err.contains("signature"), "{}", err);
+
+            let signed = Cancellation::new("limit-1", timestamp).sign(&user);
+            let err = registry.cancel("limit-1", &signed, NOW).unwrap_err().to_string();
+            assert!(err.contains("outside the allowed window"), "{}", err);
+        }
+        assert_eq!(registry.status("limit-1"), Some(IntentStatus::Pending));
+    }
+
+    #[test]
+    fn test_replace_swaps_atomically() {
+        let user = Keypair::new();
+        let registry = registered(&user);
+
+        let tighter = limit_intent(&user, "limit-1b", "req-2", 25);
+        let signed = Cancellation::new("limit-1", NOW).with_replacement(&tighter).sign(&user);
+        registry.replace("limit-1", &signed, tighter, NOW).unwrap();
+
+        let old = registry.get("limit-1").unwrap();
+        assert_eq!(old.status, IntentStatus::Cancelled);
+        assert_eq!(old.replaced_by.as_deref(), Some("limit-1b"));
+
+        let new = registry.get("limit-1b").unwrap();
+        assert_eq!(new.status, IntentStatus::Pending);
+        assert_eq!(new.intent.constraints.max_slippage_bps, 25);
+    }
+
+    #[test]
+    fn test_replace_rejections_leave_original_pending() {
+        let user = Keypair::new();
+        let registry = registered(&user);
+
+        // Replacement differs from what the user signed
+        let signed_for = limit_intent(&user, "limit-1b", "req-2", 25);
+        let signed = Cancellation::new("limit-1", NOW).with_replacement(&signed_for).sign(&user);
+        let swapped = limit_intent(&user, "limit-1b", "req-2", 500);
+        assert!(registry.replace("limit-1", &signed, swapped, NOW).is_err());
+
+        // Reused consent block
+        let reused = limit_intent(&user, "limit-1b", "req-1", 25);
+        let signed = Cancellation::new("limit-1", NOW).with_replacement(&reused).sign(&user);
+        assert!(registry.replace("limit-1", &signed, reused, NOW).unwrap_err().to_string().contains("consent"));
+
+        // Plain cancel carrying a replacement commitment
+        assert!(registry.cancel("limit-1", &signed, NOW).is_err());
+
+        assert_eq!(registry.status("limit-1"), Some(IntentStatus::Pending));
+        assert!(registry.get("limit-1b").is_none());
+    }
+
+    #[test]
+    fn test_expire_due_by_expiry_or_ttl() {
+        let user = Keypair::new();
+        let registry = IntentRegistry::new();
+        let mut by_expiry = limit_intent(&user, "expiry", "req-1", 100);
+        by_expiry.constraints.expiry_timestamp = Some(NOW + 600);
+        let mut by_ttl = limit_intent(&user, "ttl", "req-2", 100);
+        by_ttl.constraints.ttl_seconds = Some(60);
+        for intent in [by_expiry, by_ttl, limit_intent(&user, "open", "req-3", 100)] {
+            registry.register(intent, NOW).unwrap();
+        }
+        assert_eq!(registry.get("ttl").unwrap().expires_at(), Some(NOW + 60));
+
+        assert!(registry.expire_due(NOW + 59).is_empty());
+        let expired = registry.expire_due(NOW + 60);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].intent_id, "ttl");
+        assert_eq!(expired[0].entry.event, TimelineEvent::Expired);
+        assert!(registry.timeline("ttl").unwrap().is_terminal());
+
+        // Already expired and intents without a deadline stay put
+        let expired = registry.expire_due(NOW + 3_600);
+        assert_eq!(expired.iter().map(|u| u.intent_id.as_str()).collect::<Vec<_>>(), ["expiry"]);
+        assert_eq!(registry.status("open"), Some(IntentStatus::Pending));
+        assert_eq!(registry.status("ttl"), Some(IntentStatus::Expired));
+    }
+}
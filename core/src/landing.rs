@@ -0,0 +1,270 @@
+//! Bundle Landing Prediction
+//!
+//! [`RouteEconomics`](crate::route_economics::RouteEconomics) prices retries by
+//! `1 / p_land`, but a fixed per-route probability ignores what actually
+//! decides landing: how competitive the tip is, who the leader is, how many
+//! transactions the bundle carries and how the route has been doing lately.
+//! [`LandingPredictor`] learns that from our own submission history with one
+//! small online logistic model per route over:
+//!
+//! - tip percentile (0-1) vs. recent / network tips
+//! - `ln(bundle_size)`: every extra transaction is another chance to revert
+//! - leader acceptance: the leader's landed share of our submissions, shrunk
+//!   toward the route's rate until it has history
+//! - route acceptance: EWMA of recent outcomes on the route
+//!
+//! Weights start from a sensible prior (≈0.83 for a median tip on a healthy
+//! route) and move by SGD on every observed outcome. Predictions only replace
+//! the profile defaults once a route has `min_observations`.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::types::RouteType;
+
+/// Feature count (bias, tip, size, leader, route)
+const FEATURES: usize = 5;
+
+/// Prior weights for [`FEATURES`]
+const PRIOR_WEIGHTS: [f64; FEATURES] = [-3.0, 1.5, -0.3, 2.0, 2.5];
+
+/// Submission context the prediction is conditioned on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LandingContext {
+    /// Tip percentile vs. recent tips (0-100)
+    pub tip_percentile: f32,
+
+    /// Leader of the target slot, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leader: Option<Pubkey>,
+
+    /// Transactions in the bundle (1 for single transactions)
+    pub bundle_size: usize,
+}
+
+/// One historical submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LandingObservation {
+    pub route: RouteType,
+    pub context: LandingContext,
+    pub landed: bool,
+}
+
+/// Predictor tuning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LandingPredictorSettings {
+    /// SGD step size
+    pub learning_rate: f64,
+
+    /// Weight of the newest outcome in the route acceptance EWMA
+    pub route_rate_alpha: f64,
+
+    /// Pseudo-observations pulling a leader's rate toward the route's
+    pub leader_prior_strength: f64,
+
+    /// Outcomes on a route before its predictions override the profile default
+    pub min_observations: u64,
+}
+
+impl Default for LandingPredictorSettings {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.05,
+            route_rate_alpha: 0.05,
+            leader_prior_strength: 4.0,
+            min_observations: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RouteModel {
+    route: RouteType,
+    weights: [f64; FEATURES],
+    acceptance: f64,
+    observations: u64,
+    leaders: HashMap<Pubkey, (u64, u64)>,
+}
+
+impl RouteModel {
+    fn new(route: RouteType) -> Self {
+        Self {
+            route,
+            weights: PRIOR_WEIGHTS,
+            acceptance: 0.85,
+            observations: 0,
+            leaders: HashMap::new(),
+        }
+    }
+
+    fn features(&self, context: &LandingContext, prior_strength: f64) -> [f64; FEATURES] {
+        let leader_rate = match context.leader.and_then(|leader| self.leaders.get(&leader)) {
+            Some(&(landed, total)) => (landed as f64 + prior_strength * self.acceptance) / (total as f64 + prior_strength),
+            None => self.acceptance,
+        };
+        [
+            1.0,
+            (context.tip_percentile as f64 / 100.0).clamp(0.0, 1.0),
+            (context.bundle_size.max(1) as f64).ln(),
+            leader_rate,
+            self.acceptance,
+        ]
+    }
+
+    fn predict(&self, context: &LandingContext, prior_strength: f64) -> f64 {
+        let x = self.features(context, prior_strength);
+        sigmoid(self.weights.iter().zip(x).map(|(w, x)| w * x).sum())
+    }
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+/// Online landing-probability model per route
+#[derive(Debug, Default)]
+pub struct LandingPredictor {
+    settings: LandingPredictorSettings,
+    models: Mutex<Vec<RouteModel>>,
+}
+
+impl LandingPredictor {
+    pub fn new(settings: LandingPredictorSettings) -> Self {
+        Self {
+            settings,
+            models: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Probability a submission on `route` lands
+    pub fn predict(&self, route: &RouteType, context: &LandingContext) -> f64 {
+        let models = self.models.lock().unwrap_or_else(|p| p.into_inner());
+        match models.iter().find(|m| m.route == *route) {
+            Some(model) => model.predict(context, self.settings.leader_prior_strength),
+            None => RouteModel::new(route.clone()).predict(context, self.settings.leader_prior_strength),
+        }
+    }
+
+    /// Learn from one outcome
+    pub fn observe(&self, route: &RouteType, context: &LandingContext, landed: bool) {
+        let mut models = self.models.lock().unwrap_or_else(|p| p.into_inner());
+        let index = match models.iter().position(|m| m.route == *route) {
+            Some(index) => index,
+            None => {
+                models.push(RouteModel::new(route.clone()));
+                models.len() - 1
+            }
+        };
+        let model = &mut models[index];
+
+        // Gradient step against the features as they were before this outcome
+        let outcome = if landed { 1.0 } else { 0.0 };
+        let x = model.features(context, self.settings.leader_prior_strength);
+        let error = outcome - model.predict(context, self.settings.leader_prior_strength);
+        for (w, x) in model.weights.iter_mut().zip(x) {
+            *w += self.settings.learning_rate * error * x;
+        }
+
+        model.acceptance += self.settings.route_rate_alpha * (outcome - model.acceptance);
+        model.observations += 1;
+        if let Some(leader) = context.leader {
+            let (hits, total) = model.leaders.entry(leader).or_insert((0, 0));
+            *hits += landed as u64;
+            *total += 1;
+        }
+    }
+
+    /// Replay a batch of historical outcomes (oldest first)
+    pub fn train(&self, history: impl IntoIterator<Item = LandingObservation>) {
+        for observation in history {
+            self.observe(&observation.route, &observation.context, observation.landed);
+        }
+    }
+
+    /// Outcomes observed on `route`
+    pub fn observations(&self, route: &RouteType) -> u64 {
+        let models = self.models.lock().unwrap_or_else(|p| p.into_inner());
+        models.iter().find(|m| m.route == *route).map_or(0, |m| m.observations)
+    }
+
+    /// Predictions for routes with enough history, as `RouteCostInputs::landing_overrides`
+    pub fn overrides(&self, context: &LandingContext) -> Vec<(RouteType, f64)> {
+        let models = self.models.lock().unwrap_or_else(|p| p.into_inner());
+        models
+            .iter()
+            .filter(|m| m.observations >= self.settings.min_observations)
+            .map(|m| (m.route.clone(), m.predict(context, self.settings.leader_prior_strength)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(tip_percentile: f32, leader: Option<Pubkey>, bundle_size: usize) -> LandingContext {
+        LandingContext {
+            tip_percentile,
+            leader,
+            bundle_size,
+        }
+    }
+
+    #[test]
+    fn test_prior_orders_by_tip_and_size() {
+        let predictor = LandingPredictor::default();
+        let route = RouteType::JitoBundle;
+        let median = predictor.predict(&route, &context(50.0, None, 1));
+
+        assert!((0.8..0.86).contains(&median), "{}", median);
+        assert!(predictor.predict(&route, &context(95.0, None, 1)) > median);
+        assert!(predictor.predict(&route, &context(5.0, None, 1)) < median);
+        assert!(predictor.predict(&route, &context(50.0, None, 5)) < median);
+    }
+
+    #[test]
+    fn test_learns_leader_and_route_history() {
+        let predictor = LandingPredictor::default();
+        let route = RouteType::JitoBundle;
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let history = (0..200).flat_map(|_| {
+            [
+                LandingObservation { route: route.clone(), context: context(50.0, Some(good), 2), landed: true },
+                LandingObservation { route: route.clone(), context: context(50.0, Some(bad), 2), landed: false },
+            ]
+        });
+        predictor.train(history);
+
+        let on_good = predictor.predict(&route, &context(50.0, Some(good), 2));
+        let on_bad = predictor.predict(&route, &context(50.0, Some(bad), 2));
+        assert!(on_good > 0.7, "{}", on_good);
+        assert!(on_bad < 0.3, "{}", on_bad);
+        assert_eq!(predictor.observations(&route), 400);
+
+        // Other routes keep the prior
+        let rpc = predictor.predict(&RouteType::StandardRpc, &context(50.0, Some(bad), 1));
+        assert!(rpc > 0.8);
+    }
+
+    #[test]
+    fn test_overrides_require_history() {
+        let predictor = LandingPredictor::new(LandingPredictorSettings {
+            min_observations: 3,
+            ..Default::default()
+        });
+        let ctx = context(50.0, None, 1);
+        for _ in 0..2 {
+            predictor.observe(&RouteType::JitoSingle, &ctx, false);
+        }
+        assert!(predictor.overrides(&ctx).is_empty());
+
+        predictor.observe(&RouteType::JitoSingle, &ctx, false);
+        let overrides = predictor.overrides(&ctx);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].0, RouteType::JitoSingle);
+        assert!(overrides[0].1 < predictor.predict(&RouteType::JitoBundle, &ctx));
+    }
+}
@@ -0,0 +1,527 @@
+//! End-to-End Latency Budget Instrumentation
+//!
+//! A [`LatencyTracer`] travels with a request through
+//! validate → extract → predict → route → submit and records per-stage timings.
+//! Finishing the tracer yields a serializable [`LatencyBreakdown`] (stored in the
+//! decision log) tagged with the slowest stage; [`LatencyHistograms`] aggregates
+//! breakdowns across requests for monitoring.
+//!
+//! Each stage's call site has a `*_traced` variant that records it:
+//! `Intent::validate_traced`, `FeatureExtractor::extract_traced` and
+//! `InferenceEngine::predict_traced` (ai-engine),
+//! `SlotRiskForecaster::select_route_traced`, and
+//! `BundleSubmitter::submit_intent_traced` (jito-bundler).
+//!
+//! SLO budgets: validation <5ms, extraction <0.3ms, inference <50ms.
+//!
+//! A caller's total budget travels as a [`Deadline`], passed explicitly or
+//! task-locally through [`Deadline::scope`]. Each stage checks it
+//! before starting and, when the remaining time is below the stage budget,
+//! degrades instead of answering late: extraction skips shared history,
+//! inference answers from heuristics alone, routing skips the slot-window
+//! forecast. [`DeadlineCounters`] count those fallbacks per stage.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Pipeline stages in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Validate,
+    Extract,
+    Predict,
+    Route,
+    Submit,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::Validate,
+        PipelineStage::Extract,
+        PipelineStage::Predict,
+        PipelineStage::Route,
+        PipelineStage::Submit,
+    ];
+
+    /// SLO budget for the stage
+    pub fn budget(&self) -> Duration {
+        match self {
+            PipelineStage::Validate => Duration::from_millis(5),
+            PipelineStage::Extract => Duration::from_micros(300),
+            PipelineStage::Predict => Duration::from_millis(50),
+            PipelineStage::Route => Duration::from_millis(5),
+            PipelineStage::Submit => Duration::from_millis(500),
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Timing for a single stage
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StageTiming {
+    pub stage: PipelineStage,
+    pub duration_us: u64,
+    pub budget_us: u64,
+    pub over_budget: bool,
+}
+
+/// Per-request latency breakdown (decision log payload)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyBreakdown {
+    pub stages: Vec<StageTiming>,
+    pub total_us: u64,
+    pub slowest_stage: Option<PipelineStage>,
+}
+
+impl LatencyBreakdown {
+    /// Stages that exceeded their SLO budget
+    pub fn over_budget(&self) -> impl Iterator<Item = &StageTiming> {
+        self.stages.iter().filter(|s| s.over_budget)
+    }
+}
+
+/// Per-request stage timer
+#[derive(Debug)]
+pub struct LatencyTracer {
+    started: Instant,
+    stages: Vec<StageTiming>,
+}
+
+impl Default for LatencyTracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyTracer {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            stages: Vec::with_capacity(PipelineStage::ALL.len()),
+        }
+    }
+
+    /// Time a synchronous stage
+    pub fn time<T>(&mut self, stage: PipelineStage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let out = f();
+        self.record(stage, start.elapsed());
+        out
+    }
+
+    /// Record a stage duration measured by the caller (e.g. across an await)
+    pub fn record(&mut self, stage: PipelineStage, duration: Duration) {
+        let budget = stage.budget();
+        let over_budget = duration > budget;
+
+        if over_budget {
+            warn!(
+                "⏱️  Stage {:?} took {:?} (budget {:?})",
+                stage, duration, budget
+            );
+        }
+
+        self.stages.push(StageTiming {
+            stage,
+            duration_us: duration.as_micros() as u64,
+            budget_us: budget.as_micros() as u64,
+            over_budget,
+        });
+    }
+
+    /// Recorded timing for a stage, if any
+    pub fn stage(&self, stage: PipelineStage) -> Option<&StageTiming> {
+        self.stages.iter().find(|s| s.stage == stage)
+    }
+
+    /// Finish the trace and produce the breakdown
+    pub fn finish(self) -> LatencyBreakdown {
+        let slowest_stage = self
+            .stages
+            .iter()
+            .max_by_key(|s| s.duration_us)
+            .map(|s| s.stage);
+
+        LatencyBreakdown {
+            total_us: self.started.elapsed().as_micros() as u64,
+            stages: self.stages,
+            slowest_stage,
+        }
+    }
+}
+
+// ================================================================================================
+// Deadlines
+// ================================================================================================
+
+/// Absolute point by which a request must be answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Deadline {
+    expires_at: Instant,
+}
+
+tokio::task_local! {
+    static CURRENT_DEADLINE: Deadline;
+}
+
+impl Deadline {
+    pub fn at(expires_at: Instant) -> Self {
+        Self { expires_at }
+    }
+
+    /// Deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Self::at(Instant::now() + timeout)
+    }
+
+    /// Deadline from a gRPC `grpc-timeout` header value (e.g. `50m`, `2S`)
+    pub fn from_grpc_timeout(value: &str) -> Option<Self> {
+        parse_grpc_timeout(value).map(Self::after)
+    }
+
+    /// Run `future` with this deadline as [`Deadline::current`]
+    ///
+    /// Nested scopes can only tighten the budget: the earlier of this and any
+    /// enclosing deadline applies.
+    pub async fn scope<F: std::future::Future>(self, future: F) -> F::Output {
+        let effective = Self::current().map_or(self, |outer| outer.min(self));
+        CURRENT_DEADLINE.scope(effective, future).await
+    }
+
+    /// Deadline of the enclosing [`Deadline::scope`], if any
+    pub fn current() -> Option<Self> {
+        CURRENT_DEADLINE.try_with(|deadline| *deadline).ok()
+    }
+
+    pub fn expires_at(&self) -> Instant {
+        self.expires_at
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Whether the remaining budget covers `stage`'s SLO budget
+    pub fn allows(&self, stage: PipelineStage) -> bool {
+        self.remaining() >= stage.budget()
+    }
+}
+
+/// Parse a gRPC timeout: up to 8 digits followed by `H`, `M`, `S`, `m`, `u` or `n`
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    let (&unit, digits) = value.as_bytes().split_last()?;
+    if digits.is_empty() || digits.len() > 8 || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let amount: u64 = std::str::from_utf8(digits).ok()?.parse().ok()?;
+    Some(match unit {
+        b'H' => Duration::from_secs(amount * 3_600),
+        b'M' => Duration::from_secs(amount * 60),
+        b'S' => Duration::from_secs(amount),
+        b'm' => Duration::from_millis(amount),
+        b'u' => Duration::from_micros(amount),
+        b'n' => Duration::from_nanos(amount),
+        _ => return None,
+    })
+}
+
+/// Deadline-exceeded fallbacks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadlineStats {
+    /// Requests that carried a deadline
+    pub requests: u64,
+
+    /// Extractions answered from the request alone (no shared history or oracle)
+    pub extract_fallbacks: u64,
+
+    /// Heuristic-only scores (model and adaptive pipeline skipped)
+    pub predict_fallbacks: u64,
+
+    /// Routes picked from the score alone (slot-window forecast skipped)
+    pub route_fallbacks: u64,
+}
+
+impl DeadlineStats {
+    pub fn total_fallbacks(&self) -> u64 {
+        self.extract_fallbacks + self.predict_fallbacks + self.route_fallbacks
+    }
+
+    /// Add counts from another component (e.g. each engine of a pool)
+    pub fn merge(&mut self, other: &DeadlineStats) {
+        self.requests += other.requests;
+        self.extract_fallbacks += other.extract_fallbacks;
+        self.predict_fallbacks += other.predict_fallbacks;
+        self.route_fallbacks += other.route_fallbacks;
+    }
+}
+
+/// Lock-free deadline fallback counters
+#[derive(Debug, Default)]
+pub struct DeadlineCounters {
+    requests: AtomicU64,
+    fallbacks: [AtomicU64; 5],
+}
+
+impl DeadlineCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a stage that degraded because the deadline could not cover it
+    pub fn record_fallback(&self, stage: PipelineStage) {
+        debug!("Deadline too close for {:?} - degraded answer", stage);
+        self.fallbacks[stage.index()].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> DeadlineStats {
+        let fallbacks = |stage: PipelineStage| self.fallbacks[stage.index()].load(Ordering::Relaxed);
+        DeadlineStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            extract_fallbacks: fallbacks(PipelineStage::Extract),
+            predict_fallbacks: fallbacks(PipelineStage::Predict),
+            route_fallbacks: fallbacks(PipelineStage::Route),
+        }
+    }
+}
+
+// ================================================================================================
+// Histograms
+// ================================================================================================
+
+/// Histogram bucket upper bounds (microseconds); last bucket is unbounded
+const BUCKET_BOUNDS_US: [u64; 14] = [
+    50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000,
+];
+const BUCKET_COUNT: usize = BUCKET_BOUNDS_US.len() + 1;
+
+/// Lock-free fixed-bucket histogram
+#[derive(Debug)]
+struct Histogram {
+    buckets: [AtomicU64; BUCKET_COUNT],
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, value_us: u64) {
+        let idx = BUCKET_BOUNDS_US
+            .iter()
+            .position(|&bound| value_us <= bound)
+            .unwrap_or(BUCKET_BOUNDS_US.len());
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(value_us, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> HistogramSnapshot {
+        let buckets: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect();
+        let count = buckets.iter().sum::<u64>();
+        let sum_us = self.sum_us.load(Ordering::Relaxed);
+
+        HistogramSnapshot {
+            count,
+            mean_us: sum_us.checked_div(count).unwrap_or(0),
+            p50_us: Self::percentile(&buckets, count, 0.50),
+            p99_us: Self::percentile(&buckets, count, 0.99),
+            buckets,
+        }
+    }
+
+    /// Upper bound of the bucket containing the requested rank
+    fn percentile(buckets: &[u64], count: u64, q: f64) -> u64 {
+        if count == 0 {
+            return 0;
+        }
+        let rank = ((count as f64) * q).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, &n) in buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return BUCKET_BOUNDS_US.get(i).copied().unwrap_or(u64::MAX);
+            }
+        }
+        u64::MAX
+    }
+}
+
+/// Point-in-time histogram view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub mean_us: u64,
+    pub p50_us: u64,
+    pub p99_us: u64,
+    pub buckets: Vec<u64>,
+}
+
+/// Per-stage and end-to-end latency histograms
+#[derive(Debug)]
+pub struct LatencyHistograms {
+    stages: [Histogram; 5],
+    total: Histogram,
+    slowest_counts: [AtomicU64; 5],
+}
+
+impl Default for LatencyHistograms {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyHistograms {
+    pub fn new() -> Self {
+        Self {
+            stages: std::array::from_fn(|_| Histogram::new()),
+            total: Histogram::new(),
+            slowest_counts: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    /// Fold a request breakdown into the histograms
+    pub fn observe(&self, breakdown: &LatencyBreakdown) {
+        for timing in &breakdown.stages {
+            self.stages[timing.stage.index()].observe(timing.duration_us);
+        }
+        self.total.observe(breakdown.total_us);
+        if let Some(stage) = breakdown.slowest_stage {
+            self.slowest_counts[stage.index()].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Snapshot for a single stage
+    pub fn stage(&self, stage: PipelineStage) -> HistogramSnapshot {
+        self.stages[stage.index()].snapshot()
+    }
+
+    /// End-to-end snapshot
+    pub fn total(&self) -> HistogramSnapshot {
+        self.total.snapshot()
+    }
+
+    /// How often each stage was the slowest in a request
+    pub fn slowest_stage_counts(&self) -> Vec<(PipelineStage, u64)> {
+        PipelineStage::ALL
+            .iter()
+            .map(|s| (*s, self.slowest_counts[s.index()].load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracer_tags_slowest_stage() {
+        let mut tracer = LatencyTracer::new();
+        tracer.record(PipelineStage::Validate, Duration::from_micros(200));
+        tracer.record(PipelineStage::Predict, Duration::from_micros(1_500));
+        tracer.record(PipelineStage::Route, Duration::from_micros(10));
+
+        let breakdown = tracer.finish();
+        assert_eq!(breakdown.slowest_stage, Some(PipelineStage::Predict));
+        assert_eq!(breakdown.stages.len(), 3);
+        assert_eq!(breakdown.over_budget().count(), 0);
+    }
+
+    #[test]
+    fn test_over_budget_flag() {
+        let mut tracer = LatencyTracer::new();
+        tracer.record(PipelineStage::Extract, Duration::from_millis(1));
+
+        let breakdown = tracer.finish();
+        assert!(breakdown.stages[0].over_budget);
+        assert_eq!(breakdown.stages[0].budget_us, 300);
+    }
+
+    #[test]
+    fn test_time_closure() {
+        let mut tracer = LatencyTracer::new();
+        let value = tracer.time(PipelineStage::Validate, || 42);
+        assert_eq!(value, 42);
+        assert!(tracer.stage(PipelineStage::Validate).is_some());
+    }
+
+    #[test]
+    fn test_histograms() {
+        let histograms = LatencyHistograms::new();
+
+        for us in [40, 80, 90, 2_000] {
+            let mut tracer = LatencyTracer::new();
+            tracer.record(PipelineStage::Predict, Duration::from_micros(us));
+            histograms.observe(&tracer.finish());
+        }
+
+        let snap = histograms.stage(PipelineStage::Predict);
+        assert_eq!(snap.count, 4);
+        assert_eq!(snap.p50_us, 100);
+        assert_eq!(snap.p99_us, 2_500);
+        assert_eq!(histograms.stage(PipelineStage::Submit).count, 0);
+
+        let slowest = histograms.slowest_stage_counts();
+        assert_eq!(slowest[2], (PipelineStage::Predict, 4));
+    }
+
+    #[test]
+    fn test_deadline_budget_and_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("50m"), Some(Duration::from_millis(50)));
+        assert_eq!(parse_grpc_timeout("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_grpc_timeout("100u"), Some(Duration::from_micros(100)));
+        for invalid in ["", "m", "123456789m", "5x", "-5m", "5 m", "5é"] {
+            assert_eq!(parse_grpc_timeout(invalid), None, "{:?}", invalid);
+        }
+
+        let roomy = Deadline::after(Duration::from_secs(1));
+        assert!(roomy.allows(PipelineStage::Predict) && !roomy.is_expired());
+        let tight = Deadline::from_grpc_timeout("1m").unwrap();
+        assert!(tight.allows(PipelineStage::Extract));
+        assert!(!tight.allows(PipelineStage::Predict));
+        assert!(Deadline::at(Instant::now()).is_expired());
+
+        let counters = DeadlineCounters::new();
+        counters.record_request();
+        counters.record_fallback(PipelineStage::Predict);
+        counters.record_fallback(PipelineStage::Route);
+        let mut stats = counters.stats();
+        stats.merge(&counters.stats());
+        assert_eq!((stats.requests, stats.predict_fallbacks, stats.total_fallbacks()), (2, 2, 4));
+    }
+
+    #[tokio::test]
+    async fn test_deadline_scope_only_tightens() {
+        assert_eq!(Deadline::current(), None);
+        let outer = Deadline::after(Duration::from_millis(20));
+        let inner = Deadline::after(Duration::from_secs(5));
+        let seen = outer
+            .scope(async move { inner.scope(async { Deadline::current() }).await })
+            .await;
+        assert_eq!(seen, Some(outer));
+    }
+}
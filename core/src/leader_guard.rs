@@ -0,0 +1,266 @@
+//! Submission-Time Malicious Leader Enforcement
+//!
+//! A transaction sent while a known sandwiching validator leads is likely to be
+//! sandwiched regardless of its risk score. Before submission, `LeaderGuard`
+//! checks the leaders of the target slot window against the malicious set:
+//! - **delay**: hold the submission by `delay_slots` steps until a window
+//!   without a risky leader, up to `max_delay_slots`
+//! - **force_jito_bundle**: submit now, but only atomically via JitoBundle
+//!
+//! If no clean window exists within `max_delay_slots` (or the schedule does not
+//! reach far enough), delay falls back to a forced JitoBundle. Users may opt
+//! out per intent with `FeePreferences::allow_malicious_leader`.
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard};
+use tracing::{debug, info, warn};
+
+use crate::config::{LeaderGuardAction, LeaderGuardSettings, SentinelConfig};
+use crate::intent::Intent;
+use crate::types::RouteType;
+
+/// Leader schedule slice (`leaders[i]` leads `first_slot + i`, as returned by `getSlotLeaders`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpcomingLeaders {
+    pub first_slot: u64,
+    pub leaders: Vec<Pubkey>,
+}
+
+impl UpcomingLeaders {
+    pub fn new(first_slot: u64, leaders: Vec<Pubkey>) -> Self {
+        Self { first_slot, leaders }
+    }
+
+    pub fn leader_at(&self, slot: u64) -> Option<&Pubkey> {
+        let offset = usize::try_from(slot.checked_sub(self.first_slot)?).ok()?;
+        self.leaders.get(offset)
+    }
+
+    /// Whether every slot in `[start, start + len)` is known
+    fn covers(&self, start: u64, len: u64) -> bool {
+        start >= self.first_slot && start + len <= self.first_slot + self.leaders.len() as u64
+    }
+}
+
+/// Submission decision for the target window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum LeaderDecision {
+    /// No risky leader (or user override): submit on the proposed route
+    Proceed { route: RouteType },
+
+    /// Hold submission until `until_slot`, then use `route`
+    Delay {
+        until_slot: u64,
+        route: RouteType,
+        leader: Pubkey,
+        risk: f32,
+    },
+
+    /// Submit now as an atomic Jito bundle
+    ForceJitoBundle { leader: Pubkey, risk: f32 },
+}
+
+impl LeaderDecision {
+    /// Route to submit on once the decision allows it
+    pub fn route(&self) -> RouteType {
+        match self {
+            LeaderDecision::Proceed { route } | LeaderDecision::Delay { route, .. } => route.clone(),
+            LeaderDecision::ForceJitoBundle { .. } => RouteType::JitoBundle,
+        }
+    }
+}
+
+/// Malicious-leader submission gate
+pub struct LeaderGuard {
+    settings: RwLock<LeaderGuardSettings>,
+    risks: RwLock<HashMap<Pubkey, f32>>,
+}
+
+impl LeaderGuard {
+    pub fn new(settings: LeaderGuardSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            risks: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.leader_guard.clone())
+    }
+
+    /// Apply the hot-reloadable `[leader_guard]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.leader_guard.clone();
+    }
+
+    /// Replace the malicious validator set (identity → risk 0-1)
+    pub fn set_validator_risks(&self, risks: HashMap<Pubkey, f32>) {
+        info!("🛡️  Leader guard tracking {} malicious validators", risks.len());
+        *self.risks.write().unwrap_or_else(|p| p.into_inner()) = risks;
+    }
+
+    pub fn mark_malicious(&self, validator: Pubkey, risk: f32) {
+        self.risks
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(validator, risk.clamp(0.0, 1.0));
+    }
+
+    pub fn risk(&self, validator: &Pubkey) -> Option<f32> {
+        self.read_risks().get(validator).copied()
+    }
+
+    /// Decide how to submit `intent` targeting the window starting at `current_slot`
+    pub fn evaluate(
+        &self,
+        intent: &Intent,
+        proposed: RouteType,
+        current_slot: u64,
+        leaders: &UpcomingLeaders,
+    ) -> LeaderDecision {
+        let settings = self.settings.read().unwrap_or_else(|p| p.into_inner()).clone();
+        if !settings.enabled {
+            return LeaderDecision::Proceed { route: proposed };
+        }
+
+        let Some((leader, risk)) = self.riskiest_leader(&settings, current_slot, leaders) else {
+            return LeaderDecision::Proceed { route: proposed };
+        };
+
+        if intent.fee_preferences.allow_malicious_leader {
+            warn!(
+                "⚠️  Intent {} submitting under malicious leader {} (risk {:.2}) by user override",
+                intent.intent_id, leader, risk
+            );
+            return LeaderDecision::Proceed { route: proposed };
+        }
+
+        if settings.action == LeaderGuardAction::Delay && settings.delay_slots > 0 {
+            let mut offset = settings.delay_slots;
+            while offset <= settings.max_delay_slots {
+                let start = current_slot + offset;
+                if !leaders.covers(start, settings.window_slots) {
+                    debug!("Leader schedule ends before slot {} - cannot delay further", start);
+                    break;
+                }
+                if self.riskiest_leader(&settings, start, leaders).is_none() {
+                    info!(
+                        "⏳ Delaying intent {} by {} slots to avoid malicious leader {} (risk {:.2})",
+                        intent.intent_id, offset, leader, risk
+                    );
+                    return LeaderDecision::Delay { until_slot: start, route: proposed, leader, risk };
+                }
+                offset += settings.delay_slots;
+            }
+        }
+
+        info!(
+            "🛡️  Forcing JitoBundle for intent {}: malicious leader {} (risk {:.2})",
+            intent.intent_id, leader, risk
+        );
+        LeaderDecision::ForceJitoBundle { leader, risk }
+    }
+
+    /// Highest-risk leader above the bound in `[start, start + window_slots)`
+    fn riskiest_leader(
+        &self,
+        settings: &LeaderGuardSettings,
+        start: u64,
+        leaders: &UpcomingLeaders,
+    ) -> Option<(Pubkey, f32)> {
+        let risks = self.read_risks();
+        (start..start + settings.window_slots.max(1))
+            .filter_map(|slot| leaders.leader_at(slot))
+            .filter_map(|leader| risks.get(leader).map(|risk| (*leader, *risk)))
+            .filter(|(_, risk)| *risk > settings.max_leader_risk)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    fn read_risks(&self) -> RwLockReadGuard<'_, HashMap<Pubkey, f32>> {
+        self.risks.read().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent() -> Intent {
+        Intent {
+            intent_id: "intent-1".to_string(),
+            swap_details: None,
+            ..Intent::test_swap()
+        }
+    }
+
+    /// 4-slot rotations: [bad, good, bad, good, ...] starting at slot 100
+    fn schedule(bad: Pubkey, good: Pubkey, rotations: usize) -> UpcomingLeaders {
+        let leaders = (0..rotations)
+            .flat_map(|r| [if r % 2 == 0 { bad } else { good }; 4])
+            .collect();
+        UpcomingLeaders::new(100, leaders)
+    }
+
+    fn guard(action: LeaderGuardAction, bad: Pubkey) -> LeaderGuard {
+        let guard = LeaderGuard::new(LeaderGuardSettings { action, ..Default::default() });
+        guard.mark_malicious(bad, 0.9);
+        guard
+    }
+
+    #[test]
+    fn test_clean_window_proceeds() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let guard = guard(LeaderGuardAction::Delay, bad);
+        let decision = guard.evaluate(&intent(), RouteType::StandardRpc, 104, &schedule(bad, good, 4));
+        assert_eq!(decision, LeaderDecision::Proceed { route: RouteType::StandardRpc });
+    }
+
+    #[test]
+    fn test_delay_until_clean_window() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let guard = guard(LeaderGuardAction::Delay, bad);
+
+        let decision = guard.evaluate(&intent(), RouteType::StandardRpc, 100, &schedule(bad, good, 4));
+        assert_eq!(
+            decision,
+            LeaderDecision::Delay { until_slot: 104, route: RouteType::StandardRpc, leader: bad, risk: 0.9 }
+        );
+
+        // Schedule too short to delay: fall back to a forced bundle
+        let decision = guard.evaluate(&intent(), RouteType::StandardRpc, 100, &schedule(bad, good, 1));
+        assert_eq!(decision.route(), RouteType::JitoBundle);
+    }
+
+    #[test]
+    fn test_force_bundle_and_risk_bound() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let guard = guard(LeaderGuardAction::ForceJitoBundle, bad);
+        let leaders = schedule(bad, good, 2);
+
+        assert_eq!(
+            guard.evaluate(&intent(), RouteType::StandardRpc, 100, &leaders),
+            LeaderDecision::ForceJitoBundle { leader: bad, risk: 0.9 }
+        );
+
+        // Below the risk bound the leader is tolerated
+        guard.mark_malicious(bad, 0.5);
+        assert_eq!(
+            guard.evaluate(&intent(), RouteType::StandardRpc, 100, &leaders).route(),
+            RouteType::StandardRpc
+        );
+    }
+
+    #[test]
+    fn test_user_override() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let guard = guard(LeaderGuardAction::Delay, bad);
+        let mut intent = intent();
+        intent.fee_preferences.allow_malicious_leader = true;
+
+        let decision = guard.evaluate(&intent, RouteType::JitoSingle, 100, &schedule(bad, good, 4));
+        assert_eq!(decision, LeaderDecision::Proceed { route: RouteType::JitoSingle });
+    }
+}
@@ -1,14 +1,267 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod actions;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod alert;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod alt_cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod analytics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod best_execution;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chain_clock;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod circuit_breaker;
+pub mod clock;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config_history;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod congestion;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod consent_guard;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod coordination;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod dca;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod decision_log;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod dex;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod envelope;
 pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod expiry_sweeper;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fee_payer;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fee_split;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fill_report;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod health;
 pub mod intent;
+pub mod intent_decode;
+pub mod intent_registry;
+pub mod landing;
+pub mod latency;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod leader_guard;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod market_calendar;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mev_heatmap;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mev_incident;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod netting;
+pub mod network;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod nonce_manager;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod order_slicing;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod passthrough;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod policy;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod preflight;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod preview;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod queue;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod quota;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod redaction;
+pub mod route_economics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod route_explain;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod safety;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scheduler;
+pub mod schema;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod screening;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod signed_tx_vault;
+pub mod signing_policy;
+pub mod slippage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod slo;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod solana_pay;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod submission_timer;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod telemetry;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tenant;
+pub mod timeline;
+pub mod tip_floor;
 pub mod types;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod slot_risk;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod token_risk;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod whirlpool;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use actions::{
+    action_headers, blockchain_id, ActionError, ActionGetResponse, ActionLinks, ActionParameter, ActionPostRequest,
+    ActionPostResponse, ActionType, LinkedAction, SwapActionQuery, SwapActionService,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use alert::{Alert, AlertSeverity, AlertSink, LogAlertSink, MemoryAlertSink};
+#[cfg(not(target_arch = "wasm32"))]
+pub use alt_cache::AltCache;
+#[cfg(not(target_arch = "wasm32"))]
+pub use analytics::{IntentAnalytics, Rollup, RollupPeriod, RouteStats};
+#[cfg(not(target_arch = "wasm32"))]
+pub use best_execution::{BestExecution, DexVenue, QuoteComparator, VenueQuote};
+#[cfg(not(target_arch = "wasm32"))]
+pub use chain_clock::{ChainClock, ChainClockService, ChainClockSettings, ChainSource, ChainState};
+#[cfg(feature = "chaos")]
+pub use chaos::{ArmedFault, ChaosInjector, Fault, FaultSpec};
+#[cfg(not(target_arch = "wasm32"))]
+pub use circuit_breaker::{
+    is_dependency_failure, CircuitBreaker, CircuitBreakers, CircuitState, DependencyHealth, Permit,
+};
+pub use clock::{system_clock, Clock, ManualClock, SharedClock, SystemClock};
+#[cfg(not(target_arch = "wasm32"))]
+pub use config::{
+    CalendarSettings, CircuitBreakerSettings, ComplianceMode, ConfigHandle, CongestionSettings, CoordinationBackendKind,
+    CoordinationSettings, EndpointConfig, FeePayerMode, FusionSettings, HealthSettings, Imputation, ImputationSettings,
+    LeaderGuardAction, LeaderGuardSettings, MarketSession, MetaModelSettings, ModelBackendKind, ModelSettings,
+    NettingSettings, OracleSettings, PolicySettings, ProtectionTierProfile, ProtectionTierSettings, QuotaSettings,
+    RedactionSettings, RegistrySchema, SafetySettings, SchedulerSettings, ScreeningMode, ScreeningSettings,
+    SentinelConfig, SimulationStrictness, SlicingSettings, SloSettings, SlotRiskSettings, SponsorConfig,
+    StorageBackendKind, StorageSettings, SubmissionTimingSettings, TelemetrySettings, TenantConfig, ThresholdSettings,
+    TipPolicy, ValidatorListConfig,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use config_history::{ConfigHistory, ConfigSnapshot, CONFIG_SNAPSHOT_STREAM};
+#[cfg(not(target_arch = "wasm32"))]
+pub use congestion::{CongestionCache, CongestionReading, PerformanceSample};
+#[cfg(not(target_arch = "wasm32"))]
+pub use consent_guard::{ConsentGuard, GuardInstruction, GuardTerms};
+#[cfg(not(target_arch = "wasm32"))]
+pub use coordination::{Coordination, Coordinator, CounterCharge, MemoryCoordinator};
+#[cfg(not(target_arch = "wasm32"))]
+pub use dca::{
+    DcaAction, DcaActionRequest, DcaExecution, DcaOrder, DcaSchedule, DcaScheduler, DcaStatus, SignedDcaAction,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use decision_log::{DecisionLog, DecisionLogConfig, DecisionRecord};
+#[cfg(not(target_arch = "wasm32"))]
 pub use dex::DexAggregator;
+#[cfg(not(target_arch = "wasm32"))]
+pub use envelope::{decode_submission, EnvelopeKeyring, PublishedKey, SealedEnvelope};
 pub use error::{Result, SentinelError};
+#[cfg(not(target_arch = "wasm32"))]
+pub use expiry_sweeper::ExpirySweeper;
+#[cfg(not(target_arch = "wasm32"))]
+pub use fee_payer::{FeeAssignment, FeePayerProvider, SponsorLedger, UserPaysFees};
+#[cfg(not(target_arch = "wasm32"))]
+pub use fee_split::{FeeSplitDecision, FeeSplitter};
+#[cfg(not(target_arch = "wasm32"))]
+pub use fill_report::{ConfirmedTransaction, FeeComponent, FeeEntry, FeesPaid, FillReport, TokenBalance};
+#[cfg(not(target_arch = "wasm32"))]
+pub use health::{
+    BlockEngineCheck, DependencyStatus, FreshnessCheck, HealthCheck, HealthChecker, HealthReport, Heartbeat,
+    KvStoreCheck,
+};
 pub use intent::{
-    ConsentBlock, Constraints, FeePreferences, Intent, IntentError, IntentStatus, IntentType,
-    LimitDetails, Priority, SwapDetails, SwapMode, TwapDetails,
+    ConsentBlock, Constraints, DcaDetails, DepositDetails, FeePreferences, FeeSplit, Intent, IntentError, IntentLeg,
+    IntentStatus, IntentType, LimitDetails, Priority, ProtectionTier, SwapDetails, SwapMode, TwapDetails,
+    INTENT_SCHEMA_VERSION, MAX_INTENT_LEGS,
 };
+pub use intent_decode::{check_json, decode_intent, decode_json, DecodeError, JsonLimits};
+pub use intent_registry::{Cancellation, IntentRegistry, RegisteredIntent, SignedCancellation};
+pub use landing::{LandingContext, LandingObservation, LandingPredictor, LandingPredictorSettings};
+pub use latency::{
+    parse_grpc_timeout, Deadline, DeadlineCounters, DeadlineStats, LatencyBreakdown, LatencyHistograms, LatencyTracer,
+    PipelineStage,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use leader_guard::{LeaderDecision, LeaderGuard, UpcomingLeaders};
+#[cfg(not(target_arch = "wasm32"))]
+pub use market_calendar::{CalendarFactors, MarketCalendar};
+#[cfg(not(target_arch = "wasm32"))]
+pub use mev_heatmap::{MevHeatmap, PairHeat, PoolHeat};
+#[cfg(not(target_arch = "wasm32"))]
+pub use mev_incident::{detect_sandwich, BlockTransaction, ConfirmedBlock, MevIncident};
+#[cfg(not(target_arch = "wasm32"))]
+pub use netting::{NettedTrade, NettingEngine, NettingLeg, NettingOutcome, NettingResidual};
+pub use network::{Network, LOCALNET_BLOCK_ENGINE_URL};
+#[cfg(not(target_arch = "wasm32"))]
 pub use nonce_manager::{NonceAccountInfo, NonceManager};
-pub use types::{MevRiskScore, RouteType, TransactionStatus};
+#[cfg(not(target_arch = "wasm32"))]
+pub use order_slicing::{ImpactReport, OrderSlicer, PlannedSlice, SliceFill, SliceImpact, SlicePlan};
+#[cfg(not(target_arch = "wasm32"))]
+pub use passthrough::{JupiterAccount, JupiterInstruction, JupiterSwapInstructions, PassthroughPlan, PassthroughRequest};
+#[cfg(not(target_arch = "wasm32"))]
+pub use policy::{IntentPolicy, MintInfo, PolicyRule};
+#[cfg(not(target_arch = "wasm32"))]
+pub use preflight::{PreflightReport, SimulationOutcome, TransactionSimulator};
+#[cfg(not(target_arch = "wasm32"))]
+pub use preview::{PreviewBuilder, PreviewFees, RiskLevel, RiskPreview, SignPreview, TokenAmount};
+#[cfg(not(target_arch = "wasm32"))]
+pub use queue::{BoundedQueue, OverflowPolicy, QueueStats};
+#[cfg(not(target_arch = "wasm32"))]
+pub use quota::{QuotaManager, QuotaUsage};
+#[cfg(not(target_arch = "wasm32"))]
+pub use redaction::Scrubber;
+pub use route_economics::{
+    estimate_sandwich_loss, RouteCost, RouteCostInputs, RouteEconomics, RouteEconomicsSettings, RouteProfile,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use route_explain::{ExplainSubject, RouteExplainRequest, RouteExplanation, ScoreContributions, ThresholdsInEffect};
+#[cfg(not(target_arch = "wasm32"))]
+pub use safety::{EnforcedRoute, SafetyController, SafetyStatus};
+#[cfg(not(target_arch = "wasm32"))]
+pub use scheduler::{ExecutionScheduler, ScheduledIntent};
+pub use schema::{intent_schema, intent_schema_json, INTENT_SCHEMA_PATH};
+#[cfg(not(target_arch = "wasm32"))]
+pub use screening::{
+    load_deny_list, AddressRole, AddressScreener, DenyListScreening, ScreenedAddress, ScreeningHit, ScreeningProvider,
+    ScreeningReport, WebhookScreening,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use slot_risk::{LeaderExposure, SlotRiskForecaster, WindowRisk};
+#[cfg(not(target_arch = "wasm32"))]
+pub use signed_tx_vault::{SealedTransaction, SignedTxVault, VaultEntry, VaultStatus};
+pub use signing_policy::{AuthorizedPolicy, PolicyRegistry, PolicyRevocation, SignedPolicyRevocation, SigningPolicy};
+pub use slippage::{SlippageAdvisor, SlippageAdvisorSettings, SlippageInputs, SlippageRecommendation};
+#[cfg(not(target_arch = "wasm32"))]
+pub use slo::{RouteSlo, SloObjective, SloTracker, SloWindow};
+#[cfg(not(target_arch = "wasm32"))]
+pub use solana_pay::{
+    TransactionRequest, TransactionRequestGetResponse, TransactionRequestPost, TransactionRequestPostResponse,
+    TransactionRequestService,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use storage::{append_json, read_json, AppendLog, FileLog, KvStore, KvVisit, MemoryKv, MemoryLog, Storage};
+#[cfg(not(target_arch = "wasm32"))]
+pub use submission_timer::{SubmissionTimer, SubmissionTiming};
+#[cfg(not(target_arch = "wasm32"))]
+pub use telemetry::{current_trace_id, set_remote_parent, traceparent, TRACEPARENT_HEADER};
+#[cfg(not(target_arch = "wasm32"))]
+pub use tenant::{hash_api_key, Tenant, TenantRegistry, DEFAULT_TENANT_ID};
+pub use timeline::{ExecutionTimeline, TimelineEntry, TimelineEvent, TimelineUpdate};
+#[cfg(not(target_arch = "wasm32"))]
+pub use timeline::TimelineFeed;
+pub use tip_floor::{TipFloor, TipFloorCache};
+#[cfg(not(target_arch = "wasm32"))]
+pub use token_risk::{MintHoldings, PairTokenRisk, PoolObservation, TokenRisk, TokenRiskScreener, TokenRiskThresholds};
+pub use types::{MevRiskScore, RawTransactionScore, RouteType, ScoreRawRequest, TransactionStatus};
+#[cfg(not(target_arch = "wasm32"))]
+pub use whirlpool::{PoolDepth, WhirlpoolClient, WhirlpoolQuote, WhirlpoolState};
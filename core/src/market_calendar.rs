@@ -0,0 +1,175 @@
+//! Market Calendar
+//!
+//! MEV activity tracks trading activity: it peaks while the big equity
+//! sessions are open and memecoin attacks spike on weekends. The adaptive
+//! heuristics scale their time-of-day adjustment by the factors a
+//! [`MarketCalendar`] reports for the current instant. Everything is evaluated
+//! in the calendar's IANA time zone, so sessions follow daylight saving time
+//! instead of drifting an hour twice a year:
+//! - weekend days and holidays (markets closed) get `weekend_multiplier`
+//! - on trading days, the first session containing the local time applies its
+//!   `multiplier`
+//!
+//! The defaults reproduce the US-equities window (09:00-16:00 New York time,
+//! 1.1x) and the weekend bump (1.3x).
+
+use chrono::{DateTime, Datelike, FixedOffset, Offset, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{CalendarSettings, MarketSession, SentinelConfig};
+use crate::{Result, SentinelError};
+
+/// Calendar state at one instant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarFactors {
+    /// The instant in the calendar's time zone
+    pub local_time: DateTime<FixedOffset>,
+
+    /// Weekend day or holiday
+    pub market_closed: bool,
+    pub holiday: bool,
+
+    /// Session the local time falls in (trading days only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+
+    pub weekend_factor: f32,
+    pub session_factor: f32,
+}
+
+impl CalendarFactors {
+    /// Combined time-of-day adjustment
+    pub fn adjustment(&self) -> f32 {
+        self.weekend_factor * self.session_factor
+    }
+}
+
+/// Sessions, weekends and holidays in a time zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketCalendar {
+    timezone: Tz,
+    settings: CalendarSettings,
+}
+
+impl Default for MarketCalendar {
+    fn default() -> Self {
+        Self::new(&CalendarSettings::default()).expect("Default calendar time zone is valid")
+    }
+}
+
+impl MarketCalendar {
+    pub fn new(settings: &CalendarSettings) -> Result<Self> {
+        let timezone = settings.timezone.parse::<Tz>().map_err(|_| {
+            SentinelError::ConfigError(format!("Unknown calendar time zone {}", settings.timezone))
+        })?;
+        Ok(Self {
+            timezone,
+            settings: settings.clone(),
+        })
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Result<Self> {
+        Self::new(&config.calendar)
+    }
+
+    pub fn settings(&self) -> &CalendarSettings {
+        &self.settings
+    }
+
+    /// Factors in effect at `now`
+    pub fn factors(&self, now: DateTime<Utc>) -> CalendarFactors {
+        let local = now.with_timezone(&self.timezone);
+        let date = local.date_naive();
+        let holiday = self.settings.holidays.contains(&date);
+        let market_closed = holiday || self.settings.weekend_days.contains(&local.weekday());
+
+        let time = local.time();
+        let session = if market_closed {
+            None
+        } else {
+            self.settings.sessions.iter().find(|s| in_session(s, time))
+        };
+
+        CalendarFactors {
+            local_time: local.with_timezone(&local.offset().fix()),
+            market_closed,
+            holiday,
+            session: session.map(|s| s.name.clone()),
+            weekend_factor: if market_closed { self.settings.weekend_multiplier } else { 1.0 },
+            session_factor: session.map_or(1.0, |s| s.multiplier),
+        }
+    }
+}
+
+fn in_session(session: &MarketSession, time: chrono::NaiveTime) -> bool {
+    if session.open <= session.close {
+        session.open <= time && time < session.close
+    } else {
+        time >= session.open || time < session.close
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime, TimeZone};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_session_follows_daylight_saving() {
+        let calendar = MarketCalendar::default();
+
+        // 13:30 UTC is 08:30 EST in January (before the open) but 09:30 EDT in July
+        let winter = calendar.factors(at(2025, 1, 6, 13, 30));
+        assert_eq!((winter.session.as_deref(), winter.adjustment()), (None, 1.0));
+
+        let summer = calendar.factors(at(2025, 7, 7, 13, 30));
+        assert_eq!(summer.session.as_deref(), Some("us_equities"));
+        assert_eq!(summer.local_time.offset().local_minus_utc(), -4 * 3600);
+        assert!((summer.adjustment() - 1.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weekends_and_holidays_close_the_market() {
+        let calendar = MarketCalendar::new(&CalendarSettings {
+            holidays: vec![NaiveDate::from_ymd_opt(2025, 7, 4).unwrap()],
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Saturday 15:00 UTC is inside the session hours, but no session runs on weekends
+        let saturday = calendar.factors(at(2025, 1, 4, 15, 0));
+        assert!(saturday.market_closed && !saturday.holiday);
+        assert_eq!(saturday.session, None);
+        assert!((saturday.adjustment() - 1.3).abs() < 1e-6);
+
+        let independence_day = calendar.factors(at(2025, 7, 4, 15, 0));
+        assert!(independence_day.market_closed && independence_day.holiday);
+        assert!((independence_day.adjustment() - 1.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_overnight_session_and_unknown_zone() {
+        let calendar = MarketCalendar::new(&CalendarSettings {
+            timezone: "Asia/Tokyo".to_string(),
+            sessions: vec![MarketSession {
+                name: "overnight".to_string(),
+                open: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                close: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+                multiplier: 1.5,
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+        // Tuesday 16:30 UTC is Wednesday 01:30 in Tokyo
+        assert_eq!(calendar.factors(at(2025, 1, 7, 16, 30)).session.as_deref(), Some("overnight"));
+        assert_eq!(calendar.factors(at(2025, 1, 7, 18, 0)).session, None);
+
+        let unknown = CalendarSettings { timezone: "Nowhere/Special".to_string(), ..Default::default() };
+        assert!(MarketCalendar::new(&unknown).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Per-Pair MEV Heatmap
+//!
+//! The token pairs and pools that get sandwiched most, so wallets can warn
+//! users while they pick tokens. Built from decision-log records merged per
+//! request (see [`IntentAnalytics::heatmap`](crate::IntentAnalytics::heatmap)):
+//! - every landed swap (fill report) counts toward its pair
+//! - every confirmed sandwich ([`MevIncident`]) counts toward its pair, when
+//!   the fill is known, and toward the pool written by all three legs
+//!
+//! Pairs are unordered, so SOL→USDC and USDC→SOL swaps share an entry. The
+//! average extracted value is per attack and per mint, in raw token units.
+//! Only attacked pairs and pools are listed, most attacked first.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::decision_log::DecisionRecord;
+use crate::mev_incident::MevIncident;
+
+/// Attacks on one token pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairHeat {
+    /// Mints in sorted order
+    pub mints: [String; 2],
+    pub swaps: u64,
+    pub attacks: u64,
+
+    /// Attacks per landed swap
+    pub attack_rate: f64,
+
+    /// Extracted value per attack, per mint
+    pub avg_extracted: BTreeMap<String, u64>,
+    pub last_attack_ms: u64,
+}
+
+/// Attacks through one pool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolHeat {
+    pub pool: String,
+    pub attacks: u64,
+
+    /// Distinct attacker signers
+    pub attackers: u64,
+
+    /// Extracted value per attack, per mint
+    pub avg_extracted: BTreeMap<String, u64>,
+    pub last_attack_ms: u64,
+}
+
+/// Most-attacked pairs and pools over `[from_ms, to_ms)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MevHeatmap {
+    pub from_ms: u64,
+    pub to_ms: u64,
+
+    /// Landed swaps and confirmed sandwiches in the window
+    pub swaps: u64,
+    pub attacks: u64,
+    pub pairs: Vec<PairHeat>,
+    pub pools: Vec<PoolHeat>,
+}
+
+#[derive(Default)]
+struct Tally {
+    swaps: u64,
+    attacks: u64,
+    extracted: BTreeMap<String, u64>,
+    attackers: HashSet<String>,
+    last_attack_ms: u64,
+}
+
+impl Tally {
+    fn attack(&mut self, incident: &MevIncident, timestamp_ms: u64) {
+        self.attacks += 1;
+        for (mint, amount) in &incident.extracted {
+            let total = self.extracted.entry(mint.clone()).or_default();
+            *total = total.saturating_add(*amount);
+        }
+        self.attackers.insert(incident.attacker.clone());
+        self.last_attack_ms = self.last_attack_ms.max(timestamp_ms);
+    }
+
+    fn avg_extracted(&self) -> BTreeMap<String, u64> {
+        let attacks = self.attacks.max(1);
+        self.extracted.iter().map(|(mint, total)| (mint.clone(), total / attacks)).collect()
+    }
+}
+
+impl MevHeatmap {
+    /// Heatmap of merged records timestamped in `[from_ms, to_ms)`, at most
+    /// `limit` pairs and `limit` pools
+    pub fn build<'a>(
+        records: impl IntoIterator<Item = &'a DecisionRecord>,
+        from_ms: u64,
+        to_ms: u64,
+        limit: usize,
+    ) -> Self {
+        let mut pairs: HashMap<[String; 2], Tally> = HashMap::new();
+        let mut pools: HashMap<String, Tally> = HashMap::new();
+        let (mut swaps, mut attacks) = (0, 0);
+
+        for record in records {
+            if !(from_ms..to_ms).contains(&record.timestamp_ms) {
+                continue;
+            }
+            let pair = record.fill.as_ref().map(|fill| {
+                let mut mints = [fill.input_mint.clone(), fill.output_mint.clone()];
+                mints.sort();
+                mints
+            });
+            if let Some(ref pair) = pair {
+                swaps += 1;
+                pairs.entry(pair.clone()).or_default().swaps += 1;
+            }
+            if let Some(ref incident) = record.incident {
+                attacks += 1;
+                pools.entry(incident.pool.clone()).or_default().attack(incident, record.timestamp_ms);
+                if let Some(pair) = pair {
+                    pairs.entry(pair).or_default().attack(incident, record.timestamp_ms);
+                }
+            }
+        }
+
+        let mut pairs: Vec<PairHeat> = pairs
+            .into_iter()
+            .filter(|(_, tally)| tally.attacks > 0)
+            .map(|(mints, tally)| PairHeat {
+                mints,
+                swaps: tally.swaps,
+                attacks: tally.attacks,
+                attack_rate: tally.attacks as f64 / tally.swaps.max(1) as f64,
+                avg_extracted: tally.avg_extracted(),
+                last_attack_ms: tally.last_attack_ms,
+            })
+            .collect();
+        pairs.sort_by(|a, b| {
+            b.attacks
+                .cmp(&a.attacks)
+                .then(b.attack_rate.total_cmp(&a.attack_rate))
+                .then_with(|| a.mints.cmp(&b.mints))
+        });
+        pairs.truncate(limit);
+
+        let mut pools: Vec<PoolHeat> = pools
+            .into_iter()
+            .map(|(pool, tally)| PoolHeat {
+                pool,
+                attacks: tally.attacks,
+                attackers: tally.attackers.len() as u64,
+                avg_extracted: tally.avg_extracted(),
+                last_attack_ms: tally.last_attack_ms,
+            })
+            .collect();
+        pools.sort_by(|a, b| b.attacks.cmp(&a.attacks).then_with(|| a.pool.cmp(&b.pool)));
+        pools.truncate(limit);
+
+        Self {
+            from_ms,
+            to_ms,
+            swaps,
+            attacks,
+            pairs,
+            pools,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fill_report::{FeesPaid, FillReport};
+    use crate::types::RouteType;
+
+    fn swap(id: &str, input: &str, output: &str, timestamp_ms: u64) -> DecisionRecord {
+        let mut record = DecisionRecord::new(id.to_string(), 0.5, RouteType::JitoBundle).with_fill(FillReport {
+            intent_id: id.to_string(),
+            signature: "sig".to_string(),
+            slot: 1,
+            input_mint: input.to_string(),
+            output_mint: output.to_string(),
+            quoted_in_amount: 1_000,
+            quoted_out_amount: 1_000,
+            realized_in_amount: 1_000,
+            realized_out_amount: 990,
+            realized_slippage_bps: 100.0,
+            fees: FeesPaid {
+                base_fee_lamports: 5_000,
+                priority_fee_lamports: 0,
+                jito_tip_lamports: 10_000,
+                dex_fee_amount: 0,
+            },
+            mev_savings_estimate: 0,
+            fee_entries: Vec::new(),
+        });
+        record.timestamp_ms = timestamp_ms;
+        record
+    }
+
+    fn sandwiched(record: DecisionRecord, pool: &str, attacker: &str, extracted: u64) -> DecisionRecord {
+        record.with_incident(MevIncident {
+            signature: "victim".to_string(),
+            slot: 1,
+            leader: None,
+            attacker: attacker.to_string(),
+            pool: pool.to_string(),
+            front_run: "front".to_string(),
+            back_run: "back".to_string(),
+            extracted: BTreeMap::from([("SOL".to_string(), extracted)]),
+        })
+    }
+
+    #[test]
+    fn test_ranks_attacked_pairs_and_pools() {
+        let records = [
+            sandwiched(swap("a", "SOL", "BONK", 100), "pool-1", "x", 40),
+            sandwiched(swap("b", "BONK", "SOL", 200), "pool-1", "y", 20),
+            swap("c", "SOL", "BONK", 300),
+            sandwiched(swap("d", "SOL", "USDC", 400), "pool-2", "x", 5),
+            swap("e", "SOL", "USDC", 500),
+            swap("f", "SOL", "USDC", 600),
+            swap("g", "SOL", "JUP", 700),
+        ];
+
+        let heatmap = MevHeatmap::build(&records, 0, 1_000, 10);
+        assert_eq!((heatmap.swaps, heatmap.attacks), (7, 3));
+
+        // Both directions count toward one pair; unattacked pairs are not listed
+        assert_eq!(heatmap.pairs.len(), 2);
+        let bonk = &heatmap.pairs[0];
+        assert_eq!(bonk.mints, ["BONK".to_string(), "SOL".to_string()]);
+        assert_eq!((bonk.swaps, bonk.attacks), (3, 2));
+        assert!((bonk.attack_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(bonk.avg_extracted["SOL"], 30);
+        assert_eq!(bonk.last_attack_ms, 200);
+        assert!((heatmap.pairs[1].attack_rate - 1.0 / 3.0).abs() < 1e-9);
+
+        assert_eq!(heatmap.pools[0].pool, "pool-1");
+        assert_eq!((heatmap.pools[0].attacks, heatmap.pools[0].attackers), (2, 2));
+    }
+
+    #[test]
+    fn test_window_and_limit() {
+        let records = [
+            sandwiched(swap("a", "SOL", "BONK", 100), "pool-1", "x", 40),
+            sandwiched(swap("b", "SOL", "USDC", 2_000), "pool-2", "x", 5),
+            sandwiched(DecisionRecord::new("c".to_string(), 0.5, RouteType::JitoBundle), "pool-3", "x", 5),
+        ];
+
+        let heatmap = MevHeatmap::build(&records, 1_000, 3_000, 10);
+        assert_eq!(heatmap.attacks, 1);
+        assert_eq!(heatmap.pairs[0].mints[1], "USDC");
+
+        // Without a fill the attack still counts for its pool
+        let heatmap = MevHeatmap::build(&records, 0, u64::MAX, 1);
+        assert_eq!(heatmap.attacks, 3);
+        assert_eq!((heatmap.pairs.len(), heatmap.pools.len()), (1, 1));
+    }
+}
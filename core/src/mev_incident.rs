@@ -0,0 +1,297 @@
+//! Realized MEV Incidents
+//!
+//! Risk scores are predictions; whether a landed swap was actually sandwiched
+//! can only be read from the confirmed block. [`ConfirmedBlock`] parses a
+//! `getBlock` result (`transactionDetails: "accounts"`, `rewards: true`) and
+//! [`detect_sandwich`] checks one of our transactions in it:
+//! - a **front-run** within `max_distance` positions before the victim and a
+//!   **back-run** within `max_distance` after it, both signed by the same
+//!   outside signer
+//! - all three write a common account (the pool)
+//!
+//! The incident's extracted value is the attacker's net token gain over the
+//! two legs, per mint in raw units. The block leader comes from the `Fee`
+//! reward, so incidents can be attributed to the validator that ordered them.
+//!
+//! `ai_engine::IncidentDetector` runs this over the blocks our transactions
+//! landed in and feeds the results into validator stats, training labels and
+//! analytics (`DecisionRecord::with_incident`).
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::fill_report::{parse_balances, TokenBalance};
+use crate::{Result, SentinelError};
+
+/// Positions either side of the victim a sandwich leg may sit at by default
+pub const DEFAULT_MAX_DISTANCE: usize = 3;
+
+/// Transaction as confirmed in a block
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockTransaction {
+    pub signature: Signature,
+    pub signers: Vec<Pubkey>,
+    pub writable: Vec<Pubkey>,
+    pub failed: bool,
+    pub pre_token_balances: Vec<TokenBalance>,
+    pub post_token_balances: Vec<TokenBalance>,
+}
+
+impl BlockTransaction {
+    /// Net change per mint across `owner`'s token accounts
+    pub fn owner_deltas(&self, owner: &Pubkey) -> HashMap<Pubkey, i128> {
+        let mut deltas: HashMap<Pubkey, i128> = HashMap::new();
+        let owned = |b: &&TokenBalance| b.owner.as_ref() == Some(owner);
+
+        for balance in self.post_token_balances.iter().filter(owned) {
+            *deltas.entry(balance.mint).or_default() += balance.amount as i128;
+        }
+        for balance in self.pre_token_balances.iter().filter(owned) {
+            *deltas.entry(balance.mint).or_default() -= balance.amount as i128;
+        }
+        deltas
+    }
+}
+
+/// Confirmed block, transactions in execution order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmedBlock {
+    pub slot: u64,
+
+    /// Validator that produced the block (`Fee` reward recipient)
+    pub leader: Option<Pubkey>,
+    pub transactions: Vec<BlockTransaction>,
+}
+
+impl ConfirmedBlock {
+    /// Parse a `getBlock` result fetched with `transactionDetails: "accounts"`
+    pub fn from_rpc_json(slot: u64, block: &serde_json::Value) -> Result<Self> {
+        let leader = block["rewards"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|reward| reward["rewardType"].as_str() == Some("Fee"))
+            .and_then(|reward| reward["pubkey"].as_str())
+            .and_then(|pubkey| Pubkey::from_str(pubkey).ok());
+
+        let transactions = block["transactions"]
+            .as_array()
+            .ok_or_else(|| parse_error("block without transactions"))?
+            .iter()
+            .map(parse_transaction)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { slot, leader, transactions })
+    }
+
+    pub fn position(&self, signature: &Signature) -> Option<usize> {
+        self.transactions.iter().position(|tx| tx.signature == *signature)
+    }
+}
+
+fn parse_transaction(tx: &serde_json::Value) -> Result<BlockTransaction> {
+    let signature = tx["transaction"]["signatures"][0]
+        .as_str()
+        .and_then(|s| Signature::from_str(s).ok())
+        .ok_or_else(|| parse_error("transaction without signature"))?;
+
+    let mut signers = Vec::new();
+    let mut writable = Vec::new();
+    for key in tx["transaction"]["accountKeys"].as_array().into_iter().flatten() {
+        let Some(pubkey) = key["pubkey"].as_str().and_then(|k| Pubkey::from_str(k).ok()) else {
+            continue;
+        };
+        if key["signer"].as_bool().unwrap_or(false) {
+            signers.push(pubkey);
+        }
+        if key["writable"].as_bool().unwrap_or(false) {
+            writable.push(pubkey);
+        }
+    }
+
+    let meta = &tx["meta"];
+    Ok(BlockTransaction {
+        signature,
+        signers,
+        writable,
+        failed: !meta["err"].is_null(),
+        pre_token_balances: parse_balances(&meta["preTokenBalances"])?,
+        post_token_balances: parse_balances(&meta["postTokenBalances"])?,
+    })
+}
+
+fn parse_error(reason: &str) -> SentinelError {
+    SentinelError::ParseError(format!("Invalid confirmed block: {}", reason))
+}
+
+/// A confirmed sandwich of one of our transactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MevIncident {
+    /// Victim (our) transaction
+    pub signature: String,
+    pub slot: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leader: Option<String>,
+    pub attacker: String,
+
+    /// Account written by all three legs
+    pub pool: String,
+    pub front_run: String,
+    pub back_run: String,
+
+    /// Attacker's net gain over both legs, per mint (raw units; gains only)
+    pub extracted: BTreeMap<String, u64>,
+}
+
+/// Sandwich around the transaction at `victim`, if any
+///
+/// `own_signers` (our fee payers / users) are never taken for an attacker.
+pub fn detect_sandwich(
+    block: &ConfirmedBlock,
+    victim: usize,
+    own_signers: &HashSet<Pubkey>,
+    max_distance: usize,
+) -> Option<MevIncident> {
+    let txs = &block.transactions;
+    let target = txs.get(victim)?;
+    if target.failed {
+        return None;
+    }
+    let pools: HashSet<&Pubkey> = target.writable.iter().filter(|a| !own_signers.contains(*a)).collect();
+    let outside = |tx: &BlockTransaction| {
+        !tx.failed && tx.signers.first().is_some_and(|s| !own_signers.contains(s))
+    };
+
+    let fronts = &txs[victim.saturating_sub(max_distance)..victim];
+    let backs = &txs[victim + 1..(victim + 1 + max_distance).min(txs.len())];
+
+    // Closest legs first
+    for front in fronts.iter().rev().filter(|tx| outside(tx)) {
+        let attacker = front.signers[0];
+        for back in backs.iter().filter(|tx| outside(tx) && tx.signers[0] == attacker) {
+            let Some(pool) = front
+                .writable
+                .iter()
+                .find(|a| pools.contains(a) && back.writable.contains(a))
+            else {
+                continue;
+            };
+
+            let mut net: HashMap<Pubkey, i128> = front.owner_deltas(&attacker);
+            for (mint, delta) in back.owner_deltas(&attacker) {
+                *net.entry(mint).or_default() += delta;
+            }
+            let extracted = net
+                .into_iter()
+                .filter(|(_, delta)| *delta > 0)
+                .map(|(mint, delta)| (mint.to_string(), delta.min(u64::MAX as i128) as u64))
+                .collect();
+
+            return Some(MevIncident {
+                signature: target.signature.to_string(),
+                slot: block.slot,
+                leader: block.leader.map(|l| l.to_string()),
+                attacker: attacker.to_string(),
+                pool: pool.to_string(),
+                front_run: front.signature.to_string(),
+                back_run: back.signature.to_string(),
+                extracted,
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn balance(index: u32, mint: &Pubkey, owner: &Pubkey, amount: u64) -> serde_json::Value {
+        json!({
+            "accountIndex": index,
+            "mint": mint.to_string(),
+            "owner": owner.to_string(),
+            "uiTokenAmount": { "amount": amount.to_string() }
+        })
+    }
+
+    fn tx(
+        signer: &Pubkey,
+        writable: &[Pubkey],
+        pre: Vec<serde_json::Value>,
+        post: Vec<serde_json::Value>,
+    ) -> serde_json::Value {
+        let mut keys = vec![json!({ "pubkey": signer.to_string(), "signer": true, "writable": true })];
+        keys.extend(writable.iter().map(|k| json!({ "pubkey": k.to_string(), "signer": false, "writable": true })));
+        json!({
+            "transaction": {
+                "signatures": [Signature::new_unique().to_string()],
+                "accountKeys": keys
+            },
+            "meta": { "err": null, "preTokenBalances": pre, "postTokenBalances": post }
+        })
+    }
+
+    #[test]
+    fn test_detects_adjacent_sandwich_and_extracted_value() {
+        let (attacker, user, leader, bystander) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool, sol, token) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let block = json!({
+            "rewards": [{ "pubkey": leader.to_string(), "rewardType": "Fee", "lamports": 5000 }],
+            "transactions": [
+                tx(&bystander, &[Pubkey::new_unique()], vec![], vec![]),
+                // Front-run: 1,000 SOL units in for 500 tokens
+                tx(&attacker, &[pool], vec![balance(1, &sol, &attacker, 10_000)], vec![
+                    balance(1, &sol, &attacker, 9_000),
+                    balance(2, &token, &attacker, 500),
+                ]),
+                tx(&user, &[pool], vec![], vec![]),
+                // Back-run: 500 tokens out for 1,040 SOL units
+                tx(
+                    &attacker,
+                    &[pool],
+                    vec![balance(1, &sol, &attacker, 9_000), balance(2, &token, &attacker, 500)],
+                    vec![balance(1, &sol, &attacker, 10_040), balance(2, &token, &attacker, 0)],
+                ),
+            ]
+        });
+        let block = ConfirmedBlock::from_rpc_json(42, &block).unwrap();
+        assert_eq!(block.leader, Some(leader));
+
+        let victim = block.transactions[2].signature;
+        let incident = detect_sandwich(&block, 2, &HashSet::from([user]), DEFAULT_MAX_DISTANCE).unwrap();
+        assert_eq!(incident.signature, victim.to_string());
+        assert_eq!(incident.attacker, attacker.to_string());
+        assert_eq!(incident.pool, pool.to_string());
+        assert_eq!(incident.leader, Some(leader.to_string()));
+        assert_eq!(incident.extracted, BTreeMap::from([(sol.to_string(), 40)]));
+
+        // Too far apart to count as adjacent
+        assert!(detect_sandwich(&block, 2, &HashSet::from([user]), 0).is_none());
+        // The attacker's own legs are never victims of themselves
+        assert!(detect_sandwich(&block, 0, &HashSet::new(), DEFAULT_MAX_DISTANCE).is_none());
+    }
+
+    #[test]
+    fn test_different_pools_are_not_a_sandwich() {
+        let (attacker, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let block = json!({
+            "transactions": [
+                tx(&attacker, &[Pubkey::new_unique()], vec![], vec![]),
+                tx(&user, &[Pubkey::new_unique()], vec![], vec![]),
+                tx(&attacker, &[Pubkey::new_unique()], vec![], vec![]),
+            ]
+        });
+        let block = ConfirmedBlock::from_rpc_json(7, &block).unwrap();
+        assert_eq!(block.leader, None);
+        assert!(detect_sandwich(&block, 1, &HashSet::from([user]), DEFAULT_MAX_DISTANCE).is_none());
+        assert!(ConfirmedBlock::from_rpc_json(7, &json!({})).is_err());
+    }
+}
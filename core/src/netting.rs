@@ -0,0 +1,437 @@
+//! Atomic Multi-User Netting
+//!
+//! Opposing swaps that arrive close together (A sells SOL for USDC, B sells
+//! USDC for SOL) can be crossed against each other at the oracle mid price
+//! instead of both paying DEX fees and sitting in the mempool as sandwich
+//! targets. [`NettingEngine`] keeps a short-lived book of opted-in intents:
+//! - only ExactIn swaps whose user set `constraints.allow_netting` (part of the
+//!   signed intent, so consent is explicit) take part; everything else routes
+//!   normally
+//! - an arrival crosses the oldest resting opposite intent of another user,
+//!   otherwise rests for `window_ms` ([`NettingEngine::expire_due`] hands
+//!   lapsed intents back for normal routing)
+//! - the cross is sized by the smaller side at the mid price, must be at
+//!   least `min_cross_fraction` of each side and must honour each side's
+//!   `minimum_received` pro rata
+//!
+//! A [`NettedTrade`] settles as direct token transfers between the two users
+//! ([`NettedTrade::transfer_instructions`], one transaction both sign) plus a
+//! DEX swap for each [`NettingResidual`], all in one bundle so either every
+//! part lands or none does.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, RwLock};
+use tracing::{debug, info};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{NettingSettings, SentinelConfig};
+use crate::intent::{Intent, IntentType, SwapMode};
+use crate::whirlpool::{associated_token_address, token_program_id};
+use crate::{Result, SentinelError};
+
+/// SPL Token `Transfer` instruction tag
+const TRANSFER_TAG: u8 = 3;
+
+/// One user's side of a cross
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NettingLeg {
+    pub intent_id: String,
+    pub user: Pubkey,
+    pub input_mint: Pubkey,
+    /// Atoms given to the counterparty
+    pub input_amount: u64,
+    pub output_mint: Pubkey,
+    /// Atoms received from the counterparty
+    pub output_amount: u64,
+}
+
+/// Input a side still has to swap on a DEX after the cross
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NettingResidual {
+    pub intent_id: String,
+    pub input_mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Two intents crossed at the oracle mid price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NettedTrade {
+    /// Atoms of `legs[0].output_mint` per atom of `legs[0].input_mint`
+    pub mid_price: f64,
+
+    /// Arriving intent first, then the resting one
+    pub legs: [NettingLeg; 2],
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub residuals: Vec<NettingResidual>,
+}
+
+impl NettedTrade {
+    /// Users that must sign the settlement transaction
+    pub fn signers(&self) -> [Pubkey; 2] {
+        [self.legs[0].user, self.legs[1].user]
+    }
+
+    /// Token transfers settling the cross, from each user's input ATA to the
+    /// counterparty's ATA of the same mint
+    pub fn transfer_instructions(&self) -> Vec<Instruction> {
+        let token_program = token_program_id();
+        let [ref first, ref second] = self.legs;
+
+        [(first, second), (second, first)]
+            .into_iter()
+            .map(|(from, to)| {
+                let mut data = vec![TRANSFER_TAG];
+                data.extend_from_slice(&from.input_amount.to_le_bytes());
+                Instruction {
+                    program_id: token_program,
+                    accounts: vec![
+                        AccountMeta::new(associated_token_address(&from.user, &from.input_mint), false),
+                        AccountMeta::new(associated_token_address(&to.user, &from.input_mint), false),
+                        AccountMeta::new_readonly(from.user, true),
+                    ],
+                    data,
+                }
+            })
+            .collect()
+    }
+}
+
+/// What happened to a submitted intent
+#[derive(Debug, Clone, PartialEq)]
+pub enum NettingOutcome {
+    /// Not eligible (netting disabled, no consent, not an ExactIn swap) or the book is full
+    Route,
+    /// Waiting for an opposing intent
+    Resting,
+    Crossed(Box<NettedTrade>),
+}
+
+#[derive(Debug, Clone)]
+struct Resting {
+    intent: Intent,
+    arrived_ms: i64,
+}
+
+/// Swap terms netting works with
+struct Side<'a> {
+    intent: &'a Intent,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount: u64,
+    minimum_received: u64,
+}
+
+impl<'a> Side<'a> {
+    fn of(intent: &'a Intent) -> Option<Self> {
+        let swap = intent.swap_details.as_ref()?;
+        let eligible = intent.constraints.allow_netting
+            && intent.intent_type == IntentType::Swap
+            && intent.legs.is_empty()
+            && swap.mode == SwapMode::ExactIn
+            && swap.amount > 0;
+        eligible.then(|| Side {
+            intent,
+            input_mint: swap.input_mint,
+            output_mint: swap.output_mint,
+            amount: swap.amount,
+            minimum_received: swap.minimum_received.unwrap_or(0),
+        })
+    }
+}
+
+/// Crosses opposing opted-in intents within a short window
+pub struct NettingEngine {
+    settings: RwLock<NettingSettings>,
+
+    /// Resting intents by (input mint, output mint), oldest first
+    book: Mutex<HashMap<(Pubkey, Pubkey), VecDeque<Resting>>>,
+    clock: SharedClock,
+}
+
+impl NettingEngine {
+    pub fn new(settings: NettingSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            book: Mutex::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.netting.clone())
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Apply the hot-reloadable `[netting]` section; resting intents stay
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        info!(
+            "Netting updated: enabled={}, window {}ms",
+            config.netting.enabled, config.netting.window_ms
+        );
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.netting.clone();
+    }
+
+    fn settings(&self) -> NettingSettings {
+        self.settings.read().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<(Pubkey, Pubkey), VecDeque<Resting>>> {
+        self.book.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Cross `intent` against a resting opposite intent, or rest it
+    ///
+    /// `mid_price` is the oracle mid in atoms of the intent's output mint per
+    /// atom of its input mint.
+    pub fn submit(&self, intent: &Intent, mid_price: f64) -> Result<NettingOutcome> {
+        let settings = self.settings();
+        let Some(side) = Side::of(intent).filter(|_| settings.enabled) else {
+            return Ok(NettingOutcome::Route);
+        };
+        if !(mid_price.is_finite() && mid_price > 0.0) {
+            return Err(SentinelError::PriceOracleError(format!(
+                "Invalid mid price {} for netting intent {}",
+                mid_price, intent.intent_id
+            )));
+        }
+
+        let now_ms = self.clock.unix_millis();
+        let window_ms = settings.window_ms as i64;
+        let mut book = self.lock();
+
+        if let Some(opposite) = book.get_mut(&(side.output_mint, side.input_mint)) {
+            opposite.retain(|resting| now_ms - resting.arrived_ms < window_ms);
+            let matched = opposite.iter().enumerate().find_map(|(index, resting)| {
+                let resting_side = Side::of(&resting.intent)?;
+                if resting.intent.user_public_key == intent.user_public_key {
+                    return None;
+                }
+                let trade = cross(&side, &resting_side, mid_price, settings.min_cross_fraction)?;
+                Some((index, Box::new(trade)))
+            });
+            if let Some((index, trade)) = matched {
+                opposite.remove(index);
+                info!(
+                    "🔀 Netted {} against {}: {} ↔ {} atoms at mid {}",
+                    trade.legs[0].intent_id,
+                    trade.legs[1].intent_id,
+                    trade.legs[0].input_amount,
+                    trade.legs[1].input_amount,
+                    mid_price
+                );
+                return Ok(NettingOutcome::Crossed(trade));
+            }
+        }
+
+        let queue = book.entry((side.input_mint, side.output_mint)).or_default();
+        queue.retain(|resting| now_ms - resting.arrived_ms < window_ms);
+        if queue.len() >= settings.max_resting_per_pair {
+            debug!("Netting book full for {} → {}", side.input_mint, side.output_mint);
+            return Ok(NettingOutcome::Route);
+        }
+        queue.push_back(Resting {
+            intent: intent.clone(),
+            arrived_ms: now_ms,
+        });
+        Ok(NettingOutcome::Resting)
+    }
+
+    /// Remove and return intents whose window lapsed, for normal routing
+    pub fn expire_due(&self) -> Vec<Intent> {
+        let now_ms = self.clock.unix_millis();
+        let window_ms = self.settings().window_ms as i64;
+        let mut book = self.lock();
+
+        let mut lapsed = Vec::new();
+        for queue in book.values_mut() {
+            while queue.front().is_some_and(|resting| now_ms - resting.arrived_ms >= window_ms) {
+                lapsed.extend(queue.pop_front().map(|resting| resting.intent));
+            }
+        }
+        book.retain(|_, queue| !queue.is_empty());
+        lapsed
+    }
+
+    /// Take a resting intent out of the book (cancelled, or routed elsewhere)
+    pub fn withdraw(&self, intent_id: &str) -> Option<Intent> {
+        let mut book = self.lock();
+        for queue in book.values_mut() {
+            if let Some(index) = queue.iter().position(|resting| resting.intent.intent_id == intent_id) {
+                return queue.remove(index).map(|resting| resting.intent);
+            }
+        }
+        None
+    }
+
+    /// Intents currently waiting
+    pub fn resting(&self) -> usize {
+        self.lock().values().map(VecDeque::len).sum()
+    }
+}
+
+/// Largest cross of `arriving` (I → O) against `resting` (O → I) at `mid` (O per I)
+fn cross(arriving: &Side<'_>, resting: &Side<'_>, mid: f64, min_fraction: f64) -> Option<NettedTrade> {
+    let arriving_in = (arriving.amount as f64).min(resting.amount as f64 / mid).floor() as u64;
+    let resting_in = ((arriving_in as f64 * mid).floor() as u64).min(resting.amount);
+    if arriving_in == 0 || resting_in == 0 {
+        return None;
+    }
+
+    let arriving_fraction = arriving_in as f64 / arriving.amount as f64;
+    let resting_fraction = resting_in as f64 / resting.amount as f64;
+    if arriving_fraction.min(resting_fraction) < min_fraction {
+        return None;
+    }
+
+    // Each side's floor applies pro rata to the crossed part
+    let honours_floor = |received: u64, floor: u64, fraction: f64| received as f64 >= floor as f64 * fraction;
+    if !honours_floor(resting_in, arriving.minimum_received, arriving_fraction)
+        || !honours_floor(arriving_in, resting.minimum_received, resting_fraction)
+    {
+        return None;
+    }
+
+    let leg = |side: &Side<'_>, given: u64, received: u64| NettingLeg {
+        intent_id: side.intent.intent_id.clone(),
+        user: side.intent.user_public_key,
+        input_mint: side.input_mint,
+        input_amount: given,
+        output_mint: side.output_mint,
+        output_amount: received,
+    };
+    let residuals = [(arriving, arriving_in), (resting, resting_in)]
+        .into_iter()
+        .filter(|(side, crossed)| side.amount > *crossed)
+        .map(|(side, crossed)| NettingResidual {
+            intent_id: side.intent.intent_id.clone(),
+            input_mint: side.input_mint,
+            amount: side.amount - crossed,
+        })
+        .collect();
+
+    Some(NettedTrade {
+        mid_price: mid,
+        legs: [leg(arriving, arriving_in, resting_in), leg(resting, resting_in, arriving_in)],
+        residuals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::intent::Constraints;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn sol() -> Pubkey {
+        Pubkey::new_from_array([1; 32])
+    }
+
+    fn usdc() -> Pubkey {
+        Pubkey::new_from_array([2; 32])
+    }
+
+    fn swap(id: &str, input_mint: Pubkey, output_mint: Pubkey, amount: u64, allow_netting: bool) -> Intent {
+        let mut intent = Intent {
+            intent_id: id.to_string(),
+            constraints: Constraints {
+                allow_netting,
+                ..Constraints::default()
+            },
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.input_mint = input_mint;
+        swap.output_mint = output_mint;
+        swap.amount = amount;
+        swap.minimum_received = None;
+        intent
+    }
+
+    fn engine(clock: Arc<ManualClock>) -> NettingEngine {
+        NettingEngine::new(NettingSettings {
+            enabled: true,
+            ..NettingSettings::default()
+        })
+        .with_clock(clock)
+    }
+
+    #[test]
+    fn test_crosses_opposing_intents_at_mid() {
+        let engine = engine(Arc::new(ManualClock::at_timestamp(1_700_000_000)));
+
+        // A sells 2 SOL (1e9 atoms each); B sells 150 USDC (1e6 atoms each); mid 100 USDC/SOL
+        let a = swap("a", sol(), usdc(), 2_000_000_000, true);
+        let b = swap("b", usdc(), sol(), 150_000_000, true);
+        assert_eq!(engine.submit(&a, 0.1).unwrap(), NettingOutcome::Resting);
+
+        let NettingOutcome::Crossed(trade) = engine.submit(&b, 10.0).unwrap() else {
+            panic!("expected a cross");
+        };
+        assert_eq!(trade.legs[0].intent_id, "b");
+        assert_eq!((trade.legs[0].input_amount, trade.legs[0].output_amount), (150_000_000, 1_500_000_000));
+        assert_eq!((trade.legs[1].input_amount, trade.legs[1].output_amount), (1_500_000_000, 150_000_000));
+
+        // A's last 0.5 SOL goes to the DEX in the same bundle
+        assert_eq!(trade.residuals.len(), 1);
+        assert_eq!((trade.residuals[0].intent_id.as_str(), trade.residuals[0].amount), ("a", 500_000_000));
+        assert_eq!(engine.resting(), 0);
+
+        let transfers = trade.transfer_instructions();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].data[0], TRANSFER_TAG);
+        assert_eq!(transfers[0].accounts[2].pubkey, trade.legs[0].user);
+        assert_eq!(transfers[1].accounts[1].pubkey, associated_token_address(&trade.legs[0].user, &sol()));
+    }
+
+    #[test]
+    fn test_requires_consent_and_respects_floors() {
+        let engine = engine(Arc::new(ManualClock::at_timestamp(1_700_000_000)));
+
+        let unconsented = swap("a", sol(), usdc(), 1_000_000_000, false);
+        assert_eq!(engine.submit(&unconsented, 0.1).unwrap(), NettingOutcome::Route);
+
+        // B wants at least 0.11 SOL per USDC; the mid gives 0.1
+        let a = swap("a", sol(), usdc(), 1_000_000_000, true);
+        let mut b = swap("b", usdc(), sol(), 100_000_000, true);
+        b.swap_details.as_mut().unwrap().minimum_received = Some(1_100_000_000);
+        assert_eq!(engine.submit(&a, 0.1).unwrap(), NettingOutcome::Resting);
+        assert_eq!(engine.submit(&b, 10.0).unwrap(), NettingOutcome::Resting);
+        assert_eq!(engine.resting(), 2);
+
+        // Too small a cross is not worth a netting leg
+        let dust = swap("c", usdc(), sol(), 1_000_000, true);
+        assert_eq!(engine.submit(&dust, 10.0).unwrap(), NettingOutcome::Resting);
+        assert!(engine.submit(&a, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_window_expiry_and_withdraw() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let engine = engine(clock.clone());
+
+        engine.submit(&swap("a", sol(), usdc(), 1_000_000_000, true), 0.1).unwrap();
+        engine.submit(&swap("b", sol(), usdc(), 1_000_000_000, true), 0.1).unwrap();
+        assert_eq!(engine.withdraw("b").map(|i| i.intent_id), Some("b".to_string()));
+
+        clock.advance(Duration::from_millis(2_000));
+        let lapsed = engine.expire_due();
+        assert_eq!(lapsed.len(), 1);
+        assert_eq!(engine.resting(), 0);
+
+        // A lapsed intent no longer crosses
+        engine.submit(&swap("c", sol(), usdc(), 1_000_000_000, true), 0.1).unwrap();
+        clock.advance(Duration::from_millis(2_000));
+        let late = swap("d", usdc(), sol(), 100_000_000, true);
+        assert_eq!(engine.submit(&late, 10.0).unwrap(), NettingOutcome::Resting);
+    }
+}
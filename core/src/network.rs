@@ -0,0 +1,251 @@
+//! Solana Cluster Selection
+//!
+//! Every network-specific constant (RPC / websocket / block engine URLs, Jito
+//! tip accounts, which well-known programs are deployed) hangs off
+//! [`Network`], so switching a deployment to devnet, testnet or a local
+//! validator is a single `network = "devnet"` config key rather than a code
+//! edit. Endpoint values set explicitly in config still win over the
+//! network's defaults.
+//!
+//! `Localnet` targets `solana-test-validator` plus the mock block engine in
+//! `jito-mock` (`localnet-block-engine` binary), so integration tests need
+//! no credentials or faucet SOL beyond the local validator's airdrops.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Result, SentinelError};
+
+/// Block engine address the localnet mock listens on
+pub const LOCALNET_BLOCK_ENGINE_URL: &str = "http://127.0.0.1:8910";
+
+/// Official mainnet Jito tip payment accounts
+const MAINNET_TIP_ACCOUNTS: &[&str] = &[
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Jito tip payment accounts on the devnet / testnet block engines
+///
+/// Confirm against the block engine's `getTipAccounts` when Jito rotates them.
+const TEST_CLUSTER_TIP_ACCOUNTS: &[&str] = &[
+    "B1mrQSpdeMU9gCvkJ6VsXVVoYjRGkNA7TtjMyqxrhecH",
+    "aTtUk2DHgLhKZRDjePq6eiHRKC1XXFMBiSUfQ2JNDbN",
+    "E2eSqe33tuhAHKTrwky5uEjaVqnb2T9ns6nHHUrN8588",
+    "4xgEmT58RwTNsF5xm2RMYCnR1EVukdK8a1i2qFjnJFu3",
+    "EoW3SUQap7ZeynXQ2QJ847aerhxbPVr843uMeTfc9dxM",
+    "ARTtviJkLLt6cHGQDydfo1Wyk6M4VGZdKZ2ZhdnJL336",
+    "9n3d1K5YD2vECAbRFhFFGYNNjiXtHXJWn9F31t89vsAV",
+    "9ttgPBBhRYFuQccdR1DSnb7hydsWANoDsV3P9kaGMCEh",
+];
+
+/// Programs the router builds against or recognizes, with where they exist
+const KNOWN_PROGRAMS: &[(&str, &str, &[Network])] = &[
+    ("Jupiter v6", "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", &[Network::Mainnet]),
+    ("Raydium AMM v4", "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8", &[Network::Mainnet]),
+    ("Raydium CLMM", "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK", &[Network::Mainnet]),
+    (
+        "Orca Whirlpool",
+        "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+        &[Network::Mainnet, Network::Devnet],
+    ),
+];
+
+/// Solana cluster the router runs against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Network {
+    #[default]
+    #[serde(alias = "mainnet-beta")]
+    Mainnet,
+    Devnet,
+    Testnet,
+    /// `solana-test-validator` with the mock block engine
+    Localnet,
+}
+
+impl Network {
+    pub const ALL: [Network; 4] = [Network::Mainnet, Network::Devnet, Network::Testnet, Network::Localnet];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Devnet => "devnet",
+            Network::Testnet => "testnet",
+            Network::Localnet => "localnet",
+        }
+    }
+
+    /// Public JSON-RPC endpoint
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "https://api.mainnet-beta.solana.com",
+            Network::Devnet => "https://api.devnet.solana.com",
+            Network::Testnet => "https://api.testnet.solana.com",
+            Network::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+
+    /// Public websocket endpoint
+    pub fn ws_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "wss://api.mainnet-beta.solana.com",
+            Network::Devnet => "wss://api.devnet.solana.com",
+            Network::Testnet => "wss://api.testnet.solana.com",
+            Network::Localnet => "ws://127.0.0.1:8900",
+        }
+    }
+
+    /// Jito block engine
+    pub fn jito_block_engine_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "https://mainnet.block-engine.jito.wtf",
+            Network::Devnet => "https://frankfurt.devnet.block-engine.jito.wtf",
+            Network::Testnet => "https://dallas.testnet.block-engine.jito.wtf",
+            Network::Localnet => LOCALNET_BLOCK_ENGINE_URL,
+        }
+    }
+
+    /// Network-wide landed-tip percentiles
+    ///
+    /// Jito only publishes mainnet data; the localnet mock serves its own.
+    pub fn jito_tip_floor_url(&self) -> String {
+        match self {
+            Network::Localnet => format!("{}/api/v1/bundles/tip_floor", LOCALNET_BLOCK_ENGINE_URL),
+            _ => "https://bundles.jito.wtf/api/v1/bundles/tip_floor".to_string(),
+        }
+    }
+
+    /// Jito tip payment accounts (base58)
+    ///
+    /// Localnet has no tip program; the mock engine accepts transfers to the
+    /// mainnet addresses, which a local validator treats as plain accounts.
+    pub fn tip_accounts(&self) -> &'static [&'static str] {
+        match self {
+            Network::Mainnet | Network::Localnet => MAINNET_TIP_ACCOUNTS,
+            Network::Devnet | Network::Testnet => TEST_CLUSTER_TIP_ACCOUNTS,
+        }
+    }
+
+    pub fn tip_account_pubkeys(&self) -> Vec<Pubkey> {
+        self.tip_accounts()
+            .iter()
+            .filter_map(|account| Pubkey::from_str(account).ok())
+            .collect()
+    }
+
+    /// Tip account for a bundle, rotated by `seed` to spread write locks
+    pub fn tip_account(&self, seed: usize) -> Pubkey {
+        let accounts = self.tip_accounts();
+        Pubkey::from_str(accounts[seed % accounts.len()]).expect("Hardcoded Jito tip account must be valid")
+    }
+
+    /// Whether `account` is a Jito tip account on any cluster
+    pub fn is_any_tip_account(account: &Pubkey) -> bool {
+        let account = account.to_string();
+        MAINNET_TIP_ACCOUNTS
+            .iter()
+            .chain(TEST_CLUSTER_TIP_ACCOUNTS)
+            .any(|tip| *tip == account)
+    }
+
+    /// Well-known programs deployed on this cluster, as (label, program id)
+    ///
+    /// Localnet lists none: programs exist only if the test harness deploys
+    /// or clones them.
+    pub fn known_programs(&self) -> Vec<(&'static str, Pubkey)> {
+        KNOWN_PROGRAMS
+            .iter()
+            .filter(|(_, _, networks)| networks.contains(self))
+            .filter_map(|(label, id, _)| Pubkey::from_str(id).ok().map(|id| (*label, id)))
+            .collect()
+    }
+
+    pub fn has_program(&self, program_id: &Pubkey) -> bool {
+        self.known_programs().iter().any(|(_, id)| id == program_id)
+    }
+
+    /// Devnet, testnet and localnet hand out SOL for free
+    pub fn has_faucet(&self) -> bool {
+        !matches!(self, Network::Mainnet)
+    }
+
+    /// Mainnet validator intel (identities, MEV history) applies
+    pub fn uses_mainnet_intel(&self) -> bool {
+        matches!(self, Network::Mainnet)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Network {
+    type Err = SentinelError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Network::Mainnet),
+            "devnet" => Ok(Network::Devnet),
+            "testnet" => Ok(Network::Testnet),
+            "localnet" => Ok(Network::Localnet),
+            other => Err(SentinelError::ConfigError(format!("Unknown network: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        for network in Network::ALL {
+            assert_eq!(network.to_string().parse::<Network>().unwrap(), network);
+        }
+        assert_eq!("mainnet-beta".parse::<Network>().unwrap(), Network::Mainnet);
+        assert!("solana".parse::<Network>().is_err());
+        assert_eq!(serde_json::to_string(&Network::Devnet).unwrap(), "\"devnet\"");
+    }
+
+    #[test]
+    fn test_tip_accounts_are_valid_and_recognized() {
+        for network in Network::ALL {
+            let pubkeys = network.tip_account_pubkeys();
+            assert_eq!(pubkeys.len(), network.tip_accounts().len(), "{}", network);
+            assert!(pubkeys.iter().all(Network::is_any_tip_account));
+            assert_eq!(network.tip_account(9), pubkeys[1]);
+        }
+        assert!(!Network::is_any_tip_account(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_known_programs_per_cluster() {
+        let jupiter = Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap();
+        let whirlpool = Pubkey::from_str("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").unwrap();
+
+        assert!(Network::Mainnet.has_program(&jupiter));
+        assert!(!Network::Devnet.has_program(&jupiter));
+        assert!(Network::Devnet.has_program(&whirlpool));
+        assert!(Network::Localnet.known_programs().is_empty());
+    }
+
+    #[test]
+    fn test_localnet_points_at_local_services() {
+        let local = Network::Localnet;
+        assert!(local.rpc_url().starts_with("http://127.0.0.1"));
+        assert_eq!(local.jito_block_engine_url(), LOCALNET_BLOCK_ENGINE_URL);
+        assert!(local.jito_tip_floor_url().starts_with(LOCALNET_BLOCK_ENGINE_URL));
+        assert!(local.has_faucet() && !Network::Mainnet.has_faucet());
+    }
+}
@@ -18,6 +18,8 @@ use tracing::info;
 #[derive(Clone)]
 pub struct NonceManager {
     nonce_accounts: Arc<RwLock<HashMap<Pubkey, NonceAccountInfo>>>,
+    /// Nonce account → intent whose held transaction is built on it
+    reservations: Arc<RwLock<HashMap<Pubkey, String>>>,
     rpc_endpoint: String,
 }
 
@@ -43,6 +45,7 @@ impl NonceManager {
 
         Self {
             nonce_accounts: Arc::new(RwLock::new(HashMap::new())),
+            reservations: Arc::new(RwLock::new(HashMap::new())),
             rpc_endpoint,
         }
     }
@@ -82,6 +85,29 @@ impl NonceManager {
         let cache = self.nonce_accounts.read().await;
         cache.get(address).cloned()
     }
+
+    /// Reserve an unreserved nonce account for `intent_id`'s held transaction
+    pub async fn reserve(&self, intent_id: &str) -> Option<NonceAccountInfo> {
+        let cache = self.nonce_accounts.read().await;
+        let mut reservations = self.reservations.write().await;
+        let info = cache.values().find(|info| !reservations.contains_key(&info.address))?;
+        reservations.insert(info.address, intent_id.to_string());
+        Some(info.clone())
+    }
+
+    /// Return `intent_id`'s nonce accounts to the pool
+    pub async fn release(&self, intent_id: &str) -> Vec<Pubkey> {
+        let mut reservations = self.reservations.write().await;
+        let released: Vec<Pubkey> = reservations
+            .iter()
+            .filter(|(_, reserved_for)| *reserved_for == intent_id)
+            .map(|(address, _)| *address)
+            .collect();
+        for address in &released {
+            reservations.remove(address);
+        }
+        released
+    }
 }
 
 #[cfg(test)]
@@ -161,4 +187,26 @@ mod tests {
         let not_found = manager.get_nonce_account(&Pubkey::new_unique()).await;
         assert!(not_found.is_none());
     }
+
+    #[tokio::test]
+    async fn test_reserve_and_release() {
+        let manager = NonceManager::new("https://api.devnet.solana.com".to_string());
+
+        let info = NonceAccountInfo {
+            address: Pubkey::new_unique(),
+            current_nonce: Hash::new_unique(),
+            authority: Pubkey::new_unique(),
+            lamports: 1000000,
+            last_updated: 1234567890,
+        };
+
+        manager.add_nonce_account(info.clone()).await;
+
+        assert_eq!(manager.reserve("intent-1").await.unwrap().address, info.address);
+        assert!(manager.reserve("intent-2").await.is_none());
+
+        assert!(manager.release("intent-2").await.is_empty());
+        assert_eq!(manager.release("intent-1").await, vec![info.address]);
+        assert!(manager.reserve("intent-2").await.is_some());
+    }
 }
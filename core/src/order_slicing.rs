@@ -0,0 +1,405 @@
+//! Impact-Aware Order Slicing
+//!
+//! A swap that consumes a large share of pool depth (`liquidity_utilization`
+//! above `slicing.utilization_threshold`) pays heavy price impact and is an
+//! obvious sandwich target. Instead of only flagging it, the router splits it
+//! into child swaps spread over slots and venues:
+//! - slice count: enough that each slice stays under
+//!   `target_slice_utilization`, capped at `max_slices`
+//! - venues: slices alternate across the venues that quoted, best first
+//! - timing: `slots_between_slices` apart, so arbitrage restores the pool
+//!   between slices
+//!
+//! This is router-initiated and distinct from a user TWAP: it only applies to
+//! ExactIn swaps that allow partial fills (a sliced order can end partially
+//! filled), and each child carries the parent's price floor prorated to its
+//! size. Projected impact uses a linear model (impact ∝ size, with the pool
+//! recovering between slices); realized impact is measured against the
+//! pre-trade rate of the best quote and reported per slice.
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use tracing::info;
+
+use crate::best_execution::{DexVenue, VenueQuote};
+use crate::config::{SentinelConfig, SlicingSettings};
+use crate::intent::{Intent, IntentType, SwapMode};
+use crate::{Result, SentinelError};
+
+/// One child swap of a sliced order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedSlice {
+    pub index: u16,
+    pub venue: DexVenue,
+    pub amount: u64,
+
+    /// Earliest slot the slice may be submitted in
+    pub not_before_slot: u64,
+
+    pub projected_impact_bps: f64,
+
+    /// Child swap intent (`<intent_id>-slice-<index>`)
+    pub intent: Intent,
+}
+
+/// Realized execution of one slice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliceFill {
+    pub index: u16,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub realized_impact_bps: f64,
+}
+
+/// Execution plan of a sliced order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlicePlan {
+    pub parent_intent_id: String,
+
+    /// Pre-trade output atoms per input atom (best quote with its impact removed)
+    pub reference_rate: f64,
+
+    /// Impact of executing the whole order at once on the best venue
+    pub unsliced_impact_bps: f64,
+
+    /// Amount-weighted projected impact of the slices
+    pub projected_impact_bps: f64,
+
+    pub slices: Vec<PlannedSlice>,
+    pub fills: Vec<SliceFill>,
+}
+
+impl SlicePlan {
+    /// Record the realized amounts of a filled slice
+    pub fn record_fill(&mut self, index: u16, in_amount: u64, out_amount: u64) -> Result<&SliceFill> {
+        if !self.slices.iter().any(|s| s.index == index) {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Slice {} is not part of {}",
+                index, self.parent_intent_id
+            )));
+        }
+        if self.fills.iter().any(|f| f.index == index) {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Slice {} of {} is already filled",
+                index, self.parent_intent_id
+            )));
+        }
+
+        let expected_out = in_amount as f64 * self.reference_rate;
+        let realized_impact_bps = if expected_out > 0.0 {
+            (expected_out - out_amount as f64) / expected_out * 10_000.0
+        } else {
+            0.0
+        };
+        self.fills.push(SliceFill {
+            index,
+            in_amount,
+            out_amount,
+            realized_impact_bps,
+        });
+        Ok(self.fills.last().expect("fill was just pushed"))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fills.len() == self.slices.len()
+    }
+
+    /// Projected-vs-realized impact so far
+    pub fn impact_report(&self) -> ImpactReport {
+        let per_slice = self
+            .slices
+            .iter()
+            .map(|slice| SliceImpact {
+                index: slice.index,
+                venue: slice.venue,
+                amount: slice.amount,
+                projected_impact_bps: slice.projected_impact_bps,
+                realized_impact_bps: self
+                    .fills
+                    .iter()
+                    .find(|f| f.index == slice.index)
+                    .map(|f| f.realized_impact_bps),
+            })
+            .collect();
+
+        let filled_in_amount: u64 = self.fills.iter().map(|f| f.in_amount).sum();
+        let realized_impact_bps = (filled_in_amount > 0).then(|| {
+            self.fills
+                .iter()
+                .map(|f| f.realized_impact_bps * f.in_amount as f64)
+                .sum::<f64>()
+                / filled_in_amount as f64
+        });
+
+        ImpactReport {
+            parent_intent_id: self.parent_intent_id.clone(),
+            slices_planned: self.slices.len(),
+            slices_filled: self.fills.len(),
+            planned_in_amount: self.slices.iter().map(|s| s.amount).sum(),
+            filled_in_amount,
+            unsliced_impact_bps: self.unsliced_impact_bps,
+            projected_impact_bps: self.projected_impact_bps,
+            realized_impact_bps,
+            slices: per_slice,
+        }
+    }
+}
+
+/// Projected vs realized impact of one slice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliceImpact {
+    pub index: u16,
+    pub venue: DexVenue,
+    pub amount: u64,
+    pub projected_impact_bps: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realized_impact_bps: Option<f64>,
+}
+
+/// Projected-vs-realized impact of a sliced order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImpactReport {
+    pub parent_intent_id: String,
+    pub slices_planned: usize,
+    pub slices_filled: usize,
+    pub planned_in_amount: u64,
+    pub filled_in_amount: u64,
+    pub unsliced_impact_bps: f64,
+    pub projected_impact_bps: f64,
+
+    /// Input-weighted realized impact of filled slices (None before the first fill)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realized_impact_bps: Option<f64>,
+
+    pub slices: Vec<SliceImpact>,
+}
+
+/// Decides when and how to slice large swaps
+pub struct OrderSlicer {
+    settings: RwLock<SlicingSettings>,
+}
+
+impl OrderSlicer {
+    pub fn new(settings: SlicingSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.slicing.clone())
+    }
+
+    /// Apply the hot-reloadable `[slicing]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.slicing.clone();
+    }
+
+    fn settings(&self) -> SlicingSettings {
+        self.settings.read().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    /// Whether `intent` is eligible and large enough to slice
+    pub fn should_slice(&self, intent: &Intent, liquidity_utilization: f32) -> bool {
+        let settings = self.settings();
+        settings.enabled
+            && intent.intent_type == IntentType::Swap
+            && intent.constraints.partial_fill
+            && intent.swap_details.as_ref().is_some_and(|s| s.mode == SwapMode::ExactIn)
+            && liquidity_utilization > settings.utilization_threshold
+    }
+
+    /// Split `intent` across slots and the venues in `quotes` (full-size quotes)
+    ///
+    /// Returns None when the intent should execute as a single swap.
+    pub fn plan(
+        &self,
+        intent: &Intent,
+        liquidity_utilization: f32,
+        quotes: &[VenueQuote],
+        current_slot: u64,
+    ) -> Option<SlicePlan> {
+        if !self.should_slice(intent, liquidity_utilization) {
+            return None;
+        }
+        let settings = self.settings();
+        let swap = intent.swap_details.as_ref()?;
+
+        // Best venue first (ExactIn: most output)
+        let mut venues: Vec<&VenueQuote> = quotes.iter().filter(|q| q.is_ok() && q.in_amount > 0).collect();
+        venues.sort_by_key(|q| std::cmp::Reverse(q.out_amount));
+        let best = *venues.first()?;
+
+        let wanted = (liquidity_utilization / settings.target_slice_utilization).ceil() as u64;
+        let count = wanted.clamp(2, u64::from(settings.max_slices)).min(swap.amount) as u16;
+        if count < 2 {
+            return None;
+        }
+
+        let impact = |quote: &VenueQuote| quote.price_impact_bps.max(0.0);
+        let reference_rate = best.out_amount as f64 / (1.0 - (impact(best) / 10_000.0).min(0.99)) / best.in_amount as f64;
+
+        let slices: Vec<PlannedSlice> = (0..count)
+            .map(|index| {
+                let amount = slice_amount(swap.amount, count, index);
+                let quote = venues[index as usize % venues.len()];
+                PlannedSlice {
+                    index,
+                    venue: quote.venue,
+                    amount,
+                    not_before_slot: current_slot + u64::from(index) * settings.slots_between_slices,
+                    projected_impact_bps: impact(quote) * amount as f64 / swap.amount as f64,
+                    intent: child_intent(intent, index, amount, quote.venue),
+                }
+            })
+            .collect();
+
+        let projected_impact_bps = slices
+            .iter()
+            .map(|s| s.projected_impact_bps * s.amount as f64)
+            .sum::<f64>()
+            / swap.amount as f64;
+
+        info!(
+            "✂️ Slicing {} into {} swaps across {} venue(s): projected impact {:.1} bps (unsliced {:.1})",
+            intent.intent_id,
+            count,
+            venues.len().min(count as usize),
+            projected_impact_bps,
+            impact(best)
+        );
+
+        Some(SlicePlan {
+            parent_intent_id: intent.intent_id.clone(),
+            reference_rate,
+            unsliced_impact_bps: impact(best),
+            projected_impact_bps,
+            slices,
+            fills: Vec::new(),
+        })
+    }
+}
+
+/// Input amount of slice `index`; the last slice takes the remainder
+fn slice_amount(total: u64, count: u16, index: u16) -> u64 {
+    let count = u64::from(count);
+    let base = total / count;
+    if u64::from(index) + 1 == count {
+        base + total % count
+    } else {
+        base
+    }
+}
+
+fn child_intent(parent: &Intent, index: u16, amount: u64, venue: DexVenue) -> Intent {
+    let mut child = parent.clone();
+    child.intent_id = format!("{}-slice-{}", parent.intent_id, index);
+    if let Some(swap) = child.swap_details.as_mut() {
+        // The parent's price floor, prorated to the slice
+        swap.minimum_received = swap
+            .minimum_received
+            .map(|minimum| (minimum as u128 * amount as u128).div_ceil(swap.amount.max(1) as u128) as u64);
+        swap.amount = amount;
+        swap.dex = Some(
+            match venue {
+                DexVenue::Jupiter => "Jupiter",
+                DexVenue::Orca => "Orca",
+            }
+            .to_string(),
+        );
+    }
+    child
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::Constraints;
+
+    fn intent(partial_fill: bool) -> Intent {
+        let mut intent = Intent {
+            intent_id: "big-1".to_string(),
+            constraints: Constraints {
+                partial_fill,
+                ..Constraints::default()
+            },
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.amount = 1_000_001;
+        swap.minimum_received = Some(1_800_000);
+        intent
+    }
+
+    fn quotes() -> Vec<VenueQuote> {
+        vec![
+            VenueQuote::new(DexVenue::Orca, 1_000_001, 1_900_000, 0, 150.0),
+            VenueQuote::new(DexVenue::Jupiter, 1_000_001, 1_950_000, 0, 100.0),
+            VenueQuote::failed(DexVenue::Orca, "no pool".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_small_or_atomic_orders_are_not_sliced() {
+        let slicer = OrderSlicer::new(SlicingSettings::default());
+        assert!(slicer.plan(&intent(true), 0.02, &quotes(), 100).is_none());
+        assert!(slicer.plan(&intent(false), 0.2, &quotes(), 100).is_none());
+        assert!(slicer.plan(&intent(true), 0.2, &[], 100).is_none());
+    }
+
+    #[test]
+    fn test_plan_splits_across_slots_and_venues() {
+        let slicer = OrderSlicer::new(SlicingSettings::default());
+        let plan = slicer.plan(&intent(true), 0.055, &quotes(), 100).unwrap();
+
+        // 0.055 / 0.01 rounds up to 6 slices
+        assert_eq!(plan.slices.len(), 6);
+        assert_eq!(plan.slices.iter().map(|s| s.amount).sum::<u64>(), 1_000_001);
+        assert_eq!(plan.slices[0].venue, DexVenue::Jupiter);
+        assert_eq!(plan.slices[1].venue, DexVenue::Orca);
+        assert_eq!(plan.slices[5].not_before_slot, 120);
+        assert!(plan.projected_impact_bps < plan.unsliced_impact_bps);
+
+        let child = &plan.slices[0].intent;
+        assert_eq!(child.intent_id, "big-1-slice-0");
+        let swap = child.swap_details.as_ref().unwrap();
+        assert_eq!(swap.dex.as_deref(), Some("Jupiter"));
+        // 1_800_000 × 166_666 / 1_000_001, rounded up
+        assert_eq!(swap.minimum_received, Some(299_999));
+    }
+
+    #[test]
+    fn test_slice_count_capped() {
+        let slicer = OrderSlicer::new(SlicingSettings::default());
+        let plan = slicer.plan(&intent(true), 0.5, &quotes(), 0).unwrap();
+        assert_eq!(plan.slices.len(), 8);
+    }
+
+    #[test]
+    fn test_impact_report() {
+        let slicer = OrderSlicer::new(SlicingSettings::default());
+        let mut plan = slicer.plan(&intent(true), 0.055, &quotes(), 100).unwrap();
+        assert_eq!(plan.impact_report().realized_impact_bps, None);
+
+        // Fill at exactly the reference rate: zero impact
+        let amount = plan.slices[0].amount;
+        let out = (amount as f64 * plan.reference_rate).round() as u64;
+        let fill = plan.record_fill(0, amount, out).unwrap();
+        assert!(fill.realized_impact_bps.abs() < 0.01);
+
+        // 1% short of the reference rate
+        let amount = plan.slices[1].amount;
+        let out = (amount as f64 * plan.reference_rate * 0.99) as u64;
+        plan.record_fill(1, amount, out).unwrap();
+
+        let report = plan.impact_report();
+        assert_eq!(report.slices_filled, 2);
+        assert!((report.realized_impact_bps.unwrap() - 50.0).abs() < 0.1);
+        assert!((report.slices[1].realized_impact_bps.unwrap() - 100.0).abs() < 0.1);
+        assert_eq!(report.slices[2].realized_impact_bps, None);
+        assert!(!plan.is_complete());
+
+        assert!(plan.record_fill(1, amount, out).is_err());
+        assert!(plan.record_fill(42, amount, out).is_err());
+    }
+}
@@ -0,0 +1,406 @@
+//! Jupiter Swap-Instructions Passthrough
+//!
+//! Integrators that already call Jupiter's `/swap-instructions` can hand the
+//! response to the router instead of switching quote providers. The router
+//! keeps Jupiter's setup, swap and cleanup instructions and rewraps them:
+//! - Jupiter's compute budget instructions are replaced by the router's,
+//!   priced from the request's fee caps
+//! - the swap instruction gets the jitodontfront marker
+//! - routes that go through the block engine get a tip transfer from the user
+//! - `minimum_received` (explicit, or the quote at the slippage tolerance) is
+//!   enforced on the simulated output before any bundle is sent, and on-chain
+//!   as well when a [`ConsentGuard`] brackets the swap
+//!
+//! `ai_engine::PreflightChecker::plan_passthrough` scores the candidate and
+//! picks the route, producing a [`PassthroughPlan`]; the wallet signs its
+//! transaction and `BundleSubmitter::submit_passthrough` routes it.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::str::FromStr;
+
+use crate::actions::DONT_FRONT_MARKER;
+use crate::best_execution::{DexVenue, VenueQuote};
+use crate::consent_guard::{ConsentGuard, GuardTerms};
+use crate::intent::{
+    ConsentBlock, Constraints, FeePreferences, FeeSplit, Intent, IntentType, SwapDetails, SwapMode, INTENT_SCHEMA_VERSION,
+};
+use crate::preflight::{compute_budget_instructions, PreflightReport};
+use crate::types::RouteType;
+use crate::{Result, SentinelError};
+
+/// Compute unit limit of passthrough transactions when the request names none
+pub const PASSTHROUGH_COMPUTE_UNIT_LIMIT: u32 = 600_000;
+
+/// Account as serialized by Jupiter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterAccount {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Instruction as serialized by Jupiter (base58 keys, base64 data)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterInstruction {
+    pub program_id: String,
+    pub accounts: Vec<JupiterAccount>,
+    pub data: String,
+}
+
+impl JupiterInstruction {
+    pub fn to_instruction(&self) -> Result<Instruction> {
+        let pubkey = |key: &str| {
+            Pubkey::from_str(key).map_err(|_| SentinelError::ParseError(format!("Invalid Jupiter pubkey {}", key)))
+        };
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|a| {
+                Ok(AccountMeta {
+                    pubkey: pubkey(&a.pubkey)?,
+                    is_signer: a.is_signer,
+                    is_writable: a.is_writable,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = BASE64
+            .decode(&self.data)
+            .map_err(|e| SentinelError::ParseError(format!("Invalid Jupiter instruction data: {}", e)))?;
+
+        Ok(Instruction {
+            program_id: pubkey(&self.program_id)?,
+            accounts,
+            data,
+        })
+    }
+}
+
+/// Jupiter `/swap-instructions` response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterSwapInstructions {
+    #[serde(default)]
+    pub compute_budget_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    pub setup_instructions: Vec<JupiterInstruction>,
+    pub swap_instruction: JupiterInstruction,
+    #[serde(default)]
+    pub cleanup_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    pub other_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    pub address_lookup_table_addresses: Vec<String>,
+}
+
+/// Body of a passthrough request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PassthroughRequest {
+    pub user_public_key: Pubkey,
+
+    /// From the Jupiter quote the instructions were built for
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub in_amount: u64,
+    pub quoted_out_amount: u64,
+    #[serde(default)]
+    pub price_impact_bps: f64,
+
+    /// User's token account receiving `output_mint`
+    pub output_token_account: Pubkey,
+
+    /// Output floor (atoms); the quote at `max_slippage_bps` when unset
+    #[serde(default)]
+    pub minimum_received: Option<u64>,
+
+    #[serde(default)]
+    pub constraints: Constraints,
+    #[serde(default)]
+    pub fee_preferences: FeePreferences,
+    #[serde(default)]
+    pub compute_unit_limit: Option<u32>,
+
+    pub swap_instructions: JupiterSwapInstructions,
+}
+
+impl PassthroughRequest {
+    /// Output floor the routed transaction must meet in simulation
+    pub fn minimum_received(&self) -> u64 {
+        self.minimum_received.unwrap_or_else(|| {
+            let tolerance = 10_000u128.saturating_sub(self.constraints.max_slippage_bps as u128);
+            (self.quoted_out_amount as u128 * tolerance / 10_000) as u64
+        })
+    }
+
+    pub fn compute_unit_limit(&self) -> u32 {
+        self.compute_unit_limit.unwrap_or(PASSTHROUGH_COMPUTE_UNIT_LIMIT)
+    }
+
+    /// The swap as a Sentinel intent, for scoring, logging and submission tracking
+    pub fn intent(&self, recent_blockhash: Hash) -> Intent {
+        Intent {
+            intent_id: uuid::Uuid::new_v4().to_string(),
+            user_public_key: self.user_public_key,
+            intent_type: IntentType::Swap,
+            swap_details: Some(SwapDetails {
+                mode: SwapMode::ExactIn,
+                input_mint: self.input_mint,
+                output_mint: self.output_mint,
+                amount: self.in_amount,
+                minimum_received: Some(self.minimum_received()),
+                dex: Some("Jupiter".to_string()),
+                route_hints: None,
+            }),
+            constraints: self.constraints.clone(),
+            fee_preferences: self.fee_preferences.clone(),
+            consent_block: ConsentBlock {
+                recent_blockhash,
+                signature_request_id: Intent::new_signature_request_id(),
+                nonce: None,
+            },
+            limit_details: None,
+            twap_details: None,
+            dca_details: None,
+            legs: vec![],
+            schema_version: INTENT_SCHEMA_VERSION,
+        }
+    }
+
+    /// Jupiter's quote in best-execution form
+    pub fn quote(&self) -> VenueQuote {
+        VenueQuote::new(DexVenue::Jupiter, self.in_amount, self.quoted_out_amount, 0, self.price_impact_bps)
+    }
+
+    /// Setup, marked swap, cleanup and other instructions, then the tip transfer
+    pub fn protected_instructions(&self, route: &RouteType, tip_lamports: u64, tip_account: &Pubkey) -> Result<Vec<Instruction>> {
+        let jupiter = &self.swap_instructions;
+        if !jupiter.address_lookup_table_addresses.is_empty() {
+            return Err(SentinelError::InvalidIntent(
+                "Passthrough needs legacy swap instructions (request Jupiter with asLegacyTransaction=true)".to_string(),
+            ));
+        }
+
+        let mut swap = jupiter.swap_instruction.to_instruction()?;
+        let marker = Pubkey::from_str(DONT_FRONT_MARKER).expect("Valid jitodontfront pubkey");
+        if !swap.accounts.iter().any(|a| a.pubkey == marker) {
+            swap.accounts.push(AccountMeta::new_readonly(marker, false));
+        }
+
+        let mut instructions = jupiter
+            .setup_instructions
+            .iter()
+            .map(JupiterInstruction::to_instruction)
+            .collect::<Result<Vec<_>>>()?;
+        instructions.push(swap);
+        for instruction in jupiter.cleanup_instruction.iter().chain(&jupiter.other_instructions) {
+            instructions.push(instruction.to_instruction()?);
+        }
+        if route.pays_tip() && tip_lamports > 0 {
+            #[allow(deprecated)]
+            instructions.push(solana_sdk::system_instruction::transfer(
+                &self.user_public_key,
+                tip_account,
+                tip_lamports,
+            ));
+        }
+        Ok(instructions)
+    }
+
+    /// Unsigned transaction for `route`: router compute budget, then [`Self::protected_instructions`]
+    pub fn protected_transaction(
+        &self,
+        intent: &Intent,
+        route: &RouteType,
+        tip_lamports: u64,
+        tip_account: &Pubkey,
+    ) -> Result<Transaction> {
+        let instructions = self.protected_instructions(route, tip_lamports, tip_account)?;
+        Ok(self.transaction(intent, instructions))
+    }
+
+    /// [`Self::protected_transaction`] with the swap bracketed by `guard`
+    ///
+    /// The tip transfer stays inside the bracket; the guard only checks the
+    /// output token account.
+    pub fn guarded_transaction(
+        &self,
+        intent: &Intent,
+        route: &RouteType,
+        tip_lamports: u64,
+        tip_account: &Pubkey,
+        guard: &ConsentGuard,
+    ) -> Result<Transaction> {
+        let terms = GuardTerms::for_intent(intent, self.output_token_account)?;
+        let instructions = guard.wrap(&terms, self.protected_instructions(route, tip_lamports, tip_account)?);
+        Ok(self.transaction(intent, instructions))
+    }
+
+    fn transaction(&self, intent: &Intent, protected: Vec<Instruction>) -> Transaction {
+        let mut instructions = compute_budget_instructions(intent, self.compute_unit_limit()).to_vec();
+        instructions.extend(protected);
+
+        let mut transaction = Transaction::new_unsigned(Message::new(&instructions, Some(&self.user_public_key)));
+        transaction.message.recent_blockhash = intent.consent_block.recent_blockhash;
+        transaction
+    }
+}
+
+/// Scored, routed passthrough swap awaiting the user's signature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PassthroughPlan {
+    pub intent: Intent,
+    pub report: PreflightReport,
+    pub route: RouteType,
+
+    /// How the fee budget was divided for `route`
+    pub fee_split: FeeSplit,
+
+    /// Unsigned protected transaction for the wallet to sign
+    pub transaction: Transaction,
+
+    pub output_token_account: Pubkey,
+    pub minimum_received: u64,
+}
+
+impl PassthroughPlan {
+    /// Whether `signed` is this plan's transaction (same message) with the user's signature
+    pub fn check_signed(&self, signed: &Transaction) -> Result<()> {
+        if signed.message != self.transaction.message {
+            return Err(SentinelError::InvalidIntent(
+                "Signed transaction differs from the planned passthrough transaction".to_string(),
+            ));
+        }
+        signed
+            .verify()
+            .map_err(|e| SentinelError::InvalidIntent(format!("Passthrough transaction signature invalid: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    fn jupiter_ix(program: Pubkey, accounts: &[(Pubkey, bool, bool)], data: &[u8]) -> JupiterInstruction {
+        JupiterInstruction {
+            program_id: program.to_string(),
+            accounts: accounts
+                .iter()
+                .map(|(pubkey, is_signer, is_writable)| JupiterAccount {
+                    pubkey: pubkey.to_string(),
+                    is_signer: *is_signer,
+                    is_writable: *is_writable,
+                })
+                .collect(),
+            data: BASE64.encode(data),
+        }
+    }
+
+    fn request(user: Pubkey) -> PassthroughRequest {
+        let (jupiter, ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let response = serde_json::json!({
+            "computeBudgetInstructions": [jupiter_ix(solana_sdk::compute_budget::id(), &[], &[2, 0, 0, 0, 0])],
+            "setupInstructions": [jupiter_ix(Pubkey::new_unique(), &[(user, true, true), (ata, false, true)], &[1])],
+            "swapInstruction": jupiter_ix(jupiter, &[(user, true, false), (ata, false, true)], &[9, 9]),
+            "cleanupInstruction": null,
+            "addressLookupTableAddresses": [],
+        });
+        PassthroughRequest {
+            user_public_key: user,
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            in_amount: 1_000_000,
+            quoted_out_amount: 2_000_000,
+            price_impact_bps: 10.0,
+            output_token_account: ata,
+            minimum_received: None,
+            constraints: Constraints { max_slippage_bps: 100, ..Default::default() },
+            fee_preferences: FeePreferences::default(),
+            compute_unit_limit: None,
+            swap_instructions: serde_json::from_value(response).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_minimum_received_from_slippage() {
+        let mut request = request(Pubkey::new_unique());
+        assert_eq!(request.minimum_received(), 1_980_000);
+        request.minimum_received = Some(1_990_000);
+        assert_eq!(request.minimum_received(), 1_990_000);
+        assert_eq!(request.intent(Hash::new_unique()).swap_details.unwrap().minimum_received, Some(1_990_000));
+    }
+
+    #[test]
+    fn test_protected_transaction_injects_budget_marker_and_tip() {
+        let user = Keypair::new();
+        let request = request(user.pubkey());
+        let intent = request.intent(Hash::new_unique());
+        let tip_account = Pubkey::new_unique();
+        let marker = Pubkey::from_str(DONT_FRONT_MARKER).unwrap();
+
+        let tx = request.protected_transaction(&intent, &RouteType::JitoSingle, 10_000, &tip_account).unwrap();
+        let programs: Vec<Pubkey> = tx
+            .message
+            .instructions
+            .iter()
+            .map(|ix| tx.message.account_keys[ix.program_id_index as usize])
+            .collect();
+        // Router budget (2), setup, swap, tip; Jupiter's own budget instruction dropped
+        assert_eq!(programs.len(), 5);
+        assert_eq!(&programs[..2], &[solana_sdk::compute_budget::id(); 2]);
+        assert_eq!(programs[4], solana_sdk::system_program::id());
+        assert!(tx.message.account_keys.contains(&marker));
+        assert!(tx.message.account_keys.contains(&tip_account));
+        assert_eq!(tx.message.account_keys[0], user.pubkey());
+        assert_eq!(tx.message.recent_blockhash, intent.consent_block.recent_blockhash);
+
+        // No tip off the block engine
+        let rpc = request.protected_transaction(&intent, &RouteType::StandardRpc, 10_000, &tip_account).unwrap();
+        assert!(!rpc.message.account_keys.contains(&tip_account));
+        assert_eq!(rpc.message.instructions.len(), 4);
+    }
+
+    #[test]
+    fn test_guarded_transaction_brackets_swap() {
+        let request = request(Pubkey::new_unique());
+        let intent = request.intent(Hash::new_unique());
+        let guard = ConsentGuard::new(Pubkey::new_unique());
+
+        let tx = request
+            .guarded_transaction(&intent, &RouteType::JitoSingle, 10_000, &Pubkey::new_unique(), &guard)
+            .unwrap();
+        let programs: Vec<Pubkey> = tx
+            .message
+            .instructions
+            .iter()
+            .map(|ix| tx.message.account_keys[ix.program_id_index as usize])
+            .collect();
+        // Budget (2), open, setup, swap, tip, close
+        assert_eq!(programs.len(), 7);
+        assert_eq!((programs[2], programs[6]), (guard.program_id(), guard.program_id()));
+
+        let terms = GuardTerms::for_intent(&intent, request.output_token_account).unwrap();
+        assert_eq!(terms.minimum_received, request.minimum_received());
+        guard.check_message(&tx.message, &terms).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_lookup_tables_and_bad_keys() {
+        let mut request = request(Pubkey::new_unique());
+        request.swap_instructions.swap_instruction.program_id = "nope".to_string();
+        assert!(request.protected_instructions(&RouteType::JitoSingle, 0, &Pubkey::new_unique()).is_err());
+
+        let mut request = self::request(Pubkey::new_unique());
+        request.swap_instructions.address_lookup_table_addresses = vec![Pubkey::new_unique().to_string()];
+        let err = request.protected_instructions(&RouteType::JitoSingle, 0, &Pubkey::new_unique()).unwrap_err();
+        assert!(err.to_string().contains("asLegacyTransaction"));
+    }
+}
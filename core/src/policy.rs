@@ -0,0 +1,354 @@
+//! Deployment Allow/Deny Policy
+//!
+//! Per-deployment lists of token mints and destination programs, enforced when
+//! an intent is validated (before quoting or routing). Rules, in order:
+//! - `denied_mint` / `denied_program`: explicitly blocked
+//! - `mint_not_allowed` / `program_not_allowed`: an allow list is configured
+//!   and does not contain the key
+//! - `new_mint_liquidity`: the mint is younger than `new_mint_age_secs` and its
+//!   observed liquidity is below `min_new_mint_liquidity_usd`
+//!
+//! Destination programs are the DEX program each swap is routed to and the
+//! vault program of each deposit leg. Mint age and liquidity come from
+//! [`IntentPolicy::observe_mint`] (fed by ingestion); mints never observed are
+//! not subject to the liquidity floor.
+//!
+//! Violations surface as `SentinelError::PolicyRejected` carrying the rule id
+//! and the offending key, so wallets can tell users exactly what was blocked.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{RwLock, RwLockReadGuard};
+use tracing::{info, warn};
+
+use crate::best_execution::DexVenue;
+use crate::config::{PolicySettings, SentinelConfig};
+use crate::intent::{Intent, IntentLeg, SwapDetails};
+use crate::{Result, SentinelError};
+
+/// Policy rule that rejected an intent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRule {
+    DeniedMint,
+    MintNotAllowed,
+    DeniedProgram,
+    ProgramNotAllowed,
+    NewMintLiquidity,
+}
+
+impl PolicyRule {
+    /// Stable identifier reported to wallets
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyRule::DeniedMint => "denied_mint",
+            PolicyRule::MintNotAllowed => "mint_not_allowed",
+            PolicyRule::DeniedProgram => "denied_program",
+            PolicyRule::ProgramNotAllowed => "program_not_allowed",
+            PolicyRule::NewMintLiquidity => "new_mint_liquidity",
+        }
+    }
+}
+
+impl fmt::Display for PolicyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Mint facts the new-mint rule is evaluated against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MintInfo {
+    /// Mint creation time (unix seconds)
+    pub created_at: i64,
+
+    /// Pool liquidity across known venues (USD)
+    pub liquidity_usd: f64,
+}
+
+/// Parsed policy lists
+#[derive(Debug, Default)]
+struct PolicyLists {
+    allowed_mints: HashSet<Pubkey>,
+    denied_mints: HashSet<Pubkey>,
+    allowed_programs: HashSet<Pubkey>,
+    denied_programs: HashSet<Pubkey>,
+    new_mint_age_secs: u64,
+    min_new_mint_liquidity_usd: f64,
+}
+
+impl PolicyLists {
+    fn from_settings(settings: &PolicySettings) -> Self {
+        // Config validation rejects invalid keys; anything left over is skipped
+        let parse = |keys: &[String]| keys.iter().filter_map(|k| Pubkey::from_str(k).ok()).collect();
+        Self {
+            allowed_mints: parse(&settings.allowed_mints),
+            denied_mints: parse(&settings.denied_mints),
+            allowed_programs: parse(&settings.allowed_programs),
+            denied_programs: parse(&settings.denied_programs),
+            new_mint_age_secs: settings.new_mint_age_secs,
+            min_new_mint_liquidity_usd: settings.min_new_mint_liquidity_usd,
+        }
+    }
+}
+
+/// Enforces the `[policy]` section against intents
+pub struct IntentPolicy {
+    lists: RwLock<PolicyLists>,
+    mints: RwLock<HashMap<Pubkey, MintInfo>>,
+}
+
+impl IntentPolicy {
+    pub fn new(settings: PolicySettings) -> Self {
+        Self {
+            lists: RwLock::new(PolicyLists::from_settings(&settings)),
+            mints: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.policy.clone())
+    }
+
+    /// Apply the hot-reloadable `[policy]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        let lists = PolicyLists::from_settings(&config.policy);
+        info!(
+            "Policy updated: {} allowed / {} denied mints, {} allowed / {} denied programs",
+            lists.allowed_mints.len(),
+            lists.denied_mints.len(),
+            lists.allowed_programs.len(),
+            lists.denied_programs.len()
+        );
+        *self.lists.write().unwrap_or_else(|p| p.into_inner()) = lists;
+    }
+
+    /// Record creation time and liquidity of a mint
+    pub fn observe_mint(&self, mint: Pubkey, info: MintInfo) {
+        self.mints.write().unwrap_or_else(|p| p.into_inner()).insert(mint, info);
+    }
+
+    fn lists(&self) -> RwLockReadGuard<'_, PolicyLists> {
+        self.lists.read().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Check every mint and destination program an intent touches
+    pub fn check_intent(&self, intent: &Intent, now: i64) -> Result<()> {
+        let swaps = intent.swap_details.iter().chain(intent.legs.iter().filter_map(|leg| match leg {
+            IntentLeg::Swap(swap) => Some(swap),
+            IntentLeg::Deposit(_) => None,
+        }));
+        for swap in swaps {
+            self.check_swap(swap, now)?;
+        }
+
+        for leg in &intent.legs {
+            if let IntentLeg::Deposit(deposit) = leg {
+                self.check_mint(&deposit.mint, now)?;
+                self.check_program(&deposit.program_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_swap(&self, swap: &SwapDetails, now: i64) -> Result<()> {
+        self.check_mint(&swap.input_mint, now)?;
+        self.check_mint(&swap.output_mint, now)?;
+        self.check_program(&DexVenue::for_swap(swap).program_id())
+    }
+
+    /// Check the program a built instruction invokes
+    pub fn check_instruction(&self, instruction: &Instruction) -> Result<()> {
+        self.check_program(&instruction.program_id)
+    }
+
+    pub fn check_mint(&self, mint: &Pubkey, now: i64) -> Result<()> {
+        let lists = self.lists();
+        if lists.denied_mints.contains(mint) {
+            return Err(rejection(PolicyRule::DeniedMint, mint, "mint is on the deny list".to_string()));
+        }
+        if !lists.allowed_mints.is_empty() && !lists.allowed_mints.contains(mint) {
+            return Err(rejection(PolicyRule::MintNotAllowed, mint, "mint is not on the allow list".to_string()));
+        }
+
+        // Explicitly allowed mints are trusted regardless of age
+        if lists.new_mint_age_secs == 0 || lists.allowed_mints.contains(mint) {
+            return Ok(());
+        }
+        let Some(info) = self.mints.read().unwrap_or_else(|p| p.into_inner()).get(mint).copied() else {
+            return Ok(());
+        };
+
+        let age_secs = now.saturating_sub(info.created_at);
+        if age_secs < lists.new_mint_age_secs as i64 && info.liquidity_usd < lists.min_new_mint_liquidity_usd {
+            return Err(rejection(
+                PolicyRule::NewMintLiquidity,
+                mint,
+                format!(
+                    "mint is {}s old with ${:.0} liquidity (floor ${:.0} for mints younger than {}s)",
+                    age_secs.max(0),
+                    info.liquidity_usd,
+                    lists.min_new_mint_liquidity_usd,
+                    lists.new_mint_age_secs
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn check_program(&self, program: &Pubkey) -> Result<()> {
+        let lists = self.lists();
+        if lists.denied_programs.contains(program) {
+            return Err(rejection(
+                PolicyRule::DeniedProgram,
+                program,
+                "program is on the deny list".to_string(),
+            ));
+        }
+        if !lists.allowed_programs.is_empty() && !lists.allowed_programs.contains(program) {
+            return Err(rejection(
+                PolicyRule::ProgramNotAllowed,
+                program,
+                "program is not on the allow list".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn rejection(rule: PolicyRule, subject: &Pubkey, reason: String) -> SentinelError {
+    warn!("Policy rule {} rejected {}: {}", rule, subject, reason);
+    SentinelError::PolicyRejected {
+        rule: rule.to_string(),
+        subject: subject.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::{DepositDetails, SwapMode};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn swap(input_mint: Pubkey, output_mint: Pubkey) -> SwapDetails {
+        SwapDetails {
+            mode: SwapMode::ExactIn,
+            input_mint,
+            output_mint,
+            amount: 1_000_000,
+            minimum_received: None,
+            dex: None,
+            route_hints: None,
+        }
+    }
+
+    fn intent(swap_details: SwapDetails) -> Intent {
+        Intent {
+            intent_id: "intent-1".to_string(),
+            swap_details: Some(swap_details),
+            ..Intent::test_swap()
+        }
+    }
+
+    fn rule_of(result: Result<()>) -> (String, String) {
+        match result {
+            Err(SentinelError::PolicyRejected { rule, subject, .. }) => (rule, subject),
+            other => panic!("expected PolicyRejected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_deny_list_takes_precedence() {
+        let scam = Pubkey::new_unique();
+        let policy = IntentPolicy::new(PolicySettings {
+            allowed_mints: vec![scam.to_string()],
+            denied_mints: vec![scam.to_string()],
+            ..PolicySettings::default()
+        });
+
+        let (rule, subject) = rule_of(policy.check_mint(&scam, NOW));
+        assert_eq!(rule, "denied_mint");
+        assert_eq!(subject, scam.to_string());
+
+        let other = Pubkey::new_unique();
+        let (rule, subject) = rule_of(policy.check_intent(&intent(swap(other, scam)), NOW));
+        assert_eq!(rule, "mint_not_allowed");
+        assert_eq!(subject, other.to_string());
+    }
+
+    #[test]
+    fn test_destination_programs() {
+        let jupiter = DexVenue::Jupiter.program_id();
+        let policy = IntentPolicy::new(PolicySettings {
+            allowed_programs: vec![jupiter.to_string()],
+            ..PolicySettings::default()
+        });
+
+        let mut swap_details = swap(Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(policy.check_intent(&intent(swap_details.clone()), NOW).is_ok());
+
+        swap_details.dex = Some("Orca".to_string());
+        let (rule, subject) = rule_of(policy.check_intent(&intent(swap_details.clone()), NOW));
+        assert_eq!(rule, "program_not_allowed");
+        assert_eq!(subject, DexVenue::Orca.program_id().to_string());
+
+        // Deposit legs route to their vault program
+        let vault_program = Pubkey::new_unique();
+        let mut bundle = intent(swap(Pubkey::new_unique(), Pubkey::new_unique()));
+        bundle.legs = vec![IntentLeg::Deposit(DepositDetails {
+            program_id: vault_program,
+            vault: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            amount: None,
+        })];
+        let (_, subject) = rule_of(policy.check_intent(&bundle, NOW));
+        assert_eq!(subject, vault_program.to_string());
+    }
+
+    #[test]
+    fn test_new_mint_liquidity_floor() {
+        let fresh = Pubkey::new_unique();
+        let policy = IntentPolicy::new(PolicySettings {
+            new_mint_age_secs: 86_400,
+            min_new_mint_liquidity_usd: 50_000.0,
+            ..PolicySettings::default()
+        });
+
+        // Unobserved mints are not subject to the floor
+        assert!(policy.check_mint(&fresh, NOW).is_ok());
+
+        policy.observe_mint(
+            fresh,
+            MintInfo {
+                created_at: NOW - 600,
+                liquidity_usd: 1_000.0,
+            },
+        );
+        let (rule, _) = rule_of(policy.check_mint(&fresh, NOW));
+        assert_eq!(rule, "new_mint_liquidity");
+
+        // Old enough
+        assert!(policy.check_mint(&fresh, NOW + 86_400).is_ok());
+    }
+
+    #[test]
+    fn test_apply_config_replaces_lists() {
+        let program = Pubkey::new_unique();
+        let policy = IntentPolicy::new(PolicySettings::default());
+        assert!(policy.check_program(&program).is_ok());
+
+        let mut config = SentinelConfig::default();
+        config.policy.denied_programs = vec![program.to_string()];
+        policy.apply_config(&config);
+
+        let ix = Instruction::new_with_bytes(program, &[], vec![]);
+        let (rule, _) = rule_of(policy.check_instruction(&ix));
+        assert_eq!(rule, "denied_program");
+    }
+}
@@ -0,0 +1,335 @@
+//! Pre-Trade Simulation
+//!
+//! Building blocks for the `preflight` API, which answers "what would happen
+//! if I sent this intent now?" without committing to execution:
+//! - [`candidate_transaction`]: unsigned compute-budget + swap transaction for
+//!   the intent, as the router would build it ([`candidate_v0_transaction`]
+//!   when the route uses lookup tables, resolved through the shared `AltCache`)
+//! - [`TransactionSimulator`]: `simulateTransaction` against current bank
+//!   state (signature verification off, blockhash replaced)
+//! - [`PreflightReport`]: projected fill, simulation result, risk score,
+//!   token risk, slippage and route recommendations returned to the caller
+//!
+//! Scoring lives in `ai_engine::PreflightChecker`, which ties these together
+//! with feature extraction and the inference pool.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::time::Duration;
+
+use crate::alt_cache::AltCache;
+use crate::intent::{Intent, ProtectionTier};
+use crate::latency::PipelineStage;
+use crate::screening::ScreeningReport;
+use crate::slippage::SlippageRecommendation;
+use crate::token_risk::PairTokenRisk;
+use crate::types::RouteType;
+use crate::{Result, SentinelError};
+
+/// Compute unit limit used when simulating (the simulation reports actual usage)
+pub const SIMULATION_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Unsigned candidate transaction: compute budget, then the swap instruction
+///
+/// The priority fee is the intent's cap spread over `compute_unit_limit`, so
+/// the simulated fee matches the worst case the user consented to.
+pub fn candidate_transaction(intent: &Intent, swap_instruction: Instruction, compute_unit_limit: u32) -> Transaction {
+    let instructions = candidate_instructions(intent, swap_instruction, compute_unit_limit);
+    let mut transaction = Transaction::new_unsigned(Message::new(&instructions, Some(&intent.user_public_key)));
+    transaction.message.recent_blockhash = intent.consent_block.recent_blockhash;
+    transaction
+}
+
+/// [`candidate_transaction`] as v0, loading accounts from `lookup_tables`
+///
+/// Aggregator routes name their lookup tables; compiling against them keeps
+/// multi-hop swaps under the transaction size limit.
+pub async fn candidate_v0_transaction(
+    intent: &Intent,
+    swap_instruction: Instruction,
+    compute_unit_limit: u32,
+    lookup_tables: &[Pubkey],
+    alt_cache: &AltCache,
+) -> Result<VersionedTransaction> {
+    let instructions = candidate_instructions(intent, swap_instruction, compute_unit_limit);
+    let message = alt_cache
+        .compile_v0(
+            &intent.user_public_key,
+            &instructions,
+            lookup_tables,
+            intent.consent_block.recent_blockhash,
+        )
+        .await?;
+    Ok(VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    })
+}
+
+fn candidate_instructions(intent: &Intent, swap_instruction: Instruction, compute_unit_limit: u32) -> [Instruction; 3] {
+    let [limit, price] = compute_budget_instructions(intent, compute_unit_limit);
+    [limit, price, swap_instruction]
+}
+
+/// Compute unit limit and price, the intent's priority fee cap spread over the limit
+pub(crate) fn compute_budget_instructions(intent: &Intent, compute_unit_limit: u32) -> [Instruction; 2] {
+    let micro_lamports_per_cu =
+        intent.fee_preferences.max_priority_fee_lamports * 1_000_000 / compute_unit_limit.max(1) as u64;
+
+    [
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(micro_lamports_per_cu),
+    ]
+}
+
+/// Result of `simulateTransaction`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationOutcome {
+    /// Slot of the bank the simulation ran against
+    pub slot: u64,
+
+    /// Transaction error, if the simulation failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units_consumed: Option<u64>,
+
+    #[serde(default)]
+    pub logs: Vec<String>,
+}
+
+impl SimulationOutcome {
+    /// Parse a `simulateTransaction` result (`{ context, value }`)
+    pub fn from_rpc_json(result: &serde_json::Value) -> Result<Self> {
+        let slot = result["context"]["slot"]
+            .as_u64()
+            .ok_or_else(|| SentinelError::ParseError("Simulation result without context slot".to_string()))?;
+        let value = &result["value"];
+
+        Ok(Self {
+            slot,
+            error: value.get("err").filter(|e| !e.is_null()).map(|e| e.to_string()),
+            units_consumed: value["unitsConsumed"].as_u64(),
+            logs: value["logs"]
+                .as_array()
+                .map(|logs| logs.iter().filter_map(|l| l.as_str().map(str::to_string)).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Runs `simulateTransaction` over JSON-RPC
+pub struct TransactionSimulator {
+    http_client: Client,
+    rpc_url: String,
+}
+
+impl TransactionSimulator {
+    pub fn new(rpc_url: impl Into<String>) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+        })
+    }
+
+    /// Simulate an unsigned transaction against the latest bank
+    pub async fn simulate(&self, transaction: &Transaction) -> Result<SimulationOutcome> {
+        let bytes = bincode::serialize(transaction)
+            .map_err(|e| SentinelError::SerializationError(format!("Failed to serialize transaction: {}", e)))?;
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "simulateTransaction",
+            "params": [
+                BASE64.encode(bytes),
+                {
+                    "encoding": "base64",
+                    "sigVerify": false,
+                    "replaceRecentBlockhash": true,
+                    "commitment": "processed",
+                }
+            ],
+        });
+
+        let response: serde_json::Value = self
+            .http_client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("simulateTransaction failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("Invalid simulateTransaction response: {}", e)))?;
+
+        if let Some(message) = response["error"]["message"].as_str() {
+            return Err(SentinelError::RpcError(message.to_string()));
+        }
+        SimulationOutcome::from_rpc_json(&response["result"])
+    }
+}
+
+/// Projected execution of an intent, returned by the `preflight` API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub intent_id: String,
+
+    /// Quoted amounts the candidate transaction was built from
+    pub projected_in_amount: u64,
+    pub projected_out_amount: u64,
+
+    /// Worst-case output at the intent's slippage tolerance
+    pub minimum_out_amount: u64,
+    pub price_impact_bps: f64,
+
+    pub simulation: SimulationOutcome,
+
+    /// MEV risk score of the simulated transaction (0.0-1.0)
+    pub risk_score: f32,
+
+    /// Engine confidence in `risk_score` (0.0-1.0), when reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_confidence: Option<f32>,
+
+    /// Recommended submission route (None when the simulation failed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<RouteType>,
+
+    /// Honeypot / rug-risk screening of the swap's mints, when a screener is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_risk: Option<PairTokenRisk>,
+
+    /// Tightest viable slippage (see `SlippageAdvisor`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slippage: Option<SlippageRecommendation>,
+
+    /// Chance the recommended route lands (see `LandingPredictor`), when a predictor is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub landing_probability: Option<f64>,
+
+    /// Stages answered in degraded form because the caller's deadline could not cover them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<PipelineStage>,
+
+    /// Tier the route, tip and checks were chosen for
+    #[serde(default)]
+    pub protection_tier: ProtectionTier,
+
+    /// Tier's tip percentile for this risk, within the user's tip cap (tipping routes with tip history only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended_tip_lamports: Option<u64>,
+
+    /// Checks of the tier's `SimulationStrictness` that withheld the route, beyond the simulation itself
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strictness_failures: Vec<String>,
+
+    /// Sanctioned-address screening of the user and output destinations, when a screener is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screening: Option<ScreeningReport>,
+}
+
+impl PreflightReport {
+    /// Whether the intent would execute if sent now
+    pub fn would_execute(&self) -> bool {
+        self.simulation.succeeded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::FeePreferences;
+    use serde_json::json;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn intent() -> Intent {
+        Intent {
+            intent_id: "intent-1".to_string(),
+            swap_details: None,
+            fee_preferences: FeePreferences {
+                max_priority_fee_lamports: 14_000,
+                ..FeePreferences::default()
+            },
+            ..Intent::test_swap()
+        }
+    }
+
+    #[test]
+    fn test_candidate_transaction_layout() {
+        let intent = intent();
+        let swap = Instruction::new_with_bytes(Pubkey::new_unique(), &[1, 2, 3], vec![]);
+        let tx = candidate_transaction(&intent, swap, SIMULATION_COMPUTE_UNIT_LIMIT);
+
+        assert_eq!(tx.message.instructions.len(), 3);
+        assert_eq!(tx.message.account_keys[0], intent.user_public_key);
+        assert_eq!(tx.message.recent_blockhash, intent.consent_block.recent_blockhash);
+        // 14_000 lamports over 1.4M CU = 10_000 micro-lamports per CU
+        assert_eq!(
+            tx.message.instructions[1].data,
+            ComputeBudgetInstruction::set_compute_unit_price(10_000).data
+        );
+    }
+
+    #[tokio::test]
+    async fn test_candidate_v0_transaction_uses_lookup_tables() {
+        let intent = intent();
+        let (pool, table) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let alt_cache = AltCache::new("http://127.0.0.1:9").unwrap();
+        alt_cache.insert(table, vec![pool]);
+
+        let swap = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![solana_sdk::instruction::AccountMeta::new(pool, false)],
+        );
+        let tx = candidate_v0_transaction(&intent, swap, SIMULATION_COMPUTE_UNIT_LIMIT, &[table], &alt_cache)
+            .await
+            .unwrap();
+
+        assert_eq!(tx.signatures.len(), 1);
+        assert_eq!(tx.message.static_account_keys()[0], intent.user_public_key);
+        assert_eq!(tx.message.instructions().len(), 3);
+        assert_eq!(tx.message.address_table_lookups().unwrap()[0].writable_indexes, vec![0]);
+    }
+
+    #[test]
+    fn test_simulation_outcome_parsing() {
+        let ok = SimulationOutcome::from_rpc_json(&json!({
+            "context": { "slot": 300 },
+            "value": { "err": null, "unitsConsumed": 85_000, "logs": ["Program log: swap"] }
+        }))
+        .unwrap();
+        assert!(ok.succeeded());
+        assert_eq!(ok.units_consumed, Some(85_000));
+        assert_eq!(ok.logs.len(), 1);
+
+        let failed = SimulationOutcome::from_rpc_json(&json!({
+            "context": { "slot": 300 },
+            "value": { "err": { "InstructionError": [2, { "Custom": 6001 }] }, "logs": null }
+        }))
+        .unwrap();
+        assert!(!failed.succeeded());
+        assert!(failed.error.unwrap().contains("6001"));
+
+        assert!(SimulationOutcome::from_rpc_json(&json!({ "value": {} })).is_err());
+    }
+}
@@ -0,0 +1,619 @@
+//! Pre-Sign Wallet Preview
+//!
+//! Wallets ask the user to sign a transaction they cannot read. A
+//! [`PreviewBuilder`] turns the built transaction and the intent behind it into
+//! a [`SignPreview`] a wallet can render before the signing prompt: tokens in
+//! and out, the worst case the transaction still lands at, every lamport of
+//! fees (base, priority and Jito tip), the route and the MEV risk with a plain
+//! explanation.
+//!
+//! Fees are read from the transaction itself (signature count, compute budget
+//! instructions, transfers to Jito tip accounts), not from the intent's caps,
+//! so the preview shows what signing actually costs. Mismatches with the
+//! intent (a different blockhash, fees above the caps) surface as `warnings`
+//! rather than errors: the wallet decides whether to block.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::collections::HashMap;
+
+use crate::intent::{Intent, SwapMode};
+use crate::network::Network;
+use crate::preflight::PreflightReport;
+use crate::types::{MevRiskScore, RouteType};
+use crate::{Result, SentinelError};
+
+/// Lamports charged per required signature
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Compute unit limit the runtime assumes per instruction without a `SetComputeUnitLimit`
+const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+const MAX_COMPUTE_UNITS: u64 = 1_400_000;
+
+/// System program `Transfer` discriminator (bincode u32)
+const SYSTEM_TRANSFER: [u8; 4] = [2, 0, 0, 0];
+
+/// Coarse risk bucket shown to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskLevel {
+    pub fn from_score(score: f32) -> Self {
+        let score = MevRiskScore::new(score);
+        if score.is_high_risk() {
+            RiskLevel::High
+        } else if score.is_medium_risk() {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            RiskLevel::Low => "Low",
+            RiskLevel::Medium => "Medium",
+            RiskLevel::High => "High",
+        }
+    }
+}
+
+/// An amount of one token, in atoms and (when decimals are known) UI units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenAmount {
+    /// Mint address (base58)
+    pub mint: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+
+    /// Smallest token units
+    pub amount: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_amount: Option<f64>,
+}
+
+/// Everything the signer pays in SOL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreviewFees {
+    /// Signature fees
+    pub base_fee_lamports: u64,
+
+    /// Compute unit limit × price
+    pub priority_fee_lamports: u64,
+
+    /// Transfers to Jito tip accounts (including a separate tip transaction)
+    pub jito_tip_lamports: u64,
+
+    pub total_lamports: u64,
+
+    /// Account paying the signature and priority fees (base58)
+    pub fee_payer: String,
+
+    /// The fee payer is not the user (a sponsor covers base and priority fees)
+    pub sponsored: bool,
+}
+
+/// MEV risk as shown to the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskPreview {
+    pub level: RiskLevel,
+
+    /// MEV risk score (0.0-1.0)
+    pub score: f32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+
+    /// One or two sentences for the signing prompt
+    pub explanation: String,
+
+    /// Signals behind the score, most significant first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signals: Vec<String>,
+}
+
+/// Human-readable summary of a transaction, rendered before the signing prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignPreview {
+    pub intent_id: String,
+
+    /// Tokens leaving the wallet (the quoted input for ExactOut)
+    pub pays: TokenAmount,
+
+    /// Tokens arriving (the quoted output for ExactIn)
+    pub receives: TokenAmount,
+
+    /// Least the transaction can land with (None when no minimum is known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worst_case_received: Option<TokenAmount>,
+
+    /// Most ExactOut can spend at the slippage tolerance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_paid: Option<TokenAmount>,
+
+    pub max_slippage_bps: u16,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<RouteType>,
+    pub route_summary: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<RiskPreview>,
+
+    pub fees: PreviewFees,
+
+    /// Programs the transaction invokes, named where known
+    pub programs: Vec<String>,
+
+    /// Accounts that must sign (base58)
+    pub signers: Vec<String>,
+
+    /// Things the wallet should call out before signing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Builds a [`SignPreview`] from a built transaction and its intent
+pub struct PreviewBuilder<'a> {
+    intent: &'a Intent,
+    transaction: &'a Transaction,
+    tip_transaction: Option<&'a Transaction>,
+    quoted_in: Option<u64>,
+    quoted_out: Option<u64>,
+    minimum_out: Option<u64>,
+    route: Option<RouteType>,
+    risk: Option<(MevRiskScore, Vec<String>)>,
+    tokens: HashMap<Pubkey, (String, u8)>,
+}
+
+impl<'a> PreviewBuilder<'a> {
+    pub fn new(intent: &'a Intent, transaction: &'a Transaction) -> Self {
+        Self {
+            intent,
+            transaction,
+            tip_transaction: None,
+            quoted_in: None,
+            quoted_out: None,
+            minimum_out: None,
+            route: None,
+            risk: None,
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Quoted amounts the transaction was built from
+    pub fn with_quote(mut self, in_amount: u64, out_amount: u64) -> Self {
+        self.quoted_in = Some(in_amount);
+        self.quoted_out = Some(out_amount);
+        self
+    }
+
+    /// Minimum output the swap instruction enforces
+    pub fn with_minimum_out(mut self, minimum_out: u64) -> Self {
+        self.minimum_out = Some(minimum_out);
+        self
+    }
+
+    pub fn with_route(mut self, route: RouteType) -> Self {
+        self.route = Some(route);
+        self
+    }
+
+    pub fn with_risk(mut self, score: MevRiskScore, signals: Vec<String>) -> Self {
+        self.risk = Some((score, signals));
+        self
+    }
+
+    /// Quote, minimum, route and risk from a preflight of the same transaction
+    pub fn with_preflight(mut self, report: &PreflightReport) -> Self {
+        self.quoted_in = Some(report.projected_in_amount);
+        self.quoted_out = Some(report.projected_out_amount);
+        self.minimum_out = Some(report.minimum_out_amount);
+        if let Some(route) = report.route.clone() {
+            self.route = Some(route);
+        }
+        let mut score = MevRiskScore::new(report.risk_score);
+        if let Some(confidence) = report.risk_confidence {
+            score = score.with_confidence(confidence);
+        }
+        let signals = self.risk.take().map(|(_, signals)| signals).unwrap_or_default();
+        self.risk = Some((score, signals));
+        self
+    }
+
+    /// Symbol and decimals for rendering a mint
+    pub fn with_token(mut self, mint: Pubkey, symbol: impl Into<String>, decimals: u8) -> Self {
+        self.tokens.insert(mint, (symbol.into(), decimals));
+        self
+    }
+
+    /// Separate tip transaction bundled with the swap (its fees count too)
+    pub fn with_tip_transaction(mut self, tip_transaction: &'a Transaction) -> Self {
+        self.tip_transaction = Some(tip_transaction);
+        self
+    }
+
+    pub fn build(self) -> Result<SignPreview> {
+        let intent = self.intent;
+        let details = intent
+            .swap_details
+            .as_ref()
+            .ok_or_else(|| SentinelError::InvalidIntent("Preview needs swap details".to_string()))?;
+        let message = &self.transaction.message;
+        let required_signatures = message.header.num_required_signatures as usize;
+        let signer_keys = &message.account_keys[..required_signatures.min(message.account_keys.len())];
+        if !signer_keys.contains(&intent.user_public_key) {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Intent {} user {} does not sign the previewed transaction",
+                intent.intent_id, intent.user_public_key
+            )));
+        }
+
+        let slippage_bps = intent.constraints.max_slippage_bps;
+        let (pays, receives, worst_case_received, max_paid) = match details.mode {
+            SwapMode::ExactIn => {
+                let quoted_out = self.quoted_out.or(details.minimum_received).unwrap_or(0);
+                let quote_minimum = self.quoted_out.map(|out| apply_slippage_down(out, slippage_bps));
+                let minimum = self
+                    .minimum_out
+                    .or_else(|| quote_minimum.max(details.minimum_received));
+                (
+                    self.token(&details.input_mint, details.amount),
+                    self.token(&details.output_mint, quoted_out),
+                    minimum.map(|minimum| self.token(&details.output_mint, minimum)),
+                    None,
+                )
+            }
+            SwapMode::ExactOut => {
+                let quoted_in = self.quoted_in.unwrap_or(0);
+                (
+                    self.token(&details.input_mint, quoted_in),
+                    self.token(&details.output_mint, details.amount),
+                    Some(self.token(&details.output_mint, details.amount)),
+                    self.quoted_in
+                        .map(|quoted| self.token(&details.input_mint, apply_slippage_up(quoted, slippage_bps))),
+                )
+            }
+        };
+
+        let fees = self.fees();
+        let mut warnings = Vec::new();
+        let consent_hash = intent.consent_block.nonce.clone().unwrap_or_else(|| {
+            intent.consent_block.recent_blockhash.to_string()
+        });
+        if message.recent_blockhash.to_string() != consent_hash {
+            warnings.push("Transaction blockhash differs from the one the intent consented to".to_string());
+        }
+        if fees.priority_fee_lamports > intent.fee_preferences.max_priority_fee_lamports {
+            warnings.push(format!(
+                "Priority fee {} lamports exceeds the intent cap of {}",
+                fees.priority_fee_lamports, intent.fee_preferences.max_priority_fee_lamports
+            ));
+        }
+        if fees.jito_tip_lamports > intent.fee_preferences.max_jito_tip_lamports {
+            warnings.push(format!(
+                "Jito tip {} lamports exceeds the intent cap of {}",
+                fees.jito_tip_lamports, intent.fee_preferences.max_jito_tip_lamports
+            ));
+        }
+        if worst_case_received.is_none() {
+            warnings.push("No minimum output is known: the swap may land at any price".to_string());
+        }
+
+        let risk = self.risk.as_ref().map(|(score, signals)| {
+            let level = RiskLevel::from_score(score.score());
+            if level == RiskLevel::High && self.route == Some(RouteType::StandardRpc) {
+                warnings.push("High MEV risk on a public route: consider waiting or raising protection".to_string());
+            }
+            RiskPreview {
+                level,
+                score: score.score(),
+                confidence: score.confidence(),
+                explanation: explain_risk(level, score, signals, self.route.as_ref()),
+                signals: signals.clone(),
+            }
+        });
+
+        Ok(SignPreview {
+            intent_id: intent.intent_id.clone(),
+            pays,
+            receives,
+            worst_case_received,
+            max_paid,
+            max_slippage_bps: slippage_bps,
+            route_summary: route_summary(self.route.as_ref()).to_string(),
+            route: self.route.clone(),
+            risk,
+            fees,
+            programs: self.programs(),
+            signers: signer_keys.iter().map(|key| key.to_string()).collect(),
+            warnings,
+        })
+    }
+
+    fn token(&self, mint: &Pubkey, amount: u64) -> TokenAmount {
+        let known = self.tokens.get(mint);
+        TokenAmount {
+            mint: mint.to_string(),
+            symbol: known.map(|(symbol, _)| symbol.clone()),
+            amount,
+            decimals: known.map(|(_, decimals)| *decimals),
+            ui_amount: known.map(|(_, decimals)| amount as f64 / 10f64.powi(*decimals as i32)),
+        }
+    }
+
+    fn fees(&self) -> PreviewFees {
+        let transactions = std::iter::once(self.transaction).chain(self.tip_transaction);
+        let mut fees = PreviewFees {
+            base_fee_lamports: 0,
+            priority_fee_lamports: 0,
+            jito_tip_lamports: 0,
+            total_lamports: 0,
+            fee_payer: String::new(),
+            sponsored: false,
+        };
+        for transaction in transactions {
+            let message = &transaction.message;
+            fees.base_fee_lamports += message.header.num_required_signatures as u64 * LAMPORTS_PER_SIGNATURE;
+            fees.priority_fee_lamports += priority_fee(transaction);
+            fees.jito_tip_lamports += tip_lamports(transaction);
+        }
+        fees.total_lamports = fees.base_fee_lamports + fees.priority_fee_lamports + fees.jito_tip_lamports;
+
+        let fee_payer = self.transaction.message.account_keys.first().copied().unwrap_or_default();
+        fees.fee_payer = fee_payer.to_string();
+        fees.sponsored = fee_payer != self.intent.user_public_key;
+        fees
+    }
+
+    /// Invoked programs in instruction order, deduplicated
+    fn programs(&self) -> Vec<String> {
+        let mut known: HashMap<Pubkey, &str> = Network::ALL
+            .iter()
+            .flat_map(|network| network.known_programs())
+            .map(|(label, id)| (id, label))
+            .collect();
+        known.insert(solana_sdk::system_program::id(), "System Program");
+        known.insert(solana_sdk::compute_budget::id(), "Compute Budget");
+        known.insert(crate::whirlpool::token_program_id(), "SPL Token");
+
+        let message = &self.transaction.message;
+        let mut programs: Vec<String> = Vec::new();
+        for instruction in &message.instructions {
+            let Some(program_id) = message.account_keys.get(instruction.program_id_index as usize) else {
+                continue;
+            };
+            let name = known.get(program_id).map_or_else(|| program_id.to_string(), |label| label.to_string());
+            if !programs.contains(&name) {
+                programs.push(name);
+            }
+        }
+        programs
+    }
+}
+
+/// Compute unit limit × price from the compute budget instructions
+fn priority_fee(transaction: &Transaction) -> u64 {
+    let message = &transaction.message;
+    let compute_budget = solana_sdk::compute_budget::id();
+    let mut limit = None;
+    let mut micro_lamports_per_cu = 0u64;
+    let mut other_instructions = 0u64;
+    for instruction in &message.instructions {
+        if message.account_keys.get(instruction.program_id_index as usize) != Some(&compute_budget) {
+            other_instructions += 1;
+            continue;
+        }
+        match instruction.data.split_first() {
+            Some((2, rest)) if rest.len() >= 4 => {
+                limit = Some(u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as u64);
+            }
+            Some((3, rest)) if rest.len() >= 8 => {
+                let mut price = [0u8; 8];
+                price.copy_from_slice(&rest[..8]);
+                micro_lamports_per_cu = u64::from_le_bytes(price);
+            }
+            _ => {}
+        }
+    }
+    let limit = limit
+        .unwrap_or(other_instructions * DEFAULT_INSTRUCTION_COMPUTE_UNITS)
+        .min(MAX_COMPUTE_UNITS);
+    // Rounded up, as the runtime charges
+    ((limit as u128 * micro_lamports_per_cu as u128).div_ceil(1_000_000)) as u64
+}
+
+/// System transfers to Jito tip accounts
+fn tip_lamports(transaction: &Transaction) -> u64 {
+    let message = &transaction.message;
+    let system_program = solana_sdk::system_program::id();
+    message
+        .instructions
+        .iter()
+        .filter(|instruction| message.account_keys.get(instruction.program_id_index as usize) == Some(&system_program))
+        .filter(|instruction| instruction.data.len() == 12 && instruction.data[..4] == SYSTEM_TRANSFER)
+        .filter(|instruction| {
+            instruction
+                .accounts
+                .get(1)
+                .and_then(|index| message.account_keys.get(*index as usize))
+                .is_some_and(Network::is_any_tip_account)
+        })
+        .map(|instruction| {
+            let mut lamports = [0u8; 8];
+            lamports.copy_from_slice(&instruction.data[4..12]);
+            u64::from_le_bytes(lamports)
+        })
+        .sum()
+}
+
+fn apply_slippage_down(amount: u64, slippage_bps: u16) -> u64 {
+    (amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64
+}
+
+fn apply_slippage_up(amount: u64, slippage_bps: u16) -> u64 {
+    (amount as u128 * (10_000 + slippage_bps as u128)).div_ceil(10_000) as u64
+}
+
+fn route_summary(route: Option<&RouteType>) -> &'static str {
+    match route {
+        Some(RouteType::JitoBundle) => {
+            "Private Jito bundle: lands atomically with its tip, never visible in the public mempool"
+        }
+        Some(RouteType::JitoSingle) => "Sent privately through the Jito block engine",
+        Some(RouteType::Firedancer) => "Sent directly to the upcoming Firedancer leaders",
+        Some(RouteType::StandardRpc) => "Public RPC broadcast: visible to searchers before it lands",
+        None => "Route not yet decided",
+    }
+}
+
+fn explain_risk(level: RiskLevel, score: &MevRiskScore, signals: &[String], route: Option<&RouteType>) -> String {
+    let mut explanation = format!("{} MEV risk (score {:.2}", level.label(), score.score());
+    if let Some(confidence) = score.confidence() {
+        explanation.push_str(&format!(", confidence {:.0}%", confidence * 100.0));
+    }
+    explanation.push(')');
+    if signals.is_empty() {
+        explanation.push('.');
+    } else {
+        explanation.push_str(&format!(": {}.", signals.join(", ")));
+    }
+
+    let protection = match (level, route) {
+        (_, Some(RouteType::JitoBundle)) => " The private bundle keeps it out of reach of sandwich bots.",
+        (_, Some(RouteType::JitoSingle)) => " Private submission hides it from the public mempool.",
+        (RiskLevel::Low, _) => "",
+        (_, Some(RouteType::StandardRpc)) => " It goes out publicly, so searchers can see it before it lands.",
+        _ => " Tight slippage limits what a sandwich can take.",
+    };
+    explanation.push_str(protection);
+    explanation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::Constraints;
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+
+    fn intent(mode: SwapMode) -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            constraints: Constraints {
+                max_slippage_bps: 50,
+                ..Constraints::default()
+            },
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.mode = mode;
+        swap.amount = 1_000_000_000;
+        swap.minimum_received = None;
+        intent
+    }
+
+    fn transaction(intent: &Intent, payer: &Pubkey, tip: u64) -> Transaction {
+        let user = intent.user_public_key;
+        let swap = Instruction::new_with_bytes(
+            crate::whirlpool::program_id(),
+            &[1, 2, 3],
+            vec![AccountMeta::new(user, true)],
+        );
+        #[allow(deprecated)]
+        let instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            ComputeBudgetInstruction::set_compute_unit_price(10_000),
+            swap,
+            solana_sdk::system_instruction::transfer(&user, &Network::Mainnet.tip_account(0), tip),
+        ];
+        let message = Message::new_with_blockhash(&instructions, Some(payer), &intent.consent_block.recent_blockhash);
+        Transaction::new_unsigned(message)
+    }
+
+    #[test]
+    fn test_exact_in_preview_reads_fees_from_transaction() {
+        let intent = intent(SwapMode::ExactIn);
+        let details = intent.swap_details.clone().unwrap();
+        let tx = transaction(&intent, &intent.user_public_key, 40_000);
+
+        let preview = PreviewBuilder::new(&intent, &tx)
+            .with_quote(1_000_000_000, 5_000_000_000)
+            .with_route(RouteType::JitoBundle)
+            .with_risk(MevRiskScore::new(0.85).with_confidence(0.9), vec!["thin pool".to_string()])
+            .with_token(details.input_mint, "USDC", 6)
+            .build()
+            .unwrap();
+
+        assert_eq!(preview.pays.ui_amount, Some(1_000.0));
+        assert_eq!(preview.pays.symbol.as_deref(), Some("USDC"));
+        assert_eq!(preview.receives.amount, 5_000_000_000);
+        // 0.5% below the quote
+        assert_eq!(preview.worst_case_received.as_ref().unwrap().amount, 4_975_000_000);
+        assert!(preview.max_paid.is_none());
+
+        // One signature; 300k CU at 10k micro-lamports = 3_000 lamports
+        assert_eq!(preview.fees.base_fee_lamports, 5_000);
+        assert_eq!(preview.fees.priority_fee_lamports, 3_000);
+        assert_eq!(preview.fees.jito_tip_lamports, 40_000);
+        assert_eq!(preview.fees.total_lamports, 48_000);
+        assert!(!preview.fees.sponsored);
+
+        let risk = preview.risk.as_ref().unwrap();
+        assert_eq!(risk.level, RiskLevel::High);
+        assert!(risk.explanation.starts_with("High MEV risk (score 0.85, confidence 90%): thin pool."));
+        assert!(risk.explanation.contains("private bundle"));
+        assert_eq!(preview.programs, vec!["Compute Budget", "Orca Whirlpool", "System Program"]);
+        assert_eq!(preview.signers, vec![intent.user_public_key.to_string()]);
+        assert!(preview.warnings.is_empty(), "{:?}", preview.warnings);
+
+        let json = serde_json::to_value(&preview).unwrap();
+        assert_eq!(json["risk"]["level"], "high");
+        assert_eq!(json["route"], "JitoBundle");
+    }
+
+    #[test]
+    fn test_exact_out_sponsored_preview_warnings() {
+        let mut exact_out = intent(SwapMode::ExactOut);
+        exact_out.fee_preferences.max_jito_tip_lamports = 10_000;
+        let sponsor = Pubkey::new_unique();
+        let mut tx = transaction(&exact_out, &sponsor, 40_000);
+        tx.message.recent_blockhash = Hash::new_unique();
+
+        let preview = PreviewBuilder::new(&exact_out, &tx)
+            .with_quote(2_000_000, 1_000_000_000)
+            .with_route(RouteType::StandardRpc)
+            .with_risk(MevRiskScore::new(0.9), vec![])
+            .build()
+            .unwrap();
+
+        assert_eq!(preview.receives.amount, 1_000_000_000);
+        assert_eq!(preview.worst_case_received.as_ref().unwrap().amount, 1_000_000_000);
+        assert_eq!(preview.max_paid.as_ref().unwrap().amount, 2_010_000);
+        // Sponsor and user both sign
+        assert_eq!(preview.fees.base_fee_lamports, 10_000);
+        assert!(preview.fees.sponsored);
+        assert_eq!(preview.fees.fee_payer, sponsor.to_string());
+        assert_eq!(preview.warnings.len(), 3, "{:?}", preview.warnings);
+        assert!(preview.warnings[0].contains("blockhash"));
+        assert!(preview.warnings[1].contains("Jito tip 40000"));
+        assert!(preview.warnings[2].contains("public route"));
+
+        // A transaction the user does not sign cannot be previewed for them
+        let stranger = intent(SwapMode::ExactIn);
+        assert!(PreviewBuilder::new(&stranger, &tx).build().is_err());
+    }
+}
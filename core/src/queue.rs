@@ -0,0 +1,306 @@
+//! Bounded Pipeline Queues
+//!
+//! Slot bursts used to pile up in unbounded channels between ingestion,
+//! extraction and inference until memory ran out. Every hop now goes through a
+//! [`BoundedQueue`] with an explicit [`OverflowPolicy`]:
+//! - [`OverflowPolicy::DropOldest`]: for observations whose value decays with
+//!   age (feature history updates); the oldest item is shed to admit the newest
+//! - [`OverflowPolicy::Block`]: for work that must never be lost (routed
+//!   intents); producers wait for space instead
+//!
+//! [`QueueStats`] exposes depth, high-water mark and shed/blocked counters.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+/// What a full queue does with a new item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Evict the oldest queued item (counted as shed)
+    DropOldest,
+    /// Make the producer wait (never drops)
+    Block,
+}
+
+/// Queue depth and load-shedding counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStats {
+    pub capacity: usize,
+    pub depth: usize,
+
+    /// Deepest the queue has been
+    pub high_water_mark: usize,
+    pub enqueued: u64,
+    pub dequeued: u64,
+
+    /// Items evicted by `DropOldest`
+    pub shed: u64,
+
+    /// Pushes that found the queue full (waited under `Block`, refused by `try_push`)
+    pub blocked: u64,
+}
+
+impl QueueStats {
+    /// Fraction of enqueued items that were shed
+    pub fn shed_rate(&self) -> f64 {
+        if self.enqueued == 0 {
+            0.0
+        } else {
+            self.shed as f64 / self.enqueued as f64
+        }
+    }
+
+    /// Current depth as a fraction of capacity
+    pub fn utilization(&self) -> f64 {
+        self.depth as f64 / self.capacity.max(1) as f64
+    }
+}
+
+struct State<T> {
+    items: VecDeque<T>,
+    closed: bool,
+    stats: QueueStats,
+}
+
+struct Shared<T> {
+    name: String,
+    policy: OverflowPolicy,
+    state: Mutex<State<T>>,
+    not_empty: Notify,
+    not_full: Notify,
+}
+
+/// Multi-producer, multi-consumer FIFO with a fixed capacity
+///
+/// Clones share the same queue.
+pub struct BoundedQueue<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for BoundedQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(name: impl Into<String>, capacity: usize, policy: OverflowPolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            shared: Arc::new(Shared {
+                name: name.into(),
+                policy,
+                state: Mutex::new(State {
+                    items: VecDeque::new(),
+                    closed: false,
+                    stats: QueueStats {
+                        capacity,
+                        ..Default::default()
+                    },
+                }),
+                not_empty: Notify::new(),
+                not_full: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.shared.name
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.shared.policy
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.shared.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Enqueue without waiting
+    ///
+    /// Returns the item back if the queue is closed, or full under `Block`.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(item);
+        }
+        if state.items.len() >= state.stats.capacity {
+            match self.shared.policy {
+                OverflowPolicy::Block => {
+                    state.stats.blocked += 1;
+                    return Err(item);
+                }
+                OverflowPolicy::DropOldest => {
+                    state.items.pop_front();
+                    state.stats.shed += 1;
+                    if state.stats.shed.is_power_of_two() {
+                        warn!("Queue '{}' full - shed {} items so far", self.shared.name, state.stats.shed);
+                    } else {
+                        debug!("Queue '{}' full - shed oldest item", self.shared.name);
+                    }
+                }
+            }
+        }
+        self.enqueue(&mut state, item);
+        drop(state);
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Enqueue, waiting for space under `Block`
+    ///
+    /// Returns the item back only if the queue is closed.
+    pub async fn push(&self, item: T) -> Result<(), T> {
+        if self.shared.policy == OverflowPolicy::DropOldest {
+            return self.try_push(item);
+        }
+
+        let mut counted = false;
+        loop {
+            // Register before checking so a pop in between is not missed
+            let space = self.shared.not_full.notified();
+            {
+                let mut state = self.lock();
+                if state.closed {
+                    return Err(item);
+                }
+                if state.items.len() < state.stats.capacity {
+                    self.enqueue(&mut state, item);
+                    drop(state);
+                    self.shared.not_empty.notify_one();
+                    return Ok(());
+                }
+                if !counted {
+                    state.stats.blocked += 1;
+                    counted = true;
+                }
+            }
+            space.await;
+        }
+    }
+
+    fn enqueue(&self, state: &mut State<T>, item: T) {
+        state.items.push_back(item);
+        state.stats.enqueued += 1;
+        state.stats.high_water_mark = state.stats.high_water_mark.max(state.items.len());
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        let mut state = self.lock();
+        let item = state.items.pop_front()?;
+        state.stats.dequeued += 1;
+        drop(state);
+        self.shared.not_full.notify_one();
+        Some(item)
+    }
+
+    /// Wait for the next item; `None` once the queue is closed and drained
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            let available = self.shared.not_empty.notified();
+            if let Some(item) = self.try_pop() {
+                return Some(item);
+            }
+            if self.lock().closed {
+                return None;
+            }
+            available.await;
+        }
+    }
+
+    /// Refuse further pushes; consumers drain what is left
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.shared.not_empty.notify_waiters();
+        self.shared.not_full.notify_waiters();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        let state = self.lock();
+        QueueStats {
+            depth: state.items.len(),
+            ..state.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_drop_oldest_sheds_and_counts() {
+        let queue = BoundedQueue::new("history", 3, OverflowPolicy::DropOldest);
+        for i in 0..5 {
+            queue.try_push(i).unwrap();
+        }
+        assert_eq!(queue.try_pop(), Some(2));
+
+        let stats = queue.stats();
+        assert_eq!((stats.depth, stats.high_water_mark, stats.enqueued, stats.dequeued, stats.shed), (2, 3, 5, 1, 2));
+        assert!((stats.shed_rate() - 0.4).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space_and_never_drops() {
+        let queue = BoundedQueue::new("intents", 2, OverflowPolicy::Block);
+        queue.push(1).await.unwrap();
+        queue.push(2).await.unwrap();
+        assert_eq!(queue.try_push(3), Err(3));
+
+        let producer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.push(3).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!producer.is_finished());
+
+        assert_eq!(queue.pop().await, Some(1));
+        producer.await.unwrap().unwrap();
+
+        let drained: Vec<_> = std::iter::from_fn(|| queue.try_pop()).collect();
+        assert_eq!(drained, vec![2, 3]);
+        let stats = queue.stats();
+        assert_eq!((stats.shed, stats.blocked), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn test_close_drains_then_ends() {
+        let queue = BoundedQueue::new("features", 4, OverflowPolicy::DropOldest);
+        let consumer = {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let mut seen = Vec::new();
+                while let Some(item) = queue.pop().await {
+                    seen.push(item);
+                }
+                seen
+            })
+        };
+        queue.push("a").await.unwrap();
+        queue.push("b").await.unwrap();
+        queue.close();
+
+        assert_eq!(queue.push("c").await, Err("c"));
+        assert_eq!(consumer.await.unwrap(), vec!["a", "b"]);
+    }
+}
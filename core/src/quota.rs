@@ -0,0 +1,396 @@
+//! Per-User Intent Quotas
+//!
+//! Shared router deployments must not let one spamming wallet starve others.
+//! `QuotaManager` keeps two token buckets per `user_public_key`:
+//! - intents per minute, with `intent_burst` extra capacity
+//! - notional USD per hour, with `notional_burst_usd` extra capacity
+//!
+//! An intent is admitted only if both buckets can pay for it; otherwise the
+//! caller gets `SentinelError::RateLimited` with the time until it would fit.
+//!
+//! Buckets are per process. Behind a load balancer, attach a shared
+//! [`Coordination`] (`with_coordination`) and the limits are enforced
+//! cluster-wide instead, as fixed windows (one minute / one hour) each
+//! allowing the sustained rate plus the burst.
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tracing::{debug, warn};
+
+use crate::config::{QuotaSettings, SentinelConfig};
+use crate::coordination::Coordination;
+use crate::intent::Intent;
+use crate::{Result, SentinelError};
+
+/// Token bucket refilled continuously at `rate` tokens per second
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, capacity: f64, rate: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity);
+        self.updated = now;
+    }
+
+    /// Milliseconds until `cost` tokens are available (`u64::MAX` if never)
+    fn wait_ms(&self, cost: f64, capacity: f64, rate: f64) -> u64 {
+        if cost > capacity || rate <= 0.0 {
+            return u64::MAX;
+        }
+        let missing = (cost - self.tokens).max(0.0);
+        (missing / rate * 1_000.0).ceil() as u64
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UserQuota {
+    intents: Bucket,
+    notional: Bucket,
+}
+
+/// Remaining allowance for a wallet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuotaUsage {
+    pub intents_remaining: u32,
+    pub notional_usd_remaining: f64,
+}
+
+/// Shared counter windows
+const MINUTE_MS: u64 = 60_000;
+const HOUR_MS: u64 = 3_600_000;
+
+/// Per-wallet intent rate and notional limits
+pub struct QuotaManager {
+    settings: Mutex<QuotaSettings>,
+    users: Mutex<HashMap<Pubkey, UserQuota>>,
+    shared: Option<(Arc<Coordination>, String)>,
+}
+
+impl QuotaManager {
+    pub fn new(settings: QuotaSettings) -> Self {
+        Self {
+            settings: Mutex::new(settings),
+            users: Mutex::new(HashMap::new()),
+            shared: None,
+        }
+    }
+
+    /// Enforce limits across instances through shared counters under `namespace` (e.g. the tenant id)
+    pub fn with_coordination(mut self, coordination: Arc<Coordination>, namespace: impl Into<String>) -> Self {
+        self.shared = Some((coordination, namespace.into()));
+        self
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.quotas.clone())
+    }
+
+    /// Apply the hot-reloadable `[quotas]` section (existing balances are kept)
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *lock(&self.settings) = config.quotas.clone();
+    }
+
+    /// Admit an intent whose notional value is `notional_usd`
+    pub fn check_intent(&self, intent: &Intent, notional_usd: f64) -> Result<()> {
+        self.check(&intent.user_public_key, notional_usd)
+    }
+
+    /// Charge one intent and `notional_usd` to `user`, or reject without charging
+    pub fn check(&self, user: &Pubkey, notional_usd: f64) -> Result<()> {
+        match self.shared {
+            Some((ref coordination, ref namespace)) => self.check_shared(coordination, namespace, user, notional_usd),
+            None => self.check_at(user, notional_usd, Instant::now()),
+        }
+    }
+
+    fn check_shared(
+        &self,
+        coordination: &Coordination,
+        namespace: &str,
+        user: &Pubkey,
+        notional_usd: f64,
+    ) -> Result<()> {
+        let settings = lock(&self.settings).clone();
+        let limits = Limits::from(&settings);
+        let notional_usd = notional_usd.max(0.0);
+        let intents_key = format!("quota:{}:intents:{}", namespace, user);
+
+        if limits.intents_enabled {
+            let (charge, retry_after_ms) = coordination.charge(&intents_key, MINUTE_MS, 1.0, limits.intent_capacity)?;
+            if !charge.charged {
+                return Err(intents_exceeded(user, &settings, retry_after_ms));
+            }
+        }
+
+        if limits.notional_enabled {
+            let notional_key = format!("quota:{}:notional:{}", namespace, user);
+            let (charge, retry_after_ms) =
+                coordination.charge(&notional_key, HOUR_MS, notional_usd, limits.notional_capacity)?;
+            if !charge.charged {
+                // Rejected intents are not charged
+                if limits.intents_enabled {
+                    coordination.refund(&intents_key, MINUTE_MS, 1.0)?;
+                }
+                let retry_after_ms = if notional_usd > limits.notional_capacity { u64::MAX } else { retry_after_ms };
+                let remaining = limits.notional_capacity - charge.total;
+                return Err(notional_exceeded(user, notional_usd, remaining, &settings, retry_after_ms));
+            }
+        }
+        debug!("Shared quota charged for {}: ${:.2}", user, notional_usd);
+
+        Ok(())
+    }
+
+    fn check_at(&self, user: &Pubkey, notional_usd: f64, now: Instant) -> Result<()> {
+        let settings = lock(&self.settings).clone();
+        let limits = Limits::from(&settings);
+        let notional_usd = notional_usd.max(0.0);
+
+        let mut users = lock(&self.users);
+        if !users.contains_key(user) && users.len() >= settings.max_tracked_users {
+            evict_idle(&mut users, &limits, now);
+        }
+
+        let quota = users.entry(*user).or_insert_with(|| UserQuota {
+            intents: Bucket::full(limits.intent_capacity, now),
+            notional: Bucket::full(limits.notional_capacity, now),
+        });
+        quota.intents.refill(limits.intent_capacity, limits.intent_rate, now);
+        quota.notional.refill(limits.notional_capacity, limits.notional_rate, now);
+
+        if limits.intents_enabled && quota.intents.tokens < 1.0 {
+            let retry_after_ms = quota.intents.wait_ms(1.0, limits.intent_capacity, limits.intent_rate);
+            return Err(intents_exceeded(user, &settings, retry_after_ms));
+        }
+
+        if limits.notional_enabled && quota.notional.tokens < notional_usd {
+            let retry_after_ms =
+                quota.notional.wait_ms(notional_usd, limits.notional_capacity, limits.notional_rate);
+            return Err(notional_exceeded(user, notional_usd, quota.notional.tokens, &settings, retry_after_ms));
+        }
+
+        if limits.intents_enabled {
+            quota.intents.tokens -= 1.0;
+        }
+        if limits.notional_enabled {
+            quota.notional.tokens -= notional_usd;
+        }
+        debug!("Quota charged for {}: ${:.2}", user, notional_usd);
+
+        Ok(())
+    }
+
+    /// Remaining allowance for `user` (full allowance if never seen)
+    pub fn usage(&self, user: &Pubkey) -> QuotaUsage {
+        let limits = Limits::from(&*lock(&self.settings));
+        let now = Instant::now();
+
+        let (intents, notional) = match lock(&self.users).get(user) {
+            Some(quota) => {
+                let mut quota = quota.clone();
+                quota.intents.refill(limits.intent_capacity, limits.intent_rate, now);
+                quota.notional.refill(limits.notional_capacity, limits.notional_rate, now);
+                (quota.intents.tokens, quota.notional.tokens)
+            }
+            None => (limits.intent_capacity, limits.notional_capacity),
+        };
+
+        QuotaUsage {
+            intents_remaining: intents.floor() as u32,
+            notional_usd_remaining: notional,
+        }
+    }
+
+    /// Number of wallets currently tracked
+    pub fn tracked_users(&self) -> usize {
+        lock(&self.users).len()
+    }
+}
+
+/// Bucket parameters derived from settings
+struct Limits {
+    intents_enabled: bool,
+    intent_capacity: f64,
+    intent_rate: f64,
+    notional_enabled: bool,
+    notional_capacity: f64,
+    notional_rate: f64,
+}
+
+impl From<&QuotaSettings> for Limits {
+    fn from(settings: &QuotaSettings) -> Self {
+        Self {
+            intents_enabled: settings.intents_per_minute > 0,
+            intent_capacity: f64::from(settings.intents_per_minute + settings.intent_burst),
+            intent_rate: f64::from(settings.intents_per_minute) / 60.0,
+            notional_enabled: settings.notional_usd_per_hour > 0.0,
+            notional_capacity: settings.notional_usd_per_hour + settings.notional_burst_usd,
+            notional_rate: settings.notional_usd_per_hour / 3_600.0,
+        }
+    }
+}
+
+fn intents_exceeded(user: &Pubkey, settings: &QuotaSettings, retry_after_ms: u64) -> SentinelError {
+    warn!("🚦 Wallet {} exceeded {} intents/min", user, settings.intents_per_minute);
+    SentinelError::RateLimited {
+        reason: format!(
+            "wallet {} exceeded {} intents per minute",
+            user, settings.intents_per_minute
+        ),
+        retry_after_ms,
+    }
+}
+
+fn notional_exceeded(
+    user: &Pubkey,
+    notional_usd: f64,
+    remaining: f64,
+    settings: &QuotaSettings,
+    retry_after_ms: u64,
+) -> SentinelError {
+    warn!(
+        "🚦 Wallet {} exceeded ${:.0}/hour notional",
+        user, settings.notional_usd_per_hour
+    );
+    SentinelError::RateLimited {
+        reason: format!(
+            "wallet {} notional ${:.2} exceeds remaining ${:.2} of ${:.0} per hour",
+            user, notional_usd, remaining, settings.notional_usd_per_hour
+        ),
+        retry_after_ms,
+    }
+}
+
+/// Drop wallets whose buckets have fully refilled (they carry no state)
+fn evict_idle(users: &mut HashMap<Pubkey, UserQuota>, limits: &Limits, now: Instant) {
+    let before = users.len();
+    users.retain(|_, quota| {
+        quota.intents.refill(limits.intent_capacity, limits.intent_rate, now);
+        quota.notional.refill(limits.notional_capacity, limits.notional_rate, now);
+        quota.intents.tokens < limits.intent_capacity || quota.notional.tokens < limits.notional_capacity
+    });
+    debug!("Evicted {} idle quota entries", before - users.len());
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn manager(intents_per_minute: u32, intent_burst: u32, notional: f64, burst: f64) -> QuotaManager {
+        QuotaManager::new(QuotaSettings {
+            intents_per_minute,
+            intent_burst,
+            notional_usd_per_hour: notional,
+            notional_burst_usd: burst,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_intent_rate_with_burst() {
+        let quotas = manager(6, 2, 0.0, 0.0);
+        let user = Pubkey::new_unique();
+        let now = Instant::now();
+
+        for _ in 0..8 {
+            assert!(quotas.check_at(&user, 0.0, now).is_ok());
+        }
+        match quotas.check_at(&user, 0.0, now) {
+            Err(SentinelError::RateLimited { retry_after_ms, .. }) => assert_eq!(retry_after_ms, 10_000),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+
+        // 6/min refills one intent every 10s
+        assert!(quotas.check_at(&user, 0.0, now + Duration::from_secs(10)).is_ok());
+        assert!(quotas.check_at(&user, 0.0, now + Duration::from_secs(10)).is_err());
+    }
+
+    #[test]
+    fn test_notional_limit_is_per_user() {
+        let quotas = manager(0, 0, 10_000.0, 5_000.0);
+        let whale = Pubkey::new_unique();
+        let now = Instant::now();
+
+        assert!(quotas.check_at(&whale, 12_000.0, now).is_ok());
+        let err = quotas.check_at(&whale, 5_000.0, now).unwrap_err();
+        assert!(err.to_string().contains("per hour"));
+
+        // Rejected intents are not charged; other wallets are unaffected
+        assert!(quotas.check_at(&whale, 3_000.0, now).is_ok());
+        assert!(quotas.check_at(&Pubkey::new_unique(), 15_000.0, now).is_ok());
+    }
+
+    #[test]
+    fn test_oversized_notional_never_fits() {
+        let quotas = manager(0, 0, 1_000.0, 0.0);
+        match quotas.check(&Pubkey::new_unique(), 2_000.0) {
+            Err(SentinelError::RateLimited { retry_after_ms, .. }) => assert_eq!(retry_after_ms, u64::MAX),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_shared_counters_limit_across_instances() {
+        use crate::clock::ManualClock;
+        use crate::config::CoordinationSettings;
+        use crate::coordination::MemoryCoordinator;
+
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let shared = Arc::new(MemoryCoordinator::new());
+        let instance = || {
+            let coordination =
+                Coordination::new(shared.clone(), &CoordinationSettings::default()).with_clock(clock.clone());
+            manager(2, 1, 10_000.0, 0.0).with_coordination(Arc::new(coordination), "default")
+        };
+        let (a, b) = (instance(), instance());
+        let user = Pubkey::new_unique();
+
+        a.check(&user, 6_000.0).unwrap();
+        // Over the notional limit: rejected without using up an intent
+        assert!(b.check(&user, 6_000.0).is_err());
+        b.check(&user, 1_000.0).unwrap();
+        a.check(&user, 0.0).unwrap();
+        match b.check(&user, 0.0) {
+            Err(SentinelError::RateLimited { retry_after_ms, .. }) => assert_eq!(retry_after_ms, 40_000),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+
+        clock.advance(Duration::from_secs(40));
+        b.check(&user, 0.0).unwrap();
+    }
+
+    #[test]
+    fn test_idle_users_evicted() {
+        let quotas = QuotaManager::new(QuotaSettings {
+            max_tracked_users: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        quotas.check_at(&Pubkey::new_unique(), 0.0, now).unwrap();
+        quotas.check_at(&Pubkey::new_unique(), 0.0, now).unwrap();
+        quotas.check_at(&Pubkey::new_unique(), 0.0, now + Duration::from_secs(3_600)).unwrap();
+
+        assert_eq!(quotas.tracked_users(), 1);
+        assert_eq!(quotas.usage(&Pubkey::new_unique()).intents_remaining, 80);
+    }
+}
@@ -0,0 +1,173 @@
+//! Log Redaction
+//!
+//! Decision and shadow logs are kept for months and shipped to analysts, so
+//! anything tying a record to a user is scrubbed before it is buffered:
+//! - signatures are cut to a prefix (enough to eyeball, not to look up)
+//! - in [`ComplianceMode::GdprStrict`] user public keys (fee payers, and any
+//!   pubkey in free text such as error messages) are replaced by
+//!   [`REDACTED_PUBKEY`]
+//! - feature vectors can be left out of shadow predictions entirely
+//!
+//! Records apply a [`Scrubber`] themselves (`DecisionRecord::scrub`,
+//! `ShadowPrediction::scrub`); the logs call it on every append. The default
+//! scrubber leaves records untouched.
+
+use crate::config::{ComplianceMode, RedactionSettings, SentinelConfig};
+
+/// Signature prefix kept in GDPR-strict mode unless configured
+pub const STRICT_SIGNATURE_PREFIX: usize = 8;
+
+/// Replacement for public keys in GDPR-strict mode
+pub const REDACTED_PUBKEY: &str = "[pubkey]";
+
+/// Marks a truncated signature
+const ELLIPSIS: char = '…';
+
+/// Applies [`RedactionSettings`] to values about to be persisted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scrubber {
+    mode: ComplianceMode,
+    signature_prefix: Option<usize>,
+    drop_features: bool,
+}
+
+impl Scrubber {
+    pub fn new(settings: &RedactionSettings) -> Self {
+        let strict = settings.compliance_mode == ComplianceMode::GdprStrict;
+        Self {
+            mode: settings.compliance_mode,
+            signature_prefix: settings
+                .signature_prefix
+                .or(strict.then_some(STRICT_SIGNATURE_PREFIX)),
+            drop_features: !settings.log_features,
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(&config.redaction)
+    }
+
+    pub fn mode(&self) -> ComplianceMode {
+        self.mode
+    }
+
+    /// Whether scrubbing changes anything
+    pub fn is_passthrough(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether feature vectors may be persisted
+    pub fn keeps_features(&self) -> bool {
+        !self.drop_features
+    }
+
+    /// Signature cut to the configured prefix
+    ///
+    /// Idempotent, so already-scrubbed signatures (and lookups by them) match.
+    pub fn signature(&self, signature: &str) -> String {
+        match self.signature_prefix {
+            Some(prefix) if signature.chars().count() > prefix => {
+                let mut truncated: String = signature.chars().take(prefix).collect();
+                if !truncated.ends_with(ELLIPSIS) {
+                    truncated.push(ELLIPSIS);
+                }
+                truncated
+            }
+            _ => signature.to_string(),
+        }
+    }
+
+    /// A user's public key, replaced in strict mode
+    pub fn pubkey(&self, pubkey: &str) -> String {
+        match self.mode {
+            ComplianceMode::GdprStrict => REDACTED_PUBKEY.to_string(),
+            ComplianceMode::Standard => pubkey.to_string(),
+        }
+    }
+
+    /// Free text with signature- and (in strict mode) pubkey-shaped base58 tokens scrubbed
+    pub fn text(&self, text: &str) -> String {
+        if self.signature_prefix.is_none() && self.mode == ComplianceMode::Standard {
+            return text.to_string();
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut token_start = None;
+        for (i, c) in text.char_indices() {
+            match (c.is_ascii_alphanumeric(), token_start) {
+                (true, None) => token_start = Some(i),
+                (false, Some(start)) => {
+                    out.push_str(&self.token(&text[start..i]));
+                    out.push(c);
+                    token_start = None;
+                }
+                (false, None) => out.push(c),
+                (true, Some(_)) => {}
+            }
+        }
+        if let Some(start) = token_start {
+            out.push_str(&self.token(&text[start..]));
+        }
+        out
+    }
+
+    fn token(&self, token: &str) -> String {
+        let base58 = token.bytes().all(|b| !matches!(b, b'0' | b'O' | b'I' | b'l'));
+        match token.len() {
+            64..=88 if base58 => self.signature(token),
+            32..=44 if base58 && self.mode == ComplianceMode::GdprStrict => REDACTED_PUBKEY.to_string(),
+            _ => token.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURE: &str =
+        "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+    const PUBKEY: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn test_default_is_passthrough() {
+        let scrubber = Scrubber::new(&RedactionSettings::default());
+        assert!(scrubber.is_passthrough() && scrubber.keeps_features());
+        assert_eq!(scrubber.signature(SIGNATURE), SIGNATURE);
+
+        let message = format!("mint {} is not allowed, tx {}", PUBKEY, SIGNATURE);
+        assert_eq!(scrubber.text(&message), message);
+    }
+
+    #[test]
+    fn test_strict_mode_truncates_signatures_and_drops_pubkeys() {
+        let scrubber = Scrubber::new(&RedactionSettings {
+            compliance_mode: ComplianceMode::GdprStrict,
+            log_features: false,
+            ..Default::default()
+        });
+        assert!(!scrubber.keeps_features());
+
+        let short = scrubber.signature(SIGNATURE);
+        assert_eq!(short, "5VERv8NM…");
+        assert_eq!(scrubber.signature(&short), short);
+
+        assert_eq!(
+            scrubber.text(&format!("owner {}: tx {} failed (code 0x1771)", PUBKEY, SIGNATURE)),
+            "owner [pubkey]: tx 5VERv8NM… failed (code 0x1771)"
+        );
+    }
+
+    #[test]
+    fn test_standard_mode_with_prefix_keeps_pubkeys() {
+        let scrubber = Scrubber::new(&RedactionSettings {
+            signature_prefix: Some(12),
+            ..Default::default()
+        });
+        assert_eq!(scrubber.mode(), ComplianceMode::Standard);
+        assert_eq!(
+            scrubber.text(&format!("{} {}", PUBKEY, SIGNATURE)),
+            format!("{} 5VERv8NMvzbJ…", PUBKEY)
+        );
+    }
+}
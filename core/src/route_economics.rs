@@ -0,0 +1,278 @@
+//! Route Cost/Benefit Estimation
+//!
+//! A Jito bundle protects against sandwiches but costs a tip and lands less
+//! often than it is submitted; plain RPC is free but fully exposed. Instead of
+//! a fixed risk threshold, [`RouteEconomics`] prices each route in lamports and
+//! the router picks the cheapest:
+//!
+//! ```text
+//! expected_net_cost = tip + exposure × sandwich_loss + latency_penalty / p_land
+//! ```
+//!
+//! - **sandwich loss**: expected extraction if the swap is exposed
+//!   ([`estimate_sandwich_loss`]), scaled by how much of it the route leaves
+//!   open (0 for a protected bundle, 1 for the public path)
+//! - **tip**: the recommended percentile tip, paid only on routes that tip and
+//!   only once (tips are charged when the transaction lands)
+//! - **landing probability**: failed attempts are retried, so the expected
+//!   number of attempts is `1 / p_land`; each attempt costs its latency
+//!   ([`LandingPredictor`] supplies them from submission history)
+//! - **latency penalty**: price drift while waiting, `notional ×
+//!   latency_bps_per_sec × seconds`
+//!
+//! All amounts are lamports; the trade notional must be valued in SOL.
+
+use serde::{Deserialize, Serialize};
+
+use crate::landing::{LandingContext, LandingPredictor};
+use crate::types::RouteType;
+
+/// Landing probabilities below this are treated as "does not land"
+const MIN_LANDING_PROBABILITY: f64 = 0.01;
+
+/// Cost model for one route
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteProfile {
+    pub route: RouteType,
+
+    /// Chance a single attempt lands (0.0-1.0)
+    pub landing_probability: f64,
+
+    /// Submission to confirmation (milliseconds)
+    pub expected_latency_ms: f64,
+
+    /// Share of the sandwich loss the route leaves exposed (0.0-1.0)
+    pub sandwich_exposure: f64,
+
+    /// Route requires a Jito tip
+    pub pays_tip: bool,
+}
+
+/// Estimator tuning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteEconomicsSettings {
+    pub profiles: Vec<RouteProfile>,
+
+    /// Expected adverse drift while waiting (basis points of notional per second)
+    pub latency_bps_per_sec: f64,
+}
+
+impl Default for RouteEconomicsSettings {
+    fn default() -> Self {
+        Self {
+            profiles: vec![
+                RouteProfile {
+                    route: RouteType::JitoBundle,
+                    landing_probability: 0.90,
+                    expected_latency_ms: 800.0,
+                    sandwich_exposure: 0.0,
+                    pays_tip: true,
+                },
+                RouteProfile {
+                    route: RouteType::JitoSingle,
+                    landing_probability: 0.85,
+                    expected_latency_ms: 600.0,
+                    sandwich_exposure: 0.25,
+                    pays_tip: true,
+                },
+                RouteProfile {
+                    route: RouteType::Firedancer,
+                    landing_probability: 0.80,
+                    expected_latency_ms: 400.0,
+                    sandwich_exposure: 0.7,
+                    pays_tip: false,
+                },
+                RouteProfile {
+                    route: RouteType::StandardRpc,
+                    landing_probability: 0.75,
+                    expected_latency_ms: 500.0,
+                    sandwich_exposure: 1.0,
+                    pays_tip: false,
+                },
+            ],
+            latency_bps_per_sec: 5.0,
+        }
+    }
+}
+
+/// Per-swap inputs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteCostInputs {
+    /// Trade value (lamports)
+    pub notional_lamports: u64,
+
+    /// Expected loss if fully exposed (lamports)
+    pub sandwich_loss_lamports: f64,
+
+    /// Tip at the recommended percentile (lamports)
+    pub tip_lamports: u64,
+
+    /// Observed landing rates replacing the profile defaults
+    pub landing_overrides: Vec<(RouteType, f64)>,
+}
+
+impl RouteCostInputs {
+    /// Replace profile landing rates with the predictor's (routes with enough history)
+    pub fn with_landing_predictions(mut self, predictor: &LandingPredictor, context: &LandingContext) -> Self {
+        self.landing_overrides = predictor.overrides(context);
+        self
+    }
+}
+
+/// Expected cost breakdown for one route (lamports)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteCost {
+    pub route: RouteType,
+    pub landing_probability: f64,
+    pub sandwich_cost: f64,
+    pub tip_cost: f64,
+    pub latency_cost: f64,
+    pub expected_net_cost: f64,
+}
+
+/// Expected sandwich loss for an exposed swap (lamports)
+///
+/// A sandwich pushes the price right up to the user's slippage limit, so the
+/// extractable amount is the tolerance left after the swap's own price impact;
+/// `risk_score` is the chance an attacker takes it.
+pub fn estimate_sandwich_loss(notional_lamports: u64, max_slippage_bps: u16, price_impact_bps: f64, risk_score: f32) -> f64 {
+    let extractable_bps = (max_slippage_bps as f64 - price_impact_bps.max(0.0)).max(0.0);
+    notional_lamports as f64 * extractable_bps / 10_000.0 * risk_score.clamp(0.0, 1.0) as f64
+}
+
+/// Prices execution routes so the cheapest can be chosen
+#[derive(Debug, Clone, Default)]
+pub struct RouteEconomics {
+    settings: RouteEconomicsSettings,
+}
+
+impl RouteEconomics {
+    pub fn new(settings: RouteEconomicsSettings) -> Self {
+        Self { settings }
+    }
+
+    pub fn settings(&self) -> &RouteEconomicsSettings {
+        &self.settings
+    }
+
+    /// Cost of one route, or None if it has no profile or cannot land
+    pub fn cost(&self, route: &RouteType, inputs: &RouteCostInputs) -> Option<RouteCost> {
+        let profile = self.settings.profiles.iter().find(|p| p.route == *route)?;
+
+        let landing_probability = inputs
+            .landing_overrides
+            .iter()
+            .find(|(r, _)| r == route)
+            .map_or(profile.landing_probability, |(_, p)| *p)
+            .clamp(0.0, 1.0);
+        if landing_probability < MIN_LANDING_PROBABILITY {
+            return None;
+        }
+
+        let sandwich_cost = inputs.sandwich_loss_lamports.max(0.0) * profile.sandwich_exposure.clamp(0.0, 1.0);
+        let tip_cost = if profile.pays_tip { inputs.tip_lamports as f64 } else { 0.0 };
+        let per_attempt = inputs.notional_lamports as f64 * self.settings.latency_bps_per_sec / 10_000.0
+            * profile.expected_latency_ms.max(0.0)
+            / 1_000.0;
+        let latency_cost = per_attempt / landing_probability;
+
+        Some(RouteCost {
+            route: route.clone(),
+            landing_probability,
+            sandwich_cost,
+            tip_cost,
+            latency_cost,
+            expected_net_cost: sandwich_cost + tip_cost + latency_cost,
+        })
+    }
+
+    /// Every profiled route that can land, cheapest first
+    pub fn evaluate(&self, inputs: &RouteCostInputs) -> Vec<RouteCost> {
+        let mut costs: Vec<_> = self
+            .settings
+            .profiles
+            .iter()
+            .filter_map(|p| self.cost(&p.route, inputs))
+            .collect();
+        costs.sort_by(|a, b| a.expected_net_cost.total_cmp(&b.expected_net_cost));
+        costs
+    }
+
+    /// Cheapest of the routes currently allowed (e.g. by safety or leader guard)
+    pub fn cheapest(&self, inputs: &RouteCostInputs, allowed: &[RouteType]) -> Option<RouteCost> {
+        self.evaluate(inputs).into_iter().find(|c| allowed.contains(&c.route))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [RouteType; 4] = [
+        RouteType::JitoBundle,
+        RouteType::JitoSingle,
+        RouteType::Firedancer,
+        RouteType::StandardRpc,
+    ];
+
+    fn inputs(notional_sol: u64, risk_score: f32) -> RouteCostInputs {
+        let notional_lamports = notional_sol * 1_000_000_000;
+        RouteCostInputs {
+            notional_lamports,
+            sandwich_loss_lamports: estimate_sandwich_loss(notional_lamports, 100, 20.0, risk_score),
+            tip_lamports: 100_000,
+            landing_overrides: vec![],
+        }
+    }
+
+    #[test]
+    fn test_sandwich_loss_estimate() {
+        // 0.8% extractable on 10 SOL at 50% attack odds
+        assert_eq!(estimate_sandwich_loss(10_000_000_000, 100, 20.0, 0.5), 40_000_000.0);
+        assert_eq!(estimate_sandwich_loss(10_000_000_000, 100, 150.0, 1.0), 0.0);
+        assert_eq!(estimate_sandwich_loss(10_000_000_000, 100, 20.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_low_risk_small_trade_skips_tip() {
+        let economics = RouteEconomics::default();
+        let best = economics.cheapest(&inputs(0, 0.0), &ALL).unwrap();
+        assert!(!matches!(best.route, RouteType::JitoBundle | RouteType::JitoSingle));
+        assert_eq!(best.tip_cost, 0.0);
+    }
+
+    #[test]
+    fn test_high_risk_trade_pays_for_bundle() {
+        let economics = RouteEconomics::default();
+        let costs = economics.evaluate(&inputs(50, 0.9));
+        assert_eq!(costs[0].route, RouteType::JitoBundle);
+        assert_eq!(costs[0].sandwich_cost, 0.0);
+        assert_eq!(costs[0].tip_cost, 100_000.0);
+        assert!(costs.windows(2).all(|w| w[0].expected_net_cost <= w[1].expected_net_cost));
+    }
+
+    #[test]
+    fn test_landing_probability_scales_latency() {
+        let economics = RouteEconomics::default();
+        let mut inputs = inputs(10, 0.0);
+        let base = economics.cost(&RouteType::StandardRpc, &inputs).unwrap();
+
+        inputs.landing_overrides = vec![(RouteType::StandardRpc, 0.375)];
+        let degraded = economics.cost(&RouteType::StandardRpc, &inputs).unwrap();
+        assert!((degraded.latency_cost - base.latency_cost * 2.0).abs() < 1e-6);
+
+        inputs.landing_overrides = vec![(RouteType::StandardRpc, 0.0)];
+        assert!(economics.cost(&RouteType::StandardRpc, &inputs).is_none());
+        assert!(economics.evaluate(&inputs).iter().all(|c| c.route != RouteType::StandardRpc));
+    }
+
+    #[test]
+    fn test_cheapest_respects_allowed_routes() {
+        let economics = RouteEconomics::default();
+        let best = economics
+            .cheapest(&inputs(50, 0.9), &[RouteType::Firedancer, RouteType::StandardRpc])
+            .unwrap();
+        assert_eq!(best.route, RouteType::Firedancer);
+        assert!(economics.cheapest(&inputs(50, 0.9), &[]).is_none());
+    }
+}
@@ -0,0 +1,223 @@
+//! Route Decision Explanations
+//!
+//! Integrators debugging why a swap went through a bundle (or did not) can ask
+//! for the full decision trace with `POST /api/v1/route/explain`. It is a dry
+//! run: nothing is submitted, and no history, drift or fusion statistics are
+//! recorded. The body ([`RouteExplainRequest`]) names either:
+//! - an intent, which is quoted, built and simulated like a preflight
+//! - a raw base64 transaction, which is scored as is (no simulation; accounts
+//!   behind lookup tables are not inspected)
+//!
+//! The answer ([`RouteExplanation`]) carries the feature vector (left out with
+//! `redact_features`), the fused risk score with each component's
+//! contribution and the heuristic rules it trips, the leader-window and
+//! combined routing risk, every route priced by `RouteEconomics` (when the
+//! trade value in lamports is known), the route the router would choose, and
+//! the thresholds in effect.
+//!
+//! `ai_engine::PreflightChecker::explain` produces it.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::config::{FusionSettings, SlotRiskSettings};
+use crate::intent::Intent;
+use crate::preflight::SimulationOutcome;
+use crate::route_economics::{RouteCost, RouteEconomicsSettings};
+use crate::types::RouteType;
+use crate::{Result, SentinelError};
+
+/// Wrapped SOL; an intent trading it has a known notional in lamports
+pub const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Body of `POST /api/v1/route/explain`: exactly one of `intent` or `transaction`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteExplainRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent: Option<Intent>,
+
+    /// Base64 wire transaction, legacy or v0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<String>,
+
+    /// Leave the feature vector out of the explanation
+    #[serde(default)]
+    pub redact_features: bool,
+
+    /// Trade value (lamports) routes are priced at; defaults to the SOL leg of an intent's quote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notional_lamports: Option<u64>,
+}
+
+/// What a [`RouteExplainRequest`] asks about
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplainSubject<'a> {
+    Intent(&'a Intent),
+    Transaction(&'a str),
+}
+
+impl RouteExplainRequest {
+    pub fn for_intent(intent: Intent) -> Self {
+        Self {
+            intent: Some(intent),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_transaction(base64_tx: impl Into<String>) -> Self {
+        Self {
+            transaction: Some(base64_tx.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_redacted_features(mut self) -> Self {
+        self.redact_features = true;
+        self
+    }
+
+    pub fn with_notional_lamports(mut self, notional_lamports: u64) -> Self {
+        self.notional_lamports = Some(notional_lamports);
+        self
+    }
+
+    pub fn subject(&self) -> Result<ExplainSubject<'_>> {
+        match (&self.intent, &self.transaction) {
+            (Some(intent), None) => Ok(ExplainSubject::Intent(intent)),
+            (None, Some(transaction)) => Ok(ExplainSubject::Transaction(transaction)),
+            _ => Err(SentinelError::InvalidIntent(
+                "Route explain takes exactly one of `intent` or `transaction`".to_string(),
+            )),
+        }
+    }
+
+    /// `notional_lamports`, or the SOL side of a quoted `in_amount` -> `out_amount` swap
+    pub fn notional_for(&self, in_amount: u64, out_amount: u64) -> Option<u64> {
+        if self.notional_lamports.is_some() {
+            return self.notional_lamports;
+        }
+        let swap = self.intent.as_ref()?.swap_details.as_ref()?;
+        let sol = Pubkey::from_str(WRAPPED_SOL_MINT).expect("Valid wrapped SOL mint");
+        if swap.input_mint == sol {
+            Some(in_amount)
+        } else if swap.output_mint == sol {
+            Some(out_amount)
+        } else {
+            None
+        }
+    }
+}
+
+/// Share of the fused score each component carried (see `ScoreFusion`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreContributions {
+    pub heuristic: f32,
+    pub model: f32,
+    pub adaptive: f32,
+
+    /// Component with the largest contribution (`heuristic`, `model` or `adaptive`)
+    pub dominant: String,
+}
+
+/// Settings the decision was made under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdsInEffect {
+    /// Routing bands and confidence shrinkage
+    pub slot_risk: SlotRiskSettings,
+    pub fusion: FusionSettings,
+    pub route_economics: RouteEconomicsSettings,
+
+    pub heuristic_rules_version: String,
+
+    /// Active heuristic rule set, as loaded
+    pub heuristic_rules: serde_json::Value,
+}
+
+/// Decision trace for one intent or transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteExplanation {
+    /// Explained intent; None for a raw transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent_id: Option<String>,
+
+    /// Feature vector the score was computed from, unless redacted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<serde_json::Value>,
+
+    /// Fused MEV risk score (0.0-1.0)
+    pub risk_score: f32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_confidence: Option<f32>,
+    pub contributions: ScoreContributions,
+
+    /// Heuristic rules the features trip
+    pub signals: Vec<String>,
+
+    /// Landing-weighted risk of the upcoming leaders (0.0-1.0)
+    pub window_risk: f32,
+
+    /// Score and window risk combined, as compared against the routing bands
+    pub routing_risk: f32,
+
+    /// Simulation of the intent's candidate transaction (intents only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SimulationOutcome>,
+
+    /// Every route that can land, cheapest first; empty when the notional is unknown
+    #[serde(default)]
+    pub candidates: Vec<RouteCost>,
+
+    /// Route the router would pick (None when the simulation failed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chosen_route: Option<RouteType>,
+
+    pub thresholds: ThresholdsInEffect,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(input_mint: Pubkey, output_mint: Pubkey) -> Intent {
+        let mut intent = Intent {
+            intent_id: "explain-1".to_string(),
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.input_mint = input_mint;
+        swap.output_mint = output_mint;
+        swap.minimum_received = None;
+        intent
+    }
+
+    #[test]
+    fn test_subject_requires_exactly_one() {
+        assert!(RouteExplainRequest::default().subject().is_err());
+        let both = RouteExplainRequest {
+            transaction: Some("AQ==".to_string()),
+            ..RouteExplainRequest::for_intent(intent(Pubkey::new_unique(), Pubkey::new_unique()))
+        };
+        assert!(both.subject().is_err());
+
+        let raw = RouteExplainRequest::for_transaction("AQ==").with_redacted_features();
+        assert_eq!(raw.subject().unwrap(), ExplainSubject::Transaction("AQ=="));
+        let json = serde_json::to_value(&raw).unwrap();
+        assert_eq!(json, serde_json::json!({ "transaction": "AQ==", "redact_features": true }));
+    }
+
+    #[test]
+    fn test_notional_from_sol_leg() {
+        let sol = Pubkey::from_str(WRAPPED_SOL_MINT).unwrap();
+        let selling = RouteExplainRequest::for_intent(intent(sol, Pubkey::new_unique()));
+        assert_eq!(selling.notional_for(1_000, 5_000), Some(1_000));
+
+        let buying = RouteExplainRequest::for_intent(intent(Pubkey::new_unique(), sol));
+        assert_eq!(buying.notional_for(1_000, 5_000), Some(5_000));
+
+        let tokens = RouteExplainRequest::for_intent(intent(Pubkey::new_unique(), Pubkey::new_unique()));
+        assert_eq!(tokens.notional_for(1_000, 5_000), None);
+        assert_eq!(tokens.with_notional_lamports(42).notional_for(1_000, 5_000), Some(42));
+    }
+}
@@ -0,0 +1,329 @@
+//! Operational Safety Controller
+//!
+//! Runtime switches for degrading the router without a restart:
+//! - **Kill switch**: every intent goes to StandardRpc with maximum protection
+//! - **Heuristics-only**: the ONNX model is bypassed; set by operators or
+//!   tripped automatically when the inference error rate spikes
+//! - **No-submit**: the full pipeline runs but nothing is sent (dry run)
+//!
+//! Switches are read from [`SafetySettings`] on every config reload and can be
+//! flipped directly through the setters (API handlers). The last write wins.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::{SafetySettings, SentinelConfig};
+use crate::types::RouteType;
+
+/// Route after safety overrides
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnforcedRoute {
+    pub route: RouteType,
+
+    /// Apply every available protection (jitodontfront, tight slippage)
+    pub max_protection: bool,
+}
+
+/// Point-in-time switch state (API representation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyStatus {
+    pub kill_switch: bool,
+
+    /// Heuristics-only set by an operator
+    pub heuristics_only: bool,
+
+    /// Heuristics-only tripped by the inference error rate
+    pub heuristics_auto_tripped: bool,
+
+    pub no_submit: bool,
+
+    /// Error rate over the current window (0-1)
+    pub inference_error_rate: f64,
+}
+
+/// Rolling inference outcomes plus the auto-trip state
+struct ErrorWindow {
+    outcomes: VecDeque<bool>,
+    errors: usize,
+    tripped_at: Option<Instant>,
+}
+
+impl ErrorWindow {
+    fn new() -> Self {
+        Self {
+            outcomes: VecDeque::new(),
+            errors: 0,
+            tripped_at: None,
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.errors as f64 / self.outcomes.len() as f64
+    }
+
+    fn clear(&mut self) {
+        self.outcomes.clear();
+        self.errors = 0;
+    }
+}
+
+struct SafetyState {
+    kill_switch: AtomicBool,
+    heuristics_only: AtomicBool,
+    no_submit: AtomicBool,
+    settings: Mutex<SafetySettings>,
+    window: Mutex<ErrorWindow>,
+}
+
+/// Shared, runtime-toggleable safety switches
+#[derive(Clone)]
+pub struct SafetyController {
+    state: Arc<SafetyState>,
+}
+
+impl Default for SafetyController {
+    fn default() -> Self {
+        Self::new(SafetySettings::default())
+    }
+}
+
+impl SafetyController {
+    pub fn new(settings: SafetySettings) -> Self {
+        let controller = Self {
+            state: Arc::new(SafetyState {
+                kill_switch: AtomicBool::new(false),
+                heuristics_only: AtomicBool::new(false),
+                no_submit: AtomicBool::new(false),
+                settings: Mutex::new(settings.clone()),
+                window: Mutex::new(ErrorWindow::new()),
+            }),
+        };
+        controller.apply_settings(&settings);
+        controller
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.safety.clone())
+    }
+
+    /// Apply the hot-reloadable `[safety]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        self.apply_settings(&config.safety);
+    }
+
+    fn apply_settings(&self, settings: &SafetySettings) {
+        self.set_kill_switch(settings.kill_switch);
+        self.set_heuristics_only(settings.heuristics_only);
+        self.set_no_submit(settings.no_submit);
+        *lock(&self.state.settings) = settings.clone();
+    }
+
+    pub fn set_kill_switch(&self, enabled: bool) {
+        if self.state.kill_switch.swap(enabled, Ordering::Relaxed) != enabled {
+            if enabled {
+                warn!("🛑 Kill switch engaged - all intents routed to StandardRpc with max protection");
+            } else {
+                info!("🟢 Kill switch released");
+            }
+        }
+    }
+
+    pub fn set_heuristics_only(&self, enabled: bool) {
+        if self.state.heuristics_only.swap(enabled, Ordering::Relaxed) != enabled {
+            info!("Heuristics-only mode {}", if enabled { "enabled" } else { "disabled" });
+        }
+    }
+
+    pub fn set_no_submit(&self, enabled: bool) {
+        if self.state.no_submit.swap(enabled, Ordering::Relaxed) != enabled {
+            info!("No-submit (dry-run) mode {}", if enabled { "enabled" } else { "disabled" });
+        }
+    }
+
+    pub fn kill_switch(&self) -> bool {
+        self.state.kill_switch.load(Ordering::Relaxed)
+    }
+
+    /// Whether the model must be bypassed (operator or auto-tripped)
+    pub fn heuristics_only(&self) -> bool {
+        self.state.heuristics_only.load(Ordering::Relaxed) || self.auto_tripped()
+    }
+
+    pub fn no_submit(&self) -> bool {
+        self.state.no_submit.load(Ordering::Relaxed)
+    }
+
+    /// Whether transactions may be sent
+    pub fn should_submit(&self) -> bool {
+        !self.no_submit()
+    }
+
+    /// Apply the kill switch to a proposed route
+    pub fn enforce_route(&self, proposed: RouteType) -> EnforcedRoute {
+        if self.kill_switch() {
+            return EnforcedRoute {
+                route: RouteType::StandardRpc,
+                max_protection: true,
+            };
+        }
+
+        EnforcedRoute {
+            route: proposed,
+            max_protection: false,
+        }
+    }
+
+    /// Record a model inference result; trips heuristics-only on error spikes
+    pub fn record_inference(&self, success: bool) {
+        let (window_size, max_rate) = {
+            let settings = lock(&self.state.settings);
+            (settings.error_window.max(1), settings.inference_error_rate)
+        };
+
+        let mut window = lock(&self.state.window);
+        window.outcomes.push_back(success);
+        if !success {
+            window.errors += 1;
+        }
+        while window.outcomes.len() > window_size {
+            if window.outcomes.pop_front() == Some(false) {
+                window.errors -= 1;
+            }
+        }
+
+        if window.tripped_at.is_none()
+            && window.outcomes.len() == window_size
+            && window.rate() >= max_rate
+        {
+            warn!(
+                "⚠️  Inference error rate {:.1}% over last {} calls - switching to heuristics-only",
+                window.rate() * 100.0,
+                window_size
+            );
+            window.tripped_at = Some(Instant::now());
+        }
+    }
+
+    /// Clear the auto-trip and error history (e.g. after a model fix)
+    pub fn reset_inference_errors(&self) {
+        let mut window = lock(&self.state.window);
+        window.clear();
+        window.tripped_at = None;
+    }
+
+    /// Auto-trip is active until the cooldown passes, then the model is retried
+    fn auto_tripped(&self) -> bool {
+        let cooldown = Duration::from_secs(lock(&self.state.settings).heuristics_cooldown_secs);
+        let mut window = lock(&self.state.window);
+
+        match window.tripped_at {
+            Some(at) if at.elapsed() < cooldown => true,
+            Some(_) => {
+                info!("Heuristics-only cooldown elapsed - retrying model inference");
+                window.clear();
+                window.tripped_at = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn status(&self) -> SafetyStatus {
+        let heuristics_auto_tripped = self.auto_tripped();
+        SafetyStatus {
+            kill_switch: self.kill_switch(),
+            heuristics_only: self.state.heuristics_only.load(Ordering::Relaxed),
+            heuristics_auto_tripped,
+            no_submit: self.no_submit(),
+            inference_error_rate: lock(&self.state.window).rate(),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(window: usize, rate: f64, cooldown: u64) -> SafetySettings {
+        SafetySettings {
+            inference_error_rate: rate,
+            error_window: window,
+            heuristics_cooldown_secs: cooldown,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_kill_switch_forces_standard_rpc() {
+        let safety = SafetyController::default();
+        assert_eq!(
+            safety.enforce_route(RouteType::JitoBundle),
+            EnforcedRoute { route: RouteType::JitoBundle, max_protection: false }
+        );
+
+        safety.set_kill_switch(true);
+        assert_eq!(
+            safety.enforce_route(RouteType::JitoBundle),
+            EnforcedRoute { route: RouteType::StandardRpc, max_protection: true }
+        );
+    }
+
+    #[test]
+    fn test_error_spike_trips_heuristics_only() {
+        let safety = SafetyController::new(settings(10, 0.3, 300));
+
+        for i in 0..10 {
+            safety.record_inference(i % 5 != 0);
+        }
+        assert!(!safety.heuristics_only());
+
+        for _ in 0..2 {
+            safety.record_inference(false);
+        }
+        assert!(safety.heuristics_only());
+        assert!(safety.status().heuristics_auto_tripped);
+
+        safety.reset_inference_errors();
+        assert!(!safety.heuristics_only());
+    }
+
+    #[test]
+    fn test_auto_trip_expires_after_cooldown() {
+        let safety = SafetyController::new(settings(2, 0.5, 0));
+        safety.record_inference(false);
+        safety.record_inference(false);
+        assert!(!safety.heuristics_only());
+        assert_eq!(safety.status().inference_error_rate, 0.0);
+    }
+
+    #[test]
+    fn test_config_reload_toggles_switches() {
+        let safety = SafetyController::default();
+        assert!(safety.should_submit());
+
+        let mut config = SentinelConfig::default();
+        config.safety.no_submit = true;
+        config.safety.heuristics_only = true;
+        safety.apply_config(&config);
+
+        assert!(!safety.should_submit());
+        assert!(safety.heuristics_only());
+        assert!(!safety.kill_switch());
+
+        // Shared across clones
+        let api_handle = safety.clone();
+        api_handle.set_no_submit(false);
+        assert!(safety.should_submit());
+    }
+}
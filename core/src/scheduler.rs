@@ -0,0 +1,456 @@
+//! Priority Execution Scheduler
+//!
+//! Pending intents are released for execution in priority order rather than
+//! arrival order. The next intent is the one with, in order:
+//! 1. the highest effective [`Priority`]: the fee-derived level, boosted one
+//!    level per `aging_secs` waited so low-fee intents cannot starve
+//! 2. a deadline within `urgent_within_secs` (expiring intents jump ahead
+//!    within their level)
+//! 3. the earliest deadline
+//! 4. the highest MEV risk score
+//! 5. the earliest arrival
+//!
+//! Each fee-derived level has its own in-flight limit; an aged intent still
+//! counts against its original level, so boosts never consume Critical
+//! capacity. Intents past their deadline are never released; collect them with
+//! [`ExecutionScheduler::take_expired`].
+//!
+//! Routed intents are never shed: once `max_pending` intents are waiting,
+//! [`ExecutionScheduler::admit`] holds the caller until one is released.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, RwLock};
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{SchedulerSettings, SentinelConfig};
+use crate::intent::{Intent, Priority};
+use crate::queue::QueueStats;
+
+/// Intent waiting for (or holding) an execution slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledIntent {
+    pub ticket: u64,
+    pub intent: Intent,
+
+    /// Fee-derived level; in-flight limits are charged here
+    pub priority: Priority,
+    pub risk_score: f32,
+
+    /// Unix seconds
+    pub enqueued_at: i64,
+    pub deadline: Option<i64>,
+}
+
+impl ScheduledIntent {
+    fn is_expired(&self, now: i64) -> bool {
+        self.deadline.is_some_and(|deadline| deadline <= now)
+    }
+}
+
+const LEVELS: [Priority; 4] = [Priority::Low, Priority::Medium, Priority::High, Priority::Critical];
+
+fn level(priority: Priority) -> usize {
+    LEVELS.iter().position(|p| *p == priority).unwrap_or(0)
+}
+
+fn limit(settings: &SchedulerSettings, priority: Priority) -> usize {
+    match priority {
+        Priority::Low => settings.max_in_flight_low,
+        Priority::Medium => settings.max_in_flight_medium,
+        Priority::High => settings.max_in_flight_high,
+        Priority::Critical => settings.max_in_flight_critical,
+    }
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    pending: Vec<ScheduledIntent>,
+    in_flight: HashMap<u64, Priority>,
+    next_ticket: u64,
+    stats: QueueStats,
+}
+
+impl SchedulerState {
+    fn in_flight(&self, priority: Priority) -> usize {
+        self.in_flight.values().filter(|p| **p == priority).count()
+    }
+}
+
+/// Orders pending intents by priority, deadline and risk
+pub struct ExecutionScheduler {
+    settings: RwLock<SchedulerSettings>,
+    state: Mutex<SchedulerState>,
+    released: Notify,
+    /// Signalled when pending intents leave the queue
+    space: Notify,
+    clock: SharedClock,
+}
+
+impl ExecutionScheduler {
+    pub fn new(settings: SchedulerSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            state: Mutex::new(SchedulerState::default()),
+            released: Notify::new(),
+            space: Notify::new(),
+            clock: system_clock(),
+        }
+    }
+
+    /// Read the time for [`Self::next`] from `clock` instead of the wall clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.scheduler.clone())
+    }
+
+    /// Apply the hot-reloadable `[scheduler]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.scheduler.clone();
+        // Limits may have grown
+        self.released.notify_waiters();
+        self.space.notify_waiters();
+    }
+
+    fn settings(&self) -> SchedulerSettings {
+        self.settings.read().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Queue a validated intent; returns its ticket
+    pub fn enqueue(&self, intent: Intent, risk_score: f32, now: i64) -> u64 {
+        let deadline = intent
+            .constraints
+            .expiry_timestamp
+            .or_else(|| intent.constraints.ttl_seconds.map(|ttl| now + i64::from(ttl)));
+        let priority = intent.priority_level();
+
+        let mut state = self.lock();
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.pending.push(ScheduledIntent {
+            ticket,
+            intent,
+            priority,
+            risk_score,
+            enqueued_at: now,
+            deadline,
+        });
+        state.stats.enqueued += 1;
+        state.stats.high_water_mark = state.stats.high_water_mark.max(state.pending.len());
+        drop(state);
+
+        self.released.notify_waiters();
+        ticket
+    }
+
+    /// Queue an intent unless `max_pending` are already waiting; the intent is handed back if so
+    pub fn try_admit(&self, intent: Intent, risk_score: f32, now: i64) -> std::result::Result<u64, Box<Intent>> {
+        let max_pending = self.settings().max_pending;
+        {
+            let mut state = self.lock();
+            if max_pending > 0 && state.pending.len() >= max_pending {
+                state.stats.blocked += 1;
+                return Err(Box::new(intent));
+            }
+        }
+        Ok(self.enqueue(intent, risk_score, now))
+    }
+
+    /// Queue an intent, waiting while `max_pending` are already waiting (never drops)
+    pub async fn admit(&self, intent: Intent, risk_score: f32) -> u64 {
+        let mut counted = false;
+        loop {
+            // Register before checking so a release in between is not missed
+            let space = self.space.notified();
+            let max_pending = self.settings().max_pending;
+            {
+                let mut state = self.lock();
+                if max_pending == 0 || state.pending.len() < max_pending {
+                    drop(state);
+                    return self.enqueue(intent, risk_score, self.clock.unix_timestamp());
+                }
+                if !counted {
+                    state.stats.blocked += 1;
+                    counted = true;
+                    warn!("Scheduler full ({} pending) - holding intent {}", max_pending, intent.intent_id);
+                }
+            }
+            space.await;
+        }
+    }
+
+    /// Release the next intent, if one is pending and its level has capacity
+    ///
+    /// The caller must `complete` the ticket once execution finishes.
+    pub fn try_next(&self, now: i64) -> Option<ScheduledIntent> {
+        let settings = self.settings();
+        let mut state = self.lock();
+
+        let mut best: Option<(usize, _)> = None;
+        for (index, entry) in state.pending.iter().enumerate() {
+            if entry.is_expired(now) {
+                continue;
+            }
+            let max = limit(&settings, entry.priority);
+            if max > 0 && state.in_flight(entry.priority) >= max {
+                continue;
+            }
+
+            let key = order_key(entry, &settings, now);
+            match &best {
+                Some((_, best_key)) if key <= *best_key => {}
+                _ => best = Some((index, key)),
+            }
+        }
+
+        let (index, _) = best?;
+        let entry = state.pending.swap_remove(index);
+        state.in_flight.insert(entry.ticket, entry.priority);
+        state.stats.dequeued += 1;
+        self.space.notify_waiters();
+        debug!(
+            "Released intent {} (ticket {}, {:?}, waited {}s)",
+            entry.intent.intent_id,
+            entry.ticket,
+            entry.priority,
+            now - entry.enqueued_at
+        );
+        Some(entry)
+    }
+
+    /// Wait until an intent can be released
+    pub async fn next(&self) -> ScheduledIntent {
+        loop {
+            // Register before checking so a release in between is not missed
+            let released = self.released.notified();
+            if let Some(entry) = self.try_next(self.clock.unix_timestamp()) {
+                return entry;
+            }
+            released.await;
+        }
+    }
+
+    /// Free the execution slot held by `ticket`
+    pub fn complete(&self, ticket: u64) -> bool {
+        let freed = self.lock().in_flight.remove(&ticket).is_some();
+        if freed {
+            self.released.notify_waiters();
+        } else {
+            warn!("Completed unknown scheduler ticket {}", ticket);
+        }
+        freed
+    }
+
+    /// Remove and return pending intents whose deadline has passed
+    pub fn take_expired(&self, now: i64) -> Vec<ScheduledIntent> {
+        let mut state = self.lock();
+        let (expired, pending) = std::mem::take(&mut state.pending)
+            .into_iter()
+            .partition(|entry| entry.is_expired(now));
+        state.pending = pending;
+        if !expired.is_empty() {
+            self.space.notify_waiters();
+        }
+        expired
+    }
+
+    pub fn pending(&self) -> usize {
+        self.lock().pending.len()
+    }
+
+    /// Pending-queue depth and admission counters (`shed` is always 0)
+    pub fn queue_stats(&self) -> QueueStats {
+        let max_pending = self.settings().max_pending;
+        let state = self.lock();
+        QueueStats {
+            capacity: max_pending,
+            depth: state.pending.len(),
+            ..state.stats
+        }
+    }
+
+    pub fn in_flight(&self, priority: Priority) -> usize {
+        self.lock().in_flight(priority)
+    }
+}
+
+/// Larger keys run first
+fn order_key(
+    entry: &ScheduledIntent,
+    settings: &SchedulerSettings,
+    now: i64,
+) -> (usize, bool, Reverse<i64>, u32, Reverse<u64>) {
+    let waited = now.saturating_sub(entry.enqueued_at).max(0) as u64;
+    let boost = waited.checked_div(settings.aging_secs).unwrap_or(0) as usize;
+    let effective = (level(entry.priority) + boost).min(LEVELS.len() - 1);
+
+    let deadline = entry.deadline.unwrap_or(i64::MAX);
+    let urgent = deadline.saturating_sub(now) <= settings.urgent_within_secs;
+
+    // Risk scores are within 0-1; fixed-point keeps the key totally ordered
+    let risk = (entry.risk_score.clamp(0.0, 1.0) * 1_000_000.0) as u32;
+
+    (effective, urgent, Reverse(deadline), risk, Reverse(entry.ticket))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::intent::{Constraints, FeePreferences};
+    use std::sync::Arc;
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Fee totals: Low <= 10k, Medium <= 50k, High <= 200k, Critical above
+    fn intent(id: &str, fee_lamports: u64, expiry: Option<i64>) -> Intent {
+        Intent {
+            intent_id: id.to_string(),
+            swap_details: None,
+            constraints: Constraints {
+                expiry_timestamp: expiry,
+                ..Constraints::default()
+            },
+            fee_preferences: FeePreferences {
+                max_priority_fee_lamports: fee_lamports,
+                max_jito_tip_lamports: 0,
+                ..FeePreferences::default()
+            },
+            ..Intent::test_swap()
+        }
+    }
+
+    fn next_id(scheduler: &ExecutionScheduler, now: i64) -> Option<String> {
+        scheduler.try_next(now).map(|entry| entry.intent.intent_id)
+    }
+
+    #[test]
+    fn test_priority_then_urgency_then_risk() {
+        let scheduler = ExecutionScheduler::new(SchedulerSettings::default());
+        scheduler.enqueue(intent("low", 5_000, None), 0.9, NOW);
+        scheduler.enqueue(intent("critical", 500_000, Some(NOW + 600)), 0.1, NOW);
+        scheduler.enqueue(intent("critical-expiring", 500_000, Some(NOW + 10)), 0.1, NOW);
+        scheduler.enqueue(intent("high-risky", 100_000, None), 0.9, NOW);
+        scheduler.enqueue(intent("high", 100_000, None), 0.2, NOW);
+
+        let order: Vec<_> = std::iter::from_fn(|| next_id(&scheduler, NOW)).collect();
+        assert_eq!(order, ["critical-expiring", "critical", "high-risky", "high", "low"]);
+    }
+
+    #[test]
+    fn test_per_priority_concurrency_limit() {
+        let scheduler = ExecutionScheduler::new(SchedulerSettings {
+            max_in_flight_high: 1,
+            ..SchedulerSettings::default()
+        });
+        scheduler.enqueue(intent("high-1", 100_000, None), 0.5, NOW);
+        scheduler.enqueue(intent("high-2", 100_000, None), 0.5, NOW);
+        scheduler.enqueue(intent("medium", 20_000, None), 0.5, NOW);
+
+        let first = scheduler.try_next(NOW).unwrap();
+        assert_eq!(first.intent.intent_id, "high-1");
+        // High is saturated, so Medium runs rather than waiting
+        assert_eq!(next_id(&scheduler, NOW).as_deref(), Some("medium"));
+        assert_eq!(next_id(&scheduler, NOW), None);
+
+        assert!(scheduler.complete(first.ticket));
+        assert!(!scheduler.complete(first.ticket));
+        assert_eq!(next_id(&scheduler, NOW).as_deref(), Some("high-2"));
+    }
+
+    #[test]
+    fn test_aging_prevents_starvation() {
+        let scheduler = ExecutionScheduler::new(SchedulerSettings::default());
+        scheduler.enqueue(intent("old-low", 5_000, None), 0.0, NOW);
+        // Two aging steps later a fresh Medium intent arrives
+        let later = NOW + 20;
+        scheduler.enqueue(intent("new-medium", 20_000, None), 0.0, later);
+
+        assert_eq!(next_id(&scheduler, later).as_deref(), Some("old-low"));
+        assert_eq!(scheduler.in_flight(Priority::Low), 1);
+        assert_eq!(scheduler.in_flight(Priority::High), 0);
+    }
+
+    #[test]
+    fn test_expired_intents_are_not_released() {
+        let scheduler = ExecutionScheduler::new(SchedulerSettings::default());
+        scheduler.enqueue(intent("stale", 500_000, Some(NOW + 5)), 0.5, NOW);
+        scheduler.enqueue(intent("fresh", 5_000, None), 0.5, NOW);
+
+        assert_eq!(next_id(&scheduler, NOW + 5).as_deref(), Some("fresh"));
+        let expired = scheduler.take_expired(NOW + 5);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].intent.intent_id, "stale");
+        assert_eq!(scheduler.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_next_waits_for_capacity() {
+        let scheduler = Arc::new(
+            ExecutionScheduler::new(SchedulerSettings {
+                max_in_flight_low: 1,
+                ..SchedulerSettings::default()
+            })
+            .with_clock(Arc::new(ManualClock::at_timestamp(NOW))),
+        );
+        scheduler.enqueue(intent("a", 5_000, Some(NOW + 60)), 0.5, NOW);
+        scheduler.enqueue(intent("b", 5_000, Some(NOW + 60)), 0.5, NOW);
+
+        let first = scheduler.next().await;
+        let waiter = tokio::spawn({
+            let scheduler = Arc::clone(&scheduler);
+            async move { scheduler.next().await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        scheduler.complete(first.ticket);
+        let second = tokio::time::timeout(std::time::Duration::from_secs(5), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.intent.intent_id, "b");
+    }
+
+    #[tokio::test]
+    async fn test_admit_holds_when_full_and_never_drops() {
+        let scheduler = Arc::new(
+            ExecutionScheduler::new(SchedulerSettings {
+                max_pending: 2,
+                ..SchedulerSettings::default()
+            })
+            .with_clock(Arc::new(ManualClock::at_timestamp(NOW))),
+        );
+        scheduler.admit(intent("a", 5_000, None), 0.5).await;
+        scheduler.admit(intent("b", 5_000, None), 0.5).await;
+        let refused = scheduler.try_admit(intent("c", 5_000, None), 0.5, NOW).unwrap_err();
+        assert_eq!(refused.intent_id, "c");
+
+        let waiter = tokio::spawn({
+            let scheduler = Arc::clone(&scheduler);
+            async move { scheduler.admit(intent("c", 5_000, None), 0.5).await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        scheduler.try_next(NOW).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(5), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let stats = scheduler.queue_stats();
+        assert_eq!((stats.capacity, stats.depth, stats.high_water_mark), (2, 2, 2));
+        assert_eq!((stats.enqueued, stats.dequeued, stats.blocked, stats.shed), (3, 1, 2, 0));
+    }
+}
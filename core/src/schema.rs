@@ -0,0 +1,65 @@
+//! Intent JSON Schema
+//!
+//! Generated from the [`Intent`] type itself, so it can't drift from what the
+//! router deserializes. Served at [`INTENT_SCHEMA_PATH`] for integrators to
+//! validate payloads client-side before signing.
+//!
+//! Pubkeys appear as 32-byte arrays (their serde encoding); the recent
+//! blockhash and nonce are base58 strings.
+
+use schemars::schema::RootSchema;
+
+use crate::intent::{Intent, INTENT_SCHEMA_VERSION};
+
+/// Path the schema is served at; the suffix tracks [`INTENT_SCHEMA_VERSION`]
+pub const INTENT_SCHEMA_PATH: &str = "/schema/intent/v1";
+
+/// JSON Schema (draft-07) of the intent payload
+pub fn intent_schema() -> RootSchema {
+    let mut schema = schemars::schema_for!(Intent);
+    let metadata = schema.schema.metadata();
+    metadata.id = Some(INTENT_SCHEMA_PATH.to_string());
+    metadata.title = Some(format!("Sentinel Router intent (schema v{})", INTENT_SCHEMA_VERSION));
+    schema
+}
+
+/// [`intent_schema`] as pretty-printed JSON
+pub fn intent_schema_json() -> String {
+    serde_json::to_string_pretty(&intent_schema()).expect("Intent schema serialization failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_schema_path_tracks_version() {
+        assert!(INTENT_SCHEMA_PATH.ends_with(&format!("/v{}", INTENT_SCHEMA_VERSION)));
+    }
+
+    #[test]
+    fn test_intent_schema_shape() {
+        let schema: Value = serde_json::from_str(&intent_schema_json()).unwrap();
+        assert_eq!(schema["$id"], INTENT_SCHEMA_PATH);
+
+        let required: Vec<&str> = schema["required"].as_array().unwrap().iter().filter_map(Value::as_str).collect();
+        for field in ["intent_id", "user_public_key", "intent_type", "constraints", "fee_preferences", "consent_block"] {
+            assert!(required.contains(&field), "{} should be required", field);
+        }
+        // Defaulted fields stay optional so older payloads validate
+        for field in ["schema_version", "legs", "dca_details"] {
+            assert!(!required.contains(&field), "{} should be optional", field);
+        }
+
+        let pubkey = &schema["properties"]["user_public_key"];
+        assert_eq!(pubkey["type"], "array");
+        assert_eq!((pubkey["minItems"].as_u64(), pubkey["maxItems"].as_u64()), (Some(32), Some(32)));
+
+        let definitions = schema["definitions"].as_object().unwrap();
+        for name in ["SwapDetails", "IntentLeg", "DepositDetails", "ConsentBlock", "IntentType"] {
+            assert!(definitions.contains_key(name), "missing definition {}", name);
+        }
+        assert_eq!(definitions["ConsentBlock"]["properties"]["recent_blockhash"]["type"], "string");
+    }
+}
@@ -0,0 +1,562 @@
+//! Sanctioned-Counterparty Address Screening
+//!
+//! Optional compliance hook run before an intent executes. Every address the
+//! intent moves value for is screened by each [`ScreeningProvider`]:
+//! - the intent's `user_public_key`
+//! - output destinations: the user's associated token account of each swap's
+//!   output mint, and the vault of each deposit leg
+//!
+//! Bundled providers are [`DenyListScreening`] (`deny_list` plus
+//! `deny_list_path`) and [`WebhookScreening`] (`webhook_url`, an external
+//! screening service); others plug in with [`AddressScreener::with_provider`].
+//!
+//! In `monitor` mode hits are logged and reported but the intent proceeds. In
+//! `block` mode a hit rejects it with `SentinelError::PolicyRejected`
+//! (`sanctioned_address`, the flagged address as subject); so does a provider
+//! failure when `fail_closed` is set (`screening_unavailable`). Otherwise
+//! provider failures are reported and the intent proceeds.
+//!
+//! The webhook receives `{"addresses": [{"address", "role"}]}` and answers
+//! `{"hits": [{"address", "reason"}]}`; only requested addresses count.
+
+use futures_util::future::{join_all, BoxFuture, FutureExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::{ScreeningMode, ScreeningSettings, SentinelConfig};
+use crate::intent::{Intent, IntentLeg};
+use crate::whirlpool::associated_token_address;
+use crate::{Result, SentinelError};
+
+/// Why an address is screened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressRole {
+    User,
+    OutputDestination,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScreenedAddress {
+    pub address: Pubkey,
+    pub role: AddressRole,
+}
+
+/// An address a provider flagged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreeningHit {
+    pub address: Pubkey,
+    pub role: AddressRole,
+    pub provider: String,
+    pub reason: String,
+}
+
+/// Screening result of one intent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreeningReport {
+    pub intent_id: String,
+    pub mode: ScreeningMode,
+    pub screened: Vec<ScreenedAddress>,
+    pub hits: Vec<ScreeningHit>,
+
+    /// Providers that failed, with their error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl ScreeningReport {
+    pub fn is_clean(&self) -> bool {
+        self.hits.is_empty()
+    }
+}
+
+/// Source of sanctioned / denied addresses
+pub trait ScreeningProvider: Send + Sync {
+    /// Short identifier for logs and hits
+    fn name(&self) -> &'static str;
+
+    /// Hits among `addresses`
+    fn screen<'a>(&'a self, addresses: &'a [ScreenedAddress]) -> BoxFuture<'a, Result<Vec<ScreeningHit>>>;
+}
+
+/// Static deny list
+#[derive(Debug, Clone, Default)]
+pub struct DenyListScreening {
+    denied: HashSet<Pubkey>,
+}
+
+impl DenyListScreening {
+    pub fn new(denied: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self {
+            denied: denied.into_iter().collect(),
+        }
+    }
+
+    /// `deny_list` plus the addresses in `deny_list_path`
+    ///
+    /// An unreadable file is logged and skipped, leaving the inline list.
+    pub fn from_settings(settings: &ScreeningSettings) -> Self {
+        // Config validation rejects invalid keys
+        let mut denied: HashSet<Pubkey> = settings.deny_list.iter().filter_map(|k| Pubkey::from_str(k).ok()).collect();
+        if let Some(ref path) = settings.deny_list_path {
+            match load_deny_list(path) {
+                Ok(keys) => denied.extend(keys),
+                Err(e) => warn!("Screening deny list not loaded: {}", e),
+            }
+        }
+        Self { denied }
+    }
+
+    pub fn len(&self) -> usize {
+        self.denied.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.denied.is_empty()
+    }
+}
+
+/// Addresses of a deny-list file: one per line, `#` starts a comment
+pub fn load_deny_list(path: impl AsRef<Path>) -> Result<Vec<Pubkey>> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| SentinelError::IoError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            Pubkey::from_str(line).map_err(|_| {
+                SentinelError::ParseError(format!("Invalid address in {}: {}", path.display(), line))
+            })
+        })
+        .collect()
+}
+
+impl ScreeningProvider for DenyListScreening {
+    fn name(&self) -> &'static str {
+        "deny_list"
+    }
+
+    fn screen<'a>(&'a self, addresses: &'a [ScreenedAddress]) -> BoxFuture<'a, Result<Vec<ScreeningHit>>> {
+        let hits = addresses
+            .iter()
+            .filter(|screened| self.denied.contains(&screened.address))
+            .map(|screened| ScreeningHit {
+                address: screened.address,
+                role: screened.role,
+                provider: self.name().to_string(),
+                reason: "on the deny list".to_string(),
+            })
+            .collect();
+        futures_util::future::ready(Ok(hits)).boxed()
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookRequest<'a> {
+    addresses: Vec<WebhookAddress<'a>>,
+}
+
+#[derive(Serialize)]
+struct WebhookAddress<'a> {
+    address: String,
+    role: &'a AddressRole,
+}
+
+#[derive(Deserialize)]
+struct WebhookResponse {
+    #[serde(default)]
+    hits: Vec<WebhookHit>,
+}
+
+#[derive(Deserialize)]
+struct WebhookHit {
+    address: String,
+    #[serde(default)]
+    reason: String,
+}
+
+/// External screening service reached over HTTP
+#[derive(Debug, Clone)]
+pub struct WebhookScreening {
+    http_client: Client,
+    url: String,
+}
+
+impl WebhookScreening {
+    pub fn new(url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+        Ok(Self {
+            http_client,
+            url: url.into(),
+        })
+    }
+
+    async fn call(&self, addresses: &[ScreenedAddress]) -> Result<Vec<ScreeningHit>> {
+        let request = WebhookRequest {
+            addresses: addresses
+                .iter()
+                .map(|screened| WebhookAddress {
+                    address: screened.address.to_string(),
+                    role: &screened.role,
+                })
+                .collect(),
+        };
+        let response = self
+            .http_client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Screening webhook failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(SentinelError::NetworkError(format!(
+                "Screening webhook returned {}",
+                response.status()
+            )));
+        }
+        let response: WebhookResponse = response
+            .json()
+            .await
+            .map_err(|e| SentinelError::ParseError(format!("Invalid screening webhook response: {}", e)))?;
+
+        let requested: HashMap<String, &ScreenedAddress> =
+            addresses.iter().map(|screened| (screened.address.to_string(), screened)).collect();
+        Ok(response
+            .hits
+            .into_iter()
+            .filter_map(|hit| {
+                let screened = requested.get(&hit.address)?;
+                Some(ScreeningHit {
+                    address: screened.address,
+                    role: screened.role,
+                    provider: self.name().to_string(),
+                    reason: hit.reason,
+                })
+            })
+            .collect())
+    }
+}
+
+impl ScreeningProvider for WebhookScreening {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn screen<'a>(&'a self, addresses: &'a [ScreenedAddress]) -> BoxFuture<'a, Result<Vec<ScreeningHit>>> {
+        self.call(addresses).boxed()
+    }
+}
+
+/// Settings with the providers built from them
+struct ScreenerState {
+    settings: ScreeningSettings,
+    providers: Vec<Arc<dyn ScreeningProvider>>,
+}
+
+impl ScreenerState {
+    fn from_settings(settings: ScreeningSettings) -> Self {
+        let mut providers: Vec<Arc<dyn ScreeningProvider>> = Vec::new();
+        let deny_list = DenyListScreening::from_settings(&settings);
+        if !deny_list.is_empty() {
+            providers.push(Arc::new(deny_list));
+        }
+        if let Some(ref url) = settings.webhook_url {
+            match WebhookScreening::new(url.clone(), Duration::from_millis(settings.webhook_timeout_ms)) {
+                Ok(webhook) => providers.push(Arc::new(webhook)),
+                Err(e) => warn!("Screening webhook disabled: {}", e),
+            }
+        }
+        Self { settings, providers }
+    }
+}
+
+/// Screens intents against the configured providers
+pub struct AddressScreener {
+    state: RwLock<Arc<ScreenerState>>,
+
+    /// Providers added in code; kept across reloads
+    custom: Vec<Arc<dyn ScreeningProvider>>,
+}
+
+impl AddressScreener {
+    pub fn new(settings: ScreeningSettings) -> Self {
+        Self {
+            state: RwLock::new(Arc::new(ScreenerState::from_settings(settings))),
+            custom: Vec::new(),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.screening.clone())
+    }
+
+    /// Screen with `provider` too (while screening is enabled)
+    pub fn with_provider(mut self, provider: Arc<dyn ScreeningProvider>) -> Self {
+        self.custom.push(provider);
+        self
+    }
+
+    /// Apply the hot-reloadable `[screening]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        let state = ScreenerState::from_settings(config.screening.clone());
+        info!(
+            "Screening updated: enabled={}, mode={:?}, {} providers",
+            state.settings.enabled,
+            state.settings.mode,
+            state.providers.len() + self.custom.len()
+        );
+        *self.state.write().unwrap_or_else(|p| p.into_inner()) = Arc::new(state);
+    }
+
+    /// Addresses of `intent` subject to screening, user first
+    pub fn addresses(intent: &Intent) -> Vec<ScreenedAddress> {
+        let user = intent.user_public_key;
+        let mut addresses = vec![ScreenedAddress {
+            address: user,
+            role: AddressRole::User,
+        }];
+
+        let swaps = intent.swap_details.iter().chain(intent.legs.iter().filter_map(|leg| match leg {
+            IntentLeg::Swap(swap) => Some(swap),
+            IntentLeg::Deposit(_) => None,
+        }));
+        let destinations = swaps.map(|swap| associated_token_address(&user, &swap.output_mint)).chain(
+            intent.legs.iter().filter_map(|leg| match leg {
+                IntentLeg::Deposit(deposit) => Some(deposit.vault),
+                IntentLeg::Swap(_) => None,
+            }),
+        );
+        for address in destinations {
+            if !addresses.iter().any(|screened| screened.address == address) {
+                addresses.push(ScreenedAddress {
+                    address,
+                    role: AddressRole::OutputDestination,
+                });
+            }
+        }
+        addresses
+    }
+
+    /// Screen `intent`; in block mode a hit (or, fail-closed, a provider error) rejects it
+    pub async fn screen_intent(&self, intent: &Intent) -> Result<ScreeningReport> {
+        let state = Arc::clone(&self.state.read().unwrap_or_else(|p| p.into_inner()));
+        let settings = &state.settings;
+        let mut report = ScreeningReport {
+            intent_id: intent.intent_id.clone(),
+            mode: settings.mode,
+            screened: Vec::new(),
+            hits: Vec::new(),
+            errors: Vec::new(),
+        };
+        if !settings.enabled {
+            return Ok(report);
+        }
+
+        report.screened = Self::addresses(intent);
+        let providers: Vec<&Arc<dyn ScreeningProvider>> = state.providers.iter().chain(&self.custom).collect();
+        let results = join_all(providers.iter().map(|provider| provider.screen(&report.screened))).await;
+        for (provider, result) in providers.iter().zip(results) {
+            match result {
+                Ok(hits) => report.hits.extend(hits),
+                Err(e) => {
+                    warn!("Screening provider {} failed for {}: {}", provider.name(), intent.intent_id, e);
+                    report.errors.push(format!("{}: {}", provider.name(), e));
+                }
+            }
+        }
+
+        for hit in &report.hits {
+            warn!(
+                "🚫 Screening hit for intent {}: {:?} {} flagged by {} ({})",
+                intent.intent_id, hit.role, hit.address, hit.provider, hit.reason
+            );
+        }
+        if settings.mode == ScreeningMode::Monitor {
+            return Ok(report);
+        }
+
+        if let Some(hit) = report.hits.first() {
+            return Err(SentinelError::PolicyRejected {
+                rule: "sanctioned_address".to_string(),
+                subject: hit.address.to_string(),
+                reason: format!("{:?} address flagged by {}: {}", hit.role, hit.provider, hit.reason),
+            });
+        }
+        if settings.fail_closed && !report.errors.is_empty() {
+            return Err(SentinelError::PolicyRejected {
+                rule: "screening_unavailable".to_string(),
+                subject: intent.intent_id.clone(),
+                reason: report.errors.join("; "),
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::{DepositDetails, SwapDetails, SwapMode};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn swap(output_mint: Pubkey) -> SwapDetails {
+        SwapDetails {
+            mode: SwapMode::ExactIn,
+            input_mint: Pubkey::new_unique(),
+            output_mint,
+            amount: 1_000_000,
+            minimum_received: None,
+            dex: None,
+            route_hints: None,
+        }
+    }
+
+    fn intent(user: Pubkey, output_mint: Pubkey) -> Intent {
+        Intent {
+            intent_id: "intent-1".to_string(),
+            user_public_key: user,
+            swap_details: Some(swap(output_mint)),
+            ..Intent::test_swap()
+        }
+    }
+
+    fn settings(mode: ScreeningMode, denied: &[Pubkey]) -> ScreeningSettings {
+        ScreeningSettings {
+            enabled: true,
+            mode,
+            deny_list: denied.iter().map(Pubkey::to_string).collect(),
+            ..ScreeningSettings::default()
+        }
+    }
+
+    /// Always fails, like an unreachable service
+    struct Unavailable;
+
+    impl ScreeningProvider for Unavailable {
+        fn name(&self) -> &'static str {
+            "unavailable"
+        }
+
+        fn screen<'a>(&'a self, _addresses: &'a [ScreenedAddress]) -> BoxFuture<'a, Result<Vec<ScreeningHit>>> {
+            futures_util::future::ready(Err(SentinelError::NetworkError("down".to_string()))).boxed()
+        }
+    }
+
+    #[test]
+    fn test_addresses_cover_user_and_destinations() {
+        let user = Pubkey::new_unique();
+        let mut intent = intent(user, Pubkey::new_unique());
+        let vault = Pubkey::new_unique();
+        intent.legs = vec![
+            IntentLeg::Swap(swap(Pubkey::new_unique())),
+            IntentLeg::Deposit(DepositDetails {
+                program_id: Pubkey::new_unique(),
+                vault,
+                mint: Pubkey::new_unique(),
+                amount: None,
+            }),
+        ];
+
+        let addresses = AddressScreener::addresses(&intent);
+        assert_eq!(addresses.len(), 4);
+        assert_eq!(addresses[0], ScreenedAddress { address: user, role: AddressRole::User });
+        let output_ata = associated_token_address(&user, &intent.swap_details.as_ref().unwrap().output_mint);
+        assert_eq!(addresses[1].address, output_ata);
+        assert!(addresses.iter().any(|a| a.address == vault && a.role == AddressRole::OutputDestination));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_reports_block_rejects() {
+        let user = Pubkey::new_unique();
+        let intent = intent(user, Pubkey::new_unique());
+
+        let monitor = AddressScreener::new(settings(ScreeningMode::Monitor, &[user]));
+        let report = monitor.screen_intent(&intent).await.unwrap();
+        assert_eq!(report.hits.len(), 1);
+        assert_eq!(report.hits[0].role, AddressRole::User);
+
+        let screener = AddressScreener::new(settings(ScreeningMode::Block, &[user]));
+        let result = screener.screen_intent(&intent).await;
+        assert!(matches!(result, Err(SentinelError::PolicyRejected { ref rule, .. }) if rule == "sanctioned_address"));
+
+        // Clean intents pass; disabled screening screens nothing
+        let clean = AddressScreener::new(settings(ScreeningMode::Block, &[Pubkey::new_unique()]));
+        assert!(clean.screen_intent(&intent).await.unwrap().is_clean());
+        let disabled = AddressScreener::new(ScreeningSettings {
+            enabled: false,
+            ..settings(ScreeningMode::Block, &[user])
+        });
+        assert!(disabled.screen_intent(&intent).await.unwrap().screened.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_provider_failure_fails_open_unless_configured() {
+        let intent = intent(Pubkey::new_unique(), Pubkey::new_unique());
+
+        let open = AddressScreener::new(settings(ScreeningMode::Block, &[])).with_provider(Arc::new(Unavailable));
+        let report = open.screen_intent(&intent).await.unwrap();
+        assert_eq!(report.errors.len(), 1);
+
+        let closed = AddressScreener::new(ScreeningSettings {
+            fail_closed: true,
+            ..settings(ScreeningMode::Block, &[])
+        })
+        .with_provider(Arc::new(Unavailable));
+        match closed.screen_intent(&intent).await {
+            Err(SentinelError::PolicyRejected { rule, .. }) => assert_eq!(rule, "screening_unavailable"),
+            other => panic!("expected rejection, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_hits_and_deny_list_file() {
+        let user = Pubkey::new_unique();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/screen", listener.local_addr().unwrap());
+        let body = serde_json::json!({ "hits": [
+            { "address": user.to_string(), "reason": "OFAC SDN" },
+            { "address": Pubkey::new_unique().to_string(), "reason": "not requested" }
+        ]})
+        .to_string();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let path = std::env::temp_dir().join(format!("deny-{}.txt", uuid::Uuid::new_v4()));
+        let listed = Pubkey::new_unique();
+        std::fs::write(&path, format!("# sanctioned\n{}  # wallet\n\n", listed)).unwrap();
+        assert_eq!(load_deny_list(&path).unwrap(), vec![listed]);
+
+        let screener = AddressScreener::new(ScreeningSettings {
+            webhook_url: Some(url),
+            deny_list_path: Some(path.clone()),
+            ..settings(ScreeningMode::Monitor, &[])
+        });
+        let report = screener.screen_intent(&intent(user, Pubkey::new_unique())).await.unwrap();
+        assert_eq!(report.hits.len(), 1);
+        assert_eq!((report.hits[0].provider.as_str(), report.hits[0].reason.as_str()), ("webhook", "OFAC SDN"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
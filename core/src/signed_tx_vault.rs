@@ -0,0 +1,501 @@
+//! Pre-Signed Transaction Vault
+//!
+//! Offline signing flows (DCA executions, limit triggers, TWAP children) have
+//! the wallet sign each transaction up front against a durable nonce; the
+//! router holds it until the execution condition fires. The
+//! [`SignedTxVault`] keeps those transactions:
+//! - encrypted at rest (ChaCha20-Poly1305, random nonce per entry, intent id
+//!   and owner bound as associated data so ciphertexts cannot be swapped)
+//! - keyed by `intent_id`: one deposit per intent, child intents use their own
+//!   id (`<intent_id>-<n>` for DCA orders)
+//! - released at most once: the entry is marked released and its ciphertext
+//!   dropped before the transaction is handed out, so a crash mid-release
+//!   loses the transaction instead of sending it twice
+//! - until the intent's expiry (or `max_hold_secs`), after which it can no
+//!   longer be released and [`SignedTxVault::sweep_expired`] deletes it
+//!
+//! Entries live in a [`KvStore`] namespace (`signed_tx_vault`), so the vault
+//! needs a backend with KV support. Calls block, like the store. When several
+//! router instances share the store, attach their [`Coordination`] so a
+//! release is also recorded in the shared replay ledger: the write lock only
+//! serializes releases within one process.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+use crate::clock::{system_clock, SharedClock};
+use crate::coordination::Coordination;
+use crate::intent::{Intent, IntentError};
+use crate::storage::{KvStore, Storage};
+use crate::{Result, SentinelError};
+
+/// KV namespace of vault entries
+pub const VAULT_NAMESPACE: &str = "signed_tx_vault";
+
+/// Longest a transaction is held when its intent sets no expiry (7 days)
+pub const DEFAULT_MAX_HOLD_SECS: i64 = 7 * 86_400;
+
+/// `SystemInstruction::AdvanceNonceAccount` discriminant
+const ADVANCE_NONCE_ACCOUNT: u32 = 4;
+
+/// Domain separation for the associated data
+const AAD_PREFIX: &[u8] = b"sentinel-signed-tx-v1";
+
+/// Lifecycle of a vault entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultStatus {
+    Held,
+    Released,
+    Cancelled,
+}
+
+/// Encrypted transaction (base64 nonce and ciphertext + tag)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedTransaction {
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// One stored transaction; the ciphertext is dropped once it leaves `Held`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultEntry {
+    pub intent_id: String,
+    pub user: Pubkey,
+    pub status: VaultStatus,
+    pub deposited_at: i64,
+    /// Unix seconds after which the transaction is not released
+    pub expires_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub released_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<SealedTransaction>,
+}
+
+/// Encrypted, one-time-release store of user-signed transactions
+pub struct SignedTxVault {
+    kv: Arc<dyn KvStore>,
+    cipher: ChaCha20Poly1305,
+    max_hold_secs: i64,
+    clock: SharedClock,
+    coordination: Option<Arc<Coordination>>,
+    // Serializes read-modify-write so a release cannot race another release
+    write: Mutex<()>,
+}
+
+impl SignedTxVault {
+    /// Vault over `kv`, encrypting with the 32-byte `key`
+    pub fn new(kv: Arc<dyn KvStore>, key: [u8; 32]) -> Self {
+        Self {
+            kv,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            max_hold_secs: DEFAULT_MAX_HOLD_SECS,
+            clock: system_clock(),
+            coordination: None,
+            write: Mutex::new(()),
+        }
+    }
+
+    /// Vault in the storage backend's `signed_tx_vault` namespace
+    pub fn from_storage(storage: &Storage, key: [u8; 32]) -> Result<Self> {
+        Ok(Self::new(storage.kv(VAULT_NAMESPACE)?, key))
+    }
+
+    /// Cap on how long any transaction is held
+    pub fn with_max_hold_secs(mut self, max_hold_secs: i64) -> Self {
+        self.max_hold_secs = max_hold_secs;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Make releases exactly-once across instances sharing the store
+    pub fn with_coordination(mut self, coordination: Arc<Coordination>) -> Self {
+        self.coordination = Some(coordination);
+        self
+    }
+
+    /// Store `transaction`, signed by the intent's user, until the intent is executed
+    ///
+    /// The transaction must be fully signed and start with `AdvanceNonceAccount`,
+    /// since a recent blockhash would expire long before a limit or schedule
+    /// fires. When the intent names its nonce (`consent_block.nonce`) the
+    /// transaction must be built on it. Each held transaction needs its own
+    /// nonce account: the nonce advances when one lands.
+    pub fn deposit(&self, intent: &Intent, transaction: &Transaction) -> Result<VaultEntry> {
+        let now = self.clock.unix_timestamp();
+        check_transaction(intent, transaction)?;
+
+        let expires_at = match (intent.constraints.expiry_timestamp, intent.constraints.ttl_seconds) {
+            (Some(expiry), _) => expiry,
+            (None, Some(ttl)) => now + i64::from(ttl),
+            (None, None) => now + self.max_hold_secs,
+        }
+        .min(now + self.max_hold_secs);
+        if expires_at <= now {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Intent {} has already expired",
+                intent.intent_id
+            )));
+        }
+
+        let plaintext =
+            bincode::serialize(transaction).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(&intent.intent_id, &intent.user_public_key);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: &plaintext, aad: &aad })
+            .map_err(|_| SentinelError::EnvelopeError("Failed to encrypt signed transaction".to_string()))?;
+
+        let entry = VaultEntry {
+            intent_id: intent.intent_id.clone(),
+            user: intent.user_public_key,
+            status: VaultStatus::Held,
+            deposited_at: now,
+            expires_at,
+            released_at: None,
+            sealed: Some(SealedTransaction {
+                nonce: BASE64.encode(nonce),
+                ciphertext: BASE64.encode(ciphertext),
+            }),
+        };
+
+        let _write = self.lock();
+        // Tombstones of released intents stay until expiry, so an intent is held at most once
+        if self.get(&entry.intent_id)?.is_some() {
+            return Err(SentinelError::InvalidIntent(format!(
+                "A signed transaction for intent {} was already deposited",
+                entry.intent_id
+            )));
+        }
+        self.store(&entry)?;
+        debug!("🔐 Holding signed transaction for {} until {}", entry.intent_id, expires_at);
+        Ok(entry)
+    }
+
+    /// Entry metadata (and ciphertext while held)
+    pub fn get(&self, intent_id: &str) -> Result<Option<VaultEntry>> {
+        self.kv
+            .get(intent_id.as_bytes())?
+            .map(|value| serde_json::from_slice(&value).map_err(|e| SentinelError::SerializationError(e.to_string())))
+            .transpose()
+    }
+
+    /// Hand out the held transaction for `intent_id`, exactly once
+    pub fn release(&self, intent_id: &str) -> Result<Transaction> {
+        let now = self.clock.unix_timestamp();
+        let _write = self.lock();
+        let mut entry = self
+            .get(intent_id)?
+            .ok_or_else(|| SentinelError::InvalidIntent(format!("No signed transaction held for {}", intent_id)))?;
+
+        let sealed = match (entry.status, entry.sealed.take()) {
+            (VaultStatus::Held, Some(sealed)) if now < entry.expires_at => sealed,
+            (VaultStatus::Held, _) => {
+                return Err(SentinelError::InvalidIntent(format!(
+                    "Signed transaction for {} expired at {}",
+                    intent_id, entry.expires_at
+                )))
+            }
+            (status, _) => {
+                return Err(SentinelError::InvalidIntent(format!(
+                    "Signed transaction for {} is {:?}",
+                    intent_id, status
+                )))
+            }
+        };
+        let transaction = self.open(&entry, &sealed)?;
+
+        if let Some(ref coordination) = self.coordination {
+            if !coordination.record_once(&format!("vault-release:{}", intent_id))? {
+                return Err(SentinelError::InvalidIntent(format!(
+                    "Signed transaction for {} was released by another instance",
+                    intent_id
+                )));
+            }
+        }
+
+        entry.status = VaultStatus::Released;
+        entry.released_at = Some(now);
+        self.store(&entry)?;
+        info!("🔓 Released signed transaction for {}", intent_id);
+        Ok(transaction)
+    }
+
+    /// Withdraw a held transaction on the user's behalf
+    pub fn cancel(&self, intent_id: &str, user: &Pubkey) -> Result<VaultEntry> {
+        let _write = self.lock();
+        let mut entry = self
+            .get(intent_id)?
+            .ok_or_else(|| SentinelError::InvalidIntent(format!("No signed transaction held for {}", intent_id)))?;
+        if entry.user != *user {
+            return Err(SentinelError::Unauthorized(format!(
+                "Signed transaction for {} belongs to another wallet",
+                intent_id
+            )));
+        }
+        if entry.status == VaultStatus::Held {
+            entry.status = VaultStatus::Cancelled;
+            entry.sealed = None;
+            self.store(&entry)?;
+        }
+        Ok(entry)
+    }
+
+    /// Delete every entry past its expiry (held, released or cancelled)
+    pub fn sweep_expired(&self) -> Result<usize> {
+        let now = self.clock.unix_timestamp();
+        let _write = self.lock();
+        let mut expired = Vec::new();
+        self.kv.scan_from(&[], &mut |key, value| {
+            if serde_json::from_slice::<VaultEntry>(value).is_ok_and(|entry| entry.expires_at <= now) {
+                expired.push(key.to_vec());
+            }
+            Ok(true)
+        })?;
+        for key in &expired {
+            self.kv.delete(key)?;
+        }
+        if !expired.is_empty() {
+            self.kv.flush()?;
+            debug!("🔐 Swept {} expired vault entries", expired.len());
+        }
+        Ok(expired.len())
+    }
+
+    fn open(&self, entry: &VaultEntry, sealed: &SealedTransaction) -> Result<Transaction> {
+        let corrupt = || SentinelError::EnvelopeError(format!("Vault entry for {} is corrupt", entry.intent_id));
+        let nonce = BASE64.decode(&sealed.nonce).map_err(|_| corrupt())?;
+        let ciphertext = BASE64.decode(&sealed.ciphertext).map_err(|_| corrupt())?;
+        if nonce.len() != 12 {
+            return Err(corrupt());
+        }
+
+        let aad = associated_data(&entry.intent_id, &entry.user);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| {
+                SentinelError::EnvelopeError(format!("Failed to decrypt signed transaction for {}", entry.intent_id))
+            })?;
+        bincode::deserialize(&plaintext).map_err(|_| corrupt())
+    }
+
+    fn store(&self, entry: &VaultEntry) -> Result<()> {
+        let value = serde_json::to_vec(entry).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        self.kv.put(entry.intent_id.as_bytes(), &value)?;
+        self.kv.flush()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.write.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// Signed by the intent's user and built on a durable nonce
+fn check_transaction(intent: &Intent, transaction: &Transaction) -> Result<()> {
+    let message = &transaction.message;
+    let signers = usize::from(message.header.num_required_signatures).min(message.account_keys.len());
+    if !message.account_keys[..signers].contains(&intent.user_public_key) {
+        return Err(SentinelError::InvalidIntent(format!(
+            "Transaction for {} is not signed by the intent's user",
+            intent.intent_id
+        )));
+    }
+    transaction
+        .verify()
+        .map_err(|e| SentinelError::InvalidIntent(format!("Signed transaction invalid: {}", e)))?;
+
+    if !advances_nonce(transaction) {
+        return Err(SentinelError::InvalidIntent(format!(
+            "Transaction for {} must start with AdvanceNonceAccount to be held",
+            intent.intent_id
+        )));
+    }
+    if let Some(nonce) = intent.consent_block.nonce.as_deref() {
+        let nonce = Hash::from_str(nonce).map_err(|_| SentinelError::IntentValidation(IntentError::InvalidNonce))?;
+        if message.recent_blockhash != nonce {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Transaction for {} does not use the intent's durable nonce",
+                intent.intent_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// First instruction is the system program's `AdvanceNonceAccount`
+fn advances_nonce(transaction: &Transaction) -> bool {
+    let message = &transaction.message;
+    message.instructions.first().is_some_and(|ix| {
+        message.account_keys.get(usize::from(ix.program_id_index)) == Some(&solana_sdk::system_program::id())
+            && ix.data.get(..4) == Some(&ADVANCE_NONCE_ACCOUNT.to_le_bytes())
+    })
+}
+
+fn associated_data(intent_id: &str, user: &Pubkey) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(AAD_PREFIX.len() + intent_id.len() + 32);
+    aad.extend_from_slice(AAD_PREFIX);
+    aad.extend_from_slice(intent_id.as_bytes());
+    aad.extend_from_slice(user.as_ref());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::intent::Constraints;
+    use crate::storage::MemoryKv;
+    use solana_sdk::signature::{Keypair, Signer};
+    use std::time::Duration;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn intent(user: Pubkey, nonce: Hash) -> Intent {
+        let mut intent = Intent {
+            intent_id: "limit-1".to_string(),
+            user_public_key: user,
+            constraints: Constraints {
+                expiry_timestamp: Some(NOW + 3_600),
+                ..Default::default()
+            },
+            ..Intent::test_swap()
+        };
+        intent.consent_block.nonce = Some(nonce.to_string());
+        let swap = intent.test_swap_details();
+        swap.amount = 1_000;
+        swap.minimum_received = Some(900);
+        intent
+    }
+
+    fn signed(user: &Keypair, nonce: Hash) -> Transaction {
+        #[allow(deprecated)]
+        let instructions = [
+            solana_sdk::system_instruction::advance_nonce_account(&Pubkey::new_unique(), &user.pubkey()),
+            solana_sdk::system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1),
+        ];
+        Transaction::new_signed_with_payer(&instructions, Some(&user.pubkey()), &[user], nonce)
+    }
+
+    fn vault(kv: Arc<dyn KvStore>, clock: &Arc<ManualClock>) -> SignedTxVault {
+        SignedTxVault::new(kv, [7; 32]).with_clock(clock.clone())
+    }
+
+    #[test]
+    fn test_release_exactly_once_and_encrypted_at_rest() {
+        let clock = Arc::new(ManualClock::at_timestamp(NOW));
+        let kv: Arc<dyn KvStore> = Arc::new(MemoryKv::new());
+        let vault = vault(kv.clone(), &clock);
+        let (user, nonce) = (Keypair::new(), Hash::new_unique());
+        let intent = intent(user.pubkey(), nonce);
+        let tx = signed(&user, nonce);
+
+        let entry = vault.deposit(&intent, &tx).unwrap();
+        assert_eq!((entry.status, entry.expires_at), (VaultStatus::Held, NOW + 3_600));
+        assert!(vault.deposit(&intent, &tx).is_err());
+
+        let stored = kv.get(b"limit-1").unwrap().unwrap();
+        let signature = bincode::serialize(&tx.signatures[0]).unwrap();
+        assert!(!stored.windows(signature.len()).any(|w| w == signature.as_slice()));
+
+        // A different key cannot open it
+        let other = SignedTxVault::new(kv.clone(), [8; 32]).with_clock(clock.clone());
+        assert!(matches!(other.release("limit-1"), Err(SentinelError::EnvelopeError(_))));
+
+        assert_eq!(vault.release("limit-1").unwrap(), tx);
+        assert!(vault.release("limit-1").is_err());
+        let entry = vault.get("limit-1").unwrap().unwrap();
+        assert_eq!((entry.status, entry.released_at, entry.sealed), (VaultStatus::Released, Some(NOW), None));
+
+        // The tombstone blocks a second deposit until it is swept
+        assert!(vault.deposit(&intent, &tx).is_err());
+        clock.advance(Duration::from_secs(3_600));
+        assert_eq!(vault.sweep_expired().unwrap(), 1);
+        assert!(vault.get("limit-1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_release_exactly_once_across_instances() {
+        use crate::config::CoordinationSettings;
+        use crate::coordination::MemoryCoordinator;
+
+        let clock = Arc::new(ManualClock::at_timestamp(NOW));
+        let kv: Arc<dyn KvStore> = Arc::new(MemoryKv::new());
+        let shared = Arc::new(MemoryCoordinator::new());
+        let coordination = || {
+            Arc::new(Coordination::new(shared.clone(), &CoordinationSettings::default()).with_clock(clock.clone()))
+        };
+        let (a, b) = (coordination(), coordination());
+        let vault_b = vault(kv.clone(), &clock).with_coordination(b);
+        let (user, nonce) = (Keypair::new(), Hash::new_unique());
+        vault_b.deposit(&intent(user.pubkey(), nonce), &signed(&user, nonce)).unwrap();
+
+        // Instance A is mid-release: recorded in the ledger, entry not yet updated
+        assert!(a.record_once("vault-release:limit-1").unwrap());
+        let err = vault_b.release("limit-1").unwrap_err();
+        assert!(err.to_string().contains("another instance"));
+        assert_eq!(vault_b.get("limit-1").unwrap().unwrap().status, VaultStatus::Held);
+    }
+
+    #[test]
+    fn test_deposit_checks_signer_and_durable_nonce() {
+        let clock = Arc::new(ManualClock::at_timestamp(NOW));
+        let vault = vault(Arc::new(MemoryKv::new()), &clock);
+        let (user, nonce) = (Keypair::new(), Hash::new_unique());
+        let intent = intent(user.pubkey(), nonce);
+
+        assert!(vault.deposit(&intent, &signed(&Keypair::new(), nonce)).is_err());
+        assert!(vault.deposit(&intent, &signed(&user, Hash::new_unique())).is_err());
+
+        let mut tampered = signed(&user, nonce);
+        tampered.signatures[0] = Default::default();
+        assert!(vault.deposit(&intent, &tampered).is_err());
+
+        #[allow(deprecated)]
+        let transfer = solana_sdk::system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1);
+        let expiring = Transaction::new_signed_with_payer(&[transfer], Some(&user.pubkey()), &[&user], nonce);
+        assert!(vault.deposit(&intent, &expiring).unwrap_err().to_string().contains("AdvanceNonceAccount"));
+
+        // Without a nonce on the intent any durable nonce is accepted
+        let mut any_nonce = intent.clone();
+        any_nonce.consent_block.nonce = None;
+        vault.deposit(&any_nonce, &signed(&user, Hash::new_unique())).unwrap();
+    }
+
+    #[test]
+    fn test_expiry_and_cancel() {
+        let clock = Arc::new(ManualClock::at_timestamp(NOW));
+        let vault = vault(Arc::new(MemoryKv::new()), &clock).with_max_hold_secs(600);
+        let (user, nonce) = (Keypair::new(), Hash::new_unique());
+
+        // Capped at max_hold_secs despite the one-hour intent expiry
+        let entry = vault.deposit(&intent(user.pubkey(), nonce), &signed(&user, nonce)).unwrap();
+        assert_eq!(entry.expires_at, NOW + 600);
+        clock.advance(Duration::from_secs(600));
+        assert!(vault.release("limit-1").unwrap_err().to_string().contains("expired"));
+
+        let mut other = intent(user.pubkey(), nonce);
+        other.intent_id = "limit-2".to_string();
+        vault.deposit(&other, &signed(&user, nonce)).unwrap();
+        assert!(matches!(
+            vault.cancel("limit-2", &Pubkey::new_unique()),
+            Err(SentinelError::Unauthorized(_))
+        ));
+        assert_eq!(vault.cancel("limit-2", &user.pubkey()).unwrap().status, VaultStatus::Cancelled);
+        assert!(vault.release("limit-2").is_err());
+    }
+}
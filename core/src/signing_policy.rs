@@ -0,0 +1,521 @@
+//! Delegated Signing Policies
+//!
+//! A wallet owner can pre-authorize a session key (bot, automation) to submit
+//! intents on their behalf within bounded constraints, without handing over the
+//! main key. The owner signs a [`SigningPolicy`]; the router stores it in a
+//! [`PolicyRegistry`] and checks every session-signed intent against the active
+//! policy before execution:
+//! - intent belongs to the policy owner and is signed by the session key
+//! - policy is within its validity window and not revoked
+//! - input/output mints, bundle legs included, are on the allow-list (empty
+//!   list = any mint)
+//! - notional value and slippage are within the policy caps
+//!
+//! The session key co-signs the policy, so no wallet can claim someone else's
+//! session key. Revocations are owner-signed [`PolicyRevocation`]s with the
+//! cancellation skew window; a revoked policy is remembered until it expires,
+//! so its signed blob cannot be registered again.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tracing::{info, warn};
+
+use crate::intent::{Intent, IntentLeg, SwapDetails};
+use crate::intent_registry::MAX_CANCELLATION_SKEW_SECS;
+use crate::{Result, SentinelError};
+
+/// Constraints a session key operates under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningPolicy {
+    /// Main wallet that granted the policy
+    pub owner: Pubkey,
+
+    /// Delegated key allowed to sign intents
+    pub session_key: Pubkey,
+
+    /// Maximum notional value per swap (USD)
+    pub max_notional_usd: f64,
+
+    /// Mints the session may trade (empty = any)
+    pub allowed_mints: Vec<Pubkey>,
+
+    /// Maximum slippage the session may request (basis points)
+    pub max_slippage_bps: u16,
+
+    /// Policy start (unix seconds)
+    pub valid_from: i64,
+
+    /// Policy expiry (unix seconds)
+    pub expires_at: i64,
+}
+
+impl SigningPolicy {
+    /// Hash the owner signs (BLAKE3 over bincode, like `Intent::hash`)
+    pub fn hash(&self) -> Hash {
+        let serialized = bincode::serialize(self).expect("Policy serialization failed");
+        Hash::new_from_array(*blake3::hash(&serialized).as_bytes())
+    }
+
+    /// Sign the policy with the owner's wallet and the session key it grants
+    pub fn authorize<O: Signer + ?Sized, S: Signer + ?Sized>(self, owner: &O, session: &S) -> AuthorizedPolicy {
+        let hash = self.hash();
+        AuthorizedPolicy {
+            owner_signature: owner.sign_message(hash.as_ref()),
+            session_signature: session.sign_message(hash.as_ref()),
+            policy: self,
+        }
+    }
+
+    fn is_active(&self, now: i64) -> bool {
+        (self.valid_from..self.expires_at).contains(&now)
+    }
+
+    fn allows_mint(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+
+    /// Check an intent's contents against the policy caps
+    pub fn check_intent(&self, intent: &Intent, notional_usd: f64) -> Result<()> {
+        if intent.user_public_key != self.owner {
+            return Err(violation("intent user is not the policy owner"));
+        }
+
+        if intent.constraints.max_slippage_bps > self.max_slippage_bps {
+            return Err(violation(format!(
+                "slippage {}bps exceeds policy cap {}bps",
+                intent.constraints.max_slippage_bps, self.max_slippage_bps
+            )));
+        }
+
+        if notional_usd > self.max_notional_usd {
+            return Err(violation(format!(
+                "notional ${:.2} exceeds policy cap ${:.2}",
+                notional_usd, self.max_notional_usd
+            )));
+        }
+
+        let swap_mints = |swap: &SwapDetails| [swap.input_mint, swap.output_mint];
+        let leg_mints = intent.legs.iter().flat_map(|leg| match leg {
+            IntentLeg::Swap(swap) => swap_mints(swap).to_vec(),
+            IntentLeg::Deposit(deposit) => vec![deposit.mint],
+        });
+        let mut mints = intent.swap_details.iter().flat_map(swap_mints).chain(leg_mints);
+        match mints.find(|mint| !self.allows_mint(mint)) {
+            Some(mint) => Err(violation(format!("mint {} is not allowed by policy", mint))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Policy plus the owner's and the session key's signatures over its hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizedPolicy {
+    pub policy: SigningPolicy,
+    pub owner_signature: Signature,
+
+    /// Session key's consent to operate under this owner and policy
+    pub session_signature: Signature,
+}
+
+impl AuthorizedPolicy {
+    /// Verify the owner and the session key both signed this exact policy
+    pub fn verify(&self) -> Result<()> {
+        let hash = self.policy.hash();
+        if !self.owner_signature.verify(self.policy.owner.as_ref(), hash.as_ref()) {
+            return Err(violation("owner signature does not match policy"));
+        }
+        if !self.session_signature.verify(self.policy.session_key.as_ref(), hash.as_ref()) {
+            return Err(violation("session key signature does not match policy"));
+        }
+        Ok(())
+    }
+}
+
+/// Owner's request to revoke one registered policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRevocation {
+    pub session_key: Pubkey,
+
+    /// `SigningPolicy::hash()` of the policy being revoked
+    pub policy_hash: Hash,
+
+    /// Signing time (unix seconds)
+    pub timestamp: i64,
+}
+
+impl PolicyRevocation {
+    pub fn new(policy: &SigningPolicy, timestamp: i64) -> Self {
+        Self {
+            session_key: policy.session_key,
+            policy_hash: policy.hash(),
+            timestamp,
+        }
+    }
+
+    /// Hash the owner signs (BLAKE3 over bincode, like `Intent::hash`)
+    pub fn hash(&self) -> Hash {
+        let serialized = bincode::serialize(self).expect("Revocation serialization failed");
+        Hash::new_from_array(*blake3::hash(&serialized).as_bytes())
+    }
+
+    /// Sign with the policy owner's wallet
+    pub fn sign<S: Signer + ?Sized>(self, owner: &S) -> SignedPolicyRevocation {
+        let signature = owner.sign_message(self.hash().as_ref());
+        SignedPolicyRevocation {
+            revocation: self,
+            signature,
+        }
+    }
+}
+
+/// Revocation plus the owner's signature over its hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedPolicyRevocation {
+    pub revocation: PolicyRevocation,
+    pub signature: Signature,
+}
+
+fn violation(reason: impl Into<String>) -> SentinelError {
+    SentinelError::InvalidIntent(format!("Signing policy violation: {}", reason.into()))
+}
+
+#[derive(Default)]
+struct Policies {
+    active: HashMap<Pubkey, AuthorizedPolicy>,
+
+    /// Revoked (session key, policy hash) → the policy's `expires_at`
+    revoked: HashMap<(Pubkey, Hash), i64>,
+}
+
+/// Active session policies keyed by session key
+#[derive(Default)]
+pub struct PolicyRegistry {
+    policies: RwLock<Policies>,
+}
+
+impl PolicyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a session policy after verifying both signatures
+    ///
+    /// A session key registered by one wallet cannot be taken over by another;
+    /// only its owner may replace the policy (as with `revoke`). A revoked
+    /// policy stays refused until it expires.
+    pub fn register(&self, authorized: AuthorizedPolicy) -> Result<()> {
+        authorized.verify()?;
+
+        let policy = &authorized.policy;
+        if policy.expires_at <= policy.valid_from {
+            return Err(violation("policy expires before it becomes valid"));
+        }
+
+        let mut policies = self.write();
+        if policies.revoked.contains_key(&(policy.session_key, policy.hash())) {
+            return Err(violation(format!("policy for session key {} was revoked", policy.session_key)));
+        }
+        if let Some(existing) = policies.active.get(&policy.session_key) {
+            if existing.policy.owner != policy.owner {
+                return Err(violation(format!(
+                    "session key {} is registered to another wallet",
+                    policy.session_key
+                )));
+            }
+        }
+
+        info!(
+            "🔑 Session key {} authorized for {} until {}",
+            policy.session_key, policy.owner, policy.expires_at
+        );
+        policies.active.insert(policy.session_key, authorized);
+        Ok(())
+    }
+
+    /// Revoke a session key's policy; only its owner's signature is accepted
+    ///
+    /// `now` is unix seconds; the revocation must be signed within
+    /// `MAX_CANCELLATION_SKEW_SECS` of it.
+    pub fn revoke(&self, signed: &SignedPolicyRevocation, now: i64) -> Result<()> {
+        let revocation = &signed.revocation;
+        let mut policies = self.write();
+        let existing = policies
+            .active
+            .get(&revocation.session_key)
+            .filter(|existing| existing.policy.hash() == revocation.policy_hash)
+            .ok_or_else(|| violation(format!("no such policy for session key {}", revocation.session_key)))?;
+
+        // Signature first: nothing else in the request is trusted until it verifies
+        let owner = existing.policy.owner;
+        if !signed.signature.verify(owner.as_ref(), revocation.hash().as_ref()) {
+            return Err(violation("revocation signature does not match the policy owner"));
+        }
+        if now.abs_diff(revocation.timestamp) > MAX_CANCELLATION_SKEW_SECS as u64 {
+            return Err(violation("revocation timestamp outside the allowed window"));
+        }
+
+        let expires_at = existing.policy.expires_at;
+        policies.active.remove(&revocation.session_key);
+        policies.revoked.insert((revocation.session_key, revocation.policy_hash), expires_at);
+        info!("Session key {} revoked by {}", revocation.session_key, owner);
+        Ok(())
+    }
+
+    /// Active policy for a session key
+    pub fn policy(&self, session_key: &Pubkey, now: i64) -> Option<SigningPolicy> {
+        self.read()
+            .active
+            .get(session_key)
+            .map(|a| &a.policy)
+            .filter(|p| p.is_active(now))
+            .cloned()
+    }
+
+    /// Verify a session-signed intent before execution
+    ///
+    /// `signature` must be the session key's signature over `Intent::hash()`.
+    pub fn verify_intent(
+        &self,
+        intent: &Intent,
+        session_key: &Pubkey,
+        signature: &Signature,
+        notional_usd: f64,
+        now: i64,
+    ) -> Result<()> {
+        if !signature.verify(session_key.as_ref(), intent.hash().as_ref()) {
+            return Err(violation("session signature does not match intent"));
+        }
+
+        let policy = self
+            .policy(session_key, now)
+            .ok_or_else(|| violation(format!("no active policy for session key {}", session_key)))?;
+
+        policy.check_intent(intent, notional_usd).map_err(|e| {
+            warn!("🚫 Session {} intent {} rejected: {}", session_key, intent.intent_id, e);
+            e
+        })
+    }
+
+    /// Drop expired policies (and revocation records whose policy has expired)
+    pub fn prune_expired(&self, now: i64) -> usize {
+        let mut policies = self.write();
+        let before = policies.active.len();
+        policies.active.retain(|_, a| a.policy.expires_at > now);
+        policies.revoked.retain(|_, expires_at| *expires_at > now);
+        before - policies.active.len()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Policies> {
+        self.policies.read().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Policies> {
+        self.policies.write().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::{DepositDetails, IntentType};
+    use solana_sdk::signature::Keypair;
+
+    struct Fixture {
+        owner: Keypair,
+        session: Keypair,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        registry: PolicyRegistry,
+    }
+
+    fn fixture() -> Fixture {
+        let owner = Keypair::new();
+        let session = Keypair::new();
+        let input_mint = Pubkey::new_unique();
+        let output_mint = Pubkey::new_unique();
+
+        let registry = PolicyRegistry::new();
+        registry
+            .register(
+                SigningPolicy {
+                    owner: owner.pubkey(),
+                    session_key: session.pubkey(),
+                    max_notional_usd: 1_000.0,
+                    allowed_mints: vec![input_mint, output_mint],
+                    max_slippage_bps: 100,
+                    valid_from: 0,
+                    expires_at: 1_000,
+                }
+                .authorize(&owner, &session),
+            )
+            .unwrap();
+
+        Fixture { owner, session, input_mint, output_mint, registry }
+    }
+
+    fn intent(f: &Fixture, output_mint: Pubkey) -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            user_public_key: f.owner.pubkey(),
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.input_mint = f.input_mint;
+        swap.output_mint = output_mint;
+        swap.minimum_received = None;
+        intent
+    }
+
+    fn verify(f: &Fixture, intent: &Intent, notional_usd: f64, now: i64) -> Result<()> {
+        let signature = f.session.sign_message(intent.hash().as_ref());
+        f.registry
+            .verify_intent(intent, &f.session.pubkey(), &signature, notional_usd, now)
+    }
+
+    #[test]
+    fn test_session_intent_within_policy() {
+        let f = fixture();
+        assert!(verify(&f, &intent(&f, f.output_mint), 500.0, 10).is_ok());
+    }
+
+    #[test]
+    fn test_policy_caps_enforced() {
+        let f = fixture();
+        let ok = intent(&f, f.output_mint);
+
+        assert!(verify(&f, &ok, 5_000.0, 10).unwrap_err().to_string().contains("notional"));
+        assert!(verify(&f, &ok, 500.0, 1_000).unwrap_err().to_string().contains("no active policy"));
+        assert!(verify(&f, &intent(&f, Pubkey::new_unique()), 500.0, 10)
+            .unwrap_err()
+            .to_string()
+            .contains("not allowed"));
+
+        let mut wide = ok.clone();
+        wide.constraints.max_slippage_bps = 500;
+        assert!(verify(&f, &wide, 500.0, 10).unwrap_err().to_string().contains("slippage"));
+    }
+
+    #[test]
+    fn test_bundle_leg_mints_enforced() {
+        let f = fixture();
+        let mut bundle = intent(&f, f.output_mint);
+        bundle.intent_type = IntentType::Bundle;
+        let mut leg = bundle.swap_details.clone().unwrap();
+        bundle.legs = vec![IntentLeg::Swap(leg.clone())];
+        assert!(verify(&f, &bundle, 500.0, 10).is_ok());
+
+        leg.output_mint = Pubkey::new_unique();
+        bundle.legs.push(IntentLeg::Swap(leg));
+        assert!(verify(&f, &bundle, 500.0, 10).unwrap_err().to_string().contains("not allowed"));
+
+        bundle.legs = vec![IntentLeg::Deposit(DepositDetails {
+            program_id: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            amount: None,
+        })];
+        assert!(verify(&f, &bundle, 500.0, 10).unwrap_err().to_string().contains("not allowed"));
+    }
+
+    #[test]
+    fn test_forged_policy_and_signature_rejected() {
+        let f = fixture();
+
+        let mut forged = SigningPolicy {
+            owner: f.owner.pubkey(),
+            session_key: Pubkey::new_unique(),
+            max_notional_usd: f64::MAX,
+            allowed_mints: vec![],
+            max_slippage_bps: 10_000,
+            valid_from: 0,
+            expires_at: 1_000,
+        }
+        .authorize(&f.session, &f.session);
+        assert!(f.registry.register(forged.clone()).is_err());
+
+        forged.policy.session_key = f.session.pubkey();
+        assert!(f.registry.register(forged).is_err());
+
+        let intent = intent(&f, f.output_mint);
+        let wrong = Keypair::new().sign_message(intent.hash().as_ref());
+        assert!(f
+            .registry
+            .verify_intent(&intent, &f.session.pubkey(), &wrong, 1.0, 10)
+            .is_err());
+    }
+
+    #[test]
+    fn test_session_key_cannot_be_taken_over() {
+        let f = fixture();
+        let policy = |owner: Pubkey, max_notional_usd: f64| SigningPolicy {
+            owner,
+            session_key: f.session.pubkey(),
+            max_notional_usd,
+            allowed_mints: vec![],
+            max_slippage_bps: 10_000,
+            valid_from: 0,
+            expires_at: 1_000,
+        };
+
+        // Validly signed by another wallet, but the session key is taken
+        let attacker = Keypair::new();
+        let hijack = policy(attacker.pubkey(), f64::MAX).authorize(&attacker, &f.session);
+        assert!(f.registry.register(hijack).unwrap_err().to_string().contains("another wallet"));
+        assert_eq!(f.registry.policy(&f.session.pubkey(), 10).unwrap().owner, f.owner.pubkey());
+
+        // The owner can still replace its own policy
+        f.registry.register(policy(f.owner.pubkey(), 50.0).authorize(&f.owner, &f.session)).unwrap();
+        assert_eq!(f.registry.policy(&f.session.pubkey(), 10).unwrap().max_notional_usd, 50.0);
+    }
+
+    #[test]
+    fn test_session_key_must_consent() {
+        // An attacker cannot squat a victim's session key without its signature
+        let (attacker, victim_session) = (Keypair::new(), Keypair::new());
+        let policy = SigningPolicy {
+            owner: attacker.pubkey(),
+            session_key: victim_session.pubkey(),
+            max_notional_usd: 1.0,
+            allowed_mints: vec![],
+            max_slippage_bps: 0,
+            valid_from: 0,
+            expires_at: 1_000,
+        };
+        let registry = PolicyRegistry::new();
+        let squat = policy.clone().authorize(&attacker, &attacker);
+        assert!(registry.register(squat).unwrap_err().to_string().contains("session key signature"));
+        assert!(registry.policy(&victim_session.pubkey(), 10).is_none());
+    }
+
+    #[test]
+    fn test_revoke_and_prune() {
+        let f = fixture();
+        let registered = f.registry.policy(&f.session.pubkey(), 10).unwrap();
+        let authorized = registered.clone().authorize(&f.owner, &f.session);
+
+        // Naming the owner is not enough: the revocation must carry its signature
+        let forged = PolicyRevocation::new(&registered, 10).sign(&Keypair::new());
+        assert!(f.registry.revoke(&forged, 10).is_err());
+        let stale = PolicyRevocation::new(&registered, 10).sign(&f.owner);
+        assert!(f.registry.revoke(&stale, 10 + MAX_CANCELLATION_SKEW_SECS + 1).is_err());
+        for timestamp in [i64::MIN, i64::MAX] {
+            let extreme = PolicyRevocation::new(&registered, timestamp).sign(&f.owner);
+            assert!(f.registry.revoke(&extreme, 10).is_err());
+        }
+        assert!(f.registry.policy(&f.session.pubkey(), 10).is_some());
+
+        f.registry.revoke(&PolicyRevocation::new(&registered, 10).sign(&f.owner), 10).unwrap();
+        assert!(f.registry.policy(&f.session.pubkey(), 10).is_none());
+
+        // The old signed blob cannot bring the key back; a new policy can
+        assert!(f.registry.register(authorized).unwrap_err().to_string().contains("revoked"));
+        let renewed = SigningPolicy { expires_at: 2_000, ..registered };
+        f.registry.register(renewed.authorize(&f.owner, &f.session)).unwrap();
+        assert_eq!(f.registry.prune_expired(2_000), 1);
+        assert!(f.registry.read().revoked.is_empty());
+    }
+}
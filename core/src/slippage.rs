@@ -0,0 +1,253 @@
+//! Slippage Auto-Tuning
+//!
+//! Users tend to set a round, loose slippage (1%, 3%) that a sandwich bot can
+//! extract in full. [`SlippageAdvisor`] recommends the tightest tolerance that
+//! should still land, built from three terms (basis points):
+//! - **volatility**: expected price drift between quote and landing, the 24h
+//!   range scaled by `sqrt(horizon / 24h)` and a safety multiple
+//! - **depth**: how far equal-sized concurrent flow would move the pool, from
+//!   the share of the ±1% depth this trade consumes (falls back to the quote's
+//!   own price impact when depth is unknown)
+//! - **headroom**: a fixed buffer that shrinks as MEV risk grows, since every
+//!   basis point of slack is extractable by an attacker
+//!
+//! The recommendation never exceeds the user's signed `max_slippage_bps`. When
+//! the intent opts into managed slippage ([`crate::Constraints::managed_slippage`])
+//! the router executes with the recommended, tighter constraints.
+
+use serde::{Deserialize, Serialize};
+
+use crate::intent::{Intent, SwapMode};
+
+/// Seconds in the 24h volatility window
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// Advisor tuning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlippageAdvisorSettings {
+    /// Expected time from quote to landing (seconds)
+    pub landing_horizon_secs: f64,
+
+    /// Multiple of the horizon drift covered (≈ standard deviations)
+    pub volatility_multiple: f64,
+
+    /// Basis points per unit of ±1% depth consumed
+    pub depth_weight: f64,
+
+    /// Buffer at zero MEV risk (basis points)
+    pub headroom_bps: f64,
+
+    /// Tightest recommendation (basis points)
+    pub min_slippage_bps: u16,
+}
+
+impl Default for SlippageAdvisorSettings {
+    fn default() -> Self {
+        Self {
+            landing_horizon_secs: 2.0,
+            volatility_multiple: 3.0,
+            depth_weight: 50.0,
+            headroom_bps: 20.0,
+            min_slippage_bps: 10,
+        }
+    }
+}
+
+/// Market inputs for one swap
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlippageInputs {
+    pub mode: SwapMode,
+    pub quoted_in_amount: u64,
+    pub quoted_out_amount: u64,
+    pub price_impact_bps: f64,
+
+    /// Trade size as a share of the pool's ±1% depth on the input side
+    pub depth_utilization: Option<f64>,
+
+    /// 24h high-low range (%)
+    pub volatility_24h_pct: f64,
+
+    /// MEV risk score (0.0-1.0)
+    pub risk_score: f32,
+}
+
+/// Recommended constraints, returned by the preflight API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlippageRecommendation {
+    pub max_slippage_bps: u16,
+
+    /// ExactIn: minimum output at the recommended tolerance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_received: Option<u64>,
+
+    /// ExactOut: maximum input at the recommended tolerance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum_sent: Option<u64>,
+
+    /// Term breakdown (basis points)
+    pub volatility_bps: f64,
+    pub depth_bps: f64,
+    pub headroom_bps: f64,
+
+    /// The user's own limit was tighter than the recommendation
+    pub capped_by_user: bool,
+}
+
+impl SlippageRecommendation {
+    /// Tighten a managed-slippage intent's constraints to the recommendation
+    ///
+    /// Only ever tightens; returns false (and leaves the intent untouched) when
+    /// the intent did not opt in.
+    pub fn apply(&self, intent: &mut Intent) -> bool {
+        if !intent.constraints.managed_slippage {
+            return false;
+        }
+
+        intent.constraints.max_slippage_bps = intent.constraints.max_slippage_bps.min(self.max_slippage_bps);
+        if let (Some(swap), Some(minimum)) = (intent.swap_details.as_mut(), self.minimum_received) {
+            swap.minimum_received = Some(swap.minimum_received.map_or(minimum, |m| m.max(minimum)));
+        }
+        true
+    }
+}
+
+/// Recommends the tightest viable slippage for a swap
+#[derive(Debug, Clone, Default)]
+pub struct SlippageAdvisor {
+    settings: SlippageAdvisorSettings,
+}
+
+impl SlippageAdvisor {
+    pub fn new(settings: SlippageAdvisorSettings) -> Self {
+        Self { settings }
+    }
+
+    pub fn recommend(&self, inputs: &SlippageInputs, user_max_bps: u16) -> SlippageRecommendation {
+        let s = &self.settings;
+
+        let horizon = (s.landing_horizon_secs / SECS_PER_DAY).sqrt();
+        let volatility_bps = inputs.volatility_24h_pct.max(0.0) * 100.0 * horizon * s.volatility_multiple;
+
+        let depth_bps = match inputs.depth_utilization {
+            Some(utilization) => utilization.max(0.0) * s.depth_weight,
+            // Small trades move the price about `utilization` percent
+            None => inputs.price_impact_bps.max(0.0) * s.depth_weight / 100.0,
+        };
+
+        let headroom_bps = s.headroom_bps * (1.0 - inputs.risk_score.clamp(0.0, 1.0) as f64);
+
+        let total = (volatility_bps + depth_bps + headroom_bps).ceil();
+        let recommended = (total.min(10_000.0) as u16).max(s.min_slippage_bps);
+        let max_slippage_bps = recommended.min(user_max_bps);
+
+        let bps = max_slippage_bps as u128;
+        let (minimum_received, maximum_sent) = match inputs.mode {
+            SwapMode::ExactIn => (Some((inputs.quoted_out_amount as u128 * (10_000 - bps) / 10_000) as u64), None),
+            SwapMode::ExactOut => {
+                let maximum = (inputs.quoted_in_amount as u128 * (10_000 + bps)).div_ceil(10_000);
+                (None, Some(maximum.min(u64::MAX as u128) as u64))
+            }
+        };
+
+        SlippageRecommendation {
+            max_slippage_bps,
+            minimum_received,
+            maximum_sent,
+            volatility_bps,
+            depth_bps,
+            headroom_bps,
+            capped_by_user: user_max_bps < recommended,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::Constraints;
+
+    fn inputs(volatility_24h_pct: f64, depth_utilization: Option<f64>, risk_score: f32) -> SlippageInputs {
+        SlippageInputs {
+            mode: SwapMode::ExactIn,
+            quoted_in_amount: 1_000_000,
+            quoted_out_amount: 2_000_000,
+            price_impact_bps: 30.0,
+            depth_utilization,
+            volatility_24h_pct,
+            risk_score,
+        }
+    }
+
+    fn intent(managed_slippage: bool) -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            constraints: Constraints {
+                max_slippage_bps: 300,
+                managed_slippage,
+                ..Constraints::default()
+            },
+            ..Intent::test_swap()
+        };
+        intent.test_swap_details().minimum_received = Some(1_000);
+        intent
+    }
+
+    #[test]
+    fn test_recommendation_terms() {
+        let advisor = SlippageAdvisor::default();
+
+        // 10% daily range over a 2s horizon: 1000 * sqrt(2/86400) * 3 ≈ 14.4 bps
+        let calm = advisor.recommend(&inputs(10.0, Some(0.2), 0.0), 300);
+        assert!((calm.volatility_bps - 14.43).abs() < 0.01);
+        assert_eq!(calm.depth_bps, 10.0);
+        assert_eq!(calm.headroom_bps, 20.0);
+        assert_eq!(calm.max_slippage_bps, 45);
+        assert_eq!(calm.minimum_received, Some(1_991_000));
+        assert!(!calm.capped_by_user);
+
+        // High MEV risk removes the headroom
+        let risky = advisor.recommend(&inputs(10.0, Some(0.2), 1.0), 300);
+        assert_eq!(risky.max_slippage_bps, 25);
+
+        // Unknown depth falls back to the quote's impact (30 bps -> 15 bps)
+        let no_depth = advisor.recommend(&inputs(0.0, None, 1.0), 300);
+        assert_eq!(no_depth.depth_bps, 15.0);
+        assert_eq!(no_depth.max_slippage_bps, 15);
+    }
+
+    #[test]
+    fn test_floor_and_user_cap() {
+        let advisor = SlippageAdvisor::default();
+        let floor = advisor.recommend(&inputs(0.0, Some(0.0), 1.0), 300);
+        assert_eq!(floor.max_slippage_bps, 10);
+
+        let capped = advisor.recommend(&inputs(50.0, Some(5.0), 0.0), 100);
+        assert_eq!(capped.max_slippage_bps, 100);
+        assert!(capped.capped_by_user);
+    }
+
+    #[test]
+    fn test_exact_out_bounds_input() {
+        let advisor = SlippageAdvisor::default();
+        let mut exact_out = inputs(0.0, Some(0.0), 1.0);
+        exact_out.mode = SwapMode::ExactOut;
+
+        let recommendation = advisor.recommend(&exact_out, 300);
+        assert_eq!(recommendation.minimum_received, None);
+        assert_eq!(recommendation.maximum_sent, Some(1_001_000));
+    }
+
+    #[test]
+    fn test_apply_only_when_managed() {
+        let recommendation = SlippageAdvisor::default().recommend(&inputs(10.0, Some(0.2), 0.0), 300);
+
+        let mut unmanaged = intent(false);
+        assert!(!recommendation.apply(&mut unmanaged));
+        assert_eq!(unmanaged.constraints.max_slippage_bps, 300);
+
+        let mut managed = intent(true);
+        assert!(recommendation.apply(&mut managed));
+        assert_eq!(managed.constraints.max_slippage_bps, 45);
+        assert_eq!(managed.swap_details.unwrap().minimum_received, Some(1_991_000));
+    }
+}
@@ -0,0 +1,409 @@
+//! Per-Route Submission SLOs
+//!
+//! [`SloTracker`] keeps every submission outcome per route for the long window
+//! and reports, over both the short and the long window, submit-to-land p50/p99
+//! and the failure rate (failed or expired submissions).
+//!
+//! Alerting follows the multi-window burn-rate pattern: an objective alerts
+//! when both windows spend its error budget at least `burn_rate_threshold`
+//! times faster than sustainable. The short window makes the alert fast, the
+//! long one keeps a handful of slow bundles from paging anyone. A short-window
+//! burn at twice the threshold is critical. Each (route, objective) pair
+//! re-alerts at most once per `alert_cooldown_secs`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::alert::{Alert, AlertSeverity, AlertSink, LogAlertSink};
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{SentinelConfig, SloSettings};
+use crate::types::RouteType;
+
+/// Share of landings allowed over `latency_p99_ms`
+const LATENCY_BUDGET: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SloObjective {
+    LatencyP99,
+    FailureRate,
+}
+
+impl SloObjective {
+    const ALL: [SloObjective; 2] = [SloObjective::LatencyP99, SloObjective::FailureRate];
+
+    fn name(&self) -> &'static str {
+        match self {
+            SloObjective::LatencyP99 => "latency_p99",
+            SloObjective::FailureRate => "failure_rate",
+        }
+    }
+}
+
+/// Outcomes within one window
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SloWindow {
+    pub window_secs: u64,
+    pub samples: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+    pub p50_latency_ms: Option<u64>,
+    pub p99_latency_ms: Option<u64>,
+
+    /// Failure budget spend rate (1.0 = exactly on budget)
+    pub failure_burn_rate: f64,
+
+    /// Latency budget spend rate (1.0 = exactly 1% over `latency_p99_ms`)
+    pub latency_burn_rate: f64,
+}
+
+impl SloWindow {
+    fn burn_rate(&self, objective: SloObjective) -> f64 {
+        match objective {
+            SloObjective::LatencyP99 => self.latency_burn_rate,
+            SloObjective::FailureRate => self.failure_burn_rate,
+        }
+    }
+}
+
+/// SLO state for one route (API representation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteSlo {
+    pub route: RouteType,
+    pub short: SloWindow,
+    pub long: SloWindow,
+}
+
+struct Outcome {
+    at_ms: i64,
+    /// None when the submission failed or expired
+    latency_ms: Option<u64>,
+}
+
+struct RouteOutcomes {
+    route: RouteType,
+    outcomes: VecDeque<Outcome>,
+    /// Last alert per `SloObjective::ALL` entry
+    last_alert_ms: [Option<i64>; 2],
+}
+
+/// Sliding-window SLO tracking per submission route
+pub struct SloTracker {
+    settings: Mutex<SloSettings>,
+    routes: Mutex<Vec<RouteOutcomes>>,
+    sink: Arc<dyn AlertSink>,
+    clock: SharedClock,
+}
+
+impl Default for SloTracker {
+    fn default() -> Self {
+        Self::new(SloSettings::default())
+    }
+}
+
+impl SloTracker {
+    pub fn new(settings: SloSettings) -> Self {
+        Self {
+            settings: Mutex::new(settings),
+            routes: Mutex::new(Vec::new()),
+            sink: Arc::new(LogAlertSink),
+            clock: system_clock(),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.slo.clone())
+    }
+
+    /// Apply the hot-reloadable `[slo]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *lock(&self.settings) = config.slo.clone();
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn AlertSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Submission on `route` landed `latency` after it was sent
+    pub fn record_landed(&self, route: &RouteType, latency: Duration) {
+        self.record(route, Some(latency.as_millis() as u64));
+    }
+
+    /// Submission on `route` failed or expired
+    pub fn record_failed(&self, route: &RouteType) {
+        self.record(route, None);
+    }
+
+    /// Current windows for `route` (empty windows if nothing was recorded)
+    pub fn status(&self, route: &RouteType) -> RouteSlo {
+        let settings = lock(&self.settings).clone();
+        let now = self.clock.unix_millis();
+        let routes = lock(&self.routes);
+        match routes.iter().find(|r| r.route == *route) {
+            Some(entry) => route_slo(entry, &settings, now),
+            None => RouteSlo {
+                route: route.clone(),
+                short: SloWindow { window_secs: settings.short_window_secs, ..Default::default() },
+                long: SloWindow { window_secs: settings.long_window_secs, ..Default::default() },
+            },
+        }
+    }
+
+    /// Every route with recorded outcomes
+    pub fn statuses(&self) -> Vec<RouteSlo> {
+        let settings = lock(&self.settings).clone();
+        let now = self.clock.unix_millis();
+        lock(&self.routes).iter().map(|entry| route_slo(entry, &settings, now)).collect()
+    }
+
+    fn record(&self, route: &RouteType, latency_ms: Option<u64>) {
+        let settings = lock(&self.settings).clone();
+        let now = self.clock.unix_millis();
+
+        let alerts = {
+            let mut routes = lock(&self.routes);
+            let index = match routes.iter().position(|r| r.route == *route) {
+                Some(index) => index,
+                None => {
+                    routes.push(RouteOutcomes {
+                        route: route.clone(),
+                        outcomes: VecDeque::new(),
+                        last_alert_ms: [None; 2],
+                    });
+                    routes.len() - 1
+                }
+            };
+            let entry = &mut routes[index];
+            entry.outcomes.push_back(Outcome { at_ms: now, latency_ms });
+            let horizon = now - window_ms(settings.long_window_secs);
+            while entry.outcomes.front().is_some_and(|o| o.at_ms < horizon) {
+                entry.outcomes.pop_front();
+            }
+
+            burn_alerts(entry, &settings, now)
+        };
+
+        for alert in alerts {
+            self.sink.send(alert);
+        }
+    }
+}
+
+fn route_slo(entry: &RouteOutcomes, settings: &SloSettings, now: i64) -> RouteSlo {
+    RouteSlo {
+        route: entry.route.clone(),
+        short: window(&entry.outcomes, settings, settings.short_window_secs, now),
+        long: window(&entry.outcomes, settings, settings.long_window_secs, now),
+    }
+}
+
+/// Alerts due for `entry`, marking them sent
+fn burn_alerts(entry: &mut RouteOutcomes, settings: &SloSettings, now: i64) -> Vec<Alert> {
+    let slo = route_slo(entry, settings, now);
+    if slo.short.samples < settings.min_samples {
+        return Vec::new();
+    }
+
+    let mut alerts = Vec::new();
+    for (i, objective) in SloObjective::ALL.into_iter().enumerate() {
+        let (short, long) = (slo.short.burn_rate(objective), slo.long.burn_rate(objective));
+        if short < settings.burn_rate_threshold || long < settings.burn_rate_threshold {
+            continue;
+        }
+        if entry.last_alert_ms[i].is_some_and(|at| now - at < window_ms(settings.alert_cooldown_secs)) {
+            continue;
+        }
+        entry.last_alert_ms[i] = Some(now);
+
+        let observed = match objective {
+            SloObjective::LatencyP99 => format!(
+                "p99 {}ms against {}ms",
+                slo.short.p99_latency_ms.unwrap_or_default(),
+                settings.latency_p99_ms
+            ),
+            SloObjective::FailureRate => format!(
+                "{:.1}% failed against {:.1}%",
+                slo.short.failure_rate * 100.0,
+                settings.max_failure_rate * 100.0
+            ),
+        };
+        alerts.push(Alert {
+            source: "slo".to_string(),
+            key: format!("{:?}/{}", entry.route, objective.name()),
+            severity: if short >= 2.0 * settings.burn_rate_threshold {
+                AlertSeverity::Critical
+            } else {
+                AlertSeverity::Warning
+            },
+            message: format!(
+                "{:?} {} over {}s (burn {:.1}x, {:.1}x over {}s)",
+                entry.route, observed, settings.short_window_secs, short, long, settings.long_window_secs
+            ),
+            timestamp_ms: now.max(0) as u64,
+        });
+    }
+    alerts
+}
+
+fn window(outcomes: &VecDeque<Outcome>, settings: &SloSettings, window_secs: u64, now: i64) -> SloWindow {
+    let since = now - window_ms(window_secs);
+    let recent = outcomes.iter().rev().take_while(|o| o.at_ms >= since);
+
+    let mut samples = 0;
+    let mut latencies = Vec::new();
+    for outcome in recent {
+        samples += 1;
+        latencies.extend(outcome.latency_ms);
+    }
+    if samples == 0 {
+        return SloWindow { window_secs, ..Default::default() };
+    }
+    latencies.sort_unstable();
+
+    let failures = samples - latencies.len();
+    let failure_rate = failures as f64 / samples as f64;
+    let slow = latencies.iter().filter(|&&ms| ms > settings.latency_p99_ms).count();
+    let latency_burn_rate = if latencies.is_empty() {
+        0.0
+    } else {
+        slow as f64 / latencies.len() as f64 / LATENCY_BUDGET
+    };
+
+    SloWindow {
+        window_secs,
+        samples,
+        failures,
+        failure_rate,
+        p50_latency_ms: percentile(&latencies, 0.50),
+        p99_latency_ms: percentile(&latencies, 0.99),
+        failure_burn_rate: failure_rate / settings.max_failure_rate,
+        latency_burn_rate,
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn window_ms(secs: u64) -> i64 {
+    (secs as i64).saturating_mul(1_000)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alert::MemoryAlertSink;
+    use crate::clock::ManualClock;
+
+    fn tracker() -> (SloTracker, Arc<MemoryAlertSink>, Arc<ManualClock>) {
+        let sink = Arc::new(MemoryAlertSink::default());
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let settings = SloSettings {
+            min_samples: 10,
+            ..Default::default()
+        };
+        let tracker = SloTracker::new(settings)
+            .with_sink(sink.clone())
+            .with_clock(clock.clone());
+        (tracker, sink, clock)
+    }
+
+    #[test]
+    fn test_window_percentiles_and_failure_rate() {
+        let (tracker, sink, clock) = tracker();
+        let route = RouteType::JitoBundle;
+        for ms in 1..=100 {
+            tracker.record_landed(&route, Duration::from_millis(ms * 10));
+        }
+        tracker.record_failed(&route);
+        clock.advance(Duration::from_secs(1));
+
+        let status = tracker.status(&route);
+        assert_eq!(status.short.samples, 101);
+        assert_eq!(status.short.failures, 1);
+        assert_eq!(status.short.p50_latency_ms, Some(500));
+        assert_eq!(status.short.p99_latency_ms, Some(990));
+        assert_eq!(status.short.latency_burn_rate, 0.0);
+        assert!((status.short.failure_burn_rate - 1.0 / 101.0 / 0.1).abs() < 1e-9);
+        assert!(sink.alerts().is_empty());
+
+        // Outcomes age out of the short window, then out of the long one
+        clock.advance(Duration::from_secs(600));
+        let status = tracker.status(&route);
+        assert_eq!((status.short.samples, status.long.samples), (0, 101));
+        assert_eq!(tracker.status(&RouteType::StandardRpc).long.samples, 0);
+    }
+
+    #[test]
+    fn test_failure_burn_alerts_once_per_cooldown() {
+        let (tracker, sink, clock) = tracker();
+        let route = RouteType::JitoSingle;
+        for i in 0..20 {
+            if i % 2 == 0 {
+                tracker.record_failed(&route);
+            } else {
+                tracker.record_landed(&route, Duration::from_millis(800));
+            }
+        }
+
+        // 50% failures against a 10% budget: burn 5x in both windows
+        let alerts = sink.alerts();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].key, "JitoSingle/failure_rate");
+        assert_eq!(alerts[0].severity, AlertSeverity::Critical);
+
+        for _ in 0..10 {
+            tracker.record_failed(&route);
+        }
+        assert_eq!(sink.alerts().len(), 1);
+
+        clock.advance(Duration::from_secs(601));
+        for _ in 0..10 {
+            tracker.record_failed(&route);
+        }
+        assert_eq!(sink.alerts().len(), 2);
+    }
+
+    #[test]
+    fn test_latency_burn_needs_both_windows() {
+        let (tracker, sink, clock) = tracker();
+        let route = RouteType::JitoBundle;
+
+        // A healthy hour keeps the long window under budget
+        for _ in 0..2_000 {
+            tracker.record_landed(&route, Duration::from_millis(900));
+        }
+        clock.advance(Duration::from_secs(1_200));
+
+        // 20 slow landings: short window burns 100x, the long one 1.0x
+        for _ in 0..20 {
+            tracker.record_landed(&route, Duration::from_secs(8));
+        }
+        assert!(sink.alerts().is_empty());
+
+        for _ in 0..40 {
+            tracker.record_landed(&route, Duration::from_secs(8));
+        }
+        let alerts = sink.alerts();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].key, "JitoBundle/latency_p99");
+        assert!(alerts[0].message.contains("p99 8000ms against 5000ms"), "{}", alerts[0].message);
+    }
+}
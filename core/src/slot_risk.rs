@@ -0,0 +1,334 @@
+//! Stake-Weighted Slot Window Risk
+//!
+//! The next leader alone is a poor proxy for who will actually include a
+//! transaction: with a few hundred milliseconds of confirmation latency it can
+//! land several slots, and leaders, later. `SlotRiskForecaster` looks at the
+//! next `lookahead_leaders` rotations and weights each leader's risk by the
+//! probability that the transaction lands in one of its slots.
+//!
+//! Landing delay is modelled as exponential with the (EWMA) confirmation
+//! latency as its mean, so `P(slot i) = e^(-i/m) - e^(-(i+1)/m)` with `m` in
+//! slots. The leader schedule is already stake-weighted, so a high-stake
+//! validator holding several rotations in the window accumulates their
+//! landing mass. Route selection combines the window risk with the
+//! transaction's own score as independent risks: `1 - (1 - score)(1 - window)`.
+//!
+//! A score reported with less than `min_route_confidence` is first pulled
+//! toward the middle of the JitoSingle band by the missing confidence, so an
+//! uncertain high score buys partial protection instead of a full bundle and
+//! an uncertain low score is not sent unprotected.
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard};
+use std::time::Duration;
+
+use crate::config::{SentinelConfig, SlotRiskSettings};
+use crate::latency::{LatencyTracer, PipelineStage};
+use crate::leader_guard::UpcomingLeaders;
+use crate::types::{MevRiskScore, RouteType};
+
+/// Weight of a new confirmation latency sample in the EWMA
+const LATENCY_ALPHA: f64 = 0.2;
+
+/// One leader's share of the landing window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LeaderExposure {
+    pub leader: Pubkey,
+    pub slots: u32,
+
+    /// Probability the transaction lands in one of this leader's slots
+    pub landing_probability: f64,
+    pub risk: f32,
+}
+
+/// Aggregate risk over the look-ahead window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowRisk {
+    /// Landing-probability-weighted leader risk (0-1), conditional on landing in the window
+    pub risk: f32,
+
+    /// Probability of landing within the window at all
+    pub landing_probability: f64,
+
+    /// Confirmation latency the forecast assumed (slots)
+    pub latency_slots: f64,
+
+    /// Per-leader exposure, highest weighted risk first
+    pub leaders: Vec<LeaderExposure>,
+}
+
+impl WindowRisk {
+    /// Leader contributing the most weighted risk
+    pub fn riskiest(&self) -> Option<&LeaderExposure> {
+        self.leaders.first()
+    }
+}
+
+/// Forecasts leader risk over the slots a transaction may land in
+pub struct SlotRiskForecaster {
+    settings: RwLock<SlotRiskSettings>,
+    risks: RwLock<HashMap<Pubkey, f32>>,
+
+    /// EWMA confirmation latency (ms); None until the first sample
+    latency_ms: RwLock<Option<f64>>,
+}
+
+impl SlotRiskForecaster {
+    pub fn new(settings: SlotRiskSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            risks: RwLock::new(HashMap::new()),
+            latency_ms: RwLock::new(None),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        Self::new(config.slot_risk.clone())
+    }
+
+    /// Apply the hot-reloadable `[slot_risk]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.slot_risk.clone();
+    }
+
+    /// Replace validator risk scores (identity → risk 0-1); unknown leaders score 0
+    pub fn set_validator_risks(&self, risks: HashMap<Pubkey, f32>) {
+        *self.risks.write().unwrap_or_else(|p| p.into_inner()) = risks;
+    }
+
+    /// Record an observed submit-to-confirmation latency
+    pub fn observe_confirmation(&self, latency: Duration) {
+        let sample = latency.as_secs_f64() * 1000.0;
+        let mut current = self.latency_ms.write().unwrap_or_else(|p| p.into_inner());
+        *current = Some(match *current {
+            Some(ms) => ms + LATENCY_ALPHA * (sample - ms),
+            None => sample,
+        });
+    }
+
+    /// Current confirmation latency estimate (one slot until observed)
+    pub fn confirmation_latency(&self) -> Duration {
+        let ms = self
+            .latency_ms
+            .read()
+            .unwrap_or_else(|p| p.into_inner())
+            .unwrap_or(self.read_settings().slot_time_ms as f64);
+        Duration::from_secs_f64(ms / 1000.0)
+    }
+
+    /// Window risk for a transaction sent at `current_slot`
+    pub fn forecast(&self, current_slot: u64, leaders: &UpcomingLeaders) -> WindowRisk {
+        self.forecast_with_latency(current_slot, leaders, self.confirmation_latency())
+    }
+
+    /// Window risk assuming the given confirmation latency
+    pub fn forecast_with_latency(
+        &self,
+        current_slot: u64,
+        leaders: &UpcomingLeaders,
+        latency: Duration,
+    ) -> WindowRisk {
+        let settings = self.read_settings().clone();
+        let risks = self.risks.read().unwrap_or_else(|p| p.into_inner());
+
+        let window_slots = settings.lookahead_leaders * settings.slots_per_leader;
+        let latency_slots = latency.as_secs_f64() * 1000.0 / settings.slot_time_ms.max(1) as f64;
+        // Sub-slot latencies still land in the current slot at the earliest
+        let mean = latency_slots.max(0.5);
+        let cdf = |slots: f64| 1.0 - (-slots / mean).exp();
+
+        let mut exposures: Vec<LeaderExposure> = Vec::new();
+        for offset in 0..window_slots {
+            let Some(leader) = leaders.leader_at(current_slot + offset) else {
+                continue;
+            };
+            let p = cdf((offset + 1) as f64) - cdf(offset as f64);
+            match exposures.iter_mut().find(|e| e.leader == *leader) {
+                Some(exposure) => {
+                    exposure.slots += 1;
+                    exposure.landing_probability += p;
+                }
+                None => exposures.push(LeaderExposure {
+                    leader: *leader,
+                    slots: 1,
+                    landing_probability: p,
+                    risk: risks.get(leader).copied().unwrap_or(0.0).clamp(0.0, 1.0),
+                }),
+            }
+        }
+
+        let landing_probability: f64 = exposures.iter().map(|e| e.landing_probability).sum();
+        let weighted: f64 = exposures.iter().map(|e| e.landing_probability * e.risk as f64).sum();
+        let risk = if landing_probability > 0.0 { (weighted / landing_probability) as f32 } else { 0.0 };
+
+        exposures.sort_by(|a, b| {
+            let weight = |e: &LeaderExposure| e.landing_probability * e.risk as f64;
+            weight(b).total_cmp(&weight(a))
+        });
+
+        WindowRisk {
+            risk,
+            landing_probability,
+            latency_slots,
+            leaders: exposures,
+        }
+    }
+
+    pub fn settings(&self) -> SlotRiskSettings {
+        self.read_settings().clone()
+    }
+
+    /// Score and window risk combined, as compared against the routing bands
+    pub fn routing_risk(&self, score: MevRiskScore, window: &WindowRisk) -> f32 {
+        1.0 - (1.0 - routing_score(score, &self.read_settings())) * (1.0 - window.risk)
+    }
+
+    /// Route for a transaction with risk `score` sent into `window`
+    pub fn select_route(&self, score: MevRiskScore, window: &WindowRisk) -> RouteType {
+        self.select_route_with_bias(score, window, 0.0)
+    }
+
+    /// [`Self::select_route`] with `bias` added to the routing risk (a `ProtectionTierProfile::route_bias`)
+    #[tracing::instrument(name = "routing.select", skip_all, fields(score = score.score(), window = window.risk, bias))]
+    pub fn select_route_with_bias(&self, score: MevRiskScore, window: &WindowRisk, bias: f32) -> RouteType {
+        let combined = (self.routing_risk(score, window) + bias).clamp(0.0, 1.0);
+        let settings = self.read_settings();
+        if combined >= settings.jito_bundle_threshold {
+            RouteType::JitoBundle
+        } else if combined >= settings.jito_single_threshold {
+            RouteType::JitoSingle
+        } else {
+            RouteType::StandardRpc
+        }
+    }
+
+    /// [`Self::select_route_with_bias`], recording the routing stage on a request's latency tracer
+    pub fn select_route_traced(
+        &self,
+        score: MevRiskScore,
+        window: &WindowRisk,
+        bias: f32,
+        tracer: &mut LatencyTracer,
+    ) -> RouteType {
+        tracer.time(PipelineStage::Route, || self.select_route_with_bias(score, window, bias))
+    }
+
+    fn read_settings(&self) -> RwLockReadGuard<'_, SlotRiskSettings> {
+        self.settings.read().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// Score used for routing: low-confidence scores shrink toward the JitoSingle band
+fn routing_score(score: MevRiskScore, settings: &SlotRiskSettings) -> f32 {
+    match score.confidence() {
+        Some(confidence) if confidence < settings.min_route_confidence => {
+            let center = (settings.jito_single_threshold + settings.jito_bundle_threshold) / 2.0;
+            center + (score.score() - center) * confidence
+        }
+        _ => score.score(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Four 4-slot rotations starting at slot 100
+    fn schedule(rotation: [Pubkey; 4]) -> UpcomingLeaders {
+        UpcomingLeaders::new(100, rotation.iter().flat_map(|l| [*l; 4]).collect())
+    }
+
+    fn forecaster(risky: Pubkey) -> SlotRiskForecaster {
+        let forecaster = SlotRiskForecaster::new(SlotRiskSettings::default());
+        forecaster.set_validator_risks(HashMap::from([(risky, 0.9)]));
+        forecaster
+    }
+
+    #[test]
+    fn test_later_leaders_weigh_less_at_low_latency() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let forecaster = forecaster(bad);
+
+        let next = forecaster.forecast_with_latency(100, &schedule([bad, good, good, good]), Duration::from_millis(400));
+        let later = forecaster.forecast_with_latency(100, &schedule([good, good, good, bad]), Duration::from_millis(400));
+
+        assert!(next.risk > 0.8, "risk {}", next.risk);
+        assert!(later.risk < 0.05, "risk {}", later.risk);
+        assert_eq!(next.riskiest().unwrap().leader, bad);
+        assert!(next.landing_probability > 0.99);
+    }
+
+    #[test]
+    fn test_high_latency_spreads_risk_over_window() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let forecaster = forecaster(bad);
+        let leaders = schedule([good, good, bad, good]);
+
+        let fast = forecaster.forecast_with_latency(100, &leaders, Duration::from_millis(200));
+        let slow = forecaster.forecast_with_latency(100, &leaders, Duration::from_millis(4_000));
+
+        // The next leader is clean, but a slow confirmation likely lands in rotation 3
+        assert!(fast.risk < 0.01);
+        assert!(slow.risk > 0.15, "risk {}", slow.risk);
+        assert!(slow.landing_probability < fast.landing_probability);
+        assert_eq!(slow.leaders.len(), 2);
+    }
+
+    #[test]
+    fn test_latency_ewma() {
+        let forecaster = SlotRiskForecaster::new(SlotRiskSettings::default());
+        assert_eq!(forecaster.confirmation_latency(), Duration::from_millis(400));
+
+        forecaster.observe_confirmation(Duration::from_millis(1_000));
+        forecaster.observe_confirmation(Duration::from_millis(2_000));
+        assert_eq!(forecaster.confirmation_latency(), Duration::from_millis(1_200));
+    }
+
+    #[test]
+    fn test_route_selection_uses_window_risk() {
+        let (bad, good) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let forecaster = forecaster(bad);
+        let latency = Duration::from_millis(400);
+
+        let clean = forecaster.forecast_with_latency(100, &schedule([good; 4]), latency);
+        let risky = forecaster.forecast_with_latency(100, &schedule([bad, good, good, good]), latency);
+
+        assert_eq!(forecaster.select_route(MevRiskScore::new(0.1), &clean), RouteType::StandardRpc);
+        assert_eq!(forecaster.select_route(MevRiskScore::new(0.4), &clean), RouteType::JitoSingle);
+        assert_eq!(forecaster.select_route(MevRiskScore::new(0.1), &risky), RouteType::JitoBundle);
+    }
+
+    #[test]
+    fn test_route_bias_shifts_bands() {
+        let good = Pubkey::new_unique();
+        let forecaster = forecaster(Pubkey::new_unique());
+        let clean = forecaster.forecast_with_latency(100, &schedule([good; 4]), Duration::from_millis(400));
+
+        let score = MevRiskScore::new(0.45);
+        assert_eq!(forecaster.select_route(score, &clean), RouteType::JitoSingle);
+        assert_eq!(forecaster.select_route_with_bias(score, &clean, 0.2), RouteType::JitoBundle);
+        assert_eq!(forecaster.select_route_with_bias(score, &clean, -0.2), RouteType::StandardRpc);
+
+        let mut tracer = LatencyTracer::new();
+        assert_eq!(forecaster.select_route_traced(score, &clean, 0.2, &mut tracer), RouteType::JitoBundle);
+        assert!(tracer.stage(PipelineStage::Route).is_some());
+    }
+
+    #[test]
+    fn test_low_confidence_scores_route_toward_single() {
+        let good = Pubkey::new_unique();
+        let forecaster = forecaster(Pubkey::new_unique());
+        let clean = forecaster.forecast_with_latency(100, &schedule([good; 4]), Duration::from_millis(400));
+
+        let high = MevRiskScore::new(0.9);
+        assert_eq!(forecaster.select_route(high, &clean), RouteType::JitoBundle);
+        assert_eq!(forecaster.select_route(high.with_confidence(0.9), &clean), RouteType::JitoBundle);
+        assert_eq!(forecaster.select_route(high.with_confidence(0.2), &clean), RouteType::JitoSingle);
+
+        let low = MevRiskScore::new(0.05);
+        assert_eq!(forecaster.select_route(low.with_confidence(0.95), &clean), RouteType::StandardRpc);
+        assert_eq!(forecaster.select_route(low.with_confidence(0.3), &clean), RouteType::JitoSingle);
+    }
+}
@@ -0,0 +1,338 @@
+//! Solana Pay Transaction Requests
+//!
+//! Merchants hand customers a `solana:<url>` link (or QR code) for an intent
+//! registered with the router; any Solana Pay wallet can then sign the
+//! protected swap without knowing about Sentinel. The API service mounts the
+//! pair at `/api/v1/pay/<intent_id>`:
+//! - `GET` answers with the merchant's `label` and `icon`
+//!   ([`TransactionRequestGetResponse`]), which the wallet shows before asking
+//! - `POST` with body `{ "account": "<wallet>" }` resolves the intent, checks
+//!   the wallet is its owner and that it is still pending, and answers with the
+//!   unsigned transaction and a `message` ([`TransactionRequestPostResponse`])
+//!
+//! The transaction is built like a swap action's: compute budget from the
+//! intent's fee caps and the jitodontfront marker on the swap instruction,
+//! optionally bracketed by a [`ConsentGuard`]. Solana Pay `reference` keys on
+//! the link are appended read-only to the swap instruction so the merchant can
+//! find the signature with `getSignaturesForAddress`.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::str::FromStr;
+
+use crate::actions::{mark_dont_front, ACTION_COMPUTE_UNIT_LIMIT};
+use crate::clock::Clock;
+use crate::consent_guard::{ConsentGuard, GuardTerms};
+use crate::intent::{Intent, IntentStatus};
+use crate::intent_registry::IntentRegistry;
+use crate::preflight::compute_budget_instructions;
+use crate::{Result, SentinelError};
+
+/// URL scheme of Solana Pay links
+pub const SOLANA_PAY_SCHEME: &str = "solana:";
+
+/// `GET` body: what the wallet shows before requesting the transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionRequestGetResponse {
+    pub label: String,
+
+    /// Absolute SVG, PNG or WebP URL
+    pub icon: String,
+}
+
+/// `POST` body from the wallet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionRequestPost {
+    /// Wallet that will sign (base58)
+    pub account: String,
+}
+
+/// `POST` answer: transaction for the wallet to sign and send
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionRequestPostResponse {
+    /// Bincode-serialized unsigned transaction, base64
+    pub transaction: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Intent and `reference` keys named by a transaction request URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionRequest {
+    pub intent_id: String,
+    pub references: Vec<Pubkey>,
+}
+
+impl TransactionRequest {
+    /// Parse `.../pay/<intent_id>[?reference=<pubkey>...]`
+    pub fn parse(path_and_query: &str) -> Result<Self> {
+        let (path, query) = path_and_query.split_once('?').unwrap_or((path_and_query, ""));
+        let intent_id = path.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+        if intent_id.is_empty() {
+            return Err(SentinelError::InvalidIntent(
+                "Transaction request is missing the intent id".to_string(),
+            ));
+        }
+
+        let references = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .filter(|(key, _)| *key == "reference")
+            .map(|(_, value)| {
+                Pubkey::from_str(value)
+                    .map_err(|_| SentinelError::InvalidIntent(format!("Invalid reference {}", value)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { intent_id: intent_id.to_string(), references })
+    }
+}
+
+/// Serves Solana Pay transaction requests for registered intents
+#[derive(Debug, Clone)]
+pub struct TransactionRequestService {
+    /// Absolute URL requests are served under, e.g. `https://router.example/api/v1/pay`
+    base_url: String,
+    label: String,
+    icon: String,
+}
+
+impl TransactionRequestService {
+    pub fn new(base_url: impl Into<String>, label: impl Into<String>, icon: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            label: label.into(),
+            icon: icon.into(),
+        }
+    }
+
+    /// `solana:` link for `intent_id`; the request URL is percent-encoded when it has a query
+    pub fn link(&self, intent_id: &str, references: &[Pubkey]) -> String {
+        let mut url = format!("{}/{}", self.base_url, intent_id);
+        if references.is_empty() {
+            return format!("{}{}", SOLANA_PAY_SCHEME, url);
+        }
+
+        let query: Vec<String> = references.iter().map(|r| format!("reference={}", r)).collect();
+        url.push('?');
+        url.push_str(&query.join("&"));
+        format!("{}{}", SOLANA_PAY_SCHEME, percent_encode(&url))
+    }
+
+    /// `GET` response
+    pub fn metadata(&self) -> TransactionRequestGetResponse {
+        TransactionRequestGetResponse {
+            label: self.label.clone(),
+            icon: self.icon.clone(),
+        }
+    }
+
+    /// Intent a `POST` asks for, provided `body.account` owns it and it can still execute
+    pub fn intent(
+        &self,
+        registry: &IntentRegistry,
+        request: &TransactionRequest,
+        body: &TransactionRequestPost,
+        clock: &dyn Clock,
+    ) -> Result<Intent> {
+        let account = Pubkey::from_str(&body.account).map_err(|_| {
+            SentinelError::InvalidIntent(format!("Invalid transaction request account {}", body.account))
+        })?;
+        let entry = registry
+            .get(&request.intent_id)
+            .ok_or_else(|| SentinelError::InvalidIntent(format!("Unknown intent {}", request.intent_id)))?;
+
+        if entry.intent.user_public_key != account {
+            return Err(SentinelError::Unauthorized(format!(
+                "Intent {} belongs to another wallet",
+                request.intent_id
+            )));
+        }
+        if entry.status != IntentStatus::Pending {
+            return Err(SentinelError::InvalidIntent(format!(
+                "Intent {} is no longer pending ({:?})",
+                request.intent_id, entry.status
+            )));
+        }
+        entry.intent.validate_at(clock)?;
+        Ok(entry.intent)
+    }
+
+    /// `POST` response: the intent's swap, dontfront-marked and tagged with the request's references
+    pub fn transaction(
+        &self,
+        intent: &Intent,
+        request: &TransactionRequest,
+        swap_instruction: Instruction,
+    ) -> Result<TransactionRequestPostResponse> {
+        let swap = Self::tagged(swap_instruction, &request.references);
+        self.respond(intent, vec![swap])
+    }
+
+    /// [`Self::transaction`] bracketed by `guard`, so the swap reverts below `minimum_received`
+    pub fn guarded_transaction(
+        &self,
+        intent: &Intent,
+        request: &TransactionRequest,
+        swap_instruction: Instruction,
+        guard: &ConsentGuard,
+        output_token_account: Pubkey,
+    ) -> Result<TransactionRequestPostResponse> {
+        let terms = GuardTerms::for_intent(intent, output_token_account)?;
+        let swap = Self::tagged(swap_instruction, &request.references);
+        self.respond(intent, guard.wrap(&terms, vec![swap]))
+    }
+
+    fn tagged(mut swap_instruction: Instruction, references: &[Pubkey]) -> Instruction {
+        mark_dont_front(&mut swap_instruction);
+        for reference in references {
+            if !swap_instruction.accounts.iter().any(|a| a.pubkey == *reference) {
+                swap_instruction.accounts.push(AccountMeta::new_readonly(*reference, false));
+            }
+        }
+        swap_instruction
+    }
+
+    fn respond(&self, intent: &Intent, protected: Vec<Instruction>) -> Result<TransactionRequestPostResponse> {
+        let mut instructions = compute_budget_instructions(intent, ACTION_COMPUTE_UNIT_LIMIT).to_vec();
+        instructions.extend(protected);
+
+        let mut transaction = Transaction::new_unsigned(Message::new(&instructions, Some(&intent.user_public_key)));
+        transaction.message.recent_blockhash = intent.consent_block.recent_blockhash;
+        let bytes = bincode::serialize(&transaction).map_err(|e| {
+            SentinelError::SerializationError(format!("Failed to serialize transaction request: {}", e))
+        })?;
+
+        Ok(TransactionRequestPostResponse {
+            transaction: BASE64.encode(bytes),
+            message: Some(format!("{}: MEV-protected swap (intent {})", self.label, intent.intent_id)),
+        })
+    }
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() * 3 / 2);
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DONT_FRONT_MARKER;
+    use crate::clock::ManualClock;
+    use crate::intent::Constraints;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn service() -> TransactionRequestService {
+        let icon = "https://acme.example/icon.svg";
+        TransactionRequestService::new("https://router.example/api/v1/pay/", "Acme Store", icon)
+    }
+
+    fn intent(user: Pubkey) -> Intent {
+        let mut intent = Intent {
+            intent_id: "pay-1".to_string(),
+            user_public_key: user,
+            constraints: Constraints {
+                expiry_timestamp: Some(NOW + 120),
+                ..Default::default()
+            },
+            ..Intent::test_swap()
+        };
+        intent.test_swap_details().minimum_received = Some(990_000);
+        intent
+    }
+
+    fn decode(response: &TransactionRequestPostResponse) -> Transaction {
+        bincode::deserialize(&BASE64.decode(&response.transaction).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_link_and_request_round_trip() {
+        let reference = Pubkey::new_unique();
+        assert_eq!(service().link("pay-1", &[]), "solana:https://router.example/api/v1/pay/pay-1");
+
+        let link = service().link("pay-1", &[reference]);
+        assert!(link.starts_with("solana:https%3A%2F%2Frouter.example%2Fapi%2Fv1%2Fpay%2Fpay-1%3Freference%3D"));
+
+        let request = TransactionRequest::parse(&format!("/api/v1/pay/pay-1?reference={}&label=x", reference)).unwrap();
+        assert_eq!(request, TransactionRequest { intent_id: "pay-1".to_string(), references: vec![reference] });
+        assert!(TransactionRequest::parse("/?reference=nope").is_err());
+
+        let json = serde_json::to_value(service().metadata()).unwrap();
+        assert_eq!(json, serde_json::json!({ "label": "Acme Store", "icon": "https://acme.example/icon.svg" }));
+    }
+
+    #[test]
+    fn test_post_returns_protected_transaction_for_owner() {
+        let clock = ManualClock::at_timestamp(NOW);
+        let wallet = Pubkey::new_unique();
+        let registry = IntentRegistry::new();
+        registry.register(intent(wallet), NOW).unwrap();
+
+        let reference = Pubkey::new_unique();
+        let request = TransactionRequest { intent_id: "pay-1".to_string(), references: vec![reference] };
+        let body = TransactionRequestPost { account: wallet.to_string() };
+        let resolved = service().intent(&registry, &request, &body, &clock).unwrap();
+
+        let swap = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![AccountMeta::new(wallet, true)]);
+        let response = service().transaction(&resolved, &request, swap.clone()).unwrap();
+        assert!(response.message.as_deref().unwrap().contains("pay-1"));
+
+        let transaction = decode(&response);
+        let keys = &transaction.message.account_keys;
+        assert_eq!(keys[0], wallet);
+        assert_eq!(transaction.message.recent_blockhash, resolved.consent_block.recent_blockhash);
+        assert_eq!(transaction.message.instructions.len(), 3);
+        assert!(keys.contains(&Pubkey::from_str(DONT_FRONT_MARKER).unwrap()) && keys.contains(&reference));
+
+        let guard = ConsentGuard::new(Pubkey::new_unique());
+        let guarded = service()
+            .guarded_transaction(&resolved, &request, swap, &guard, Pubkey::new_unique())
+            .unwrap();
+        assert_eq!(decode(&guarded).message.instructions.len(), 5);
+    }
+
+    #[test]
+    fn test_post_rejects_other_wallets_and_settled_intents() {
+        let clock = ManualClock::at_timestamp(NOW);
+        let wallet = Pubkey::new_unique();
+        let registry = IntentRegistry::new();
+        registry.register(intent(wallet), NOW).unwrap();
+        let request = TransactionRequest { intent_id: "pay-1".to_string(), references: vec![] };
+
+        let stranger = TransactionRequestPost { account: Pubkey::new_unique().to_string() };
+        assert!(matches!(
+            service().intent(&registry, &request, &stranger, &clock),
+            Err(SentinelError::Unauthorized(_))
+        ));
+
+        let owner = TransactionRequestPost { account: wallet.to_string() };
+        let unknown = TransactionRequest { intent_id: "pay-2".to_string(), references: vec![] };
+        assert!(service().intent(&registry, &unknown, &owner, &clock).is_err());
+
+        clock.advance(std::time::Duration::from_secs(600));
+        assert!(service().intent(&registry, &request, &owner, &clock).is_err());
+
+        registry.set_status("pay-1", IntentStatus::Submitted);
+        let fresh = ManualClock::at_timestamp(NOW);
+        assert!(service()
+            .intent(&registry, &request, &owner, &fresh)
+            .unwrap_err()
+            .to_string()
+            .contains("no longer pending"));
+    }
+}
@@ -0,0 +1,1064 @@
+//! Pluggable Storage
+//!
+//! Shadow predictions, decision records and intent data used to be persisted
+//! ad hoc (hand-written JSONL here, a sled tree there). Everything now goes
+//! through two primitives:
+//! - [`AppendLog`]: ordered, append-only stream of records (one JSON document each)
+//! - [`KvStore`]: ordered byte-keyed map with range scans, for indexed lookups
+//!
+//! Query layers (e.g. the shadow prediction store in `sentinel-ai-engine`) are
+//! built on top of these. [`Storage::from_config`] opens the backend selected
+//! by `[storage]`:
+//!
+//! | backend    | append log                        | KV                          | feature    |
+//! |------------|-----------------------------------|-----------------------------|------------|
+//! | `file`     | `<path>/<stream>.jsonl`           | -                           | always     |
+//! | `memory`   | in-process                        | in-process                  | always     |
+//! | `sled`     | tree `log:<stream>`               | tree `kv:<namespace>`       | `sled`     |
+//! | `postgres` | table `sentinel_log`              | table `sentinel_kv`         | `postgres` |
+//! | `s3`       | one object per appended batch     | one object per key          | `s3`       |
+//!
+//! All calls block; use `spawn_blocking` from async code.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::config::{StorageBackendKind, StorageSettings};
+use crate::error::{Result, SentinelError};
+
+/// Ordered, append-only record stream
+pub trait AppendLog: Send + Sync {
+    /// Short identifier for logs
+    fn name(&self) -> &'static str;
+
+    /// Append `records` atomically where the backend allows it
+    ///
+    /// Records must not contain newlines (JSON documents never do).
+    fn append(&self, records: &[Vec<u8>]) -> Result<()>;
+
+    /// Visit records in append order until `visit` returns false
+    fn scan(&self, visit: &mut dyn FnMut(&[u8]) -> Result<bool>) -> Result<()>;
+}
+
+/// Callback for KV scans; return false to stop
+pub type KvVisit<'a> = &'a mut dyn FnMut(&[u8], &[u8]) -> Result<bool>;
+
+/// Ordered byte-keyed map
+pub trait KvStore: Send + Sync {
+    /// Short identifier for logs
+    fn name(&self) -> &'static str;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()>;
+
+    fn delete(&self, key: &[u8]) -> Result<()>;
+
+    /// Visit entries with keys >= `start` in key order until `visit` returns false
+    fn scan_from(&self, start: &[u8], visit: KvVisit<'_>) -> Result<()>;
+
+    /// Write many entries; backends with transactions override this
+    fn put_batch(&self, entries: &[(Vec<u8>, Vec<u8>)]) -> Result<()> {
+        entries.iter().try_for_each(|(key, value)| self.put(key, value))
+    }
+
+    /// Make previous writes durable
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Visit entries whose key starts with `prefix`, in key order
+    fn scan_prefix(&self, prefix: &[u8], visit: KvVisit<'_>) -> Result<()> {
+        self.scan_from(prefix, &mut |key, value| {
+            if key.starts_with(prefix) {
+                visit(key, value)
+            } else {
+                Ok(false)
+            }
+        })
+    }
+}
+
+/// Serialize `items` as JSON and append them as one batch
+pub fn append_json<T: Serialize>(log: &dyn AppendLog, items: &[T]) -> Result<()> {
+    let records = items
+        .iter()
+        .map(|item| serde_json::to_vec(item).map_err(|e| SentinelError::SerializationError(e.to_string())))
+        .collect::<Result<Vec<_>>>()?;
+    log.append(&records)
+}
+
+/// Every record of `log` that parses as `T` (others are skipped)
+pub fn read_json<T: DeserializeOwned>(log: &dyn AppendLog) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    log.scan(&mut |record| {
+        if let Ok(item) = serde_json::from_slice(record) {
+            items.push(item);
+        }
+        Ok(true)
+    })?;
+    Ok(items)
+}
+
+/// Stream and namespace names: `[A-Za-z0-9_-]` segments separated by `/`
+fn checked_name(name: &str) -> Result<&str> {
+    let valid = !name.is_empty()
+        && name.split('/').all(|segment| {
+            !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        });
+    if valid {
+        Ok(name)
+    } else {
+        Err(SentinelError::ConfigError(format!("Invalid storage stream name '{}'", name)))
+    }
+}
+
+fn check_record(record: &[u8]) -> Result<()> {
+    if record.contains(&b'\n') {
+        return Err(SentinelError::SerializationError(
+            "Append log records must not contain newlines".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// JSONL file; one record per line
+pub struct FileLog {
+    path: PathBuf,
+    append: Mutex<()>,
+}
+
+impl FileLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            append: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AppendLog for FileLog {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn append(&self, records: &[Vec<u8>]) -> Result<()> {
+        records.iter().try_for_each(|record| check_record(record))?;
+        let _guard = self.append.lock().unwrap_or_else(|p| p.into_inner());
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| SentinelError::IoError(format!("Failed to create log dir: {}", e)))?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| SentinelError::IoError(format!("Failed to open {:?}: {}", self.path, e)))?;
+
+        let mut writer = std::io::BufWriter::new(file);
+        for record in records {
+            writer
+                .write_all(record)
+                .and_then(|_| writer.write_all(b"\n"))
+                .map_err(|e| SentinelError::IoError(format!("Failed to write {:?}: {}", self.path, e)))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| SentinelError::IoError(format!("Failed to flush {:?}: {}", self.path, e)))
+    }
+
+    fn scan(&self, visit: &mut dyn FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(SentinelError::IoError(format!("Failed to open {:?}: {}", self.path, e))),
+        };
+
+        let mut reader = BufReader::new(file);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|e| SentinelError::IoError(format!("Failed to read {:?}: {}", self.path, e)))?;
+            if read == 0 {
+                return Ok(());
+            }
+            let record = line.strip_suffix(b"\n").unwrap_or(&line);
+            if !record.is_empty() && !visit(record)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// In-process log (tests, dry runs)
+#[derive(Default)]
+pub struct MemoryLog {
+    records: RwLock<Vec<Vec<u8>>>,
+}
+
+impl MemoryLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.read().unwrap_or_else(|p| p.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl AppendLog for MemoryLog {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn append(&self, records: &[Vec<u8>]) -> Result<()> {
+        records.iter().try_for_each(|record| check_record(record))?;
+        self.records
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .extend_from_slice(records);
+        Ok(())
+    }
+
+    fn scan(&self, visit: &mut dyn FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+        let records = self.records.read().unwrap_or_else(|p| p.into_inner());
+        for record in records.iter() {
+            if !visit(record)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// In-process ordered map (tests, dry runs)
+#[derive(Default)]
+pub struct MemoryKv {
+    entries: RwLock<BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl MemoryKv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KvStore for MemoryKv {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.entries.read().unwrap_or_else(|p| p.into_inner()).get(key).cloned())
+    }
+
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.entries
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.entries.write().unwrap_or_else(|p| p.into_inner()).remove(key);
+        Ok(())
+    }
+
+    fn scan_from(&self, start: &[u8], visit: KvVisit<'_>) -> Result<()> {
+        let entries = self.entries.read().unwrap_or_else(|p| p.into_inner());
+        for (key, value) in entries.range::<[u8], _>((std::ops::Bound::Included(start), std::ops::Bound::Unbounded)) {
+            if !visit(key, value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Opened storage backend handing out logs and KV namespaces
+pub struct Storage {
+    backend: Backend,
+}
+
+enum Backend {
+    File(PathBuf),
+    Memory {
+        logs: Mutex<HashMap<String, Arc<MemoryLog>>>,
+        kvs: Mutex<HashMap<String, Arc<MemoryKv>>>,
+    },
+    #[cfg(feature = "sled")]
+    Sled(sled::Db),
+    #[cfg(feature = "postgres")]
+    Postgres(Arc<Mutex<postgres::Client>>),
+    #[cfg(feature = "s3")]
+    S3 {
+        store: Arc<dyn object_store::ObjectStore>,
+        prefix: String,
+    },
+}
+
+impl Storage {
+    /// Open the backend selected by `settings`
+    ///
+    /// Fails if the backend's feature was not compiled in.
+    pub fn from_config(settings: &StorageSettings) -> Result<Self> {
+        let backend = match settings.backend {
+            StorageBackendKind::File => Backend::File(settings.path.clone()),
+            StorageBackendKind::Memory => return Ok(Self::memory()),
+            #[cfg(feature = "sled")]
+            StorageBackendKind::Sled => Backend::Sled(sled_backend::open(&settings.path.join("sled"))?),
+            #[cfg(feature = "postgres")]
+            StorageBackendKind::Postgres => {
+                let url = settings
+                    .postgres_url
+                    .as_deref()
+                    .ok_or_else(|| SentinelError::ConfigError("storage.postgres_url is not set".to_string()))?;
+                Backend::Postgres(postgres_backend::connect(url)?)
+            }
+            #[cfg(feature = "s3")]
+            StorageBackendKind::S3 => Backend::S3 {
+                store: s3_backend::connect(settings)?,
+                prefix: settings.s3_prefix.trim_matches('/').to_string(),
+            },
+            #[allow(unreachable_patterns)]
+            other => {
+                return Err(SentinelError::ConfigError(format!(
+                    "Storage backend {:?} is not compiled in (enable the matching sentinel-core feature)",
+                    other
+                )))
+            }
+        };
+        tracing::info!("💾 Storage backend: {:?}", settings.backend);
+        Ok(Self { backend })
+    }
+
+    /// JSONL files under `root`
+    pub fn file(root: impl Into<PathBuf>) -> Self {
+        Self {
+            backend: Backend::File(root.into()),
+        }
+    }
+
+    /// In-process backend; the same name always yields the same log or map
+    pub fn memory() -> Self {
+        Self {
+            backend: Backend::Memory {
+                logs: Mutex::new(HashMap::new()),
+                kvs: Mutex::new(HashMap::new()),
+            },
+        }
+    }
+
+    /// Objects in `store` under `prefix` (e.g. `object_store::memory::InMemory` in tests)
+    #[cfg(feature = "s3")]
+    pub fn object_store(store: Arc<dyn object_store::ObjectStore>, prefix: &str) -> Self {
+        Self {
+            backend: Backend::S3 {
+                store,
+                prefix: prefix.trim_matches('/').to_string(),
+            },
+        }
+    }
+
+    pub fn kind(&self) -> StorageBackendKind {
+        match &self.backend {
+            Backend::File(_) => StorageBackendKind::File,
+            Backend::Memory { .. } => StorageBackendKind::Memory,
+            #[cfg(feature = "sled")]
+            Backend::Sled(_) => StorageBackendKind::Sled,
+            #[cfg(feature = "postgres")]
+            Backend::Postgres(_) => StorageBackendKind::Postgres,
+            #[cfg(feature = "s3")]
+            Backend::S3 { .. } => StorageBackendKind::S3,
+        }
+    }
+
+    /// Append log for `stream` (e.g. `shadow_predictions`, `acme/decisions`)
+    pub fn log(&self, stream: &str) -> Result<Arc<dyn AppendLog>> {
+        let stream = checked_name(stream)?;
+        Ok(match &self.backend {
+            Backend::File(root) => Arc::new(FileLog::new(root.join(format!("{}.jsonl", stream)))),
+            Backend::Memory { logs, .. } => logs
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .entry(stream.to_string())
+                .or_default()
+                .clone(),
+            #[cfg(feature = "sled")]
+            Backend::Sled(db) => Arc::new(sled_backend::SledLog::open(db, stream)?),
+            #[cfg(feature = "postgres")]
+            Backend::Postgres(client) => Arc::new(postgres_backend::PostgresLog::new(client.clone(), stream)),
+            #[cfg(feature = "s3")]
+            Backend::S3 { store, prefix } => Arc::new(s3_backend::S3Log::new(store.clone(), prefix, stream)),
+        })
+    }
+
+    /// Ordered map for `namespace`
+    pub fn kv(&self, namespace: &str) -> Result<Arc<dyn KvStore>> {
+        let namespace = checked_name(namespace)?;
+        Ok(match &self.backend {
+            Backend::File(_) => {
+                return Err(SentinelError::ConfigError(
+                    "The file storage backend only provides append logs; use sled, postgres or s3".to_string(),
+                ))
+            }
+            Backend::Memory { kvs, .. } => kvs
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .entry(namespace.to_string())
+                .or_default()
+                .clone(),
+            #[cfg(feature = "sled")]
+            Backend::Sled(db) => Arc::new(sled_backend::SledKv::open(db, namespace)?),
+            #[cfg(feature = "postgres")]
+            Backend::Postgres(client) => Arc::new(postgres_backend::PostgresKv::new(client.clone(), namespace)),
+            #[cfg(feature = "s3")]
+            Backend::S3 { store, prefix } => Arc::new(s3_backend::S3Kv::new(store.clone(), prefix, namespace)),
+        })
+    }
+}
+
+#[cfg(feature = "sled")]
+mod sled_backend {
+    use super::*;
+
+    fn db_error(e: sled::Error) -> SentinelError {
+        SentinelError::IoError(format!("Sled storage: {}", e))
+    }
+
+    pub fn open(path: &Path) -> Result<sled::Db> {
+        sled::open(path).map_err(db_error)
+    }
+
+    /// Records keyed by `Db::generate_id` (monotonic, big-endian)
+    pub struct SledLog {
+        db: sled::Db,
+        tree: sled::Tree,
+    }
+
+    impl SledLog {
+        pub fn open(db: &sled::Db, stream: &str) -> Result<Self> {
+            Ok(Self {
+                db: db.clone(),
+                tree: db.open_tree(format!("log:{}", stream)).map_err(db_error)?,
+            })
+        }
+    }
+
+    impl AppendLog for SledLog {
+        fn name(&self) -> &'static str {
+            "sled"
+        }
+
+        fn append(&self, records: &[Vec<u8>]) -> Result<()> {
+            let mut batch = sled::Batch::default();
+            for record in records {
+                check_record(record)?;
+                let id = self.db.generate_id().map_err(db_error)?;
+                batch.insert(&id.to_be_bytes(), record.as_slice());
+            }
+            self.tree.apply_batch(batch).map_err(db_error)?;
+            self.tree.flush().map_err(db_error)?;
+            Ok(())
+        }
+
+        fn scan(&self, visit: &mut dyn FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+            for entry in self.tree.iter() {
+                let (_, value) = entry.map_err(db_error)?;
+                if !visit(&value)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    pub struct SledKv {
+        tree: sled::Tree,
+    }
+
+    impl SledKv {
+        pub fn open(db: &sled::Db, namespace: &str) -> Result<Self> {
+            Ok(Self {
+                tree: db.open_tree(format!("kv:{}", namespace)).map_err(db_error)?,
+            })
+        }
+    }
+
+    impl KvStore for SledKv {
+        fn name(&self) -> &'static str {
+            "sled"
+        }
+
+        fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+            Ok(self.tree.get(key).map_err(db_error)?.map(|value| value.to_vec()))
+        }
+
+        fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+            self.tree.insert(key, value).map_err(db_error)?;
+            Ok(())
+        }
+
+        fn delete(&self, key: &[u8]) -> Result<()> {
+            self.tree.remove(key).map_err(db_error)?;
+            Ok(())
+        }
+
+        fn scan_from(&self, start: &[u8], visit: KvVisit<'_>) -> Result<()> {
+            for entry in self.tree.range(start..) {
+                let (key, value) = entry.map_err(db_error)?;
+                if !visit(&key, &value)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+
+        fn put_batch(&self, entries: &[(Vec<u8>, Vec<u8>)]) -> Result<()> {
+            let mut batch = sled::Batch::default();
+            for (key, value) in entries {
+                batch.insert(key.as_slice(), value.as_slice());
+            }
+            self.tree.apply_batch(batch).map_err(db_error)
+        }
+
+        fn flush(&self) -> Result<()> {
+            self.tree.flush().map_err(db_error)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres_backend {
+    use super::*;
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS sentinel_log (
+            stream TEXT NOT NULL,
+            seq BIGSERIAL NOT NULL,
+            record BYTEA NOT NULL,
+            PRIMARY KEY (stream, seq)
+        );
+        CREATE TABLE IF NOT EXISTS sentinel_kv (
+            namespace TEXT NOT NULL,
+            key BYTEA NOT NULL,
+            value BYTEA NOT NULL,
+            PRIMARY KEY (namespace, key)
+        );
+    ";
+
+    /// Rows fetched per round trip while scanning
+    const SCAN_PAGE: i64 = 1_000;
+
+    type Client = Arc<Mutex<postgres::Client>>;
+
+    fn db_error(e: postgres::Error) -> SentinelError {
+        SentinelError::ConnectionError(format!("Postgres storage: {}", e))
+    }
+
+    /// Connect (e.g. `host=localhost user=sentinel dbname=sentinel`) and create the schema
+    pub fn connect(url: &str) -> Result<Client> {
+        let mut client = postgres::Client::connect(url, postgres::NoTls).map_err(db_error)?;
+        client.batch_execute(SCHEMA).map_err(db_error)?;
+        Ok(Arc::new(Mutex::new(client)))
+    }
+
+    pub struct PostgresLog {
+        client: Client,
+        stream: String,
+    }
+
+    impl PostgresLog {
+        pub fn new(client: Client, stream: &str) -> Self {
+            Self {
+                client,
+                stream: stream.to_string(),
+            }
+        }
+    }
+
+    impl AppendLog for PostgresLog {
+        fn name(&self) -> &'static str {
+            "postgres"
+        }
+
+        fn append(&self, records: &[Vec<u8>]) -> Result<()> {
+            records.iter().try_for_each(|record| check_record(record))?;
+            let mut client = self.client.lock().unwrap_or_else(|p| p.into_inner());
+            let mut tx = client.transaction().map_err(db_error)?;
+            let statement = tx
+                .prepare("INSERT INTO sentinel_log (stream, record) VALUES ($1, $2)")
+                .map_err(db_error)?;
+            for record in records {
+                tx.execute(&statement, &[&self.stream, record]).map_err(db_error)?;
+            }
+            tx.commit().map_err(db_error)
+        }
+
+        fn scan(&self, visit: &mut dyn FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+            let mut after = 0i64;
+            loop {
+                let rows = self
+                    .client
+                    .lock()
+                    .unwrap_or_else(|p| p.into_inner())
+                    .query(
+                        "SELECT seq, record FROM sentinel_log WHERE stream = $1 AND seq > $2 ORDER BY seq LIMIT $3",
+                        &[&self.stream, &after, &SCAN_PAGE],
+                    )
+                    .map_err(db_error)?;
+                for row in &rows {
+                    after = row.get(0);
+                    if !visit(row.get::<_, &[u8]>(1))? {
+                        return Ok(());
+                    }
+                }
+                if (rows.len() as i64) < SCAN_PAGE {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    pub struct PostgresKv {
+        client: Client,
+        namespace: String,
+    }
+
+    impl PostgresKv {
+        pub fn new(client: Client, namespace: &str) -> Self {
+            Self {
+                client,
+                namespace: namespace.to_string(),
+            }
+        }
+    }
+
+    impl KvStore for PostgresKv {
+        fn name(&self) -> &'static str {
+            "postgres"
+        }
+
+        fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+            let mut client = self.client.lock().unwrap_or_else(|p| p.into_inner());
+            let row = client
+                .query_opt(
+                    "SELECT value FROM sentinel_kv WHERE namespace = $1 AND key = $2",
+                    &[&self.namespace, &key],
+                )
+                .map_err(db_error)?;
+            Ok(row.map(|row| row.get(0)))
+        }
+
+        fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+            self.put_batch(&[(key.to_vec(), value.to_vec())])
+        }
+
+        fn delete(&self, key: &[u8]) -> Result<()> {
+            let mut client = self.client.lock().unwrap_or_else(|p| p.into_inner());
+            client
+                .execute(
+                    "DELETE FROM sentinel_kv WHERE namespace = $1 AND key = $2",
+                    &[&self.namespace, &key],
+                )
+                .map_err(db_error)?;
+            Ok(())
+        }
+
+        fn scan_from(&self, start: &[u8], visit: KvVisit<'_>) -> Result<()> {
+            let mut rows = self
+                .client
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .query(
+                    "SELECT key, value FROM sentinel_kv WHERE namespace = $1 AND key >= $2 ORDER BY key LIMIT $3",
+                    &[&self.namespace, &start, &SCAN_PAGE],
+                )
+                .map_err(db_error)?;
+            loop {
+                for row in &rows {
+                    if !visit(row.get::<_, &[u8]>(0), row.get::<_, &[u8]>(1))? {
+                        return Ok(());
+                    }
+                }
+                let Some(last) = rows.last().filter(|_| rows.len() as i64 == SCAN_PAGE) else {
+                    return Ok(());
+                };
+                let last: Vec<u8> = last.get(0);
+                rows = self
+                    .client
+                    .lock()
+                    .unwrap_or_else(|p| p.into_inner())
+                    .query(
+                        "SELECT key, value FROM sentinel_kv WHERE namespace = $1 AND key > $2 ORDER BY key LIMIT $3",
+                        &[&self.namespace, &last, &SCAN_PAGE],
+                    )
+                    .map_err(db_error)?;
+            }
+        }
+
+        fn put_batch(&self, entries: &[(Vec<u8>, Vec<u8>)]) -> Result<()> {
+            let mut client = self.client.lock().unwrap_or_else(|p| p.into_inner());
+            let mut tx = client.transaction().map_err(db_error)?;
+            let statement = tx
+                .prepare(
+                    "INSERT INTO sentinel_kv (namespace, key, value) VALUES ($1, $2, $3)
+                     ON CONFLICT (namespace, key) DO UPDATE SET value = EXCLUDED.value",
+                )
+                .map_err(db_error)?;
+            for (key, value) in entries {
+                tx.execute(&statement, &[&self.namespace, key, value]).map_err(db_error)?;
+            }
+            tx.commit().map_err(db_error)
+        }
+    }
+}
+
+#[cfg(feature = "s3")]
+mod s3_backend {
+    use super::*;
+    use futures_util::TryStreamExt;
+    use object_store::path::Path as ObjectPath;
+    use object_store::ObjectStore;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::OnceLock;
+
+    fn store_error(e: object_store::Error) -> SentinelError {
+        SentinelError::ConnectionError(format!("Object storage: {}", e))
+    }
+
+    /// Build an S3 client from `settings` plus the usual `AWS_*` environment
+    pub fn connect(settings: &StorageSettings) -> Result<Arc<dyn ObjectStore>> {
+        let bucket = settings
+            .s3_bucket
+            .as_deref()
+            .ok_or_else(|| SentinelError::ConfigError("storage.s3_bucket is not set".to_string()))?;
+        let mut builder = object_store::aws::AmazonS3Builder::from_env().with_bucket_name(bucket);
+        if let Some(region) = &settings.s3_region {
+            builder = builder.with_region(region);
+        }
+        if let Some(endpoint) = &settings.s3_endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"));
+        }
+        let store = builder.build().map_err(store_error)?;
+        Ok(Arc::new(store))
+    }
+
+    /// Drive an object store future to completion from blocking code
+    ///
+    /// Inside `spawn_blocking` the caller's runtime is reused; plain threads
+    /// share one lazily started runtime.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle.block_on(future),
+            Err(_) => RUNTIME
+                .get_or_init(|| {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .expect("Failed to start storage runtime")
+                })
+                .block_on(future),
+        }
+    }
+
+    /// Locations under `prefix`, sorted
+    fn list_sorted(store: &dyn ObjectStore, prefix: &ObjectPath) -> Result<Vec<ObjectPath>> {
+        let mut locations: Vec<ObjectPath> = block_on(
+            store
+                .list(Some(prefix))
+                .map_ok(|meta| meta.location)
+                .try_collect::<Vec<_>>(),
+        )
+        .map_err(store_error)?;
+        locations.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        Ok(locations)
+    }
+
+    fn fetch(store: &dyn ObjectStore, location: &ObjectPath) -> Result<Option<Vec<u8>>> {
+        let result = block_on(async {
+            let object = store.get(location).await?;
+            object.bytes().await
+        });
+        match result {
+            Ok(bytes) => Ok(Some(bytes.to_vec())),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(store_error(e)),
+        }
+    }
+
+    /// One JSONL object per appended batch: `<prefix>/logs/<stream>/<ms>-<seq>-<uuid>.jsonl`
+    ///
+    /// Names sort by append time; the uuid keeps concurrent writers apart.
+    pub struct S3Log {
+        store: Arc<dyn ObjectStore>,
+        dir: ObjectPath,
+        seq: AtomicU64,
+    }
+
+    impl S3Log {
+        pub fn new(store: Arc<dyn ObjectStore>, prefix: &str, stream: &str) -> Self {
+            Self {
+                store,
+                dir: ObjectPath::from(format!("{}/logs/{}", prefix, stream)),
+                seq: AtomicU64::new(0),
+            }
+        }
+    }
+
+    impl AppendLog for S3Log {
+        fn name(&self) -> &'static str {
+            "s3"
+        }
+
+        fn append(&self, records: &[Vec<u8>]) -> Result<()> {
+            if records.is_empty() {
+                return Ok(());
+            }
+            let mut body = Vec::new();
+            for record in records {
+                check_record(record)?;
+                body.extend_from_slice(record);
+                body.push(b'\n');
+            }
+            let name = format!(
+                "{:020}-{:010}-{}.jsonl",
+                chrono::Utc::now().timestamp_millis(),
+                self.seq.fetch_add(1, Ordering::Relaxed),
+                uuid::Uuid::new_v4().simple()
+            );
+            let location = self.dir.child(name);
+            block_on(self.store.put(&location, body.into())).map_err(store_error)?;
+            Ok(())
+        }
+
+        fn scan(&self, visit: &mut dyn FnMut(&[u8]) -> Result<bool>) -> Result<()> {
+            for location in list_sorted(self.store.as_ref(), &self.dir)? {
+                let Some(body) = fetch(self.store.as_ref(), &location)? else {
+                    continue;
+                };
+                for record in body.split(|b| *b == b'\n').filter(|r| !r.is_empty()) {
+                    if !visit(record)? {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// One object per key: `<prefix>/kv/<namespace>/<hex key>`
+    ///
+    /// Lowercase hex preserves byte order, so listings come back in key order.
+    pub struct S3Kv {
+        store: Arc<dyn ObjectStore>,
+        dir: ObjectPath,
+    }
+
+    impl S3Kv {
+        pub fn new(store: Arc<dyn ObjectStore>, prefix: &str, namespace: &str) -> Self {
+            Self {
+                store,
+                dir: ObjectPath::from(format!("{}/kv/{}", prefix, namespace)),
+            }
+        }
+
+        fn location(&self, key: &[u8]) -> ObjectPath {
+            // Empty keys still need a non-empty object name
+            self.dir.child(format!("k{}", hex::encode(key)))
+        }
+    }
+
+    impl KvStore for S3Kv {
+        fn name(&self) -> &'static str {
+            "s3"
+        }
+
+        fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+            fetch(self.store.as_ref(), &self.location(key))
+        }
+
+        fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+            block_on(self.store.put(&self.location(key), value.to_vec().into())).map_err(store_error)?;
+            Ok(())
+        }
+
+        fn delete(&self, key: &[u8]) -> Result<()> {
+            match block_on(self.store.delete(&self.location(key))) {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+                Err(e) => Err(store_error(e)),
+            }
+        }
+
+        fn scan_from(&self, start: &[u8], visit: KvVisit<'_>) -> Result<()> {
+            let start = self.location(start);
+            for location in list_sorted(self.store.as_ref(), &self.dir)? {
+                if location.as_ref() < start.as_ref() {
+                    continue;
+                }
+                let Some(key) = location
+                    .filename()
+                    .and_then(|name| name.strip_prefix('k'))
+                    .and_then(|hex_key| hex::decode(hex_key).ok())
+                else {
+                    continue;
+                };
+                let Some(value) = fetch(self.store.as_ref(), &location)? else {
+                    continue;
+                };
+                if !visit(&key, &value)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise_log(log: &dyn AppendLog) {
+        append_json(log, &[1u32, 2]).unwrap();
+        append_json(log, &[3u32]).unwrap();
+        assert_eq!(read_json::<u32>(log).unwrap(), vec![1, 2, 3]);
+
+        // Early stop
+        let mut seen = 0;
+        log.scan(&mut |_| {
+            seen += 1;
+            Ok(seen < 2)
+        })
+        .unwrap();
+        assert_eq!(seen, 2);
+
+        assert!(log.append(&[b"multi\nline".to_vec()]).is_err());
+    }
+
+    fn exercise_kv(kv: &dyn KvStore) {
+        assert_eq!(kv.get(b"missing").unwrap(), None);
+        kv.put_batch(&[
+            (b"b/2".to_vec(), b"two".to_vec()),
+            (b"a/1".to_vec(), b"one".to_vec()),
+            (b"b/1".to_vec(), b"uno".to_vec()),
+            (b"c".to_vec(), b"three".to_vec()),
+        ])
+        .unwrap();
+        kv.put(b"b/2", b"dos").unwrap();
+        kv.delete(b"c").unwrap();
+        kv.flush().unwrap();
+        assert_eq!(kv.get(b"b/2").unwrap().as_deref(), Some(&b"dos"[..]));
+        assert_eq!(kv.get(b"c").unwrap(), None);
+
+        let mut keys = Vec::new();
+        kv.scan_from(b"b", &mut |key, _| {
+            keys.push(key.to_vec());
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(keys, vec![b"b/1".to_vec(), b"b/2".to_vec()]);
+
+        let mut values = Vec::new();
+        kv.scan_prefix(b"a/", &mut |_, value| {
+            values.push(value.to_vec());
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(values, vec![b"one".to_vec()]);
+    }
+
+    #[test]
+    fn test_memory_backend() {
+        let storage = Storage::memory();
+        exercise_log(storage.log("decisions").unwrap().as_ref());
+        exercise_kv(storage.kv("intents").unwrap().as_ref());
+
+        // Same name, same log
+        assert_eq!(read_json::<u32>(storage.log("decisions").unwrap().as_ref()).unwrap().len(), 3);
+        assert!(storage.log("../escape").is_err());
+        assert!(storage.kv("").is_err());
+    }
+
+    #[test]
+    fn test_file_backend() {
+        let root = std::env::temp_dir().join(format!("storage-{}", uuid::Uuid::new_v4()));
+        let storage = Storage::from_config(&StorageSettings {
+            path: root.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(storage.kind(), StorageBackendKind::File);
+
+        let log = storage.log("acme/shadow_predictions").unwrap();
+        exercise_log(log.as_ref());
+        let raw = std::fs::read_to_string(root.join("acme/shadow_predictions.jsonl")).unwrap();
+        assert_eq!(raw, "1\n2\n3\n");
+
+        assert!(storage.kv("intents").is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(not(all(feature = "sled", feature = "postgres", feature = "s3")))]
+    #[test]
+    fn test_missing_feature_rejected() {
+        let backends = [StorageBackendKind::Sled, StorageBackendKind::Postgres, StorageBackendKind::S3];
+        let settings = |backend| StorageSettings {
+            backend,
+            postgres_url: Some("host=localhost".to_string()),
+            s3_bucket: Some("bucket".to_string()),
+            ..Default::default()
+        };
+        let compiled = [cfg!(feature = "sled"), cfg!(feature = "postgres"), cfg!(feature = "s3")];
+        for (backend, compiled) in backends.into_iter().zip(compiled) {
+            if !compiled {
+                let err = Storage::from_config(&settings(backend)).err().unwrap();
+                assert!(err.to_string().contains("not compiled in"));
+            }
+        }
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_sled_backend() {
+        let root = std::env::temp_dir().join(format!("storage-sled-{}", uuid::Uuid::new_v4()));
+        let storage = Storage::from_config(&StorageSettings {
+            backend: StorageBackendKind::Sled,
+            path: root.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+        exercise_log(storage.log("decisions").unwrap().as_ref());
+        exercise_kv(storage.kv("intents").unwrap().as_ref());
+        drop(storage);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_object_store_backend() {
+        let storage = Storage::object_store(Arc::new(object_store::memory::InMemory::new()), "/sentinel/");
+        assert_eq!(storage.kind(), StorageBackendKind::S3);
+        exercise_log(storage.log("decisions").unwrap().as_ref());
+        exercise_kv(storage.kv("intents").unwrap().as_ref());
+    }
+}
@@ -0,0 +1,315 @@
+//! Slot-Leader Aware Submission Timing
+//!
+//! A bundle sent a few hundred milliseconds earlier or later can land under a
+//! different leader. `SubmissionTimer` shifts a planned send time within
+//! `[planned - max_advance_ms, planned + max_delay_ms]` so the bundle reaches
+//! the block engine `send_lead_ms` before its landing point, aimed
+//! `boundary_margin_ms` inside either end of a slot whose leader is below
+//! `max_leader_risk`:
+//!
+//! ```text
+//! slot start = chain slot start + (slot - chain slot) × slot_time_ms
+//! send at    = target slot start + boundary_margin_ms - send_lead_ms   (delaying)
+//!            | next slot start - boundary_margin_ms - send_lead_ms     (advancing)
+//! ```
+//!
+//! Slot starts are extrapolated from the [`ChainClock`] (the local time its
+//! slot was observed). The unshifted plan wins whenever it is clean; otherwise
+//! the smallest shift onto a clean leader. When every reachable slot is risky
+//! the plan is [`SubmissionTiming::Avoid`], left to `LeaderGuard` policy. Without
+//! a fresh clock or a leader schedule, submissions go out as planned.
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::chain_clock::{ChainClock, ChainState};
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{SentinelConfig, SubmissionTimingSettings};
+use crate::leader_guard::UpcomingLeaders;
+
+/// Clock age, in slots, beyond which slot boundaries are not extrapolated
+const MAX_CLOCK_AGE_SLOTS: u64 = 4;
+
+/// When to send a bundle
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum SubmissionTiming {
+    /// Send at `send_at_ms` (Unix ms), `shift_ms` from the plan (negative = advanced)
+    Send {
+        send_at_ms: i64,
+        shift_ms: i64,
+        /// Slot the bundle should land in, when boundaries are known
+        target_slot: Option<u64>,
+        leader: Option<Pubkey>,
+    },
+
+    /// Every slot reachable within the shift bounds has a risky leader
+    Avoid { slot: u64, leader: Pubkey, risk: f32 },
+}
+
+impl SubmissionTiming {
+    pub fn is_shifted(&self) -> bool {
+        matches!(self, SubmissionTiming::Send { shift_ms, .. } if *shift_ms != 0)
+    }
+}
+
+/// Where a send time lands and how risky its leader is
+struct Landing {
+    slot: u64,
+    leader: Option<Pubkey>,
+    risky: Option<(Pubkey, f32)>,
+}
+
+/// Shifts bundle submissions onto low-risk leaders' slots
+pub struct SubmissionTimer {
+    settings: RwLock<SubmissionTimingSettings>,
+    risks: RwLock<HashMap<Pubkey, f32>>,
+    leaders: RwLock<Option<UpcomingLeaders>>,
+    chain: ChainClock,
+    clock: SharedClock,
+}
+
+impl SubmissionTimer {
+    pub fn new(settings: SubmissionTimingSettings, chain: ChainClock) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            risks: RwLock::new(HashMap::new()),
+            leaders: RwLock::new(None),
+            chain,
+            clock: system_clock(),
+        }
+    }
+
+    pub fn from_config(config: &SentinelConfig, chain: ChainClock) -> Self {
+        Self::new(config.submission_timing.clone(), chain)
+    }
+
+    /// Time source for planning and waiting (the chain clock's own should match)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Apply the hot-reloadable `[submission_timing]` section
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        *self.settings.write().unwrap_or_else(|p| p.into_inner()) = config.submission_timing.clone();
+    }
+
+    /// Replace validator risk scores (identity → risk 0-1); unknown leaders score 0
+    pub fn set_validator_risks(&self, risks: HashMap<Pubkey, f32>) {
+        *self.risks.write().unwrap_or_else(|p| p.into_inner()) = risks;
+    }
+
+    /// Replace the leader schedule slice (`getSlotLeaders`)
+    pub fn set_leaders(&self, leaders: UpcomingLeaders) {
+        *self.leaders.write().unwrap_or_else(|p| p.into_inner()) = Some(leaders);
+    }
+
+    pub fn settings(&self) -> SubmissionTimingSettings {
+        self.read_settings().clone()
+    }
+
+    /// Timing for a submission planned for now
+    pub fn plan_now(&self) -> SubmissionTiming {
+        self.plan(self.clock.unix_millis())
+    }
+
+    /// Timing for a submission planned for `planned_at_ms` (Unix ms)
+    pub fn plan(&self, planned_at_ms: i64) -> SubmissionTiming {
+        let settings = self.read_settings().clone();
+        let now = self.clock.unix_millis();
+        let planned = planned_at_ms.max(now);
+        let unshifted = SubmissionTiming::Send {
+            send_at_ms: planned,
+            shift_ms: planned - planned_at_ms,
+            target_slot: None,
+            leader: None,
+        };
+
+        let max_age = Duration::from_millis(settings.slot_time_ms * MAX_CLOCK_AGE_SLOTS);
+        let leaders = self.leaders.read().unwrap_or_else(|p| p.into_inner());
+        let Some(ref leaders) = *leaders else {
+            return unshifted;
+        };
+        if !settings.enabled || self.chain.is_stale(max_age) {
+            return unshifted;
+        }
+
+        let chain = self.chain.state();
+        let slot_ms = settings.slot_time_ms.max(1) as i64;
+        let lead = settings.send_lead_ms as i64;
+        let margin = settings.boundary_margin_ms as i64;
+        let earliest = (planned_at_ms - settings.max_advance_ms as i64).max(now);
+        let latest = (planned_at_ms + settings.max_delay_ms as i64).max(earliest);
+
+        let mut candidates = vec![planned];
+        for slot in slot_at(&chain, slot_ms, earliest + lead)..=slot_at(&chain, slot_ms, latest + lead) {
+            let first = slot_start(&chain, slot_ms, slot) + margin - lead;
+            let last = slot_start(&chain, slot_ms, slot + 1) - margin - lead;
+            candidates.extend([first, last].into_iter().filter(|send| (earliest..=latest).contains(send)));
+        }
+
+        let risks = self.risks.read().unwrap_or_else(|p| p.into_inner());
+        let landing = |send: i64| -> Landing {
+            let at = send + lead;
+            let slot = slot_at(&chain, slot_ms, at);
+            let leader = leaders.leader_at(slot).copied();
+            let mut exposed = vec![leader];
+            // A landing this close to the boundary may slip into the next slot
+            if slot_start(&chain, slot_ms, slot + 1) - at < margin {
+                exposed.push(leaders.leader_at(slot + 1).copied());
+            }
+            let risky = exposed
+                .into_iter()
+                .flatten()
+                .filter_map(|leader| Some((leader, *risks.get(&leader)?)))
+                .filter(|(_, risk)| *risk >= settings.max_leader_risk)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            Landing { slot, leader, risky }
+        };
+
+        let (send_at_ms, best) = candidates
+            .into_iter()
+            .map(|send| (send, landing(send)))
+            .min_by_key(|(send, landing)| (landing.risky.is_some(), (send - planned).abs()))
+            .expect("the planned time is always a candidate");
+
+        if let Some((leader, risk)) = best.risky {
+            return SubmissionTiming::Avoid {
+                slot: best.slot,
+                leader,
+                risk,
+            };
+        }
+        if send_at_ms != planned {
+            debug!(
+                "Shifting submission {}ms to land in slot {} ({:?})",
+                send_at_ms - planned_at_ms,
+                best.slot,
+                best.leader
+            );
+        }
+        SubmissionTiming::Send {
+            send_at_ms,
+            shift_ms: send_at_ms - planned_at_ms,
+            target_slot: Some(best.slot),
+            leader: best.leader,
+        }
+    }
+
+    /// Sleep until a `Send` timing's send time; `Avoid` returns at once
+    pub async fn wait(&self, timing: &SubmissionTiming) {
+        if let SubmissionTiming::Send { send_at_ms, .. } = timing {
+            let remaining = (send_at_ms - self.clock.unix_millis()).max(0) as u64;
+            if remaining > 0 {
+                tokio::time::sleep(Duration::from_millis(remaining)).await;
+            }
+        }
+    }
+
+    fn read_settings(&self) -> RwLockReadGuard<'_, SubmissionTimingSettings> {
+        self.settings.read().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// Slot in progress at `at_ms`, extrapolated from the chain clock
+fn slot_at(chain: &ChainState, slot_ms: i64, at_ms: i64) -> u64 {
+    chain
+        .slot
+        .saturating_add_signed((at_ms - chain.updated_at_ms).div_euclid(slot_ms))
+}
+
+fn slot_start(chain: &ChainState, slot_ms: i64, slot: u64) -> i64 {
+    chain.updated_at_ms + (slot as i64 - chain.slot as i64) * slot_ms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_clock::ChainSource;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    const START_MS: i64 = 1_700_000_000_000;
+
+    /// Clock at slot 100's start; four 4-slot rotations from slot 100
+    fn timer(rotation: [Pubkey; 4], risky: Pubkey) -> (SubmissionTimer, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::at_timestamp(START_MS / 1_000));
+        let chain = ChainClock::new().with_clock(clock.clone());
+        chain.observe_slot(100, ChainSource::Websocket);
+
+        let timer = SubmissionTimer::new(SubmissionTimingSettings::default(), chain).with_clock(clock.clone());
+        timer.set_leaders(UpcomingLeaders::new(100, rotation.iter().flat_map(|l| [*l; 4]).collect()));
+        timer.set_validator_risks(HashMap::from([(risky, 0.9)]));
+        (timer, clock)
+    }
+
+    #[test]
+    fn test_clean_plan_sent_unshifted() {
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (timer, _clock) = timer([good, good, bad, bad], bad);
+
+        let timing = timer.plan(START_MS + 100);
+        assert_eq!(
+            timing,
+            SubmissionTiming::Send {
+                send_at_ms: START_MS + 100,
+                shift_ms: 0,
+                target_slot: Some(100),
+                leader: Some(good),
+            }
+        );
+        assert!(!timing.is_shifted());
+    }
+
+    #[test]
+    fn test_delays_past_malicious_leader() {
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // Slots 100-103 bad, 104-107 good
+        let (timer, _clock) = timer([bad, good, good, good], bad);
+
+        // Planned to land in slot 101; slot 104 is beyond max_delay_ms
+        assert!(matches!(timer.plan(START_MS + 300), SubmissionTiming::Avoid { slot: 101, .. }));
+
+        // Planned to land at the end of slot 103: delayed into slot 104
+        let timing = timer.plan(START_MS + 1_200);
+        let SubmissionTiming::Send { send_at_ms, shift_ms, target_slot, leader } = timing else {
+            panic!("expected a send, got {:?}", timing);
+        };
+        assert_eq!(target_slot, Some(104));
+        assert_eq!(leader, Some(good));
+        assert_eq!(send_at_ms, START_MS + 1_600 + 50 - 150);
+        assert_eq!(shift_ms, 300);
+    }
+
+    #[test]
+    fn test_advances_ahead_of_malicious_leader() {
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (timer, clock) = timer([good, bad, good, good], bad);
+
+        // Planned to land 50ms into slot 104 (bad): brought forward to the end of slot 103
+        clock.advance(Duration::from_millis(1_000));
+        let timing = timer.plan(START_MS + 1_500);
+        assert!(matches!(timing, SubmissionTiming::Send { target_slot: Some(103), shift_ms: -100, .. }), "{:?}", timing);
+
+        // A landing within the margin of a risky slot counts as risky
+        let timing = timer.plan(START_MS + 1_420);
+        assert!(matches!(timing, SubmissionTiming::Send { target_slot: Some(103), .. }), "{:?}", timing);
+        assert!(timing.is_shifted());
+    }
+
+    #[test]
+    fn test_unknown_timing_sends_as_planned() {
+        let chain = ChainClock::new();
+        let timer = SubmissionTimer::new(SubmissionTimingSettings::default(), chain);
+        let planned = timer.clock.unix_millis() + 10_000;
+        assert!(matches!(
+            timer.plan(planned),
+            SubmissionTiming::Send { shift_ms: 0, target_slot: None, .. }
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+//! OpenTelemetry Tracing
+//!
+//! The pipeline stages run inside `tracing` spans: `intent.intake` (or `score.request` on the
+//! inference server) → `features.extract` → `inference.score` → `routing.select` → `bundle.submit`.
+//! With the `otlp` feature, `init` installs a subscriber that exports them over OTLP/gRPC
+//! to the collector in `TelemetrySettings::otlp_endpoint` (Tempo, Jaeger).
+//!
+//! Trace context crosses process boundaries as a W3C `traceparent` header: [`set_remote_parent`]
+//! joins an incoming trace, [`traceparent`] produces the header for outgoing requests (Jito
+//! block engine) and [`current_trace_id`] tags decision records so a logged decision can be
+//! looked up in the tracing backend. All three are no-ops while no OpenTelemetry layer is installed.
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use std::collections::HashMap;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+#[cfg(feature = "otlp")]
+use crate::config::TelemetrySettings;
+#[cfg(feature = "otlp")]
+use crate::{Result, SentinelError};
+
+/// W3C trace context header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Trace id (32 hex characters) of the current span, if it is being traced
+pub fn current_trace_id() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| span_context.trace_id().to_string())
+}
+
+/// `traceparent` header value for the current span
+pub fn traceparent() -> Option<String> {
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&tracing::Span::current().context(), &mut carrier);
+    carrier.remove(TRACEPARENT_HEADER)
+}
+
+/// Make `span` a child of the trace in an incoming `traceparent` header
+///
+/// Malformed headers are ignored and `span` stays a root.
+pub fn set_remote_parent(span: &tracing::Span, traceparent: &str) {
+    let carrier = HashMap::from([(TRACEPARENT_HEADER.to_string(), traceparent.to_string())]);
+    let context = TraceContextPropagator::new().extract(&carrier);
+    if context.span().span_context().is_valid() {
+        span.set_parent(context);
+    }
+}
+
+/// Flushes and shuts down the OTLP exporter when dropped
+#[cfg(feature = "otlp")]
+#[must_use = "spans are only flushed while the guard is alive"]
+pub struct TelemetryGuard {
+    exporting: bool,
+}
+
+#[cfg(feature = "otlp")]
+impl TelemetryGuard {
+    /// Whether spans are exported (an OTLP endpoint is configured)
+    pub fn is_exporting(&self) -> bool {
+        self.exporting
+    }
+}
+
+#[cfg(feature = "otlp")]
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if self.exporting {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+/// Install the global subscriber: `RUST_LOG`-filtered logs plus OTLP span export
+///
+/// Without an `otlp_endpoint` only the log output is installed. The batch exporter
+/// runs on the Tokio runtime, so call this from within one.
+#[cfg(feature = "otlp")]
+pub fn init(settings: &TelemetrySettings) -> Result<TelemetryGuard> {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let otel = match settings.otlp_endpoint {
+        Some(ref endpoint) => Some(tracing_opentelemetry::layer().with_tracer(otlp_tracer(settings, endpoint)?)),
+        None => None,
+    };
+    let exporting = otel.is_some();
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::from_default_env())
+        .with(tracing_subscriber::fmt::layer())
+        .with(otel)
+        .try_init()
+        .map_err(|e| SentinelError::ConfigError(format!("Failed to install tracing subscriber: {}", e)))?;
+
+    if exporting {
+        tracing::info!("Exporting traces to {:?} as {}", settings.otlp_endpoint, settings.service_name);
+    }
+    Ok(TelemetryGuard { exporting })
+}
+
+#[cfg(feature = "otlp")]
+fn otlp_tracer(settings: &TelemetrySettings, endpoint: &str) -> Result<opentelemetry_sdk::trace::Tracer> {
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::trace::Sampler;
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(settings.sample_ratio)));
+    let resource = opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+        "service.name",
+        settings.service_name.clone(),
+    )]);
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(opentelemetry_sdk::trace::config().with_sampler(sampler).with_resource(resource))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .map_err(|e| SentinelError::ConfigError(format!("Failed to start OTLP exporter for {}: {}", endpoint, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TracerProvider as _;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn traced<T>(f: impl FnOnce() -> T) -> T {
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        tracing::subscriber::with_default(subscriber, f)
+    }
+
+    #[test]
+    fn test_trace_id_and_traceparent_of_current_span() {
+        assert_eq!(current_trace_id(), None);
+        assert_eq!(traceparent(), None);
+
+        traced(|| {
+            let _span = tracing::info_span!("intent.intake").entered();
+            let trace_id = current_trace_id().unwrap();
+            assert_eq!(trace_id.len(), 32);
+
+            let header = traceparent().unwrap();
+            assert!(header.starts_with(&format!("00-{}-", trace_id)), "{}", header);
+        });
+    }
+
+    #[test]
+    fn test_remote_parent_joins_incoming_trace() {
+        let incoming = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        traced(|| {
+            let span = tracing::info_span!("intent.intake");
+            set_remote_parent(&span, incoming);
+            let _entered = span.enter();
+            assert_eq!(current_trace_id().as_deref(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+
+            let child = tracing::info_span!("bundle.submit");
+            let _entered = child.enter();
+            assert_eq!(current_trace_id().as_deref(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+        });
+
+        traced(|| {
+            let span = tracing::info_span!("intent.intake");
+            set_remote_parent(&span, "not-a-traceparent");
+            let _entered = span.enter();
+            assert_ne!(current_trace_id().as_deref(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+            assert!(current_trace_id().is_some());
+        });
+    }
+}
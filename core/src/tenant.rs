@@ -0,0 +1,345 @@
+//! Multi-Tenant Namespaces
+//!
+//! One router deployment serves several frontends. Each API key maps to a
+//! tenant (`[[tenants]]` in the config, keys stored as SHA-256 hashes), and
+//! each tenant gets its own partition of router state:
+//! - effective config: global sections with the tenant's tip policy / quota
+//!   overrides (`SentinelConfig::for_tenant`) plus a mint allow-list
+//! - its own `QuotaManager` and `IntentRegistry`, so one frontend's traffic
+//!   can neither exhaust nor see another's
+//! - log partitions: [`partition_path`] puts decision and shadow logs under
+//!   `<dir>/<tenant>/`, and records are tagged with the tenant id
+//!
+//! With no tenants configured every caller resolves to [`DEFAULT_TENANT_ID`],
+//! which keeps single-frontend deployments working unchanged.
+
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use tracing::{info, warn};
+
+use crate::config::SentinelConfig;
+use crate::decision_log::DecisionLogConfig;
+use crate::intent::{Intent, IntentLeg, SwapDetails};
+use crate::intent_registry::IntentRegistry;
+use crate::quota::QuotaManager;
+use crate::{Result, SentinelError};
+
+/// Tenant used when no tenants are configured
+pub const DEFAULT_TENANT_ID: &str = "default";
+
+/// Hex SHA-256 of an API key, as stored in `TenantConfig::api_key_sha256`
+pub fn hash_api_key(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+/// `logs/decisions.jsonl` → `logs/<tenant>/decisions.jsonl`
+pub fn partition_path(path: &str, tenant_id: &str) -> String {
+    let path = Path::new(path);
+    let file_name = path.file_name().unwrap_or_default();
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    parent.join(tenant_id).join(file_name).to_string_lossy().to_string()
+}
+
+/// One tenant's namespace
+pub struct Tenant {
+    id: String,
+    config: RwLock<Arc<SentinelConfig>>,
+    allowed_mints: RwLock<Vec<Pubkey>>,
+    quotas: QuotaManager,
+    intents: IntentRegistry,
+}
+
+impl Tenant {
+    fn new(id: String, config: SentinelConfig, allowed_mints: Vec<Pubkey>) -> Self {
+        Self {
+            id,
+            quotas: QuotaManager::from_config(&config),
+            config: RwLock::new(Arc::new(config)),
+            allowed_mints: RwLock::new(allowed_mints),
+            intents: IntentRegistry::new(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Effective configuration snapshot (overrides applied)
+    pub fn config(&self) -> Arc<SentinelConfig> {
+        Arc::clone(&self.config.read().unwrap_or_else(|p| p.into_inner()))
+    }
+
+    pub fn quotas(&self) -> &QuotaManager {
+        &self.quotas
+    }
+
+    pub fn intents(&self) -> &IntentRegistry {
+        &self.intents
+    }
+
+    /// Reject intents trading mints outside the tenant's allow-list
+    pub fn check_intent(&self, intent: &Intent) -> Result<()> {
+        let allowed = self.allowed_mints.read().unwrap_or_else(|p| p.into_inner());
+        if allowed.is_empty() {
+            return Ok(());
+        }
+
+        let swap_mints = |swap: &SwapDetails| [swap.input_mint, swap.output_mint];
+        let leg_mints = intent.legs.iter().flat_map(|leg| match leg {
+            IntentLeg::Swap(swap) => swap_mints(swap).to_vec(),
+            IntentLeg::Deposit(deposit) => vec![deposit.mint],
+        });
+        let mut mints = intent.swap_details.iter().flat_map(swap_mints).chain(leg_mints);
+        match mints.find(|mint| !allowed.contains(mint)) {
+            Some(mint) => Err(SentinelError::InvalidIntent(format!(
+                "mint {} is not allowed for tenant {}",
+                mint, self.id
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Decision log config writing to this tenant's partition and tagging its records
+    pub fn decision_log_config(&self, base: &DecisionLogConfig) -> DecisionLogConfig {
+        DecisionLogConfig {
+            buffer_size: base.buffer_size,
+            log_path: partition_path(&base.log_path, &self.id),
+            tenant_id: Some(self.id.clone()),
+        }
+    }
+
+    fn apply(&self, config: SentinelConfig, allowed_mints: Vec<Pubkey>) {
+        self.quotas.apply_config(&config);
+        *self.config.write().unwrap_or_else(|p| p.into_inner()) = Arc::new(config);
+        *self.allowed_mints.write().unwrap_or_else(|p| p.into_inner()) = allowed_mints;
+    }
+}
+
+/// API key → tenant resolution
+pub struct TenantRegistry {
+    tenants: RwLock<HashMap<String, Arc<Tenant>>>,
+    keys: RwLock<HashMap<String, String>>,
+}
+
+impl TenantRegistry {
+    pub fn from_config(config: &SentinelConfig) -> Self {
+        let registry = Self {
+            tenants: RwLock::new(HashMap::new()),
+            keys: RwLock::new(HashMap::new()),
+        };
+        registry.apply_config(config);
+        registry
+    }
+
+    /// Apply the hot-reloadable `[[tenants]]` section
+    ///
+    /// Surviving tenants keep their quota balances and registered intents;
+    /// removed tenants stop authenticating immediately.
+    pub fn apply_config(&self, config: &SentinelConfig) {
+        let mut tenants = self.tenants.write().unwrap_or_else(|p| p.into_inner());
+        let mut keys = HashMap::new();
+        let mut next = HashMap::new();
+
+        let configured: Vec<(String, SentinelConfig, Vec<Pubkey>)> = if config.tenants.is_empty() {
+            vec![(DEFAULT_TENANT_ID.to_string(), config.clone(), Vec::new())]
+        } else {
+            config
+                .tenants
+                .iter()
+                .filter_map(|tenant| {
+                    let effective = config.for_tenant(&tenant.id)?;
+                    let mints = tenant.allowed_mints.iter().filter_map(|m| Pubkey::from_str(m).ok()).collect();
+                    for key in &tenant.api_key_sha256 {
+                        keys.insert(key.to_ascii_lowercase(), tenant.id.clone());
+                    }
+                    Some((tenant.id.clone(), effective, mints))
+                })
+                .collect()
+        };
+
+        for (id, effective, mints) in configured {
+            let tenant = match tenants.remove(&id) {
+                Some(existing) => {
+                    existing.apply(effective, mints);
+                    existing
+                }
+                None => Arc::new(Tenant::new(id.clone(), effective, mints)),
+            };
+            next.insert(id, tenant);
+        }
+
+        for removed in tenants.keys() {
+            warn!("Tenant {} removed from config", removed);
+        }
+        info!("🏢 {} tenant(s) configured", next.len());
+
+        *tenants = next;
+        *self.keys.write().unwrap_or_else(|p| p.into_inner()) = keys;
+    }
+
+    /// Resolve the tenant for a request's API key
+    pub fn authenticate(&self, api_key: Option<&str>) -> Result<Arc<Tenant>> {
+        // Resolve the id first; `apply_config` takes the tenant lock before the key lock
+        let id = {
+            let keys = self.keys.read().unwrap_or_else(|p| p.into_inner());
+            if keys.is_empty() {
+                DEFAULT_TENANT_ID.to_string()
+            } else {
+                let api_key = api_key.ok_or_else(|| SentinelError::Unauthorized("missing API key".to_string()))?;
+                keys.get(&hash_api_key(api_key))
+                    .cloned()
+                    .ok_or_else(|| SentinelError::Unauthorized("unknown API key".to_string()))?
+            }
+        };
+
+        self.tenant(&id)
+            .ok_or_else(|| SentinelError::Unauthorized(format!("tenant {} is not configured", id)))
+    }
+
+    pub fn tenant(&self, id: &str) -> Option<Arc<Tenant>> {
+        self.read().get(id).cloned()
+    }
+
+    /// Every configured tenant
+    pub fn tenants(&self) -> Vec<Arc<Tenant>> {
+        self.read().values().cloned().collect()
+    }
+
+    pub fn tenant_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.read().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Arc<Tenant>>> {
+        self.tenants.read().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{QuotaSettings, TenantConfig, TipPolicy};
+    use crate::intent::DepositDetails;
+
+    fn config(usdc: &Pubkey) -> SentinelConfig {
+        SentinelConfig {
+            tenants: vec![
+                TenantConfig {
+                    id: "wallet".to_string(),
+                    api_key_sha256: vec![hash_api_key("wallet-key")],
+                    tip_policy: Some(TipPolicy {
+                        max_tip_lamports: 50_000,
+                        ..TipPolicy::default()
+                    }),
+                    quotas: Some(QuotaSettings {
+                        intents_per_minute: 1,
+                        intent_burst: 0,
+                        ..QuotaSettings::default()
+                    }),
+                    allowed_mints: vec![usdc.to_string()],
+                    sponsor: None,
+                },
+                TenantConfig {
+                    id: "dex".to_string(),
+                    api_key_sha256: vec![hash_api_key("dex-key")],
+                    ..TenantConfig::default()
+                },
+            ],
+            ..SentinelConfig::default()
+        }
+    }
+
+    fn swap(user: Pubkey, input_mint: Pubkey, output_mint: Pubkey) -> Intent {
+        let mut intent = Intent {
+            intent_id: "intent-1".to_string(),
+            user_public_key: user,
+            ..Intent::test_swap()
+        };
+        let swap = intent.test_swap_details();
+        swap.input_mint = input_mint;
+        swap.output_mint = output_mint;
+        swap.amount = 1_000;
+        swap.minimum_received = None;
+        intent
+    }
+
+    #[test]
+    fn test_api_keys_resolve_to_tenants() {
+        let registry = TenantRegistry::from_config(&config(&Pubkey::new_unique()));
+
+        assert_eq!(registry.authenticate(Some("wallet-key")).unwrap().id(), "wallet");
+        assert_eq!(registry.authenticate(Some("dex-key")).unwrap().id(), "dex");
+        assert!(matches!(registry.authenticate(Some("nope")), Err(SentinelError::Unauthorized(_))));
+        assert!(matches!(registry.authenticate(None), Err(SentinelError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn test_single_tenant_default() {
+        let registry = TenantRegistry::from_config(&SentinelConfig::default());
+        assert_eq!(registry.authenticate(None).unwrap().id(), DEFAULT_TENANT_ID);
+        assert_eq!(registry.tenant_ids(), vec![DEFAULT_TENANT_ID.to_string()]);
+    }
+
+    #[test]
+    fn test_overrides_and_partitioned_state() {
+        let usdc = Pubkey::new_unique();
+        let registry = TenantRegistry::from_config(&config(&usdc));
+        let wallet = registry.tenant("wallet").unwrap();
+        let dex = registry.tenant("dex").unwrap();
+
+        assert_eq!(wallet.config().tip_policy.max_tip_lamports, 50_000);
+        assert_eq!(dex.config().tip_policy, TipPolicy::default());
+
+        // Quotas are per tenant: the same wallet is limited in one namespace only
+        let user = Pubkey::new_unique();
+        assert!(wallet.quotas().check(&user, 1.0).is_ok());
+        assert!(wallet.quotas().check(&user, 1.0).is_err());
+        assert!(dex.quotas().check(&user, 1.0).is_ok());
+
+        assert!(wallet.check_intent(&swap(user, usdc, usdc)).is_ok());
+        assert!(wallet.check_intent(&swap(user, usdc, Pubkey::new_unique())).is_err());
+        assert!(dex.check_intent(&swap(user, usdc, Pubkey::new_unique())).is_ok());
+
+        // Deposit legs are held to the same allow-list
+        let deposit = |mint| {
+            let mut bundle = swap(user, usdc, usdc);
+            bundle.legs = vec![IntentLeg::Deposit(DepositDetails {
+                program_id: Pubkey::new_unique(),
+                vault: Pubkey::new_unique(),
+                mint,
+                amount: None,
+            })];
+            bundle
+        };
+        assert!(wallet.check_intent(&deposit(usdc)).is_ok());
+        assert!(wallet.check_intent(&deposit(Pubkey::new_unique())).unwrap_err().to_string().contains("not allowed"));
+
+        let log = wallet.decision_log_config(&DecisionLogConfig::default());
+        assert_eq!(log.log_path, "logs/wallet/decisions.jsonl");
+        assert_eq!(log.tenant_id.as_deref(), Some("wallet"));
+    }
+
+    #[test]
+    fn test_reload_keeps_state_and_drops_removed_tenants() {
+        let usdc = Pubkey::new_unique();
+        let mut config = config(&usdc);
+        let registry = TenantRegistry::from_config(&config);
+
+        let user = Pubkey::new_unique();
+        registry.tenant("wallet").unwrap().quotas().check(&user, 1.0).unwrap();
+
+        config.tenants.retain(|t| t.id == "wallet");
+        config.tenants[0].tip_policy = None;
+        registry.apply_config(&config);
+
+        let wallet = registry.tenant("wallet").unwrap();
+        assert_eq!(wallet.config().tip_policy, TipPolicy::default());
+        assert!(wallet.quotas().check(&user, 1.0).is_err());
+        assert!(registry.authenticate(Some("dex-key")).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! Per-Intent Execution Timeline
+//!
+//! `IntentStatus` only says where an intent is now. Wallet UIs rendering
+//! progress need the path it took, so every registered intent carries an
+//! [`ExecutionTimeline`]: typed, timestamped events appended as the pipeline
+//! moves (validated → scored → routed → simulated → submitted → landed, or
+//! failed at some stage, or expired while pending).
+//!
+//! The registry returns each appended event as a [`TimelineUpdate`]; the API
+//! layer serves the full timeline with the intent status and forwards updates
+//! to WebSocket subscribers (see [`TimelineFeed`] on native targets).
+
+use serde::{Deserialize, Serialize};
+
+use crate::latency::PipelineStage;
+use crate::types::RouteType;
+
+/// Something that happened to an intent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TimelineEvent {
+    Validated,
+    Scored { risk_score: f32 },
+    Routed { route: RouteType },
+    Simulated {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        units_consumed: Option<u64>,
+    },
+    Submitted { bundle_id: String },
+    Landed { slot: u64 },
+    Failed { stage: PipelineStage, error: String },
+
+    /// Expiry or TTL passed while pending
+    Expired,
+}
+
+impl TimelineEvent {
+    /// No further events follow
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TimelineEvent::Landed { .. } | TimelineEvent::Failed { .. } | TimelineEvent::Expired
+        )
+    }
+}
+
+/// Event with its time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    /// Unix ms
+    pub timestamp_ms: i64,
+
+    #[serde(flatten)]
+    pub event: TimelineEvent,
+}
+
+/// Ordered events for one intent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTimeline {
+    pub entries: Vec<TimelineEntry>,
+}
+
+impl ExecutionTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event; returns the stored entry
+    pub fn record(&mut self, event: TimelineEvent, timestamp_ms: i64) -> TimelineEntry {
+        let entry = TimelineEntry { timestamp_ms, event };
+        self.entries.push(entry.clone());
+        entry
+    }
+
+    pub fn latest(&self) -> Option<&TimelineEntry> {
+        self.entries.last()
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.latest().is_some_and(|entry| entry.event.is_terminal())
+    }
+
+    /// First to last event (ms)
+    pub fn elapsed_ms(&self) -> i64 {
+        match (self.entries.first(), self.entries.last()) {
+            (Some(first), Some(last)) => last.timestamp_ms - first.timestamp_ms,
+            _ => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// One appended event, as pushed to stream subscribers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineUpdate {
+    pub intent_id: String,
+
+    #[serde(flatten)]
+    pub entry: TimelineEntry,
+}
+
+/// Fan-out of timeline updates to WebSocket subscribers
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct TimelineFeed {
+    sender: tokio::sync::broadcast::Sender<TimelineUpdate>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for TimelineFeed {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TimelineFeed {
+    /// `capacity` updates are buffered per lagging subscriber
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: tokio::sync::broadcast::channel(capacity.max(1)).0,
+        }
+    }
+
+    /// Deliver to current subscribers (dropped when there are none)
+    pub fn publish(&self, update: TimelineUpdate) {
+        let _ = self.sender.send(update);
+    }
+
+    /// All intents' updates; filter on `intent_id` per connection
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<TimelineUpdate> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_records_in_order() {
+        let mut timeline = ExecutionTimeline::new();
+        timeline.record(TimelineEvent::Validated, 1_000);
+        timeline.record(TimelineEvent::Scored { risk_score: 0.8 }, 1_002);
+        timeline.record(TimelineEvent::Routed { route: RouteType::JitoBundle }, 1_003);
+        assert!(!timeline.is_terminal());
+
+        timeline.record(TimelineEvent::Landed { slot: 42 }, 1_900);
+        assert!(timeline.is_terminal());
+        assert_eq!(timeline.len(), 4);
+        assert_eq!(timeline.elapsed_ms(), 900);
+    }
+
+    #[test]
+    fn test_event_json_shape() {
+        let update = TimelineUpdate {
+            intent_id: "intent-1".to_string(),
+            entry: TimelineEntry {
+                timestamp_ms: 5,
+                event: TimelineEvent::Failed {
+                    stage: PipelineStage::Submit,
+                    error: "bundle rejected".to_string(),
+                },
+            },
+        };
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "intent_id": "intent-1",
+                "timestamp_ms": 5,
+                "event": "failed",
+                "stage": "submit",
+                "error": "bundle rejected"
+            })
+        );
+        assert_eq!(serde_json::from_value::<TimelineUpdate>(json).unwrap(), update);
+
+        let simulated: TimelineEntry =
+            serde_json::from_str(r#"{"timestamp_ms": 1, "event": "simulated"}"#).unwrap();
+        assert_eq!(simulated.event, TimelineEvent::Simulated { units_consumed: None });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_feed_fans_out() {
+        let feed = TimelineFeed::default();
+        let mut rx = feed.subscribe();
+        feed.publish(TimelineUpdate {
+            intent_id: "a".to_string(),
+            entry: TimelineEntry { timestamp_ms: 1, event: TimelineEvent::Validated },
+        });
+        assert_eq!(rx.recv().await.unwrap().intent_id, "a");
+    }
+}
@@ -0,0 +1,168 @@
+//! Network-Wide Jito Tip Floor
+//!
+//! Jito publishes rolling landed-tip percentiles across all bundles. Our own
+//! tip history only covers flow we scored, so it lags (or misses entirely) a
+//! network-wide tip war. [`TipFloor`] holds the published percentiles in
+//! lamports; [`TipFloorCache`] is the shared handle a poller writes and the
+//! tip recommendation / feature extraction read, ignoring values older than
+//! `max_age`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::clock::{system_clock, SharedClock};
+
+/// Landed-tip percentiles (lamports)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TipFloor {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p95: u64,
+    pub p99: u64,
+
+    /// Exponential moving average of the median
+    pub ema_p50: u64,
+
+    /// Unix ms when the values were fetched
+    pub observed_at_ms: i64,
+}
+
+impl TipFloor {
+    /// (percentile, lamports) knots, ascending
+    fn knots(&self) -> [(f64, f64); 6] {
+        [
+            (0.0, 0.0),
+            (25.0, self.p25 as f64),
+            (50.0, self.p50 as f64),
+            (75.0, self.p75 as f64),
+            (95.0, self.p95 as f64),
+            (99.0, self.p99 as f64),
+        ]
+    }
+
+    /// Where `tip_lamports` ranks among landed tips (0-100)
+    ///
+    /// Linear between the published percentiles; above p99 saturates at 100.
+    pub fn percentile_of(&self, tip_lamports: u64) -> f32 {
+        let tip = tip_lamports as f64;
+        let knots = self.knots();
+        if tip >= knots[5].1 {
+            return if tip > knots[5].1 { 100.0 } else { 99.0 };
+        }
+        for pair in knots.windows(2) {
+            let ((q0, t0), (q1, t1)) = (pair[0], pair[1]);
+            if tip < t1 {
+                let span = t1 - t0;
+                let fraction = if span > 0.0 { (tip - t0) / span } else { 1.0 };
+                return (q0 + fraction * (q1 - q0)) as f32;
+            }
+        }
+        99.0
+    }
+
+    /// Tip at percentile `q` (0-100), interpolated between published values
+    pub fn quantile(&self, q: f64) -> u64 {
+        let q = q.clamp(0.0, 99.0);
+        let knots = self.knots();
+        for pair in knots.windows(2) {
+            let ((q0, t0), (q1, t1)) = (pair[0], pair[1]);
+            if q <= q1 {
+                return (t0 + (q - q0) / (q1 - q0) * (t1 - t0)).round() as u64;
+            }
+        }
+        self.p99
+    }
+}
+
+/// Shared, staleness-aware latest tip floor
+#[derive(Debug, Clone)]
+pub struct TipFloorCache {
+    latest: Arc<RwLock<Option<TipFloor>>>,
+    max_age: Duration,
+    clock: SharedClock,
+}
+
+impl Default for TipFloorCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60))
+    }
+}
+
+impl TipFloorCache {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            latest: Arc::new(RwLock::new(None)),
+            max_age,
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn update(&self, floor: TipFloor) {
+        *self.latest.write().unwrap_or_else(|p| p.into_inner()) = Some(floor);
+    }
+
+    /// Most recent value regardless of age
+    pub fn latest(&self) -> Option<TipFloor> {
+        *self.latest.read().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Most recent value if younger than `max_age`
+    pub fn current(&self) -> Option<TipFloor> {
+        let now = self.clock.unix_millis();
+        self.latest()
+            .filter(|floor| now.saturating_sub(floor.observed_at_ms) <= self.max_age.as_millis() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+
+    fn floor(observed_at_ms: i64) -> TipFloor {
+        TipFloor {
+            p25: 1_000,
+            p50: 10_000,
+            p75: 50_000,
+            p95: 1_000_000,
+            p99: 5_000_000,
+            ema_p50: 9_000,
+            observed_at_ms,
+        }
+    }
+
+    #[test]
+    fn test_percentile_interpolation() {
+        let floor = floor(0);
+        assert_eq!(floor.percentile_of(0), 0.0);
+        assert_eq!(floor.percentile_of(10_000), 50.0);
+        assert!((floor.percentile_of(30_000) - 62.5).abs() < 1e-3);
+        assert_eq!(floor.percentile_of(5_000_000), 99.0);
+        assert_eq!(floor.percentile_of(50_000_000), 100.0);
+
+        assert_eq!(floor.quantile(50.0), 10_000);
+        assert_eq!(floor.quantile(85.0), 525_000);
+        assert_eq!(floor.quantile(100.0), 5_000_000);
+    }
+
+    #[test]
+    fn test_cache_expires_stale_values() {
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let cache = TipFloorCache::new(Duration::from_secs(30)).with_clock(clock.clone());
+        assert!(cache.current().is_none());
+
+        cache.update(floor(clock.unix_millis()));
+        assert!(cache.clone().current().is_some());
+
+        clock.advance(Duration::from_secs(31));
+        assert!(cache.current().is_none());
+        assert!(cache.latest().is_some());
+    }
+}
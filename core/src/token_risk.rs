@@ -0,0 +1,490 @@
+//! Honeypot / Rug-Risk Token Screening
+//!
+//! Scores each mint of a swap before execution from:
+//! - **Mint authority**: still set, so supply can be inflated at will
+//! - **Freeze authority**: still set, so holder accounts (the user's output)
+//!   can be frozen; the classic honeypot
+//! - **Top-holder concentration**: share of supply in the 10 largest token
+//!   accounts (`getTokenLargestAccounts`; pool vaults count as holders)
+//! - **Pool age / liquidity**: from pools recorded by ingestion
+//!   ([`TokenRiskScreener::record_pool`])
+//!
+//! Authority and holder data are fetched over JSON-RPC and cached for
+//! `cache_ttl_secs`. The hot path (feature extraction) only reads the cache via
+//! [`TokenRiskScreener::cached_pair`]; [`TokenRiskScreener::screen_pair`] fetches
+//! missing data and is used by preflight, where the result is surfaced to the
+//! user. Trusted mints (USDC, USDT and wrapped SOL by default) keep their
+//! issuer authorities and are never scored.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::{Result, SentinelError};
+
+/// Mints exempt from screening by default (USDC, USDT, wrapped SOL)
+pub const DEFAULT_TRUSTED_MINTS: [&str; 3] = [
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+    "So11111111111111111111111111111111111111112",
+];
+
+/// Largest accounts counted towards holder concentration
+const TOP_HOLDERS: usize = 10;
+
+/// Screening thresholds and weights of each signal in the sub-score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenRiskThresholds {
+    pub mint_authority_weight: f32,
+    pub freeze_authority_weight: f32,
+
+    /// Top-holder share (0-1) above which concentration counts
+    pub max_top_holder_share: f64,
+    pub concentration_weight: f32,
+
+    /// Pools younger than this (seconds) count as new
+    pub min_pool_age_secs: i64,
+    pub new_pool_weight: f32,
+
+    /// Pool liquidity floor (USD)
+    pub min_liquidity_usd: f64,
+    pub low_liquidity_weight: f32,
+
+    /// Sub-score (0-1) at or above which a token is high-risk
+    pub high_risk_score: f32,
+
+    /// Seconds authority/holder data stays cached
+    pub cache_ttl_secs: i64,
+}
+
+impl Default for TokenRiskThresholds {
+    fn default() -> Self {
+        Self {
+            mint_authority_weight: 0.25,
+            freeze_authority_weight: 0.4,
+            max_top_holder_share: 0.5,
+            concentration_weight: 0.2,
+            min_pool_age_secs: 86_400,
+            new_pool_weight: 0.15,
+            min_liquidity_usd: 10_000.0,
+            low_liquidity_weight: 0.2,
+            high_risk_score: 0.5,
+            cache_ttl_secs: 300,
+        }
+    }
+}
+
+/// On-chain mint state relevant to screening
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintHoldings {
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub supply: u64,
+
+    /// Balances of the largest token accounts, descending
+    pub top_balances: Vec<u64>,
+}
+
+impl MintHoldings {
+    /// Parse `getAccountInfo` (jsonParsed) and `getTokenLargestAccounts` results
+    pub fn from_rpc_json(account_info: &serde_json::Value, largest_accounts: &serde_json::Value) -> Result<Self> {
+        let info = &account_info["value"]["data"]["parsed"]["info"];
+        if !info.is_object() {
+            return Err(SentinelError::ParseError("Account is not a parsed SPL mint".to_string()));
+        }
+
+        let authority = |key: &str| info[key].as_str().map(str::to_string);
+        let amount = |value: &serde_json::Value| value.as_str().and_then(|s| s.parse::<u64>().ok());
+
+        let supply = amount(&info["supply"])
+            .ok_or_else(|| SentinelError::ParseError("Mint without supply".to_string()))?;
+        let mut top_balances: Vec<u64> = largest_accounts["value"]
+            .as_array()
+            .map(|accounts| accounts.iter().filter_map(|a| amount(&a["amount"])).collect())
+            .unwrap_or_default();
+        top_balances.sort_unstable_by(|a, b| b.cmp(a));
+
+        Ok(Self {
+            mint_authority: authority("mintAuthority"),
+            freeze_authority: authority("freezeAuthority"),
+            supply,
+            top_balances,
+        })
+    }
+
+    /// Share of supply (0-1) held by the largest accounts
+    pub fn top_holder_share(&self) -> f64 {
+        if self.supply == 0 {
+            return 0.0;
+        }
+        let held: u128 = self.top_balances.iter().take(TOP_HOLDERS).map(|b| *b as u128).sum();
+        (held as f64 / self.supply as f64).min(1.0)
+    }
+}
+
+/// Pool observation recorded by ingestion
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PoolObservation {
+    /// Pool creation time (unix seconds)
+    pub created_at: i64,
+    pub liquidity_usd: f64,
+}
+
+/// Risk assessment of one mint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenRisk {
+    pub mint: String,
+
+    /// Token risk sub-score (0.0-1.0)
+    pub score: f32,
+    pub is_high_risk: bool,
+
+    pub mint_authority_active: bool,
+    pub freeze_authority_active: bool,
+    pub top_holder_share: f64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_age_secs: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_usd: Option<f64>,
+
+    /// Signals that contributed to the score, for display
+    pub reasons: Vec<String>,
+}
+
+impl TokenRisk {
+    /// Risk of a trusted mint
+    pub fn trusted(mint: &Pubkey) -> Self {
+        Self {
+            mint: mint.to_string(),
+            score: 0.0,
+            is_high_risk: false,
+            mint_authority_active: false,
+            freeze_authority_active: false,
+            top_holder_share: 0.0,
+            pool_age_secs: None,
+            liquidity_usd: None,
+            reasons: vec!["trusted mint".to_string()],
+        }
+    }
+
+    /// Score a mint from its holdings and (optionally) its pool
+    pub fn assess(
+        mint: &Pubkey,
+        holdings: &MintHoldings,
+        pool: Option<&PoolObservation>,
+        thresholds: &TokenRiskThresholds,
+        now: i64,
+    ) -> Self {
+        let mut score = 0.0f32;
+        let mut reasons = Vec::new();
+
+        if holdings.mint_authority.is_some() {
+            score += thresholds.mint_authority_weight;
+            reasons.push("mint authority is active".to_string());
+        }
+        if holdings.freeze_authority.is_some() {
+            score += thresholds.freeze_authority_weight;
+            reasons.push("freeze authority is active".to_string());
+        }
+
+        let top_holder_share = holdings.top_holder_share();
+        if top_holder_share > thresholds.max_top_holder_share {
+            score += thresholds.concentration_weight;
+            reasons.push(format!("top {} holders own {:.0}% of supply", TOP_HOLDERS, top_holder_share * 100.0));
+        }
+
+        let pool_age_secs = pool.map(|p| now.saturating_sub(p.created_at).max(0));
+        if let Some(age) = pool_age_secs.filter(|age| *age < thresholds.min_pool_age_secs) {
+            score += thresholds.new_pool_weight;
+            reasons.push(format!("pool is {}s old", age));
+        }
+        if let Some(liquidity) = pool.map(|p| p.liquidity_usd).filter(|l| *l < thresholds.min_liquidity_usd) {
+            score += thresholds.low_liquidity_weight;
+            reasons.push(format!("pool liquidity ${:.0}", liquidity));
+        }
+
+        let score = score.min(1.0);
+        Self {
+            mint: mint.to_string(),
+            score,
+            is_high_risk: score >= thresholds.high_risk_score,
+            mint_authority_active: holdings.mint_authority.is_some(),
+            freeze_authority_active: holdings.freeze_authority.is_some(),
+            top_holder_share,
+            pool_age_secs,
+            liquidity_usd: pool.map(|p| p.liquidity_usd),
+            reasons,
+        }
+    }
+}
+
+/// Combined risk of a swap's two mints (feeds `is_high_risk_pair`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairTokenRisk {
+    pub input: TokenRisk,
+    pub output: TokenRisk,
+
+    /// The riskier of the two sub-scores
+    pub score: f32,
+    pub is_high_risk: bool,
+}
+
+impl PairTokenRisk {
+    pub fn new(input: TokenRisk, output: TokenRisk) -> Self {
+        Self {
+            score: input.score.max(output.score),
+            is_high_risk: input.is_high_risk || output.is_high_risk,
+            input,
+            output,
+        }
+    }
+}
+
+/// Screens mints over JSON-RPC with cached results
+pub struct TokenRiskScreener {
+    http_client: Client,
+    rpc_url: String,
+    thresholds: TokenRiskThresholds,
+    trusted: HashSet<Pubkey>,
+
+    /// Holdings with the time they were fetched (unix seconds)
+    holdings: RwLock<HashMap<Pubkey, (MintHoldings, i64)>>,
+    pools: RwLock<HashMap<Pubkey, PoolObservation>>,
+}
+
+impl TokenRiskScreener {
+    pub fn new(rpc_url: impl Into<String>) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        let trusted = DEFAULT_TRUSTED_MINTS
+            .iter()
+            .map(|m| Pubkey::from_str(m).expect("Hardcoded trusted mint must be valid"))
+            .collect();
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+            thresholds: TokenRiskThresholds::default(),
+            trusted,
+            holdings: RwLock::new(HashMap::new()),
+            pools: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn with_thresholds(mut self, thresholds: TokenRiskThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Add mints exempt from screening
+    pub fn with_trusted_mints(mut self, mints: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.trusted.extend(mints);
+        self
+    }
+
+    /// Record (or refresh) the pool a mint trades in; the deepest pool wins
+    pub fn record_pool(&self, mint: Pubkey, observation: PoolObservation) {
+        let mut pools = self.pools.write().unwrap_or_else(|p| p.into_inner());
+        let entry = pools.entry(mint).or_insert(observation);
+        if observation.liquidity_usd >= entry.liquidity_usd {
+            *entry = observation;
+        }
+    }
+
+    /// Seed holdings without RPC (tests, snapshots)
+    pub fn insert_holdings(&self, mint: Pubkey, holdings: MintHoldings, now: i64) {
+        self.holdings
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(mint, (holdings, now));
+    }
+
+    /// Cached assessment; None if the mint has not been fetched or the entry expired
+    pub fn cached(&self, mint: &Pubkey, now: i64) -> Option<TokenRisk> {
+        if self.trusted.contains(mint) {
+            return Some(TokenRisk::trusted(mint));
+        }
+
+        let holdings = self.holdings.read().unwrap_or_else(|p| p.into_inner());
+        let (holdings, fetched_at) = holdings.get(mint)?;
+        if now - fetched_at > self.thresholds.cache_ttl_secs {
+            return None;
+        }
+        let pool = self.pools.read().unwrap_or_else(|p| p.into_inner()).get(mint).copied();
+        Some(TokenRisk::assess(mint, holdings, pool.as_ref(), &self.thresholds, now))
+    }
+
+    /// Cached pair assessment (no RPC; safe on the hot path)
+    pub fn cached_pair(&self, input: &Pubkey, output: &Pubkey, now: i64) -> Option<PairTokenRisk> {
+        Some(PairTokenRisk::new(self.cached(input, now)?, self.cached(output, now)?))
+    }
+
+    /// Assess a mint, fetching authority/holder data when not cached
+    pub async fn screen(&self, mint: &Pubkey, now: i64) -> Result<TokenRisk> {
+        if let Some(risk) = self.cached(mint, now) {
+            return Ok(risk);
+        }
+
+        let holdings = self.fetch_holdings(mint).await?;
+        debug!(
+            "Fetched mint {}: supply {} top-holder share {:.2}",
+            mint,
+            holdings.supply,
+            holdings.top_holder_share()
+        );
+        self.insert_holdings(*mint, holdings, now);
+        self.cached(mint, now)
+            .ok_or_else(|| SentinelError::RpcError(format!("Mint {} could not be screened", mint)))
+    }
+
+    /// Assess both mints of a swap
+    pub async fn screen_pair(&self, input: &Pubkey, output: &Pubkey, now: i64) -> Result<PairTokenRisk> {
+        let (input, output) = futures_util::future::try_join(self.screen(input, now), self.screen(output, now)).await?;
+        Ok(PairTokenRisk::new(input, output))
+    }
+
+    async fn fetch_holdings(&self, mint: &Pubkey) -> Result<MintHoldings> {
+        let (account_info, largest_accounts) = futures_util::future::try_join(
+            self.rpc("getAccountInfo", serde_json::json!([mint.to_string(), { "encoding": "jsonParsed" }])),
+            self.rpc("getTokenLargestAccounts", serde_json::json!([mint.to_string()])),
+        )
+        .await?;
+        MintHoldings::from_rpc_json(&account_info, &largest_accounts)
+    }
+
+    async fn rpc(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let mut response: serde_json::Value = self
+            .http_client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("{} failed: {}", method, e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("Invalid {} response: {}", method, e)))?;
+
+        if let Some(message) = response["error"]["message"].as_str() {
+            return Err(SentinelError::RpcError(message.to_string()));
+        }
+        Ok(response["result"].take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn holdings(mint_authority: bool, freeze_authority: bool, top_balances: Vec<u64>) -> MintHoldings {
+        MintHoldings {
+            mint_authority: mint_authority.then(|| Pubkey::new_unique().to_string()),
+            freeze_authority: freeze_authority.then(|| Pubkey::new_unique().to_string()),
+            supply: 1_000_000,
+            top_balances,
+        }
+    }
+
+    #[test]
+    fn test_holdings_parsing() {
+        let account_info = json!({
+            "context": { "slot": 1 },
+            "value": { "data": { "parsed": { "type": "mint", "info": {
+                "mintAuthority": null,
+                "freezeAuthority": "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+                "supply": "1000000",
+                "decimals": 6
+            }}}}
+        });
+        let largest = json!({
+            "value": [{ "amount": "100000" }, { "amount": "600000" }]
+        });
+
+        let holdings = MintHoldings::from_rpc_json(&account_info, &largest).unwrap();
+        assert_eq!(holdings.mint_authority, None);
+        assert!(holdings.freeze_authority.is_some());
+        assert_eq!(holdings.top_balances, vec![600_000, 100_000]);
+        assert!((holdings.top_holder_share() - 0.7).abs() < 1e-9);
+
+        assert!(MintHoldings::from_rpc_json(&json!({ "value": null }), &largest).is_err());
+    }
+
+    #[test]
+    fn test_honeypot_is_high_risk() {
+        let thresholds = TokenRiskThresholds::default();
+        let mint = Pubkey::new_unique();
+
+        let renounced = TokenRisk::assess(&mint, &holdings(false, false, vec![100_000]), None, &thresholds, NOW);
+        assert_eq!(renounced.score, 0.0);
+        assert!(!renounced.is_high_risk);
+
+        let pool = PoolObservation {
+            created_at: NOW - 600,
+            liquidity_usd: 2_000.0,
+        };
+        let honeypot = TokenRisk::assess(&mint, &holdings(false, true, vec![900_000]), Some(&pool), &thresholds, NOW);
+        assert!(honeypot.is_high_risk);
+        assert!(honeypot.freeze_authority_active);
+        assert_eq!(honeypot.pool_age_secs, Some(600));
+        assert_eq!(honeypot.reasons.len(), 4);
+    }
+
+    #[test]
+    fn test_cached_pair_and_trusted_mints() {
+        let screener = TokenRiskScreener::new("http://127.0.0.1:8899").unwrap();
+        let usdc = Pubkey::from_str(DEFAULT_TRUSTED_MINTS[0]).unwrap();
+        let token = Pubkey::new_unique();
+
+        assert!(screener.cached_pair(&usdc, &token, NOW).is_none());
+
+        screener.insert_holdings(token, holdings(true, true, vec![]), NOW);
+        let pair = screener.cached_pair(&usdc, &token, NOW).unwrap();
+        assert_eq!(pair.input.score, 0.0);
+        assert!(pair.is_high_risk);
+        assert_eq!(pair.score, pair.output.score);
+
+        // Expired entries are not served
+        let ttl = TokenRiskThresholds::default().cache_ttl_secs;
+        assert!(screener.cached_pair(&usdc, &token, NOW + ttl + 1).is_none());
+    }
+
+    #[test]
+    fn test_record_pool_keeps_deepest() {
+        let screener = TokenRiskScreener::new("http://127.0.0.1:8899").unwrap();
+        let token = Pubkey::new_unique();
+        screener.insert_holdings(token, holdings(false, false, vec![]), NOW);
+
+        let deep = PoolObservation {
+            created_at: NOW - 1_000_000,
+            liquidity_usd: 500_000.0,
+        };
+        let shallow = PoolObservation {
+            created_at: NOW - 60,
+            liquidity_usd: 100.0,
+        };
+        screener.record_pool(token, deep);
+        screener.record_pool(token, shallow);
+
+        let risk = screener.cached(&token, NOW).unwrap();
+        assert_eq!(risk.liquidity_usd, Some(500_000.0));
+        assert_eq!(risk.score, 0.0);
+    }
+}
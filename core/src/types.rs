@@ -1,18 +1,58 @@
 use serde::{Deserialize, Serialize};
 
 /// MEV risk score from AI engine (0.0 = safe, 1.0 = high risk)
+///
+/// May carry the engine's confidence in the score (0.0-1.0). Serializes as a
+/// bare number without one and as `{"score", "confidence"}` with one.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct MevRiskScore(pub f32);
+#[serde(from = "ScoreRepr", into = "ScoreRepr")]
+pub struct MevRiskScore(pub f32, Option<f32>);
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ScoreRepr {
+    Bare(f32),
+    WithConfidence { score: f32, confidence: Option<f32> },
+}
+
+impl From<ScoreRepr> for MevRiskScore {
+    fn from(repr: ScoreRepr) -> Self {
+        match repr {
+            ScoreRepr::Bare(score) => Self(score, None),
+            ScoreRepr::WithConfidence { score, confidence } => Self(score, confidence),
+        }
+    }
+}
+
+impl From<MevRiskScore> for ScoreRepr {
+    fn from(score: MevRiskScore) -> Self {
+        match score.1 {
+            None => ScoreRepr::Bare(score.0),
+            confidence => ScoreRepr::WithConfidence { score: score.0, confidence },
+        }
+    }
+}
 
 impl MevRiskScore {
     pub fn new(score: f32) -> Self {
-        Self(score.clamp(0.0, 1.0))
+        Self(score.clamp(0.0, 1.0), None)
+    }
+
+    /// Attach the engine's confidence (clamped to 0.0-1.0)
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.1 = Some(if confidence.is_nan() { 0.0 } else { confidence.clamp(0.0, 1.0) });
+        self
     }
     
     pub fn score(&self) -> f32 {
         self.0
     }
 
+    /// Confidence in `score`, if the engine reported one
+    pub fn confidence(&self) -> Option<f32> {
+        self.1
+    }
+
     pub fn is_high_risk(&self) -> bool {
         self.0 >= 0.8
     }
@@ -26,6 +66,27 @@ impl MevRiskScore {
     }
 }
 
+/// Body of a stateless scoring request (`POST /api/v1/score/raw`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreRawRequest {
+    /// Base64 wire transaction, legacy or v0
+    pub transaction: String,
+}
+
+/// Classification of an arbitrary transaction: no intent, persistence or routing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawTransactionScore {
+    pub risk_score: MevRiskScore,
+
+    /// Heuristic rules the transaction trips
+    pub signals: Vec<String>,
+
+    pub is_dex_swap: bool,
+
+    /// Lookup tables are not resolved; accounts behind them were not inspected
+    pub uses_lookup_tables: bool,
+}
+
 /// Transaction status tracking
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TransactionStatus {
@@ -50,4 +111,9 @@ impl RouteType {
     pub fn requires_bundle(&self) -> bool {
         matches!(self, RouteType::JitoBundle)
     }
+
+    /// Route lands through the block engine, so a Jito tip buys priority
+    pub fn pays_tip(&self) -> bool {
+        matches!(self, RouteType::JitoBundle | RouteType::JitoSingle)
+    }
 }
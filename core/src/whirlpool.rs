@@ -0,0 +1,632 @@
+//! Orca Whirlpool (concentrated liquidity) backend
+//!
+//! Decodes Whirlpool pool accounts, quotes swaps against the active liquidity
+//! range, resolves the tick-array/oracle accounts a swap touches, and builds the
+//! `swap` instruction. Pool liquidity and ±1% depth feed the market features.
+//!
+//! Quotes assume the swap stays within the current initialized range (constant
+//! liquidity). Larger trades that cross ticks are still bounded on-chain by
+//! `other_amount_threshold`, so an optimistic quote can fail but never overfill.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::{Result, SentinelError, SwapDetails, SwapMode};
+
+/// Orca Whirlpool program ID on Solana mainnet
+pub const WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+/// SPL Token program (Whirlpool vaults are classic SPL token accounts)
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+/// Associated Token Account program
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// Ticks per tick-array account
+pub const TICK_ARRAY_SIZE: i32 = 88;
+
+/// Tick arrays passed to every swap
+pub const SWAP_TICK_ARRAYS: usize = 3;
+
+/// Sqrt-price bounds (Q64.64) used as "no limit" for each direction
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+/// `fee_rate` denominator (hundredths of a basis point)
+const FEE_RATE_DENOMINATOR: f64 = 1_000_000.0;
+
+/// 2^64 for Q64.64 conversion
+const Q64: f64 = 18_446_744_073_709_551_616.0;
+
+/// Whirlpool account size (discriminator + fields)
+const WHIRLPOOL_ACCOUNT_LEN: usize = 653;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(WHIRLPOOL_PROGRAM_ID).expect("Hardcoded Whirlpool program ID must be valid")
+}
+
+pub(crate) fn token_program_id() -> Pubkey {
+    Pubkey::from_str(TOKEN_PROGRAM_ID).expect("Hardcoded token program ID must be valid")
+}
+
+/// Decoded Whirlpool pool state (fields needed for quoting and swaps)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhirlpoolState {
+    pub tick_spacing: u16,
+    /// Swap fee in hundredths of a basis point (3000 = 0.30%)
+    pub fee_rate: u16,
+    pub liquidity: u128,
+    pub sqrt_price_x64: u128,
+    pub tick_current_index: i32,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+}
+
+impl WhirlpoolState {
+    /// Decode raw Whirlpool account data
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < WHIRLPOOL_ACCOUNT_LEN {
+            return Err(SentinelError::DexError(format!(
+                "Whirlpool account too short: {} bytes",
+                data.len()
+            )));
+        }
+
+        let u16_at = |o: usize| u16::from_le_bytes([data[o], data[o + 1]]);
+        let u128_at = |o: usize| {
+            let mut buf = [0u8; 16];
+            buf.copy_from_slice(&data[o..o + 16]);
+            u128::from_le_bytes(buf)
+        };
+        let pubkey_at = |o: usize| {
+            let mut buf = [0u8; 32];
+            buf.copy_from_slice(&data[o..o + 32]);
+            Pubkey::new_from_array(buf)
+        };
+
+        Ok(Self {
+            tick_spacing: u16_at(41),
+            fee_rate: u16_at(45),
+            liquidity: u128_at(49),
+            sqrt_price_x64: u128_at(65),
+            tick_current_index: i32::from_le_bytes([data[81], data[82], data[83], data[84]]),
+            token_mint_a: pubkey_at(101),
+            token_vault_a: pubkey_at(133),
+            token_mint_b: pubkey_at(181),
+            token_vault_b: pubkey_at(213),
+        })
+    }
+
+    /// Spot price of A in B (raw atoms)
+    pub fn price(&self) -> f64 {
+        let sqrt_price = self.sqrt_price_x64 as f64 / Q64;
+        sqrt_price * sqrt_price
+    }
+
+    /// Swap direction for an input/output mint pair
+    pub fn direction(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<bool> {
+        if *input_mint == self.token_mint_a && *output_mint == self.token_mint_b {
+            Ok(true)
+        } else if *input_mint == self.token_mint_b && *output_mint == self.token_mint_a {
+            Ok(false)
+        } else {
+            Err(SentinelError::DexError(format!(
+                "Whirlpool does not trade {} -> {}",
+                input_mint, output_mint
+            )))
+        }
+    }
+
+    /// Liquidity and depth of the active range
+    pub fn depth(&self) -> PoolDepth {
+        let liquidity = self.liquidity as f64;
+        let sqrt_price = self.sqrt_price_x64 as f64 / Q64;
+        let down = sqrt_price * 0.99_f64.sqrt();
+        let up = sqrt_price * 1.01_f64.sqrt();
+
+        PoolDepth {
+            liquidity: self.liquidity,
+            price: self.price(),
+            // A sold to push price down 1%, B sold to push it up 1%
+            depth_a_1pct: liquidity * (1.0 / down - 1.0 / sqrt_price),
+            depth_b_1pct: liquidity * (up - sqrt_price),
+        }
+    }
+}
+
+/// Active-range liquidity figures for market features
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolDepth {
+    pub liquidity: u128,
+    /// Spot price of A in B (raw atoms)
+    pub price: f64,
+    /// Token A atoms that move the price down 1%
+    pub depth_a_1pct: f64,
+    /// Token B atoms that move the price up 1%
+    pub depth_b_1pct: f64,
+}
+
+impl PoolDepth {
+    /// ±1% depth in USD (`pool_liquidity_usd` feature)
+    pub fn depth_usd(&self, price_a_usd: f64, decimals_a: u8, price_b_usd: f64, decimals_b: u8) -> f64 {
+        let a = self.depth_a_1pct / 10f64.powi(i32::from(decimals_a)) * price_a_usd;
+        let b = self.depth_b_1pct / 10f64.powi(i32::from(decimals_b)) * price_b_usd;
+        a + b
+    }
+
+    /// Share of the input side's 1% depth an `in_amount` trade consumes
+    pub fn utilization(&self, in_amount: u64, a_to_b: bool) -> f64 {
+        let depth = if a_to_b { self.depth_a_1pct } else { self.depth_b_1pct };
+        if depth > 0.0 {
+            in_amount as f64 / depth
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+/// Quote against a single Whirlpool
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhirlpoolQuote {
+    pub whirlpool: Pubkey,
+    pub a_to_b: bool,
+    pub mode: SwapMode,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_amount: u64,
+    /// Execution price vs spot (basis points)
+    pub price_impact_bps: f64,
+    pub tick_arrays: [Pubkey; SWAP_TICK_ARRAYS],
+}
+
+/// Quote a swap within the active liquidity range
+pub fn quote(
+    whirlpool: &Pubkey,
+    state: &WhirlpoolState,
+    amount: u64,
+    mode: SwapMode,
+    a_to_b: bool,
+) -> Result<WhirlpoolQuote> {
+    if state.liquidity == 0 {
+        return Err(SentinelError::DexError(
+            "Whirlpool has no liquidity in the current range".to_string(),
+        ));
+    }
+
+    let liquidity = state.liquidity as f64;
+    let sqrt_price = state.sqrt_price_x64 as f64 / Q64;
+    let fee = f64::from(state.fee_rate) / FEE_RATE_DENOMINATOR;
+    let amount_f = amount as f64;
+
+    // (input net of fee, output) along the constant-liquidity curve
+    let (net_in, out) = match (mode, a_to_b) {
+        (SwapMode::ExactIn, true) => {
+            let net_in = amount_f * (1.0 - fee);
+            let next = liquidity * sqrt_price / (liquidity + net_in * sqrt_price);
+            (net_in, liquidity * (sqrt_price - next))
+        }
+        (SwapMode::ExactIn, false) => {
+            let net_in = amount_f * (1.0 - fee);
+            let next = sqrt_price + net_in / liquidity;
+            (net_in, liquidity * (1.0 / sqrt_price - 1.0 / next))
+        }
+        (SwapMode::ExactOut, true) => {
+            let next = sqrt_price - amount_f / liquidity;
+            if next <= 0.0 {
+                return Err(insufficient_liquidity(amount));
+            }
+            (liquidity * (1.0 / next - 1.0 / sqrt_price), amount_f)
+        }
+        (SwapMode::ExactOut, false) => {
+            let inv_next = 1.0 / sqrt_price - amount_f / liquidity;
+            if inv_next <= 0.0 {
+                return Err(insufficient_liquidity(amount));
+            }
+            (liquidity * (1.0 / inv_next - sqrt_price), amount_f)
+        }
+    };
+
+    let gross_in = match mode {
+        SwapMode::ExactIn => amount_f,
+        SwapMode::ExactOut => net_in / (1.0 - fee),
+    };
+
+    let spot_out = if a_to_b {
+        net_in * sqrt_price * sqrt_price
+    } else {
+        net_in / (sqrt_price * sqrt_price)
+    };
+    let price_impact_bps = if spot_out > 0.0 {
+        ((1.0 - out / spot_out) * 10_000.0).max(0.0)
+    } else {
+        0.0
+    };
+
+    Ok(WhirlpoolQuote {
+        whirlpool: *whirlpool,
+        a_to_b,
+        mode,
+        in_amount: gross_in.ceil() as u64,
+        out_amount: out.floor() as u64,
+        fee_amount: (gross_in - net_in).ceil() as u64,
+        price_impact_bps,
+        tick_arrays: tick_array_addresses(whirlpool, state, a_to_b),
+    })
+}
+
+fn insufficient_liquidity(amount: u64) -> SentinelError {
+    SentinelError::DexError(format!(
+        "Output {} exceeds Whirlpool active-range liquidity",
+        amount
+    ))
+}
+
+/// First tick of the array containing `tick`
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let span = i32::from(tick_spacing) * TICK_ARRAY_SIZE;
+    tick.div_euclid(span) * span
+}
+
+/// Tick-array PDA for a start index
+pub fn tick_array_address(whirlpool: &Pubkey, start_tick_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"tick_array",
+            whirlpool.as_ref(),
+            start_tick_index.to_string().as_bytes(),
+        ],
+        &program_id(),
+    )
+    .0
+}
+
+/// The three tick arrays a swap traverses, in swap direction
+///
+/// B→A swaps start one tick spacing ahead, matching the on-chain traversal.
+pub fn tick_array_addresses(
+    whirlpool: &Pubkey,
+    state: &WhirlpoolState,
+    a_to_b: bool,
+) -> [Pubkey; SWAP_TICK_ARRAYS] {
+    let span = i32::from(state.tick_spacing) * TICK_ARRAY_SIZE;
+    let shift = if a_to_b { 0 } else { i32::from(state.tick_spacing) };
+    let start = tick_array_start_index(state.tick_current_index + shift, state.tick_spacing);
+    let step = if a_to_b { -span } else { span };
+
+    [0, 1, 2].map(|i| tick_array_address(whirlpool, start + step * i))
+}
+
+/// Oracle PDA for a Whirlpool
+pub fn oracle_address(whirlpool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"oracle", whirlpool.as_ref()], &program_id()).0
+}
+
+/// Associated token account of `owner` for `mint`
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let ata_program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)
+        .expect("Hardcoded ATA program ID must be valid");
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program_id().as_ref(), mint.as_ref()],
+        &ata_program,
+    )
+    .0
+}
+
+/// Accounts a swap touches, for `SwapDetails::route_hints`
+///
+/// Order: whirlpool, tick arrays (swap direction), oracle.
+pub fn route_hints(whirlpool: &Pubkey, state: &WhirlpoolState, a_to_b: bool) -> Vec<Pubkey> {
+    let mut hints = vec![*whirlpool];
+    hints.extend(tick_array_addresses(whirlpool, state, a_to_b));
+    hints.push(oracle_address(whirlpool));
+    hints
+}
+
+/// Slippage-adjusted bound: min out (ExactIn) or max in (ExactOut)
+pub fn other_amount_threshold(quote: &WhirlpoolQuote, slippage_bps: u16, minimum_received: Option<u64>) -> u64 {
+    let slippage = u128::from(slippage_bps.min(10_000));
+    match quote.mode {
+        SwapMode::ExactIn => {
+            let bound = u128::from(quote.out_amount) * (10_000 - slippage) / 10_000;
+            (bound as u64).max(minimum_received.unwrap_or(0))
+        }
+        SwapMode::ExactOut => {
+            let bound = u128::from(quote.in_amount) * (10_000 + slippage) / 10_000;
+            u64::try_from(bound).unwrap_or(u64::MAX)
+        }
+    }
+}
+
+/// Build the Whirlpool `swap` instruction for `user`'s associated token accounts
+pub fn swap_instruction(
+    user: &Pubkey,
+    state: &WhirlpoolState,
+    quote: &WhirlpoolQuote,
+    other_amount_threshold: u64,
+) -> Instruction {
+    let amount_specified_is_input = matches!(quote.mode, SwapMode::ExactIn);
+    let amount = if amount_specified_is_input {
+        quote.in_amount
+    } else {
+        quote.out_amount
+    };
+    let sqrt_price_limit = if quote.a_to_b {
+        MIN_SQRT_PRICE_X64
+    } else {
+        MAX_SQRT_PRICE_X64
+    };
+
+    // Anchor discriminator: sha256("global:swap")[..8]
+    let mut data = Sha256::digest(b"global:swap")[..8].to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&other_amount_threshold.to_le_bytes());
+    data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+    data.push(u8::from(amount_specified_is_input));
+    data.push(u8::from(quote.a_to_b));
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(token_program_id(), false),
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(quote.whirlpool, false),
+        AccountMeta::new(associated_token_address(user, &state.token_mint_a), false),
+        AccountMeta::new(state.token_vault_a, false),
+        AccountMeta::new(associated_token_address(user, &state.token_mint_b), false),
+        AccountMeta::new(state.token_vault_b, false),
+    ];
+    accounts.extend(quote.tick_arrays.iter().map(|t| AccountMeta::new(*t, false)));
+    accounts.push(AccountMeta::new(oracle_address(&quote.whirlpool), false));
+
+    Instruction {
+        program_id: program_id(),
+        accounts,
+        data,
+    }
+}
+
+/// Fetches Whirlpool accounts over JSON-RPC
+pub struct WhirlpoolClient {
+    http_client: Client,
+    rpc_url: String,
+}
+
+impl WhirlpoolClient {
+    pub fn new(rpc_url: impl Into<String>) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            rpc_url: rpc_url.into(),
+        })
+    }
+
+    /// Fetch and decode a Whirlpool account
+    pub async fn fetch_pool(&self, whirlpool: &Pubkey) -> Result<WhirlpoolState> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAccountInfo",
+            "params": [whirlpool.to_string(), { "encoding": "base64" }],
+        });
+
+        let response: RpcResponse = self
+            .http_client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("getAccountInfo failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("Invalid getAccountInfo response: {}", e)))?;
+
+        if let Some(error) = response.error {
+            return Err(SentinelError::RpcError(error.message));
+        }
+
+        let account = response
+            .result
+            .and_then(|r| r.value)
+            .ok_or_else(|| SentinelError::DexError(format!("Whirlpool {} not found", whirlpool)))?;
+
+        if account.owner != WHIRLPOOL_PROGRAM_ID {
+            return Err(SentinelError::DexError(format!(
+                "{} is not a Whirlpool account (owner {})",
+                whirlpool, account.owner
+            )));
+        }
+
+        let data = BASE64
+            .decode(&account.data.0)
+            .map_err(|e| SentinelError::SerializationError(format!("Invalid account data: {}", e)))?;
+
+        debug!("Fetched Whirlpool {} ({} bytes)", whirlpool, data.len());
+        WhirlpoolState::parse(&data)
+    }
+
+    /// Quote intent swap details against the Whirlpool in `route_hints[0]`
+    pub async fn quote(&self, swap_details: &SwapDetails) -> Result<(WhirlpoolState, WhirlpoolQuote)> {
+        let whirlpool = swap_details
+            .route_hints
+            .as_ref()
+            .and_then(|hints| hints.first())
+            .ok_or_else(|| {
+                SentinelError::DexError("Orca swaps require the Whirlpool address in route_hints".to_string())
+            })?;
+
+        let state = self.fetch_pool(whirlpool).await?;
+        let a_to_b = state.direction(&swap_details.input_mint, &swap_details.output_mint)?;
+        let quote = quote(whirlpool, &state, swap_details.amount, swap_details.mode, a_to_b)?;
+        Ok((state, quote))
+    }
+
+    /// Quote and build a swap for intent swap details
+    ///
+    /// `swap_details.route_hints[0]` selects the Whirlpool.
+    pub async fn build_swap_instruction(
+        &self,
+        user: &Pubkey,
+        swap_details: &SwapDetails,
+        slippage_bps: u16,
+    ) -> Result<(Instruction, WhirlpoolQuote)> {
+        let (state, quote) = self.quote(swap_details).await?;
+        let threshold = other_amount_threshold(&quote, slippage_bps, swap_details.minimum_received);
+
+        Ok((swap_instruction(user, &state, &quote, threshold), quote))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<RpcAccountResult>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcAccountResult {
+    value: Option<RpcAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcAccount {
+    owner: String,
+    data: (String, String),
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SOL/USDC-like pool: price 150 USDC per SOL in raw atoms (9 vs 6 decimals)
+    fn pool() -> WhirlpoolState {
+        let sqrt_price = (150.0_f64 * 1e6 / 1e9).sqrt();
+        WhirlpoolState {
+            tick_spacing: 64,
+            fee_rate: 3_000,
+            liquidity: 50_000_000_000_000,
+            sqrt_price_x64: (sqrt_price * Q64) as u128,
+            tick_current_index: -18_970,
+            token_mint_a: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+        }
+    }
+
+    fn encode(state: &WhirlpoolState) -> Vec<u8> {
+        let mut data = vec![0u8; WHIRLPOOL_ACCOUNT_LEN];
+        data[41..43].copy_from_slice(&state.tick_spacing.to_le_bytes());
+        data[45..47].copy_from_slice(&state.fee_rate.to_le_bytes());
+        data[49..65].copy_from_slice(&state.liquidity.to_le_bytes());
+        data[65..81].copy_from_slice(&state.sqrt_price_x64.to_le_bytes());
+        data[81..85].copy_from_slice(&state.tick_current_index.to_le_bytes());
+        data[101..133].copy_from_slice(state.token_mint_a.as_ref());
+        data[133..165].copy_from_slice(state.token_vault_a.as_ref());
+        data[181..213].copy_from_slice(state.token_mint_b.as_ref());
+        data[213..245].copy_from_slice(state.token_vault_b.as_ref());
+        data
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let state = pool();
+        assert_eq!(WhirlpoolState::parse(&encode(&state)).unwrap(), state);
+        assert!(WhirlpoolState::parse(&[0u8; 100]).is_err());
+    }
+
+    #[test]
+    fn test_tick_array_start_index() {
+        assert_eq!(tick_array_start_index(0, 64), 0);
+        assert_eq!(tick_array_start_index(5_631, 64), 0);
+        assert_eq!(tick_array_start_index(5_632, 64), 5_632);
+        assert_eq!(tick_array_start_index(-1, 64), -5_632);
+    }
+
+    #[test]
+    fn test_tick_arrays_follow_direction() {
+        let whirlpool = Pubkey::new_unique();
+        let state = pool();
+        let start = tick_array_start_index(state.tick_current_index, state.tick_spacing);
+
+        let down = tick_array_addresses(&whirlpool, &state, true);
+        assert_eq!(down[0], tick_array_address(&whirlpool, start));
+        assert_eq!(down[1], tick_array_address(&whirlpool, start - 5_632));
+
+        let hints = route_hints(&whirlpool, &state, false);
+        assert_eq!(hints.len(), 5);
+        assert_eq!(hints[0], whirlpool);
+        assert_eq!(hints[4], oracle_address(&whirlpool));
+    }
+
+    #[test]
+    fn test_exact_in_quote() {
+        let state = pool();
+        let whirlpool = Pubkey::new_unique();
+
+        // 1 SOL -> ~150 USDC minus 0.3% fee and a little impact
+        let quote = quote(&whirlpool, &state, 1_000_000_000, SwapMode::ExactIn, true).unwrap();
+        assert!(quote.out_amount > 149_000_000 && quote.out_amount < 149_600_000);
+        assert_eq!(quote.fee_amount, 3_000_000);
+        assert!(quote.price_impact_bps > 0.0 && quote.price_impact_bps < 10.0);
+
+        let threshold = other_amount_threshold(&quote, 50, None);
+        assert_eq!(threshold, quote.out_amount * 9_950 / 10_000);
+    }
+
+    #[test]
+    fn test_exact_out_quote_inverts_exact_in() {
+        let state = pool();
+        let whirlpool = Pubkey::new_unique();
+
+        let exact_in = quote(&whirlpool, &state, 150_000_000, SwapMode::ExactIn, false).unwrap();
+        let exact_out = quote(&whirlpool, &state, exact_in.out_amount, SwapMode::ExactOut, false).unwrap();
+        assert!(exact_out.in_amount.abs_diff(150_000_000) <= 2);
+    }
+
+    #[test]
+    fn test_swap_instruction_layout() {
+        let state = pool();
+        let whirlpool = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let quote = quote(&whirlpool, &state, 1_000_000, SwapMode::ExactIn, true).unwrap();
+
+        let ix = swap_instruction(&user, &state, &quote, 42);
+        assert_eq!(ix.program_id, program_id());
+        assert_eq!(ix.accounts.len(), 11);
+        assert_eq!(ix.data.len(), 8 + 8 + 8 + 16 + 2);
+        assert_eq!(&ix.data[..8], &[248, 198, 158, 145, 225, 117, 135, 200]);
+        assert_eq!(ix.accounts[3].pubkey, associated_token_address(&user, &state.token_mint_a));
+        assert_eq!(&ix.data[40..], &[1, 1]);
+    }
+
+    #[test]
+    fn test_depth_usd() {
+        let depth = pool().depth();
+        assert_eq!(depth.liquidity, 50_000_000_000_000);
+        assert!((depth.price - 0.15).abs() < 1e-9);
+
+        let usd = depth.depth_usd(150.0, 9, 1.0, 6);
+        assert!(usd > 0.0);
+
+        let half = (depth.depth_a_1pct / 2.0) as u64;
+        assert!((depth.utilization(half, true) - 0.5).abs() < 1e-6);
+        assert!(depth.utilization(half, false) != depth.utilization(half, true));
+    }
+}
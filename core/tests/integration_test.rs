@@ -1,7 +1,7 @@
 use chrono::Utc;
 use sentinel_core::{
-    ConsentBlock, Constraints, FeePreferences, Intent, IntentType, MevRiskScore, RouteType, SwapDetails,
-    SwapMode, TransactionStatus,
+    ConsentBlock, Constraints, FeePreferences, Intent, IntentType, MevRiskScore, ProtectionTier, RouteType,
+    SwapDetails, SwapMode, TransactionStatus, INTENT_SCHEMA_VERSION,
 };
 use solana_sdk::{hash::Hash, pubkey::Pubkey};
 
@@ -26,11 +26,15 @@ fn test_intent_creation() {
             expiry_timestamp: Some(Utc::now().timestamp() + 60),
             partial_fill: false,
             ttl_seconds: None,
+            managed_slippage: false,
+            allow_netting: false,
         },
         fee_preferences: FeePreferences {
             max_priority_fee_lamports: 10_000,
             max_jito_tip_lamports: 100_000,
             tip_allocation_pct: 50, // 50% of tip goes to priority
+            allow_malicious_leader: false,
+            protection_tier: ProtectionTier::Standard,
         },
         consent_block: ConsentBlock {
             recent_blockhash: Hash::default(),
@@ -39,6 +43,9 @@ fn test_intent_creation() {
         },
         limit_details: None,
         twap_details: None,
+        dca_details: None,
+        legs: vec![],
+        schema_version: INTENT_SCHEMA_VERSION,
     };
 
     assert!(matches!(intent.intent_type, IntentType::Swap));
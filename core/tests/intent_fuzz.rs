@@ -0,0 +1,197 @@
+//! Intent Decoding Fuzz Tests
+//! Property-based checks that untrusted intent JSON never panics the decoder
+//! and that structural limits hold for arbitrary input
+
+use proptest::prelude::*;
+use sentinel_core::{
+    check_json, decode_intent, ConsentBlock, Constraints, DecodeError, FeePreferences, Intent, IntentType,
+    JsonLimits, ProtectionTier, SwapDetails, SwapMode, INTENT_SCHEMA_VERSION,
+};
+use serde_json::Value;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// Schema-valid intents with arbitrary field values
+fn intent() -> impl Strategy<Value = Intent> {
+    (
+        "[a-z0-9-]{1,36}",
+        pubkey(),
+        pubkey(),
+        pubkey(),
+        any::<u64>(),
+        proptest::option::of(any::<u64>()),
+        proptest::option::of(proptest::collection::vec(pubkey(), 0..8)),
+        any::<u16>(),
+        any::<bool>(),
+        proptest::option::of(any::<i64>()),
+        (any::<u64>(), any::<u64>(), any::<u8>()),
+        any::<[u8; 32]>(),
+    )
+        .prop_map(
+            |(id, user, input_mint, output_mint, amount, minimum, hints, slippage, partial, expiry, fees, blockhash)| {
+                Intent {
+                    intent_id: id.clone(),
+                    user_public_key: user,
+                    intent_type: IntentType::Swap,
+                    swap_details: Some(SwapDetails {
+                        mode: SwapMode::ExactIn,
+                        input_mint,
+                        output_mint,
+                        amount,
+                        minimum_received: minimum,
+                        dex: Some("Jupiter".to_string()),
+                        route_hints: hints,
+                    }),
+                    constraints: Constraints {
+                        max_slippage_bps: slippage,
+                        partial_fill: partial,
+                        expiry_timestamp: expiry,
+                        ..Constraints::default()
+                    },
+                    fee_preferences: FeePreferences {
+                        max_priority_fee_lamports: fees.0,
+                        max_jito_tip_lamports: fees.1,
+                        tip_allocation_pct: fees.2,
+                        allow_malicious_leader: false,
+                        protection_tier: ProtectionTier::Standard,
+                    },
+                    consent_block: ConsentBlock {
+                        recent_blockhash: Hash::new_from_array(blockhash),
+                        signature_request_id: id,
+                        nonce: None,
+                    },
+                    limit_details: None,
+                    twap_details: None,
+                    dca_details: None,
+                    legs: vec![],
+                    schema_version: INTENT_SCHEMA_VERSION,
+                }
+            },
+        )
+}
+
+/// Arbitrary JSON documents, including deep and wide ones
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        any::<f64>().prop_map(Value::from),
+        ".{0,300}".prop_map(Value::String),
+    ];
+    leaf.prop_recursive(24, 512, 80, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..80).prop_map(Value::Array),
+            proptest::collection::hash_map(".{0,12}", inner, 0..80)
+                .prop_map(|members| Value::Object(members.into_iter().collect())),
+        ]
+    })
+}
+
+/// Depth and largest string/collection of a parsed document
+fn shape(value: &Value) -> (usize, usize, usize) {
+    match value {
+        Value::String(s) => (0, s.len(), 0),
+        Value::Array(items) => items.iter().map(shape).fold((1, 0, items.len()), |acc, s| {
+            (acc.0.max(s.0 + 1), acc.1.max(s.1), acc.2.max(s.2))
+        }),
+        Value::Object(members) => members
+            .iter()
+            .map(|(k, v)| {
+                let s = shape(v);
+                (s.0, s.1.max(k.len()), s.2)
+            })
+            .fold((1, 0, members.len()), |acc, s| (acc.0.max(s.0 + 1), acc.1.max(s.1), acc.2.max(s.2))),
+        _ => (0, 0, 0),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    /// Test: Arbitrary bytes are rejected or decoded, never panic
+    #[test]
+    fn test_arbitrary_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..4096)) {
+        let _ = decode_intent(&bytes);
+    }
+
+    /// Test: Arbitrary JSON documents are rejected or decoded, never panic
+    #[test]
+    fn test_arbitrary_json_never_panics(value in json_value()) {
+        let _ = decode_intent(value.to_string().as_bytes());
+    }
+
+    /// Test: Generated intents survive the hardened decoder unchanged
+    #[test]
+    fn test_valid_intents_round_trip(intent in intent()) {
+        let json = serde_json::to_vec(&intent).unwrap();
+        prop_assert_eq!(decode_intent(&json), Ok(intent));
+    }
+
+    /// Test: Corrupted intent JSON never panics
+    #[test]
+    fn test_mutated_intents_never_panic(
+        intent in intent(),
+        edits in proptest::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..16),
+        cut in any::<prop::sample::Index>(),
+    ) {
+        let mut json = serde_json::to_vec(&intent).unwrap();
+        for (at, byte) in edits {
+            let i = at.index(json.len());
+            json[i] = byte;
+        }
+        json.truncate(cut.index(json.len() + 1));
+        let _ = decode_intent(&json);
+    }
+
+    /// Test: Accepted documents respect every limit; in-limit documents are accepted
+    #[test]
+    fn test_limits_match_document_shape(value in json_value()) {
+        let json = value.to_string();
+        let limits = JsonLimits::default();
+        let (depth, longest_string, widest) = shape(&value);
+        let escaped = json.contains('\\');
+
+        match check_json(json.as_bytes(), &limits) {
+            Ok(()) => {
+                prop_assert!(json.len() <= limits.max_bytes);
+                prop_assert!(depth <= limits.max_depth);
+                prop_assert!(longest_string <= limits.max_string_len);
+                prop_assert!(widest <= limits.max_collection_len);
+            }
+            // Escapes count raw bytes, so only unescaped documents must pass
+            Err(_) if escaped => {}
+            Err(e) => prop_assert!(
+                json.len() > limits.max_bytes
+                    || depth > limits.max_depth
+                    || longest_string > limits.max_string_len
+                    || widest > limits.max_collection_len,
+                "rejected in-limit document: {}", e
+            ),
+        }
+    }
+}
+
+/// Test: Pathological nesting is rejected before parsing
+#[test]
+fn test_deep_nesting_rejected() {
+    let json = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+    assert!(matches!(decode_intent(json.as_bytes()), Err(DecodeError::TooDeep(_))));
+}
+
+/// Test: Oversized strings and arrays are rejected before allocation
+#[test]
+fn test_oversized_fields_rejected() {
+    let long_id = format!(r#"{{"intent_id":"{}"}}"#, "a".repeat(10_000));
+    assert!(matches!(decode_intent(long_id.as_bytes()), Err(DecodeError::StringTooLong { .. })));
+
+    let many_hints = format!("[{}]", vec!["0"; 1_000].join(","));
+    assert!(matches!(decode_intent(many_hints.as_bytes()), Err(DecodeError::CollectionTooLong { .. })));
+
+    let huge = vec![b' '; 1 << 20];
+    assert!(matches!(decode_intent(&huge), Err(DecodeError::TooLarge { .. })));
+}
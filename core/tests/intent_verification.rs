@@ -6,35 +6,15 @@
 use chrono::Utc;
 use sentinel_core::{
     ConsentBlock, Constraints, FeePreferences, Intent, IntentError, IntentType, LimitDetails,
-    Priority, SwapDetails, SwapMode, TwapDetails,
+    Priority, ProtectionTier, SwapDetails, SwapMode, TwapDetails, INTENT_SCHEMA_VERSION,
 };
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 
 fn create_valid_swap_intent() -> Intent {
-    Intent {
-        intent_id: Intent::new_signature_request_id(),
-        user_public_key: Pubkey::new_unique(),
-        intent_type: IntentType::Swap,
-        swap_details: Some(SwapDetails {
-            mode: SwapMode::ExactIn,
-            input_mint: Pubkey::new_unique(),
-            output_mint: Pubkey::new_unique(),
-            amount: 1_000_000,
-            minimum_received: Some(900_000),
-            dex: Some("Jupiter".to_string()),
-            route_hints: None,
-        }),
-        constraints: Constraints::default(),
-        fee_preferences: FeePreferences::default(),
-        consent_block: ConsentBlock {
-            recent_blockhash: Hash::new_unique(),
-            signature_request_id: Intent::new_signature_request_id(),
-            nonce: None,
-        },
-        limit_details: None,
-        twap_details: None,
-    }
+    let mut intent = Intent::test_swap();
+    intent.test_swap_details().dex = Some("Jupiter".to_string());
+    intent
 }
 
 // ================================================================================================
@@ -662,11 +642,15 @@ fn test_intent_with_all_optional_fields() {
             partial_fill: true,
             expiry_timestamp: Some(Utc::now().timestamp() + 3600),
             ttl_seconds: None,
+            managed_slippage: false,
+            allow_netting: false,
         },
         fee_preferences: FeePreferences {
             max_priority_fee_lamports: 200_000,
             max_jito_tip_lamports: 100_000,
             tip_allocation_pct: 80,
+            allow_malicious_leader: false,
+            protection_tier: ProtectionTier::Standard,
         },
         consent_block: ConsentBlock {
             recent_blockhash: Hash::new_unique(),
@@ -675,6 +659,9 @@ fn test_intent_with_all_optional_fields() {
         },
         limit_details: None,
         twap_details: None,
+        dca_details: None,
+        legs: vec![],
+        schema_version: INTENT_SCHEMA_VERSION,
     };
     
     let current_time = Utc::now().timestamp();
@@ -711,22 +698,13 @@ fn test_performance_target_validation() {
 #[test]
 fn test_limit_order_with_oracle() {
     let intent = Intent {
-        intent_id: Intent::new_signature_request_id(),
-        user_public_key: Pubkey::new_unique(),
         intent_type: IntentType::Limit,
         swap_details: None,
-        constraints: Constraints::default(),
-        fee_preferences: FeePreferences::default(),
-        consent_block: ConsentBlock {
-            recent_blockhash: Hash::new_unique(),
-            signature_request_id: Intent::new_signature_request_id(),
-            nonce: None,
-        },
         limit_details: Some(LimitDetails {
             price_threshold: 100.5,
             oracle: Some(Pubkey::new_unique()), // Pyth oracle address
         }),
-        twap_details: None,
+        ..Intent::test_swap()
     };
     
     // Limit orders now validate successfully with real validation logic
@@ -775,6 +753,7 @@ fn test_intent_status_serialization() {
         IntentStatus::Confirmed,
         IntentStatus::Failed("Transaction timeout".to_string()),
         IntentStatus::Expired,
+        IntentStatus::Cancelled,
     ];
     
     for status in statuses {
@@ -198,6 +198,21 @@ fn test_mev_risk_score_serialization() {
     assert_eq!(score.score(), deserialized.score());
 }
 
+/// Test: confidence round-trips; scores without one stay bare numbers
+#[test]
+fn test_mev_risk_score_confidence_serialization() {
+    assert_eq!(serde_json::to_string(&MevRiskScore::new(0.5)).unwrap(), "0.5");
+
+    let score = MevRiskScore::new(0.9).with_confidence(1.5);
+    assert_eq!(score.confidence(), Some(1.0));
+
+    let json = serde_json::to_string(&score).unwrap();
+    assert_eq!(json, r#"{"score":0.9,"confidence":1.0}"#);
+    let deserialized: MevRiskScore = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.confidence(), Some(1.0));
+    assert_eq!(serde_json::from_str::<MevRiskScore>("0.25").unwrap().confidence(), None);
+}
+
 /// Test: Transaction status serialization
 #[test]
 fn test_transaction_status_serialization() {
@@ -0,0 +1,111 @@
+//! Minimal constant-product AMM for the local validator end-to-end tests
+//!
+//! A pool is one account owned by this program holding two reserves
+//! (`reserve_a`, `reserve_b`, little-endian u64s). `Swap` prices against
+//! x·y=k with a 30 bps fee and fails when the output falls below
+//! `minimum_out`, like a real DEX's slippage check. No tokens move: the tests
+//! only need a deployed program a swap intent can hit and whose state can be
+//! read back. `sentinel-e2e`'s `amm` module encodes the same layout.
+//!
+//! Instructions (first byte is the tag):
+//! - `0` Initialize `{ reserve_a: u64, reserve_b: u64 }` — accounts `[pool (w)]`
+//! - `1` Swap `{ amount_in: u64, minimum_out: u64, a_to_b: u8 }` — accounts `[pool (w), user (s)]`
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Pool account size
+pub const POOL_LEN: usize = 16;
+
+/// Swap fee (basis points of the input)
+pub const FEE_BPS: u64 = 30;
+
+/// Output below the swap's `minimum_out`
+pub const ERR_SLIPPAGE: u32 = 1;
+
+/// Pool not initialized, or already initialized
+pub const ERR_POOL_STATE: u32 = 2;
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let pool = next_account_info(accounts)?;
+    if pool.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if pool.data_len() < POOL_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    match data.split_first() {
+        Some((0, rest)) => {
+            let (reserve_a, reserve_b) = (read_u64(rest, 0)?, read_u64(rest, 8)?);
+            if read_reserves(pool)? != (0, 0) || reserve_a == 0 || reserve_b == 0 {
+                return Err(ProgramError::Custom(ERR_POOL_STATE));
+            }
+            write_reserves(pool, reserve_a, reserve_b)
+        }
+        Some((1, rest)) => {
+            let user = next_account_info(accounts)?;
+            if !user.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let (amount_in, minimum_out) = (read_u64(rest, 0)?, read_u64(rest, 8)?);
+            let a_to_b = *rest.get(16).ok_or(ProgramError::InvalidInstructionData)? != 0;
+
+            let (reserve_a, reserve_b) = read_reserves(pool)?;
+            if reserve_a == 0 || reserve_b == 0 {
+                return Err(ProgramError::Custom(ERR_POOL_STATE));
+            }
+            let (reserve_in, reserve_out) = if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+            let amount_out = quote(reserve_in, reserve_out, amount_in);
+            if amount_out < minimum_out {
+                msg!("Slippage: {} out < {} minimum", amount_out, minimum_out);
+                return Err(ProgramError::Custom(ERR_SLIPPAGE));
+            }
+
+            let reserve_in = reserve_in.checked_add(amount_in).ok_or(ProgramError::ArithmeticOverflow)?;
+            let reserve_out = reserve_out - amount_out;
+            msg!("Swapped {} for {}", amount_in, amount_out);
+            if a_to_b {
+                write_reserves(pool, reserve_in, reserve_out)
+            } else {
+                write_reserves(pool, reserve_out, reserve_in)
+            }
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Constant-product output for `amount_in`, after the fee
+pub fn quote(reserve_in: u64, reserve_out: u64, amount_in: u64) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - FEE_BPS) as u128 / 10_000;
+    let out = amount_in * reserve_out as u128 / (reserve_in as u128 + amount_in);
+    out as u64
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, ProgramError> {
+    data.get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn read_reserves(pool: &AccountInfo) -> Result<(u64, u64), ProgramError> {
+    let data = pool.try_borrow_data()?;
+    Ok((read_u64(&data, 0)?, read_u64(&data, 8)?))
+}
+
+fn write_reserves(pool: &AccountInfo, reserve_a: u64, reserve_b: u64) -> ProgramResult {
+    let mut data = pool.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(&reserve_a.to_le_bytes());
+    data[8..16].copy_from_slice(&reserve_b.to_le_bytes());
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Client side of `programs/minimal-amm` (same layout and pricing)
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+
+/// Pool account size
+pub const POOL_LEN: usize = 16;
+
+/// Swap fee (basis points of the input)
+pub const FEE_BPS: u64 = 30;
+
+/// Custom program error for a swap below its minimum output
+pub const ERR_SLIPPAGE: u32 = 1;
+
+/// Where `cargo build-sbf` leaves the program, unless `SENTINEL_E2E_AMM_SO` says otherwise
+pub fn program_path() -> PathBuf {
+    std::env::var_os("SENTINEL_E2E_AMM_SO").map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("programs/minimal-amm/target/deploy/minimal_amm.so")
+    })
+}
+
+pub fn initialize(program_id: &Pubkey, pool: &Pubkey, reserve_a: u64, reserve_b: u64) -> Instruction {
+    let mut data = vec![0];
+    data.extend_from_slice(&reserve_a.to_le_bytes());
+    data.extend_from_slice(&reserve_b.to_le_bytes());
+    Instruction::new_with_bytes(*program_id, &data, vec![AccountMeta::new(*pool, false)])
+}
+
+pub fn swap(program_id: &Pubkey, pool: &Pubkey, user: &Pubkey, amount_in: u64, minimum_out: u64, a_to_b: bool) -> Instruction {
+    let mut data = vec![1];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_out.to_le_bytes());
+    data.push(a_to_b as u8);
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![AccountMeta::new(*pool, false), AccountMeta::new_readonly(*user, true)],
+    )
+}
+
+/// (reserve_a, reserve_b) from pool account data
+pub fn reserves(data: &[u8]) -> Option<(u64, u64)> {
+    let a = u64::from_le_bytes(data.get(0..8)?.try_into().ok()?);
+    let b = u64::from_le_bytes(data.get(8..16)?.try_into().ok()?);
+    Some((a, b))
+}
+
+/// Constant-product output for `amount_in`, after the fee
+pub fn quote(reserve_in: u64, reserve_out: u64, amount_in: u64) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - FEE_BPS) as u128 / 10_000;
+    (amount_in * reserve_out as u128 / (reserve_in as u128 + amount_in)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_encoding_and_quote() {
+        let (program, pool, user) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = swap(&program, &pool, &user, 1_000, 900, true);
+        assert_eq!(ix.data.len(), 18);
+        assert_eq!((ix.data[0], ix.data[17]), (1, 1));
+        assert!(ix.accounts[1].is_signer && !ix.accounts[1].is_writable);
+
+        let mut data = 7u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&9u64.to_le_bytes());
+        assert_eq!(reserves(&data), Some((7, 9)));
+        assert_eq!(reserves(&data[..8]), None);
+
+        // 1% of a balanced pool: ~0.3% fee plus ~1% price impact
+        assert_eq!(quote(1_000_000, 1_000_000, 10_000), 9_871);
+    }
+}
@@ -0,0 +1,24 @@
+//! End-to-end harness: a real `solana-test-validator` behind the router
+//!
+//! The tests in `tests/` drive the whole pipeline — intent → features →
+//! score → route → build → submit → confirm — against a throwaway local
+//! validator with the [`minimal-amm`](../programs/minimal-amm) program
+//! deployed, and the `jito-mock` block engine forwarding accepted bundles to
+//! it so they really land.
+//!
+//! They are behind the `local-validator` feature and need the Solana CLI:
+//!
+//! ```text
+//! cargo build-sbf --manifest-path e2e/programs/minimal-amm/Cargo.toml
+//! cargo test -p sentinel-e2e --features local-validator
+//! ```
+//!
+//! `SOLANA_TEST_VALIDATOR` overrides the validator binary and
+//! `SENTINEL_E2E_AMM_SO` the program path.
+
+pub mod amm;
+pub mod rpc;
+pub mod validator;
+
+pub use rpc::LocalRpc;
+pub use validator::{LocalValidator, ValidatorOptions};
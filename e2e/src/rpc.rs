@@ -0,0 +1,141 @@
+//! Just enough validator JSON-RPC for the tests
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sentinel_core::{Result, SentinelError};
+use serde_json::{json, Value};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How often `confirm` polls signature statuses
+const CONFIRM_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+pub struct LocalRpc {
+    url: String,
+    http_client: reqwest::Client,
+}
+
+impl LocalRpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// `result` of one call; JSON-RPC errors become `RpcError`
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .http_client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("{} failed: {}", method, e)))?
+            .json()
+            .await
+            .map_err(|e| SentinelError::RpcError(format!("{} returned invalid JSON: {}", method, e)))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(SentinelError::RpcError(format!("{}: {}", method, error)));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    pub async fn is_healthy(&self) -> bool {
+        self.call("getHealth", json!([])).await.is_ok()
+    }
+
+    pub async fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }])).await?;
+        let blockhash = result["value"]["blockhash"].as_str().unwrap_or_default();
+        Hash::from_str(blockhash).map_err(|e| SentinelError::RpcError(format!("Bad blockhash {}: {}", blockhash, e)))
+    }
+
+    pub async fn balance(&self, account: &Pubkey) -> Result<u64> {
+        let result = self
+            .call("getBalance", json!([account.to_string(), { "commitment": "confirmed" }]))
+            .await?;
+        Ok(result["value"].as_u64().unwrap_or(0))
+    }
+
+    /// Account data, or None if the account does not exist
+    pub async fn account_data(&self, account: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self
+            .call(
+                "getAccountInfo",
+                json!([account.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+            )
+            .await?;
+        let Some(data) = result["value"]["data"][0].as_str() else {
+            return Ok(None);
+        };
+        BASE64
+            .decode(data)
+            .map(Some)
+            .map_err(|e| SentinelError::SerializationError(format!("Account data: {}", e)))
+    }
+
+    pub async fn is_executable(&self, program_id: &Pubkey) -> Result<bool> {
+        let result = self
+            .call("getAccountInfo", json!([program_id.to_string(), { "encoding": "base64" }]))
+            .await?;
+        Ok(result["value"]["executable"].as_bool().unwrap_or(false))
+    }
+
+    /// Fund `account` from the validator's faucet and wait for it to confirm
+    pub async fn airdrop(&self, account: &Pubkey, lamports: u64) -> Result<()> {
+        let result = self.call("requestAirdrop", json!([account.to_string(), lamports])).await?;
+        let signature = parse_signature(&result)?;
+        self.confirm(&signature, Duration::from_secs(30)).await
+    }
+
+    /// Send without preflight, so on-chain failures show up in `confirm`
+    pub async fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        let bytes = bincode::serialize(transaction).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        let result = self
+            .call(
+                "sendTransaction",
+                json!([BASE64.encode(bytes), { "encoding": "base64", "skipPreflight": true }]),
+            )
+            .await?;
+        parse_signature(&result)
+    }
+
+    /// Wait until `signature` is confirmed; a failed transaction is an `RpcError`
+    pub async fn confirm(&self, signature: &Signature, timeout: Duration) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            let result = self
+                .call(
+                    "getSignatureStatuses",
+                    json!([[signature.to_string()], { "searchTransactionHistory": true }]),
+                )
+                .await?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(SentinelError::RpcError(format!("{} failed: {}", signature, status["err"])));
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(());
+                }
+            }
+            if started.elapsed() >= timeout {
+                return Err(SentinelError::Timeout(format!("{} not confirmed after {:?}", signature, timeout)));
+            }
+            tokio::time::sleep(CONFIRM_POLL).await;
+        }
+    }
+}
+
+fn parse_signature(value: &Value) -> Result<Signature> {
+    let signature = value.as_str().unwrap_or_default();
+    Signature::from_str(signature).map_err(|e| SentinelError::RpcError(format!("Bad signature {}: {}", signature, e)))
+}
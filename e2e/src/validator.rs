@@ -0,0 +1,124 @@
+//! Throwaway `solana-test-validator` process
+
+use sentinel_core::{Result, SentinelError};
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::rpc::LocalRpc;
+
+/// Ports used above `rpc_port`: websocket (+1), faucet (+2), gossip (+3), dynamic range (+10..+60)
+const PORT_SPAN: u16 = 60;
+
+/// A fresh ledger boots in a few seconds; CI machines can be much slower
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Clone)]
+pub struct ValidatorOptions {
+    /// JSON-RPC port; tests running in parallel need distinct ones
+    pub rpc_port: u16,
+
+    /// Programs loaded at genesis, as (program id, `.so` path)
+    pub programs: Vec<(Pubkey, PathBuf)>,
+}
+
+impl Default for ValidatorOptions {
+    fn default() -> Self {
+        Self {
+            rpc_port: 18_899,
+            programs: Vec::new(),
+        }
+    }
+}
+
+impl ValidatorOptions {
+    pub fn with_rpc_port(mut self, rpc_port: u16) -> Self {
+        self.rpc_port = rpc_port;
+        self
+    }
+
+    pub fn with_program(mut self, program_id: Pubkey, path: impl Into<PathBuf>) -> Self {
+        self.programs.push((program_id, path.into()));
+        self
+    }
+}
+
+/// Running validator; killed and its ledger removed on drop
+pub struct LocalValidator {
+    child: Child,
+    ledger: PathBuf,
+    rpc: LocalRpc,
+}
+
+impl LocalValidator {
+    /// Spawn on a fresh ledger and wait until RPC reports healthy
+    pub async fn start(options: ValidatorOptions) -> Result<Self> {
+        let binary = std::env::var("SOLANA_TEST_VALIDATOR").unwrap_or_else(|_| "solana-test-validator".to_string());
+        let ledger = std::env::temp_dir().join(format!("sentinel-e2e-{}-{}", std::process::id(), options.rpc_port));
+        let port = options.rpc_port;
+
+        let mut command = Command::new(&binary);
+        command
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .args(["--rpc-port", &port.to_string()])
+            .args(["--faucet-port", &(port + 2).to_string()])
+            .args(["--gossip-port", &(port + 3).to_string()])
+            .args(["--dynamic-port-range", &format!("{}-{}", port + 10, port + PORT_SPAN)]);
+        for (program_id, path) in &options.programs {
+            if !path.exists() {
+                return Err(SentinelError::ConfigError(format!(
+                    "Program {} not found at {} (build it with `cargo build-sbf`)",
+                    program_id,
+                    path.display()
+                )));
+            }
+            command.arg("--bpf-program").arg(program_id.to_string()).arg(path);
+        }
+
+        let child = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| SentinelError::IoError(format!("Failed to start {}: {}", binary, e)))?;
+        let mut validator = Self {
+            child,
+            ledger,
+            rpc: LocalRpc::new(format!("http://127.0.0.1:{}", port)),
+        };
+
+        let started = Instant::now();
+        while !validator.rpc.is_healthy().await {
+            if let Ok(Some(status)) = validator.child.try_wait() {
+                return Err(SentinelError::IoError(format!("{} exited during startup: {}", binary, status)));
+            }
+            if started.elapsed() >= STARTUP_TIMEOUT {
+                return Err(SentinelError::Timeout(format!("{} not healthy after {:?}", binary, STARTUP_TIMEOUT)));
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+
+        info!("Local validator ready at {} ({:?})", validator.rpc.url(), started.elapsed());
+        Ok(validator)
+    }
+
+    pub fn rpc(&self) -> &LocalRpc {
+        &self.rpc
+    }
+
+    pub fn rpc_url(&self) -> &str {
+        self.rpc.url()
+    }
+}
+
+impl Drop for LocalValidator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
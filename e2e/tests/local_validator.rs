@@ -0,0 +1,201 @@
+//! Full pipeline against solana-test-validator (`--features local-validator`)
+//!
+//! intent → features → score → route → build → submit → confirm, with the
+//! swap executed by the deployed minimal AMM and bundles landed through the
+//! mock block engine's forwarding.
+#![cfg(feature = "local-validator")]
+
+use ai_engine::{FeatureExtractor, InferenceEngine};
+use jito_bundler::builder::FeeAllocation;
+use jito_bundler::{BundleBuilder, BundleSubmitter, JitoClient, SubmitOptions};
+use jito_mock::{MockServer, MockSettings};
+use sentinel_core::{
+    estimate_sandwich_loss, Constraints, Intent, Network, RouteCostInputs, RouteEconomics, RouteType, SystemClock,
+};
+use sentinel_e2e::{amm, LocalRpc, LocalValidator, ValidatorOptions};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+use std::time::Duration;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const POOL_RESERVE: u64 = 1_000_000_000;
+const SLIPPAGE_BPS: u16 = 100;
+const TIP_LAMPORTS: u64 = 10_000;
+
+struct Localnet {
+    _validator: LocalValidator,
+    rpc: LocalRpc,
+    mock: MockServer,
+    amm_id: Pubkey,
+    pool: Pubkey,
+    user: Keypair,
+}
+
+impl Localnet {
+    /// Validator with the AMM deployed, a seeded pool, a funded user and a forwarding mock engine
+    async fn start(rpc_port: u16) -> Self {
+        let amm_id = Pubkey::new_unique();
+        let validator = LocalValidator::start(
+            ValidatorOptions::default()
+                .with_rpc_port(rpc_port)
+                .with_program(amm_id, amm::program_path()),
+        )
+        .await
+        .unwrap();
+        let rpc = validator.rpc().clone();
+        assert!(rpc.is_executable(&amm_id).await.unwrap());
+
+        let user = Keypair::new();
+        rpc.airdrop(&user.pubkey(), 10 * LAMPORTS_PER_SOL).await.unwrap();
+        // Tip accounts don't exist on a fresh ledger; fund one so small tips clear rent
+        rpc.airdrop(&Network::Localnet.tip_account(0), LAMPORTS_PER_SOL).await.unwrap();
+
+        let pool = Keypair::new();
+        let rent = rpc
+            .call("getMinimumBalanceForRentExemption", serde_json::json!([amm::POOL_LEN]))
+            .await
+            .unwrap()
+            .as_u64()
+            .unwrap();
+        #[allow(deprecated)]
+        let create =
+            solana_sdk::system_instruction::create_account(&user.pubkey(), &pool.pubkey(), rent, amm::POOL_LEN as u64, &amm_id);
+        let init = amm::initialize(&amm_id, &pool.pubkey(), POOL_RESERVE, POOL_RESERVE);
+        let blockhash = rpc.latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(&[create, init], Some(&user.pubkey()), &[&user, &pool], blockhash);
+        let signature = rpc.send_transaction(&tx).await.unwrap();
+        rpc.confirm(&signature, CONFIRM_TIMEOUT).await.unwrap();
+
+        let mock = MockServer::start(MockSettings {
+            forward_rpc_url: Some(rpc.url().to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        Self {
+            _validator: validator,
+            rpc,
+            mock,
+            amm_id,
+            pool: pool.pubkey(),
+            user,
+        }
+    }
+
+    async fn reserves(&self) -> (u64, u64) {
+        let data = self.rpc.account_data(&self.pool).await.unwrap().unwrap();
+        amm::reserves(&data).unwrap()
+    }
+
+    fn swap_intent(&self, amount: u64, minimum_received: u64, blockhash: Hash) -> Intent {
+        let mut intent = Intent {
+            intent_id: format!("e2e-{}", Pubkey::new_unique()),
+            user_public_key: self.user.pubkey(),
+            constraints: Constraints {
+                max_slippage_bps: SLIPPAGE_BPS,
+                ..Default::default()
+            },
+            ..Intent::test_swap()
+        };
+        intent.consent_block.recent_blockhash = blockhash;
+        let swap = intent.test_swap_details();
+        swap.amount = amount;
+        swap.minimum_received = Some(minimum_received);
+        swap.route_hints = Some(vec![self.pool]);
+        intent
+    }
+
+    fn swap_transaction(&self, intent: &Intent) -> Transaction {
+        let details = intent.swap_details.as_ref().unwrap();
+        let ix = amm::swap(
+            &self.amm_id,
+            &self.pool,
+            &self.user.pubkey(),
+            details.amount,
+            details.minimum_received.unwrap_or(0),
+            true,
+        );
+        Transaction::new_signed_with_payer(&[ix], Some(&self.user.pubkey()), &[&self.user], intent.consent_block.recent_blockhash)
+    }
+
+    /// Protected bundle through the mock engine; returns the tip signature
+    async fn submit_bundle(&self, intent: &Intent, user_tx: Transaction) -> solana_sdk::signature::Signature {
+        let tipper = Keypair::new();
+        self.rpc.airdrop(&tipper.pubkey(), LAMPORTS_PER_SOL).await.unwrap();
+
+        let bundle = BundleBuilder::new(intent.consent_block.recent_blockhash, tipper)
+            .with_network(Network::Localnet)
+            .build_protected_bundle(user_tx, &FeeAllocation::new(0, TIP_LAMPORTS))
+            .unwrap();
+        let submitter = BundleSubmitter::new(JitoClient::new(self.mock.url().to_string()).unwrap());
+        let bundle_id = submitter
+            .submit_intent(intent, &bundle, &SubmitOptions::default())
+            .await
+            .unwrap();
+
+        assert!(self.mock.engine().bundle_ids().contains(&bundle_id));
+        bundle.transactions[1].signatures[0]
+    }
+}
+
+#[tokio::test]
+async fn test_swap_intent_lands_through_pipeline() {
+    let net = Localnet::start(18_899).await;
+    let amount = POOL_RESERVE / 100;
+    let expected_out = amm::quote(POOL_RESERVE, POOL_RESERVE, amount);
+    let blockhash = net.rpc.latest_blockhash().await.unwrap();
+    let intent = net.swap_intent(amount, expected_out * (10_000 - SLIPPAGE_BPS as u64) / 10_000, blockhash);
+    intent.validate_at(&SystemClock).unwrap();
+
+    // Features and score
+    let features = FeatureExtractor::new()
+        .with_network(Network::Localnet)
+        .extract_from_intent(&intent, &net.user.pubkey());
+    let score = InferenceEngine::fallback().unwrap().predict(&features).unwrap();
+    assert!((0.0..=1.0).contains(&score.score()));
+
+    // Route
+    let inputs = RouteCostInputs {
+        notional_lamports: amount,
+        sandwich_loss_lamports: estimate_sandwich_loss(amount, SLIPPAGE_BPS, features.price_impact_bps, score.score()),
+        tip_lamports: TIP_LAMPORTS,
+        ..Default::default()
+    };
+    let route = RouteEconomics::default()
+        .cheapest(&inputs, &[RouteType::JitoBundle, RouteType::StandardRpc])
+        .unwrap()
+        .route;
+
+    // Build, submit, confirm
+    let user_tx = net.swap_transaction(&intent);
+    let signature = user_tx.signatures[0];
+    if route == RouteType::JitoBundle {
+        let tip_signature = net.submit_bundle(&intent, user_tx).await;
+        net.rpc.confirm(&tip_signature, CONFIRM_TIMEOUT).await.unwrap();
+    } else {
+        net.rpc.send_transaction(&user_tx).await.unwrap();
+    }
+    net.rpc.confirm(&signature, CONFIRM_TIMEOUT).await.unwrap();
+
+    assert_eq!(net.reserves().await, (POOL_RESERVE + amount, POOL_RESERVE - expected_out));
+}
+
+#[tokio::test]
+async fn test_slippage_guard_fails_on_chain() {
+    let net = Localnet::start(19_099).await;
+    let amount = POOL_RESERVE / 100;
+    let blockhash = net.rpc.latest_blockhash().await.unwrap();
+    // Minimum above what the pool can pay
+    let intent = net.swap_intent(amount, amount, blockhash);
+
+    let user_tx = net.swap_transaction(&intent);
+    let signature = user_tx.signatures[0];
+    let tip_signature = net.submit_bundle(&intent, user_tx).await;
+    net.rpc.confirm(&tip_signature, CONFIRM_TIMEOUT).await.unwrap();
+
+    // The mock forwards without bundle atomicity: the tip lands, the swap reverts
+    let error = net.rpc.confirm(&signature, CONFIRM_TIMEOUT).await.unwrap_err();
+    assert!(error.to_string().contains(&format!("Custom\":{}", amm::ERR_SLIPPAGE)), "{}", error);
+    assert_eq!(net.reserves().await, (POOL_RESERVE, POOL_RESERVE));
+}
@@ -0,0 +1,12 @@
+// Generates the tonic service from `proto/inference.proto` using a vendored
+// protoc, so building does not require a system protobuf install.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+
+    println!("cargo:rerun-if-changed=proto/inference.proto");
+    tonic_build::compile_protos("proto/inference.proto")?;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Chaos admin endpoint (HTTP, `chaos` feature)
+//!
+//! Arms and disarms [`ChaosInjector`] faults at runtime. Every request needs
+//! `Authorization: Bearer <admin token>`:
+//! - `GET /admin/chaos`: armed faults with their injection counts
+//! - `PUT /admin/chaos/{fault}` with a [`FaultSpec`] body: arm (or re-arm) a
+//!   fault (`oracle_timeout`, `bundle_rejection`, `rpc_rate_limit`, `slow_inference`)
+//! - `DELETE /admin/chaos/{fault}`: disarm one fault (404 when not armed)
+//! - `DELETE /admin/chaos`: disarm everything
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use sentinel_core::{ArmedFault, ChaosInjector, Fault, FaultSpec};
+use std::sync::Arc;
+
+struct ChaosState {
+    chaos: Arc<ChaosInjector>,
+    admin_token: String,
+}
+
+pub fn router(chaos: Arc<ChaosInjector>, admin_token: impl Into<String>) -> Router {
+    let state = Arc::new(ChaosState {
+        chaos,
+        admin_token: admin_token.into(),
+    });
+    Router::new()
+        .route("/admin/chaos", get(armed).delete(disarm_all))
+        .route("/admin/chaos/:fault", delete(disarm).put(arm))
+        .route_layer(middleware::from_fn_with_state(Arc::clone(&state), authorize))
+        .with_state(state)
+}
+
+async fn authorize(State(state): State<Arc<ChaosState>>, request: Request, next: Next) -> Result<Response, StatusCode> {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match presented {
+        Some(token) if !state.admin_token.is_empty() && token_matches(token, &state.admin_token) => {
+            Ok(next.run(request).await)
+        }
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+/// Compare without an early exit on the first differing byte
+fn token_matches(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn armed(State(state): State<Arc<ChaosState>>) -> Json<Vec<ArmedFault>> {
+    Json(state.chaos.armed())
+}
+
+async fn arm(
+    State(state): State<Arc<ChaosState>>,
+    Path(fault): Path<Fault>,
+    Json(spec): Json<FaultSpec>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state
+        .chaos
+        .arm(fault, spec)
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+async fn disarm(State(state): State<Arc<ChaosState>>, Path(fault): Path<Fault>) -> StatusCode {
+    if state.chaos.disarm(fault) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn disarm_all(State(state): State<Arc<ChaosState>>) -> StatusCode {
+    state.chaos.disarm_all();
+    StatusCode::NO_CONTENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "rehearsal";
+
+    async fn send(router: Router, method: &str, path: &str, token: Option<&str>, body: &str) -> (StatusCode, Vec<u8>) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = router
+            .oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_arm_list_and_disarm() {
+        let chaos = Arc::new(ChaosInjector::new());
+        let router = router(Arc::clone(&chaos), TOKEN);
+
+        let spec = r#"{"probability": 1.0, "max_injections": 3}"#;
+        let (status, _) = send(router.clone(), "PUT", "/admin/chaos/rpc_rate_limit", Some(TOKEN), spec).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(chaos.inject(Fault::RpcRateLimit).await.is_err());
+
+        let (status, body) = send(router.clone(), "GET", "/admin/chaos", Some(TOKEN), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let armed: Vec<ArmedFault> = serde_json::from_slice(&body).unwrap();
+        assert_eq!((armed[0].fault, armed[0].injected), (Fault::RpcRateLimit, 1));
+
+        let (status, _) = send(router.clone(), "DELETE", "/admin/chaos/rpc_rate_limit", Some(TOKEN), "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(router.clone(), "DELETE", "/admin/chaos/rpc_rate_limit", Some(TOKEN), "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let bad = r#"{"probability": 2.0}"#;
+        let (status, _) = send(router.clone(), "PUT", "/admin/chaos/slow_inference", Some(TOKEN), bad).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(router, "PUT", "/admin/chaos/disk_full", Some(TOKEN), "{}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_requires_admin_token() {
+        let chaos = Arc::new(ChaosInjector::new());
+        let router = router(Arc::clone(&chaos), TOKEN);
+
+        for token in [None, Some("wrong"), Some("rehearsal!")] {
+            let (status, _) = send(router.clone(), "PUT", "/admin/chaos/oracle_timeout", token, "{}").await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+        assert!(chaos.armed().is_empty());
+    }
+}
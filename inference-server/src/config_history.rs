@@ -0,0 +1,111 @@
+//! Config snapshot endpoint (HTTP, next to the probes)
+//!
+//! `GET /config/{version}` returns the [`ConfigSnapshot`] recorded under a
+//! config version, the hash stamped on decision records and shadow rows, so an
+//! audit can reconstruct the policy in force when a decision was made. The
+//! listener is unauthenticated, so the config is served
+//! [redacted](sentinel_core::SentinelConfig::redacted): no endpoint, database
+//! or webhook URLs and no tenant key hashes.
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use sentinel_core::{ConfigHistory, ConfigSnapshot};
+use std::sync::Arc;
+
+pub fn router(history: Arc<ConfigHistory>) -> Router {
+    Router::new().route("/config/:version", get(snapshot)).with_state(history)
+}
+
+async fn snapshot(
+    State(history): State<Arc<ConfigHistory>>,
+    Path(version): Path<String>,
+) -> Result<Json<ConfigSnapshot>, (StatusCode, String)> {
+    // `SentinelConfig::fingerprint` is 16 lowercase hex characters
+    let well_formed = version.len() == 16 && version.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    if !well_formed {
+        return Err((StatusCode::BAD_REQUEST, format!("Malformed config version '{}'", version)));
+    }
+
+    // The first lookup reads the snapshot stream (blocking IO)
+    let lookup = version.clone();
+    let snapshot = tokio::task::spawn_blocking(move || history.get(&lookup))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+    match snapshot {
+        Some(snapshot) => Ok(Json(ConfigSnapshot {
+            version: snapshot.version.clone(),
+            recorded_at_ms: snapshot.recorded_at_ms,
+            config: snapshot.config.redacted(),
+        })),
+        None => Err((StatusCode::NOT_FOUND, format!("No config recorded under version {}", version))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use sentinel_core::storage::Storage;
+    use sentinel_core::SentinelConfig;
+    use tower::ServiceExt;
+
+    async fn get(router: Router, path: &str) -> (StatusCode, Vec<u8>) {
+        let response = router
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_snapshot_by_version() {
+        let history = Arc::new(ConfigHistory::from_storage(&Storage::memory()).unwrap());
+        let mut config = SentinelConfig::default();
+        config.thresholds.high_tip = 250_000;
+        let version = history.record(&config).unwrap();
+        let router = router(history);
+
+        let (status, body) = get(router.clone(), &format!("/config/{}", version)).await;
+        assert_eq!(status, StatusCode::OK);
+        let snapshot: ConfigSnapshot = serde_json::from_slice(&body).unwrap();
+        assert_eq!(snapshot.version, version);
+        assert_eq!(snapshot.config, config.redacted());
+        assert_eq!(snapshot.config.thresholds.high_tip, 250_000);
+
+        let (status, _) = get(router.clone(), "/config/0123456789abcdef").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get(router, "/config/..%2Fsecrets").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_leaves_out_secrets() {
+        let history = Arc::new(ConfigHistory::from_storage(&Storage::memory()).unwrap());
+        let mut config = SentinelConfig::default();
+        config.endpoints.rpc_url = "https://mainnet.helius-rpc.com/?api-key=rpc-secret".to_string();
+        config.endpoints.ws_url = Some("wss://mainnet.helius-rpc.com/?api-key=rpc-secret".to_string());
+        config.storage.postgres_url = Some("postgres://sentinel:db-secret@db/sentinel".to_string());
+        config.coordination.postgres_url = Some("postgres://sentinel:db-secret@db/coordination".to_string());
+        config.screening.webhook_url = Some("https://screen.example/hook?token=hook-secret".to_string());
+        config.tenants.push(sentinel_core::TenantConfig {
+            id: "acme".to_string(),
+            api_key_sha256: vec!["key-hash-secret".to_string()],
+            ..Default::default()
+        });
+        let version = history.record(&config).unwrap();
+
+        let (status, body) = get(router(history), &format!("/config/{}", version)).await;
+        assert_eq!(status, StatusCode::OK);
+        let body = String::from_utf8(body).unwrap();
+        for secret in ["rpc-secret", "db-secret", "hook-secret", "key-hash-secret"] {
+            assert!(!body.contains(secret), "{} leaked: {}", secret, body);
+        }
+        assert!(body.contains(sentinel_core::config::REDACTED));
+    }
+}
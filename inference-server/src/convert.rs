@@ -0,0 +1,174 @@
+//! Protobuf ↔ engine type conversion
+
+use ai_engine::drift_detection::DriftStats;
+use ai_engine::{AdjustedThresholds, SwapDetailsData, TransactionData};
+use sentinel_core::{Deadline, MevRiskScore};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::time::Duration;
+use tonic::metadata::MetadataMap;
+use tonic::Status;
+
+use crate::proto;
+
+fn pubkey(field: &str, value: &str) -> Result<Pubkey, Status> {
+    if value.is_empty() {
+        return Ok(Pubkey::default());
+    }
+    Pubkey::from_str(value)
+        .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", field, e)))
+}
+
+impl TryFrom<&proto::TransactionData> for TransactionData {
+    type Error = Status;
+
+    fn try_from(tx: &proto::TransactionData) -> Result<Self, Self::Error> {
+        let swap_details = tx
+            .swap_details
+            .as_ref()
+            .map(|swap| -> Result<SwapDetailsData, Status> {
+                Ok(SwapDetailsData {
+                    input_mint: pubkey("swap_details.input_mint", &swap.input_mint)?,
+                    output_mint: pubkey("swap_details.output_mint", &swap.output_mint)?,
+                    input_amount: swap.input_amount,
+                    output_amount: swap.output_amount,
+                    expected_output: swap.expected_output,
+                    route_length: swap.route_length,
+                    slippage_tolerance_bps: swap.slippage_tolerance_bps,
+                    pool_liquidity_usd: swap.pool_liquidity_usd,
+                    pool: None,
+                })
+            })
+            .transpose()?;
+
+        Ok(TransactionData {
+            slot: tx.slot,
+            fee_payer: pubkey("fee_payer", &tx.fee_payer)?,
+            compute_unit_limit: tx.compute_unit_limit,
+            compute_unit_price: tx.compute_unit_price,
+            jito_tip_lamports: tx.jito_tip_lamports,
+            total_fee_lamports: tx.total_fee_lamports,
+            account_count: tx.account_count,
+            instruction_count: tx.instruction_count,
+            tx_size_bytes: tx.tx_size_bytes,
+            swap_details,
+            time_since_last_slot_ms: tx.time_since_last_slot_ms,
+            next_leader_pubkey: pubkey("next_leader_pubkey", &tx.next_leader_pubkey)?,
+            uses_lookup_tables: tx.uses_lookup_tables,
+            timestamp_ms: tx.timestamp_ms,
+        })
+    }
+}
+
+/// Call deadline from the `grpc-timeout` header
+pub fn grpc_deadline(metadata: &MetadataMap) -> Option<Deadline> {
+    metadata
+        .get("grpc-timeout")
+        .and_then(|value| value.to_str().ok())
+        .and_then(Deadline::from_grpc_timeout)
+}
+
+/// Item budget (`timeout_ms`) tightened by the enclosing call deadline
+pub fn deadline(tx: &proto::TransactionData) -> Option<Deadline> {
+    let item = tx.timeout_ms.map(|ms| Deadline::after(Duration::from_millis(ms.into())));
+    match (item, Deadline::current()) {
+        (Some(item), Some(call)) => Some(item.min(call)),
+        (item, call) => item.or(call),
+    }
+}
+
+pub fn risk_score(request_id: String, score: MevRiskScore, latency_us: u64) -> proto::RiskScore {
+    proto::RiskScore {
+        request_id,
+        score: score.score(),
+        is_high_risk: score.is_high_risk(),
+        is_medium_risk: score.is_medium_risk(),
+        latency_us,
+        error: String::new(),
+        confidence: score.confidence(),
+        degraded: false,
+    }
+}
+
+pub fn error_score(request_id: String, status: &Status) -> proto::RiskScore {
+    proto::RiskScore {
+        request_id,
+        error: status.message().to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn drift_stats(stats: DriftStats, engines: usize, adjusted: &AdjustedThresholds) -> proto::DriftStats {
+    let calendar = adjusted.calendar.as_ref();
+    proto::DriftStats {
+        history_size: stats.history_size as u64,
+        max_history: stats.max_history as u64,
+        psi_threshold: stats.psi_threshold,
+        ks_threshold: stats.ks_threshold,
+        js_threshold: stats.js_threshold,
+        engines: engines as u32,
+        time_adjustment: adjusted.time_adjustment,
+        market_session: calendar.and_then(|c| c.session.clone()).unwrap_or_default(),
+        market_closed: calendar.is_some_and(|c| c.market_closed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_conversion() {
+        let fee_payer = Pubkey::new_unique();
+        let tx = proto::TransactionData {
+            slot: 42,
+            fee_payer: fee_payer.to_string(),
+            jito_tip_lamports: 150_000,
+            swap_details: Some(proto::SwapDetails {
+                input_mint: Pubkey::new_unique().to_string(),
+                output_mint: Pubkey::new_unique().to_string(),
+                route_length: 2,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let data = TransactionData::try_from(&tx).unwrap();
+        assert_eq!(data.slot, 42);
+        assert_eq!(data.fee_payer, fee_payer);
+        assert_eq!(data.next_leader_pubkey, Pubkey::default());
+        assert_eq!(data.swap_details.unwrap().route_length, 2);
+    }
+
+    #[tokio::test]
+    async fn test_item_timeout_tightened_by_call_deadline() {
+        let mut metadata = MetadataMap::new();
+        metadata.insert("grpc-timeout", "20m".parse().unwrap());
+        let call = grpc_deadline(&metadata).unwrap();
+
+        let roomy = proto::TransactionData {
+            timeout_ms: Some(5_000),
+            ..Default::default()
+        };
+        let tight = proto::TransactionData {
+            timeout_ms: Some(1),
+            ..Default::default()
+        };
+        assert!(deadline(&roomy).unwrap().remaining() > Duration::from_secs(1));
+        let (roomy, tight) = call.scope(async { (deadline(&roomy), deadline(&tight)) }).await;
+        assert_eq!(roomy, Some(call));
+        assert!(tight.unwrap() < call);
+        assert_eq!(deadline(&proto::TransactionData::default()), None);
+    }
+
+    #[test]
+    fn test_invalid_pubkey_rejected() {
+        let tx = proto::TransactionData {
+            fee_payer: "not-a-key".to_string(),
+            ..Default::default()
+        };
+        let status = TransactionData::try_from(&tx).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("fee_payer"));
+    }
+}
@@ -0,0 +1,103 @@
+//! Kubernetes probe endpoints (HTTP, next to the gRPC port)
+//!
+//! - `GET /health/live`: 200 while the process serves requests
+//! - `GET /health/ready`: 200 once every readiness check passes, 503 otherwise;
+//!   both return the [`HealthReport`] with per-dependency status
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use sentinel_core::{HealthChecker, HealthReport};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Default probe listen address (matches the deployment manifests)
+pub const DEFAULT_HEALTH_ADDR: &str = "0.0.0.0:8080";
+
+pub fn router(checker: Arc<HealthChecker>) -> Router {
+    Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .with_state(checker)
+}
+
+/// Serve the probe endpoints (merged with any other HTTP routes) on `addr`
+/// until `shutdown` resolves
+pub async fn serve(
+    addr: SocketAddr,
+    router: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router).with_graceful_shutdown(shutdown).await
+}
+
+async fn live(State(checker): State<Arc<HealthChecker>>) -> Json<HealthReport> {
+    Json(checker.liveness())
+}
+
+async fn ready(State(checker): State<Arc<HealthChecker>>) -> (StatusCode, Json<HealthReport>) {
+    // Store checks may block (Postgres)
+    let report = match tokio::task::spawn_blocking(move || checker.readiness()).await {
+        Ok(report) => report,
+        Err(e) => HealthReport {
+            ready: false,
+            uptime_secs: 0,
+            checks: vec![sentinel_core::DependencyStatus {
+                name: "health".to_string(),
+                healthy: false,
+                detail: format!("readiness check panicked: {}", e),
+            }],
+        },
+    };
+    let status = if report.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_engine::{InferencePool, ModelConfig};
+    use axum::body::Body;
+    use axum::http::Request;
+    use sentinel_core::{FreshnessCheck, Heartbeat};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    async fn get(router: Router, path: &str) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_ready_lists_failing_dependency() {
+        let pool = Arc::new(InferencePool::new(ModelConfig::default(), 1).unwrap());
+        let oracle = Heartbeat::new();
+        let checker = Arc::new(
+            HealthChecker::new()
+                .with_check(pool)
+                .with_check(Arc::new(FreshnessCheck::new("oracle", oracle.clone(), Duration::from_secs(60)))),
+        );
+
+        let (status, body) = get(router(Arc::clone(&checker)), "/health/live").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ready"], true);
+
+        let (status, body) = get(router(Arc::clone(&checker)), "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"][0]["name"], "model");
+        assert_eq!(body["checks"][0]["healthy"], true);
+        assert_eq!(body["checks"][1]["healthy"], false);
+
+        oracle.beat(sentinel_core::system_clock().unix_millis());
+        let (status, _) = get(router(checker), "/health/ready").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,135 @@
+//! MEV heatmap endpoint (HTTP, next to the probes)
+//!
+//! `GET /mev/heatmap?window_secs=86400&limit=20` returns the [`MevHeatmap`]
+//! of the most-attacked pairs and pools over the trailing window, read from
+//! the decision log on each call.
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use sentinel_core::{system_clock, IntentAnalytics, MevHeatmap, SharedClock};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const DEFAULT_WINDOW_SECS: u64 = 86_400;
+const MAX_WINDOW_SECS: u64 = 90 * 86_400;
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+struct HeatmapQuery {
+    window_secs: Option<u64>,
+    limit: Option<usize>,
+}
+
+struct HeatmapState {
+    decision_log: PathBuf,
+    clock: SharedClock,
+}
+
+pub fn router(decision_log: impl Into<PathBuf>) -> Router {
+    router_with_clock(decision_log, system_clock())
+}
+
+pub fn router_with_clock(decision_log: impl Into<PathBuf>, clock: SharedClock) -> Router {
+    let state = HeatmapState {
+        decision_log: decision_log.into(),
+        clock,
+    };
+    Router::new().route("/mev/heatmap", get(heatmap)).with_state(Arc::new(state))
+}
+
+async fn heatmap(
+    State(state): State<Arc<HeatmapState>>,
+    Query(query): Query<HeatmapQuery>,
+) -> Result<Json<MevHeatmap>, (StatusCode, String)> {
+    let window_secs = query.window_secs.unwrap_or(DEFAULT_WINDOW_SECS);
+    if window_secs == 0 || window_secs > MAX_WINDOW_SECS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("window_secs must be within 1-{}", MAX_WINDOW_SECS),
+        ));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    let to_ms = state.clock.unix_millis().max(0) as u64;
+    let from_ms = to_ms.saturating_sub(window_secs * 1_000);
+    let path = state.decision_log.clone();
+    // Reading the log is blocking file IO
+    let analytics = tokio::task::spawn_blocking(move || IntentAnalytics::new().load_decision_log(path))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    // Requests up to now inclusive
+    Ok(Json(analytics.heatmap(from_ms, to_ms + 1, limit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use sentinel_core::{DecisionRecord, ManualClock, MevIncident, RouteType};
+    use std::collections::BTreeMap;
+    use tower::ServiceExt;
+
+    const NOW_SECS: i64 = 1_700_000_000;
+
+    fn sandwich(id: &str, pool: &str, age_secs: i64) -> String {
+        let mut record = DecisionRecord::new(id.to_string(), 0.9, RouteType::JitoBundle).with_incident(MevIncident {
+            signature: id.to_string(),
+            slot: 1,
+            leader: None,
+            attacker: "attacker".to_string(),
+            pool: pool.to_string(),
+            front_run: "front".to_string(),
+            back_run: "back".to_string(),
+            extracted: BTreeMap::from([("SOL".to_string(), 100)]),
+        });
+        record.timestamp_ms = ((NOW_SECS - age_secs) * 1_000) as u64;
+        serde_json::to_string(&record).unwrap()
+    }
+
+    async fn get(router: Router, path: &str) -> (StatusCode, Vec<u8>) {
+        let response = router
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_heatmap_over_requested_window() {
+        let path = std::env::temp_dir().join(format!("heatmap-{}.jsonl", uuid::Uuid::new_v4()));
+        let log = [sandwich("a", "pool-1", 60), sandwich("b", "pool-1", 600), sandwich("c", "pool-2", 7_200)];
+        std::fs::write(&path, log.join("\n")).unwrap();
+        let router = router_with_clock(&path, Arc::new(ManualClock::at_timestamp(NOW_SECS)));
+
+        let (status, body) = get(router.clone(), "/mev/heatmap?window_secs=3600").await;
+        assert_eq!(status, StatusCode::OK);
+        let heatmap: MevHeatmap = serde_json::from_slice(&body).unwrap();
+        assert_eq!(heatmap.attacks, 2);
+        assert_eq!(heatmap.pools.len(), 1);
+        assert_eq!(heatmap.pools[0].avg_extracted["SOL"], 100);
+
+        let (_, body) = get(router.clone(), "/mev/heatmap").await;
+        let heatmap: MevHeatmap = serde_json::from_slice(&body).unwrap();
+        assert_eq!(heatmap.pools.len(), 2);
+
+        let (status, _) = get(router, "/mev/heatmap?window_secs=0").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_log_unavailable() {
+        let router = router("/nonexistent/decisions.jsonl");
+        let (status, _) = get(router, "/mev/heatmap").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,33 @@
+//! Standalone gRPC inference service
+//!
+//! Exposes the AI engine without the router: `Score`, `ScoreStream` and
+//! `GetDriftStats` (see `proto/inference.proto`). Scoring runs feature
+//! extraction and the production MEV pipeline on an [`InferencePool`], so drift
+//! detection and shadow mode behave exactly as inside the router.
+//!
+//! Kubernetes liveness/readiness probes are served over HTTP by [`health`],
+//! next to the per-pair MEV [`heatmap`] report, the [`config_history`]
+//! snapshot lookup, the intent [`schema`] and, in `chaos` builds, the
+//! failure-injection admin endpoint.
+
+// `tonic::Status` is the error type of every gRPC handler
+#![allow(clippy::result_large_err)]
+
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod config_history;
+pub mod convert;
+pub mod health;
+pub mod heatmap;
+pub mod schema;
+pub mod service;
+
+/// Generated protobuf types and service stubs
+pub mod proto {
+    tonic::include_proto!("sentinel.inference.v1");
+}
+
+pub use service::InferenceServer;
+
+/// Default listen address
+pub const DEFAULT_ADDR: &str = "0.0.0.0:50051";
@@ -0,0 +1,101 @@
+//! Standalone inference server
+//!
+//! Environment:
+//! - `SENTINEL_GRPC_ADDR`   listen address (default `0.0.0.0:50051`)
+//! - `SENTINEL_GRPC_POOL`   engines in the inference pool (default: CPU count)
+//! - `SENTINEL_CONFIG`      optional TOML config (plus `SENTINEL_*` overrides)
+//! - `SENTINEL_HEALTH_ADDR` HTTP probe, heatmap and config snapshot address (default `0.0.0.0:8080`)
+//! - `SENTINEL_DECISION_LOG` decision log read by `/mev/heatmap` (default `logs/decisions.jsonl`)
+//! - `SENTINEL_CHAOS_TOKEN` `chaos` builds only: serves `/admin/chaos` with this bearer token
+//!
+//! Spans are exported over OTLP when `telemetry.otlp_endpoint` is set; calls
+//! carrying a `traceparent` header join the caller's trace.
+//!
+//! The loaded config is recorded in the `[storage]` backend's config history,
+//! served by version at `/config/{version}`.
+//!
+//! When `model.snapshot_path` is set, engine runtime state is restored from it
+//! on boot and saved to it on Ctrl-C.
+
+use ai_engine::InferencePool;
+use sentinel_core::storage::Storage;
+use sentinel_core::{telemetry, ConfigHistory, DecisionLogConfig, HealthChecker, SentinelConfig};
+use sentinel_inference_server::health::{self, DEFAULT_HEALTH_ADDR};
+use sentinel_inference_server::{config_history, heatmap, schema};
+use sentinel_inference_server::{InferenceServer, DEFAULT_ADDR};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = std::env::var_os("SENTINEL_CONFIG").map(PathBuf::from);
+    let config = SentinelConfig::load(config_path.as_deref())?;
+    // Flushes pending spans on exit
+    let _telemetry = telemetry::init(&config.telemetry)?;
+    let history = Arc::new(ConfigHistory::from_storage(&Storage::from_config(&config.storage)?)?);
+    let config_version = history.record(&config)?;
+    info!("Running config version {}", config_version);
+
+    let pool_size = match std::env::var("SENTINEL_GRPC_POOL") {
+        Ok(size) => size.parse()?,
+        Err(_) => std::thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let addr = std::env::var("SENTINEL_GRPC_ADDR")
+        .unwrap_or_else(|_| DEFAULT_ADDR.to_string())
+        .parse()?;
+
+    let pool = InferencePool::from_config(&config, pool_size)?;
+    #[cfg(feature = "chaos")]
+    let chaos = Arc::new(sentinel_core::ChaosInjector::new());
+    #[cfg(feature = "chaos")]
+    let pool = pool.with_chaos(Arc::clone(&chaos));
+    let pool = Arc::new(pool);
+    if let Some(path) = &config.model.snapshot_path {
+        // A stale or incompatible snapshot only costs a cold start
+        if let Err(e) = pool.restore(path) {
+            warn!("Ignoring engine snapshot {:?}: {}", path, e);
+        }
+    }
+    info!("🛰️  Inference gRPC server listening on {} ({} engines)", addr, pool_size);
+
+    let health_addr = std::env::var("SENTINEL_HEALTH_ADDR")
+        .unwrap_or_else(|_| DEFAULT_HEALTH_ADDR.to_string())
+        .parse()?;
+    let decision_log =
+        std::env::var("SENTINEL_DECISION_LOG").unwrap_or_else(|_| DecisionLogConfig::default().log_path);
+    let checker = Arc::new(HealthChecker::new().with_check(Arc::clone(&pool) as _));
+    let http = health::router(checker)
+        .merge(heatmap::router(decision_log))
+        .merge(config_history::router(history))
+        .merge(schema::router());
+    #[cfg(feature = "chaos")]
+    let http = match std::env::var("SENTINEL_CHAOS_TOKEN") {
+        Ok(token) if !token.is_empty() => {
+            warn!("🐒 Chaos admin endpoint enabled at /admin/chaos");
+            http.merge(sentinel_inference_server::chaos::router(chaos, token))
+        }
+        _ => http,
+    };
+    tokio::spawn(async move {
+        let shutdown = async {
+            tokio::signal::ctrl_c().await.ok();
+        };
+        if let Err(e) = health::serve(health_addr, http, shutdown).await {
+            warn!("Health endpoints on {} stopped: {}", health_addr, e);
+        }
+    });
+
+    tonic::transport::Server::builder()
+        .add_service(InferenceServer::new(Arc::clone(&pool)).into_service())
+        .serve_with_shutdown(addr, async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+
+    if let Some(path) = &config.model.snapshot_path {
+        pool.snapshot(path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Intent JSON Schema endpoint (HTTP, next to the probes)
+//!
+//! `GET /schema/intent/v1` returns [`sentinel_core::intent_schema`], the
+//! schema the SDK's `intent_schema` fetches so integrators can validate
+//! payloads before signing.
+
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use sentinel_core::INTENT_SCHEMA_PATH;
+use std::sync::Arc;
+
+pub fn router() -> Router {
+    // Generated once; the schema is fixed for the lifetime of the binary
+    let schema: Arc<str> = sentinel_core::intent_schema_json().into();
+    Router::new().route(INTENT_SCHEMA_PATH, get(intent_schema)).with_state(schema)
+}
+
+async fn intent_schema(State(schema): State<Arc<str>>) -> impl IntoResponse {
+    ([(CONTENT_TYPE, "application/json")], schema.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_serves_intent_schema() {
+        let response = router()
+            .oneshot(Request::get(INTENT_SCHEMA_PATH).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(schema, serde_json::to_value(sentinel_core::intent_schema()).unwrap());
+        assert_eq!(schema["$id"], INTENT_SCHEMA_PATH);
+    }
+}
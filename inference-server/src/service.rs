@@ -0,0 +1,346 @@
+//! `InferenceService` implementation
+
+use ai_engine::{
+    DedupSettings, DedupStats, FeatureExtractor, FeatureVector, InferencePool, IngestSettings, ShardedExtractor,
+    ShardingSettings, TransactionData,
+};
+use sentinel_core::{set_remote_parent, Deadline, DeadlineCounters, DeadlineStats, PipelineStage, TRACEPARENT_HEADER};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{debug, warn, Instrument};
+
+use crate::convert;
+use crate::proto;
+use crate::proto::inference_service_server::{InferenceService, InferenceServiceServer};
+
+/// In-flight responses buffered per `ScoreStream` call
+const STREAM_BUFFER: usize = 128;
+
+/// gRPC front end over an inference pool
+///
+/// Extraction is sharded by token pair, so a request awaiting an oracle only
+/// holds up requests for the same shard.
+#[derive(Clone)]
+pub struct InferenceServer {
+    pool: Arc<InferencePool>,
+    extractor: Arc<ShardedExtractor>,
+    deadline: Arc<DeadlineCounters>,
+}
+
+impl InferenceServer {
+    pub fn new(pool: Arc<InferencePool>) -> Self {
+        let extractor = ShardedExtractor::new(IngestSettings::default(), ShardingSettings::default(), |_| {
+            FeatureExtractor::new()
+        })
+        .with_dedup(DedupSettings::default());
+        Self {
+            pool,
+            extractor: Arc::new(extractor),
+            deadline: Arc::new(DeadlineCounters::new()),
+        }
+    }
+
+    /// Use pre-configured extractor shards (Pyth client, market stats, dedup window)
+    pub fn with_extractor(mut self, extractor: ShardedExtractor) -> Self {
+        self.extractor = Arc::new(extractor);
+        self
+    }
+
+    /// Duplicate-delivery metrics of the extractor's dedup window
+    pub fn dedup_stats(&self) -> Option<DedupStats> {
+        self.extractor.stats().dedup
+    }
+
+    /// Requests with a deadline and the stages they degraded
+    pub fn deadline_stats(&self) -> DeadlineStats {
+        let mut stats = self.deadline.stats();
+        stats.merge(&self.pool.deadline_stats());
+        stats
+    }
+
+    /// Wrap in the generated tonic service
+    pub fn into_service(self) -> InferenceServiceServer<Self> {
+        InferenceServiceServer::new(self)
+    }
+
+    /// Extract features and score one transaction
+    ///
+    /// With a deadline (`timeout_ms` or the call's `grpc-timeout`) too close
+    /// to cover a stage, extraction uses the request alone and scoring the
+    /// heuristics alone; the response is then marked `degraded`.
+    pub async fn score(&self, tx: proto::TransactionData) -> Result<proto::RiskScore, Status> {
+        let start = Instant::now();
+        let request_id = if tx.request_id.is_empty() {
+            uuid::Uuid::new_v4().to_string()
+        } else {
+            tx.request_id.clone()
+        };
+
+        let data = TransactionData::try_from(&tx)?;
+        let deadline = convert::deadline(&tx);
+        if deadline.is_some() {
+            self.deadline.record_request();
+        }
+        let mut degraded = false;
+
+        let features = match self.extractor.extract(&tx.signature, &data, deadline.as_ref()).await {
+            Some(features) => features,
+            None => {
+                self.deadline.record_fallback(PipelineStage::Extract);
+                degraded = true;
+                FeatureVector::from_transaction(&data)
+            }
+        };
+
+        let score = match deadline {
+            // Counted by the engine
+            Some(ref deadline) if !deadline.allows(PipelineStage::Predict) => {
+                degraded = true;
+                self.pool.predict_heuristic(&features).map(|fused| fused.score)
+            }
+            _ => {
+                self.pool
+                    .predict_with_shadow(&features, request_id.clone(), tx.signature)
+                    .await
+            }
+        }
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        let latency_us = start.elapsed().as_micros() as u64;
+        debug!("Scored {} = {:.3} in {}µs (degraded: {})", request_id, score.score(), latency_us, degraded);
+        Ok(proto::RiskScore {
+            degraded,
+            ..convert::risk_score(request_id, score, latency_us)
+        })
+    }
+}
+
+/// Run `future` inside the call's deadline, if it has one
+async fn within<F: Future>(deadline: Option<Deadline>, future: F) -> F::Output {
+    match deadline {
+        Some(deadline) => deadline.scope(future).await,
+        None => future.await,
+    }
+}
+
+/// Span for one call, joined to the caller's trace when it sent a `traceparent`
+fn request_span(metadata: &MetadataMap) -> tracing::Span {
+    let span = tracing::info_span!("score.request");
+    if let Some(traceparent) = metadata.get(TRACEPARENT_HEADER).and_then(|value| value.to_str().ok()) {
+        set_remote_parent(&span, traceparent);
+    }
+    span
+}
+
+type ScoreStream = Pin<Box<dyn Stream<Item = Result<proto::RiskScore, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl InferenceService for InferenceServer {
+    async fn score(
+        &self,
+        request: Request<proto::TransactionData>,
+    ) -> Result<Response<proto::RiskScore>, Status> {
+        let deadline = convert::grpc_deadline(request.metadata());
+        let span = request_span(request.metadata());
+        within(deadline, InferenceServer::score(self, request.into_inner()))
+            .instrument(span)
+            .await
+            .map(Response::new)
+    }
+
+    type ScoreStreamStream = ScoreStream;
+
+    /// Per-item failures are returned inline (`error` set) so one bad
+    /// transaction does not terminate the stream
+    async fn score_stream(
+        &self,
+        request: Request<Streaming<proto::TransactionData>>,
+    ) -> Result<Response<Self::ScoreStreamStream>, Status> {
+        let deadline = convert::grpc_deadline(request.metadata());
+        let span = request_span(request.metadata());
+        let mut inbound = request.into_inner();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let server = self.clone();
+
+        tokio::spawn(within(deadline, async move {
+            while let Some(item) = inbound.next().await {
+                let response = match item {
+                    Ok(transaction) => {
+                        let request_id = transaction.request_id.clone();
+                        match server.score(transaction).await {
+                            Ok(score) => score,
+                            Err(status) => convert::error_score(request_id, &status),
+                        }
+                    }
+                    Err(status) => {
+                        warn!("Score stream receive error: {}", status);
+                        let _ = tx.send(Err(status)).await;
+                        return;
+                    }
+                };
+
+                if tx.send(Ok(response)).await.is_err() {
+                    debug!("Score stream client disconnected");
+                    return;
+                }
+            }
+        }).instrument(span));
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn get_drift_stats(
+        &self,
+        _request: Request<proto::DriftStatsRequest>,
+    ) -> Result<Response<proto::DriftStats>, Status> {
+        Ok(Response::new(convert::drift_stats(
+            self.pool.drift_stats(),
+            self.pool.len(),
+            &self.pool.engine().adjusted_thresholds(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_engine::ModelConfig;
+    use solana_sdk::pubkey::Pubkey;
+    use std::time::Duration;
+
+    fn server() -> InferenceServer {
+        let pool = InferencePool::new(ModelConfig::default(), 2).unwrap();
+        InferenceServer::new(Arc::new(pool))
+    }
+
+    #[tokio::test]
+    async fn test_score_and_drift_stats() {
+        let server = server();
+
+        let score = server
+            .score(proto::TransactionData {
+                request_id: "req-1".to_string(),
+                jito_tip_lamports: 500_000,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(score.request_id, "req-1");
+        assert!((0.0..=1.0).contains(&score.score));
+        assert!(score.confidence.is_some_and(|c| (0.0..=1.0).contains(&c)));
+        assert!(score.error.is_empty());
+
+        let stats = InferenceService::get_drift_stats(&server, Request::new(proto::DriftStatsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(stats.engines, 2);
+        assert_eq!(stats.history_size, 1);
+        assert!(stats.time_adjustment >= 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_redelivered_signature_counted_as_duplicate() {
+        let server = server();
+        for _ in 0..2 {
+            server
+                .score(proto::TransactionData {
+                    signature: "sig-replayed".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        let stats = server.dedup_stats().unwrap();
+        assert_eq!((stats.seen, stats.duplicates), (2, 1));
+        assert_eq!(stats.duplicate_rate(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_busy_shard_does_not_hold_up_other_shards() {
+        let sharding = ShardingSettings {
+            shards: 4,
+            ..ShardingSettings::default()
+        };
+        let extractor = ShardedExtractor::new(IngestSettings::default(), sharding, |_| FeatureExtractor::new());
+        let server = server().with_extractor(extractor);
+        let tx = |payer: Pubkey| proto::TransactionData {
+            fee_payer: payer.to_string(),
+            ..Default::default()
+        };
+        let shard_of = |tx: &proto::TransactionData| {
+            let data = TransactionData::try_from(tx).unwrap();
+            server.extractor.shard_for(&data)
+        };
+
+        let busy = tx(Pubkey::new_unique());
+        let other = std::iter::repeat_with(|| tx(Pubkey::new_unique()))
+            .find(|tx| shard_of(tx) != shard_of(&busy))
+            .unwrap();
+
+        // Stands in for a request awaiting an oracle on `busy`'s shard
+        let shard = server.extractor.shard(shard_of(&busy)).unwrap();
+        let _held = shard.lock().await;
+        let score = tokio::time::timeout(Duration::from_secs(5), server.score(other)).await.unwrap().unwrap();
+        assert!(!score.degraded);
+        assert!(tokio::time::timeout(Duration::from_millis(50), server.score(busy)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_spent_deadline_returns_degraded_heuristic_score() {
+        let server = server();
+        let tx = proto::TransactionData {
+            jito_tip_lamports: 500_000,
+            ..Default::default()
+        };
+
+        let full = server.score(tx.clone()).await.unwrap();
+        assert!(!full.degraded);
+
+        let late = server
+            .score(proto::TransactionData {
+                timeout_ms: Some(0),
+                ..tx.clone()
+            })
+            .await
+            .unwrap();
+        assert!(late.degraded && late.confidence.is_none());
+
+        // A 1ms grpc-timeout covers extraction but not inference
+        let mut request = Request::new(tx);
+        request.metadata_mut().insert("grpc-timeout", "1m".parse().unwrap());
+        let tight = InferenceService::score(&server, request).await.unwrap().into_inner();
+        assert!(tight.degraded);
+
+        let stats = server.deadline_stats();
+        assert_eq!((stats.requests, stats.predict_fallbacks), (2, 2));
+        assert!(stats.extract_fallbacks >= 1);
+        // Only the full-budget request reached drift history
+        assert_eq!(server.pool.drift_stats().history_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_generated_request_id_and_invalid_input() {
+        let server = server();
+
+        let score = server.score(proto::TransactionData::default()).await.unwrap();
+        assert!(!score.request_id.is_empty());
+
+        let status = server
+            .score(proto::TransactionData {
+                next_leader_pubkey: "bad".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,74 @@
+//! Dead-letter queue operator CLI
+//!
+//! ```text
+//! sentinel-dlq list
+//! sentinel-dlq show <id>
+//! sentinel-dlq export <id>          base64 transactions, one per line
+//! sentinel-dlq replay <id> [--force]
+//! sentinel-dlq drop <id>
+//! ```
+//!
+//! Environment:
+//! - `SENTINEL_DLQ_DIR`   queue directory (default `dead_letters`)
+//! - `SENTINEL_CONFIG`    optional TOML config (block engine endpoint for replay)
+
+use jito_bundler::{BundleSubmitter, DeadLetterQueue, JitoClient, SubmitOptions};
+use sentinel_core::SentinelConfig;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const USAGE: &str = "usage: sentinel-dlq <list | show <id> | export <id> | replay <id> [--force] | drop <id>>";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let dir = std::env::var_os("SENTINEL_DLQ_DIR").map_or_else(|| PathBuf::from("dead_letters"), PathBuf::from);
+    let queue = Arc::new(DeadLetterQueue::open(dir)?);
+
+    let id = || args.get(1).map(String::as_str).ok_or(USAGE);
+    match args.first().map(String::as_str) {
+        Some("list") => {
+            for letter in queue.list()? {
+                println!(
+                    "{}\t{:?}\t{} tx\t{} replays\t{}",
+                    letter.id,
+                    letter.route,
+                    letter.transactions.len(),
+                    letter.replays,
+                    letter.last_error().unwrap_or("-")
+                );
+            }
+        }
+        Some("show") => {
+            let letter = queue.get(id()?)?.ok_or("no such dead letter")?;
+            println!("{}", serde_json::to_string_pretty(&letter)?);
+        }
+        Some("export") => {
+            let letter = queue.get(id()?)?.ok_or("no such dead letter")?;
+            for tx in &letter.transactions {
+                println!("{}", tx);
+            }
+        }
+        Some("replay") => {
+            let config_path = std::env::var_os("SENTINEL_CONFIG").map(PathBuf::from);
+            let config = SentinelConfig::load(config_path.as_deref())?;
+            let submitter =
+                BundleSubmitter::new(JitoClient::from_config(&config.endpoints)?).with_dead_letters(queue.clone());
+
+            let mut options = SubmitOptions::default();
+            if args.iter().any(|arg| arg == "--force") {
+                options = options.force();
+            }
+            let bundle_id = submitter.replay(id()?, &options).await?;
+            println!("{}", bundle_id);
+        }
+        Some("drop") => {
+            if !queue.remove(id()?)? {
+                return Err("no such dead letter".into());
+            }
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,622 @@
+//! Landed Block Listener
+//!
+//! Observes landed blocks in near real time (Solana `blockSubscribe`, or any
+//! ShredStream-backed source pushing [`ObservedBlock`]s into the channel) to:
+//! - confirm our bundles landed without polling `getBundleStatuses`
+//! - check whether a landed transaction was sandwiched: an outside signer
+//!   writing one of its writable accounts both before and after it in the block
+//! - feed that outcome back as a training label (`DecisionRecord::mev_incident`)
+//!   and, for submissions carrying a [`LandingContext`], to the `LandingPredictor`
+//! - feed submit-to-land latency and failures per route to the `SloTracker`
+//!
+//! Bundles not seen within `expiry_slots` of submission are reported as expired.
+//! With the `block-subscribe` feature, [`spawn_block_subscribe`] connects the
+//! listener to `EndpointConfig::block_subscribe_url`.
+
+use sentinel_core::{
+    DecisionLog, DecisionRecord, LandingContext, LandingPredictor, Result, RouteType, SentinelError, SloTracker,
+};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+
+/// Blockhash validity window (slots)
+pub const DEFAULT_EXPIRY_SLOTS: u64 = 150;
+
+/// Transaction as seen in a landed block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservedTransaction {
+    pub signature: Signature,
+    pub signers: Vec<Pubkey>,
+    pub writable: Vec<Pubkey>,
+    pub failed: bool,
+}
+
+/// Landed block, transactions in execution order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservedBlock {
+    pub slot: u64,
+    pub transactions: Vec<ObservedTransaction>,
+}
+
+impl ObservedBlock {
+    /// Parse a `blockNotification` (`transactionDetails: "accounts"`, `encoding: "jsonParsed"`)
+    ///
+    /// Returns `None` for notifications without a block (skipped slots, errors).
+    pub fn from_notification(notification: &serde_json::Value) -> Result<Option<Self>> {
+        let value = &notification["params"]["result"]["value"];
+        let Some(block) = value.get("block").filter(|b| !b.is_null()) else {
+            return Ok(None);
+        };
+        let slot = value["slot"]
+            .as_u64()
+            .ok_or_else(|| parse_error("notification without slot"))?;
+
+        let transactions = block["transactions"]
+            .as_array()
+            .map(|txs| txs.iter().map(parse_transaction).collect::<Result<Vec<_>>>())
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Some(Self { slot, transactions }))
+    }
+}
+
+fn parse_transaction(tx: &serde_json::Value) -> Result<ObservedTransaction> {
+    let signature = tx["transaction"]["signatures"][0]
+        .as_str()
+        .and_then(|s| Signature::from_str(s).ok())
+        .ok_or_else(|| parse_error("transaction without signature"))?;
+
+    let mut signers = Vec::new();
+    let mut writable = Vec::new();
+    for key in tx["transaction"]["accountKeys"].as_array().into_iter().flatten() {
+        let Some(pubkey) = key["pubkey"].as_str().and_then(|k| Pubkey::from_str(k).ok()) else {
+            continue;
+        };
+        if key["signer"].as_bool().unwrap_or(false) {
+            signers.push(pubkey);
+        }
+        if key["writable"].as_bool().unwrap_or(false) {
+            writable.push(pubkey);
+        }
+    }
+
+    Ok(ObservedTransaction {
+        signature,
+        signers,
+        writable,
+        failed: !tx["meta"]["err"].is_null(),
+    })
+}
+
+fn parse_error(reason: &str) -> SentinelError {
+    SentinelError::SerializationError(format!("Invalid block notification: {}", reason))
+}
+
+/// Submitted bundle awaiting landing
+#[derive(Debug, Clone)]
+pub struct TrackedSubmission {
+    /// Correlation ID shared with the decision log
+    pub request_id: String,
+    pub bundle_id: String,
+    pub signatures: Vec<Signature>,
+    pub risk_score: f32,
+    pub route: RouteType,
+    pub submitted_slot: u64,
+    /// Tip / leader / size at submission, for landing-probability training
+    pub landing: Option<LandingContext>,
+}
+
+/// What happened to a tracked bundle
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LandingStatus {
+    Landed {
+        slot: u64,
+        sandwiched: bool,
+        /// Signer of the surrounding transactions when sandwiched
+        attacker: Option<Pubkey>,
+    },
+    /// Landed but a transaction failed (bundles revert atomically)
+    Failed { slot: u64 },
+    /// Not seen within the expiry window
+    Expired { last_slot: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleOutcome {
+    pub request_id: String,
+    pub bundle_id: String,
+    pub status: LandingStatus,
+}
+
+/// Listener counters
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ListenerStats {
+    pub blocks_observed: u64,
+    pub pending: usize,
+    pub landed: u64,
+    pub sandwiched: u64,
+    pub failed: u64,
+    pub expired: u64,
+}
+
+struct Pending {
+    submission: TrackedSubmission,
+    notify: Option<oneshot::Sender<BundleOutcome>>,
+    tracked_at: Instant,
+}
+
+/// Matches landed blocks against tracked bundles
+pub struct BlockListener {
+    pending: Mutex<HashMap<String, Pending>>,
+    stats: Mutex<ListenerStats>,
+    expiry_slots: u64,
+    decision_log: Option<Arc<DecisionLog>>,
+    landing_predictor: Option<Arc<LandingPredictor>>,
+    slo_tracker: Option<Arc<SloTracker>>,
+}
+
+impl Default for BlockListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockListener {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            stats: Mutex::new(ListenerStats::default()),
+            expiry_slots: DEFAULT_EXPIRY_SLOTS,
+            decision_log: None,
+            landing_predictor: None,
+            slo_tracker: None,
+        }
+    }
+
+    pub fn with_expiry_slots(mut self, slots: u64) -> Self {
+        self.expiry_slots = slots;
+        self
+    }
+
+    /// Record landed outcomes as training labels
+    pub fn with_decision_log(mut self, log: Arc<DecisionLog>) -> Self {
+        self.decision_log = Some(log);
+        self
+    }
+
+    /// Train `predictor` on every resolved submission that carries a landing context
+    pub fn with_landing_predictor(mut self, predictor: Arc<LandingPredictor>) -> Self {
+        self.landing_predictor = Some(predictor);
+        self
+    }
+
+    /// Report every resolved submission's latency or failure against its route's SLOs
+    pub fn with_slo_tracker(mut self, tracker: Arc<SloTracker>) -> Self {
+        self.slo_tracker = Some(tracker);
+        self
+    }
+
+    /// Track a submitted bundle; the receiver resolves when it lands or expires
+    pub fn track(&self, submission: TrackedSubmission) -> oneshot::Receiver<BundleOutcome> {
+        let (tx, rx) = oneshot::channel();
+        debug!("Tracking bundle {} ({} txs)", submission.bundle_id, submission.signatures.len());
+        lock(&self.pending).insert(
+            submission.bundle_id.clone(),
+            Pending {
+                submission,
+                notify: Some(tx),
+                tracked_at: Instant::now(),
+            },
+        );
+        rx
+    }
+
+    pub fn stats(&self) -> ListenerStats {
+        let mut stats = lock(&self.stats).clone();
+        stats.pending = lock(&self.pending).len();
+        stats
+    }
+
+    /// Match a block against tracked bundles and expire stale ones
+    pub fn process_block(&self, block: &ObservedBlock) -> Vec<BundleOutcome> {
+        let positions: HashMap<Signature, usize> = block
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(i, tx)| (tx.signature, i))
+            .collect();
+
+        let mut resolved = Vec::new();
+        {
+            let mut pending = lock(&self.pending);
+            let finished: Vec<String> = pending
+                .iter()
+                .filter(|(_, p)| {
+                    p.submission.signatures.iter().any(|s| positions.contains_key(s))
+                        || block.slot > p.submission.submitted_slot + self.expiry_slots
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+
+            for bundle_id in finished {
+                if let Some(entry) = pending.remove(&bundle_id) {
+                    let status = landing_status(block, &positions, &entry.submission);
+                    resolved.push((entry, status));
+                }
+            }
+        }
+
+        let mut stats = lock(&self.stats);
+        stats.blocks_observed += 1;
+        resolved
+            .into_iter()
+            .map(|(mut entry, status)| {
+                match status {
+                    LandingStatus::Landed { sandwiched, attacker, .. } => {
+                        stats.landed += 1;
+                        if sandwiched {
+                            stats.sandwiched += 1;
+                            warn!(
+                                "🥪 Bundle {} sandwiched in slot {} by {:?}",
+                                entry.submission.bundle_id, block.slot, attacker
+                            );
+                        } else {
+                            info!("✅ Bundle {} landed in slot {}", entry.submission.bundle_id, block.slot);
+                        }
+                    }
+                    LandingStatus::Failed { .. } => stats.failed += 1,
+                    LandingStatus::Expired { .. } => stats.expired += 1,
+                }
+
+                let outcome = BundleOutcome {
+                    request_id: entry.submission.request_id.clone(),
+                    bundle_id: entry.submission.bundle_id.clone(),
+                    status,
+                };
+                if let Some(notify) = entry.notify.take() {
+                    let _ = notify.send(outcome.clone());
+                }
+                if let Some((predictor, context)) = self.landing_predictor.as_ref().zip(entry.submission.landing.as_ref()) {
+                    let landed = matches!(outcome.status, LandingStatus::Landed { .. });
+                    predictor.observe(&entry.submission.route, context, landed);
+                }
+                if let Some(ref tracker) = self.slo_tracker {
+                    match outcome.status {
+                        LandingStatus::Landed { .. } => {
+                            tracker.record_landed(&entry.submission.route, entry.tracked_at.elapsed())
+                        }
+                        _ => tracker.record_failed(&entry.submission.route),
+                    }
+                }
+                if let Some(record) = label_record(&entry.submission, &outcome) {
+                    self.record_label(record);
+                }
+                outcome
+            })
+            .collect()
+    }
+
+    /// Consume blocks until the source closes
+    pub async fn run(self: Arc<Self>, mut blocks: mpsc::Receiver<ObservedBlock>) {
+        while let Some(block) = blocks.recv().await {
+            self.process_block(&block);
+        }
+        info!("Block source closed, listener stopped");
+    }
+
+    fn record_label(&self, record: DecisionRecord) {
+        let Some(ref log) = self.decision_log else {
+            return;
+        };
+        let log = Arc::clone(log);
+        tokio::spawn(async move {
+            if let Err(e) = log.record(record).await {
+                warn!("Failed to record landing label: {}", e);
+            }
+        });
+    }
+}
+
+fn landing_status(
+    block: &ObservedBlock,
+    positions: &HashMap<Signature, usize>,
+    submission: &TrackedSubmission,
+) -> LandingStatus {
+    let ours: Vec<usize> = submission
+        .signatures
+        .iter()
+        .filter_map(|s| positions.get(s).copied())
+        .collect();
+    if ours.is_empty() {
+        return LandingStatus::Expired { last_slot: block.slot };
+    }
+    if ours.iter().any(|i| block.transactions[*i].failed) {
+        return LandingStatus::Failed { slot: block.slot };
+    }
+
+    let own_signers: HashSet<Pubkey> = ours
+        .iter()
+        .flat_map(|i| block.transactions[*i].signers.iter().copied())
+        .collect();
+    let attacker = ours
+        .iter()
+        .find_map(|i| find_sandwich(&block.transactions, *i, &own_signers));
+
+    LandingStatus::Landed {
+        slot: block.slot,
+        sandwiched: attacker.is_some(),
+        attacker,
+    }
+}
+
+/// Outside signer writing a shared account both before and after `victim`
+fn find_sandwich(txs: &[ObservedTransaction], victim: usize, own_signers: &HashSet<Pubkey>) -> Option<Pubkey> {
+    let targets: HashSet<&Pubkey> = txs[victim].writable.iter().filter(|a| !own_signers.contains(*a)).collect();
+    let touching = |tx: &ObservedTransaction| !tx.failed && tx.writable.iter().any(|a| targets.contains(a));
+
+    let front_runners: HashSet<Pubkey> = txs[..victim]
+        .iter()
+        .filter(|tx| touching(tx))
+        .filter_map(|tx| tx.signers.first().copied())
+        .filter(|s| !own_signers.contains(s))
+        .collect();
+
+    txs[victim + 1..]
+        .iter()
+        .filter(|tx| touching(tx))
+        .filter_map(|tx| tx.signers.first())
+        .find(|s| front_runners.contains(*s))
+        .copied()
+}
+
+/// Landed outcomes become labels joined on `request_id` by the dataset export
+fn label_record(submission: &TrackedSubmission, outcome: &BundleOutcome) -> Option<DecisionRecord> {
+    match outcome.status {
+        LandingStatus::Landed { sandwiched, .. } => Some(
+            DecisionRecord::new(submission.request_id.clone(), submission.risk_score, submission.route.clone())
+                .with_mev_incident(sandwiched),
+        ),
+        _ => None,
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// Stream `blockSubscribe` notifications from `ws_url` into `listener`
+///
+/// Reconnects with a fixed backoff until the listener's channel closes.
+#[cfg(feature = "block-subscribe")]
+pub fn spawn_block_subscribe(ws_url: String, listener: Arc<BlockListener>) -> tokio::task::JoinHandle<()> {
+    let (tx, rx) = mpsc::channel(64);
+    tokio::spawn(listener.run(rx));
+    tokio::spawn(async move {
+        while !tx.is_closed() {
+            if let Err(e) = subscribe::stream_blocks(&ws_url, &tx).await {
+                warn!("blockSubscribe stream error: {} - reconnecting", e);
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
+    })
+}
+
+#[cfg(feature = "block-subscribe")]
+mod subscribe {
+    use super::ObservedBlock;
+    use futures_util::{SinkExt, StreamExt};
+    use sentinel_core::{Result, SentinelError};
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+    use tracing::{debug, info};
+
+    pub async fn stream_blocks(ws_url: &str, blocks: &mpsc::Sender<ObservedBlock>) -> Result<()> {
+        let (mut socket, _) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("blockSubscribe connect failed: {}", e)))?;
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "blockSubscribe",
+            "params": ["all", {
+                "commitment": "confirmed",
+                "encoding": "jsonParsed",
+                "transactionDetails": "accounts",
+                "showRewards": false,
+                "maxSupportedTransactionVersion": 0
+            }]
+        });
+        socket
+            .send(Message::Text(request.to_string()))
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("blockSubscribe request failed: {}", e)))?;
+        info!("📡 Subscribed to landed blocks at {}", ws_url);
+
+        while let Some(message) = socket.next().await {
+            let message = message.map_err(|e| SentinelError::NetworkError(e.to_string()))?;
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let notification: serde_json::Value =
+                serde_json::from_str(&text).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+            if notification.get("method").and_then(|m| m.as_str()) != Some("blockNotification") {
+                debug!("blockSubscribe: {}", text);
+                continue;
+            }
+            if let Some(block) = ObservedBlock::from_notification(&notification)? {
+                if blocks.send(block).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(SentinelError::NetworkError("blockSubscribe stream closed".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(signer: Pubkey, writable: &[Pubkey]) -> ObservedTransaction {
+        ObservedTransaction {
+            signature: Signature::new_unique(),
+            signers: vec![signer],
+            writable: std::iter::once(signer).chain(writable.iter().copied()).collect(),
+            failed: false,
+        }
+    }
+
+    fn submission(signature: Signature, submitted_slot: u64) -> TrackedSubmission {
+        TrackedSubmission {
+            request_id: "req-1".to_string(),
+            bundle_id: "bundle-1".to_string(),
+            signatures: vec![signature],
+            risk_score: 0.7,
+            route: RouteType::JitoBundle,
+            submitted_slot,
+            landing: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_landed_clean_resolves_receiver() {
+        let (user, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ours = tx(user, &[pool]);
+        let listener = BlockListener::new();
+        let rx = listener.track(submission(ours.signature, 100));
+
+        let other = tx(Pubkey::new_unique(), &[pool]);
+        let block = ObservedBlock { slot: 101, transactions: vec![other, ours] };
+        listener.process_block(&block);
+
+        let outcome = rx.await.unwrap();
+        assert_eq!(outcome.status, LandingStatus::Landed { slot: 101, sandwiched: false, attacker: None });
+        assert_eq!(listener.stats().landed, 1);
+        assert_eq!(listener.stats().pending, 0);
+    }
+
+    #[test]
+    fn test_sandwich_detected() {
+        let (user, attacker, pool) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ours = tx(user, &[pool]);
+        let listener = BlockListener::new();
+        listener.track(submission(ours.signature, 100));
+
+        let block = ObservedBlock {
+            slot: 102,
+            transactions: vec![tx(attacker, &[pool]), ours, tx(attacker, &[pool])],
+        };
+        let outcomes = listener.process_block(&block);
+
+        assert_eq!(
+            outcomes[0].status,
+            LandingStatus::Landed { slot: 102, sandwiched: true, attacker: Some(attacker) }
+        );
+        let label = label_record(&submission(Signature::default(), 100), &outcomes[0]).unwrap();
+        assert_eq!(label.mev_incident, Some(true));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let listener = BlockListener::new().with_expiry_slots(10);
+        listener.track(submission(Signature::new_unique(), 100));
+
+        let empty = |slot| ObservedBlock { slot, transactions: vec![] };
+        assert!(listener.process_block(&empty(110)).is_empty());
+        let outcomes = listener.process_block(&empty(111));
+        assert_eq!(outcomes[0].status, LandingStatus::Expired { last_slot: 111 });
+        assert_eq!(listener.stats().expired, 1);
+    }
+
+    #[test]
+    fn test_outcomes_feed_slo_tracker() {
+        let tracker = Arc::new(SloTracker::default());
+        let listener = BlockListener::new()
+            .with_expiry_slots(10)
+            .with_slo_tracker(Arc::clone(&tracker));
+
+        let landed = tx(Pubkey::new_unique(), &[]);
+        listener.track(submission(landed.signature, 100));
+        listener.track(TrackedSubmission {
+            bundle_id: "bundle-2".to_string(),
+            ..submission(Signature::new_unique(), 100)
+        });
+        listener.process_block(&ObservedBlock { slot: 101, transactions: vec![landed] });
+        listener.process_block(&ObservedBlock { slot: 111, transactions: vec![] });
+
+        let window = tracker.status(&RouteType::JitoBundle).short;
+        assert_eq!((window.samples, window.failures), (2, 1));
+        assert!(window.p50_latency_ms.is_some());
+    }
+
+    #[test]
+    fn test_outcomes_train_landing_predictor() {
+        let predictor = Arc::new(LandingPredictor::default());
+        let listener = BlockListener::new()
+            .with_expiry_slots(10)
+            .with_landing_predictor(Arc::clone(&predictor));
+        let context = LandingContext { tip_percentile: 50.0, leader: None, bundle_size: 2 };
+
+        let landed = tx(Pubkey::new_unique(), &[]);
+        let mut tracked = submission(landed.signature, 100);
+        tracked.landing = Some(context.clone());
+        listener.track(tracked);
+        listener.track(TrackedSubmission {
+            bundle_id: "bundle-2".to_string(),
+            landing: Some(context.clone()),
+            ..submission(Signature::new_unique(), 100)
+        });
+        // No landing context: not a training sample
+        listener.track(TrackedSubmission {
+            bundle_id: "bundle-3".to_string(),
+            ..submission(Signature::new_unique(), 100)
+        });
+
+        listener.process_block(&ObservedBlock { slot: 101, transactions: vec![landed] });
+        listener.process_block(&ObservedBlock { slot: 111, transactions: vec![] });
+        assert_eq!(predictor.observations(&RouteType::JitoBundle), 2);
+    }
+
+    #[test]
+    fn test_parse_block_notification() {
+        let (signer, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let signature = Signature::new_unique();
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "blockNotification",
+            "params": {"result": {"context": {"slot": 7}, "value": {"slot": 7, "err": null, "block": {
+                "transactions": [{
+                    "transaction": {
+                        "signatures": [signature.to_string()],
+                        "accountKeys": [
+                            {"pubkey": signer.to_string(), "signer": true, "writable": true},
+                            {"pubkey": pool.to_string(), "signer": false, "writable": true}
+                        ]
+                    },
+                    "meta": {"err": {"InstructionError": [0, "Custom"]}}
+                }]
+            }}}, "subscription": 1}
+        });
+
+        let block = ObservedBlock::from_notification(&notification).unwrap().unwrap();
+        assert_eq!(block.slot, 7);
+        assert_eq!(block.transactions[0].signature, signature);
+        assert_eq!(block.transactions[0].signers, vec![signer]);
+        assert_eq!(block.transactions[0].writable, vec![signer, pool]);
+        assert!(block.transactions[0].failed);
+
+        let skipped = serde_json::json!({"params": {"result": {"value": {"slot": 8, "block": null}}}});
+        assert!(ObservedBlock::from_notification(&skipped).unwrap().is_none());
+    }
+}
@@ -1,29 +1,24 @@
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
-use sentinel_core::{Result, SentinelError};
+use sentinel_core::{Intent, IntentType, NettedTrade, Network, Result, SentinelError, TipPolicy};
 #[allow(deprecated)]
 use solana_sdk::system_instruction;
 use solana_sdk::{
     hash::Hash, instruction::CompiledInstruction, pubkey::Pubkey, signature::Keypair,
     signer::Signer, transaction::Transaction,
 };
-use std::str::FromStr;
 use tracing::{debug, info};
 
+use crate::protection::JitoDontFrontMarker;
+
 const MAX_BUNDLE_SIZE: usize = 5;
 const MIN_TIP_LAMPORTS: u64 = 1000;
 
-/// Official Jito tip payment accounts
-const JITO_TIP_ACCOUNTS: &[&str] = &[
-    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
-    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
-    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
-    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
-    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
-    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
-    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
-    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
-];
+/// Base fee per transaction signature (lamports)
+const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Default cap on a tip-only protection transaction's cost (tip + signature fee)
+const TIP_ONLY_BUDGET_LAMPORTS: u64 = 20_000;
 
 /// Fee allocation for bundle creation
 #[derive(Debug, Clone)]
@@ -72,32 +67,32 @@ impl JitoBundle {
             )));
         }
 
-        // Verify tip transaction exists in last position
-        if let Some(last_tx) = self.transactions.last() {
-            let has_tip = last_tx.message.instructions.iter().any(|ix| {
-                let program_id = last_tx.message.account_keys[ix.program_id_index as usize];
-                program_id == solana_sdk::system_program::id()
-                    && self.is_tip_instruction_compiled(ix, &last_tx.message.account_keys)
-            });
-
-            if !has_tip {
-                return Err(SentinelError::BundleError(
-                    "Last transaction must contain Jito tip".to_string(),
-                ));
-            }
+        // Tip goes last, unless a dontfront-marked tip transaction leads the bundle
+        let first = &self.transactions[0];
+        let leading_tip = first.message.account_keys.contains(&JitoDontFrontMarker::pubkey()) && self.has_tip(first);
+        if !leading_tip && !self.transactions.last().is_some_and(|tx| self.has_tip(tx)) {
+            return Err(SentinelError::BundleError(
+                "Last transaction must contain Jito tip".to_string(),
+            ));
         }
 
         Ok(())
     }
 
+    fn has_tip(&self, tx: &Transaction) -> bool {
+        tx.message.instructions.iter().any(|ix| {
+            let program_id = tx.message.account_keys[ix.program_id_index as usize];
+            program_id == solana_sdk::system_program::id()
+                && self.is_tip_instruction_compiled(ix, &tx.message.account_keys)
+        })
+    }
+
     fn is_tip_instruction_compiled(&self, ix: &CompiledInstruction, accounts: &[Pubkey]) -> bool {
         // Check if instruction transfers to a Jito tip account
         if ix.accounts.len() >= 2 {
             let to_account = accounts.get(ix.accounts[1] as usize);
             if let Some(to) = to_account {
-                return JITO_TIP_ACCOUNTS
-                    .iter()
-                    .any(|&tip_acc| to.to_string() == tip_acc);
+                return Network::is_any_tip_account(to);
             }
         }
         false
@@ -114,6 +109,10 @@ impl Default for JitoBundle {
 pub struct BundleBuilder {
     pub recent_blockhash: Hash,
     fee_payer: Keypair,
+    min_tip_lamports: u64,
+    max_tip_lamports: u64,
+    tip_only_budget_lamports: u64,
+    network: Network,
 }
 
 impl BundleBuilder {
@@ -121,9 +120,33 @@ impl BundleBuilder {
         Self {
             recent_blockhash,
             fee_payer,
+            min_tip_lamports: MIN_TIP_LAMPORTS,
+            max_tip_lamports: u64::MAX,
+            tip_only_budget_lamports: TIP_ONLY_BUDGET_LAMPORTS,
+            network: Network::default(),
         }
     }
 
+    /// Tip the cluster's Jito tip accounts (mainnet by default)
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Apply tip bounds from runtime configuration
+    pub fn with_tip_policy(mut self, policy: &TipPolicy) -> Self {
+        self.min_tip_lamports = policy.min_tip_lamports;
+        self.max_tip_lamports = policy.max_tip_lamports;
+        self.tip_only_budget_lamports = policy.tip_only_budget_lamports;
+        self
+    }
+
+    /// Cap on a tip-only protection transaction's cost (tip + signature fee)
+    pub fn with_tip_only_budget(mut self, lamports: u64) -> Self {
+        self.tip_only_budget_lamports = lamports;
+        self
+    }
+
     /// Build a protected bundle with user transaction and tip
     pub fn build_protected_bundle(
         &self,
@@ -131,14 +154,7 @@ impl BundleBuilder {
         fee_allocation: &FeeAllocation,
     ) -> Result<JitoBundle> {
         info!("Building protected Jito bundle");
-
-        // Ensure tip meets minimum
-        if fee_allocation.jito_tip_lamports < MIN_TIP_LAMPORTS {
-            return Err(SentinelError::BundleError(format!(
-                "Tip must be at least {} lamports",
-                MIN_TIP_LAMPORTS
-            )));
-        }
+        self.check_tip(fee_allocation)?;
 
         // Add jitodontfront marker to first instruction of user transaction
         if let Some(_first_ix) = user_transaction.message.instructions.first_mut() {
@@ -165,10 +181,178 @@ impl BundleBuilder {
         Ok(bundle)
     }
 
+    /// Wrap an already signed swap in a 2-transaction dontfront bundle
+    ///
+    /// The user's signature rules out adding the jitodontfront marker to the
+    /// swap itself, so a minimal tip transaction carries it instead. Jito only
+    /// accepts marked transactions at index 0, so the tip leads the bundle and
+    /// the swap follows atomically, out of reach of a front-runner. The tip is
+    /// `target_tip_lamports` shrunk to fit the tip-only budget (see
+    /// [`BundleBuilder::size_tip_only`]).
+    pub fn build_tip_only_bundle(
+        &self,
+        user_transaction: Transaction,
+        target_tip_lamports: u64,
+    ) -> Result<JitoBundle> {
+        let tip = self.size_tip_only(target_tip_lamports)?;
+
+        let mut tip_ix = system_instruction::transfer(&self.fee_payer.pubkey(), &self.network.tip_account(0), tip);
+        JitoDontFrontMarker::add_to_instruction(&mut tip_ix);
+        let tip_transaction = Transaction::new_signed_with_payer(
+            &[tip_ix],
+            Some(&self.fee_payer.pubkey()),
+            &[&self.fee_payer],
+            self.recent_blockhash,
+        );
+
+        let mut bundle = JitoBundle::new();
+        bundle.transactions.push(tip_transaction);
+        bundle.transactions.push(user_transaction);
+        bundle.validate()?;
+
+        info!(
+            "Tip-only protection bundle: {} lamport tip, {} lamport overhead",
+            tip,
+            tip + SIGNATURE_FEE_LAMPORTS
+        );
+        Ok(bundle)
+    }
+
+    /// Tip for a tip-only bundle: the target, clamped to the tip bounds and to
+    /// what the budget leaves after the tip transaction's signature fee
+    pub fn size_tip_only(&self, target_tip_lamports: u64) -> Result<u64> {
+        let available = self
+            .tip_only_budget_lamports
+            .saturating_sub(SIGNATURE_FEE_LAMPORTS)
+            .min(self.max_tip_lamports);
+        if available < self.min_tip_lamports {
+            return Err(SentinelError::BundleError(format!(
+                "Tip-only budget of {} lamports leaves less than the {} lamport minimum tip",
+                self.tip_only_budget_lamports, self.min_tip_lamports
+            )));
+        }
+        Ok(target_tip_lamports.clamp(self.min_tip_lamports, available))
+    }
+
+    /// Pack a Bundle intent's legs, one transaction per leg in leg order, plus the tip
+    ///
+    /// Jito executes bundles all-or-nothing: if any leg fails, none land.
+    pub fn build_intent_bundle(
+        &self,
+        intent: &Intent,
+        leg_transactions: Vec<Transaction>,
+        fee_allocation: &FeeAllocation,
+    ) -> Result<JitoBundle> {
+        if intent.intent_type != IntentType::Bundle {
+            return Err(SentinelError::BundleError(format!(
+                "Intent {} is not a Bundle intent",
+                intent.intent_id
+            )));
+        }
+        if leg_transactions.len() != intent.legs.len() {
+            return Err(SentinelError::BundleError(format!(
+                "Intent {} has {} legs but {} leg transactions were built",
+                intent.intent_id,
+                intent.legs.len(),
+                leg_transactions.len()
+            )));
+        }
+        if leg_transactions.len() >= MAX_BUNDLE_SIZE {
+            return Err(SentinelError::BundleError(format!(
+                "At most {} legs fit in a bundle with its tip",
+                MAX_BUNDLE_SIZE - 1
+            )));
+        }
+        self.check_tip(fee_allocation)?;
+
+        let mut bundle = JitoBundle::new();
+        bundle.transactions.extend(leg_transactions);
+        bundle
+            .transactions
+            .push(self.create_tip_transaction(fee_allocation.jito_tip_lamports)?);
+        bundle.validate()?;
+
+        info!(
+            "Multi-leg bundle for intent {}: {} legs + {} lamport tip",
+            intent.intent_id,
+            intent.legs.len(),
+            fee_allocation.jito_tip_lamports
+        );
+        Ok(bundle)
+    }
+
+    /// Pack a netted cross: the settlement transfers, a DEX swap per residual, then the tip
+    ///
+    /// The settlement must require both users' signatures (built from
+    /// [`NettedTrade::transfer_instructions`]). Bundling makes the cross and
+    /// its residual swaps land together or not at all.
+    pub fn build_netting_bundle(
+        &self,
+        trade: &NettedTrade,
+        settlement: Transaction,
+        residual_transactions: Vec<Transaction>,
+        fee_allocation: &FeeAllocation,
+    ) -> Result<JitoBundle> {
+        let signers = &settlement.message.account_keys[..settlement.message.header.num_required_signatures as usize];
+        if let Some(missing) = trade.signers().iter().find(|user| !signers.contains(user)) {
+            return Err(SentinelError::BundleError(format!(
+                "Netting settlement for {} is not signed by {}",
+                trade.legs[0].intent_id, missing
+            )));
+        }
+        if residual_transactions.len() != trade.residuals.len() {
+            return Err(SentinelError::BundleError(format!(
+                "Netted trade has {} residuals but {} residual transactions were built",
+                trade.residuals.len(),
+                residual_transactions.len()
+            )));
+        }
+        if residual_transactions.len() + 2 > MAX_BUNDLE_SIZE {
+            return Err(SentinelError::BundleError(format!(
+                "At most {} residual swaps fit in a netting bundle",
+                MAX_BUNDLE_SIZE - 2
+            )));
+        }
+        self.check_tip(fee_allocation)?;
+
+        let mut bundle = JitoBundle::new();
+        bundle.transactions.push(settlement);
+        bundle.transactions.extend(residual_transactions);
+        bundle
+            .transactions
+            .push(self.create_tip_transaction(fee_allocation.jito_tip_lamports)?);
+        bundle.validate()?;
+
+        info!(
+            "Netting bundle for {} ↔ {}: {} residual swaps + {} lamport tip",
+            trade.legs[0].intent_id,
+            trade.legs[1].intent_id,
+            trade.residuals.len(),
+            fee_allocation.jito_tip_lamports
+        );
+        Ok(bundle)
+    }
+
+    fn check_tip(&self, fee_allocation: &FeeAllocation) -> Result<()> {
+        if fee_allocation.jito_tip_lamports < self.min_tip_lamports {
+            return Err(SentinelError::BundleError(format!(
+                "Tip must be at least {} lamports",
+                self.min_tip_lamports
+            )));
+        }
+
+        if fee_allocation.jito_tip_lamports > self.max_tip_lamports {
+            return Err(SentinelError::BundleError(format!(
+                "Tip exceeds policy maximum of {} lamports",
+                self.max_tip_lamports
+            )));
+        }
+
+        Ok(())
+    }
+
     fn create_tip_transaction(&self, tip_lamports: u64) -> Result<Transaction> {
-        // Select a Jito tip account (round-robin or random)
-        let tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[0])
-            .map_err(|e| SentinelError::BundleError(e.to_string()))?;
+        let tip_account = self.network.tip_account(0);
 
         // Use solana_system_interface for system instructions
         let tip_ix =
@@ -210,6 +394,33 @@ mod tests {
         assert!(bundle.validate().is_err()); // Empty bundle should fail
     }
 
+    #[test]
+    fn test_tip_policy_bounds() {
+        let policy = TipPolicy {
+            min_tip_lamports: 5_000,
+            max_tip_lamports: 10_000,
+            ..Default::default()
+        };
+        let builder = BundleBuilder::new(Hash::new_unique(), Keypair::new()).with_tip_policy(&policy);
+
+        let too_low = builder.build_protected_bundle(Transaction::default(), &FeeAllocation::new(0, 2_000));
+        assert!(too_low.unwrap_err().to_string().contains("at least 5000"));
+
+        let too_high = builder.build_protected_bundle(Transaction::default(), &FeeAllocation::new(0, 20_000));
+        assert!(too_high.unwrap_err().to_string().contains("maximum"));
+    }
+
+    #[test]
+    fn test_tip_goes_to_network_tip_account() {
+        let fees = FeeAllocation::new(0, 5_000);
+        for network in [Network::Mainnet, Network::Testnet] {
+            let builder = BundleBuilder::new(Hash::new_unique(), Keypair::new()).with_network(network);
+            let bundle = builder.build_protected_bundle(Transaction::default(), &fees).unwrap();
+            let tip_tx = bundle.transactions.last().unwrap();
+            assert!(tip_tx.message.account_keys.contains(&network.tip_account(0)));
+        }
+    }
+
     #[test]
     fn test_bundle_max_size() {
         let mut bundle = JitoBundle::new();
@@ -219,4 +430,134 @@ mod tests {
         }
         assert!(bundle.validate().is_err()); // > 5 transactions should fail
     }
+
+    #[test]
+    fn test_tip_only_bundle_leads_with_marked_tip() {
+        let builder = BundleBuilder::new(Hash::new_unique(), Keypair::new()).with_tip_only_budget(12_000);
+        let user = Keypair::new();
+        let swap = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1)],
+            Some(&user.pubkey()),
+            &[&user],
+            builder.recent_blockhash,
+        );
+
+        let bundle = builder.build_tip_only_bundle(swap.clone(), 50_000).unwrap();
+        assert_eq!(bundle.transactions.len(), 2);
+        assert_eq!(bundle.transactions[1], swap);
+
+        let tip_tx = &bundle.transactions[0];
+        assert!(tip_tx.message.account_keys.contains(&JitoDontFrontMarker::pubkey()));
+        assert_eq!(tip_tx.signatures.len(), 1);
+        // 12_000 budget - 5_000 signature fee
+        let transfer = &tip_tx.message.instructions[0];
+        assert_eq!(u64::from_le_bytes(transfer.data[4..12].try_into().unwrap()), 7_000);
+        assert!(tip_tx.verify().is_ok());
+    }
+
+    #[test]
+    fn test_tip_only_sizing() {
+        let builder = BundleBuilder::new(Hash::new_unique(), Keypair::new());
+        assert_eq!(builder.size_tip_only(500).unwrap(), MIN_TIP_LAMPORTS);
+        assert_eq!(builder.size_tip_only(8_000).unwrap(), 8_000);
+        assert_eq!(builder.size_tip_only(100_000).unwrap(), 15_000);
+
+        let policy = TipPolicy { max_tip_lamports: 3_000, ..Default::default() };
+        assert_eq!(builder.with_tip_policy(&policy).size_tip_only(100_000).unwrap(), 3_000);
+
+        let tight = BundleBuilder::new(Hash::new_unique(), Keypair::new()).with_tip_only_budget(5_500);
+        assert!(tight.size_tip_only(1_000).unwrap_err().to_string().contains("Tip-only budget"));
+
+        // An unmarked tip may not lead
+        let mut bundle = tight.build_protected_bundle(Transaction::default(), &FeeAllocation::new(0, 1_000)).unwrap();
+        bundle.transactions.reverse();
+        assert!(bundle.validate().is_err());
+    }
+
+    #[test]
+    fn test_intent_bundle_packs_legs_in_order() {
+        use sentinel_core::{DepositDetails, IntentLeg};
+        use solana_sdk::instruction::Instruction;
+
+        let payer = Keypair::new();
+        let builder = BundleBuilder::new(Hash::new_unique(), Keypair::new());
+        let leg_tx = |data: u8| {
+            let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[data], vec![]);
+            Transaction::new_with_payer(&[ix], Some(&payer.pubkey()))
+        };
+        let deposit = |mint| {
+            IntentLeg::Deposit(DepositDetails {
+                program_id: Pubkey::new_unique(),
+                vault: Pubkey::new_unique(),
+                mint,
+                amount: Some(1),
+            })
+        };
+
+        let intent = Intent {
+            intent_id: "bundle-1".to_string(),
+            user_public_key: payer.pubkey(),
+            intent_type: IntentType::Bundle,
+            swap_details: None,
+            legs: vec![deposit(Pubkey::new_unique()), deposit(Pubkey::new_unique())],
+            ..Intent::test_swap()
+        };
+
+        let fees = FeeAllocation::new(0, 5_000);
+        let bundle = builder
+            .build_intent_bundle(&intent, vec![leg_tx(1), leg_tx(2)], &fees)
+            .unwrap();
+        assert_eq!(bundle.transactions.len(), 3);
+        assert_eq!(bundle.transactions[0].message.instructions[0].data, vec![1]);
+        assert_eq!(bundle.transactions[1].message.instructions[0].data, vec![2]);
+
+        // Every leg needs its transaction
+        assert!(builder.build_intent_bundle(&intent, vec![leg_tx(1)], &fees).is_err());
+    }
+
+    #[test]
+    fn test_netting_bundle_requires_both_signers() {
+        use sentinel_core::{NettingLeg, NettingResidual};
+
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let leg = |user, input_mint, input_amount, output_mint, output_amount| NettingLeg {
+            intent_id: format!("intent-{}", input_amount),
+            user,
+            input_mint,
+            input_amount,
+            output_mint,
+            output_amount,
+        };
+        let trade = NettedTrade {
+            mid_price: 10.0,
+            legs: [leg(b, usdc, 150, sol, 1_500), leg(a, sol, 1_500, usdc, 150)],
+            residuals: vec![NettingResidual {
+                intent_id: "intent-1500".to_string(),
+                input_mint: sol,
+                amount: 500,
+            }],
+        };
+        let builder = BundleBuilder::new(Hash::new_unique(), Keypair::new());
+        let fees = FeeAllocation::new(0, 5_000);
+        let residual = Transaction::new_with_payer(
+            &[system_instruction::transfer(&a, &Pubkey::new_unique(), 1)],
+            Some(&a),
+        );
+
+        let settlement = Transaction::new_with_payer(&trade.transfer_instructions(), Some(&a));
+        let bundle = builder
+            .build_netting_bundle(&trade, settlement.clone(), vec![residual.clone()], &fees)
+            .unwrap();
+        assert_eq!(bundle.transactions.len(), 3);
+        assert_eq!(bundle.transactions[0], settlement);
+
+        // Each residual needs its swap
+        assert!(builder.build_netting_bundle(&trade, settlement, vec![], &fees).is_err());
+
+        // B never consented to this settlement
+        let one_sided = Transaction::new_with_payer(&trade.transfer_instructions()[1..], Some(&a));
+        let err = builder.build_netting_bundle(&trade, one_sided, vec![residual], &fees).unwrap_err();
+        assert!(err.to_string().contains("not signed by"));
+    }
 }
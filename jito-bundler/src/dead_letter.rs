@@ -0,0 +1,317 @@
+//! Dead-Letter Queue for Failed Submissions
+//!
+//! A submission that fails non-retryably (simulation rejection, invalid
+//! bundle) or keeps failing after its retries used to leave nothing behind
+//! but a `Failed` status. [`BundleSubmitter::submit_intent`](crate::BundleSubmitter::submit_intent)
+//! now parks it here with everything needed to investigate or try again:
+//! the intent, the signed transactions, per-transaction simulation logs (and
+//! their classified [`SimulationFailure`]) and the error from every attempt.
+//!
+//! Letters are stored one JSON file per entry under the queue directory
+//! (written via tmp + rename), so operators can inspect them with the
+//! `sentinel-dlq` CLI or plain tools, replay them through the submitter, or
+//! export the transactions for manual submission.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sentinel_core::{system_clock, Intent, Result, RouteType, SentinelError, SharedClock};
+use serde::{Deserialize, Serialize};
+use solana_sdk::transaction::Transaction;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::log_parser::SimulationFailure;
+
+/// Whether another attempt at the same bundle could succeed
+///
+/// Transport failures and timeouts are transient; simulation rejections,
+/// invalid bundles, the kill switch and bad input fail the same way every time.
+pub fn is_retryable(error: &SentinelError) -> bool {
+    matches!(
+        error,
+        SentinelError::NetworkError(_)
+            | SentinelError::RpcError(_)
+            | SentinelError::Timeout(_)
+            | SentinelError::ConnectionError(_)
+    )
+}
+
+/// A failed submission with its full context
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: String,
+    pub intent: Intent,
+    pub route: RouteType,
+
+    /// Signed transactions (base64 bincode, as sent to the block engine)
+    pub transactions: Vec<String>,
+
+    /// Simulation logs per transaction from the last simulated attempt
+    #[serde(default)]
+    pub simulation_logs: Vec<Vec<String>>,
+
+    /// Classified cause from those logs
+    #[serde(default)]
+    pub failure: Option<SimulationFailure>,
+
+    /// One entry per failed attempt, oldest first
+    pub errors: Vec<String>,
+
+    /// Unix ms
+    pub failed_at_ms: i64,
+
+    /// Replays attempted from the queue
+    #[serde(default)]
+    pub replays: u32,
+}
+
+impl DeadLetter {
+    pub fn new(intent: &Intent, route: RouteType, transactions: &[Transaction], failed_at_ms: i64) -> Result<Self> {
+        let transactions = transactions
+            .iter()
+            .map(|tx| {
+                bincode::serialize(tx)
+                    .map(|bytes| BASE64.encode(bytes))
+                    .map_err(|e| SentinelError::SerializationError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: format!("{}-{}", intent.intent_id, failed_at_ms),
+            intent: intent.clone(),
+            route,
+            transactions,
+            simulation_logs: Vec::new(),
+            failure: None,
+            errors: Vec::new(),
+            failed_at_ms,
+            replays: 0,
+        })
+    }
+
+    pub fn with_simulation_logs(mut self, logs: Vec<Vec<String>>) -> Self {
+        self.simulation_logs = logs;
+        self
+    }
+
+    pub fn with_failure(mut self, failure: Option<SimulationFailure>) -> Self {
+        self.failure = failure;
+        self
+    }
+
+    pub fn with_errors(mut self, errors: Vec<String>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    /// Decode the stored transactions for resubmission
+    pub fn decode_transactions(&self) -> Result<Vec<Transaction>> {
+        self.transactions
+            .iter()
+            .map(|encoded| {
+                let bytes = BASE64
+                    .decode(encoded)
+                    .map_err(|e| SentinelError::SerializationError(format!("Invalid dead-letter tx: {}", e)))?;
+                bincode::deserialize(&bytes).map_err(|e| SentinelError::SerializationError(e.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.errors.last().map(String::as_str)
+    }
+}
+
+/// Directory-backed queue of dead letters
+pub struct DeadLetterQueue {
+    dir: PathBuf,
+    clock: SharedClock,
+}
+
+impl DeadLetterQueue {
+    /// Open (creating if needed) a queue directory
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| SentinelError::IoError(format!("Failed to create dead-letter dir {:?}: {}", dir, e)))?;
+        Ok(Self {
+            dir,
+            clock: system_clock(),
+        })
+    }
+
+    /// Time source for `failed_at_ms`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn now_ms(&self) -> i64 {
+        self.clock.unix_millis()
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        // Ids become file names; refuse anything that could escape the directory
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(SentinelError::InvalidIntent(format!("Invalid dead-letter id: {}", id)));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+
+    /// Store (or overwrite) a letter
+    pub fn push(&self, letter: &DeadLetter) -> Result<()> {
+        let path = self.path(&letter.id)?;
+        let data = serde_json::to_vec_pretty(letter).map_err(|e| SentinelError::SerializationError(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, data)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| SentinelError::IoError(format!("Failed to persist dead letter: {}", e)))?;
+
+        warn!(
+            "📮 Dead-lettered intent {} ({:?}): {}",
+            letter.intent.intent_id,
+            letter.route,
+            letter.last_error().unwrap_or("unknown error")
+        );
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<DeadLetter>> {
+        let path = self.path(id)?;
+        match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map(Some)
+                .map_err(|e| SentinelError::SerializationError(format!("Corrupt dead letter {}: {}", id, e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SentinelError::IoError(format!("Failed to read dead letter {}: {}", id, e))),
+        }
+    }
+
+    /// All letters, oldest first (unreadable files are skipped)
+    pub fn list(&self) -> Result<Vec<DeadLetter>> {
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| SentinelError::IoError(format!("Failed to list dead letters: {}", e)))?;
+
+        let mut letters: Vec<DeadLetter> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let letter = std::fs::read(&path)
+                    .ok()
+                    .and_then(|data| serde_json::from_slice(&data).ok());
+                if letter.is_none() {
+                    warn!("Skipping unreadable dead letter {:?}", path);
+                }
+                letter
+            })
+            .collect();
+        letters.sort_by(|a, b| a.failed_at_ms.cmp(&b.failed_at_ms).then_with(|| a.id.cmp(&b.id)));
+        Ok(letters)
+    }
+
+    pub fn len(&self) -> usize {
+        self.list().map_or(0, |letters| letters.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop a letter once handled; returns whether it existed
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        match std::fs::remove_file(self.path(id)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SentinelError::IoError(format!("Failed to remove dead letter {}: {}", id, e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sentinel_core::ManualClock;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{Keypair, Signer};
+    use std::sync::Arc;
+
+    fn intent(id: &str) -> Intent {
+        Intent {
+            intent_id: id.to_string(),
+            swap_details: None,
+            ..Intent::test_swap()
+        }
+    }
+
+    fn transfer() -> Transaction {
+        let payer = Keypair::new();
+        #[allow(deprecated)]
+        let ix = solana_sdk::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000);
+        Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], Hash::new_unique())
+    }
+
+    fn temp_queue() -> DeadLetterQueue {
+        let dir = std::env::temp_dir().join(format!("dlq-{}", Pubkey::new_unique()));
+        DeadLetterQueue::open(dir)
+            .unwrap()
+            .with_clock(Arc::new(ManualClock::at_timestamp(1_700_000_000)))
+    }
+
+    #[test]
+    fn test_retryable_classification() {
+        assert!(is_retryable(&SentinelError::NetworkError("reset".into())));
+        assert!(is_retryable(&SentinelError::Timeout("5s".into())));
+        assert!(!is_retryable(&SentinelError::BundleError("Simulation rejected bundle".into())));
+        assert!(!is_retryable(&SentinelError::InvalidIntent("bad".into())));
+    }
+
+    #[test]
+    fn test_letter_round_trips_transactions() {
+        let tx = transfer();
+        let letter = DeadLetter::new(&intent("intent-1"), RouteType::JitoBundle, std::slice::from_ref(&tx), 1_000)
+            .unwrap()
+            .with_errors(vec!["first".into(), "second".into()]);
+
+        assert_eq!(letter.id, "intent-1-1000");
+        assert_eq!(letter.decode_transactions().unwrap(), vec![tx]);
+        assert_eq!(letter.last_error(), Some("second"));
+    }
+
+    #[test]
+    fn test_queue_push_list_remove() {
+        let queue = temp_queue();
+        assert!(queue.is_empty());
+
+        let older = DeadLetter::new(&intent("a"), RouteType::JitoBundle, &[transfer()], 2_000).unwrap();
+        let newer = DeadLetter::new(&intent("b"), RouteType::StandardRpc, &[transfer()], 3_000)
+            .unwrap()
+            .with_simulation_logs(vec![vec!["Program log: slippage".into()]]);
+        queue.push(&newer).unwrap();
+        queue.push(&older).unwrap();
+        std::fs::write(queue.dir().join("garbage.json"), b"{").unwrap();
+
+        let ids: Vec<_> = queue.list().unwrap().into_iter().map(|l| l.id).collect();
+        assert_eq!(ids, vec!["a-2000", "b-3000"]);
+        assert_eq!(queue.get("b-3000").unwrap().unwrap(), newer);
+
+        assert!(queue.remove("a-2000").unwrap());
+        assert!(!queue.remove("a-2000").unwrap());
+        assert!(queue.get("a-2000").unwrap().is_none());
+        assert_eq!(queue.len(), 1);
+        std::fs::remove_dir_all(queue.dir()).ok();
+    }
+
+    #[test]
+    fn test_ids_cannot_escape_directory() {
+        let queue = temp_queue();
+        assert!(queue.get("../etc/passwd").is_err());
+        assert!(queue.remove("").is_err());
+        std::fs::remove_dir_all(queue.dir()).ok();
+    }
+}
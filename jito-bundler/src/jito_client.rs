@@ -1,14 +1,26 @@
-use reqwest::Client;
-use sentinel_core::{Result, SentinelError};
+use reqwest::{Client, RequestBuilder};
+#[cfg(feature = "chaos")]
+use sentinel_core::{ChaosInjector, Fault};
+use sentinel_core::{
+    traceparent, CircuitBreaker, EndpointConfig, Network, Result, SentinelError, TRACEPARENT_HEADER,
+};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::Transaction;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::log_parser::SimulationFailure;
+
 /// Production Jito Block Engine client
 pub struct JitoClient {
     http_client: Client,
     block_engine_url: String,
+    breaker: Option<Arc<CircuitBreaker>>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<ChaosInjector>>,
 }
 
 impl JitoClient {
@@ -22,17 +34,48 @@ impl JitoClient {
         Ok(Self {
             http_client,
             block_engine_url,
+            breaker: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
         })
     }
 
+    /// Create client from endpoint configuration
+    pub fn from_config(endpoints: &EndpointConfig) -> Result<Self> {
+        Self::new(endpoints.jito_block_engine_url.clone())
+    }
+
+    /// Create client for a cluster's public block engine (or the localnet mock)
+    pub fn for_network(network: Network) -> Result<Self> {
+        Self::new(network.jito_block_engine_url().to_string())
+    }
+
     /// Create devnet client
     pub fn devnet() -> Result<Self> {
-        Self::new("https://frankfurt.devnet.block-engine.jito.wtf".to_string())
+        Self::for_network(Network::Devnet)
     }
 
     /// Create mainnet client  
     pub fn mainnet() -> Result<Self> {
-        Self::new("https://mainnet.block-engine.jito.wtf".to_string())
+        Self::for_network(Network::Mainnet)
+    }
+
+    /// Fail fast with `CircuitOpen` while this block engine keeps failing
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Inject RPC 429s (inside the circuit breaker) and bundle rejections
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<ChaosInjector>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Breaker state of this block engine, when one is attached
+    pub fn circuit_breaker(&self) -> Option<&Arc<CircuitBreaker>> {
+        self.breaker.as_ref()
     }
 
     /// Get the block engine URL
@@ -40,8 +83,54 @@ impl JitoClient {
         &self.block_engine_url
     }
 
+    /// JSON-RPC POST to the bundles endpoint, carrying the current trace context
+    fn bundles_request(&self) -> RequestBuilder {
+        let request = self.http_client.post(format!("{}/api/v1/bundles", self.block_engine_url));
+        match traceparent() {
+            Some(traceparent) => request.header(TRACEPARENT_HEADER, traceparent),
+            None => request,
+        }
+    }
+
+    /// POST a JSON-RPC request, through the circuit breaker when one is attached
+    ///
+    /// Only transport and decoding failures count against the breaker; a
+    /// JSON-RPC `error` (rejected bundle) is the block engine answering.
+    async fn post<Req: Serialize, Resp: DeserializeOwned>(&self, request: &Req, method: &str) -> Result<Resp> {
+        let call = async {
+            #[cfg(feature = "chaos")]
+            if let Some(ref chaos) = self.chaos {
+                chaos.inject(Fault::RpcRateLimit).await?;
+            }
+            self.bundles_request()
+                .json(request)
+                .send()
+                .await
+                .map_err(|e| SentinelError::RpcError(format!("{} request failed: {}", method, e)))?
+                .json::<Resp>()
+                .await
+                .map_err(|e| SentinelError::RpcError(format!("Failed to parse {} response: {}", method, e)))
+        };
+        match self.breaker {
+            Some(ref breaker) => breaker.call(call).await,
+            None => call.await,
+        }
+    }
+
     /// Simulate a bundle before sending
     pub async fn simulate_bundle(&self, transactions: &[Transaction]) -> Result<SimulationResult> {
+        self.simulate_bundle_with_accounts(transactions, &[]).await
+    }
+
+    /// Simulate a bundle and capture pre/post execution state of accounts
+    ///
+    /// `watched` pairs a transaction index with the account to snapshot around
+    /// it (e.g. the user's output token account for slippage checks).
+    pub async fn simulate_bundle_with_accounts(
+        &self,
+        transactions: &[Transaction],
+        watched: &[(usize, Pubkey)],
+    ) -> Result<SimulationResult> {
         let serialized_txs: Vec<String> = transactions
             .iter()
             .map(|tx| {
@@ -53,27 +142,37 @@ impl JitoClient {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        let params = if watched.is_empty() {
+            serde_json::json!([serialized_txs])
+        } else {
+            let mut account_configs = vec![serde_json::Value::Null; transactions.len()];
+            for (index, account) in watched {
+                if let Some(slot) = account_configs.get_mut(*index) {
+                    *slot = serde_json::json!({
+                        "addresses": [account.to_string()],
+                        "encoding": "base64",
+                    });
+                }
+            }
+            serde_json::json!([
+                { "encodedTransactions": serialized_txs },
+                {
+                    "preExecutionAccountsConfigs": account_configs,
+                    "postExecutionAccountsConfigs": account_configs,
+                }
+            ])
+        };
+
         let request = SimulateBundleRequest {
             jsonrpc: "2.0".to_string(),
             id: 1,
             method: "simulateBundle".to_string(),
-            params: vec![serialized_txs],
+            params,
         };
 
         info!("Simulating bundle with {} transactions", transactions.len());
 
-        let response = self
-            .http_client
-            .post(format!("{}/api/v1/bundles", self.block_engine_url))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Simulation request failed: {}", e)))?;
-
-        let result: SimulateBundleResponse = response
-            .json()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Failed to parse simulation: {}", e)))?;
+        let result: SimulateBundleResponse = self.post(&request, "simulateBundle").await?;
 
         if let Some(error) = result.error {
             return Err(SentinelError::BundleError(format!(
@@ -110,18 +209,11 @@ impl JitoClient {
             transactions.len()
         );
 
-        let response = self
-            .http_client
-            .post(format!("{}/api/v1/bundles", self.block_engine_url))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Send bundle failed: {}", e)))?;
-
-        let result: SendBundleResponse = response
-            .json()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Failed to parse response: {}", e)))?;
+        let result: SendBundleResponse = self.post(&request, "sendBundle").await?;
+        #[cfg(feature = "chaos")]
+        if let Some(ref chaos) = self.chaos {
+            chaos.inject(Fault::BundleRejection).await?;
+        }
 
         if let Some(error) = result.error {
             return Err(SentinelError::BundleError(format!(
@@ -153,17 +245,7 @@ impl JitoClient {
 
         debug!("Checking inflight status for {} bundles", bundle_ids.len());
 
-        let response = self
-            .http_client
-            .post(format!("{}/api/v1/bundles", self.block_engine_url))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Inflight status check failed: {}", e)))?;
-
-        let result: GetInflightBundleStatusesResponse = response.json().await.map_err(|e| {
-            SentinelError::RpcError(format!("Failed to parse inflight status: {}", e))
-        })?;
+        let result: GetInflightBundleStatusesResponse = self.post(&request, "getInflightBundleStatuses").await?;
 
         if let Some(error) = result.error {
             return Err(SentinelError::BundleError(format!(
@@ -186,18 +268,7 @@ impl JitoClient {
 
         debug!("Checking status for {} bundles", bundle_ids.len());
 
-        let response = self
-            .http_client
-            .post(format!("{}/api/v1/bundles", self.block_engine_url))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Status check failed: {}", e)))?;
-
-        let result: GetBundleStatusesResponse = response
-            .json()
-            .await
-            .map_err(|e| SentinelError::RpcError(format!("Failed to parse status: {}", e)))?;
+        let result: GetBundleStatusesResponse = self.post(&request, "getBundleStatuses").await?;
 
         if let Some(error) = result.error {
             return Err(SentinelError::BundleError(format!(
@@ -209,6 +280,36 @@ impl JitoClient {
         Ok(result.result.unwrap_or_default().value)
     }
 
+    /// Tip payment accounts the block engine currently accepts
+    pub async fn get_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getTipAccounts",
+            "params": [],
+        });
+
+        let result: GetTipAccountsResponse = self.post(&request, "getTipAccounts").await?;
+
+        if let Some(error) = result.error {
+            return Err(SentinelError::BundleError(format!(
+                "Tip accounts request failed: {}",
+                error.message
+            )));
+        }
+
+        result
+            .result
+            .unwrap_or_default()
+            .iter()
+            .map(|account| {
+                account
+                    .parse()
+                    .map_err(|e| SentinelError::RpcError(format!("Invalid tip account {}: {}", account, e)))
+            })
+            .collect()
+    }
+
     /// Wait for bundle to land or fail
     pub async fn wait_for_bundle(
         &self,
@@ -262,7 +363,7 @@ struct SimulateBundleRequest {
     jsonrpc: String,
     id: u64,
     method: String,
-    params: Vec<Vec<String>>,
+    params: serde_json::Value,
 }
 
 #[derive(Deserialize)]
@@ -286,6 +387,43 @@ pub struct TransactionResult {
     pub logs: Vec<String>,
     #[serde(default)]
     pub units_consumed: Option<u64>,
+    /// Watched account state before the transaction executed
+    #[serde(default, rename = "preExecutionAccounts")]
+    pub pre_execution_accounts: Option<Vec<SimulatedAccount>>,
+    /// Watched account state after the transaction executed
+    #[serde(default, rename = "postExecutionAccounts")]
+    pub post_execution_accounts: Option<Vec<SimulatedAccount>>,
+}
+
+impl TransactionResult {
+    /// Typed failure cause (None when the transaction succeeded)
+    pub fn failure(&self) -> Option<SimulationFailure> {
+        self.err.as_deref().map(|err| SimulationFailure::classify(err, &self.logs))
+    }
+}
+
+/// Account snapshot returned by simulateBundle (base64 encoding)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulatedAccount {
+    #[serde(default)]
+    pub lamports: u64,
+    #[serde(default)]
+    pub owner: String,
+    /// `[data, encoding]`
+    #[serde(default)]
+    pub data: Vec<String>,
+}
+
+impl SimulatedAccount {
+    /// SPL token account amount (bytes 64..72: mint, owner, amount)
+    pub fn token_amount(&self) -> Option<u64> {
+        use base64::engine::general_purpose::STANDARD as BASE64;
+        use base64::Engine;
+
+        let bytes = BASE64.decode(self.data.first()?).ok()?;
+        let amount: [u8; 8] = bytes.get(64..72)?.try_into().ok()?;
+        Some(u64::from_le_bytes(amount))
+    }
 }
 
 #[derive(Serialize)]
@@ -330,6 +468,12 @@ struct GetBundleStatusesResponse {
     error: Option<RpcError>,
 }
 
+#[derive(Deserialize)]
+struct GetTipAccountsResponse {
+    result: Option<Vec<String>>,
+    error: Option<RpcError>,
+}
+
 #[derive(Deserialize, Default)]
 struct BundleStatusesResult {
     value: Vec<BundleStatus>,
@@ -359,9 +503,67 @@ mod tests {
         assert!(client.block_engine_url().contains("devnet"));
     }
 
+    #[test]
+    fn test_client_from_config() {
+        let endpoints = EndpointConfig {
+            jito_block_engine_url: "http://localhost:1234".to_string(),
+            ..Default::default()
+        };
+        let client = JitoClient::from_config(&endpoints).unwrap();
+        assert_eq!(client.block_engine_url(), "http://localhost:1234");
+    }
+
     #[test]
     fn test_mainnet_client() {
         let client = JitoClient::mainnet().unwrap();
         assert!(client.block_engine_url().contains("mainnet"));
     }
+
+    #[tokio::test]
+    async fn test_unreachable_block_engine_opens_circuit() {
+        use sentinel_core::{CircuitBreakerSettings, CircuitState};
+
+        let settings = CircuitBreakerSettings {
+            min_calls: 2,
+            window: 2,
+            ..Default::default()
+        };
+        let breaker = Arc::new(CircuitBreaker::new("jito:local", settings));
+        // Nothing listens on the discard port
+        let client = JitoClient::new("http://127.0.0.1:9".to_string())
+            .unwrap()
+            .with_circuit_breaker(Arc::clone(&breaker));
+
+        for _ in 0..2 {
+            assert!(matches!(client.get_tip_accounts().await, Err(SentinelError::RpcError(_))));
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(
+            client.get_tip_accounts().await,
+            Err(SentinelError::CircuitOpen { ref dependency, .. }) if dependency == "jito:local"
+        ));
+    }
+
+    #[test]
+    fn test_bundle_requests_carry_traceparent() {
+        use opentelemetry::trace::TracerProvider as _;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let client = JitoClient::new("http://localhost:1234".to_string()).unwrap();
+        let request = client.bundles_request().build().unwrap();
+        assert!(request.headers().get(TRACEPARENT_HEADER).is_none());
+
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("bundle.submit");
+            sentinel_core::set_remote_parent(&span, "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01");
+            let _entered = span.enter();
+
+            let request = client.bundles_request().build().unwrap();
+            let header = request.headers()[TRACEPARENT_HEADER].to_str().unwrap();
+            assert!(header.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"), "{}", header);
+        });
+    }
 }
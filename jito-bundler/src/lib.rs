@@ -1,10 +1,25 @@
+pub mod block_listener;
 pub mod builder;
+pub mod dead_letter;
 pub mod jito_client;
+pub mod log_parser;
 pub mod protection;
 pub mod simulation;
+pub mod submitter;
+pub mod tip_floor;
 
-pub use jito_client::{BundleStatus, JitoClient, SimulationResult};
+pub use jito_client::{BundleStatus, JitoClient, SimulatedAccount, SimulationResult, TransactionResult};
+pub use log_parser::SimulationFailure;
 
+pub use block_listener::{
+    BlockListener, BundleOutcome, LandingStatus, ListenerStats, ObservedBlock, ObservedTransaction,
+    TrackedSubmission,
+};
+#[cfg(feature = "block-subscribe")]
+pub use block_listener::spawn_block_subscribe;
 pub use builder::{BundleBuilder, JitoBundle};
+pub use dead_letter::{is_retryable, DeadLetter, DeadLetterQueue};
 pub use protection::JitoDontFrontMarker;
 pub use simulation::BundleSimulator;
+pub use submitter::{BundleSubmitter, OutputCheck, SubmitOptions, DEFAULT_MAX_ATTEMPTS};
+pub use tip_floor::{parse_tip_floor, TipFloorClient};
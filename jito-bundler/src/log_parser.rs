@@ -0,0 +1,309 @@
+//! Simulation Failure Classification
+//!
+//! `simulateBundle` reports failures as an error string plus raw program
+//! logs. [`SimulationFailure::classify`] turns them into a typed cause so
+//! retry logic and user-facing errors can say what actually went wrong:
+//!
+//! - the runtime error (`InstructionError(0, Custom(6001))`, `AccountInUse`, ...)
+//! - `Program <id> failed: custom program error: 0x..` lines, naming the program
+//! - Anchor's `Error Code: <Name>. Error Number: <n>.` lines
+//! - well-known log text (`insufficient lamports`, `exceeded CUs meter`)
+//!
+//! Program error codes are mapped for the programs the router swaps through
+//! (Jupiter v6, Raydium AMM v4 / CLMM / CPMM, SPL Token); anything else is
+//! reported as a generic [`SimulationFailure::ProgramError`].
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const JUPITER_V6_PROGRAM: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+pub const RAYDIUM_AMM_V4_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const RAYDIUM_CLMM_PROGRAM: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+pub const RAYDIUM_CPMM_PROGRAM: &str = "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C";
+pub const SPL_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNTGpxsGrUX1Xp8KZXHxFzoS2o";
+pub const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+/// Typed cause of a failed simulation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SimulationFailure {
+    /// The swap would receive less than its minimum (price moved)
+    SlippageExceeded { program: String, code: Option<u32> },
+
+    /// Not enough SOL (fees, rent, transfers) or tokens for the trade
+    InsufficientFunds { program: Option<String> },
+
+    /// A writable account is locked by another in-flight transaction
+    AccountInUse,
+
+    /// The transaction's blockhash expired
+    BlockhashNotFound,
+
+    /// Ran out of compute units
+    ComputeBudgetExceeded,
+
+    /// Some other program error
+    ProgramError {
+        program: Option<String>,
+        code: u32,
+        name: Option<String>,
+    },
+
+    /// Unrecognized failure (raw runtime error)
+    Other(String),
+}
+
+impl SimulationFailure {
+    /// Classify a failed transaction from its runtime error and logs
+    pub fn classify(err: &str, logs: &[String]) -> Self {
+        if err.contains("AccountInUse") || err.contains("AccountLoadedTwice") {
+            return Self::AccountInUse;
+        }
+        if err.contains("BlockhashNotFound") {
+            return Self::BlockhashNotFound;
+        }
+        if err.contains("InsufficientFundsForFee") || err.contains("InsufficientFundsForRent") {
+            return Self::InsufficientFunds { program: None };
+        }
+        if err.contains("ComputationalBudgetExceeded") || logs.iter().any(|l| l.contains("exceeded CUs meter")) {
+            return Self::ComputeBudgetExceeded;
+        }
+
+        let failed = logs.iter().rev().find_map(|line| parse_program_failure(line));
+        let anchor_name = logs.iter().rev().find_map(|line| parse_anchor_error(line));
+        let code = failed.as_ref().and_then(|(_, code)| *code).or_else(|| parse_custom_code(err));
+        let program = failed.map(|(program, _)| program);
+
+        if logs.iter().any(|l| l.contains("insufficient lamports")) {
+            return Self::InsufficientFunds {
+                program: Some(SYSTEM_PROGRAM.to_string()),
+            };
+        }
+
+        match code {
+            Some(code) => known_error(program.as_deref(), code, anchor_name.as_deref()).unwrap_or(Self::ProgramError {
+                program,
+                code,
+                name: anchor_name,
+            }),
+            None => Self::Other(err.to_string()),
+        }
+    }
+
+    /// Resending the same signed transactions may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::AccountInUse)
+    }
+
+    /// A fresh quote, blockhash or compute budget may succeed
+    pub fn needs_rebuild(&self) -> bool {
+        matches!(
+            self,
+            Self::SlippageExceeded { .. } | Self::BlockhashNotFound | Self::ComputeBudgetExceeded
+        )
+    }
+
+    /// Short explanation suitable for end users
+    pub fn user_message(&self) -> &'static str {
+        match self {
+            Self::SlippageExceeded { .. } => "Price moved beyond your slippage tolerance",
+            Self::InsufficientFunds { .. } => "Insufficient balance to cover the trade and fees",
+            Self::AccountInUse => "A required account is busy; retrying shortly",
+            Self::BlockhashNotFound => "Transaction expired before it could land",
+            Self::ComputeBudgetExceeded => "Transaction ran out of compute",
+            Self::ProgramError { .. } | Self::Other(_) => "The transaction failed on-chain",
+        }
+    }
+}
+
+impl fmt::Display for SimulationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SlippageExceeded { program, code } => {
+                write!(f, "slippage exceeded ({}", program_label(program))?;
+                if let Some(code) = code {
+                    write!(f, " error {}", code)?;
+                }
+                write!(f, ")")
+            }
+            Self::InsufficientFunds { program: Some(program) } => {
+                write!(f, "insufficient funds ({})", program_label(program))
+            }
+            Self::InsufficientFunds { program: None } => write!(f, "insufficient funds"),
+            Self::AccountInUse => write!(f, "account in use"),
+            Self::BlockhashNotFound => write!(f, "blockhash not found"),
+            Self::ComputeBudgetExceeded => write!(f, "compute budget exceeded"),
+            Self::ProgramError { program, code, name } => {
+                let program = program.as_deref().map_or("unknown program", program_label);
+                match name {
+                    Some(name) => write!(f, "{} error {} ({})", program, code, name),
+                    None => write!(f, "{} error {}", program, code),
+                }
+            }
+            Self::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Human name for the programs we know
+pub fn program_label(program: &str) -> &str {
+    match program {
+        JUPITER_V6_PROGRAM => "Jupiter",
+        RAYDIUM_AMM_V4_PROGRAM => "Raydium AMM",
+        RAYDIUM_CLMM_PROGRAM => "Raydium CLMM",
+        RAYDIUM_CPMM_PROGRAM => "Raydium CPMM",
+        SPL_TOKEN_PROGRAM => "SPL Token",
+        SYSTEM_PROGRAM => "System",
+        other => other,
+    }
+}
+
+/// Program-specific error codes
+fn known_error(program: Option<&str>, code: u32, anchor_name: Option<&str>) -> Option<SimulationFailure> {
+    let slippage = |program: &str| SimulationFailure::SlippageExceeded {
+        program: program.to_string(),
+        code: Some(code),
+    };
+    let program = program?;
+    match (program, code) {
+        // SlippageToleranceExceeded
+        (JUPITER_V6_PROGRAM, 6001) => Some(slippage(program)),
+        // ExceededSlippage
+        (RAYDIUM_AMM_V4_PROGRAM, 30) => Some(slippage(program)),
+        // TooLittleOutputReceived / TooMuchInputPaid
+        (RAYDIUM_CLMM_PROGRAM, 6022 | 6023) => Some(slippage(program)),
+        // ExceededSlippage
+        (RAYDIUM_CPMM_PROGRAM, 6005) => Some(slippage(program)),
+        // TokenError::InsufficientFunds
+        (SPL_TOKEN_PROGRAM, 1) => Some(SimulationFailure::InsufficientFunds {
+            program: Some(program.to_string()),
+        }),
+        // SystemError::ResultWithNegativeLamports
+        (SYSTEM_PROGRAM, 1) => Some(SimulationFailure::InsufficientFunds {
+            program: Some(program.to_string()),
+        }),
+        _ if anchor_name.is_some_and(|name| name.contains("Slippage")) => Some(slippage(program)),
+        _ => None,
+    }
+}
+
+/// `Program <id> failed: custom program error: 0x1771` → (id, Some(6001))
+fn parse_program_failure(line: &str) -> Option<(String, Option<u32>)> {
+    let rest = line.strip_prefix("Program ")?;
+    let (program, reason) = rest.split_once(" failed: ")?;
+    let code = reason
+        .strip_prefix("custom program error: 0x")
+        .and_then(|hex| u32::from_str_radix(hex.trim(), 16).ok());
+    Some((program.to_string(), code))
+}
+
+/// `... Error Code: SlippageToleranceExceeded. Error Number: 6001. ...` → name
+fn parse_anchor_error(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("Error Code: ")?;
+    let name = rest.split(['.', ' ']).next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// `InstructionError(0, Custom(6001))` → 6001
+fn parse_custom_code(err: &str) -> Option<u32> {
+    let (_, rest) = err.split_once("Custom(")?;
+    rest.split(')').next()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_jupiter_slippage() {
+        let logs = logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: AnchorError occurred. Error Code: SlippageToleranceExceeded. Error Number: 6001. Error Message: Slippage tolerance exceeded.",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771",
+        ]);
+        let failure = SimulationFailure::classify("InstructionError(0, Custom(6001))", &logs);
+        assert_eq!(
+            failure,
+            SimulationFailure::SlippageExceeded {
+                program: JUPITER_V6_PROGRAM.to_string(),
+                code: Some(6001)
+            }
+        );
+        assert!(failure.needs_rebuild());
+        assert!(!failure.is_retryable());
+        assert_eq!(failure.to_string(), "slippage exceeded (Jupiter error 6001)");
+    }
+
+    #[test]
+    fn test_raydium_amm_slippage() {
+        let logs = logs(&["Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 failed: custom program error: 0x1e"]);
+        assert!(matches!(
+            SimulationFailure::classify("InstructionError(1, Custom(30))", &logs),
+            SimulationFailure::SlippageExceeded { code: Some(30), .. }
+        ));
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        let token = logs(&[
+            "Program log: Error: insufficient funds",
+            "Program TokenkegQfeZyiNwAJbNTGpxsGrUX1Xp8KZXHxFzoS2o failed: custom program error: 0x1",
+        ]);
+        assert_eq!(
+            SimulationFailure::classify("InstructionError(2, Custom(1))", &token),
+            SimulationFailure::InsufficientFunds {
+                program: Some(SPL_TOKEN_PROGRAM.to_string())
+            }
+        );
+
+        let sol = logs(&["Transfer: insufficient lamports 1000, need 5000"]);
+        assert!(matches!(
+            SimulationFailure::classify("InstructionError(0, Custom(1))", &sol),
+            SimulationFailure::InsufficientFunds { .. }
+        ));
+        assert_eq!(
+            SimulationFailure::classify("InsufficientFundsForFee", &[]),
+            SimulationFailure::InsufficientFunds { program: None }
+        );
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let in_use = SimulationFailure::classify("AccountInUse", &[]);
+        assert_eq!(in_use, SimulationFailure::AccountInUse);
+        assert!(in_use.is_retryable());
+
+        assert_eq!(SimulationFailure::classify("BlockhashNotFound", &[]), SimulationFailure::BlockhashNotFound);
+        let cu = logs(&["Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 200000 of 200000 compute units", "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: exceeded CUs meter at BPF instruction"]);
+        assert_eq!(
+            SimulationFailure::classify("InstructionError(0, ProgramFailedToComplete)", &cu),
+            SimulationFailure::ComputeBudgetExceeded
+        );
+    }
+
+    #[test]
+    fn test_unknown_program_error_keeps_details() {
+        let logs = logs(&[
+            "Program log: AnchorError occurred. Error Code: PoolPaused. Error Number: 6010. Error Message: paused.",
+            "Program Prog1111111111111111111111111111111111111 failed: custom program error: 0x177a",
+        ]);
+        let failure = SimulationFailure::classify("InstructionError(0, Custom(6010))", &logs);
+        assert_eq!(
+            failure,
+            SimulationFailure::ProgramError {
+                program: Some("Prog1111111111111111111111111111111111111".to_string()),
+                code: 6010,
+                name: Some("PoolPaused".to_string())
+            }
+        );
+
+        assert_eq!(
+            SimulationFailure::classify("InvalidAccountForFee", &[]),
+            SimulationFailure::Other("InvalidAccountForFee".to_string())
+        );
+    }
+}
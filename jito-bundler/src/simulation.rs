@@ -3,6 +3,7 @@ use tracing::{info, warn};
 
 use crate::builder::JitoBundle;
 use crate::jito_client::JitoClient;
+use crate::log_parser::SimulationFailure;
 
 /// Production-ready bundle simulator using JitoClient
 pub struct BundleSimulator {
@@ -24,6 +25,13 @@ impl BundleSimulator {
         })
     }
 
+    /// Create new simulator for a cluster's block engine
+    pub fn for_network(network: sentinel_core::Network) -> sentinel_core::Result<Self> {
+        Ok(Self {
+            client: JitoClient::for_network(network)?,
+        })
+    }
+
     /// Create new simulator with custom endpoint
     pub fn new(block_engine_url: String) -> sentinel_core::Result<Self> {
         Ok(Self {
@@ -47,6 +55,7 @@ impl BundleSimulator {
             jito_result.results.is_empty() || jito_result.results.iter().all(|r| r.err.is_none());
 
         let error = jito_result.results.iter().find_map(|r| r.err.clone());
+        let failure = jito_result.results.iter().find_map(|r| r.failure());
 
         let logs: Vec<String> = jito_result
             .results
@@ -63,6 +72,7 @@ impl BundleSimulator {
         let result = SimulationResult {
             success,
             error,
+            failure,
             logs,
             compute_units_consumed,
         };
@@ -73,7 +83,10 @@ impl BundleSimulator {
                 result.compute_units_consumed
             );
         } else {
-            warn!("Bundle simulation failed: {:?}", result.error);
+            match &result.failure {
+                Some(failure) => warn!("Bundle simulation failed: {}", failure),
+                None => warn!("Bundle simulation failed: {:?}", result.error),
+            }
         }
 
         Ok(result)
@@ -84,6 +97,8 @@ impl BundleSimulator {
 pub struct SimulationResult {
     pub success: bool,
     pub error: Option<String>,
+    /// Classified cause of the first failing transaction
+    pub failure: Option<SimulationFailure>,
     pub logs: Vec<String>,
     pub compute_units_consumed: u64,
 }
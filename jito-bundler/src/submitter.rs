@@ -0,0 +1,840 @@
+//! Simulation-Gated Bundle Submission
+//!
+//! `BundleSubmitter` enforces simulate-before-send: a bundle is only forwarded
+//! to the block engine when every transaction simulates cleanly and, if an
+//! [`OutputCheck`] is supplied, the user's (victim) transaction still receives
+//! at least `minimum_received` of the output token. Rejections carry the
+//! per-transaction simulation logs. Power users can bypass the gate with an
+//! explicit `force` flag.
+//!
+//! An attached [`SafetyController`] refuses all bundle submission while the kill
+//! switch is engaged and turns `submit` into a simulation-only dry run in
+//! no-submit mode.
+//!
+//! [`BundleSubmitter::submit_intent`] adds retries for transient failures and,
+//! with a [`DeadLetterQueue`] attached, parks submissions that fail for good
+//! so operators can replay them. With a [`Coordination`] attached, the
+//! instance claims the intent first and refuses intents any instance already
+//! submitted, so routers behind a load balancer never send one twice.
+//! With a [`LeaderGuard`] attached, the first attempt is held until the target
+//! window has no known malicious leader (`delay` policy); otherwise the bundle,
+//! already atomic, goes out as the guard's forced JitoBundle. With a
+//! [`SubmissionTimer`] attached, the first attempt then waits (or goes out
+//! early) so the bundle lands in a low-risk leader's slot.
+//!
+//! [`BundleSubmitter::submit_passthrough`] routes a wallet-signed Jupiter
+//! passthrough transaction (see `sentinel_core::passthrough`) as a
+//! single-transaction bundle, with the plan's output floor as the check.
+
+use sentinel_core::{
+    ChainClock, Coordination, Intent, LatencyTracer, LeaderDecision, LeaderGuard, PassthroughPlan, PipelineStage,
+    Result, RouteType, SafetyController, SentinelError, SubmissionTimer, SubmissionTiming, SwapDetails,
+    UpcomingLeaders,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::builder::JitoBundle;
+use crate::dead_letter::{is_retryable, DeadLetter, DeadLetterQueue};
+use crate::jito_client::{JitoClient, SimulatedAccount, SimulationResult};
+use crate::log_parser::SimulationFailure;
+
+/// Logs included per transaction in a rejection message
+const MAX_LOG_LINES: usize = 20;
+
+/// Bundle id returned when no-submit mode skips the send
+pub const DRY_RUN_BUNDLE_ID: &str = "dry-run";
+
+/// Minimum output the victim transaction must realize in simulation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputCheck {
+    /// Bundle index of the user's transaction
+    pub transaction_index: usize,
+
+    /// User's output token account
+    pub output_token_account: Pubkey,
+
+    /// Minimum output amount (atoms)
+    pub minimum_received: u64,
+}
+
+impl OutputCheck {
+    pub fn new(transaction_index: usize, output_token_account: Pubkey, minimum_received: u64) -> Self {
+        Self {
+            transaction_index,
+            output_token_account,
+            minimum_received,
+        }
+    }
+
+    /// Build from intent swap details (None when no `minimum_received` is set)
+    pub fn from_swap(
+        details: &SwapDetails,
+        transaction_index: usize,
+        output_token_account: Pubkey,
+    ) -> Option<Self> {
+        details
+            .minimum_received
+            .map(|min| Self::new(transaction_index, output_token_account, min))
+    }
+}
+
+/// Per-submission options
+#[derive(Debug, Clone, Default)]
+pub struct SubmitOptions {
+    /// Slippage guard for the user's transaction
+    pub output_check: Option<OutputCheck>,
+
+    /// Skip simulation entirely (explicit power-user override)
+    pub force: bool,
+}
+
+impl SubmitOptions {
+    pub fn with_output_check(mut self, check: OutputCheck) -> Self {
+        self.output_check = Some(check);
+        self
+    }
+
+    pub fn force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+}
+
+/// Attempts per intent submission when retries are not configured
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Backoff before the first retry (doubles per attempt)
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Nominal slot duration, for bounding leader-guard holds
+const SLOT_TIME_MS: u64 = 400;
+
+/// Chain clock age beyond which the current slot is not trusted for leader checks
+const MAX_CLOCK_AGE: Duration = Duration::from_millis(4 * SLOT_TIME_MS);
+
+/// Malicious-leader gate in front of the first attempt
+struct LeaderGate {
+    guard: Arc<LeaderGuard>,
+    chain: ChainClock,
+    leaders: RwLock<Option<UpcomingLeaders>>,
+}
+
+impl LeaderGate {
+    /// Wait out a `Delay` decision for the window starting at the current slot
+    async fn hold(&self, intent: &Intent) -> Option<LeaderDecision> {
+        let leaders = self.leaders.read().unwrap_or_else(|p| p.into_inner()).clone()?;
+        if self.chain.is_stale(MAX_CLOCK_AGE) {
+            warn!("Chain clock stale: submitting intent {} without a leader check", intent.intent_id);
+            return None;
+        }
+
+        let current_slot = self.chain.slot();
+        let decision = self.guard.evaluate(intent, RouteType::JitoBundle, current_slot, &leaders);
+        if let LeaderDecision::Delay { until_slot, .. } = decision {
+            // Bounded at twice the nominal duration, in case the clock stops advancing
+            let bound = Duration::from_millis(until_slot.saturating_sub(current_slot) * SLOT_TIME_MS * 2);
+            let mut updates = self.chain.subscribe();
+            let reached = tokio::time::timeout(bound, updates.wait_for(|state| state.slot >= until_slot))
+                .await
+                .is_ok_and(|update| update.is_ok());
+            if !reached {
+                warn!("Slot {} not observed in time: submitting intent {} now", until_slot, intent.intent_id);
+            }
+        }
+        Some(decision)
+    }
+}
+
+/// What the last simulation said about a submission
+#[derive(Debug, Default)]
+struct SimulationCapture {
+    logs: Vec<Vec<String>>,
+    failure: Option<SimulationFailure>,
+}
+
+/// Enforced simulate-then-send submission
+pub struct BundleSubmitter {
+    client: JitoClient,
+    safety: Option<SafetyController>,
+    dead_letters: Option<Arc<DeadLetterQueue>>,
+    coordination: Option<Arc<Coordination>>,
+    timer: Option<Arc<SubmissionTimer>>,
+    leader_gate: Option<LeaderGate>,
+    max_attempts: u32,
+}
+
+impl BundleSubmitter {
+    pub fn new(client: JitoClient) -> Self {
+        Self {
+            client,
+            safety: None,
+            dead_letters: None,
+            coordination: None,
+            timer: None,
+            leader_gate: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    /// Park submissions that fail for good
+    pub fn with_dead_letters(mut self, queue: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = Some(queue);
+        self
+    }
+
+    /// Attempts per `submit_intent` call (retryable failures only)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Claim intents and record submissions in state shared with other instances
+    pub fn with_coordination(mut self, coordination: Arc<Coordination>) -> Self {
+        self.coordination = Some(coordination);
+        self
+    }
+
+    /// Time the first attempt of `submit_intent` around the leader schedule
+    pub fn with_submission_timer(mut self, timer: Arc<SubmissionTimer>) -> Self {
+        self.timer = Some(timer);
+        self
+    }
+
+    /// Hold `submit_intent` while a malicious leader leads the target window
+    ///
+    /// `chain` supplies the current slot; the schedule comes from `set_leaders`.
+    pub fn with_leader_guard(mut self, guard: Arc<LeaderGuard>, chain: ChainClock) -> Self {
+        self.leader_gate = Some(LeaderGate {
+            guard,
+            chain,
+            leaders: RwLock::new(None),
+        });
+        self
+    }
+
+    /// Replace the leader schedule slice the leader guard checks (`getSlotLeaders`)
+    pub fn set_leaders(&self, leaders: UpcomingLeaders) {
+        if let Some(gate) = &self.leader_gate {
+            *gate.leaders.write().unwrap_or_else(|p| p.into_inner()) = Some(leaders);
+        }
+    }
+
+    /// Honor the kill switch and no-submit mode
+    pub fn with_safety(mut self, safety: SafetyController) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+
+    pub fn client(&self) -> &JitoClient {
+        &self.client
+    }
+
+    /// Validate, simulate, check the policy, then send
+    pub async fn submit(&self, bundle: &JitoBundle, options: &SubmitOptions) -> Result<String> {
+        self.submit_capturing(bundle, options, &mut SimulationCapture::default()).await
+    }
+
+    /// Submit an intent's bundle, retrying transient failures
+    ///
+    /// Transport errors and simulation failures that resending can fix
+    /// ([`SimulationFailure::is_retryable`]) are retried. When every attempt
+    /// fails, or one fails non-retryably, the submission is dead-lettered (if
+    /// a queue is attached) and the last error returned.
+    ///
+    /// With coordination attached, fails with `ClaimConflict` while another
+    /// instance owns the intent and refuses intents already submitted. The
+    /// claim is renewed and the ledger rechecked before every send, since
+    /// leader holds, timing waits and retry backoff can outlast the lease.
+    #[tracing::instrument(name = "bundle.submit", skip_all, fields(intent_id = %intent.intent_id))]
+    pub async fn submit_intent(&self, intent: &Intent, bundle: &JitoBundle, options: &SubmitOptions) -> Result<String> {
+        let Some(coordination) = self.coordination.clone() else {
+            return self.submit_with_retries(intent, bundle, options).await;
+        };
+        let intent_id = intent.intent_id.clone();
+        let submitted = format!("submitted:{}", intent_id);
+
+        let result = match self.claim_for_send(&intent_id).await {
+            Ok(()) => self.submit_with_retries(intent, bundle, options).await,
+            Err(e) => Err(e),
+        };
+
+        if result.is_ok() {
+            if let Err(e) = coordinate(&coordination, move |c| c.record_once(&submitted)).await {
+                warn!("Failed to record submission of intent {}: {}", intent.intent_id, e);
+            }
+        }
+        let id = intent_id.clone();
+        if let Err(e) = coordinate(&coordination, move |c| c.release_intent(&id)).await {
+            warn!("Failed to release claim on intent {}: {}", intent_id, e);
+        }
+        result
+    }
+
+    /// [`Self::submit_intent`], recording the submission stage on a request's latency tracer
+    ///
+    /// The stage covers leader holds, retries and dead-lettering, and is
+    /// recorded whether or not the submission succeeds.
+    pub async fn submit_intent_traced(
+        &self,
+        intent: &Intent,
+        bundle: &JitoBundle,
+        options: &SubmitOptions,
+        tracer: &mut LatencyTracer,
+    ) -> Result<String> {
+        let start = Instant::now();
+        let result = self.submit_intent(intent, bundle, options).await;
+        tracer.record(PipelineStage::Submit, start.elapsed());
+        result
+    }
+
+    /// Claim (or renew the claim on) an intent, then check no instance submitted it
+    ///
+    /// Claiming before checking the ledger means no other instance can submit
+    /// in between. No-op without coordination.
+    async fn claim_for_send(&self, intent_id: &str) -> Result<()> {
+        let Some(coordination) = &self.coordination else {
+            return Ok(());
+        };
+        let id = intent_id.to_string();
+        coordinate(coordination, move |c| c.claim_intent(&id)).await?;
+        let key = format!("submitted:{}", intent_id);
+        if coordinate(coordination, move |c| c.is_recorded(&key)).await? {
+            return Err(SentinelError::InvalidIntent(format!("Intent {} was already submitted", intent_id)));
+        }
+        Ok(())
+    }
+
+    async fn submit_with_retries(&self, intent: &Intent, bundle: &JitoBundle, options: &SubmitOptions) -> Result<String> {
+        let mut errors = Vec::new();
+        let mut capture = SimulationCapture::default();
+
+        if let Some(gate) = &self.leader_gate {
+            gate.hold(intent).await;
+        }
+        if let Some(timer) = &self.timer {
+            let timing = timer.plan_now();
+            if let SubmissionTiming::Avoid { slot, leader, risk } = &timing {
+                // Nothing clean is reachable; the bundle is atomic, so send as planned
+                warn!(
+                    "Intent {} will land in slot {} of risky leader {} ({:.2})",
+                    intent.intent_id, slot, leader, risk
+                );
+            }
+            timer.wait(&timing).await;
+        }
+
+        for attempt in 1..=self.max_attempts {
+            // Losing the intent to another instance is not a failed submission
+            self.claim_for_send(&intent.intent_id).await?;
+            capture.failure = None;
+            let error = match self.submit_capturing(bundle, options, &mut capture).await {
+                Ok(bundle_id) => return Ok(bundle_id),
+                Err(e) => e,
+            };
+            errors.push(format!("attempt {}: {}", attempt, error));
+
+            let retryable = match &capture.failure {
+                Some(failure) => failure.is_retryable(),
+                None => is_retryable(&error),
+            };
+            if !retryable || attempt == self.max_attempts {
+                self.dead_letter(intent, bundle, capture, errors);
+                return Err(error);
+            }
+            warn!("Bundle attempt {} for intent {} failed, retrying: {}", attempt, intent.intent_id, error);
+            tokio::time::sleep(RETRY_BACKOFF * 2u32.pow(attempt - 1)).await;
+        }
+
+        unreachable!("max_attempts is at least 1")
+    }
+
+    /// Submit the user-signed transaction of a passthrough plan
+    ///
+    /// The signature must cover exactly the planned message (tip, marker and
+    /// compute budget included). The transaction carries its own tip, so it
+    /// goes out alone, gated on the plan's `minimum_received`.
+    pub async fn submit_passthrough(&self, plan: &PassthroughPlan, signed: Transaction) -> Result<String> {
+        if !plan.route.pays_tip() {
+            return Err(SentinelError::BundleError(format!(
+                "Passthrough routed {:?} does not go through the block engine",
+                plan.route
+            )));
+        }
+        plan.check_signed(&signed)?;
+
+        let bundle = JitoBundle {
+            transactions: vec![signed],
+            bundle_id: None,
+        };
+        let options = SubmitOptions::default().with_output_check(OutputCheck::new(
+            0,
+            plan.output_token_account,
+            plan.minimum_received,
+        ));
+        self.submit_intent(&plan.intent, &bundle, &options).await
+    }
+
+    /// Resubmit a dead letter; removed from the queue on success
+    pub async fn replay(&self, id: &str, options: &SubmitOptions) -> Result<String> {
+        let queue = self
+            .dead_letters
+            .as_ref()
+            .ok_or_else(|| SentinelError::ConfigError("No dead-letter queue attached".to_string()))?;
+        let mut letter = queue
+            .get(id)?
+            .ok_or_else(|| SentinelError::BundleError(format!("No dead letter {}", id)))?;
+
+        let bundle = JitoBundle {
+            transactions: letter.decode_transactions()?,
+            bundle_id: None,
+        };
+        let mut capture = SimulationCapture::default();
+        match self.submit_capturing(&bundle, options, &mut capture).await {
+            Ok(bundle_id) => {
+                queue.remove(id)?;
+                info!("📬 Replayed dead letter {} as bundle {}", id, bundle_id);
+                Ok(bundle_id)
+            }
+            Err(e) => {
+                letter.replays += 1;
+                letter.errors.push(format!("replay {}: {}", letter.replays, e));
+                if !capture.logs.is_empty() {
+                    letter.simulation_logs = capture.logs;
+                    letter.failure = capture.failure;
+                }
+                queue.push(&letter)?;
+                Err(e)
+            }
+        }
+    }
+
+    fn dead_letter(&self, intent: &Intent, bundle: &JitoBundle, capture: SimulationCapture, errors: Vec<String>) {
+        let Some(queue) = &self.dead_letters else {
+            return;
+        };
+        let letter = DeadLetter::new(intent, RouteType::JitoBundle, &bundle.transactions, queue.now_ms())
+            .map(|letter| {
+                letter
+                    .with_simulation_logs(capture.logs)
+                    .with_failure(capture.failure)
+                    .with_errors(errors)
+            });
+        // The submission already failed; a queue error must not mask that failure
+        if let Err(e) = letter.and_then(|letter| queue.push(&letter)) {
+            warn!("Failed to dead-letter intent {}: {}", intent.intent_id, e);
+        }
+    }
+
+    /// `submit`, recording simulation logs and the failure cause when a simulation ran
+    async fn submit_capturing(&self, bundle: &JitoBundle, options: &SubmitOptions, capture: &mut SimulationCapture) -> Result<String> {
+        bundle.validate()?;
+        self.check_kill_switch()?;
+        let dry_run = self.safety.as_ref().is_some_and(|s| s.no_submit());
+
+        if options.force && !dry_run {
+            warn!(
+                "⚠️  Forced submission: skipping simulation for {}-tx bundle",
+                bundle.transactions.len()
+            );
+            return self.client.send_bundle(&bundle.transactions).await;
+        }
+
+        let watched: Vec<(usize, Pubkey)> = options
+            .output_check
+            .iter()
+            .map(|c| (c.transaction_index, c.output_token_account))
+            .collect();
+
+        let simulation = self
+            .client
+            .simulate_bundle_with_accounts(&bundle.transactions, &watched)
+            .await?;
+        capture.logs = simulation.results.iter().map(|r| r.logs.clone()).collect();
+        capture.failure = simulation.results.iter().find_map(|r| r.failure());
+
+        enforce_policy(&simulation, options.output_check.as_ref())?;
+
+        if dry_run {
+            info!("🧪 No-submit mode: bundle passed simulation, not sending");
+            return Ok(DRY_RUN_BUNDLE_ID.to_string());
+        }
+
+        info!("✅ Simulation passed policy, submitting bundle");
+        self.client.send_bundle(&bundle.transactions).await
+    }
+
+    fn check_kill_switch(&self) -> Result<()> {
+        match self.safety {
+            Some(ref safety) if safety.kill_switch() => Err(SentinelError::BundleError(
+                "Kill switch engaged: bundle submission disabled, route via StandardRpc".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reject failed simulations and victim outputs below `minimum_received`
+pub fn enforce_policy(simulation: &SimulationResult, output_check: Option<&OutputCheck>) -> Result<()> {
+    if let Some((index, err)) = simulation
+        .results
+        .iter()
+        .enumerate()
+        .find_map(|(i, r)| r.err.as_ref().map(|e| (i, e)))
+    {
+        let failure = SimulationFailure::classify(err, &simulation.results[index].logs);
+        return Err(rejection(
+            simulation,
+            format!("transaction {} failed simulation: {} ({})", index, failure, err),
+        ));
+    }
+
+    let Some(check) = output_check else {
+        return Ok(());
+    };
+
+    let result = simulation.results.get(check.transaction_index).ok_or_else(|| {
+        rejection(
+            simulation,
+            format!("no simulation result for transaction {}", check.transaction_index),
+        )
+    })?;
+
+    let post = first_token_amount(&result.post_execution_accounts).ok_or_else(|| {
+        rejection(
+            simulation,
+            format!(
+                "simulation did not return output account {}",
+                check.output_token_account
+            ),
+        )
+    })?;
+    // Missing pre-state means the account did not exist yet (created in-tx)
+    let pre = first_token_amount(&result.pre_execution_accounts).unwrap_or(0);
+    let received = post.saturating_sub(pre);
+
+    if received < check.minimum_received {
+        return Err(rejection(
+            simulation,
+            format!(
+                "transaction {} receives {} < minimum_received {}",
+                check.transaction_index, received, check.minimum_received
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn first_token_amount(accounts: &Option<Vec<SimulatedAccount>>) -> Option<u64> {
+    accounts.as_ref()?.first()?.token_amount()
+}
+
+fn rejection(simulation: &SimulationResult, reason: String) -> SentinelError {
+    let mut message = format!("Simulation rejected bundle: {}", reason);
+
+    for (i, result) in simulation.results.iter().enumerate() {
+        message.push_str(&format!("\n  tx[{}] err={:?}", i, result.err));
+        let skip = result.logs.len().saturating_sub(MAX_LOG_LINES);
+        for line in &result.logs[skip..] {
+            message.push_str("\n    ");
+            message.push_str(line);
+        }
+    }
+
+    warn!("🚫 {}", message);
+    SentinelError::BundleError(message)
+}
+
+/// Run a blocking coordination call off the async runtime
+async fn coordinate<T: Send + 'static>(
+    coordination: &Arc<Coordination>,
+    call: impl FnOnce(&Coordination) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let coordination = Arc::clone(coordination);
+    tokio::task::spawn_blocking(move || call(&coordination))
+        .await
+        .map_err(|e| SentinelError::BundleError(format!("Coordination task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+
+    fn token_account_json(amount: u64) -> serde_json::Value {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        serde_json::json!({
+            "lamports": 2_039_280,
+            "owner": "TokenkegQfeZyiNwAJbNTGpxsGrUX1Xp8KZXHxFzoS2o",
+            "data": [BASE64.encode(&data), "base64"],
+        })
+    }
+
+    fn simulation(err: Option<&str>, pre: u64, post: u64) -> SimulationResult {
+        serde_json::from_value(serde_json::json!({
+            "summary": "succeeded",
+            "results": [
+                {
+                    "err": err,
+                    "logs": ["Program JUP6Lk invoke [1]", "Program log: Instruction: Route"],
+                    "preExecutionAccounts": [token_account_json(pre)],
+                    "postExecutionAccounts": [token_account_json(post)],
+                },
+                { "err": null, "logs": ["Program 11111111111111111111111111111111 success"] }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn check(minimum_received: u64) -> OutputCheck {
+        OutputCheck::new(0, Pubkey::new_unique(), minimum_received)
+    }
+
+    #[test]
+    fn test_output_above_minimum_passes() {
+        let sim = simulation(None, 1_000, 951_000);
+        assert!(enforce_policy(&sim, Some(&check(950_000))).is_ok());
+        assert!(enforce_policy(&sim, None).is_ok());
+    }
+
+    #[test]
+    fn test_output_below_minimum_rejected_with_logs() {
+        let sim = simulation(None, 0, 900_000);
+        let err = enforce_policy(&sim, Some(&check(950_000))).unwrap_err().to_string();
+
+        assert!(err.contains("receives 900000 < minimum_received 950000"));
+        assert!(err.contains("tx[0]"));
+        assert!(err.contains("Instruction: Route"));
+    }
+
+    #[test]
+    fn test_failed_transaction_rejected() {
+        let sim = simulation(Some("InstructionError(0, Custom(6001))"), 0, 0);
+        let err = enforce_policy(&sim, None).unwrap_err().to_string();
+        assert!(err.contains("transaction 0 failed simulation"));
+        assert!(err.contains("error 6001"));
+    }
+
+    #[test]
+    fn test_kill_switch_blocks_submission() {
+        let safety = SafetyController::default();
+        let submitter = BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap())
+            .with_safety(safety.clone());
+        assert!(submitter.check_kill_switch().is_ok());
+
+        safety.set_kill_switch(true);
+        let err = submitter.check_kill_switch().unwrap_err().to_string();
+        assert!(err.contains("Kill switch"));
+    }
+
+    fn queue() -> Arc<DeadLetterQueue> {
+        let dir = std::env::temp_dir().join(format!("submitter-dlq-{}", Pubkey::new_unique()));
+        Arc::new(DeadLetterQueue::open(dir).unwrap())
+    }
+
+    fn intent() -> Intent {
+        Intent {
+            intent_id: "intent-1".to_string(),
+            swap_details: None,
+            ..Intent::test_swap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_failure_dead_lettered() {
+        let queue = queue();
+        let submitter = BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap())
+            .with_dead_letters(queue.clone());
+
+        let err = submitter
+            .submit_intent(&intent(), &JitoBundle::new(), &SubmitOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SentinelError::BundleError(_)));
+
+        let letters = queue.list().unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].intent.intent_id, "intent-1");
+        assert_eq!(letters[0].errors.len(), 1, "non-retryable errors are not retried");
+        assert!(letters[0].errors[0].contains("at least one transaction"));
+        std::fs::remove_dir_all(queue.dir()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_submit_traced_records_stage_on_failure() {
+        let submitter = BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap());
+        let mut tracer = LatencyTracer::new();
+
+        let result = submitter
+            .submit_intent_traced(&intent(), &JitoBundle::new(), &SubmitOptions::default(), &mut tracer)
+            .await;
+        assert!(result.is_err());
+        assert!(tracer.stage(PipelineStage::Submit).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_coordinated_submission_claims_and_refuses_resubmission() {
+        use sentinel_core::{CoordinationSettings, MemoryCoordinator};
+
+        let shared = Arc::new(MemoryCoordinator::new());
+        let instance = |id: &str| {
+            let settings = CoordinationSettings {
+                instance_id: Some(id.to_string()),
+                ..Default::default()
+            };
+            Arc::new(Coordination::new(shared.clone(), &settings))
+        };
+        let (a, b) = (instance("a"), instance("b"));
+        let queue = queue();
+        let submitter = BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap())
+            .with_dead_letters(queue.clone())
+            .with_coordination(b);
+
+        // Instance A is executing the intent
+        a.claim_intent("intent-1").unwrap();
+        let err = submitter
+            .submit_intent(&intent(), &JitoBundle::new(), &SubmitOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SentinelError::ClaimConflict { ref owner, .. } if owner == "a"));
+
+        // ... and submitted it
+        a.record_once("submitted:intent-1").unwrap();
+        a.release_intent("intent-1").unwrap();
+        let err = submitter
+            .submit_intent(&intent(), &JitoBundle::new(), &SubmitOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already submitted"));
+        assert_eq!(a.intent_owner("intent-1").unwrap(), None, "claim released after refusing");
+
+        assert!(queue.list().unwrap().is_empty());
+        std::fs::remove_dir_all(queue.dir()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_claim_renewed_before_sending_after_a_hold() {
+        use sentinel_core::{ChainSource, CoordinationSettings, LeaderGuardSettings, ManualClock, MemoryCoordinator};
+
+        let shared = Arc::new(MemoryCoordinator::new());
+        let clock = Arc::new(ManualClock::at_timestamp(1_700_000_000));
+        let instance = |id: &str| {
+            let settings = CoordinationSettings {
+                instance_id: Some(id.to_string()),
+                claim_ttl_ms: 1_000,
+                ..Default::default()
+            };
+            Arc::new(Coordination::new(shared.clone(), &settings).with_clock(clock.clone()))
+        };
+        let (a, b) = (instance("a"), instance("b"));
+
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let guard = Arc::new(LeaderGuard::new(LeaderGuardSettings::default()));
+        guard.mark_malicious(bad, 0.95);
+        let chain = ChainClock::new();
+        chain.observe_slot(100, ChainSource::Websocket);
+        let queue = queue();
+        let submitter = Arc::new(
+            BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap())
+                .with_dead_letters(queue.clone())
+                .with_leader_guard(guard, chain.clone())
+                .with_coordination(b),
+        );
+        submitter.set_leaders(UpcomingLeaders::new(100, [[bad; 4], [good; 4], [good; 4]].concat()));
+
+        let submission = tokio::spawn({
+            let submitter = Arc::clone(&submitter);
+            async move { submitter.submit_intent(&intent(), &JitoBundle::new(), &SubmitOptions::default()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(a.intent_owner("intent-1").unwrap().as_deref(), Some("b"));
+
+        // B's lease lapses during the hold and A takes the intent over
+        clock.advance(Duration::from_millis(1_001));
+        a.claim_intent("intent-1").unwrap();
+        chain.observe_slot(104, ChainSource::Websocket);
+
+        let err = tokio::time::timeout(Duration::from_secs(1), submission).await.unwrap().unwrap().unwrap_err();
+        assert!(matches!(err, SentinelError::ClaimConflict { ref owner, .. } if owner == "a"));
+        assert_eq!(a.intent_owner("intent-1").unwrap().as_deref(), Some("a"), "A's claim survives");
+        assert!(queue.list().unwrap().is_empty(), "nothing was sent, so nothing is dead-lettered");
+        std::fs::remove_dir_all(queue.dir()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_failed_replay_stays_queued() {
+        let queue = queue();
+        let safety = SafetyController::default();
+        safety.set_kill_switch(true);
+        let submitter = BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap())
+            .with_safety(safety)
+            .with_dead_letters(queue.clone());
+
+        let letter = DeadLetter::new(&intent(), RouteType::JitoBundle, &[], 1_000).unwrap();
+        queue.push(&letter).unwrap();
+
+        assert!(submitter.replay(&letter.id, &SubmitOptions::default()).await.is_err());
+        let stored = queue.get(&letter.id).unwrap().unwrap();
+        assert_eq!(stored.replays, 1);
+        assert!(stored.last_error().unwrap().starts_with("replay 1:"));
+
+        assert!(submitter.replay("missing", &SubmitOptions::default()).await.is_err());
+        std::fs::remove_dir_all(queue.dir()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_leader_guard_holds_submission_past_malicious_leader() {
+        use sentinel_core::{ChainSource, LeaderGuardSettings};
+
+        let (good, bad) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let guard = Arc::new(LeaderGuard::new(LeaderGuardSettings::default()));
+        guard.mark_malicious(bad, 0.95);
+        let chain = ChainClock::new();
+        chain.observe_slot(100, ChainSource::Websocket);
+        let submitter = Arc::new(
+            BundleSubmitter::new(JitoClient::new("http://localhost:1".to_string()).unwrap())
+                .with_leader_guard(guard, chain.clone()),
+        );
+        // Slots 100-103 are led by the sandwicher
+        submitter.set_leaders(UpcomingLeaders::new(100, [[bad; 4], [good; 4], [good; 4]].concat()));
+
+        let held = tokio::spawn({
+            let submitter = Arc::clone(&submitter);
+            async move { submitter.leader_gate.as_ref().unwrap().hold(&intent()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!held.is_finished(), "submission went out under the malicious leader");
+
+        chain.observe_slot(104, ChainSource::Websocket);
+        let decision = tokio::time::timeout(Duration::from_secs(1), held).await.unwrap().unwrap();
+        assert!(matches!(decision, Some(LeaderDecision::Delay { until_slot: 104, leader, .. }) if leader == bad));
+
+        // A clean window goes straight through
+        let decision = submitter.leader_gate.as_ref().unwrap().hold(&intent()).await;
+        assert_eq!(decision, Some(LeaderDecision::Proceed { route: RouteType::JitoBundle }));
+    }
+
+    #[test]
+    fn test_output_check_from_swap() {
+        let details = SwapDetails {
+            mode: sentinel_core::SwapMode::ExactIn,
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            amount: 1_000_000,
+            minimum_received: Some(990_000),
+            dex: None,
+            route_hints: None,
+        };
+        let check = OutputCheck::from_swap(&details, 0, Pubkey::new_unique()).unwrap();
+        assert_eq!(check.minimum_received, 990_000);
+
+        let options = SubmitOptions::default().with_output_check(check).force();
+        assert!(options.force);
+    }
+}
@@ -0,0 +1,198 @@
+//! Jito Tip Floor Client
+//!
+//! Polls Jito's `tip_floor` endpoint (rolling landed-tip percentiles across all
+//! bundles, quoted in SOL) and publishes each sample to a shared
+//! [`TipFloorCache`]. Consumers hold the cache, not the client:
+//! - `InferenceEngine::with_tip_floor`: tip recommendations never drop below
+//!   the network's percentiles
+//! - `FeatureExtractor::with_tip_floor`: `tip_percentile_vs_recent` ranks tips
+//!   against network-wide data rather than our local history only
+//!
+//! The `tip_stream` websocket publishes the same payload; feed its messages
+//! through [`parse_tip_floor`] and [`TipFloorCache::update`] to stream instead.
+
+use reqwest::Client;
+use sentinel_core::{system_clock, EndpointConfig, Result, SentinelError, SharedClock, TipFloor, TipFloorCache};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// One entry of the endpoint's JSON array (values in SOL)
+#[derive(Debug, Deserialize)]
+struct TipFloorEntry {
+    landed_tips_25th_percentile: f64,
+    landed_tips_50th_percentile: f64,
+    landed_tips_75th_percentile: f64,
+    landed_tips_95th_percentile: f64,
+    landed_tips_99th_percentile: f64,
+    ema_landed_tips_50th_percentile: f64,
+}
+
+fn lamports(sol: f64) -> u64 {
+    (sol.max(0.0) * LAMPORTS_PER_SOL).round() as u64
+}
+
+/// Parse a `tip_floor` / `tip_stream` payload (array of entries; first is newest)
+pub fn parse_tip_floor(body: &str, observed_at_ms: i64) -> Result<TipFloor> {
+    let entries: Vec<TipFloorEntry> = serde_json::from_str(body)
+        .map_err(|e| SentinelError::SerializationError(format!("Invalid tip floor payload: {}", e)))?;
+    let entry = entries
+        .first()
+        .ok_or_else(|| SentinelError::SerializationError("Empty tip floor payload".to_string()))?;
+
+    Ok(TipFloor {
+        p25: lamports(entry.landed_tips_25th_percentile),
+        p50: lamports(entry.landed_tips_50th_percentile),
+        p75: lamports(entry.landed_tips_75th_percentile),
+        p95: lamports(entry.landed_tips_95th_percentile),
+        p99: lamports(entry.landed_tips_99th_percentile),
+        ema_p50: lamports(entry.ema_landed_tips_50th_percentile),
+        observed_at_ms,
+    })
+}
+
+/// Poller for Jito's network-wide tip percentiles
+pub struct TipFloorClient {
+    http_client: Client,
+    url: String,
+    cache: TipFloorCache,
+    clock: SharedClock,
+}
+
+impl TipFloorClient {
+    pub fn new(url: String) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            url,
+            cache: TipFloorCache::default(),
+            clock: system_clock(),
+        })
+    }
+
+    pub fn from_config(endpoints: &EndpointConfig) -> Result<Self> {
+        Self::new(endpoints.jito_tip_floor_url.clone())
+    }
+
+    /// Publish into an existing cache (shared with the engine / extractor)
+    pub fn with_cache(mut self, cache: TipFloorCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn cache(&self) -> &TipFloorCache {
+        &self.cache
+    }
+
+    /// Fetch the current percentiles
+    pub async fn fetch(&self) -> Result<TipFloor> {
+        let response = self
+            .http_client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Tip floor request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(SentinelError::NetworkError(format!(
+                "Tip floor endpoint returned {}",
+                response.status()
+            )));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to read tip floor: {}", e)))?;
+        parse_tip_floor(&body, self.clock.unix_millis())
+    }
+
+    /// Fetch and publish to the cache
+    pub async fn refresh(&self) -> Result<TipFloor> {
+        let floor = self.fetch().await?;
+        self.cache.update(floor);
+        debug!(
+            "💰 Tip floor: p50={} p75={} p95={} lamports",
+            floor.p50, floor.p75, floor.p95
+        );
+        Ok(floor)
+    }
+
+    /// Refresh every `interval` until the task is aborted
+    ///
+    /// Failures are logged and the cache keeps its last value (readers drop it
+    /// once it exceeds the cache's `max_age`).
+    pub fn spawn_polling(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    warn!("Tip floor refresh failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &str = r#"[{
+        "time": "2024-09-01T12:58:00Z",
+        "landed_tips_25th_percentile": 6.001000000000001e-6,
+        "landed_tips_50th_percentile": 1.0e-5,
+        "landed_tips_75th_percentile": 3.6196500000000005e-5,
+        "landed_tips_95th_percentile": 0.0014479055000000002,
+        "landed_tips_99th_percentile": 0.010007999,
+        "ema_landed_tips_50th_percentile": 9.836078125000002e-6
+    }]"#;
+
+    #[test]
+    fn test_parse_payload_to_lamports() {
+        let floor = parse_tip_floor(PAYLOAD, 42).unwrap();
+        assert_eq!(floor.p25, 6_001);
+        assert_eq!(floor.p50, 10_000);
+        assert_eq!(floor.p75, 36_197);
+        assert_eq!(floor.p95, 1_447_906);
+        assert_eq!(floor.p99, 10_007_999);
+        assert_eq!(floor.ema_p50, 9_836);
+        assert_eq!(floor.observed_at_ms, 42);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_payloads() {
+        assert!(parse_tip_floor("[]", 0).is_err());
+        assert!(parse_tip_floor("{\"error\": \"rate limited\"}", 0).is_err());
+    }
+
+    #[test]
+    fn test_client_from_config_shares_cache() {
+        let endpoints = EndpointConfig {
+            jito_tip_floor_url: "http://localhost:1234/tip_floor".to_string(),
+            ..Default::default()
+        };
+        let cache = TipFloorCache::default();
+        let client = TipFloorClient::from_config(&endpoints).unwrap().with_cache(cache.clone());
+        assert_eq!(client.url(), "http://localhost:1234/tip_floor");
+
+        client.cache().update(parse_tip_floor(PAYLOAD, i64::MAX).unwrap());
+        assert_eq!(cache.latest().map(|floor| floor.p50), Some(10_000));
+    }
+}
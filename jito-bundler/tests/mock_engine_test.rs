@@ -0,0 +1,267 @@
+//! JitoClient / BundleSubmitter against the in-process mock block engine
+
+use jito_bundler::builder::FeeAllocation;
+use jito_bundler::*;
+use jito_mock::{Fault, LandingBehavior, MockMethod, MockServer, MockSettings};
+use sentinel_core::{
+    Constraints, FeePreferences, FeeSplit, Intent, JupiterSwapInstructions, Network, PassthroughPlan,
+    PassthroughRequest, RouteType, SentinelError,
+};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn bundle() -> JitoBundle {
+    let payer = Keypair::new();
+    #[allow(deprecated)]
+    let ix = solana_sdk::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000);
+    let user_tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], Hash::new_unique());
+
+    BundleBuilder::new(Hash::new_unique(), Keypair::new())
+        .with_network(Network::Localnet)
+        .build_protected_bundle(user_tx, &FeeAllocation::new(0, 5_000))
+        .unwrap()
+}
+
+fn intent() -> Intent {
+    Intent {
+        intent_id: format!("mock-{}", Pubkey::new_unique()),
+        swap_details: None,
+        ..Intent::test_swap()
+    }
+}
+
+fn temp_queue() -> Arc<DeadLetterQueue> {
+    // `new_unique` restarts each run; the pid keeps earlier runs' letters out
+    let dir = std::env::temp_dir().join(format!("mock-dlq-{}-{}", std::process::id(), Pubkey::new_unique()));
+    Arc::new(DeadLetterQueue::open(dir).unwrap())
+}
+
+fn submitter(mock: &MockServer) -> BundleSubmitter {
+    BundleSubmitter::new(JitoClient::new(mock.url().to_string()).unwrap())
+}
+
+#[tokio::test]
+async fn test_round_trip_lands() {
+    let mock = MockServer::start(MockSettings::default()).await.unwrap();
+    let client = JitoClient::new(mock.url().to_string()).unwrap();
+    let bundle = bundle();
+
+    let simulation = client.simulate_bundle(&bundle.transactions).await.unwrap();
+    assert_eq!(simulation.results.len(), 2);
+    assert!(simulation.results.iter().all(|r| r.err.is_none()));
+
+    let bundle_id = client.send_bundle(&bundle.transactions).await.unwrap();
+    let statuses = client
+        .get_bundle_statuses(&[bundle_id.clone(), "unknown".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(statuses[0].status, "Landed");
+    assert_eq!(statuses[0].landed_slot, Some(1));
+    assert_eq!(statuses[1].status, "Invalid");
+    assert_eq!(mock.engine().bundle_ids(), vec![bundle_id]);
+
+    // Untipped bundles are rejected like the real engine
+    let untipped = client.send_bundle(&bundle.transactions[..1]).await;
+    assert!(matches!(untipped, Err(SentinelError::BundleError(_))));
+
+    assert_eq!(client.get_tip_accounts().await.unwrap(), Network::Localnet.tip_account_pubkeys());
+}
+
+#[tokio::test]
+async fn test_landing_after_polls() {
+    let mock = MockServer::start(MockSettings {
+        landing: LandingBehavior::Land { after_polls: 2 },
+        start_slot: 500,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let client = JitoClient::new(mock.url().to_string()).unwrap();
+    let bundle_id = client.send_bundle(&bundle().transactions).await.unwrap();
+
+    let ids = [bundle_id];
+    for _ in 0..2 {
+        assert_eq!(client.get_inflight_bundle_statuses(&ids).await.unwrap()[0].status, "Pending");
+    }
+    let landed = client.get_bundle_statuses(&ids).await.unwrap().remove(0);
+    assert_eq!((landed.status.as_str(), landed.landed_slot), ("Landed", Some(500)));
+}
+
+#[tokio::test]
+async fn test_transient_failures_retried_until_sent() {
+    let mock = MockServer::start(MockSettings::default()).await.unwrap();
+    mock.engine().push_fault(MockMethod::SimulateBundle, Fault::Unavailable);
+    mock.engine().push_fault(MockMethod::SendBundle, Fault::Unavailable);
+
+    let queue = temp_queue();
+    let submitter = submitter(&mock).with_dead_letters(queue.clone());
+    let bundle_id = submitter
+        .submit_intent(&intent(), &bundle(), &SubmitOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(mock.engine().bundle_ids(), vec![bundle_id]);
+    assert_eq!(mock.engine().calls(MockMethod::SimulateBundle), 3);
+    assert_eq!(mock.engine().calls(MockMethod::SendBundle), 2);
+    assert!(queue.is_empty());
+}
+
+#[tokio::test]
+async fn test_rejection_not_retried() {
+    let mock = MockServer::start(MockSettings {
+        rejection_rate: 1.0,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let queue = temp_queue();
+    let submitter = submitter(&mock).with_dead_letters(queue.clone());
+    let result = submitter.submit_intent(&intent(), &bundle(), &SubmitOptions::default()).await;
+
+    assert!(matches!(result, Err(SentinelError::BundleError(_))));
+    assert_eq!(mock.engine().calls(MockMethod::SendBundle), 1);
+    assert_eq!(mock.engine().bundle_count(), 0);
+    assert_eq!(queue.list().unwrap()[0].errors.len(), 1);
+}
+
+#[tokio::test]
+async fn test_retryable_simulation_failure_then_exhausted() {
+    let mock = MockServer::start(MockSettings::default()).await.unwrap();
+    for _ in 0..2 {
+        mock.engine().push_fault(
+            MockMethod::SimulateBundle,
+            Fault::SimulationError {
+                transaction: 0,
+                err: "AccountInUse".to_string(),
+                logs: vec![],
+            },
+        );
+    }
+
+    let queue = temp_queue();
+    let submitter = submitter(&mock).with_dead_letters(queue.clone()).with_max_attempts(2);
+    let result = submitter.submit_intent(&intent(), &bundle(), &SubmitOptions::default()).await;
+
+    assert!(result.is_err());
+    assert_eq!(mock.engine().calls(MockMethod::SimulateBundle), 2);
+    assert_eq!(mock.engine().calls(MockMethod::SendBundle), 0);
+    let letter = queue.list().unwrap().remove(0);
+    assert_eq!(letter.errors.len(), 2);
+    assert_eq!(letter.failure, Some(SimulationFailure::AccountInUse));
+}
+
+#[tokio::test]
+async fn test_configured_delay() {
+    let mock = MockServer::start(MockSettings {
+        send_delay: Duration::from_millis(150),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    mock.engine().push_fault(MockMethod::SendBundle, Fault::Delay(Duration::from_millis(100)));
+    let client = JitoClient::new(mock.url().to_string()).unwrap();
+
+    let started = Instant::now();
+    client.send_bundle(&bundle().transactions).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn test_mock_serves_tip_floor() {
+    let mock = MockServer::start(MockSettings::default()).await.unwrap();
+    let client = TipFloorClient::new(format!("{}/api/v1/bundles/tip_floor", mock.url())).unwrap();
+    assert_eq!(client.refresh().await.unwrap().p50, 5_000);
+}
+
+fn passthrough_plan(user: &Keypair, route: RouteType) -> PassthroughPlan {
+    let (jupiter, ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let swap_instructions: JupiterSwapInstructions = serde_json::from_value(serde_json::json!({
+        "swapInstruction": {
+            "programId": jupiter.to_string(),
+            "accounts": [
+                { "pubkey": user.pubkey().to_string(), "isSigner": true, "isWritable": true },
+                { "pubkey": ata.to_string(), "isSigner": false, "isWritable": true },
+            ],
+            "data": "CQk=",
+        },
+    }))
+    .unwrap();
+    let request = PassthroughRequest {
+        user_public_key: user.pubkey(),
+        input_mint: Pubkey::new_unique(),
+        output_mint: Pubkey::new_unique(),
+        in_amount: 1_000_000,
+        quoted_out_amount: 2_000_000,
+        price_impact_bps: 5.0,
+        output_token_account: ata,
+        minimum_received: None,
+        constraints: Constraints::default(),
+        fee_preferences: FeePreferences::default(),
+        compute_unit_limit: None,
+        swap_instructions,
+    };
+    let intent = request.intent(Hash::new_unique());
+    let transaction = request
+        .protected_transaction(&intent, &route, 10_000, &Network::Localnet.tip_account(0))
+        .unwrap();
+    let report = serde_json::from_value(serde_json::json!({
+        "intent_id": intent.intent_id,
+        "projected_in_amount": 1_000_000,
+        "projected_out_amount": 2_000_000,
+        "minimum_out_amount": request.minimum_received(),
+        "price_impact_bps": 5.0,
+        "simulation": { "slot": 1, "logs": [] },
+        "risk_score": 0.4,
+        "route": route,
+    }))
+    .unwrap();
+
+    PassthroughPlan {
+        intent,
+        report,
+        route,
+        fee_split: FeeSplit {
+            jito_tip_lamports: 10_000,
+            priority_fee_lamports: 0,
+        },
+        transaction,
+        output_token_account: ata,
+        minimum_received: request.minimum_received(),
+    }
+}
+
+#[tokio::test]
+async fn test_submit_passthrough_checks_signature_and_output() {
+    let mock = MockServer::start(MockSettings::default()).await.unwrap();
+    let submitter = submitter(&mock);
+    let user = Keypair::new();
+
+    // Off the block engine: nothing to submit
+    let rpc = passthrough_plan(&user, RouteType::StandardRpc);
+    let mut signed = rpc.transaction.clone();
+    signed.sign(&[&user], rpc.transaction.message.recent_blockhash);
+    let err = submitter.submit_passthrough(&rpc, signed).await.unwrap_err();
+    assert!(err.to_string().contains("block engine"));
+
+    // Unsigned, or signed over a different message
+    let plan = passthrough_plan(&user, RouteType::JitoSingle);
+    let err = submitter.submit_passthrough(&plan, plan.transaction.clone()).await.unwrap_err();
+    assert!(err.to_string().contains("signature"));
+    let other = passthrough_plan(&user, RouteType::JitoSingle);
+    let mut signed = other.transaction.clone();
+    signed.sign(&[&user], other.transaction.message.recent_blockhash);
+    let err = submitter.submit_passthrough(&plan, signed).await.unwrap_err();
+    assert!(err.to_string().contains("differs"));
+    assert_eq!(mock.engine().calls(MockMethod::SimulateBundle), 0);
+
+    // Signed plan is simulated with the output floor attached; the mock returns
+    // no post-execution accounts, so the floor can't be confirmed and nothing is sent
+    let mut signed = plan.transaction.clone();
+    signed.sign(&[&user], plan.transaction.message.recent_blockhash);
+    let err = submitter.submit_passthrough(&plan, signed).await.unwrap_err();
+    assert!(err.to_string().contains("output account"));
+    assert_eq!(mock.engine().calls(MockMethod::SimulateBundle), 1);
+    assert_eq!(mock.engine().calls(MockMethod::SendBundle), 0);
+}
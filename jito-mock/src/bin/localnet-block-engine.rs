@@ -0,0 +1,26 @@
+//! Mock Jito block engine for `network = "localnet"`
+//!
+//! ```text
+//! localnet-block-engine [addr]      default 127.0.0.1:8910
+//! ```
+//!
+//! Run next to `solana-test-validator`; bundles are accepted and reported as
+//! landed without being executed.
+
+use jito_mock::{MockBlockEngine, MockSettings};
+use sentinel_core::LOCALNET_BLOCK_ENGINE_URL;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr: SocketAddr = match std::env::args().nth(1) {
+        Some(addr) => addr.parse()?,
+        None => LOCALNET_BLOCK_ENGINE_URL.trim_start_matches("http://").parse()?,
+    };
+
+    let (bound, handle) = Arc::new(MockBlockEngine::new(MockSettings::default())).spawn(addr).await?;
+    println!("mock block engine listening on http://{}", bound);
+    handle.await?;
+    Ok(())
+}
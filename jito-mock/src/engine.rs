@@ -0,0 +1,572 @@
+//! Scriptable Mock Block Engine
+//!
+//! [`MockBlockEngine`] answers the block engine's JSON-RPC surface from
+//! memory. Behaviour is set two ways:
+//!
+//! - [`MockSettings`]: per-method delays, seeded rejection / unavailability
+//!   rates and how accepted bundles land ([`LandingBehavior`])
+//! - [`MockBlockEngine::push_fault`]: one-shot [`Fault`]s consumed in order
+//!   by the next calls of a method, for exact retry sequences
+//!
+//! Bundles are decoded and sanity-checked (size, base64/bincode, a tip to a
+//! known Jito tip account in the last transaction), never executed. With
+//! `forward_rpc_url` set, accepted bundles' transactions are also sent in
+//! order to that RPC (e.g. `solana-test-validator`) so they really land,
+//! though without the all-or-nothing guarantee of a real bundle.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sentinel_core::{Network, Result, SentinelError};
+use serde_json::{json, Value};
+use solana_sdk::hash::hashv;
+use solana_sdk::transaction::Transaction;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+use crate::http::{read_request, write_response};
+
+const MAX_BUNDLE_SIZE: usize = 5;
+
+/// Tip floor served on `GET /api/v1/bundles/tip_floor` (SOL)
+const TIP_FLOOR_PAYLOAD: &str = r#"[{
+    "landed_tips_25th_percentile": 1.0e-6,
+    "landed_tips_50th_percentile": 5.0e-6,
+    "landed_tips_75th_percentile": 1.0e-5,
+    "landed_tips_95th_percentile": 5.0e-5,
+    "landed_tips_99th_percentile": 1.0e-4,
+    "ema_landed_tips_50th_percentile": 5.0e-6
+}]"#;
+
+/// JSON-RPC methods the mock distinguishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockMethod {
+    SendBundle,
+    SimulateBundle,
+    /// `getBundleStatuses` and `getInflightBundleStatuses`
+    GetBundleStatuses,
+    GetTipAccounts,
+}
+
+impl MockMethod {
+    fn from_rpc(method: &str) -> Option<Self> {
+        match method {
+            "sendBundle" => Some(Self::SendBundle),
+            "simulateBundle" => Some(Self::SimulateBundle),
+            "getBundleStatuses" | "getInflightBundleStatuses" => Some(Self::GetBundleStatuses),
+            "getTipAccounts" => Some(Self::GetTipAccounts),
+            _ => None,
+        }
+    }
+}
+
+/// Misbehaviour injected into one call
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// HTTP 503 with a plain-text body; `JitoClient` surfaces an `RpcError` (retryable)
+    Unavailable,
+
+    /// JSON-RPC error; `JitoClient` surfaces a `BundleError` (not retryable)
+    Reject(String),
+
+    /// `simulateBundle` reports `err` for one transaction
+    SimulationError {
+        transaction: usize,
+        err: String,
+        logs: Vec<String>,
+    },
+
+    /// Hold the (otherwise normal) response
+    Delay(Duration),
+}
+
+/// What happens to accepted bundles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandingBehavior {
+    /// `Pending` for the first `after_polls` status checks, then `Landed`
+    Land { after_polls: u32 },
+
+    /// Reported `Failed`
+    Fail,
+
+    /// `Pending` forever (exercises timeouts)
+    Drop,
+}
+
+/// Mock tuning
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockSettings {
+    pub send_delay: Duration,
+    pub simulate_delay: Duration,
+    pub status_delay: Duration,
+
+    /// Share of `sendBundle` calls rejected with a JSON-RPC error (0-1)
+    pub rejection_rate: f64,
+
+    /// Share of calls to any method answered with HTTP 503 (0-1)
+    pub unavailable_rate: f64,
+
+    pub landing: LandingBehavior,
+
+    /// Seed for the rate draws; equal seeds replay equal sequences
+    pub seed: u64,
+
+    /// Slot the first landed bundle is reported at
+    pub start_slot: u64,
+
+    /// Forward accepted bundles' transactions to this JSON-RPC endpoint
+    pub forward_rpc_url: Option<String>,
+}
+
+impl Default for MockSettings {
+    fn default() -> Self {
+        Self {
+            send_delay: Duration::ZERO,
+            simulate_delay: Duration::ZERO,
+            status_delay: Duration::ZERO,
+            rejection_rate: 0.0,
+            unavailable_rate: 0.0,
+            landing: LandingBehavior::Land { after_polls: 0 },
+            seed: 0x5EED,
+            start_slot: 1,
+            forward_rpc_url: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BundleRecord {
+    slot: u64,
+    polls: u32,
+}
+
+#[derive(Debug)]
+struct State {
+    rng: u64,
+    faults: HashMap<MockMethod, VecDeque<Fault>>,
+    calls: HashMap<MockMethod, usize>,
+    bundles: HashMap<String, BundleRecord>,
+    order: Vec<String>,
+    next_slot: u64,
+}
+
+impl State {
+    /// Uniform draw in [0, 1) (xorshift64*)
+    fn draw(&mut self) -> f64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        (self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn hits(&mut self, rate: f64) -> bool {
+        rate > 0.0 && self.draw() < rate
+    }
+}
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+/// In-memory block engine
+#[derive(Debug)]
+pub struct MockBlockEngine {
+    settings: MockSettings,
+    state: Mutex<State>,
+    http_client: reqwest::Client,
+}
+
+impl Default for MockBlockEngine {
+    fn default() -> Self {
+        Self::new(MockSettings::default())
+    }
+}
+
+impl MockBlockEngine {
+    pub fn new(settings: MockSettings) -> Self {
+        let state = State {
+            // xorshift is stuck at zero
+            rng: settings.seed.max(1),
+            faults: HashMap::new(),
+            calls: HashMap::new(),
+            bundles: HashMap::new(),
+            order: Vec::new(),
+            next_slot: settings.start_slot,
+        };
+        Self {
+            settings,
+            state: Mutex::new(state),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &MockSettings {
+        &self.settings
+    }
+
+    /// Queue a fault for the next not-yet-faulted call of `method`
+    pub fn push_fault(&self, method: MockMethod, fault: Fault) {
+        self.lock().faults.entry(method).or_default().push_back(fault);
+    }
+
+    /// Calls received for `method`, including faulted ones
+    pub fn calls(&self, method: MockMethod) -> usize {
+        self.lock().calls.get(&method).copied().unwrap_or(0)
+    }
+
+    /// Accepted bundle ids, in arrival order
+    pub fn bundle_ids(&self) -> Vec<String> {
+        self.lock().order.clone()
+    }
+
+    pub fn bundle_count(&self) -> usize {
+        self.lock().order.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Bind `addr` and serve until the task is aborted
+    ///
+    /// Returns the bound address (pass port 0 for an ephemeral one).
+    pub async fn spawn(self: Arc<Self>, addr: SocketAddr) -> Result<(SocketAddr, tokio::task::JoinHandle<()>)> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to bind mock block engine: {}", e)))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| SentinelError::NetworkError(e.to_string()))?;
+        info!("🧪 Mock block engine listening on http://{}", local_addr);
+
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let engine = self.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = engine.serve_connection(stream).await {
+                                debug!("Mock block engine connection error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Mock block engine accept failed: {}", e),
+                }
+            }
+        });
+        Ok((local_addr, handle))
+    }
+
+    async fn serve_connection(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let Some(request) = read_request(&mut stream).await? else {
+            return write_response(&mut stream, 400, "{}").await;
+        };
+
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/api/v1/bundles/tip_floor") => write_response(&mut stream, 200, TIP_FLOOR_PAYLOAD).await,
+            ("POST", "/api/v1/bundles") => {
+                let (status, body) = match serde_json::from_slice::<Value>(&request.body) {
+                    Ok(rpc) => self.respond(&rpc).await,
+                    Err(e) => (200, rpc_error(Value::Null, -32700, &format!("Parse error: {}", e))),
+                };
+                // Load balancers answer 503 with plain text, not JSON-RPC
+                let body = if status == 503 { "Service Unavailable".to_string() } else { body.to_string() };
+                write_response(&mut stream, status, &body).await
+            }
+            _ => write_response(&mut stream, 404, "{}").await,
+        }
+    }
+
+    /// Answer one JSON-RPC request as (HTTP status, body), applying delays and faults
+    pub async fn respond(&self, request: &Value) -> (u16, Value) {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return (200, rpc_error(id, -32600, "Invalid request"));
+        };
+        let Some(kind) = MockMethod::from_rpc(method) else {
+            return (200, rpc_error(id, -32601, &format!("Method not found: {}", method)));
+        };
+
+        let fault = {
+            let mut state = self.lock();
+            *state.calls.entry(kind).or_default() += 1;
+            let queued = state.faults.get_mut(&kind).and_then(VecDeque::pop_front);
+            queued.or_else(|| {
+                if state.hits(self.settings.unavailable_rate) {
+                    Some(Fault::Unavailable)
+                } else if kind == MockMethod::SendBundle && state.hits(self.settings.rejection_rate) {
+                    Some(Fault::Reject("Bundle rejected by mock block engine".to_string()))
+                } else {
+                    None
+                }
+            })
+        };
+
+        let mut delay = match kind {
+            MockMethod::SendBundle => self.settings.send_delay,
+            MockMethod::SimulateBundle => self.settings.simulate_delay,
+            MockMethod::GetBundleStatuses | MockMethod::GetTipAccounts => self.settings.status_delay,
+        };
+        if let Some(Fault::Delay(extra)) = &fault {
+            delay += *extra;
+        }
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        let result = match (fault, kind) {
+            (Some(Fault::Unavailable), _) => return (503, Value::Null),
+            (Some(Fault::Reject(message)), _) => Err((-32000, message)),
+            (fault, MockMethod::SimulateBundle) => simulate_bundle(&params, fault),
+            (_, MockMethod::SendBundle) => match self.send_bundle(&params) {
+                Ok(bundle_id) => self.forward(&params).await.map(|_| bundle_id),
+                Err(e) => Err(e),
+            },
+            (_, MockMethod::GetBundleStatuses) => Ok(self.bundle_statuses(&params)),
+            (_, MockMethod::GetTipAccounts) => Ok(json!(Network::Localnet.tip_accounts())),
+        };
+
+        match result {
+            Ok(result) => (200, json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+            Err((code, message)) => (200, rpc_error(id, code, &message)),
+        }
+    }
+
+    fn send_bundle(&self, params: &Value) -> RpcResult {
+        let transactions = decode_transactions(params.get(0).unwrap_or(&Value::Null))?;
+        let has_tip = transactions
+            .last()
+            .is_some_and(|tx| tx.message.account_keys.iter().any(Network::is_any_tip_account));
+        if !has_tip {
+            return Err((-32602, "Bundle must tip a Jito tip account".to_string()));
+        }
+
+        let signatures: Vec<&[u8]> = transactions
+            .iter()
+            .filter_map(|tx| tx.signatures.first().map(|sig| sig.as_ref()))
+            .collect();
+        let bundle_id = hashv(&signatures).to_string();
+
+        let mut state = self.lock();
+        if !state.bundles.contains_key(&bundle_id) {
+            let slot = state.next_slot;
+            state.next_slot += 1;
+            state.bundles.insert(bundle_id.clone(), BundleRecord { slot, polls: 0 });
+            state.order.push(bundle_id.clone());
+        }
+
+        debug!("Mock bundle {} accepted ({} tx)", bundle_id, transactions.len());
+        Ok(json!(bundle_id))
+    }
+
+    /// Send the bundle's transactions to `forward_rpc_url`, in bundle order
+    async fn forward(&self, params: &Value) -> std::result::Result<(), (i64, String)> {
+        let Some(url) = &self.settings.forward_rpc_url else {
+            return Ok(());
+        };
+        let transactions = params.get(0).and_then(Value::as_array).cloned().unwrap_or_default();
+
+        for (index, transaction) in transactions.iter().enumerate() {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": index,
+                "method": "sendTransaction",
+                "params": [transaction, { "encoding": "base64", "skipPreflight": true }],
+            });
+            let response: Value = async { self.http_client.post(url).json(&request).send().await?.error_for_status()?.json().await }
+                .await
+                .map_err(|e: reqwest::Error| (-32002, format!("Forwarding to {} failed: {}", url, e)))?;
+            if let Some(error) = response.get("error") {
+                return Err((-32002, format!("Forwarded transaction {} rejected: {}", index, error)));
+            }
+        }
+        Ok(())
+    }
+
+    fn bundle_statuses(&self, params: &Value) -> Value {
+        let landing = self.settings.landing;
+        let mut state = self.lock();
+        let statuses: Vec<Value> = params
+            .get(0)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(|bundle_id| {
+                let Some(record) = state.bundles.get_mut(bundle_id) else {
+                    return json!({ "bundle_id": bundle_id, "status": "Invalid", "landed_slot": null });
+                };
+                let polled = record.polls;
+                record.polls += 1;
+                match landing {
+                    LandingBehavior::Land { after_polls } if polled >= after_polls => {
+                        json!({ "bundle_id": bundle_id, "status": "Landed", "landed_slot": record.slot })
+                    }
+                    LandingBehavior::Fail => json!({ "bundle_id": bundle_id, "status": "Failed", "landed_slot": null }),
+                    _ => json!({ "bundle_id": bundle_id, "status": "Pending", "landed_slot": null }),
+                }
+            })
+            .collect();
+        json!({ "value": statuses })
+    }
+}
+
+/// Clean simulation, unless a `SimulationError` fault names a transaction
+fn simulate_bundle(params: &Value, fault: Option<Fault>) -> RpcResult {
+    // `[txs]` or `[{ "encodedTransactions": txs }, config]`
+    let first = params.get(0).unwrap_or(&Value::Null);
+    let encoded = first.get("encodedTransactions").unwrap_or(first);
+    let transactions = decode_transactions(encoded)?;
+
+    let mut results: Vec<Value> = transactions
+        .iter()
+        .map(|_| json!({ "err": null, "logs": [], "units_consumed": 0 }))
+        .collect();
+    let mut summary = "succeeded";
+    if let Some(Fault::SimulationError { transaction, err, logs }) = fault {
+        if let Some(result) = results.get_mut(transaction) {
+            *result = json!({ "err": err, "logs": logs, "units_consumed": 0 });
+            summary = "failed";
+        }
+    }
+    Ok(json!({ "summary": summary, "results": results }))
+}
+
+fn decode_transactions(encoded: &Value) -> std::result::Result<Vec<Transaction>, (i64, String)> {
+    let encoded = encoded
+        .as_array()
+        .ok_or((-32602, "Expected an array of base64 transactions".to_string()))?;
+    if encoded.is_empty() || encoded.len() > MAX_BUNDLE_SIZE {
+        return Err((-32602, format!("Bundle must contain 1-{} transactions", MAX_BUNDLE_SIZE)));
+    }
+
+    encoded
+        .iter()
+        .map(|tx| {
+            let bytes = tx
+                .as_str()
+                .and_then(|tx| BASE64.decode(tx).ok())
+                .ok_or((-32602, "Transaction is not base64".to_string()))?;
+            bincode::deserialize::<Transaction>(&bytes).map_err(|e| (-32602, format!("Invalid transaction: {}", e)))
+        })
+        .collect()
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// A running mock, stopped on drop
+pub struct MockServer {
+    engine: Arc<MockBlockEngine>,
+    url: String,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Serve `settings` on an ephemeral localhost port
+    pub async fn start(settings: MockSettings) -> Result<Self> {
+        let engine = Arc::new(MockBlockEngine::new(settings));
+        let addr: SocketAddr = ([127, 0, 0, 1], 0).into();
+        let (bound, handle) = engine.clone().spawn(addr).await?;
+        Ok(Self {
+            engine,
+            url: format!("http://{}", bound),
+            handle,
+        })
+    }
+
+    /// Block engine URL for `JitoClient::new`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn engine(&self) -> &Arc<MockBlockEngine> {
+        &self.engine
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpc(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+    }
+
+    #[tokio::test]
+    async fn test_queued_faults_apply_in_order() {
+        let engine = MockBlockEngine::default();
+        engine.push_fault(MockMethod::SendBundle, Fault::Unavailable);
+        engine.push_fault(MockMethod::SendBundle, Fault::Reject("no".to_string()));
+
+        let request = rpc("sendBundle", json!([[]]));
+        assert_eq!(engine.respond(&request).await.0, 503);
+        let (_, body) = engine.respond(&request).await;
+        assert_eq!(body["error"]["message"], "no");
+        // Faults exhausted: normal validation applies
+        let (_, body) = engine.respond(&request).await;
+        assert_eq!(body["error"]["code"], -32602);
+        assert_eq!(engine.calls(MockMethod::SendBundle), 3);
+        assert_eq!(engine.calls(MockMethod::SimulateBundle), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rates_are_seeded() {
+        let settings = MockSettings {
+            unavailable_rate: 0.5,
+            ..Default::default()
+        };
+        let request = rpc("getTipAccounts", json!([]));
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let engine = MockBlockEngine::new(settings.clone());
+            let mut statuses = Vec::new();
+            for _ in 0..64 {
+                statuses.push(engine.respond(&request).await.0);
+            }
+            runs.push(statuses);
+        }
+        assert_eq!(runs[0], runs[1]);
+        let unavailable = runs[0].iter().filter(|s| **s == 503).count();
+        assert!((16..48).contains(&unavailable), "{}", unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_forwarding_errors_surface() {
+        // Another mock stands in for an RPC that rejects `sendTransaction`
+        let validator = MockServer::start(MockSettings::default()).await.unwrap();
+        let engine = MockBlockEngine::new(MockSettings {
+            forward_rpc_url: Some(format!("{}/api/v1/bundles", validator.url())),
+            ..Default::default()
+        });
+
+        #[allow(deprecated)]
+        let tip = solana_sdk::system_instruction::transfer(
+            &solana_sdk::pubkey::Pubkey::new_unique(),
+            &Network::Localnet.tip_account(0),
+            1_000,
+        );
+        let tx = Transaction::new_with_payer(&[tip], None);
+        let encoded = BASE64.encode(bincode::serialize(&tx).unwrap());
+
+        let (_, body) = engine.respond(&rpc("sendBundle", json!([[encoded]]))).await;
+        assert_eq!(body["error"]["code"], -32002);
+        assert!(body["error"]["message"].as_str().unwrap().contains("Method not found"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method() {
+        let (status, body) = MockBlockEngine::default().respond(&rpc("getSlot", json!([]))).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["error"]["code"], -32601);
+    }
+}
@@ -0,0 +1,22 @@
+//! Mock Jito Block Engine
+//!
+//! In-process HTTP server speaking the block engine's bundle API
+//! (`sendBundle`, `simulateBundle`, `getBundleStatuses`,
+//! `getInflightBundleStatuses`, `getTipAccounts`) plus the `tip_floor`
+//! endpoint, so `JitoClient` / `BundleSubmitter` behaviour (retries,
+//! rejections, landing and timeouts) can be tested deterministically
+//! without network access.
+//!
+//! ```ignore
+//! let mock = MockServer::start(MockSettings::default()).await?;
+//! mock.engine().push_fault(MockMethod::SendBundle, Fault::Unavailable);
+//! let client = JitoClient::new(mock.url().to_string())?;
+//! ```
+//!
+//! The `localnet-block-engine` binary serves the same mock on
+//! `sentinel_core::LOCALNET_BLOCK_ENGINE_URL` for `network = "localnet"`.
+
+pub mod engine;
+mod http;
+
+pub use engine::{Fault, LandingBehavior, MockBlockEngine, MockMethod, MockServer, MockSettings};
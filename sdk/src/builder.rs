@@ -0,0 +1,374 @@
+//! Builder-pattern intent construction
+//!
+//! Defaults: ExactIn, 0.5% slippage, 2-minute TTL, fee preferences from
+//! `FeePreferences::default()` (100k priority / 50k tip, 70/30 split).
+//! `build()` runs the same `Intent::validate` the router runs on receipt.
+
+use chrono::Utc;
+use sentinel_core::{
+    ConsentBlock, Constraints, DcaDetails, FeePreferences, Intent, IntentError, IntentLeg, IntentType, LimitDetails,
+    ProtectionTier, SwapDetails, SwapMode, TwapDetails, INTENT_SCHEMA_VERSION,
+};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use uuid::Uuid;
+
+/// Default intent lifetime (seconds)
+pub const DEFAULT_TTL_SECS: u32 = 120;
+
+/// Fluent intent builder
+#[derive(Debug, Clone)]
+pub struct IntentBuilder {
+    user: Pubkey,
+    intent_type: IntentType,
+    swap: SwapDetails,
+    constraints: Constraints,
+    fee_preferences: FeePreferences,
+    recent_blockhash: Hash,
+    nonce: Option<String>,
+    limit_details: Option<LimitDetails>,
+    twap_details: Option<TwapDetails>,
+    dca_details: Option<DcaDetails>,
+    legs: Vec<IntentLeg>,
+}
+
+impl IntentBuilder {
+    /// Immediate swap of `amount` atoms of `input_mint` into `output_mint`
+    pub fn swap(user: Pubkey, input_mint: Pubkey, output_mint: Pubkey, amount: u64) -> Self {
+        Self {
+            user,
+            intent_type: IntentType::Swap,
+            swap: SwapDetails {
+                mode: SwapMode::ExactIn,
+                input_mint,
+                output_mint,
+                amount,
+                minimum_received: None,
+                dex: None,
+                route_hints: None,
+            },
+            constraints: Constraints {
+                ttl_seconds: Some(DEFAULT_TTL_SECS),
+                ..Default::default()
+            },
+            fee_preferences: FeePreferences::default(),
+            recent_blockhash: Hash::default(),
+            nonce: None,
+            limit_details: None,
+            twap_details: None,
+            dca_details: None,
+            legs: Vec::new(),
+        }
+    }
+
+    /// Limit order executing once `price_threshold` is reached
+    pub fn limit(
+        user: Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+        price_threshold: f64,
+    ) -> Self {
+        let mut builder = Self::swap(user, input_mint, output_mint, amount);
+        builder.intent_type = IntentType::Limit;
+        builder.limit_details = Some(LimitDetails {
+            price_threshold,
+            oracle: None,
+        });
+        builder
+    }
+
+    /// TWAP order spread over `duration_secs`
+    pub fn twap(
+        user: Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+        duration_secs: u32,
+    ) -> Self {
+        let mut builder = Self::swap(user, input_mint, output_mint, amount);
+        builder.intent_type = IntentType::TWAP;
+        builder.twap_details = Some(TwapDetails {
+            duration_secs,
+            num_chunks: None,
+        });
+        builder
+    }
+
+    /// Recurring buy of `total_amount` split over `num_orders`, `interval_secs` apart
+    pub fn dca(
+        user: Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        total_amount: u64,
+        interval_secs: u32,
+        num_orders: u16,
+    ) -> Self {
+        let mut builder = Self::swap(user, input_mint, output_mint, total_amount);
+        builder.intent_type = IntentType::DCA;
+        builder.dca_details = Some(DcaDetails {
+            interval_secs,
+            total_amount,
+            num_orders,
+        });
+        builder
+    }
+
+    /// Multi-leg intent executed atomically in one Jito bundle, legs in order
+    pub fn bundle(user: Pubkey, legs: Vec<IntentLeg>) -> Self {
+        let mut builder = Self::swap(user, Pubkey::default(), Pubkey::default(), 0);
+        builder.intent_type = IntentType::Bundle;
+        builder.legs = legs;
+        builder
+    }
+
+    /// Append a leg (Bundle intents)
+    pub fn leg(mut self, leg: IntentLeg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Switch to exact-output mode
+    pub fn exact_out(mut self) -> Self {
+        self.swap.mode = SwapMode::ExactOut;
+        self
+    }
+
+    pub fn minimum_received(mut self, amount: u64) -> Self {
+        self.swap.minimum_received = Some(amount);
+        self
+    }
+
+    /// Preferred aggregator ("Jupiter", "Raydium")
+    pub fn dex(mut self, dex: impl Into<String>) -> Self {
+        self.swap.dex = Some(dex.into());
+        self
+    }
+
+    pub fn route_hints(mut self, hints: Vec<Pubkey>) -> Self {
+        self.swap.route_hints = Some(hints);
+        self
+    }
+
+    pub fn slippage_bps(mut self, bps: u16) -> Self {
+        self.constraints.max_slippage_bps = bps;
+        self
+    }
+
+    /// Let the router tighten slippage below `slippage_bps` to its recommendation
+    pub fn managed_slippage(mut self, enabled: bool) -> Self {
+        self.constraints.managed_slippage = enabled;
+        self
+    }
+
+    /// Let the router cross the swap against opposing intents at the oracle mid price
+    pub fn allow_netting(mut self, enabled: bool) -> Self {
+        self.constraints.allow_netting = enabled;
+        self
+    }
+
+    pub fn partial_fill(mut self, allowed: bool) -> Self {
+        self.constraints.partial_fill = allowed;
+        self
+    }
+
+    /// Relative lifetime; clears any absolute expiry
+    pub fn ttl_seconds(mut self, ttl: u32) -> Self {
+        self.constraints.ttl_seconds = Some(ttl);
+        self.constraints.expiry_timestamp = None;
+        self
+    }
+
+    /// Absolute expiry (unix seconds); takes precedence over TTL
+    pub fn expires_at(mut self, timestamp: i64) -> Self {
+        self.constraints.expiry_timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn max_priority_fee(mut self, lamports: u64) -> Self {
+        self.fee_preferences.max_priority_fee_lamports = lamports;
+        self
+    }
+
+    pub fn max_jito_tip(mut self, lamports: u64) -> Self {
+        self.fee_preferences.max_jito_tip_lamports = lamports;
+        self
+    }
+
+    /// Share of the fee budget allocated to the Jito tip (0-100)
+    pub fn tip_allocation_pct(mut self, pct: u8) -> Self {
+        self.fee_preferences.tip_allocation_pct = pct;
+        self
+    }
+
+    /// Submit even if the upcoming leader is a known malicious validator
+    pub fn allow_malicious_leader(mut self) -> Self {
+        self.fee_preferences.allow_malicious_leader = true;
+        self
+    }
+
+    /// Protection tier (tip percentiles, route bias and simulation checks set by the router's config)
+    pub fn protection_tier(mut self, tier: ProtectionTier) -> Self {
+        self.fee_preferences.protection_tier = tier;
+        self
+    }
+
+    /// Limit order price oracle (Pyth/Switchboard account)
+    pub fn oracle(mut self, oracle: Pubkey) -> Self {
+        if let Some(ref mut details) = self.limit_details {
+            details.oracle = Some(oracle);
+        }
+        self
+    }
+
+    /// TWAP sub-order count
+    pub fn chunks(mut self, num_chunks: u16) -> Self {
+        if let Some(ref mut details) = self.twap_details {
+            details.num_chunks = Some(num_chunks);
+        }
+        self
+    }
+
+    /// Blockhash the consent is bound to (replay protection)
+    pub fn recent_blockhash(mut self, blockhash: Hash) -> Self {
+        self.recent_blockhash = blockhash;
+        self
+    }
+
+    /// Durable nonce for offline signing
+    pub fn nonce(mut self, nonce: Hash) -> Self {
+        self.nonce = Some(nonce.to_string());
+        self
+    }
+
+    /// Assemble without validation
+    pub fn build_unchecked(self) -> Intent {
+        Intent {
+            intent_id: Uuid::new_v4().to_string(),
+            user_public_key: self.user,
+            intent_type: self.intent_type,
+            swap_details: (self.intent_type != IntentType::Bundle).then_some(self.swap),
+            constraints: self.constraints,
+            fee_preferences: self.fee_preferences,
+            consent_block: ConsentBlock {
+                recent_blockhash: self.recent_blockhash,
+                signature_request_id: Intent::new_signature_request_id(),
+                nonce: self.nonce,
+            },
+            limit_details: self.limit_details,
+            twap_details: self.twap_details,
+            dca_details: self.dca_details,
+            legs: self.legs,
+            schema_version: INTENT_SCHEMA_VERSION,
+        }
+    }
+
+    /// Assemble and validate against the current time
+    pub fn build(self) -> Result<Intent, IntentError> {
+        let intent = self.build_unchecked();
+        intent.validate(Utc::now().timestamp())?;
+        Ok(intent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_defaults() {
+        let intent = IntentBuilder::swap(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+        )
+        .build()
+        .unwrap();
+
+        assert_eq!(intent.intent_type, IntentType::Swap);
+        assert_eq!(intent.constraints.max_slippage_bps, 50);
+        assert_eq!(intent.constraints.ttl_seconds, Some(DEFAULT_TTL_SECS));
+        assert!(!intent.constraints.managed_slippage);
+        assert_eq!(intent.fee_preferences, FeePreferences::default());
+    }
+
+    #[test]
+    fn test_build_validates() {
+        let mint = Pubkey::new_unique();
+        let result = IntentBuilder::swap(Pubkey::new_unique(), mint, mint, 1_000).build();
+        assert_eq!(result.unwrap_err(), IntentError::SameMints);
+
+        let result = IntentBuilder::swap(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000,
+        )
+        .slippage_bps(20_000)
+        .build();
+        assert_eq!(result.unwrap_err(), IntentError::SlippageTooHigh);
+    }
+
+    #[test]
+    fn test_twap_builder() {
+        let intent = IntentBuilder::twap(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            5_000_000,
+            3_600,
+        )
+        .chunks(12)
+        .build()
+        .unwrap();
+
+        let details = intent.twap_details.unwrap();
+        assert_eq!(details.duration_secs, 3_600);
+        assert_eq!(details.num_chunks, Some(12));
+    }
+
+    #[test]
+    fn test_dca_builder() {
+        let intent = IntentBuilder::dca(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            7_000_000,
+            86_400,
+            7,
+        )
+        .build()
+        .unwrap();
+
+        assert_eq!(intent.intent_type, IntentType::DCA);
+        assert_eq!(intent.dca_details.unwrap().order_amount(0), 1_000_000);
+    }
+
+    #[test]
+    fn test_bundle_builder() {
+        use sentinel_core::DepositDetails;
+
+        let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let swap = IntentBuilder::swap(Pubkey::new_unique(), usdc, sol, 1_000_000)
+            .minimum_received(990_000)
+            .build()
+            .unwrap()
+            .swap_details
+            .unwrap();
+
+        let intent = IntentBuilder::bundle(Pubkey::new_unique(), vec![IntentLeg::Swap(swap)])
+            .leg(IntentLeg::Deposit(DepositDetails {
+                program_id: Pubkey::new_unique(),
+                vault: Pubkey::new_unique(),
+                mint: sol,
+                amount: None,
+            }))
+            .build()
+            .unwrap();
+
+        assert_eq!(intent.intent_type, IntentType::Bundle);
+        assert!(intent.swap_details.is_none());
+        assert_eq!(intent.legs.len(), 2);
+    }
+}
@@ -0,0 +1,523 @@
+//! Async API client
+//!
+//! Endpoints of the router API service (behind the `/api/` ingress; the
+//! service itself is deployed separately, and only the schema route is also
+//! served by `sentinel-inference-server`):
+//! - `POST /api/v1/intents`                submit a signed intent (plain or sealed envelope)
+//! - `POST /api/v1/intents/preflight`      simulate and score an intent without executing it
+//! - `POST /api/v1/intents/{id}/cancel`    cancel a pending intent (signed cancellation body)
+//! - `POST /api/v1/intents/{id}/replace`   cancel and re-register with a new signed intent
+//! - `POST /api/v1/intents/{id}/dca`       pause, resume or cancel a DCA schedule (signed action body)
+//! - `GET  /schema/intent/v1`              JSON Schema of the intent payload (also `sentinel_core::intent_schema`)
+//! - `GET  /api/v1/envelope/key`           router's current envelope public key
+//! - `GET  /api/v1/intents/{id}`           current status with the execution timeline
+//! - `GET  /api/v1/intents/{id}/events`    newline-delimited JSON status stream (timeline so far on each update)
+//! - `GET  /api/v1/intents/{id}/ws`        WebSocket stream of `TimelineUpdate` events
+//! - `GET  /api/v1/analytics`              daily/weekly rollups (`period`, `from_ms`, `to_ms`)
+//! - `POST /api/v1/score/raw`              score a base64 transaction without an intent (pure classification)
+//! - `POST /api/v1/route/explain`          dry-run decision trace for an intent or raw transaction
+//! - `GET  /api/v1/actions/swap`           Solana Actions (Blinks) swap; `POST` returns the unsigned transaction
+
+use reqwest::{Client, RequestBuilder, Response};
+use sentinel_core::{
+    DcaSchedule, FillReport, Intent, IntentStatus, PreflightReport, PublishedKey, RawTransactionScore, Result,
+    Rollup, RollupPeriod, RouteExplainRequest, RouteExplanation, ScoreRawRequest, SealedEnvelope, SentinelError,
+    SignedCancellation, SignedDcaAction, TimelineEntry, INTENT_SCHEMA_PATH,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::signing::{consent_hash, SignedIntent};
+
+/// Status-stream channel depth
+const STREAM_BUFFER: usize = 32;
+
+/// Router response to a submission
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubmitReceipt {
+    pub intent_id: String,
+    pub status: IntentStatus,
+    pub consent_hash: String,
+}
+
+/// Body of a replace request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReplaceRequest {
+    /// Cancellation of the original intent, committing to `replacement`
+    pub cancellation: SignedCancellation,
+    pub replacement: SignedIntent,
+}
+
+/// Point-in-time intent status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntentStatusUpdate {
+    pub intent_id: String,
+    pub status: IntentStatus,
+
+    /// Landed transaction signature, once submitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+
+    /// Slot the transaction landed in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+
+    /// Realized amounts, slippage and fees, once confirmed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<FillReport>,
+
+    /// Execution events so far (validated, scored, routed, ...), oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<TimelineEntry>,
+
+    /// Update time (milliseconds since epoch)
+    pub timestamp_ms: u64,
+}
+
+impl IntentStatusUpdate {
+    /// No further updates follow a terminal status
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status,
+            IntentStatus::Confirmed | IntentStatus::Failed(_) | IntentStatus::Expired | IntentStatus::Cancelled
+        )
+    }
+}
+
+/// Sentinel Router API client
+#[derive(Debug, Clone)]
+pub struct SentinelClient {
+    http_client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl SentinelClient {
+    /// Create a client for the given API base URL
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| SentinelError::NetworkError(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http_client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+        })
+    }
+
+    /// Authenticate requests with an API key (`x-api-key` header)
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn intents_url(&self) -> String {
+        format!("{}/api/v1/intents", self.base_url)
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.api_key {
+            Some(ref key) => request.header("x-api-key", key),
+            None => request,
+        }
+    }
+
+    /// Submit a signed intent
+    pub async fn submit(&self, signed: &SignedIntent) -> Result<SubmitReceipt> {
+        signed.verify()?;
+
+        let response = self
+            .authorized(self.http_client.post(self.intents_url()))
+            .json(signed)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Submit request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Simulate and score an intent against current state without submitting it
+    pub async fn preflight(&self, intent: &Intent) -> Result<PreflightReport> {
+        let response = self
+            .authorized(self.http_client.post(format!("{}/preflight", self.intents_url())))
+            .json(intent)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Preflight request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Classify an arbitrary base64 wire transaction; nothing is stored or routed
+    pub async fn score_raw_transaction(&self, base64_tx: &str) -> Result<RawTransactionScore> {
+        let request = ScoreRawRequest {
+            transaction: base64_tx.to_string(),
+        };
+        let response = self
+            .authorized(self.http_client.post(format!("{}/api/v1/score/raw", self.base_url)))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Score request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Why the router would route an intent or raw transaction the way it does; nothing is submitted
+    pub async fn explain_route(&self, request: &RouteExplainRequest) -> Result<RouteExplanation> {
+        let response = self
+            .authorized(self.http_client.post(format!("{}/api/v1/route/explain", self.base_url)))
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Route explain request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Fetch the intent JSON Schema the router validates against
+    pub async fn intent_schema(&self) -> Result<serde_json::Value> {
+        let response = self
+            .authorized(self.http_client.get(format!("{}{}", self.base_url, INTENT_SCHEMA_PATH)))
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Schema request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Fetch the router's current envelope key
+    pub async fn envelope_key(&self) -> Result<PublishedKey> {
+        let response = self
+            .authorized(self.http_client.get(format!("{}/api/v1/envelope/key", self.base_url)))
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Envelope key request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Submit a signed intent sealed to `router_key`, hiding it from intermediate proxies
+    ///
+    /// The API service must open bodies with `sentinel_core::decode_submission`.
+    pub async fn submit_sealed(&self, signed: &SignedIntent, router_key: &PublishedKey) -> Result<SubmitReceipt> {
+        signed.verify()?;
+        let envelope = SealedEnvelope::seal_json(signed, router_key)?;
+
+        let response = self
+            .authorized(self.http_client.post(self.intents_url()))
+            .json(&envelope)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Submit request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Fetch current status
+    pub async fn status(&self, intent_id: &str) -> Result<IntentStatusUpdate> {
+        let response = self
+            .authorized(
+                self.http_client
+                    .get(format!("{}/{}", self.intents_url(), intent_id)),
+            )
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Status request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Cancel a pending intent (signed by the intent's user)
+    pub async fn cancel(&self, intent_id: &str, cancellation: &SignedCancellation) -> Result<IntentStatusUpdate> {
+        let response = self
+            .authorized(
+                self.http_client
+                    .post(format!("{}/{}/cancel", self.intents_url(), intent_id)),
+            )
+            .json(cancellation)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Cancel request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Atomically cancel a pending intent and submit its replacement
+    ///
+    /// `cancellation` must commit to `replacement.intent` (`Cancellation::with_replacement`).
+    pub async fn replace(
+        &self,
+        intent_id: &str,
+        cancellation: &SignedCancellation,
+        replacement: &SignedIntent,
+    ) -> Result<SubmitReceipt> {
+        replacement.verify()?;
+        if cancellation.cancellation.replacement_hash != Some(consent_hash(&replacement.intent)) {
+            return Err(SentinelError::InvalidIntent(
+                "Cancellation does not commit to the replacement intent".to_string(),
+            ));
+        }
+
+        let request = ReplaceRequest {
+            cancellation: cancellation.clone(),
+            replacement: replacement.clone(),
+        };
+        let response = self
+            .authorized(
+                self.http_client
+                    .post(format!("{}/{}/replace", self.intents_url(), intent_id)),
+            )
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Replace request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Pause, resume or cancel a DCA schedule (signed by the intent's user)
+    pub async fn dca_action(&self, action: &SignedDcaAction) -> Result<DcaSchedule> {
+        let request = &action.request;
+        let response = self
+            .authorized(
+                self.http_client
+                    .post(format!("{}/{}/dca", self.intents_url(), request.intent_id)),
+            )
+            .json(action)
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("DCA {:?} request failed: {}", request.action, e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Historical rollups for periods starting in `[from_ms, to_ms)`
+    pub async fn analytics(&self, period: RollupPeriod, from_ms: u64, to_ms: u64) -> Result<Vec<Rollup>> {
+        let period = match period {
+            RollupPeriod::Day => "day",
+            RollupPeriod::Week => "week",
+        };
+        let query = [
+            ("period", period.to_string()),
+            ("from_ms", from_ms.to_string()),
+            ("to_ms", to_ms.to_string()),
+        ];
+        let response = self
+            .authorized(
+                self.http_client
+                    .get(format!("{}/api/v1/analytics", self.base_url))
+                    .query(&query),
+            )
+            .send()
+            .await
+            .map_err(|e| SentinelError::NetworkError(format!("Analytics request failed: {}", e)))?;
+
+        Self::parse(response).await
+    }
+
+    /// Stream status updates until a terminal status or the server closes
+    ///
+    /// The channel yields parse/transport errors inline and closes afterwards.
+    pub async fn stream_status(
+        &self,
+        intent_id: &str,
+    ) -> Result<mpsc::Receiver<Result<IntentStatusUpdate>>> {
+        let mut response = self
+            .authorized(
+                self.http_client
+                    .get(format!("{}/{}/events", self.intents_url(), intent_id))
+                    .timeout(Duration::from_secs(3600)),
+            )
+            .send()
+            .await
+            .map_err(|e| SentinelError::StreamError(format!("Stream request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(SentinelError::StreamError(format!(
+                "Stream rejected with HTTP {}",
+                response.status()
+            )));
+        }
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let intent_id = intent_id.to_string();
+
+        tokio::spawn(async move {
+            let mut pending = Vec::new();
+
+            loop {
+                let chunk = match response.chunk().await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(e) => {
+                        let _ = tx
+                            .send(Err(SentinelError::StreamError(e.to_string())))
+                            .await;
+                        return;
+                    }
+                };
+                pending.extend_from_slice(&chunk);
+
+                while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = pending.drain(..=pos).collect();
+                    if line.iter().all(u8::is_ascii_whitespace) {
+                        continue;
+                    }
+
+                    let update = serde_json::from_slice::<IntentStatusUpdate>(&line)
+                        .map_err(|e| SentinelError::SerializationError(e.to_string()));
+                    let terminal = update.as_ref().map_or(true, |u| u.is_terminal());
+
+                    if tx.send(update).await.is_err() || terminal {
+                        debug!("Status stream for {} closed", intent_id);
+                        return;
+                    }
+                }
+            }
+
+            if !pending.iter().all(u8::is_ascii_whitespace) {
+                warn!("Status stream for {} ended mid-record", intent_id);
+            }
+        });
+
+        Ok(rx)
+    }
+
+    async fn parse<T: for<'de> Deserialize<'de>>(response: Response) -> Result<T> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SentinelError::NetworkError(format!(
+                "API returned HTTP {}: {}",
+                status, body
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| SentinelError::SerializationError(format!("Failed to parse response: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// One-shot HTTP server returning `body` as a chunked response
+    async fn serve_once(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        format!("http://{}", addr)
+    }
+
+    fn update(status: IntentStatus) -> IntentStatusUpdate {
+        IntentStatusUpdate {
+            intent_id: "intent-1".to_string(),
+            status,
+            signature: None,
+            slot: None,
+            fill: None,
+            timeline: vec![],
+            timestamp_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_base_url_normalized() {
+        let client = SentinelClient::new("http://localhost:8080/").unwrap();
+        assert_eq!(client.intents_url(), "http://localhost:8080/api/v1/intents");
+    }
+
+    #[test]
+    fn test_status_update_json() {
+        let json = serde_json::to_string(&update(IntentStatus::Failed("slippage".into()))).unwrap();
+        assert!(json.contains(r#""status":{"failed":"slippage"}"#));
+        assert!(!json.contains("signature"));
+        assert!(!json.contains("timeline"));
+
+        let with_timeline: IntentStatusUpdate = serde_json::from_str(
+            r#"{"intent_id":"intent-1","status":"submitted","timestamp_ms":9,
+                "timeline":[{"timestamp_ms":1,"event":"validated"},
+                            {"timestamp_ms":8,"event":"submitted","bundle_id":"b-1"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            with_timeline.timeline[1].event,
+            sentinel_core::TimelineEvent::Submitted { bundle_id: "b-1".into() }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_stops_at_terminal_status() {
+        let body = [
+            update(IntentStatus::Pending),
+            update(IntentStatus::Submitted),
+            update(IntentStatus::Confirmed),
+            update(IntentStatus::Pending),
+        ]
+        .iter()
+        .map(|u| serde_json::to_string(u).unwrap() + "\n")
+        .collect::<String>();
+
+        let client = SentinelClient::new(serve_once(body).await).unwrap();
+        let mut rx = client.stream_status("intent-1").await.unwrap();
+
+        let mut statuses = Vec::new();
+        while let Some(update) = rx.recv().await {
+            statuses.push(update.unwrap().status);
+        }
+        assert_eq!(
+            statuses,
+            vec![IntentStatus::Pending, IntentStatus::Submitted, IntentStatus::Confirmed]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_requires_matching_commitment() {
+        use crate::{sign_intent, IntentBuilder};
+        use sentinel_core::Cancellation;
+        use solana_sdk::pubkey::Pubkey;
+        use solana_sdk::signature::{Keypair, Signer};
+
+        let wallet = Keypair::new();
+        let build = |slippage_bps| {
+            IntentBuilder::swap(wallet.pubkey(), Pubkey::new_unique(), Pubkey::new_unique(), 1_000)
+                .slippage_bps(slippage_bps)
+                .build()
+                .unwrap()
+        };
+        let replacement = sign_intent(build(25), &wallet);
+        let cancellation = Cancellation::new("intent-1", 0).with_replacement(&build(50)).sign(&wallet);
+
+        // Rejected locally before any request is made
+        let client = SentinelClient::new("http://127.0.0.1:9").unwrap();
+        let err = client.replace("intent-1", &cancellation, &replacement).await.unwrap_err();
+        assert!(err.to_string().contains("does not commit"));
+    }
+}
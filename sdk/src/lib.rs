@@ -0,0 +1,47 @@
+//! Sentinel Router SDK
+//!
+//! Integration crate for wallets and dApps:
+//! - [`IntentBuilder`]: builder-pattern intent construction with sane defaults
+//! - [`sign_intent`] / [`SignedIntent`]: canonical consent hash + wallet signature
+//! - [`SentinelClient`]: async submit / status / status-stream against the API service
+//! - [`Cancellation`]: signed cancel / replace of a pending intent
+//! - [`SealedEnvelope`]: intents sealed to the router's envelope key (`submit_sealed`)
+//!
+//! Intent types are re-exported from `sentinel-core`, so the JSON produced here
+//! is byte-for-byte what the router deserializes.
+//!
+//! # Example
+//! ```rust,no_run
+//! use sentinel_sdk::{IntentBuilder, SentinelClient, sign_intent};
+//! use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer};
+//!
+//! # async fn run() -> sentinel_core::Result<()> {
+//! let wallet = Keypair::new();
+//! let intent = IntentBuilder::swap(wallet.pubkey(), Pubkey::new_unique(), Pubkey::new_unique(), 1_000_000)
+//!     .slippage_bps(75)
+//!     .recent_blockhash(Hash::default())
+//!     .build()?;
+//!
+//! let signed = sign_intent(intent, &wallet);
+//! let client = SentinelClient::new("https://api.sentinel-router.io")?;
+//! let receipt = client.submit(&signed).await?;
+//! println!("submitted {}", receipt.intent_id);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod builder;
+pub mod client;
+pub mod signing;
+
+pub use builder::IntentBuilder;
+pub use client::{IntentStatusUpdate, ReplaceRequest, SentinelClient, SubmitReceipt};
+pub use signing::{consent_hash, sign_intent, SignedIntent};
+
+pub use sentinel_core::{
+    Cancellation, ConsentBlock, Constraints, DcaAction, DcaActionRequest, DcaDetails, DcaSchedule, DcaStatus,
+    DepositDetails, FeePreferences, FeesPaid, FillReport, Intent, IntentError, IntentLeg, IntentStatus, IntentType,
+    LimitDetails, PreflightReport, PreviewBuilder, Priority, ProtectionTier, PublishedKey, Rollup, RollupPeriod,
+    RouteStats, SealedEnvelope, SignPreview, SignedCancellation, SignedDcaAction, SwapDetails, SwapMode, TimelineEntry,
+    TimelineEvent, TwapDetails,
+};
@@ -0,0 +1,121 @@
+//! Consent hashing and signing
+//!
+//! The consent hash is `Intent::hash()` (BLAKE3 over the bincode encoding), the
+//! same value the router recomputes. Wallets sign the 32 hash bytes, so any
+//! field tampered with after signing invalidates the signature.
+
+use sentinel_core::{Intent, Result, SentinelError};
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::{Signature, Signer};
+use std::str::FromStr;
+
+/// Intent plus the user's consent signature (submission payload)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedIntent {
+    pub intent: Intent,
+
+    /// Base58 consent hash the signature covers
+    pub consent_hash: String,
+
+    /// Base58 ed25519 signature by `intent.user_public_key`
+    pub signature: String,
+}
+
+impl SignedIntent {
+    /// Check the hash matches the intent and the signature matches the user key
+    pub fn verify(&self) -> Result<()> {
+        let expected = consent_hash(&self.intent);
+        if self.consent_hash != expected.to_string() {
+            return Err(SentinelError::InvalidIntent(
+                "Consent hash does not match intent".to_string(),
+            ));
+        }
+
+        let signature = Signature::from_str(&self.signature)
+            .map_err(|e| SentinelError::ParseError(format!("Invalid signature: {}", e)))?;
+
+        if !signature.verify(self.intent.user_public_key.as_ref(), expected.as_ref()) {
+            return Err(SentinelError::InvalidIntent(
+                "Consent signature does not match user public key".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Canonical consent hash for an intent
+pub fn consent_hash(intent: &Intent) -> Hash {
+    intent.hash()
+}
+
+/// Sign an intent's consent hash with the user's wallet
+pub fn sign_intent<S: Signer + ?Sized>(intent: Intent, signer: &S) -> SignedIntent {
+    let hash = consent_hash(&intent);
+    let signature = signer.sign_message(hash.as_ref());
+
+    SignedIntent {
+        intent,
+        consent_hash: hash.to_string(),
+        signature: signature.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntentBuilder;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+
+    fn signed() -> (SignedIntent, Keypair) {
+        let wallet = Keypair::new();
+        let intent = IntentBuilder::swap(
+            wallet.pubkey(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+        )
+        .build()
+        .unwrap();
+        (sign_intent(intent, &wallet), wallet)
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (signed, _) = signed();
+        assert!(signed.verify().is_ok());
+    }
+
+    #[test]
+    fn test_tampered_intent_rejected() {
+        let (mut signed, _) = signed();
+        signed.intent.constraints.max_slippage_bps = 1_000;
+        assert!(signed.verify().is_err());
+
+        // Re-hashing without re-signing is also caught
+        signed.consent_hash = consent_hash(&signed.intent).to_string();
+        assert!(signed.verify().is_err());
+    }
+
+    #[test]
+    fn test_wrong_signer_rejected() {
+        let (signed, _) = signed();
+        let forged = sign_intent(signed.intent.clone(), &Keypair::new());
+        assert!(forged.verify().is_err());
+    }
+
+    #[test]
+    fn test_sealed_intent_opens_at_intake() {
+        use sentinel_core::{decode_submission, EnvelopeKeyring, SealedEnvelope};
+
+        let (signed, _) = signed();
+        let keyring = EnvelopeKeyring::generate();
+        let body = serde_json::to_vec(&SealedEnvelope::seal_json(&signed, &keyring.current()).unwrap()).unwrap();
+
+        let opened: SignedIntent = decode_submission(&body, Some(&keyring)).unwrap();
+        assert_eq!(opened, signed);
+        assert!(opened.verify().is_ok());
+    }
+}
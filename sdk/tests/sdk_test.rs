@@ -0,0 +1,48 @@
+use sentinel_sdk::*;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[test]
+fn test_signed_intent_json_roundtrip() {
+    let wallet = Keypair::new();
+    let intent = IntentBuilder::swap(
+        wallet.pubkey(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        2_500_000,
+    )
+    .dex("Jupiter")
+    .minimum_received(2_400_000)
+    .recent_blockhash(Hash::new_unique())
+    .build()
+    .expect("valid intent");
+
+    let signed = sign_intent(intent, &wallet);
+    let json = serde_json::to_string(&signed).unwrap();
+
+    // The router deserializes the embedded intent with the core type
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let intent: Intent = serde_json::from_value(value["intent"].clone()).unwrap();
+    assert_eq!(intent, signed.intent);
+
+    let parsed: SignedIntent = serde_json::from_str(&json).unwrap();
+    assert!(parsed.verify().is_ok());
+    assert_eq!(parsed.consent_hash, consent_hash(&intent).to_string());
+}
+
+#[test]
+fn test_limit_intent_builder() {
+    let intent = IntentBuilder::limit(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1_000_000,
+        142.5,
+    )
+    .build()
+    .expect("valid limit intent");
+
+    assert_eq!(intent.intent_type, IntentType::Limit);
+    assert_eq!(intent.limit_details.unwrap().price_threshold, 142.5);
+}
@@ -0,0 +1,129 @@
+//! WebAssembly bindings for frontends
+//!
+//! Exposes the exact validation, consent hashing and fee/priority logic the Rust
+//! core runs, so a browser computes the same consent hash the router verifies.
+//! Intents cross the boundary as the API's JSON representation.
+//!
+//! Build: `wasm-pack build wasm-bindings --target web`
+//!
+//! Only the intent schema is compiled for wasm32; `sentinel-core` keeps its
+//! RPC/runtime-bound modules (nonce manager, DEX, config, decision log) behind
+//! `cfg(not(target_arch = "wasm32"))`.
+
+use sentinel_core::{decode_intent, intent_schema_json, Intent, Priority};
+use wasm_bindgen::prelude::*;
+
+fn parse_intent(intent_json: &str) -> Result<Intent, String> {
+    decode_intent(intent_json.as_bytes()).map_err(|e| format!("Invalid intent JSON: {}", e))
+}
+
+fn validate(intent_json: &str, current_time: i64) -> Result<(), String> {
+    parse_intent(intent_json)?
+        .validate(current_time)
+        .map_err(|e| e.to_string())
+}
+
+fn hash_base58(intent_json: &str) -> Result<String, String> {
+    Ok(parse_intent(intent_json)?.hash().to_string())
+}
+
+fn hash_bytes(intent_json: &str) -> Result<Vec<u8>, String> {
+    Ok(parse_intent(intent_json)?.hash().to_bytes().to_vec())
+}
+
+fn priority(intent_json: &str) -> Result<&'static str, String> {
+    Ok(match parse_intent(intent_json)?.priority_level() {
+        Priority::Low => "low",
+        Priority::Medium => "medium",
+        Priority::High => "high",
+        Priority::Critical => "critical",
+    })
+}
+
+fn split(intent_json: &str, total_budget_lamports: u64) -> Result<String, String> {
+    let split = parse_intent(intent_json)?
+        .fee_preferences
+        .split(total_budget_lamports);
+    serde_json::to_string(&split).map_err(|e| e.to_string())
+}
+
+/// Run `Intent::validate`; throws with the validation error message
+#[wasm_bindgen(js_name = validateIntent)]
+pub fn validate_intent(intent_json: &str, current_time_secs: f64) -> Result<(), JsError> {
+    validate(intent_json, current_time_secs as i64).map_err(|e| JsError::new(&e))
+}
+
+/// Canonical consent hash (base58)
+#[wasm_bindgen(js_name = consentHash)]
+pub fn consent_hash(intent_json: &str) -> Result<String, JsError> {
+    hash_base58(intent_json).map_err(|e| JsError::new(&e))
+}
+
+/// Raw 32-byte consent hash for `signMessage`
+#[wasm_bindgen(js_name = consentMessage)]
+pub fn consent_message(intent_json: &str) -> Result<Vec<u8>, JsError> {
+    hash_bytes(intent_json).map_err(|e| JsError::new(&e))
+}
+
+/// Priority level ("low" | "medium" | "high" | "critical")
+#[wasm_bindgen(js_name = priorityLevel)]
+pub fn priority_level(intent_json: &str) -> Result<String, JsError> {
+    priority(intent_json)
+        .map(str::to_string)
+        .map_err(|e| JsError::new(&e))
+}
+
+/// Tip/priority split of a fee budget as `{"jito_tip_lamports", "priority_fee_lamports"}` JSON
+#[wasm_bindgen(js_name = feeSplit)]
+pub fn fee_split(intent_json: &str, total_budget_lamports: f64) -> Result<String, JsError> {
+    split(intent_json, total_budget_lamports.max(0.0) as u64).map_err(|e| JsError::new(&e))
+}
+
+/// JSON Schema of the intent payload, for validating forms before signing
+#[wasm_bindgen(js_name = intentSchema)]
+pub fn intent_schema() -> String {
+    intent_schema_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent_json() -> (Intent, String) {
+        let mut intent = Intent {
+            intent_id: "7b1e8f9a-0000-4000-8000-000000000001".to_string(),
+            ..Intent::test_swap()
+        };
+        intent.test_swap_details().minimum_received = Some(990_000);
+        let json = serde_json::to_string(&intent).unwrap();
+        (intent, json)
+    }
+
+    #[test]
+    fn test_hash_matches_core() {
+        let (intent, json) = intent_json();
+        assert_eq!(hash_base58(&json).unwrap(), intent.hash().to_string());
+        assert_eq!(hash_bytes(&json).unwrap(), intent.hash().to_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_validation_errors_surface() {
+        let (mut intent, json) = intent_json();
+        assert!(validate(&json, 0).is_ok());
+
+        intent.constraints.max_slippage_bps = 20_000;
+        let json = serde_json::to_string(&intent).unwrap();
+        assert!(validate(&json, 0).unwrap_err().contains("Slippage"));
+        assert!(validate("{}", 0).unwrap_err().starts_with("Invalid intent JSON"));
+    }
+
+    #[test]
+    fn test_fee_helpers() {
+        let (_, json) = intent_json();
+        assert_eq!(priority(&json).unwrap(), "high");
+        assert_eq!(
+            split(&json, 10_000).unwrap(),
+            r#"{"jito_tip_lamports":7000,"priority_fee_lamports":3000}"#
+        );
+    }
+}